@@ -0,0 +1,108 @@
+//! Flush throughput benchmark for AeternusDB.
+//!
+//! Measures how fast one full write buffer is turned into an SSTable. The
+//! database is opened with the manual scheduler of feature `simulation`,
+//! so the flush runs on the benchmark thread and nothing else competes
+//! with it.
+//!
+//! # Running
+//!
+//! ```bash
+//! cargo bench --bench flush --features simulation
+//! cargo bench --bench flush --features simulation -- 1K   # filter by name
+//! ```
+//!
+//! Reports are generated in `target/criterion/report/index.html`.
+
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+
+use aeternusdb::{Db, DbConfig};
+use tempfile::TempDir;
+
+// ------------------------------------------------------------------------------------------------
+// Helpers
+// ------------------------------------------------------------------------------------------------
+
+/// Write buffer flushed by every iteration (8 MiB).
+const WRITE_BUFFER_SIZE: usize = 8 * 1024 * 1024;
+
+/// Small value payload (128 bytes).
+const VALUE_128B: &[u8; 128] = &[0xAB; 128];
+
+/// Larger value payload (1 KiB).
+const VALUE_1K: &[u8; 1024] = &[0xCD; 1024];
+
+/// Format a zero-padded key.
+fn make_key(i: u64) -> Vec<u8> {
+    format!("key-{i:012}").into_bytes()
+}
+
+/// Open a fresh simulated database and write `value`s until one full
+/// write buffer is frozen and its flush is queued.
+fn fill_one_buffer(value: &[u8]) -> (TempDir, Db) {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open_simulated(
+        dir.path(),
+        DbConfig {
+            write_buffer_size: WRITE_BUFFER_SIZE,
+            ..DbConfig::default()
+        },
+    )
+    .expect("open");
+
+    let mut seq = 0u64;
+    while db.queue_stats().flush == 0 {
+        db.put(&make_key(seq), value).unwrap();
+        seq += 1;
+    }
+    (dir, db)
+}
+
+// ================================================================================================
+// Flush benchmarks
+// ================================================================================================
+
+/// Benchmark group for flushing one frozen memtable.
+///
+/// # Sub-benchmarks
+///
+/// ## `memtable/128B` and `memtable/1K`
+///
+/// **Scenario:** Fills an 8 MiB write buffer with sequential keys and 128 B or 1 KiB
+/// values, then runs the queued flush.
+///
+/// **What it measures:** Flush throughput in bytes of write buffer per second: walking the
+/// memtable, encoding data blocks, building the index and bloom filter, and the SSTable fsync.
+/// The setup is not timed.
+///
+/// **Expected behaviour:** Hundreds of MiB/s on SSD. 1 KiB values flush more bytes per
+/// second than 128 B values because per-entry costs are spread over more payload; a drop in
+/// either points at extra copies of keys or values on the flush path.
+fn bench_flush(c: &mut Criterion) {
+    let mut group = c.benchmark_group("flush");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(WRITE_BUFFER_SIZE as u64));
+
+    for &(label, value) in &[("128B", VALUE_128B.as_slice()), ("1K", VALUE_1K.as_slice())] {
+        group.bench_function(BenchmarkId::new("memtable", label), |b| {
+            b.iter_batched(
+                || fill_one_buffer(value),
+                |(dir, db)| {
+                    db.run_pending_background_work().unwrap();
+                    (dir, db)
+                },
+                BatchSize::PerIteration,
+            );
+        });
+    }
+
+    group.finish();
+}
+
+// ================================================================================================
+// Group registration
+// ================================================================================================
+
+criterion_group!(benches, bench_flush);
+
+criterion_main!(benches);
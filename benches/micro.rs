@@ -16,7 +16,7 @@ use criterion::{
     BatchSize, BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main,
 };
 
-use aeternusdb::{Db, DbConfig};
+use aeternusdb::{Db, DbConfig, ReadOptions};
 use std::sync::Arc;
 use tempfile::TempDir;
 
@@ -330,7 +330,8 @@ fn bench_delete(c: &mut Criterion) {
 /// Benchmark group for ordered range-scan operations.
 ///
 /// Tests scan performance across two storage layers (memtable and SSTable) and three range
-/// sizes (10, 100, 1,000 keys). Criterion's `Throughput::Elements` annotation enables
+/// sizes (10, 100, 1,000 keys), and over SSTables overlaid by memtable writes: overwrites,
+/// deletes, or keys past every SSTable. Criterion's `Throughput::Elements` annotation enables
 /// per-key throughput reporting in the output.
 ///
 /// # Sub-benchmarks
@@ -356,6 +357,39 @@ fn bench_delete(c: &mut Criterion) {
 /// **Expected behaviour:** Slower than memtable scans due to block decompression and
 /// possible cross-SSTable merging. Per-key cost should still decrease with larger ranges
 /// due to amortisation of seek overhead.
+///
+/// ## `mixed/100_keys`
+///
+/// **Scenario:** 5,000 keys flushed to SSTables, then every fourth key overwritten in
+/// the memtable; scans 100-key ranges.
+///
+/// **What it measures:** The merge of a memtable and SSTables over the same keys, where
+/// a quarter of the SSTable entries are shadowed.
+///
+/// **Expected behaviour:** Between `memtable` and `sstable` at the same range size,
+/// closer to `sstable`, since every SSTable block in the range is still read.
+///
+/// ## `tombstone_heavy/100_keys`
+///
+/// **Scenario:** 5,000 keys flushed to SSTables, then three of every four keys deleted
+/// in the memtable; scans 100-key ranges, which return about 25 live keys each.
+///
+/// **What it measures:** Scans that step over point tombstones shadowing SSTable entries
+/// before they can yield a key.
+///
+/// **Expected behaviour:** Slower than `mixed` per returned key: the same entries are
+/// merged, but most are dropped.
+///
+/// ## `past_sstables/100_keys`
+///
+/// **Scenario:** 5,000 keys flushed to SSTables, then 5,000 later keys written to the
+/// memtable; scans 100-key ranges of the later keys.
+///
+/// **What it measures:** The shortcut for ranges past every SSTable key: no SSTable is
+/// checked or opened, so the scan costs what a memtable-only scan does.
+///
+/// **Expected behaviour:** Close to `memtable/100_keys`, and independent of the number
+/// of SSTables.
 fn bench_scan(c: &mut Criterion) {
     let mut group = c.benchmark_group("scan");
 
@@ -415,6 +449,210 @@ fn bench_scan(c: &mut Criterion) {
         db.close().unwrap();
     }
 
+    // --- SSTables overlaid by memtable writes ---
+    let n = 5_000u64;
+    let range_size = 100u64;
+    // Writes key `i` of the overlay.
+    type Overlay = fn(&Db, u64);
+    let overlays: [(&str, u64, Overlay); 3] = [
+        // Overwrite every fourth key.
+        ("mixed", 0, |db, i| {
+            if i % 4 == 0 {
+                db.put(&make_key(i), VALUE_1K).unwrap();
+            }
+        }),
+        // Delete three of every four keys.
+        ("tombstone_heavy", 0, |db, i| {
+            if i % 4 != 0 {
+                db.delete(&make_key(i)).unwrap();
+            }
+        }),
+        // Write as many keys again, past every SSTable.
+        ("past_sstables", n, |db, i| {
+            db.put(&make_key(5_000 + i), VALUE_128B).unwrap();
+        }),
+    ];
+    for (name, first_key, overlay) in overlays {
+        let dir = TempDir::new().unwrap();
+        prepopulate(dir.path(), n, VALUE_128B);
+        let db = open_memtable_only(dir.path());
+        for i in 0..n {
+            overlay(&db, i);
+        }
+
+        group.throughput(Throughput::Elements(range_size));
+        group.bench_function(BenchmarkId::new(name, format!("{range_size}_keys")), |b| {
+            let mut offset = 0u64;
+            b.iter(|| {
+                let start = make_key(first_key + offset % (n - range_size));
+                let end = make_key(first_key + offset % (n - range_size) + range_size);
+                let results = db.scan(black_box(&start), black_box(&end)).unwrap();
+                black_box(&results);
+                offset += 1;
+            });
+        });
+
+        db.close().unwrap();
+    }
+
+    group.finish();
+}
+
+// ================================================================================================
+// Seek benchmarks
+// ================================================================================================
+
+/// Benchmark group for seek-heavy access: many short scans at scattered
+/// start keys, where building the per-SSTable iterators dominates.
+///
+/// # Sub-benchmarks
+///
+/// ## `short_scan/{1,10}_keys`
+///
+/// **Scenario:** Scans N keys starting at a pseudo-random key, over 10,000 keys flushed
+/// with a 4 KiB write buffer and compaction thresholds of 64 tables, so the data spans
+/// many SSTables.
+///
+/// **What it measures:** The fixed cost of a scan — snapshotting the layers, seeking an
+/// iterator in every overlapping SSTable, and setting up the merge — which per-key
+/// scan benchmarks amortise away.
+///
+/// **Expected behaviour:** Latency barely changes between 1 and 10 keys; it grows
+/// with the number of SSTables whose key range covers the start key.
+///
+/// ## `prefix/10_keys`
+///
+/// **Scenario:** Same data; each scan passes an open-ended range with
+/// `ReadOptions::prefix_same_as_start` and a start key that is a 10-key prefix.
+///
+/// **What it measures:** Seeks where the scan end is derived from the prefix, so the
+/// merge stops when the prefix is exhausted.
+///
+/// **Expected behaviour:** Close to `short_scan/10_keys`.
+fn bench_seek(c: &mut Criterion) {
+    let mut group = c.benchmark_group("seek");
+
+    let dir = TempDir::new().unwrap();
+    let n = 10_000u64;
+    // High compaction thresholds keep the flushed SSTables apart.
+    let db = Db::open(
+        dir.path(),
+        DbConfig {
+            write_buffer_size: 4 * 1024,
+            thread_pool_size: 1,
+            min_compaction_threshold: 64,
+            max_compaction_threshold: 256,
+            ..DbConfig::default()
+        },
+    )
+    .unwrap();
+    for i in 0..n {
+        db.put(&make_key(i), VALUE_128B).unwrap();
+    }
+
+    // Scattered start keys, so consecutive seeks land in different blocks.
+    let start_of = |offset: u64, range_size: u64| (offset * 7_919) % (n - range_size);
+
+    for &range_size in &[1u64, 10] {
+        group.bench_function(
+            BenchmarkId::new("short_scan", format!("{range_size}_keys")),
+            |b| {
+                let mut offset = 0u64;
+                b.iter(|| {
+                    let start = start_of(offset, range_size);
+                    let results = db
+                        .scan(
+                            black_box(&make_key(start)),
+                            black_box(&make_key(start + range_size)),
+                        )
+                        .unwrap();
+                    black_box(&results);
+                    offset += 1;
+                });
+            },
+        );
+    }
+
+    let prefix_opts = ReadOptions {
+        prefix_same_as_start: true,
+        ..ReadOptions::default()
+    };
+    group.bench_function(BenchmarkId::new("prefix", "10_keys"), |b| {
+        let mut offset = 0u64;
+        b.iter(|| {
+            // Dropping the last digit leaves a prefix shared by 10 keys.
+            let mut prefix = make_key(start_of(offset, 10));
+            prefix.pop();
+            let results = db
+                .scan_opt(black_box(&prefix), b"key-~", &prefix_opts)
+                .unwrap();
+            black_box(&results);
+            offset += 1;
+        });
+    });
+
+    db.close().unwrap();
+    group.finish();
+}
+
+// ================================================================================================
+// Scan setup benchmarks
+// ================================================================================================
+
+/// Benchmark group for the setup cost of a scan as the SSTable count
+/// grows.
+///
+/// # Sub-benchmarks
+///
+/// ## `first_page/{8,24,48}_sstables`
+///
+/// **Scenario:** Writes keys in ascending order with a 4 KiB write buffer and compaction
+/// thresholds of 64 tables, until N SSTables with disjoint key ranges exist. Each
+/// iteration calls `scan_paged` over the whole key space with a page of 10 keys.
+///
+/// **What it measures:** The cost of starting a scan whose range overlaps every
+/// SSTable but whose result lies in the first one. SSTable scans are opened only once
+/// the merge reaches their first key, so the tables past the page are never read.
+///
+/// **Expected behaviour:** Nearly flat across SSTable counts: what grows with the count
+/// is only the bookkeeping per table, not a data block read per table.
+fn bench_scan_setup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scan_setup");
+
+    for &sstables in &[8usize, 24, 48] {
+        let dir = TempDir::new().unwrap();
+        let db = Db::open(
+            dir.path(),
+            DbConfig {
+                write_buffer_size: 4 * 1024,
+                thread_pool_size: 1,
+                min_compaction_threshold: 64,
+                max_compaction_threshold: 256,
+                ..DbConfig::default()
+            },
+        )
+        .unwrap();
+        let mut i = 0u64;
+        while db.sstable_metadata().unwrap().len() < sstables {
+            db.put(&make_key(i), VALUE_128B).unwrap();
+            i += 1;
+        }
+
+        group.bench_function(
+            BenchmarkId::new("first_page", format!("{sstables}_sstables")),
+            |b| {
+                b.iter(|| {
+                    let page = db
+                        .scan_paged(black_box(&make_key(0)), b"key-~", 10)
+                        .unwrap();
+                    black_box(&page);
+                });
+            },
+        );
+
+        db.close().unwrap();
+    }
+
     group.finish();
 }
 
@@ -465,6 +703,61 @@ fn bench_compaction(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark group for point reads right after a major compaction.
+///
+/// # Sub-benchmarks
+///
+/// ## `hints_off` and `hints_on`
+///
+/// **Scenario:** Prepopulates 20,000 keys with 1 KiB values via a small buffer, reopens
+/// the database with `page_cache_hints` off or on, and runs a major compaction. Each
+/// iteration then reads 1,000 keys spread across the whole key range.
+///
+/// **What it measures:** Read latency while the freshly compacted SSTable is still cold
+/// in the process's mappings. With hints on, its data blocks were advised `WILLNEED`
+/// when it was installed, so the reads find them in the page cache instead of faulting
+/// pages in one by one.
+///
+/// **Expected behaviour:** `hints_on` at most as slow as `hints_off`; the gap widens on
+/// slow storage or when the compaction output was evicted from the page cache while it
+/// was written. Sample size is reduced to 10 because each setup compacts.
+fn bench_read_after_compaction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("read_after_compaction");
+    group.sample_size(10);
+    const COUNT: u64 = 20_000;
+
+    for (name, hints) in [("hints_off", false), ("hints_on", true)] {
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || {
+                    let dir = TempDir::new().unwrap();
+                    prepopulate(dir.path(), COUNT, VALUE_1K);
+                    let db = Db::open(
+                        dir.path(),
+                        DbConfig {
+                            page_cache_hints: hints,
+                            ..DbConfig::default()
+                        },
+                    )
+                    .unwrap();
+                    db.major_compact().unwrap();
+                    (dir, db)
+                },
+                |(dir, db)| {
+                    for i in (0..COUNT).step_by((COUNT / 1_000) as usize) {
+                        black_box(db.get(&make_key(i)).unwrap());
+                    }
+                    // Dropped, and so closed, outside the measurement.
+                    (dir, db)
+                },
+                BatchSize::PerIteration,
+            );
+        });
+    }
+
+    group.finish();
+}
+
 // ================================================================================================
 // Recovery benchmark
 // ================================================================================================
@@ -848,7 +1141,7 @@ fn bench_tombstone_scan(c: &mut Criterion) {
                     db.put(&make_key(i), VALUE_128B).unwrap();
                 }
                 // Delete a percentage of keys.
-                let delete_every = if pct == 0 { 0 } else { 100 / pct };
+                let delete_every = 100u32.checked_div(pct).unwrap_or(0);
                 if delete_every > 0 {
                     for i in 0..n {
                         if i % delete_every as u64 == 0 {
@@ -1042,7 +1335,10 @@ criterion_group!(
     bench_get,
     bench_delete,
     bench_scan,
+    bench_seek,
+    bench_scan_setup,
     bench_compaction,
+    bench_read_after_compaction,
     bench_recovery,
     bench_value_sizes,
     bench_concurrent,
@@ -0,0 +1,318 @@
+//! Task queues of the background worker pool.
+//!
+//! Every background task belongs to a [`TaskKind`] with a bounded FIFO
+//! queue of its own. An idle worker takes the oldest task of the most
+//! urgent non-empty queue: flushes first, since they release memory and
+//! WAL space, then minor, tombstone, and major compaction.
+//!
+//! No queue grows past
+//! [`DbConfig::background_queue_depth`](crate::DbConfig::background_queue_depth)
+//! tasks. A caller queueing a flush, or another task that must run, waits
+//! for room — so a write storm that outpaces flushing stalls the writers
+//! that freeze memtables instead of piling up frozen memtables. Compaction
+//! triggered by a flush or timer is rejected instead: a compaction task
+//! runs until nothing of its kind is left to do, so the task already
+//! waiting in the full queue does the rejected task's work.
+//!
+//! Compaction and timer work is also **coalesced**: each [`Job`] has at
+//! most one instance waiting in the queues. A burst of flushes queues one
+//! minor compaction rather than one per flush, since the first to run
+//! would leave the rest nothing to do. An instance stops counting as
+//! waiting once a worker takes it, so work arriving while it runs queues
+//! the next one. Flushes themselves are not coalesced — each frees one
+//! frozen memtable, and their queue is what slows writers down.
+//!
+//! In **manual** mode (feature `simulation`) no worker serves the queues:
+//! the caller runs the queued tasks itself, one at a time, and a task that
+//! must run is queued past the depth limit instead of waiting for room
+//! that would never come.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex};
+
+use tracing::debug;
+
+/// A task run by a background worker.
+pub(crate) type Task = Box<dyn FnOnce() + Send>;
+
+/// Queue of a background task, in descending priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TaskKind {
+    /// Memtable flushes and short housekeeping: memtable age checks and
+    /// lease renewal.
+    Flush,
+
+    /// Minor compaction, and compaction after a threshold change.
+    Minor,
+
+    /// Tombstone and periodic compaction.
+    Tombstone,
+
+    /// Major compaction and other long reads: exports and SSTable
+    /// warm-up.
+    Major,
+}
+
+impl TaskKind {
+    const COUNT: usize = 4;
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Background work of which at most one instance waits in the queues;
+/// see [`TaskQueues::push_job`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Job {
+    /// Write lease renewal.
+    LeaseRenewal,
+
+    /// Memtable age check, flushing a write buffer that is due.
+    MemtableAge,
+
+    /// Hinted and minor compaction after a flush, a threshold change, or
+    /// a suggested range.
+    MinorCompaction,
+
+    /// Read depth check, hinting and compacting deep SSTable stacks.
+    ReadDepth,
+
+    /// Tombstone run check, hinting and compacting heavily deleted
+    /// SSTables.
+    DeletionRuns,
+
+    /// Report of reads past the fan-out limit.
+    ReadFanout,
+
+    /// Adaptive compaction threshold tuning.
+    Tuning,
+
+    /// Tombstone and periodic compaction after a minor compaction.
+    TombstoneCompaction,
+
+    /// Periodic compaction from its timer.
+    PeriodicCompaction,
+
+    /// Deletion of removed SSTable files past their grace period.
+    FileGc,
+
+    /// Report of the WAL and SSTable writes since the previous one.
+    WriteActivity,
+}
+
+impl Job {
+    const COUNT: usize = 11;
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The queue the job waits in.
+    fn kind(self) -> TaskKind {
+        match self {
+            Job::LeaseRenewal
+            | Job::MemtableAge
+            | Job::ReadFanout
+            | Job::FileGc
+            | Job::WriteActivity => TaskKind::Flush,
+            Job::MinorCompaction | Job::ReadDepth | Job::DeletionRuns | Job::Tuning => {
+                TaskKind::Minor
+            }
+            Job::TombstoneCompaction | Job::PeriodicCompaction => TaskKind::Tombstone,
+        }
+    }
+}
+
+/// Background queue lengths returned by
+/// [`Db::queue_stats`](crate::Db::queue_stats).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueueStats {
+    /// Flush and housekeeping tasks waiting for a worker.
+    pub flush: usize,
+
+    /// Minor compaction tasks waiting for a worker.
+    pub minor: usize,
+
+    /// Tombstone and periodic compaction tasks waiting for a worker.
+    pub tombstone: usize,
+
+    /// Major compaction, export, and warm-up tasks waiting for a worker.
+    pub major: usize,
+
+    /// Compaction tasks rejected since open because their queue was
+    /// full.
+    pub rejected: u64,
+
+    /// Compaction and timer tasks dropped since open because the same
+    /// job was already waiting.
+    pub coalesced: u64,
+}
+
+/// A queued task, and the job it is the waiting instance of.
+struct Queued {
+    task: Task,
+    job: Option<Job>,
+}
+
+struct QueueState {
+    queues: [VecDeque<Queued>; TaskKind::COUNT],
+    /// For each job, whether an instance waits in the queues.
+    waiting: [bool; Job::COUNT],
+    /// Set on shutdown: workers exit once every queue is empty.
+    shutdown: bool,
+}
+
+impl QueueState {
+    /// Takes the oldest task of the most urgent non-empty queue.
+    fn pop(&mut self) -> Option<Task> {
+        let queued = self.queues.iter_mut().find_map(VecDeque::pop_front)?;
+        if let Some(job) = queued.job {
+            self.waiting[job.index()] = false;
+        }
+        Some(queued.task)
+    }
+}
+
+/// The per-kind task queues shared by the workers and the `Db` handle.
+pub(crate) struct TaskQueues {
+    state: Mutex<QueueState>,
+    /// Signalled when a task is queued or the pool shuts down.
+    queued: Condvar,
+    /// Signalled when a worker takes a task.
+    taken: Condvar,
+    depth: usize,
+    /// No worker serves the queues; see [`take_next`](Self::take_next).
+    manual: bool,
+    rejected: AtomicU64,
+    coalesced: AtomicU64,
+}
+
+impl TaskQueues {
+    /// Creates empty queues holding at most `depth` tasks each, served by
+    /// workers, or by the caller when `manual`.
+    pub(crate) fn new(depth: usize, manual: bool) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                queues: Default::default(),
+                waiting: [false; Job::COUNT],
+                shutdown: false,
+            }),
+            queued: Condvar::new(),
+            taken: Condvar::new(),
+            depth,
+            manual,
+            rejected: AtomicU64::new(0),
+            coalesced: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns `true` if the caller, not a worker, runs the queued tasks.
+    pub(crate) fn is_manual(&self) -> bool {
+        self.manual
+    }
+
+    /// Queues `task`, waiting while the queue of `kind` is full. In manual
+    /// mode it is queued at once, past the depth limit if need be.
+    ///
+    /// Must not be called from a worker: with every worker waiting here,
+    /// no queue would drain.
+    pub(crate) fn push(&self, kind: TaskKind, task: Task) {
+        let mut state = self.state.lock().unwrap();
+        while !self.manual && state.queues[kind.index()].len() >= self.depth {
+            state = self.taken.wait(state).unwrap();
+        }
+        state.queues[kind.index()].push_back(Queued { task, job: None });
+        self.queued.notify_one();
+    }
+
+    /// Queues `task` as the waiting instance of `job`, unless one is
+    /// already waiting: that instance does the same work once it runs,
+    /// so `task` is dropped and counted in [`QueueStats::coalesced`].
+    ///
+    /// Otherwise a job of the flush queue waits for room as
+    /// [`push`](Self::push) does, and any other job is rejected while
+    /// its queue is full, counted in [`QueueStats::rejected`]. Returns
+    /// whether `task` was queued.
+    pub(crate) fn push_job(&self, job: Job, task: Task) -> bool {
+        let kind = job.kind();
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if state.waiting[job.index()] {
+                self.coalesced.fetch_add(1, Ordering::Relaxed);
+                debug!(?job, "background job already queued, task coalesced");
+                return false;
+            }
+            if state.queues[kind.index()].len() < self.depth
+                || (self.manual && kind == TaskKind::Flush)
+            {
+                break;
+            }
+            if kind != TaskKind::Flush {
+                self.rejected.fetch_add(1, Ordering::Relaxed);
+                debug!(
+                    ?kind,
+                    depth = self.depth,
+                    "background queue full, task rejected"
+                );
+                return false;
+            }
+            state = self.taken.wait(state).unwrap();
+        }
+        state.queues[kind.index()].push_back(Queued {
+            task,
+            job: Some(job),
+        });
+        state.waiting[job.index()] = true;
+        self.queued.notify_one();
+        true
+    }
+
+    /// Takes the oldest task of the most urgent non-empty queue, waiting
+    /// for one. Returns `None` once the pool shuts down and every queue
+    /// is empty.
+    pub(crate) fn next(&self) -> Option<Task> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if let Some(task) = state.pop() {
+                self.taken.notify_all();
+                return Some(task);
+            }
+            if state.shutdown {
+                return None;
+            }
+            state = self.queued.wait(state).unwrap();
+        }
+    }
+
+    /// Takes the oldest task of the most urgent non-empty queue without
+    /// waiting, for the caller to run in manual mode.
+    pub(crate) fn take_next(&self) -> Option<Task> {
+        let task = self.state.lock().unwrap().pop();
+        if task.is_some() {
+            self.taken.notify_all();
+        }
+        task
+    }
+
+    /// Lets the workers exit once the queues are drained. Tasks queued
+    /// by a running task meanwhile still run.
+    pub(crate) fn shutdown(&self) {
+        self.state.lock().unwrap().shutdown = true;
+        self.queued.notify_all();
+    }
+
+    pub(crate) fn stats(&self) -> QueueStats {
+        let state = self.state.lock().unwrap();
+        let len = |kind: TaskKind| state.queues[kind.index()].len();
+        QueueStats {
+            flush: len(TaskKind::Flush),
+            minor: len(TaskKind::Minor),
+            tombstone: len(TaskKind::Tombstone),
+            major: len(TaskKind::Major),
+            rejected: self.rejected.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+        }
+    }
+}
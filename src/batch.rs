@@ -0,0 +1,213 @@
+//! Write batches with a readable index.
+//!
+//! A [`WriteBatchWithIndex`] buffers puts, deletes, and range deletes in
+//! memory until it is committed with [`Db::write`]. Unlike a plain list of
+//! operations it also indexes its contents by key, so pending writes can be
+//! read back — on their own via [`get_from_batch`](WriteBatchWithIndex::get_from_batch),
+//! or overlaid on the database via
+//! [`get_from_batch_and_db`](WriteBatchWithIndex::get_from_batch_and_db).
+//! This is the building block for read-your-own-writes business logic.
+
+use std::collections::BTreeMap;
+
+use crate::{ChangeEvent, Db, DbError};
+
+/// Longest idempotency token accepted by [`Db::write`].
+pub(crate) const MAX_IDEMPOTENCY_TOKEN_LEN: usize = 1024;
+
+/// A single buffered write operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum WriteOp {
+    Put { key: Vec<u8>, value: Vec<u8> },
+    Delete { key: Vec<u8> },
+    DeleteRange { start: Vec<u8>, end: Vec<u8> },
+}
+
+impl WriteOp {
+    /// The key of a put or delete; `None` for a range delete.
+    pub(crate) fn point_key(&self) -> Option<&[u8]> {
+        match self {
+            WriteOp::Put { key, .. } | WriteOp::Delete { key } => Some(key),
+            WriteOp::DeleteRange { .. } => None,
+        }
+    }
+
+    /// The event published to subscribers once this operation commits.
+    pub(crate) fn to_change_event(&self) -> ChangeEvent {
+        match self {
+            WriteOp::Put { key, value } => ChangeEvent::Put {
+                key: key.clone(),
+                value: value.clone(),
+            },
+            WriteOp::Delete { key } => ChangeEvent::Delete { key: key.clone() },
+            WriteOp::DeleteRange { start, end } => ChangeEvent::DeleteRange {
+                start: start.clone(),
+                end: end.clone(),
+            },
+        }
+    }
+}
+
+/// An uncommitted batch of writes that can be read before it is applied.
+///
+/// Operations are recorded in order; later operations on the same key win.
+/// Nothing touches the database until the batch is passed to
+/// [`Db::write`], which validates every operation up front and then
+/// applies them under a single engine write lock, so the batch never
+/// interleaves with other writers.
+///
+/// # Example
+///
+/// ```rust
+/// use aeternusdb::{Db, DbConfig, WriteBatchWithIndex};
+/// # let dir = tempfile::TempDir::new().unwrap();
+/// let db = Db::open(dir.path(), DbConfig::default()).unwrap();
+/// db.put(b"balance", b"100").unwrap();
+///
+/// let mut batch = WriteBatchWithIndex::new();
+/// batch.put(b"balance", b"70");
+/// batch.put(b"audit", b"withdraw 30");
+///
+/// // Pending writes are visible through the batch, not through the db.
+/// assert_eq!(batch.get_from_batch_and_db(&db, b"balance").unwrap(), Some(b"70".to_vec()));
+/// assert_eq!(db.get(b"balance").unwrap(), Some(b"100".to_vec()));
+///
+/// db.write(&batch).unwrap();
+/// assert_eq!(db.get(b"balance").unwrap(), Some(b"70".to_vec()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WriteBatchWithIndex {
+    /// Operations in insertion order.
+    ops: Vec<WriteOp>,
+
+    /// Latest point operation (put or delete) per key, as an index into `ops`.
+    index: BTreeMap<Vec<u8>, usize>,
+
+    /// Range deletes, as indices into `ops`, in insertion order.
+    range_deletes: Vec<usize>,
+
+    /// Idempotency token recorded when the batch commits.
+    token: Option<Vec<u8>>,
+}
+
+impl WriteBatchWithIndex {
+    /// Creates an empty batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Buffers a put of `key` → `value`.
+    pub fn put(&mut self, key: &[u8], value: &[u8]) {
+        self.index.insert(key.to_vec(), self.ops.len());
+        self.ops.push(WriteOp::Put {
+            key: key.to_vec(),
+            value: value.to_vec(),
+        });
+    }
+
+    /// Buffers a point delete of `key`.
+    pub fn delete(&mut self, key: &[u8]) {
+        self.index.insert(key.to_vec(), self.ops.len());
+        self.ops.push(WriteOp::Delete { key: key.to_vec() });
+    }
+
+    /// Buffers a delete of every key in `[start, end)`.
+    pub fn delete_range(&mut self, start: &[u8], end: &[u8]) {
+        self.range_deletes.push(self.ops.len());
+        self.ops.push(WriteOp::DeleteRange {
+            start: start.to_vec(),
+            end: end.to_vec(),
+        });
+    }
+
+    /// Number of buffered operations.
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// Returns `true` if no operations are buffered.
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Discards every buffered operation and the idempotency token.
+    pub fn clear(&mut self) {
+        self.ops.clear();
+        self.index.clear();
+        self.range_deletes.clear();
+        self.token = None;
+    }
+
+    /// Tags the batch with an idempotency token, for exactly-once
+    /// application by callers that retry after a crash or timeout.
+    ///
+    /// [`Db::write`] records the token once the batch is committed, and
+    /// skips a batch whose token is already recorded, returning `Ok` as
+    /// if it had been applied; [`Db::has_applied`] looks a token up.
+    /// Tokens are remembered for
+    /// [`DbConfig::idempotency_retention_seconds`](crate::DbConfig::idempotency_retention_seconds),
+    /// so a retry must come within that window. A token must be 1 to
+    /// 1024 bytes long, which [`Db::write`] checks.
+    pub fn set_idempotency_token(&mut self, token: &[u8]) {
+        self.token = Some(token.to_vec());
+    }
+
+    /// The idempotency token set by
+    /// [`set_idempotency_token`](Self::set_idempotency_token), if any.
+    pub fn idempotency_token(&self) -> Option<&[u8]> {
+        self.token.as_deref()
+    }
+
+    /// Looks up `key` in the batch alone.
+    ///
+    /// Returns:
+    /// - `None` — the batch does not touch `key`; the database decides.
+    /// - `Some(None)` — the batch deletes `key` (point or range delete).
+    /// - `Some(Some(value))` — the batch writes `value` to `key`.
+    pub fn get_from_batch(&self, key: &[u8]) -> Option<Option<&[u8]>> {
+        let point = self.index.get(key).copied();
+        let range = self.range_deletes.iter().rev().copied().find(|&i| {
+            matches!(&self.ops[i], WriteOp::DeleteRange { start, end }
+                if start.as_slice() <= key && key < end.as_slice())
+        });
+
+        // Whichever operation came last wins.
+        match (point, range) {
+            (Some(p), Some(r)) if r > p => Some(None),
+            (Some(p), _) => match &self.ops[p] {
+                WriteOp::Put { value, .. } => Some(Some(value.as_slice())),
+                _ => Some(None),
+            },
+            (None, Some(_)) => Some(None),
+            (None, None) => None,
+        }
+    }
+
+    /// Looks up `key` with the batch overlaid on the database.
+    ///
+    /// Pending batch operations take precedence; keys the batch does not
+    /// touch are read from `db` as by [`Db::get`].
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::InvalidArgument`] — `key` is empty.
+    /// - [`DbError::Engine`] — SSTable read or I/O failed.
+    pub fn get_from_batch_and_db(&self, db: &Db, key: &[u8]) -> Result<Option<Vec<u8>>, DbError> {
+        db.check_open()?;
+
+        if key.is_empty() {
+            return Err(DbError::InvalidArgument("key must not be empty".into()));
+        }
+
+        match self.get_from_batch(key) {
+            Some(value) => Ok(value.map(<[u8]>::to_vec)),
+            None => db.get(key),
+        }
+    }
+
+    /// Buffered operations in insertion order.
+    pub(crate) fn ops(&self) -> &[WriteOp] {
+        &self.ops
+    }
+}
@@ -0,0 +1,129 @@
+//! Time source of the write path and background decisions.
+//!
+//! Record timestamps, SSTable creation times, memtable ages, tombstone
+//! grace periods, periodic compaction, and idempotency token retention all
+//! read the [`Clock`] set in [`DbConfig::clock`](crate::DbConfig::clock).
+//! [`SystemClock`] is the default; [`MockClock`] only moves when told to,
+//! so tests and simulations can step through hours of age-based behaviour
+//! without sleeping.
+//!
+//! Record timestamps go through a [`HybridClock`] over the configured
+//! clock, so they never run backwards when the system clock is stepped
+//! back, e.g. by NTP.
+//!
+//! File modification times and the cross-process write lease are compared
+//! against other processes and the file system, and keep reading the
+//! system clock.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A source of wall-clock time.
+pub trait Clock: Send + Sync {
+    /// Current time in nanoseconds since UNIX epoch.
+    fn now_nanos(&self) -> u64;
+}
+
+/// Reads [`SystemTime::now`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_nanos(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64
+    }
+}
+
+/// A clock that stands still until [`set`](Self::set) or
+/// [`advance`](Self::advance) moves it.
+///
+/// # Example
+///
+/// ```rust
+/// use aeternusdb::{Clock, MockClock};
+/// use std::time::Duration;
+///
+/// let clock = MockClock::new(1_000);
+/// clock.advance(Duration::from_secs(1));
+/// assert_eq!(clock.now_nanos(), 1_000_001_000);
+/// ```
+#[derive(Debug, Default)]
+pub struct MockClock {
+    nanos: AtomicU64,
+}
+
+impl MockClock {
+    /// Creates a clock reading `nanos` nanoseconds since UNIX epoch.
+    pub fn new(nanos: u64) -> Self {
+        Self {
+            nanos: AtomicU64::new(nanos),
+        }
+    }
+
+    /// Moves the clock to `nanos`, backwards if need be.
+    pub fn set(&self, nanos: u64) {
+        self.nanos.store(nanos, Ordering::SeqCst);
+    }
+
+    /// Moves the clock forward by `by`.
+    pub fn advance(&self, by: Duration) {
+        self.nanos.fetch_add(by.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now_nanos(&self) -> u64 {
+        self.nanos.load(Ordering::SeqCst)
+    }
+}
+
+/// A clock that never goes backwards: physical time from an inner
+/// [`Clock`], held up by the highest timestamp issued or observed.
+///
+/// A reading past that mark is returned as is. A reading at or behind it
+/// — a clock that did not tick, or was stepped back — returns the mark
+/// plus one nanosecond instead: a logical tick, carried in the low digits
+/// of the timestamp, that keeps every later stamp above every earlier one
+/// until physical time overtakes the mark again.
+///
+/// Each engine stamps its records through one, seeded at open with the
+/// newest timestamp it recovered, so a version written after a clock jump
+/// still carries a later timestamp than the versions it overwrites, and
+/// timestamp tie-breaks between equal LSNs keep their order.
+pub(crate) struct HybridClock {
+    physical: Arc<dyn Clock>,
+    /// Highest timestamp issued or observed.
+    last: AtomicU64,
+}
+
+impl HybridClock {
+    /// Creates a clock over `physical` that has seen no timestamp yet.
+    pub(crate) fn new(physical: Arc<dyn Clock>) -> Self {
+        Self {
+            physical,
+            last: AtomicU64::new(0),
+        }
+    }
+
+    /// Raises the mark to `timestamp`, stamped elsewhere or before a
+    /// restart, so later stamps follow it.
+    pub(crate) fn observe(&self, timestamp: u64) {
+        self.last.fetch_max(timestamp, Ordering::AcqRel);
+    }
+}
+
+impl Clock for HybridClock {
+    fn now_nanos(&self) -> u64 {
+        let physical = self.physical.now_nanos();
+        let next = |last: u64| physical.max(last.saturating_add(1));
+        let previous = self
+            .last
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |last| Some(next(last)))
+            .unwrap_or_else(|last| last);
+        next(previous)
+    }
+}
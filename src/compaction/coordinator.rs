@@ -0,0 +1,123 @@
+//! Claims on the SSTables that running compactions consume.
+//!
+//! A compaction claims its inputs when the manifest records its intent,
+//! after it has chosen them and before it writes any output, and releases
+//! them when it finishes or fails. A compaction whose inputs overlap a
+//! claim is skipped instead, so two jobs never replace — and never delete
+//! the files of — the same SSTable. Each engine keeps one
+//! [`CompactionCoordinator`], owned by its manifest since SSTable IDs are
+//! only unique within one.
+
+use std::ops::AddAssign;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex};
+
+/// Snapshot of compaction job counters.
+///
+/// Returned by [`Db::compaction_job_stats`](crate::Db::compaction_job_stats),
+/// summed across partitions. Counters start at zero when the database is
+/// opened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompactionJobStats {
+    /// Compactions that hold a claim on their inputs right now.
+    pub running: usize,
+
+    /// Compactions skipped because another one had already claimed some
+    /// of their inputs, or committed while they ran.
+    pub skipped: u64,
+}
+
+impl AddAssign for CompactionJobStats {
+    fn add_assign(&mut self, other: Self) {
+        self.running += other.running;
+        self.skipped += other.skipped;
+    }
+}
+
+/// Input SSTable IDs of every running compaction of one engine.
+#[derive(Debug, Default)]
+pub(crate) struct CompactionCoordinator {
+    /// Inputs of each running compaction.
+    claims: Mutex<Vec<Vec<u64>>>,
+
+    /// Signalled whenever a claim is released.
+    released: Condvar,
+
+    /// Compactions refused a claim, or thrown away, since open.
+    skipped: AtomicU64,
+}
+
+impl CompactionCoordinator {
+    /// Claims `ids` for one compaction.
+    ///
+    /// Fails with the IDs already claimed by another compaction, and
+    /// counts the compaction as skipped, if there are any.
+    pub(crate) fn claim(&self, ids: &[u64]) -> Result<(), Vec<u64>> {
+        let mut claims = self.claims.lock().unwrap();
+        let busy: Vec<u64> = ids
+            .iter()
+            .copied()
+            .filter(|id| claims.iter().any(|claim| claim.contains(id)))
+            .collect();
+        if !busy.is_empty() {
+            self.skipped.fetch_add(1, Ordering::Relaxed);
+            return Err(busy);
+        }
+        claims.push(ids.to_vec());
+        Ok(())
+    }
+
+    /// Counts a compaction as skipped that another one overtook: it
+    /// committed while the compaction ran, which threw its output away, or
+    /// before the compaction claimed its inputs.
+    pub(crate) fn skip(&self) {
+        self.skipped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Releases the claim on `ids`, in any order. A no-op if there is
+    /// none, as for a compaction left pending by a crash.
+    pub(crate) fn release(&self, ids: &[u64]) {
+        let mut ids = ids.to_vec();
+        ids.sort_unstable();
+        let mut claims = self.claims.lock().unwrap();
+        let held = claims.iter().position(|claim| {
+            let mut claim = claim.clone();
+            claim.sort_unstable();
+            claim == ids
+        });
+        if let Some(i) = held {
+            claims.swap_remove(i);
+            self.released.notify_all();
+        }
+    }
+
+    /// Returns those of `ids` that a running compaction claims.
+    pub(crate) fn claimed(&self, ids: &[u64]) -> Vec<u64> {
+        let claims = self.claims.lock().unwrap();
+        ids.iter()
+            .copied()
+            .filter(|id| claims.iter().any(|claim| claim.contains(id)))
+            .collect()
+    }
+
+    /// Blocks until no running compaction claims any of `ids`.
+    pub(crate) fn wait_released(&self, ids: &[u64]) {
+        let claims = self.claims.lock().unwrap();
+        let _claims = self
+            .released
+            .wait_while(claims, |claims| {
+                claims
+                    .iter()
+                    .any(|claim| claim.iter().any(|id| ids.contains(id)))
+            })
+            .unwrap();
+    }
+
+    /// Returns a snapshot of the counters.
+    pub(crate) fn stats(&self) -> CompactionJobStats {
+        CompactionJobStats {
+            running: self.claims.lock().unwrap().len(),
+            skipped: self.skipped.load(Ordering::Relaxed),
+        }
+    }
+}
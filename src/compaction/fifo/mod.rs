@@ -0,0 +1,127 @@
+//! # FIFO Compaction Strategy
+//!
+//! Never merges. Once the SSTables together exceed `max_total_bytes`, or
+//! the newest entry of an SSTable is older than `ttl_seconds`, the
+//! **oldest** SSTables are dropped whole — data and tombstones alike —
+//! until neither limit holds. Suited to caches and event buffers, where
+//! rewriting data that will soon expire is wasted I/O.
+//!
+//! SSTables are dropped strictly from the oldest end, by descending
+//! maximum LSN, so a table is never dropped while an older one is kept.
+//! A tombstone therefore never outlives the data it shadows, and no
+//! deleted key resurrects. Tombstones go with their tables, whatever
+//! `tombstone_gc_grace_seconds` says.
+//!
+//! A table cannot be dropped in part, so while a reader is pinned, the
+//! drop stops at the first table holding a version at or below the
+//! version horizon. The tables may then exceed the size budget or TTL
+//! until the pin is released.
+//!
+//! Every compaction operation of the family — minor, tombstone, major,
+//! periodic and hinted — runs the same drop.
+
+#[cfg(test)]
+mod tests;
+
+use std::sync::Arc;
+
+use tracing::{debug, info};
+
+use crate::compaction::{CompactionError, CompactionResult, CompactionStrategy, drop_sstables};
+use crate::engine::{EngineConfig, NO_HORIZON};
+use crate::manifest::Manifest;
+use crate::sstable::SSTable;
+
+// ------------------------------------------------------------------------------------------------
+// Selection
+// ------------------------------------------------------------------------------------------------
+
+/// Selects the SSTables to drop, oldest first.
+///
+/// `sstables` is ordered newest first. Tables are taken from its end
+/// while their total file size exceeds `max_total_bytes` or the
+/// table's newest entry is at least `ttl_seconds` old at `now_nanos`;
+/// a limit of `0` is disabled. Taking stops at the first table with a
+/// version at or below `horizon`, which a pinned reader may still read.
+pub(crate) fn select_expired(
+    sstables: &[Arc<SSTable>],
+    max_total_bytes: usize,
+    ttl_seconds: usize,
+    now_nanos: u64,
+    horizon: u64,
+) -> Vec<usize> {
+    let ttl_nanos = (ttl_seconds as u64).saturating_mul(1_000_000_000);
+    let mut total: u64 = sstables.iter().map(|sst| sst.file_size()).sum();
+
+    let mut dropped = Vec::new();
+    for (idx, sst) in sstables.iter().enumerate().rev() {
+        let over_budget = max_total_bytes > 0 && total > max_total_bytes as u64;
+        let expired = ttl_seconds > 0 && now_nanos.saturating_sub(sst.max_timestamp()) >= ttl_nanos;
+        let pinned = horizon != NO_HORIZON && sst.min_lsn() <= horizon;
+        if (!over_budget && !expired) || pinned {
+            break;
+        }
+        total -= sst.file_size();
+        dropped.push(idx);
+    }
+    dropped
+}
+
+// ------------------------------------------------------------------------------------------------
+// CompactionStrategy implementation
+// ------------------------------------------------------------------------------------------------
+
+/// FIFO compaction — drops the oldest SSTables past the size budget or TTL.
+pub struct FifoCompaction {
+    /// Total SSTable bytes to keep; `0` disables the budget.
+    pub(crate) max_total_bytes: usize,
+
+    /// Age of the newest entry past which an SSTable is dropped; `0`
+    /// disables the TTL.
+    pub(crate) ttl_seconds: usize,
+}
+
+impl CompactionStrategy for FifoCompaction {
+    fn compact(
+        &self,
+        sstables: &[Arc<SSTable>],
+        manifest: &Manifest,
+        _data_dir: &str,
+        config: &EngineConfig,
+        horizon: u64,
+    ) -> Result<Option<CompactionResult>, CompactionError> {
+        let dropped = select_expired(
+            sstables,
+            self.max_total_bytes,
+            self.ttl_seconds,
+            config.clock.now_nanos(),
+            horizon,
+        );
+        if dropped.is_empty() {
+            debug!(
+                sstable_count = sstables.len(),
+                max_total_bytes = self.max_total_bytes,
+                ttl_seconds = self.ttl_seconds,
+                horizon,
+                "fifo compaction: nothing to drop"
+            );
+            return Ok(None);
+        }
+
+        let removed_ids: Vec<u64> = dropped.iter().map(|&idx| sstables[idx].id()).collect();
+        let removed_paths: Vec<_> = manifest
+            .get_sstables()?
+            .into_iter()
+            .filter(|entry| removed_ids.contains(&entry.id))
+            .map(|entry| (entry.id, entry.path))
+            .collect();
+
+        info!(
+            removed_count = removed_ids.len(),
+            ?removed_ids,
+            "fifo compaction: dropping oldest SSTables"
+        );
+
+        drop_sstables(manifest, removed_ids, removed_paths).map(Some)
+    }
+}
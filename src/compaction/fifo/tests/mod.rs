@@ -0,0 +1,3 @@
+//! Tests for FIFO compaction.
+
+mod tests_fifo;
@@ -0,0 +1,202 @@
+//! FIFO (drop-oldest) compaction tests.
+
+#[cfg(test)]
+mod tests {
+    use crate::clock::MockClock;
+    use crate::compaction::CompactionStrategyType;
+    use crate::engine::{Engine, EngineConfig};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn fifo_config(
+        max_total_bytes: usize,
+        ttl_seconds: usize,
+        clock: Arc<MockClock>,
+    ) -> EngineConfig {
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .with_test_writer()
+            .try_init();
+        EngineConfig {
+            write_buffer_size: 256,
+            compaction_strategy: CompactionStrategyType::Fifo {
+                max_total_bytes,
+                ttl_seconds,
+            },
+            clock,
+            ..EngineConfig::default()
+        }
+    }
+
+    /// Write `count` keys starting at `first`, flushing each into its own
+    /// SSTable.
+    fn write_batch(engine: &Engine, first: usize, count: usize) {
+        for i in first..first + count {
+            let key = format!("key_{i:04}").into_bytes();
+            engine.put(key, vec![b'v'; 32]).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+    }
+
+    /// # Scenario
+    /// Once the SSTables exceed the size budget, the oldest are dropped
+    /// whole until the rest fit, and nothing is merged.
+    ///
+    /// # Starting environment
+    /// Several SSTables written with no budget in effect.
+    ///
+    /// # Actions
+    /// 1. Reopen with a budget of about half the total size.
+    /// 2. Run `minor_compact()` until it returns `false`.
+    ///
+    /// # Expected behavior
+    /// - The remaining SSTables fit the budget and are a subset of the
+    ///   original ones — no new SSTable was written.
+    /// - The dropped tables are the oldest: the newest keys still read
+    ///   back and the oldest are gone.
+    #[test]
+    fn fifo_drops_oldest_over_budget() {
+        let dir = TempDir::new().unwrap();
+        let clock = Arc::new(MockClock::new(1_000_000_000));
+        let engine = Engine::open(dir.path(), fifo_config(usize::MAX, 0, clock.clone())).unwrap();
+        write_batch(&engine, 0, 40);
+        let before = engine.sstable_metadata().unwrap();
+        assert!(before.len() > 2);
+        let total: u64 = before.iter().map(|m| m.file_size).sum();
+        engine.close().unwrap();
+
+        let budget = (total / 2) as usize;
+        let engine = Engine::open(dir.path(), fifo_config(budget, 0, clock)).unwrap();
+        assert!(engine.minor_compact().unwrap());
+        assert!(!engine.minor_compact().unwrap());
+
+        let after = engine.sstable_metadata().unwrap();
+        assert!(!after.is_empty() && after.len() < before.len());
+        assert!(after.iter().map(|m| m.file_size).sum::<u64>() <= budget as u64);
+        assert!(after.iter().all(|m| before.iter().any(|b| b.id == m.id)));
+
+        assert!(engine.get(b"key_0039".to_vec()).unwrap().is_some());
+        assert!(engine.get(b"key_0000".to_vec()).unwrap().is_none());
+    }
+
+    /// # Scenario
+    /// SSTables whose newest entry outlived the TTL are dropped; younger
+    /// ones are kept.
+    ///
+    /// # Starting environment
+    /// Old SSTables written, then the clock advanced by two minutes and
+    /// young SSTables written; TTL of one minute, no size budget.
+    ///
+    /// # Actions
+    /// 1. Run `periodic_compact()` twice.
+    ///
+    /// # Expected behavior
+    /// - The first call drops, the second finds nothing left to drop.
+    /// - Only SSTables written after the clock advanced remain.
+    /// - The oldest key is gone; the young keys read back.
+    #[test]
+    fn fifo_drops_expired_tables() {
+        let dir = TempDir::new().unwrap();
+        let clock = Arc::new(MockClock::new(1_000_000_000));
+        let engine = Engine::open(dir.path(), fifo_config(0, 60, clock.clone())).unwrap();
+
+        write_batch(&engine, 0, 20);
+        let old_ids: Vec<u64> = engine
+            .sstable_metadata()
+            .unwrap()
+            .iter()
+            .map(|m| m.id)
+            .collect();
+        assert!(!old_ids.is_empty());
+        clock.advance(Duration::from_secs(120));
+        write_batch(&engine, 100, 20);
+
+        assert!(engine.periodic_compact().unwrap());
+        assert!(!engine.periodic_compact().unwrap());
+
+        let after = engine.sstable_metadata().unwrap();
+        assert!(!after.is_empty());
+        assert!(after.iter().all(|m| !old_ids.contains(&m.id)));
+        assert!(engine.get(b"key_0000".to_vec()).unwrap().is_none());
+        assert!(engine.get(b"key_0100".to_vec()).unwrap().is_some());
+        assert!(engine.get(b"key_0119".to_vec()).unwrap().is_some());
+    }
+
+    /// # Scenario
+    /// A tombstone never outlives the data it deletes: tables are dropped
+    /// strictly oldest first.
+    ///
+    /// # Starting environment
+    /// A key written, deleted in a later SSTable, then more data pushing
+    /// the total over the budget.
+    ///
+    /// # Actions
+    /// 1. Run `major_compact()` until it returns `false`.
+    ///
+    /// # Expected behavior
+    /// The deleted key does not reappear.
+    #[test]
+    fn fifo_never_resurrects_deleted_keys() {
+        let dir = TempDir::new().unwrap();
+        let clock = Arc::new(MockClock::new(1_000_000_000));
+        let engine = Engine::open(dir.path(), fifo_config(usize::MAX, 0, clock.clone())).unwrap();
+        engine.put(b"victim".to_vec(), vec![b'v'; 200]).unwrap();
+        engine.flush_all_frozen().unwrap();
+        engine.delete(b"victim".to_vec()).unwrap();
+        write_batch(&engine, 0, 40);
+        let total: u64 = engine
+            .sstable_metadata()
+            .unwrap()
+            .iter()
+            .map(|m| m.file_size)
+            .sum();
+        engine.close().unwrap();
+
+        let engine = Engine::open(dir.path(), fifo_config((total / 3) as usize, 0, clock)).unwrap();
+        while engine.major_compact().unwrap() {}
+
+        assert_eq!(engine.get(b"victim".to_vec()).unwrap(), None);
+    }
+
+    /// # Scenario
+    /// A table a pinned reader may still read is not dropped, even over
+    /// the budget; it goes once the pin is released.
+    ///
+    /// # Starting environment
+    /// Several SSTables written with no budget in effect, then reopened
+    /// with a budget of about half the total size.
+    ///
+    /// # Actions
+    /// 1. Write a key and pin the versions visible after it.
+    /// 2. Run `minor_compact()`.
+    /// 3. Drop the pin and run `minor_compact()` again.
+    ///
+    /// # Expected behavior
+    /// - Step 2 drops nothing: every SSTable and the oldest key remain.
+    /// - Step 3 drops the oldest SSTables and the oldest key is gone.
+    #[test]
+    fn fifo_keeps_tables_a_pinned_reader_needs() {
+        let dir = TempDir::new().unwrap();
+        let clock = Arc::new(MockClock::new(1_000_000_000));
+        let engine = Engine::open(dir.path(), fifo_config(usize::MAX, 0, clock.clone())).unwrap();
+        write_batch(&engine, 0, 40);
+        let before = engine.sstable_metadata().unwrap();
+        assert!(before.len() > 2);
+        let total: u64 = before.iter().map(|m| m.file_size).sum();
+        engine.close().unwrap();
+
+        let engine = Engine::open(dir.path(), fifo_config((total / 2) as usize, 0, clock)).unwrap();
+        engine.put(b"fresh".to_vec(), b"v".to_vec()).unwrap();
+        let pin = engine.pin_versions().unwrap();
+
+        assert!(!engine.minor_compact().unwrap());
+        assert_eq!(engine.sstable_metadata().unwrap().len(), before.len());
+        assert!(engine.get(b"key_0000".to_vec()).unwrap().is_some());
+
+        drop(pin);
+        assert!(engine.minor_compact().unwrap());
+        assert!(engine.sstable_metadata().unwrap().len() < before.len());
+        assert!(engine.get(b"key_0000".to_vec()).unwrap().is_none());
+    }
+}
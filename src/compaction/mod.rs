@@ -1,6 +1,6 @@
 //! # Compaction Module
 //!
-//! Implements three compaction strategies for the LSM storage engine:
+//! Implements five compaction strategies for the LSM storage engine:
 //!
 //! ## Minor Compaction (Size-Tiered)
 //!
@@ -8,7 +8,8 @@
 //! when a bucket exceeds `min_threshold` entries. Deduplicates point entries
 //! (keeps highest LSN per key) but **preserves all tombstones** — both point
 //! and range — because other SSTables outside the merge set may still hold
-//! covered data.
+//! covered data. Redundant range tombstones are still coalesced (see
+//! [`finalize_compaction`]).
 //!
 //! ## Tombstone Compaction (Per-SSTable GC)
 //!
@@ -16,8 +17,8 @@
 //! unnecessary. Uses bloom filters on other SSTables to determine safety:
 //!
 //! - If no other SSTable's bloom says "maybe" for the key → drop the tombstone.
-//! - If bloom says "maybe" and `tombstone_bloom_fallback = true` → do actual `get()`
-//!   to resolve false positives.
+//! - If bloom says "maybe" and `tombstone_bloom_fallback = true` → look the
+//!   key up to resolve false positives, in one sorted sweep per SSTable.
 //! - If `tombstone_range_drop = true` → scan older SSTables to check
 //!   whether a range tombstone still covers any live keys.
 //!
@@ -28,22 +29,86 @@
 //! All spent tombstones (both point and range) are dropped from the output
 //! since the entire SSTable set is merged — no data can resurrect.
 //!
+//! ## Periodic Compaction (Age-Based Rewrite)
+//!
+//! When `periodic_compaction_seconds` is non-zero, the oldest SSTable past
+//! that age is rewritten on its own — with the same tombstone GC as
+//! tombstone compaction — even if no size or tombstone trigger fires. This
+//! guarantees that cold data is eventually revisited.
+//!
+//! ## Hinted Compaction (Application-Suggested)
+//!
+//! `Db::suggest_compact_range` names the SSTables overlapping a key range,
+//! e.g. after a bulk delete. They are merged into one and the result is
+//! rewritten with tombstone GC, without waiting for a size bucket or
+//! tombstone ratio threshold. Hints with a higher [`CompactionPriority`]
+//! go first.
+//!
+//! ## FIFO (Drop Oldest)
+//!
+//! [`CompactionStrategyType::Fifo`] never merges: every operation drops
+//! the oldest SSTables whole once the tables exceed a size budget or
+//! their entries outlive a TTL. See [`fifo`].
+//!
+//! ## Version horizon
+//!
+//! Every strategy is given the engine's version horizon, the oldest LSN a
+//! pinned reader may read at. Versions of a key down to the newest one at
+//! or below the horizon are kept, as are tombstones above it; see
+//! [`VersionGc`]. Without a pin the horizon is `u64::MAX`, and only the
+//! newest version of each key survives. FIFO, which cannot keep part of
+//! a table, keeps every table holding a version at or below the horizon.
+//!
+//! ## Tombstone GC grace period
+//!
+//! When `tombstone_gc_grace_seconds` is non-zero, tombstones written within
+//! the grace period are retained by **every** merging strategy, regardless
+//! of whether they are provably spent. See [`tombstone_gc_cutoff`]. FIFO
+//! does not merge: it drops tombstones with their tables, grace period or
+//! not.
+//!
+//! ## Locking
+//!
+//! A strategy selects its inputs and builds its output from a snapshot of
+//! the SSTables, without the engine lock, so writes and flushes go on
+//! meanwhile; the engine takes its write lock only to commit the result
+//! and publish it. If another compaction or a clear removed an SSTable of
+//! the snapshot in the meantime, the output is thrown away, since tombstone
+//! GC relied on the tables it saw. A major compaction then runs again on
+//! the SSTables left, so that it still merges them all.
+//!
+//! ## Coordination
+//!
+//! A compaction claims its input SSTables when the manifest records its
+//! intent, before it writes its output, and is skipped if another running
+//! compaction already claimed any of them. Since outputs are built without
+//! the engine lock, two compactions may run at once; one that finds the
+//! SSTables changed when it commits is counted as skipped too. A major
+//! compaction waits for the claims to be released and runs again instead.
+//! See [`coordinator`].
+//!
 //! ## Code organization
 //!
 //! The module separates strategy-specific logic (bucketing, selection) from
 //! shared execution primitives (merge, dedup, build). This allows future
 //! strategies (e.g., leveled compaction) to reuse the merge/build plumbing.
 
+pub(crate) mod coordinator;
+pub mod fifo;
 pub mod stcs;
 
+pub use coordinator::CompactionJobStats;
+
+use std::ops::AddAssign;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 
 use crate::engine::RangeTombstone;
 pub use crate::engine::utils::MergeIterator;
-use crate::engine::utils::Record;
-use crate::sstable::{self, PointEntry, SSTable, SSTableError};
+use crate::engine::utils::{Record, coalesce_range_tombstones};
+use crate::sstable::{self, PointEntry, SSTable, SSTableError, SstBuildStats};
 
-use crate::engine::{EngineConfig, SSTABLE_DIR};
+use crate::engine::{EngineConfig, layout};
 use crate::manifest::{Manifest, ManifestError, ManifestSstEntry};
 use tracing::{debug, info};
 
@@ -53,23 +118,27 @@ use tracing::{debug, info};
 
 /// A uniform interface for compaction strategies.
 ///
-/// Each strategy receives the current set of SSTables (by reference), a
-/// mutable manifest for atomic metadata updates, the data directory, and
-/// the engine configuration. It returns:
+/// Each strategy receives the current set of SSTables (by reference), the
+/// manifest, the data directory, and the engine configuration. It returns:
 ///
-/// - `Ok(Some(result))` — compaction was performed; the caller should
-///   update in-memory state using [`CompactionResult`].
+/// - `Ok(Some(result))` — the intent is recorded and the output built; the
+///   caller commits it with [`commit_compaction`], or throws it away with
+///   [`abort_compaction`], then updates in-memory state using
+///   [`CompactionResult`].
 /// - `Ok(None)` — nothing to compact (thresholds not met, etc.).
 pub trait CompactionStrategy {
     /// Execute one round of compaction, if the strategy's preconditions
     /// are met. Implementations must be idempotent — calling when there
     /// is nothing to do should simply return `Ok(None)`.
+    ///
+    /// Versions above `horizon` are kept; see the module docs.
     fn compact(
         &self,
         sstables: &[Arc<SSTable>],
-        manifest: &mut Manifest,
+        manifest: &Manifest,
         data_dir: &str,
         config: &EngineConfig,
+        horizon: u64,
     ) -> Result<Option<CompactionResult>, CompactionError>;
 }
 
@@ -100,27 +169,190 @@ pub enum CompactionStrategyType {
     /// Groups SSTables into size buckets and merges similarly-sized tables.
     /// Good for write-heavy workloads with moderate space amplification.
     Stcs,
+
+    /// First-in, first-out: never merges, and drops the oldest SSTables
+    /// whole once they exceed `max_total_bytes` or the newest entry of
+    /// a table is `ttl_seconds` old. A limit of `0` is disabled; at
+    /// least one must be set.
+    ///
+    /// For caches and event buffers, where data expires before a merge
+    /// would pay off. With [`DbConfig::partitions`](crate::DbConfig::partitions)
+    /// above one, the budget is split evenly between the partitions.
+    ///
+    /// Tables are dropped with their tombstones, ignoring
+    /// `tombstone_gc_grace_seconds`. A table that a reader with pinned
+    /// versions may still read is kept until the pin is released, even
+    /// past the budget or TTL.
+    Fifo {
+        /// Total SSTable bytes to keep; `0` disables the budget.
+        max_total_bytes: usize,
+
+        /// Age, in seconds, of a table's newest entry past which the
+        /// table is dropped; `0` disables the TTL.
+        ttl_seconds: usize,
+    },
 }
 
 impl CompactionStrategyType {
+    /// Name of the SSTable subdirectory of this family under
+    /// [`SstDirLayout::PerStrategy`](crate::SstDirLayout::PerStrategy).
+    pub(crate) fn dir_name(&self) -> &'static str {
+        match self {
+            Self::Stcs => "stcs",
+            Self::Fifo { .. } => "fifo",
+        }
+    }
+
+    /// Returns this family with a [`Fifo`](Self::Fifo) budget split
+    /// evenly between `partitions`.
+    pub(crate) fn per_partition(self, partitions: u32) -> Self {
+        match self {
+            Self::Fifo {
+                max_total_bytes,
+                ttl_seconds,
+            } => Self::Fifo {
+                max_total_bytes: max_total_bytes.div_ceil(partitions as usize),
+                ttl_seconds,
+            },
+            Self::Stcs => Self::Stcs,
+        }
+    }
+
     /// Returns the minor compaction strategy for this family.
     pub fn minor(&self) -> Box<dyn CompactionStrategy> {
-        match self {
+        match *self {
             Self::Stcs => Box::new(stcs::MinorCompaction),
+            Self::Fifo {
+                max_total_bytes,
+                ttl_seconds,
+            } => Box::new(fifo::FifoCompaction {
+                max_total_bytes,
+                ttl_seconds,
+            }),
         }
     }
 
     /// Returns the tombstone compaction strategy for this family.
     pub fn tombstone(&self) -> Box<dyn CompactionStrategy> {
-        match self {
-            Self::Stcs => Box::new(stcs::TombstoneCompaction),
+        match *self {
+            Self::Stcs => Box::new(stcs::TombstoneCompaction { gc_stats: None }),
+            Self::Fifo {
+                max_total_bytes,
+                ttl_seconds,
+            } => Box::new(fifo::FifoCompaction {
+                max_total_bytes,
+                ttl_seconds,
+            }),
+        }
+    }
+
+    /// Returns the tombstone compaction strategy for this family, adding
+    /// its tombstone checks to `gc_stats`.
+    pub(crate) fn tombstone_tracked(
+        &self,
+        gc_stats: Arc<TombstoneGcCounters>,
+    ) -> Box<dyn CompactionStrategy> {
+        match *self {
+            Self::Stcs => Box::new(stcs::TombstoneCompaction {
+                gc_stats: Some(gc_stats),
+            }),
+            Self::Fifo {
+                max_total_bytes,
+                ttl_seconds,
+            } => Box::new(fifo::FifoCompaction {
+                max_total_bytes,
+                ttl_seconds,
+            }),
         }
     }
 
     /// Returns the major compaction strategy for this family.
     pub fn major(&self) -> Box<dyn CompactionStrategy> {
-        match self {
-            Self::Stcs => Box::new(stcs::MajorCompaction),
+        match *self {
+            Self::Stcs => Box::new(stcs::MajorCompaction { progress: None }),
+            Self::Fifo {
+                max_total_bytes,
+                ttl_seconds,
+            } => Box::new(fifo::FifoCompaction {
+                max_total_bytes,
+                ttl_seconds,
+            }),
+        }
+    }
+
+    /// Returns the major compaction strategy for this family, reporting
+    /// its merge to `progress`.
+    pub(crate) fn major_tracked(
+        &self,
+        progress: Arc<MajorProgress>,
+    ) -> Box<dyn CompactionStrategy> {
+        match *self {
+            Self::Stcs => Box::new(stcs::MajorCompaction {
+                progress: Some(progress),
+            }),
+            Self::Fifo {
+                max_total_bytes,
+                ttl_seconds,
+            } => Box::new(fifo::FifoCompaction {
+                max_total_bytes,
+                ttl_seconds,
+            }),
+        }
+    }
+
+    /// Returns the strategy compacting the hinted SSTables `ids`, adding
+    /// its tombstone checks to `gc_stats`.
+    pub(crate) fn hinted(
+        &self,
+        ids: Vec<u64>,
+        gc_stats: Arc<TombstoneGcCounters>,
+    ) -> Box<dyn CompactionStrategy> {
+        match *self {
+            Self::Stcs => Box::new(stcs::HintedCompaction {
+                ids,
+                gc_stats: Some(gc_stats),
+            }),
+            Self::Fifo {
+                max_total_bytes,
+                ttl_seconds,
+            } => Box::new(fifo::FifoCompaction {
+                max_total_bytes,
+                ttl_seconds,
+            }),
+        }
+    }
+
+    /// Returns the periodic compaction strategy for this family.
+    pub fn periodic(&self) -> Box<dyn CompactionStrategy> {
+        match *self {
+            Self::Stcs => Box::new(stcs::PeriodicCompaction { gc_stats: None }),
+            Self::Fifo {
+                max_total_bytes,
+                ttl_seconds,
+            } => Box::new(fifo::FifoCompaction {
+                max_total_bytes,
+                ttl_seconds,
+            }),
+        }
+    }
+
+    /// Returns the periodic compaction strategy for this family, adding
+    /// its tombstone checks to `gc_stats`.
+    pub(crate) fn periodic_tracked(
+        &self,
+        gc_stats: Arc<TombstoneGcCounters>,
+    ) -> Box<dyn CompactionStrategy> {
+        match *self {
+            Self::Stcs => Box::new(stcs::PeriodicCompaction {
+                gc_stats: Some(gc_stats),
+            }),
+            Self::Fifo {
+                max_total_bytes,
+                ttl_seconds,
+            } => Box::new(fifo::FifoCompaction {
+                max_total_bytes,
+                ttl_seconds,
+            }),
         }
     }
 }
@@ -129,6 +361,20 @@ impl CompactionStrategyType {
 // Shared types
 // ------------------------------------------------------------------------------------------------
 
+/// Urgency of a [`Db::suggest_compact_range`](crate::Db::suggest_compact_range)
+/// hint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CompactionPriority {
+    /// Compacted by the next compaction round, e.g. after the next
+    /// flush, once the `High` hints are done.
+    Low,
+
+    /// Compaction is queued on the background pool right away, and the
+    /// hint goes before every `Low` one.
+    #[default]
+    High,
+}
+
 /// Result of a compaction execution — enough information to update the
 /// manifest and in-memory SSTable list.
 pub struct CompactionResult {
@@ -141,24 +387,186 @@ pub struct CompactionResult {
 
     /// The ID allocated for the new SSTable (if one was produced).
     pub new_sst_id: Option<u64>,
+
+    /// IDs and paths of the consumed SSTable files, left on disk for the
+    /// engine to delete once no reader holds them.
+    pub removed_paths: Vec<(u64, std::path::PathBuf)>,
+
+    /// What building the new SSTable wrote; zero if none was built.
+    pub built: SstBuildStats,
+}
+
+/// Counters a major compaction advances as it merges, shared across the
+/// partitions it runs on.
+#[derive(Debug, Default)]
+pub(crate) struct MajorProgress {
+    /// Input records — points and range tombstones — of every merge
+    /// started so far.
+    pub(crate) records_total: AtomicU64,
+
+    /// Input records read by the merges so far.
+    pub(crate) records_merged: AtomicU64,
+
+    /// Partitions whose major compaction has finished.
+    pub(crate) partitions_done: AtomicUsize,
+}
+
+/// Snapshot of the point-tombstone checks made by tombstone and periodic
+/// compaction, returned by
+/// [`Db::tombstone_gc_stats`](crate::Db::tombstone_gc_stats).
+///
+/// Each point tombstone is checked against every older SSTable until one
+/// may still hold its key. A bloom filter that passes is resolved by
+/// reading the table when `tombstone_bloom_fallback` is set; the keys
+/// resolved against one table are checked in a single sorted sweep.
+/// Counters start at zero when the database is opened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TombstoneGcStats {
+    /// Point tombstones outside the GC grace period that were checked.
+    pub tombstones_checked: u64,
+
+    /// Of those, the tombstones dropped because no older SSTable holds
+    /// their key.
+    pub tombstones_dropped: u64,
+
+    /// Bloom filter checks, one per tombstone and older SSTable.
+    pub bloom_checks: u64,
+
+    /// Checks where the bloom filter ruled the key out.
+    pub bloom_negatives: u64,
+
+    /// Checks where the bloom filter passed but the table, once read, did
+    /// not hold the key. Only counted with `tombstone_bloom_fallback`.
+    pub bloom_false_positives: u64,
+
+    /// Data blocks read to resolve bloom filter passes.
+    pub block_reads: u64,
+}
+
+impl TombstoneGcStats {
+    /// Fraction of checks for absent keys that the bloom filter failed to
+    /// rule out: `false_positives / (negatives + false_positives)`.
+    ///
+    /// Returns `0.0` before any absent key has been checked.
+    pub fn bloom_false_positive_rate(&self) -> f64 {
+        let absent = self.bloom_negatives + self.bloom_false_positives;
+        if absent == 0 {
+            return 0.0;
+        }
+        self.bloom_false_positives as f64 / absent as f64
+    }
+}
+
+impl AddAssign for TombstoneGcStats {
+    fn add_assign(&mut self, other: Self) {
+        self.tombstones_checked += other.tombstones_checked;
+        self.tombstones_dropped += other.tombstones_dropped;
+        self.bloom_checks += other.bloom_checks;
+        self.bloom_negatives += other.bloom_negatives;
+        self.bloom_false_positives += other.bloom_false_positives;
+        self.block_reads += other.block_reads;
+    }
+}
+
+/// Live counters behind a [`TombstoneGcStats`] snapshot, shared by every
+/// tombstone and periodic compaction of one engine.
+#[derive(Debug, Default)]
+pub(crate) struct TombstoneGcCounters {
+    tombstones_checked: AtomicU64,
+    tombstones_dropped: AtomicU64,
+    bloom_checks: AtomicU64,
+    bloom_negatives: AtomicU64,
+    bloom_false_positives: AtomicU64,
+    block_reads: AtomicU64,
+}
+
+impl TombstoneGcCounters {
+    /// Adds the checks of one compaction.
+    pub(crate) fn record(&self, stats: &TombstoneGcStats) {
+        self.tombstones_checked
+            .fetch_add(stats.tombstones_checked, Ordering::Relaxed);
+        self.tombstones_dropped
+            .fetch_add(stats.tombstones_dropped, Ordering::Relaxed);
+        self.bloom_checks
+            .fetch_add(stats.bloom_checks, Ordering::Relaxed);
+        self.bloom_negatives
+            .fetch_add(stats.bloom_negatives, Ordering::Relaxed);
+        self.bloom_false_positives
+            .fetch_add(stats.bloom_false_positives, Ordering::Relaxed);
+        self.block_reads
+            .fetch_add(stats.block_reads, Ordering::Relaxed);
+    }
+
+    /// Returns the current values.
+    pub(crate) fn snapshot(&self) -> TombstoneGcStats {
+        TombstoneGcStats {
+            tombstones_checked: self.tombstones_checked.load(Ordering::Relaxed),
+            tombstones_dropped: self.tombstones_dropped.load(Ordering::Relaxed),
+            bloom_checks: self.bloom_checks.load(Ordering::Relaxed),
+            bloom_negatives: self.bloom_negatives.load(Ordering::Relaxed),
+            bloom_false_positives: self.bloom_false_positives.load(Ordering::Relaxed),
+            block_reads: self.block_reads.load(Ordering::Relaxed),
+        }
+    }
 }
 
 // ------------------------------------------------------------------------------------------------
 // Dedup logic — shared between minor and tombstone compaction
 // ------------------------------------------------------------------------------------------------
 
+/// Picks the point versions that survive a merge, fed in
+/// `(key ASC, LSN DESC)` order.
+///
+/// The newest version of a key always survives. Older ones survive while
+/// the version before them is above `horizon`, so a reader at any LSN at
+/// or above the horizon still finds the version it would have seen. Of
+/// versions sharing an LSN — one write found in two sources — only the
+/// first survives.
+pub(crate) struct VersionGc {
+    horizon: u64,
+    last_key: Option<Vec<u8>>,
+    last_lsn: u64,
+}
+
+impl VersionGc {
+    pub(crate) fn new(horizon: u64) -> Self {
+        Self {
+            horizon,
+            last_key: None,
+            last_lsn: 0,
+        }
+    }
+
+    /// Returns whether the version of `key` at `lsn`, the next in merge
+    /// order, survives.
+    pub(crate) fn keep(&mut self, key: &[u8], lsn: u64) -> bool {
+        let keep = if self.last_key.as_deref() == Some(key) {
+            // A second copy of the same write is never kept.
+            self.last_lsn > self.horizon && lsn < self.last_lsn
+        } else {
+            self.last_key = Some(key.to_vec());
+            true
+        };
+        self.last_lsn = lsn;
+        keep
+    }
+}
+
 /// Deduplicates a merge iterator stream into separate point entries
 /// and range tombstones.
 ///
-/// For each unique key, keeps only the version with the highest LSN.
-/// **All tombstones (point and range) are preserved** — this is safe
-/// for minor compaction where other SSTables may hold covered data.
+/// For each unique key, keeps the version with the highest LSN and, while
+/// a reader is pinned, the older versions above `horizon` as well (see
+/// [`VersionGc`]). **All tombstones (point and range) are preserved** —
+/// this is safe for minor compaction where other SSTables may hold
+/// covered data.
 pub fn dedup_records(
     merge_iter: impl Iterator<Item = Record>,
+    horizon: u64,
 ) -> (Vec<PointEntry>, Vec<RangeTombstone>) {
     let mut point_entries = Vec::new();
     let mut range_tombstones = Vec::new();
-    let mut last_key: Option<Vec<u8>> = None;
+    let mut versions = VersionGc::new(horizon);
 
     for record in merge_iter {
         match record {
@@ -181,10 +589,9 @@ pub fn dedup_records(
                 lsn,
                 timestamp,
             } => {
-                if last_key.as_ref() == Some(&key) {
+                if !versions.keep(&key, lsn) {
                     continue; // Older version — skip
                 }
-                last_key = Some(key.clone());
                 point_entries.push(PointEntry {
                     key,
                     value: Some(value),
@@ -197,10 +604,9 @@ pub fn dedup_records(
                 lsn,
                 timestamp,
             } => {
-                if last_key.as_ref() == Some(&key) {
+                if !versions.keep(&key, lsn) {
                     continue; // Older version — skip
                 }
-                last_key = Some(key.clone());
                 point_entries.push(PointEntry {
                     key,
                     value: None,
@@ -218,40 +624,86 @@ pub fn dedup_records(
 // Helpers
 // ------------------------------------------------------------------------------------------------
 
+/// Computes the tombstone GC cutoff (nanoseconds since the Unix epoch).
+///
+/// Tombstones with a write timestamp at or after the cutoff are still
+/// within `tombstone_gc_grace_seconds` and must not be dropped. Returns
+/// `None` when the grace period is disabled.
+pub(crate) fn tombstone_gc_cutoff(config: &EngineConfig) -> Option<u64> {
+    if config.tombstone_gc_grace_seconds == 0 {
+        return None;
+    }
+    let now_nanos = config.clock.now_nanos();
+    let grace_nanos = (config.tombstone_gc_grace_seconds as u64).saturating_mul(1_000_000_000);
+    Some(now_nanos.saturating_sub(grace_nanos))
+}
+
+/// Returns `true` if a tombstone written at `timestamp` is still within
+/// the GC grace period described by `cutoff`.
+pub(crate) fn within_gc_grace(timestamp: u64, cutoff: Option<u64>) -> bool {
+    cutoff.is_some_and(|c| timestamp >= c)
+}
+
+/// Returns scan bounds `[start, end)` that cover every point key and every
+/// range tombstone of `sstables`.
+///
+/// Point key bounds alone are not enough: a range tombstone may start
+/// below the smallest point key or above the largest, and a scan skips
+/// tombstones outside its bounds.
+pub(crate) fn full_scan_bounds(sstables: &[&SSTable]) -> (Vec<u8>, Vec<u8>) {
+    let start = sstables
+        .iter()
+        .map(|s| s.scan_lower_bound(&[]))
+        .min()
+        .unwrap_or_default()
+        .to_vec();
+
+    let mut end = sstables
+        .iter()
+        .flat_map(|s| {
+            let points = (s.record_count() > 0).then(|| s.max_key());
+            let ranges = s
+                .range_deletes
+                .data
+                .iter()
+                .map(|rd| rd.start_key.as_slice());
+            points.into_iter().chain(ranges)
+        })
+        .max()
+        .unwrap_or_default()
+        .to_vec();
+    // Extend past the largest key or range start to make it exclusive.
+    end.push(0xFF);
+    (start, end)
+}
+
 /// Creates scan iterators for the given SSTables covering their full key range.
 ///
-/// Computes the min/max key bounds across all selected SSTables and returns
-/// a vec of boxed iterators that can be fed into [`MergeIterator`].
+/// Computes key bounds across all selected SSTables, range tombstones
+/// included, and returns a vec of boxed iterators that can be fed into
+/// [`MergeIterator`].
+///
+/// The iterators are ordered newest SSTable first — by max LSN, then id,
+/// descending — so the merge resolves duplicate versions the same way as
+/// reads do (see [`VersionRank`](crate::engine::utils::VersionRank)).
 ///
 /// The returned iterators borrow the SSTables; iteration is **streaming**
 /// (block-by-block via mmap) so only one data block per SSTable is
 /// resident in memory at a time.
 pub fn full_range_scan_iters<'a>(
     sstables: &'a [&'a SSTable],
-) -> Result<Vec<Box<dyn Iterator<Item = Record> + 'a>>, SSTableError> {
+) -> Result<Vec<Box<dyn Iterator<Item = Record> + Send + 'a>>, SSTableError> {
     if sstables.is_empty() {
         return Ok(Vec::new());
     }
 
-    // Compute scan bounds from properties.
-    let min_key = sstables
-        .iter()
-        .map(|s| &s.properties.min_key)
-        .min()
-        .ok_or_else(|| SSTableError::Internal("empty sstables in full_range_scan".into()))?
-        .clone();
+    let (min_key, max_key) = full_scan_bounds(sstables);
 
-    let mut max_key = sstables
-        .iter()
-        .map(|s| &s.properties.max_key)
-        .max()
-        .ok_or_else(|| SSTableError::Internal("empty sstables in full_range_scan".into()))?
-        .clone();
-    // Extend max_key past the actual max key to make it exclusive.
-    max_key.push(0xFF);
+    let mut ordered: Vec<&'a SSTable> = sstables.to_vec();
+    ordered.sort_by_key(|s| std::cmp::Reverse((s.max_lsn(), s.id())));
 
-    let mut iters: Vec<Box<dyn Iterator<Item = Record> + 'a>> = Vec::new();
-    for sst in sstables {
+    let mut iters: Vec<Box<dyn Iterator<Item = Record> + Send + 'a>> = Vec::new();
+    for sst in ordered {
         let scan = sst.scan(&min_key, &max_key)?;
         iters.push(Box::new(scan));
     }
@@ -280,22 +732,161 @@ pub enum CompactionError {
 // Finalize — shared build + manifest + cleanup
 // ------------------------------------------------------------------------------------------------
 
-/// Builds a new SSTable from the given entries, atomically updates the
-/// manifest, and deletes old SSTable files.
+/// Records the intent to remove the SSTables `removed_ids`, stored at
+/// `removed_paths`, without writing a replacement. Their files are left to
+/// the caller.
+pub(crate) fn drop_sstables(
+    manifest: &Manifest,
+    removed_ids: Vec<u64>,
+    removed_paths: Vec<(u64, std::path::PathBuf)>,
+) -> Result<CompactionResult, CompactionError> {
+    manifest.begin_compaction(
+        input_entries(manifest, &removed_ids, &removed_paths)?,
+        Vec::new(),
+    )?;
+
+    Ok(CompactionResult {
+        removed_ids,
+        new_sst_path: None,
+        new_sst_id: None,
+        removed_paths,
+        built: SstBuildStats::default(),
+    })
+}
+
+/// Returns the manifest entries of the compaction inputs `removed_ids`,
+/// stored at `removed_paths`.
+///
+/// Fails with [`ManifestError::CompactionConflict`], and counts the
+/// compaction as skipped, if some inputs are missing from `removed_paths`:
+/// a compaction that committed after the strategy's snapshot was taken
+/// replaced them.
+fn input_entries(
+    manifest: &Manifest,
+    removed_ids: &[u64],
+    removed_paths: &[(u64, std::path::PathBuf)],
+) -> Result<Vec<ManifestSstEntry>, CompactionError> {
+    let gone: Vec<u64> = removed_ids
+        .iter()
+        .copied()
+        .filter(|id| !removed_paths.iter().any(|(live, _)| live == id))
+        .collect();
+    if !gone.is_empty() {
+        manifest.coordinator().skip();
+        return Err(ManifestError::CompactionConflict(gone).into());
+    }
+    Ok(removed_paths
+        .iter()
+        .map(|(id, path)| ManifestSstEntry {
+            id: *id,
+            path: path.clone(),
+        })
+        .collect())
+}
+
+/// Commits a compaction begun by a strategy: replaces its inputs with its
+/// output in the manifest and finishes it.
+///
+/// The input files stay on disk: readers may still hold them, and the
+/// engine deletes them once they let go. A crash before that leaves them
+/// as orphans for the next open to collect. If the commit fails, the
+/// inputs are released for other compactions; the intent stays pending
+/// for recovery to settle.
+pub(crate) fn commit_compaction(
+    manifest: &Manifest,
+    cr: &CompactionResult,
+) -> Result<(), CompactionError> {
+    let added = match (cr.new_sst_id, &cr.new_sst_path) {
+        (Some(id), Some(path)) => vec![ManifestSstEntry {
+            id,
+            path: path.into(),
+        }],
+        _ => Vec::new(),
+    };
+    if let Err(e) = record_commit(manifest, added, &cr.removed_ids) {
+        manifest.abandon_compaction(&cr.removed_ids);
+        return Err(e);
+    }
+    manifest.finish_compaction(&cr.removed_ids)?;
+    Ok(())
+}
+
+/// Finishes a compaction begun by a strategy without committing it: its
+/// inputs stay live, and its output, if any, is deleted.
+pub(crate) fn abort_compaction(
+    manifest: &Manifest,
+    cr: &CompactionResult,
+) -> Result<(), CompactionError> {
+    if let Some(path) = &cr.new_sst_path {
+        remove_output(path, cr.new_sst_id);
+    }
+    manifest.finish_compaction(&cr.removed_ids)?;
+    Ok(())
+}
+
+/// Deletes the output of a compaction that is not committed.
+fn remove_output(path: impl AsRef<std::path::Path>, new_sst_id: Option<u64>) {
+    if let Err(e) = std::fs::remove_file(path)
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        tracing::warn!(?new_sst_id, %e, "failed to remove compaction output");
+    }
+}
+
+/// Records the commit of a compaction in the manifest, in one entry, and
+/// checkpoints it.
+fn record_commit(
+    manifest: &Manifest,
+    added: Vec<ManifestSstEntry>,
+    removed_ids: &[u64],
+) -> Result<(), CompactionError> {
+    fail_point!(COMPACTION_BEFORE_MANIFEST);
+    manifest.apply_compaction(added, removed_ids.to_vec())?;
+    manifest.checkpoint()?;
+    fail_point!(COMPACTION_BEFORE_CLEANUP);
+    Ok(())
+}
+
+/// Builds a new SSTable from the given entries, for the engine to commit
+/// with [`commit_compaction`]; the input files are returned in the result
+/// for the engine to delete.
+///
+/// The manifest records the intent of the compaction before the new
+/// SSTable is built. Recovery settles a compaction a crash left pending:
+/// without the commit it deletes the output, with it the inputs. A failed
+/// build deletes the output and finishes at once.
+///
+/// Recording the intent claims the inputs; if another running compaction
+/// holds any of them, or one that committed since replaced them, this
+/// fails with [`ManifestError::CompactionConflict`] before anything is
+/// written.
 ///
 /// If both `point_entries` and `range_tombstones` are empty, no new SSTable
-/// is produced — old SSTables are simply removed.
+/// is produced — old SSTables are simply removed. Range tombstones may
+/// arrive in any order; they are sorted by start key and passed through
+/// [`coalesce_range_tombstones`] without bridging.
 ///
 /// This is the common tail shared by minor, tombstone, and major compaction.
+/// The new SSTable stores a checksum of every value if
+/// `config.value_checksums` is set, and its creation time is read from
+/// `config.clock`.
 pub(crate) fn finalize_compaction(
-    manifest: &mut Manifest,
+    manifest: &Manifest,
     data_dir: &str,
+    config: &EngineConfig,
     removed_ids: Vec<u64>,
     point_entries: Vec<PointEntry>,
-    range_tombstones: Vec<RangeTombstone>,
+    mut range_tombstones: Vec<RangeTombstone>,
 ) -> Result<CompactionResult, CompactionError> {
-    use std::fs;
-    use std::path::PathBuf;
+    use std::path::Path;
+
+    // Paths of the SSTables being replaced, as the manifest records them.
+    let removed_paths: Vec<_> = manifest
+        .get_sstables()?
+        .into_iter()
+        .filter(|entry| removed_ids.contains(&entry.id))
+        .map(|entry| (entry.id, entry.path))
+        .collect();
 
     if point_entries.is_empty() && range_tombstones.is_empty() {
         // Nothing survived — just remove old SSTables from manifest.
@@ -304,26 +895,22 @@ pub(crate) fn finalize_compaction(
             ?removed_ids,
             "finalize: all entries eliminated, removing old SSTables"
         );
-        manifest.apply_compaction(Vec::new(), removed_ids.clone())?;
-        manifest.checkpoint()?;
-
-        for id in &removed_ids {
-            let path = format!("{}/{}/{:06}.sst", data_dir, SSTABLE_DIR, id);
-            if let Err(e) = fs::remove_file(&path) {
-                tracing::warn!(id, %e, "failed to remove old SSTable file during compaction");
-            }
-        }
-
-        return Ok(CompactionResult {
-            removed_ids,
-            new_sst_path: None,
-            new_sst_id: None,
-        });
+        return drop_sstables(manifest, removed_ids, removed_paths);
     }
 
-    // Build new SSTable.
-    let new_sst_id = manifest.allocate_sst_id()?;
-    let new_sst_path = format!("{}/{}/{:06}.sst", data_dir, SSTABLE_DIR, new_sst_id);
+    // Strategies may collect range tombstones out of order; restore start
+    // order and drop redundant ones. SSTables outside the merge may hold
+    // versions with any LSN, so only dominated / equal-LSN ranges merge.
+    range_tombstones.sort_by(|a, b| a.start.cmp(&b.start).then(b.lsn.cmp(&a.lsn)));
+    let range_tombstones = coalesce_range_tombstones(range_tombstones, None);
+
+    // Record the intent, then build the new SSTable.
+    let new_entry = manifest.begin_compaction_with_output(
+        input_entries(manifest, &removed_ids, &removed_paths)?,
+        |id| layout::sstable_path(Path::new(data_dir), config, id),
+    )?;
+    let new_sst_id = new_entry.id;
+    let new_sst_path = new_entry.path.to_string_lossy().into_owned();
 
     let point_count = point_entries.len();
     let range_count = range_tombstones.len();
@@ -337,32 +924,31 @@ pub(crate) fn finalize_compaction(
         "finalize: building new SSTable"
     );
 
-    sstable::SstWriter::new(&new_sst_path).build(
-        point_entries.into_iter(),
-        point_count,
-        range_tombstones.into_iter(),
-        range_count,
-    )?;
-
-    // Atomic manifest update: add new, remove old.
-    let new_entry = ManifestSstEntry {
-        id: new_sst_id,
-        path: PathBuf::from(&new_sst_path),
-    };
-    manifest.apply_compaction(vec![new_entry], removed_ids.clone())?;
-    manifest.checkpoint()?;
-
-    // Delete old SSTable files.
-    for id in &removed_ids {
-        let path = format!("{}/{}/{:06}.sst", data_dir, SSTABLE_DIR, id);
-        if let Err(e) = fs::remove_file(&path) {
-            tracing::warn!(id, %e, "failed to remove old SSTable file during compaction");
+    let built = match sstable::SstWriter::new(&new_sst_path)
+        .value_checksums(config.value_checksums)
+        .file_digest(config.sstable_file_digest)
+        .tenant_prefixes(&config.tenant_prefixes)
+        .clock(Arc::clone(&config.clock))
+        .build(
+            point_entries.into_iter(),
+            point_count,
+            range_tombstones.into_iter(),
+            range_count,
+        ) {
+        Ok(built) => built,
+        Err(err) => {
+            // Roll back: the inputs stay live.
+            remove_output(&new_sst_path, Some(new_sst_id));
+            manifest.finish_compaction(&removed_ids)?;
+            return Err(err.into());
         }
-    }
+    };
 
     Ok(CompactionResult {
         removed_ids,
         new_sst_path: Some(new_sst_path),
         new_sst_id: Some(new_sst_id),
+        removed_paths,
+        built,
     })
 }
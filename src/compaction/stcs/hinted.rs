@@ -0,0 +1,82 @@
+//! Hinted compaction — compaction of the SSTables named by a hint from
+//! [`Db::suggest_compact_range`](crate::Db::suggest_compact_range).
+//!
+//! A hint names the SSTables that overlapped the suggested key range. They
+//! are compacted without waiting for a size bucket or tombstone ratio
+//! threshold, in two steps:
+//!
+//! 1. While the hint names several SSTables, they are merged like a minor
+//!    compaction, up to `max_threshold` at a time, dropping the versions
+//!    the newer tables shadow.
+//! 2. The single SSTable left is rewritten like a periodic compaction,
+//!    dropping the tombstones that no older SSTable needs.
+//!
+//! The engine keeps the hint pointing at the merged SSTable after step 1
+//! and retires it after step 2.
+
+use crate::compaction::{CompactionError, CompactionResult, TombstoneGcCounters};
+use crate::engine::EngineConfig;
+use crate::manifest::Manifest;
+use crate::sstable::SSTable;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+use super::{minor, tombstone};
+
+/// Runs the next step for the hinted SSTables `ids`.
+///
+/// Returns `Ok(None)` if fewer than two of them are live and the one left,
+/// if any, holds no tombstones. Tombstone checks are added to `gc_stats`,
+/// if given.
+pub fn compact(
+    sstables: &[Arc<SSTable>],
+    manifest: &Manifest,
+    data_dir: &str,
+    config: &EngineConfig,
+    horizon: u64,
+    ids: &[u64],
+    gc_stats: Option<&TombstoneGcCounters>,
+) -> Result<Option<CompactionResult>, CompactionError> {
+    let selected: Vec<usize> = sstables
+        .iter()
+        .enumerate()
+        .filter(|(_, sst)| ids.contains(&sst.id()))
+        .map(|(i, _)| i)
+        .take(config.max_threshold)
+        .collect();
+
+    let result = match selected.as_slice() {
+        [] => None,
+        &[target_idx] => {
+            let target = &sstables[target_idx];
+            if target.tombstone_count() + target.range_tombstone_count() == 0 {
+                None
+            } else {
+                info!(
+                    target_id = target.id(),
+                    "hinted compaction: rewriting SSTable"
+                );
+                Some(tombstone::execute(
+                    sstables, target_idx, manifest, data_dir, config, horizon, true, gc_stats,
+                )?)
+            }
+        }
+        _ => {
+            let selected_ids: Vec<u64> = selected.iter().map(|&i| sstables[i].id()).collect();
+            info!(?selected_ids, "hinted compaction: starting merge");
+            Some(minor::execute(
+                sstables, &selected, manifest, data_dir, config, horizon,
+            )?)
+        }
+    };
+
+    match &result {
+        Some(result) => info!(
+            new_sst_id = ?result.new_sst_id,
+            removed_count = result.removed_ids.len(),
+            "hinted compaction: complete"
+        ),
+        None => debug!(?ids, "hinted compaction: nothing left to compact"),
+    }
+    Ok(result)
+}
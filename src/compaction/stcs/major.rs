@@ -17,16 +17,26 @@
 //!   Put (if any) has already been suppressed or isn't present.
 //! - Range tombstones are dropped entirely — all covered data was
 //!   suppressed during the merge.
+//!
+//! The exception is tombstones still within `tombstone_gc_grace_seconds`:
+//! those are written to the output unchanged (range tombstones still
+//! suppress covered Puts during the merge).
+//!
+//! Tombstones above the version horizon are written unchanged as well,
+//! and suppress nothing: a reader pinned at the horizon still sees the
+//! versions beneath them.
 
 use crate::compaction::{
-    CompactionError, CompactionResult, MergeIterator, finalize_compaction, full_range_scan_iters,
+    CompactionError, CompactionResult, MajorProgress, MergeIterator, VersionGc,
+    finalize_compaction, full_range_scan_iters, tombstone_gc_cutoff, within_gc_grace,
 };
 use crate::engine::EngineConfig;
 use crate::engine::RangeTombstone;
-use crate::engine::utils::Record;
+use crate::engine::utils::{Record, VersionRank};
 use crate::manifest::Manifest;
 use crate::sstable::{PointEntry, SSTable};
 use std::sync::Arc;
+use std::sync::atomic::Ordering;
 use tracing::{debug, info, trace};
 
 // ------------------------------------------------------------------------------------------------
@@ -38,12 +48,17 @@ use tracing::{debug, info, trace};
 /// This is always user-triggered (via `Engine::major_compact()`). It
 /// will refuse to run if there are fewer than 2 SSTables.
 ///
+/// Versions and tombstones above `horizon` are kept. Every input record
+/// read is counted in `progress`, if given.
+///
 /// Returns `Ok(None)` if nothing to compact (0–1 SSTables).
 pub fn compact(
     sstables: &[Arc<SSTable>],
-    manifest: &mut Manifest,
+    manifest: &Manifest,
     data_dir: &str,
-    _config: &EngineConfig,
+    config: &EngineConfig,
+    horizon: u64,
+    progress: Option<&MajorProgress>,
 ) -> Result<Option<CompactionResult>, CompactionError> {
     if sstables.len() < 2 {
         debug!(
@@ -60,7 +75,7 @@ pub fn compact(
         "major compaction: starting full merge"
     );
 
-    let result = execute(sstables, manifest, data_dir)?;
+    let result = execute(sstables, manifest, data_dir, config, horizon, progress)?;
 
     info!(
         new_sst_id = ?result.new_sst_id,
@@ -77,19 +92,34 @@ pub fn compact(
 
 fn execute(
     sstables: &[Arc<SSTable>],
-    manifest: &mut Manifest,
+    manifest: &Manifest,
     data_dir: &str,
+    config: &EngineConfig,
+    horizon: u64,
+    progress: Option<&MajorProgress>,
 ) -> Result<CompactionResult, CompactionError> {
+    let gc_cutoff = tombstone_gc_cutoff(config);
     let sst_refs: Vec<&SSTable> = sstables.iter().map(|s| &**s).collect();
     let removed_ids: Vec<u64> = sstables.iter().map(|s| s.id()).collect();
 
     // Phase 1: Collect all range tombstones upfront from all SSTables.
     // We need them before processing point entries so we can check coverage.
+    // Those above the version horizon are not applied: a pinned reader
+    // still sees what they cover.
     let mut all_range_tombstones: Vec<RangeTombstone> = Vec::new();
     for sst in sstables {
         all_range_tombstones.extend(sst.range_tombstone_iter());
     }
 
+    // Range tombstones still within the GC grace period, or above the
+    // horizon, survive the merge.
+    let retained_ranges: Vec<RangeTombstone> = all_range_tombstones
+        .iter()
+        .filter(|rt| within_gc_grace(rt.timestamp, gc_cutoff) || rt.lsn > horizon)
+        .cloned()
+        .collect();
+    all_range_tombstones.retain(|rt| rt.lsn <= horizon);
+
     // Phase 2: Create merge iterator over all SSTables.
     let iters = full_range_scan_iters(&sst_refs)?;
     let merge_iter = MergeIterator::new(iters);
@@ -97,20 +127,45 @@ fn execute(
     // Phase 3: Process records — dedup point entries, apply range tombstones,
     // drop all tombstones.
     let mut point_entries: Vec<PointEntry> = Vec::new();
-    let mut last_key: Option<Vec<u8>> = None;
+    let mut versions = VersionGc::new(horizon);
+
+    if let Some(progress) = progress {
+        let total: u64 = sstables
+            .iter()
+            .map(|s| s.record_count() + s.range_tombstone_count())
+            .sum();
+        progress.records_total.fetch_add(total, Ordering::Relaxed);
+    }
 
     for record in merge_iter {
+        if let Some(progress) = progress {
+            progress.records_merged.fetch_add(1, Ordering::Relaxed);
+        }
+        let rank = record.rank();
         match record {
             Record::RangeDelete { .. } => {
                 // In major compaction, range tombstones are dropped entirely.
                 // Their effect was applied when we suppressed covered Puts below.
             }
-            Record::Delete { key, lsn, .. } => {
-                // Dedup: skip older versions.
-                if last_key.as_ref() == Some(&key) {
+            Record::Delete {
+                key,
+                lsn,
+                timestamp,
+            } => {
+                // Dedup: skip older versions below the horizon.
+                if !versions.keep(&key, lsn) {
+                    continue;
+                }
+                if within_gc_grace(timestamp, gc_cutoff) || lsn > horizon {
+                    trace!(key = ?key, lsn, "major: keeping point tombstone within GC grace or above the horizon");
+                    point_entries.push(PointEntry {
+                        key,
+                        value: None,
+                        lsn,
+                        timestamp,
+                    });
                     continue;
                 }
-                last_key = Some(key.clone());
                 // Point deletes are dropped in major compaction — the covered
                 // Put (if any) was already suppressed or isn't present in any
                 // SSTable.
@@ -122,15 +177,14 @@ fn execute(
                 lsn,
                 timestamp,
             } => {
-                // Dedup: skip older versions.
-                if last_key.as_ref() == Some(&key) {
+                // Dedup: skip older versions below the horizon.
+                if !versions.keep(&key, lsn) {
                     continue;
                 }
-                last_key = Some(key.clone());
 
-                // Check if this Put is suppressed by a range tombstone with
-                // higher LSN.
-                if is_suppressed_by_range(&key, lsn, &all_range_tombstones) {
+                // Check if this Put is suppressed by a range tombstone that
+                // outranks it.
+                if is_suppressed_by_range(&key, rank, &all_range_tombstones) {
                     trace!(key = ?key, lsn, "major: Put suppressed by range tombstone");
                     continue;
                 }
@@ -145,21 +199,28 @@ fn execute(
         }
     }
 
-    // Major compaction produces no tombstones in the output.
-    finalize_compaction(manifest, data_dir, removed_ids, point_entries, Vec::new())
+    // Only tombstones within the GC grace period or above the horizon
+    // reach the output.
+    finalize_compaction(
+        manifest,
+        data_dir,
+        config,
+        removed_ids,
+        point_entries,
+        retained_ranges,
+    )
 }
 
 // ------------------------------------------------------------------------------------------------
 // Range tombstone helpers
 // ------------------------------------------------------------------------------------------------
 
-/// Returns `true` if the given key+lsn is suppressed by any range
-/// tombstone with a strictly higher LSN.
-fn is_suppressed_by_range(key: &[u8], put_lsn: u64, range_tombstones: &[RangeTombstone]) -> bool {
-    for rt in range_tombstones {
-        if key >= rt.start.as_slice() && key < rt.end.as_slice() && rt.lsn > put_lsn {
-            return true;
-        }
-    }
-    false
+/// Returns `true` if the version of `key` ranked `rank` is hidden by any
+/// range tombstone (see [`RangeTombstone::hides`]).
+fn is_suppressed_by_range(
+    key: &[u8],
+    rank: VersionRank,
+    range_tombstones: &[RangeTombstone],
+) -> bool {
+    range_tombstones.iter().any(|rt| rt.hides(key, rank))
 }
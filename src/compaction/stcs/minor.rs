@@ -20,12 +20,13 @@ use tracing::{debug, info};
 /// `Ok(None)` if no bucket met the threshold.
 pub fn maybe_compact(
     sstables: &[Arc<SSTable>],
-    manifest: &mut Manifest,
+    manifest: &Manifest,
     data_dir: &str,
     config: &EngineConfig,
+    horizon: u64,
 ) -> Result<Option<CompactionResult>, CompactionError> {
     let buckets = bucket_sstables(sstables, config);
-    let selected = match select_compaction_bucket(&buckets, config) {
+    let selected = match select_compaction_bucket(sstables, &buckets, config) {
         Some(s) => s,
         None => {
             debug!(
@@ -43,7 +44,7 @@ pub fn maybe_compact(
         "minor compaction: starting merge"
     );
 
-    let result = execute(sstables, &selected, manifest, data_dir)?;
+    let result = execute(sstables, &selected, manifest, data_dir, config, horizon)?;
 
     info!(
         new_sst_id = ?result.new_sst_id,
@@ -57,12 +58,15 @@ pub fn maybe_compact(
 /// Executes minor compaction on the selected SSTable indices.
 ///
 /// Merges the selected SSTables into a single new SSTable, deduplicating
-/// point entries (keeping highest LSN per key) and preserving all tombstones.
-fn execute(
+/// point entries (keeping highest LSN per key, and older versions above
+/// `horizon`) and preserving all tombstones.
+pub(super) fn execute(
     sstables: &[Arc<SSTable>],
     selected_indices: &[usize],
-    manifest: &mut Manifest,
+    manifest: &Manifest,
     data_dir: &str,
+    config: &EngineConfig,
+    horizon: u64,
 ) -> Result<CompactionResult, CompactionError> {
     let selected_ssts: Vec<&SSTable> = selected_indices.iter().map(|&i| &*sstables[i]).collect();
 
@@ -72,12 +76,14 @@ fn execute(
     let iters = full_range_scan_iters(&selected_ssts)?;
     let merge_iter = MergeIterator::new(iters);
 
-    // Deduplicate — keeps highest LSN per key, preserves all tombstones.
-    let (point_entries, range_tombstones) = dedup_records(merge_iter);
+    // Deduplicate — keeps highest LSN per key, and the versions above the
+    // horizon; preserves all tombstones.
+    let (point_entries, range_tombstones) = dedup_records(merge_iter, horizon);
 
     finalize_compaction(
         manifest,
         data_dir,
+        config,
         removed_ids,
         point_entries,
         range_tombstones,
@@ -1,6 +1,6 @@
 //! # Size-Tiered Compaction Strategy (STCS)
 //!
-//! Groups SSTables into **size buckets** and provides three compaction
+//! Groups SSTables into **size buckets** and provides five compaction
 //! operations:
 //!
 //! - **Minor** — merges similarly-sized SSTables within a bucket, deduplicates
@@ -9,20 +9,29 @@
 //!   point and range tombstones that are provably unnecessary.
 //! - **Major** — merges *all* SSTables into one, actively applying range
 //!   tombstones and dropping all spent tombstones.
+//! - **Periodic** — rewrites the oldest SSTable once it outlives
+//!   `periodic_compaction_seconds`, even if no other trigger fires.
+//! - **Hinted** — merges, then rewrites, the SSTables overlapping a range
+//!   suggested by the application, without waiting for a threshold.
 
 #[cfg(test)]
 mod tests;
 
+pub mod hinted;
 pub mod major;
 pub mod minor;
+pub mod periodic;
 pub mod tombstone;
 
 use std::sync::Arc;
 
 use crate::engine::EngineConfig;
+use crate::sketch::KeySketch;
 use crate::sstable::SSTable;
 
-use crate::compaction::{CompactionError, CompactionResult, CompactionStrategy};
+use crate::compaction::{
+    CompactionError, CompactionResult, CompactionStrategy, MajorProgress, TombstoneGcCounters,
+};
 use crate::manifest::Manifest;
 
 // ------------------------------------------------------------------------------------------------
@@ -103,25 +112,106 @@ pub fn bucket_sstables(sstables: &[Arc<SSTable>], config: &EngineConfig) -> Vec<
 ///
 /// Returns the indices of SSTables to compact, or `None` if no bucket
 /// meets `min_threshold`. If multiple buckets qualify, picks the one
-/// with the most SSTables (to maximize compaction ratio). Limits the
-/// selection to `max_threshold` SSTables.
+/// with the most SSTables (to maximize compaction ratio); among buckets
+/// of equal size, the one whose merge drops the most duplicate versions
+/// (see [`duplicate_ratio`]). Limits the selection to `max_threshold`
+/// SSTables.
+///
+/// With `hot_sstable_compaction`, the qualifying bucket with the highest
+/// [`read_heat`] wins instead, and its SSTables are taken hottest first,
+/// so tables that reads keep probing are merged before cold ones. While
+/// no qualifying bucket has been read, selection falls back to the rule
+/// above.
 pub fn select_compaction_bucket(
+    sstables: &[Arc<SSTable>],
     buckets: &[Vec<usize>],
     config: &EngineConfig,
 ) -> Option<Vec<usize>> {
+    if config.hot_sstable_compaction
+        && let Some(selected) = select_hot_bucket(sstables, buckets, config)
+    {
+        return Some(selected);
+    }
+
     let mut best_bucket: Option<&Vec<usize>> = None;
     let mut best_count = 0usize;
+    let mut best_ratio = 0.0f64;
 
     for bucket in buckets {
-        if bucket.len() >= config.min_threshold && bucket.len() > best_count {
+        if bucket.len() < config.min_threshold || bucket.len() < best_count {
+            continue;
+        }
+        let ratio = duplicate_ratio(sstables, bucket);
+        if bucket.len() > best_count || ratio > best_ratio {
             best_bucket = Some(bucket);
             best_count = bucket.len();
+            best_ratio = ratio;
         }
     }
 
     best_bucket.map(|bucket| bucket.iter().take(config.max_threshold).copied().collect())
 }
 
+/// Picks the qualifying bucket with the highest [`read_heat`], ordered
+/// hottest SSTable first; `None` if no qualifying bucket has been read.
+fn select_hot_bucket(
+    sstables: &[Arc<SSTable>],
+    buckets: &[Vec<usize>],
+    config: &EngineConfig,
+) -> Option<Vec<usize>> {
+    let (bucket, _) = buckets
+        .iter()
+        .filter(|bucket| bucket.len() >= config.min_threshold)
+        .map(|bucket| (bucket, read_heat(sstables, bucket)))
+        .filter(|&(_, heat)| heat > 0.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+    let mut selected = bucket.clone();
+    // Stable: equally hot tables stay in ascending size order.
+    selected.sort_by(|&a, &b| read_heat(sstables, &[b]).total_cmp(&read_heat(sstables, &[a])));
+    selected.truncate(config.max_threshold);
+    Some(selected)
+}
+
+/// Reads served by the SSTables in `bucket` since open — lookups they
+/// answered plus scans that read them — per byte of their files.
+///
+/// Returns 0.0 for an unread or empty bucket.
+pub fn read_heat(sstables: &[Arc<SSTable>], bucket: &[usize]) -> f64 {
+    let mut reads = 0u64;
+    let mut bytes = 0u64;
+    for &i in bucket {
+        reads += sstables[i].access.hits() + sstables[i].access.scans();
+        bytes += sstables[i].file_size();
+    }
+    if reads == 0 {
+        return 0.0;
+    }
+    reads as f64 / bytes.max(1) as f64
+}
+
+/// Estimated fraction of the point records in `bucket` that are older
+/// versions of a key stored in another SSTable of the bucket, from the
+/// union of their key sketches.
+///
+/// Returns 0.0 if any SSTable lacks a sketch or the bucket holds no
+/// point records.
+pub fn duplicate_ratio(sstables: &[Arc<SSTable>], bucket: &[usize]) -> f64 {
+    let mut union = KeySketch::new();
+    let mut records = 0u64;
+    for &i in bucket {
+        match sstables[i].key_sketch() {
+            Some(sketch) => union.merge(sketch),
+            None => return 0.0,
+        }
+        records += sstables[i].record_count();
+    }
+    if records == 0 {
+        return 0.0;
+    }
+    1.0 - (union.estimate().min(records) as f64 / records as f64)
+}
+
 // ------------------------------------------------------------------------------------------------
 // CompactionStrategy implementations
 // ------------------------------------------------------------------------------------------------
@@ -133,40 +223,120 @@ impl CompactionStrategy for MinorCompaction {
     fn compact(
         &self,
         sstables: &[Arc<SSTable>],
-        manifest: &mut Manifest,
+        manifest: &Manifest,
         data_dir: &str,
         config: &EngineConfig,
+        horizon: u64,
     ) -> Result<Option<CompactionResult>, CompactionError> {
-        minor::maybe_compact(sstables, manifest, data_dir, config)
+        minor::maybe_compact(sstables, manifest, data_dir, config, horizon)
     }
 }
 
 /// STCS tombstone compaction — rewrites a single SSTable to drop safe tombstones.
-pub struct TombstoneCompaction;
+pub struct TombstoneCompaction {
+    /// Where to add the tombstone checks, if anywhere.
+    pub(crate) gc_stats: Option<Arc<TombstoneGcCounters>>,
+}
 
 impl CompactionStrategy for TombstoneCompaction {
     fn compact(
         &self,
         sstables: &[Arc<SSTable>],
-        manifest: &mut Manifest,
+        manifest: &Manifest,
         data_dir: &str,
         config: &EngineConfig,
+        horizon: u64,
     ) -> Result<Option<CompactionResult>, CompactionError> {
-        tombstone::maybe_compact(sstables, manifest, data_dir, config)
+        tombstone::maybe_compact(
+            sstables,
+            manifest,
+            data_dir,
+            config,
+            horizon,
+            self.gc_stats.as_deref(),
+        )
     }
 }
 
 /// STCS major compaction — full merge of all SSTables.
-pub struct MajorCompaction;
+pub struct MajorCompaction {
+    /// Where to report merge progress, if anywhere.
+    pub(crate) progress: Option<Arc<MajorProgress>>,
+}
 
 impl CompactionStrategy for MajorCompaction {
     fn compact(
         &self,
         sstables: &[Arc<SSTable>],
-        manifest: &mut Manifest,
+        manifest: &Manifest,
+        data_dir: &str,
+        config: &EngineConfig,
+        horizon: u64,
+    ) -> Result<Option<CompactionResult>, CompactionError> {
+        major::compact(
+            sstables,
+            manifest,
+            data_dir,
+            config,
+            horizon,
+            self.progress.as_deref(),
+        )
+    }
+}
+
+/// STCS periodic compaction — rewrites SSTables that outlived the period.
+pub struct PeriodicCompaction {
+    /// Where to add the tombstone checks, if anywhere.
+    pub(crate) gc_stats: Option<Arc<TombstoneGcCounters>>,
+}
+
+impl CompactionStrategy for PeriodicCompaction {
+    fn compact(
+        &self,
+        sstables: &[Arc<SSTable>],
+        manifest: &Manifest,
+        data_dir: &str,
+        config: &EngineConfig,
+        horizon: u64,
+    ) -> Result<Option<CompactionResult>, CompactionError> {
+        periodic::maybe_compact(
+            sstables,
+            manifest,
+            data_dir,
+            config,
+            horizon,
+            self.gc_stats.as_deref(),
+        )
+    }
+}
+
+/// STCS hinted compaction — merges, then rewrites, the SSTables named by
+/// a compaction hint.
+pub struct HintedCompaction {
+    /// Ids of the hinted SSTables.
+    pub(crate) ids: Vec<u64>,
+
+    /// Where to add the tombstone checks, if anywhere.
+    pub(crate) gc_stats: Option<Arc<TombstoneGcCounters>>,
+}
+
+impl CompactionStrategy for HintedCompaction {
+    fn compact(
+        &self,
+        sstables: &[Arc<SSTable>],
+        manifest: &Manifest,
         data_dir: &str,
         config: &EngineConfig,
+        horizon: u64,
     ) -> Result<Option<CompactionResult>, CompactionError> {
-        major::compact(sstables, manifest, data_dir, config)
+        hinted::compact(
+            sstables,
+            manifest,
+            data_dir,
+            config,
+            horizon,
+            &self.ids,
+            self.gc_stats.as_deref(),
+        )
     }
 }
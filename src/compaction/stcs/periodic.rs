@@ -0,0 +1,89 @@
+//! Periodic compaction — age-based rewrite of cold SSTables.
+//!
+//! Size-tiered and tombstone compaction only fire when their thresholds are
+//! met, so an SSTable that stops receiving neighbours may never be rewritten
+//! again. Periodic compaction picks the **oldest** SSTable whose age exceeds
+//! `periodic_compaction_seconds` and rewrites it on its own, so that
+//! tombstone GC (and any other per-record filtering done on rewrite)
+//! eventually visits every piece of data.
+//!
+//! The rewrite reuses tombstone compaction's per-SSTable GC, but always
+//! produces a new SSTable even when nothing could be dropped. The output
+//! carries a fresh creation timestamp, so each SSTable is revisited at most
+//! once per period.
+
+use crate::compaction::{CompactionError, CompactionResult, TombstoneGcCounters};
+use crate::engine::EngineConfig;
+use crate::manifest::Manifest;
+use crate::sstable::SSTable;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+use super::tombstone;
+
+// ------------------------------------------------------------------------------------------------
+// Public API
+// ------------------------------------------------------------------------------------------------
+
+/// Selects the oldest SSTable past the compaction period and rewrites it.
+///
+/// Returns `Ok(Some(result))` if compaction was performed, or
+/// `Ok(None)` if periodic compaction is disabled or no SSTable is old
+/// enough. Tombstone checks are added to `gc_stats`, if given.
+pub fn maybe_compact(
+    sstables: &[Arc<SSTable>],
+    manifest: &Manifest,
+    data_dir: &str,
+    config: &EngineConfig,
+    horizon: u64,
+    gc_stats: Option<&TombstoneGcCounters>,
+) -> Result<Option<CompactionResult>, CompactionError> {
+    let Some(target_idx) = select_candidate(sstables, config) else {
+        debug!(
+            sstable_count = sstables.len(),
+            period_secs = config.periodic_compaction_seconds,
+            "periodic compaction: no SSTable past the period"
+        );
+        return Ok(None);
+    };
+
+    let target = &sstables[target_idx];
+    info!(
+        target_id = target.id(),
+        creation_timestamp = target.creation_timestamp(),
+        "periodic compaction: rewriting aged SSTable"
+    );
+
+    let result = tombstone::execute(
+        sstables, target_idx, manifest, data_dir, config, horizon, true, gc_stats,
+    )?;
+
+    info!(
+        new_sst_id = ?result.new_sst_id,
+        "periodic compaction: complete"
+    );
+
+    Ok(Some(result))
+}
+
+// ------------------------------------------------------------------------------------------------
+// Selection
+// ------------------------------------------------------------------------------------------------
+
+/// Selects the oldest SSTable whose age is at least
+/// `config.periodic_compaction_seconds`.
+pub(crate) fn select_candidate(sstables: &[Arc<SSTable>], config: &EngineConfig) -> Option<usize> {
+    if config.periodic_compaction_seconds == 0 {
+        return None;
+    }
+
+    let now_nanos = config.clock.now_nanos();
+    let period_nanos = (config.periodic_compaction_seconds as u64).saturating_mul(1_000_000_000);
+
+    sstables
+        .iter()
+        .enumerate()
+        .filter(|(_, sst)| now_nanos.saturating_sub(sst.creation_timestamp()) >= period_nanos)
+        .min_by_key(|(_, sst)| sst.creation_timestamp())
+        .map(|(idx, _)| idx)
+}
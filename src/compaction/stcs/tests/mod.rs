@@ -2,4 +2,5 @@
 
 mod tests_major;
 mod tests_minor;
+mod tests_periodic;
 mod tests_tombstone;
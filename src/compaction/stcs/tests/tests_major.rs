@@ -2,8 +2,12 @@
 
 #[cfg(test)]
 mod tests {
+    use crate::clock::SystemClock;
+    use crate::compaction::MajorProgress;
     use crate::engine::{Engine, EngineConfig};
     use std::fs;
+    use std::sync::Arc;
+    use std::sync::atomic::Ordering;
 
     fn compaction_config() -> EngineConfig {
         let _ = tracing_subscriber::fmt()
@@ -12,6 +16,8 @@ mod tests {
             .try_init();
         EngineConfig {
             write_buffer_size: 256,
+            max_memtables_per_flush: 1,
+            max_frozen_memtables: 0,
             compaction_strategy: crate::compaction::CompactionStrategyType::Stcs,
             bucket_low: 0.5,
             bucket_high: 1.5,
@@ -22,7 +28,34 @@ mod tests {
             tombstone_compaction_interval: 0,
             tombstone_bloom_fallback: false,
             tombstone_range_drop: false,
+            tombstone_gc_grace_seconds: 0,
+            periodic_compaction_seconds: 0,
+            read_depth_compaction_threshold: 0,
+            deletion_run_compaction_threshold: 0,
+            hot_sstable_compaction: false,
+            read_fanout_limit: 0,
+            read_fanout_fallback: false,
             thread_pool_size: 2,
+            parallel_sstable_probe: false,
+            wal_recovery_mode: crate::wal::WalRecoveryMode::TolerateCorruptedTail,
+            max_memtable_age: 0,
+            max_total_wal_size: 0,
+            wal_retention_bytes: 0,
+            wal_retention_seconds: 0,
+            obsolete_file_grace_seconds: 0,
+            row_cache_size: 0,
+            max_mmap_bytes: 0,
+            page_cache_hints: false,
+            slow_op_threshold: None,
+            sstable_open_checks: crate::sstable::OpenChecks::Metadata,
+            sstable_layout: crate::engine::SstDirLayout::Flat,
+            sstable_naming: crate::engine::SstFileNaming::Id,
+            sstable_file_digest: None,
+            tenant_prefixes: Vec::new(),
+            value_checksums: false,
+            idempotency_retention_seconds: 3600,
+            idempotency_max_tokens: 100_000,
+            clock: Arc::new(SystemClock),
         }
     }
 
@@ -689,4 +722,57 @@ mod tests {
             "expected 30 live keys after mixed tombstone major compact"
         );
     }
+
+    /// # Scenario
+    /// A tracked major compaction counts every input record it merges.
+    ///
+    /// # Starting environment
+    /// Empty engine, 256 B write buffer.
+    ///
+    /// # Actions
+    /// 1. Write keys 0..50, flush; `delete_range("key_0030", "key_0040")`,
+    ///    then 20 `pad_` keys so every earlier write leaves the memtable,
+    ///    flush.
+    /// 2. Sum the point and range tombstone counts of the SSTables.
+    /// 3. `major_compact_tracked()`, then again on the single SSTable left.
+    ///
+    /// # Expected behavior
+    /// The first call reports the summed input as both total and merged;
+    /// the second compacts nothing and leaves the counters unchanged.
+    #[test]
+    fn major_compact_tracked_counts_merged_records() {
+        let dir = fresh_dir("tracked");
+        let engine = Engine::open(&dir, compaction_config()).unwrap();
+
+        for i in 0..50 {
+            let key = format!("key_{:04}", i).into_bytes();
+            engine.put(key, b"val".to_vec()).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+        engine
+            .delete_range(b"key_0030".to_vec(), b"key_0040".to_vec())
+            .unwrap();
+        for i in 0..20 {
+            let key = format!("pad_{:04}", i).into_bytes();
+            engine.put(key, b"val".to_vec()).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+
+        let expected: u64 = engine
+            .sstable_metadata()
+            .unwrap()
+            .iter()
+            .map(|m| m.record_count + m.range_tombstone_count)
+            .sum();
+        assert!(expected >= 51);
+
+        let progress = Arc::new(MajorProgress::default());
+        assert!(engine.major_compact_tracked(Arc::clone(&progress)).unwrap());
+        assert_eq!(progress.records_total.load(Ordering::Relaxed), expected);
+        assert_eq!(progress.records_merged.load(Ordering::Relaxed), expected);
+
+        assert!(!engine.major_compact_tracked(Arc::clone(&progress)).unwrap());
+        assert_eq!(progress.records_total.load(Ordering::Relaxed), expected);
+        assert_eq!(progress.records_merged.load(Ordering::Relaxed), expected);
+    }
 }
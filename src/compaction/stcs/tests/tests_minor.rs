@@ -2,8 +2,10 @@
 
 #[cfg(test)]
 mod tests {
+    use crate::clock::SystemClock;
     use crate::engine::{Engine, EngineConfig};
     use std::fs;
+    use std::sync::Arc;
 
     fn compaction_config() -> EngineConfig {
         let _ = tracing_subscriber::fmt()
@@ -12,6 +14,8 @@ mod tests {
             .try_init();
         EngineConfig {
             write_buffer_size: 256, // tiny — forces many SSTables
+            max_memtables_per_flush: 1,
+            max_frozen_memtables: 0,
             compaction_strategy: crate::compaction::CompactionStrategyType::Stcs,
             bucket_low: 0.5,
             bucket_high: 1.5,
@@ -22,7 +26,34 @@ mod tests {
             tombstone_compaction_interval: 0,
             tombstone_bloom_fallback: false,
             tombstone_range_drop: false,
+            tombstone_gc_grace_seconds: 0,
+            periodic_compaction_seconds: 0,
+            read_depth_compaction_threshold: 0,
+            deletion_run_compaction_threshold: 0,
+            hot_sstable_compaction: false,
+            read_fanout_limit: 0,
+            read_fanout_fallback: false,
             thread_pool_size: 2,
+            parallel_sstable_probe: false,
+            wal_recovery_mode: crate::wal::WalRecoveryMode::TolerateCorruptedTail,
+            max_memtable_age: 0,
+            max_total_wal_size: 0,
+            wal_retention_bytes: 0,
+            wal_retention_seconds: 0,
+            obsolete_file_grace_seconds: 0,
+            row_cache_size: 0,
+            max_mmap_bytes: 0,
+            page_cache_hints: false,
+            slow_op_threshold: None,
+            sstable_open_checks: crate::sstable::OpenChecks::Metadata,
+            sstable_layout: crate::engine::SstDirLayout::Flat,
+            sstable_naming: crate::engine::SstFileNaming::Id,
+            sstable_file_digest: None,
+            tenant_prefixes: Vec::new(),
+            value_checksums: false,
+            idempotency_retention_seconds: 3600,
+            idempotency_max_tokens: 100_000,
+            clock: Arc::new(SystemClock),
         }
     }
 
@@ -557,4 +588,135 @@ mod tests {
             assert_eq!(engine.get(key).unwrap(), Some(b"v1".to_vec()));
         }
     }
+
+    /// Builds and opens an SSTable holding `prefix_{i}` for each `i` in
+    /// `keys`.
+    fn sstable_with_keys(
+        dir: &str,
+        name: &str,
+        prefix: &str,
+        keys: std::ops::Range<u32>,
+    ) -> std::sync::Arc<crate::sstable::SSTable> {
+        let path = format!("{dir}/{name}.sst");
+        let points: Vec<crate::sstable::PointEntry> = keys
+            .map(|i| crate::sstable::PointEntry {
+                key: format!("{prefix}_{i:04}").into_bytes(),
+                value: Some(b"value".to_vec()),
+                lsn: 1,
+                timestamp: 1,
+            })
+            .collect();
+        let count = points.len();
+        crate::sstable::SstWriter::new(&path)
+            .build(points.into_iter(), count, std::iter::empty(), 0)
+            .unwrap();
+        std::sync::Arc::new(crate::sstable::SSTable::open(&path).unwrap())
+    }
+
+    /// # Scenario
+    /// Between qualifying buckets of equal size, minor compaction picks
+    /// the one whose tables share the most keys; a larger bucket still
+    /// wins outright.
+    ///
+    /// # Starting environment
+    /// Four hand-built SSTables: 0 and 1 with disjoint keys, 2 and 3 with
+    /// the same keys.
+    ///
+    /// # Actions
+    /// 1. Select among buckets `[0, 1]` and `[2, 3]`.
+    /// 2. Select among buckets `[0, 1, 2]` and `[3]`.
+    ///
+    /// # Expected behavior
+    /// - Step 1 selects `[2, 3]`, whose duplicate ratio is about 0.5.
+    /// - Step 2 selects `[0, 1, 2]`.
+    #[test]
+    fn minor_select_prefers_duplicate_heavy_bucket() {
+        let dir = fresh_dir("duplicate_ratio");
+        std::fs::create_dir_all(&dir).unwrap();
+        let sstables = vec![
+            sstable_with_keys(&dir, "0", "a", 0..200),
+            sstable_with_keys(&dir, "1", "b", 0..200),
+            sstable_with_keys(&dir, "2", "c", 0..200),
+            sstable_with_keys(&dir, "3", "c", 0..200),
+        ];
+        let config = compaction_config();
+
+        let ratio = crate::compaction::stcs::duplicate_ratio(&sstables, &[2, 3]);
+        assert!((0.45..0.55).contains(&ratio), "ratio {ratio}");
+        assert!(crate::compaction::stcs::duplicate_ratio(&sstables, &[0, 1]) < 0.05);
+
+        let selected = crate::compaction::stcs::select_compaction_bucket(
+            &sstables,
+            &[vec![0, 1], vec![2, 3]],
+            &config,
+        );
+        assert_eq!(selected, Some(vec![2, 3]));
+
+        let selected = crate::compaction::stcs::select_compaction_bucket(
+            &sstables,
+            &[vec![0, 1, 2], vec![3]],
+            &config,
+        );
+        assert_eq!(selected, Some(vec![0, 1, 2]));
+    }
+
+    /// # Scenario
+    /// With `hot_sstable_compaction`, minor compaction picks the bucket
+    /// read most per byte over a larger cold one, and takes its hottest
+    /// tables first.
+    ///
+    /// # Starting environment
+    /// Five hand-built SSTables of equal size with disjoint keys.
+    ///
+    /// # Actions
+    /// 1. Select among buckets `[0, 1, 2]` and `[3, 4]` with the knob.
+    /// 2. Record a lookup hit on table 3 and three scans of table 4;
+    ///    select again with and without the knob.
+    /// 3. Select with `max_threshold` = 2 among `[2, 3, 4]` and `[0, 1]`.
+    ///
+    /// # Expected behavior
+    /// - Step 1 falls back to the largest bucket, `[0, 1, 2]`.
+    /// - Step 2 selects `[4, 3]` with the knob and `[0, 1, 2]` without.
+    /// - Step 3 selects `[4, 3]`, leaving the cold table out.
+    #[test]
+    fn minor_select_prefers_hot_bucket() {
+        let dir = fresh_dir("hot_bucket");
+        std::fs::create_dir_all(&dir).unwrap();
+        let sstables: Vec<_> = (0..5)
+            .map(|i| sstable_with_keys(&dir, &i.to_string(), &format!("t{i}"), 0..200))
+            .collect();
+        let select = |buckets: &[Vec<usize>], config: &EngineConfig| {
+            crate::compaction::stcs::select_compaction_bucket(&sstables, buckets, config)
+        };
+        let cold = compaction_config();
+        let hot = EngineConfig {
+            hot_sstable_compaction: true,
+            ..compaction_config()
+        };
+        let buckets = [vec![0, 1, 2], vec![3, 4]];
+
+        assert_eq!(select(&buckets, &hot), Some(vec![0, 1, 2]));
+
+        sstables[3].access.record_hit(1);
+        for _ in 0..3 {
+            sstables[4].access.record_scan(1);
+        }
+        assert!(crate::compaction::stcs::read_heat(&sstables, &[3, 4]) > 0.0);
+        assert_eq!(
+            crate::compaction::stcs::read_heat(&sstables, &[0, 1, 2]),
+            0.0
+        );
+
+        assert_eq!(select(&buckets, &hot), Some(vec![4, 3]));
+        assert_eq!(select(&buckets, &cold), Some(vec![0, 1, 2]));
+
+        let capped = EngineConfig {
+            max_threshold: 2,
+            ..hot
+        };
+        assert_eq!(
+            select(&[vec![2, 3, 4], vec![0, 1]], &capped),
+            Some(vec![4, 3])
+        );
+    }
 }
@@ -0,0 +1,114 @@
+//! Periodic (age-based) compaction tests.
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::{Engine, EngineConfig};
+    use std::collections::HashSet;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn periodic_config(period_secs: usize) -> EngineConfig {
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .with_test_writer()
+            .try_init();
+        EngineConfig {
+            write_buffer_size: 256,
+            min_threshold: 100,
+            max_threshold: 200,
+            // High enough that tombstone compaction never fires on its own.
+            tombstone_ratio_threshold: 1.0,
+            tombstone_bloom_fallback: true,
+            tombstone_range_drop: true,
+            periodic_compaction_seconds: period_secs,
+            ..EngineConfig::default()
+        }
+    }
+
+    /// Write keys interleaved with deletes of keys that exist nowhere else,
+    /// spread across several SSTables.
+    fn populate(engine: &Engine) {
+        for i in 0..30 {
+            if i % 6 == 0 {
+                engine.delete(format!("gone_{i:04}").into_bytes()).unwrap();
+            }
+            let key = format!("key_{i:04}").into_bytes();
+            engine.put(key, b"val".to_vec()).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+    }
+
+    /// # Scenario
+    /// Periodic compaction is a no-op when disabled or when no SSTable has
+    /// reached the period.
+    ///
+    /// # Actions
+    /// 1. Populate with `periodic_compaction_seconds = 0`, compact.
+    /// 2. Reopen with a one-hour period, compact.
+    ///
+    /// # Expected behavior
+    /// Both calls return `false`; the SSTable set is unchanged.
+    #[test]
+    fn periodic_compact_noop_when_disabled_or_young() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), periodic_config(0)).unwrap();
+        populate(&engine);
+        let before = engine.stats().unwrap().sstables_count;
+        assert!(before > 1);
+
+        assert!(!engine.periodic_compact().unwrap());
+        engine.close().unwrap();
+
+        let engine = Engine::open(dir.path(), periodic_config(3600)).unwrap();
+        assert!(!engine.periodic_compact().unwrap());
+        assert_eq!(engine.stats().unwrap().sstables_count, before);
+    }
+
+    /// # Scenario
+    /// Aged SSTables are rewritten even though no size or tombstone
+    /// threshold is met.
+    ///
+    /// # Starting environment
+    /// Several SSTables holding puts and point tombstones for keys that
+    /// exist nowhere else; `tombstone_ratio_threshold = 1.0`.
+    ///
+    /// # Actions
+    /// 1. Confirm `tombstone_compact()` does nothing.
+    /// 2. Wait past a one-second period, then run `periodic_compact()`
+    ///    until it returns `false`.
+    ///
+    /// # Expected behavior
+    /// - Every original SSTable has been replaced.
+    /// - The orphaned tombstones were garbage-collected along the way.
+    /// - All live keys still read back.
+    #[test]
+    fn periodic_compact_rewrites_aged_sstables() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), periodic_config(1)).unwrap();
+        populate(&engine);
+
+        assert!(!engine.tombstone_compact().unwrap());
+
+        let before = engine.sstable_metadata().unwrap();
+        let before_ids: HashSet<u64> = before.iter().map(|m| m.id).collect();
+        let before_tombstones: u64 = before.iter().map(|m| m.tombstone_count).sum();
+        assert!(before_tombstones > 0);
+
+        std::thread::sleep(Duration::from_millis(1100));
+
+        let mut rounds = 0;
+        while engine.periodic_compact().unwrap() {
+            rounds += 1;
+        }
+        assert!(rounds >= before_ids.len());
+
+        let after = engine.sstable_metadata().unwrap();
+        assert!(after.iter().all(|m| !before_ids.contains(&m.id)));
+        assert_eq!(after.iter().map(|m| m.tombstone_count).sum::<u64>(), 0);
+
+        for i in 0..30 {
+            let key = format!("key_{i:04}").into_bytes();
+            assert_eq!(engine.get(key).unwrap(), Some(b"val".to_vec()));
+        }
+    }
+}
@@ -2,8 +2,11 @@
 
 #[cfg(test)]
 mod tests {
+    use crate::clock::SystemClock;
+    use crate::compaction::TombstoneGcStats;
     use crate::engine::{Engine, EngineConfig};
     use std::fs;
+    use std::sync::Arc;
 
     fn tombstone_config() -> EngineConfig {
         let _ = tracing_subscriber::fmt()
@@ -12,6 +15,8 @@ mod tests {
             .try_init();
         EngineConfig {
             write_buffer_size: 256,
+            max_memtables_per_flush: 1,
+            max_frozen_memtables: 0,
             compaction_strategy: crate::compaction::CompactionStrategyType::Stcs,
             bucket_low: 0.5,
             bucket_high: 1.5,
@@ -22,7 +27,34 @@ mod tests {
             tombstone_compaction_interval: 0,
             tombstone_bloom_fallback: true,
             tombstone_range_drop: true,
+            tombstone_gc_grace_seconds: 0,
+            periodic_compaction_seconds: 0,
+            read_depth_compaction_threshold: 0,
+            deletion_run_compaction_threshold: 0,
+            hot_sstable_compaction: false,
+            read_fanout_limit: 0,
+            read_fanout_fallback: false,
             thread_pool_size: 2,
+            parallel_sstable_probe: false,
+            wal_recovery_mode: crate::wal::WalRecoveryMode::TolerateCorruptedTail,
+            max_memtable_age: 0,
+            max_total_wal_size: 0,
+            wal_retention_bytes: 0,
+            wal_retention_seconds: 0,
+            obsolete_file_grace_seconds: 0,
+            row_cache_size: 0,
+            max_mmap_bytes: 0,
+            page_cache_hints: false,
+            slow_op_threshold: None,
+            sstable_open_checks: crate::sstable::OpenChecks::Metadata,
+            sstable_layout: crate::engine::SstDirLayout::Flat,
+            sstable_naming: crate::engine::SstFileNaming::Id,
+            sstable_file_digest: None,
+            tenant_prefixes: Vec::new(),
+            value_checksums: false,
+            idempotency_retention_seconds: 3600,
+            idempotency_max_tokens: 100_000,
+            clock: Arc::new(SystemClock),
         }
     }
 
@@ -159,6 +191,58 @@ mod tests {
         }
     }
 
+    /// # Scenario
+    /// Tombstone compaction counts its point-tombstone checks, and every
+    /// bloom filter pass for a never-written key is a false positive.
+    ///
+    /// # Starting environment
+    /// Empty engine, 256 B write buffer, `tombstone_bloom_fallback = true`.
+    ///
+    /// # Actions
+    /// 1. Write keys 0..20, flush.
+    /// 2. Delete never-written keys 100..110, each followed by a padding
+    ///    put, flush.
+    /// 3. `tombstone_compact()`; read `tombstone_gc_stats()`.
+    ///
+    /// # Expected behavior
+    /// - Before compaction the counters are zero.
+    /// - Every checked tombstone is dropped, and every bloom check is
+    ///   either a negative or a false positive.
+    #[test]
+    fn tombstone_compact_counts_bloom_false_positives() {
+        let dir = fresh_dir("gc_stats");
+        let engine = Engine::open(&dir, tombstone_config()).unwrap();
+
+        for i in 0..20 {
+            let key = format!("key_{:04}", i).into_bytes();
+            engine.put(key, b"val".to_vec()).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+        for i in 100..110 {
+            engine.delete(format!("key_{:04}", i).into_bytes()).unwrap();
+            let pad = format!("pad_{:04}", i).into_bytes();
+            engine.put(pad, b"x".to_vec()).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+        assert_eq!(engine.tombstone_gc_stats(), TombstoneGcStats::default());
+
+        assert!(engine.tombstone_compact().unwrap());
+
+        let stats = engine.tombstone_gc_stats();
+        assert!(stats.tombstones_checked > 0, "{stats:?}");
+        assert_eq!(stats.tombstones_dropped, stats.tombstones_checked);
+        assert_eq!(
+            stats.bloom_negatives + stats.bloom_false_positives,
+            stats.bloom_checks
+        );
+        assert!(stats.block_reads <= stats.bloom_false_positives);
+
+        for i in 100..110 {
+            let key = format!("key_{:04}", i).into_bytes();
+            assert_eq!(engine.get(key).unwrap(), None);
+        }
+    }
+
     /// # Scenario
     /// Without bloom fallback, the "maybe present" result from the bloom
     /// filter cannot be resolved. Tombstones must be conservatively kept
@@ -7,18 +7,33 @@
 //! no other SSTable *could* contain a live version of `key`.
 //! - Bloom filter check across all *other* SSTables.
 //!   - If no bloom says "maybe" → safe to drop.
-//!   - If bloom says "maybe" and `tombstone_bloom_fallback` is enabled → do
-//!     actual `get()` to resolve the false positive.
+//!   - If bloom says "maybe" and `tombstone_bloom_fallback` is enabled →
+//!     look the key up to resolve the false positive.
+//!
+//!   The checks run after the target is scanned, one SSTable at a time:
+//!   the keys whose bloom says "maybe" are looked up in a single sorted
+//!   sweep, which reads each data block of that SSTable at most once
+//!   instead of once per tombstone.
 //!
 //! **Range tombstones:** A range tombstone `[start, end)` can be dropped when
 //! `tombstone_range_drop` is enabled and scanning all older SSTables
 //! confirms that no live keys exist within that range.
+//!
+//! **Grace period:** Tombstones written within `tombstone_gc_grace_seconds`
+//! are never dropped, regardless of the checks above.
+//!
+//! **Version horizon:** Tombstones above the version horizon are never
+//! dropped either, nor are the older versions a pinned reader still sees.
 
-use crate::compaction::{CompactionError, CompactionResult, finalize_compaction};
+use crate::compaction::{
+    CompactionError, CompactionResult, TombstoneGcCounters, TombstoneGcStats, VersionGc,
+    finalize_compaction, full_scan_bounds, tombstone_gc_cutoff, within_gc_grace,
+};
 use crate::engine::EngineConfig;
 use crate::engine::RangeTombstone;
+use crate::engine::utils::below_end;
 use crate::manifest::Manifest;
-use crate::sstable::{GetResult, PointEntry, SSTable, SSTableError};
+use crate::sstable::{PointEntry, SSTable, SSTableError, SstBuildStats};
 use std::sync::Arc;
 use tracing::{debug, info, trace};
 
@@ -29,12 +44,15 @@ use tracing::{debug, info, trace};
 /// Selects an SSTable eligible for tombstone compaction and executes it.
 ///
 /// Returns `Ok(Some(result))` if compaction was performed, or
-/// `Ok(None)` if no SSTable was eligible.
+/// `Ok(None)` if no SSTable was eligible. Tombstone checks are added to
+/// `gc_stats`, if given.
 pub fn maybe_compact(
     sstables: &[Arc<SSTable>],
-    manifest: &mut Manifest,
+    manifest: &Manifest,
     data_dir: &str,
     config: &EngineConfig,
+    horizon: u64,
+    gc_stats: Option<&TombstoneGcCounters>,
 ) -> Result<Option<CompactionResult>, CompactionError> {
     let target_idx = match select_candidate(sstables, config) {
         Some(idx) => idx,
@@ -57,7 +75,9 @@ pub fn maybe_compact(
         "tombstone compaction: starting rewrite"
     );
 
-    let result = execute(sstables, target_idx, manifest, data_dir, config)?;
+    let result = execute(
+        sstables, target_idx, manifest, data_dir, config, horizon, false, gc_stats,
+    )?;
 
     // If execute() found a candidate but could not drop any tombstones,
     // the result has empty removed_ids.  Treat that as "nothing to do"
@@ -87,11 +107,8 @@ pub fn maybe_compact(
 ///
 /// Picks the SSTable with the highest tombstone ratio that exceeds
 /// `config.tombstone_ratio_threshold` and meets the minimum age.
-fn select_candidate(sstables: &[Arc<SSTable>], config: &EngineConfig) -> Option<usize> {
-    let now_secs = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
+pub(crate) fn select_candidate(sstables: &[Arc<SSTable>], config: &EngineConfig) -> Option<usize> {
+    let now_secs = config.clock.now_nanos() / 1_000_000_000;
 
     let mut best: Option<(usize, f64)> = None;
 
@@ -133,12 +150,22 @@ fn select_candidate(sstables: &[Arc<SSTable>], config: &EngineConfig) -> Option<
 
 /// Rewrites the target SSTable, dropping tombstones that are provably safe
 /// to remove.
-fn execute(
+///
+/// When nothing could be dropped the SSTable is left alone and the result
+/// has empty `removed_ids` — unless `always_rewrite` is set, in which case
+/// it is rewritten anyway (used by periodic compaction).
+///
+/// Versions and tombstones above `horizon` are kept for pinned readers.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn execute(
     sstables: &[Arc<SSTable>],
     target_idx: usize,
-    manifest: &mut Manifest,
+    manifest: &Manifest,
     data_dir: &str,
     config: &EngineConfig,
+    horizon: u64,
+    always_rewrite: bool,
+    gc_stats: Option<&TombstoneGcCounters>,
 ) -> Result<CompactionResult, CompactionError> {
     let target = &*sstables[target_idx];
     // Only check SSTables that are **older** (lower ID) than the target.
@@ -152,12 +179,11 @@ fn execute(
         .map(|(_, s)| &**s)
         .collect();
 
-    // Full scan of the target SSTable.
-    let min_key = target.properties.min_key.clone();
-    let mut max_key = target.properties.max_key.clone();
-    max_key.push(0xFF);
+    // Full scan of the target SSTable, range tombstones included.
+    let (min_key, max_key) = full_scan_bounds(&[target]);
 
     let scan_iter = target.scan(&min_key, &max_key)?;
+    let gc_cutoff = tombstone_gc_cutoff(config);
 
     let mut point_entries: Vec<PointEntry> = Vec::new();
     let mut range_tombstones: Vec<RangeTombstone> = Vec::new();
@@ -166,7 +192,10 @@ fn execute(
     // all point entries have been gathered, so we can detect coverage
     // of puts inside the same SSTable.
     let mut range_candidates: Vec<RangeTombstone> = Vec::new();
-    let mut last_key: Option<Vec<u8>> = None;
+    // Indices into `point_entries` of the point tombstones that may be
+    // dropped, resolved against the older SSTables after the scan.
+    let mut point_candidates: Vec<usize> = Vec::new();
+    let mut versions = VersionGc::new(horizon);
     let mut dropped_anything = false;
 
     for record in scan_iter {
@@ -177,12 +206,12 @@ fn execute(
                 lsn,
                 timestamp,
             } => {
-                // Dedup: keep only highest LSN per key.
-                if last_key.as_ref() == Some(&key) {
+                // Dedup: keep the highest LSN per key, and the versions
+                // above the horizon.
+                if !versions.keep(&key, lsn) {
                     dropped_anything = true;
                     continue;
                 }
-                last_key = Some(key.clone());
                 point_entries.push(PointEntry {
                     key,
                     value: Some(value),
@@ -195,18 +224,20 @@ fn execute(
                 lsn,
                 timestamp,
             } => {
-                // Dedup: keep only highest LSN per key.
-                if last_key.as_ref() == Some(&key) {
+                // Dedup: keep the highest LSN per key, and the versions
+                // above the horizon.
+                if !versions.keep(&key, lsn) {
                     dropped_anything = true;
                     continue;
                 }
-                last_key = Some(key.clone());
 
                 // Can we drop this point tombstone?
-                if can_drop_point_tombstone(&key, &older_sstables, config)? {
-                    trace!(key = ?key, lsn, "dropping point tombstone — no older data found");
-                    dropped_anything = true;
-                    continue;
+                if within_gc_grace(timestamp, gc_cutoff) {
+                    trace!(key = ?key, lsn, "keeping point tombstone — within GC grace period");
+                } else if lsn > horizon {
+                    trace!(key = ?key, lsn, "keeping point tombstone — above the version horizon");
+                } else {
+                    point_candidates.push(point_entries.len());
                 }
 
                 point_entries.push(PointEntry {
@@ -224,7 +255,10 @@ fn execute(
             } => {
                 // Defer the drop decision to a second pass so that we
                 // can check collected point_entries for covered puts.
-                if config.tombstone_range_drop {
+                if config.tombstone_range_drop
+                    && !within_gc_grace(timestamp, gc_cutoff)
+                    && lsn <= horizon
+                {
                     range_candidates.push(RangeTombstone {
                         start,
                         end,
@@ -243,22 +277,57 @@ fn execute(
         }
     }
 
-    // --- Second pass: resolve range tombstone candidates ---
+    // --- Second pass: resolve point tombstone candidates ---
+    let candidate_keys: Vec<&[u8]> = point_candidates
+        .iter()
+        .map(|&i| point_entries[i].key.as_slice())
+        .collect();
+    let mut stats = TombstoneGcStats::default();
+    let droppable =
+        droppable_point_tombstones(&candidate_keys, &older_sstables, config, &mut stats)?;
+    stats.tombstones_dropped = droppable.iter().filter(|&&drop| drop).count() as u64;
+    debug!(
+        target_id = target.id(),
+        tombstones_checked = stats.tombstones_checked,
+        tombstones_dropped = stats.tombstones_dropped,
+        bloom_checks = stats.bloom_checks,
+        bloom_false_positives = stats.bloom_false_positives,
+        bloom_false_positive_rate = stats.bloom_false_positive_rate(),
+        block_reads = stats.block_reads,
+        "tombstone compaction: point tombstones checked"
+    );
+    if let Some(gc_stats) = gc_stats {
+        gc_stats.record(&stats);
+    }
+    if stats.tombstones_dropped > 0 {
+        dropped_anything = true;
+        let mut drops = vec![false; point_entries.len()];
+        for (&i, &droppable) in point_candidates.iter().zip(&droppable) {
+            drops[i] = droppable;
+        }
+        let mut drops = drops.into_iter();
+        point_entries.retain(|entry| {
+            let dropped = drops.next().unwrap_or(false);
+            if dropped {
+                trace!(key = ?entry.key, lsn = entry.lsn, "dropping point tombstone — no older data found");
+            }
+            !dropped
+        });
+    }
+
+    // --- Third pass: resolve range tombstone candidates ---
     //
     // A range tombstone can only be dropped when:
     //   (a) no older SSTable contains live keys in the range, AND
     //   (b) no Put in *this* SSTable falls within the range with a
     //       lower LSN (the tombstone still suppresses it).
     for rt in range_candidates {
-        let safe_in_older = can_drop_range_tombstone(&rt.start, &rt.end, rt.lsn, &older_sstables)?;
+        let safe_in_older = can_drop_range_tombstone(&rt, &older_sstables)?;
 
         let covers_own_puts = safe_in_older
-            && point_entries.iter().any(|pe| {
-                pe.value.is_some()
-                    && pe.key.as_slice() >= rt.start.as_slice()
-                    && pe.key.as_slice() < rt.end.as_slice()
-                    && pe.lsn < rt.lsn
-            });
+            && point_entries
+                .iter()
+                .any(|pe| pe.value.is_some() && rt.hides(&pe.key, pe.rank()));
 
         if safe_in_older && !covers_own_puts {
             trace!(
@@ -272,11 +341,13 @@ fn execute(
     }
 
     // If nothing was dropped, no need to rewrite.
-    if !dropped_anything {
+    if !dropped_anything && !always_rewrite {
         return Ok(CompactionResult {
             removed_ids: Vec::new(),
             new_sst_path: None,
             new_sst_id: None,
+            removed_paths: Vec::new(),
+            built: SstBuildStats::default(),
         });
     }
 
@@ -284,6 +355,7 @@ fn execute(
     finalize_compaction(
         manifest,
         data_dir,
+        config,
         removed_ids,
         point_entries,
         range_tombstones,
@@ -294,66 +366,100 @@ fn execute(
 // Tombstone safety checks
 // ------------------------------------------------------------------------------------------------
 
-/// Determines whether a point tombstone for `key` can be safely dropped.
+/// Determines which of the point tombstones for `keys`, in ascending
+/// order, can be safely dropped, adding the checks made to `stats`.
 ///
 /// A tombstone is safe to drop when no other SSTable *could* contain a
-/// live version of `key` that this tombstone is suppressing.
-fn can_drop_point_tombstone(
-    key: &[u8],
+/// live version of its key. Each SSTable is checked for the keys still
+/// droppable: its bloom filter first, then — with
+/// `tombstone_bloom_fallback` — a single sorted sweep over the keys the
+/// filter passed, to resolve false positives.
+fn droppable_point_tombstones(
+    keys: &[&[u8]],
     others: &[&SSTable],
     config: &EngineConfig,
-) -> Result<bool, SSTableError> {
+    stats: &mut TombstoneGcStats,
+) -> Result<Vec<bool>, SSTableError> {
+    stats.tombstones_checked += keys.len() as u64;
+    let mut droppable = vec![true; keys.len()];
+
     for sst in others {
-        // Quick bloom filter check.
-        if !sst.bloom_may_contain(key) {
-            // Bloom definitively says "not present" → this SSTable is safe.
+        // Quick bloom filter check of every key still droppable.
+        let mut maybe_present = Vec::new();
+        for (i, key) in keys.iter().enumerate() {
+            if !droppable[i] {
+                continue;
+            }
+            stats.bloom_checks += 1;
+            if sst.bloom_may_contain(key) {
+                maybe_present.push(i);
+            } else {
+                // Bloom definitively says "not present" → this SSTable is safe.
+                stats.bloom_negatives += 1;
+            }
+        }
+        if maybe_present.is_empty() {
             continue;
         }
 
-        // Bloom says "maybe present".
-        if config.tombstone_bloom_fallback {
-            // Resolve the false positive via actual get().
-            let result = sst.get(key)?;
-            match result {
-                GetResult::NotFound => {} // false positive → safe
-                _ => return Ok(false),    // actually present → keep tombstone
+        if !config.tombstone_bloom_fallback {
+            // Without fallback lookups, we must conservatively keep them.
+            for i in maybe_present {
+                droppable[i] = false;
+            }
+            continue;
+        }
+
+        // Resolve the bloom positives in one sweep over the table.
+        let probe: Vec<&[u8]> = maybe_present.iter().map(|&i| keys[i]).collect();
+        let (found, block_reads) = sst.contains_sorted(&probe)?;
+        stats.block_reads += block_reads;
+        for (i, found) in maybe_present.into_iter().zip(found) {
+            if found {
+                // Actually present → keep tombstone.
+                droppable[i] = false;
+            } else {
+                stats.bloom_false_positives += 1;
             }
-        } else {
-            // Without fallback scan, we must conservatively keep the tombstone.
-            return Ok(false);
         }
     }
 
-    // No other SSTable could contain this key → safe to drop.
-    Ok(true)
+    Ok(droppable)
 }
 
-/// Determines whether a range tombstone `[start, end)` can be safely dropped.
+/// Determines whether a range tombstone can be safely dropped.
 ///
-/// Scans all other SSTables for live keys that fall within the range and
-/// have an LSN lower than the tombstone's LSN (i.e., keys that this
-/// tombstone is actively suppressing).
+/// Scans all other SSTables for point versions the tombstone hides
+/// (see [`RangeTombstone::hides`]), i.e. that it is actively suppressing.
 fn can_drop_range_tombstone(
-    start: &[u8],
-    end: &[u8],
-    tombstone_lsn: u64,
+    tombstone: &RangeTombstone,
     others: &[&SSTable],
 ) -> Result<bool, SSTableError> {
+    let (start, end) = (tombstone.start.as_slice(), tombstone.end.as_slice());
     for sst in others {
         // Quick check: does this SSTable's key range overlap with the tombstone?
-        if sst.properties.max_key.as_slice() < start || sst.properties.min_key.as_slice() >= end {
+        if sst.properties.max_key.as_slice() < start || !below_end(&sst.properties.min_key, end) {
             continue; // No overlap.
         }
 
-        // Scan the overlapping range.
-        let scan_iter = sst.scan(start, end)?;
+        // Scan the overlapping range; an unbounded one up to the table's
+        // last key.
+        let mut past_max = Vec::new();
+        let scan_end = if end.is_empty() {
+            past_max.extend_from_slice(&sst.properties.max_key);
+            past_max.push(0);
+            past_max.as_slice()
+        } else {
+            end
+        };
+        let scan_iter = sst.scan(start, scan_end)?;
         for record in scan_iter {
             match &record {
-                crate::engine::utils::Record::Put { lsn, .. }
-                | crate::engine::utils::Record::Delete { lsn, .. } => {
-                    if *lsn < tombstone_lsn {
-                        // There's a live key with lower LSN that this tombstone
-                        // is suppressing → cannot drop.
+                crate::engine::utils::Record::Put { key, .. }
+                | crate::engine::utils::Record::Delete { key, .. } => {
+                    if tombstone.hides(key, record.rank()) {
+                        // There's a live key this tombstone is suppressing
+                        // → cannot drop.
                         return Ok(false);
                     }
                 }
@@ -0,0 +1,148 @@
+//! Background major compaction started by
+//! [`Db::major_compact_async`](crate::Db::major_compact_async).
+//!
+//! The compaction runs on the background thread pool, one partition after
+//! another. A [`CompactionHandle`] reports how far the merge has got and
+//! lets the caller wait for the outcome; dropping the handle does not stop
+//! the compaction.
+//!
+//! At most one such compaction runs at a time: starting another while one
+//! is in flight returns a handle to the running one.
+
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Condvar, Mutex};
+
+use crate::DbError;
+use crate::compaction::MajorProgress;
+use crate::engine::EngineError;
+
+/// A snapshot of the progress of a background major compaction.
+///
+/// With a single partition, `records_merged / records_total` is the
+/// completed share once the merge has started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactionProgress {
+    /// Number of partitions to compact.
+    pub partitions: usize,
+
+    /// Partitions whose compaction has finished.
+    pub partitions_done: usize,
+
+    /// Input records — points and range tombstones — of the partitions
+    /// whose merge has started. Grows as later partitions start.
+    pub records_total: u64,
+
+    /// Input records merged so far.
+    pub records_merged: u64,
+
+    /// Whether the compaction has finished, successfully or not.
+    pub finished: bool,
+}
+
+/// Shared state of one background major compaction.
+pub(crate) struct MajorState {
+    /// Counters advanced by the merge.
+    pub(crate) progress: Arc<MajorProgress>,
+
+    /// Number of partitions to compact.
+    partitions: usize,
+
+    /// `Some` once finished: whether anything was compacted, or the error.
+    outcome: Mutex<Option<Result<bool, String>>>,
+
+    /// Signalled when `outcome` is set.
+    finished: Condvar,
+}
+
+impl MajorState {
+    /// Creates the state of a compaction over `partitions` partitions.
+    pub(crate) fn new(partitions: usize) -> Arc<Self> {
+        Arc::new(Self {
+            progress: Arc::new(MajorProgress::default()),
+            partitions,
+            outcome: Mutex::new(None),
+            finished: Condvar::new(),
+        })
+    }
+
+    /// Records the outcome and wakes every waiter.
+    pub(crate) fn finish(&self, outcome: Result<bool, String>) {
+        let mut slot = self.outcome.lock().unwrap_or_else(|e| e.into_inner());
+        *slot = Some(outcome);
+        self.finished.notify_all();
+    }
+
+    /// Returns `true` once the outcome is recorded.
+    pub(crate) fn is_finished(&self) -> bool {
+        self.outcome
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some()
+    }
+}
+
+/// Handle to a major compaction running in the background, returned by
+/// [`Db::major_compact_async`](crate::Db::major_compact_async).
+///
+/// Cloning the handle, or asking for the running compaction again, gives
+/// another view of the same compaction.
+#[derive(Clone)]
+pub struct CompactionHandle {
+    state: Arc<MajorState>,
+}
+
+impl std::fmt::Debug for CompactionHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompactionHandle")
+            .field("progress", &self.progress())
+            .finish()
+    }
+}
+
+impl CompactionHandle {
+    pub(crate) fn new(state: Arc<MajorState>) -> Self {
+        Self { state }
+    }
+
+    /// Returns the current progress. Never blocks.
+    pub fn progress(&self) -> CompactionProgress {
+        let progress = &self.state.progress;
+        CompactionProgress {
+            partitions: self.state.partitions,
+            partitions_done: progress.partitions_done.load(Ordering::Relaxed),
+            records_total: progress.records_total.load(Ordering::Relaxed),
+            records_merged: progress.records_merged.load(Ordering::Relaxed),
+            finished: self.is_finished(),
+        }
+    }
+
+    /// Returns `true` once the compaction has finished.
+    pub fn is_finished(&self) -> bool {
+        self.state.is_finished()
+    }
+
+    /// Blocks until the compaction finishes.
+    ///
+    /// Returns `true` if any partition was compacted, `false` if none had
+    /// two or more SSTables — as [`Db::major_compact`](crate::Db::major_compact).
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Engine`] — the compaction failed; the message names
+    ///   the cause.
+    pub fn wait(&self) -> Result<bool, DbError> {
+        let mut slot = self.state.outcome.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            if let Some(outcome) = slot.as_ref() {
+                return outcome
+                    .clone()
+                    .map_err(|msg| DbError::Engine(EngineError::Internal(msg)));
+            }
+            slot = self
+                .state
+                .finished
+                .wait(slot)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+}
@@ -0,0 +1,279 @@
+//! Builder and tuning presets for [`DbConfig`].
+//!
+//! [`DbConfig`] has grown a knob for every subsystem, and several of them
+//! only make sense together: a large write buffer wants fewer, larger
+//! compactions and more background threads; a point-lookup workload wants
+//! a row cache and shallow SSTable stacks. A [`ConfigProfile`] sets such a
+//! group of knobs coherently for one kind of workload, and
+//! [`DbConfigBuilder`] combines a profile with individual overrides and
+//! validates the result before [`Db::open`](crate::Db::open) does.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{
+    AdaptiveCompaction, BackgroundPanicPolicy, Clock, CompactionStrategyType, DbConfig, DbError,
+    EventListener, OpenChecks, SstDirLayout, SstFileDigest, SstFileNaming, TenantPrefix,
+    WalRecoveryMode,
+};
+
+const MIB: usize = 1024 * 1024;
+
+/// Workload a [`DbConfig`] is tuned for.
+///
+/// A profile sets the write buffer, flush batching, compaction
+/// thresholds, read-side compaction triggers, caches, and background pool
+/// size; every other setting is left alone. Each knob a profile does not
+/// tune is reset to its default, so applying a profile twice, or one
+/// after another, gives the same result as applying only the last.
+///
+/// | Knob | `Balanced` | `WriteHeavy` | `ReadHeavy` | `PointLookup` | `BulkLoad` |
+/// |------|-----------|--------------|-------------|---------------|------------|
+/// | `write_buffer_size` | 64 KiB | 4 MiB | 1 MiB | 1 MiB | 64 MiB |
+/// | `max_memtables_per_flush` | 1 | 4 | 1 | 1 | 8 |
+/// | `max_frozen_memtables` | 0 | 8 | 0 | 0 | 4 |
+/// | `min_compaction_threshold` | 4 | 8 | 2 | 2 | 16 |
+/// | `max_compaction_threshold` | 32 | 64 | 16 | 16 | 64 |
+/// | `read_depth_compaction_threshold` | 0 | 0 | 8 | 4 | 0 |
+/// | `read_fanout_limit` | 0 | 0 | 0 | 16 | 0 |
+/// | `read_fanout_fallback` | false | false | false | true | false |
+/// | `row_cache_size` | 0 | 0 | 0 | `cache_mb` MiB | 0 |
+/// | `page_cache_hints` | false | false | true | true | false |
+/// | `warm_up_sstables` | 0 | 0 | 4 | 4 | 0 |
+/// | `thread_pool_size` | 2 | 4 | 2 | 2 | 4 |
+/// | `background_queue_depth` | 16 | 64 | 16 | 16 | 64 |
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConfigProfile {
+    /// The defaults of [`DbConfig::default`].
+    #[default]
+    Balanced,
+
+    /// Sustained writes: large write buffers flushed several at a time,
+    /// and fewer, larger compactions run by more background threads, to
+    /// keep write amplification down. Frozen buffers are bounded so
+    /// writers slow down instead of exhausting memory when flushes fall
+    /// behind.
+    WriteHeavy,
+
+    /// Reads and scans dominate: SSTables are merged early and whenever
+    /// lookups probe too many of them, freshly written SSTables are
+    /// advised into the page cache, and the newest ones are read in at
+    /// open.
+    ReadHeavy,
+
+    /// Point lookups dominate: [`ReadHeavy`](Self::ReadHeavy) with a
+    /// row cache of `cache_mb` MiB and lookups that skip SSTables whose
+    /// key range rules the key out once too many would be probed.
+    PointLookup {
+        /// Size of the row cache in MiB; `0` disables it.
+        cache_mb: usize,
+    },
+
+    /// Loading a large data set: very large write buffers flushed in
+    /// batches, and compaction deferred until many SSTables pile up. Pair
+    /// with [`Db::enter_bulk_load`](crate::Db::enter_bulk_load) to also
+    /// skip per-write WAL syncs.
+    BulkLoad,
+}
+
+impl ConfigProfile {
+    /// Sets the knobs this profile tunes on `config`.
+    fn apply(self, config: &mut DbConfig) {
+        let defaults = DbConfig::default();
+        config.write_buffer_size = defaults.write_buffer_size;
+        config.max_memtables_per_flush = defaults.max_memtables_per_flush;
+        config.max_frozen_memtables = defaults.max_frozen_memtables;
+        config.min_compaction_threshold = defaults.min_compaction_threshold;
+        config.max_compaction_threshold = defaults.max_compaction_threshold;
+        config.read_depth_compaction_threshold = defaults.read_depth_compaction_threshold;
+        config.read_fanout_limit = defaults.read_fanout_limit;
+        config.read_fanout_fallback = defaults.read_fanout_fallback;
+        config.row_cache_size = defaults.row_cache_size;
+        config.page_cache_hints = defaults.page_cache_hints;
+        config.warm_up_sstables = defaults.warm_up_sstables;
+        config.thread_pool_size = defaults.thread_pool_size;
+        config.background_queue_depth = defaults.background_queue_depth;
+
+        match self {
+            Self::Balanced => {}
+            Self::WriteHeavy => {
+                config.write_buffer_size = 4 * MIB;
+                config.max_memtables_per_flush = 4;
+                config.max_frozen_memtables = 8;
+                config.min_compaction_threshold = 8;
+                config.max_compaction_threshold = 64;
+                config.thread_pool_size = 4;
+                config.background_queue_depth = 64;
+            }
+            Self::ReadHeavy => {
+                config.write_buffer_size = MIB;
+                config.min_compaction_threshold = 2;
+                config.max_compaction_threshold = 16;
+                config.read_depth_compaction_threshold = 8;
+                config.page_cache_hints = true;
+                config.warm_up_sstables = 4;
+            }
+            Self::PointLookup { cache_mb } => {
+                Self::ReadHeavy.apply(config);
+                config.read_depth_compaction_threshold = 4;
+                config.read_fanout_limit = 16;
+                config.read_fanout_fallback = true;
+                config.row_cache_size = cache_mb.saturating_mul(MIB);
+            }
+            Self::BulkLoad => {
+                config.write_buffer_size = 64 * MIB;
+                config.max_memtables_per_flush = 8;
+                config.max_frozen_memtables = 4;
+                config.min_compaction_threshold = 16;
+                config.max_compaction_threshold = 64;
+                config.thread_pool_size = 4;
+                config.background_queue_depth = 64;
+            }
+        }
+    }
+}
+
+impl DbConfig {
+    /// Returns a builder starting from [`DbConfig::default`].
+    pub fn builder() -> DbConfigBuilder {
+        DbConfigBuilder::default()
+    }
+
+    /// Returns the defaults tuned for sustained writes; see
+    /// [`ConfigProfile::WriteHeavy`].
+    pub fn for_write_heavy() -> Self {
+        Self::with_profile(ConfigProfile::WriteHeavy)
+    }
+
+    /// Returns the defaults tuned for read- and scan-heavy workloads; see
+    /// [`ConfigProfile::ReadHeavy`].
+    pub fn for_read_heavy() -> Self {
+        Self::with_profile(ConfigProfile::ReadHeavy)
+    }
+
+    /// Returns the defaults tuned for point lookups, with a row cache of
+    /// `cache_mb` MiB; see [`ConfigProfile::PointLookup`].
+    pub fn for_point_lookup(cache_mb: usize) -> Self {
+        Self::with_profile(ConfigProfile::PointLookup { cache_mb })
+    }
+
+    /// Returns the defaults tuned for loading a large data set; see
+    /// [`ConfigProfile::BulkLoad`].
+    pub fn for_bulk_load() -> Self {
+        Self::with_profile(ConfigProfile::BulkLoad)
+    }
+
+    fn with_profile(profile: ConfigProfile) -> Self {
+        let mut config = Self::default();
+        profile.apply(&mut config);
+        config
+    }
+}
+
+/// Builds a [`DbConfig`] from a [`ConfigProfile`] and individual settings.
+///
+/// Settings apply in call order, so call [`profile`](Self::profile)
+/// first and override single knobs after it. [`build`](Self::build)
+/// checks every bound [`Db::open`](crate::Db::open) would.
+///
+/// # Example
+///
+/// ```rust
+/// use aeternusdb::{ConfigProfile, DbConfig};
+///
+/// let config = DbConfig::builder()
+///     .profile(ConfigProfile::PointLookup { cache_mb: 64 })
+///     .partitions(4)
+///     .build()
+///     .unwrap();
+/// assert_eq!(config.row_cache_size, 64 * 1024 * 1024);
+/// ```
+#[derive(Default)]
+pub struct DbConfigBuilder {
+    config: DbConfig,
+}
+
+/// Defines one setter per [`DbConfig`] field.
+macro_rules! setters {
+    ($($field:ident: $ty:ty),* $(,)?) => {
+        $(
+            #[doc = concat!("Sets [`DbConfig::", stringify!($field), "`].")]
+            pub fn $field(mut self, $field: $ty) -> Self {
+                self.config.$field = $field;
+                self
+            }
+        )*
+    };
+}
+
+impl DbConfigBuilder {
+    /// Sets every knob `profile` tunes, keeping the other settings.
+    pub fn profile(mut self, profile: ConfigProfile) -> Self {
+        profile.apply(&mut self.config);
+        self
+    }
+
+    /// Adds a listener to [`DbConfig::event_listeners`].
+    pub fn event_listener(mut self, listener: Arc<dyn EventListener>) -> Self {
+        self.config.event_listeners.push(listener);
+        self
+    }
+
+    setters! {
+        write_buffer_size: usize,
+        max_memtables_per_flush: usize,
+        max_frozen_memtables: usize,
+        compaction_strategy: CompactionStrategyType,
+        min_compaction_threshold: usize,
+        max_compaction_threshold: usize,
+        tombstone_compaction_ratio: f64,
+        tombstone_compaction_interval: usize,
+        tombstone_bloom_fallback: bool,
+        tombstone_range_drop: bool,
+        tombstone_gc_grace_seconds: usize,
+        periodic_compaction_seconds: usize,
+        read_depth_compaction_threshold: usize,
+        deletion_run_compaction_threshold: usize,
+        hot_sstable_compaction: bool,
+        read_fanout_limit: usize,
+        read_fanout_fallback: bool,
+        max_memtable_age: usize,
+        max_total_wal_size: usize,
+        wal_retention_bytes: usize,
+        wal_retention_seconds: usize,
+        obsolete_file_grace_seconds: usize,
+        row_cache_size: usize,
+        max_mmap_bytes: usize,
+        page_cache_hints: bool,
+        sstable_open_checks: OpenChecks,
+        sstable_layout: SstDirLayout,
+        sstable_naming: SstFileNaming,
+        sstable_file_digest: Option<SstFileDigest>,
+        value_checksums: bool,
+        idempotency_retention_seconds: usize,
+        idempotency_max_tokens: usize,
+        warm_up_sstables: usize,
+        thread_pool_size: usize,
+        background_queue_depth: usize,
+        parallel_sstable_probe: bool,
+        wal_recovery_mode: WalRecoveryMode,
+        partitions: u32,
+        trace_sampling_ratio: f64,
+        slow_op_threshold: Option<Duration>,
+        adaptive_compaction: Option<AdaptiveCompaction>,
+        tenant_prefixes: Vec<TenantPrefix>,
+        background_panic_policy: BackgroundPanicPolicy,
+        clock: Arc<dyn Clock>,
+    }
+
+    /// Returns the configuration.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::InvalidConfig`] — settings are out of their documented
+    ///   bounds, alone or in combination, with every violation listed.
+    pub fn build(self) -> Result<DbConfig, DbError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
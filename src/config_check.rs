@@ -0,0 +1,335 @@
+//! Consistency checks for [`DbConfig`].
+//!
+//! [`Db::open`](crate::Db::open) and
+//! [`DbConfigBuilder::build`](crate::DbConfigBuilder::build) check every
+//! setting against its documented bounds, and settings that depend on
+//! each other against one another: a per-partition share of a cache that
+//! rounds down to nothing, or a switch that only acts together with a
+//! limit left disabled. Every check runs, and all violations are returned
+//! together in one [`ConfigViolations`], so a configuration can be fixed
+//! in one pass instead of one error at a time.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::{AdaptiveCompaction, CompactionStrategyType, DbConfig, DbError};
+
+/// One setting, or combination of settings, out of bounds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigViolation {
+    /// Names of the [`DbConfig`] fields involved; more than one when the
+    /// fields conflict with each other.
+    pub fields: Vec<&'static str>,
+
+    /// What is wrong, naming the bounds.
+    pub message: String,
+}
+
+impl fmt::Display for ConfigViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Every violation found in a configuration.
+///
+/// Carried by [`DbError::InvalidConfig`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigViolations {
+    /// Every violation, in field declaration order.
+    pub violations: Vec<ConfigViolation>,
+}
+
+impl ConfigViolations {
+    /// Returns `true` if no violation was found.
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Returns `true` if a violation involves the field named `field`.
+    pub fn involves(&self, field: &str) -> bool {
+        self.violations.iter().any(|v| v.fields.contains(&field))
+    }
+
+    /// Returns a report of the one violation `message` on `fields`.
+    pub(crate) fn single(fields: &[&'static str], message: impl Into<String>) -> Self {
+        let mut violations = Self::default();
+        violations.push(fields, message);
+        violations
+    }
+
+    /// Records the violation `message` on `fields`.
+    fn push(&mut self, fields: &[&'static str], message: impl Into<String>) {
+        self.violations.push(ConfigViolation {
+            fields: fields.to_vec(),
+            message: message.into(),
+        });
+    }
+
+    /// Records the violation `message` on `fields` unless `ok`.
+    fn require(&mut self, ok: bool, fields: &[&'static str], message: &str) {
+        if !ok {
+            self.push(fields, message);
+        }
+    }
+
+    /// Returns `Ok` if no violation was found, or the violations as
+    /// [`DbError::InvalidConfig`].
+    pub(crate) fn into_result(self) -> Result<(), DbError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(DbError::InvalidConfig(self))
+        }
+    }
+}
+
+impl fmt::Display for ConfigViolations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, violation) in self.violations.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{violation}")?;
+        }
+        Ok(())
+    }
+}
+
+impl DbConfig {
+    /// Validates all configuration parameters against their documented
+    /// bounds and against each other.
+    pub(crate) fn validate(&self) -> Result<(), DbError> {
+        self.check().into_result()
+    }
+
+    /// Returns every violation of the documented bounds.
+    fn check(&self) -> ConfigViolations {
+        let mut v = ConfigViolations::default();
+        v.require(
+            (1024..=256 * 1024 * 1024).contains(&self.write_buffer_size),
+            &["write_buffer_size"],
+            "write_buffer_size must be in [1024, 268435456]",
+        );
+        v.require(
+            (1..=64).contains(&self.max_memtables_per_flush),
+            &["max_memtables_per_flush"],
+            "max_memtables_per_flush must be in [1, 64]",
+        );
+        v.require(
+            self.max_frozen_memtables <= 1024,
+            &["max_frozen_memtables"],
+            "max_frozen_memtables must be in [0, 1024]",
+        );
+        if let CompactionStrategyType::Fifo {
+            max_total_bytes,
+            ttl_seconds,
+        } = self.compaction_strategy
+        {
+            v.require(
+                max_total_bytes != 0 || ttl_seconds != 0,
+                &["compaction_strategy"],
+                "compaction_strategy Fifo needs max_total_bytes or ttl_seconds",
+            );
+            v.require(
+                ttl_seconds <= 31_536_000,
+                &["compaction_strategy"],
+                "compaction_strategy Fifo ttl_seconds must be in [0, 31536000]",
+            );
+        }
+        v.require(
+            (2..=64).contains(&self.min_compaction_threshold),
+            &["min_compaction_threshold"],
+            "min_compaction_threshold must be in [2, 64]",
+        );
+        v.require(
+            (self.min_compaction_threshold..=256).contains(&self.max_compaction_threshold),
+            &["max_compaction_threshold", "min_compaction_threshold"],
+            "max_compaction_threshold must be in [min_compaction_threshold, 256]",
+        );
+        v.require(
+            self.tombstone_compaction_ratio > 0.0 && self.tombstone_compaction_ratio <= 1.0,
+            &["tombstone_compaction_ratio"],
+            "tombstone_compaction_ratio must be in (0.0, 1.0]",
+        );
+        v.require(
+            self.tombstone_compaction_interval <= 604_800,
+            &["tombstone_compaction_interval"],
+            "tombstone_compaction_interval must be in [0, 604800]",
+        );
+        v.require(
+            self.tombstone_gc_grace_seconds <= 31_536_000,
+            &["tombstone_gc_grace_seconds"],
+            "tombstone_gc_grace_seconds must be in [0, 31536000]",
+        );
+        v.require(
+            self.periodic_compaction_seconds <= 31_536_000,
+            &["periodic_compaction_seconds"],
+            "periodic_compaction_seconds must be in [0, 31536000]",
+        );
+        v.require(
+            self.read_depth_compaction_threshold <= 1024,
+            &["read_depth_compaction_threshold"],
+            "read_depth_compaction_threshold must be in [0, 1024]",
+        );
+        v.require(
+            self.deletion_run_compaction_threshold <= 1_000_000,
+            &["deletion_run_compaction_threshold"],
+            "deletion_run_compaction_threshold must be in [0, 1000000]",
+        );
+        v.require(
+            self.read_fanout_limit <= 65_536,
+            &["read_fanout_limit"],
+            "read_fanout_limit must be in [0, 65536]",
+        );
+        v.require(
+            !self.read_fanout_fallback || self.read_fanout_limit != 0,
+            &["read_fanout_fallback", "read_fanout_limit"],
+            "read_fanout_fallback needs a non-zero read_fanout_limit",
+        );
+        v.require(
+            self.max_memtable_age <= 31_536_000,
+            &["max_memtable_age"],
+            "max_memtable_age must be in [0, 31536000]",
+        );
+        v.require(
+            self.max_total_wal_size == 0 || self.max_total_wal_size >= 1024,
+            &["max_total_wal_size"],
+            "max_total_wal_size must be 0 or >= 1024",
+        );
+        v.require(
+            self.wal_retention_bytes == 0 || self.wal_retention_bytes >= 1024,
+            &["wal_retention_bytes"],
+            "wal_retention_bytes must be 0 or >= 1024",
+        );
+        v.require(
+            self.wal_retention_seconds <= 31_536_000,
+            &["wal_retention_seconds"],
+            "wal_retention_seconds must be in [0, 31536000]",
+        );
+        v.require(
+            self.obsolete_file_grace_seconds <= 31_536_000,
+            &["obsolete_file_grace_seconds"],
+            "obsolete_file_grace_seconds must be in [0, 31536000]",
+        );
+        self.check_partition_share(&mut v, "row_cache_size", self.row_cache_size);
+        self.check_partition_share(&mut v, "max_mmap_bytes", self.max_mmap_bytes);
+        v.require(
+            (1..=31_536_000).contains(&self.idempotency_retention_seconds),
+            &["idempotency_retention_seconds"],
+            "idempotency_retention_seconds must be in [1, 31536000]",
+        );
+        v.require(
+            (1..=10_000_000).contains(&self.idempotency_max_tokens),
+            &["idempotency_max_tokens"],
+            "idempotency_max_tokens must be in [1, 10000000]",
+        );
+        v.require(
+            self.warm_up_sstables <= 1024,
+            &["warm_up_sstables"],
+            "warm_up_sstables must be in [0, 1024]",
+        );
+        v.require(
+            (1..=32).contains(&self.thread_pool_size),
+            &["thread_pool_size"],
+            "thread_pool_size must be in [1, 32]",
+        );
+        v.require(
+            (1..=1024).contains(&self.background_queue_depth),
+            &["background_queue_depth"],
+            "background_queue_depth must be in [1, 1024]",
+        );
+        v.require(
+            (1..=256).contains(&self.partitions),
+            &["partitions"],
+            "partitions must be in [1, 256]",
+        );
+        v.require(
+            (0.0..=1.0).contains(&self.trace_sampling_ratio),
+            &["trace_sampling_ratio"],
+            "trace_sampling_ratio must be in [0.0, 1.0]",
+        );
+        if let Some(tuning) = &self.adaptive_compaction {
+            Self::check_adaptive_compaction(&mut v, tuning);
+        }
+        let mut prefixes = HashSet::new();
+        let mut duplicate = false;
+        for entry in &self.tenant_prefixes {
+            duplicate |= !prefixes.insert(entry.prefix.as_slice());
+        }
+        v.require(
+            self.tenant_prefixes
+                .iter()
+                .all(|entry| !entry.prefix.is_empty() && !entry.tenant.is_empty()),
+            &["tenant_prefixes"],
+            "tenant_prefixes entries must have a non-empty prefix and tenant",
+        );
+        v.require(
+            !duplicate,
+            &["tenant_prefixes"],
+            "tenant_prefixes must not list a prefix twice",
+        );
+        v
+    }
+
+    /// Checks a size split evenly between the partitions: `0`, or at least
+    /// 1024 bytes once split.
+    fn check_partition_share(&self, v: &mut ConfigViolations, field: &'static str, size: usize) {
+        if size != 0 && size < 1024 {
+            v.push(&[field], format!("{field} must be 0 or >= 1024"));
+        } else if size != 0
+            && (1..=256).contains(&self.partitions)
+            && size / (self.partitions as usize) < 1024
+        {
+            v.push(
+                &[field, "partitions"],
+                format!(
+                    "{field} must be 0 or >= 1024 per partition, got {} for each of {} partitions",
+                    size / self.partitions as usize,
+                    self.partitions
+                ),
+            );
+        }
+    }
+
+    /// Checks the bounds of [`DbConfig::adaptive_compaction`].
+    fn check_adaptive_compaction(v: &mut ConfigViolations, tuning: &AdaptiveCompaction) {
+        const FIELD: &[&str] = &["adaptive_compaction"];
+        v.require(
+            (1..=3600).contains(&tuning.interval_seconds),
+            FIELD,
+            "adaptive_compaction.interval_seconds must be in [1, 3600]",
+        );
+        v.require(
+            (1.0..).contains(&tuning.target_read_amplification),
+            FIELD,
+            "adaptive_compaction.target_read_amplification must be >= 1.0",
+        );
+        v.require(
+            (1.0..).contains(&tuning.target_write_amplification),
+            FIELD,
+            "adaptive_compaction.target_write_amplification must be >= 1.0",
+        );
+        v.require(
+            tuning.min_threshold_floor >= 2
+                && tuning.min_threshold_ceiling >= tuning.min_threshold_floor
+                && tuning.min_threshold_ceiling <= 64,
+            FIELD,
+            "adaptive_compaction min_threshold bounds must satisfy 2 <= floor <= ceiling <= 64",
+        );
+        v.require(
+            tuning.max_threshold_floor >= tuning.min_threshold_floor
+                && tuning.max_threshold_ceiling >= tuning.max_threshold_floor
+                && tuning.max_threshold_ceiling <= 256,
+            FIELD,
+            "adaptive_compaction max_threshold bounds must satisfy min_threshold_floor <= floor <= ceiling <= 256",
+        );
+        v.require(
+            tuning.tombstone_ratio_floor > 0.0
+                && tuning.tombstone_ratio_ceiling >= tuning.tombstone_ratio_floor
+                && tuning.tombstone_ratio_ceiling <= 1.0,
+            FIELD,
+            "adaptive_compaction tombstone_ratio bounds must satisfy 0.0 < floor <= ceiling <= 1.0",
+        );
+    }
+}
@@ -0,0 +1,71 @@
+//! Platform layer for making file contents and directory entries durable.
+//!
+//! Writing a file and fsyncing it makes its *contents* durable, but not
+//! its *name*: a newly created or renamed file is an entry in its parent
+//! directory, and that entry reaches disk only when the directory itself
+//! is synced. Every step that creates or renames a file the engine relies
+//! on after a crash goes through [`sync_parent`] or [`rename`].
+//!
+//! ## Platform notes
+//! - [`sync_file`] calls [`File::sync_all`], which on macOS and iOS issues
+//!   `F_FULLFSYNC` rather than `fsync` — plain `fsync` there only hands the
+//!   data to the drive, whose cache may still lose it on power loss — and
+//!   which retries on `EINTR`.
+//! - Directories are synced on Unix only. Windows cannot open a directory
+//!   as a file, and NTFS journals directory updates itself.
+//!
+//! ## Failed syncs
+//! A failed `fsync` (typically `EIO`) is never retried. Linux clears the
+//! error once it has been reported and may already have dropped the dirty
+//! pages, so a second `fsync` can succeed without the data ever reaching
+//! disk. The error is returned to the caller, which must treat the file
+//! as lost: the WAL refuses further writes, and flushes and compactions
+//! abandon their output.
+
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+
+/// Makes the contents and metadata of `file` durable.
+pub(crate) fn sync_file(file: &File) -> io::Result<()> {
+    file.sync_all().inspect_err(|e| {
+        tracing::error!(error = %e, "fsync failed; the file's unsynced data may be lost");
+    })
+}
+
+/// Makes the entries of directory `dir` durable: files created, renamed,
+/// or removed in it since the last sync.
+pub(crate) fn sync_dir(dir: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+    #[cfg(feature = "failpoints")]
+    crate::failpoints::record_dir_sync(dir);
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
+
+/// Makes the directory entry of the newly created `path` durable.
+pub(crate) fn sync_parent(path: &Path) -> io::Result<()> {
+    sync_dir(parent(path))
+}
+
+/// Renames `from` to `to` and syncs the parent directory of `to`, so the
+/// new name survives a crash once this returns.
+///
+/// Both paths must be in the same directory, or `from`'s directory must
+/// be synced separately for the old name to disappear durably.
+pub(crate) fn rename(from: &Path, to: &Path) -> io::Result<()> {
+    fs::rename(from, to)?;
+    #[cfg(feature = "failpoints")]
+    crate::failpoints::record_rename(from, to);
+    fail_point!(RENAME_BEFORE_DIR_SYNC);
+    sync_parent(to)
+}
+
+fn parent(path: &Path) -> &Path {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
@@ -0,0 +1,87 @@
+//! Compaction hints recorded by
+//! [`Db::suggest_compact_range`](crate::Db::suggest_compact_range).
+//!
+//! A hint names the SSTables that overlapped the suggested range when it
+//! was recorded; data written later is not part of it. Each compaction
+//! updates the hints: a merge of several SSTables, any of them hinted,
+//! puts its output in their place, while a single-SSTable rewrite retires
+//! the input. A hint ends when it names no live SSTable, or only one
+//! without tombstones, so every hint runs at most one merge and one
+//! rewrite.
+//!
+//! A suggested hint also keeps its key range, and is passed over while a
+//! [range lock](crate::range_lock) overlaps it.
+//!
+//! Hints live in memory only and are dropped on close.
+
+use std::sync::Arc;
+
+use crate::compaction::CompactionPriority;
+use crate::range_lock::RangeLocks;
+use crate::sstable::SSTable;
+
+/// SSTables suggested for compaction, in one partition.
+#[derive(Debug)]
+pub(crate) struct CompactionHint {
+    pub priority: CompactionPriority,
+
+    /// Ids of the hinted SSTables.
+    pub sstables: Vec<u64>,
+
+    /// Suggested `[start, end)` range; `None` for read-depth and
+    /// deletion-run hints.
+    pub range: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+/// Updates `hints` after a compaction consumed `removed` and produced
+/// `new_id`.
+pub(crate) fn record_compaction(
+    hints: &mut [CompactionHint],
+    removed: &[u64],
+    new_id: Option<u64>,
+) {
+    for hint in hints {
+        let before = hint.sstables.len();
+        hint.sstables.retain(|id| !removed.contains(id));
+        if hint.sstables.len() < before
+            && removed.len() > 1
+            && let Some(id) = new_id
+        {
+            hint.sstables.push(id);
+        }
+    }
+}
+
+/// Drops the hints with nothing left to compact, then returns the
+/// SSTables of the most urgent remaining hint whose range is not locked —
+/// the oldest of the highest priority.
+pub(crate) fn next_hint(
+    hints: &mut Vec<CompactionHint>,
+    sstables: &[Arc<SSTable>],
+    locks: &RangeLocks,
+) -> Option<Vec<u64>> {
+    hints.retain_mut(|hint| {
+        hint.sstables
+            .retain(|id| sstables.iter().any(|sst| sst.id() == *id));
+        match hint.sstables.as_slice() {
+            [] => false,
+            [id] => sstables.iter().any(|sst| {
+                sst.id() == *id && sst.tombstone_count() + sst.range_tombstone_count() > 0
+            }),
+            _ => true,
+        }
+    });
+
+    let mut best: Option<&CompactionHint> = None;
+    for hint in hints.iter() {
+        if let Some((start, end)) = &hint.range
+            && locks.is_locked(start, end)
+        {
+            continue;
+        }
+        if best.is_none_or(|b| hint.priority > b.priority) {
+            best = Some(hint);
+        }
+    }
+    best.map(|hint| hint.sstables.clone())
+}
@@ -0,0 +1,222 @@
+//! Consistency check between the manifest and the files it references.
+//!
+//! [`Engine::open`](super::Engine::open) runs the check before serving
+//! anything and fails with [`EngineError::Inconsistent`](super::EngineError::Inconsistent)
+//! if it finds a problem; [`Engine::check_consistency`](super::Engine::check_consistency)
+//! runs it again on demand against the files currently on disk.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::manifest::ManifestSstEntry;
+use crate::sstable::{OpenChecks, SSTable};
+
+/// One mismatch between the manifest and the files on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConsistencyIssue {
+    /// A manifest SSTable file does not exist.
+    MissingSSTable { id: u64, path: PathBuf },
+
+    /// A manifest SSTable file exists but its header, footer, or metadata
+    /// blocks do not parse.
+    UnreadableSSTable {
+        id: u64,
+        path: PathBuf,
+        reason: String,
+    },
+
+    /// Two manifest entries share an SSTable id.
+    DuplicateSSTableId { id: u64 },
+
+    /// An SSTable id was never handed out by the manifest's id counter, so
+    /// a future flush or compaction could overwrite the file.
+    UnallocatedSSTableId { id: u64, next_id: u64 },
+
+    /// An SSTable's properties claim `min_lsn > max_lsn`.
+    InvalidLsnRange { id: u64, min_lsn: u64, max_lsn: u64 },
+
+    /// An SSTable's properties claim `min_key > max_key`.
+    InvalidKeyRange { id: u64 },
+
+    /// A frozen WAL listed in the manifest does not exist, so the writes
+    /// it held were lost.
+    MissingFrozenWal { wal_id: u64, path: PathBuf },
+
+    /// A frozen WAL is not older than the active WAL.
+    FrozenWalNotOlder { wal_id: u64, active_wal_id: u64 },
+}
+
+impl fmt::Display for ConsistencyIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingSSTable { id, path } => {
+                write!(f, "SSTable {id} missing at {}", path.display())
+            }
+            Self::UnreadableSSTable { id, path, reason } => {
+                write!(f, "SSTable {id} at {} unreadable: {reason}", path.display())
+            }
+            Self::DuplicateSSTableId { id } => {
+                write!(f, "SSTable id {id} listed more than once")
+            }
+            Self::UnallocatedSSTableId { id, next_id } => {
+                write!(f, "SSTable id {id} not below next allocated id {next_id}")
+            }
+            Self::InvalidLsnRange {
+                id,
+                min_lsn,
+                max_lsn,
+            } => write!(f, "SSTable {id} has min LSN {min_lsn} > max LSN {max_lsn}"),
+            Self::InvalidKeyRange { id } => write!(f, "SSTable {id} has min key > max key"),
+            Self::MissingFrozenWal { wal_id, path } => {
+                write!(f, "frozen WAL {wal_id} missing at {}", path.display())
+            }
+            Self::FrozenWalNotOlder {
+                wal_id,
+                active_wal_id,
+            } => write!(
+                f,
+                "frozen WAL {wal_id} is not older than active WAL {active_wal_id}"
+            ),
+        }
+    }
+}
+
+/// Result of a consistency check.
+///
+/// Returned by [`Db::check_consistency`](crate::Db::check_consistency),
+/// and carried by the error when [`Db::open`](crate::Db::open) finds a
+/// problem. With several partitions the counts and issues are combined.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    /// Manifest SSTable entries checked.
+    pub sstables_checked: usize,
+
+    /// Frozen WALs checked.
+    pub frozen_wals_checked: usize,
+
+    /// Every problem found, in the order it was found.
+    pub issues: Vec<ConsistencyIssue>,
+}
+
+impl ConsistencyReport {
+    /// Returns `true` if no issue was found.
+    pub fn is_consistent(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Checks that every frozen WAL exists and predates the active WAL.
+    ///
+    /// Returns the frozen WAL ids whose files exist.
+    pub(crate) fn check_frozen_wals(
+        &mut self,
+        memtable_dir: &Path,
+        active_wal_id: u64,
+        frozen_wal_ids: &[u64],
+    ) -> Vec<u64> {
+        let mut present = Vec::with_capacity(frozen_wal_ids.len());
+        for &wal_id in frozen_wal_ids {
+            self.frozen_wals_checked += 1;
+            if wal_id >= active_wal_id {
+                self.issues.push(ConsistencyIssue::FrozenWalNotOlder {
+                    wal_id,
+                    active_wal_id,
+                });
+            }
+            let path = memtable_dir.join(format!("{wal_id:06}.log"));
+            if path.is_file() {
+                present.push(wal_id);
+            } else {
+                self.issues
+                    .push(ConsistencyIssue::MissingFrozenWal { wal_id, path });
+            }
+        }
+        present
+    }
+
+    /// Checks SSTable ids for duplicates and for ids the manifest never
+    /// allocated.
+    pub(crate) fn check_sstable_ids(&mut self, entries: &[ManifestSstEntry], next_id: u64) {
+        let mut seen = HashSet::with_capacity(entries.len());
+        for entry in entries {
+            if !seen.insert(entry.id) {
+                self.issues
+                    .push(ConsistencyIssue::DuplicateSSTableId { id: entry.id });
+            }
+            if entry.id >= next_id {
+                self.issues.push(ConsistencyIssue::UnallocatedSSTableId {
+                    id: entry.id,
+                    next_id,
+                });
+            }
+        }
+    }
+
+    /// Opens the SSTable of `entry` with `checks` and checks its
+    /// properties.
+    ///
+    /// Returns the opened table, or `None` if it is missing or unreadable.
+    pub(crate) fn open_sstable(
+        &mut self,
+        entry: &ManifestSstEntry,
+        checks: OpenChecks,
+    ) -> Option<SSTable> {
+        self.sstables_checked += 1;
+        let id = entry.id;
+        if !entry.path.is_file() {
+            self.issues.push(ConsistencyIssue::MissingSSTable {
+                id,
+                path: entry.path.clone(),
+            });
+            return None;
+        }
+
+        let mut sstable = match SSTable::open_with(&entry.path, checks) {
+            Ok(sstable) => sstable,
+            Err(e) => {
+                self.issues.push(ConsistencyIssue::UnreadableSSTable {
+                    id,
+                    path: entry.path.clone(),
+                    reason: e.to_string(),
+                });
+                return None;
+            }
+        };
+        sstable.set_id(id);
+
+        if sstable.min_lsn() > sstable.max_lsn() {
+            self.issues.push(ConsistencyIssue::InvalidLsnRange {
+                id,
+                min_lsn: sstable.min_lsn(),
+                max_lsn: sstable.max_lsn(),
+            });
+        }
+        if sstable.record_count() > 0 && sstable.min_key() > sstable.max_key() {
+            self.issues.push(ConsistencyIssue::InvalidKeyRange { id });
+        }
+        Some(sstable)
+    }
+
+    /// Folds in the report of another partition.
+    pub(crate) fn merge(&mut self, other: ConsistencyReport) {
+        self.sstables_checked += other.sstables_checked;
+        self.frozen_wals_checked += other.frozen_wals_checked;
+        self.issues.extend(other.issues);
+    }
+}
+
+impl fmt::Display for ConsistencyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} issue(s) in {} SSTable(s) and {} frozen WAL(s)",
+            self.issues.len(),
+            self.sstables_checked,
+            self.frozen_wals_checked
+        )?;
+        for issue in &self.issues {
+            write!(f, "; {issue}")?;
+        }
+        Ok(())
+    }
+}
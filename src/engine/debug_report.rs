@@ -0,0 +1,286 @@
+//! Structured description of the LSM tree for debugging compaction.
+//!
+//! [`Db::debug_report`](crate::Db::debug_report) lists every SSTable with
+//! its size-tier bucket, the pairs of SSTables whose key ranges overlap,
+//! and the tables each compaction strategy would pick right now, together
+//! with the thresholds that drove the choice. [`DebugReport::to_dot`]
+//! renders the same data as a Graphviz graph.
+//!
+//! With the `serde` feature every type implements `serde::Serialize`.
+
+use std::fmt::Write;
+use std::sync::Arc;
+
+use super::EngineConfig;
+use crate::compaction::stcs::{self, periodic, tombstone};
+use crate::sstable::SSTable;
+
+/// Description of the whole tree, one entry per partition.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DebugReport {
+    /// Partitions in index order; a single entry without partitioning.
+    pub partitions: Vec<PartitionReport>,
+}
+
+/// Description of one LSM tree.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PartitionReport {
+    /// Partition index.
+    pub partition: usize,
+
+    /// Frozen memtables awaiting flush.
+    pub frozen_memtables: usize,
+
+    /// SSTables, newest max LSN first.
+    pub sstables: Vec<SSTableReport>,
+
+    /// Size-tier buckets, smallest SSTables first.
+    pub buckets: Vec<BucketReport>,
+
+    /// Pairs of SSTable ids whose key ranges overlap.
+    pub overlaps: Vec<(u64, u64)>,
+
+    /// What each compaction strategy would pick now.
+    pub candidates: CompactionCandidates,
+}
+
+/// One SSTable as seen by compaction.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SSTableReport {
+    /// Manifest-assigned SSTable identifier.
+    pub id: u64,
+
+    /// File size in bytes.
+    pub file_size: u64,
+
+    /// Smallest point key.
+    pub min_key: Vec<u8>,
+
+    /// Largest point key.
+    pub max_key: Vec<u8>,
+
+    /// Lowest LSN present.
+    pub min_lsn: u64,
+
+    /// Highest LSN present.
+    pub max_lsn: u64,
+
+    /// Point records (puts and point deletes).
+    pub record_count: u64,
+
+    /// Point plus range tombstones over `record_count`.
+    pub tombstone_ratio: f64,
+
+    /// Seconds since the SSTable was written.
+    pub age_seconds: u64,
+
+    /// Index into [`PartitionReport::buckets`].
+    pub bucket: usize,
+}
+
+/// One size-tier bucket.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BucketReport {
+    /// SSTable ids, smallest file first.
+    pub sstable_ids: Vec<u64>,
+
+    /// Total file size in bytes.
+    pub total_size: u64,
+
+    /// `true` for the bucket of SSTables below the minimum SSTable size.
+    pub small: bool,
+}
+
+/// Tables each compaction strategy would pick, and the thresholds it used.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CompactionCandidates {
+    /// SSTables minor compaction would merge: the largest bucket holding
+    /// at least `min_threshold` tables, capped at `max_threshold`.
+    pub minor: Option<Vec<u64>>,
+
+    /// SSTable tombstone compaction would rewrite: the highest tombstone
+    /// ratio at or above `tombstone_ratio_threshold` among tables older
+    /// than `tombstone_compaction_interval`.
+    pub tombstone: Option<u64>,
+
+    /// SSTable periodic compaction would rewrite: the oldest table past
+    /// `periodic_compaction_seconds`.
+    pub periodic: Option<u64>,
+
+    /// SSTables major compaction would merge: all of them, if at least two.
+    pub major: Option<Vec<u64>>,
+
+    /// Minimum bucket size for minor compaction.
+    pub min_threshold: usize,
+
+    /// Maximum SSTables merged by one minor compaction.
+    pub max_threshold: usize,
+
+    /// Minimum tombstone ratio for tombstone compaction.
+    pub tombstone_ratio_threshold: f64,
+
+    /// Minimum SSTable age (seconds) for tombstone compaction.
+    pub tombstone_compaction_interval: usize,
+
+    /// SSTable age (seconds) that triggers periodic compaction; `0` is
+    /// disabled.
+    pub periodic_compaction_seconds: usize,
+}
+
+impl PartitionReport {
+    /// Describes `sstables` (newest max LSN first) as compaction sees them.
+    pub(crate) fn build(
+        partition: usize,
+        frozen_memtables: usize,
+        sstables: &[Arc<SSTable>],
+        config: &EngineConfig,
+    ) -> Self {
+        let buckets = stcs::bucket_sstables(sstables, config);
+        let mut bucket_of = vec![0; sstables.len()];
+        for (b, bucket) in buckets.iter().enumerate() {
+            for &i in bucket {
+                bucket_of[i] = b;
+            }
+        }
+
+        let now_nanos = config.clock.now_nanos();
+        let sstable_reports = sstables
+            .iter()
+            .enumerate()
+            .map(|(i, sst)| {
+                let tombstones = sst.tombstone_count() + sst.range_tombstone_count();
+                SSTableReport {
+                    id: sst.id(),
+                    file_size: sst.file_size(),
+                    min_key: sst.min_key().to_vec(),
+                    max_key: sst.max_key().to_vec(),
+                    min_lsn: sst.min_lsn(),
+                    max_lsn: sst.max_lsn(),
+                    record_count: sst.record_count(),
+                    tombstone_ratio: tombstones as f64 / sst.record_count().max(1) as f64,
+                    age_seconds: now_nanos.saturating_sub(sst.creation_timestamp()) / 1_000_000_000,
+                    bucket: bucket_of[i],
+                }
+            })
+            .collect();
+
+        let bucket_reports = buckets
+            .iter()
+            .map(|bucket| BucketReport {
+                sstable_ids: bucket.iter().map(|&i| sstables[i].id()).collect(),
+                total_size: bucket.iter().map(|&i| sstables[i].file_size()).sum(),
+                small: bucket
+                    .iter()
+                    .all(|&i| sstables[i].file_size() < config.min_sstable_size as u64),
+            })
+            .collect();
+
+        let mut overlaps = Vec::new();
+        for (i, a) in sstables.iter().enumerate() {
+            for b in &sstables[i + 1..] {
+                if a.min_key() <= b.max_key() && b.min_key() <= a.max_key() {
+                    overlaps.push((a.id(), b.id()));
+                }
+            }
+        }
+
+        let ids = |indices: Vec<usize>| -> Vec<u64> {
+            indices.iter().map(|&i| sstables[i].id()).collect()
+        };
+        let candidates = CompactionCandidates {
+            minor: stcs::select_compaction_bucket(sstables, &buckets, config).map(ids),
+            tombstone: tombstone::select_candidate(sstables, config).map(|i| sstables[i].id()),
+            periodic: periodic::select_candidate(sstables, config).map(|i| sstables[i].id()),
+            major: (sstables.len() >= 2).then(|| sstables.iter().map(|s| s.id()).collect()),
+            min_threshold: config.min_threshold,
+            max_threshold: config.max_threshold,
+            tombstone_ratio_threshold: config.tombstone_ratio_threshold,
+            tombstone_compaction_interval: config.tombstone_compaction_interval,
+            periodic_compaction_seconds: config.periodic_compaction_seconds,
+        };
+
+        Self {
+            partition,
+            frozen_memtables,
+            sstables: sstable_reports,
+            buckets: bucket_reports,
+            overlaps,
+            candidates,
+        }
+    }
+}
+
+impl DebugReport {
+    /// Renders the report as a Graphviz `digraph`.
+    ///
+    /// Each partition and each bucket is a cluster and each SSTable a node
+    /// labelled with its id, size, and tombstone ratio. Minor compaction
+    /// candidates are filled, tombstone and periodic candidates drawn bold,
+    /// and overlapping key ranges joined by dashed edges.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph lsm {\n  node [shape=box];\n");
+        for p in &self.partitions {
+            let c = &p.candidates;
+            let _ = writeln!(dot, "  subgraph cluster_p{} {{", p.partition);
+            let _ = writeln!(
+                dot,
+                "    label=\"partition {} ({} frozen)\";",
+                p.partition, p.frozen_memtables
+            );
+            for (b, bucket) in p.buckets.iter().enumerate() {
+                let _ = writeln!(dot, "    subgraph cluster_p{}_b{b} {{", p.partition);
+                let kind = if bucket.small { "small " } else { "" };
+                let _ = writeln!(
+                    dot,
+                    "      label=\"{kind}bucket {b}: {} B\";",
+                    bucket.total_size
+                );
+                for sst in p.sstables.iter().filter(|s| s.bucket == b) {
+                    let mut style = Vec::new();
+                    if c.minor.as_ref().is_some_and(|m| m.contains(&sst.id)) {
+                        style.push("filled");
+                    }
+                    if c.tombstone == Some(sst.id) || c.periodic == Some(sst.id) {
+                        style.push("bold");
+                    }
+                    let _ = writeln!(
+                        dot,
+                        "      p{}_s{} [label=\"#{}\\n{} B\\n[{} .. {}]\\ntombstones {:.2}\" style=\"{}\"];",
+                        p.partition,
+                        sst.id,
+                        sst.id,
+                        sst.file_size,
+                        escape(&sst.min_key),
+                        escape(&sst.max_key),
+                        sst.tombstone_ratio,
+                        style.join(",")
+                    );
+                }
+                dot.push_str("    }\n");
+            }
+            dot.push_str("  }\n");
+            for (a, b) in &p.overlaps {
+                let _ = writeln!(
+                    dot,
+                    "  p{0}_s{a} -> p{0}_s{b} [dir=none style=dashed];",
+                    p.partition
+                );
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Renders a key for a DOT label: lossy UTF-8 with quotes and
+/// backslashes escaped.
+fn escape(key: &[u8]) -> String {
+    String::from_utf8_lossy(key)
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+}
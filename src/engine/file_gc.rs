@@ -0,0 +1,136 @@
+//! Deferred deletion of SSTable files that left the live set.
+//!
+//! Every change that removes SSTables from the live set — a compaction, a
+//! FIFO drop, a clear, an applied replication delta — starts a new **file
+//! generation**. By default the removed files are deleted right away.
+//! With
+//! [`EngineConfig::obsolete_file_grace_seconds`](super::EngineConfig::obsolete_file_grace_seconds)
+//! set, each generation is instead kept in a short in-memory history, and
+//! its files stay on disk until
+//!
+//! - no reader holds them any more: no scan iterator, pinned value, or
+//!   read view published before the generation, and
+//! - the grace period has passed since the generation started,
+//!
+//! so a backup tool that lists the directory and then copies what it
+//! found does not race a compaction. A timer deletes the files once both
+//! hold; [`Engine::purge_obsolete_files`](super::Engine::purge_obsolete_files)
+//! skips the grace period. The history lives in memory only: files still
+//! pending at a crash are orphans, removed on the next open.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::ops::AddAssign;
+use std::path::PathBuf;
+use std::sync::Weak;
+
+use crate::sstable::SSTable;
+
+/// Outcome of a pass over the removed SSTable files, returned by
+/// [`Db::purge_obsolete_files`](crate::Db::purge_obsolete_files).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PurgeReport {
+    /// Removed SSTable files deleted by the pass.
+    pub files_deleted: usize,
+
+    /// Bytes of the deleted files.
+    pub bytes_deleted: u64,
+
+    /// Removed files left on disk because a reader still holds them, or
+    /// because their grace period has not passed.
+    pub files_pending: usize,
+}
+
+impl AddAssign for PurgeReport {
+    fn add_assign(&mut self, other: Self) {
+        self.files_deleted += other.files_deleted;
+        self.bytes_deleted += other.bytes_deleted;
+        self.files_pending += other.files_pending;
+    }
+}
+
+/// An SSTable file removed from the live set, not yet deleted.
+#[derive(Debug)]
+struct ObsoleteFile {
+    path: PathBuf,
+    /// The table as readers saw it; dead once the last one lets go.
+    table: Weak<SSTable>,
+}
+
+/// The files removed by one change of the live set.
+#[derive(Debug)]
+struct Generation {
+    number: u64,
+    /// Clock time of the change, in UNIX nanoseconds.
+    retired_at: u64,
+    files: Vec<ObsoleteFile>,
+}
+
+/// Generations whose removed files are still on disk, oldest first.
+#[derive(Debug, Default)]
+pub(crate) struct ObsoleteFiles {
+    next_generation: u64,
+    pending: VecDeque<Generation>,
+}
+
+impl ObsoleteFiles {
+    /// Starts a generation removing the SSTables stored at each path, as
+    /// readers hold them, and returns its number.
+    ///
+    /// The files are deleted by a later [`purge`](Self::purge).
+    pub fn retire(&mut self, tables: Vec<(PathBuf, Weak<SSTable>)>, now_nanos: u64) -> u64 {
+        let number = self.next_generation;
+        self.next_generation += 1;
+        let files = tables
+            .into_iter()
+            .map(|(path, table)| ObsoleteFile { path, table })
+            .collect();
+        self.pending.push_back(Generation {
+            number,
+            retired_at: now_nanos,
+            files,
+        });
+        number
+    }
+
+    /// Deletes the files no reader holds, of every generation at least
+    /// `grace_nanos` old.
+    ///
+    /// A file that cannot be deleted is only logged and forgotten; the
+    /// next open collects it as an orphan.
+    pub fn purge(&mut self, now_nanos: u64, grace_nanos: u64) -> PurgeReport {
+        let mut report = PurgeReport::default();
+        for generation in &mut self.pending {
+            let number = generation.number;
+            if now_nanos.saturating_sub(generation.retired_at) < grace_nanos {
+                report.files_pending += generation.files.len();
+                continue;
+            }
+            generation.files.retain(|file| {
+                if file.table.strong_count() > 0 {
+                    report.files_pending += 1;
+                    return true;
+                }
+                let size = fs::metadata(&file.path).map_or(0, |meta| meta.len());
+                match fs::remove_file(&file.path) {
+                    Ok(()) => {
+                        report.files_deleted += 1;
+                        report.bytes_deleted += size;
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                    Err(e) => tracing::warn!(
+                        path = %file.path.display(),
+                        generation = number,
+                        %e,
+                        "failed to remove obsolete SSTable file"
+                    ),
+                }
+                false
+            });
+        }
+        self.pending
+            .retain(|generation| !generation.files.is_empty());
+        report
+    }
+}
@@ -0,0 +1,122 @@
+//! Where SSTable files live under the database directory, and what they
+//! are called.
+//!
+//! Every SSTable lives below `<db>/sstables/`: directly with
+//! [`SstDirLayout::Flat`], or in a subdirectory named after the
+//! compaction strategy family with [`SstDirLayout::PerStrategy`]
+//! (`sstables/stcs/`). Files are named after their manifest id
+//! (`000042.sst`) with [`SstFileNaming::Id`], or after a ULID with
+//! [`SstFileNaming::Ulid`]; the manifest maps ids to paths either way.
+//!
+//! The manifest stores each path relative to the database directory, so
+//! the directory can be moved or renamed as a whole. Both settings may
+//! change between opens: existing files keep their names and places, and
+//! only new files follow the new settings.
+
+use std::fs;
+use std::hash::{BuildHasher, RandomState};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::{EngineConfig, SSTABLE_DIR};
+
+/// Directory layout of SSTable files, selected by
+/// [`DbConfig::sstable_layout`](crate::DbConfig::sstable_layout).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SstDirLayout {
+    /// Every SSTable directly in `sstables/`.
+    #[default]
+    Flat,
+
+    /// SSTables in a subdirectory of `sstables/` named after the
+    /// compaction strategy family that wrote them, e.g. `sstables/stcs/`.
+    PerStrategy,
+}
+
+/// File naming scheme of new SSTables, selected by
+/// [`DbConfig::sstable_naming`](crate::DbConfig::sstable_naming).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SstFileNaming {
+    /// The zero-padded manifest id, e.g. `000042.sst`.
+    #[default]
+    Id,
+
+    /// A [ULID](https://github.com/ulid/spec) — creation time in
+    /// milliseconds and 80 random bits — e.g.
+    /// `01J9ZQ3V8X4K7M2N5P6R8S0T1V.sst`. Names sort by creation time and
+    /// do not collide when SSTables of several databases are gathered in
+    /// one place.
+    Ulid,
+}
+
+/// Crockford base32 alphabet used by ULIDs.
+const ULID_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Directory new SSTables of `config` are written to.
+pub(crate) fn sstable_dir(data_dir: &Path, config: &EngineConfig) -> PathBuf {
+    let root = data_dir.join(SSTABLE_DIR);
+    match config.sstable_layout {
+        SstDirLayout::Flat => root,
+        SstDirLayout::PerStrategy => root.join(config.compaction_strategy.dir_name()),
+    }
+}
+
+/// Path of a new SSTable with manifest id `id`.
+pub(crate) fn sstable_path(data_dir: &Path, config: &EngineConfig, id: u64) -> PathBuf {
+    let name = match config.sstable_naming {
+        SstFileNaming::Id => format!("{id:06}.sst"),
+        SstFileNaming::Ulid => format!("{}.sst", ulid(config.clock.now_nanos(), id)),
+    };
+    sstable_dir(data_dir, config).join(name)
+}
+
+/// Every `.sst` file in `sstable_root` and its immediate subdirectories.
+pub(crate) fn sstable_files(sstable_root: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(sstable_root)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            for entry in fs::read_dir(&path)? {
+                let path = entry?.path();
+                if is_sstable_file(&path) {
+                    files.push(path);
+                }
+            }
+        } else if is_sstable_file(&path) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Whether `path` is a file named like one of [`sstable_path`]'s: a
+/// numeric id or a ULID, with an `.sst` extension. Other `.sst` files are
+/// not ours and are left alone.
+fn is_sstable_file(path: &Path) -> bool {
+    let Some(stem) = path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .and_then(|s| s.strip_suffix(".sst"))
+    else {
+        return false;
+    };
+    let is_id = !stem.is_empty() && stem.bytes().all(|b| b.is_ascii_digit());
+    let is_ulid = stem.len() == 26 && stem.bytes().all(|b| ULID_ALPHABET.contains(&b));
+    path.is_file() && (is_id || is_ulid)
+}
+
+/// Encodes a ULID for `now_nanos`, its random part drawn from a randomly
+/// keyed hash of the time and `id`.
+fn ulid(now_nanos: u64, id: u64) -> String {
+    let millis = u128::from(now_nanos / 1_000_000) & ((1 << 48) - 1);
+    let high = RandomState::new().hash_one((now_nanos, id));
+    let low = RandomState::new().hash_one((id, now_nanos));
+    let random = ((u128::from(high) << 16) | u128::from(low & 0xFFFF)) & ((1 << 80) - 1);
+    let value = (millis << 80) | random;
+
+    (0..26)
+        .rev()
+        .map(|i| ULID_ALPHABET[((value >> (5 * i)) & 0x1F) as usize] as char)
+        .collect()
+}
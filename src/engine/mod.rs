@@ -21,10 +21,14 @@
 //!
 //! ## Concurrency Model
 //!
-//! All engine state is protected by a single `Arc<RwLock<EngineInner>>`.
-//! Reads acquire a **read lock**; writes and flushes acquire a **write lock**.
-//! Compaction first acquires a short read lock to obtain the strategy, then
-//! acquires a write lock for the merge/swap phase.
+//! Mutable engine state is protected by a single `Arc<RwLock<EngineInner>>`.
+//! Writes, flushes, and compactions acquire the **write lock**; compaction
+//! first takes a short read lock to obtain the strategy.
+//!
+//! Point lookups and scans take **no engine lock**. Every change to the set
+//! of layers (freeze, flush, compaction) publishes a new immutable
+//! `SuperVersion` — active memtable, frozen memtables, and SSTables — and
+//! readers pick up the current one with a single atomic load.
 //!
 //! ## Compaction
 //!
@@ -47,24 +51,83 @@
 //!   are replayed to reconstruct the last durable state.
 //! - **Multi-version reads:** Point lookups and scans always see the latest
 //!   committed version of each key, respecting tombstones.
-//! - **Atomic flushes:** Each frozen memtable is flushed to a single SSTable
-//!   and the manifest is updated atomically.
+//! - **Atomic flushes:** Each frozen memtable (or batch of frozen memtables,
+//!   see [`EngineConfig::max_memtables_per_flush`]) is flushed to a single
+//!   SSTable and the manifest is updated atomically.
 
+use std::borrow::Cow;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
 use thiserror::Error;
 
+use crate::batch::WriteOp;
+use crate::clock::{Clock, HybridClock, SystemClock};
+use crate::compaction::coordinator::CompactionCoordinator;
+use crate::compaction::{
+    CompactionError, CompactionJobStats, CompactionPriority, TombstoneGcCounters, TombstoneGcStats,
+    VersionGc,
+};
+use crate::durability;
 use crate::manifest::{Manifest, ManifestError, ManifestSstEntry};
-use crate::memtable::{FrozenMemtable, Memtable, MemtableError, MemtableGetResult};
-use crate::sstable::{self, SSTable, SSTableError};
-
+use crate::memtable::{FrozenMemtable, Memtable, MemtableError};
+use crate::range_lock::RangeLocks;
+use crate::slow_op::{self, SlowOpTimer};
+use crate::sstable::mapping::{FileBytes, MmapBudget};
+use crate::sstable::{self, OpenChecks, SSTable, SSTableError, SstBuildStats, SstFileDigest};
+use crate::tenant::{self, PrefixTotals};
+use crate::wal::{WalCounters, WalRecoveryMode};
+use compaction_hints::CompactionHint;
+use file_gc::ObsoleteFiles;
+use read_stats::{FanoutGuard, ProbeDepthWindow, ReadCounters, SstProbe};
+use row_cache::{CacheLookup, RowCache};
+use superversion::{SstKeySpan, SuperVersion, SuperVersionCell};
+use utils::{LsnBound, RecordSource, Unseekable, VersionRank, below_end};
+use write_stats::WriteActivityLog;
+
+mod compaction_hints;
+mod consistency;
+mod debug_report;
 mod encoding_impls;
+mod file_gc;
+mod key_estimate;
+pub(crate) mod layout;
+mod pinned;
+mod read_only;
+pub(crate) mod read_stats;
+mod recovery;
+mod repair;
+mod replication;
+mod retention;
+mod row_cache;
+mod superversion;
+mod tuning;
 pub mod utils;
+mod version_pins;
 mod visibility;
-pub use utils::{PointEntry, RangeTombstone, Record, RecordEntry};
+mod write_stats;
+pub use consistency::{ConsistencyIssue, ConsistencyReport};
+pub use debug_report::{
+    BucketReport, CompactionCandidates, DebugReport, PartitionReport, SSTableReport,
+};
+pub use file_gc::PurgeReport;
+pub use layout::{SstDirLayout, SstFileNaming};
+pub use pinned::PinnedSlice;
+pub(crate) use read_only::ReadOnlyEngine;
+pub use read_stats::{ReadFanout, ReadStats};
+pub use recovery::RecoveryReport;
+pub use repair::RepairReport;
+pub use replication::{DeltaSSTable, ReplicationDelta};
+pub use retention::{WalFile, WalFileStatus};
+pub(crate) use tuning::TuningWindow;
+pub use tuning::{AdaptiveCompaction, CompactionThresholds, CompactionTuning, TuningReason};
+pub use utils::{AsPointEntry, PointEntry, PointEntryRef, RangeTombstone, Record, RecordEntry};
+pub(crate) use version_pins::{NO_HORIZON, VersionPin, VersionPins};
 pub use visibility::VisibilityFilter;
+pub use write_stats::{WriteActivity, WriteStats};
 
 #[cfg(test)]
 mod tests;
@@ -73,6 +136,24 @@ pub const MANIFEST_DIR: &str = "manifest";
 pub const MEMTABLE_DIR: &str = "memtables";
 pub const SSTABLE_DIR: &str = "sstables";
 
+/// Minimum number of SSTables before a point lookup fans its bloom filter
+/// checks out across threads; below this, thread start-up costs more than
+/// the checks themselves.
+const PARALLEL_PROBE_MIN_SSTABLES: usize = 8;
+
+/// Factor applied to [`EngineConfig::write_buffer_size`] while bulk
+/// loading, so that fewer, larger SSTables are flushed.
+pub const BULK_LOAD_WRITE_BUFFER_FACTOR: usize = 16;
+
+/// LSNs reserved in the manifest at a time. Each reservation costs one
+/// manifest WAL append; recovery skips the unused rest of the block.
+const LSN_RESERVATION_BLOCK: u64 = 1 << 20;
+
+/// Minimum length of a write batch of puts and deletes in strictly
+/// ascending key order that is merged into the memtable at once rather
+/// than inserted one operation at a time.
+const SORTED_BATCH_MIN_OPS: usize = 1024;
+
 /// Errors that can occur during engine operations.
 #[derive(Debug, Error)]
 pub enum EngineError {
@@ -95,16 +176,47 @@ pub enum EngineError {
     /// Internal invariant violation (poisoned lock, unexpected state, etc.).
     #[error("Internal error: {0}")]
     Internal(String),
+
+    /// The manifest references missing or malformed files.
+    #[error("Inconsistent database: {0}")]
+    Inconsistent(ConsistencyReport),
+
+    /// A replication delta does not fit this engine's state.
+    #[error("Replication error: {0}")]
+    Replication(String),
+
+    /// Data on disk carries an LSN the manifest never reserved: the
+    /// manifest was rolled back or replaced after the data was written.
+    #[error("LSN regression: data holds LSN {observed}, manifest reserved up to {reserved}")]
+    LsnRegression { observed: u64, reserved: u64 },
+
+    /// A read passed its [`ReadOptions::deadline`].
+    #[error("Read deadline exceeded")]
+    TimedOut,
+
+    /// An argument does not meet the operation's requirements.
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
 }
 
 /// Configuration for an [`Engine`] instance.
 ///
 /// Controls memtable sizing, compaction strategy selection, and all
 /// compaction-related thresholds. Passed to [`Engine::open`].
+#[derive(Clone)]
 pub struct EngineConfig {
     /// Max memtable size (bytes) before freeze.
     pub write_buffer_size: usize,
 
+    /// Max number of frozen memtables merged into a single SSTable by one
+    /// flush. `1` flushes each frozen memtable to its own SSTable.
+    pub max_memtables_per_flush: usize,
+
+    /// Max number of frozen memtables awaiting flush (`0` = unlimited).
+    /// A freeze that exceeds it flushes the oldest inline, under the write
+    /// lock, before the write returns.
+    pub max_frozen_memtables: usize,
+
     /// Compaction strategy to use for this engine instance.
     ///
     /// Determines which [`CompactionStrategy`](crate::compaction::CompactionStrategy)
@@ -134,7 +246,8 @@ pub struct EngineConfig {
     pub tombstone_compaction_interval: usize,
 
     /// When true, tombstone compaction resolves bloom filter false positives
-    /// by doing an actual `get()` on other SSTables for point tombstones.
+    /// by looking point tombstones up in other SSTables, one sorted sweep
+    /// per SSTable.
     pub tombstone_bloom_fallback: bool,
 
     /// When true, tombstone compaction will scan older SSTables to check
@@ -142,14 +255,129 @@ pub struct EngineConfig {
     /// aggressive range tombstone removal.
     pub tombstone_range_drop: bool,
 
+    /// Grace period (seconds) during which a tombstone is never dropped by
+    /// any compaction, even when it is provably spent. `0` disables it.
+    pub tombstone_gc_grace_seconds: usize,
+
+    /// SSTables older than this many seconds are rewritten by periodic
+    /// compaction even if no other trigger fires. `0` disables it.
+    pub periodic_compaction_seconds: usize,
+
+    /// When the 99th percentile of SSTables probed by recent lookups
+    /// exceeds this, the newest SSTables are hinted for compaction. `0`
+    /// disables it.
+    pub read_depth_compaction_threshold: usize,
+
+    /// Consecutive point tombstones a scan may step over in one SSTable
+    /// before it is hinted for compaction with the older SSTables it
+    /// overlaps. `0` disables.
+    pub deletion_run_compaction_threshold: usize,
+
+    /// Whether minor compaction prefers the size bucket whose SSTables
+    /// are read most per byte, and merges its hottest tables first.
+    pub hot_sstable_compaction: bool,
+
+    /// SSTables a lookup or scan may consult before it is counted as past
+    /// the fan-out limit. `0` disables.
+    pub read_fanout_limit: usize,
+
+    /// Whether a lookup past `read_fanout_limit` consults only the
+    /// SSTables whose key bounds or range tombstones cover its key.
+    pub read_fanout_fallback: bool,
+
     /// Thread pool size for flushing memtables and compactions.
     pub thread_pool_size: usize,
+
+    /// When true, point lookups over many SSTables first run the bloom
+    /// filter checks for all candidate SSTables in parallel (up to
+    /// `thread_pool_size` threads), then probe only the surviving tables
+    /// in max-LSN order.
+    pub parallel_sstable_probe: bool,
+
+    /// How replay of the manifest and memtable WALs on open treats torn or
+    /// corrupt records.
+    pub wal_recovery_mode: WalRecoveryMode,
+
+    /// A non-empty active memtable whose oldest record is older than this
+    /// many seconds is frozen for flush. `0` disables it.
+    pub max_memtable_age: usize,
+
+    /// The active memtable is frozen for flush once its WAL exceeds this
+    /// many bytes and no frozen memtable is awaiting flush. `0` disables
+    /// it.
+    pub max_total_wal_size: usize,
+
+    /// Flushed WAL segments are deleted, oldest first, while together they
+    /// take more than this many bytes. `0` sets no size limit.
+    pub wal_retention_bytes: usize,
+
+    /// Flushed WAL segments last written this many seconds ago are
+    /// deleted. `0` sets no age limit. With both limits at `0`, flushed
+    /// segments are deleted right after the flush.
+    pub wal_retention_seconds: usize,
+
+    /// Seconds an SSTable file removed from the live set stays on disk
+    /// after its last reader lets go. `0` deletes it at once.
+    pub obsolete_file_grace_seconds: usize,
+
+    /// Bytes of keys and values the row cache may hold for point lookups
+    /// answered from SSTables. `0` disables the row cache.
+    pub row_cache_size: usize,
+
+    /// Bytes of SSTable files that may be memory-mapped at once. Beyond
+    /// it, the least recently read SSTables are read with `pread` instead.
+    /// `0` sets no limit.
+    pub max_mmap_bytes: usize,
+
+    /// When true, the data blocks of each SSTable a flush or compaction
+    /// writes are advised `MADV_WILLNEED`, and the SSTables a compaction
+    /// replaces are unmapped for good.
+    pub page_cache_hints: bool,
+
+    /// Flushes and compactions taking at least this long are logged with
+    /// a `warn` event. `None` logs none.
+    pub slow_op_threshold: Option<Duration>,
+
+    /// How much of each SSTable listed in the manifest is verified when
+    /// the engine opens it.
+    pub sstable_open_checks: OpenChecks,
+
+    /// Directory layout of new SSTable files.
+    pub sstable_layout: SstDirLayout,
+
+    /// File naming scheme of new SSTables.
+    pub sstable_naming: SstFileNaming,
+
+    /// Algorithm of the whole-file digest stored in new SSTables, or
+    /// `None` to store none.
+    pub sstable_file_digest: Option<SstFileDigest>,
+
+    /// Key prefixes tallied in the properties of new SSTables, sorted and
+    /// free of duplicates.
+    pub tenant_prefixes: Vec<Vec<u8>>,
+
+    /// When true, every put records a CRC-32 of its value, kept in the
+    /// memtable and in the cells of flushed and compacted SSTables.
+    pub value_checksums: bool,
+
+    /// Seconds for which the idempotency token of a committed write batch
+    /// is remembered.
+    pub idempotency_retention_seconds: usize,
+
+    /// Most idempotency tokens remembered; beyond it the oldest are
+    /// forgotten early.
+    pub idempotency_max_tokens: usize,
+
+    /// Source of write timestamps and of the current time.
+    pub clock: Arc<dyn Clock>,
 }
 
 impl Default for EngineConfig {
     fn default() -> Self {
         Self {
             write_buffer_size: 64 * 1024,
+            max_memtables_per_flush: 1,
+            max_frozen_memtables: 0,
             compaction_strategy: crate::compaction::CompactionStrategyType::Stcs,
             bucket_low: 0.5,
             bucket_high: 1.5,
@@ -160,11 +388,200 @@ impl Default for EngineConfig {
             tombstone_compaction_interval: 0,
             tombstone_bloom_fallback: true,
             tombstone_range_drop: true,
+            tombstone_gc_grace_seconds: 0,
+            periodic_compaction_seconds: 0,
+            read_depth_compaction_threshold: 0,
+            deletion_run_compaction_threshold: 0,
+            hot_sstable_compaction: false,
+            read_fanout_limit: 0,
+            read_fanout_fallback: false,
             thread_pool_size: 2,
+            parallel_sstable_probe: false,
+            wal_recovery_mode: WalRecoveryMode::default(),
+            max_memtable_age: 0,
+            max_total_wal_size: 0,
+            wal_retention_bytes: 0,
+            wal_retention_seconds: 0,
+            obsolete_file_grace_seconds: 0,
+            row_cache_size: 0,
+            max_mmap_bytes: 0,
+            page_cache_hints: false,
+            slow_op_threshold: None,
+            sstable_open_checks: OpenChecks::default(),
+            sstable_layout: SstDirLayout::default(),
+            sstable_naming: SstFileNaming::default(),
+            sstable_file_digest: None,
+            tenant_prefixes: Vec::new(),
+            value_checksums: false,
+            idempotency_retention_seconds: 3600,
+            idempotency_max_tokens: 100_000,
+            clock: Arc::new(SystemClock),
+        }
+    }
+}
+
+/// Per-read options accepted by [`Engine::get_opt`] and [`Engine::scan_opt`].
+///
+/// The default matches [`Engine::get`]: every SSTable data block is
+/// checksum-verified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadOptions {
+    /// Verify the CRC32 of every SSTable data block read. Disabling this
+    /// trades corruption detection for lower read latency.
+    pub verify_checksums: bool,
+
+    /// Verify the value a point lookup returns against the checksum taken
+    /// when it was written, catching corruption in memory between the
+    /// WAL, memtable, and SSTable block checks. Only values written with
+    /// [`EngineConfig::value_checksums`] carry a checksum; values served
+    /// from the row cache are not re-verified.
+    pub verify_value_checksum: bool,
+
+    /// Hint that blocks read on behalf of this operation may be cached.
+    ///
+    /// Currently has no effect — SSTables are read through `mmap` and the
+    /// engine keeps no block cache of its own.
+    pub fill_cache: bool,
+
+    /// Restrict a scan to keys that start with its start key.
+    ///
+    /// The scan's end is lowered to the first key past that prefix, so
+    /// the merge stops as soon as the prefix is exhausted and SSTables
+    /// holding no key under it are not opened. Ignored by point lookups.
+    pub prefix_same_as_start: bool,
+
+    /// Point in time by which the read must finish, or `None` for no
+    /// limit.
+    ///
+    /// A point lookup checks it before each SSTable it probes and fails
+    /// with [`EngineError::TimedOut`] once it has passed; lookups answered
+    /// by the memtables never time out. Scans through
+    /// [`Db::scan_opt`](crate::Db::scan_opt) check it before each pair.
+    /// A block read already under way is not interrupted, so a read can
+    /// overrun the deadline by one cold page fault.
+    pub deadline: Option<Instant>,
+}
+
+impl Default for ReadOptions {
+    fn default() -> Self {
+        Self {
+            verify_checksums: true,
+            verify_value_checksum: true,
+            fill_cache: true,
+            prefix_same_as_start: false,
+            deadline: None,
+        }
+    }
+}
+
+impl ReadOptions {
+    /// Returns the exclusive end of a scan from `start_key` to `end_key`:
+    /// `end_key`, lowered to the end of the `start_key` prefix when
+    /// [`prefix_same_as_start`](Self::prefix_same_as_start) is set.
+    pub(crate) fn scan_end<'a>(&self, start_key: &[u8], end_key: &'a [u8]) -> Cow<'a, [u8]> {
+        if !self.prefix_same_as_start {
+            return Cow::Borrowed(end_key);
         }
+        match prefix_successor(start_key) {
+            Some(bound) if bound.as_slice() < end_key => Cow::Owned(bound),
+            _ => Cow::Borrowed(end_key),
+        }
+    }
+
+    /// Returns `true` once the [`deadline`](Self::deadline) has passed.
+    pub(crate) fn deadline_passed(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
     }
 }
 
+/// Per-write options accepted by [`Engine::put_opt`] and
+/// [`Engine::delete_opt`].
+///
+/// The default matches [`Engine::put`] / [`Engine::delete`]: every write is
+/// appended to the WAL, fsynced unless bulk loading turned that off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// Skip the WAL: the write goes to the memtable only.
+    ///
+    /// An unlogged write is durable once the memtable holding it has been
+    /// flushed to an SSTable. A crash before that loses it, while logged
+    /// writes around it are recovered from the WAL — so after a crash a key
+    /// may show an older value instead. A clean [`Engine::close`] flushes
+    /// such a memtable first, so nothing is lost then. Meant for data that
+    /// can be recomputed, such as caches and derived indexes.
+    pub disable_wal: bool,
+
+    /// Fsync the WAL before the write returns, even while bulk loading
+    /// has per-record fsync turned off. Has no effect with
+    /// [`disable_wal`](Self::disable_wal).
+    pub sync: bool,
+}
+
+/// Returns the smallest key greater than every key prefixed by `prefix`,
+/// or `None` if there is none (`prefix` is empty or all `0xFF`).
+fn prefix_successor(prefix: &[u8]) -> Option<Vec<u8>> {
+    let last = prefix.iter().rposition(|&b| b != 0xFF)?;
+    let mut bound = prefix[..=last].to_vec();
+    bound[last] += 1;
+    Some(bound)
+}
+
+/// Returns whether `ops` holds only puts and deletes, in strictly
+/// ascending key order.
+fn is_sorted_point_batch(ops: &[WriteOp]) -> bool {
+    let mut keys = ops.iter().map(WriteOp::point_key);
+    let Some(Some(mut last)) = keys.next() else {
+        return false;
+    };
+    keys.all(|key| {
+        key.is_some_and(|key| {
+            let ascending = key > last;
+            last = key;
+            ascending
+        })
+    })
+}
+
+/// A value together with the version that wrote it, returned by
+/// [`Engine::get_entry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The stored value.
+    pub value: Vec<u8>,
+
+    /// LSN of the write that stored the value. Any later write to the
+    /// key — put, delete, or range delete — gets a higher LSN.
+    pub lsn: u64,
+
+    /// Commit timestamp of the write (UNIX epoch nanoseconds).
+    pub timestamp: u64,
+}
+
+/// Outcome of [`Engine::compare_and_swap`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CasOutcome {
+    /// The visible value matched the expected one and the new value was
+    /// written.
+    Swapped,
+
+    /// The visible value differed from the expected one; nothing was
+    /// written. Holds the value found, `None` if the key was absent.
+    Mismatch(Option<Vec<u8>>),
+}
+
+/// The visible version of a key found by a point lookup.
+pub(crate) struct Resolved {
+    /// The value; pinned when it was found in an SSTable.
+    pub value: PinnedSlice,
+
+    /// LSN of the write that stored the value.
+    pub lsn: u64,
+
+    /// Timestamp of the write that stored the value.
+    pub timestamp: u64,
+}
+
 /// Snapshot of engine statistics returned by [`Engine::stats`].
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -177,14 +594,88 @@ pub struct EngineStats {
     pub total_sst_size_bytes: u64,
     /// Per-SSTable file sizes in bytes (newest-first order).
     pub sst_sizes: Vec<u64>,
+    /// Sum of the file sizes of the SSTables read through a memory map.
+    pub mmap_bytes: u64,
+}
+
+/// Per-SSTable metadata returned by [`Engine::sstable_metadata`].
+///
+/// Assembled from the manifest entry (id, path) and the SSTable's
+/// properties block (keys, LSNs, counts, creation time).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SSTableMetadata {
+    /// Manifest-assigned SSTable identifier.
+    pub id: u64,
+
+    /// On-disk path of the SSTable file.
+    pub path: PathBuf,
+
+    /// File size in bytes.
+    pub file_size: u64,
+
+    /// Smallest point key stored in the table.
+    pub min_key: Vec<u8>,
+
+    /// Largest point key stored in the table.
+    pub max_key: Vec<u8>,
+
+    /// Lowest LSN present in the table.
+    pub min_lsn: u64,
+
+    /// Highest LSN present in the table.
+    pub max_lsn: u64,
+
+    /// Total number of point records (puts and point deletes).
+    pub record_count: u64,
+
+    /// Number of point tombstones.
+    pub tombstone_count: u64,
+
+    /// Number of range tombstones.
+    pub range_tombstone_count: u64,
+
+    /// `(tombstone_count + range_tombstone_count) / record_count`, the same
+    /// ratio used to select candidates for tombstone compaction.
+    pub tombstone_ratio: f64,
+
+    /// Creation timestamp (UNIX epoch nanos).
+    pub creation_timestamp: u64,
+
+    /// Compaction level, for strategies that organise tables into levels.
+    /// Always `None` under size-tiered compaction.
+    pub level: Option<u32>,
+
+    /// Point-lookup counters for this table since it was loaded.
+    pub reads: ReadStats,
+
+    /// Point lookups that found a version of their key in this table
+    /// since it was loaded.
+    pub hits: u64,
+
+    /// Scans whose range overlapped this table since it was loaded.
+    pub scans: u64,
+
+    /// Time of the latest lookup hit or scan (UNIX epoch nanos), or
+    /// `None` if there was none since the table was loaded.
+    pub last_access: Option<u64>,
+
+    /// Whether the table is read through a memory map. `false` once the
+    /// mmap limit demoted it to positioned reads.
+    pub mapped: bool,
 }
 
 struct EngineInner {
     /// Persistent manifest for this engine (keeps track of SSTables, generations, etc).
-    manifest: Manifest,
+    ///
+    /// Shared with running compactions, which record their intent and
+    /// build their output without the engine lock.
+    manifest: Arc<Manifest>,
 
     /// Active memtable that accepts writes.
-    active: Memtable,
+    ///
+    /// Shared with the published [`SuperVersion`], so readers can consult it
+    /// without the engine lock.
+    active: Arc<Memtable>,
 
     /// Frozen memtables waiting to be flushed to SSTable.
     /// We keep them in memory for reads until flush completes.
@@ -198,6 +689,222 @@ struct EngineInner {
 
     /// A short config for thresholds, sizes, etc.
     config: EngineConfig,
+
+    /// Read view republished after every change to `active`, `frozen`, or
+    /// `sstables`.
+    version: Arc<SuperVersionCell>,
+
+    /// Bulk-load mode: larger write buffers, no per-record WAL fsync, no
+    /// age or WAL-size flush triggers.
+    bulk_load: bool,
+
+    /// Bytes of SSTables written by flushes since open.
+    bytes_flushed: u64,
+
+    /// Bytes of SSTables written by compactions since open.
+    bytes_compacted: u64,
+
+    /// Key and value bytes under each of `config.tenant_prefixes` written
+    /// to SSTables by flushes and ingestion since open.
+    prefix_bytes_flushed: Vec<u64>,
+
+    /// Caps the bytes of SSTables mapped at once, if `max_mmap_bytes` is
+    /// non-zero.
+    mmap_budget: Option<Arc<MmapBudget>>,
+
+    /// SSTables suggested for compaction by
+    /// [`Engine::suggest_compact_range`], in the order suggested.
+    hints: Vec<CompactionHint>,
+
+    /// Highest LSN reserved in the manifest; no write is assigned a
+    /// higher one before the reservation moves past it.
+    reserved_lsn: u64,
+
+    /// LSNs whose versions flushes and compactions must keep.
+    version_pins: Arc<VersionPins>,
+
+    /// SSTable files removed from the live set, awaiting deletion.
+    obsolete: ObsoleteFiles,
+
+    /// Append, sync, and rotation counters shared by the WALs of every
+    /// memtable, since open.
+    wal_metrics: Arc<WalCounters>,
+
+    /// What the SSTables built by flushes, ingestions, and compactions
+    /// wrote since open.
+    sstables_built: SstBuildStats,
+
+    /// Stamps record timestamps: the configured clock, never below a
+    /// timestamp already stored.
+    timestamps: Arc<HybridClock>,
+}
+
+impl EngineInner {
+    /// Write buffer size for new memtables, raised while bulk loading.
+    fn write_buffer_size(&self) -> usize {
+        if self.bulk_load {
+            self.config
+                .write_buffer_size
+                .saturating_mul(BULK_LOAD_WRITE_BUFFER_FACTOR)
+        } else {
+            self.config.write_buffer_size
+        }
+    }
+
+    /// Deletes flushed WAL segments outside the configured retention —
+    /// all of them if no retention is configured.
+    ///
+    /// The flush that made them deletable has already been committed, so a
+    /// failure here is only logged; the next flush or open retries.
+    fn prune_flushed_wals(&self) {
+        let frozen_wal_ids: Vec<u64> = self.frozen.iter().map(|f| f.wal_seq()).collect();
+        match retention::prune_flushed_wals(
+            &self.data_dir.join(MEMTABLE_DIR),
+            self.active.wal_seq(),
+            &frozen_wal_ids,
+            self.config.wal_retention_bytes,
+            self.config.wal_retention_seconds,
+        ) {
+            Ok(removed) if !removed.is_empty() => {
+                tracing::debug!(segments = removed.len(), "pruned flushed WAL segments");
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!(error = %e, "failed to prune flushed WAL segments"),
+        }
+    }
+
+    /// Deletes the files of SSTables just removed from the live set, each
+    /// given with its id and path, or hands them to the file GC while
+    /// `obsolete_file_grace_seconds` is set.
+    ///
+    /// `removed` are the engine's handles of the tables; the new set must
+    /// already be published. Without a grace period the files are deleted
+    /// at once, as readers on Unix keep reading an unlinked file; a file
+    /// that cannot be deleted is only logged, and the next open collects
+    /// it.
+    fn retire_sstables(&mut self, removed: Vec<Arc<SSTable>>, paths: Vec<(u64, PathBuf)>) {
+        if paths.is_empty() {
+            return;
+        }
+        if self.config.obsolete_file_grace_seconds == 0 {
+            for (id, path) in paths {
+                if let Err(e) = fs::remove_file(&path) {
+                    tracing::warn!(id, path = %path.display(), %e, "failed to remove obsolete SSTable file");
+                }
+            }
+            return;
+        }
+        let tables = paths
+            .into_iter()
+            .map(|(id, path)| {
+                let table = removed
+                    .iter()
+                    .find(|sst| sst.id() == id)
+                    .map(Arc::downgrade)
+                    .unwrap_or_default();
+                (path, table)
+            })
+            .collect::<Vec<_>>();
+        drop(removed);
+        let files = tables.len();
+        let generation = self.obsolete.retire(tables, self.config.clock.now_nanos());
+        tracing::debug!(generation, files, "deferred deletion of removed SSTables");
+        self.purge_obsolete(false);
+    }
+
+    /// Deletes the removed SSTable files no reader holds any more whose
+    /// grace period has passed — or, with `force`, whatever its age.
+    fn purge_obsolete(&mut self, force: bool) -> PurgeReport {
+        // Retired read views may be all that still holds a table.
+        self.version.reclaim();
+        let grace_nanos = if force {
+            0
+        } else {
+            (self.config.obsolete_file_grace_seconds as u64).saturating_mul(1_000_000_000)
+        };
+        let report = self
+            .obsolete
+            .purge(self.config.clock.now_nanos(), grace_nanos);
+        if report.files_deleted > 0 {
+            tracing::debug!(
+                files = report.files_deleted,
+                bytes = report.bytes_deleted,
+                pending = report.files_pending,
+                "purged obsolete SSTable files"
+            );
+        }
+        report
+    }
+
+    /// Returns the commit timestamp below which applied idempotency
+    /// tokens fall outside the retention window.
+    fn token_cutoff(&self, now_nanos: u64) -> u64 {
+        let max_age_nanos =
+            (self.config.idempotency_retention_seconds as u64).saturating_mul(1_000_000_000);
+        now_nanos.saturating_sub(max_age_nanos)
+    }
+
+    /// Forgets the applied idempotency tokens outside the retention
+    /// limits.
+    fn prune_applied_tokens(&self, now_nanos: u64) -> Result<(), EngineError> {
+        self.manifest.prune_applied_tokens(
+            self.token_cutoff(now_nanos),
+            self.config.idempotency_max_tokens,
+        )?;
+        Ok(())
+    }
+
+    /// Charges a newly loaded SSTable to the mmap budget, if any.
+    fn attach_budget(&self, sstable: &SSTable) {
+        if let Some(budget) = &self.mmap_budget {
+            sstable.attach_budget(budget);
+        }
+    }
+
+    /// Adds the prefix tally of the newest SSTable, just written by a
+    /// flush or ingestion, to `prefix_bytes_flushed`.
+    fn record_prefix_bytes_written(&mut self) {
+        // The table was written with `config.tenant_prefixes`, so its
+        // tally lists them in the same order.
+        if let Some(usage) = self.sstables[0].prefix_usage() {
+            for (written, usage) in self.prefix_bytes_flushed.iter_mut().zip(usage) {
+                *written += usage.bytes;
+            }
+        }
+    }
+
+    /// Builds a [`SuperVersion`] from the current layers.
+    fn super_version(&self) -> SuperVersion {
+        SuperVersion {
+            active: Arc::clone(&self.active),
+            frozen: self.frozen.clone(),
+            sstables: self.sstables.clone(),
+            sstable_span: SstKeySpan::of(&self.sstables),
+            parallel_sstable_probe: self.config.parallel_sstable_probe,
+            thread_pool_size: self.config.thread_pool_size,
+        }
+    }
+
+    /// Publishes the current layers to lock-free readers.
+    ///
+    /// Must be called, still under the write lock, after every change to
+    /// `active`, `frozen`, or `sstables`.
+    fn publish(&self) {
+        self.version.store(self.super_version());
+    }
+}
+
+/// How one run of a compaction strategy ended.
+enum CompactionRun {
+    /// The output replaced the inputs (`true`), or there was nothing to
+    /// do (`false`).
+    Done(bool),
+
+    /// Skipped: another compaction had claimed or replaced these inputs.
+    Conflict(Vec<u64>),
+
+    /// Skipped: an SSTable of its snapshot was removed while it ran.
+    Replaced,
 }
 
 /// The main LSM storage engine handle.
@@ -206,12 +913,56 @@ struct EngineInner {
 /// internal `Arc<RwLock<_>>`.
 pub struct Engine {
     inner: Arc<RwLock<EngineInner>>,
+
+    /// Same cell as `EngineInner::version`, reachable without the lock.
+    version: Arc<SuperVersionCell>,
+
+    /// Point-lookup counters across all SSTables, since open.
+    reads: Arc<ReadCounters>,
+
+    /// Point-tombstone checks of tombstone and periodic compaction, since
+    /// open.
+    tombstone_gc: Arc<TombstoneGcCounters>,
+
+    /// Claims on the inputs of running compactions; the manifest's,
+    /// reachable without the lock.
+    compactions: Arc<CompactionCoordinator>,
+
+    /// What [`open`](Self::open) recovered.
+    recovery: Arc<RecoveryReport>,
+
+    /// Point-lookup results, if `row_cache_size` is non-zero.
+    row_cache: Option<Arc<RowCache>>,
+
+    /// SSTables probed by recent lookups, if
+    /// `read_depth_compaction_threshold` is non-zero.
+    probe_depths: Option<Arc<ProbeDepthWindow>>,
+
+    /// Reads past the fan-out limit, if `read_fanout_limit` is non-zero.
+    fanout: Option<Arc<FanoutGuard>>,
+
+    /// The configured clock, reachable without the lock; stamps SSTable
+    /// accesses.
+    clock: Arc<dyn Clock>,
+
+    /// Write counters as last reported to the event listeners.
+    write_activity: Arc<WriteActivityLog>,
 }
 
 impl Clone for Engine {
     fn clone(&self) -> Self {
         Self {
             inner: Arc::clone(&self.inner),
+            version: Arc::clone(&self.version),
+            reads: Arc::clone(&self.reads),
+            tombstone_gc: Arc::clone(&self.tombstone_gc),
+            compactions: Arc::clone(&self.compactions),
+            recovery: Arc::clone(&self.recovery),
+            row_cache: self.row_cache.clone(),
+            probe_depths: self.probe_depths.clone(),
+            fanout: self.fanout.clone(),
+            clock: Arc::clone(&self.clock),
+            write_activity: Arc::clone(&self.write_activity),
         }
     }
 }
@@ -246,21 +997,126 @@ impl Engine {
     /// `Ok(false)` if the write succeeded without freezing.
     fn write_with_retry(
         inner: &mut EngineInner,
-        mut op: impl FnMut(&mut Memtable) -> Result<(), MemtableError>,
+        mut op: impl FnMut(&Memtable) -> Result<(), MemtableError>,
     ) -> Result<bool, EngineError> {
-        match op(&mut inner.active) {
-            Ok(()) => Ok(false),
+        let next_lsn = inner.active.max_lsn().unwrap_or(0) + 1;
+        Self::reserve_lsns(inner, next_lsn)?;
+        match op(&inner.active) {
+            Ok(()) => Self::freeze_if_due_inner(inner),
             Err(MemtableError::FlushRequired) => {
                 Self::freeze_active(inner)?;
-                op(&mut inner.active)?;
+                let next_lsn = inner.active.max_lsn().unwrap_or(0) + 1;
+                Self::reserve_lsns(inner, next_lsn)?;
+                op(&inner.active)?;
+                let max_lsn = inner.active.max_lsn().unwrap_or(0);
+                inner.manifest.update_lsn(max_lsn)?;
+                Ok(true)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Writes `ops`, puts and deletes in strictly ascending key order, to
+    /// the active memtable in one merge, freezing it first if they do not
+    /// fit.
+    ///
+    /// Returns `Ok(false)`, having written nothing, if they do not fit in
+    /// an empty memtable either. Sets `frozen` if a freeze occurred.
+    fn write_sorted_with_retry(
+        inner: &mut EngineInner,
+        ops: &[WriteOp],
+        opts: &WriteOptions,
+        frozen: &mut bool,
+    ) -> Result<bool, EngineError> {
+        let last_lsn = |inner: &EngineInner| inner.active.max_lsn().unwrap_or(0) + ops.len() as u64;
+        Self::reserve_lsns(inner, last_lsn(inner))?;
+        match inner.active.write_sorted(ops, opts) {
+            Ok(()) => {
+                *frozen |= Self::freeze_if_due_inner(inner)?;
+                return Ok(true);
+            }
+            Err(MemtableError::FlushRequired) => {
+                if inner.active.oldest_timestamp()?.is_none() {
+                    return Ok(false);
+                }
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        Self::freeze_active(inner)?;
+        *frozen = true;
+        Self::reserve_lsns(inner, last_lsn(inner))?;
+        match inner.active.write_sorted(ops, opts) {
+            Ok(()) => {
                 let max_lsn = inner.active.max_lsn().unwrap_or(0);
                 inner.manifest.update_lsn(max_lsn)?;
                 Ok(true)
             }
+            Err(MemtableError::FlushRequired) => Ok(false),
             Err(e) => Err(e.into()),
         }
     }
 
+    /// Reserves every LSN up to at least `lsn` in the manifest, a block
+    /// at a time, so no LSN is assigned before it is reserved.
+    fn reserve_lsns(inner: &mut EngineInner, lsn: u64) -> Result<(), EngineError> {
+        if lsn > inner.reserved_lsn {
+            let reserved = lsn.saturating_add(LSN_RESERVATION_BLOCK);
+            inner.manifest.reserve_lsn(reserved)?;
+            inner.reserved_lsn = reserved;
+        }
+        Ok(())
+    }
+
+    /// Freezes the active memtable if a flush trigger other than buffer
+    /// fullness fires: its oldest record is older than
+    /// [`EngineConfig::max_memtable_age`], or its WAL exceeds
+    /// [`EngineConfig::max_total_wal_size`] while no frozen memtable is
+    /// awaiting flush.
+    ///
+    /// Returns `Ok(true)` if a freeze occurred (caller should schedule a flush).
+    fn freeze_if_due_inner(inner: &mut EngineInner) -> Result<bool, EngineError> {
+        let config = &inner.config;
+        if inner.bulk_load || (config.max_memtable_age == 0 && config.max_total_wal_size == 0) {
+            return Ok(false);
+        }
+        let Some(oldest) = inner.active.oldest_timestamp()? else {
+            return Ok(false); // nothing to flush
+        };
+
+        let now_nanos = config.clock.now_nanos();
+        let max_age_nanos = (config.max_memtable_age as u64).saturating_mul(1_000_000_000);
+
+        let trigger =
+            if config.max_memtable_age > 0 && now_nanos.saturating_sub(oldest) >= max_age_nanos {
+                "age"
+            } else if config.max_total_wal_size > 0
+                && inner.frozen.is_empty()
+                && inner.active.wal_size()? > config.max_total_wal_size as u64
+            {
+                "wal_size"
+            } else {
+                return Ok(false);
+            };
+
+        tracing::debug!(trigger, "freezing active memtable");
+        Self::freeze_active(inner)?;
+        let max_lsn = inner.active.max_lsn().unwrap_or(0);
+        inner.manifest.update_lsn(max_lsn)?;
+        Ok(true)
+    }
+
+    /// Freezes the active memtable if it holds writes made with
+    /// [`WriteOptions::disable_wal`], so that the caller's flush of the
+    /// frozen memtables persists them — its WAL cannot.
+    fn freeze_unlogged_inner(inner: &mut EngineInner) -> Result<(), EngineError> {
+        if inner.active.has_unlogged_writes() {
+            tracing::debug!("freezing active memtable holding unlogged writes");
+            Self::freeze_active(inner)?;
+        }
+        Ok(())
+    }
+
     // --------------------------------------------------------------------------------------------
     // Lifecycle
     // --------------------------------------------------------------------------------------------
@@ -269,8 +1125,12 @@ impl Engine {
     ///
     /// On a fresh directory the manifest, WAL, and SSTable sub-directories
     /// are created automatically. On an existing directory the manifest is
-    /// replayed, frozen WALs are loaded, and SSTables are opened.
+    /// replayed, frozen WALs are loaded, and SSTables are opened. What was
+    /// recovered is available from [`recovery_report`](Self::recovery_report).
     pub fn open(path: impl AsRef<Path>, config: EngineConfig) -> Result<Self, EngineError> {
+        let started = Instant::now();
+        let mut report = RecoveryReport::default();
+
         // 0. Create necessary directories
         let base = path.as_ref();
         let manifest_dir = base.join(MANIFEST_DIR);
@@ -279,50 +1139,109 @@ impl Engine {
 
         fs::create_dir_all(&manifest_dir)?;
         fs::create_dir_all(&memtable_dir)?;
-        fs::create_dir_all(&sstable_dir)?;
-
-        // 1. Load or create manifest.
-        let manifest = Manifest::open(&manifest_dir)?;
+        fs::create_dir_all(layout::sstable_dir(base, &config))?;
+        durability::sync_dir(&sstable_dir)?;
+        durability::sync_dir(base)?;
+
+        // 1. Load or create manifest; rewrite SSTable paths recorded by
+        //    older versions relative to `base`.
+        let manifest = Manifest::open_with_recovery_mode(&manifest_dir, config.wal_recovery_mode)?
+            .with_root(base);
+        report.manifest_paths_rewritten = manifest.relocate_legacy_paths()?;
         let manifest_last_lsn = manifest.get_last_lsn()?;
 
+        // 1a. Settle compactions a crash interrupted: roll one that
+        //     committed forward by deleting its inputs, any other back by
+        //     deleting its outputs.
+        let live_ids: Vec<u64> = manifest.get_sstables()?.iter().map(|e| e.id).collect();
+        for pending in manifest.pending_compactions()? {
+            let committed = pending.is_committed(&live_ids);
+            let leftovers = if committed {
+                &pending.inputs
+            } else {
+                &pending.outputs
+            };
+            for entry in leftovers {
+                match fs::remove_file(&entry.path) {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            manifest.finish_compaction(&pending.input_ids())?;
+            if committed {
+                report.compactions_rolled_forward += 1;
+            } else {
+                report.compactions_rolled_back += 1;
+            }
+        }
+
         // 2. Discover existing WAL files and load active/frozen WAL info from manifest.
+        //    Missing frozen WALs are reported rather than recreated empty.
+        let mut consistency = ConsistencyReport::default();
         let active_wal_nr = manifest.get_active_wal()?;
         let active_wal_path = memtable_dir.join(format!("{:06}.log", active_wal_nr));
-        let memtable = Memtable::new(active_wal_path, None, config.write_buffer_size)?;
-
-        let frozen_wals = manifest.get_frozen_wals()?;
+        let wal_metrics = Arc::new(WalCounters::default());
+        let timestamps = Arc::new(HybridClock::new(Arc::clone(&config.clock)));
+        let (memtable, replay) = Memtable::recover(
+            active_wal_path,
+            None,
+            config.write_buffer_size,
+            config.wal_recovery_mode,
+        )?;
+        let memtable = memtable
+            .with_clock(timestamps.clone())
+            .with_wal_metrics(Arc::clone(&wal_metrics));
+        memtable.set_value_checksums(config.value_checksums)?;
+        timestamps.observe(replay.newest_timestamp);
+        report.add_wal(replay);
+
+        let frozen_wals = consistency.check_frozen_wals(
+            &memtable_dir,
+            active_wal_nr,
+            &manifest.get_frozen_wals()?,
+        );
         let mut frozen_memtables = Vec::new();
         for wal_nr in frozen_wals {
             let frozen_wal_path = memtable_dir.join(format!("{:06}.log", wal_nr));
-            let memtable = Memtable::new(frozen_wal_path, None, config.write_buffer_size)?;
+            let (memtable, replay) = Memtable::recover(
+                frozen_wal_path,
+                None,
+                config.write_buffer_size,
+                config.wal_recovery_mode,
+            )?;
+            let memtable = memtable
+                .with_clock(timestamps.clone())
+                .with_wal_metrics(Arc::clone(&wal_metrics));
+            memtable.set_value_checksums(config.value_checksums)?;
+            timestamps.observe(replay.newest_timestamp);
+            report.add_wal(replay);
             frozen_memtables.push(memtable.frozen()?);
         }
 
-        // 3. Discover existing SSTables on disk and remove orphans.
+        // 3. Discover existing SSTables on disk and remove orphans. File
+        //    names are unique across the layout, whatever its scheme.
         let sstables = manifest.get_sstables()?;
 
-        for entry in fs::read_dir(&sstable_dir)? {
-            let entry = entry?;
-            let file_path = entry.path();
-
-            if file_path.is_file()
-                && file_path.extension().and_then(|s| s.to_str()) == Some("sst")
-                && let Some(file_name) = file_path.file_name().and_then(|s| s.to_str())
-                && let Some(id) = file_name
-                    .strip_suffix(".sst")
-                    .and_then(|s| s.parse::<u64>().ok())
-                && !sstables.iter().any(|entry| entry.id == id)
+        for file_path in layout::sstable_files(&sstable_dir)? {
+            if !sstables
+                .iter()
+                .any(|entry| entry.path.file_name() == file_path.file_name())
             {
                 fs::remove_file(&file_path)?;
+                report.orphan_files_removed.push(file_path);
             }
         }
 
-        // 4. Load SSTables from manifest.
-        let mut sstable_handles = Vec::new();
-        for sstable_entry in sstables {
-            let mut sstable = SSTable::open(&sstable_entry.path)?;
-            sstable.set_id(sstable_entry.id);
-            sstable_handles.push(sstable);
+        // 4. Load SSTables from manifest, checking each against it.
+        consistency.check_sstable_ids(&sstables, manifest.peek_next_sst_id()?);
+        let mut sstable_handles: Vec<SSTable> = sstables
+            .iter()
+            .filter_map(|entry| consistency.open_sstable(entry, config.sstable_open_checks))
+            .collect();
+        if !consistency.is_consistent() {
+            tracing::error!(%consistency, "manifest does not match files on disk");
+            return Err(EngineError::Inconsistent(consistency));
         }
 
         // 5. Compute max LSN across all sources.
@@ -342,11 +1261,27 @@ impl Engine {
             if sstable.max_lsn() > max_lsn {
                 max_lsn = sstable.max_lsn();
             }
+            timestamps.observe(sstable.max_timestamp());
+        }
+
+        // Every LSN in use must have been reserved first; one that was not
+        // means the manifest is older than the data.
+        let reserved_lsn = manifest.get_reserved_lsn()?;
+        if reserved_lsn > 0 && max_lsn > reserved_lsn {
+            tracing::error!(max_lsn, reserved_lsn, "data holds unreserved LSNs");
+            return Err(EngineError::LsnRegression {
+                observed: max_lsn,
+                reserved: reserved_lsn,
+            });
         }
+        report.recovered_lsn = max_lsn;
 
+        // Resume above the reservation: LSNs of a lost WAL are not reused.
+        let max_lsn = max_lsn.max(reserved_lsn);
         if memtable.max_lsn().unwrap_or(0) != max_lsn {
-            memtable.inject_max_lsn(max_lsn + 1);
+            memtable.inject_max_lsn(max_lsn);
         }
+        report.sstables_opened = sstable_handles.len();
 
         // Sort frozen memtables by WAL sequence number, newest first.
         // We use wal_seq rather than creation_timestamp because on crash
@@ -357,35 +1292,131 @@ impl Engine {
         // Sort SSTables by max_lsn descending.  This lets get()
         // early-terminate: once we find a result at LSN L, any SSTable
         // whose max_lsn ≤ L cannot contain a newer version of any key.
-        sstable_handles.sort_by_key(|s| std::cmp::Reverse(s.max_lsn()));
+        sstable_handles.sort_by_key(|s| std::cmp::Reverse((s.max_lsn(), s.id())));
+
+        // Charge the tables to the mmap budget oldest first, so that the
+        // newest stay mapped if they do not all fit.
+        let mmap_budget =
+            (config.max_mmap_bytes > 0).then(|| Arc::new(MmapBudget::new(config.max_mmap_bytes)));
+        if let Some(budget) = &mmap_budget {
+            for sstable in sstable_handles.iter().rev() {
+                sstable.attach_budget(budget);
+            }
+        }
+
+        let active = Arc::new(memtable);
+        let frozen: Vec<_> = frozen_memtables.into_iter().map(Arc::new).collect();
+        let sstables: Vec<_> = sstable_handles.into_iter().map(Arc::new).collect();
+
+        let version = Arc::new(SuperVersionCell::new(SuperVersion {
+            active: Arc::clone(&active),
+            frozen: frozen.clone(),
+            sstable_span: SstKeySpan::of(&sstables),
+            sstables: sstables.clone(),
+            parallel_sstable_probe: config.parallel_sstable_probe,
+            thread_pool_size: config.thread_pool_size,
+        }));
 
+        let prefix_bytes_flushed = vec![0; config.tenant_prefixes.len()];
         let inner = EngineInner {
-            manifest,
-            active: memtable,
-            frozen: frozen_memtables.into_iter().map(Arc::new).collect(),
-            sstables: sstable_handles.into_iter().map(Arc::new).collect(),
+            manifest: Arc::new(manifest),
+            active,
+            frozen,
+            sstables,
             data_dir: base.to_path_buf(),
             config,
+            version: Arc::clone(&version),
+            bulk_load: false,
+            bytes_flushed: 0,
+            bytes_compacted: 0,
+            prefix_bytes_flushed,
+            mmap_budget,
+            hints: Vec::new(),
+            reserved_lsn,
+            version_pins: Arc::default(),
+            obsolete: ObsoleteFiles::default(),
+            wal_metrics,
+            sstables_built: SstBuildStats::default(),
+            timestamps,
         };
+        inner.prune_flushed_wals();
+        inner.prune_applied_tokens(inner.config.clock.now_nanos())?;
+
+        report.duration = started.elapsed();
+        tracing::info!(
+            wal_segments = report.wal_segments_replayed,
+            records = report.records_recovered,
+            wal_bytes_dropped = report.wal_bytes_dropped,
+            orphans_removed = report.orphan_files_removed.len(),
+            compactions_rolled_forward = report.compactions_rolled_forward,
+            compactions_rolled_back = report.compactions_rolled_back,
+            sstables = report.sstables_opened,
+            elapsed_ms = report.duration.as_millis() as u64,
+            "engine recovered"
+        );
 
+        let row_cache = (inner.config.row_cache_size > 0)
+            .then(|| Arc::new(RowCache::new(inner.config.row_cache_size)));
+        let probe_depths = (inner.config.read_depth_compaction_threshold > 0)
+            .then(Arc::<ProbeDepthWindow>::default);
+        let fanout = (inner.config.read_fanout_limit > 0).then(|| {
+            Arc::new(FanoutGuard::new(
+                inner.config.read_fanout_limit,
+                inner.config.read_fanout_fallback,
+            ))
+        });
+        let compactions = Arc::clone(inner.manifest.coordinator());
+        let clock = Arc::clone(&inner.config.clock);
         Ok(Self {
             inner: Arc::new(RwLock::new(inner)),
+            version,
+            reads: Arc::default(),
+            tombstone_gc: Arc::default(),
+            compactions,
+            recovery: Arc::new(report),
+            row_cache,
+            probe_depths,
+            fanout,
+            clock,
+            write_activity: Arc::default(),
         })
     }
 
+    /// Returns what [`open`](Self::open) recovered.
+    pub fn recovery_report(&self) -> &RecoveryReport {
+        &self.recovery
+    }
+
+    /// Repairs the engine directory at `path` so that it opens again:
+    /// damaged SSTables are rebuilt from their readable blocks and a new
+    /// manifest lists the surviving files. The directory must not be open.
+    pub fn repair(path: impl AsRef<Path>) -> Result<RepairReport, EngineError> {
+        repair::repair(path.as_ref())
+    }
+
     /// Gracefully shuts down the engine.
     ///
     /// Flushes all remaining frozen memtables, checkpoints the manifest,
-    /// and fsyncs all directories to ensure full durability.
+    /// and fsyncs all directories to ensure full durability. The active
+    /// memtable is left to WAL replay, unless it holds writes made with
+    /// [`WriteOptions::disable_wal`]; then it is flushed too. Removed
+    /// SSTable files no reader holds are deleted without waiting for
+    /// their grace period.
     pub fn close(&self) -> Result<(), EngineError> {
         let mut inner = self.write_lock()?;
 
-        // 1. Flush any remaining frozen memtables to SSTables
+        // 1. Flush any remaining frozen memtables to SSTables — and the
+        //    active one if it holds writes that skipped the WAL.
+        Self::freeze_unlogged_inner(&mut inner)?;
         while !inner.frozen.is_empty() {
             Self::flush_frozen_to_sstable_inner(&mut inner)?;
         }
+        inner.purge_obsolete(true);
 
         // 2. Checkpoint the manifest to create a snapshot
+        if inner.bulk_load {
+            inner.active.sync_wal()?;
+        }
         let max_lsn = inner.active.max_lsn().unwrap_or(0);
         inner.manifest.update_lsn(max_lsn)?;
         inner.manifest.checkpoint()?;
@@ -393,19 +1424,17 @@ impl Engine {
         // 3. Fsync directories to ensure metadata is durable
         let manifest_dir = inner.data_dir.join(MANIFEST_DIR);
         let memtable_dir = inner.data_dir.join(MEMTABLE_DIR);
-        let sstable_dir = inner.data_dir.join(SSTABLE_DIR);
+        let sstable_dir = layout::sstable_dir(&inner.data_dir, &inner.config);
 
         // Fsync each directory
         for dir_path in [&manifest_dir, &memtable_dir, &sstable_dir] {
-            if let Ok(dir) = fs::File::open(dir_path) {
-                dir.sync_all()?;
+            if dir_path.is_dir() {
+                durability::sync_dir(dir_path)?;
             }
         }
 
         // 4. Fsync the root data directory
-        if let Ok(root) = fs::File::open(&inner.data_dir) {
-            root.sync_all()?;
-        }
+        durability::sync_dir(&inner.data_dir)?;
 
         Ok(())
     }
@@ -414,19 +1443,43 @@ impl Engine {
     ///
     /// Returns `Ok(true)` if the active memtable was frozen (caller should
     /// arrange a flush), `Ok(false)` otherwise.
+    #[allow(dead_code)]
     pub fn put(&self, key: Vec<u8>, value: Vec<u8>) -> Result<bool, EngineError> {
+        self.put_opt(key, value, &WriteOptions::default())
+    }
+
+    /// Like [`put`](Self::put), with per-write [`WriteOptions`].
+    pub fn put_opt(
+        &self,
+        key: Vec<u8>,
+        value: Vec<u8>,
+        opts: &WriteOptions,
+    ) -> Result<bool, EngineError> {
         let mut inner = self.write_lock()?;
         tracing::trace!(key_len = key.len(), value_len = value.len(), "engine put");
-        Self::write_with_retry(&mut inner, |active| active.put(key.clone(), value.clone()))
+        let frozen = Self::write_with_retry(&mut inner, |active| {
+            active.put_opt(key.clone(), value.clone(), opts)
+        });
+        self.invalidate_rows(&key, None);
+        frozen
     }
 
     /// Delete a key (insert a point tombstone).
     ///
     /// Returns `Ok(true)` if the active memtable was frozen, `Ok(false)` otherwise.
+    #[allow(dead_code)]
     pub fn delete(&self, key: Vec<u8>) -> Result<bool, EngineError> {
+        self.delete_opt(key, &WriteOptions::default())
+    }
+
+    /// Like [`delete`](Self::delete), with per-write [`WriteOptions`].
+    pub fn delete_opt(&self, key: Vec<u8>, opts: &WriteOptions) -> Result<bool, EngineError> {
         let mut inner = self.write_lock()?;
         tracing::trace!(key_len = key.len(), "engine delete");
-        Self::write_with_retry(&mut inner, |active| active.delete(key.clone()))
+        let frozen =
+            Self::write_with_retry(&mut inner, |active| active.delete_opt(key.clone(), opts));
+        self.invalidate_rows(&key, None);
+        frozen
     }
 
     /// Delete all keys in `[start_key, end_key)` (insert a range tombstone).
@@ -439,155 +1492,953 @@ impl Engine {
             end_len = end_key.len(),
             "engine delete_range"
         );
-        Self::write_with_retry(&mut inner, |active| {
+        let frozen = Self::write_with_retry(&mut inner, |active| {
             active.delete_range(start_key.clone(), end_key.clone())
-        })
+        });
+        self.invalidate_rows(&start_key, Some(&end_key));
+        frozen
     }
 
-    /// Look up a single key.
+    /// Delete every key at or after `start_key` (insert a range tombstone
+    /// with the unbounded end).
     ///
-    /// Returns `Ok(Some(value))` if the key exists, `Ok(None)` if it has
-    /// been deleted or was never written, or `Err` on I/O failure.
+    /// Returns `Ok(true)` if the active memtable was frozen, `Ok(false)` otherwise.
+    pub fn delete_range_from(&self, start_key: Vec<u8>) -> Result<bool, EngineError> {
+        let mut inner = self.write_lock()?;
+        tracing::trace!(start_len = start_key.len(), "engine delete_range_from");
+        let frozen = Self::write_with_retry(&mut inner, |active| {
+            active.delete_range_from(start_key.clone())
+        });
+        self.invalidate_rows(&start_key, Some(&[]));
+        frozen
+    }
+
+    /// Drops every key: all SSTables, frozen memtables, and the active
+    /// memtable, with their files.
     ///
-    /// The lookup order is: active memtable → frozen memtables → SSTables
-    /// (all newest-first). The first definitive result wins.
-    pub fn get(&self, key: Vec<u8>) -> Result<Option<Vec<u8>>, EngineError> {
-        tracing::trace!(key_len = key.len(), "engine get");
-        let inner = self.read_lock()?;
+    /// The manifest records the new, empty state in one entry and is then
+    /// checkpointed, so a crash leaves either the old or the empty
+    /// database; files the crash left behind are collected on the next
+    /// open. LSNs and SSTable IDs continue where they were. Scans opened
+    /// earlier still read the dropped data.
+    pub fn clear(&self) -> Result<(), EngineError> {
+        let mut inner = self.write_lock()?;
+        let inner = &mut *inner;
 
-        // --------------------------------------------------
-        // 1. Active memtable (newest)
-        // --------------------------------------------------
-        match inner.active.get(&key)? {
-            MemtableGetResult::Put(value) => return Ok(Some(value)),
-            MemtableGetResult::Delete | MemtableGetResult::RangeDelete => return Ok(None),
-            MemtableGetResult::NotFound => {}
+        // 1. Start an empty active memtable on a new WAL.
+        let current_max_lsn = inner.active.max_lsn().unwrap_or(0);
+        let new_active_wal_id = inner.active.wal_seq() + 1;
+        let wal_dir = inner.data_dir.join(MEMTABLE_DIR);
+        let new_active = Memtable::new(
+            wal_dir.join(format!("{:06}.log", new_active_wal_id)),
+            None,
+            inner.write_buffer_size(),
+        )?
+        .with_clock(inner.timestamps.clone())
+        .with_wal_metrics(Arc::clone(&inner.wal_metrics));
+        inner.wal_metrics.record_rotation();
+        new_active.set_value_checksums(inner.config.value_checksums)?;
+        if inner.bulk_load {
+            new_active.set_wal_sync(false);
         }
 
+        // 2. Commit the empty state in the manifest.
+        let dropped_sstables = inner.manifest.get_sstables()?;
+        inner.manifest.clear(new_active_wal_id)?;
+        inner.manifest.checkpoint()?;
+
+        // 3. Publish it.
+        let mut dropped_wals: Vec<u64> = inner.frozen.iter().map(|f| f.wal_seq()).collect();
+        dropped_wals.push(inner.active.wal_seq());
+        inner.active = Arc::new(new_active);
+        inner.active.inject_max_lsn(current_max_lsn);
+        inner.frozen.clear();
+        let removed = std::mem::take(&mut inner.sstables);
+        inner.hints.clear();
+        inner.publish();
+        if let Some(cache) = &self.row_cache {
+            cache.advance_epoch();
+        }
+        tracing::info!(
+            sstables = dropped_sstables.len(),
+            wals = dropped_wals.len(),
+            "engine cleared"
+        );
+
+        // 4. Delete the dropped files. A file that cannot be deleted is
+        //    only logged; the next open collects it.
+        let removed_paths = dropped_sstables
+            .into_iter()
+            .map(|entry| (entry.id, entry.path))
+            .collect();
+        inner.retire_sstables(removed, removed_paths);
+        for wal_id in dropped_wals {
+            let path = wal_dir.join(format!("{:06}.log", wal_id));
+            if let Err(e) = fs::remove_file(&path) {
+                tracing::warn!(path = %path.display(), %e, "failed to remove cleared WAL segment");
+            }
+        }
+        durability::sync_dir(&wal_dir)?;
+        inner.prune_flushed_wals();
+        Ok(())
+    }
+
+    /// Apply a sequence of writes under a single write lock.
+    ///
+    /// The batch never interleaves with other writers. Readers take no
+    /// engine lock, so a concurrent lookup or scan may observe a prefix of
+    /// `ops`. Each operation is still logged as its own WAL record, so a
+    /// crash midway through may persist only a prefix of the batch.
+    ///
+    /// `opts` applies to every operation; with [`WriteOptions::sync`] the
+    /// WAL is fsynced after each record.
+    ///
+    /// A batch of at least [`SORTED_BATCH_MIN_OPS`] puts and deletes in
+    /// strictly ascending key order that fits in one memtable is merged
+    /// into it at once instead (see [`Memtable::write_sorted`]); its WAL
+    /// is fsynced once, after the last record.
+    ///
+    /// Returns `Ok(true)` if the active memtable was frozen at least once,
+    /// `Ok(false)` otherwise.
+    pub(crate) fn write_batch(
+        &self,
+        ops: &[WriteOp],
+        opts: &WriteOptions,
+    ) -> Result<bool, EngineError> {
+        let mut inner = self.write_lock()?;
+        tracing::trace!(ops = ops.len(), "engine write_batch");
+
+        let mut frozen = false;
+        if ops.len() >= SORTED_BATCH_MIN_OPS
+            && is_sorted_point_batch(ops)
+            && Self::write_sorted_with_retry(&mut inner, ops, opts, &mut frozen)?
+        {
+            for op in ops {
+                if let Some(key) = op.point_key() {
+                    self.invalidate_rows(key, None);
+                }
+            }
+            return Ok(frozen);
+        }
+
+        for op in ops {
+            let result = match op {
+                WriteOp::Put { key, value } => Self::write_with_retry(&mut inner, |active| {
+                    active.put_opt(key.clone(), value.clone(), opts)
+                }),
+                WriteOp::Delete { key } => Self::write_with_retry(&mut inner, |active| {
+                    active.delete_opt(key.clone(), opts)
+                }),
+                WriteOp::DeleteRange { start, end } => {
+                    Self::write_with_retry(&mut inner, |active| {
+                        active.delete_range_opt(start.clone(), end.clone(), opts)
+                    })
+                }
+            };
+            match op {
+                WriteOp::Put { key, .. } | WriteOp::Delete { key } => {
+                    self.invalidate_rows(key, None)
+                }
+                WriteOp::DeleteRange { start, end } => self.invalidate_rows(start, Some(end)),
+            }
+            frozen |= result?;
+        }
+        Ok(frozen)
+    }
+
+    /// Fsyncs the active WAL, making every logged write durable even
+    /// while bulk loading has per-record fsync turned off.
+    pub fn sync_wal(&self) -> Result<(), EngineError> {
+        self.write_lock()?.active.sync_wal()?;
+        Ok(())
+    }
+
+    /// Returns whether the idempotency `token` of a write batch was
+    /// recorded within
+    /// [`EngineConfig::idempotency_retention_seconds`].
+    pub fn has_applied(&self, token: &[u8]) -> Result<bool, EngineError> {
+        let inner = self.read_lock()?;
+        let now_nanos = inner.config.clock.now_nanos();
+        let cutoff = inner.token_cutoff(now_nanos);
+        Ok(inner
+            .manifest
+            .applied_token_timestamp(token)?
+            .is_some_and(|timestamp| timestamp >= cutoff))
+    }
+
+    /// Records the idempotency `token` of a committed write batch in the
+    /// manifest, then forgets the tokens outside the retention limits.
+    ///
+    /// The caller makes the batch's writes durable first: a token must
+    /// never survive a crash that the writes it stands for did not.
+    pub fn record_applied(&self, token: &[u8]) -> Result<(), EngineError> {
+        let inner = self.write_lock()?;
+        let now_nanos = inner.config.clock.now_nanos();
+        inner.manifest.record_applied_token(token, now_nanos)?;
+        inner.prune_applied_tokens(now_nanos)
+    }
+
+    /// Write `new` — a put, or a delete when `None` — if the visible
+    /// value of `key` equals `expected`, `None` meaning absent.
+    ///
+    /// The lookup and the write both run under the write lock, so no
+    /// other write to the engine lands in between. The lookup reads
+    /// SSTables if the key is not in a memtable, holding up other writers
+    /// meanwhile.
+    ///
+    /// Returns the outcome, and whether the active memtable was frozen.
+    pub fn compare_and_swap(
+        &self,
+        key: Vec<u8>,
+        expected: Option<&[u8]>,
+        new: Option<Vec<u8>>,
+    ) -> Result<(CasOutcome, bool), EngineError> {
+        let mut inner = self.write_lock()?;
+        tracing::trace!(key_len = key.len(), "engine compare_and_swap");
+
+        self.reads.record_get();
+        let current = self
+            .resolve(&key, &ReadOptions::default())?
+            .map(|found| found.value);
+        if current.as_deref() != expected {
+            let current = current.map(PinnedSlice::into_vec);
+            return Ok((CasOutcome::Mismatch(current), false));
+        }
+
+        let frozen = match &new {
+            Some(value) => {
+                Self::write_with_retry(&mut inner, |active| active.put(key.clone(), value.clone()))
+            }
+            None => Self::write_with_retry(&mut inner, |active| active.delete(key.clone())),
+        };
+        self.invalidate_rows(&key, None);
+        Ok((CasOutcome::Swapped, frozen?))
+    }
+
+    /// Drops the row cache entries a write made stale: `start` alone, or
+    /// every key in `[start, end)` for a range delete (from `start` on if
+    /// `end` is empty).
+    ///
+    /// Called after the write reached the memtable, so that a lookup which
+    /// began earlier cannot cache the value the write replaced.
+    fn invalidate_rows(&self, start: &[u8], end: Option<&[u8]>) {
+        match (&self.row_cache, end) {
+            (None, _) => {}
+            (Some(cache), None) => cache.invalidate(start),
+            (Some(cache), Some(end)) => cache.invalidate_range(start, end),
+        }
+    }
+
+    /// Look up a single key.
+    ///
+    /// Returns `Ok(Some(value))` if the key exists, `Ok(None)` if it has
+    /// been deleted or was never written, or `Err` on I/O failure.
+    ///
+    /// The lookup order is: active memtable → frozen memtables → SSTables
+    /// (all newest-first). The version with the greatest [`VersionRank`]
+    /// wins, and of equal ranks the one in the newer layer.
+    pub fn get(&self, key: Vec<u8>) -> Result<Option<Vec<u8>>, EngineError> {
+        self.get_opt(key, &ReadOptions::default())
+    }
+
+    /// Look up a single key with explicit [`ReadOptions`].
+    ///
+    /// Identical to [`get`](Self::get) except that SSTable data block
+    /// checksum verification follows `opts.verify_checksums`.
+    pub fn get_opt(
+        &self,
+        key: Vec<u8>,
+        opts: &ReadOptions,
+    ) -> Result<Option<Vec<u8>>, EngineError> {
+        Ok(self.lookup(key, opts)?.map(PinnedSlice::into_vec))
+    }
+
+    /// Look up a single key without copying SSTable-resident values.
+    ///
+    /// Same lookup order and semantics as [`get`](Self::get). A value found
+    /// in an SSTable is returned as a [`PinnedSlice`] that borrows the
+    /// SSTable's mmap; a value found in a memtable is returned as an owned
+    /// copy.
+    pub fn get_pinned(&self, key: Vec<u8>) -> Result<Option<PinnedSlice>, EngineError> {
+        self.lookup(key, &ReadOptions::default())
+    }
+
+    /// Look up a single key together with the LSN and timestamp of the
+    /// write that stored its value.
+    ///
+    /// Same lookup order and semantics as [`get_opt`](Self::get_opt), but
+    /// never answered by the row cache, which keeps values only.
+    pub fn get_entry(
+        &self,
+        key: Vec<u8>,
+        opts: &ReadOptions,
+    ) -> Result<Option<Entry>, EngineError> {
+        tracing::trace!(key_len = key.len(), "engine get entry");
+        self.reads.record_get();
+        Ok(self.resolve(&key, opts)?.map(|found| Entry {
+            value: found.value.into_vec(),
+            lsn: found.lsn,
+            timestamp: found.timestamp,
+        }))
+    }
+
+    /// Shared point-lookup path behind [`get_opt`](Self::get_opt) and
+    /// [`get_pinned`](Self::get_pinned).
+    fn lookup(&self, key: Vec<u8>, opts: &ReadOptions) -> Result<Option<PinnedSlice>, EngineError> {
+        tracing::trace!(key_len = key.len(), "engine get");
+        self.reads.record_get();
+
+        // Taken before the version is loaded, so that a write landing in
+        // between invalidates the token and the result is not cached.
+        let token = match self.row_cache.as_ref().map(|cache| cache.get(&key)) {
+            Some(CacheLookup::Hit(value)) => {
+                self.reads.record_row_cache_hit();
+                return Ok(Some(PinnedSlice::owned(value)));
+            }
+            Some(CacheLookup::Miss(token)) => Some(token),
+            None => None,
+        };
+
+        let value = self.resolve(&key, opts)?.map(|found| found.value);
+        // Only SSTable hits are cached; memtables are already in RAM.
+        if let (Some(value), Some(cache), Some(token)) = (&value, &self.row_cache, token)
+            && value.is_pinned()
+        {
+            cache.insert(key, value.to_vec(), token);
+        }
+        Ok(value)
+    }
+
+    /// Finds the visible version of `key` in the current superversion.
+    ///
+    /// Of all versions found, the one with the greatest [`VersionRank`]
+    /// wins; equal ranks go to the newer layer.
+    fn resolve(&self, key: &[u8], opts: &ReadOptions) -> Result<Option<Resolved>, EngineError> {
+        let inner = self.version.load();
+
         // --------------------------------------------------
+        // 1. Active memtable (newest)
         // 2. Frozen memtables (newest → oldest)
         // --------------------------------------------------
+        // An older memtable is only read while it may hold a version at
+        // least as new as the best one so far — i.e. a duplicate — and
+        // its fence keys and bloom filter do not rule the key out.
+        let mut best = inner.active.get_record(key, opts.verify_value_checksum)?;
         for frozen in &inner.frozen {
-            match frozen.get(&key)? {
-                MemtableGetResult::Put(value) => return Ok(Some(value)),
-                MemtableGetResult::Delete | MemtableGetResult::RangeDelete => {
-                    return Ok(None);
-                }
-                MemtableGetResult::NotFound => {}
+            if let Some(found) = &best
+                && frozen.max_lsn().is_none_or(|max| max < found.lsn())
+            {
+                continue;
+            }
+            if !frozen.may_contain(key) {
+                continue;
+            }
+            if let Some(record) = frozen.get_record(key, opts.verify_value_checksum)?
+                && best
+                    .as_ref()
+                    .is_none_or(|found| record.rank() > found.rank())
+            {
+                best = Some(record);
             }
         }
 
         // --------------------------------------------------
         // 3. SSTables (sorted by max_lsn descending)
-        //
-        //    After size-tiered compaction, a merged SSTable may
-        //    span a wide LSN range. We track the best (highest-LSN)
-        //    result found so far. Once an SSTable's max_lsn is ≤
-        //    the best LSN, no subsequent SSTable can beat it, so
-        //    we break early.
         // --------------------------------------------------
-        let mut best_sst: Option<sstable::GetResult> = None;
-        let mut best_lsn: u64 = 0;
+        let parallel = inner
+            .parallel_sstable_probe
+            .then_some(inner.thread_pool_size);
+        let floor = best.as_ref().map(Record::rank);
+        let mut probed = 0;
+        let found = Self::lookup_sstables(
+            &self.reads,
+            self.fanout.as_deref(),
+            self.clock.as_ref(),
+            &inner.sstables,
+            parallel,
+            key,
+            opts,
+            floor,
+            &mut probed,
+        )?;
+        if let Some(depths) = &self.probe_depths {
+            depths.record(probed);
+        }
+        if let Some(result) = found {
+            return Ok(Self::resolved(result));
+        }
 
-        for sst in &inner.sstables {
+        Ok(match best {
+            Some(Record::Put {
+                value,
+                lsn,
+                timestamp,
+                ..
+            }) => Some(Resolved {
+                value: PinnedSlice::owned(value),
+                lsn,
+                timestamp,
+            }),
+            _ => None,
+        })
+    }
+
+    /// Probes `sstables`, sorted by `max_lsn` descending, for the newest
+    /// version of `key`, counting every probe in `reads` and in the
+    /// table's own counters and the SSTables consulted in `probed`. With
+    /// `parallel` set, bloom filters are first checked on that many
+    /// threads.
+    ///
+    /// Returns the highest-ranked version that outranks `floor` — the best
+    /// version found in the memtables — or `None` if no SSTable holds one.
+    /// Tables whose `max_lsn` is below the floor are not probed.
+    ///
+    /// After size-tiered compaction, a merged SSTable may span a wide LSN
+    /// range. We track the best (highest-rank) result found so far. Once
+    /// an SSTable's max_lsn is below the best LSN, no subsequent SSTable
+    /// can beat or tie it, so we break early.
+    #[allow(clippy::too_many_arguments)]
+    fn lookup_sstables(
+        reads: &ReadCounters,
+        fanout: Option<&FanoutGuard>,
+        clock: &dyn Clock,
+        sstables: &[Arc<SSTable>],
+        parallel: Option<usize>,
+        key: &[u8],
+        opts: &ReadOptions,
+        floor: Option<VersionRank>,
+        probed: &mut usize,
+    ) -> Result<Option<sstable::GetResult<FileBytes>>, EngineError> {
+        let sstables = match floor {
+            Some(floor) => &sstables[..sstables.partition_point(|sst| sst.max_lsn() >= floor.lsn)],
+            None => sstables,
+        };
+        // Past the fan-out limit, the fallback skips tables whose key
+        // bounds rule the key out before touching their bloom filters.
+        let bounded = match fanout {
+            Some(guard) if guard.check(sstables.len()) => {
+                reads.record_fanout_exceeded();
+                guard.bounded_fallback()
+            }
+            _ => false,
+        };
+        let mut best_sst: Option<sstable::GetResult<FileBytes>> = None;
+        let mut best_rank = floor;
+
+        let candidates = parallel
+            .filter(|_| sstables.len() >= PARALLEL_PROBE_MIN_SSTABLES)
+            .map(|threads| Self::probe_candidates(sstables, key, threads));
+
+        // Per-lookup counters for a sampled `db.get` span, if any.
+        let span = tracing::Span::current();
+        let record = !span.is_disabled();
+        let (mut touched, mut bloom_hits, mut bloom_misses) = (0usize, 0usize, 0usize);
+
+        for (idx, sst) in sstables.iter().enumerate() {
             // Early termination: this SSTable (and all after it) have
-            // max_lsn ≤ best_lsn, so they can't contain a newer version.
-            if sst.max_lsn() <= best_lsn {
+            // max_lsn < best LSN, so they can't contain a newer version.
+            if best_rank.is_some_and(|best| sst.max_lsn() < best.lsn) {
                 break;
             }
 
-            match sst.get(&key)? {
-                sstable::GetResult::NotFound => {}
-                result => {
-                    let lsn = result.lsn();
-                    if lsn > best_lsn {
-                        best_lsn = lsn;
-                        best_sst = Some(result);
-                    }
-                }
+            if bounded && !sst.may_hold_key(key) {
+                continue;
+            }
+
+            // Skip tables already ruled out by the parallel bloom pass.
+            *probed += 1;
+            if candidates.as_ref().is_some_and(|c| !c[idx]) {
+                bloom_misses += 1;
+                Self::record_probe(reads, sst, &SstProbe::BLOOM_NEGATIVE);
+                continue;
+            }
+
+            if opts.deadline_passed() {
+                return Err(EngineError::TimedOut);
+            }
+
+            touched += 1;
+            let mut probe = SstProbe::default();
+            let result = sst.get_ref(
+                key,
+                opts.verify_checksums,
+                opts.verify_value_checksum,
+                &mut probe,
+            )?;
+            Self::record_probe(reads, sst, &probe);
+            if probe.key_in_block {
+                sst.access.record_hit(clock.now_nanos());
+            }
+            if probe.bloom_negative {
+                bloom_misses += 1;
+            } else {
+                bloom_hits += 1;
             }
+
+            if let Some(rank) = result.rank()
+                && best_rank.is_none_or(|best| rank > best)
+            {
+                best_rank = Some(rank);
+                best_sst = Some(result);
+            }
+        }
+
+        if record {
+            span.record("sstables_touched", touched);
+            span.record("bloom_hits", bloom_hits);
+            span.record("bloom_misses", bloom_misses);
         }
 
-        match best_sst {
-            Some(sstable::GetResult::Put { value, .. }) => Ok(Some(value)),
-            Some(sstable::GetResult::Delete { .. } | sstable::GetResult::RangeDelete { .. }) => {
-                Ok(None)
+        Ok(best_sst)
+    }
+
+    /// Converts the version picked by [`lookup_sstables`](Self::lookup_sstables)
+    /// into the lookup result: its value if it is a put.
+    fn resolved(result: sstable::GetResult<FileBytes>) -> Option<Resolved> {
+        match result {
+            sstable::GetResult::Put {
+                value,
+                lsn,
+                timestamp,
+            } => Some(Resolved {
+                value: PinnedSlice::from_sstable(value),
+                lsn,
+                timestamp,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Adds one SSTable probe to the table's and the engine's counters.
+    fn record_probe(reads: &ReadCounters, sst: &SSTable, probe: &SstProbe) {
+        sst.reads.record_get();
+        sst.reads.record_probe(probe);
+        reads.record_probe(probe);
+        slow_op::record_tables_probed(1);
+    }
+
+    /// Returns the point-lookup counters accumulated since open.
+    ///
+    /// Per-SSTable counters are reported by
+    /// [`sstable_metadata`](Self::sstable_metadata).
+    pub fn read_stats(&self) -> ReadStats {
+        self.reads.snapshot()
+    }
+
+    /// Returns the WAL and SSTable build counters accumulated since open.
+    pub fn write_stats(&self) -> Result<WriteStats, EngineError> {
+        let inner = self.read_lock()?;
+        Ok(WriteStats {
+            wal: inner.wal_metrics.snapshot(),
+            sstables: inner.sstables_built,
+        })
+    }
+
+    /// Returns what was written since the last call, as an event of
+    /// `partition`, and remembers the counters as reported.
+    ///
+    /// Returns `None` if nothing was written.
+    pub fn take_write_activity(
+        &self,
+        partition: usize,
+    ) -> Result<Option<WriteActivity>, EngineError> {
+        let stats = self.write_stats()?;
+        Ok(self
+            .write_activity
+            .take(stats)
+            .map(|stats| WriteActivity { partition, stats }))
+    }
+
+    /// Returns the reads past `read_fanout_limit` since the last call, as
+    /// an event of `partition`, and forgets them.
+    ///
+    /// Returns `None` if the limit is disabled or no read exceeded it.
+    pub fn take_read_fanout(&self, partition: usize) -> Option<ReadFanout> {
+        let guard = self.fanout.as_ref()?;
+        let (reads, widest) = guard.take();
+        (reads > 0).then(|| ReadFanout {
+            partition,
+            reads,
+            widest,
+            limit: guard.limit(),
+            sstables: self.version.load().sstables.len(),
+        })
+    }
+
+    /// Estimates the number of live keys from memtable and SSTable
+    /// metadata, without reading data blocks.
+    ///
+    /// See [`key_estimate`] for the heuristics; the result is exact for a
+    /// single layer without deletes.
+    pub fn estimate_num_keys(&self) -> Result<u64, EngineError> {
+        let version = self.version.load();
+        let mut layers = Vec::with_capacity(1 + version.frozen.len() + version.sstables.len());
+
+        let memtables = std::iter::once((version.active.stats(), version.active.key_sketch()))
+            .chain(
+                version
+                    .frozen
+                    .iter()
+                    .map(|frozen| (frozen.stats(), frozen.key_sketch())),
+            );
+        for (stats, sketch) in memtables {
+            let stats = stats?;
+            if let Some((min_key, max_key)) = stats.key_range {
+                layers.push(key_estimate::LayerKeys {
+                    min_key,
+                    max_key,
+                    puts: stats.live_key_count as u64,
+                    deletes: (stats.key_count - stats.live_key_count) as u64,
+                    sketch: Some(sketch?),
+                });
             }
-            _ => Ok(None),
         }
+        for sst in version.sstables.iter().filter(|sst| sst.record_count() > 0) {
+            layers.push(key_estimate::LayerKeys {
+                min_key: sst.min_key().to_vec(),
+                max_key: sst.max_key().to_vec(),
+                puts: sst.record_count() - sst.tombstone_count(),
+                deletes: sst.tombstone_count(),
+                sketch: sst.key_sketch().cloned(),
+            });
+        }
+
+        Ok(key_estimate::estimate_live_keys(layers))
+    }
+
+    /// Runs [`SSTable::may_contain`] for every SSTable in parallel.
+    ///
+    /// SSTables are split into at most `threads` contiguous chunks, each
+    /// checked on a scoped thread. Returns one flag per SSTable, in the
+    /// same order as `sstables`.
+    fn probe_candidates(sstables: &[Arc<SSTable>], key: &[u8], threads: usize) -> Vec<bool> {
+        let chunk_size = sstables.len().div_ceil(threads.max(1));
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = sstables
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|sst| sst.may_contain(key))
+                            .collect::<Vec<bool>>()
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|h| {
+                    // A panicking probe cannot rule anything out.
+                    h.join().unwrap_or_else(|_| Vec::new())
+                })
+                .collect()
+        })
     }
 
     /// Scan all live key-value pairs in `[start_key, end_key)`.
     ///
     /// Returns an iterator of `(key, value)` pairs, merging entries from
     /// all layers and applying point/range tombstones to filter out
-    /// deleted keys.
-    pub fn scan(
+    /// deleted keys. SSTable data block checksum verification follows
+    /// `opts.verify_checksums`.
+    pub fn scan_opt(
         &self,
         start_key: &[u8],
         end_key: &[u8],
-    ) -> Result<impl Iterator<Item = (Vec<u8>, Vec<u8>)>, EngineError> {
+        opts: &ReadOptions,
+    ) -> Result<impl Iterator<Item = (Vec<u8>, Vec<u8>)> + Send + use<>, EngineError> {
         tracing::trace!(
             start_len = start_key.len(),
             end_len = end_key.len(),
+            verify_checksums = opts.verify_checksums,
             "engine scan"
         );
-        let merged = self.raw_scan(start_key, end_key)?;
+        let merged = self.raw_scan(start_key, end_key, 0, u64::MAX, opts)?;
+        Ok(VisibilityFilter::new(merged))
+    }
+
+    /// Scan `[start_key, end_key)` as of `max_lsn`: each key resolves to
+    /// its newest version at or below `max_lsn`, and writes after it are
+    /// not seen.
+    ///
+    /// Flushes and compactions drop the older versions of a key, so a
+    /// version at or below `max_lsn` is only guaranteed to be found while
+    /// a [`pin_versions`](Self::pin_versions) pin at or below `max_lsn`
+    /// is held.
+    #[allow(dead_code)]
+    pub fn scan_at(
+        &self,
+        start_key: &[u8],
+        end_key: &[u8],
+        max_lsn: u64,
+        opts: &ReadOptions,
+    ) -> Result<impl Iterator<Item = (Vec<u8>, Vec<u8>)> + Send + use<>, EngineError> {
+        tracing::trace!(
+            start_len = start_key.len(),
+            end_len = end_key.len(),
+            max_lsn,
+            "engine scan at"
+        );
+        let merged = self.raw_scan(start_key, end_key, 0, max_lsn, opts)?;
         Ok(VisibilityFilter::new(merged))
     }
 
+    /// Scan `[start_key, end_key)` for keys whose visible version was
+    /// written at or after `min_timestamp` (UNIX epoch nanoseconds).
+    ///
+    /// SSTable data blocks whose index entry shows every entry older than
+    /// `min_timestamp` are skipped unread. Deleted keys are not reported.
+    pub fn scan_since(
+        &self,
+        start_key: &[u8],
+        end_key: &[u8],
+        min_timestamp: u64,
+        opts: &ReadOptions,
+    ) -> Result<impl Iterator<Item = (Vec<u8>, Vec<u8>)> + Send + use<>, EngineError> {
+        tracing::trace!(
+            start_len = start_key.len(),
+            end_len = end_key.len(),
+            min_timestamp,
+            "engine scan since"
+        );
+        let merged = self.raw_scan(start_key, end_key, min_timestamp, u64::MAX, opts)?;
+        Ok(VisibilityFilter::since(merged, min_timestamp))
+    }
+
+    /// Counts the live keys in `[start_key, end_key)` without returning
+    /// their values.
+    ///
+    /// An SSTable lying inside the range, holding no tombstones, and
+    /// sharing no key with any other layer is counted from its properties
+    /// without reading a data block. The remaining layers are merged as by
+    /// [`scan_opt`](Self::scan_opt) and their visible keys counted.
+    pub fn count(&self, start_key: &[u8], end_key: &[u8]) -> Result<u64, EngineError> {
+        tracing::trace!(
+            start_len = start_key.len(),
+            end_len = end_key.len(),
+            "engine count"
+        );
+        let version = self.version.load();
+        let memtables = Self::scan_memtables(&version, start_key, end_key, u64::MAX)?;
+        let sstables: Vec<&Arc<SSTable>> =
+            Self::overlapping_sstables(&version, start_key, end_key).collect();
+
+        // Range tombstones in the memtables, which may reach past the keys
+        // they hold.
+        let memtable_ranges: Vec<(&[u8], &[u8])> = memtables
+            .iter()
+            .flatten()
+            .filter_map(|record| match record {
+                Record::RangeDelete { start, end, .. } => Some((start.as_slice(), end.as_slice())),
+                _ => None,
+            })
+            .collect();
+
+        let mut from_properties = 0u64;
+        let mut scanned = Vec::with_capacity(sstables.len());
+        for (i, sst) in sstables.iter().enumerate() {
+            let unshadowed = || {
+                let (min_key, max_key) = (sst.min_key(), sst.max_key());
+                // The smallest key above `max_key`.
+                let mut past_max = max_key.to_vec();
+                past_max.push(0);
+                let in_memtable = memtables.iter().any(|records| {
+                    let first = records.partition_point(|record| record.key() < min_key);
+                    records
+                        .get(first)
+                        .is_some_and(|record| record.key() <= max_key)
+                });
+                !in_memtable
+                    && !memtable_ranges
+                        .iter()
+                        .any(|&(start, end)| start <= max_key && below_end(min_key, end))
+                    && !sstables
+                        .iter()
+                        .enumerate()
+                        .any(|(j, other)| j != i && other.may_overlap(min_key, &past_max))
+            };
+            match sst.key_count() {
+                Some(keys)
+                    if sst.tombstone_count() == 0
+                        && sst.range_tombstone_count() == 0
+                        && sst.min_key() >= start_key
+                        && sst.max_key() < end_key
+                        && unshadowed() =>
+                {
+                    from_properties += keys;
+                }
+                _ => scanned.push(*sst),
+            }
+        }
+
+        let merged = Self::merge_layers(
+            memtables,
+            scanned.into_iter(),
+            start_key,
+            end_key,
+            0,
+            u64::MAX,
+            &ReadOptions::default(),
+        )?;
+        Ok(from_properties + VisibilityFilter::new(merged).count() as u64)
+    }
+
     /// Captures an MVCC snapshot of all layers and merges them lazily.
     ///
     /// # MVCC snapshot approach
     ///
-    /// 1. **Active memtable** — `.collect()` (mutable, already in RAM).
-    /// 2. **Frozen memtables / SSTables** — `Arc::clone` the handles
-    ///    (cheap pointer bump), then release the lock.
+    /// 1. Load the current [`SuperVersion`] (one atomic load, no lock).
+    /// 2. **Active memtable** — `.collect()` (mutable, already in RAM).
     /// 3. Scan frozen memtables (in-RAM, scan already collects).
-    /// 4. Create **lazy** `ScanIterator<Arc<SSTable>>` per SSTable — reads
-    ///    blocks on demand via mmap, never materialising the full result
-    ///    set in RAM.
+    /// 4. Create a [`LazyScanIterator`](crate::sstable::LazyScanIterator)
+    ///    per SSTable that [may overlap](SSTable::may_overlap) the range —
+    ///    it reads nothing until the merge reaches the table's first key,
+    ///    then reads blocks on demand via mmap, never materialising the
+    ///    full result set in RAM.
+    /// 5. Merge with [`MergeIterator::skipping_covered`](utils::MergeIterator::skipping_covered),
+    ///    so SSTables entirely older than a range tombstone skip the
+    ///    deleted range.
     ///
-    /// The `Arc` keeps each layer alive even if a concurrent flush or
-    /// compaction removes it from `EngineInner` while we’re iterating.
+    /// The `SuperVersion` keeps each layer alive even if a concurrent flush
+    /// or compaction removes it from `EngineInner` while we’re iterating.
+    ///
+    /// With a non-zero `min_timestamp`, SSTable data blocks written
+    /// entirely before it are skipped; the caller must then drop versions
+    /// older than `min_timestamp` from the result. Records with an LSN
+    /// above `max_lsn` are left out of every layer.
     fn raw_scan(
         &self,
         start_key: &[u8],
         end_key: &[u8],
+        min_timestamp: u64,
+        max_lsn: u64,
+        opts: &ReadOptions,
     ) -> Result<utils::MergeIterator<'static>, EngineError> {
-        // --- snapshot under read lock (fast) ---
-        let (active_records, frozen_snapshot, sstable_snapshot) = {
-            let inner = self.read_lock()?;
-
-            // Active memtable — collect (mutable & in RAM, cheap).
-            let active_records: Vec<_> = inner.active.scan(start_key, end_key)?.collect();
-
-            // Clone Arc handles (pointer bumps, no data copy).
-            let frozen: Vec<Arc<FrozenMemtable>> = inner.frozen.iter().map(Arc::clone).collect();
-            let sstables: Vec<Arc<SSTable>> = inner.sstables.iter().map(Arc::clone).collect();
+        let end_key = opts.scan_end(start_key, end_key);
+        let end_key = end_key.as_ref();
+        let version = self.version.load();
+
+        let memtables = Self::scan_memtables(&version, start_key, end_key, max_lsn)?;
+        // Tables with nothing in the range, or written entirely after
+        // `max_lsn`, are skipped.
+        let sstables: Vec<&Arc<SSTable>> = Self::overlapping_sstables(&version, start_key, end_key)
+            .filter(|sst| sst.min_lsn() <= max_lsn)
+            .collect();
+        if self
+            .fanout
+            .as_ref()
+            .is_some_and(|guard| guard.check(sstables.len()))
+        {
+            self.reads.record_fanout_exceeded();
+        }
+        if !sstables.is_empty() {
+            let now = self.clock.now_nanos();
+            for sst in &sstables {
+                sst.access.record_scan(now);
+            }
+        }
+        Self::merge_layers(
+            memtables,
+            sstables.into_iter(),
+            start_key,
+            end_key,
+            min_timestamp,
+            max_lsn,
+            opts,
+        )
+    }
 
-            (active_records, frozen, sstables)
+    /// Returns the SSTables of `version` that
+    /// [may overlap](SSTable::may_overlap) `[start_key, end_key)`.
+    ///
+    /// A range outside the [`SstKeySpan`] of all tables — typically one
+    /// past the newest keys — yields none without checking each table.
+    fn overlapping_sstables<'a>(
+        version: &'a SuperVersion,
+        start_key: &'a [u8],
+        end_key: &'a [u8],
+    ) -> impl Iterator<Item = &'a Arc<SSTable>> {
+        let tables: &[Arc<SSTable>] = if version.sstable_span.may_overlap(start_key, end_key) {
+            &version.sstables
+        } else {
+            &[]
         };
-        // --- lock released ---
-
-        let mut iters: Vec<Box<dyn Iterator<Item = Record>>> = Vec::new();
+        tables
+            .iter()
+            .filter(|sst| sst.may_overlap(start_key, end_key))
+    }
 
-        // Active memtable (already collected).
-        iters.push(Box::new(active_records.into_iter()));
+    /// Collects the records of the active and then each frozen memtable
+    /// in `[start_key, end_key)` with an LSN at or below `max_lsn` — they
+    /// are mutable or in RAM, so collecting is cheap.
+    ///
+    /// Frozen memtables whose keys and range tombstones all lie outside
+    /// the range are not scanned, and memtables with nothing in the range
+    /// are left out, so they add no source to the merge.
+    fn scan_memtables(
+        version: &SuperVersion,
+        start_key: &[u8],
+        end_key: &[u8],
+        max_lsn: u64,
+    ) -> Result<Vec<Vec<Record>>, EngineError> {
+        let mut memtables = Vec::with_capacity(1 + version.frozen.len());
+        let active: Vec<Record> = version
+            .active
+            .scan_at(start_key, end_key, max_lsn)?
+            .collect();
+        if !active.is_empty() {
+            memtables.push(active);
+        }
+        for fm in &version.frozen {
+            if !fm.may_overlap(start_key, end_key) {
+                continue;
+            }
+            let records: Vec<Record> = fm.scan_at(start_key, end_key, max_lsn)?.collect();
+            if !records.is_empty() {
+                memtables.push(records);
+            }
+        }
+        Ok(memtables)
+    }
 
-        // Frozen memtables — scan produces owned Records (in-RAM data).
-        for fm in &frozen_snapshot {
-            let records: Vec<_> = fm.scan(start_key, end_key)?.collect();
-            iters.push(Box::new(records.into_iter()));
+    /// Merges collected memtable records with lazy scans of `sstables`,
+    /// hiding the SSTable records with an LSN above `max_lsn`.
+    fn merge_layers<'a>(
+        memtables: Vec<Vec<Record>>,
+        sstables: impl Iterator<Item = &'a Arc<SSTable>>,
+        start_key: &[u8],
+        end_key: &[u8],
+        min_timestamp: u64,
+        max_lsn: u64,
+        opts: &ReadOptions,
+    ) -> Result<utils::MergeIterator<'static>, EngineError> {
+        let mut iters: Vec<Box<dyn RecordSource + Send>> = Vec::new();
+        for records in memtables {
+            iters.push(Box::new(Unseekable(records.into_iter())));
         }
 
-        // SSTables — lazy, block-at-a-time via mmap.
-        for sst in &sstable_snapshot {
-            let scan = SSTable::scan_owned(sst, start_key, end_key)?;
-            iters.push(Box::new(scan));
+        // SSTables — lazy, block-at-a-time via mmap. Under a range
+        // tombstone newer than the whole table they seek past the deleted
+        // range instead of decoding it, and they are opened only once the
+        // merge reaches their first key.
+        for sst in sstables {
+            let scan = SSTable::scan_owned_lazy(
+                sst,
+                start_key,
+                end_key,
+                opts.verify_checksums,
+                min_timestamp,
+            )?;
+            if sst.max_lsn() > max_lsn {
+                iters.push(Box::new(LsnBound::new(scan, max_lsn)));
+            } else {
+                iters.push(Box::new(scan));
+            }
         }
 
-        Ok(utils::MergeIterator::new(iters))
+        Ok(utils::MergeIterator::skipping_covered(iters))
     }
 
     /// Returns a snapshot of engine statistics.
@@ -600,15 +2451,294 @@ impl Engine {
 
         let sst_sizes: Vec<u64> = inner.sstables.iter().map(|s| s.file_size()).collect();
         let total_sst_size_bytes: u64 = sst_sizes.iter().sum();
+        let mmap_bytes = inner
+            .sstables
+            .iter()
+            .filter(|s| s.is_mapped())
+            .map(|s| s.file_size())
+            .sum();
 
         Ok(EngineStats {
             frozen_count: inner.frozen.len(),
             sstables_count: inner.sstables.len(),
             total_sst_size_bytes,
             sst_sizes,
+            mmap_bytes,
         })
     }
 
+    /// Returns the number of live SSTables without taking the engine lock.
+    pub fn sstable_count(&self) -> usize {
+        self.version.load().sstables.len()
+    }
+
+    /// Reads the SSTable data blocks that may hold keys in
+    /// `[start_key, end_key)` into the OS page cache.
+    ///
+    /// Works on the current superversion without taking the engine lock.
+    /// Returns the number of bytes read.
+    pub fn warm_up(&self, start_key: &[u8], end_key: &[u8]) -> Result<u64, EngineError> {
+        if start_key >= end_key {
+            return Ok(0);
+        }
+        let inner = self.version.load();
+        let mut bytes = 0;
+        for sst in &inner.sstables {
+            if sst.max_key() < start_key || sst.min_key() >= end_key {
+                continue;
+            }
+            bytes += sst.warm_up(start_key, Some(end_key))?;
+        }
+        Ok(bytes)
+    }
+
+    /// Reads every data block of the `count` most recently created
+    /// SSTables into the OS page cache.
+    ///
+    /// Returns the number of bytes read.
+    pub fn warm_up_recent(&self, count: usize) -> Result<u64, EngineError> {
+        let inner = self.version.load();
+        let mut recent: Vec<&Arc<SSTable>> = inner.sstables.iter().collect();
+        recent.sort_by_key(|sst| std::cmp::Reverse(sst.id()));
+        let mut bytes = 0;
+        for sst in recent.into_iter().take(count) {
+            bytes += sst.warm_up(&[], None)?;
+        }
+        Ok(bytes)
+    }
+
+    /// Deletes the SSTable files removed from the live set that no reader
+    /// holds any more, without waiting for
+    /// [`EngineConfig::obsolete_file_grace_seconds`].
+    pub fn purge_obsolete_files(&self) -> Result<PurgeReport, EngineError> {
+        let mut inner = self.write_lock()?;
+        Ok(inner.purge_obsolete(true))
+    }
+
+    /// Deletes the removed SSTable files no reader holds whose grace
+    /// period has passed. Run by the file GC timer.
+    pub fn collect_obsolete_files(&self) -> Result<PurgeReport, EngineError> {
+        let mut inner = self.write_lock()?;
+        Ok(inner.purge_obsolete(false))
+    }
+
+    /// Lists the WAL segments in the memtable directory, oldest first,
+    /// with their role: active, frozen (awaiting flush), or flushed.
+    pub fn wal_files(&self) -> Result<Vec<WalFile>, EngineError> {
+        let inner = self.read_lock()?;
+        let frozen_wal_ids: Vec<u64> = inner.frozen.iter().map(|f| f.wal_seq()).collect();
+        Ok(retention::list_wal_files(
+            &inner.data_dir.join(MEMTABLE_DIR),
+            inner.active.wal_seq(),
+            &frozen_wal_ids,
+        )?)
+    }
+
+    /// Returns metadata for every live SSTable, newest-first.
+    ///
+    /// Identity and path come from the manifest; key bounds, LSN bounds,
+    /// record counts, and creation time come from each table's
+    /// properties block.
+    pub fn sstable_metadata(&self) -> Result<Vec<SSTableMetadata>, EngineError> {
+        let inner = self.read_lock()?;
+        let entries = inner.manifest.get_sstables()?;
+
+        inner
+            .sstables
+            .iter()
+            .map(|sst| {
+                let path = entries
+                    .iter()
+                    .find(|e| e.id == sst.id())
+                    .map(|e| e.path.clone())
+                    .ok_or_else(|| {
+                        EngineError::Internal(format!(
+                            "SSTable {} is loaded but missing from manifest",
+                            sst.id()
+                        ))
+                    })?;
+                let tombstones = sst.tombstone_count() + sst.range_tombstone_count();
+
+                Ok(SSTableMetadata {
+                    id: sst.id(),
+                    path,
+                    file_size: sst.file_size(),
+                    min_key: sst.min_key().to_vec(),
+                    max_key: sst.max_key().to_vec(),
+                    min_lsn: sst.min_lsn(),
+                    max_lsn: sst.max_lsn(),
+                    record_count: sst.record_count(),
+                    tombstone_count: sst.tombstone_count(),
+                    range_tombstone_count: sst.range_tombstone_count(),
+                    tombstone_ratio: tombstones as f64 / sst.record_count().max(1) as f64,
+                    creation_timestamp: sst.creation_timestamp(),
+                    level: None,
+                    reads: sst.reads.snapshot(),
+                    hits: sst.access.hits(),
+                    scans: sst.access.scans(),
+                    last_access: sst.access.last_access(),
+                    mapped: sst.is_mapped(),
+                })
+            })
+            .collect()
+    }
+
+    /// Describes the SSTables, size-tier buckets, key-range overlaps, and
+    /// current compaction candidates of this engine, as partition
+    /// `partition` of a [`DebugReport`].
+    pub fn debug_report(&self, partition: usize) -> Result<PartitionReport, EngineError> {
+        let inner = self.read_lock()?;
+        Ok(PartitionReport::build(
+            partition,
+            inner.frozen.len(),
+            &inner.sstables,
+            &inner.config,
+        ))
+    }
+
+    /// Returns the size-tiered compaction thresholds currently in effect.
+    pub fn compaction_thresholds(&self) -> Result<CompactionThresholds, EngineError> {
+        let inner = self.read_lock()?;
+        Ok(CompactionThresholds {
+            min_threshold: inner.config.min_threshold,
+            max_threshold: inner.config.max_threshold,
+            tombstone_ratio_threshold: inner.config.tombstone_ratio_threshold,
+        })
+    }
+
+    /// Runs one step of the adaptive compaction controller.
+    ///
+    /// Closes `window` at the current read and write counters and, if the
+    /// amplification measured over it missed a target in `tuning`, applies
+    /// the adjusted thresholds. Returns the change, reported as partition
+    /// `partition`, or `None` if the thresholds were kept.
+    pub(crate) fn tune_compaction(
+        &self,
+        partition: usize,
+        window: &mut TuningWindow,
+        tuning: &AdaptiveCompaction,
+    ) -> Result<Option<CompactionTuning>, EngineError> {
+        let reads = self.read_stats();
+        let mut inner = self.write_lock()?;
+        let sample = tuning::TuningSample {
+            gets: reads.gets,
+            bloom_checks: reads.bloom_checks,
+            bytes_flushed: inner.bytes_flushed,
+            bytes_compacted: inner.bytes_compacted,
+        };
+        let current = CompactionThresholds {
+            min_threshold: inner.config.min_threshold,
+            max_threshold: inner.config.max_threshold,
+            tombstone_ratio_threshold: inner.config.tombstone_ratio_threshold,
+        };
+
+        let change = window.advance(partition, sample, current, tuning);
+        if let Some(change) = &change {
+            inner.config.min_threshold = change.current.min_threshold;
+            inner.config.max_threshold = change.current.max_threshold;
+            inner.config.tombstone_ratio_threshold = change.current.tombstone_ratio_threshold;
+            tracing::info!(
+                partition,
+                reason = ?change.reason,
+                read_amplification = ?change.read_amplification,
+                write_amplification = ?change.write_amplification,
+                min_threshold = change.current.min_threshold,
+                max_threshold = change.current.max_threshold,
+                tombstone_ratio_threshold = change.current.tombstone_ratio_threshold,
+                "compaction thresholds tuned"
+            );
+        }
+        Ok(change)
+    }
+
+    /// Creates an independently openable copy of the engine at `target`.
+    ///
+    /// Frozen memtables are flushed first, so the copy consists of the
+    /// live SSTables plus the active WAL. SSTables are immutable and are
+    /// hard-linked into `target` (falling back to a byte copy when linking
+    /// fails, e.g. across filesystems, after which the copy is verified
+    /// against its whole-file digest, if it has one); the active WAL is
+    /// copied, and a fresh manifest describing both is written and
+    /// checkpointed.
+    ///
+    /// `target` must not exist or must be an empty directory. Writes are
+    /// blocked for the duration of the clone; reads are not.
+    pub fn clone_to(&self, target: impl AsRef<Path>) -> Result<(), EngineError> {
+        let target = target.as_ref();
+        if target.exists() && fs::read_dir(target)?.next().is_some() {
+            return Err(EngineError::Io(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("clone target {} is not empty", target.display()),
+            )));
+        }
+
+        let mut inner = self.write_lock()?;
+
+        // 1. Reduce the state to SSTables + active WAL.
+        Self::freeze_unlogged_inner(&mut inner)?;
+        while !inner.frozen.is_empty() {
+            Self::flush_frozen_to_sstable_inner(&mut inner)?;
+        }
+
+        let manifest_dir = target.join(MANIFEST_DIR);
+        let memtable_dir = target.join(MEMTABLE_DIR);
+        let sstable_dir = layout::sstable_dir(target, &inner.config);
+        fs::create_dir_all(&manifest_dir)?;
+        fs::create_dir_all(&memtable_dir)?;
+        fs::create_dir_all(&sstable_dir)?;
+
+        // 2. Link every live SSTable under its existing ID.
+        let mut entries = Vec::with_capacity(inner.sstables.len());
+        for entry in inner.manifest.get_sstables()? {
+            let path = layout::sstable_path(target, &inner.config, entry.id);
+            if fs::hard_link(&entry.path, &path).is_err() {
+                fs::copy(&entry.path, &path)?;
+                sstable::verify_file_digest_at(&path)?;
+            }
+            entries.push(ManifestSstEntry { id: entry.id, path });
+        }
+
+        // 3. Copy the active WAL — it is still being appended to here.
+        let active_wal_id = inner.active.wal_seq();
+        let wal_name = format!("{:06}.log", active_wal_id);
+        fs::copy(
+            inner.data_dir.join(MEMTABLE_DIR).join(&wal_name),
+            memtable_dir.join(&wal_name),
+        )?;
+
+        // 4. Describe the copy in a fresh manifest.
+        let manifest = Manifest::open(&manifest_dir)?.with_root(target);
+        manifest.set_active_wal(active_wal_id)?;
+        for entry in entries {
+            manifest.add_sstable(entry)?;
+        }
+        manifest.advance_sst_id(inner.manifest.peek_next_sst_id()?)?;
+        let last_lsn = inner
+            .active
+            .max_lsn()
+            .unwrap_or(0)
+            .max(inner.manifest.get_last_lsn()?);
+        manifest.update_lsn(last_lsn)?;
+        manifest.checkpoint()?;
+
+        for dir_path in [
+            manifest_dir.as_path(),
+            memtable_dir.as_path(),
+            sstable_dir.as_path(),
+            &target.join(SSTABLE_DIR),
+            target,
+        ] {
+            durability::sync_dir(dir_path)?;
+        }
+
+        tracing::info!(
+            path = %target.display(),
+            sstables = inner.sstables.len(),
+            "engine cloned"
+        );
+        Ok(())
+    }
+
     /// Freeze the current active memtable and swap in a fresh one.
     /// The old memtable is pushed to the front of `inner.frozen`.
     fn freeze_active(inner: &mut EngineInner) -> Result<(), EngineError> {
@@ -620,26 +2750,53 @@ impl Engine {
             .data_dir
             .join(MEMTABLE_DIR)
             .join(format!("{:06}.log", new_active_wal_id));
-        let new_active = Memtable::new(wal_path, None, inner.config.write_buffer_size)?;
+        let new_active = Memtable::new(wal_path, None, inner.write_buffer_size())?
+            .with_clock(inner.timestamps.clone())
+            .with_wal_metrics(Arc::clone(&inner.wal_metrics));
+        inner.wal_metrics.record_rotation();
+        new_active.set_value_checksums(inner.config.value_checksums)?;
+        fail_point!(WAL_ROTATE_BEFORE_MANIFEST);
+        if inner.bulk_load {
+            // The frozen WAL was written without per-record fsyncs.
+            inner.active.sync_wal()?;
+            new_active.set_wal_sync(false);
+        }
 
-        let old_active = std::mem::replace(&mut inner.active, new_active);
-        let frozen = old_active.frozen()?;
+        let old_active = std::mem::replace(&mut inner.active, Arc::new(new_active));
+        let frozen = FrozenMemtable::from_shared(old_active);
         // Insert at beginning to maintain sorted order (newest first)
         inner.frozen.insert(0, Arc::new(frozen));
 
         // Ensure LSN continuity
         inner.active.inject_max_lsn(current_max_lsn);
+        inner.publish();
 
         inner.manifest.add_frozen_wal(frozen_wal_id)?;
         inner.manifest.set_active_wal(new_active_wal_id)?;
 
+        // Backpressure: a writer that outpaces the background flush pays
+        // for it, rather than letting frozen memtables pile up in memory.
+        let limit = inner.config.max_frozen_memtables;
+        while limit > 0 && inner.frozen.len() > limit {
+            tracing::debug!(
+                frozen = inner.frozen.len(),
+                limit,
+                "frozen memtable limit reached, flushing inline"
+            );
+            Self::flush_frozen_to_sstable_inner(inner)?;
+        }
+
         Ok(())
     }
 
     /// Flush the oldest frozen memtable to a new SSTable.
     ///
-    /// Returns `Ok(true)` if a frozen memtable was flushed, `Ok(false)` if
-    /// there were no frozen memtables to flush.
+    /// When [`EngineConfig::max_memtables_per_flush`] is greater than one,
+    /// up to that many of the oldest frozen memtables are merged into the
+    /// same SSTable.
+    ///
+    /// Returns `Ok(true)` if at least one frozen memtable was flushed,
+    /// `Ok(false)` if there were no frozen memtables to flush.
     pub fn flush_oldest_frozen(&self) -> Result<bool, EngineError> {
         let mut inner = self.write_lock()?;
 
@@ -650,6 +2807,76 @@ impl Engine {
         Ok(true)
     }
 
+    /// Freezes the active memtable if it has outlived
+    /// [`EngineConfig::max_memtable_age`] or its WAL has outgrown
+    /// [`EngineConfig::max_total_wal_size`].
+    ///
+    /// Writes check these triggers themselves; this lets a timer enforce
+    /// the age limit when no writes arrive.
+    ///
+    /// Returns `Ok(true)` if a freeze occurred (caller should schedule a flush).
+    pub fn freeze_if_due(&self) -> Result<bool, EngineError> {
+        let mut inner = self.write_lock()?;
+        Self::freeze_if_due_inner(&mut inner)
+    }
+
+    /// Checks the manifest against the files currently on disk.
+    ///
+    /// Verifies that every frozen WAL exists and predates the active WAL,
+    /// that SSTable ids are unique and were allocated by the manifest, and
+    /// that every SSTable exists, parses, and has sane LSN and key bounds.
+    /// The same check runs in [`open`](Self::open), which fails with
+    /// [`EngineError::Inconsistent`] on any issue; this method returns the
+    /// report instead.
+    pub fn check_consistency(&self) -> Result<ConsistencyReport, EngineError> {
+        let inner = self.read_lock()?;
+        let mut report = ConsistencyReport::default();
+        report.check_frozen_wals(
+            &inner.data_dir.join(MEMTABLE_DIR),
+            inner.manifest.get_active_wal()?,
+            &inner.manifest.get_frozen_wals()?,
+        );
+        let sstables = inner.manifest.get_sstables()?;
+        report.check_sstable_ids(&sstables, inner.manifest.peek_next_sst_id()?);
+        for entry in &sstables {
+            report.open_sstable(entry, inner.config.sstable_open_checks);
+        }
+        Ok(report)
+    }
+
+    /// Enters or leaves bulk-load mode.
+    ///
+    /// While enabled, memtables hold [`BULK_LOAD_WRITE_BUFFER_FACTOR`]
+    /// times [`EngineConfig::write_buffer_size`], WAL appends are not
+    /// fsynced individually (each WAL is synced once, when its memtable
+    /// is frozen), and the age and WAL-size flush triggers are off.
+    /// Leaving the mode freezes the oversized active memtable, so the
+    /// caller can flush it, and restores the configured behaviour.
+    /// Compaction is not affected here; the `Db` layer stops scheduling
+    /// it.
+    pub fn set_bulk_load(&self, enabled: bool) -> Result<(), EngineError> {
+        let mut inner = self.write_lock()?;
+        if inner.bulk_load && !enabled && inner.active.oldest_timestamp()?.is_some() {
+            Self::freeze_active(&mut inner)?;
+            let max_lsn = inner.active.max_lsn().unwrap_or(0);
+            inner.manifest.update_lsn(max_lsn)?;
+        }
+        inner.bulk_load = enabled;
+        inner
+            .active
+            .set_write_buffer_size(inner.write_buffer_size())?;
+        inner.active.set_wal_sync(!enabled);
+        if !enabled {
+            inner.active.sync_wal()?;
+        }
+        Ok(())
+    }
+
+    /// Returns `true` while bulk-load mode is enabled.
+    pub fn is_bulk_loading(&self) -> Result<bool, EngineError> {
+        Ok(self.read_lock()?.bulk_load)
+    }
+
     /// Flush **all** frozen memtables to SSTables.
     ///
     /// Returns the number of frozen memtables that were flushed.
@@ -659,77 +2886,442 @@ impl Engine {
 
         let mut count = 0usize;
         while !inner.frozen.is_empty() {
-            Self::flush_frozen_to_sstable_inner(&mut inner)?;
-            count += 1;
+            count += Self::flush_frozen_to_sstable_inner(&mut inner)?;
         }
         Ok(count)
     }
 
-    /// Allocates the next unique SSTable ID from the manifest's monotonic counter.
-    fn next_sstable_id(inner: &mut EngineInner) -> Result<u64, EngineError> {
-        Ok(inner.manifest.allocate_sst_id()?)
+    /// Returns the highest LSN assigned so far: every committed write has
+    /// an LSN at or below it, and every later write a higher one. LSNs
+    /// skipped on open, up to the manifest's last reservation, count as
+    /// assigned.
+    pub fn latest_lsn(&self) -> Result<u64, EngineError> {
+        Ok(self.read_lock()?.active.max_lsn().unwrap_or(0))
     }
 
-    fn flush_frozen_to_sstable_inner(inner: &mut EngineInner) -> Result<(), EngineError> {
-        if inner.frozen.is_empty() {
-            return Ok(());
+    /// Pins the versions visible at [`latest_lsn`](Self::latest_lsn):
+    /// until the returned pin is dropped, flushes and compactions keep
+    /// the version of every key a read at that LSN would return.
+    #[allow(dead_code)]
+    pub fn pin_versions(&self) -> Result<VersionPin, EngineError> {
+        let inner = self.read_lock()?;
+        Ok(inner.version_pins.pin(inner.active.max_lsn().unwrap_or(0)))
+    }
+
+    /// Returns the replication version: the next SSTable id the manifest
+    /// will allocate.
+    pub fn replication_version(&self) -> Result<u64, EngineError> {
+        Ok(self.read_lock()?.manifest.peek_next_sst_id()?)
+    }
+
+    /// Flushes every write and returns the SSTables created since
+    /// `since_version` plus the ids of all live SSTables.
+    ///
+    /// A non-empty active memtable is frozen first, so the delta covers
+    /// every write acknowledged before the call. Writes are blocked while
+    /// the SSTables are read; reads are not.
+    ///
+    /// A compaction building its output holds an SSTable id below the
+    /// version but commits only later, so the export first waits for it.
+    pub fn export_delta(&self, since_version: u64) -> Result<ReplicationDelta, EngineError> {
+        let (inner, version) = loop {
+            let mut inner = self.write_lock()?;
+
+            // 1. Move every write into an SSTable.
+            if inner.active.oldest_timestamp()?.is_some() {
+                Self::freeze_active(&mut inner)?;
+                let max_lsn = inner.active.max_lsn().unwrap_or(0);
+                inner.manifest.update_lsn(max_lsn)?;
+            }
+            while !inner.frozen.is_empty() {
+                Self::flush_frozen_to_sstable_inner(&mut inner)?;
+            }
+
+            // 2. Wait for running compactions that allocated an output id.
+            // Read the version first: ids allocated after it are above it.
+            let version = inner.manifest.peek_next_sst_id()?;
+            let inputs: Vec<u64> = inner
+                .manifest
+                .pending_compactions()?
+                .iter()
+                .filter(|pending| !pending.outputs.is_empty())
+                .flat_map(|pending| pending.inputs.iter().map(|e| e.id))
+                .collect();
+            let busy = self.compactions.claimed(&inputs);
+            if busy.is_empty() {
+                break (inner, version);
+            }
+            drop(inner);
+            self.compactions.wait_released(&busy);
+        };
+
+        // 3. Ship the SSTables allocated at or after `since_version`.
+        let entries = inner.manifest.get_sstables()?;
+        let mut sstables = Vec::new();
+        for entry in entries.iter().filter(|e| e.id >= since_version) {
+            sstables.push(DeltaSSTable {
+                id: entry.id,
+                data: fs::read(&entry.path)?,
+            });
         }
 
-        // Take the oldest frozen memtable (last in the newest-first vec).
-        // We flush oldest first so that `insert(0, sstable)` keeps the
-        // sstables list in newest-first order after a batch flush.
-        let frozen = inner
-            .frozen
-            .pop()
-            .ok_or_else(|| EngineError::Internal("frozen list became empty unexpectedly".into()))?;
-        let frozen_wal_id = frozen.wal_seq();
+        let last_lsn = inner
+            .sstables
+            .iter()
+            .map(|s| s.max_lsn())
+            .fold(inner.manifest.get_last_lsn()?, u64::max);
+        Ok(ReplicationDelta {
+            since_version,
+            version,
+            last_lsn,
+            sstables,
+            live_ids: entries.iter().map(|e| e.id).collect(),
+        })
+    }
 
-        // Get all records from the frozen memtable and split into
-        // point entries and range tombstones via Record::into_entry().
-        let mut point_entries = Vec::new();
-        let mut range_tombstones = Vec::new();
+    /// Installs a delta exported by a leader's
+    /// [`export_delta`](Self::export_delta).
+    ///
+    /// The engine must be at `delta.since_version` and hold no unflushed
+    /// writes of its own. Shipped SSTables carrying a whole-file digest
+    /// are verified against it before anything is written, and are then
+    /// written under their leader ids, SSTables missing from `delta.live_ids` are dropped, and the
+    /// manifest records both in one entry, so a crash leaves either the
+    /// old or the new SSTable set. Future LSNs continue after
+    /// `delta.last_lsn`.
+    ///
+    /// # Errors
+    /// [`EngineError::Replication`] if the versions differ, the engine has
+    /// local writes, a live SSTable is neither shipped nor held, or a
+    /// shipped SSTable fails its digest.
+    pub fn apply_delta(&self, delta: &ReplicationDelta) -> Result<(), EngineError> {
+        let mut inner = self.write_lock()?;
 
-        for record in frozen.iter_for_flush()? {
-            match record.into_entry() {
-                RecordEntry::Point(pe) => point_entries.push(pe),
-                RecordEntry::Range(rt) => range_tombstones.push(rt),
+        // 1. The delta must continue exactly where this engine stands.
+        let version = inner.manifest.peek_next_sst_id()?;
+        if delta.since_version != version {
+            return Err(EngineError::Replication(format!(
+                "delta starts at version {} but engine is at version {version}",
+                delta.since_version
+            )));
+        }
+        if !inner.frozen.is_empty() || inner.active.oldest_timestamp()?.is_some() {
+            return Err(EngineError::Replication(
+                "engine holds unflushed local writes".into(),
+            ));
+        }
+        for id in &delta.live_ids {
+            let shipped = delta.sstables.iter().any(|sst| sst.id == *id);
+            if !shipped && !inner.sstables.iter().any(|sst| sst.id() == *id) {
+                return Err(EngineError::Replication(format!(
+                    "live SSTable {id} is neither shipped nor held"
+                )));
+            }
+        }
+        for shipped in &delta.sstables {
+            if let Err(e) = sstable::verify_file_digest(&shipped.data) {
+                return Err(EngineError::Replication(format!(
+                    "shipped SSTable {} fails verification: {e}",
+                    shipped.id
+                )));
             }
         }
 
-        // Generate unique SSTable ID and path
-        let sstable_id = Self::next_sstable_id(inner)?;
-        let sstable_path = inner
-            .data_dir
-            .join(SSTABLE_DIR)
-            .join(format!("{:06}.sst", sstable_id));
+        // 2. Write and open the shipped SSTables.
+        let sstable_dir = layout::sstable_dir(&inner.data_dir, &inner.config);
+        let mut added = Vec::with_capacity(delta.sstables.len());
+        let mut opened = Vec::with_capacity(delta.sstables.len());
+        for shipped in &delta.sstables {
+            let path = layout::sstable_path(&inner.data_dir, &inner.config, shipped.id);
+            let mut file = fs::File::create(&path)?;
+            file.write_all(&shipped.data)?;
+            durability::sync_file(&file)?;
+
+            let mut sstable = SSTable::open(&path)?;
+            sstable.set_id(shipped.id);
+            inner.attach_budget(&sstable);
+            inner.timestamps.observe(sstable.max_timestamp());
+            opened.push(Arc::new(sstable));
+            added.push(ManifestSstEntry {
+                id: shipped.id,
+                path,
+            });
+        }
+        durability::sync_dir(&sstable_dir)?;
 
-        // Build the SSTable
-        let point_count = point_entries.len();
-        let range_count = range_tombstones.len();
+        // 3. Swap the SSTable set in the manifest.
+        let removed: Vec<u64> = inner
+            .sstables
+            .iter()
+            .map(|sst| sst.id())
+            .filter(|id| !delta.live_ids.contains(id))
+            .collect();
+        let removed_paths: Vec<(u64, PathBuf)> = inner
+            .manifest
+            .get_sstables()?
+            .into_iter()
+            .filter(|entry| removed.contains(&entry.id))
+            .map(|entry| (entry.id, entry.path))
+            .collect();
+        // Advance the id counter first, so the manifest never lists an id
+        // it has not allocated.
+        inner.manifest.advance_sst_id(delta.version)?;
+        Self::reserve_lsns(&mut inner, delta.last_lsn)?;
+        inner.manifest.apply_compaction(added, removed.clone())?;
+        inner.manifest.update_lsn(delta.last_lsn)?;
+        inner.manifest.checkpoint()?;
 
-        sstable::SstWriter::new(&sstable_path).build(
-            point_entries.into_iter(),
-            point_count,
-            range_tombstones.into_iter(),
-            range_count,
-        )?;
+        // 4. Publish the new set and drop the replaced files.
+        let (replaced, kept) = std::mem::take(&mut inner.sstables)
+            .into_iter()
+            .partition(|sst| removed.contains(&sst.id()));
+        inner.sstables = kept;
+        inner.sstables.extend(opened);
+        inner
+            .sstables
+            .sort_by_key(|s| std::cmp::Reverse((s.max_lsn(), s.id())));
+        if inner.active.max_lsn().unwrap_or(0) < delta.last_lsn {
+            inner.active.inject_max_lsn(delta.last_lsn);
+        }
+        inner.publish();
+        if let Some(cache) = &self.row_cache {
+            cache.advance_epoch();
+        }
+
+        inner.retire_sstables(replaced, removed_paths);
+        Ok(())
+    }
+
+    /// Writes `pairs`, sorted by strictly ascending key, straight into a
+    /// new SSTable instead of through the WAL and memtable.
+    ///
+    /// Pending writes are flushed first, so the new SSTable is the newest
+    /// layer: its pairs get LSNs above every existing write and replace
+    /// older versions of their keys. The SSTable is durable once the
+    /// manifest lists it. Writes are blocked while it is built; reads are
+    /// not.
+    ///
+    /// Returns `Ok(false)` without writing if `pairs` is empty.
+    ///
+    /// # Errors
+    /// [`EngineError::InvalidArgument`] if the keys are not in strictly
+    /// ascending order; nothing is written.
+    pub fn ingest_sorted(&self, pairs: &[(Vec<u8>, Vec<u8>)]) -> Result<bool, EngineError> {
+        Self::check_sorted(pairs)?;
+        if pairs.is_empty() {
+            return Ok(false);
+        }
+        let mut inner = self.write_lock()?;
+
+        // 1. Move every write into an SSTable.
+        if inner.active.oldest_timestamp()?.is_some() {
+            Self::freeze_active(&mut inner)?;
+            let max_lsn = inner.active.max_lsn().unwrap_or(0);
+            inner.manifest.update_lsn(max_lsn)?;
+        }
+        while !inner.frozen.is_empty() {
+            Self::flush_frozen_to_sstable_inner(&mut inner)?;
+        }
+
+        // 2. Build the SSTable with LSNs above everything stored.
+        let base_lsn = inner
+            .sstables
+            .iter()
+            .map(|s| s.max_lsn())
+            .fold(inner.manifest.get_last_lsn()?, u64::max)
+            .max(inner.active.max_lsn().unwrap_or(0));
+        Self::reserve_lsns(&mut inner, base_lsn + pairs.len() as u64)?;
+        let timestamp = inner.timestamps.now_nanos();
+        let sstable_id = Self::next_sstable_id(&mut inner)?;
+        let sstable_path = layout::sstable_path(&inner.data_dir, &inner.config, sstable_id);
+        let entries = pairs
+            .iter()
+            .zip(base_lsn + 1..)
+            .map(|((key, value), lsn)| PointEntry::new(key.clone(), value.clone(), lsn, timestamp));
+        let built = sstable::SstWriter::new(&sstable_path)
+            .value_checksums(inner.config.value_checksums)
+            .file_digest(inner.config.sstable_file_digest)
+            .tenant_prefixes(&inner.config.tenant_prefixes)
+            .clock(Arc::clone(&inner.config.clock))
+            .build(entries, pairs.len(), std::iter::empty(), 0)?;
+        inner.sstables_built += built;
+
+        // 3. Record it and the LSNs it used, then publish it.
+        let mut sstable = SSTable::open(&sstable_path)?;
+        sstable.set_id(sstable_id);
+        inner.attach_budget(&sstable);
+        let last_lsn = base_lsn + pairs.len() as u64;
+        inner.manifest.add_sstable(ManifestSstEntry {
+            id: sstable_id,
+            path: sstable_path,
+        })?;
+        inner.manifest.update_lsn(last_lsn)?;
+
+        inner.sstables.insert(0, Arc::new(sstable));
+        inner.active.inject_max_lsn(last_lsn);
+        inner.publish();
+        if let Some(cache) = &self.row_cache {
+            cache.advance_epoch();
+        }
+        inner.record_prefix_bytes_written();
+        tracing::info!(
+            id = sstable_id,
+            pairs = pairs.len(),
+            "ingested sorted pairs as SSTable"
+        );
+        Ok(true)
+    }
+
+    /// Fails with [`EngineError::InvalidArgument`] unless the keys of
+    /// `pairs` are in strictly ascending order.
+    pub(crate) fn check_sorted(pairs: &[(Vec<u8>, Vec<u8>)]) -> Result<(), EngineError> {
+        match pairs.windows(2).position(|w| w[0].0 >= w[1].0) {
+            Some(i) => Err(EngineError::InvalidArgument(format!(
+                "ingested keys not in strictly ascending order at pair {}",
+                i + 1
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Allocates the next unique SSTable ID from the manifest's monotonic counter.
+    fn next_sstable_id(inner: &mut EngineInner) -> Result<u64, EngineError> {
+        Ok(inner.manifest.allocate_sst_id()?)
+    }
+
+    fn flush_frozen_to_sstable_inner(inner: &mut EngineInner) -> Result<usize, EngineError> {
+        if inner.frozen.is_empty() {
+            return Ok(0);
+        }
+        let _slow = SlowOpTimer::start("flush", inner.config.slow_op_threshold);
+
+        // Take the oldest frozen memtables (the tail of the newest-first
+        // vec). We flush oldest first so that `insert(0, sstable)` keeps
+        // the sstables list in newest-first order after a batch flush.
+        let batch_len = inner
+            .config
+            .max_memtables_per_flush
+            .clamp(1, inner.frozen.len());
+        let split_at = inner.frozen.len() - batch_len;
+
+        // The batch stays in `frozen` until its SSTable is written, so a
+        // memtable whose values fail their checksums keeps serving reads
+        // and its WAL.
+        let horizon = inner.version_pins.horizon();
+        let (sstable_id, sstable_path) = if batch_len == 1 {
+            // A single memtable already yields sorted entries with the
+            // versions the horizon calls for. They are written straight
+            // from its tree, borrowed; its lock is shared with readers.
+            let frozen = Arc::clone(&inner.frozen[split_at]);
+            let view = frozen.flush_view(horizon)?;
+            let mut point_entries: Vec<PointEntryRef<'_>> = Vec::with_capacity(view.point_count());
+            point_entries.extend(view.points());
+            let range_tombstones = utils::coalesce_range_tombstones_over(
+                view.range_tombstones(),
+                Some(&point_entries),
+            );
+            Self::write_flushed_sstable(inner, point_entries, range_tombstones)?
+        } else {
+            // Get all records from the batch and split into point entries
+            // and range tombstones via Record::into_entry().
+            let mut point_entries = Vec::new();
+            let mut range_tombstones = Vec::new();
+            for frozen in &inner.frozen[split_at..] {
+                for record in frozen.iter_for_flush(horizon)? {
+                    match record.into_entry() {
+                        RecordEntry::Point(pe) => point_entries.push(pe),
+                        RecordEntry::Range(rt) => range_tombstones.push(rt),
+                    }
+                }
+            }
+
+            // Restore (key ASC, LSN DESC) order and drop the versions the
+            // horizon no longer needs across the batch. The batch is newest
+            // first and the sort stable, so a duplicate LSN goes to the
+            // later timestamp, then the newer memtable (`VersionRank`).
+            tracing::debug!(
+                memtables = batch_len,
+                "merging frozen memtables into one SSTable"
+            );
+            point_entries.sort_by(|a, b| {
+                a.key
+                    .cmp(&b.key)
+                    .then(b.lsn.cmp(&a.lsn))
+                    .then(b.timestamp.cmp(&a.timestamp))
+            });
+            let mut versions = VersionGc::new(horizon);
+            point_entries.retain(|entry| versions.keep(&entry.key, entry.lsn));
+            range_tombstones.sort_by(|a, b| a.start.cmp(&b.start).then(b.lsn.cmp(&a.lsn)));
+
+            // Every other version of these keys is older or newer than the
+            // whole batch, so tombstones can also be bridged across
+            // differing LSNs.
+            let range_tombstones =
+                utils::coalesce_range_tombstones(range_tombstones, Some(&point_entries));
+            Self::write_flushed_sstable(inner, point_entries, range_tombstones)?
+        };
+
+        let batch = inner.frozen.split_off(split_at);
+        let frozen_wal_ids: Vec<u64> = batch.iter().map(|f| f.wal_seq()).collect();
 
         // Load the newly created SSTable
         let mut sstable = SSTable::open(&sstable_path)?;
         sstable.set_id(sstable_id);
+        inner.attach_budget(&sstable);
+        if inner.config.page_cache_hints {
+            sstable.advise_will_need();
+        }
         // Insert at beginning to maintain sorted order (newest first)
         inner.sstables.insert(0, Arc::new(sstable));
+        inner.publish();
 
         // Update manifest
+        fail_point!(FLUSH_BEFORE_MANIFEST);
         inner.manifest.add_sstable(ManifestSstEntry {
             id: sstable_id,
             path: sstable_path,
         })?;
 
-        // Remove the frozen WAL from manifest
-        inner.manifest.remove_frozen_wal(frozen_wal_id)?;
+        // Release the flushed frozen WALs in one manifest record, then
+        // delete them from disk
+        fail_point!(FLUSH_BEFORE_WAL_RELEASE);
+        inner.manifest.remove_frozen_wals(&frozen_wal_ids)?;
+        inner.prune_flushed_wals();
 
-        Ok(())
+        let bytes = inner.sstables[0].file_size();
+        inner.bytes_flushed += bytes;
+        inner.record_prefix_bytes_written();
+        let span = tracing::Span::current();
+        span.record("memtables", batch.len());
+        span.record("bytes", bytes);
+
+        Ok(batch.len())
+    }
+
+    /// Writes the entries of a flush to an SSTable under a new id, and
+    /// returns the id and path.
+    fn write_flushed_sstable<P: AsPointEntry>(
+        inner: &mut EngineInner,
+        point_entries: Vec<P>,
+        range_tombstones: Vec<RangeTombstone>,
+    ) -> Result<(u64, PathBuf), EngineError> {
+        let sstable_id = Self::next_sstable_id(inner)?;
+        let sstable_path = layout::sstable_path(&inner.data_dir, &inner.config, sstable_id);
+        let point_count = point_entries.len();
+        let range_count = range_tombstones.len();
+        let built = sstable::SstWriter::new(&sstable_path)
+            .value_checksums(inner.config.value_checksums)
+            .file_digest(inner.config.sstable_file_digest)
+            .tenant_prefixes(&inner.config.tenant_prefixes)
+            .clock(Arc::clone(&inner.config.clock))
+            .build(
+                point_entries.into_iter(),
+                point_count,
+                range_tombstones.into_iter(),
+                range_count,
+            )?;
+        inner.sstables_built += built;
+        Ok((sstable_id, sstable_path))
     }
 
     // --------------------------------------------------------------------------------------------
@@ -738,42 +3330,120 @@ impl Engine {
 
     /// Execute a compaction strategy, applying the result to the engine.
     ///
+    /// The strategy selects its inputs and builds its output from a
+    /// snapshot of the SSTables, without the engine lock; only the commit
+    /// takes it. If an SSTable of the snapshot was removed meanwhile —
+    /// by another compaction or a clear — the output is thrown away and
+    /// the compaction counted as skipped.
+    ///
     /// Returns `Ok(true)` if compaction was performed, `Ok(false)` if
-    /// the strategy decided there was nothing to do.
+    /// the strategy decided there was nothing to do or was skipped.
     fn run_compaction(
         &self,
         strategy: &dyn crate::compaction::CompactionStrategy,
     ) -> Result<bool, EngineError> {
-        let mut inner = self.write_lock()?;
+        Ok(match self.try_compaction(strategy)? {
+            CompactionRun::Done(applied) => applied,
+            CompactionRun::Conflict(_) | CompactionRun::Replaced => false,
+        })
+    }
 
-        let inner = &mut *inner; // reborrow to split fields
-        let sst_count = inner.sstables.len();
-        let data_dir_str = inner.data_dir.to_string_lossy();
-        let result = strategy
-            .compact(
-                &inner.sstables,
-                &mut inner.manifest,
-                &data_dir_str,
-                &inner.config,
-            )
-            .map_err(|e| EngineError::Internal(format!("Compaction failed: {e}")))?;
+    /// Runs `strategy` like [`run_compaction`](Self::run_compaction), again
+    /// each time it is skipped, so `Ok(false)` only means there was nothing
+    /// to do. For compactions a caller asked for, which a background
+    /// compaction running meanwhile must not turn into a no-op.
+    ///
+    /// A run skipped over claimed inputs waits for their claims to be
+    /// released before it runs again.
+    fn run_compaction_to_completion(
+        &self,
+        strategy: &dyn crate::compaction::CompactionStrategy,
+    ) -> Result<bool, EngineError> {
+        loop {
+            match self.try_compaction(strategy)? {
+                CompactionRun::Done(applied) => return Ok(applied),
+                CompactionRun::Conflict(busy) => self.compactions.wait_released(&busy),
+                CompactionRun::Replaced => {}
+            }
+        }
+    }
 
-        match result {
-            None => {
-                tracing::debug!(sst_count, "compaction strategy found nothing to do");
-                Ok(false)
-            }
-            Some(cr) => {
-                tracing::info!(
-                    sst_count_before = sst_count,
-                    removed = cr.removed_ids.len(),
-                    new_id = ?cr.new_sst_id,
-                    "compaction applied"
+    /// One run of [`run_compaction`](Self::run_compaction).
+    fn try_compaction(
+        &self,
+        strategy: &dyn crate::compaction::CompactionStrategy,
+    ) -> Result<CompactionRun, EngineError> {
+        let (sstables, manifest, data_dir, config, horizon) = {
+            let inner = self.read_lock()?;
+            (
+                inner.sstables.clone(),
+                Arc::clone(&inner.manifest),
+                inner.data_dir.clone(),
+                inner.config.clone(),
+                inner.version_pins.horizon(),
+            )
+        };
+        let _slow = SlowOpTimer::start("compaction", config.slow_op_threshold);
+
+        let sst_count = sstables.len();
+        let result = match strategy.compact(
+            &sstables,
+            &manifest,
+            &data_dir.to_string_lossy(),
+            &config,
+            horizon,
+        ) {
+            Ok(result) => result,
+            Err(CompactionError::Manifest(ManifestError::CompactionConflict(busy))) => {
+                tracing::debug!(
+                    ?busy,
+                    "compaction skipped, inputs claimed or replaced by another"
                 );
-                Self::apply_compaction_result(inner, cr)?;
-                Ok(true)
+                return Ok(CompactionRun::Conflict(busy));
             }
+            Err(e) => return Err(EngineError::Internal(format!("Compaction failed: {e}"))),
+        };
+        let Some(cr) = result else {
+            tracing::debug!(sst_count, "compaction strategy found nothing to do");
+            return Ok(CompactionRun::Done(false));
+        };
+
+        let mut inner = self.write_lock()?;
+        let inner = &mut *inner; // reborrow to split fields
+        let gone: Vec<u64> = sstables
+            .iter()
+            .map(|sst| sst.id())
+            .filter(|id| !inner.sstables.iter().any(|sst| sst.id() == *id))
+            .collect();
+        if !gone.is_empty() {
+            tracing::debug!(?gone, "compaction skipped, SSTables replaced while it ran");
+            self.compactions.skip();
+            crate::compaction::abort_compaction(&manifest, &cr)
+                .map_err(|e| EngineError::Internal(format!("Compaction failed: {e}")))?;
+            return Ok(CompactionRun::Replaced);
+        }
+        crate::compaction::commit_compaction(&manifest, &cr)
+            .map_err(|e| EngineError::Internal(format!("Compaction failed: {e}")))?;
+
+        tracing::info!(
+            sst_count_before = sst_count,
+            removed = cr.removed_ids.len(),
+            new_id = ?cr.new_sst_id,
+            "compaction applied"
+        );
+        let span = tracing::Span::current();
+        span.record("sstables_in", cr.removed_ids.len());
+        slow_op::record_tables_probed(cr.removed_ids.len());
+        let new_id = cr.new_sst_id;
+        Self::apply_compaction_result(inner, cr)?;
+        if let Some(cache) = &self.row_cache {
+            cache.advance_epoch();
         }
+        if let Some(id) = new_id {
+            let new_sst = inner.sstables.iter().find(|s| s.id() == id);
+            span.record("bytes", new_sst.map_or(0, |s| s.file_size()));
+        }
+        Ok(CompactionRun::Done(true))
     }
 
     /// Acquires the compaction strategy from the configuration and runs it.
@@ -814,7 +3484,14 @@ impl Engine {
     /// Returns `Ok(true)` if compaction was performed, `Ok(false)` if no
     /// SSTable was eligible.
     pub fn tombstone_compact(&self) -> Result<bool, EngineError> {
-        self.compact_with(crate::compaction::CompactionStrategyType::tombstone)
+        let strategy = {
+            let inner = self.read_lock()?;
+            inner
+                .config
+                .compaction_strategy
+                .tombstone_tracked(Arc::clone(&self.tombstone_gc))
+        };
+        self.run_compaction(strategy.as_ref())
     }
 
     /// Runs **major compaction** — merges all SSTables into one.
@@ -823,9 +3500,238 @@ impl Engine {
     /// drops all spent tombstones from the output.
     ///
     /// Returns `Ok(true)` if compaction was performed, `Ok(false)` if
-    /// there are fewer than 2 SSTables.
+    /// there are fewer than 2 SSTables. A run skipped because another
+    /// compaction claimed or replaced its inputs is run again.
     pub fn major_compact(&self) -> Result<bool, EngineError> {
-        self.compact_with(crate::compaction::CompactionStrategyType::major)
+        let strategy = {
+            let inner = self.read_lock()?;
+            inner.config.compaction_strategy.major()
+        };
+        self.run_compaction_to_completion(strategy.as_ref())
+    }
+
+    /// Runs **major compaction** like [`major_compact`](Self::major_compact),
+    /// counting the records it merges in `progress`.
+    pub(crate) fn major_compact_tracked(
+        &self,
+        progress: Arc<crate::compaction::MajorProgress>,
+    ) -> Result<bool, EngineError> {
+        let strategy = {
+            let inner = self.read_lock()?;
+            inner.config.compaction_strategy.major_tracked(progress)
+        };
+        self.run_compaction_to_completion(strategy.as_ref())
+    }
+
+    /// Runs one round of **periodic compaction**.
+    ///
+    /// Rewrites the oldest SSTable whose age exceeds
+    /// `periodic_compaction_seconds`, dropping provably-unnecessary
+    /// tombstones along the way.
+    ///
+    /// Returns `Ok(true)` if compaction was performed, `Ok(false)` if
+    /// periodic compaction is disabled or no SSTable is old enough.
+    pub fn periodic_compact(&self) -> Result<bool, EngineError> {
+        let strategy = {
+            let inner = self.read_lock()?;
+            inner
+                .config
+                .compaction_strategy
+                .periodic_tracked(Arc::clone(&self.tombstone_gc))
+        };
+        self.run_compaction(strategy.as_ref())
+    }
+
+    /// Records a compaction hint for the SSTables overlapping
+    /// `[start_key, end_key)`; returns how many there are.
+    ///
+    /// No hint is recorded if none overlaps. Data still in memtables is
+    /// not covered.
+    pub fn suggest_compact_range(
+        &self,
+        start_key: &[u8],
+        end_key: &[u8],
+        priority: CompactionPriority,
+    ) -> Result<usize, EngineError> {
+        let mut inner = self.write_lock()?;
+        let sstables: Vec<u64> = inner
+            .sstables
+            .iter()
+            .filter(|sst| sst.may_overlap(start_key, end_key))
+            .map(|sst| sst.id())
+            .collect();
+        let count = sstables.len();
+        if count > 0 {
+            tracing::debug!(?priority, ?sstables, "compaction hint recorded");
+            inner.hints.push(CompactionHint {
+                priority,
+                sstables,
+                range: Some((start_key.to_vec(), end_key.to_vec())),
+            });
+        }
+        Ok(count)
+    }
+
+    /// Hints the newest SSTables for compaction if the 99th percentile of
+    /// SSTables probed by recent lookups exceeds
+    /// `read_depth_compaction_threshold`: enough of them that merging
+    /// brings the percentile back to the threshold, at least two. Lookups
+    /// are counted afresh after a hint.
+    ///
+    /// Returns `Ok(true)` if a hint was recorded; run
+    /// [`hinted_compact`](Self::hinted_compact) to act on it.
+    pub fn hint_read_depth_compaction(&self) -> Result<bool, EngineError> {
+        let Some(depths) = &self.probe_depths else {
+            return Ok(false);
+        };
+        let Some(p99) = depths.percentile(0.99) else {
+            return Ok(false);
+        };
+        let mut inner = self.write_lock()?;
+        let threshold = inner.config.read_depth_compaction_threshold;
+        if p99 <= threshold || inner.sstables.len() < 2 {
+            return Ok(false);
+        }
+        depths.reset();
+
+        // Lookups probe SSTables newest first, so merging the newest ones
+        // shortens every deep lookup.
+        let count = (p99 - threshold + 1).clamp(2, inner.sstables.len());
+        let sstables: Vec<u64> = inner.sstables[..count].iter().map(|sst| sst.id()).collect();
+        tracing::info!(
+            p99,
+            threshold,
+            ?sstables,
+            "read depth exceeded, compaction hinted"
+        );
+        inner.hints.push(CompactionHint {
+            priority: CompactionPriority::High,
+            sstables,
+            range: None,
+        });
+        Ok(true)
+    }
+
+    /// Hints every SSTable in which a scan stepped over a run of at least
+    /// `deletion_run_compaction_threshold` point tombstones since the last
+    /// check, together with the older SSTables its key range overlaps:
+    /// merging them drops the deleted keys along with their tombstones.
+    ///
+    /// Returns `Ok(true)` if a hint was recorded; run
+    /// [`hinted_compact`](Self::hinted_compact) to act on it.
+    pub fn hint_deletion_compaction(&self) -> Result<bool, EngineError> {
+        let mut inner = self.write_lock()?;
+        let inner = &mut *inner;
+        let threshold = inner.config.deletion_run_compaction_threshold as u64;
+        let mut hinted = false;
+        // SSTables are ordered newest first.
+        for (i, sst) in inner.sstables.iter().enumerate() {
+            let run = sst.tombstone_runs.take();
+            if threshold == 0 || run < threshold {
+                continue;
+            }
+            let mut past_max = sst.max_key().to_vec();
+            past_max.push(0);
+            let sstables: Vec<u64> = std::iter::once(sst.id())
+                .chain(
+                    inner.sstables[i + 1..]
+                        .iter()
+                        .filter(|older| older.may_overlap(sst.min_key(), &past_max))
+                        .map(|older| older.id()),
+                )
+                .collect();
+            if inner.hints.iter().any(|hint| hint.sstables == sstables) {
+                continue;
+            }
+            tracing::info!(
+                run,
+                threshold,
+                ?sstables,
+                "tombstone run exceeded, compaction hinted"
+            );
+            hinted = true;
+            inner.hints.push(CompactionHint {
+                priority: CompactionPriority::High,
+                sstables,
+                range: None,
+            });
+        }
+        Ok(hinted)
+    }
+
+    /// Runs one round of **hinted compaction** for the most urgent hint
+    /// recorded by [`suggest_compact_range`](Self::suggest_compact_range):
+    /// merges its SSTables into one, or — once a single one is left —
+    /// rewrites it, dropping provably-unnecessary tombstones.
+    ///
+    /// Hints whose range overlaps a range held in `locks` are skipped.
+    ///
+    /// Returns `Ok(true)` if compaction was performed, `Ok(false)` if no
+    /// unlocked hint has anything left to compact.
+    pub fn hinted_compact(&self, locks: &RangeLocks) -> Result<bool, EngineError> {
+        let (ids, strategy) = {
+            let mut inner = self.write_lock()?;
+            let inner = &mut *inner;
+            let Some(ids) = compaction_hints::next_hint(&mut inner.hints, &inner.sstables, locks)
+            else {
+                return Ok(false);
+            };
+            let strategy = inner
+                .config
+                .compaction_strategy
+                .hinted(ids.clone(), Arc::clone(&self.tombstone_gc));
+            (ids, strategy)
+        };
+        let compacted = self.run_compaction(strategy.as_ref())?;
+        if !compacted {
+            // The SSTables changed since the hint was selected; retire it
+            // rather than select it again.
+            self.write_lock()?.hints.retain(|hint| hint.sstables != ids);
+        }
+        Ok(compacted)
+    }
+
+    /// Returns the point-tombstone checks made by tombstone and periodic
+    /// compaction since open.
+    pub fn tombstone_gc_stats(&self) -> TombstoneGcStats {
+        self.tombstone_gc.snapshot()
+    }
+
+    /// Returns the compactions running now and those skipped since open
+    /// because another one had claimed their inputs.
+    pub fn compaction_job_stats(&self) -> CompactionJobStats {
+        self.compactions.stats()
+    }
+
+    /// Returns the usage of each of `config.tenant_prefixes`, in order,
+    /// summed from the prefix tallies of the live SSTables.
+    pub fn prefix_usage(&self) -> Result<Vec<PrefixTotals>, EngineError> {
+        let inner = self.read_lock()?;
+        let prefixes = &inner.config.tenant_prefixes;
+        let mut totals: Vec<PrefixTotals> = inner
+            .prefix_bytes_flushed
+            .iter()
+            .map(|&bytes_written| PrefixTotals {
+                bytes_written,
+                ..PrefixTotals::default()
+            })
+            .collect();
+        for sst in &inner.sstables {
+            let usage = sst.prefix_usage().unwrap_or_default();
+            for (prefix, total) in prefixes.iter().zip(totals.iter_mut()) {
+                match usage.iter().find(|usage| &usage.prefix == prefix) {
+                    Some(usage) => {
+                        total.live_bytes += usage.bytes;
+                        total.live_entries += usage.entries;
+                    }
+                    None if tenant::range_may_hold_prefix(sst.min_key(), sst.max_key(), prefix) => {
+                        total.untracked_tables += 1;
+                    }
+                    None => {}
+                }
+            }
+        }
+        Ok(totals)
     }
 
     /// Applies a `CompactionResult` to the in-memory engine state.
@@ -837,15 +3743,32 @@ impl Engine {
         inner: &mut EngineInner,
         cr: crate::compaction::CompactionResult,
     ) -> Result<(), EngineError> {
-        // Remove consumed SSTables.
-        inner
-            .sstables
-            .retain(|sst| !cr.removed_ids.contains(&sst.id()));
+        // Remove consumed SSTables; their files go once the new set is
+        // published.
+        let page_cache_hints = inner.config.page_cache_hints;
+        let mut removed = Vec::with_capacity(cr.removed_ids.len());
+        inner.sstables.retain(|sst| {
+            if !cr.removed_ids.contains(&sst.id()) {
+                return true;
+            }
+            if page_cache_hints {
+                sst.retire();
+            }
+            removed.push(Arc::clone(sst));
+            false
+        });
+        compaction_hints::record_compaction(&mut inner.hints, &cr.removed_ids, cr.new_sst_id);
 
         // Load and insert new SSTable if one was produced.
         if let Some(ref path) = cr.new_sst_path {
             let mut new_sst = SSTable::open(path)?;
             new_sst.set_id(cr.new_sst_id.unwrap_or(0));
+            inner.attach_budget(&new_sst);
+            if page_cache_hints {
+                new_sst.advise_will_need();
+            }
+            inner.bytes_compacted += new_sst.file_size();
+            inner.sstables_built += cr.built;
             inner.sstables.push(Arc::new(new_sst));
         }
 
@@ -853,7 +3776,9 @@ impl Engine {
         // invariant used by get().
         inner
             .sstables
-            .sort_by_key(|s| std::cmp::Reverse(s.max_lsn()));
+            .sort_by_key(|s| std::cmp::Reverse((s.max_lsn(), s.id())));
+        inner.publish();
+        inner.retire_sstables(removed, cr.removed_paths);
 
         Ok(())
     }
@@ -0,0 +1,105 @@
+//! Zero-copy value handles returned by [`Engine::get_pinned`](super::Engine::get_pinned).
+//!
+//! A [`PinnedSlice`] either borrows a value straight out of an SSTable's
+//! memory map — keeping that map alive through an `Arc` — or owns a copy
+//! of a value that was found in a memtable or read with `pread`.
+
+use std::fmt;
+use std::ops::Deref;
+
+use crate::sstable::mapping::FileBytes;
+
+/// A value returned by a pinned lookup.
+///
+/// Dereferences to `[u8]`. Values read from SSTables are not copied: the
+/// slice points into the SSTable's mmap, and the handle keeps the SSTable
+/// mapped for as long as it lives — even if a compaction has since removed
+/// the SSTable from the engine, or [`max_mmap_bytes`](crate::DbConfig::max_mmap_bytes)
+/// demoted it to positioned reads. Values found in a memtable, or in an
+/// SSTable that is not mapped, are owned copies.
+///
+/// Long-lived handles therefore delay the release of compacted SSTables;
+/// call [`into_vec`](Self::into_vec) to detach a value you intend to keep.
+#[derive(Clone)]
+pub struct PinnedSlice {
+    inner: Inner,
+}
+
+#[derive(Clone)]
+enum Inner {
+    /// Copied out of a memtable.
+    Owned(Vec<u8>),
+
+    /// Borrowed from an SSTable mmap.
+    Mapped(FileBytes),
+}
+
+impl PinnedSlice {
+    pub(crate) fn owned(value: Vec<u8>) -> Self {
+        Self {
+            inner: Inner::Owned(value),
+        }
+    }
+
+    /// Wraps a value read from an SSTable; pinned only if it was mapped.
+    pub(crate) fn from_sstable(value: FileBytes) -> Self {
+        let inner = match value {
+            FileBytes::Owned(value) => Inner::Owned(value),
+            mapped => Inner::Mapped(mapped),
+        };
+        Self { inner }
+    }
+
+    /// Returns `true` if the value is borrowed from an SSTable mmap rather
+    /// than owned.
+    pub fn is_pinned(&self) -> bool {
+        matches!(self.inner, Inner::Mapped(_))
+    }
+
+    /// Converts the handle into an owned `Vec<u8>`, releasing any pinned
+    /// SSTable. Copies only if the value was pinned.
+    pub fn into_vec(self) -> Vec<u8> {
+        match self.inner {
+            Inner::Owned(value) => value,
+            Inner::Mapped(bytes) => bytes.into_vec(),
+        }
+    }
+}
+
+impl Deref for PinnedSlice {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match &self.inner {
+            Inner::Owned(value) => value,
+            Inner::Mapped(bytes) => bytes,
+        }
+    }
+}
+
+impl AsRef<[u8]> for PinnedSlice {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl PartialEq<[u8]> for PinnedSlice {
+    fn eq(&self, other: &[u8]) -> bool {
+        **self == *other
+    }
+}
+
+impl PartialEq<Vec<u8>> for PinnedSlice {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        **self == **other
+    }
+}
+
+impl fmt::Debug for PinnedSlice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PinnedSlice")
+            .field("len", &self.len())
+            .field("pinned", &self.is_pinned())
+            .finish()
+    }
+}
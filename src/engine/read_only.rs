@@ -0,0 +1,219 @@
+//! Read-only view of an engine directory written by another process.
+//!
+//! A [`ReadOnlyEngine`] never writes to its directory. It reads the
+//! manifest with [`Manifest::read_view`], opens the SSTables it lists, and
+//! serves lookups and scans from them. Writes still in the writer's
+//! memtables become visible once the writer flushes them.
+//!
+//! [`refresh`](ReadOnlyEngine::refresh) re-reads the manifest and, when
+//! the SSTable set changed, opens the new tables and publishes the new
+//! set; tables present in both sets are not reopened. Reads that started
+//! on the old set keep its tables mapped, even after the writer deletes
+//! the files.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use super::read_stats::ReadCounters;
+use super::utils::{MergeIterator, RecordSource};
+use super::{Engine, EngineError, MANIFEST_DIR, ReadOptions, ReadStats, VisibilityFilter};
+use crate::clock::SystemClock;
+use crate::manifest::{Manifest, ManifestView};
+use crate::sstable::SSTable;
+
+/// Attempts at loading a consistent SSTable set before giving up.
+///
+/// A manifest read racing with a writer's checkpoint or compaction may
+/// list a table that was just deleted; the next read sees the new state.
+const LOAD_ATTEMPTS: usize = 5;
+
+/// Pause between two load attempts.
+const LOAD_RETRY_DELAY: Duration = Duration::from_millis(20);
+
+/// One published SSTable set.
+struct ReadOnlyVersion {
+    /// The manifest state the set was loaded from.
+    manifest: ManifestView,
+
+    /// Live SSTables, sorted by `max_lsn` descending.
+    sstables: Vec<Arc<SSTable>>,
+}
+
+/// Serves reads from the SSTables of an engine directory owned by
+/// another process.
+pub(crate) struct ReadOnlyEngine {
+    manifest_dir: PathBuf,
+    version: RwLock<Arc<ReadOnlyVersion>>,
+
+    /// Point-lookup counters across all SSTables, since open.
+    reads: ReadCounters,
+}
+
+impl ReadOnlyEngine {
+    /// Opens the engine directory at `path` without modifying it.
+    ///
+    /// # Errors
+    /// [`EngineError::Io`] with `NotFound` if `path` holds no manifest.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, EngineError> {
+        let manifest_dir = path.as_ref().join(MANIFEST_DIR);
+        if !manifest_dir.is_dir() {
+            return Err(EngineError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("no database at {}", path.as_ref().display()),
+            )));
+        }
+
+        let version = Self::load(&manifest_dir, None)?;
+        tracing::info!(
+            path = %path.as_ref().display(),
+            sstables = version.sstables.len(),
+            "read-only engine opened"
+        );
+        Ok(Self {
+            manifest_dir,
+            version: RwLock::new(Arc::new(version)),
+            reads: ReadCounters::default(),
+        })
+    }
+
+    /// Re-reads the manifest and publishes its SSTable set if it changed.
+    ///
+    /// Returns `Ok(true)` if a new set was published.
+    pub fn refresh(&self) -> Result<bool, EngineError> {
+        let current = self.current()?;
+        if Manifest::read_view(&self.manifest_dir)? == current.manifest {
+            return Ok(false);
+        }
+
+        let version = Self::load(&self.manifest_dir, Some(&current))?;
+        tracing::debug!(
+            sstables = version.sstables.len(),
+            next_sst_id = version.manifest.next_sst_id,
+            "read-only engine refreshed"
+        );
+        *self
+            .version
+            .write()
+            .map_err(|_| EngineError::Internal("RwLock poisoned".into()))? = Arc::new(version);
+        Ok(true)
+    }
+
+    /// Returns the writer's next SSTable id as of the last refresh; it
+    /// grows with every flush and compaction.
+    pub fn manifest_version(&self) -> Result<u64, EngineError> {
+        Ok(self.current()?.manifest.next_sst_id)
+    }
+
+    /// Looks up `key` in the published SSTable set.
+    pub fn get_opt(&self, key: &[u8], opts: &ReadOptions) -> Result<Option<Vec<u8>>, EngineError> {
+        self.reads.record_get();
+        let version = self.current()?;
+        let found = Engine::lookup_sstables(
+            &self.reads,
+            None,
+            &SystemClock,
+            &version.sstables,
+            None,
+            key,
+            opts,
+            None,
+            &mut 0,
+        )?;
+        Ok(found
+            .and_then(Engine::resolved)
+            .map(|found| found.value.into_vec()))
+    }
+
+    /// Scans `[start_key, end_key)` in the published SSTable set.
+    pub fn scan_opt(
+        &self,
+        start_key: &[u8],
+        end_key: &[u8],
+        opts: &ReadOptions,
+    ) -> Result<impl Iterator<Item = (Vec<u8>, Vec<u8>)> + Send + use<>, EngineError> {
+        let end_key = opts.scan_end(start_key, end_key);
+        let end_key = end_key.as_ref();
+        let version = self.current()?;
+        let mut sources: Vec<Box<dyn RecordSource + Send>> =
+            Vec::with_capacity(version.sstables.len());
+        for sst in version
+            .sstables
+            .iter()
+            .filter(|sst| sst.may_overlap(start_key, end_key))
+        {
+            let scan = SSTable::scan_owned_lazy(sst, start_key, end_key, opts.verify_checksums, 0)?;
+            sources.push(Box::new(scan));
+        }
+        Ok(VisibilityFilter::new(MergeIterator::skipping_covered(
+            sources,
+        )))
+    }
+
+    /// Returns the point-lookup counters accumulated since open.
+    pub fn read_stats(&self) -> ReadStats {
+        self.reads.snapshot()
+    }
+
+    /// Returns the number of SSTables in the published set.
+    #[cfg(test)]
+    pub fn sstable_count(&self) -> Result<usize, EngineError> {
+        Ok(self.current()?.sstables.len())
+    }
+
+    fn current(&self) -> Result<Arc<ReadOnlyVersion>, EngineError> {
+        let version = self
+            .version
+            .read()
+            .map_err(|_| EngineError::Internal("RwLock poisoned".into()))?;
+        Ok(Arc::clone(&version))
+    }
+
+    /// Reads the manifest and opens the SSTables it lists, reusing the
+    /// already open tables of `previous`. Retries while the manifest and
+    /// the files on disk disagree.
+    fn load(
+        manifest_dir: &Path,
+        previous: Option<&ReadOnlyVersion>,
+    ) -> Result<ReadOnlyVersion, EngineError> {
+        let mut attempt = 1;
+        loop {
+            let result = Manifest::read_view(manifest_dir)
+                .map_err(EngineError::from)
+                .and_then(|manifest| {
+                    let sstables = Self::open_sstables(&manifest, previous)?;
+                    Ok(ReadOnlyVersion { manifest, sstables })
+                });
+            match result {
+                Ok(version) => return Ok(version),
+                Err(e) if attempt < LOAD_ATTEMPTS => {
+                    tracing::debug!(attempt, %e, "read-only manifest load failed; retrying");
+                    attempt += 1;
+                    std::thread::sleep(LOAD_RETRY_DELAY);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn open_sstables(
+        manifest: &ManifestView,
+        previous: Option<&ReadOnlyVersion>,
+    ) -> Result<Vec<Arc<SSTable>>, EngineError> {
+        let mut sstables = Vec::with_capacity(manifest.sstables.len());
+        for entry in &manifest.sstables {
+            let open = previous.and_then(|p| p.sstables.iter().find(|sst| sst.id() == entry.id));
+            let sst = match open {
+                Some(sst) => Arc::clone(sst),
+                None => {
+                    let mut sst = SSTable::open(&entry.path)?;
+                    sst.set_id(entry.id);
+                    Arc::new(sst)
+                }
+            };
+            sstables.push(sst);
+        }
+        sstables.sort_by_key(|s| std::cmp::Reverse((s.max_lsn(), s.id())));
+        Ok(sstables)
+    }
+}
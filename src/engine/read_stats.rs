@@ -0,0 +1,386 @@
+//! Point-lookup counters for tuning bloom filters.
+//!
+//! Every [`Engine`](super::Engine) keeps one set of [`ReadCounters`] for
+//! all its lookups, and every loaded [`SSTable`](crate::sstable::SSTable)
+//! keeps one for the lookups that reached it. Both are snapshotted as
+//! [`ReadStats`].
+//!
+//! Only user point lookups are counted; compaction's internal reads are
+//! not.
+//!
+//! Each engine also keeps a [`ProbeDepthWindow`] of the number of
+//! SSTables its most recent lookups probed, for the read depth guardrail,
+//! and every SSTable keeps the [`TombstoneRuns`] its scans stepped over,
+//! for deletion-triggered compaction, and its [`SstAccess`] hits and last
+//! access time, for hot-table-first compaction. A [`FanoutGuard`]
+//! remembers the reads that would have consulted more SSTables than the
+//! fan-out limit.
+
+use std::ops::AddAssign;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+/// Lookups whose probe depth a [`ProbeDepthWindow`] remembers.
+const PROBE_DEPTH_WINDOW: usize = 1024;
+
+/// Lookups a [`ProbeDepthWindow`] needs before its percentile is trusted.
+const PROBE_DEPTH_MIN_SAMPLES: u64 = 100;
+
+/// Snapshot of point-lookup counters.
+///
+/// Returned per database by [`Db::read_stats`](crate::Db::read_stats) and
+/// per SSTable in [`SSTableMetadata::reads`](super::SSTableMetadata::reads).
+/// Counters start at zero when the database is opened, or when the SSTable
+/// is created by a flush or compaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReadStats {
+    /// Point lookups served. Per SSTable, the lookups that consulted it.
+    pub gets: u64,
+
+    /// Bloom filter checks, one per SSTable consulted by a lookup.
+    pub bloom_checks: u64,
+
+    /// Checks where the bloom filter ruled the key out, skipping the
+    /// data block read.
+    pub bloom_negatives: u64,
+
+    /// Checks where the bloom filter passed but the data block read for
+    /// the key did not contain it.
+    pub bloom_false_positives: u64,
+
+    /// Data blocks read.
+    pub block_reads: u64,
+
+    /// Lookups answered by the row cache without consulting any SSTable.
+    /// Always zero per SSTable.
+    pub row_cache_hits: u64,
+
+    /// Lookups and scans that would have consulted more SSTables than
+    /// [`DbConfig::read_fanout_limit`](crate::DbConfig::read_fanout_limit)
+    /// allows — a sign of compaction debt. Always zero per SSTable.
+    pub fanout_exceeded: u64,
+}
+
+impl ReadStats {
+    /// Fraction of lookups for absent keys that the bloom filter failed
+    /// to rule out: `false_positives / (negatives + false_positives)`.
+    ///
+    /// Returns `0.0` before any absent key has been looked up.
+    pub fn bloom_false_positive_rate(&self) -> f64 {
+        let absent = self.bloom_negatives + self.bloom_false_positives;
+        if absent == 0 {
+            return 0.0;
+        }
+        self.bloom_false_positives as f64 / absent as f64
+    }
+
+    /// Average number of data blocks read per lookup.
+    ///
+    /// Returns `0.0` before the first lookup.
+    pub fn block_reads_per_get(&self) -> f64 {
+        if self.gets == 0 {
+            return 0.0;
+        }
+        self.block_reads as f64 / self.gets as f64
+    }
+}
+
+impl AddAssign for ReadStats {
+    fn add_assign(&mut self, other: Self) {
+        self.gets += other.gets;
+        self.bloom_checks += other.bloom_checks;
+        self.bloom_negatives += other.bloom_negatives;
+        self.bloom_false_positives += other.bloom_false_positives;
+        self.block_reads += other.block_reads;
+        self.row_cache_hits += other.row_cache_hits;
+        self.fanout_exceeded += other.fanout_exceeded;
+    }
+}
+
+/// What one lookup did inside one SSTable.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SstProbe {
+    /// The table's bloom filter was consulted.
+    pub bloom_checked: bool,
+
+    /// The bloom filter ruled the key out.
+    pub bloom_negative: bool,
+
+    /// A data block was read.
+    pub block_read: bool,
+
+    /// The data block that was read held at least one version of the key.
+    pub key_in_block: bool,
+}
+
+impl SstProbe {
+    /// A probe ruled out by the bloom filter without calling `get_ref`,
+    /// as the parallel bloom pass does.
+    pub const BLOOM_NEGATIVE: Self = Self {
+        bloom_checked: true,
+        bloom_negative: true,
+        block_read: false,
+        key_in_block: false,
+    };
+
+    /// The bloom filter passed, but the block it led to lacked the key.
+    pub fn is_false_positive(&self) -> bool {
+        self.bloom_checked && !self.bloom_negative && self.block_read && !self.key_in_block
+    }
+}
+
+/// Live, lock-free counters behind a [`ReadStats`] snapshot.
+#[derive(Debug, Default)]
+pub(crate) struct ReadCounters {
+    gets: AtomicU64,
+    bloom_checks: AtomicU64,
+    bloom_negatives: AtomicU64,
+    bloom_false_positives: AtomicU64,
+    block_reads: AtomicU64,
+    row_cache_hits: AtomicU64,
+    fanout_exceeded: AtomicU64,
+}
+
+impl ReadCounters {
+    /// Counts one lookup.
+    pub fn record_get(&self) {
+        self.gets.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts one lookup answered by the row cache.
+    pub fn record_row_cache_hit(&self) {
+        self.row_cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts one lookup or scan past the fan-out limit.
+    pub fn record_fanout_exceeded(&self) {
+        self.fanout_exceeded.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Accumulates the outcome of one SSTable probe.
+    pub fn record_probe(&self, probe: &SstProbe) {
+        let bump = |counter: &AtomicU64, hit: bool| {
+            if hit {
+                counter.fetch_add(1, Ordering::Relaxed);
+            }
+        };
+        bump(&self.bloom_checks, probe.bloom_checked);
+        bump(&self.bloom_negatives, probe.bloom_negative);
+        bump(&self.bloom_false_positives, probe.is_false_positive());
+        bump(&self.block_reads, probe.block_read);
+    }
+
+    /// Returns the current values.
+    pub fn snapshot(&self) -> ReadStats {
+        ReadStats {
+            gets: self.gets.load(Ordering::Relaxed),
+            bloom_checks: self.bloom_checks.load(Ordering::Relaxed),
+            bloom_negatives: self.bloom_negatives.load(Ordering::Relaxed),
+            bloom_false_positives: self.bloom_false_positives.load(Ordering::Relaxed),
+            block_reads: self.block_reads.load(Ordering::Relaxed),
+            row_cache_hits: self.row_cache_hits.load(Ordering::Relaxed),
+            fanout_exceeded: self.fanout_exceeded.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Sliding window of the number of SSTables probed by the most recent
+/// lookups.
+///
+/// Recording is a pair of relaxed atomic operations; a concurrent
+/// [`percentile`](Self::percentile) may see a slot mid-update, which only
+/// shifts the estimate by one lookup.
+#[derive(Debug)]
+pub(crate) struct ProbeDepthWindow {
+    depths: Box<[AtomicU32]>,
+    /// Lookups recorded since open or the last [`reset`](Self::reset).
+    recorded: AtomicU64,
+}
+
+impl Default for ProbeDepthWindow {
+    fn default() -> Self {
+        Self {
+            depths: (0..PROBE_DEPTH_WINDOW).map(|_| AtomicU32::new(0)).collect(),
+            recorded: AtomicU64::new(0),
+        }
+    }
+}
+
+impl ProbeDepthWindow {
+    /// Records a lookup that probed `depth` SSTables, replacing the
+    /// oldest one once the window is full.
+    pub fn record(&self, depth: usize) {
+        let slot = self.recorded.fetch_add(1, Ordering::Relaxed) as usize % PROBE_DEPTH_WINDOW;
+        self.depths[slot].store(depth.min(u32::MAX as usize) as u32, Ordering::Relaxed);
+    }
+
+    /// Returns the `p`-th percentile (`0.0 < p <= 1.0`) of the depths in
+    /// the window, or `None` while it holds too few lookups to tell.
+    pub fn percentile(&self, p: f64) -> Option<usize> {
+        let recorded = self.recorded.load(Ordering::Relaxed);
+        if recorded < PROBE_DEPTH_MIN_SAMPLES {
+            return None;
+        }
+        let len = (recorded as usize).min(PROBE_DEPTH_WINDOW);
+        let mut depths: Vec<u32> = self.depths[..len]
+            .iter()
+            .map(|depth| depth.load(Ordering::Relaxed))
+            .collect();
+        depths.sort_unstable();
+        let rank = ((len as f64 * p).ceil() as usize).clamp(1, len);
+        Some(depths[rank - 1] as usize)
+    }
+
+    /// Forgets every recorded lookup.
+    pub fn reset(&self) {
+        self.recorded.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Longest run of consecutive point tombstones that scans stepped over in
+/// one SSTable since it was last [taken](Self::take).
+///
+/// A scan reports its longest run once, when it is dropped.
+#[derive(Debug, Default)]
+pub(crate) struct TombstoneRuns {
+    longest: AtomicU64,
+}
+
+impl TombstoneRuns {
+    /// Records a scan whose longest run was `run` tombstones.
+    pub fn record(&self, run: u64) {
+        if run > 0 {
+            self.longest.fetch_max(run, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the longest run recorded since the last call, and forgets
+    /// it.
+    pub fn take(&self) -> u64 {
+        self.longest.swap(0, Ordering::Relaxed)
+    }
+}
+
+/// Lookup hits, scans, and last access time of one SSTable, kept in
+/// memory only: they start afresh when the table is loaded.
+#[derive(Debug, Default)]
+pub(crate) struct SstAccess {
+    /// Lookups that found a version of their key in the table.
+    hits: AtomicU64,
+
+    /// Scans whose range the table overlapped.
+    scans: AtomicU64,
+
+    /// Time of the latest hit or scan (UNIX epoch nanos); `0` if none.
+    last_access: AtomicU64,
+}
+
+impl SstAccess {
+    /// Records a lookup that found its key in the table at `now_nanos`.
+    pub fn record_hit(&self, now_nanos: u64) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        self.last_access.fetch_max(now_nanos, Ordering::Relaxed);
+    }
+
+    /// Records a scan over the table started at `now_nanos`.
+    pub fn record_scan(&self, now_nanos: u64) {
+        self.scans.fetch_add(1, Ordering::Relaxed);
+        self.last_access.fetch_max(now_nanos, Ordering::Relaxed);
+    }
+
+    /// Returns the lookup hits recorded so far.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the scans recorded so far.
+    pub fn scans(&self) -> u64 {
+        self.scans.load(Ordering::Relaxed)
+    }
+
+    /// Returns the time of the latest hit or scan, or `None` if there was
+    /// none.
+    pub fn last_access(&self) -> Option<u64> {
+        Some(self.last_access.load(Ordering::Relaxed)).filter(|&nanos| nanos > 0)
+    }
+}
+
+/// Reads of one partition past the fan-out limit, delivered about once
+/// a second to
+/// [`EventListener::on_read_fanout_exceeded`](crate::EventListener::on_read_fanout_exceeded).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadFanout {
+    /// Partition the reads went to.
+    pub partition: usize,
+
+    /// Lookups and scans past the limit since the previous event.
+    pub reads: u64,
+
+    /// Most SSTables one of them would have consulted.
+    pub widest: usize,
+
+    /// [`DbConfig::read_fanout_limit`](crate::DbConfig::read_fanout_limit).
+    pub limit: usize,
+
+    /// SSTables in the partition when the event was raised.
+    pub sstables: usize,
+}
+
+/// Reads of one engine that would have consulted more than `limit`
+/// SSTables since the guard was last [taken](Self::take).
+#[derive(Debug)]
+pub(crate) struct FanoutGuard {
+    /// SSTables a read may consult before it counts.
+    limit: usize,
+
+    /// Whether a lookup past the limit consults only the SSTables whose
+    /// key bounds or range tombstones cover its key.
+    bounded_fallback: bool,
+
+    /// Reads past the limit since the last take.
+    exceeded: AtomicU64,
+
+    /// Most SSTables a read would have consulted since the last take.
+    widest: AtomicU64,
+}
+
+impl FanoutGuard {
+    /// Creates a guard counting reads past `limit` SSTables.
+    pub fn new(limit: usize, bounded_fallback: bool) -> Self {
+        Self {
+            limit,
+            bounded_fallback,
+            exceeded: AtomicU64::new(0),
+            widest: AtomicU64::new(0),
+        }
+    }
+
+    /// Checks a read that would consult `fanout` SSTables, recording it
+    /// if that is past the limit. Returns `true` if it is.
+    pub fn check(&self, fanout: usize) -> bool {
+        if fanout <= self.limit {
+            return false;
+        }
+        self.exceeded.fetch_add(1, Ordering::Relaxed);
+        self.widest.fetch_max(fanout as u64, Ordering::Relaxed);
+        true
+    }
+
+    /// Returns `true` if a lookup past the limit falls back to the
+    /// SSTables whose key bounds cover its key.
+    pub fn bounded_fallback(&self) -> bool {
+        self.bounded_fallback
+    }
+
+    /// Returns the fan-out limit.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Returns the reads past the limit and the widest fan-out recorded
+    /// since the last call, and forgets them.
+    pub fn take(&self) -> (u64, usize) {
+        let exceeded = self.exceeded.swap(0, Ordering::Relaxed);
+        let widest = self.widest.swap(0, Ordering::Relaxed);
+        (exceeded, widest as usize)
+    }
+}
@@ -0,0 +1,83 @@
+//! Report of what [`Engine::open`](super::Engine::open) recovered.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::memtable::WalReplayStats;
+
+/// Structured summary of crash recovery performed while opening.
+///
+/// Returned by [`Db::recovery_report`](crate::Db::recovery_report). With
+/// several partitions the counts are summed and `duration` covers all of
+/// them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecoveryReport {
+    /// WAL segments replayed: the active WAL plus every frozen WAL that
+    /// had not been flushed.
+    pub wal_segments_replayed: usize,
+
+    /// WAL records (puts, deletes, range deletes) replayed into memtables.
+    pub records_recovered: u64,
+
+    /// WAL segments whose damaged tail was cut off, as allowed by the
+    /// configured [`WalRecoveryMode`](crate::WalRecoveryMode).
+    pub wal_tails_dropped: usize,
+
+    /// Bytes of damaged WAL records dropped across all segments. A torn
+    /// tail usually holds a single partially written record.
+    pub wal_bytes_dropped: u64,
+
+    /// SSTable files found on disk but missing from the manifest, left
+    /// behind by an interrupted flush or compaction, and deleted.
+    pub orphan_files_removed: Vec<PathBuf>,
+
+    /// Compactions a crash interrupted after they committed, whose input
+    /// files were deleted.
+    pub compactions_rolled_forward: usize,
+
+    /// Compactions a crash interrupted before they committed, whose
+    /// output files were deleted; their inputs stay live.
+    pub compactions_rolled_back: usize,
+
+    /// SSTable paths the manifest recorded as absolute, or relative to
+    /// the working directory, as older versions did, and that were
+    /// rewritten relative to the database directory.
+    pub manifest_paths_rewritten: usize,
+
+    /// SSTables opened from the manifest.
+    pub sstables_opened: usize,
+
+    /// Highest LSN found in the manifest, WALs, and SSTables (the highest
+    /// of any partition).
+    pub recovered_lsn: u64,
+
+    /// Wall-clock time spent opening.
+    pub duration: Duration,
+}
+
+impl RecoveryReport {
+    /// Accounts for one replayed WAL segment.
+    pub(crate) fn add_wal(&mut self, replay: WalReplayStats) {
+        self.wal_segments_replayed += 1;
+        self.records_recovered += replay.records;
+        if replay.dropped_bytes > 0 {
+            self.wal_tails_dropped += 1;
+            self.wal_bytes_dropped += replay.dropped_bytes;
+        }
+    }
+
+    /// Folds in the report of another partition.
+    pub(crate) fn merge(&mut self, other: RecoveryReport) {
+        self.wal_segments_replayed += other.wal_segments_replayed;
+        self.records_recovered += other.records_recovered;
+        self.wal_tails_dropped += other.wal_tails_dropped;
+        self.wal_bytes_dropped += other.wal_bytes_dropped;
+        self.orphan_files_removed.extend(other.orphan_files_removed);
+        self.compactions_rolled_forward += other.compactions_rolled_forward;
+        self.compactions_rolled_back += other.compactions_rolled_back;
+        self.manifest_paths_rewritten += other.manifest_paths_rewritten;
+        self.sstables_opened += other.sstables_opened;
+        self.recovered_lsn = self.recovered_lsn.max(other.recovered_lsn);
+        self.duration += other.duration;
+    }
+}
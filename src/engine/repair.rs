@@ -0,0 +1,320 @@
+//! Offline repair of an engine directory.
+//!
+//! [`Engine::open`](super::Engine::open) refuses a directory whose
+//! manifest lists an SSTable that is missing or fails to open.
+//! [`repair`] brings such a directory back into an openable state:
+//!
+//! 1. The manifest is read for its WAL and SSTable lists. If it cannot be
+//!    opened, its snapshot is damaged, or it lists no SSTable while some
+//!    are on disk, every `.sst` file in `sstables/` and its
+//!    subdirectories is used instead: a file named after an id keeps it,
+//!    others — ULID-named ones — are given new ids.
+//! 2. SSTables that open and pass every data block checksum are kept.
+//!    Damaged ones are rebuilt from their readable blocks under a new id
+//!    (see [`tools::repair_sstable`](crate::tools::repair_sstable)), and
+//!    the originals are moved to `lost/`.
+//! 3. A fresh manifest describing the surviving files is written next to
+//!    the old one and swapped in; the old manifest is moved to `lost/`.
+//!
+//! When the manifest is rebuilt from files, the newest WAL becomes the
+//! active one, and an older WAL is replayed only if it holds writes newer
+//! than every surviving SSTable — older ones were flushed already.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::durability;
+use crate::manifest::{Manifest, ManifestSstEntry};
+use crate::sst::SstError;
+use crate::sstable::SSTable;
+use crate::tools;
+use crate::wal::Wal;
+
+use super::{EngineError, MANIFEST_DIR, MEMTABLE_DIR, Record, SSTABLE_DIR, layout};
+
+/// Directory, under the engine root, that damaged files are moved to.
+const LOST_DIR: &str = "lost";
+
+/// Summary of what [`Db::repair`](crate::Db::repair) did.
+///
+/// With several partitions the counts are summed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepairReport {
+    /// SSTables that passed every checksum and were kept unchanged.
+    pub sstables_kept: usize,
+
+    /// Damaged SSTables rebuilt from their readable blocks.
+    pub sstables_repaired: usize,
+
+    /// SSTables with nothing left to recover — moved to `lost/` — or
+    /// listed by the manifest but missing.
+    pub sstables_lost: Vec<PathBuf>,
+
+    /// Point entries recovered from damaged SSTables.
+    pub points_salvaged: u64,
+
+    /// Repaired SSTables whose range tombstones could not be read. Keys
+    /// those tombstones deleted in older SSTables may reappear.
+    pub range_tombstones_lost: usize,
+
+    /// Whether the manifest was unusable and the SSTable and WAL lists
+    /// were rebuilt from the files on disk.
+    pub manifest_rebuilt: bool,
+
+    /// WAL segments the new manifest lists: the active WAL plus frozen
+    /// WALs still to be flushed.
+    pub wal_segments: usize,
+}
+
+impl RepairReport {
+    /// Returns `true` if nothing was damaged.
+    pub fn is_clean(&self) -> bool {
+        self.sstables_repaired == 0 && self.sstables_lost.is_empty() && !self.manifest_rebuilt
+    }
+
+    /// Adds the counts of another partition's report.
+    pub(crate) fn merge(&mut self, other: RepairReport) {
+        self.sstables_kept += other.sstables_kept;
+        self.sstables_repaired += other.sstables_repaired;
+        self.sstables_lost.extend(other.sstables_lost);
+        self.points_salvaged += other.points_salvaged;
+        self.range_tombstones_lost += other.range_tombstones_lost;
+        self.manifest_rebuilt |= other.manifest_rebuilt;
+        self.wal_segments += other.wal_segments;
+    }
+}
+
+/// What a readable manifest records.
+struct ManifestState {
+    active_wal: u64,
+    frozen_wals: Vec<u64>,
+    sstables: Vec<ManifestSstEntry>,
+    last_lsn: u64,
+    next_sst_id: u64,
+}
+
+/// Repairs the engine directory `base`. The caller must hold its write
+/// lease.
+pub(crate) fn repair(base: &Path) -> Result<RepairReport, EngineError> {
+    let manifest_dir = base.join(MANIFEST_DIR);
+    let memtable_dir = base.join(MEMTABLE_DIR);
+    let sstable_dir = base.join(SSTABLE_DIR);
+    let lost_dir = base.join(LOST_DIR);
+    fs::create_dir_all(&memtable_dir)?;
+    fs::create_dir_all(&sstable_dir)?;
+
+    let mut report = RepairReport::default();
+    let sst_files = layout::sstable_files(&sstable_dir)?;
+    let wal_ids = list_ids(&memtable_dir, "log")?;
+
+    // 1. What the old manifest still tells.
+    let old = read_manifest(&manifest_dir, base)
+        .filter(|state| !state.sstables.is_empty() || sst_files.is_empty());
+    report.manifest_rebuilt = old.is_none();
+    let mut next_id = sst_files
+        .iter()
+        .filter_map(|path| file_id(path))
+        .max()
+        .map_or(0, |id| id + 1)
+        .max(old.as_ref().map_or(0, |state| state.next_sst_id));
+    let candidates = match &old {
+        Some(state) => state.sstables.clone(),
+        None => sst_files
+            .iter()
+            .map(|path| {
+                let id = file_id(path).unwrap_or_else(|| {
+                    next_id += 1;
+                    next_id - 1
+                });
+                ManifestSstEntry {
+                    id,
+                    path: path.clone(),
+                }
+            })
+            .collect(),
+    };
+    let mut sst_max_lsn = 0;
+
+    // 2. Keep, rebuild, or give up on each SSTable.
+    let mut live = Vec::with_capacity(candidates.len());
+    for entry in candidates {
+        if !entry.path.is_file() {
+            tracing::warn!(path = %entry.path.display(), "listed SSTable missing");
+            report.sstables_lost.push(entry.path);
+            continue;
+        }
+        if let Ok(sstable) = SSTable::open(&entry.path)
+            && sstable.verify_data_blocks().is_ok()
+        {
+            sst_max_lsn = sst_max_lsn.max(sstable.max_lsn());
+            report.sstables_kept += 1;
+            live.push(entry);
+            continue;
+        }
+
+        let path = sstable_dir.join(format!("{next_id:06}.sst"));
+        let repaired = match tools::repair_sstable(&entry.path, &path) {
+            Ok(sst_report) => {
+                sst_max_lsn = sst_max_lsn.max(SSTable::open(&path)?.max_lsn());
+                report.sstables_repaired += 1;
+                report.points_salvaged += sst_report.points_recovered;
+                if !sst_report.range_tombstones_intact {
+                    report.range_tombstones_lost += 1;
+                }
+                live.push(ManifestSstEntry { id: next_id, path });
+                next_id += 1;
+                true
+            }
+            Err(SstError::Io(e)) => return Err(e.into()),
+            Err(_) => false,
+        };
+        let moved = move_to_lost(&lost_dir, &entry.path)?;
+        tracing::warn!(
+            path = %entry.path.display(),
+            moved_to = %moved.display(),
+            repaired,
+            "damaged SSTable moved aside"
+        );
+        if !repaired {
+            report.sstables_lost.push(moved);
+        }
+    }
+
+    // 3. WALs. Without a manifest, an older WAL is only replayed if it
+    //    holds writes the surviving SSTables do not.
+    let mut last_lsn = sst_max_lsn;
+    let (active_wal, frozen_wals) = match &old {
+        Some(state) => {
+            last_lsn = last_lsn.max(state.last_lsn);
+            let frozen = state
+                .frozen_wals
+                .iter()
+                .copied()
+                .filter(|id| wal_ids.contains(id))
+                .collect();
+            (state.active_wal, frozen)
+        }
+        None => {
+            let active = wal_ids.last().copied().unwrap_or(0);
+            let mut frozen = Vec::new();
+            for &id in &wal_ids {
+                let wal_lsn = wal_max_lsn(&memtable_dir.join(format!("{id:06}.log")));
+                last_lsn = last_lsn.max(wal_lsn);
+                if id != active && wal_lsn > sst_max_lsn {
+                    frozen.push(id);
+                }
+            }
+            (active, frozen)
+        }
+    };
+    report.wal_segments = 1 + frozen_wals.len();
+
+    // 4. A fresh manifest, written aside and swapped in.
+    let staging = base.join(format!("{MANIFEST_DIR}.repair"));
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    {
+        let manifest = Manifest::open(&staging)?.with_root(base);
+        manifest.set_active_wal(active_wal)?;
+        for id in frozen_wals {
+            manifest.add_frozen_wal(id)?;
+        }
+        for entry in live {
+            manifest.add_sstable(entry)?;
+        }
+        manifest.advance_sst_id(next_id)?;
+        manifest.update_lsn(last_lsn)?;
+        manifest.reserve_lsn(last_lsn)?;
+        manifest.checkpoint()?;
+    }
+    if manifest_dir.exists() {
+        move_to_lost(&lost_dir, &manifest_dir)?;
+    }
+    durability::rename(&staging, &manifest_dir)?;
+
+    for dir in [manifest_dir.as_path(), sstable_dir.as_path()] {
+        durability::sync_dir(dir)?;
+    }
+
+    tracing::info!(
+        path = %base.display(),
+        kept = report.sstables_kept,
+        repaired = report.sstables_repaired,
+        lost = report.sstables_lost.len(),
+        manifest_rebuilt = report.manifest_rebuilt,
+        "engine directory repaired"
+    );
+    Ok(report)
+}
+
+/// Reads the manifest under `dir` of the engine directory `base`, or
+/// `None` if it cannot be opened or its snapshot is damaged.
+fn read_manifest(dir: &Path, base: &Path) -> Option<ManifestState> {
+    if !dir.exists() || !Manifest::snapshot_intact(dir) {
+        return None;
+    }
+    let manifest = match Manifest::open(dir) {
+        Ok(manifest) => manifest.with_root(base),
+        Err(e) => {
+            tracing::warn!(error = %e, "manifest unreadable, rebuilding from files");
+            return None;
+        }
+    };
+    Some(ManifestState {
+        active_wal: manifest.get_active_wal().ok()?,
+        frozen_wals: manifest.get_frozen_wals().ok()?,
+        sstables: manifest.get_sstables().ok()?,
+        last_lsn: manifest.get_last_lsn().ok()?,
+        next_sst_id: manifest.peek_next_sst_id().ok()?,
+    })
+}
+
+/// Returns the ids of the `NNNNNN.<extension>` files in `dir`, ascending.
+fn list_ids(dir: &Path, extension: &str) -> Result<Vec<u64>, EngineError> {
+    let mut ids = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file()
+            && path.extension().and_then(|s| s.to_str()) == Some(extension)
+            && let Some(id) = file_id(&path)
+        {
+            ids.push(id);
+        }
+    }
+    ids.sort_unstable();
+    Ok(ids)
+}
+
+/// Returns the id a `NNNNNN.<extension>` file is named after.
+fn file_id(path: &Path) -> Option<u64> {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .and_then(|s| s.parse::<u64>().ok())
+}
+
+/// Highest LSN among the readable records of the WAL at `path`.
+fn wal_max_lsn(path: &Path) -> u64 {
+    Wal::<Record>::replay_read_only(path).map_or(0, |records| {
+        records
+            .map_while(Result::ok)
+            .map(|record| record.lsn())
+            .max()
+            .unwrap_or(0)
+    })
+}
+
+/// Moves `path` into `lost_dir`, numbering the name if it is taken, and
+/// returns the new path.
+fn move_to_lost(lost_dir: &Path, path: &Path) -> Result<PathBuf, EngineError> {
+    fs::create_dir_all(lost_dir)?;
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let mut target = lost_dir.join(name.as_ref());
+    let mut n = 1;
+    while target.exists() {
+        target = lost_dir.join(format!("{name}.{n}"));
+        n += 1;
+    }
+    durability::rename(path, &target)?;
+    Ok(target)
+}
@@ -0,0 +1,52 @@
+//! SSTable deltas for physical replication.
+//!
+//! [`Engine::export_delta`](super::Engine::export_delta) flushes the leader
+//! and packages the SSTables created since a given version, together with
+//! the full list of live SSTable ids; [`Engine::apply_delta`](super::Engine::apply_delta)
+//! installs such a package on a follower. SSTable ids are allocated from a
+//! monotonic counter and never reused, so the counter doubles as the
+//! replication version: every SSTable a flush or compaction produced after
+//! version `v` has an id of at least `v`.
+
+/// One SSTable shipped in a [`ReplicationDelta`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeltaSSTable {
+    /// SSTable id on the leader, kept on the follower.
+    pub id: u64,
+
+    /// Complete contents of the SSTable file.
+    pub data: Vec<u8>,
+}
+
+/// SSTable set changes of a leader since a replication version.
+///
+/// Produced by [`Db::export_delta`](crate::Db::export_delta) and consumed
+/// by [`Db::apply_delta`](crate::Db::apply_delta). All fields are plain
+/// data, so the delta can be shipped with any serialization format.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplicationDelta {
+    /// Version the delta starts from; the follower must be at exactly this
+    /// version.
+    pub since_version: u64,
+
+    /// Version of the leader after the delta; the follower is at this
+    /// version once the delta is applied.
+    pub version: u64,
+
+    /// Highest LSN held by the leader's SSTables and manifest.
+    pub last_lsn: u64,
+
+    /// SSTables created since `since_version` that are still live.
+    pub sstables: Vec<DeltaSSTable>,
+
+    /// Ids of every live SSTable after the delta, shipped or not. The
+    /// follower drops its SSTables missing from this list.
+    pub live_ids: Vec<u64>,
+}
+
+impl ReplicationDelta {
+    /// Total bytes of SSTable data carried.
+    pub fn data_size(&self) -> usize {
+        self.sstables.iter().map(|sst| sst.data.len()).sum()
+    }
+}
@@ -0,0 +1,161 @@
+//! Garbage collection and retention of WAL segments.
+//!
+//! Once a frozen memtable is flushed and the manifest has released its WAL
+//! segment, the segment is no longer needed for recovery. By default it is
+//! deleted right away. With
+//! [`EngineConfig::wal_retention_bytes`](super::EngineConfig::wal_retention_bytes)
+//! or [`EngineConfig::wal_retention_seconds`](super::EngineConfig::wal_retention_seconds)
+//! set, flushed segments are instead kept on disk so that log tailers and
+//! external replicators can still read writes they missed, and deleted,
+//! oldest first, once they fall outside the limits.
+//!
+//! The pass runs after every flush and on open. Any segment below the
+//! active one that the manifest does not list as frozen counts as flushed,
+//! so segments left behind by a crash between the manifest update and the
+//! deletion are collected by the next pass.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::durability;
+
+/// Role of a WAL segment, as reported by
+/// [`Db::wal_files`](crate::Db::wal_files).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalFileStatus {
+    /// The segment the active memtable appends to.
+    Active,
+
+    /// Backs a frozen memtable that has not been flushed yet; replayed on
+    /// open.
+    Frozen,
+
+    /// Its memtable is flushed and the segment is no longer needed for
+    /// recovery. Kept only within the WAL retention limits; without them,
+    /// a flushed segment that outlives the next flush or open is a leak.
+    Flushed,
+}
+
+/// A WAL segment on disk, returned by
+/// [`Db::wal_files`](crate::Db::wal_files).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalFile {
+    /// WAL sequence number, taken from the file name.
+    pub wal_id: u64,
+
+    /// On-disk path of the segment.
+    pub path: PathBuf,
+
+    /// File size in bytes.
+    pub size: u64,
+
+    /// Whether the segment is still needed for recovery.
+    pub status: WalFileStatus,
+}
+
+/// A flushed WAL segment found in the memtable directory.
+struct Segment {
+    wal_id: u64,
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+/// Parses the WAL id out of a `NNNNNN.log` file name.
+fn wal_id(path: &Path) -> Option<u64> {
+    path.file_name()
+        .and_then(|s| s.to_str())
+        .and_then(|s| s.strip_suffix(".log"))
+        .and_then(|s| s.parse::<u64>().ok())
+}
+
+/// Lists the WAL segments in `memtable_dir`, oldest first.
+pub(crate) fn list_wal_files(
+    memtable_dir: &Path,
+    active_wal_id: u64,
+    frozen_wal_ids: &[u64],
+) -> io::Result<Vec<WalFile>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(memtable_dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if let Some(wal_id) = wal_id(&path) {
+            let status = if wal_id == active_wal_id {
+                WalFileStatus::Active
+            } else if frozen_wal_ids.contains(&wal_id) {
+                WalFileStatus::Frozen
+            } else {
+                WalFileStatus::Flushed
+            };
+            files.push(WalFile {
+                wal_id,
+                path,
+                size: entry.metadata()?.len(),
+                status,
+            });
+        }
+    }
+    files.sort_by_key(|f| f.wal_id);
+    Ok(files)
+}
+
+/// Deletes the flushed WAL segments in `memtable_dir` that exceed the
+/// retention limits, syncs the directory, and returns their paths.
+///
+/// A segment is flushed when its id is below `active_wal_id` and not in
+/// `frozen_wal_ids`. It is deleted if its last write is at least
+/// `retention_seconds` old, or while the flushed segments together take
+/// more than `retention_bytes`. A limit of `0` does not apply; with both
+/// at `0` every flushed segment is deleted.
+pub(crate) fn prune_flushed_wals(
+    memtable_dir: &Path,
+    active_wal_id: u64,
+    frozen_wal_ids: &[u64],
+    retention_bytes: usize,
+    retention_seconds: usize,
+) -> io::Result<Vec<PathBuf>> {
+    let mut segments = Vec::new();
+    for entry in fs::read_dir(memtable_dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if let Some(wal_id) = wal_id(&path)
+            && wal_id < active_wal_id
+            && !frozen_wal_ids.contains(&wal_id)
+        {
+            let metadata = entry.metadata()?;
+            segments.push(Segment {
+                wal_id,
+                path,
+                size: metadata.len(),
+                modified: metadata.modified()?,
+            });
+        }
+    }
+    segments.sort_by_key(|s| s.wal_id);
+
+    let retain = retention_bytes > 0 || retention_seconds > 0;
+    let max_age = Duration::from_secs(retention_seconds as u64);
+    let now = SystemTime::now();
+    let mut retained: u64 = segments.iter().map(|s| s.size).sum();
+    let mut removed = Vec::new();
+    for segment in segments {
+        let expired = retention_seconds > 0
+            && now.duration_since(segment.modified).unwrap_or_default() >= max_age;
+        let over_size = retention_bytes > 0 && retained > retention_bytes as u64;
+        if retain && !expired && !over_size {
+            break;
+        }
+        fs::remove_file(&segment.path)?;
+        retained -= segment.size;
+        removed.push(segment.path);
+    }
+
+    // Make the deletions durable, so a crash cannot resurrect a segment
+    // whose memtable is already in an SSTable.
+    if !removed.is_empty() {
+        durability::sync_dir(memtable_dir)?;
+    }
+    Ok(removed)
+}
@@ -0,0 +1,190 @@
+//! Row cache for hot point lookups.
+//!
+//! With [`EngineConfig::row_cache_size`](super::EngineConfig::row_cache_size)
+//! above zero, values that point lookups resolved from SSTables are kept in
+//! a byte-bounded LRU map, so a small set of hot keys is served without
+//! touching bloom filters or data blocks. Memtable hits are not cached;
+//! they are already in memory.
+//!
+//! Writes remove the keys they touch (a range delete removes every cached
+//! key in its range), and compactions and replication deltas advance the
+//! cache epoch, which retires every entry cached before. A lookup that
+//! raced with either is not inserted: it takes a [`CacheToken`] before
+//! reading the tree and its result is dropped if an invalidation happened
+//! in between.
+
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use std::sync::Mutex;
+
+/// Fixed per-entry overhead charged against the capacity, in bytes.
+const ENTRY_OVERHEAD: usize = 64;
+
+/// Proof of the cache state a lookup started from.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CacheToken {
+    invalidations: u64,
+}
+
+/// Outcome of [`RowCache::get`].
+pub(crate) enum CacheLookup {
+    /// The key's current value.
+    Hit(Vec<u8>),
+
+    /// Not cached; insert the resolved value with this token.
+    Miss(CacheToken),
+}
+
+struct CachedRow {
+    value: Vec<u8>,
+    epoch: u64,
+    last_used: u64,
+}
+
+struct RowCacheState {
+    rows: BTreeMap<Vec<u8>, CachedRow>,
+
+    /// `last_used` tick → key, oldest first.
+    lru: BTreeMap<u64, Vec<u8>>,
+
+    /// Bytes charged for the cached rows.
+    size: usize,
+
+    /// Source of `last_used` ticks.
+    tick: u64,
+
+    /// Entries cached in an earlier epoch are stale.
+    epoch: u64,
+
+    /// Bumped by every invalidation and epoch change.
+    invalidations: u64,
+}
+
+/// Byte-bounded LRU cache of point-lookup results.
+pub(crate) struct RowCache {
+    capacity: usize,
+    state: Mutex<RowCacheState>,
+}
+
+impl RowCache {
+    /// Creates an empty cache holding up to `capacity` bytes of keys and
+    /// values.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(RowCacheState {
+                rows: BTreeMap::new(),
+                lru: BTreeMap::new(),
+                size: 0,
+                tick: 0,
+                epoch: 0,
+                invalidations: 0,
+            }),
+        }
+    }
+
+    /// Returns the cached value of `key`, or a token for inserting it.
+    pub fn get(&self, key: &[u8]) -> CacheLookup {
+        let mut state = self.state.lock().unwrap();
+        let token = CacheToken {
+            invalidations: state.invalidations,
+        };
+        let epoch = state.epoch;
+        state.tick += 1;
+        let tick = state.tick;
+
+        let Some(row) = state.rows.get_mut(key) else {
+            return CacheLookup::Miss(token);
+        };
+        if row.epoch != epoch {
+            state.remove(key);
+            return CacheLookup::Miss(token);
+        }
+        let previous = std::mem::replace(&mut row.last_used, tick);
+        let value = row.value.clone();
+        if let Some(k) = state.lru.remove(&previous) {
+            state.lru.insert(tick, k);
+        }
+        CacheLookup::Hit(value)
+    }
+
+    /// Caches `value` for `key`, unless an invalidation happened since
+    /// `token` was taken. Evicts least recently used rows to stay within
+    /// capacity; a row larger than the whole cache is not stored.
+    pub fn insert(&self, key: Vec<u8>, value: Vec<u8>, token: CacheToken) {
+        let charge = key.len() + value.len() + ENTRY_OVERHEAD;
+        if charge > self.capacity {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        if state.invalidations != token.invalidations {
+            return;
+        }
+
+        state.remove(&key);
+        while state.size + charge > self.capacity {
+            let Some((_, oldest)) = state.lru.pop_first() else {
+                break;
+            };
+            state.remove(&oldest);
+        }
+
+        state.tick += 1;
+        let tick = state.tick;
+        let epoch = state.epoch;
+        state.size += charge;
+        state.lru.insert(tick, key.clone());
+        state.rows.insert(
+            key,
+            CachedRow {
+                value,
+                epoch,
+                last_used: tick,
+            },
+        );
+    }
+
+    /// Drops the cached row of `key`.
+    pub fn invalidate(&self, key: &[u8]) {
+        let mut state = self.state.lock().unwrap();
+        state.invalidations += 1;
+        state.remove(key);
+    }
+
+    /// Drops every cached row with a key in `[start, end)`, or from `start`
+    /// on if `end` is empty.
+    pub fn invalidate_range(&self, start: &[u8], end: &[u8]) {
+        let mut state = self.state.lock().unwrap();
+        state.invalidations += 1;
+        let upper = if end.is_empty() {
+            Bound::Unbounded
+        } else {
+            Bound::Excluded(end)
+        };
+        let keys: Vec<Vec<u8>> = state
+            .rows
+            .range::<[u8], _>((Bound::Included(start), upper))
+            .map(|(k, _)| k.clone())
+            .collect();
+        for key in keys {
+            state.remove(&key);
+        }
+    }
+
+    /// Starts a new epoch: every row cached so far is treated as absent.
+    pub fn advance_epoch(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.invalidations += 1;
+        state.epoch += 1;
+    }
+}
+
+impl RowCacheState {
+    /// Removes `key` and its LRU slot, releasing its charge.
+    fn remove(&mut self, key: &[u8]) {
+        if let Some(row) = self.rows.remove(key) {
+            self.lru.remove(&row.last_used);
+            self.size -= key.len() + row.value.len() + ENTRY_OVERHEAD;
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! Lock-free read views of the engine state.
+//!
+//! A [`SuperVersion`] is an immutable snapshot of everything a read needs:
+//! the active memtable, the frozen memtables, and the live SSTables. The
+//! engine publishes a new one through a [`SuperVersionCell`] whenever that
+//! set changes — on freeze, flush, and compaction — and readers grab the
+//! current one with a single atomic load instead of taking the engine lock.
+//!
+//! Retired versions are reclaimed through `crossbeam`'s epoch GC, so a
+//! reader that loaded a pointer just before a swap can still safely bump
+//! its reference count.
+
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+
+use crossbeam::epoch::{self, Atomic, Owned};
+
+use crate::engine::utils::{below_end, cmp_ends};
+use crate::memtable::{FrozenMemtable, Memtable};
+use crate::sstable::SSTable;
+
+/// An immutable view of the layers a read has to consult.
+///
+/// The active memtable is still mutable through its own internal lock; the
+/// frozen and SSTable lists are fixed for the lifetime of the view.
+pub(crate) struct SuperVersion {
+    /// Active memtable at the time of publication.
+    pub active: Arc<Memtable>,
+
+    /// Frozen memtables, newest first.
+    pub frozen: Vec<Arc<FrozenMemtable>>,
+
+    /// Live SSTables, sorted by `max_lsn` descending.
+    pub sstables: Vec<Arc<SSTable>>,
+
+    /// Keys the SSTables hold or cover, so a scan of a range outside them
+    /// skips the per-table checks.
+    pub sstable_span: SstKeySpan,
+
+    /// Copy of [`EngineConfig::parallel_sstable_probe`](super::EngineConfig::parallel_sstable_probe).
+    pub parallel_sstable_probe: bool,
+
+    /// Copy of [`EngineConfig::thread_pool_size`](super::EngineConfig::thread_pool_size).
+    pub thread_pool_size: usize,
+}
+
+/// Bounds of the keys a set of SSTables holds or covers with a range
+/// tombstone.
+///
+/// Coarser than checking each table with
+/// [`SSTable::may_overlap`], but a single check: a scan past the newest
+/// keys — the usual case for time-ordered or append-mostly keys — reads
+/// the memtables alone without looking at any table.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SstKeySpan {
+    /// Smallest point key or range tombstone start, or `None` if no table
+    /// holds either.
+    first: Option<Vec<u8>>,
+
+    /// Largest point key, or `None` if no table holds one.
+    last_point: Option<Vec<u8>>,
+
+    /// Largest exclusive range tombstone end, empty if unbounded, or
+    /// `None` if no table holds a range tombstone.
+    range_end: Option<Vec<u8>>,
+}
+
+impl SstKeySpan {
+    /// Computes the span of `sstables`.
+    pub fn of(sstables: &[Arc<SSTable>]) -> Self {
+        let mut span = Self::default();
+        for sst in sstables {
+            if sst.record_count() > 0 {
+                span.widen_first(sst.min_key());
+                if span
+                    .last_point
+                    .as_deref()
+                    .is_none_or(|last| last < sst.max_key())
+                {
+                    span.last_point = Some(sst.max_key().to_vec());
+                }
+            }
+            for (start, end) in sst.range_tombstone_bounds() {
+                span.widen_first(start);
+                if span
+                    .range_end
+                    .as_deref()
+                    .is_none_or(|current| cmp_ends(current, end).is_lt())
+                {
+                    span.range_end = Some(end.to_vec());
+                }
+            }
+        }
+        span
+    }
+
+    fn widen_first(&mut self, key: &[u8]) {
+        if self.first.as_deref().is_none_or(|first| key < first) {
+            self.first = Some(key.to_vec());
+        }
+    }
+
+    /// Checks whether any of the SSTables may hold or cover a key in
+    /// `[start, end)`.
+    ///
+    /// Returns `false` only when every point key and range tombstone lies
+    /// outside the range; `true` does not mean any single table overlaps.
+    pub fn may_overlap(&self, start: &[u8], end: &[u8]) -> bool {
+        let Some(first) = &self.first else {
+            return false;
+        };
+        first.as_slice() < end
+            && (self.last_point.as_deref().is_some_and(|last| last >= start)
+                || self
+                    .range_end
+                    .as_deref()
+                    .is_some_and(|range_end| below_end(start, range_end)))
+    }
+}
+
+/// Atomically swappable holder of the current [`SuperVersion`].
+pub(crate) struct SuperVersionCell {
+    current: Atomic<Arc<SuperVersion>>,
+}
+
+impl SuperVersionCell {
+    /// Creates a cell holding `version`.
+    pub fn new(version: SuperVersion) -> Self {
+        Self {
+            current: Atomic::new(Arc::new(version)),
+        }
+    }
+
+    /// Returns the current view. Never blocks.
+    pub fn load(&self) -> Arc<SuperVersion> {
+        let guard = epoch::pin();
+        let current = self.current.load(Ordering::Acquire, &guard);
+        // SAFETY: the cell is never null — it is initialised in `new` and
+        // `store` only ever swaps in another valid pointer. While `guard`
+        // is pinned, a concurrently retired pointer is not destroyed.
+        Arc::clone(unsafe { current.deref() })
+    }
+
+    /// Publishes `version`, retiring the previous one.
+    pub fn store(&self, version: SuperVersion) {
+        let guard = epoch::pin();
+        let previous = self
+            .current
+            .swap(Owned::new(Arc::new(version)), Ordering::AcqRel, &guard);
+        // SAFETY: `previous` is no longer reachable through the cell, so only
+        // readers pinned before the swap can observe it; the epoch GC waits
+        // for them before dropping it.
+        unsafe { guard.defer_destroy(previous) };
+        // Hand it to the global queue, so `reclaim` on any thread can
+        // free it rather than only this one once its local bag fills.
+        guard.flush();
+    }
+
+    /// Drops the retired versions that no reader pinned before their
+    /// swap can still see, releasing the layers only they held.
+    ///
+    /// Retired garbage is freed two epochs after its swap, and each
+    /// flush tries to advance the epoch, so a few rounds suffice unless
+    /// another thread stays pinned meanwhile.
+    pub fn reclaim(&self) {
+        for _ in 0..4 {
+            epoch::pin().flush();
+        }
+    }
+}
+
+impl Drop for SuperVersionCell {
+    fn drop(&mut self) {
+        // SAFETY: `&mut self` guarantees no concurrent readers remain.
+        unsafe {
+            let current = self.current.load(Ordering::Relaxed, epoch::unprotected());
+            drop(current.into_owned());
+        }
+    }
+}
@@ -1,5 +1,9 @@
-use crate::engine::{Engine, EngineConfig};
+use crate::compaction::{CompactionError, CompactionResult, CompactionStrategy};
+use crate::engine::{Engine, EngineConfig, EngineError, ReadOptions};
+use crate::manifest::Manifest;
+use crate::sstable::SSTable;
 use std::path::Path;
+use std::sync::{Arc, Barrier};
 use tracing_subscriber::EnvFilter;
 
 /// Initialize tracing subscriber controlled by `RUST_LOG` env var.
@@ -101,6 +105,31 @@ pub fn engine_with_multi_sstables(path: &Path, num_keys: usize, prefix: &str) ->
     engine
 }
 
+/// Compaction by `S` that stops once it has built its output, with its
+/// inputs claimed, until the test lets it go on.
+///
+/// Waits on the barrier twice: the first wait tells the test the output
+/// is built, the second lets the compaction commit.
+pub struct Paused<S>(pub S, pub Barrier);
+
+impl<S: CompactionStrategy> CompactionStrategy for Paused<S> {
+    fn compact(
+        &self,
+        sstables: &[Arc<SSTable>],
+        manifest: &Manifest,
+        data_dir: &str,
+        config: &EngineConfig,
+        horizon: u64,
+    ) -> Result<Option<CompactionResult>, CompactionError> {
+        let result = self
+            .0
+            .compact(sstables, manifest, data_dir, config, horizon);
+        self.1.wait();
+        self.1.wait();
+        result
+    }
+}
+
 /// Helper: force a flush cycle by closing and reopening the engine.
 pub fn reopen(path: &Path) -> Engine {
     Engine::open(path, default_config()).expect("reopen")
@@ -110,3 +139,15 @@ pub fn reopen(path: &Path) -> Engine {
 pub fn collect_scan(engine: &Engine, start: &[u8], end: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
     engine.scan(start, end).expect("scan").collect()
 }
+
+impl Engine {
+    /// [`scan_opt`](Engine::scan_opt) with default [`ReadOptions`], for
+    /// tests that do not care about checksum verification.
+    pub fn scan(
+        &self,
+        start_key: &[u8],
+        end_key: &[u8],
+    ) -> Result<impl Iterator<Item = (Vec<u8>, Vec<u8>)> + Send + use<>, EngineError> {
+        self.scan_opt(start_key, end_key, &ReadOptions::default())
+    }
+}
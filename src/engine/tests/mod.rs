@@ -1,34 +1,72 @@
 pub mod helpers;
+mod tests_adaptive_compaction;
+mod tests_bulk_load;
+mod tests_clear;
+mod tests_clock_regression;
+mod tests_compaction_conflicts;
+mod tests_compaction_lock;
+mod tests_compare_and_swap;
 mod tests_crash_compaction;
 mod tests_crash_flush;
 mod tests_crash_recovery;
+mod tests_debug_report;
 mod tests_delete;
+mod tests_deletion_runs;
 mod tests_edge_cases;
+mod tests_estimate_num_keys;
 mod tests_flush_api;
+mod tests_flush_triggers;
+mod tests_get_entry;
+mod tests_get_pinned;
 mod tests_hardening;
+mod tests_ingest_sorted;
 mod tests_layers;
 mod tests_lsn_continuity;
 mod tests_lsn_crash;
+mod tests_mmap_budget;
 mod tests_multi_crash;
 mod tests_multi_sstable;
+mod tests_parallel_probe;
 mod tests_precedence;
 mod tests_put_get;
 mod tests_range_delete;
+mod tests_read_deadline;
+mod tests_read_depth;
+mod tests_read_only;
+mod tests_read_stats;
 mod tests_recovery;
+mod tests_repair;
+mod tests_replication;
+mod tests_row_cache;
 mod tests_scan;
+mod tests_scan_bounds;
+mod tests_sorted_batch;
+mod tests_sstable_metadata;
 mod tests_stress;
+mod tests_superversion;
+mod tests_warm_up;
+mod tests_write_options;
+mod tests_write_stats;
 
 // Priority 2 — robustness tests
 mod tests_boundary_values;
 mod tests_compaction_edge;
 mod tests_concurrent_ops;
+mod tests_consistency;
 mod tests_file_cleanup;
+mod tests_file_gc;
+mod tests_wal_retention;
 
 // Priority 3 — hardening (edge cases)
 mod tests_hardening_edge;
 mod tests_mvcc_scan;
 mod tests_scan_edge;
+mod tests_scan_ordering;
 
 // Priority 4 — coverage
 mod tests_tombstone_gc;
+mod tests_tombstone_gc_grace;
 mod tests_utils_coverage;
+mod tests_version_pins;
+mod tests_version_precedence;
+mod tests_wal_tail;
@@ -0,0 +1,182 @@
+//! Tests for the adaptive compaction controller (`Engine::tune_compaction`).
+//!
+//! ## Coverage areas
+//! - Lookups probing many SSTables lower `min_threshold` and the tombstone
+//!   ratio and raise `max_threshold`
+//! - Compaction rewriting more than the write target raises
+//!   `min_threshold`
+//! - Adjustments stop at the configured bounds, and quiet windows change
+//!   nothing
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{AdaptiveCompaction, Engine, TuningReason, TuningWindow};
+    use tempfile::TempDir;
+
+    /// Opens an engine whose keys `k_0000..k_0099` are spread over
+    /// several overlapping SSTables, so that absent-key lookups consult
+    /// every bloom filter.
+    fn overlapping_engine(path: &std::path::Path) -> Engine {
+        let engine = Engine::open(path, multi_sstable_config()).unwrap();
+        for round in 0..4u32 {
+            for i in 0..100u32 {
+                let key = format!("k_{i:04}").into_bytes();
+                let value = format!("value_{round}_{i:04}").into_bytes();
+                engine.put(key, value).unwrap();
+            }
+        }
+        engine.flush_all_frozen().unwrap();
+        engine
+    }
+
+    /// Looks up `count` absent keys inside the engine's key range.
+    fn miss(engine: &Engine, count: u32) {
+        for i in 0..count {
+            let key = format!("k_{:04}x", i % 100).into_bytes();
+            assert_eq!(engine.get(key).unwrap(), None);
+        }
+    }
+
+    /// # Scenario
+    /// Read-heavy workload over many SSTables.
+    ///
+    /// # Starting environment
+    /// Engine with overlapping SSTables; read target of 1 probe per get.
+    ///
+    /// # Actions
+    /// 1. Look up 200 absent keys.
+    /// 2. Run one controller step.
+    ///
+    /// # Expected behavior
+    /// The step reports read amplification equal to the SSTable count and
+    /// makes compaction more eager; the engine's thresholds match.
+    #[test]
+    fn adaptive_read_amplification_lowers_thresholds() {
+        let dir = TempDir::new().unwrap();
+        let engine = overlapping_engine(dir.path());
+        let sstables = engine.stats().unwrap().sstables_count;
+        assert!(sstables >= 2);
+        let tuning = AdaptiveCompaction {
+            target_read_amplification: 1.0,
+            ..AdaptiveCompaction::default()
+        };
+
+        let mut window = TuningWindow::default();
+        let before = engine.compaction_thresholds().unwrap();
+        miss(&engine, 200);
+        let change = engine
+            .tune_compaction(0, &mut window, &tuning)
+            .unwrap()
+            .expect("thresholds changed");
+
+        assert_eq!(change.reason, TuningReason::ReadAmplification);
+        assert_eq!(change.read_amplification, Some(sstables as f64));
+        assert_eq!(change.previous, before);
+        assert_eq!(change.current.min_threshold, before.min_threshold - 1);
+        assert!(change.current.max_threshold > before.max_threshold);
+        assert!(change.current.tombstone_ratio_threshold < before.tombstone_ratio_threshold);
+        assert_eq!(engine.compaction_thresholds().unwrap(), change.current);
+    }
+
+    /// # Scenario
+    /// Write-heavy workload with expensive compaction.
+    ///
+    /// # Starting environment
+    /// Engine with overlapping SSTables; write target of 1.5.
+    ///
+    /// # Actions
+    /// 1. Run a controller step to start a window after the setup flushes.
+    /// 2. Rewrite ten keys and flush them, major-compact everything.
+    /// 3. Run another step.
+    ///
+    /// # Expected behavior
+    /// The second step measures write amplification above the target, as
+    /// compaction rewrote all 100 keys for ten flushed ones, and raises
+    /// `min_threshold`; no lookups means no read amplification.
+    #[test]
+    fn adaptive_write_amplification_raises_thresholds() {
+        let dir = TempDir::new().unwrap();
+        let engine = overlapping_engine(dir.path());
+        let tuning = AdaptiveCompaction {
+            target_write_amplification: 1.5,
+            ..AdaptiveCompaction::default()
+        };
+
+        let mut window = TuningWindow::default();
+        engine.tune_compaction(0, &mut window, &tuning).unwrap();
+        let before = engine.compaction_thresholds().unwrap();
+
+        for i in 0..10u32 {
+            let key = format!("k_{i:04}").into_bytes();
+            engine.put(key, b"rewritten".to_vec()).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+        assert!(engine.major_compact().unwrap());
+
+        let change = engine
+            .tune_compaction(0, &mut window, &tuning)
+            .unwrap()
+            .expect("thresholds changed");
+        assert_eq!(change.reason, TuningReason::WriteAmplification);
+        assert_eq!(change.read_amplification, None);
+        assert!(change.write_amplification.unwrap() > 1.5);
+        assert_eq!(change.current.min_threshold, before.min_threshold + 1);
+        assert!(change.current.tombstone_ratio_threshold > before.tombstone_ratio_threshold);
+    }
+
+    /// # Scenario
+    /// The controller respects its bounds and ignores quiet windows.
+    ///
+    /// # Starting environment
+    /// Engine with overlapping SSTables; `min_threshold` floor of 3 and a
+    /// read target of 1 probe per get.
+    ///
+    /// # Actions
+    /// 1. Run a step with no activity.
+    /// 2. Repeat lookups plus a step until nothing changes.
+    ///
+    /// # Expected behavior
+    /// The quiet step changes nothing. The loop ends with every threshold
+    /// at its eager bound: `min_threshold` at the floor, `max_threshold`
+    /// at the ceiling, the tombstone ratio at its floor.
+    #[test]
+    fn adaptive_stops_at_bounds() {
+        let dir = TempDir::new().unwrap();
+        let engine = overlapping_engine(dir.path());
+        let tuning = AdaptiveCompaction {
+            target_read_amplification: 1.0,
+            min_threshold_floor: 3,
+            max_threshold_ceiling: 40,
+            tombstone_ratio_floor: 0.15,
+            ..AdaptiveCompaction::default()
+        };
+
+        let mut window = TuningWindow::default();
+        assert!(
+            engine
+                .tune_compaction(0, &mut window, &tuning)
+                .unwrap()
+                .is_none()
+        );
+
+        let mut steps = 0;
+        loop {
+            miss(&engine, 100);
+            if engine
+                .tune_compaction(0, &mut window, &tuning)
+                .unwrap()
+                .is_none()
+            {
+                break;
+            }
+            steps += 1;
+            assert!(steps < 50, "controller did not settle");
+        }
+
+        let thresholds = engine.compaction_thresholds().unwrap();
+        assert_eq!(thresholds.min_threshold, 3);
+        assert_eq!(thresholds.max_threshold, 40);
+        assert_eq!(thresholds.tombstone_ratio_threshold, 0.15);
+    }
+}
@@ -0,0 +1,153 @@
+//! Tests for bulk-load mode (`Engine::set_bulk_load`).
+//!
+//! ## Coverage areas
+//! - Write buffers grow by `BULK_LOAD_WRITE_BUFFER_FACTOR` while bulk
+//!   loading and shrink back afterwards
+//! - Leaving bulk-load mode freezes the oversized active memtable
+//! - Age and WAL-size flush triggers are suspended while bulk loading
+//! - Writes made without per-record fsync are replayed after a reopen
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{BULK_LOAD_WRITE_BUFFER_FACTOR, Engine, EngineConfig};
+    use tempfile::TempDir;
+
+    /// Puts `count` keys and returns how many writes froze the memtable.
+    fn put_counting_freezes(engine: &Engine, prefix: &str, count: u32) -> usize {
+        let mut freezes = 0;
+        for i in 0..count {
+            let key = format!("{prefix}_{i:04}").into_bytes();
+            if engine.put(key, b"value".to_vec()).unwrap() {
+                freezes += 1;
+            }
+        }
+        freezes
+    }
+
+    /// # Scenario
+    /// Bulk loading freezes far less often than normal writes.
+    ///
+    /// # Starting environment
+    /// Engine with a 128-byte write buffer.
+    ///
+    /// # Actions
+    /// 1. Put 200 keys normally and count freezes.
+    /// 2. Enable bulk load; put 200 more keys and count freezes.
+    ///
+    /// # Expected behavior
+    /// The bulk-loaded keys freeze at least `BULK_LOAD_WRITE_BUFFER_FACTOR / 2`
+    /// times less often, and every key is readable.
+    #[test]
+    fn bulk_load_raises_write_buffer() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), small_buffer_config()).unwrap();
+
+        let normal = put_counting_freezes(&engine, "n", 200);
+        engine.set_bulk_load(true).unwrap();
+        assert!(engine.is_bulk_loading().unwrap());
+        let bulk = put_counting_freezes(&engine, "b", 200);
+
+        assert!(
+            bulk * (BULK_LOAD_WRITE_BUFFER_FACTOR / 2) <= normal,
+            "bulk load froze {bulk} times vs {normal} normally"
+        );
+        assert!(engine.get(b"n_0199".to_vec()).unwrap().is_some());
+        assert!(engine.get(b"b_0199".to_vec()).unwrap().is_some());
+    }
+
+    /// # Scenario
+    /// Leaving bulk-load mode hands the oversized memtable to a flush and
+    /// restores the configured buffer size.
+    ///
+    /// # Starting environment
+    /// Memtable-only engine in bulk-load mode holding a few keys.
+    ///
+    /// # Actions
+    /// 1. Disable bulk load.
+    /// 2. Disable it again.
+    /// 3. Flush all frozen memtables.
+    ///
+    /// # Expected behavior
+    /// Step 1 freezes the active memtable; step 2 is a no-op; the flush
+    /// produces one SSTable and the keys remain readable.
+    #[test]
+    fn bulk_load_exit_freezes_active() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), memtable_only_config()).unwrap();
+        engine.set_bulk_load(true).unwrap();
+        put_counting_freezes(&engine, "k", 20);
+        assert_eq!(engine.stats().unwrap().frozen_count, 0);
+
+        engine.set_bulk_load(false).unwrap();
+        assert!(!engine.is_bulk_loading().unwrap());
+        assert_eq!(engine.stats().unwrap().frozen_count, 1);
+        engine.set_bulk_load(false).unwrap();
+        assert_eq!(engine.stats().unwrap().frozen_count, 1);
+
+        assert_eq!(engine.flush_all_frozen().unwrap(), 1);
+        assert_eq!(engine.stats().unwrap().sstables_count, 1);
+        assert!(engine.get(b"k_0000".to_vec()).unwrap().is_some());
+    }
+
+    /// # Scenario
+    /// Flush triggers other than buffer fullness wait for the bulk load.
+    ///
+    /// # Starting environment
+    /// Memtable-only engine with `max_total_wal_size: 1024`, in bulk-load
+    /// mode.
+    ///
+    /// # Actions
+    /// 1. Put 100 keys (well past 1024 WAL bytes).
+    /// 2. Disable bulk load, flush, and put 100 more keys.
+    ///
+    /// # Expected behavior
+    /// No write freezes during the bulk load; afterwards the WAL-size
+    /// trigger fires again.
+    #[test]
+    fn bulk_load_suspends_flush_triggers() {
+        let dir = TempDir::new().unwrap();
+        let config = EngineConfig {
+            max_total_wal_size: 1024,
+            ..memtable_only_config()
+        };
+        let engine = Engine::open(dir.path(), config).unwrap();
+
+        engine.set_bulk_load(true).unwrap();
+        assert_eq!(put_counting_freezes(&engine, "b", 100), 0);
+        assert!(!engine.freeze_if_due().unwrap());
+
+        engine.set_bulk_load(false).unwrap();
+        engine.flush_all_frozen().unwrap();
+        assert!(put_counting_freezes(&engine, "n", 100) > 0);
+    }
+
+    /// # Scenario
+    /// Bulk-loaded writes survive a restart without a graceful close.
+    ///
+    /// # Starting environment
+    /// Engine with a 128-byte write buffer in bulk-load mode.
+    ///
+    /// # Actions
+    /// 1. Put 300 keys, so some memtables freeze and some stay active.
+    /// 2. Drop the engine without `close()` and reopen it.
+    ///
+    /// # Expected behavior
+    /// Every key is readable and the reopened engine is not bulk loading.
+    #[test]
+    fn bulk_load_writes_replay_after_reopen() {
+        let dir = TempDir::new().unwrap();
+        {
+            let engine = Engine::open(dir.path(), small_buffer_config()).unwrap();
+            engine.set_bulk_load(true).unwrap();
+            put_counting_freezes(&engine, "r", 300);
+        }
+
+        let engine = Engine::open(dir.path(), small_buffer_config()).unwrap();
+        assert!(!engine.is_bulk_loading().unwrap());
+        for i in 0..300u32 {
+            let key = format!("r_{i:04}").into_bytes();
+            assert_eq!(engine.get(key).unwrap(), Some(b"value".to_vec()));
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Tests for `Engine::clear`.
+//!
+//! ## Coverage
+//! - Clearing drops SSTables, frozen memtables, and the active memtable,
+//!   with their files
+//! - Writes after a clear continue the LSN sequence
+//! - A cleared engine reopens empty, without orphans to collect
+//! - A scan opened before the clear still reads the dropped data
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::layout;
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, MEMTABLE_DIR, SSTABLE_DIR};
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Names of the WAL segments on disk, sorted.
+    fn wal_files(path: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(path.join(MEMTABLE_DIR))
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|name| name.ends_with(".log"))
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// # Scenario
+    /// Clear an engine holding data in every layer.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables, frozen memtables, and writes in the
+    /// active memtable.
+    ///
+    /// # Actions
+    /// 1. Clear the engine.
+    /// 2. Write one key.
+    /// 3. Reopen the engine.
+    ///
+    /// # Expected behavior
+    /// After the clear no key is found, no SSTable or frozen memtable is
+    /// left, and only the new active WAL remains on disk. The new write
+    /// gets an LSN above every earlier one and, after reopening, is the
+    /// only key; recovery finds no orphan files.
+    #[test]
+    fn clear_drops_every_layer() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path();
+        let engine = engine_with_multi_sstables(path, 200, "key");
+        for i in 0..20 {
+            engine
+                .put(format!("late_{i:04}").into_bytes(), vec![b'x'; 64])
+                .unwrap();
+        }
+        assert!(engine.stats().unwrap().frozen_count > 0);
+        let lsn_before = engine.latest_lsn().unwrap();
+
+        engine.clear().unwrap();
+
+        let stats = engine.stats().unwrap();
+        assert_eq!(stats.sstables_count, 0);
+        assert_eq!(stats.frozen_count, 0);
+        assert!(collect_scan(&engine, b"a", b"z").is_empty());
+        assert_eq!(engine.get(b"key_0000".to_vec()).unwrap(), None);
+        assert!(
+            layout::sstable_files(&path.join(SSTABLE_DIR))
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(wal_files(path).len(), 1, "only the new active WAL is left");
+
+        engine.put(b"fresh".to_vec(), b"v".to_vec()).unwrap();
+        assert!(engine.latest_lsn().unwrap() > lsn_before);
+        drop(engine);
+
+        let engine = Engine::open(path, multi_sstable_config()).unwrap();
+        assert!(engine.recovery_report().orphan_files_removed.is_empty());
+        assert_eq!(
+            collect_scan(&engine, b"a", b"z"),
+            vec![(b"fresh".to_vec(), b"v".to_vec())]
+        );
+        assert!(engine.latest_lsn().unwrap() > lsn_before);
+    }
+
+    /// # Scenario
+    /// Clear an engine while a scan over it is open.
+    ///
+    /// # Starting environment
+    /// Engine with keys in SSTables.
+    ///
+    /// # Actions
+    /// 1. Open a scan over every key.
+    /// 2. Clear the engine.
+    /// 3. Drain the scan, then scan again.
+    ///
+    /// # Expected behavior
+    /// The open scan still returns every key; the new one returns none.
+    #[test]
+    fn open_scan_outlives_clear() {
+        let tmp = TempDir::new().unwrap();
+        let engine = engine_with_sstables(tmp.path(), 100, "key");
+        let scan = engine.scan(b"a", b"z").unwrap();
+
+        engine.clear().unwrap();
+
+        assert_eq!(scan.count(), 100);
+        assert!(collect_scan(&engine, b"a", b"z").is_empty());
+    }
+}
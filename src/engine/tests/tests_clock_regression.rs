@@ -0,0 +1,184 @@
+//! Tests for record timestamps when the clock runs backwards.
+//!
+//! ## Coverage
+//! - Puts after the clock is stepped back are stamped above every
+//!   earlier put, and reads keep returning the newest version
+//! - Ingested SSTables are stamped the same way
+//! - The newest timestamp of the WAL and the SSTables carries over a
+//!   reopen with an earlier clock
+//! - A clock at the UNIX epoch stamps increasing timestamps
+
+#[cfg(test)]
+mod tests {
+    use crate::clock::MockClock;
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, EngineConfig, ReadOptions};
+    use std::path::Path;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    const LATER: u64 = 2_000_000_000_000;
+    const EARLIER: u64 = 1_000_000_000_000;
+
+    fn clock_config(clock: &Arc<MockClock>, base: EngineConfig) -> EngineConfig {
+        EngineConfig {
+            clock: clock.clone(),
+            ..base
+        }
+    }
+
+    fn timestamp_of(engine: &Engine, key: &[u8]) -> u64 {
+        engine
+            .get_entry(key.to_vec(), &ReadOptions::default())
+            .unwrap()
+            .unwrap()
+            .timestamp
+    }
+
+    /// # Scenario
+    /// The clock is stepped back between two puts of the same key.
+    ///
+    /// # Starting environment
+    /// Memtable-only engine on a mock clock.
+    ///
+    /// # Actions
+    /// 1. Put `k` = `v1`.
+    /// 2. Step the clock back; put `k` = `v2`, then `other`.
+    /// 3. Ingest a sorted pair.
+    ///
+    /// # Expected behavior
+    /// Each write is stamped above the one before it, though the clock
+    /// reads earlier. `get` and `scan` both return `v2`.
+    #[test]
+    fn puts_after_step_back_stay_ordered() {
+        let dir = TempDir::new().unwrap();
+        let clock = Arc::new(MockClock::new(LATER));
+        let engine =
+            Engine::open(dir.path(), clock_config(&clock, memtable_only_config())).unwrap();
+
+        engine.put(b"k".to_vec(), b"v1".to_vec()).unwrap();
+        let first = timestamp_of(&engine, b"k");
+        assert_eq!(first, LATER);
+
+        clock.set(EARLIER);
+        engine.put(b"k".to_vec(), b"v2".to_vec()).unwrap();
+        let second = timestamp_of(&engine, b"k");
+        assert!(second > first);
+        engine.put(b"other".to_vec(), b"v".to_vec()).unwrap();
+        let third = timestamp_of(&engine, b"other");
+        assert!(third > second);
+
+        let pairs = vec![(b"z".to_vec(), b"1".to_vec())];
+        assert!(engine.ingest_sorted(&pairs).unwrap());
+        assert!(timestamp_of(&engine, b"z") > third);
+
+        assert_eq!(engine.get(b"k".to_vec()).unwrap(), Some(b"v2".to_vec()));
+        let scanned = collect_scan(&engine, b"k", b"l");
+        assert_eq!(scanned, vec![(b"k".to_vec(), b"v2".to_vec())]);
+    }
+
+    /// Puts `k` = `v1` at [`LATER`], closes, and reopens with the clock
+    /// stepped back to [`EARLIER`].
+    fn reopen_behind(path: &Path, base: fn() -> EngineConfig, flush: bool) -> (Engine, u64) {
+        let clock = Arc::new(MockClock::new(LATER));
+        let engine = Engine::open(path, clock_config(&clock, base())).unwrap();
+        engine.put(b"k".to_vec(), b"v1".to_vec()).unwrap();
+        if flush {
+            for i in 0..100u32 {
+                let key = format!("fill_{i:04}").into_bytes();
+                engine
+                    .put(key, b"value_with_some_padding".to_vec())
+                    .unwrap();
+            }
+            engine.flush_all_frozen().unwrap();
+            assert!(engine.stats().unwrap().sstables_count > 0);
+        }
+        let before = timestamp_of(&engine, b"k");
+        engine.close().unwrap();
+        drop(engine);
+
+        clock.set(EARLIER);
+        (
+            Engine::open(path, clock_config(&clock, base())).unwrap(),
+            before,
+        )
+    }
+
+    /// # Scenario
+    /// The engine reopens with a clock behind its WAL.
+    ///
+    /// # Starting environment
+    /// Memtable-only engine holding `k` = `v1` in its WAL, stamped at a
+    /// later time than the clock reads on reopen.
+    ///
+    /// # Actions
+    /// 1. Reopen; put `k` = `v2`.
+    ///
+    /// # Expected behavior
+    /// The new put is stamped above the replayed one and wins.
+    #[test]
+    fn reopen_behind_wal_stays_ordered() {
+        let dir = TempDir::new().unwrap();
+        let (engine, before) = reopen_behind(dir.path(), memtable_only_config, false);
+
+        engine.put(b"k".to_vec(), b"v2".to_vec()).unwrap();
+        assert!(timestamp_of(&engine, b"k") > before);
+        assert_eq!(engine.get(b"k".to_vec()).unwrap(), Some(b"v2".to_vec()));
+    }
+
+    /// # Scenario
+    /// The engine reopens with a clock behind its SSTables.
+    ///
+    /// # Starting environment
+    /// Engine with a 1 KB write buffer whose SSTables hold `k` = `v1`,
+    /// stamped at a later time than the clock reads on reopen.
+    ///
+    /// # Actions
+    /// 1. Reopen; put `k` = `v2`; flush it; major compact.
+    ///
+    /// # Expected behavior
+    /// The new put is stamped above the flushed one and wins throughout.
+    #[test]
+    fn reopen_behind_sstables_stays_ordered() {
+        let dir = TempDir::new().unwrap();
+        let (engine, before) = reopen_behind(dir.path(), multi_sstable_config, true);
+
+        engine.put(b"k".to_vec(), b"v2".to_vec()).unwrap();
+        assert!(timestamp_of(&engine, b"k") > before);
+        engine.flush_all_frozen().unwrap();
+        engine.major_compact().unwrap();
+        assert_eq!(engine.get(b"k".to_vec()).unwrap(), Some(b"v2".to_vec()));
+        assert_eq!(
+            collect_scan(&engine, b"k", b"l"),
+            vec![(b"k".to_vec(), b"v2".to_vec())]
+        );
+    }
+
+    /// # Scenario
+    /// A clock stuck at the UNIX epoch.
+    ///
+    /// # Starting environment
+    /// Memtable-only engine on a mock clock reading 0.
+    ///
+    /// # Actions
+    /// 1. Put three keys.
+    ///
+    /// # Expected behavior
+    /// No panic; the keys are stamped with increasing timestamps.
+    #[test]
+    fn clock_at_epoch_stamps_increasing() {
+        let dir = TempDir::new().unwrap();
+        let clock = Arc::new(MockClock::new(0));
+        let engine =
+            Engine::open(dir.path(), clock_config(&clock, memtable_only_config())).unwrap();
+
+        for key in [b"a", b"b", b"c"] {
+            engine.put(key.to_vec(), b"v".to_vec()).unwrap();
+        }
+        let stamps: Vec<u64> = [b"a", b"b", b"c"]
+            .iter()
+            .map(|key| timestamp_of(&engine, *key))
+            .collect();
+        assert!(stamps.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
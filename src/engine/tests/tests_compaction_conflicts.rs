@@ -0,0 +1,209 @@
+//! Tests for the compaction coordinator (`Engine::compaction_job_stats`).
+//!
+//! ## Coverage areas
+//! - Claims on overlapping inputs conflict; releases ignore input order
+//! - A compaction whose inputs are claimed is skipped before it writes
+//!   anything, and counted
+//! - Two compactions started at once: the second is skipped while the
+//!   first builds its output
+//! - A compaction releases its claim once it finishes
+
+#[cfg(test)]
+mod tests {
+    use crate::compaction::CompactionJobStats;
+    use crate::compaction::coordinator::CompactionCoordinator;
+    use crate::compaction::stcs::MajorCompaction;
+    use crate::engine::SSTABLE_DIR;
+    use crate::engine::layout;
+    use crate::engine::tests::helpers::*;
+    use std::sync::Barrier;
+    use tempfile::TempDir;
+
+    /// # Scenario
+    /// Claim input sets that overlap and ones that do not.
+    ///
+    /// # Starting environment
+    /// Empty coordinator.
+    ///
+    /// # Actions
+    /// 1. Claim `[1, 2]`, then `[2, 3]`, then `[3, 4]`.
+    /// 2. Release `[2, 1]`, then claim `[1, 2]` again.
+    ///
+    /// # Expected behavior
+    /// `[2, 3]` fails naming `2` and counts as skipped; `[3, 4]` succeeds.
+    /// Releasing in another order frees `[1, 2]` for a new claim.
+    #[test]
+    fn overlapping_claims_conflict() {
+        let coordinator = CompactionCoordinator::default();
+
+        coordinator.claim(&[1, 2]).unwrap();
+        assert_eq!(coordinator.claim(&[2, 3]), Err(vec![2]));
+        coordinator.claim(&[3, 4]).unwrap();
+        assert_eq!(
+            coordinator.stats(),
+            CompactionJobStats {
+                running: 2,
+                skipped: 1,
+            }
+        );
+
+        coordinator.release(&[2, 1]);
+        assert_eq!(coordinator.stats().running, 1);
+        coordinator.claim(&[1, 2]).unwrap();
+
+        // Releasing a claim that is not held changes nothing.
+        coordinator.release(&[7]);
+        assert_eq!(coordinator.stats().running, 2);
+    }
+
+    /// # Scenario
+    /// A background major compaction runs while another job holds one of
+    /// its inputs.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables.
+    ///
+    /// # Actions
+    /// 1. Claim the oldest SSTable on behalf of another job.
+    /// 2. Run major compaction once, as a background job does.
+    /// 3. Release the claim and run major compaction again.
+    ///
+    /// # Expected behavior
+    /// The first run is skipped: no SSTable is replaced, no output file is
+    /// written, and `skipped` is 1. The second run compacts to a single
+    /// SSTable and leaves no claim behind.
+    #[test]
+    fn claimed_input_skips_compaction() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path();
+        let engine = engine_with_multi_sstables(path, 200, "key");
+        let before = engine.stats().unwrap().sstables_count;
+        let oldest = engine
+            .sstable_metadata()
+            .unwrap()
+            .iter()
+            .map(|meta| meta.id)
+            .min()
+            .unwrap();
+
+        engine.compactions.claim(&[oldest]).unwrap();
+        assert!(
+            !engine
+                .run_compaction(&MajorCompaction { progress: None })
+                .unwrap(),
+            "compaction must be skipped"
+        );
+        assert_eq!(engine.stats().unwrap().sstables_count, before);
+        assert_eq!(
+            layout::sstable_files(&path.join(SSTABLE_DIR))
+                .unwrap()
+                .len(),
+            before,
+            "a skipped compaction writes no output"
+        );
+        assert_eq!(
+            engine.compaction_job_stats(),
+            CompactionJobStats {
+                running: 1,
+                skipped: 1,
+            }
+        );
+
+        engine.compactions.release(&[oldest]);
+        assert!(engine.major_compact().unwrap());
+        assert_eq!(engine.stats().unwrap().sstables_count, 1);
+        assert_eq!(
+            engine.compaction_job_stats(),
+            CompactionJobStats {
+                running: 0,
+                skipped: 1,
+            }
+        );
+
+        for i in 0..200 {
+            let key = format!("key_{i:04}").into_bytes();
+            assert!(engine.get(key).unwrap().is_some(), "key_{i:04} missing");
+        }
+    }
+
+    /// # Scenario
+    /// A background major compaction starts while another one builds its
+    /// output.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables.
+    ///
+    /// # Actions
+    /// 1. Run major compaction on another thread, pausing it once its
+    ///    output is built.
+    /// 2. Run major compaction once on this thread, as a background job
+    ///    does.
+    /// 3. Let the first compaction finish.
+    ///
+    /// # Expected behavior
+    /// The second compaction is skipped at once, without waiting for the
+    /// first, and counted; the first commits a single SSTable holding
+    /// every key.
+    #[test]
+    fn concurrent_compaction_skipped() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(tmp.path(), 200, "key");
+        let paused = Paused(MajorCompaction { progress: None }, Barrier::new(2));
+
+        std::thread::scope(|s| {
+            let compaction = s.spawn(|| engine.run_compaction(&paused));
+            paused.1.wait();
+
+            assert!(
+                !engine
+                    .run_compaction(&MajorCompaction { progress: None })
+                    .unwrap(),
+                "compaction must be skipped"
+            );
+            assert_eq!(
+                engine.compaction_job_stats(),
+                CompactionJobStats {
+                    running: 1,
+                    skipped: 1,
+                }
+            );
+
+            paused.1.wait();
+            assert!(compaction.join().unwrap().unwrap());
+        });
+
+        assert_eq!(engine.stats().unwrap().sstables_count, 1);
+        assert_eq!(engine.compaction_job_stats().running, 0);
+        for i in 0..200 {
+            let key = format!("key_{i:04}").into_bytes();
+            assert!(engine.get(key).unwrap().is_some(), "key_{i:04} missing");
+        }
+    }
+
+    /// # Scenario
+    /// Minor and major compactions run to completion.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables.
+    ///
+    /// # Actions
+    /// 1. Run minor compaction, then major compaction.
+    ///
+    /// # Expected behavior
+    /// No claim is left behind and nothing is skipped.
+    #[test]
+    fn finished_compaction_releases_claim() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(tmp.path(), 200, "key");
+
+        engine.minor_compact().unwrap();
+        engine.major_compact().unwrap();
+        assert_eq!(engine.compaction_job_stats(), CompactionJobStats::default());
+    }
+}
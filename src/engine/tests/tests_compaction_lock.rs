@@ -0,0 +1,254 @@
+//! Tests for compactions that build their output without the engine lock.
+//!
+//! ## Coverage areas
+//! - Writes and flushes complete while a compaction builds its output
+//! - A compaction whose snapshot lost an SSTable meanwhile throws its
+//!   output away
+//! - A major compaction that loses a race with another one runs again
+//! - A compaction whose snapshot is already stale when it begins claims
+//!   nothing and writes nothing
+
+#[cfg(test)]
+mod tests {
+    use crate::compaction::CompactionJobStats;
+    use crate::compaction::stcs::{MajorCompaction, MinorCompaction};
+    use crate::compaction::{CompactionError, CompactionStrategy};
+    use crate::engine::SSTABLE_DIR;
+    use crate::engine::layout;
+    use crate::engine::tests::helpers::*;
+    use crate::engine::version_pins::NO_HORIZON;
+    use crate::manifest::ManifestError;
+    use std::sync::{Arc, Barrier};
+    use tempfile::TempDir;
+
+    /// # Scenario
+    /// Writes go on while a major compaction builds its output.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables.
+    ///
+    /// # Actions
+    /// 1. Run major compaction on another thread, pausing it once its
+    ///    output is built.
+    /// 2. Write new keys and flush them to an SSTable.
+    /// 3. Let the compaction finish.
+    ///
+    /// # Expected behavior
+    /// The writes and the flush complete while the compaction is paused.
+    /// The compaction then commits: the merged SSTable and the flushed one
+    /// are left, and every key reads back.
+    #[test]
+    fn writes_proceed_while_compaction_builds() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(tmp.path(), 200, "key");
+        let paused = Paused(MajorCompaction { progress: None }, Barrier::new(2));
+
+        let flushed = std::thread::scope(|s| {
+            let compaction = s.spawn(|| engine.run_compaction(&paused));
+            paused.1.wait();
+
+            let before = engine.stats().unwrap().sstables_count;
+            for i in 0..100 {
+                let key = format!("new_{i:04}").into_bytes();
+                engine.put(key, b"value".to_vec()).unwrap();
+            }
+            engine.flush_all_frozen().unwrap();
+            let flushed = engine.stats().unwrap().sstables_count - before;
+            assert!(flushed > 0);
+
+            paused.1.wait();
+            assert!(compaction.join().unwrap().unwrap());
+            flushed
+        });
+
+        assert_eq!(engine.compaction_job_stats().running, 0);
+        assert_eq!(engine.stats().unwrap().sstables_count, flushed + 1);
+        for i in 0..200 {
+            let key = format!("key_{i:04}").into_bytes();
+            assert!(engine.get(key).unwrap().is_some(), "key_{i:04} missing");
+        }
+        for i in 0..100 {
+            let key = format!("new_{i:04}").into_bytes();
+            assert!(engine.get(key).unwrap().is_some(), "new_{i:04} missing");
+        }
+    }
+
+    /// # Scenario
+    /// The engine is cleared while a major compaction builds its output.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables.
+    ///
+    /// # Actions
+    /// 1. Run major compaction on another thread, pausing it once its
+    ///    output is built.
+    /// 2. Clear the engine.
+    /// 3. Let the compaction finish, then reopen.
+    ///
+    /// # Expected behavior
+    /// The compaction reports nothing done, counts as skipped, and deletes
+    /// its output: no SSTable is left, in memory or on disk, and no key
+    /// reappears after the reopen.
+    #[test]
+    fn clear_during_build_discards_output() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path();
+        let engine = engine_with_multi_sstables(path, 200, "key");
+        let paused = Paused(MajorCompaction { progress: None }, Barrier::new(2));
+
+        std::thread::scope(|s| {
+            let compaction = s.spawn(|| engine.run_compaction(&paused));
+            paused.1.wait();
+            engine.clear().unwrap();
+            paused.1.wait();
+            assert!(!compaction.join().unwrap().unwrap());
+        });
+
+        assert_eq!(engine.stats().unwrap().sstables_count, 0);
+        assert_eq!(
+            engine.compaction_job_stats(),
+            CompactionJobStats {
+                running: 0,
+                skipped: 1,
+            }
+        );
+        assert!(collect_scan(&engine, b"key_", b"key_\xff").is_empty());
+        engine.close().unwrap();
+
+        assert!(
+            layout::sstable_files(&path.join(SSTABLE_DIR))
+                .unwrap()
+                .is_empty(),
+            "the discarded output is deleted"
+        );
+        let engine = reopen(path);
+        assert!(collect_scan(&engine, b"key_", b"key_\xff").is_empty());
+    }
+
+    /// # Scenario
+    /// A major compaction starts while a minor compaction holds its
+    /// inputs.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables of one size bucket.
+    ///
+    /// # Actions
+    /// 1. Run minor compaction on another thread, pausing it once its
+    ///    output is built.
+    /// 2. Write and flush new keys, so the major compaction has an
+    ///    SSTable to merge with the minor one's output.
+    /// 3. Start `major_compact` on a third thread and wait until it has
+    ///    been refused the claimed inputs.
+    /// 4. Let the minor compaction finish.
+    ///
+    /// # Expected behavior
+    /// Both compactions report work done: the major one runs again after
+    /// the refusal rather than returning `false`. One SSTable is left and
+    /// every key reads back.
+    #[test]
+    fn major_compaction_retries_after_conflict() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(tmp.path(), 200, "key");
+        let paused = Paused(MinorCompaction, Barrier::new(2));
+
+        std::thread::scope(|s| {
+            let minor = s.spawn(|| engine.run_compaction(&paused));
+            paused.1.wait();
+
+            for i in 0..100 {
+                let key = format!("new_{i:04}").into_bytes();
+                engine.put(key, b"value".to_vec()).unwrap();
+            }
+            engine.flush_all_frozen().unwrap();
+            let major = s.spawn(|| engine.major_compact());
+            while engine.compaction_job_stats().skipped == 0 {
+                std::thread::yield_now();
+            }
+
+            paused.1.wait();
+            assert!(minor.join().unwrap().unwrap());
+            assert!(major.join().unwrap().unwrap());
+        });
+
+        assert_eq!(engine.stats().unwrap().sstables_count, 1);
+        for i in 0..200 {
+            let key = format!("key_{i:04}").into_bytes();
+            assert!(engine.get(key).unwrap().is_some(), "key_{i:04} missing");
+        }
+        for i in 0..100 {
+            let key = format!("new_{i:04}").into_bytes();
+            assert!(engine.get(key).unwrap().is_some(), "new_{i:04} missing");
+        }
+    }
+
+    /// # Scenario
+    /// A compaction begins on a snapshot of SSTables that another
+    /// compaction has already replaced.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables.
+    ///
+    /// # Actions
+    /// 1. Take a snapshot of the SSTables.
+    /// 2. Run major compaction.
+    /// 3. Run major compaction on the stale snapshot.
+    ///
+    /// # Expected behavior
+    /// The second compaction fails with a conflict naming the replaced
+    /// SSTables and counts as skipped. It leaves no claim and no pending compaction behind, and
+    /// writes no file.
+    #[test]
+    fn stale_snapshot_claims_nothing() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path();
+        let engine = engine_with_multi_sstables(path, 200, "key");
+        let (stale, manifest, config) = {
+            let inner = engine.read_lock().unwrap();
+            (
+                inner.sstables.clone(),
+                Arc::clone(&inner.manifest),
+                inner.config.clone(),
+            )
+        };
+        assert!(engine.major_compact().unwrap());
+
+        let result = MajorCompaction { progress: None }.compact(
+            &stale,
+            &manifest,
+            &path.to_string_lossy(),
+            &config,
+            NO_HORIZON,
+        );
+        let Err(CompactionError::Manifest(ManifestError::CompactionConflict(mut gone))) = result
+        else {
+            panic!("expected a conflict");
+        };
+        let mut stale_ids: Vec<u64> = stale.iter().map(|sst| sst.id()).collect();
+        stale_ids.sort_unstable();
+        gone.sort_unstable();
+        assert_eq!(gone, stale_ids);
+
+        assert_eq!(
+            engine.compaction_job_stats(),
+            CompactionJobStats {
+                running: 0,
+                skipped: 1,
+            }
+        );
+        assert!(manifest.pending_compactions().unwrap().is_empty());
+        assert_eq!(
+            layout::sstable_files(&path.join(SSTABLE_DIR))
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
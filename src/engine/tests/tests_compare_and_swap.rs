@@ -0,0 +1,133 @@
+//! Tests for the conditional write (`Engine::compare_and_swap`).
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{CasOutcome, Engine};
+    use std::sync::Arc;
+    use std::thread;
+    use tempfile::TempDir;
+
+    /// # Scenario
+    /// The expected value is compared with the visible value, wherever it
+    /// lives.
+    ///
+    /// # Starting environment
+    /// Engine with `cas_0000..cas_0199` spread across several SSTables.
+    ///
+    /// # Actions
+    /// 1. Swap an SSTable-resident key with the wrong, then the right
+    ///    expected value.
+    /// 2. Insert a missing key with `expected = None`, twice.
+    /// 3. Delete a key through a swap; range-delete another and swap it
+    ///    back in with `expected = None`.
+    ///
+    /// # Expected behavior
+    /// Mismatches return the visible value (or `None`) and write nothing;
+    /// matches apply the put or delete. Range-deleted keys count as
+    /// absent.
+    #[test]
+    fn cas_compares_visible_value() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 200, "cas");
+        let key = b"cas_0042".to_vec();
+        let stored = b"value_with_some_padding_0042".to_vec();
+
+        let (outcome, _) = engine
+            .compare_and_swap(
+                key.clone(),
+                Some(b"stale".as_slice()),
+                Some(b"new".to_vec()),
+            )
+            .unwrap();
+        assert_eq!(outcome, CasOutcome::Mismatch(Some(stored.clone())));
+        assert_eq!(engine.get(key.clone()).unwrap(), Some(stored.clone()));
+
+        let (outcome, _) = engine
+            .compare_and_swap(key.clone(), Some(stored.as_slice()), Some(b"new".to_vec()))
+            .unwrap();
+        assert_eq!(outcome, CasOutcome::Swapped);
+        assert_eq!(engine.get(key.clone()).unwrap(), Some(b"new".to_vec()));
+
+        let (outcome, _) = engine
+            .compare_and_swap(b"cas_new".to_vec(), None, Some(b"1".to_vec()))
+            .unwrap();
+        assert_eq!(outcome, CasOutcome::Swapped);
+        let (outcome, _) = engine
+            .compare_and_swap(b"cas_new".to_vec(), None, Some(b"2".to_vec()))
+            .unwrap();
+        assert_eq!(outcome, CasOutcome::Mismatch(Some(b"1".to_vec())));
+
+        let (outcome, _) = engine
+            .compare_and_swap(key.clone(), Some(b"new".as_slice()), None)
+            .unwrap();
+        assert_eq!(outcome, CasOutcome::Swapped);
+        assert_eq!(engine.get(key.clone()).unwrap(), None);
+
+        engine
+            .delete_range(b"cas_0100".to_vec(), b"cas_0110".to_vec())
+            .unwrap();
+        let (outcome, _) = engine
+            .compare_and_swap(b"cas_0105".to_vec(), None, Some(b"back".to_vec()))
+            .unwrap();
+        assert_eq!(outcome, CasOutcome::Swapped);
+        assert_eq!(
+            engine.get(b"cas_0105".to_vec()).unwrap(),
+            Some(b"back".to_vec())
+        );
+    }
+
+    /// # Scenario
+    /// Concurrent read-modify-write loops built on compare-and-swap lose
+    /// no update.
+    ///
+    /// # Starting environment
+    /// Engine with a 1 KiB write buffer, so memtables freeze mid-test.
+    ///
+    /// # Actions
+    /// 1. Four threads each increment a shared counter 100 times, retrying
+    ///    on mismatch, while writing an unrelated key per increment.
+    ///
+    /// # Expected behavior
+    /// The counter ends at 400.
+    #[test]
+    fn cas_serializes_concurrent_increments() {
+        let dir = TempDir::new().unwrap();
+        let engine = Arc::new(Engine::open(dir.path(), multi_sstable_config()).unwrap());
+
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let engine = Arc::clone(&engine);
+                thread::spawn(move || {
+                    for i in 0..100 {
+                        loop {
+                            let current = engine.get(b"counter".to_vec()).unwrap();
+                            let n = current
+                                .as_deref()
+                                .map_or(0, |v| u32::from_le_bytes(v.try_into().unwrap()));
+                            let next = (n + 1).to_le_bytes().to_vec();
+                            let (outcome, _) = engine
+                                .compare_and_swap(
+                                    b"counter".to_vec(),
+                                    current.as_deref(),
+                                    Some(next),
+                                )
+                                .unwrap();
+                            if outcome == CasOutcome::Swapped {
+                                break;
+                            }
+                        }
+                        let key = format!("filler_{t}_{i:04}").into_bytes();
+                        engine.put(key, b"filler_value".to_vec()).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let value = engine.get(b"counter".to_vec()).unwrap().unwrap();
+        assert_eq!(u32::from_le_bytes(value.try_into().unwrap()), 400);
+    }
+}
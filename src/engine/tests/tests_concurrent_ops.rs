@@ -2,9 +2,9 @@
 //!
 //! These tests verify that concurrent readers (`get`, `scan`) work
 //! correctly while the engine is actively flushing frozen memtables
-//! or running compaction. The engine uses an `Arc<RwLock<EngineInner>>`
-//! so readers and writers contend on the same lock. These tests prove
-//! that readers always see a consistent snapshot and never observe
+//! or running compaction. Readers take no engine lock; they load the
+//! superversion published by each flush and compaction. These tests
+//! prove that readers always see a consistent snapshot and never observe
 //! partial state.
 //!
 //! ## See also
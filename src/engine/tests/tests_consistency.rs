@@ -0,0 +1,167 @@
+//! Tests for the manifest consistency check (`Engine::check_consistency`
+//! and the same check in `Engine::open`).
+//!
+//! ## Coverage areas
+//! - A healthy engine with SSTables and frozen memtables passes
+//! - A deleted SSTable is reported live and fails the next open
+//! - A damaged SSTable header fails open as unreadable
+//! - A deleted frozen WAL fails open instead of being recreated empty
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{ConsistencyIssue, Engine, EngineError, MEMTABLE_DIR};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use tempfile::TempDir;
+
+    /// Opens the engine and returns the issues that made it fail.
+    fn open_issues(path: &Path) -> Vec<ConsistencyIssue> {
+        match Engine::open(path, multi_sstable_config()) {
+            Err(EngineError::Inconsistent(report)) => report.issues,
+            Err(e) => panic!("unexpected error: {e}"),
+            Ok(_) => panic!("open succeeded on an inconsistent database"),
+        }
+    }
+
+    /// Returns the memtable WAL files, oldest first.
+    fn wal_files(path: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = fs::read_dir(path.join(MEMTABLE_DIR))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// # Scenario
+    /// A healthy engine passes the check.
+    ///
+    /// # Starting environment
+    /// Several SSTables plus frozen memtables that were never flushed.
+    ///
+    /// # Actions
+    /// 1. Call `check_consistency`.
+    ///
+    /// # Expected behavior
+    /// No issues; every SSTable and frozen WAL was checked.
+    #[test]
+    fn consistency_healthy_engine() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 300, "ok");
+        for i in 0..100u32 {
+            let key = format!("fz_{i:04}").into_bytes();
+            engine.put(key, b"value".to_vec()).unwrap();
+        }
+        let stats = engine.stats().unwrap();
+        assert!(stats.frozen_count > 0);
+
+        let report = engine.check_consistency().unwrap();
+        assert!(report.is_consistent(), "{report}");
+        assert_eq!(report.sstables_checked, stats.sstables_count);
+        assert_eq!(report.frozen_wals_checked, stats.frozen_count);
+    }
+
+    /// # Scenario
+    /// An SSTable deleted behind the engine's back is detected.
+    ///
+    /// # Starting environment
+    /// Open engine with several SSTables.
+    ///
+    /// # Actions
+    /// 1. Delete the newest SSTable file.
+    /// 2. Call `check_consistency`; drop the engine.
+    /// 3. Reopen.
+    ///
+    /// # Expected behavior
+    /// The live check and the reopen both report exactly that SSTable as
+    /// missing.
+    #[test]
+    fn consistency_missing_sstable() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 300, "ms");
+        let victim = engine.sstable_metadata().unwrap().remove(0);
+        fs::remove_file(&victim.path).unwrap();
+
+        let expected = vec![ConsistencyIssue::MissingSSTable {
+            id: victim.id,
+            path: victim.path.clone(),
+        }];
+        assert_eq!(engine.check_consistency().unwrap().issues, expected);
+        drop(engine);
+
+        assert_eq!(open_issues(dir.path()), expected);
+    }
+
+    /// # Scenario
+    /// An SSTable whose header no longer parses fails open.
+    ///
+    /// # Starting environment
+    /// Several SSTables, engine closed.
+    ///
+    /// # Actions
+    /// 1. Overwrite the first bytes of the oldest SSTable.
+    /// 2. Reopen.
+    ///
+    /// # Expected behavior
+    /// Open fails with a single `UnreadableSSTable` issue for that table.
+    #[test]
+    fn consistency_unreadable_sstable() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 300, "us");
+        let victim = engine.sstable_metadata().unwrap().pop().unwrap();
+        engine.close().unwrap();
+        drop(engine);
+
+        let mut bytes = fs::read(&victim.path).unwrap();
+        bytes[..8].fill(0xAB);
+        fs::write(&victim.path, bytes).unwrap();
+
+        let issues = open_issues(dir.path());
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(
+            &issues[0],
+            ConsistencyIssue::UnreadableSSTable { id, .. } if *id == victim.id
+        ));
+    }
+
+    /// # Scenario
+    /// A missing frozen WAL fails open rather than silently losing its
+    /// writes.
+    ///
+    /// # Starting environment
+    /// Small-buffer engine with frozen memtables, dropped without close
+    /// so they stay unflushed.
+    ///
+    /// # Actions
+    /// 1. Delete the oldest WAL file.
+    /// 2. Reopen.
+    ///
+    /// # Expected behavior
+    /// Open fails with `MissingFrozenWal` for that WAL, and the file is not
+    /// recreated.
+    #[test]
+    fn consistency_missing_frozen_wal() {
+        let dir = TempDir::new().unwrap();
+        {
+            let engine = Engine::open(dir.path(), small_buffer_config()).unwrap();
+            for i in 0..50u32 {
+                let key = format!("fw_{i:04}").into_bytes();
+                engine.put(key, b"value".to_vec()).unwrap();
+            }
+            assert!(engine.stats().unwrap().frozen_count > 1);
+        }
+
+        let oldest = wal_files(dir.path()).remove(0);
+        fs::remove_file(&oldest).unwrap();
+
+        let issues = open_issues(dir.path());
+        assert!(
+            issues.iter().any(
+                |i| matches!(i, ConsistencyIssue::MissingFrozenWal { path, .. } if *path == oldest)
+            ),
+            "{issues:?}"
+        );
+        assert!(!oldest.exists(), "missing frozen WAL was recreated");
+    }
+}
@@ -10,6 +10,10 @@
 //! 2. **Old SSTables still live** — since manifests are only updated
 //!    after the new SSTable is fully written, old SSTables are never
 //!    removed prematurely.
+//! 3. **Pending compaction** — the manifest recorded the intent of the
+//!    compaction before its output was built. On recovery one without
+//!    its commit record is rolled back (output deleted), one with it is
+//!    rolled forward (input files deleted).
 //!
 //! These tests verify that:
 //! - All committed data is recoverable after a crash during compaction.
@@ -24,11 +28,102 @@
 #[allow(non_snake_case)]
 mod tests {
     use crate::engine::tests::helpers::*;
-    use crate::engine::{Engine, SSTABLE_DIR};
-    use std::fs::File;
+    use crate::engine::{Engine, MANIFEST_DIR, SSTABLE_DIR, layout};
+    use crate::manifest::{Manifest, ManifestSstEntry};
+    use std::fs::{self, File};
     use std::io::Write;
+    use std::path::Path;
     use tempfile::TempDir;
 
+    /// Steps of a compaction, after each of which a crash can occur.
+    #[derive(Clone, Copy, PartialEq, PartialOrd)]
+    enum CrashAfter {
+        /// Intent recorded, output not written.
+        Intent,
+        /// Output written, commit not recorded.
+        Build,
+        /// Commit recorded, input not deleted.
+        Commit,
+        /// Input deleted, compaction not finished.
+        Cleanup,
+    }
+
+    /// Creates an engine with several SSTables, closes it, then replays a
+    /// compaction of its oldest SSTable directly against the manifest up
+    /// to `crash`. The output is a copy of the input under a new ID, so
+    /// either one holds the same data.
+    ///
+    /// Returns the input and output entries.
+    fn interrupt_compaction(
+        path: &Path,
+        crash: CrashAfter,
+    ) -> (ManifestSstEntry, ManifestSstEntry) {
+        {
+            let engine = engine_with_multi_sstables(path, 200, "key");
+            assert!(engine.stats().unwrap().sstables_count >= 2);
+            engine.close().unwrap();
+        }
+
+        let manifest = Manifest::open(path.join(MANIFEST_DIR))
+            .unwrap()
+            .with_root(path);
+        let input = manifest
+            .get_sstables()
+            .unwrap()
+            .into_iter()
+            .min_by_key(|e| e.id)
+            .unwrap();
+        let output_id = manifest.allocate_sst_id().unwrap();
+        let output = ManifestSstEntry {
+            id: output_id,
+            path: layout::sstable_path(path, &multi_sstable_config(), output_id),
+        };
+
+        manifest
+            .begin_compaction(vec![input.clone()], vec![output.clone()])
+            .unwrap();
+        if crash >= CrashAfter::Build {
+            fs::copy(&input.path, &output.path).unwrap();
+        }
+        if crash >= CrashAfter::Commit {
+            manifest
+                .apply_compaction(vec![output.clone()], vec![input.id])
+                .unwrap();
+            manifest.checkpoint().unwrap();
+        }
+        if crash >= CrashAfter::Cleanup {
+            fs::remove_file(&input.path).unwrap();
+        }
+        (input, output)
+    }
+
+    /// Reopens the engine after [`interrupt_compaction`], checks that
+    /// every key survived and that a second reopen finds nothing left to
+    /// settle, and returns the rolled-forward and rolled-back counts.
+    fn reopen_after_interrupted_compaction(path: &Path) -> (usize, usize) {
+        let counts = {
+            let engine = Engine::open(path, multi_sstable_config()).unwrap();
+            for i in 0..200 {
+                let key = format!("key_{i:04}").into_bytes();
+                assert!(engine.get(key).unwrap().is_some(), "key_{i:04} missing");
+            }
+            let report = engine.recovery_report();
+            let counts = (
+                report.compactions_rolled_forward,
+                report.compactions_rolled_back,
+            );
+            engine.close().unwrap();
+            counts
+        };
+
+        let engine = Engine::open(path, multi_sstable_config()).unwrap();
+        let report = engine.recovery_report();
+        assert_eq!(report.compactions_rolled_forward, 0);
+        assert_eq!(report.compactions_rolled_back, 0);
+        assert!(report.orphan_files_removed.is_empty());
+        counts
+    }
+
     // ================================================================
     // 1. Recovery after crash during minor compaction
     // ================================================================
@@ -313,4 +408,178 @@ mod tests {
             );
         }
     }
+
+    // ================================================================
+    // 5. Crash after the intent, before the output is built
+    // ================================================================
+
+    /// # Scenario
+    /// The manifest recorded the intent of a compaction, then the
+    /// process crashed before writing any output.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables, closed cleanly.
+    ///
+    /// # Actions
+    /// 1. Record the intent of compacting the oldest SSTable.
+    /// 2. Reopen, then reopen again.
+    ///
+    /// # Expected behavior
+    /// The compaction is rolled back: the input stays live, no output
+    /// exists, data is intact, and the second reopen settles nothing.
+    #[test]
+    fn memtable_sstable__crash_after_compaction_intent_rolls_back() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path();
+
+        let (input, output) = interrupt_compaction(path, CrashAfter::Intent);
+        assert_eq!(reopen_after_interrupted_compaction(path), (0, 1));
+
+        assert!(input.path.exists(), "input must stay live");
+        assert!(!output.path.exists());
+    }
+
+    // ================================================================
+    // 6. Crash after the output is built, before the commit
+    // ================================================================
+
+    /// # Scenario
+    /// A compaction wrote its output, then the process crashed before
+    /// the manifest recorded the commit.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables, closed cleanly.
+    ///
+    /// # Actions
+    /// 1. Record the intent of compacting the oldest SSTable.
+    /// 2. Write the output file.
+    /// 3. Reopen, then reopen again.
+    ///
+    /// # Expected behavior
+    /// The compaction is rolled back: the output is deleted as part of
+    /// it rather than as an orphan, and the input stays live.
+    #[test]
+    fn memtable_sstable__crash_before_compaction_commit_rolls_back() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path();
+
+        let (input, output) = interrupt_compaction(path, CrashAfter::Build);
+        assert!(output.path.exists());
+        assert_eq!(reopen_after_interrupted_compaction(path), (0, 1));
+
+        assert!(input.path.exists(), "input must stay live");
+        assert!(!output.path.exists(), "uncommitted output must be deleted");
+    }
+
+    // ================================================================
+    // 7. Crash after the commit, before the inputs are deleted
+    // ================================================================
+
+    /// # Scenario
+    /// The manifest recorded the commit of a compaction, then the
+    /// process crashed before deleting the input file.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables, closed cleanly.
+    ///
+    /// # Actions
+    /// 1. Record the intent, write the output, record the commit.
+    /// 2. Reopen, then reopen again.
+    ///
+    /// # Expected behavior
+    /// The compaction is rolled forward: the input file is deleted, the
+    /// output is live, and data is intact.
+    #[test]
+    fn memtable_sstable__crash_after_compaction_commit_rolls_forward() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path();
+
+        let (input, output) = interrupt_compaction(path, CrashAfter::Commit);
+        assert!(input.path.exists());
+        assert_eq!(reopen_after_interrupted_compaction(path), (1, 0));
+
+        assert!(!input.path.exists(), "committed input must be deleted");
+        assert!(output.path.exists(), "output must be live");
+    }
+
+    // ================================================================
+    // 8. Crash after the inputs are deleted, before the finish record
+    // ================================================================
+
+    /// # Scenario
+    /// A compaction committed and deleted its input, then the process
+    /// crashed before the manifest recorded that it finished.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables, closed cleanly.
+    ///
+    /// # Actions
+    /// 1. Record the intent, write the output, record the commit, delete
+    ///    the input file.
+    /// 2. Reopen, then reopen again.
+    ///
+    /// # Expected behavior
+    /// The compaction is rolled forward with nothing left to delete; the
+    /// missing input file is not an error.
+    #[test]
+    fn memtable_sstable__crash_before_compaction_finish_rolls_forward() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path();
+
+        let (input, output) = interrupt_compaction(path, CrashAfter::Cleanup);
+        assert_eq!(reopen_after_interrupted_compaction(path), (1, 0));
+
+        assert!(!input.path.exists());
+        assert!(output.path.exists(), "output must be live");
+    }
+
+    // ================================================================
+    // 9. A completed compaction leaves nothing pending
+    // ================================================================
+
+    /// # Scenario
+    /// Major compaction runs to completion; the engine is dropped
+    /// without closing.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables.
+    ///
+    /// # Actions
+    /// 1. Major compact, drop the engine.
+    /// 2. Inspect the manifest, reopen.
+    ///
+    /// # Expected behavior
+    /// The manifest holds no pending compaction and recovery settles
+    /// none.
+    #[test]
+    fn memtable_sstable__completed_compaction_leaves_nothing_pending() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path();
+
+        {
+            let engine = engine_with_multi_sstables(path, 200, "key");
+            assert!(engine.major_compact().unwrap());
+        }
+
+        {
+            let manifest = Manifest::open(path.join(MANIFEST_DIR)).unwrap();
+            assert!(manifest.pending_compactions().unwrap().is_empty());
+        }
+
+        let engine = Engine::open(path, multi_sstable_config()).unwrap();
+        let report = engine.recovery_report();
+        assert_eq!(report.compactions_rolled_forward, 0);
+        assert_eq!(report.compactions_rolled_back, 0);
+        assert_eq!(engine.stats().unwrap().sstables_count, 1);
+    }
 }
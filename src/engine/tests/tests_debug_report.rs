@@ -0,0 +1,158 @@
+//! Tests for the compaction debugging report (`Engine::debug_report`).
+//!
+//! ## Coverage areas
+//! - Every SSTable appears once, in the bucket that lists it
+//! - Minor and major candidates match the bucket and count thresholds
+//! - Key-range overlaps are reported only for overlapping SSTables
+//! - The tombstone candidate honours the ratio and age thresholds
+//! - DOT output has a node per SSTable and an edge per overlap
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{DebugReport, Engine, EngineConfig};
+    use tempfile::TempDir;
+
+    /// # Scenario
+    /// SSTables, buckets, and minor/major candidates agree.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables of sequentially written keys.
+    ///
+    /// # Actions
+    /// 1. Build the report.
+    ///
+    /// # Expected behavior
+    /// Each SSTable is listed once and sits in the bucket naming it; the
+    /// minor candidate exists exactly when a bucket reaches
+    /// `min_threshold`; major would merge every SSTable; disjoint key
+    /// ranges report no overlap.
+    #[test]
+    fn debug_report_buckets_and_candidates() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 300, "d");
+        let report = engine.debug_report(0).unwrap();
+
+        assert_eq!(report.partition, 0);
+        assert_eq!(
+            report.sstables.len(),
+            engine.stats().unwrap().sstables_count
+        );
+        let bucketed: usize = report.buckets.iter().map(|b| b.sstable_ids.len()).sum();
+        assert_eq!(bucketed, report.sstables.len());
+        for sst in &report.sstables {
+            assert!(report.buckets[sst.bucket].sstable_ids.contains(&sst.id));
+        }
+
+        let c = &report.candidates;
+        let bucket_full = report
+            .buckets
+            .iter()
+            .any(|b| b.sstable_ids.len() >= c.min_threshold);
+        assert_eq!(c.minor.is_some(), bucket_full);
+        let all_ids: Vec<u64> = report.sstables.iter().map(|s| s.id).collect();
+        assert_eq!(c.major.as_ref(), Some(&all_ids));
+        assert!(report.overlaps.is_empty(), "{:?}", report.overlaps);
+    }
+
+    /// # Scenario
+    /// Rewriting an existing key range produces overlapping SSTables.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables of sequentially written keys.
+    ///
+    /// # Actions
+    /// 1. Overwrite the first and last key, then a middle key until the
+    ///    memtable freezes; flush.
+    /// 2. Build the report and render it as DOT.
+    ///
+    /// # Expected behavior
+    /// The new SSTable overlaps every older one, each overlap is drawn as
+    /// a dashed edge, and every SSTable has a node.
+    #[test]
+    fn debug_report_overlaps_and_dot() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 300, "o");
+        engine.put(b"o_0000".to_vec(), b"new".to_vec()).unwrap();
+        engine.put(b"o_0299".to_vec(), b"new".to_vec()).unwrap();
+        let froze = (0..1000).any(|_| engine.put(b"o_0100".to_vec(), vec![b'x'; 64]).unwrap());
+        assert!(froze);
+        engine.flush_all_frozen().unwrap();
+
+        let report = engine.debug_report(0).unwrap();
+        let newest = report.sstables[0].id;
+        let overlapping = report
+            .overlaps
+            .iter()
+            .filter(|(a, b)| *a == newest || *b == newest)
+            .count();
+        assert_eq!(overlapping, report.sstables.len() - 1);
+
+        let dot = DebugReport {
+            partitions: vec![report.clone()],
+        }
+        .to_dot();
+        assert!(dot.starts_with("digraph lsm {"));
+        for sst in &report.sstables {
+            assert!(dot.contains(&format!("p0_s{} [", sst.id)));
+        }
+        assert_eq!(dot.matches("style=dashed").count(), report.overlaps.len());
+    }
+
+    /// # Scenario
+    /// The tombstone candidate depends on both ratio and age.
+    ///
+    /// # Starting environment
+    /// 128-byte write buffer; keys written then deleted and flushed.
+    ///
+    /// # Actions
+    /// 1. Build the report with `tombstone_compaction_interval: 0`.
+    /// 2. Reopen with `tombstone_compaction_interval: 3600` and build it
+    ///    again.
+    ///
+    /// # Expected behavior
+    /// The first report names the SSTable with the highest tombstone
+    /// ratio; the second names none, since every SSTable is too young.
+    #[test]
+    fn debug_report_tombstone_candidate() {
+        let dir = TempDir::new().unwrap();
+        let config = EngineConfig {
+            tombstone_compaction_interval: 0,
+            ..small_buffer_config()
+        };
+        {
+            let engine = Engine::open(dir.path(), config.clone()).unwrap();
+            for i in 0..20u32 {
+                let key = format!("t_{i:04}").into_bytes();
+                engine.put(key.clone(), b"value".to_vec()).unwrap();
+                engine.delete(key).unwrap();
+            }
+            engine.flush_all_frozen().unwrap();
+
+            let report = engine.debug_report(0).unwrap();
+            let best = report
+                .sstables
+                .iter()
+                .max_by(|a, b| a.tombstone_ratio.total_cmp(&b.tombstone_ratio))
+                .unwrap();
+            assert!(best.tombstone_ratio >= config.tombstone_ratio_threshold);
+            assert!(report.candidates.tombstone.is_some());
+            let candidate = report
+                .sstables
+                .iter()
+                .find(|s| Some(s.id) == report.candidates.tombstone)
+                .unwrap();
+            assert_eq!(candidate.tombstone_ratio, best.tombstone_ratio);
+            engine.close().unwrap();
+        }
+
+        let config = EngineConfig {
+            tombstone_compaction_interval: 3600,
+            ..config
+        };
+        let engine = Engine::open(dir.path(), config).unwrap();
+        let report = engine.debug_report(0).unwrap();
+        assert_eq!(report.candidates.tombstone, None);
+        assert_eq!(report.candidates.tombstone_compaction_interval, 3600);
+    }
+}
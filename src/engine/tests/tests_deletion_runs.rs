@@ -0,0 +1,136 @@
+//! Tests for deletion-triggered compaction
+//! (`Engine::hint_deletion_compaction`).
+//!
+//! ## Coverage areas
+//! - Disabled by a zero threshold
+//! - No hint while scans step over shorter runs than the threshold
+//! - A hint over a tombstone-heavy SSTable and the older SSTables it
+//!   overlaps once a scan steps over a long run, compacted by
+//!   `hinted_compact` into fewer tombstones
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, EngineConfig};
+    use crate::range_lock::RangeLocks;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Opens an engine with the given threshold, writes 300 keys, deletes
+    /// the first 200 — queue-style, from the head — and flushes.
+    fn engine_with_deleted_head(path: &Path, threshold: usize) -> Engine {
+        let engine = Engine::open(
+            path,
+            EngineConfig {
+                deletion_run_compaction_threshold: threshold,
+                ..multi_sstable_config()
+            },
+        )
+        .unwrap();
+        for i in 0..300u32 {
+            let key = format!("q_{i:04}").into_bytes();
+            let value = format!("value_with_some_padding_{i:04}").into_bytes();
+            engine.put(key, value).unwrap();
+        }
+        for i in 0..200u32 {
+            engine.delete(format!("q_{i:04}").into_bytes()).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+        engine
+    }
+
+    /// Scans the whole queue and checks that only the tail is left.
+    fn scan_all(engine: &Engine) {
+        let keys: Vec<_> = engine.scan(b"q_", b"q_~").unwrap().collect();
+        assert_eq!(keys.len(), 100);
+        assert_eq!(keys[0].0, b"q_0200");
+    }
+
+    /// Total point tombstones in the engine's SSTables.
+    fn tombstones(engine: &Engine) -> u64 {
+        engine
+            .sstable_metadata()
+            .unwrap()
+            .iter()
+            .map(|meta| meta.tombstone_count)
+            .sum()
+    }
+
+    /// # Scenario
+    /// With the threshold at `0`, tombstone runs are ignored.
+    ///
+    /// # Actions
+    /// 1. Scan over the deleted head, check for a hint.
+    ///
+    /// # Expected behavior
+    /// No hint is recorded.
+    #[test]
+    fn deletion_runs_disabled() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_deleted_head(dir.path(), 0);
+
+        scan_all(&engine);
+        assert!(!engine.hint_deletion_compaction().unwrap());
+        assert!(!engine.hinted_compact(&RangeLocks::default()).unwrap());
+    }
+
+    /// # Scenario
+    /// Scans step over runs of tombstones shorter than the threshold, and
+    /// no scan runs at all.
+    ///
+    /// # Actions
+    /// 1. Threshold 10: check for a hint before any scan.
+    /// 2. Threshold 1 000 000: scan over the deleted head, check for a
+    ///    hint.
+    ///
+    /// # Expected behavior
+    /// No hint in either case.
+    #[test]
+    fn deletion_runs_within_bounds() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_deleted_head(dir.path(), 10);
+        assert!(!engine.hint_deletion_compaction().unwrap());
+        drop(engine);
+
+        let config = EngineConfig {
+            deletion_run_compaction_threshold: 1_000_000,
+            ..multi_sstable_config()
+        };
+        let engine = Engine::open(dir.path(), config).unwrap();
+        scan_all(&engine);
+        assert!(!engine.hint_deletion_compaction().unwrap());
+    }
+
+    /// # Scenario
+    /// A scan steps over a run of tombstones longer than the threshold.
+    ///
+    /// # Starting environment
+    /// 300 keys in SSTables, the first 200 deleted in newer SSTables;
+    /// threshold 10; no size bucket is compacted.
+    ///
+    /// # Actions
+    /// 1. Scan over the deleted head, check for a hint.
+    /// 2. Run hinted compaction until it has nothing left to do.
+    /// 3. Check for a hint again, then scan once more.
+    ///
+    /// # Expected behavior
+    /// - The hint is recorded, and compaction drops flushed tombstones
+    ///   together with the keys they deleted.
+    /// - Runs are counted afresh, so no second hint follows at once.
+    /// - The scan returns the same 100 keys.
+    #[test]
+    fn deletion_runs_exceeded_compacts_deleted_head() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_deleted_head(dir.path(), 10);
+        let before = tombstones(&engine);
+        assert!(before > 0);
+
+        scan_all(&engine);
+        assert!(engine.hint_deletion_compaction().unwrap());
+        while engine.hinted_compact(&RangeLocks::default()).unwrap() {}
+
+        assert!(tombstones(&engine) < before);
+        assert!(!engine.hint_deletion_compaction().unwrap());
+        scan_all(&engine);
+    }
+}
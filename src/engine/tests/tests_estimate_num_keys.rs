@@ -0,0 +1,182 @@
+//! Tests for the metadata-only live-key estimate
+//! (`Engine::estimate_num_keys`).
+//!
+//! ## Coverage areas
+//! - Memtable counts are exact, overwrites included
+//! - Sequentially written SSTables do not overlap and are summed exactly
+//! - Overwrites spread over SSTables are counted once via key sketches
+//! - The run grouping, sketch union, and delete heuristics on hand-built
+//!   layers
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::Engine;
+    use crate::engine::key_estimate::{LayerKeys, estimate_live_keys};
+    use crate::engine::tests::helpers::*;
+    use crate::sketch::KeySketch;
+    use tempfile::TempDir;
+
+    /// Puts `key_0000..key_{count}`.
+    fn put_keys(engine: &Engine, count: u32) {
+        for i in 0..count {
+            let key = format!("key_{i:04}").into_bytes();
+            let value = format!("value_with_some_padding_{i:04}").into_bytes();
+            engine.put(key, value).unwrap();
+        }
+    }
+
+    fn layer(min_key: &str, max_key: &str, puts: u64, deletes: u64) -> LayerKeys {
+        LayerKeys {
+            min_key: min_key.as_bytes().to_vec(),
+            max_key: max_key.as_bytes().to_vec(),
+            puts,
+            deletes,
+            sketch: None,
+        }
+    }
+
+    /// A layer over `prefix_{i}` for each `i` in `keys`, with a sketch.
+    fn sketched(prefix: &str, keys: std::ops::Range<u32>) -> LayerKeys {
+        let mut sketch = KeySketch::new();
+        for i in keys.clone() {
+            sketch.insert(format!("{prefix}_{i:04}").as_bytes());
+        }
+        LayerKeys {
+            min_key: format!("{prefix}_{:04}", keys.start).into_bytes(),
+            max_key: format!("{prefix}_{:04}", keys.end - 1).into_bytes(),
+            puts: keys.len() as u64,
+            deletes: 0,
+            sketch: Some(sketch),
+        }
+    }
+
+    /// # Scenario
+    /// An empty engine estimates zero keys.
+    #[test]
+    fn estimate_empty() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), memtable_only_config()).unwrap();
+
+        assert_eq!(engine.estimate_num_keys().unwrap(), 0);
+    }
+
+    /// # Scenario
+    /// A memtable-only engine counts every key once, however often it
+    /// was overwritten.
+    ///
+    /// # Actions
+    /// 1. Put 50 keys, then overwrite 10 of them twice.
+    ///
+    /// # Expected behavior
+    /// The estimate is 50.
+    #[test]
+    fn estimate_memtable_exact() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), memtable_only_config()).unwrap();
+        put_keys(&engine, 50);
+        for _ in 0..2 {
+            for i in 0..10 {
+                let key = format!("key_{i:04}").into_bytes();
+                engine.put(key, b"overwritten".to_vec()).unwrap();
+            }
+        }
+
+        assert_eq!(engine.estimate_num_keys().unwrap(), 50);
+    }
+
+    /// # Scenario
+    /// Keys written in ascending order land in SSTables with disjoint key
+    /// ranges, so no overlap discount applies.
+    ///
+    /// # Starting environment
+    /// Engine with 300 ascending keys over several SSTables and the
+    /// active memtable.
+    ///
+    /// # Expected behavior
+    /// The estimate is exactly 300.
+    #[test]
+    fn estimate_disjoint_sstables_exact() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 300, "key");
+
+        assert_eq!(engine.estimate_num_keys().unwrap(), 300);
+    }
+
+    /// # Scenario
+    /// Overwriting every key places a second version of it in newer
+    /// SSTables whose ranges overlap the first ones.
+    ///
+    /// # Actions
+    /// 1. Put 300 keys, flush, put the same 300 keys again, flush.
+    ///
+    /// # Expected behavior
+    /// The key sketches recognise the second versions: the estimate lies
+    /// within 10 % of the 300 live keys, far from the 600 stored versions.
+    #[test]
+    fn estimate_overwrites_discounted() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), multi_sstable_config()).unwrap();
+        put_keys(&engine, 300);
+        engine.flush_all_frozen().unwrap();
+        put_keys(&engine, 300);
+        engine.flush_all_frozen().unwrap();
+
+        let estimate = engine.estimate_num_keys().unwrap();
+        assert!(
+            (270..=330).contains(&estimate),
+            "estimate {estimate} outside 300 ± 10 %"
+        );
+    }
+
+    /// # Scenario
+    /// Runs of overlapping layers are discounted; separate runs and
+    /// deletes are handled exactly as documented.
+    ///
+    /// # Expected behavior
+    /// - Disjoint layers sum: 10 + 20 = 30.
+    /// - Overlapping layers `[a, f]` (40) and `[d, k]` (20) plus a later
+    ///   touching layer `[k, m]` (10) form one run: 40 + (20 + 10) / 2.
+    /// - Deletes are subtracted, and never take the estimate below zero.
+    #[test]
+    fn estimate_heuristics() {
+        assert_eq!(estimate_live_keys(Vec::new()), 0);
+        assert_eq!(
+            estimate_live_keys(vec![layer("p", "z", 20, 0), layer("a", "c", 10, 0)]),
+            30
+        );
+        assert_eq!(
+            estimate_live_keys(vec![
+                layer("k", "m", 10, 0),
+                layer("a", "f", 40, 0),
+                layer("d", "k", 20, 0),
+                layer("x", "y", 5, 0),
+            ]),
+            40 + 15 + 5
+        );
+        assert_eq!(
+            estimate_live_keys(vec![layer("a", "c", 10, 3), layer("d", "f", 0, 4)]),
+            3
+        );
+        assert_eq!(estimate_live_keys(vec![layer("a", "c", 1, 5)]), 0);
+    }
+
+    /// # Scenario
+    /// A run whose layers all carry sketches counts the union of their
+    /// keys; one layer without a sketch falls back to the discount.
+    ///
+    /// # Expected behavior
+    /// - `[0, 300)` and `[100, 400)` sketched: about 400 keys.
+    /// - The same with the second layer unsketched: 300 + 300 / 2 = 450.
+    #[test]
+    fn estimate_sketch_union() {
+        let estimate = estimate_live_keys(vec![sketched("k", 0..300), sketched("k", 100..400)]);
+        assert!((380..=420).contains(&estimate), "estimate {estimate}");
+
+        let mut unsketched = sketched("k", 100..400);
+        unsketched.sketch = None;
+        assert_eq!(
+            estimate_live_keys(vec![sketched("k", 0..300), unsketched]),
+            450
+        );
+    }
+}
@@ -2,13 +2,15 @@
 //!
 //! These tests verify that the engine correctly manages files on disk:
 //! SSTable files are removed after compaction, temp files are cleaned up,
-//! and orphan SSTables are removed on open. WAL files are **not** deleted
-//! after flush (they remain on disk; only the manifest's frozen list is
-//! updated). These tests check actual on-disk file counts.
+//! and orphan SSTables are removed on open. Flushed WAL files are covered
+//! by [`tests_wal_retention`]. These tests check actual on-disk file
+//! counts.
 //!
 //! ## See also
 //! - [`tests_hardening`] — orphan SSTable cleanup on open
 //! - [`tests_crash_flush`] — crash debris: `.tmp` files cleaned
+//! - [`tests_wal_retention`] — deleting flushed WALs, with or without
+//!   a retention limit
 
 #[cfg(test)]
 #[allow(non_snake_case)]
@@ -194,7 +196,8 @@ mod tests {
     /// tracked by the manifest. On reopen, the engine should remove it.
     ///
     /// # Expected behavior
-    /// After reopening, the orphan SSTable file is removed.
+    /// After reopening, the orphan SSTable file is removed and listed in
+    /// the recovery report.
     #[test]
     fn memtable_sstable__orphan_sst_removed_on_reopen() {
         init_tracing();
@@ -224,6 +227,9 @@ mod tests {
             sst_after, sst_count,
             "Orphan SSTable should be removed on reopen"
         );
+        let report = engine2.recovery_report();
+        assert_eq!(report.orphan_files_removed, vec![orphan_path]);
+        assert_eq!(report.sstables_opened, sst_count);
 
         // Data should still be intact.
         assert!(engine2.get(b"key_0000".to_vec()).unwrap().is_some());
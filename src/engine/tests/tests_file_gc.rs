@@ -0,0 +1,186 @@
+//! Tests for deferred deletion of removed SSTable files
+//! (`obsolete_file_grace_seconds`, `Engine::purge_obsolete_files`).
+//!
+//! ## Coverage
+//! - Compaction inputs stay on disk until their grace period passes
+//! - A file a scan still reads outlives a forced purge, and goes once
+//!   the scan is dropped
+//! - Files removed by a clear follow the same rules
+//! - Files still pending at a reopen are collected as orphans
+
+#[cfg(test)]
+mod tests {
+    use crate::clock::MockClock;
+    use crate::engine::layout;
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, EngineConfig, SSTABLE_DIR};
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    const GRACE_SECONDS: usize = 60;
+
+    fn gc_config(clock: &Arc<MockClock>) -> EngineConfig {
+        EngineConfig {
+            obsolete_file_grace_seconds: GRACE_SECONDS,
+            clock: clock.clone(),
+            ..multi_sstable_config()
+        }
+    }
+
+    /// Opens an engine with a grace period and writes `gc_0000..gc_0199`
+    /// across several SSTables.
+    fn engine_with_gc(path: &Path, clock: &Arc<MockClock>) -> Engine {
+        let engine = Engine::open(path, gc_config(clock)).unwrap();
+        for i in 0..200u32 {
+            let key = format!("gc_{i:04}").into_bytes();
+            engine
+                .put(key, b"value_with_some_padding".to_vec())
+                .unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+        assert!(engine.stats().unwrap().sstables_count >= 2);
+        engine
+    }
+
+    fn sst_files(path: &Path) -> Vec<PathBuf> {
+        layout::sstable_files(&path.join(SSTABLE_DIR)).unwrap()
+    }
+
+    /// # Scenario
+    /// Inputs of a major compaction stay on disk for the grace period.
+    ///
+    /// # Starting environment
+    /// Several SSTables; grace period of 60 s on a mock clock.
+    ///
+    /// # Actions
+    /// 1. Major compact.
+    /// 2. Run the timer pass 59 s later, then 61 s later.
+    ///
+    /// # Expected behavior
+    /// The inputs are still on disk after step 1 and the first pass,
+    /// which reports them pending; the second pass deletes them, leaving
+    /// only the output. Reads see the compacted data throughout.
+    #[test]
+    fn inputs_kept_for_grace_period() {
+        let dir = TempDir::new().unwrap();
+        let clock = Arc::new(MockClock::new(1_000_000_000));
+        let engine = engine_with_gc(dir.path(), &clock);
+        let before = sst_files(dir.path()).len();
+
+        assert!(engine.major_compact().unwrap());
+        assert_eq!(engine.stats().unwrap().sstables_count, 1);
+        assert_eq!(sst_files(dir.path()).len(), before + 1);
+
+        clock.advance(Duration::from_secs(59));
+        let report = engine.collect_obsolete_files().unwrap();
+        assert_eq!(report.files_deleted, 0);
+        assert_eq!(report.files_pending, before);
+        assert_eq!(sst_files(dir.path()).len(), before + 1);
+
+        clock.advance(Duration::from_secs(2));
+        let report = engine.collect_obsolete_files().unwrap();
+        assert_eq!(report.files_deleted, before);
+        assert!(report.bytes_deleted > 0);
+        assert_eq!(report.files_pending, 0);
+        assert_eq!(sst_files(dir.path()).len(), 1);
+        assert_eq!(collect_scan(&engine, b"gc_", b"gc_\xff").len(), 200);
+    }
+
+    /// # Scenario
+    /// A forced purge skips the grace period, but not a reader.
+    ///
+    /// # Starting environment
+    /// Several SSTables; grace period of 60 s on a mock clock.
+    ///
+    /// # Actions
+    /// 1. Open a scan over every key; major compact.
+    /// 2. Purge; drop the scan; purge again.
+    ///
+    /// # Expected behavior
+    /// The first purge deletes nothing and reports the inputs pending;
+    /// the scan still returns every key. Once it is dropped, the second
+    /// purge deletes every input without the clock moving.
+    #[test]
+    fn purge_waits_for_open_scan() {
+        let dir = TempDir::new().unwrap();
+        let clock = Arc::new(MockClock::new(1_000_000_000));
+        let engine = engine_with_gc(dir.path(), &clock);
+        let before = sst_files(dir.path()).len();
+
+        let mut scan = engine.scan(b"gc_", b"gc_\xff").unwrap();
+        assert!(scan.next().is_some());
+        assert!(engine.major_compact().unwrap());
+
+        let report = engine.purge_obsolete_files().unwrap();
+        assert_eq!(report.files_deleted, 0);
+        assert_eq!(report.files_pending, before);
+        assert_eq!(scan.count(), 199);
+
+        let report = engine.purge_obsolete_files().unwrap();
+        assert_eq!(report.files_deleted, before);
+        assert_eq!(report.files_pending, 0);
+        assert_eq!(sst_files(dir.path()).len(), 1);
+    }
+
+    /// # Scenario
+    /// Files dropped by a clear are removed from disk only by a purge.
+    ///
+    /// # Starting environment
+    /// Several SSTables; grace period of 60 s on a mock clock.
+    ///
+    /// # Actions
+    /// 1. Clear; purge.
+    ///
+    /// # Expected behavior
+    /// The engine is empty at once, while the files stay on disk until
+    /// the purge deletes them all.
+    #[test]
+    fn clear_defers_file_deletion() {
+        let dir = TempDir::new().unwrap();
+        let clock = Arc::new(MockClock::new(1_000_000_000));
+        let engine = engine_with_gc(dir.path(), &clock);
+        let before = sst_files(dir.path()).len();
+
+        engine.clear().unwrap();
+        assert_eq!(engine.stats().unwrap().sstables_count, 0);
+        assert_eq!(sst_files(dir.path()).len(), before);
+
+        assert_eq!(engine.purge_obsolete_files().unwrap().files_deleted, before);
+        assert!(sst_files(dir.path()).is_empty());
+    }
+
+    /// # Scenario
+    /// Removed files a reader held at close are collected on reopen.
+    ///
+    /// # Starting environment
+    /// Several SSTables; grace period of 60 s on a mock clock.
+    ///
+    /// # Actions
+    /// 1. Open a scan; major compact; close with the scan still open.
+    /// 2. Drop the scan and engine; reopen.
+    ///
+    /// # Expected behavior
+    /// The close leaves the inputs the scan holds; the reopen removes
+    /// them as orphans and reads every key from the compacted SSTable.
+    #[test]
+    fn pending_files_collected_on_reopen() {
+        let dir = TempDir::new().unwrap();
+        let clock = Arc::new(MockClock::new(1_000_000_000));
+        let engine = engine_with_gc(dir.path(), &clock);
+        let before = sst_files(dir.path()).len();
+
+        let scan = engine.scan(b"gc_", b"gc_\xff").unwrap();
+        assert!(engine.major_compact().unwrap());
+        engine.close().unwrap();
+        assert_eq!(sst_files(dir.path()).len(), before + 1);
+        drop(scan);
+        drop(engine);
+
+        let engine = Engine::open(dir.path(), gc_config(&clock)).unwrap();
+        assert_eq!(engine.recovery_report().orphan_files_removed.len(), before);
+        assert_eq!(sst_files(dir.path()).len(), 1);
+        assert_eq!(collect_scan(&engine, b"gc_", b"gc_\xff").len(), 200);
+    }
+}
@@ -575,4 +575,177 @@ mod tests {
             );
         }
     }
+
+    // ================================================================
+    // Batched flush (max_memtables_per_flush)
+    // ================================================================
+
+    fn batched_flush_config(max_memtables_per_flush: usize) -> crate::engine::EngineConfig {
+        crate::engine::EngineConfig {
+            max_memtables_per_flush,
+            ..small_buffer_config()
+        }
+    }
+
+    /// # Scenario
+    /// With `max_memtables_per_flush = 4`, one flush merges the four oldest
+    /// frozen memtables into a single SSTable.
+    ///
+    /// # Actions
+    /// 1. Write keys (each key twice, in different memtables) until at
+    ///    least 5 memtables are frozen.
+    /// 2. Call `flush_oldest_frozen()` once.
+    ///
+    /// # Expected behavior
+    /// frozen_count drops by 4, exactly one SSTable is added, and every key
+    /// still reads its newest value.
+    #[test]
+    fn flush_oldest_frozen_merges_batch() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), batched_flush_config(4)).unwrap();
+
+        for round in 0..2u32 {
+            for i in 0..100u32 {
+                engine
+                    .put(
+                        format!("k_{:04}", i).into_bytes(),
+                        format!("v{}_{:04}", round, i).into_bytes(),
+                    )
+                    .unwrap();
+            }
+        }
+
+        let before = engine.stats().unwrap();
+        assert!(
+            before.frozen_count >= 5,
+            "Need at least 5 frozen, got {}",
+            before.frozen_count
+        );
+
+        assert!(engine.flush_oldest_frozen().unwrap());
+
+        let after = engine.stats().unwrap();
+        assert_eq!(after.frozen_count, before.frozen_count - 4);
+        assert_eq!(after.sstables_count, before.sstables_count + 1);
+
+        for i in 0..100u32 {
+            assert_eq!(
+                engine.get(format!("k_{:04}", i).into_bytes()).unwrap(),
+                Some(format!("v1_{:04}", i).into_bytes())
+            );
+        }
+    }
+
+    /// # Scenario
+    /// `flush_all_frozen()` with batching reports memtables flushed, not
+    /// SSTables created, and the merged SSTables survive a reopen.
+    ///
+    /// # Actions
+    /// 1. Put keys, delete some, and range-delete a span, with batching of 3.
+    /// 2. `flush_all_frozen()`, then close and reopen.
+    ///
+    /// # Expected behavior
+    /// The return value equals the prior frozen_count; SSTable count is
+    /// `ceil(frozen / 3)`; deletes and range deletes still apply after
+    /// reopen.
+    #[test]
+    fn flush_all_frozen_batched_survives_reopen() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), batched_flush_config(3)).unwrap();
+
+        for i in 0..150u32 {
+            engine
+                .put(
+                    format!("k_{:04}", i).into_bytes(),
+                    format!("v_{:04}", i).into_bytes(),
+                )
+                .unwrap();
+        }
+        for i in (0..150u32).step_by(5) {
+            engine.delete(format!("k_{:04}", i).into_bytes()).unwrap();
+        }
+        engine
+            .delete_range(b"k_0100".to_vec(), b"k_0120".to_vec())
+            .unwrap();
+        for i in 0..60u32 {
+            engine
+                .put(format!("z_{:04}", i).into_bytes(), b"filler".to_vec())
+                .unwrap();
+        }
+
+        let frozen = engine.stats().unwrap().frozen_count;
+        assert!(frozen >= 4, "Need at least 4 frozen, got {}", frozen);
+
+        assert_eq!(engine.flush_all_frozen().unwrap(), frozen);
+        let stats = engine.stats().unwrap();
+        assert_eq!(stats.frozen_count, 0);
+        assert_eq!(stats.sstables_count, frozen.div_ceil(3));
+
+        engine.close().unwrap();
+        let engine = Engine::open(dir.path(), batched_flush_config(3)).unwrap();
+
+        for i in 0..150u32 {
+            let expected = if i % 5 == 0 || (100..120).contains(&i) {
+                None
+            } else {
+                Some(format!("v_{:04}", i).into_bytes())
+            };
+            assert_eq!(
+                engine.get(format!("k_{:04}", i).into_bytes()).unwrap(),
+                expected,
+                "key k_{:04}",
+                i
+            );
+        }
+    }
+
+    // ================================================================
+    // Frozen memtable limit (max_frozen_memtables)
+    // ================================================================
+
+    /// # Scenario
+    /// With `max_frozen_memtables = 3`, writes that freeze memtables
+    /// faster than anything flushes them flush the oldest themselves.
+    ///
+    /// # Actions
+    /// 1. Put 200 keys with a 128-byte write buffer and no flush calls,
+    ///    checking `frozen_count` after every put.
+    /// 2. Read every key; close and reopen.
+    ///
+    /// # Expected behavior
+    /// - `frozen_count` never exceeds 3, and SSTables appear without
+    ///   any flush call.
+    /// - Every key reads back, before and after the reopen.
+    #[test]
+    fn frozen_limit_flushes_inline() {
+        let dir = TempDir::new().unwrap();
+        let config = || crate::engine::EngineConfig {
+            max_frozen_memtables: 3,
+            ..small_buffer_config()
+        };
+        let engine = Engine::open(dir.path(), config()).unwrap();
+
+        for i in 0..200u32 {
+            engine
+                .put(format!("k_{:04}", i).into_bytes(), b"value".to_vec())
+                .unwrap();
+            let frozen = engine.stats().unwrap().frozen_count;
+            assert!(frozen <= 3, "{frozen} frozen after put {i}");
+        }
+        assert!(engine.stats().unwrap().sstables_count > 0);
+
+        let check = |engine: &Engine| {
+            for i in 0..200u32 {
+                assert_eq!(
+                    engine.get(format!("k_{:04}", i).into_bytes()).unwrap(),
+                    Some(b"value".to_vec()),
+                    "key k_{:04}",
+                    i
+                );
+            }
+        };
+        check(&engine);
+        engine.close().unwrap();
+        check(&Engine::open(dir.path(), config()).unwrap());
+    }
 }
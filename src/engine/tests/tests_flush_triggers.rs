@@ -0,0 +1,168 @@
+//! Tests for the age and WAL-size flush triggers
+//! (`EngineConfig::max_memtable_age`, `EngineConfig::max_total_wal_size`).
+//!
+//! ## Coverage areas
+//! - Both triggers are disabled by default
+//! - The WAL-size trigger freezes on the write that crosses the limit, and
+//!   waits while a frozen memtable is still pending
+//! - The age trigger fires from `freeze_if_due` once the oldest write is
+//!   old enough, never on an empty memtable
+//! - Memtable age is taken from WAL timestamps, so it survives a reopen
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, EngineConfig};
+    use std::thread;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    /// Puts keys until a write reports a freeze; returns how many were
+    /// written. Panics after `limit` writes.
+    fn put_until_frozen(engine: &Engine, prefix: &str, limit: usize) -> usize {
+        for i in 0..limit {
+            let key = format!("{prefix}_{i:04}").into_bytes();
+            if engine.put(key, b"value".to_vec()).unwrap() {
+                return i + 1;
+            }
+        }
+        panic!("no freeze after {limit} writes");
+    }
+
+    /// # Scenario
+    /// Without either option set, only buffer fullness freezes.
+    ///
+    /// # Starting environment
+    /// Memtable-only engine (64 KiB buffer), default triggers.
+    ///
+    /// # Actions
+    /// 1. Put 100 keys.
+    /// 2. Call `freeze_if_due`.
+    ///
+    /// # Expected behavior
+    /// No write reports a freeze, `freeze_if_due` returns `false`, and no
+    /// frozen memtable exists.
+    #[test]
+    fn flush_triggers_disabled_by_default() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), memtable_only_config()).unwrap();
+
+        for i in 0..100u32 {
+            let key = format!("ft_{i:04}").into_bytes();
+            assert!(!engine.put(key, b"value".to_vec()).unwrap());
+        }
+        assert!(!engine.freeze_if_due().unwrap());
+        assert_eq!(engine.stats().unwrap().frozen_count, 0);
+    }
+
+    /// # Scenario
+    /// The WAL-size trigger freezes long before the write buffer is full.
+    ///
+    /// # Starting environment
+    /// Memtable-only engine with `max_total_wal_size: 1024`.
+    ///
+    /// # Actions
+    /// 1. Put keys until a write reports a freeze.
+    /// 2. Put as many keys again while the frozen memtable is pending.
+    /// 3. Flush it, then put keys until the next freeze.
+    ///
+    /// # Expected behavior
+    /// The first freeze comes within a few dozen writes; no second freeze
+    /// happens while a frozen memtable awaits flush; after the flush the
+    /// trigger fires again. All keys stay readable.
+    #[test]
+    fn wal_size_trigger_freezes_on_write() {
+        let dir = TempDir::new().unwrap();
+        let config = EngineConfig {
+            max_total_wal_size: 1024,
+            ..memtable_only_config()
+        };
+        let engine = Engine::open(dir.path(), config).unwrap();
+
+        let written = put_until_frozen(&engine, "a", 200);
+        assert!(written < 100, "froze only after {written} writes");
+        assert_eq!(engine.stats().unwrap().frozen_count, 1);
+
+        for i in 0..written {
+            let key = format!("b_{i:04}").into_bytes();
+            assert!(!engine.put(key, b"value".to_vec()).unwrap());
+        }
+        assert_eq!(engine.stats().unwrap().frozen_count, 1);
+
+        assert!(engine.flush_oldest_frozen().unwrap());
+        put_until_frozen(&engine, "c", 200);
+        assert_eq!(engine.stats().unwrap().frozen_count, 1);
+
+        assert!(engine.get(b"a_0000".to_vec()).unwrap().is_some());
+        assert!(engine.get(b"b_0000".to_vec()).unwrap().is_some());
+        assert!(engine.get(b"c_0000".to_vec()).unwrap().is_some());
+    }
+
+    /// # Scenario
+    /// The age trigger fires once the oldest write is old enough.
+    ///
+    /// # Starting environment
+    /// Memtable-only engine with `max_memtable_age: 1`.
+    ///
+    /// # Actions
+    /// 1. Call `freeze_if_due` on the empty memtable after 1.1 s.
+    /// 2. Put one key; call `freeze_if_due` immediately.
+    /// 3. Sleep 1.1 s; call `freeze_if_due` twice.
+    ///
+    /// # Expected behavior
+    /// Only the first call after the sleep with data freezes; an empty or
+    /// young memtable is left alone. The key stays readable.
+    #[test]
+    fn age_trigger_freezes_old_memtable() {
+        let dir = TempDir::new().unwrap();
+        let config = EngineConfig {
+            max_memtable_age: 1,
+            ..memtable_only_config()
+        };
+        let engine = Engine::open(dir.path(), config).unwrap();
+
+        thread::sleep(Duration::from_millis(1100));
+        assert!(!engine.freeze_if_due().unwrap(), "empty memtable frozen");
+
+        engine.put(b"k".to_vec(), b"v".to_vec()).unwrap();
+        assert!(!engine.freeze_if_due().unwrap(), "young memtable frozen");
+
+        thread::sleep(Duration::from_millis(1100));
+        assert!(engine.freeze_if_due().unwrap());
+        assert!(!engine.freeze_if_due().unwrap());
+        assert_eq!(engine.stats().unwrap().frozen_count, 1);
+        assert_eq!(engine.get(b"k".to_vec()).unwrap(), Some(b"v".to_vec()));
+    }
+
+    /// # Scenario
+    /// A memtable's age counts from its first write, not from the reopen.
+    ///
+    /// # Starting environment
+    /// Memtable-only engine with `max_memtable_age: 1` and one key in the
+    /// active memtable, closed.
+    ///
+    /// # Actions
+    /// 1. Sleep 1.1 s.
+    /// 2. Reopen and call `freeze_if_due`.
+    ///
+    /// # Expected behavior
+    /// The replayed memtable is already old enough and is frozen.
+    #[test]
+    fn age_trigger_survives_reopen() {
+        let dir = TempDir::new().unwrap();
+        let config = EngineConfig {
+            max_memtable_age: 1,
+            ..memtable_only_config()
+        };
+        {
+            let engine = Engine::open(dir.path(), config.clone()).unwrap();
+            engine.put(b"k".to_vec(), b"v".to_vec()).unwrap();
+            engine.close().unwrap();
+        }
+
+        thread::sleep(Duration::from_millis(1100));
+        let engine = Engine::open(dir.path(), config).unwrap();
+        assert!(engine.freeze_if_due().unwrap());
+        assert_eq!(engine.get(b"k".to_vec()).unwrap(), Some(b"v".to_vec()));
+    }
+}
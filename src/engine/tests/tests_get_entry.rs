@@ -0,0 +1,95 @@
+//! Tests for the versioned point lookup (`Engine::get_entry`).
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, EngineConfig, ReadOptions};
+    use tempfile::TempDir;
+
+    /// # Scenario
+    /// Every write to a key advances the LSN `get_entry` reports.
+    ///
+    /// # Starting environment
+    /// Memtable-only engine.
+    ///
+    /// # Actions
+    /// 1. Put `k`, read its entry; put `k` again, read again.
+    /// 2. Delete `k`; put it again; range-delete it.
+    ///
+    /// # Expected behavior
+    /// Entries carry the value and a strictly higher LSN after each put,
+    /// with non-decreasing timestamps. Deleted keys and missing keys
+    /// return `None`; a put after the delete gets an LSN above the
+    /// delete's.
+    #[test]
+    fn get_entry_tracks_memtable_versions() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), memtable_only_config()).unwrap();
+        let opts = ReadOptions::default();
+
+        engine.put(b"k".to_vec(), b"v1".to_vec()).unwrap();
+        let first = engine.get_entry(b"k".to_vec(), &opts).unwrap().unwrap();
+        assert_eq!(first.value, b"v1".to_vec());
+
+        engine.put(b"k".to_vec(), b"v2".to_vec()).unwrap();
+        let second = engine.get_entry(b"k".to_vec(), &opts).unwrap().unwrap();
+        assert_eq!(second.value, b"v2".to_vec());
+        assert!(second.lsn > first.lsn);
+        assert!(second.timestamp >= first.timestamp);
+
+        engine.delete(b"k".to_vec()).unwrap();
+        assert_eq!(engine.get_entry(b"k".to_vec(), &opts).unwrap(), None);
+        engine.put(b"k".to_vec(), b"v3".to_vec()).unwrap();
+        let third = engine.get_entry(b"k".to_vec(), &opts).unwrap().unwrap();
+        assert!(third.lsn > second.lsn);
+
+        engine.delete_range(b"a".to_vec(), b"z".to_vec()).unwrap();
+        assert_eq!(engine.get_entry(b"k".to_vec(), &opts).unwrap(), None);
+        assert_eq!(engine.get_entry(b"missing".to_vec(), &opts).unwrap(), None);
+    }
+
+    /// # Scenario
+    /// An entry keeps its LSN and timestamp when it moves to an SSTable.
+    ///
+    /// # Starting environment
+    /// Engine with a 1 KiB write buffer and a row cache.
+    ///
+    /// # Actions
+    /// 1. Put `k`, read its entry from the memtable.
+    /// 2. Push it into an SSTable with filler writes; `get` it twice to
+    ///    cache it; read its entry again; compact and read again.
+    ///
+    /// # Expected behavior
+    /// All three entries are equal: flushes, compaction, and the row
+    /// cache preserve the version metadata.
+    #[test]
+    fn get_entry_stable_across_flush_and_compaction() {
+        let dir = TempDir::new().unwrap();
+        let config = EngineConfig {
+            row_cache_size: 64 * 1024,
+            ..multi_sstable_config()
+        };
+        let engine = Engine::open(dir.path(), config).unwrap();
+        let opts = ReadOptions::default();
+
+        engine.put(b"k".to_vec(), b"value".to_vec()).unwrap();
+        let in_memtable = engine.get_entry(b"k".to_vec(), &opts).unwrap().unwrap();
+
+        for i in 0..200u32 {
+            let key = format!("zz_{i:04}").into_bytes();
+            engine.put(key, b"filler_value".to_vec()).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+        assert!(engine.stats().unwrap().sstables_count >= 2);
+
+        engine.get(b"k".to_vec()).unwrap();
+        engine.get(b"k".to_vec()).unwrap();
+        assert_eq!(engine.read_stats().row_cache_hits, 1);
+        let in_sstable = engine.get_entry(b"k".to_vec(), &opts).unwrap().unwrap();
+        assert_eq!(in_sstable, in_memtable);
+
+        assert!(engine.major_compact().unwrap());
+        let compacted = engine.get_entry(b"k".to_vec(), &opts).unwrap().unwrap();
+        assert_eq!(compacted, in_memtable);
+    }
+}
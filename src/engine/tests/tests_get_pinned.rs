@@ -0,0 +1,92 @@
+//! Tests for the zero-copy point lookup (`Engine::get_pinned`).
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::Engine;
+    use crate::engine::tests::helpers::*;
+    use tempfile::TempDir;
+
+    /// # Scenario
+    /// A key still in the active memtable is returned as an owned copy.
+    ///
+    /// # Expected behavior
+    /// `get_pinned` returns the value with `is_pinned() == false`; missing
+    /// and deleted keys return `None`.
+    #[test]
+    fn get_pinned_memtable_hit_is_owned() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), memtable_only_config()).unwrap();
+
+        engine.put(b"k1".to_vec(), b"v1".to_vec()).unwrap();
+        engine.put(b"k2".to_vec(), b"v2".to_vec()).unwrap();
+        engine.delete(b"k2".to_vec()).unwrap();
+
+        let value = engine.get_pinned(b"k1".to_vec()).unwrap().unwrap();
+        assert_eq!(value, b"v1".to_vec());
+        assert!(!value.is_pinned());
+
+        assert!(engine.get_pinned(b"k2".to_vec()).unwrap().is_none());
+        assert!(engine.get_pinned(b"missing".to_vec()).unwrap().is_none());
+    }
+
+    /// # Scenario
+    /// SSTable-resident values are borrowed from the mmap and agree with
+    /// `get`.
+    ///
+    /// # Starting environment
+    /// Several SSTables with overwrites and deletes.
+    ///
+    /// # Expected behavior
+    /// For every key, `get_pinned` matches `get`; SSTable hits are pinned.
+    #[test]
+    fn get_pinned_matches_get_across_sstables() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 200, "pin");
+        for i in (0..200u32).step_by(7) {
+            engine.delete(format!("pin_{i:04}").into_bytes()).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+        assert!(engine.stats().unwrap().sstables_count > 1);
+
+        let mut pinned_hits = 0;
+        for i in 0..200u32 {
+            let key = format!("pin_{i:04}").into_bytes();
+            let expected = engine.get(key.clone()).unwrap();
+            let pinned = engine.get_pinned(key).unwrap();
+            assert_eq!(pinned.as_deref(), expected.as_deref());
+            if pinned.is_some_and(|p| p.is_pinned()) {
+                pinned_hits += 1;
+            }
+        }
+        assert!(
+            pinned_hits > 0,
+            "expected some values to be served from SSTables"
+        );
+    }
+
+    /// # Scenario
+    /// A pinned slice outlives the SSTable it was read from.
+    ///
+    /// # Actions
+    /// 1. Pin a value served from an SSTable.
+    /// 2. Major-compact, removing the original SSTable files.
+    ///
+    /// # Expected behavior
+    /// The pinned slice still reads the original bytes, and `into_vec`
+    /// detaches an identical owned copy.
+    #[test]
+    fn get_pinned_survives_compaction() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 200, "surv");
+
+        let key = b"surv_0000".to_vec();
+        let expected = engine.get(key.clone()).unwrap().unwrap();
+        let pinned = engine.get_pinned(key).unwrap().unwrap();
+        assert!(pinned.is_pinned());
+
+        assert!(engine.major_compact().unwrap());
+
+        assert_eq!(pinned, expected);
+        assert_eq!(pinned.into_vec(), expected);
+    }
+}
@@ -0,0 +1,126 @@
+//! Tests for direct SSTable ingestion of sorted pairs
+//! (`Engine::ingest_sorted`).
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, EngineError, ReadOptions};
+    use tempfile::TempDir;
+
+    fn pairs(prefix: &str, range: std::ops::Range<u32>, value: &str) -> Vec<(Vec<u8>, Vec<u8>)> {
+        range
+            .map(|i| {
+                (
+                    format!("{prefix}_{i:04}").into_bytes(),
+                    value.as_bytes().to_vec(),
+                )
+            })
+            .collect()
+    }
+
+    /// # Scenario
+    /// Ingested pairs become the newest layer and replace older versions,
+    /// including unflushed ones.
+    ///
+    /// # Starting environment
+    /// Engine holding `k_0000..k_0099` in SSTables and possibly the
+    /// active memtable.
+    ///
+    /// # Actions
+    /// 1. Ingest `k_0050..k_0149` with value `new`.
+    /// 2. Put `k_0060` again; read; close and reopen; read again.
+    ///
+    /// # Expected behavior
+    /// - The memtables are flushed and the new SSTable is added.
+    /// - `k_0050..k_0149` read `new`, except `k_0060`, whose later put
+    ///   wins; its LSN is above every ingested one.
+    /// - Everything survives the reopen.
+    #[test]
+    fn ingest_sorted_is_newest_layer() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_sstables(dir.path(), 100, "k");
+        let before = engine.sstable_metadata().unwrap().len();
+
+        assert!(engine.ingest_sorted(&pairs("k", 50..150, "new")).unwrap());
+        let after = engine.sstable_metadata().unwrap().len();
+        assert!(after > before, "the new SSTable and the flushed memtable");
+        let ingested = engine
+            .get_entry(b"k_0149".to_vec(), &ReadOptions::default())
+            .unwrap()
+            .unwrap();
+
+        engine.put(b"k_0060".to_vec(), b"later".to_vec()).unwrap();
+        let later = engine
+            .get_entry(b"k_0060".to_vec(), &ReadOptions::default())
+            .unwrap()
+            .unwrap();
+        assert!(later.lsn > ingested.lsn);
+
+        let check = |engine: &Engine| {
+            let scan = collect_scan(engine, b"k_", b"k~");
+            assert_eq!(scan.len(), 150);
+            for (key, value) in &scan {
+                let i: u32 = std::str::from_utf8(&key[2..]).unwrap().parse().unwrap();
+                let expected = match i {
+                    60 => "later".to_string(),
+                    50.. => "new".to_string(),
+                    _ => format!("value_with_some_padding_{i:04}"),
+                };
+                assert_eq!(value, expected.as_bytes(), "key k_{i:04}");
+            }
+        };
+        check(&engine);
+        engine.close().unwrap();
+
+        let engine = reopen(dir.path());
+        check(&engine);
+    }
+
+    /// # Scenario
+    /// Ingesting nothing is a no-op.
+    ///
+    /// # Expected behavior
+    /// `ingest_sorted` returns `false` and creates no SSTable.
+    #[test]
+    fn ingest_sorted_empty() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), memtable_only_config()).unwrap();
+
+        assert!(!engine.ingest_sorted(&[]).unwrap());
+        assert!(engine.sstable_metadata().unwrap().is_empty());
+    }
+
+    /// # Scenario
+    /// Pairs out of key order are rejected.
+    ///
+    /// # Starting environment
+    /// Memtable-only engine.
+    ///
+    /// # Actions
+    /// 1. Ingest pairs with a descending key, then with a repeated key.
+    /// 2. Ingest sorted pairs.
+    ///
+    /// # Expected behavior
+    /// Both unsorted ingests fail with `InvalidArgument` and create no
+    /// SSTable; the sorted one succeeds.
+    #[test]
+    fn ingest_sorted_rejects_unsorted() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), memtable_only_config()).unwrap();
+
+        let mut descending = pairs("k", 0..3, "v");
+        descending.swap(1, 2);
+        let mut repeated = pairs("k", 0..3, "v");
+        repeated[2].0 = repeated[1].0.clone();
+        for unsorted in [descending, repeated] {
+            assert!(matches!(
+                engine.ingest_sorted(&unsorted),
+                Err(EngineError::InvalidArgument(_))
+            ));
+            assert!(engine.sstable_metadata().unwrap().is_empty());
+        }
+
+        assert!(engine.ingest_sorted(&pairs("k", 0..3, "v")).unwrap());
+        assert_eq!(collect_scan(&engine, b"k_", b"k~").len(), 3);
+    }
+}
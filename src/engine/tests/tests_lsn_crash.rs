@@ -9,6 +9,10 @@
 //! already assigned to pre-crash data, breaking the "highest-LSN wins"
 //! merge invariant and causing data corruption.
 //!
+//! The manifest's LSN reservation keeps LSNs unique even when the WAL
+//! holding the highest ones is lost, and makes open fail when the data
+//! holds LSNs the manifest never reserved.
+//!
 //! ## See also
 //! - [`tests_lsn_continuity`] — LSN continuity across clean reopens
 //! - [`tests_crash_recovery`] — single crash durability
@@ -17,10 +21,22 @@
 #[cfg(test)]
 #[allow(non_snake_case)]
 mod tests {
-    use crate::engine::Engine;
     use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, EngineError, MANIFEST_DIR, MEMTABLE_DIR};
+    use std::fs;
+    use std::path::Path;
     use tempfile::TempDir;
 
+    /// Copies the flat directory `from` into a fresh directory `to`.
+    fn copy_dir(from: &Path, to: &Path) {
+        let _ = fs::remove_dir_all(to);
+        fs::create_dir_all(to).unwrap();
+        for entry in fs::read_dir(from).unwrap() {
+            let entry = entry.unwrap();
+            fs::copy(entry.path(), to.join(entry.file_name())).unwrap();
+        }
+    }
+
     // ================================================================
     // 1. Overwrite after crash shadows old value
     // ================================================================
@@ -349,4 +365,148 @@ mod tests {
             "Latest crash-cycle overwrite (memtable) must win over SSTable data"
         );
     }
+
+    // ================================================================
+    // 8. Lost WAL: LSNs are not reused
+    // ================================================================
+
+    /// # Scenario
+    /// The active WAL is lost after a crash. The manifest's reservation
+    /// still covers the LSNs it held, so they are not handed out again.
+    ///
+    /// # Actions
+    /// 1. Write three keys, note `latest_lsn`. Drop (crash).
+    /// 2. Delete the WAL files.
+    /// 3. Reopen and write one key.
+    ///
+    /// # Expected behavior
+    /// The new write's LSN is above every LSN assigned before the crash.
+    #[test]
+    fn memtable__lost_wal_does_not_reuse_lsns() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path();
+
+        let before = {
+            let engine = Engine::open(path, default_config()).unwrap();
+            for key in [b"a", b"b", b"c"] {
+                engine.put(key.to_vec(), b"v1".to_vec()).unwrap();
+            }
+            engine.latest_lsn().unwrap()
+        };
+        assert!(before > 0);
+
+        for entry in fs::read_dir(path.join(MEMTABLE_DIR)).unwrap() {
+            fs::remove_file(entry.unwrap().path()).unwrap();
+        }
+
+        let engine = Engine::open(path, default_config()).unwrap();
+        assert_eq!(engine.get(b"a".to_vec()).unwrap(), None, "WAL was lost");
+        engine.put(b"d".to_vec(), b"v2".to_vec()).unwrap();
+        assert!(
+            engine.latest_lsn().unwrap() > before,
+            "post-crash LSN must be above the LSNs of the lost WAL"
+        );
+    }
+
+    // ================================================================
+    // 9. Manifest older than the data: open fails
+    // ================================================================
+
+    /// # Scenario
+    /// The manifest is rolled back to a copy taken before the engine
+    /// reserved a new block of LSNs, while the WAL keeps a write from
+    /// that block.
+    ///
+    /// # Actions
+    /// 1. Write a key. Drop, and copy the manifest directory.
+    /// 2. Reopen, move the LSN counter past the reservation, write a key.
+    ///    Drop (crash).
+    /// 3. Restore the manifest copy and reopen.
+    ///
+    /// # Expected behavior
+    /// Open fails with `LsnRegression` naming the unreserved LSN.
+    #[test]
+    fn memtable__stale_manifest_fails_with_lsn_regression() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path();
+        let backup = tmp.path().join("manifest-backup");
+
+        {
+            let engine = Engine::open(path, default_config()).unwrap();
+            engine.put(b"a".to_vec(), b"v1".to_vec()).unwrap();
+        }
+        copy_dir(&path.join(MANIFEST_DIR), &backup);
+
+        let reserved = {
+            let engine = Engine::open(path, default_config()).unwrap();
+            let reserved = {
+                let inner = engine.write_lock().unwrap();
+                inner.active.inject_max_lsn(inner.reserved_lsn + 10);
+                inner.reserved_lsn
+            };
+            engine.put(b"b".to_vec(), b"v2".to_vec()).unwrap();
+            assert!(engine.latest_lsn().unwrap() > reserved);
+            reserved
+        };
+        copy_dir(&backup, &path.join(MANIFEST_DIR));
+
+        match Engine::open(path, default_config()) {
+            Err(EngineError::LsnRegression {
+                observed,
+                reserved: stale,
+            }) => {
+                assert_eq!(stale, reserved);
+                assert!(observed > reserved);
+            }
+            Err(e) => panic!("unexpected error: {e}"),
+            Ok(_) => panic!("open succeeded with a stale manifest"),
+        }
+    }
+
+    // ================================================================
+    // 10. Reopen without writes after a reservation
+    // ================================================================
+
+    /// # Scenario
+    /// The engine is reopened several times without writing after the
+    /// manifest reserved a block of LSNs.
+    ///
+    /// # Actions
+    /// 1. Write a key, note `latest_lsn`. Close.
+    /// 2. Reopen and close twice without writing.
+    /// 3. Reopen and write a key.
+    ///
+    /// # Expected behavior
+    /// Every open succeeds: resuming at the reservation hands out no LSN
+    /// the manifest has not reserved. The new write's LSN is above the
+    /// first one, and both keys read back.
+    #[test]
+    fn memtable__reopen_without_writes_after_reservation() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path();
+
+        let before = {
+            let engine = Engine::open(path, default_config()).unwrap();
+            engine.put(b"a".to_vec(), b"v1".to_vec()).unwrap();
+            let before = engine.latest_lsn().unwrap();
+            engine.close().unwrap();
+            before
+        };
+
+        for _ in 0..2 {
+            reopen(path).close().unwrap();
+        }
+
+        let engine = reopen(path);
+        engine.put(b"b".to_vec(), b"v2".to_vec()).unwrap();
+        assert!(engine.latest_lsn().unwrap() > before);
+        assert_eq!(engine.get(b"a".to_vec()).unwrap(), Some(b"v1".to_vec()));
+        assert_eq!(engine.get(b"b".to_vec()).unwrap(), Some(b"v2".to_vec()));
+    }
 }
@@ -0,0 +1,144 @@
+//! Tests for the mmap limit (`EngineConfig::max_mmap_bytes`) and page
+//! cache hints (`EngineConfig::page_cache_hints`).
+//!
+//! ## See also
+//! - [`sstable::tests::tests_mmap_budget`] — demotion and remapping of
+//!   individual tables
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, EngineConfig};
+    use tempfile::TempDir;
+
+    const LIMIT: usize = 4096;
+
+    fn limited_config() -> EngineConfig {
+        EngineConfig {
+            max_mmap_bytes: LIMIT,
+            ..multi_sstable_config()
+        }
+    }
+
+    /// Checks that every key `mb_0000..mb_0399` reads back, through `get`,
+    /// `get_pinned`, and a scan.
+    fn assert_all_keys(engine: &Engine) {
+        for i in 0..400u32 {
+            let key = format!("mb_{i:04}").into_bytes();
+            let value = format!("value_with_some_padding_{i:04}").into_bytes();
+            assert_eq!(engine.get(key.clone()).unwrap(), Some(value.clone()));
+            assert_eq!(engine.get_pinned(key).unwrap().unwrap(), value);
+        }
+        assert_eq!(collect_scan(engine, b"mb_", b"mb_~").len(), 400);
+    }
+
+    /// # Scenario
+    /// SSTables beyond the mmap limit are read with `pread`, both as they
+    /// are flushed and when the engine is reopened.
+    ///
+    /// # Starting environment
+    /// Engine with a 1 KiB write buffer and a 4 KiB mmap limit.
+    ///
+    /// # Actions
+    /// 1. Write `mb_0000..mb_0399` and flush them into many SSTables.
+    /// 2. Check the mapped bytes and read every key.
+    /// 3. Close, reopen with the same limit, and repeat.
+    ///
+    /// # Expected behavior
+    /// The SSTables total more than the limit, yet the mapped bytes stay
+    /// within it and the newest table is mapped. Every key reads back
+    /// before and after the reopen.
+    #[test]
+    fn mmap_limit_demotes_old_sstables() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), limited_config()).unwrap();
+        for i in 0..400u32 {
+            let key = format!("mb_{i:04}").into_bytes();
+            let value = format!("value_with_some_padding_{i:04}").into_bytes();
+            engine.put(key, value).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+
+        let stats = engine.stats().unwrap();
+        assert!(stats.total_sst_size_bytes > LIMIT as u64);
+        assert!(stats.mmap_bytes <= LIMIT as u64);
+        let metadata = engine.sstable_metadata().unwrap();
+        assert!(metadata[0].mapped);
+        assert!(metadata.iter().any(|m| !m.mapped));
+        assert_all_keys(&engine);
+        engine.close().unwrap();
+        drop(engine);
+
+        let engine = Engine::open(dir.path(), limited_config()).unwrap();
+        let stats = engine.stats().unwrap();
+        assert!(stats.mmap_bytes <= LIMIT as u64);
+        assert!(engine.sstable_metadata().unwrap()[0].mapped);
+        assert_all_keys(&engine);
+        assert!(engine.stats().unwrap().mmap_bytes <= LIMIT as u64);
+    }
+
+    /// # Scenario
+    /// Without a limit every SSTable is mapped.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables and `max_mmap_bytes = 0`.
+    ///
+    /// # Actions
+    /// 1. Read the stats and metadata.
+    ///
+    /// # Expected behavior
+    /// The mapped bytes equal the total SSTable size.
+    #[test]
+    fn no_mmap_limit_maps_everything() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 200, "mb");
+        let stats = engine.stats().unwrap();
+        assert_eq!(stats.mmap_bytes, stats.total_sst_size_bytes);
+        assert!(engine.sstable_metadata().unwrap().iter().all(|m| m.mapped));
+    }
+
+    /// # Scenario
+    /// With page cache hints, a compaction unmaps the SSTables it
+    /// replaced at once, even while a scan still reads them.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables and `page_cache_hints` set.
+    ///
+    /// # Actions
+    /// 1. Hold the current SSTables and open a scan over every key.
+    /// 2. Run major compaction.
+    /// 3. Drain the scan.
+    ///
+    /// # Expected behavior
+    /// The replaced SSTables are no longer mapped; the new one is. The
+    /// scan still returns every key.
+    #[test]
+    fn page_cache_hints_unmap_replaced_sstables() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(
+            dir.path(),
+            EngineConfig {
+                page_cache_hints: true,
+                ..multi_sstable_config()
+            },
+        )
+        .unwrap();
+        for i in 0..400u32 {
+            let key = format!("mb_{i:04}").into_bytes();
+            let value = format!("value_with_some_padding_{i:04}").into_bytes();
+            engine.put(key, value).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+        let old = engine.read_lock().unwrap().sstables.clone();
+        assert!(old.len() >= 2);
+        assert!(old.iter().all(|s| s.is_mapped()));
+        let scan = engine.scan(b"mb_", b"mb_~").unwrap();
+
+        assert!(engine.major_compact().unwrap());
+
+        assert!(old.iter().all(|s| !s.is_mapped()));
+        assert!(engine.sstable_metadata().unwrap()[0].mapped);
+        assert_eq!(scan.count(), 400);
+        assert_all_keys(&engine);
+    }
+}
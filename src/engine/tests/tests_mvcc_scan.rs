@@ -13,6 +13,8 @@
 //!   SSTables (the `Arc` keeps them alive).
 //! - Large scan does not OOM — verifies lazy block-at-a-time iteration
 //!   by scanning many keys across multiple SSTables.
+//! - A scan iterator is `Send`: it can be consumed on another thread
+//!   while the engine compacts the SSTables it reads.
 
 #[cfg(test)]
 #[allow(non_snake_case)]
@@ -277,4 +279,47 @@ mod tests {
         assert_eq!(results.len(), 1);
         assert_eq!(results[0], (b"k".to_vec(), b"v3".to_vec()));
     }
+
+    // ----------------------------------------------------------------
+    // Scan iterator moves across threads
+    // ----------------------------------------------------------------
+
+    /// # Scenario
+    /// A scan started on one thread is consumed on another while the
+    /// engine replaces the SSTables it reads.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables.
+    ///
+    /// # Actions
+    /// 1. Start a scan and read its first pair.
+    /// 2. Move the iterator to a worker thread.
+    /// 3. Run major compaction, then let the worker drain the scan.
+    ///
+    /// # Expected behavior
+    /// The worker yields every remaining key in order from the snapshot
+    /// taken before the compaction.
+    #[test]
+    fn mvcc_scan_iterator_moves_across_threads() {
+        let tmp = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(tmp.path(), 100, "th");
+
+        let mut scan_iter = engine.scan(b"th_", b"th_\xff").unwrap();
+        let (first, _) = scan_iter.next().unwrap();
+        assert_eq!(first, b"th_0000");
+
+        let (start, rx) = std::sync::mpsc::channel::<()>();
+        let worker = std::thread::spawn(move || {
+            rx.recv().unwrap();
+            scan_iter.map(|(key, _)| key).collect::<Vec<_>>()
+        });
+        engine.major_compact().unwrap();
+        start.send(()).unwrap();
+
+        let rest = worker.join().unwrap();
+        assert_eq!(rest.len(), 99);
+        for (i, key) in rest.iter().enumerate() {
+            assert_eq!(key, &format!("th_{:04}", i + 1).into_bytes());
+        }
+    }
 }
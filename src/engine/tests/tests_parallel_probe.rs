@@ -0,0 +1,74 @@
+//! Tests for parallel SSTable bloom probing on point lookups
+//! (`EngineConfig::parallel_sstable_probe`).
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, EngineConfig};
+    use tempfile::TempDir;
+
+    fn parallel_probe_config() -> EngineConfig {
+        EngineConfig {
+            parallel_sstable_probe: true,
+            thread_pool_size: 3,
+            ..multi_sstable_config()
+        }
+    }
+
+    /// # Scenario
+    /// Point lookups with parallel probing agree with sequential lookups.
+    ///
+    /// # Starting environment
+    /// Well over `PARALLEL_PROBE_MIN_SSTABLES` SSTables containing
+    /// overwrites, point deletes, and a range delete.
+    ///
+    /// # Actions
+    /// 1. Record `get` results for every key with the flag off.
+    /// 2. Reopen with `parallel_sstable_probe: true`.
+    ///
+    /// # Expected behavior
+    /// Every key — present, overwritten, deleted, range-deleted, or never
+    /// written — returns the same result.
+    #[test]
+    fn parallel_probe_matches_sequential() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 300, "pp");
+        for i in (0..300u32).step_by(5) {
+            engine
+                .put(format!("pp_{i:04}").into_bytes(), b"overwritten".to_vec())
+                .unwrap();
+        }
+        for i in (0..300u32).step_by(11) {
+            engine.delete(format!("pp_{i:04}").into_bytes()).unwrap();
+        }
+        engine
+            .delete_range(b"pp_0100".to_vec(), b"pp_0150".to_vec())
+            .unwrap();
+        engine.flush_all_frozen().unwrap();
+        assert!(engine.stats().unwrap().sstables_count >= 8);
+
+        let keys: Vec<Vec<u8>> = (0..320u32)
+            .map(|i| format!("pp_{i:04}").into_bytes())
+            .collect();
+        let expected: Vec<_> = keys
+            .iter()
+            .map(|k| engine.get(k.clone()).unwrap())
+            .collect();
+        engine.close().unwrap();
+
+        let engine = Engine::open(dir.path(), parallel_probe_config()).unwrap();
+        for (key, want) in keys.iter().zip(&expected) {
+            assert_eq!(
+                &engine.get(key.clone()).unwrap(),
+                want,
+                "mismatch for {}",
+                String::from_utf8_lossy(key)
+            );
+        }
+        assert!(engine.get(b"pp_0120".to_vec()).unwrap().is_none());
+        assert_eq!(
+            engine.get(b"pp_0005".to_vec()).unwrap().as_deref(),
+            Some(&b"overwritten"[..])
+        );
+    }
+}
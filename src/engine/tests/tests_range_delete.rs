@@ -14,7 +14,15 @@
 //! - **Adjacent ranges** — touching but non-overlapping intervals.
 //! - **Range beyond existing keys** — no error; keys within range are deleted.
 //! - **Delete-all** — `[\x00, \xff)` wipes every key.
+//! - **Unbounded** — `delete_range_from(start)` also deletes keys longer
+//!   than any bounded end, through flush, compaction, and reopen.
 //! - **Cross-layer** — a memtable range-delete hides SSTable keys.
+//! - **Coalescing** — adjacent range deletes merge into one tombstone on
+//!   flush unless a newer put inside them would become hidden.
+//! - **Scan skip-ahead** — scans over a wide delete seek older SSTables
+//!   past the range and still return newer puts inside it.
+//! - **Compaction bounds** — a tombstone below every point key of the
+//!   merged SSTables survives minor compaction.
 //!
 //! Local helpers: `populate(n)` inserts `key_00`..`key_{n-1}` with
 //! corresponding `val_*` values; `assert_exists(i)` / `assert_deleted(i)`
@@ -384,6 +392,55 @@ mod tests {
         }
     }
 
+    // ----------------------------------------------------------------
+    // Unbounded range delete
+    // ----------------------------------------------------------------
+
+    /// # Scenario
+    /// `delete_range_from` deletes every key from its start on, including
+    /// keys a bounded `[\x00, \xff)` range would miss.
+    ///
+    /// # Starting environment
+    /// Fresh engine; 10 keys inserted, plus `\xff` repeated 64 times and
+    /// `key_09` followed by 200 `\xff` bytes.
+    ///
+    /// # Actions
+    /// 1. `delete_range_from("key_05")`.
+    /// 2. Get each key and scan everything.
+    /// 3. Put `key_07` again.
+    ///
+    /// # Expected behavior
+    /// Keys 0–4 remain; keys 5–9 and both long keys are deleted. The
+    /// later put of `key_07` is visible.
+    #[test]
+    fn memtable__range_delete_from_covers_longer_keys() {
+        let tmp = TempDir::new().unwrap();
+        let engine = Engine::open(tmp.path(), memtable_only_config()).unwrap();
+        populate(&engine, 10);
+        let all_ff = vec![0xFF; 64];
+        let long_key = [b"key_09".as_slice(), &[0xFF; 200]].concat();
+        engine.put(all_ff.clone(), b"v".to_vec()).unwrap();
+        engine.put(long_key.clone(), b"v".to_vec()).unwrap();
+
+        engine.delete_range_from(b"key_05".to_vec()).unwrap();
+
+        for i in 0..5 {
+            assert_exists(&engine, i);
+        }
+        for i in 5..10 {
+            assert_deleted(&engine, i);
+        }
+        assert_eq!(engine.get(all_ff).unwrap(), None);
+        assert_eq!(engine.get(long_key).unwrap(), None);
+        assert_eq!(collect_scan(&engine, b"\x00", &[0xFF; 65]).len(), 5);
+
+        engine.put(b"key_07".to_vec(), b"again".to_vec()).unwrap();
+        assert_eq!(
+            engine.get(b"key_07".to_vec()).unwrap(),
+            Some(b"again".to_vec())
+        );
+    }
+
     // ----------------------------------------------------------------
     // Range delete with SSTables
     // ----------------------------------------------------------------
@@ -440,4 +497,295 @@ mod tests {
             );
         }
     }
+
+    // ----------------------------------------------------------------
+    // Tombstone coalescing on flush
+    // ----------------------------------------------------------------
+
+    /// Config whose buffer holds 50 range deletes but freezes once the
+    /// filler keys are written.
+    fn coalesce_config() -> crate::engine::EngineConfig {
+        crate::engine::EngineConfig {
+            write_buffer_size: 16 * 1024,
+            ..memtable_only_config()
+        }
+    }
+
+    /// Writes filler keys outside every deleted range until the memtable
+    /// freezes, then flushes everything that is frozen.
+    fn freeze_and_flush(engine: &Engine) {
+        for i in 0..400u32 {
+            engine
+                .put(format!("zz_{i:04}").into_bytes(), vec![b'f'; 100])
+                .unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+    }
+
+    fn range_tombstones_on_disk(engine: &Engine) -> u64 {
+        engine
+            .sstable_metadata()
+            .unwrap()
+            .iter()
+            .map(|m| m.range_tombstone_count)
+            .sum()
+    }
+
+    /// # Scenario
+    /// Many adjacent range deletes are coalesced into one tombstone on flush.
+    ///
+    /// # Starting environment
+    /// Engine with a 16 KiB buffer and 50 keys `key_00`..`key_49`.
+    ///
+    /// # Actions
+    /// 1. Issue 50 one-key range deletes `[key_i, key_{i+1})`, each with
+    ///    its own LSN, plus a nested `[key_10, key_12)` after them.
+    /// 2. Write filler keys until the memtable freezes; flush.
+    ///
+    /// # Expected behavior
+    /// The SSTable holds a single range tombstone and every `key_*`
+    /// remains deleted, also after reopen.
+    #[test]
+    fn memtable_sstable__flush_coalesces_adjacent_range_deletes() {
+        let tmp = TempDir::new().unwrap();
+        let engine = Engine::open(tmp.path(), coalesce_config()).unwrap();
+        populate(&engine, 50);
+        engine.flush_all_frozen().unwrap();
+
+        for i in 0..50 {
+            engine
+                .delete_range(
+                    format!("key_{:02}", i).into_bytes(),
+                    format!("key_{:02}", i + 1).into_bytes(),
+                )
+                .unwrap();
+        }
+        engine
+            .delete_range(b"key_10".to_vec(), b"key_12".to_vec())
+            .unwrap();
+        freeze_and_flush(&engine);
+
+        assert_eq!(range_tombstones_on_disk(&engine), 1);
+        for i in 0..50 {
+            assert_deleted(&engine, i);
+        }
+
+        engine.close().unwrap();
+        let engine = reopen(tmp.path());
+        for i in 0..50 {
+            assert_deleted(&engine, i);
+        }
+    }
+
+    /// # Scenario
+    /// A put between two adjacent range deletes blocks coalescing when the
+    /// merged tombstone would hide it.
+    ///
+    /// # Starting environment
+    /// Engine with a 16 KiB buffer and 10 keys `key_00`..`key_09`.
+    ///
+    /// # Actions
+    /// 1. `delete_range(key_00, key_05)`.
+    /// 2. `put(key_02)` — newer than the first delete.
+    /// 3. `delete_range(key_05, key_10)` — newer than the put.
+    /// 4. Write filler keys until the memtable freezes; flush.
+    ///
+    /// # Expected behavior
+    /// Both range tombstones are kept and `key_02` stays visible; merging
+    /// them at the higher LSN would have deleted it.
+    #[test]
+    fn memtable_sstable__flush_keeps_range_deletes_split_by_put() {
+        let tmp = TempDir::new().unwrap();
+        let engine = Engine::open(tmp.path(), coalesce_config()).unwrap();
+        populate(&engine, 10);
+
+        engine
+            .delete_range(b"key_00".to_vec(), b"key_05".to_vec())
+            .unwrap();
+        engine.put(b"key_02".to_vec(), b"val_02".to_vec()).unwrap();
+        engine
+            .delete_range(b"key_05".to_vec(), b"key_10".to_vec())
+            .unwrap();
+        freeze_and_flush(&engine);
+
+        assert_eq!(range_tombstones_on_disk(&engine), 2);
+        assert_exists(&engine, 2);
+        for i in (0..10).filter(|&i| i != 2) {
+            assert_deleted(&engine, i);
+        }
+    }
+
+    /// # Scenario
+    /// A scan across a wide range delete skips the covered SSTable entries
+    /// without losing anything visible.
+    ///
+    /// # Starting environment
+    /// Keys `k_0000..k_0499` spread over several SSTables.
+    ///
+    /// # Actions
+    /// 1. `delete_range(k_0100, k_0400)`, then re-put `k_0250`.
+    /// 2. Scan everything, and from inside the deleted range, with the
+    ///    tombstone in the memtable.
+    /// 3. Flush the tombstone to an SSTable and scan again.
+    ///
+    /// # Expected behavior
+    /// Every scan returns `k_0000..k_0099`, the re-put `k_0250`, and
+    /// `k_0400..k_0499`, in order.
+    #[test]
+    fn memtable_sstable__scan_skips_wide_delete() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), multi_sstable_config()).unwrap();
+        for i in 0..500u32 {
+            let key = format!("k_{i:04}").into_bytes();
+            engine.put(key, format!("v_{i:04}").into_bytes()).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+        assert!(engine.stats().unwrap().sstables_count >= 2);
+
+        engine
+            .delete_range(b"k_0100".to_vec(), b"k_0400".to_vec())
+            .unwrap();
+        engine.put(b"k_0250".to_vec(), b"revived".to_vec()).unwrap();
+
+        let mut expected: Vec<(Vec<u8>, Vec<u8>)> = (0..100u32)
+            .chain(400..500)
+            .map(|i| {
+                (
+                    format!("k_{i:04}").into_bytes(),
+                    format!("v_{i:04}").into_bytes(),
+                )
+            })
+            .collect();
+        expected.insert(100, (b"k_0250".to_vec(), b"revived".to_vec()));
+
+        assert_eq!(collect_scan(&engine, b"k_", b"k~"), expected);
+        assert_eq!(
+            collect_scan(&engine, b"k_0200", b"k_0450"),
+            expected[100..151].to_vec()
+        );
+
+        for i in 0..200u32 {
+            let key = format!("z_{i:04}").into_bytes();
+            engine.put(key, b"filler".to_vec()).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+
+        assert_eq!(collect_scan(&engine, b"k_", b"k~"), expected);
+        assert_eq!(
+            collect_scan(&engine, b"k_0200", b"k_0450"),
+            expected[100..151].to_vec()
+        );
+    }
+
+    /// # Scenario
+    /// An unbounded range tombstone keeps hiding keys once it is flushed,
+    /// compacted, and read back after reopen.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables holding `key_0000`..`key_0199`, plus
+    /// `key_0150` followed by 200 `\xff` bytes.
+    ///
+    /// # Actions
+    /// 1. `delete_range_from("key_0100")`.
+    /// 2. Write filler keys (newer than the delete) and flush.
+    /// 3. Run major compaction.
+    /// 4. Close and reopen.
+    ///
+    /// # Expected behavior
+    /// After each step, a scan of `key_*` returns exactly `key_0000`..
+    /// `key_0099`, the long key is deleted, and the filler keys are
+    /// visible.
+    #[test]
+    fn memtable_sstable__range_delete_from_survives_flush_and_compaction() {
+        let tmp = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(tmp.path(), 200, "key");
+        let long_key = [b"key_0150".as_slice(), &[0xFF; 200]].concat();
+        engine.put(long_key.clone(), b"long".to_vec()).unwrap();
+
+        engine.delete_range_from(b"key_0100".to_vec()).unwrap();
+        freeze_and_flush(&engine);
+        assert!(range_tombstones_on_disk(&engine) >= 1);
+
+        let check = |engine: &Engine| {
+            let keys: Vec<Vec<u8>> = collect_scan(engine, b"key_", b"key_\xff")
+                .into_iter()
+                .map(|(key, _)| key)
+                .collect();
+            let expected: Vec<Vec<u8>> = (0..100)
+                .map(|i| format!("key_{i:04}").into_bytes())
+                .collect();
+            assert_eq!(keys, expected);
+            assert_eq!(engine.get(long_key.clone()).unwrap(), None);
+            assert!(engine.get(b"zz_0399".to_vec()).unwrap().is_some());
+        };
+
+        check(&engine);
+        engine.major_compact().unwrap();
+        check(&engine);
+
+        engine.close().unwrap();
+        drop(engine);
+        let engine = Engine::open(tmp.path(), multi_sstable_config()).unwrap();
+        check(&engine);
+    }
+
+    /// # Scenario
+    /// Minor compaction merges SSTables whose point keys all lie above a
+    /// range tombstone they carry; the tombstone must survive the merge.
+    ///
+    /// # Starting environment
+    /// Engine with a 16 KiB buffer and `min_threshold = 2`. `key_00`..
+    /// `key_59` plus enough `yy_*` filler to span several buffers are
+    /// flushed and major-compacted into one large SSTable.
+    ///
+    /// # Actions
+    /// 1. `delete_range("key_05", "key_18")`, then write `zz_*` filler
+    ///    and flush — the new SSTable's point keys all sort above the
+    ///    tombstone.
+    /// 2. Overwrite the `zz_*` filler and flush a second small SSTable.
+    /// 3. Run minor compaction, which merges the two small SSTables and
+    ///    leaves the large one alone.
+    ///
+    /// # Expected behavior
+    /// Keys 5–17 stay deleted before and after compaction; keys 0–4 and
+    /// 18–59 remain present.
+    #[test]
+    fn memtable_sstable__minor_compaction_keeps_tombstone_below_point_keys() {
+        let tmp = TempDir::new().unwrap();
+        let config = crate::engine::EngineConfig {
+            min_threshold: 2,
+            ..coalesce_config()
+        };
+        let engine = Engine::open(tmp.path(), config).unwrap();
+        populate(&engine, 60);
+        for i in 0..1200u32 {
+            engine
+                .put(format!("yy_{i:04}").into_bytes(), vec![b'f'; 100])
+                .unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+        engine.major_compact().unwrap();
+
+        engine
+            .delete_range(b"key_05".to_vec(), b"key_18".to_vec())
+            .unwrap();
+        freeze_and_flush(&engine);
+        freeze_and_flush(&engine);
+        assert!(range_tombstones_on_disk(&engine) >= 1);
+
+        let check = |engine: &Engine| {
+            for i in 0..60 {
+                if (5..18).contains(&i) {
+                    assert_deleted(engine, i);
+                } else {
+                    assert_exists(engine, i);
+                }
+            }
+        };
+
+        check(&engine);
+        assert!(engine.minor_compact().unwrap());
+        assert!(range_tombstones_on_disk(&engine) >= 1);
+        check(&engine);
+    }
 }
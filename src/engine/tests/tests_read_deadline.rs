@@ -0,0 +1,56 @@
+//! Tests for read deadlines (`ReadOptions::deadline`) on point lookups.
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{EngineError, ReadOptions};
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    /// # Scenario
+    /// A lookup that still has to probe an SSTable after its deadline
+    /// gives up; one answered by the memtable does not.
+    ///
+    /// # Starting environment
+    /// Engine with `key_0000..key_0299` spread over several SSTables, then
+    /// `hot` put into the active memtable.
+    ///
+    /// # Actions
+    /// 1. `get_opt` `key_0000` and `hot` with a deadline already passed.
+    /// 2. `get_opt` `key_0000` with a deadline a minute away.
+    ///
+    /// # Expected behavior
+    /// - `key_0000` fails with `EngineError::TimedOut`.
+    /// - `hot` is returned from the memtable.
+    /// - With the distant deadline, `key_0000` is found.
+    #[test]
+    fn deadline_stops_sstable_probes() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 300, "key");
+        engine.put(b"hot".to_vec(), b"value".to_vec()).unwrap();
+
+        let passed = ReadOptions {
+            deadline: Some(Instant::now() - Duration::from_millis(1)),
+            ..ReadOptions::default()
+        };
+        assert!(matches!(
+            engine.get_opt(b"key_0000".to_vec(), &passed),
+            Err(EngineError::TimedOut)
+        ));
+        assert_eq!(
+            engine.get_opt(b"hot".to_vec(), &passed).unwrap(),
+            Some(b"value".to_vec())
+        );
+
+        let distant = ReadOptions {
+            deadline: Some(Instant::now() + Duration::from_secs(60)),
+            ..ReadOptions::default()
+        };
+        assert!(
+            engine
+                .get_opt(b"key_0000".to_vec(), &distant)
+                .unwrap()
+                .is_some()
+        );
+    }
+}
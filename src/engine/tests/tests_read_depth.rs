@@ -0,0 +1,138 @@
+//! Tests for the read depth guardrail
+//! (`Engine::hint_read_depth_compaction`).
+//!
+//! ## Coverage areas
+//! - Disabled by a zero threshold
+//! - No hint before the window holds enough lookups
+//! - No hint while the 99th percentile stays at the threshold
+//! - A hint over the newest SSTables once it is exceeded, compacted by
+//!   `hinted_compact` down to the threshold
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, EngineConfig};
+    use crate::range_lock::RangeLocks;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Opens an engine with several SSTables and the given threshold.
+    fn engine_with_threshold(path: &Path, threshold: usize) -> Engine {
+        let engine = Engine::open(
+            path,
+            EngineConfig {
+                read_depth_compaction_threshold: threshold,
+                ..multi_sstable_config()
+            },
+        )
+        .unwrap();
+        for i in 0..300u32 {
+            let key = format!("rd_{i:04}").into_bytes();
+            let value = format!("value_with_some_padding_{i:04}").into_bytes();
+            engine.put(key, value).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+        assert!(engine.stats().unwrap().sstables_count >= 3);
+        engine
+    }
+
+    /// Looks up `count` keys that were never written; each probes every
+    /// SSTable.
+    fn get_absent(engine: &Engine, count: u32) {
+        for i in 0..count {
+            let key = format!("absent_{i:04}").into_bytes();
+            assert!(engine.get(key).unwrap().is_none());
+        }
+    }
+
+    /// # Scenario
+    /// With the threshold at `0`, lookups are not tracked.
+    ///
+    /// # Actions
+    /// 1. Look up 200 absent keys over several SSTables.
+    /// 2. Check for a hint.
+    ///
+    /// # Expected behavior
+    /// No hint is recorded.
+    #[test]
+    fn read_depth_disabled() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_threshold(dir.path(), 0);
+
+        get_absent(&engine, 200);
+        assert!(!engine.hint_read_depth_compaction().unwrap());
+        assert!(!engine.hinted_compact(&RangeLocks::default()).unwrap());
+    }
+
+    /// # Scenario
+    /// Every lookup probes more SSTables than allowed, but too few have
+    /// been made to judge, and then the threshold is high enough.
+    ///
+    /// # Actions
+    /// 1. Threshold 2: look up 99 absent keys, check for a hint.
+    /// 2. Threshold equal to the SSTable count: look up 200 absent keys,
+    ///    check for a hint.
+    ///
+    /// # Expected behavior
+    /// No hint in either case.
+    #[test]
+    fn read_depth_within_bounds() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_threshold(dir.path(), 2);
+        get_absent(&engine, 99);
+        assert!(!engine.hint_read_depth_compaction().unwrap());
+        let sstables = engine.stats().unwrap().sstables_count;
+        drop(engine);
+
+        let config = EngineConfig {
+            read_depth_compaction_threshold: sstables,
+            ..multi_sstable_config()
+        };
+        let engine = Engine::open(dir.path(), config).unwrap();
+        get_absent(&engine, 200);
+        assert!(!engine.hint_read_depth_compaction().unwrap());
+    }
+
+    /// # Scenario
+    /// Lookups probe more SSTables than the threshold allows.
+    ///
+    /// # Starting environment
+    /// Several SSTables, threshold 2; no size bucket is compacted.
+    ///
+    /// # Actions
+    /// 1. Look up 200 absent keys, check for a hint.
+    /// 2. Run hinted compaction until it has nothing left to do.
+    /// 3. Check for a hint again, then look up every key.
+    ///
+    /// # Expected behavior
+    /// - The hint is recorded and merges all but the oldest SSTable,
+    ///   leaving two.
+    /// - The window starts over, so no second hint follows at once.
+    /// - Every key still reads back.
+    #[test]
+    fn read_depth_exceeded_compacts_newest() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_threshold(dir.path(), 2);
+        let before = engine.sstable_metadata().unwrap();
+
+        get_absent(&engine, 200);
+        assert!(engine.hint_read_depth_compaction().unwrap());
+        while engine.hinted_compact(&RangeLocks::default()).unwrap() {}
+
+        let after = engine.sstable_metadata().unwrap();
+        assert_eq!(after.len(), 2, "{after:?}");
+        assert!(
+            after
+                .iter()
+                .any(|meta| meta.id == before.last().unwrap().id),
+            "the oldest SSTable is left alone"
+        );
+        assert!(!engine.hint_read_depth_compaction().unwrap());
+
+        for i in 0..300u32 {
+            let key = format!("rd_{i:04}").into_bytes();
+            let value = format!("value_with_some_padding_{i:04}").into_bytes();
+            assert_eq!(engine.get(key).unwrap(), Some(value));
+        }
+    }
+}
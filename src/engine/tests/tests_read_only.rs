@@ -0,0 +1,142 @@
+//! Tests for `ReadOnlyEngine`, the reader side of cross-process access.
+//!
+//! ## Coverage areas
+//! - Reads see the writer's SSTables but not its memtables
+//! - `refresh` publishes new SSTable sets after flushes and compactions
+//! - An unchanged manifest leaves the published set alone
+//! - Opening a directory without a manifest fails
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{EngineError, ReadOnlyEngine, ReadOptions};
+    use tempfile::TempDir;
+
+    /// Writes 100 filler keys and flushes, so every earlier write of
+    /// `engine` (1 KiB write buffer) lands in an SSTable.
+    fn push_to_sstables(engine: &crate::engine::Engine) {
+        for i in 0..100u32 {
+            let key = format!("zz_{i:04}").into_bytes();
+            engine.put(key, b"filler_value".to_vec()).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+    }
+
+    /// # Scenario
+    /// A read-only engine serves the writer's flushed data only.
+    ///
+    /// # Starting environment
+    /// Writer engine with `ro_0000..ro_0199` in several SSTables and one
+    /// more key in its active memtable.
+    ///
+    /// # Actions
+    /// 1. Open a `ReadOnlyEngine` on the same directory.
+    /// 2. Get and scan the flushed keys and the memtable key.
+    ///
+    /// # Expected behavior
+    /// The reader has the writer's SSTables, returns every flushed value,
+    /// and does not see the memtable key.
+    #[test]
+    fn read_only_serves_flushed_sstables() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 200, "ro");
+        push_to_sstables(&engine);
+        engine
+            .put(b"ro_mem".to_vec(), b"unflushed".to_vec())
+            .unwrap();
+
+        let reader = ReadOnlyEngine::open(dir.path()).unwrap();
+        assert_eq!(
+            reader.sstable_count().unwrap(),
+            engine.stats().unwrap().sstables_count
+        );
+        let opts = ReadOptions::default();
+        assert_eq!(
+            reader.get_opt(b"ro_0042", &opts).unwrap(),
+            Some(b"value_with_some_padding_0042".to_vec())
+        );
+        assert_eq!(reader.get_opt(b"ro_mem", &opts).unwrap(), None);
+
+        let scanned: Vec<_> = reader.scan_opt(b"ro_", b"ro_~", &opts).unwrap().collect();
+        assert_eq!(scanned.len(), 200);
+        assert!(scanned.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(reader.read_stats().gets >= 2);
+    }
+
+    /// # Scenario
+    /// The reader follows flushes and compactions of a live writer.
+    ///
+    /// # Starting environment
+    /// Writer engine with flushed keys; a `ReadOnlyEngine` opened on it.
+    ///
+    /// # Actions
+    /// 1. Refresh with no writes.
+    /// 2. Overwrite a key, flush, refresh.
+    /// 3. Major-compact, refresh.
+    ///
+    /// # Expected behavior
+    /// The first refresh reports no change. After the flush the reader has
+    /// more SSTables and the new value; after compaction it has a single
+    /// SSTable and the manifest version has grown at every step.
+    #[test]
+    fn read_only_refresh_follows_writer() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 200, "ro");
+        push_to_sstables(&engine);
+        let reader = ReadOnlyEngine::open(dir.path()).unwrap();
+        let opts = ReadOptions::default();
+        let tables = reader.sstable_count().unwrap();
+        let version = reader.manifest_version().unwrap();
+
+        assert!(!reader.refresh().unwrap());
+        assert_eq!(reader.manifest_version().unwrap(), version);
+
+        engine
+            .put(b"ro_0007".to_vec(), b"updated".to_vec())
+            .unwrap();
+        push_to_sstables(&engine);
+        assert!(reader.refresh().unwrap());
+        assert!(reader.sstable_count().unwrap() > tables);
+        assert!(reader.manifest_version().unwrap() > version);
+        assert_eq!(
+            reader.get_opt(b"ro_0007", &opts).unwrap(),
+            Some(b"updated".to_vec())
+        );
+
+        let version = reader.manifest_version().unwrap();
+        assert!(engine.major_compact().unwrap());
+        assert!(reader.refresh().unwrap());
+        assert_eq!(reader.sstable_count().unwrap(), 1);
+        assert!(reader.manifest_version().unwrap() > version);
+        assert_eq!(
+            reader.get_opt(b"ro_0007", &opts).unwrap(),
+            Some(b"updated".to_vec())
+        );
+        assert_eq!(
+            reader.get_opt(b"ro_0008", &opts).unwrap(),
+            Some(b"value_with_some_padding_0008".to_vec())
+        );
+    }
+
+    /// # Scenario
+    /// A directory without a database is opened read-only.
+    ///
+    /// # Starting environment
+    /// Empty temporary directory.
+    ///
+    /// # Actions
+    /// 1. `ReadOnlyEngine::open` on it.
+    ///
+    /// # Expected behavior
+    /// Fails with a `NotFound` I/O error and creates nothing.
+    #[test]
+    fn read_only_open_missing_database() {
+        let dir = TempDir::new().unwrap();
+        match ReadOnlyEngine::open(dir.path()) {
+            Err(EngineError::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::NotFound),
+            Err(e) => panic!("expected NotFound, got {e}"),
+            Ok(_) => panic!("opened an empty directory"),
+        }
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}
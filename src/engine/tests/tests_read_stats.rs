@@ -0,0 +1,282 @@
+//! Tests for point-lookup counters (`Engine::read_stats` and
+//! `SSTableMetadata::reads`).
+//!
+//! ## Coverage areas
+//! - Memtable hits count as gets without touching any bloom filter
+//! - Lookups of absent keys split every bloom check into a negative or a
+//!   false positive, and only false positives read a block
+//! - Per-SSTable counters add up to the engine counters
+//! - Tables excluded by the parallel bloom pass count as bloom negatives
+//! - Reads past `read_fanout_limit` are counted and reported, and the
+//!   fallback skips tables whose key range rules the key out
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, EngineConfig, PARALLEL_PROBE_MIN_SSTABLES, ReadFanout, ReadStats};
+    use tempfile::TempDir;
+
+    /// Looks up `count` keys that were never written.
+    fn get_absent(engine: &Engine, count: u32) {
+        for i in 0..count {
+            let key = format!("absent_{i:04}").into_bytes();
+            assert!(engine.get(key).unwrap().is_none());
+        }
+    }
+
+    /// Sums the per-SSTable counters.
+    fn sum_sstable_reads(engine: &Engine) -> ReadStats {
+        let mut total = ReadStats::default();
+        for meta in engine.sstable_metadata().unwrap() {
+            total += meta.reads;
+        }
+        total
+    }
+
+    /// # Scenario
+    /// A key served by the active memtable never reaches an SSTable.
+    ///
+    /// # Starting environment
+    /// Memtable-only engine with one key.
+    ///
+    /// # Actions
+    /// 1. Get the key twice.
+    ///
+    /// # Expected behavior
+    /// Two gets are counted; every other counter stays zero.
+    #[test]
+    fn read_stats_memtable_hit() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), memtable_only_config()).unwrap();
+        engine.put(b"k".to_vec(), b"v".to_vec()).unwrap();
+
+        engine.get(b"k".to_vec()).unwrap();
+        engine.get(b"k".to_vec()).unwrap();
+
+        assert_eq!(
+            engine.read_stats(),
+            ReadStats {
+                gets: 2,
+                ..ReadStats::default()
+            }
+        );
+    }
+
+    /// # Scenario
+    /// Absent-key lookups classify every bloom check.
+    ///
+    /// # Starting environment
+    /// Several SSTables holding `rs_*` keys.
+    ///
+    /// # Actions
+    /// 1. Get 200 keys that were never written.
+    ///
+    /// # Expected behavior
+    /// Every SSTable is checked for every key; each check is either a
+    /// negative or a false positive, block reads equal false positives,
+    /// most checks are negatives, and the per-SSTable counters add up to
+    /// the engine counters.
+    #[test]
+    fn read_stats_absent_keys() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 300, "rs");
+        let sstables = engine.stats().unwrap().sstables_count as u64;
+
+        get_absent(&engine, 200);
+
+        let stats = engine.read_stats();
+        assert_eq!(stats.gets, 200);
+        assert_eq!(stats.bloom_checks, 200 * sstables);
+        assert_eq!(
+            stats.bloom_negatives + stats.bloom_false_positives,
+            stats.bloom_checks
+        );
+        assert_eq!(stats.block_reads, stats.bloom_false_positives);
+        assert!(stats.bloom_false_positive_rate() < 0.5);
+
+        for meta in engine.sstable_metadata().unwrap() {
+            assert_eq!(meta.reads.gets, 200);
+        }
+        let per_table = sum_sstable_reads(&engine);
+        assert_eq!(per_table.bloom_checks, stats.bloom_checks);
+        assert_eq!(per_table.bloom_negatives, stats.bloom_negatives);
+        assert_eq!(per_table.bloom_false_positives, stats.bloom_false_positives);
+        assert_eq!(per_table.block_reads, stats.block_reads);
+    }
+
+    /// # Scenario
+    /// A hit in an SSTable reads its block and is not a false positive.
+    ///
+    /// # Starting environment
+    /// Several SSTables holding `rs_*` keys.
+    ///
+    /// # Actions
+    /// 1. Get the smallest key of the oldest SSTable.
+    ///
+    /// # Expected behavior
+    /// That table records one get, one bloom check, one block read, and no
+    /// false positive; the engine counts one get.
+    #[test]
+    fn read_stats_sstable_hit() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 300, "rs");
+        let oldest = engine.sstable_metadata().unwrap().pop().unwrap();
+
+        assert!(engine.get(oldest.min_key.clone()).unwrap().is_some());
+
+        let reads = engine
+            .sstable_metadata()
+            .unwrap()
+            .into_iter()
+            .find(|m| m.id == oldest.id)
+            .unwrap()
+            .reads;
+        assert_eq!(
+            reads,
+            ReadStats {
+                gets: 1,
+                bloom_checks: 1,
+                bloom_negatives: 0,
+                bloom_false_positives: 0,
+                block_reads: 1,
+                row_cache_hits: 0,
+                fanout_exceeded: 0,
+            }
+        );
+        assert_eq!(engine.read_stats().gets, 1);
+        assert!(engine.read_stats().block_reads >= 1);
+    }
+
+    /// # Scenario
+    /// Tables ruled out by the parallel bloom pass are still counted.
+    ///
+    /// # Starting environment
+    /// At least `PARALLEL_PROBE_MIN_SSTABLES` SSTables, reopened with
+    /// `parallel_sstable_probe: true`.
+    ///
+    /// # Actions
+    /// 1. Get 100 keys that were never written.
+    ///
+    /// # Expected behavior
+    /// Same accounting as the sequential path: one check per table per
+    /// key, each a negative or a false positive.
+    #[test]
+    fn read_stats_parallel_probe() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 600, "rs");
+        let sstables = engine.stats().unwrap().sstables_count as u64;
+        assert!(sstables >= PARALLEL_PROBE_MIN_SSTABLES as u64);
+        engine.close().unwrap();
+
+        let engine = Engine::open(
+            dir.path(),
+            EngineConfig {
+                parallel_sstable_probe: true,
+                thread_pool_size: 3,
+                ..multi_sstable_config()
+            },
+        )
+        .unwrap();
+        get_absent(&engine, 100);
+
+        let stats = engine.read_stats();
+        assert_eq!(stats.gets, 100);
+        assert_eq!(stats.bloom_checks, 100 * sstables);
+        assert_eq!(
+            stats.bloom_negatives + stats.bloom_false_positives,
+            stats.bloom_checks
+        );
+        assert_eq!(sum_sstable_reads(&engine).bloom_checks, stats.bloom_checks);
+    }
+
+    /// # Scenario
+    /// Lookups and scans needing more SSTables than the fan-out limit
+    /// are counted and reported once.
+    ///
+    /// # Starting environment
+    /// Several SSTables, reopened with `read_fanout_limit = 2`.
+    ///
+    /// # Actions
+    /// 1. Get 10 keys that were never written, and scan every key.
+    /// 2. Take the fan-out report twice.
+    ///
+    /// # Expected behavior
+    /// - `fanout_exceeded` counts the 11 reads.
+    /// - The first report names them, with every SSTable as the widest
+    ///   fan-out; the second is empty.
+    #[test]
+    fn read_fanout_counts_and_reports() {
+        let dir = TempDir::new().unwrap();
+        engine_with_multi_sstables(dir.path(), 300, "rs")
+            .close()
+            .unwrap();
+
+        let engine = Engine::open(
+            dir.path(),
+            EngineConfig {
+                read_fanout_limit: 2,
+                ..multi_sstable_config()
+            },
+        )
+        .unwrap();
+        let sstables = engine.stats().unwrap().sstables_count;
+        assert!(sstables > 2);
+        get_absent(&engine, 10);
+        assert_eq!(engine.scan(b"rs_", b"rs_~").unwrap().count(), 300);
+
+        assert_eq!(engine.read_stats().fanout_exceeded, 11);
+        assert_eq!(
+            engine.take_read_fanout(0),
+            Some(ReadFanout {
+                partition: 0,
+                reads: 11,
+                widest: sstables,
+                limit: 2,
+                sstables,
+            })
+        );
+        assert_eq!(engine.take_read_fanout(0), None);
+    }
+
+    /// # Scenario
+    /// The fan-out fallback skips SSTables whose key range rules the key
+    /// out, without changing results.
+    ///
+    /// # Starting environment
+    /// Several SSTables over disjoint `rs_*` key ranges, reopened with
+    /// `read_fanout_limit = 1` and `read_fanout_fallback`.
+    ///
+    /// # Actions
+    /// 1. Get 50 keys sorting before every SSTable.
+    /// 2. Get every written key.
+    ///
+    /// # Expected behavior
+    /// - The absent keys touch no bloom filter.
+    /// - Every written key is found.
+    #[test]
+    fn read_fanout_fallback_skips_out_of_range() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 300, "rs");
+        assert!(engine.stats().unwrap().sstables_count > 1);
+        engine.close().unwrap();
+
+        let engine = Engine::open(
+            dir.path(),
+            EngineConfig {
+                read_fanout_limit: 1,
+                read_fanout_fallback: true,
+                ..multi_sstable_config()
+            },
+        )
+        .unwrap();
+        get_absent(&engine, 50);
+        let stats = engine.read_stats();
+        assert_eq!(stats.fanout_exceeded, 50);
+        assert_eq!(stats.bloom_checks, 0);
+
+        for i in 0..300 {
+            let key = format!("rs_{i:04}").into_bytes();
+            assert!(engine.get(key).unwrap().is_some());
+        }
+    }
+}
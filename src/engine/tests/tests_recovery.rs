@@ -5,7 +5,8 @@
 //! reopened. Every test verifies that data written before close is fully
 //! accessible after reopen. Coverage includes single puts, overwrites, point
 //! deletes, range deletes, large SSTable datasets, multiple reopen cycles,
-//! WAL-only replay, scan correctness after reopen, and overwrite chains.
+//! WAL-only replay, scan correctness after reopen, overwrite chains, and
+//! reopening a moved directory whose manifest holds absolute paths.
 //!
 //! ## Layer coverage
 //! - All tests use `memtable_sstable` (close flushes WAL/frozen → SSTable)
@@ -20,8 +21,11 @@
 #[cfg(test)]
 #[allow(non_snake_case)]
 mod tests {
-    use crate::engine::Engine;
     use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, MANIFEST_DIR, SSTABLE_DIR};
+    use crate::manifest::{Manifest, ManifestSstEntry};
+    use std::fs;
+    use std::path::Path;
     use tempfile::TempDir;
 
     // ----------------------------------------------------------------
@@ -445,4 +449,65 @@ mod tests {
         let engine = reopen(tmp.path());
         assert_eq!(engine.get(b"chain".to_vec()).unwrap(), Some(b"v4".to_vec()));
     }
+
+    // ----------------------------------------------------------------
+    // Legacy absolute paths → move directory → reopen
+    // ----------------------------------------------------------------
+
+    /// # Scenario
+    /// A manifest written by an older version records absolute SSTable
+    /// paths, and the database directory is then moved.
+    ///
+    /// # Starting environment
+    /// Engine in `db` with SSTables of `mv_0000`..`mv_0199`, closed.
+    ///
+    /// # Actions
+    /// 1. Rewrite every manifest entry to its absolute path; checkpoint.
+    /// 2. Rename `db` to `moved`; reopen from `moved`.
+    /// 3. Get every key; close; read the manifest entries.
+    ///
+    /// # Expected behavior
+    /// Reopen succeeds and reports every entry rewritten, every key reads
+    /// back, and the manifest then records each SSTable as
+    /// `sstables/<file name>`.
+    #[test]
+    fn memtable_sstable__legacy_absolute_paths_rewritten_after_move() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let db = tmp.path().join("db");
+        engine_with_sstables(&db, 200, "mv").close().unwrap();
+
+        {
+            let manifest = Manifest::open(db.join(MANIFEST_DIR)).unwrap();
+            let entries = manifest.get_sstables().unwrap();
+            let absolute = entries
+                .iter()
+                .map(|entry| ManifestSstEntry {
+                    id: entry.id,
+                    path: db.join(&entry.path),
+                })
+                .collect();
+            let ids = entries.iter().map(|entry| entry.id).collect();
+            manifest.apply_compaction(absolute, ids).unwrap();
+            manifest.checkpoint().unwrap();
+        }
+
+        let moved = tmp.path().join("moved");
+        fs::rename(&db, &moved).unwrap();
+        let engine = reopen(&moved);
+        let sst_count = engine.stats().unwrap().sstables_count;
+        assert_eq!(engine.recovery_report().manifest_paths_rewritten, sst_count);
+        for i in 0..200 {
+            let key = format!("mv_{i:04}").into_bytes();
+            assert!(engine.get(key).unwrap().is_some(), "mv_{i:04}");
+        }
+        engine.close().unwrap();
+
+        let manifest = Manifest::open(moved.join(MANIFEST_DIR)).unwrap();
+        for entry in manifest.get_sstables().unwrap() {
+            let name = entry.path.file_name().unwrap();
+            assert_eq!(entry.path, Path::new(SSTABLE_DIR).join(name));
+        }
+    }
 }
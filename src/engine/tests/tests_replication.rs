@@ -0,0 +1,285 @@
+//! Tests for SSTable deltas (`Engine::export_delta`, `Engine::apply_delta`).
+//!
+//! ## Coverage areas
+//! - A full export brings an empty follower to the leader's contents,
+//!   including writes still in the leader's active memtable
+//! - Incremental deltas ship only new SSTables and drop compacted ones
+//! - An export waits for a compaction building its output to commit
+//! - Deltas starting at the wrong version, or applied over local writes,
+//!   are rejected without changing the follower
+//! - A follower's state survives a reopen, and its LSNs continue after
+//!   the leader's
+
+#[cfg(test)]
+mod tests {
+    use crate::compaction::stcs::MajorCompaction;
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, EngineError};
+    use std::sync::Barrier;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    /// # Scenario
+    /// A full export followed by an incremental one keeps the follower in
+    /// step with the leader.
+    ///
+    /// # Starting environment
+    /// Leader with several SSTables plus unflushed writes; empty follower.
+    ///
+    /// # Actions
+    /// 1. Export from the follower's version and apply.
+    /// 2. Write and delete more keys on the leader; export from the
+    ///    follower's new version and apply.
+    ///
+    /// # Expected behavior
+    /// The first delta ships every SSTable; the second ships only new
+    /// ones. After each apply the follower's scan equals the leader's and
+    /// both are at the same version.
+    #[test]
+    fn replication_full_then_incremental() {
+        let leader_dir = TempDir::new().unwrap();
+        let follower_dir = TempDir::new().unwrap();
+        let leader = engine_with_multi_sstables(leader_dir.path(), 200, "a");
+        leader.put(b"a_tail".to_vec(), b"v".to_vec()).unwrap();
+        let follower = Engine::open(follower_dir.path(), multi_sstable_config()).unwrap();
+
+        let delta = leader
+            .export_delta(follower.replication_version().unwrap())
+            .unwrap();
+        assert_eq!(delta.sstables.len(), delta.live_ids.len());
+        follower.apply_delta(&delta).unwrap();
+        assert_eq!(
+            collect_scan(&follower, b"a", b"b"),
+            collect_scan(&leader, b"a", b"b")
+        );
+        assert_eq!(
+            follower.replication_version().unwrap(),
+            leader.replication_version().unwrap()
+        );
+
+        for i in 0..100u32 {
+            let key = format!("b_{i:04}").into_bytes();
+            leader.put(key, b"value".to_vec()).unwrap();
+        }
+        leader.delete(b"a_0000".to_vec()).unwrap();
+        let shipped_before = delta.live_ids.len();
+        let delta = leader
+            .export_delta(follower.replication_version().unwrap())
+            .unwrap();
+        assert!(delta.sstables.len() < delta.live_ids.len());
+        assert!(delta.live_ids.len() > shipped_before);
+        follower.apply_delta(&delta).unwrap();
+
+        assert_eq!(
+            collect_scan(&follower, b"a", b"c"),
+            collect_scan(&leader, b"a", b"c")
+        );
+        assert_eq!(follower.get(b"a_0000".to_vec()).unwrap(), None);
+    }
+
+    /// # Scenario
+    /// SSTables compacted away on the leader are dropped on the follower.
+    ///
+    /// # Starting environment
+    /// Follower caught up with a leader holding several SSTables.
+    ///
+    /// # Actions
+    /// 1. Major-compact the leader.
+    /// 2. Export and apply the next delta.
+    ///
+    /// # Expected behavior
+    /// The delta ships the single compacted SSTable; afterwards the
+    /// follower holds exactly the leader's SSTable ids and the same data.
+    #[test]
+    fn replication_follows_compaction() {
+        let leader_dir = TempDir::new().unwrap();
+        let follower_dir = TempDir::new().unwrap();
+        let leader = engine_with_multi_sstables(leader_dir.path(), 200, "c");
+        let follower = Engine::open(follower_dir.path(), multi_sstable_config()).unwrap();
+        let version = follower.replication_version().unwrap();
+        follower
+            .apply_delta(&leader.export_delta(version).unwrap())
+            .unwrap();
+
+        assert!(leader.major_compact().unwrap());
+        let delta = leader
+            .export_delta(follower.replication_version().unwrap())
+            .unwrap();
+        assert_eq!(delta.sstables.len(), 1);
+        assert_eq!(delta.live_ids.len(), 1);
+        follower.apply_delta(&delta).unwrap();
+
+        let ids = |engine: &Engine| -> Vec<u64> {
+            engine
+                .sstable_metadata()
+                .unwrap()
+                .iter()
+                .map(|m| m.id)
+                .collect()
+        };
+        assert_eq!(ids(&follower), ids(&leader));
+        assert_eq!(
+            collect_scan(&follower, b"c", b"d"),
+            collect_scan(&leader, b"c", b"d")
+        );
+    }
+
+    /// # Scenario
+    /// A delta is exported while a major compaction on the leader builds
+    /// its output.
+    ///
+    /// # Starting environment
+    /// Follower caught up with a leader holding several SSTables.
+    ///
+    /// # Actions
+    /// 1. Run major compaction on the leader on another thread, pausing
+    ///    it once its output is built.
+    /// 2. Write new keys to the leader and export the next delta on
+    ///    another thread.
+    /// 3. Let the compaction commit, then apply the delta.
+    ///
+    /// # Expected behavior
+    /// The export waits for the compaction and ships its output. After
+    /// the apply the follower holds the leader's SSTables and data, and
+    /// the next delta applies too.
+    #[test]
+    fn replication_waits_for_running_compaction() {
+        let leader_dir = TempDir::new().unwrap();
+        let follower_dir = TempDir::new().unwrap();
+        let leader = engine_with_multi_sstables(leader_dir.path(), 200, "c");
+        let follower = Engine::open(follower_dir.path(), multi_sstable_config()).unwrap();
+        let version = follower.replication_version().unwrap();
+        follower
+            .apply_delta(&leader.export_delta(version).unwrap())
+            .unwrap();
+        let paused = Paused(MajorCompaction { progress: None }, Barrier::new(2));
+
+        let delta = std::thread::scope(|s| {
+            let compaction = s.spawn(|| leader.run_compaction(&paused));
+            paused.1.wait();
+
+            for i in 0..100u32 {
+                let key = format!("d_{i:04}").into_bytes();
+                leader.put(key, b"value".to_vec()).unwrap();
+            }
+            let version = follower.replication_version().unwrap();
+            let leader = &leader;
+            let export = s.spawn(move || leader.export_delta(version));
+            std::thread::sleep(Duration::from_millis(100));
+            let waited = !export.is_finished();
+
+            paused.1.wait();
+            assert!(compaction.join().unwrap().unwrap());
+            assert!(waited, "export must wait for the compaction");
+            export.join().unwrap().unwrap()
+        });
+        follower.apply_delta(&delta).unwrap();
+
+        let ids = |engine: &Engine| -> Vec<u64> {
+            let mut ids: Vec<u64> = engine
+                .sstable_metadata()
+                .unwrap()
+                .iter()
+                .map(|m| m.id)
+                .collect();
+            ids.sort_unstable();
+            ids
+        };
+        assert_eq!(ids(&follower), ids(&leader));
+        assert_eq!(
+            collect_scan(&follower, b"c", b"e"),
+            collect_scan(&leader, b"c", b"e")
+        );
+
+        leader.put(b"e".to_vec(), b"value".to_vec()).unwrap();
+        follower
+            .apply_delta(
+                &leader
+                    .export_delta(follower.replication_version().unwrap())
+                    .unwrap(),
+            )
+            .unwrap();
+        assert_eq!(ids(&follower), ids(&leader));
+    }
+
+    /// # Scenario
+    /// Deltas that do not fit the follower are rejected.
+    ///
+    /// # Starting environment
+    /// Leader with several SSTables; empty follower.
+    ///
+    /// # Actions
+    /// 1. Apply a delta exported from a version the follower is not at.
+    /// 2. Write to the follower, then apply a delta from its version.
+    ///
+    /// # Expected behavior
+    /// Both applies fail with `EngineError::Replication` and the follower
+    /// holds no SSTables.
+    #[test]
+    fn replication_rejects_mismatched_delta() {
+        let leader_dir = TempDir::new().unwrap();
+        let follower_dir = TempDir::new().unwrap();
+        let leader = engine_with_multi_sstables(leader_dir.path(), 200, "m");
+        let follower = Engine::open(follower_dir.path(), multi_sstable_config()).unwrap();
+
+        let version = follower.replication_version().unwrap();
+        let ahead = leader.export_delta(version + 1).unwrap();
+        assert!(matches!(
+            follower.apply_delta(&ahead),
+            Err(EngineError::Replication(_))
+        ));
+
+        follower.put(b"local".to_vec(), b"v".to_vec()).unwrap();
+        let delta = leader.export_delta(version).unwrap();
+        assert!(matches!(
+            follower.apply_delta(&delta),
+            Err(EngineError::Replication(_))
+        ));
+        assert_eq!(follower.stats().unwrap().sstables_count, 0);
+    }
+
+    /// # Scenario
+    /// A replicated follower reopens with the leader's data and can take
+    /// over writes.
+    ///
+    /// # Starting environment
+    /// Follower that applied a full delta from a leader.
+    ///
+    /// # Actions
+    /// 1. Close and reopen the follower.
+    /// 2. Overwrite a replicated key on the follower.
+    ///
+    /// # Expected behavior
+    /// The reopened follower passes the consistency check, serves the
+    /// replicated keys, and the local overwrite wins over the replicated
+    /// value.
+    #[test]
+    fn replication_follower_survives_reopen() {
+        let leader_dir = TempDir::new().unwrap();
+        let follower_dir = TempDir::new().unwrap();
+        let leader = engine_with_multi_sstables(leader_dir.path(), 200, "r");
+        {
+            let follower = Engine::open(follower_dir.path(), multi_sstable_config()).unwrap();
+            let version = follower.replication_version().unwrap();
+            follower
+                .apply_delta(&leader.export_delta(version).unwrap())
+                .unwrap();
+            follower.close().unwrap();
+        }
+
+        let follower = Engine::open(follower_dir.path(), multi_sstable_config()).unwrap();
+        assert!(follower.check_consistency().unwrap().is_consistent());
+        assert_eq!(
+            collect_scan(&follower, b"r", b"s"),
+            collect_scan(&leader, b"r", b"s")
+        );
+
+        follower
+            .put(b"r_0000".to_vec(), b"promoted".to_vec())
+            .unwrap();
+        assert_eq!(
+            follower.get(b"r_0000".to_vec()).unwrap(),
+            Some(b"promoted".to_vec())
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! Tests for the row cache (`EngineConfig::row_cache_size`).
+//!
+//! ## Coverage areas
+//! - Repeated lookups of an SSTable-resident key are answered by the cache
+//! - Puts, deletes, range deletes, and batches invalidate cached rows
+//! - Compaction starts a new cache epoch
+//! - Capacity is enforced by evicting the least recently used rows
+//! - A lookup that raced with an invalidation is not cached
+
+#[cfg(test)]
+mod tests {
+    use crate::batch::WriteOp;
+    use crate::engine::row_cache::{CacheLookup, RowCache};
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, EngineConfig, WriteOptions};
+    use tempfile::TempDir;
+
+    /// Opens an engine with a row cache of `row_cache_size` bytes whose
+    /// keys `rc_0000..rc_0199` are flushed to several SSTables.
+    fn cached_engine(path: &std::path::Path, row_cache_size: usize) -> Engine {
+        let config = EngineConfig {
+            row_cache_size,
+            ..multi_sstable_config()
+        };
+        let engine = Engine::open(path, config).unwrap();
+        for i in 0..200u32 {
+            let key = format!("rc_{i:04}").into_bytes();
+            let value = format!("value_with_some_padding_{i:04}").into_bytes();
+            engine.put(key, value).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+        assert!(engine.stats().unwrap().sstables_count >= 2);
+        engine
+    }
+
+    /// # Scenario
+    /// A hot key is read repeatedly.
+    ///
+    /// # Starting environment
+    /// Engine with a 64 KiB row cache and all keys in SSTables.
+    ///
+    /// # Actions
+    /// 1. Get `rc_0007` three times.
+    ///
+    /// # Expected behavior
+    /// The first get probes SSTables; the other two are row cache hits
+    /// that leave the bloom filter and block counters untouched.
+    #[test]
+    fn row_cache_serves_repeated_get() {
+        let dir = TempDir::new().unwrap();
+        let engine = cached_engine(dir.path(), 64 * 1024);
+
+        let expected = Some(b"value_with_some_padding_0007".to_vec());
+        assert_eq!(engine.get(b"rc_0007".to_vec()).unwrap(), expected);
+        let after_first = engine.read_stats();
+        assert_eq!(after_first.row_cache_hits, 0);
+        assert!(after_first.bloom_checks >= 1);
+
+        assert_eq!(engine.get(b"rc_0007".to_vec()).unwrap(), expected);
+        assert_eq!(engine.get(b"rc_0007".to_vec()).unwrap(), expected);
+        let stats = engine.read_stats();
+        assert_eq!(stats.gets, 3);
+        assert_eq!(stats.row_cache_hits, 2);
+        assert_eq!(stats.bloom_checks, after_first.bloom_checks);
+        assert_eq!(stats.block_reads, after_first.block_reads);
+    }
+
+    /// # Scenario
+    /// Writes to cached keys are visible immediately.
+    ///
+    /// # Starting environment
+    /// Engine with a row cache; `rc_0001`..`rc_0004` and `rc_0010`..`rc_0019`
+    /// cached by a first get.
+    ///
+    /// # Actions
+    /// 1. Put `rc_0001`, delete `rc_0002`, write a batch putting `rc_0003`
+    ///    and deleting `rc_0004`, and range-delete `[rc_0010, rc_0015)`.
+    /// 2. Get every key again, before and after flushing.
+    ///
+    /// # Expected behavior
+    /// Every get returns the written state; untouched keys keep being
+    /// served by the cache.
+    #[test]
+    fn row_cache_writes_invalidate() {
+        let dir = TempDir::new().unwrap();
+        let engine = cached_engine(dir.path(), 64 * 1024);
+        let keys: Vec<Vec<u8>> = (1..5)
+            .chain(10..20)
+            .map(|i| format!("rc_{i:04}").into_bytes())
+            .collect();
+        for key in &keys {
+            assert!(engine.get(key.clone()).unwrap().is_some());
+        }
+
+        engine.put(b"rc_0001".to_vec(), b"new".to_vec()).unwrap();
+        engine.delete(b"rc_0002".to_vec()).unwrap();
+        engine
+            .write_batch(
+                &[
+                    WriteOp::Put {
+                        key: b"rc_0003".to_vec(),
+                        value: b"batched".to_vec(),
+                    },
+                    WriteOp::Delete {
+                        key: b"rc_0004".to_vec(),
+                    },
+                ],
+                &WriteOptions::default(),
+            )
+            .unwrap();
+        engine
+            .delete_range(b"rc_0010".to_vec(), b"rc_0015".to_vec())
+            .unwrap();
+
+        for round in 0..2 {
+            if round == 1 {
+                // Push the writes out of the active memtable.
+                for i in 0..100u32 {
+                    let key = format!("zz_{i:04}").into_bytes();
+                    engine.put(key, b"filler_value".to_vec()).unwrap();
+                }
+                engine.flush_all_frozen().unwrap();
+            }
+            assert_eq!(
+                engine.get(b"rc_0001".to_vec()).unwrap(),
+                Some(b"new".to_vec())
+            );
+            assert_eq!(engine.get(b"rc_0002".to_vec()).unwrap(), None);
+            assert_eq!(
+                engine.get(b"rc_0003".to_vec()).unwrap(),
+                Some(b"batched".to_vec())
+            );
+            assert_eq!(engine.get(b"rc_0004".to_vec()).unwrap(), None);
+            for i in 10..20u32 {
+                let value = engine.get(format!("rc_{i:04}").into_bytes()).unwrap();
+                assert_eq!(value.is_some(), i >= 15, "rc_{i:04}");
+            }
+        }
+
+        let hits = engine.read_stats().row_cache_hits;
+        assert!(engine.get(b"rc_0017".to_vec()).unwrap().is_some());
+        assert_eq!(engine.read_stats().row_cache_hits, hits + 1);
+    }
+
+    /// # Scenario
+    /// Compaction retires every cached row.
+    ///
+    /// # Starting environment
+    /// Engine with a row cache and `rc_0042` cached.
+    ///
+    /// # Actions
+    /// 1. Run a major compaction.
+    /// 2. Get `rc_0042` twice.
+    ///
+    /// # Expected behavior
+    /// The first get after compaction misses the cache and reads the new
+    /// SSTable; the second is a hit again. The value never changes.
+    #[test]
+    fn row_cache_compaction_starts_new_epoch() {
+        let dir = TempDir::new().unwrap();
+        let engine = cached_engine(dir.path(), 64 * 1024);
+        let expected = Some(b"value_with_some_padding_0042".to_vec());
+        assert_eq!(engine.get(b"rc_0042".to_vec()).unwrap(), expected);
+
+        assert!(engine.major_compact().unwrap());
+        assert_eq!(engine.get(b"rc_0042".to_vec()).unwrap(), expected);
+        assert_eq!(engine.read_stats().row_cache_hits, 0);
+        assert_eq!(engine.get(b"rc_0042".to_vec()).unwrap(), expected);
+        assert_eq!(engine.read_stats().row_cache_hits, 1);
+    }
+
+    /// # Scenario
+    /// A cache smaller than the working set evicts old rows.
+    ///
+    /// # Starting environment
+    /// Engine with a 1 KiB row cache, room for a handful of rows.
+    ///
+    /// # Actions
+    /// 1. Get 50 distinct keys.
+    /// 2. Get the last one, then the first one, again.
+    ///
+    /// # Expected behavior
+    /// The most recent key is still cached; the first was evicted.
+    #[test]
+    fn row_cache_evicts_least_recently_used() {
+        let dir = TempDir::new().unwrap();
+        let engine = cached_engine(dir.path(), 1024);
+        for i in 0..50u32 {
+            assert!(
+                engine
+                    .get(format!("rc_{i:04}").into_bytes())
+                    .unwrap()
+                    .is_some()
+            );
+        }
+
+        engine.get(b"rc_0049".to_vec()).unwrap();
+        assert_eq!(engine.read_stats().row_cache_hits, 1);
+        engine.get(b"rc_0000".to_vec()).unwrap();
+        assert_eq!(engine.read_stats().row_cache_hits, 1);
+    }
+
+    /// # Scenario
+    /// Memtable hits and a disabled cache never count as row cache hits.
+    ///
+    /// # Starting environment
+    /// A memtable-only engine with a row cache, and an engine with SSTables
+    /// but no row cache.
+    ///
+    /// # Actions
+    /// 1. Get the same key twice on each.
+    ///
+    /// # Expected behavior
+    /// Both report zero row cache hits.
+    #[test]
+    fn row_cache_skips_memtables_and_disabled() {
+        let dir = TempDir::new().unwrap();
+        let config = EngineConfig {
+            row_cache_size: 64 * 1024,
+            ..memtable_only_config()
+        };
+        let engine = Engine::open(dir.path(), config).unwrap();
+        engine.put(b"k".to_vec(), b"v".to_vec()).unwrap();
+        engine.get(b"k".to_vec()).unwrap();
+        engine.get(b"k".to_vec()).unwrap();
+        assert_eq!(engine.read_stats().row_cache_hits, 0);
+
+        let dir = TempDir::new().unwrap();
+        let engine = cached_engine(dir.path(), 0);
+        engine.get(b"rc_0000".to_vec()).unwrap();
+        engine.get(b"rc_0000".to_vec()).unwrap();
+        assert_eq!(engine.read_stats().row_cache_hits, 0);
+    }
+
+    /// # Scenario
+    /// A lookup result is offered after a concurrent invalidation.
+    ///
+    /// # Starting environment
+    /// An empty `RowCache`.
+    ///
+    /// # Actions
+    /// 1. Take a miss token for `a`, invalidate `a`, insert with the token.
+    /// 2. Take a fresh token, insert, get.
+    ///
+    /// # Expected behavior
+    /// The stale insert is dropped; the fresh one is served.
+    #[test]
+    fn row_cache_rejects_stale_token() {
+        let cache = RowCache::new(4096);
+        let CacheLookup::Miss(stale) = cache.get(b"a") else {
+            panic!("empty cache hit");
+        };
+        cache.invalidate(b"a");
+        cache.insert(b"a".to_vec(), b"old".to_vec(), stale);
+        let CacheLookup::Miss(fresh) = cache.get(b"a") else {
+            panic!("stale insert was cached");
+        };
+
+        cache.insert(b"a".to_vec(), b"new".to_vec(), fresh);
+        assert!(matches!(cache.get(b"a"), CacheLookup::Hit(v) if v == b"new"));
+    }
+}
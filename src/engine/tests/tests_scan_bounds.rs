@@ -0,0 +1,162 @@
+//! Tests for scan bounds: skipping SSTables outside the scanned range,
+//! skipping all of them for a range past their key span, and
+//! `ReadOptions::prefix_same_as_start`.
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, ReadOptions};
+    use tempfile::TempDir;
+
+    /// # Scenario
+    /// A scan opens only the SSTables whose keys or range tombstones
+    /// overlap the scanned range.
+    ///
+    /// # Starting environment
+    /// Engine with `sb_0000..sb_0199` spread across several SSTables.
+    ///
+    /// # Actions
+    /// 1. Count the SSTables that may overlap a narrow range; scan it.
+    /// 2. Range-delete `sb_0050..sb_0060`, then flush it into an SSTable
+    ///    whose point keys all lie elsewhere; scan the deleted range.
+    ///
+    /// # Expected behavior
+    /// Fewer SSTables overlap the narrow range than exist, and the scan
+    /// still returns its keys. The SSTable holding only the tombstone in
+    /// that range still overlaps it, so the deleted keys stay hidden.
+    #[test]
+    fn scan_skips_sstables_outside_range() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 200, "sb");
+
+        let version = engine.version.load();
+        let overlapping = version
+            .sstables
+            .iter()
+            .filter(|sst| sst.may_overlap(b"sb_0100", b"sb_0105"))
+            .count();
+        assert!(overlapping >= 1);
+        assert!(overlapping < version.sstables.len());
+        drop(version);
+
+        let keys: Vec<_> = collect_scan(&engine, b"sb_0100", b"sb_0105")
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        let expected: Vec<_> = (100..105)
+            .map(|i| format!("sb_{i:04}").into_bytes())
+            .collect();
+        assert_eq!(keys, expected);
+
+        engine
+            .delete_range(b"sb_0050".to_vec(), b"sb_0060".to_vec())
+            .unwrap();
+        for i in 0..100u32 {
+            let key = format!("zz_{i:04}").into_bytes();
+            engine.put(key, b"filler_value".to_vec()).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+
+        assert!(collect_scan(&engine, b"sb_0050", b"sb_0060").is_empty());
+        assert_eq!(collect_scan(&engine, b"sb_0045", b"sb_0065").len(), 10);
+    }
+
+    /// # Scenario
+    /// A scan past the key span of every SSTable reads the memtables
+    /// alone, until a range tombstone stretches the span over its range.
+    ///
+    /// # Starting environment
+    /// Engine with `sb_0000..sb_0199` spread across several SSTables.
+    ///
+    /// # Actions
+    /// 1. Put `tail_0000` into the active memtable; scan `tail_` keys.
+    /// 2. Range-delete `sb_0190..u`, then flush it with filler keys into
+    ///    an SSTable; scan `tail_` keys and `sb_0185..sb_0195`.
+    ///
+    /// # Expected behavior
+    /// 1. The SSTable span rules out the `tail_` range but not one inside
+    ///    the SSTables, and the scan returns the memtable key.
+    /// 2. The tombstone's end widens the span over the `tail_` range;
+    ///    `tail_0000`, written before the tombstone, and `sb_0190..` are
+    ///    hidden.
+    #[test]
+    fn scan_past_sstable_span_skips_sstables() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 200, "sb");
+        engine.put(b"tail_0000".to_vec(), b"t".to_vec()).unwrap();
+
+        let version = engine.version.load();
+        assert!(version.sstable_span.may_overlap(b"sb_0100", b"sb_0105"));
+        assert!(!version.sstable_span.may_overlap(b"tail_", b"tail_\xff"));
+        drop(version);
+        assert_eq!(
+            collect_scan(&engine, b"tail_", b"tail_\xff"),
+            vec![(b"tail_0000".to_vec(), b"t".to_vec())]
+        );
+
+        engine
+            .delete_range(b"sb_0190".to_vec(), b"u".to_vec())
+            .unwrap();
+        for i in 0..100u32 {
+            let key = format!("aa_{i:04}").into_bytes();
+            engine.put(key, b"filler_value".to_vec()).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+
+        let version = engine.version.load();
+        assert!(version.sstable_span.may_overlap(b"tail_", b"tail_\xff"));
+        drop(version);
+        assert!(collect_scan(&engine, b"tail_", b"tail_\xff").is_empty());
+        assert_eq!(collect_scan(&engine, b"sb_0185", b"sb_0195").len(), 5);
+    }
+
+    /// # Scenario
+    /// `prefix_same_as_start` ends a scan where the start key's prefix
+    /// ends.
+    ///
+    /// # Starting environment
+    /// Memtable-only engine with keys under `user:1:`, `user:10:`, and
+    /// `user:2:`, plus keys around a prefix ending in `0xFF`.
+    ///
+    /// # Actions
+    /// 1. Scan from `user:1:` to `user;` with and without the flag.
+    /// 2. Scan from `k\xFF` to `m` with the flag.
+    ///
+    /// # Expected behavior
+    /// Without the flag every `user:` key from the start key on is
+    /// returned (`user:10:x` sorts before `user:1:`); with it only keys
+    /// starting with the start key. A trailing `0xFF` in the prefix is
+    /// handled: the scan stops before `l`.
+    #[test]
+    fn prefix_same_as_start_limits_scan() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), memtable_only_config()).unwrap();
+        for key in ["user:1:a", "user:1:b", "user:10:x", "user:2:a"] {
+            engine.put(key.as_bytes().to_vec(), b"v".to_vec()).unwrap();
+        }
+        engine.put(vec![b'k', 0xFF, 1], b"v".to_vec()).unwrap();
+        engine.put(b"l".to_vec(), b"v".to_vec()).unwrap();
+
+        let prefix = ReadOptions {
+            prefix_same_as_start: true,
+            ..ReadOptions::default()
+        };
+        let keys = |start: &[u8], end: &[u8], opts: &ReadOptions| -> Vec<Vec<u8>> {
+            engine
+                .scan_opt(start, end, opts)
+                .unwrap()
+                .map(|(k, _)| k)
+                .collect()
+        };
+
+        assert_eq!(keys(b"user:1:", b"user;", &ReadOptions::default()).len(), 3);
+        assert_eq!(
+            keys(b"user:1:", b"user;", &prefix),
+            vec![b"user:1:a".to_vec(), b"user:1:b".to_vec()]
+        );
+        assert_eq!(
+            keys(&[b'k', 0xFF], b"m", &prefix),
+            vec![vec![b'k', 0xFF, 1]]
+        );
+    }
+}
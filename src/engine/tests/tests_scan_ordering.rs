@@ -0,0 +1,200 @@
+//! Scan ordering guarantees, and agreement between `get` and `scan`,
+//! with overlapping range deletes spread over every layer.
+//!
+//! ## Coverage
+//! - A scan yields each live key once, in strictly ascending order
+//! - Overlapping range deletes in different SSTables and the memtable
+//!   hide exactly the versions older than them
+//! - Under churn of puts, deletes, and overlapping range deletes, with
+//!   flushes, compactions, and a reopen in between, `scan` returns
+//!   exactly the keys and values `get` does
+//!
+//! ## See also
+//! - [`tests_version_precedence`] — versions sharing an LSN
+//! - [`tests_range_delete`] — range delete boundaries
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::Engine;
+    use crate::engine::tests::helpers::*;
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+
+    const KEYS: usize = 200;
+
+    // ----------------------------------------------------------------
+    // Deterministic pseudo-random number generator (LCG)
+    // ----------------------------------------------------------------
+
+    struct Rng(u64);
+
+    impl Rng {
+        fn new(seed: u64) -> Self {
+            Self(seed)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            // LCG parameters from Numerical Recipes
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            self.0
+        }
+
+        fn next_usize(&mut self, bound: usize) -> usize {
+            (self.next_u64() % bound as u64) as usize
+        }
+    }
+
+    fn key(idx: usize) -> Vec<u8> {
+        format!("so_{idx:04}").into_bytes()
+    }
+
+    /// Pushes everything in the active memtable into a new SSTable, with
+    /// filler writes outside the `so_` keys.
+    fn push_to_sstable(engine: &Engine, round: usize) {
+        let before = engine.stats().unwrap().sstables_count;
+        let mut filler = 0;
+        while engine.stats().unwrap().frozen_count == 0 {
+            let key = format!("zz_{round}_{filler:04}").into_bytes();
+            engine
+                .put(key, b"value_with_some_padding".to_vec())
+                .unwrap();
+            filler += 1;
+        }
+        engine.flush_all_frozen().unwrap();
+        assert!(engine.stats().unwrap().sstables_count > before);
+    }
+
+    /// Asserts that a full scan is strictly ascending, equals `expected`,
+    /// and agrees with `get` on every key, live or not.
+    fn assert_reads_agree(engine: &Engine, expected: &BTreeMap<Vec<u8>, Vec<u8>>, at: &str) {
+        let scanned = collect_scan(engine, b"so_", b"so_\xff");
+        assert!(
+            scanned.windows(2).all(|pair| pair[0].0 < pair[1].0),
+            "{at}: scan out of order or repeating a key"
+        );
+        let scanned: BTreeMap<Vec<u8>, Vec<u8>> = scanned.into_iter().collect();
+        assert_eq!(&scanned, expected, "{at}: scan differs from the model");
+
+        for idx in 0..KEYS {
+            let key = key(idx);
+            assert_eq!(
+                engine.get(key.clone()).unwrap().as_ref(),
+                scanned.get(&key),
+                "{at}: get and scan disagree on {}",
+                String::from_utf8_lossy(&key)
+            );
+        }
+    }
+
+    /// # Scenario
+    /// Overlapping range deletes, each in its own layer, hide only the
+    /// versions written before them.
+    ///
+    /// # Starting environment
+    /// Engine with a 1 KB write buffer.
+    ///
+    /// # Actions
+    /// 1. Put `so_0000..so_0019`; push them into an SSTable.
+    /// 2. Range-delete `[so_0005, so_0015)`; put `so_0008`; push them into
+    ///    a second SSTable.
+    /// 3. Range-delete `[so_0007, so_0012)` and `[so_0010, so_0018)`; put
+    ///    `so_0011`, leaving it all in the memtable.
+    ///
+    /// # Expected behavior
+    /// Live keys are `so_0000..so_0004`, `so_0011` with its second value,
+    /// and `so_0018..so_0019`; `so_0008`, put between the deletes, is
+    /// hidden by the later one. Scans and gets agree, before and after a
+    /// major compaction.
+    #[test]
+    fn overlapping_range_deletes_across_layers() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), multi_sstable_config()).unwrap();
+
+        for idx in 0..20 {
+            engine.put(key(idx), b"first".to_vec()).unwrap();
+        }
+        push_to_sstable(&engine, 0);
+
+        engine.delete_range(key(5), key(15)).unwrap();
+        engine.put(key(8), b"second".to_vec()).unwrap();
+        push_to_sstable(&engine, 1);
+
+        engine.delete_range(key(7), key(12)).unwrap();
+        engine.delete_range(key(10), key(18)).unwrap();
+        engine.put(key(11), b"second".to_vec()).unwrap();
+
+        let mut expected = BTreeMap::new();
+        for idx in (0..5).chain(18..20) {
+            expected.insert(key(idx), b"first".to_vec());
+        }
+        expected.insert(key(11), b"second".to_vec());
+
+        assert_reads_agree(&engine, &expected, "before compaction");
+        push_to_sstable(&engine, 2);
+        assert!(engine.major_compact().unwrap());
+        assert_reads_agree(&engine, &expected, "after compaction");
+    }
+
+    /// # Scenario
+    /// `get` and `scan` agree under heavy churn.
+    ///
+    /// # Starting environment
+    /// Engine with a 1 KB write buffer; a model map of the live keys.
+    ///
+    /// # Actions
+    /// 1. Run 3000 seeded random operations over 200 keys: puts, deletes,
+    ///    and range deletes of up to 30 keys, which overlap each other.
+    ///    Every 250 operations, flush; every 1000, minor compact.
+    /// 2. Major compact; close and reopen.
+    ///
+    /// # Expected behavior
+    /// At every checkpoint the scan yields each live key once, in order,
+    /// with the model's value, and `get` returns the same for every key.
+    #[test]
+    fn scan_matches_get_under_churn() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), multi_sstable_config()).unwrap();
+        let mut expected: BTreeMap<Vec<u8>, Vec<u8>> = BTreeMap::new();
+        let mut rng = Rng::new(0x5CA9);
+
+        for op_num in 1..=3000 {
+            let idx = rng.next_usize(KEYS);
+            match rng.next_usize(100) {
+                0..60 => {
+                    let value = format!("v{op_num}").into_bytes();
+                    engine.put(key(idx), value.clone()).unwrap();
+                    expected.insert(key(idx), value);
+                }
+                60..80 => {
+                    engine.delete(key(idx)).unwrap();
+                    expected.remove(&key(idx));
+                }
+                _ => {
+                    let end = (idx + 1 + rng.next_usize(30)).min(KEYS);
+                    engine.delete_range(key(idx), key(end)).unwrap();
+                    expected.retain(|k, _| *k < key(idx) || *k >= key(end));
+                }
+            }
+
+            if op_num % 250 == 0 {
+                engine.flush_all_frozen().unwrap();
+                assert_reads_agree(&engine, &expected, &format!("op {op_num}"));
+            }
+            if op_num % 1000 == 0 {
+                engine.minor_compact().unwrap();
+                assert_reads_agree(&engine, &expected, &format!("minor at {op_num}"));
+            }
+        }
+
+        engine.major_compact().unwrap();
+        assert_reads_agree(&engine, &expected, "major");
+        engine.close().unwrap();
+        drop(engine);
+
+        let engine = Engine::open(dir.path(), multi_sstable_config()).unwrap();
+        assert_reads_agree(&engine, &expected, "reopen");
+    }
+}
@@ -0,0 +1,227 @@
+//! Tests for the sorted write batch path (`Memtable::write_sorted` via
+//! `Engine::write_batch`).
+//!
+//! ## Coverage areas
+//! - A large sorted batch merged into an empty, small, or larger memtable
+//! - Recovery of a merged batch from the WAL
+//! - A batch that needs a fresh memtable, and one too large for any
+//! - Unsorted batches and range deletes take the per-operation path
+
+#[cfg(test)]
+mod tests {
+    use crate::batch::WriteOp;
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, EngineConfig, SORTED_BATCH_MIN_OPS, WriteOptions};
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Config whose write buffer holds a few sorted batches.
+    fn batch_config(write_buffer_size: usize) -> EngineConfig {
+        EngineConfig {
+            write_buffer_size,
+            ..memtable_only_config()
+        }
+    }
+
+    fn key(i: usize) -> Vec<u8> {
+        format!("sb_{i:05}").into_bytes()
+    }
+
+    /// A sorted batch over `sb_00000..` of `len` operations: every tenth
+    /// a delete, the rest puts of `batch_<i>`.
+    fn sorted_batch(len: usize) -> Vec<WriteOp> {
+        (0..len)
+            .map(|i| {
+                if i % 10 == 0 {
+                    WriteOp::Delete { key: key(i) }
+                } else {
+                    WriteOp::Put {
+                        key: key(i),
+                        value: format!("batch_{i}").into_bytes(),
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Point versions held by the active memtable.
+    fn active_entries(engine: &Engine) -> usize {
+        engine.version.load().active.stats().unwrap().entry_count
+    }
+
+    /// Checks that every key of `sorted_batch(len)` reads back as written.
+    fn assert_batch_applied(engine: &Engine, len: usize) {
+        for i in 0..len {
+            let expected = (i % 10 != 0).then(|| format!("batch_{i}").into_bytes());
+            assert_eq!(engine.get(key(i)).unwrap(), expected, "key {i}");
+        }
+    }
+
+    /// Opens an engine, puts `existing` keys (every other one overlapping
+    /// the batch, the rest past it), then commits a sorted batch.
+    fn write_over_existing(path: &Path, existing: usize, len: usize) -> Engine {
+        let engine = Engine::open(path, batch_config(4 * 1024 * 1024)).unwrap();
+        for i in 0..existing {
+            let k = if i % 2 == 0 { key(i) } else { key(len + i) };
+            engine.put(k, b"old".to_vec()).unwrap();
+        }
+        let lsn_before = engine.latest_lsn().unwrap();
+
+        let frozen = engine
+            .write_batch(&sorted_batch(len), &WriteOptions::default())
+            .unwrap();
+        assert!(!frozen);
+        assert_eq!(engine.latest_lsn().unwrap(), lsn_before + len as u64);
+        engine
+    }
+
+    // ================================================================
+    // 1. Merge into the active memtable
+    // ================================================================
+
+    /// # Scenario
+    /// A sorted batch is merged into memtables holding fewer, then more
+    /// keys than the batch, some of them written by the batch again.
+    ///
+    /// # Actions
+    /// 1. For 0, 100, and 5000 existing keys: put them, commit a sorted
+    ///    batch of 2000 operations.
+    /// 2. Read every key; check the memtable entry count.
+    ///
+    /// # Expected behavior
+    /// - The batch takes one LSN per operation.
+    /// - Batch keys read as the batch wrote them; keys past the batch
+    ///   keep `old`.
+    /// - Overwritten keys keep their older version in the memtable.
+    #[test]
+    fn sorted_batch_merges_into_memtable() {
+        for existing in [0, 100, 5000] {
+            let dir = TempDir::new().unwrap();
+            let len = 2000;
+            let engine = write_over_existing(dir.path(), existing, len);
+
+            assert_batch_applied(&engine, len);
+            for i in (1..existing).step_by(2) {
+                assert_eq!(engine.get(key(len + i)).unwrap(), Some(b"old".to_vec()));
+            }
+            assert_eq!(active_entries(&engine), existing + len, "{existing}");
+        }
+    }
+
+    /// # Scenario
+    /// A merged batch is recovered from the WAL after a crash.
+    ///
+    /// # Actions
+    /// 1. Put 100 keys, commit a sorted batch of 2000 operations.
+    /// 2. Drop the engine without `close()`; reopen.
+    ///
+    /// # Expected behavior
+    /// Every key reads back as before the crash.
+    #[test]
+    fn sorted_batch_recovered_from_wal() {
+        let dir = TempDir::new().unwrap();
+        let len = 2000;
+        let engine = write_over_existing(dir.path(), 100, len);
+        drop(engine);
+
+        let engine = Engine::open(dir.path(), batch_config(4 * 1024 * 1024)).unwrap();
+        assert_batch_applied(&engine, len);
+        assert_eq!(engine.get(key(len + 1)).unwrap(), Some(b"old".to_vec()));
+    }
+
+    // ================================================================
+    // 2. Write buffer limits
+    // ================================================================
+
+    /// # Scenario
+    /// A sorted batch fits an empty memtable but not the active one, or
+    /// no memtable at all.
+    ///
+    /// # Actions
+    /// 1. 200 KiB write buffer: put 2000 keys, commit a sorted batch of
+    ///    2000 operations.
+    /// 2. 64 KiB write buffer: commit a sorted batch of 5000 operations.
+    ///
+    /// # Expected behavior
+    /// - Step 1 freezes the active memtable once and writes the whole
+    ///   batch to the next one.
+    /// - Step 2 falls back to writing one operation at a time, freezing
+    ///   several memtables.
+    /// - Every key reads back in both cases.
+    #[test]
+    fn sorted_batch_larger_than_write_buffer() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), batch_config(200 * 1024)).unwrap();
+        for i in 0..2000 {
+            engine
+                .put(format!("pre_{i:05}").into_bytes(), b"old".to_vec())
+                .unwrap();
+        }
+        let frozen_before = engine.stats().unwrap().frozen_count;
+        let frozen = engine
+            .write_batch(&sorted_batch(2000), &WriteOptions::default())
+            .unwrap();
+        assert!(frozen);
+        assert_eq!(engine.stats().unwrap().frozen_count, frozen_before + 1);
+        assert_eq!(active_entries(&engine), 2000);
+        assert_batch_applied(&engine, 2000);
+        drop(engine);
+
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), batch_config(64 * 1024)).unwrap();
+        let frozen = engine
+            .write_batch(&sorted_batch(5000), &WriteOptions::default())
+            .unwrap();
+        assert!(frozen);
+        assert!(engine.stats().unwrap().frozen_count > 1);
+        assert_batch_applied(&engine, 5000);
+    }
+
+    // ================================================================
+    // 3. Batches that do not qualify
+    // ================================================================
+
+    /// # Scenario
+    /// Large batches that repeat a key, are out of order, or hold a range
+    /// delete are applied one operation at a time, in order.
+    ///
+    /// # Actions
+    /// 1. Commit a sorted batch with its last key written twice.
+    /// 2. Commit a sorted batch in reverse order.
+    /// 3. Commit a sorted batch followed by a range delete of its first
+    ///    ten keys.
+    ///
+    /// # Expected behavior
+    /// Each reads back as applied in order: the later write of the
+    /// repeated key wins, and the range delete hides the first ten keys.
+    #[test]
+    fn unsorted_batches_applied_in_order() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), batch_config(4 * 1024 * 1024)).unwrap();
+        let len = SORTED_BATCH_MIN_OPS;
+
+        let mut ops = sorted_batch(len);
+        ops.push(WriteOp::Put {
+            key: key(len - 1),
+            value: b"again".to_vec(),
+        });
+        engine.write_batch(&ops, &WriteOptions::default()).unwrap();
+        assert_eq!(engine.get(key(len - 1)).unwrap(), Some(b"again".to_vec()));
+
+        let mut ops = sorted_batch(len);
+        ops.reverse();
+        engine.write_batch(&ops, &WriteOptions::default()).unwrap();
+        assert_batch_applied(&engine, len);
+
+        let mut ops = sorted_batch(len);
+        ops.push(WriteOp::DeleteRange {
+            start: key(0),
+            end: key(10),
+        });
+        engine.write_batch(&ops, &WriteOptions::default()).unwrap();
+        for i in 0..len {
+            let expected = (i >= 10 && i % 10 != 0).then(|| format!("batch_{i}").into_bytes());
+            assert_eq!(engine.get(key(i)).unwrap(), expected, "key {i}");
+        }
+    }
+}
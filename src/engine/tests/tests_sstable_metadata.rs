@@ -0,0 +1,151 @@
+//! Tests for the per-SSTable metadata listing API (`Engine::sstable_metadata`).
+
+#[cfg(test)]
+mod tests {
+    use crate::clock::MockClock;
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, EngineConfig};
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    /// # Scenario
+    /// A fresh engine with no SSTables reports an empty listing.
+    ///
+    /// # Expected behavior
+    /// `sstable_metadata()` returns an empty `Vec`.
+    #[test]
+    fn sstable_metadata_empty() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), memtable_only_config()).unwrap();
+
+        assert!(engine.sstable_metadata().unwrap().is_empty());
+    }
+
+    /// # Scenario
+    /// Metadata agrees with `stats()` and with the data that was flushed.
+    ///
+    /// # Actions
+    /// 1. Write enough keys to produce multiple SSTables.
+    /// 2. Call `sstable_metadata()`.
+    ///
+    /// # Expected behavior
+    /// One entry per SSTable in newest-first order, file sizes matching
+    /// `stats()`, paths that exist on disk, and key bounds within the
+    /// written key space.
+    #[test]
+    fn sstable_metadata_matches_stats() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 200, "meta");
+
+        let stats = engine.stats().unwrap();
+        let meta = engine.sstable_metadata().unwrap();
+
+        assert_eq!(meta.len(), stats.sstables_count);
+        let sizes: Vec<u64> = meta.iter().map(|m| m.file_size).collect();
+        assert_eq!(sizes, stats.sst_sizes);
+
+        for m in &meta {
+            assert!(m.path.exists(), "path {:?} should exist", m.path);
+            assert!(m.min_key.starts_with(b"meta_"));
+            assert!(m.max_key.starts_with(b"meta_"));
+            assert!(m.min_key <= m.max_key);
+            assert!(m.min_lsn <= m.max_lsn);
+            assert!(m.record_count > 0);
+            assert!(m.creation_timestamp > 0);
+            assert_eq!(m.level, None);
+        }
+
+        for pair in meta.windows(2) {
+            assert!(pair[0].max_lsn >= pair[1].max_lsn, "expected newest-first");
+        }
+    }
+
+    /// # Scenario
+    /// Tombstone counts and ratio reflect point deletes flushed to disk.
+    ///
+    /// # Actions
+    /// 1. Put 100 keys, then delete every key, with a small write buffer.
+    /// 2. Flush all frozen memtables.
+    ///
+    /// # Expected behavior
+    /// At least one SSTable carries tombstones, and every entry's ratio
+    /// equals `(tombstones + range tombstones) / records`.
+    #[test]
+    fn sstable_metadata_tombstone_ratio() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), multi_sstable_config()).unwrap();
+
+        for i in 0..100u32 {
+            engine
+                .put(format!("k_{i:04}").into_bytes(), b"value".to_vec())
+                .unwrap();
+        }
+        for i in 0..100u32 {
+            engine.delete(format!("k_{i:04}").into_bytes()).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+
+        let meta = engine.sstable_metadata().unwrap();
+        assert!(meta.iter().any(|m| m.tombstone_count > 0));
+        for m in &meta {
+            let expected =
+                (m.tombstone_count + m.range_tombstone_count) as f64 / m.record_count.max(1) as f64;
+            assert!((m.tombstone_ratio - expected).abs() < f64::EPSILON);
+        }
+    }
+
+    /// # Scenario
+    /// Lookups and scans are counted per SSTable, with the clock time of
+    /// the latest one.
+    ///
+    /// # Starting environment
+    /// Several SSTables written with a mock clock; no reads yet.
+    ///
+    /// # Actions
+    /// 1. Get the smallest key of the oldest SSTable.
+    /// 2. Advance the clock; scan that SSTable's key range.
+    ///
+    /// # Expected behavior
+    /// - Before any read, every table reports zero hits and scans and no
+    ///   last access.
+    /// - The oldest table reports one hit and at least one scan, and its
+    ///   last access is the clock time of the scan.
+    /// - The newest table, whose keys lie outside the scan, stays unread.
+    #[test]
+    fn sstable_metadata_access_counts() {
+        let dir = TempDir::new().unwrap();
+        let clock = Arc::new(MockClock::new(1_000_000_000));
+        let config = EngineConfig {
+            clock: clock.clone(),
+            ..multi_sstable_config()
+        };
+        let engine = Engine::open(dir.path(), config).unwrap();
+        for i in 0..200u32 {
+            engine
+                .put(format!("acc_{i:04}").into_bytes(), b"value".to_vec())
+                .unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+
+        let meta = engine.sstable_metadata().unwrap();
+        assert!(meta.len() >= 2);
+        for m in &meta {
+            assert_eq!((m.hits, m.scans, m.last_access), (0, 0, None));
+        }
+        let oldest = meta.last().unwrap().clone();
+
+        assert!(engine.get(oldest.min_key.clone()).unwrap().is_some());
+        clock.set(2_000_000_000);
+        let mut end = oldest.max_key.clone();
+        end.push(0);
+        assert!(engine.scan(&oldest.min_key, &end).unwrap().count() > 0);
+
+        let meta = engine.sstable_metadata().unwrap();
+        let read = meta.iter().find(|m| m.id == oldest.id).unwrap();
+        assert_eq!(read.hits, 1);
+        assert!(read.scans >= 1);
+        assert_eq!(read.last_access, Some(2_000_000_000));
+        assert_eq!(meta[0].scans, 0);
+        assert_eq!(meta[0].last_access, None);
+    }
+}
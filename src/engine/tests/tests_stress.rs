@@ -55,10 +55,12 @@
 #[cfg(test)]
 #[allow(non_snake_case)]
 mod tests {
+    use crate::clock::SystemClock;
     use crate::engine::Engine;
     use crate::engine::EngineConfig;
     use crate::engine::tests::helpers::*;
     use std::collections::HashMap;
+    use std::sync::Arc;
     use tempfile::TempDir;
 
     /// Config tuned for compaction stress tests:
@@ -69,6 +71,8 @@ mod tests {
         init_tracing();
         EngineConfig {
             write_buffer_size: 512,
+            max_memtables_per_flush: 1,
+            max_frozen_memtables: 0,
             compaction_strategy: crate::compaction::CompactionStrategyType::Stcs,
             bucket_low: 0.5,
             bucket_high: 1.5,
@@ -79,7 +83,34 @@ mod tests {
             tombstone_compaction_interval: 0, // no age gate for stress tests
             tombstone_bloom_fallback: true,
             tombstone_range_drop: true,
+            tombstone_gc_grace_seconds: 0,
+            periodic_compaction_seconds: 0,
+            read_depth_compaction_threshold: 0,
+            deletion_run_compaction_threshold: 0,
+            hot_sstable_compaction: false,
+            read_fanout_limit: 0,
+            read_fanout_fallback: false,
             thread_pool_size: 2,
+            parallel_sstable_probe: false,
+            wal_recovery_mode: crate::wal::WalRecoveryMode::TolerateCorruptedTail,
+            max_memtable_age: 0,
+            max_total_wal_size: 0,
+            wal_retention_bytes: 0,
+            wal_retention_seconds: 0,
+            obsolete_file_grace_seconds: 0,
+            row_cache_size: 0,
+            max_mmap_bytes: 0,
+            page_cache_hints: false,
+            slow_op_threshold: None,
+            sstable_open_checks: crate::sstable::OpenChecks::Metadata,
+            sstable_layout: crate::engine::SstDirLayout::Flat,
+            sstable_naming: crate::engine::SstFileNaming::Id,
+            sstable_file_digest: None,
+            tenant_prefixes: Vec::new(),
+            value_checksums: false,
+            idempotency_retention_seconds: 3600,
+            idempotency_max_tokens: 100_000,
+            clock: Arc::new(SystemClock),
         }
     }
 
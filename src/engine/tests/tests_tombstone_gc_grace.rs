@@ -0,0 +1,172 @@
+//! Tombstone GC grace period tests (`tombstone_gc_grace_seconds`).
+//!
+//! Tombstones written within the grace period must survive every
+//! compaction, even when they are provably spent. With the grace period
+//! disabled (`0`), the pre-existing drop behavior applies.
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, EngineConfig};
+    use tempfile::TempDir;
+
+    /// Config with aggressive tombstone GC and the given grace period.
+    fn grace_config(grace_seconds: usize) -> EngineConfig {
+        init_tracing();
+        EngineConfig {
+            write_buffer_size: 1024,
+            min_sstable_size: 64,
+            tombstone_ratio_threshold: 0.01,
+            tombstone_compaction_interval: 0,
+            tombstone_bloom_fallback: true,
+            tombstone_range_drop: true,
+            tombstone_gc_grace_seconds: grace_seconds,
+            ..EngineConfig::default()
+        }
+    }
+
+    /// Writes `gc_*` keys, deletes ten of them plus a range, then pushes
+    /// enough filler through the memtable that the tombstones reach disk.
+    fn write_with_tombstones(engine: &Engine) {
+        for i in 0..40u32 {
+            engine
+                .put(format!("gc_{i:04}").into_bytes(), b"value".to_vec())
+                .unwrap();
+        }
+        for i in 0..10u32 {
+            engine.delete(format!("gc_{i:04}").into_bytes()).unwrap();
+        }
+        engine
+            .delete_range(b"gc_0020".to_vec(), b"gc_0030".to_vec())
+            .unwrap();
+        for i in 0..100u32 {
+            engine
+                .put(format!("zz_{i:04}").into_bytes(), b"filler".to_vec())
+                .unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+    }
+
+    fn tombstone_totals(engine: &Engine) -> (u64, u64) {
+        engine
+            .sstable_metadata()
+            .unwrap()
+            .iter()
+            .fold((0, 0), |(p, r), m| {
+                (p + m.tombstone_count, r + m.range_tombstone_count)
+            })
+    }
+
+    fn assert_deleted(engine: &Engine) {
+        for i in (0..10u32).chain(20..30) {
+            let key = format!("gc_{i:04}").into_bytes();
+            assert_eq!(
+                engine.get(key).unwrap(),
+                None,
+                "gc_{i:04} should be deleted"
+            );
+        }
+        for i in (10..20u32).chain(30..40) {
+            let key = format!("gc_{i:04}").into_bytes();
+            assert_eq!(engine.get(key).unwrap(), Some(b"value".to_vec()));
+        }
+    }
+
+    /// # Scenario
+    /// Major compaction with a grace period keeps young tombstones.
+    ///
+    /// # Starting environment
+    /// Multiple SSTables holding puts, point deletes, and a range delete.
+    ///
+    /// # Actions
+    /// 1. Run `major_compact()` with a one-hour grace period.
+    /// 2. Reopen the engine.
+    ///
+    /// # Expected behavior
+    /// The single output SSTable still carries all ten point tombstones
+    /// and the range tombstone; deleted keys stay deleted across reopen.
+    #[test]
+    fn major_compact_retains_tombstones_within_grace() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), grace_config(3600)).unwrap();
+        write_with_tombstones(&engine);
+        assert!(engine.stats().unwrap().sstables_count >= 2);
+
+        assert!(engine.major_compact().unwrap());
+
+        let meta = engine.sstable_metadata().unwrap();
+        assert_eq!(meta.len(), 1);
+        assert_eq!(tombstone_totals(&engine), (10, 1));
+        assert_deleted(&engine);
+
+        engine.close().unwrap();
+        let engine = Engine::open(dir.path(), grace_config(3600)).unwrap();
+        assert_eq!(tombstone_totals(&engine), (10, 1));
+        assert_deleted(&engine);
+    }
+
+    /// # Scenario
+    /// Major compaction without a grace period drops all tombstones.
+    ///
+    /// # Actions
+    /// 1. Same data as above, `tombstone_gc_grace_seconds = 0`.
+    /// 2. Run `major_compact()`.
+    ///
+    /// # Expected behavior
+    /// The output SSTable contains no tombstones; reads are unchanged.
+    #[test]
+    fn major_compact_drops_tombstones_without_grace() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), grace_config(0)).unwrap();
+        write_with_tombstones(&engine);
+
+        assert!(engine.major_compact().unwrap());
+
+        assert_eq!(tombstone_totals(&engine), (0, 0));
+        assert_deleted(&engine);
+    }
+
+    /// # Scenario
+    /// Tombstone compaction never drops tombstones within the grace period.
+    ///
+    /// # Starting environment
+    /// An SSTable of deletes for keys that were never written, so every
+    /// tombstone is provably spent.
+    ///
+    /// # Actions
+    /// 1. Run `tombstone_compact()` with a one-hour grace period.
+    /// 2. Repeat on a fresh engine with the grace period disabled.
+    ///
+    /// # Expected behavior
+    /// With the grace period nothing is rewritten and the tombstone count
+    /// is unchanged; without it the tombstones are dropped.
+    #[test]
+    fn tombstone_compact_respects_grace() {
+        fn run(grace_seconds: usize) -> (bool, u64, u64) {
+            let dir = TempDir::new().unwrap();
+            let engine = Engine::open(dir.path(), grace_config(grace_seconds)).unwrap();
+            for i in 0..50u32 {
+                engine.delete(format!("ghost_{i:04}").into_bytes()).unwrap();
+            }
+            for i in 0..100u32 {
+                engine
+                    .put(format!("zz_{i:04}").into_bytes(), b"filler".to_vec())
+                    .unwrap();
+            }
+            engine.flush_all_frozen().unwrap();
+            let before = tombstone_totals(&engine).0;
+            let compacted = engine.tombstone_compact().unwrap();
+            (compacted, before, tombstone_totals(&engine).0)
+        }
+
+        let (compacted, before, after) = run(3600);
+        assert!(before > 0);
+        assert!(!compacted, "no tombstone may be dropped within grace");
+        assert_eq!(after, before);
+
+        let (compacted, before, after) = run(0);
+        assert!(before > 0);
+        assert!(compacted);
+        assert!(after < before);
+    }
+}
@@ -8,14 +8,20 @@
 //! - `RangeTombstone` `Encode` / `Decode` round-trip
 //! - Invalid tag decode error path
 //! - `Record::into_entry` for all variants
+//! - `coalesce_range_tombstones` dominance, equal-LSN merge, and bridging
+//! - Unbounded (empty) range ends in `below_end`, `cmp_ends`, and coalescing
+//! - `MergeIterator` deferring sources with a lower bound
 
 #[cfg(test)]
 mod tests {
     use crate::encoding::{self, Decode, Encode};
     use crate::engine::utils::{
-        MergeIterator, PointEntry, RangeTombstone, Record, RecordEntry, record_cmp,
+        MergeIterator, PointEntry, RangeTombstone, Record, RecordEntry, RecordSource, below_end,
+        cmp_ends, coalesce_range_tombstones, record_cmp,
     };
     use std::cmp::Ordering;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
 
     // ----------------------------------------------------------------
     // PointEntry constructors
@@ -359,8 +365,208 @@ mod tests {
 
     #[test]
     fn merge_iterator_empty() {
-        let iters: Vec<Box<dyn Iterator<Item = Record>>> = vec![];
+        let iters: Vec<Box<dyn Iterator<Item = Record> + Send>> = vec![];
         let mut merge = MergeIterator::new(iters);
         assert!(merge.next().is_none());
     }
+
+    /// A source over puts of `keys` at `lsn` that counts the records
+    /// pulled from it.
+    struct Counted {
+        records: std::vec::IntoIter<Record>,
+        bound: Option<Vec<u8>>,
+        pulled: Arc<AtomicUsize>,
+    }
+
+    impl Counted {
+        fn new(keys: &[&str], lsn: u64, bound: Option<&str>) -> (Self, Arc<AtomicUsize>) {
+            let records: Vec<Record> = keys
+                .iter()
+                .map(|key| Record::Put {
+                    key: key.as_bytes().to_vec(),
+                    value: b"v".to_vec(),
+                    lsn,
+                    timestamp: lsn,
+                })
+                .collect();
+            let pulled = Arc::new(AtomicUsize::new(0));
+            let source = Self {
+                records: records.into_iter(),
+                bound: bound.map(|b| b.as_bytes().to_vec()),
+                pulled: Arc::clone(&pulled),
+            };
+            (source, pulled)
+        }
+    }
+
+    /// Records pulled so far from the [`Counted`] source behind `counter`.
+    fn pulled(counter: &AtomicUsize) -> usize {
+        counter.load(AtomicOrdering::Relaxed)
+    }
+
+    impl Iterator for Counted {
+        type Item = Record;
+
+        fn next(&mut self) -> Option<Record> {
+            let record = self.records.next()?;
+            self.pulled.fetch_add(1, AtomicOrdering::Relaxed);
+            Some(record)
+        }
+    }
+
+    impl RecordSource for Counted {
+        fn max_lsn(&self) -> u64 {
+            u64::MAX
+        }
+
+        fn seek_points(&mut self, _key: &[u8]) {}
+
+        fn lower_bound(&self) -> Option<&[u8]> {
+            self.bound.as_deref()
+        }
+    }
+
+    /// # Scenario
+    /// A source with a lower bound joins the merge only once the output
+    /// reaches that bound.
+    ///
+    /// # Starting environment
+    /// Sources `a0..a3` (no bound), `b0..b1` bounded at `b0`, `c0`
+    /// bounded at `c0`, and `a2` at a higher LSN bounded at `a2`.
+    ///
+    /// # Expected behavior
+    /// Taking the first two records pulls nothing from the bounded
+    /// sources. The newer `a2` still precedes the older one, and the
+    /// full merge yields every record in order.
+    #[test]
+    fn merge_iterator_defers_bounded_sources() {
+        let (a, _) = Counted::new(&["a0", "a1", "a2", "a3"], 1, None);
+        let (b, b_pulled) = Counted::new(&["b0", "b1"], 1, Some("b0"));
+        let (c, c_pulled) = Counted::new(&["c0"], 1, Some("c0"));
+        let (newer, newer_pulled) = Counted::new(&["a2"], 5, Some("a2"));
+        let sources: Vec<Box<dyn RecordSource + Send>> =
+            vec![Box::new(a), Box::new(b), Box::new(c), Box::new(newer)];
+        let mut merge = MergeIterator::skipping_covered(sources);
+
+        let first: Vec<Record> = merge.by_ref().take(2).collect();
+        assert_eq!(first[1].key(), b"a1");
+        assert_eq!(
+            (pulled(&b_pulled), pulled(&c_pulled), pulled(&newer_pulled)),
+            (0, 0, 0)
+        );
+
+        let next = merge.next().unwrap();
+        assert_eq!((next.key(), next.lsn()), (b"a2".as_slice(), 5));
+        assert_eq!((pulled(&b_pulled), pulled(&c_pulled)), (0, 0));
+
+        let rest: Vec<(Vec<u8>, u64)> = merge.map(|r| (r.key().to_vec(), r.lsn())).collect();
+        let expected: Vec<(Vec<u8>, u64)> = [("a2", 1), ("a3", 1), ("b0", 1), ("b1", 1), ("c0", 1)]
+            .iter()
+            .map(|&(k, lsn)| (k.as_bytes().to_vec(), lsn))
+            .collect();
+        assert_eq!(rest, expected);
+        assert_eq!((pulled(&b_pulled), pulled(&c_pulled)), (2, 1));
+    }
+
+    // ----------------------------------------------------------------
+    // coalesce_range_tombstones
+    // ----------------------------------------------------------------
+
+    fn spans(rts: &[RangeTombstone]) -> Vec<(&[u8], &[u8], u64)> {
+        rts.iter()
+            .map(|rt| (rt.start.as_slice(), rt.end.as_slice(), rt.lsn))
+            .collect()
+    }
+
+    #[test]
+    fn coalesce_drops_dominated_and_merges_equal_lsn() {
+        let rts = vec![
+            RangeTombstone::new(b"a", b"f", 9, 90),
+            RangeTombstone::new(b"b", b"c", 3, 30), // inside, older → dropped
+            RangeTombstone::new(b"c", b"d", 12, 120), // inside, newer → kept
+            RangeTombstone::new(b"m", b"p", 5, 50),
+            RangeTombstone::new(b"p", b"r", 5, 51), // touches, same LSN → merged
+            RangeTombstone::new(b"s", b"t", 5, 52), // gap → separate
+        ];
+        let out = coalesce_range_tombstones(rts, None);
+        assert_eq!(
+            spans(&out),
+            vec![
+                (&b"a"[..], &b"f"[..], 9),
+                (&b"c"[..], &b"d"[..], 12),
+                (&b"m"[..], &b"r"[..], 5),
+                (&b"s"[..], &b"t"[..], 5),
+            ]
+        );
+        assert_eq!(out[2].timestamp, 51);
+    }
+
+    #[test]
+    fn coalesce_without_points_keeps_differing_lsns() {
+        let rts = vec![
+            RangeTombstone::new(b"a", b"c", 1, 1),
+            RangeTombstone::new(b"c", b"e", 2, 2),
+        ];
+        assert_eq!(coalesce_range_tombstones(rts, None).len(), 2);
+    }
+
+    #[test]
+    fn coalesce_bridges_when_no_point_in_lsn_window() {
+        let rts = vec![
+            RangeTombstone::new(b"a", b"c", 10, 1),
+            RangeTombstone::new(b"c", b"e", 20, 2),
+        ];
+        // Older and newer versions inside the range, none in [10, 20).
+        let points = vec![
+            PointEntry::new(b"b", b"v", 5, 1),
+            PointEntry::new(b"d", b"v", 25, 3),
+            PointEntry::new(b"z", b"v", 15, 3), // outside the union
+        ];
+        let out = coalesce_range_tombstones(rts, Some(&points));
+        assert_eq!(spans(&out), vec![(&b"a"[..], &b"e"[..], 20)]);
+        assert_eq!(out[0].timestamp, 2);
+    }
+
+    #[test]
+    fn coalesce_does_not_bridge_over_point_in_lsn_window() {
+        let rts = vec![
+            RangeTombstone::new(b"a", b"c", 10, 1),
+            RangeTombstone::new(b"c", b"e", 20, 2),
+        ];
+        // `b` at LSN 15 survives the first tombstone but would be hidden
+        // by a merged tombstone at LSN 20.
+        let points = vec![PointEntry::new(b"b", b"v", 15, 1)];
+        let out = coalesce_range_tombstones(rts, Some(&points));
+        assert_eq!(out.len(), 2);
+    }
+
+    #[test]
+    fn unbounded_end_sorts_above_every_key() {
+        let long = vec![0xFF; 64];
+        assert!(below_end(&long, b""));
+        assert!(!below_end(b"c", b"c"));
+        assert_eq!(cmp_ends(b"", &long), Ordering::Greater);
+        assert_eq!(cmp_ends(&long, b""), Ordering::Less);
+        assert_eq!(cmp_ends(b"", b""), Ordering::Equal);
+        assert!(RangeTombstone::new(b"m", b"", 1, 1).covers(&long));
+        assert!(!RangeTombstone::new(b"m", b"", 1, 1).covers(b"l"));
+    }
+
+    #[test]
+    fn coalesce_merges_into_unbounded_end() {
+        let rts = vec![
+            RangeTombstone::new(b"a", b"c", 5, 50),
+            RangeTombstone::new(b"b", b"", 5, 51), // overlaps, same LSN → merged
+            RangeTombstone::new(b"x", b"z", 3, 30), // inside, older → dropped
+        ];
+        let out = coalesce_range_tombstones(rts, None);
+        assert_eq!(spans(&out), vec![(&b"a"[..], &b""[..], 5)]);
+
+        // A bounded tombstone past an unbounded one is never disjoint.
+        let rts = vec![
+            RangeTombstone::new(b"a", b"", 2, 20),
+            RangeTombstone::new(b"x", b"z", 7, 70),
+        ];
+        assert_eq!(coalesce_range_tombstones(rts, None).len(), 2);
+    }
 }
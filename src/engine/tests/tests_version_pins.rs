@@ -0,0 +1,255 @@
+//! Tests for version pins (`Engine::pin_versions`).
+//!
+//! While a pin is held, flushes and compactions keep every version a read
+//! at the pinned LSN would return, and every tombstone written after it.
+//! Once the pin is dropped, the next compaction collects them.
+//!
+//! ## Coverage areas
+//! - Flush keeps the overwritten versions above the pin
+//! - Major compaction keeps versions and tombstones above the pin, and
+//!   drops them after it is released
+//! - Tombstone compaction leaves tombstones above the pin alone
+//! - `Engine::scan_at` the pinned LSN returns the same pairs from the
+//!   active memtable, a frozen memtable, and SSTables before and after a
+//!   major compaction
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, EngineConfig, ReadOptions};
+    use tempfile::TempDir;
+
+    /// Config with aggressive tombstone GC and no grace period.
+    fn pin_config() -> EngineConfig {
+        init_tracing();
+        EngineConfig {
+            write_buffer_size: 64 * 1024,
+            min_sstable_size: 64,
+            tombstone_ratio_threshold: 0.01,
+            tombstone_compaction_interval: 0,
+            tombstone_bloom_fallback: true,
+            tombstone_range_drop: true,
+            ..EngineConfig::default()
+        }
+    }
+
+    /// Runs `writes` in a memtable of their own and flushes it to a single
+    /// SSTable.
+    fn flush_writes(engine: &Engine, writes: impl FnOnce()) {
+        engine.set_bulk_load(true).unwrap();
+        writes();
+        engine.set_bulk_load(false).unwrap();
+        assert_eq!(engine.flush_all_frozen().unwrap(), 1);
+    }
+
+    /// Returns the point records and tombstones of the only SSTable.
+    fn single_sstable_counts(engine: &Engine) -> (u64, u64, u64) {
+        let metadata = engine.sstable_metadata().unwrap();
+        assert_eq!(metadata.len(), 1, "{metadata:?}");
+        let meta = &metadata[0];
+        (
+            meta.record_count,
+            meta.tombstone_count,
+            meta.range_tombstone_count,
+        )
+    }
+
+    // ================================================================
+    // 1. Flush
+    // ================================================================
+
+    /// # Scenario
+    /// A key is overwritten after a pin is taken, in the same memtable.
+    ///
+    /// # Actions
+    /// 1. Put `k = v0`, pin, put `k = v1` and `k = v2`, flush.
+    ///
+    /// # Expected behavior
+    /// The SSTable holds all three versions: `v0` is what a read at the
+    /// pin returns. Reads still return `v2`.
+    #[test]
+    fn flush_keeps_versions_above_pin() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), pin_config()).unwrap();
+
+        let mut pin = None;
+        flush_writes(&engine, || {
+            engine.put(b"k".to_vec(), b"v0".to_vec()).unwrap();
+            pin = Some(engine.pin_versions().unwrap());
+            engine.put(b"k".to_vec(), b"v1".to_vec()).unwrap();
+            engine.put(b"k".to_vec(), b"v2".to_vec()).unwrap();
+        });
+
+        assert_eq!(single_sstable_counts(&engine), (3, 0, 0));
+        assert_eq!(engine.get(b"k".to_vec()).unwrap(), Some(b"v2".to_vec()));
+        drop(pin);
+    }
+
+    // ================================================================
+    // 2. Major compaction
+    // ================================================================
+
+    /// # Scenario
+    /// Versions and tombstones written after a pin are merged by a major
+    /// compaction, first with the pin held, then after it is dropped.
+    ///
+    /// # Starting environment
+    /// One SSTable with `a`, `b`, and `r5` at `v0`, written before the pin.
+    ///
+    /// # Actions
+    /// 1. Pin; flush `a = v1`, a delete of `b`, and a range delete of
+    ///    `[r0, r9)` over `r5`.
+    /// 2. Major compaction.
+    /// 3. Drop the pin, flush `c = v0`, major compaction again.
+    ///
+    /// # Expected behavior
+    /// - Step 2 keeps every record: both versions of `a`, the delete and
+    ///   the put of `b`, `r5`, and the range tombstone.
+    /// - Step 3 keeps only `a = v1` and `c = v0`.
+    /// - Reads return the newest versions throughout.
+    #[test]
+    fn major_compaction_collects_versions_after_unpin() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), pin_config()).unwrap();
+
+        flush_writes(&engine, || {
+            engine.put(b"a".to_vec(), b"v0".to_vec()).unwrap();
+            engine.put(b"b".to_vec(), b"v0".to_vec()).unwrap();
+            engine.put(b"r5".to_vec(), b"v0".to_vec()).unwrap();
+        });
+        let pin = engine.pin_versions().unwrap();
+        flush_writes(&engine, || {
+            engine.put(b"a".to_vec(), b"v1".to_vec()).unwrap();
+            engine.delete(b"b".to_vec()).unwrap();
+            engine.delete_range(b"r0".to_vec(), b"r9".to_vec()).unwrap();
+        });
+
+        assert!(engine.major_compact().unwrap());
+        assert_eq!(single_sstable_counts(&engine), (5, 1, 1));
+        assert_eq!(engine.get(b"a".to_vec()).unwrap(), Some(b"v1".to_vec()));
+        assert_eq!(engine.get(b"b".to_vec()).unwrap(), None);
+        assert_eq!(engine.get(b"r5".to_vec()).unwrap(), None);
+
+        drop(pin);
+        flush_writes(&engine, || {
+            engine.put(b"c".to_vec(), b"v0".to_vec()).unwrap();
+        });
+        assert!(engine.major_compact().unwrap());
+        assert_eq!(single_sstable_counts(&engine), (2, 0, 0));
+        assert_eq!(engine.get(b"a".to_vec()).unwrap(), Some(b"v1".to_vec()));
+        assert_eq!(engine.get(b"b".to_vec()).unwrap(), None);
+        assert_eq!(engine.get(b"r5".to_vec()).unwrap(), None);
+    }
+
+    // ================================================================
+    // 3. Tombstone compaction
+    // ================================================================
+
+    /// # Scenario
+    /// A spent point tombstone written after a pin is a tombstone
+    /// compaction candidate.
+    ///
+    /// # Starting environment
+    /// One SSTable holding `k = v0`, then a pin, then a delete of `k`.
+    ///
+    /// # Actions
+    /// 1. Tombstone compaction with the pin held.
+    /// 2. Drop the pin, tombstone compaction again.
+    ///
+    /// # Expected behavior
+    /// - Step 1 does nothing: the delete and `v0` are both kept.
+    /// - Step 2 drops both, leaving no SSTable.
+    #[test]
+    fn tombstone_compaction_keeps_tombstones_above_pin() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), pin_config()).unwrap();
+
+        let mut pin = None;
+        flush_writes(&engine, || {
+            engine.put(b"k".to_vec(), b"v0".to_vec()).unwrap();
+            pin = Some(engine.pin_versions().unwrap());
+            engine.delete(b"k".to_vec()).unwrap();
+        });
+
+        assert!(!engine.tombstone_compact().unwrap());
+        assert_eq!(single_sstable_counts(&engine), (2, 1, 0));
+
+        drop(pin);
+        assert!(engine.tombstone_compact().unwrap());
+        assert!(engine.sstable_metadata().unwrap().is_empty());
+        assert_eq!(engine.get(b"k".to_vec()).unwrap(), None);
+    }
+
+    // ================================================================
+    // 4. Scans at the pin
+    // ================================================================
+
+    /// # Scenario
+    /// A scan at the pinned LSN sees the same pairs wherever the data
+    /// lives.
+    ///
+    /// # Starting environment
+    /// One SSTable with `a`, `b`, and `r5` at `v0`.
+    ///
+    /// # Actions
+    /// 1. Note the latest LSN and pin it; in bulk-load mode, put
+    ///    `a = v1` and `c = v1`, delete `b`, and range-delete `[r0, r9)`.
+    /// 2. Scan at the pinned LSN: with the writes in the active memtable,
+    ///    after freezing it, after flushing it, and after a major
+    ///    compaction.
+    ///
+    /// # Expected behavior
+    /// Every scan at the pinned LSN returns `a`, `b`, and `r5` at `v0`.
+    /// A plain scan returns `a` and `c` at `v1`.
+    #[test]
+    fn scan_at_pin_survives_flush_and_compaction() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), pin_config()).unwrap();
+
+        flush_writes(&engine, || {
+            engine.put(b"a".to_vec(), b"v0".to_vec()).unwrap();
+            engine.put(b"b".to_vec(), b"v0".to_vec()).unwrap();
+            engine.put(b"r5".to_vec(), b"v0".to_vec()).unwrap();
+        });
+        let lsn = engine.latest_lsn().unwrap();
+        let _pin = engine.pin_versions().unwrap();
+
+        let at_pin = || -> Vec<(Vec<u8>, Vec<u8>)> {
+            engine
+                .scan_at(b"a", b"z", lsn, &ReadOptions::default())
+                .unwrap()
+                .collect()
+        };
+        let expected = vec![
+            (b"a".to_vec(), b"v0".to_vec()),
+            (b"b".to_vec(), b"v0".to_vec()),
+            (b"r5".to_vec(), b"v0".to_vec()),
+        ];
+
+        engine.set_bulk_load(true).unwrap();
+        engine.put(b"a".to_vec(), b"v1".to_vec()).unwrap();
+        engine.put(b"c".to_vec(), b"v1".to_vec()).unwrap();
+        engine.delete(b"b".to_vec()).unwrap();
+        engine.delete_range(b"r0".to_vec(), b"r9".to_vec()).unwrap();
+        assert_eq!(at_pin(), expected, "active memtable");
+
+        engine.set_bulk_load(false).unwrap();
+        assert_eq!(engine.stats().unwrap().frozen_count, 1);
+        assert_eq!(at_pin(), expected, "frozen memtable");
+
+        assert_eq!(engine.flush_all_frozen().unwrap(), 1);
+        assert_eq!(at_pin(), expected, "flushed");
+
+        assert!(engine.major_compact().unwrap());
+        assert_eq!(engine.stats().unwrap().sstables_count, 1);
+        assert_eq!(at_pin(), expected, "compacted");
+
+        assert_eq!(
+            collect_scan(&engine, b"a", b"z"),
+            vec![
+                (b"a".to_vec(), b"v1".to_vec()),
+                (b"c".to_vec(), b"v1".to_vec()),
+            ]
+        );
+    }
+}
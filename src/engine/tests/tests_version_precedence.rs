@@ -0,0 +1,377 @@
+//! Precedence between versions of one key that share an LSN
+//! (`VersionRank`): the same write present in a memtable and an SSTable,
+//! or in two SSTables, after a flush race or a WAL replay.
+//!
+//! Duplicates are produced by attaching hand-built SSTables to the live
+//! version; point lookups and scans must pick the same winner.
+//!
+//! The rule for range tombstones (`RangeTombstone::hides`): a tombstone
+//! hides only versions of a lower LSN, so a point version of an equal LSN
+//! stays visible whatever the timestamps and layers, in the engine's types
+//! and the public `types` alike.
+//!
+//! ## See also
+//! - [`tests_utils_coverage`] — `Record` ordering
+//! - [`tests_scan_ordering`] — `get` and `scan` agreement under churn
+
+#[cfg(test)]
+mod tests {
+    use crate::compaction::dedup_records;
+    use crate::engine::tests::helpers::*;
+    use crate::engine::utils::{MergeIterator, PointEntry, RangeTombstone, Record, VersionRank};
+    use crate::engine::version_pins::NO_HORIZON;
+    use crate::engine::{Engine, ReadOptions};
+    use crate::sstable::{SSTable, SstWriter};
+    use crate::types;
+    use std::path::Path;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    /// Builds an SSTable from `points` and `ranges` outside the engine's
+    /// directory and makes it live under `id`, as a flush would.
+    fn attach_sstable(
+        engine: &Engine,
+        dir: &Path,
+        id: u64,
+        points: Vec<PointEntry>,
+        ranges: Vec<RangeTombstone>,
+    ) {
+        let path = dir.join(format!("attached_{id}.sst"));
+        let (point_count, range_count) = (points.len(), ranges.len());
+        SstWriter::new(&path)
+            .build(
+                points.into_iter(),
+                point_count,
+                ranges.into_iter(),
+                range_count,
+            )
+            .unwrap();
+        let mut sst = SSTable::open(&path).unwrap();
+        sst.set_id(id);
+
+        let mut inner = engine.write_lock().unwrap();
+        inner.sstables.push(Arc::new(sst));
+        inner
+            .sstables
+            .sort_by_key(|s| std::cmp::Reverse((s.max_lsn(), s.id())));
+        inner.publish();
+    }
+
+    /// Returns the value of `key` through `get`, `get_entry`, and a scan,
+    /// asserting that all three agree.
+    fn visible(engine: &Engine, key: &[u8]) -> Option<Vec<u8>> {
+        let value = engine.get(key.to_vec()).unwrap();
+        let entry = engine
+            .get_entry(key.to_vec(), &ReadOptions::default())
+            .unwrap();
+        assert_eq!(entry.map(|e| e.value), value);
+
+        let mut end = key.to_vec();
+        end.push(0);
+        let scanned = collect_scan(engine, key, &end).pop().map(|(_, v)| v);
+        assert_eq!(scanned, value, "get and scan disagree");
+        value
+    }
+
+    /// # Scenario
+    /// A memtable version and an SSTable version share an LSN; the later
+    /// timestamp wins, whichever layer holds it.
+    ///
+    /// # Starting environment
+    /// Memtable-only engine with `a` and `b` written.
+    ///
+    /// # Actions
+    /// 1. Attach an SSTable holding `a` and `b` at the memtable LSNs, `a`
+    ///    with a later timestamp and `b` with an earlier one.
+    /// 2. Read both keys with `get`, `get_entry`, and `scan`.
+    ///
+    /// # Expected behavior
+    /// `a` reads the SSTable value, `b` the memtable value, through every
+    /// read path.
+    #[test]
+    fn duplicate_lsn_later_timestamp_wins() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), memtable_only_config()).unwrap();
+        engine.put(b"a".to_vec(), b"mem_a".to_vec()).unwrap();
+        engine.put(b"b".to_vec(), b"mem_b".to_vec()).unwrap();
+        let opts = ReadOptions::default();
+        let a = engine.get_entry(b"a".to_vec(), &opts).unwrap().unwrap();
+        let b = engine.get_entry(b"b".to_vec(), &opts).unwrap().unwrap();
+
+        let spare = TempDir::new().unwrap();
+        attach_sstable(
+            &engine,
+            spare.path(),
+            1000,
+            vec![
+                PointEntry::new(b"a", b"sst_a", a.lsn, a.timestamp + 1),
+                PointEntry::new(b"b", b"sst_b", b.lsn, b.timestamp - 1),
+            ],
+            Vec::new(),
+        );
+
+        assert_eq!(visible(&engine, b"a"), Some(b"sst_a".to_vec()));
+        assert_eq!(visible(&engine, b"b"), Some(b"mem_b".to_vec()));
+        let entry = engine.get_entry(b"a".to_vec(), &opts).unwrap().unwrap();
+        assert_eq!(entry.timestamp, a.timestamp + 1);
+    }
+
+    /// # Scenario
+    /// Versions equal in LSN and timestamp go to the newer layer: the
+    /// memtable over an SSTable, and of two SSTables with the same max
+    /// LSN the one with the higher id.
+    ///
+    /// # Starting environment
+    /// Memtable-only engine with `m` written.
+    ///
+    /// # Actions
+    /// 1. Attach SSTable 1000 holding `m` (same LSN and timestamp) and `s`
+    ///    at LSN 5; attach SSTable 1001 holding `s` at the same LSN and
+    ///    timestamp, and `m` at the memtable LSN.
+    /// 2. Read both keys.
+    ///
+    /// # Expected behavior
+    /// `m` reads the memtable value, `s` the value of SSTable 1001.
+    #[test]
+    fn duplicate_lsn_equal_timestamp_newer_layer_wins() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), memtable_only_config()).unwrap();
+        engine.put(b"m".to_vec(), b"mem".to_vec()).unwrap();
+        let m = engine
+            .get_entry(b"m".to_vec(), &ReadOptions::default())
+            .unwrap()
+            .unwrap();
+
+        let spare = TempDir::new().unwrap();
+        attach_sstable(
+            &engine,
+            spare.path(),
+            1000,
+            vec![
+                PointEntry::new(b"m", b"sst_1000", m.lsn, m.timestamp),
+                PointEntry::new(b"s", b"sst_1000", 5, 50),
+            ],
+            Vec::new(),
+        );
+        attach_sstable(
+            &engine,
+            spare.path(),
+            1001,
+            vec![
+                PointEntry::new(b"m", b"sst_1001", m.lsn, m.timestamp),
+                PointEntry::new(b"s", b"sst_1001", 5, 50),
+            ],
+            Vec::new(),
+        );
+
+        assert_eq!(visible(&engine, b"m"), Some(b"mem".to_vec()));
+        assert_eq!(visible(&engine, b"s"), Some(b"sst_1001".to_vec()));
+    }
+
+    /// # Scenario
+    /// A point version beats a range tombstone of the same LSN, even one
+    /// with a later timestamp; a tombstone only hides older versions.
+    ///
+    /// # Starting environment
+    /// Memtable-only engine with `k` written.
+    ///
+    /// # Actions
+    /// 1. Attach an SSTable holding a range tombstone `[a, m)` at the LSN
+    ///    of `k` with a later timestamp, and a delete of `x` at LSN 3
+    ///    next to a put of `x` at LSN 3.
+    /// 2. Read `k` and `x`.
+    ///
+    /// # Expected behavior
+    /// `k` is still visible. `x` is deleted: at an equal LSN and
+    /// timestamp the point versions keep SSTable order, and both reads
+    /// agree on it.
+    #[test]
+    fn duplicate_lsn_point_beats_range_tombstone() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), memtable_only_config()).unwrap();
+        engine.put(b"k".to_vec(), b"mem".to_vec()).unwrap();
+        let k = engine
+            .get_entry(b"k".to_vec(), &ReadOptions::default())
+            .unwrap()
+            .unwrap();
+
+        let spare = TempDir::new().unwrap();
+        attach_sstable(
+            &engine,
+            spare.path(),
+            1000,
+            vec![PointEntry::new_delete(b"x", 3, 30)],
+            vec![RangeTombstone::new(b"a", b"m", k.lsn, k.timestamp + 1)],
+        );
+        attach_sstable(
+            &engine,
+            spare.path(),
+            999,
+            vec![PointEntry::new(b"x", b"old", 3, 30)],
+            Vec::new(),
+        );
+
+        assert_eq!(visible(&engine, b"k"), Some(b"mem".to_vec()));
+        assert_eq!(visible(&engine, b"x"), None);
+    }
+
+    /// # Scenario
+    /// Overlapping range tombstones in two SSTables share the LSN of a
+    /// put in a third; the put stays visible, while a put of a lower LSN
+    /// in the same range is hidden.
+    ///
+    /// # Starting environment
+    /// Memtable-only engine.
+    ///
+    /// # Actions
+    /// 1. Attach SSTable 1000 holding puts of `d` at LSN 5 and `f` at
+    ///    LSN 4, timestamp 50.
+    /// 2. Attach SSTables 1001 and 1002 holding range tombstones `[a, g)`
+    ///    and `[c, z)` at LSN 5, with timestamps 60 and 40.
+    /// 3. Read `d` and `f` with `get`, `get_entry`, and `scan`, and scan
+    ///    `[a, z)`.
+    ///
+    /// # Expected behavior
+    /// `d` is visible through every read path, `f` through none.
+    #[test]
+    fn duplicate_lsn_put_beats_overlapping_range_tombstones() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), memtable_only_config()).unwrap();
+
+        let spare = TempDir::new().unwrap();
+        attach_sstable(
+            &engine,
+            spare.path(),
+            1000,
+            vec![
+                PointEntry::new(b"d", b"put", 5, 50),
+                PointEntry::new(b"f", b"old", 4, 50),
+            ],
+            Vec::new(),
+        );
+        attach_sstable(
+            &engine,
+            spare.path(),
+            1001,
+            Vec::new(),
+            vec![RangeTombstone::new(b"a", b"g", 5, 60)],
+        );
+        attach_sstable(
+            &engine,
+            spare.path(),
+            1002,
+            Vec::new(),
+            vec![RangeTombstone::new(b"c", b"z", 5, 40)],
+        );
+
+        assert_eq!(visible(&engine, b"d"), Some(b"put".to_vec()));
+        assert_eq!(visible(&engine, b"f"), None);
+        assert_eq!(
+            collect_scan(&engine, b"a", b"z"),
+            vec![(b"d".to_vec(), b"put".to_vec())]
+        );
+    }
+
+    /// # Scenario
+    /// The rule by which a range tombstone hides a version, checked case
+    /// by case for the engine's tombstones and the public ones.
+    ///
+    /// # Starting environment
+    /// A tombstone `[b, d)` at LSN 10, timestamp 100.
+    ///
+    /// # Actions
+    /// 1. Ask both representations whether it hides versions of `c` and
+    ///    `d` at LSNs 9, 10, and 11, with earlier, equal, and later
+    ///    timestamps, as points and as range tombstones.
+    ///
+    /// # Expected behavior
+    /// Both hide exactly the versions of `c` at LSN 9, and the range
+    /// tombstones of `c` at LSN 10 with an earlier timestamp.
+    #[test]
+    fn range_tombstone_hides_only_outranked_versions() {
+        let engine_rt = RangeTombstone::new(b"b", b"d", 10, 100);
+        let public_rt = types::RangeTombstone::from(engine_rt.clone());
+
+        for key in [&b"c"[..], b"d"] {
+            for lsn in [9, 10, 11] {
+                for timestamp in [99, 100, 101] {
+                    for point in [true, false] {
+                        let version = VersionRank {
+                            lsn,
+                            point,
+                            timestamp,
+                        };
+                        let expected =
+                            key == b"c" && (lsn < 10 || (lsn == 10 && !point && timestamp < 100));
+                        assert_eq!(engine_rt.hides(key, version), expected, "{version:?}");
+                        assert_eq!(public_rt.hides(key, version), expected, "{version:?}");
+                    }
+                }
+            }
+        }
+
+        let put = types::Record::Put {
+            key: b"c".to_vec(),
+            value: b"v".to_vec(),
+            lsn: 10,
+            timestamp: 1,
+        };
+        assert!(!public_rt.hides(put.key(), put.rank()));
+    }
+
+    /// One source per value, each holding `k` at LSN 7; `t1` has
+    /// timestamp 1, every other value timestamp 2.
+    fn duplicate_sources(values: &[&str]) -> Vec<Box<dyn Iterator<Item = Record> + Send>> {
+        values
+            .iter()
+            .map(|&value| {
+                let record = Record::Put {
+                    key: b"k".to_vec(),
+                    value: value.as_bytes().to_vec(),
+                    lsn: 7,
+                    timestamp: if value == "t1" { 1 } else { 2 },
+                };
+                Box::new(std::iter::once(record)) as Box<dyn Iterator<Item = Record> + Send>
+            })
+            .collect()
+    }
+
+    /// Merges `values` with [`duplicate_sources`] and returns the values in
+    /// merge order.
+    fn merged_values(values: &[&str]) -> Vec<String> {
+        MergeIterator::new(duplicate_sources(values))
+            .map(|record| match record {
+                Record::Put { value, .. } => String::from_utf8(value).unwrap(),
+                other => panic!("unexpected {other:?}"),
+            })
+            .collect()
+    }
+
+    /// # Scenario
+    /// The merge used by scans and compaction orders duplicates by rank,
+    /// then by source, independent of which source holds which version.
+    ///
+    /// # Starting environment
+    /// Three in-memory sources holding `k` at LSN 7 with timestamps 1, 2,
+    /// and 2 (values `t1`, `t2_first`, `t2_second`).
+    ///
+    /// # Actions
+    /// 1. Merge them in that order and deduplicate as compaction does.
+    /// 2. Merge them with the first two sources swapped.
+    ///
+    /// # Expected behavior
+    /// Each merge yields `k` three times, the later timestamp first; the
+    /// two timestamp-2 versions come in source order. Deduplication keeps
+    /// `t2_first`.
+    #[test]
+    fn merge_orders_duplicates_by_rank_then_source() {
+        let expected = ["t2_first", "t2_second", "t1"];
+        assert_eq!(merged_values(&["t1", "t2_first", "t2_second"]), expected);
+        assert_eq!(merged_values(&["t2_first", "t1", "t2_second"]), expected);
+
+        let sources = duplicate_sources(&["t1", "t2_first", "t2_second"]);
+        let (points, _) = dedup_records(MergeIterator::new(sources), NO_HORIZON);
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].value.as_deref(), Some(&b"t2_first"[..]));
+    }
+}
@@ -0,0 +1,223 @@
+//! Tests for garbage collection and retention of flushed WAL segments
+//! (`EngineConfig::wal_retention_bytes`, `EngineConfig::wal_retention_seconds`,
+//! `Engine::wal_files`).
+//!
+//! ## Coverage areas
+//! - With no limits, every flushed segment is deleted by the flush
+//! - A stale flushed segment left by a crash is deleted on open
+//! - `wal_files` reports each segment's role
+//! - The size limit deletes the oldest flushed segments after a flush
+//! - The age limit deletes old flushed segments on the next open
+//! - Active and frozen (un-flushed) WALs are never deleted
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, EngineConfig, MEMTABLE_DIR, WalFileStatus};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::thread;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    /// Returns the memtable WAL files, oldest first.
+    fn wal_files(path: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = fs::read_dir(path.join(MEMTABLE_DIR))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Puts `count` keys with `prefix`.
+    fn put_keys(engine: &Engine, prefix: &str, count: u32) {
+        for i in 0..count {
+            let key = format!("{prefix}_{i:04}").into_bytes();
+            engine.put(key, b"value".to_vec()).unwrap();
+        }
+    }
+
+    /// # Scenario
+    /// Without retention limits, a flush deletes the WAL segments it
+    /// released.
+    ///
+    /// # Starting environment
+    /// Engine with a 128-byte write buffer, default retention.
+    ///
+    /// # Actions
+    /// 1. Put 50 keys, counting WAL files.
+    /// 2. Flush all frozen memtables.
+    ///
+    /// # Expected behavior
+    /// Only the active WAL remains, and every key is readable.
+    #[test]
+    fn wal_gc_deletes_flushed_by_default() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), small_buffer_config()).unwrap();
+        put_keys(&engine, "d", 50);
+        assert!(wal_files(dir.path()).len() > 2);
+
+        engine.flush_all_frozen().unwrap();
+        let files = engine.wal_files().unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].status, WalFileStatus::Active);
+        assert_eq!(wal_files(dir.path()), vec![files[0].path.clone()]);
+
+        for i in 0..50u32 {
+            let key = format!("d_{i:04}").into_bytes();
+            assert_eq!(engine.get(key).unwrap(), Some(b"value".to_vec()));
+        }
+    }
+
+    /// # Scenario
+    /// A flushed segment that survived a crash between the manifest
+    /// update and its deletion is collected on the next open.
+    ///
+    /// # Starting environment
+    /// Engine with a 128-byte write buffer, flushed and dropped, plus a
+    /// stray segment numbered just below the active WAL.
+    ///
+    /// # Actions
+    /// 1. Reopen.
+    ///
+    /// # Expected behavior
+    /// The stray segment is gone and every key is readable.
+    #[test]
+    fn wal_gc_collects_stale_segment_on_open() {
+        let dir = TempDir::new().unwrap();
+        let stale = {
+            let engine = Engine::open(dir.path(), small_buffer_config()).unwrap();
+            put_keys(&engine, "s", 50);
+            engine.flush_all_frozen().unwrap();
+            let active = engine.wal_files().unwrap().pop().unwrap();
+            assert!(active.wal_id > 1);
+            let stale = dir
+                .path()
+                .join(MEMTABLE_DIR)
+                .join(format!("{:06}.log", active.wal_id - 1));
+            fs::write(&stale, b"stale segment").unwrap();
+            stale
+        };
+
+        let engine = Engine::open(dir.path(), small_buffer_config()).unwrap();
+        assert!(!stale.exists());
+        assert!(
+            engine
+                .wal_files()
+                .unwrap()
+                .iter()
+                .all(|f| f.status != WalFileStatus::Flushed)
+        );
+        for i in 0..50u32 {
+            let key = format!("s_{i:04}").into_bytes();
+            assert_eq!(engine.get(key).unwrap(), Some(b"value".to_vec()));
+        }
+    }
+
+    /// # Scenario
+    /// `wal_files` lists every segment with its role.
+    ///
+    /// # Starting environment
+    /// Engine with a 128-byte write buffer and unflushed frozen memtables.
+    ///
+    /// # Expected behavior
+    /// The segments are listed oldest first with their on-disk sizes: one
+    /// `Frozen` per frozen memtable, then the `Active` WAL.
+    #[test]
+    fn wal_files_reports_status() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), small_buffer_config()).unwrap();
+        put_keys(&engine, "w", 50);
+        let frozen_count = engine.stats().unwrap().frozen_count;
+        assert!(frozen_count > 0);
+
+        let files = engine.wal_files().unwrap();
+        assert_eq!(files.len(), frozen_count + 1);
+        let (active, frozen) = files.split_last().unwrap();
+        assert_eq!(active.status, WalFileStatus::Active);
+        assert!(frozen.iter().all(|f| f.status == WalFileStatus::Frozen));
+        assert!(files.windows(2).all(|w| w[0].wal_id < w[1].wal_id));
+        for file in &files {
+            assert_eq!(file.size, fs::metadata(&file.path).unwrap().len());
+        }
+    }
+
+    /// # Scenario
+    /// The size limit bounds the bytes kept in flushed segments.
+    ///
+    /// # Starting environment
+    /// Engine with a 128-byte write buffer and `wal_retention_bytes: 1024`.
+    ///
+    /// # Actions
+    /// 1. Put 200 keys, then flush all frozen memtables.
+    ///
+    /// # Expected behavior
+    /// Flushed segments total at most 1024 bytes but are not all deleted,
+    /// and every key is readable.
+    #[test]
+    fn wal_retention_bytes_prunes_oldest() {
+        let dir = TempDir::new().unwrap();
+        let config = EngineConfig {
+            wal_retention_bytes: 1024,
+            ..small_buffer_config()
+        };
+        let engine = Engine::open(dir.path(), config).unwrap();
+        put_keys(&engine, "b", 200);
+        engine.flush_all_frozen().unwrap();
+
+        let mut files = wal_files(dir.path());
+        files.pop(); // active WAL
+        let retained: u64 = files.iter().map(|f| fs::metadata(f).unwrap().len()).sum();
+        assert!(retained <= 1024, "{retained} bytes retained");
+        assert!(!files.is_empty(), "every flushed segment was deleted");
+
+        for i in 0..200u32 {
+            let key = format!("b_{i:04}").into_bytes();
+            assert_eq!(engine.get(key).unwrap(), Some(b"value".to_vec()));
+        }
+    }
+
+    /// # Scenario
+    /// The age limit deletes old flushed segments but keeps WALs still
+    /// needed for recovery.
+    ///
+    /// # Starting environment
+    /// Engine with a 128-byte write buffer and `wal_retention_seconds: 1`,
+    /// with flushed segments and unflushed frozen memtables, dropped
+    /// without close.
+    ///
+    /// # Actions
+    /// 1. Sleep 1.1 s.
+    /// 2. Reopen.
+    ///
+    /// # Expected behavior
+    /// Only the active and frozen WALs remain, and every key is readable.
+    #[test]
+    fn wal_retention_seconds_prunes_on_open() {
+        let dir = TempDir::new().unwrap();
+        let config = EngineConfig {
+            wal_retention_seconds: 1,
+            ..small_buffer_config()
+        };
+        let frozen_count;
+        {
+            let engine = Engine::open(dir.path(), config.clone()).unwrap();
+            put_keys(&engine, "f", 50);
+            engine.flush_all_frozen().unwrap();
+            put_keys(&engine, "u", 50);
+            frozen_count = engine.stats().unwrap().frozen_count;
+            assert!(frozen_count > 0);
+        }
+
+        thread::sleep(Duration::from_millis(1100));
+        let engine = Engine::open(dir.path(), config).unwrap();
+        assert_eq!(wal_files(dir.path()).len(), frozen_count + 1);
+        for prefix in ["f", "u"] {
+            for i in 0..50u32 {
+                let key = format!("{prefix}_{i:04}").into_bytes();
+                assert_eq!(engine.get(key).unwrap(), Some(b"value".to_vec()));
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Crash-recovery tests for damaged memtable WAL tails
+//! (`EngineConfig::wal_recovery_mode`).
+//!
+//! ## See also
+//! - [`wal::tests::tests_torn_tail`] — WAL-level tail classification
+//! - [`tests_crash_recovery`] — crash recovery with intact WALs
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, EngineConfig, EngineError, MEMTABLE_DIR};
+    use crate::memtable::MemtableError;
+    use crate::wal::{WalError, WalRecoveryMode};
+    use std::fs::{self, OpenOptions};
+    use std::io::{Seek, SeekFrom, Write};
+    use std::path::{Path, PathBuf};
+    use tempfile::TempDir;
+
+    /// Path of the newest memtable WAL — the active one.
+    fn active_wal(path: &Path) -> PathBuf {
+        let mut wals: Vec<_> = fs::read_dir(path.join(MEMTABLE_DIR))
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "log"))
+            .collect();
+        wals.sort();
+        wals.pop().expect("active WAL")
+    }
+
+    /// Crash with three keys in the active WAL and return its size.
+    fn crash_with_three_keys(path: &Path) -> u64 {
+        let engine = Engine::open(path, memtable_only_config()).unwrap();
+        for k in [b"a", b"b", b"c"] {
+            engine.put(k.to_vec(), b"v".to_vec()).unwrap();
+        }
+        drop(engine);
+        fs::metadata(active_wal(path)).unwrap().len()
+    }
+
+    fn config_with(mode: WalRecoveryMode) -> EngineConfig {
+        EngineConfig {
+            wal_recovery_mode: mode,
+            ..memtable_only_config()
+        }
+    }
+
+    /// # Scenario
+    /// A crash tore the last record of the active WAL.
+    ///
+    /// # Actions
+    /// 1. Put `a`, `b`, `c`; drop without close.
+    /// 2. Cut the last 3 bytes off the active WAL.
+    /// 3. Reopen, put `d`, drop, reopen again.
+    ///
+    /// # Expected behavior
+    /// `a` and `b` survive, the torn `c` is gone, and `d` — written after
+    /// recovery — survives the second reopen. The first recovery report
+    /// counts 2 records and the dropped bytes of `c`; the second reports
+    /// no drop.
+    #[test]
+    fn torn_active_wal_tail_recovers() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path();
+        let size = crash_with_three_keys(path);
+
+        let f = OpenOptions::new()
+            .write(true)
+            .open(active_wal(path))
+            .unwrap();
+        f.set_len(size - 3).unwrap();
+        drop(f);
+
+        let engine = Engine::open(path, memtable_only_config()).unwrap();
+        assert!(engine.get(b"a".to_vec()).unwrap().is_some());
+        assert!(engine.get(b"b".to_vec()).unwrap().is_some());
+        assert!(engine.get(b"c".to_vec()).unwrap().is_none());
+        let report = engine.recovery_report();
+        let record_len = (size - 24) / 3;
+        assert_eq!(report.wal_segments_replayed, 1);
+        assert_eq!(report.records_recovered, 2);
+        assert_eq!(report.wal_tails_dropped, 1);
+        assert_eq!(report.wal_bytes_dropped, record_len - 3);
+        engine.put(b"d".to_vec(), b"v".to_vec()).unwrap();
+        drop(engine);
+
+        let engine = Engine::open(path, memtable_only_config()).unwrap();
+        assert!(engine.get(b"b".to_vec()).unwrap().is_some());
+        assert!(engine.get(b"d".to_vec()).unwrap().is_some());
+        let report = engine.recovery_report();
+        assert_eq!(report.records_recovered, 3);
+        assert_eq!(report.wal_tails_dropped, 0);
+        assert_eq!(report.wal_bytes_dropped, 0);
+    }
+
+    /// # Scenario
+    /// A record in the middle of the active WAL is corrupted.
+    ///
+    /// # Expected behavior
+    /// With the default mode, `Engine::open` fails with
+    /// `WalError::CorruptRecord` instead of silently dropping `b` and `c`.
+    /// With `PointInTime`, it opens and keeps only the records before the
+    /// damage.
+    #[test]
+    fn mid_wal_corruption_fails_open_unless_point_in_time() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path();
+        let size = crash_with_three_keys(path);
+
+        // Each record has the same length; flip a byte in the second one.
+        let wal = active_wal(path);
+        let header = 20 + 4;
+        let record_len = (size - header) / 3;
+        let mut f = OpenOptions::new().write(true).open(&wal).unwrap();
+        f.seek(SeekFrom::Start(header + record_len + 8)).unwrap();
+        f.write_all(&[0xFF]).unwrap();
+        drop(f);
+
+        let Err(err) = Engine::open(path, memtable_only_config()) else {
+            panic!("open should fail on mid-log corruption");
+        };
+        assert!(
+            matches!(
+                err,
+                EngineError::Memtable(MemtableError::Wal(WalError::CorruptRecord(_)))
+            ),
+            "{err:?}"
+        );
+
+        let engine = Engine::open(path, config_with(WalRecoveryMode::PointInTime)).unwrap();
+        assert!(engine.get(b"a".to_vec()).unwrap().is_some());
+        assert!(engine.get(b"b".to_vec()).unwrap().is_none());
+        assert!(engine.get(b"c".to_vec()).unwrap().is_none());
+    }
+}
@@ -0,0 +1,71 @@
+//! Tests for page-cache warm-up (`Engine::warm_up`, `Engine::warm_up_recent`).
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use tempfile::TempDir;
+
+    /// # Scenario
+    /// Warming a key range reads only the data blocks that may hold it.
+    ///
+    /// # Starting environment
+    /// Engine with `wu_0000..wu_0199` spread across several SSTables.
+    ///
+    /// # Actions
+    /// 1. Warm the whole key range, a narrow range, a range past the
+    ///    last key, and an empty range.
+    /// 2. Warm the newest SSTables, counting all of them.
+    ///
+    /// # Expected behavior
+    /// The whole range reads more than the narrow one and no more than
+    /// the SSTable files hold; ranges without keys read nothing. Warming
+    /// every SSTable reads the same blocks as warming the whole range.
+    #[test]
+    fn warm_up_reads_covering_blocks() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 200, "wu");
+        let metadata = engine.sstable_metadata().unwrap();
+        let total: u64 = metadata.iter().map(|m| m.file_size).sum();
+
+        let all = engine.warm_up(b"wu_", b"wu_~").unwrap();
+        let narrow = engine.warm_up(b"wu_0100", b"wu_0101").unwrap();
+        assert!(narrow > 0);
+        assert!(all > narrow);
+        assert!(all < total, "only data blocks are read");
+
+        assert_eq!(engine.warm_up(b"zz", b"zzz").unwrap(), 0);
+        assert_eq!(engine.warm_up(b"wu_0100", b"wu_0100").unwrap(), 0);
+
+        assert_eq!(engine.warm_up_recent(metadata.len()).unwrap(), all);
+        assert_eq!(engine.warm_up_recent(usize::MAX).unwrap(), all);
+    }
+
+    /// # Scenario
+    /// `warm_up_recent` picks the most recently created SSTables.
+    ///
+    /// # Starting environment
+    /// Engine with several SSTables.
+    ///
+    /// # Actions
+    /// 1. Warm the newest SSTable; warm none.
+    ///
+    /// # Expected behavior
+    /// One SSTable reads some but not all of its file — the bloom filter,
+    /// index, and footer are skipped; zero SSTables read nothing.
+    #[test]
+    fn warm_up_recent_picks_newest() {
+        let dir = TempDir::new().unwrap();
+        let engine = engine_with_multi_sstables(dir.path(), 200, "wu");
+        let newest = engine
+            .sstable_metadata()
+            .unwrap()
+            .into_iter()
+            .max_by_key(|m| m.id)
+            .unwrap();
+
+        let bytes = engine.warm_up_recent(1).unwrap();
+        assert!(bytes > 0);
+        assert!(bytes < newest.file_size);
+        assert_eq!(engine.warm_up_recent(0).unwrap(), 0);
+    }
+}
@@ -0,0 +1,149 @@
+//! Per-write options (`WriteOptions`): writes that skip the WAL, and
+//! what of them survives a crash or a clean close.
+//!
+//! ## See also
+//! - [`tests_crash_recovery`] — WAL replay of logged writes
+//! - [`tests_bulk_load`] — writes logged without per-record fsync
+
+#[cfg(test)]
+mod tests {
+    use crate::batch::WriteOp;
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, WriteOptions};
+    use tempfile::TempDir;
+
+    const UNLOGGED: WriteOptions = WriteOptions {
+        disable_wal: true,
+        sync: false,
+    };
+
+    const SYNCED: WriteOptions = WriteOptions {
+        disable_wal: false,
+        sync: true,
+    };
+
+    /// # Scenario
+    /// A crash loses the writes that skipped the WAL and keeps the rest.
+    ///
+    /// # Starting environment
+    /// Memtable-only engine.
+    ///
+    /// # Actions
+    /// 1. Put `a` and `b` logged (`b` with `sync`).
+    /// 2. Overwrite `a`, put `c`, delete `b`, all unlogged; read them back.
+    /// 3. Drop the engine without `close()`; reopen.
+    ///
+    /// # Expected behavior
+    /// Before the crash the unlogged writes are visible. After it `a` and
+    /// `b` hold their logged values again and `c` is absent.
+    #[test]
+    fn unlogged_writes_lost_on_crash() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), memtable_only_config()).unwrap();
+        engine.put(b"a".to_vec(), b"logged".to_vec()).unwrap();
+        engine
+            .put_opt(b"b".to_vec(), b"synced".to_vec(), &SYNCED)
+            .unwrap();
+
+        engine
+            .put_opt(b"a".to_vec(), b"unlogged".to_vec(), &UNLOGGED)
+            .unwrap();
+        engine
+            .write_batch(
+                &[
+                    WriteOp::Put {
+                        key: b"c".to_vec(),
+                        value: b"unlogged".to_vec(),
+                    },
+                    WriteOp::Delete { key: b"b".to_vec() },
+                ],
+                &UNLOGGED,
+            )
+            .unwrap();
+        assert_eq!(
+            engine.get(b"a".to_vec()).unwrap(),
+            Some(b"unlogged".to_vec())
+        );
+        assert_eq!(engine.get(b"b".to_vec()).unwrap(), None);
+        assert_eq!(
+            engine.get(b"c".to_vec()).unwrap(),
+            Some(b"unlogged".to_vec())
+        );
+        drop(engine);
+
+        let engine = Engine::open(dir.path(), memtable_only_config()).unwrap();
+        assert_eq!(engine.get(b"a".to_vec()).unwrap(), Some(b"logged".to_vec()));
+        assert_eq!(engine.get(b"b".to_vec()).unwrap(), Some(b"synced".to_vec()));
+        assert_eq!(engine.get(b"c".to_vec()).unwrap(), None);
+    }
+
+    /// # Scenario
+    /// Unlogged writes survive a crash once flushed to an SSTable.
+    ///
+    /// # Starting environment
+    /// Engine with a small write buffer.
+    ///
+    /// # Actions
+    /// 1. Put `uf_0000..uf_0199` unlogged, freezing several memtables.
+    /// 2. Flush the frozen memtables; note which keys reached SSTables.
+    /// 3. Drop the engine without `close()`; reopen.
+    ///
+    /// # Expected behavior
+    /// Exactly the keys that were flushed read back.
+    #[test]
+    fn flushed_unlogged_writes_survive_crash() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), small_buffer_config()).unwrap();
+        for i in 0..200u32 {
+            let key = format!("uf_{i:04}").into_bytes();
+            engine.put_opt(key, b"value".to_vec(), &UNLOGGED).unwrap();
+        }
+        engine.flush_all_frozen().unwrap();
+        let flushed =
+            (0..200u32)
+                .filter(|i| {
+                    let key = format!("uf_{i:04}").into_bytes();
+                    engine.version.load().sstables.iter().any(|sst| {
+                        sst.min_key() <= key.as_slice() && key.as_slice() <= sst.max_key()
+                    })
+                })
+                .count();
+        assert!(flushed > 0 && flushed < 200);
+        drop(engine);
+
+        let engine = Engine::open(dir.path(), small_buffer_config()).unwrap();
+        assert_eq!(collect_scan(&engine, b"uf_", b"uf_~").len(), flushed);
+    }
+
+    /// # Scenario
+    /// A clean close flushes the active memtable when it holds unlogged
+    /// writes, so none are lost.
+    ///
+    /// # Starting environment
+    /// Memtable-only engine.
+    ///
+    /// # Actions
+    /// 1. Put `uc_0000..uc_0049` unlogged and delete `uc_0007` unlogged.
+    /// 2. Close; reopen.
+    ///
+    /// # Expected behavior
+    /// The close writes one SSTable. After reopen the 49 live keys read
+    /// back and `uc_0007` stays deleted.
+    #[test]
+    fn close_flushes_unlogged_writes() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), memtable_only_config()).unwrap();
+        for i in 0..50u32 {
+            let key = format!("uc_{i:04}").into_bytes();
+            engine.put_opt(key, b"value".to_vec(), &UNLOGGED).unwrap();
+        }
+        engine.delete_opt(b"uc_0007".to_vec(), &UNLOGGED).unwrap();
+        engine.close().unwrap();
+        assert_eq!(engine.version.load().sstables.len(), 1);
+        drop(engine);
+
+        let engine = Engine::open(dir.path(), memtable_only_config()).unwrap();
+        assert_eq!(collect_scan(&engine, b"uc_", b"uc_~").len(), 49);
+        assert_eq!(engine.get(b"uc_0007".to_vec()).unwrap(), None);
+    }
+}
@@ -0,0 +1,144 @@
+//! Tests for the write pipeline counters (`Engine::write_stats` and
+//! `Engine::take_write_activity`).
+//!
+//! ## Coverage areas
+//! - Puts count WAL records; freezes count rotations; flushes count the
+//!   SSTables they build, matching the files on disk
+//! - Compaction, ingestion, and clear add to the same counters
+//! - Records replayed at open are not counted
+//! - Write activity reports only what changed since the previous report
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::tests::helpers::*;
+    use crate::engine::{Engine, WriteStats};
+    use tempfile::TempDir;
+
+    /// Puts `wst_0000..` keys with values long enough to fill several
+    /// write buffers of `multi_sstable_config`.
+    fn put_keys(engine: &Engine, count: u32) {
+        for i in 0..count {
+            let key = format!("wst_{i:04}").into_bytes();
+            engine
+                .put(key, b"value_with_some_padding".to_vec())
+                .unwrap();
+        }
+    }
+
+    /// # Scenario
+    /// The counters follow writes from the WAL to the SSTables.
+    ///
+    /// # Starting environment
+    /// Empty engine with a 1 KB write buffer.
+    ///
+    /// # Actions
+    /// 1. Put 200 keys; flush every frozen memtable.
+    /// 2. Major compact.
+    ///
+    /// # Expected behavior
+    /// Step 1 counts 200 WAL records and an fsync each, a rotation per
+    /// frozen memtable, and SSTables whose file bytes add up to the
+    /// tables on disk. Step 2 adds one more SSTable, the size of the
+    /// compacted table.
+    #[test]
+    fn write_stats_follow_flush_and_compaction() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), multi_sstable_config()).unwrap();
+        assert_eq!(engine.write_stats().unwrap(), WriteStats::default());
+
+        put_keys(&engine, 200);
+        engine.flush_all_frozen().unwrap();
+
+        let flushed = engine.write_stats().unwrap();
+        let stats = engine.stats().unwrap();
+        assert_eq!(flushed.wal.records_appended, 200);
+        assert_eq!(flushed.wal.syncs, 200);
+        assert!(flushed.wal.rotations >= stats.sstables_count as u64);
+        assert_eq!(flushed.sstables.sstables, stats.sstables_count as u64);
+        assert_eq!(flushed.sstables.file_bytes, stats.total_sst_size_bytes);
+        assert!(flushed.sstables.written_bytes > flushed.sstables.raw_bytes);
+
+        assert!(engine.major_compact().unwrap());
+        let compacted = engine.write_stats().unwrap();
+        assert_eq!(compacted.wal, flushed.wal);
+        assert_eq!(compacted.sstables.sstables, flushed.sstables.sstables + 1);
+        assert_eq!(
+            compacted.sstables.file_bytes,
+            flushed.sstables.file_bytes + engine.stats().unwrap().total_sst_size_bytes
+        );
+    }
+
+    /// # Scenario
+    /// Clear and ingestion add to the counters; recovery does not.
+    ///
+    /// # Starting environment
+    /// Empty engine with a 4 KB write buffer.
+    ///
+    /// # Actions
+    /// 1. Put 3 keys; clear.
+    /// 2. Ingest 2 sorted pairs.
+    /// 3. Put a key; reopen.
+    ///
+    /// # Expected behavior
+    /// The clear counts one rotation; the ingestion one SSTable. The
+    /// reopened engine starts from zero, though it replayed the WAL.
+    #[test]
+    fn write_stats_count_clear_and_ingest() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), default_config()).unwrap();
+        put_keys(&engine, 3);
+
+        engine.clear().unwrap();
+        let stats = engine.write_stats().unwrap();
+        assert_eq!(stats.wal.records_appended, 3);
+        assert_eq!(stats.wal.rotations, 1);
+
+        let pairs = vec![
+            (b"a".to_vec(), b"1".to_vec()),
+            (b"b".to_vec(), b"2".to_vec()),
+        ];
+        assert!(engine.ingest_sorted(&pairs).unwrap());
+        let stats = engine.write_stats().unwrap();
+        assert_eq!(stats.sstables.sstables, 1);
+        assert_eq!(stats.sstables.data_blocks, 1);
+
+        engine.put(b"c".to_vec(), b"3".to_vec()).unwrap();
+        drop(engine);
+        let engine = reopen(dir.path());
+        assert_eq!(engine.write_stats().unwrap(), WriteStats::default());
+        assert_eq!(engine.get(b"c".to_vec()).unwrap(), Some(b"3".to_vec()));
+    }
+
+    /// # Scenario
+    /// Write activity is reported once per change.
+    ///
+    /// # Starting environment
+    /// Empty engine with a 4 KB write buffer.
+    ///
+    /// # Actions
+    /// 1. Take the activity with nothing written.
+    /// 2. Put 2 keys; take the activity twice.
+    /// 3. Put 1 key; take the activity.
+    ///
+    /// # Expected behavior
+    /// Step 1 and the second take of step 2 report nothing. The first
+    /// take of step 2 reports 2 records, step 3 only the 1 since.
+    #[test]
+    fn write_activity_reports_deltas() {
+        let dir = TempDir::new().unwrap();
+        let engine = Engine::open(dir.path(), default_config()).unwrap();
+        assert_eq!(engine.take_write_activity(3).unwrap(), None);
+
+        put_keys(&engine, 2);
+        let event = engine.take_write_activity(3).unwrap().unwrap();
+        assert_eq!(event.partition, 3);
+        assert_eq!(event.stats.wal.records_appended, 2);
+        assert_eq!(engine.take_write_activity(3).unwrap(), None);
+
+        put_keys(&engine, 1);
+        let event = engine.take_write_activity(3).unwrap().unwrap();
+        assert_eq!(event.stats.wal.records_appended, 1);
+        assert_eq!(event.stats.wal.syncs, 1);
+        assert_eq!(engine.write_stats().unwrap().wal.records_appended, 3);
+    }
+}
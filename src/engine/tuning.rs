@@ -0,0 +1,245 @@
+//! Adaptive tuning of size-tiered compaction thresholds.
+//!
+//! With [`DbConfig::adaptive_compaction`](crate::DbConfig::adaptive_compaction)
+//! set, a background controller samples every partition once per
+//! [`AdaptiveCompaction::interval_seconds`] and measures, over the window
+//! since the previous sample:
+//!
+//! - **read amplification** — SSTables probed per point lookup
+//!   (bloom filter checks over lookups), and
+//! - **write amplification** — bytes written by flushes and compactions
+//!   over bytes written by flushes alone.
+//!
+//! When reads probe more SSTables than
+//! [`AdaptiveCompaction::target_read_amplification`], compaction is made
+//! more eager: `min_threshold` drops, `max_threshold` grows, and the
+//! tombstone ratio drops. Otherwise, when compaction rewrites more than
+//! [`AdaptiveCompaction::target_write_amplification`], it is made lazier
+//! by the opposite steps. Every value stays within its configured bounds,
+//! and each change is reported to the
+//! [`EventListener`](crate::EventListener)s as a [`CompactionTuning`].
+//!
+//! Tuned thresholds live in memory only; a reopened database starts again
+//! from the configured ones.
+
+/// Bounds and targets of the adaptive compaction controller.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdaptiveCompaction {
+    /// Seconds between two controller samples.
+    ///
+    /// **Bounds:** 1 ≤ `interval_seconds` ≤ 3 600 (1 hour).
+    ///
+    /// Default: `60`.
+    pub interval_seconds: usize,
+
+    /// SSTables probed per point lookup above which compaction is made
+    /// more eager.
+    ///
+    /// **Bounds:** `target_read_amplification` ≥ 1.0.
+    ///
+    /// Default: `4.0`.
+    pub target_read_amplification: f64,
+
+    /// Bytes written per byte flushed above which compaction is made
+    /// lazier.
+    ///
+    /// **Bounds:** `target_write_amplification` ≥ 1.0.
+    ///
+    /// Default: `10.0`.
+    pub target_write_amplification: f64,
+
+    /// Lowest `min_threshold` the controller may set.
+    ///
+    /// **Bounds:** 2 ≤ `min_threshold_floor` ≤ `min_threshold_ceiling`.
+    ///
+    /// Default: `2`.
+    pub min_threshold_floor: usize,
+
+    /// Highest `min_threshold` the controller may set.
+    ///
+    /// **Bounds:** `min_threshold_floor` ≤ `min_threshold_ceiling` ≤ 64.
+    ///
+    /// Default: `16`.
+    pub min_threshold_ceiling: usize,
+
+    /// Lowest `max_threshold` the controller may set.
+    ///
+    /// **Bounds:** `min_threshold_floor` ≤ `max_threshold_floor` ≤
+    /// `max_threshold_ceiling`.
+    ///
+    /// Default: `8`.
+    pub max_threshold_floor: usize,
+
+    /// Highest `max_threshold` the controller may set.
+    ///
+    /// **Bounds:** `max_threshold_floor` ≤ `max_threshold_ceiling` ≤ 256.
+    ///
+    /// Default: `64`.
+    pub max_threshold_ceiling: usize,
+
+    /// Lowest tombstone ratio threshold the controller may set.
+    ///
+    /// **Bounds:** 0.0 < `tombstone_ratio_floor` ≤ `tombstone_ratio_ceiling`.
+    ///
+    /// Default: `0.1`.
+    pub tombstone_ratio_floor: f64,
+
+    /// Highest tombstone ratio threshold the controller may set.
+    ///
+    /// **Bounds:** `tombstone_ratio_floor` ≤ `tombstone_ratio_ceiling` ≤ 1.0.
+    ///
+    /// Default: `0.8`.
+    pub tombstone_ratio_ceiling: f64,
+}
+
+impl Default for AdaptiveCompaction {
+    fn default() -> Self {
+        Self {
+            interval_seconds: 60,
+            target_read_amplification: 4.0,
+            target_write_amplification: 10.0,
+            min_threshold_floor: 2,
+            min_threshold_ceiling: 16,
+            max_threshold_floor: 8,
+            max_threshold_ceiling: 64,
+            tombstone_ratio_floor: 0.1,
+            tombstone_ratio_ceiling: 0.8,
+        }
+    }
+}
+
+/// Compaction thresholds the controller adjusts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompactionThresholds {
+    /// Minimum bucket size for minor compaction.
+    pub min_threshold: usize,
+
+    /// Maximum SSTables merged by one minor compaction.
+    pub max_threshold: usize,
+
+    /// Minimum tombstone ratio for tombstone compaction.
+    pub tombstone_ratio_threshold: f64,
+}
+
+/// Why the controller changed the thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TuningReason {
+    /// Read amplification was above target; compaction made more eager.
+    ReadAmplification,
+
+    /// Write amplification was above target; compaction made lazier.
+    WriteAmplification,
+}
+
+/// One threshold change, delivered to
+/// [`EventListener::on_compaction_tuned`](crate::EventListener::on_compaction_tuned).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompactionTuning {
+    /// Partition whose thresholds changed.
+    pub partition: usize,
+
+    /// Which target was missed.
+    pub reason: TuningReason,
+
+    /// SSTables probed per lookup over the window, or `None` if there
+    /// were too few lookups to tell.
+    pub read_amplification: Option<f64>,
+
+    /// Bytes written per byte flushed over the window, or `None` if
+    /// nothing was flushed.
+    pub write_amplification: Option<f64>,
+
+    /// Thresholds before the change.
+    pub previous: CompactionThresholds,
+
+    /// Thresholds after the change.
+    pub current: CompactionThresholds,
+}
+
+/// Fewest lookups in a window for its read amplification to count.
+const MIN_WINDOW_GETS: u64 = 64;
+
+/// Tombstone ratio change per step.
+const TOMBSTONE_RATIO_STEP: f64 = 0.05;
+
+/// Cumulative counters of one partition at one sample.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct TuningSample {
+    pub gets: u64,
+    pub bloom_checks: u64,
+    pub bytes_flushed: u64,
+    pub bytes_compacted: u64,
+}
+
+/// Controller state of one partition: the counters at the previous sample.
+#[derive(Debug, Default)]
+pub(crate) struct TuningWindow {
+    last: TuningSample,
+}
+
+impl TuningWindow {
+    /// Closes the window at `sample` and returns the thresholds that
+    /// should replace `current`, or `None` to keep them.
+    pub fn advance(
+        &mut self,
+        partition: usize,
+        sample: TuningSample,
+        current: CompactionThresholds,
+        tuning: &AdaptiveCompaction,
+    ) -> Option<CompactionTuning> {
+        let last = std::mem::replace(&mut self.last, sample);
+        let gets = sample.gets.saturating_sub(last.gets);
+        let checks = sample.bloom_checks.saturating_sub(last.bloom_checks);
+        let flushed = sample.bytes_flushed.saturating_sub(last.bytes_flushed);
+        let compacted = sample.bytes_compacted.saturating_sub(last.bytes_compacted);
+
+        let read_amplification = (gets >= MIN_WINDOW_GETS).then(|| checks as f64 / gets as f64);
+        let write_amplification =
+            (flushed > 0).then(|| (flushed + compacted) as f64 / flushed as f64);
+
+        let (reason, next) = if read_amplification
+            .is_some_and(|amp| amp > tuning.target_read_amplification)
+        {
+            let next = CompactionThresholds {
+                min_threshold: current.min_threshold.saturating_sub(1),
+                max_threshold: current.max_threshold + current.max_threshold / 4 + 1,
+                tombstone_ratio_threshold: current.tombstone_ratio_threshold - TOMBSTONE_RATIO_STEP,
+            };
+            (TuningReason::ReadAmplification, next)
+        } else if write_amplification.is_some_and(|amp| amp > tuning.target_write_amplification) {
+            let next = CompactionThresholds {
+                min_threshold: current.min_threshold + 1,
+                max_threshold: current
+                    .max_threshold
+                    .saturating_sub(current.max_threshold / 4 + 1),
+                tombstone_ratio_threshold: current.tombstone_ratio_threshold + TOMBSTONE_RATIO_STEP,
+            };
+            (TuningReason::WriteAmplification, next)
+        } else {
+            return None;
+        };
+
+        let min_threshold = next
+            .min_threshold
+            .clamp(tuning.min_threshold_floor, tuning.min_threshold_ceiling);
+        let next = CompactionThresholds {
+            min_threshold,
+            max_threshold: next
+                .max_threshold
+                .clamp(tuning.max_threshold_floor, tuning.max_threshold_ceiling)
+                .max(min_threshold),
+            tombstone_ratio_threshold: next
+                .tombstone_ratio_threshold
+                .clamp(tuning.tombstone_ratio_floor, tuning.tombstone_ratio_ceiling),
+        };
+
+        (next != current).then_some(CompactionTuning {
+            partition,
+            reason,
+            read_amplification,
+            write_amplification,
+            previous: current,
+            current: next,
+        })
+    }
+}
@@ -7,8 +7,14 @@
 //!   compaction, scan).
 //! - [`RangeTombstone`] — a versioned range deletion marker shared across
 //!   memtable, SSTable, and compaction subsystems.
+//! - [`PointEntryRef`] and [`AsPointEntry`] — a point mutation borrowed
+//!   from a frozen memtable, written into an SSTable without copying it.
+//! - [`coalesce_range_tombstones`] — merges redundant range tombstones
+//!   before they are written to an SSTable.
 //! - [`MergeIterator`] — a heap-based k-way merge iterator that combines
 //!   multiple sorted record streams into a single globally-sorted stream.
+//! - [`RecordSource`] — a sorted record stream the merge can seek forward
+//!   past keys hidden by a range tombstone.
 
 /// Represents a single item emitted by the storage engine.
 #[derive(Debug, Clone)]
@@ -87,6 +93,16 @@ impl Record {
         }
     }
 
+    /// Returns where this record stands against other versions of its
+    /// key; see [`VersionRank`].
+    pub fn rank(&self) -> VersionRank {
+        VersionRank {
+            lsn: self.lsn(),
+            point: !matches!(self, Record::RangeDelete { .. }),
+            timestamp: self.timestamp(),
+        }
+    }
+
     /// Converts this record into its SSTable-level representation.
     ///
     /// Point puts and point deletes become [`PointEntry`] values;
@@ -172,6 +188,45 @@ impl Ord for Record {
     }
 }
 
+// ------------------------------------------------------------------------------------------------
+// VersionRank — precedence between versions of one key
+// ------------------------------------------------------------------------------------------------
+
+/// Precedence of one version of a key over the others; the greater rank
+/// is the visible version.
+///
+/// Versions compare by LSN first. Two versions can only share an LSN when
+/// the same write reached two layers — a memtable and the SSTable it was
+/// flushed into while both were live, or a WAL replayed over an SSTable
+/// that already holds it. Such ties are broken by:
+///
+/// 1. **Kind** — a put or point delete beats a range tombstone, which only
+///    hides strictly older versions.
+/// 2. **Timestamp** — the later write wins.
+/// 3. **Source** — the newer layer wins: the active memtable, then frozen
+///    memtables newest first, then SSTables by max LSN and id, descending.
+///    The rank itself does not carry the source; readers visit layers in
+///    that order and keep the first of equal ranks.
+///
+/// A range tombstone hides a version of a key it covers exactly when it
+/// outranks it ([`RangeTombstone::hides`](crate::types::RangeTombstone::hides)):
+/// when its LSN is higher. At an equal LSN the point version stays
+/// visible, whatever the timestamps and whichever layers hold the two.
+/// Point lookups, the scan visibility filter, the memtables, the
+/// SSTables, and compaction all apply this one rule, so `get` and `scan`
+/// agree on every key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct VersionRank {
+    /// LSN of the version.
+    pub lsn: u64,
+
+    /// `true` for a put or point delete, `false` for a range tombstone.
+    pub point: bool,
+
+    /// Timestamp of the version.
+    pub timestamp: u64,
+}
+
 /// Compares two records by `(key ASC, LSN DESC)`.
 ///
 /// Equivalent to `a.cmp(b)` via the [`Ord`] implementation on [`Record`].
@@ -229,6 +284,99 @@ impl PointEntry {
             timestamp,
         }
     }
+
+    /// Returns where this entry stands against other versions of its
+    /// key; see [`VersionRank`].
+    pub fn rank(&self) -> VersionRank {
+        VersionRank {
+            lsn: self.lsn,
+            point: true,
+            timestamp: self.timestamp,
+        }
+    }
+}
+
+/// A point mutation borrowed from the memtable that holds it.
+///
+/// A flush of one frozen memtable writes these straight from its tree, so
+/// no key or value is copied on the way to the SSTable writer.
+#[derive(Debug, Clone, Copy)]
+pub struct PointEntryRef<'a> {
+    /// Key of the entry.
+    pub key: &'a [u8],
+
+    /// Value of the entry; `None` indicates a point deletion.
+    pub value: Option<&'a [u8]>,
+
+    /// Log sequence number of this mutation.
+    pub lsn: u64,
+
+    /// Timestamp associated with this mutation.
+    pub timestamp: u64,
+}
+
+/// A point mutation the SSTable writer can encode: an owned
+/// [`PointEntry`] or a borrowed [`PointEntryRef`].
+pub trait AsPointEntry {
+    /// Key of the entry.
+    fn key(&self) -> &[u8];
+
+    /// Value of the entry; `None` indicates a point deletion.
+    fn value(&self) -> Option<&[u8]>;
+
+    /// Log sequence number of this mutation.
+    fn lsn(&self) -> u64;
+
+    /// Timestamp associated with this mutation.
+    fn timestamp(&self) -> u64;
+
+    /// Consumes the entry and returns its key: moved out of an owned
+    /// entry, copied from a borrowed one.
+    fn into_key(self) -> Vec<u8>;
+}
+
+impl AsPointEntry for PointEntry {
+    fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    fn value(&self) -> Option<&[u8]> {
+        self.value.as_deref()
+    }
+
+    fn lsn(&self) -> u64 {
+        self.lsn
+    }
+
+    fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    fn into_key(self) -> Vec<u8> {
+        self.key
+    }
+}
+
+impl AsPointEntry for PointEntryRef<'_> {
+    fn key(&self) -> &[u8] {
+        self.key
+    }
+
+    fn value(&self) -> Option<&[u8]> {
+        self.value
+    }
+
+    fn lsn(&self) -> u64 {
+        self.lsn
+    }
+
+    fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    fn into_key(self) -> Vec<u8> {
+        self.key.to_vec()
+    }
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -240,6 +388,11 @@ impl PointEntry {
 /// Range tombstones are versioned via LSN and may overlap. During reads,
 /// the highest-LSN tombstone covering a key takes precedence.
 ///
+/// An empty `end` is the unbounded sentinel: the tombstone deletes every
+/// key from `start` on. Keys are never empty, so no bounded range can
+/// end there. Compare ends with [`below_end`] and [`cmp_ends`] rather
+/// than as plain byte strings.
+///
 /// This type is shared across the memtable, SSTable, and compaction
 /// subsystems.
 #[derive(Clone, Debug)]
@@ -247,7 +400,7 @@ pub struct RangeTombstone {
     /// Inclusive start key of the deleted range.
     pub start: Vec<u8>,
 
-    /// Exclusive end key of the deleted range.
+    /// Exclusive end key of the deleted range; empty if unbounded.
     pub end: Vec<u8>,
 
     /// Log Sequence Number of this tombstone.
@@ -272,6 +425,125 @@ impl RangeTombstone {
             timestamp,
         }
     }
+
+    /// Returns `true` if the tombstone covers `key`.
+    pub fn covers(&self, key: &[u8]) -> bool {
+        self.start.as_slice() <= key && below_end(key, &self.end)
+    }
+
+    /// Returns where this tombstone stands against the versions of the
+    /// keys it covers; see [`VersionRank`].
+    pub fn rank(&self) -> VersionRank {
+        VersionRank {
+            lsn: self.lsn,
+            point: false,
+            timestamp: self.timestamp,
+        }
+    }
+
+    /// Returns `true` if the tombstone hides the version of `key` ranked
+    /// `version`: it covers the key and outranks the version, i.e. its LSN
+    /// is higher. A point version of an equal LSN is never hidden.
+    pub fn hides(&self, key: &[u8], version: VersionRank) -> bool {
+        self.covers(key) && self.rank() > version
+    }
+}
+
+/// Returns `true` if `key` lies below the exclusive range end `end`. An
+/// empty `end` is unbounded, so every key lies below it.
+pub fn below_end(key: &[u8], end: &[u8]) -> bool {
+    end.is_empty() || key < end
+}
+
+/// Orders two exclusive range ends, the unbounded (empty) end above every
+/// other.
+pub fn cmp_ends(a: &[u8], b: &[u8]) -> Ordering {
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.cmp(b),
+    }
+}
+
+/// Merges overlapping or adjacent range tombstones that can be replaced by
+/// one without changing which versions they delete.
+///
+/// `tombstones` must be sorted by start key (ties in any order); the
+/// result is sorted the same way. Each tombstone is compared with the
+/// last one kept:
+///
+/// - **Dominated** — it lies inside the kept tombstone and has an equal
+///   or lower LSN, so every version it deletes is already deleted: it is
+///   dropped.
+/// - **Equal LSN** — the two ranges overlap or touch: they are merged into
+///   their union.
+/// - **Bridged** — only when `points` is given: the ranges overlap or
+///   touch and no entry in `points` falls inside their union with an LSN
+///   between the two tombstones' LSNs. They are merged into the union with
+///   the higher LSN.
+///
+/// Bridging is only sound when `points` holds every version that may have
+/// an LSN in that window — true for a memtable flush, where older data sits
+/// below the flushed LSNs and newer data above them, but not for a
+/// compaction of a subset of SSTables. `points` must be sorted by key.
+///
+/// A merged tombstone keeps the highest timestamp, so it never becomes
+/// eligible for GC earlier than its parts.
+pub fn coalesce_range_tombstones(
+    tombstones: Vec<RangeTombstone>,
+    points: Option<&[PointEntry]>,
+) -> Vec<RangeTombstone> {
+    coalesce_range_tombstones_over(tombstones, points)
+}
+
+/// [`coalesce_range_tombstones`] over point entries of any kind, owned or
+/// borrowed.
+pub fn coalesce_range_tombstones_over<P: AsPointEntry>(
+    tombstones: Vec<RangeTombstone>,
+    points: Option<&[P]>,
+) -> Vec<RangeTombstone> {
+    let mut out: Vec<RangeTombstone> = Vec::with_capacity(tombstones.len());
+
+    for rt in tombstones {
+        let Some(last) = out.last_mut() else {
+            out.push(rt);
+            continue;
+        };
+        if cmp_ends(&rt.start, &last.end) == Ordering::Greater {
+            out.push(rt);
+            continue;
+        }
+
+        if cmp_ends(&rt.end, &last.end) != Ordering::Greater && rt.lsn <= last.lsn {
+            continue;
+        }
+
+        let mergeable = rt.lsn == last.lsn
+            || points.is_some_and(|points| {
+                let (lo, hi) = (rt.lsn.min(last.lsn), rt.lsn.max(last.lsn));
+                let end = std::cmp::max_by(rt.end.as_slice(), last.end.as_slice(), |a, b| {
+                    cmp_ends(a, b)
+                });
+                let from = points.partition_point(|p| p.key() < last.start.as_slice());
+                points[from..]
+                    .iter()
+                    .take_while(|p| below_end(p.key(), end))
+                    .all(|p| p.lsn() < lo || p.lsn() >= hi)
+            });
+
+        if mergeable {
+            if cmp_ends(&rt.end, &last.end) == Ordering::Greater {
+                last.end = rt.end;
+            }
+            last.lsn = last.lsn.max(rt.lsn);
+            last.timestamp = last.timestamp.max(rt.timestamp);
+        } else {
+            out.push(rt);
+        }
+    }
+
+    out
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -281,15 +553,110 @@ impl RangeTombstone {
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 
+/// A sorted `(key ASC, LSN DESC)` record stream that can skip ahead.
+///
+/// Lets [`MergeIterator::skipping_covered`] jump over entries hidden by a
+/// range tombstone instead of decoding them one by one.
+pub trait RecordSource: Iterator<Item = Record> {
+    /// Upper bound on the LSN of every record the source yields.
+    fn max_lsn(&self) -> u64;
+
+    /// Skips the point records (puts and deletes) with keys below `key`
+    /// that the source has not yielded yet; an empty `key` is unbounded,
+    /// as a range tombstone end, and skips them all. Range tombstones are
+    /// still yielded. Never moves backwards.
+    fn seek_points(&mut self, key: &[u8]);
+
+    /// Lower bound on the key of every record the source yields, if
+    /// known. The merge pulls nothing from the source until its output
+    /// reaches this key, so a source that sets itself up on the first
+    /// [`next`](Iterator::next) costs nothing if the merge stops earlier.
+    fn lower_bound(&self) -> Option<&[u8]> {
+        None
+    }
+}
+
+/// A source that cannot seek; its records are merged one by one.
+pub struct Unseekable<I>(pub I);
+
+impl<I: Iterator<Item = Record>> Iterator for Unseekable<I> {
+    type Item = Record;
+
+    fn next(&mut self) -> Option<Record> {
+        self.0.next()
+    }
+}
+
+impl<I: Iterator<Item = Record>> RecordSource for Unseekable<I> {
+    fn max_lsn(&self) -> u64 {
+        u64::MAX
+    }
+
+    fn seek_points(&mut self, _key: &[u8]) {}
+}
+
+/// A source that hides the records of `inner` with an LSN above `max_lsn`,
+/// for reads at a fixed point in the write history.
+pub struct LsnBound<S> {
+    inner: S,
+    max_lsn: u64,
+}
+
+impl<S> LsnBound<S> {
+    /// Wraps `inner`, hiding its records above `max_lsn`.
+    pub fn new(inner: S, max_lsn: u64) -> Self {
+        Self { inner, max_lsn }
+    }
+}
+
+impl<S: RecordSource> Iterator for LsnBound<S> {
+    type Item = Record;
+
+    fn next(&mut self) -> Option<Record> {
+        let max_lsn = self.max_lsn;
+        self.inner.find(|record| record.lsn() <= max_lsn)
+    }
+}
+
+impl<S: RecordSource> RecordSource for LsnBound<S> {
+    fn max_lsn(&self) -> u64 {
+        self.inner.max_lsn().min(self.max_lsn)
+    }
+
+    fn seek_points(&mut self, key: &[u8]) {
+        self.inner.seek_points(key);
+    }
+
+    fn lower_bound(&self) -> Option<&[u8]> {
+        self.inner.lower_bound()
+    }
+}
+
 /// A heap-based merge iterator that yields [`Record`]s from multiple
 /// sorted sources in `(key ASC, LSN DESC)` order.
 ///
+/// Records sharing a key and LSN are yielded by descending
+/// [`VersionRank`], and records of equal rank in source order, so sources
+/// must be passed newest first. A consumer keeping the first record of
+/// each key thus resolves duplicates the same way as a point lookup.
+///
+/// Sources with a [`lower_bound`](RecordSource::lower_bound) join the
+/// merge only once the smallest pending record reaches it.
+///
 /// Used by both the engine scan path and the compaction module.
 /// The lifetime `'a` bounds any borrowed state inside the source
-/// iterators; pass `'static` when the sources own their data.
+/// iterators; pass `'static` when the sources own their data. Sources
+/// must be `Send`, so a merge over owned sources can be handed to another
+/// thread.
 pub struct MergeIterator<'a> {
-    iters: Vec<Box<dyn Iterator<Item = Record> + 'a>>,
+    iters: Vec<Box<dyn RecordSource + Send + 'a>>,
     heap: BinaryHeap<MergeHeapEntry<'a>>,
+
+    /// Sources not pulled from yet, by descending lower bound.
+    pending: Vec<usize>,
+
+    /// Whether a yielded range tombstone makes older sources seek past it.
+    skip_covered: bool,
 }
 
 struct MergeHeapEntry<'a> {
@@ -302,8 +669,14 @@ struct MergeHeapEntry<'a> {
 
 impl Ord for MergeHeapEntry<'_> {
     fn cmp(&self, other: &Self) -> Ordering {
-        // Min-heap: reverse so smallest key / highest LSN pops first.
-        self.record.cmp(&other.record).reverse()
+        // Min-heap: reverse so smallest key / highest rank pops first, and
+        // of two equally ranked versions the one from the earlier source.
+        self.record
+            .key()
+            .cmp(other.record.key())
+            .then_with(|| other.record.rank().cmp(&self.record.rank()))
+            .then_with(|| self.source_idx.cmp(&other.source_idx))
+            .reverse()
     }
 }
 
@@ -315,18 +688,45 @@ impl PartialOrd for MergeHeapEntry<'_> {
 
 impl PartialEq for MergeHeapEntry<'_> {
     fn eq(&self, other: &Self) -> bool {
-        self.record == other.record
+        self.cmp(other) == Ordering::Equal
     }
 }
 
 impl Eq for MergeHeapEntry<'_> {}
 
 impl<'a> MergeIterator<'a> {
-    pub fn new(mut iters: Vec<Box<dyn Iterator<Item = Record> + 'a>>) -> Self {
+    /// Merges `iters`, yielding every record of every source.
+    pub fn new(iters: Vec<Box<dyn Iterator<Item = Record> + Send + 'a>>) -> Self {
+        let sources = iters
+            .into_iter()
+            .map(|iter| Box::new(Unseekable(iter)) as Box<dyn RecordSource + Send + 'a>)
+            .collect();
+        Self::from_sources(sources, false)
+    }
+
+    /// Merges `sources`, dropping point records hidden by a range tombstone
+    /// from a newer source.
+    ///
+    /// When a range tombstone `[start, end)` at LSN `L` is yielded, every
+    /// source whose [`max_lsn`](RecordSource::max_lsn) is below `L` seeks
+    /// to `end`: all its points in the range are older than the tombstone,
+    /// so a visibility filter would discard them anyway. Sources that may
+    /// hold newer versions are merged as usual. Range tombstones are never
+    /// dropped, so the output stays valid input for
+    /// [`VisibilityFilter`](super::VisibilityFilter); it is not suitable
+    /// for compaction, which must see every record.
+    pub fn skipping_covered(sources: Vec<Box<dyn RecordSource + Send + 'a>>) -> Self {
+        Self::from_sources(sources, true)
+    }
+
+    fn from_sources(mut iters: Vec<Box<dyn RecordSource + Send + 'a>>, skip_covered: bool) -> Self {
         let mut heap = BinaryHeap::new();
+        let mut pending = Vec::new();
 
         for (idx, iter) in iters.iter_mut().enumerate() {
-            if let Some(record) = iter.next() {
+            if iter.lower_bound().is_some() {
+                pending.push(idx);
+            } else if let Some(record) = iter.next() {
                 heap.push(MergeHeapEntry {
                     record,
                     source_idx: idx,
@@ -334,8 +734,70 @@ impl<'a> MergeIterator<'a> {
                 });
             }
         }
+        pending.sort_by(|&a, &b| iters[b].lower_bound().cmp(&iters[a].lower_bound()));
+
+        Self {
+            iters,
+            heap,
+            pending,
+            skip_covered,
+        }
+    }
+
+    /// Pulls the first record of every pending source whose lower bound
+    /// the smallest record in the heap has reached.
+    fn join_pending(&mut self) {
+        while let Some(&idx) = self.pending.last() {
+            let bound = self.iters[idx].lower_bound().unwrap_or_default();
+            if self.heap.peek().is_some_and(|top| top.record.key() < bound) {
+                return;
+            }
+            self.pending.pop();
+            if let Some(record) = self.iters[idx].next() {
+                self.heap.push(MergeHeapEntry {
+                    record,
+                    source_idx: idx,
+                    _marker: std::marker::PhantomData,
+                });
+            }
+        }
+    }
+
+    /// Makes every source older than `lsn` skip its points below `end`,
+    /// including the one waiting in the heap.
+    fn skip_covered_by(&mut self, end: &[u8], lsn: u64) {
+        let stale: Vec<usize> = (0..self.iters.len())
+            .filter(|&idx| self.iters[idx].max_lsn() < lsn)
+            .collect();
+        if stale.is_empty() {
+            return;
+        }
+
+        let mut entries = std::mem::take(&mut self.heap).into_vec();
+        let mut refill = Vec::new();
+        entries.retain(|entry| {
+            let covered = stale.contains(&entry.source_idx)
+                && !matches!(entry.record, Record::RangeDelete { .. })
+                && below_end(entry.record.key(), end);
+            if covered {
+                refill.push(entry.source_idx);
+            }
+            !covered
+        });
 
-        Self { iters, heap }
+        for &idx in &stale {
+            self.iters[idx].seek_points(end);
+        }
+        for idx in refill {
+            if let Some(record) = self.iters[idx].next() {
+                entries.push(MergeHeapEntry {
+                    record,
+                    source_idx: idx,
+                    _marker: std::marker::PhantomData,
+                });
+            }
+        }
+        self.heap = BinaryHeap::from(entries);
     }
 }
 
@@ -343,6 +805,7 @@ impl Iterator for MergeIterator<'_> {
     type Item = Record;
 
     fn next(&mut self) -> Option<Self::Item> {
+        self.join_pending();
         let entry = self.heap.pop()?;
         let result = entry.record;
         let idx = entry.source_idx;
@@ -355,6 +818,12 @@ impl Iterator for MergeIterator<'_> {
             });
         }
 
+        if self.skip_covered
+            && let Record::RangeDelete { end, lsn, .. } = &result
+        {
+            self.skip_covered_by(end, *lsn);
+        }
+
         Some(result)
     }
 }
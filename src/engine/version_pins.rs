@@ -0,0 +1,69 @@
+//! LSNs whose view of the data must survive flushes and compactions.
+//!
+//! A reader pinned at LSN `P` sees, for every key, the newest version at
+//! or below `P`. While any pin is held, the oldest one is the **version
+//! horizon**: flushes and compactions keep every version of a key down to
+//! and including the newest one at or below the horizon, and every
+//! tombstone above it. Only the versions below that are garbage. With no
+//! pin held the horizon is `u64::MAX`, and only the newest version of each
+//! key is kept, as before pins existed.
+//!
+//! Pins live in memory only; a pin does not survive a reopen.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+/// The version horizon when no pin is held.
+pub(crate) const NO_HORIZON: u64 = u64::MAX;
+
+/// Pinned LSNs of one engine, each with its number of holders.
+#[derive(Debug, Default)]
+pub(crate) struct VersionPins {
+    pins: Mutex<BTreeMap<u64, usize>>,
+}
+
+impl VersionPins {
+    /// Pins `lsn` until the returned guard is dropped.
+    pub fn pin(self: &Arc<Self>, lsn: u64) -> VersionPin {
+        *self.pins.lock().unwrap().entry(lsn).or_default() += 1;
+        VersionPin {
+            lsn,
+            pins: Arc::clone(self),
+        }
+    }
+
+    /// Returns the oldest pinned LSN, or [`NO_HORIZON`] if none is pinned.
+    pub fn horizon(&self) -> u64 {
+        self.pins
+            .lock()
+            .unwrap()
+            .keys()
+            .next()
+            .copied()
+            .unwrap_or(NO_HORIZON)
+    }
+
+    fn release(&self, lsn: u64) {
+        let mut pins = self.pins.lock().unwrap();
+        if let Some(holders) = pins.get_mut(&lsn) {
+            *holders -= 1;
+            if *holders == 0 {
+                pins.remove(&lsn);
+            }
+        }
+    }
+}
+
+/// Keeps the versions visible at its LSN from being dropped by flushes
+/// and compactions, until dropped.
+#[derive(Debug)]
+pub(crate) struct VersionPin {
+    lsn: u64,
+    pins: Arc<VersionPins>,
+}
+
+impl Drop for VersionPin {
+    fn drop(&mut self) {
+        self.pins.release(self.lsn);
+    }
+}
@@ -11,10 +11,15 @@ use super::{RangeTombstone, Record};
 /// Applies point tombstone and range tombstone semantics:
 /// - A `Delete` record suppresses the same key in later (lower-LSN) records.
 /// - A `RangeDelete` suppresses any `Put` whose key falls within `[start, end)`
-///   and whose LSN is lower than the tombstone's LSN.
+///   and which it outranks, i.e. whose LSN is lower than the tombstone's
+///   ([`RangeTombstone::hides`]); a `Put` of an equal LSN stays visible,
+///   as it does for a point lookup.
 ///
 /// The input iterator **must** be sorted by `(key ASC, LSN DESC)` — the order
 /// produced by [`MergeIterator`](super::utils::MergeIterator).
+///
+/// Built with [`since`](Self::since), it additionally drops keys whose
+/// visible version was written before a timestamp.
 pub struct VisibilityFilter<I>
 where
     I: Iterator<Item = Record>,
@@ -25,6 +30,8 @@ where
     current_key: Option<Vec<u8>>,
     /// Accumulated range tombstones that may cover upcoming keys.
     active_ranges: Vec<RangeTombstone>,
+    /// Visible versions older than this timestamp are not yielded.
+    min_timestamp: u64,
 }
 
 impl<I> VisibilityFilter<I>
@@ -32,10 +39,17 @@ where
     I: Iterator<Item = Record>,
 {
     pub fn new(input: I) -> Self {
+        Self::since(input, 0)
+    }
+
+    /// Like [`new`](Self::new), but yields only keys whose visible version
+    /// has a timestamp of at least `min_timestamp`.
+    pub fn since(input: I, min_timestamp: u64) -> Self {
         Self {
             input,
             current_key: None,
             active_ranges: Vec::new(),
+            min_timestamp,
         }
     }
 }
@@ -48,6 +62,7 @@ where
 
     fn next(&mut self) -> Option<Self::Item> {
         for record in self.input.by_ref() {
+            let rank = record.rank();
             match record {
                 Record::RangeDelete {
                     start,
@@ -69,7 +84,10 @@ where
                 }
 
                 Record::Put {
-                    key, value, lsn, ..
+                    key,
+                    value,
+                    timestamp,
+                    ..
                 } => {
                     // Skip if we've already handled this key
                     if self.current_key.as_deref() == Some(&key) {
@@ -77,17 +95,16 @@ where
                     }
 
                     // Check range tombstones
-                    let deleted = self.active_ranges.iter().any(|r| {
-                        r.start.as_slice() <= key.as_slice()
-                            && key.as_slice() < r.end.as_slice()
-                            && r.lsn > lsn
-                    });
+                    let deleted = self.active_ranges.iter().any(|r| r.hides(&key, rank));
 
                     self.current_key = Some(key.clone());
 
                     if deleted {
                         continue; // This record is shadowed by a range tombstone
                     }
+                    if timestamp < self.min_timestamp {
+                        continue; // Visible, but written before the cut-off
+                    }
 
                     return Some((key, value));
                 }
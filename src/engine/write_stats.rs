@@ -0,0 +1,78 @@
+//! Counters of the write pipeline, from WAL append to SSTable file.
+//!
+//! Every [`Engine`](super::Engine) shares one set of
+//! [`WalCounters`](crate::wal::WalCounters) between the WALs of all its
+//! memtables, counting appends, fsyncs, and segment rotations, and sums
+//! the [`SstBuildStats`] of every SSTable its flushes, ingestions, and
+//! compactions build. Both are snapshotted together as [`WriteStats`].
+//!
+//! A [`WriteActivityLog`] remembers the snapshot last reported to the
+//! event listeners, so each [`WriteActivity`] event carries only what was
+//! written since the one before.
+
+use std::ops::AddAssign;
+use std::sync::Mutex;
+
+use crate::sstable::SstBuildStats;
+use crate::wal::WalStats;
+
+/// Snapshot of the write pipeline counters.
+///
+/// Returned per database by [`Db::write_stats`](crate::Db::write_stats).
+/// Counters start at zero when the database is opened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WriteStats {
+    /// Appends, fsyncs, and rotations of the memtable WALs.
+    pub wal: WalStats,
+
+    /// SSTables built by flushes, ingestions, and compactions.
+    pub sstables: SstBuildStats,
+}
+
+impl WriteStats {
+    /// Counters accumulated since `earlier`, a snapshot of the same
+    /// counters.
+    fn since(&self, earlier: &Self) -> Self {
+        Self {
+            wal: self.wal.since(&earlier.wal),
+            sstables: self.sstables.since(&earlier.sstables),
+        }
+    }
+}
+
+impl AddAssign for WriteStats {
+    fn add_assign(&mut self, other: Self) {
+        self.wal += other.wal;
+        self.sstables += other.sstables;
+    }
+}
+
+/// What one partition wrote since its previous event, delivered about
+/// once a second to
+/// [`EventListener::on_write_activity`](crate::EventListener::on_write_activity).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriteActivity {
+    /// Partition written to.
+    pub partition: usize,
+
+    /// Counters accumulated since the previous event of the partition,
+    /// or since open for the first.
+    pub stats: WriteStats,
+}
+
+/// The write counters of one engine as last reported.
+#[derive(Debug, Default)]
+pub(crate) struct WriteActivityLog {
+    reported: Mutex<WriteStats>,
+}
+
+impl WriteActivityLog {
+    /// Records `current` as reported and returns what changed since the
+    /// previous report, or `None` if nothing was written.
+    pub(crate) fn take(&self, current: WriteStats) -> Option<WriteStats> {
+        let mut reported = self.reported.lock().unwrap();
+        let delta = current.since(&reported);
+        *reported = current;
+        (delta != WriteStats::default()).then_some(delta)
+    }
+}
@@ -0,0 +1,260 @@
+//! Streaming export of a key range for
+//! [`Db::export_range_to_writer`](crate::Db::export_range_to_writer).
+//!
+//! The range is scanned on a background worker from one snapshot per
+//! partition — the same view a [`Db::scan`](crate::Db::scan) started at
+//! that moment would see — and encoded in the chosen [`ExportFormat`].
+//! Encoded chunks travel over a bounded channel to the calling thread,
+//! which writes them out, so memory stays bounded however large the
+//! range. An optional byte rate caps how fast the worker reads.
+
+use std::io::Write;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::DbError;
+
+/// Bytes encoded before a chunk is handed to the writing thread.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Chunks that may wait in the channel for the writing thread.
+const CHUNKS_IN_FLIGHT: usize = 4;
+
+/// Output format of [`Db::export_range_to_writer`](crate::Db::export_range_to_writer).
+///
+/// The text formats need keys and values to be valid UTF-8; the export
+/// stops with [`DbError::InvalidArgument`] at the first pair that is not,
+/// after everything before it has been written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// For each pair, `[u32 LE key_len][key][u32 LE value_len][value]`,
+    /// with no header or trailer. Handles arbitrary bytes.
+    #[default]
+    LengthPrefixed,
+
+    /// A `key,value` header line, then one line per pair. Fields
+    /// containing `,`, `"`, CR, or LF are quoted, with `"` doubled
+    /// (RFC 4180). Lines end with LF.
+    Csv,
+
+    /// One `{"key":…,"value":…}` object per line, both JSON strings.
+    JsonLines,
+}
+
+/// Options for [`Db::export_range_to_writer`](crate::Db::export_range_to_writer).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// Encoding of the exported pairs.
+    pub format: ExportFormat,
+
+    /// Upper bound on the encoded bytes produced per second, so a nightly
+    /// export does not starve foreground reads of disk bandwidth. `0`
+    /// sets no limit.
+    pub max_bytes_per_sec: u64,
+
+    /// Verify the CRC32 of every SSTable data block read.
+    pub verify_checksums: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::default(),
+            max_bytes_per_sec: 0,
+            verify_checksums: true,
+        }
+    }
+}
+
+/// Summary returned by a completed export.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportReport {
+    /// Key-value pairs written.
+    pub records: u64,
+
+    /// Bytes written, headers included.
+    pub bytes: u64,
+
+    /// Wall-clock time from the start of the scan to the last write.
+    pub duration: Duration,
+}
+
+/// Message from the exporting worker to the writing thread.
+pub(crate) enum ExportMessage {
+    Chunk(Vec<u8>),
+    Done(ExportReport),
+    Failed(DbError),
+}
+
+/// Encodes pairs into chunks and sends them, paced to the byte rate.
+pub(crate) struct ExportEncoder {
+    format: ExportFormat,
+    max_bytes_per_sec: u64,
+    sender: crossbeam::channel::Sender<ExportMessage>,
+    buf: Vec<u8>,
+    started: Instant,
+    report: ExportReport,
+}
+
+impl ExportEncoder {
+    pub(crate) fn new(
+        options: &ExportOptions,
+        sender: crossbeam::channel::Sender<ExportMessage>,
+    ) -> Self {
+        let mut buf = Vec::with_capacity(CHUNK_SIZE);
+        if options.format == ExportFormat::Csv {
+            buf.extend_from_slice(b"key,value\n");
+        }
+        Self {
+            format: options.format,
+            max_bytes_per_sec: options.max_bytes_per_sec,
+            sender,
+            buf,
+            started: Instant::now(),
+            report: ExportReport::default(),
+        }
+    }
+
+    /// Encodes every pair of `pairs`, then reports the outcome. Stops
+    /// early if the writing thread hung up.
+    pub(crate) fn run(mut self, pairs: impl Iterator<Item = (Vec<u8>, Vec<u8>)>) {
+        for (key, value) in pairs {
+            if let Err(e) = self.encode(&key, &value) {
+                // Hand over the pairs before the failing one first.
+                if self.buf.is_empty() || self.send_chunk() {
+                    let _ = self.sender.send(ExportMessage::Failed(e));
+                }
+                return;
+            }
+            self.report.records += 1;
+            if self.buf.len() >= CHUNK_SIZE && !self.send_chunk() {
+                return;
+            }
+        }
+        if !self.buf.is_empty() && !self.send_chunk() {
+            return;
+        }
+        self.report.duration = self.started.elapsed();
+        let _ = self.sender.send(ExportMessage::Done(self.report));
+    }
+
+    fn encode(&mut self, key: &[u8], value: &[u8]) -> Result<(), DbError> {
+        match self.format {
+            ExportFormat::LengthPrefixed => {
+                // Stored keys and values fit the encoding's 256 MiB limit.
+                for field in [key, value] {
+                    self.buf
+                        .extend_from_slice(&(field.len() as u32).to_le_bytes());
+                    self.buf.extend_from_slice(field);
+                }
+            }
+            ExportFormat::Csv => {
+                let (key, value) = (utf8(key, key)?, utf8(value, key)?);
+                write_csv_field(&mut self.buf, key);
+                self.buf.push(b',');
+                write_csv_field(&mut self.buf, value);
+                self.buf.push(b'\n');
+            }
+            ExportFormat::JsonLines => {
+                let (key, value) = (utf8(key, key)?, utf8(value, key)?);
+                self.buf.extend_from_slice(b"{\"key\":");
+                write_json_string(&mut self.buf, key);
+                self.buf.extend_from_slice(b",\"value\":");
+                write_json_string(&mut self.buf, value);
+                self.buf.extend_from_slice(b"}\n");
+            }
+        }
+        Ok(())
+    }
+
+    /// Waits out the byte rate, then sends the buffered chunk. Returns
+    /// `false` if the writing thread hung up.
+    fn send_chunk(&mut self) -> bool {
+        let chunk = std::mem::replace(&mut self.buf, Vec::with_capacity(CHUNK_SIZE));
+        self.report.bytes += chunk.len() as u64;
+        if self.max_bytes_per_sec > 0 {
+            let due =
+                Duration::from_secs_f64(self.report.bytes as f64 / self.max_bytes_per_sec as f64);
+            if let Some(wait) = due.checked_sub(self.started.elapsed()) {
+                thread::sleep(wait);
+            }
+        }
+        self.sender.send(ExportMessage::Chunk(chunk)).is_ok()
+    }
+}
+
+/// Creates the channel between the exporting worker and the writing
+/// thread.
+pub(crate) fn channel() -> (
+    crossbeam::channel::Sender<ExportMessage>,
+    crossbeam::channel::Receiver<ExportMessage>,
+) {
+    crossbeam::channel::bounded(CHUNKS_IN_FLIGHT)
+}
+
+/// Writes the chunks received from the worker to `writer` until the
+/// export completes.
+pub(crate) fn drain(
+    receiver: crossbeam::channel::Receiver<ExportMessage>,
+    writer: &mut impl Write,
+) -> Result<ExportReport, DbError> {
+    for message in receiver {
+        match message {
+            ExportMessage::Chunk(chunk) => writer
+                .write_all(&chunk)
+                .map_err(|e| DbError::Engine(e.into()))?,
+            ExportMessage::Done(report) => {
+                writer.flush().map_err(|e| DbError::Engine(e.into()))?;
+                return Ok(report);
+            }
+            ExportMessage::Failed(e) => {
+                writer.flush().map_err(|e| DbError::Engine(e.into()))?;
+                return Err(e);
+            }
+        }
+    }
+    Err(DbError::Engine(crate::engine::EngineError::Internal(
+        "export task ended without a result".into(),
+    )))
+}
+
+/// Returns `bytes` as UTF-8, or an error naming the pair's `key`.
+fn utf8<'a>(bytes: &'a [u8], key: &[u8]) -> Result<&'a str, DbError> {
+    std::str::from_utf8(bytes).map_err(|_| {
+        let hex: String = key.iter().map(|b| format!("{b:02x}")).collect();
+        DbError::InvalidArgument(format!(
+            "pair with key 0x{hex} is not valid UTF-8; use ExportFormat::LengthPrefixed"
+        ))
+    })
+}
+
+fn write_csv_field(buf: &mut Vec<u8>, field: &str) {
+    if field.contains([',', '"', '\r', '\n']) {
+        buf.push(b'"');
+        buf.extend_from_slice(field.replace('"', "\"\"").as_bytes());
+        buf.push(b'"');
+    } else {
+        buf.extend_from_slice(field.as_bytes());
+    }
+}
+
+fn write_json_string(buf: &mut Vec<u8>, s: &str) {
+    buf.push(b'"');
+    for c in s.chars() {
+        match c {
+            '"' => buf.extend_from_slice(b"\\\""),
+            '\\' => buf.extend_from_slice(b"\\\\"),
+            '\n' => buf.extend_from_slice(b"\\n"),
+            '\r' => buf.extend_from_slice(b"\\r"),
+            '\t' => buf.extend_from_slice(b"\\t"),
+            c if (c as u32) < 0x20 => {
+                buf.extend_from_slice(format!("\\u{:04x}", c as u32).as_bytes());
+            }
+            c => {
+                let mut utf8 = [0; 4];
+                buf.extend_from_slice(c.encode_utf8(&mut utf8).as_bytes());
+            }
+        }
+    }
+    buf.push(b'"');
+}
@@ -0,0 +1,191 @@
+//! Failpoints for deterministic crash injection (feature `failpoints`).
+//!
+//! Background work commits its results in several steps — an SSTable is
+//! written before the manifest lists it, a manifest snapshot is written
+//! before it replaces the previous one — and recovery must cope with a
+//! crash between any two of them. Each such commit point carries a named
+//! failpoint. Arming one with [`set`] makes the operation stop there:
+//! everything before the point is on disk, nothing after it is.
+//!
+//! ```rust,no_run
+//! use aeternusdb::failpoints::{self, FailAction};
+//! use aeternusdb::{Db, DbConfig};
+//!
+//! let db = Db::open("/tmp/fp_db", DbConfig::default()).unwrap();
+//! failpoints::set(failpoints::CHECKPOINT_BEFORE_RENAME, FailAction::Error);
+//! assert!(db.close().is_err()); // the shutdown checkpoint stops halfway
+//! failpoints::clear();
+//!
+//! let db = Db::open("/tmp/fp_db", DbConfig::default()).unwrap();
+//! ```
+//!
+//! With [`FailAction::Error`] the operation returns an I/O error and the
+//! handle must be treated as crashed: drop it and reopen the directory.
+//! Failpoints are process-wide, so tests arming them should not run in
+//! parallel with other tests of the same process.
+//!
+//! Without the feature the failpoints compile to nothing.
+//!
+//! ## Unsynced renames
+//! The feature also journals every rename whose parent directory has not
+//! been synced since. [`lose_unsynced_renames`] undoes them, as a power cut
+//! would before the directory reached disk, so a test can check that every
+//! file the engine relies on was made durable before it was relied on.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+/// Flush: the SSTable is written and renamed into place, but the
+/// manifest does not list it yet.
+pub const FLUSH_BEFORE_MANIFEST: &str = "flush::before_manifest";
+
+/// Flush: the manifest lists the new SSTable, but still lists the frozen
+/// WALs it was built from.
+pub const FLUSH_BEFORE_WAL_RELEASE: &str = "flush::before_wal_release";
+
+/// Compaction: the merged SSTable is written, but the manifest still
+/// lists the inputs only.
+pub const COMPACTION_BEFORE_MANIFEST: &str = "compaction::before_manifest";
+
+/// Compaction: the manifest lists the merged SSTable, but the input
+/// files have not been deleted.
+pub const COMPACTION_BEFORE_CLEANUP: &str = "compaction::before_cleanup";
+
+/// Manifest checkpoint: the new snapshot is written to its temporary
+/// file, but has not replaced the previous snapshot.
+pub const CHECKPOINT_BEFORE_RENAME: &str = "manifest::checkpoint_before_rename";
+
+/// Manifest checkpoint: the new snapshot is in place, but the manifest
+/// log it covers has not been truncated.
+pub const CHECKPOINT_BEFORE_TRUNCATE: &str = "manifest::checkpoint_before_truncate";
+
+/// WAL rotation on memtable freeze: the next WAL file is created, but the
+/// manifest still names the previous one as active.
+pub const WAL_ROTATE_BEFORE_MANIFEST: &str = "wal::rotate_before_manifest";
+
+/// Any durable rename (SSTable install, manifest snapshot install): the
+/// file has its new name, but the directory holding it is not synced.
+pub const RENAME_BEFORE_DIR_SYNC: &str = "durability::rename_before_dir_sync";
+
+/// Every failpoint, in pipeline order.
+pub const ALL: &[&str] = &[
+    WAL_ROTATE_BEFORE_MANIFEST,
+    FLUSH_BEFORE_MANIFEST,
+    FLUSH_BEFORE_WAL_RELEASE,
+    COMPACTION_BEFORE_MANIFEST,
+    COMPACTION_BEFORE_CLEANUP,
+    CHECKPOINT_BEFORE_RENAME,
+    CHECKPOINT_BEFORE_TRUNCATE,
+    RENAME_BEFORE_DIR_SYNC,
+];
+
+/// What an armed failpoint does when reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailAction {
+    /// Return an I/O error from the operation.
+    Error,
+
+    /// Panic on the thread that reached the failpoint.
+    Panic,
+
+    /// Abort the process, as a power cut would. Useful in a child process
+    /// whose parent then reopens the database.
+    Abort,
+}
+
+/// Armed failpoints by name.
+static ARMED: LazyLock<Mutex<HashMap<String, FailAction>>> = LazyLock::new(Mutex::default);
+
+/// Whether any failpoint is armed; spares the lock on every pass.
+static ANY_ARMED: AtomicBool = AtomicBool::new(false);
+
+/// Renames `(from, to)` whose target directory has not been synced since,
+/// oldest first.
+static UNSYNCED_RENAMES: Mutex<Vec<(PathBuf, PathBuf)>> = Mutex::new(Vec::new());
+
+/// Arms the failpoint `name` with `action`, replacing any previous action.
+///
+/// Names not in [`ALL`] are accepted but never reached.
+pub fn set(name: &str, action: FailAction) {
+    let mut armed = ARMED.lock().unwrap();
+    armed.insert(name.to_string(), action);
+    ANY_ARMED.store(true, Ordering::Release);
+}
+
+/// Disarms the failpoint `name`.
+pub fn remove(name: &str) {
+    let mut armed = ARMED.lock().unwrap();
+    armed.remove(name);
+    ANY_ARMED.store(!armed.is_empty(), Ordering::Release);
+}
+
+/// Disarms every failpoint and forgets the unsynced renames.
+pub fn clear() {
+    let mut armed = ARMED.lock().unwrap();
+    armed.clear();
+    ANY_ARMED.store(false, Ordering::Release);
+    UNSYNCED_RENAMES.lock().unwrap().clear();
+}
+
+/// Returns the renames, as `(from, to)`, whose target directory has not
+/// been synced since, oldest first.
+pub fn unsynced_renames() -> Vec<(PathBuf, PathBuf)> {
+    UNSYNCED_RENAMES.lock().unwrap().clone()
+}
+
+/// Undoes every rename whose target directory has not been synced since,
+/// newest first, as if a power cut had lost the directory updates, and
+/// returns them. A file the rename replaced is not brought back.
+///
+/// Call it only after every handle on the affected directories is
+/// dropped.
+pub fn lose_unsynced_renames() -> io::Result<Vec<(PathBuf, PathBuf)>> {
+    let renames = std::mem::take(&mut *UNSYNCED_RENAMES.lock().unwrap());
+    for (from, to) in renames.iter().rev() {
+        if to.exists() {
+            fs::rename(to, from)?;
+        }
+    }
+    Ok(renames)
+}
+
+/// Journals a rename until its target directory is synced.
+pub(crate) fn record_rename(from: &Path, to: &Path) {
+    UNSYNCED_RENAMES
+        .lock()
+        .unwrap()
+        .push((from.to_path_buf(), to.to_path_buf()));
+}
+
+/// Drops the journaled renames into `dir`, now durable.
+pub(crate) fn record_dir_sync(dir: &Path) {
+    UNSYNCED_RENAMES
+        .lock()
+        .unwrap()
+        .retain(|(_, to)| to.parent() != Some(dir));
+}
+
+/// Runs the action armed for `name`, if any. Called through the
+/// `fail_point!` macro.
+pub(crate) fn eval(name: &str) -> io::Result<()> {
+    if !ANY_ARMED.load(Ordering::Acquire) {
+        return Ok(());
+    }
+    let action = ARMED.lock().unwrap().get(name).copied();
+    match action {
+        None => Ok(()),
+        Some(FailAction::Error) => {
+            tracing::warn!(failpoint = name, "failpoint returned an error");
+            Err(io::Error::other(format!("failpoint {name} triggered")))
+        }
+        Some(FailAction::Panic) => panic!("failpoint {name} triggered"),
+        Some(FailAction::Abort) => {
+            tracing::error!(failpoint = name, "failpoint aborting the process");
+            std::process::abort()
+        }
+    }
+}
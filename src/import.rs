@@ -0,0 +1,366 @@
+//! Import of the formats written by
+//! [`Db::export_range_to_writer`](crate::Db::export_range_to_writer), for
+//! [`Db::import_from_reader`](crate::Db::import_from_reader).
+//!
+//! [`Records`] decodes one pair at a time from any reader, so an import
+//! holds at most one batch in memory. The `Db` layer applies the
+//! [`ConflictPolicy`] to each batch and commits it through the write path
+//! or, for sorted input, as SSTables.
+
+use std::io::{self, BufRead, BufReader, Read};
+use std::iter::Peekable;
+use std::str::Chars;
+use std::time::Duration;
+
+use crate::encoding::MAX_BYTE_LEN;
+use crate::export::ExportFormat;
+use crate::{DbError, KeyValue};
+
+/// What [`Db::import_from_reader`](crate::Db::import_from_reader) does with
+/// an imported key that already holds a value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Replace the stored value.
+    #[default]
+    Overwrite,
+
+    /// Keep the stored value and count the pair in
+    /// [`ImportReport::skipped`].
+    SkipExisting,
+
+    /// Stop the import with [`DbError::InvalidArgument`] before the batch
+    /// holding the key is written.
+    Fail,
+}
+
+/// Options for [`Db::import_from_reader`](crate::Db::import_from_reader).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImportOptions {
+    /// Encoding of the input.
+    pub format: ExportFormat,
+
+    /// Handling of keys that already hold a value.
+    pub conflict: ConflictPolicy,
+
+    /// Pairs committed together. Must be at least `1`.
+    pub batch_size: usize,
+
+    /// Write each batch whose keys are strictly ascending directly as an
+    /// SSTable per partition, bypassing the WAL and memtable. Batches that
+    /// are not sorted take the normal write path.
+    pub ingest_sorted: bool,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::default(),
+            conflict: ConflictPolicy::default(),
+            batch_size: 1000,
+            ingest_sorted: false,
+        }
+    }
+}
+
+/// Summary returned by a completed import.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Pairs read from the input.
+    pub records: u64,
+
+    /// Pairs written, including those ingested as SSTables.
+    pub written: u64,
+
+    /// Of [`written`](Self::written), the pairs ingested directly as
+    /// SSTables.
+    pub ingested: u64,
+
+    /// Pairs left out under [`ConflictPolicy::SkipExisting`].
+    pub skipped: u64,
+
+    /// Wall-clock time from the first read to the last commit.
+    pub duration: Duration,
+}
+
+/// Iterator over the pairs encoded in a reader. Stops after the first
+/// error.
+pub(crate) struct Records<R> {
+    reader: BufReader<R>,
+    format: ExportFormat,
+    /// Pairs returned so far.
+    record: u64,
+    started: bool,
+    done: bool,
+}
+
+impl<R: Read> Records<R> {
+    pub(crate) fn new(reader: R, format: ExportFormat) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            format,
+            record: 0,
+            started: false,
+            done: false,
+        }
+    }
+
+    fn next_pair(&mut self) -> Result<Option<KeyValue>, DbError> {
+        let pair = match self.format {
+            ExportFormat::LengthPrefixed => self.read_length_prefixed()?,
+            ExportFormat::Csv => {
+                if !self.started {
+                    self.started = true;
+                    match self.read_csv_record()? {
+                        Some(header) if header == [b"key".as_slice(), b"value"] => {}
+                        _ => return Err(self.invalid("missing `key,value` CSV header")),
+                    }
+                }
+                match self.read_csv_record()? {
+                    None => None,
+                    Some(fields) => match <[Vec<u8>; 2]>::try_from(fields) {
+                        Ok([key, value]) => Some((self.text(key)?, self.text(value)?)),
+                        Err(fields) => {
+                            return Err(self.invalid(&format!(
+                                "expected 2 CSV fields, found {}",
+                                fields.len()
+                            )));
+                        }
+                    },
+                }
+            }
+            ExportFormat::JsonLines => self.read_json_line()?,
+        };
+
+        let Some((key, value)) = pair else {
+            return Ok(None);
+        };
+        if key.is_empty() || value.is_empty() {
+            return Err(self.invalid("key and value must not be empty"));
+        }
+        self.record += 1;
+        Ok(Some((key, value)))
+    }
+
+    fn read_length_prefixed(&mut self) -> Result<Option<KeyValue>, DbError> {
+        if self.reader.fill_buf().map_err(io_error)?.is_empty() {
+            return Ok(None);
+        }
+        let key = self.read_field()?;
+        let value = self.read_field()?;
+        Ok(Some((key, value)))
+    }
+
+    fn read_field(&mut self) -> Result<Vec<u8>, DbError> {
+        let mut len = [0; 4];
+        self.read_exact(&mut len)?;
+        let len = u32::from_le_bytes(len);
+        if len > MAX_BYTE_LEN {
+            return Err(self.invalid(&format!(
+                "field of {len} bytes exceeds MAX_BYTE_LEN ({MAX_BYTE_LEN})"
+            )));
+        }
+        let mut field = vec![0; len as usize];
+        self.read_exact(&mut field)?;
+        Ok(field)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), DbError> {
+        self.reader.read_exact(buf).map_err(|e| {
+            if e.kind() == io::ErrorKind::UnexpectedEof {
+                self.invalid("truncated record")
+            } else {
+                io_error(e)
+            }
+        })
+    }
+
+    /// Reads one RFC 4180 record, whose quoted fields may span lines.
+    /// Returns `None` at the end of the input.
+    fn read_csv_record(&mut self) -> Result<Option<Vec<Vec<u8>>>, DbError> {
+        let mut fields = Vec::new();
+        let mut field = Vec::new();
+        let mut quoted = false;
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if self.reader.read_until(b'\n', &mut line).map_err(io_error)? == 0 {
+                if quoted {
+                    return Err(self.invalid("unterminated quoted CSV field"));
+                }
+                return Ok(None);
+            }
+
+            let mut bytes = line.iter().copied().peekable();
+            while let Some(byte) = bytes.next() {
+                match byte {
+                    b'"' if quoted => {
+                        if bytes.next_if_eq(&b'"').is_some() {
+                            field.push(b'"');
+                        } else {
+                            quoted = false;
+                        }
+                    }
+                    _ if quoted => field.push(byte),
+                    b'"' if field.is_empty() => quoted = true,
+                    b',' => fields.push(std::mem::take(&mut field)),
+                    b'\r' if bytes.peek() == Some(&b'\n') => {}
+                    b'\n' => {
+                        fields.push(field);
+                        return Ok(Some(fields));
+                    }
+                    _ => field.push(byte),
+                }
+            }
+            // The last line of the input may lack its LF.
+            if !quoted {
+                fields.push(field);
+                return Ok(Some(fields));
+            }
+        }
+    }
+
+    /// Reads the next non-blank line as a `{"key":…,"value":…}` object.
+    fn read_json_line(&mut self) -> Result<Option<KeyValue>, DbError> {
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if self.reader.read_until(b'\n', &mut line).map_err(io_error)? == 0 {
+                return Ok(None);
+            }
+            let text = std::str::from_utf8(&line).map_err(|_| self.invalid("line is not UTF-8"))?;
+            if text.trim().is_empty() {
+                continue;
+            }
+            return parse_json_pair(text)
+                .map(Some)
+                .map_err(|msg| self.invalid(&msg));
+        }
+    }
+
+    fn text(&self, field: Vec<u8>) -> Result<Vec<u8>, DbError> {
+        match String::from_utf8(field) {
+            Ok(text) => Ok(text.into_bytes()),
+            Err(_) => Err(self.invalid("CSV field is not UTF-8")),
+        }
+    }
+
+    fn invalid(&self, msg: &str) -> DbError {
+        DbError::InvalidArgument(format!("import record {}: {msg}", self.record + 1))
+    }
+}
+
+impl<R: Read> Iterator for Records<R> {
+    type Item = Result<(Vec<u8>, Vec<u8>), DbError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.next_pair().transpose();
+        self.done = !matches!(result, Some(Ok(_)));
+        result
+    }
+}
+
+fn io_error(e: io::Error) -> DbError {
+    DbError::Engine(e.into())
+}
+
+/// Parses a JSON object with exactly the string members `key` and
+/// `value`, in either order.
+fn parse_json_pair(text: &str) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let mut chars = text.trim().chars().peekable();
+    if next_token(&mut chars) != Some('{') {
+        return Err("expected a JSON object".into());
+    }
+    let (mut key, mut value) = (None, None);
+    loop {
+        if next_token(&mut chars) != Some('"') {
+            return Err("expected a member name".into());
+        }
+        let name = parse_json_string(&mut chars)?;
+        if next_token(&mut chars) != Some(':') || next_token(&mut chars) != Some('"') {
+            return Err(format!("expected a string value for \"{name}\""));
+        }
+        let member = parse_json_string(&mut chars)?;
+        match name.as_str() {
+            "key" => key = Some(member),
+            "value" => value = Some(member),
+            _ => return Err(format!("unexpected member \"{name}\"")),
+        }
+        match next_token(&mut chars) {
+            Some(',') => {}
+            Some('}') => break,
+            _ => return Err("expected ',' or '}'".into()),
+        }
+    }
+    if chars.next().is_some() {
+        return Err("trailing characters after the object".into());
+    }
+    match (key, value) {
+        (Some(key), Some(value)) => Ok((key.into_bytes(), value.into_bytes())),
+        _ => Err("object needs both \"key\" and \"value\"".into()),
+    }
+}
+
+/// Returns the next character that is not JSON whitespace.
+fn next_token(chars: &mut Peekable<Chars<'_>>) -> Option<char> {
+    while chars.next_if(|c| c.is_ascii_whitespace()).is_some() {}
+    chars.next()
+}
+
+/// Parses the rest of a JSON string whose opening quote was consumed.
+fn parse_json_string(chars: &mut Peekable<Chars<'_>>) -> Result<String, String> {
+    let mut out = String::new();
+    loop {
+        match chars.next() {
+            None => return Err("unterminated string".into()),
+            Some('"') => return Ok(out),
+            Some('\\') => {
+                let c = match chars.next() {
+                    Some('"') => '"',
+                    Some('\\') => '\\',
+                    Some('/') => '/',
+                    Some('b') => '\u{8}',
+                    Some('f') => '\u{c}',
+                    Some('n') => '\n',
+                    Some('r') => '\r',
+                    Some('t') => '\t',
+                    Some('u') => {
+                        let unit = parse_hex4(chars)?;
+                        let code = if (0xd800..0xdc00).contains(&unit) {
+                            // A high surrogate must be followed by a low one.
+                            if chars.next() != Some('\\') || chars.next() != Some('u') {
+                                return Err("unpaired surrogate".into());
+                            }
+                            let low = parse_hex4(chars)?;
+                            if !(0xdc00..0xe000).contains(&low) {
+                                return Err("unpaired surrogate".into());
+                            }
+                            0x10000 + ((unit - 0xd800) << 10) + (low - 0xdc00)
+                        } else {
+                            unit
+                        };
+                        char::from_u32(code).ok_or("unpaired surrogate")?
+                    }
+                    _ => return Err("invalid escape".into()),
+                };
+                out.push(c);
+            }
+            Some(c) if (c as u32) < 0x20 => return Err("unescaped control character".into()),
+            Some(c) => out.push(c),
+        }
+    }
+}
+
+fn parse_hex4(chars: &mut Peekable<Chars<'_>>) -> Result<u32, String> {
+    let mut unit = 0;
+    for _ in 0..4 {
+        let digit = chars
+            .next()
+            .and_then(|c| c.to_digit(16))
+            .ok_or("invalid \\u escape")?;
+        unit = unit * 16 + digit;
+    }
+    Ok(unit)
+}
@@ -0,0 +1,189 @@
+//! Cross-process write lease.
+//!
+//! A database directory has at most one writer. [`Db::open`](crate::Db::open)
+//! takes the lease by creating `<path>/LEASE` and [`Db::close`](crate::Db::close)
+//! deletes it; any number of [`ReadOnlyDb`](crate::ReadOnlyDb)s may open the
+//! directory meanwhile.
+//!
+//! The file holds one line, `<pid> <token> <renewed_at>`: the holder's
+//! process id, a token telling apart two holders in the same process, and
+//! the Unix time in seconds of the last renewal. The holder renews the
+//! lease every [`LEASE_RENEW_SECS`] seconds. A lease not renewed for
+//! [`LEASE_TIMEOUT_SECS`] seconds belongs to a process that died without
+//! closing the database, and the next writer takes it over.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tracing::{error, warn};
+
+use crate::DbError;
+use crate::engine::EngineError;
+
+/// Name of the lease file in the database directory.
+pub const LEASE_FILE: &str = "LEASE";
+
+/// Seconds between two renewals by the holder.
+pub const LEASE_RENEW_SECS: u64 = 10;
+
+/// Seconds without renewal after which a lease counts as abandoned.
+pub const LEASE_TIMEOUT_SECS: u64 = 60;
+
+/// Source of per-process lease tokens.
+static NEXT_TOKEN: AtomicU64 = AtomicU64::new(1);
+
+/// Contents of a lease file.
+struct LeaseRecord {
+    pid: u32,
+    token: u64,
+    renewed_at: u64,
+}
+
+impl LeaseRecord {
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace().map(str::parse::<u64>);
+        let pid = u32::try_from(fields.next()?.ok()?).ok()?;
+        let token = fields.next()?.ok()?;
+        let renewed_at = fields.next()?.ok()?;
+        Some(Self {
+            pid,
+            token,
+            renewed_at,
+        })
+    }
+}
+
+/// The write lease held by an open [`Db`](crate::Db).
+#[derive(Debug)]
+pub(crate) struct WriteLease {
+    path: PathBuf,
+    token: u64,
+}
+
+impl WriteLease {
+    /// Takes the write lease of the database directory `dir`.
+    ///
+    /// # Errors
+    /// [`DbError::LeaseHeld`] if another handle, in this or another
+    /// process, renewed the lease less than [`LEASE_TIMEOUT_SECS`] ago.
+    pub fn acquire(dir: &Path) -> Result<Self, DbError> {
+        fs::create_dir_all(dir).map_err(EngineError::from)?;
+        let lease = Self {
+            path: dir.join(LEASE_FILE),
+            token: NEXT_TOKEN.fetch_add(1, Ordering::Relaxed),
+        };
+
+        // A takeover deletes the abandoned file and creates a fresh one;
+        // another writer may win that race, so try a few times.
+        let mut holder = 0;
+        for _ in 0..3 {
+            match OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&lease.path)
+            {
+                Ok(mut file) => {
+                    file.write_all(lease.line().as_bytes())
+                        .and_then(|()| file.sync_all())
+                        .map_err(EngineError::from)?;
+                    return Ok(lease);
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    let Some((pid, renewed_at)) = lease.holder().map_err(EngineError::from)? else {
+                        continue;
+                    };
+                    if now_secs() < renewed_at + LEASE_TIMEOUT_SECS {
+                        return Err(DbError::LeaseHeld(pid));
+                    }
+                    warn!(pid, renewed_at, "taking over abandoned write lease");
+                    holder = pid;
+                    match fs::remove_file(&lease.path) {
+                        Ok(()) => {}
+                        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                        Err(e) => return Err(EngineError::from(e).into()),
+                    }
+                }
+                Err(e) => return Err(EngineError::from(e).into()),
+            }
+        }
+        Err(DbError::LeaseHeld(holder))
+    }
+
+    /// Records a renewal. Logs an error and returns `false` if the lease
+    /// was lost — taken over after a missed renewal, or deleted.
+    pub fn renew(&self) -> bool {
+        match self.owned() {
+            Ok(true) => {}
+            Ok(false) => {
+                error!(path = %self.path.display(), "write lease lost to another writer");
+                return false;
+            }
+            Err(e) => {
+                error!(path = %self.path.display(), error = %e, "write lease check failed");
+                return false;
+            }
+        }
+        if let Err(e) = fs::write(&self.path, self.line()) {
+            error!(path = %self.path.display(), error = %e, "write lease renewal failed");
+            return false;
+        }
+        true
+    }
+
+    /// Deletes the lease file if this handle still holds it.
+    pub fn release(&self) {
+        if let Ok(true) = self.owned()
+            && let Err(e) = fs::remove_file(&self.path)
+        {
+            warn!(path = %self.path.display(), error = %e, "failed to remove write lease");
+        }
+    }
+
+    /// Returns whether the lease file still names this handle.
+    fn owned(&self) -> io::Result<bool> {
+        match fs::read_to_string(&self.path) {
+            Ok(line) => Ok(LeaseRecord::parse(&line)
+                .is_some_and(|r| r.pid == std::process::id() && r.token == self.token)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns the pid and last renewal time of the current holder, or
+    /// `None` if the file is gone. A file being rewritten may not parse;
+    /// its modification time stands in for the renewal time then.
+    fn holder(&self) -> io::Result<Option<(u32, u64)>> {
+        let line = match fs::read_to_string(&self.path) {
+            Ok(line) => line,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        if let Some(record) = LeaseRecord::parse(&line) {
+            return Ok(Some((record.pid, record.renewed_at)));
+        }
+        let modified = fs::metadata(&self.path)?.modified()?;
+        let renewed_at = modified
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Ok(Some((0, renewed_at)))
+    }
+
+    fn line(&self) -> String {
+        format!("{} {} {}\n", std::process::id(), self.token, now_secs())
+    }
+}
+
+impl Drop for WriteLease {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
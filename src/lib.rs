@@ -39,31 +39,232 @@
 //! - **Bloom filters** — fast negative lookups on SSTables.
 //! - **CRC32 integrity** — all on-disk blocks are checksummed.
 //! - **Crash recovery** — automatic recovery from WAL on restart.
+//! - **Multi-process readers** — [`ReadOnlyDb`] follows a database written by another process.
+//! - **Ordered composite keys** — [`orderedcode`] encodes integers, floats, strings, and tuples so keys scan in their natural order.
+//!
+//! ## Version precedence
+//!
+//! Every write gets a log sequence number (LSN), and the version of a key
+//! with the highest LSN is the visible one. The same write can briefly
+//! exist in two places — a write buffer and the SSTable it was flushed
+//! into, or an SSTable and a WAL replayed over it after a crash — and then
+//! the versions share an LSN. [`Db::get`], [`Db::scan`], and compaction
+//! all break such ties the same way:
+//!
+//! 1. A put or point delete wins over a range delete; a range delete only
+//!    hides versions with a lower LSN.
+//! 2. The version with the later timestamp wins.
+//! 3. The version in the newer layer wins: the active write buffer, then
+//!    frozen write buffers from newest to oldest, then SSTables by
+//!    descending maximum LSN and, for equal maxima, descending id.
+
+/// Returns early with an I/O error, panics, or aborts when the named
+/// failpoint is armed; compiles to nothing without feature `failpoints`.
+macro_rules! fail_point {
+    ($name:ident) => {
+        #[cfg(feature = "failpoints")]
+        crate::failpoints::eval(crate::failpoints::$name)?;
+    };
+}
 
+pub(crate) mod background;
+pub(crate) mod batch;
+pub(crate) mod clock;
 pub(crate) mod compaction;
+pub(crate) mod compaction_handle;
+pub(crate) mod config_builder;
+pub(crate) mod config_check;
+pub(crate) mod durability;
 pub(crate) mod encoding;
 pub(crate) mod engine;
+pub(crate) mod export;
+#[cfg(feature = "failpoints")]
+pub mod failpoints;
+pub(crate) mod import;
+pub(crate) mod lease;
+pub(crate) mod listener;
 pub(crate) mod manifest;
 pub(crate) mod memtable;
+pub mod orderedcode;
+pub(crate) mod partition;
+pub(crate) mod range_lock;
+pub(crate) mod read_only;
+pub(crate) mod sampling;
+pub(crate) mod sketch;
+pub(crate) mod slow_op;
+pub mod sst;
 pub(crate) mod sstable;
+pub(crate) mod subscribe;
+pub(crate) mod supervisor;
+pub(crate) mod tenant;
+pub mod tools;
+#[cfg(feature = "typed")]
+pub mod typed;
+pub mod types;
 pub(crate) mod wal;
 
 use std::path::Path;
-use std::sync::Mutex;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::Duration;
 
-use engine::{Engine, EngineConfig, EngineError};
+use background::{Job, Task, TaskKind, TaskQueues};
+use compaction_handle::MajorState;
+use engine::{EngineConfig, EngineError, TuningWindow};
+use lease::{LEASE_RENEW_SECS, WriteLease};
+use partition::PartitionedEngine;
+use sampling::{TraceSampler, key_hash};
+use slow_op::SlowOpTimer;
+use subscribe::ChangeFeed;
+use supervisor::TaskSupervisor;
+use tenant::TenantAccounting;
 use thiserror::Error;
-use tracing::{debug, error, info};
+use tracing::{debug, debug_span, error, field, info, info_span, warn};
 
 /// A single key-value pair returned by [`Db::scan`].
 pub type KeyValue = (Vec<u8>, Vec<u8>);
 
+/// One page of a range returned by [`Db::scan_paged`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanPage {
+    /// Live pairs of the page, in key order.
+    pub pairs: Vec<KeyValue>,
+
+    /// Start key of the next page — the first live key after this page —
+    /// or `None` if the range holds no more keys. Pass it as `start` to
+    /// the next [`Db::scan_paged`] call.
+    pub next_start: Option<Vec<u8>>,
+}
+
 /// Re-export the compaction strategy selector so callers can configure it
 /// without reaching into internal modules.
 pub use compaction::CompactionStrategyType;
 
+/// Urgency of a [`Db::suggest_compact_range`] hint.
+pub use compaction::CompactionPriority;
+
+/// Tombstone compaction checks returned by [`Db::tombstone_gc_stats`].
+pub use compaction::TombstoneGcStats;
+
+/// Compaction job counters returned by [`Db::compaction_job_stats`].
+pub use compaction::CompactionJobStats;
+
+/// Background major compaction returned by [`Db::major_compact_async`].
+pub use compaction_handle::{CompactionHandle, CompactionProgress};
+
+/// Builder and workload presets for [`DbConfig`].
+pub use config_builder::{ConfigProfile, DbConfigBuilder};
+
+/// Configuration violations carried by [`DbError::InvalidConfig`].
+pub use config_check::{ConfigViolation, ConfigViolations};
+
+/// Per-SSTable metadata returned by [`Db::sstable_metadata`].
+pub use engine::SSTableMetadata;
+
+/// WAL segments returned by [`Db::wal_files`].
+pub use engine::{WalFile, WalFileStatus};
+
+/// Outcome of [`Db::purge_obsolete_files`].
+pub use engine::PurgeReport;
+
+/// Point-lookup counters returned by [`Db::read_stats`], and the reads
+/// past the fan-out limit reported to
+/// [`EventListener::on_read_fanout_exceeded`].
+pub use engine::{ReadFanout, ReadStats};
+
+/// Write pipeline counters returned by [`Db::write_stats`], and the
+/// writes reported to [`EventListener::on_write_activity`].
+pub use engine::{WriteActivity, WriteStats};
+
+/// SSTable build counters, part of [`WriteStats`].
+pub use sstable::SstBuildStats;
+
+/// WAL counters, part of [`WriteStats`].
+pub use wal::{WAL_SYNC_HISTOGRAM_BOUNDS, WalStats};
+
+/// Summary of crash recovery returned by [`Db::recovery_report`].
+pub use engine::RecoveryReport;
+
+/// Summary of an offline repair returned by [`Db::repair`].
+pub use engine::RepairReport;
+
+/// Manifest consistency report returned by [`Db::check_consistency`].
+pub use engine::{ConsistencyIssue, ConsistencyReport};
+
+/// SSTable delta exchanged by [`Db::export_delta`] and [`Db::apply_delta`].
+pub use engine::{DeltaSSTable, ReplicationDelta};
+
+/// Compaction debugging report returned by [`Db::debug_report`].
+pub use engine::{BucketReport, CompactionCandidates, DebugReport, PartitionReport, SSTableReport};
+
+/// Adaptive compaction settings, thresholds, and the changes reported to
+/// [`EventListener::on_compaction_tuned`].
+pub use engine::{AdaptiveCompaction, CompactionThresholds, CompactionTuning, TuningReason};
+
+/// Callbacks registered with [`DbConfig::event_listeners`].
+pub use listener::EventListener;
+
+/// Panic handling of background tasks.
+pub use supervisor::{BackgroundPanic, BackgroundPanicPolicy, BackgroundStats};
+
+/// Background queue lengths returned by [`Db::queue_stats`].
+pub use background::QueueStats;
+
+/// Per-read options accepted by [`Db::get_opt`] and [`Db::scan_opt`].
+pub use engine::ReadOptions;
+
+/// Per-write options accepted by [`Db::put_opt`], [`Db::delete_opt`] and
+/// [`Db::write_opt`].
+pub use engine::WriteOptions;
+
+/// Zero-copy value handle returned by [`Db::get_pinned`].
+pub use engine::PinnedSlice;
+
+/// Value and version returned by [`Db::get_entry`].
+pub use engine::Entry;
+
+/// Outcome of [`Db::compare_and_swap`].
+pub use engine::CasOutcome;
+
+/// WAL replay policy selected by [`DbConfig::wal_recovery_mode`].
+pub use wal::WalRecoveryMode;
+
+/// SSTable open-time validation selected by [`DbConfig::sstable_open_checks`].
+pub use sstable::OpenChecks;
+
+/// Whole-file SSTable digest selected by [`DbConfig::sstable_file_digest`].
+pub use sstable::SstFileDigest;
+
+/// SSTable directory layout and file naming selected by
+/// [`DbConfig::sstable_layout`] and [`DbConfig::sstable_naming`].
+pub use engine::{SstDirLayout, SstFileNaming};
+
+/// Readable batch of uncommitted writes, committed with [`Db::write`].
+pub use batch::WriteBatchWithIndex;
+
+/// Advisory key-range lock returned by [`Db::lock_range`].
+pub use range_lock::RangeGuard;
+
+/// Time source selected by [`DbConfig::clock`].
+pub use clock::{Clock, MockClock, SystemClock};
+
+/// Committed mutation delivered to [`Db::subscribe`] receivers.
+pub use subscribe::ChangeEvent;
+
+/// Options, formats and summary of [`Db::export_range_to_writer`].
+pub use export::{ExportFormat, ExportOptions, ExportReport};
+
+/// Options, conflict policy and summary of [`Db::import_from_reader`].
+pub use import::{ConflictPolicy, ImportOptions, ImportReport};
+
+/// Read-only handle to a database written by another process.
+pub use read_only::{ReadOnlyConfig, ReadOnlyDb};
+
+/// Prefix-to-tenant mapping of [`DbConfig::tenant_prefixes`] and the usage
+/// returned by [`Db::tenant_usage`].
+pub use tenant::{TenantPrefix, TenantUsage};
+
 // ------------------------------------------------------------------------------------------------
 // Configuration
 // ------------------------------------------------------------------------------------------------
@@ -71,7 +272,10 @@ pub use compaction::CompactionStrategyType;
 /// Configuration for a [`Db`] instance.
 ///
 /// All fields have sensible defaults via [`DbConfig::default()`].
-/// The configuration is validated when passed to [`Db::open`].
+/// Presets such as [`DbConfig::for_write_heavy`] tune groups of related
+/// fields for one kind of workload, and [`DbConfig::builder`] combines a
+/// [`ConfigProfile`] with individual settings. The configuration is
+/// validated when passed to [`Db::open`].
 ///
 /// # Example
 ///
@@ -87,6 +291,12 @@ pub use compaction::CompactionStrategyType;
 ///     thread_pool_size: 4,
 ///     ..DbConfig::default()
 /// };
+///
+/// // Or start from a preset
+/// let config = DbConfig {
+///     partitions: 4,
+///     ..DbConfig::for_read_heavy()
+/// };
 /// ```
 pub struct DbConfig {
     /// Maximum size of the in-memory write buffer in bytes.
@@ -99,11 +309,44 @@ pub struct DbConfig {
     /// Default: `65 536` (64 KiB).
     pub write_buffer_size: usize,
 
+    /// Maximum number of frozen write buffers merged into a single SSTable
+    /// by one background flush.
+    ///
+    /// During write bursts several buffers may be frozen before the
+    /// background flush runs. With a value above `1`, up to this many of
+    /// the oldest frozen buffers are merged into one sorted SSTable instead
+    /// of producing one small SSTable each, reducing compaction pressure.
+    ///
+    /// **Bounds:** 1 ≤ `max_memtables_per_flush` ≤ 64.
+    ///
+    /// Default: `1` (one SSTable per frozen buffer).
+    pub max_memtables_per_flush: usize,
+
+    /// Maximum number of frozen write buffers held in memory awaiting
+    /// flush.
+    ///
+    /// Frozen buffers stay readable until flushed, so a write burst that
+    /// outpaces the background flush grows memory use and the number of
+    /// buffers a read may visit. Once a freeze would exceed this limit,
+    /// the writing thread flushes the oldest buffers itself before the
+    /// write returns, slowing writers down to the speed of flushing.
+    ///
+    /// **Bounds:** `max_frozen_memtables` ≤ 1024.
+    ///
+    /// Default: `0` (unlimited).
+    pub max_frozen_memtables: usize,
+
     /// Compaction strategy family.
     ///
     /// Determines how SSTables are grouped and merged during minor,
     /// tombstone, and major compaction.
     ///
+    /// [`CompactionStrategyType::Fifo`] never merges; it drops the oldest
+    /// SSTables instead, checking its TTL on the periodic compaction timer.
+    ///
+    /// **Bounds:** for `Fifo`, `max_total_bytes` and `ttl_seconds` are not
+    /// both `0`, and `ttl_seconds` ≤ 31 536 000 (365 days).
+    ///
     /// Default: [`CompactionStrategyType::Stcs`] (Size-Tiered).
     pub compaction_strategy: CompactionStrategyType,
 
@@ -139,9 +382,11 @@ pub struct DbConfig {
     pub tombstone_compaction_interval: usize,
 
     /// When `true`, tombstone compaction resolves bloom filter false
-    /// positives by performing an actual `get()` against older SSTables
-    /// for each point tombstone. This enables more aggressive tombstone
-    /// removal at the cost of extra I/O.
+    /// positives by looking point tombstones up in the older SSTables.
+    /// The lookups against one SSTable are made in a single sorted sweep
+    /// that reads each of its data blocks at most once. This enables more
+    /// aggressive tombstone removal at the cost of extra I/O; see
+    /// [`Db::tombstone_gc_stats`].
     ///
     /// Default: `true`.
     pub tombstone_bloom_fallback: bool,
@@ -153,18 +398,485 @@ pub struct DbConfig {
     /// Default: `true`.
     pub tombstone_range_drop: bool,
 
+    /// Grace period (in seconds) during which tombstones are retained by
+    /// **every** compaction — minor, tombstone, and major — even when they
+    /// are provably spent.
+    ///
+    /// Protects against resurrecting deleted data when an old backup or
+    /// replica is restored alongside this database. Unlike
+    /// `tombstone_compaction_interval`, which only gates which SSTables are
+    /// selected, this applies to each individual tombstone by its own
+    /// write timestamp. Set to `0` to disable.
+    ///
+    /// **Bounds:** 0 ≤ `tombstone_gc_grace_seconds` ≤ 31 536 000 (365 days).
+    ///
+    /// Default: `0` (no grace period).
+    pub tombstone_gc_grace_seconds: usize,
+
+    /// Maximum age in seconds before an SSTable is rewritten by periodic
+    /// compaction.
+    ///
+    /// SSTables older than this are picked for recompaction even if no
+    /// size or tombstone trigger fires, so that cold data with expired
+    /// tombstones is eventually rewritten. Checked after every background
+    /// flush and on a timer of half the period (capped at one hour). Set
+    /// to `0` to disable.
+    ///
+    /// **Bounds:** 0 ≤ `periodic_compaction_seconds` ≤ 31 536 000 (365 days).
+    ///
+    /// Default: `0` (disabled).
+    pub periodic_compaction_seconds: usize,
+
+    /// Number of SSTables a `get` may probe, at the 99th percentile of
+    /// the last 1024 lookups in a partition, before compaction is queued
+    /// regardless of the size-tiered thresholds.
+    ///
+    /// A timer checks every second. Once the percentile exceeds the
+    /// threshold, the newest SSTables — which every lookup probes first —
+    /// are merged until it should fall back to the threshold, like a
+    /// [`Db::suggest_compact_range`] hint, and the lookups are counted
+    /// afresh. Lookups answered by a memtable or the row cache count as
+    /// probing no SSTable. Set to `0` to disable.
+    ///
+    /// **Bounds:** 0 ≤ `read_depth_compaction_threshold` ≤ 1024.
+    ///
+    /// Default: `0` (disabled).
+    pub read_depth_compaction_threshold: usize,
+
+    /// Number of consecutive point tombstones a scan may step over in one
+    /// SSTable before that SSTable is compacted, regardless of the
+    /// size-tiered thresholds.
+    ///
+    /// Queue-like workloads that delete from the head of a range leave
+    /// runs of tombstones that every later scan from the head has to step
+    /// over. A timer checks every second for SSTables in which a scan met
+    /// such a run since the last check, and merges each with the older
+    /// SSTables its key range overlaps, like a
+    /// [`Db::suggest_compact_range`] hint, so the deleted keys and their
+    /// tombstones are dropped. Keys hidden by range deletes are skipped
+    /// without being read and do not count. Set to `0` to disable.
+    ///
+    /// **Bounds:** 0 ≤ `deletion_run_compaction_threshold` ≤ 1 000 000.
+    ///
+    /// Default: `0` (disabled).
+    pub deletion_run_compaction_threshold: usize,
+
+    /// Whether minor compaction merges the most-read SSTables first.
+    ///
+    /// Every SSTable counts the lookups it answered and the scans that
+    /// read it since open, reported by [`Db::sstable_metadata`]. When
+    /// several size buckets qualify for minor compaction, the one with
+    /// the most reads per byte of its files is merged instead of the
+    /// largest, and a bucket past `max_compaction_threshold` contributes
+    /// its hottest SSTables. Small tables that every read probes are
+    /// merged first, cutting read amplification sooner per byte of
+    /// compaction I/O. The counters live in memory and start at zero on
+    /// open.
+    ///
+    /// Default: `false` (the bucket with the most SSTables is merged).
+    pub hot_sstable_compaction: bool,
+
+    /// Number of SSTables a `get` or scan may need to consult before it
+    /// counts as past the read fan-out limit.
+    ///
+    /// Compaction that falls behind leaves a growing stack of SSTables,
+    /// and read latency grows with it. Every read past the limit is
+    /// counted in [`ReadStats::fanout_exceeded`], and a timer reports the
+    /// reads of the last second to
+    /// [`EventListener::on_read_fanout_exceeded`], so operators notice the
+    /// compaction debt before latency does. A `get` counts the SSTables
+    /// that may hold a newer version than the memtables; a scan the
+    /// SSTables overlapping its range. Set to `0` to disable.
+    ///
+    /// **Bounds:** 0 ≤ `read_fanout_limit` ≤ 65 536.
+    ///
+    /// Default: `0` (disabled).
+    pub read_fanout_limit: usize,
+
+    /// Whether a `get` past [`read_fanout_limit`](Self::read_fanout_limit)
+    /// consults only the SSTables whose key range, or one of whose range
+    /// deletes, covers its key.
+    ///
+    /// The key range check rules out fewer SSTables than a bloom filter
+    /// but costs two key comparisons instead of hashing, so a lookup over
+    /// many disjoint SSTables skips most of them without touching their
+    /// filters. Results are unchanged. Scans always skip SSTables outside
+    /// their range. Requires a non-zero
+    /// [`read_fanout_limit`](Self::read_fanout_limit).
+    ///
+    /// Default: `false`.
+    pub read_fanout_fallback: bool,
+
+    /// Maximum age (in seconds) of the oldest write in the active
+    /// memtable before it is flushed, even if the write buffer is not full.
+    ///
+    /// Bounds how much WAL a restart has to replay on a database that is
+    /// written too slowly to ever fill `write_buffer_size`. Checked on
+    /// every write and on a timer of half the age (capped at one hour),
+    /// so an idle database is flushed too. Set to `0` to disable.
+    ///
+    /// **Bounds:** 0 ≤ `max_memtable_age` ≤ 31 536 000 (365 days).
+    ///
+    /// Default: `0` (disabled).
+    pub max_memtable_age: usize,
+
+    /// Maximum number of un-flushed WAL bytes per partition before the
+    /// active memtable is flushed.
+    ///
+    /// Checked on every write while no earlier memtable is still waiting
+    /// to be flushed. Useful when the WAL grows faster than the memtable,
+    /// e.g. with many overwrites of the same keys. Set to `0` to disable.
+    ///
+    /// **Bounds:** `max_total_wal_size` = 0 or ≥ 1024.
+    ///
+    /// Default: `0` (disabled).
+    pub max_total_wal_size: usize,
+
+    /// Maximum total size (in bytes) of flushed WAL segments kept on disk
+    /// per partition.
+    ///
+    /// A WAL segment is no longer needed for recovery once its memtable
+    /// is flushed, but keeping it lets log tailers and external
+    /// replicators catch up after a brief outage. Past this size the
+    /// oldest flushed segments are deleted. Set to `0` for no size limit.
+    ///
+    /// **Bounds:** `wal_retention_bytes` = 0 or ≥ 1024.
+    ///
+    /// Default: `0` (no limit).
+    pub wal_retention_bytes: usize,
+
+    /// Maximum age (in seconds) of a flushed WAL segment, measured from
+    /// its last write, before it is deleted.
+    ///
+    /// Both retention limits are applied after every flush and on open.
+    /// Set to `0` for no age limit. With both this and
+    /// `wal_retention_bytes` at `0`, no segment is retained: each is
+    /// deleted as soon as its memtable is flushed.
+    ///
+    /// **Bounds:** 0 ≤ `wal_retention_seconds` ≤ 31 536 000 (365 days).
+    ///
+    /// Default: `0` (no limit).
+    pub wal_retention_seconds: usize,
+
+    /// Seconds an SSTable file removed by a compaction, a clear, or a
+    /// replication delta stays on disk before it is deleted.
+    ///
+    /// Backup tools that list the directory and then copy the files they
+    /// found race a compaction deleting one of them. With a grace period,
+    /// a removed file is deleted only once no scan iterator or pinned
+    /// value still reads it and the period has passed; a timer checks
+    /// every second, and [`Db::purge_obsolete_files`] deletes them at once
+    /// without waiting for the period. Files still waiting when the
+    /// process stops are removed as orphans on the next open. Set to `0`
+    /// to delete removed files as soon as they leave the live set.
+    ///
+    /// **Bounds:** 0 ≤ `obsolete_file_grace_seconds` ≤ 31 536 000 (365 days).
+    ///
+    /// Default: `0` (deleted at once).
+    pub obsolete_file_grace_seconds: usize,
+
+    /// Total size (in bytes) of the row cache for point lookups.
+    ///
+    /// The row cache keeps the values of recently read keys that were
+    /// found in SSTables, so a small hot set read over and over skips
+    /// bloom filters and data blocks entirely. Writes invalidate the keys
+    /// they touch, and every compaction empties it. The size is split
+    /// evenly between the partitions. Set to `0` to disable it.
+    ///
+    /// **Bounds:** `row_cache_size` = 0 or ≥ 1024 per partition.
+    ///
+    /// Default: `0` (disabled).
+    pub row_cache_size: usize,
+
+    /// Total size (in bytes) of SSTable files that may be memory-mapped at
+    /// once.
+    ///
+    /// SSTables are normally read through `mmap`, and every page touched
+    /// counts toward the process's resident memory — with large tables,
+    /// e.g. during a major compaction, enough to draw the OOM killer in a
+    /// memory-constrained container. Past this limit the least recently
+    /// read SSTables are unmapped and read with positioned reads
+    /// (`pread`) instead; they are mapped again once room frees up. The
+    /// limit is split evenly between the partitions. Set to `0` to map
+    /// every SSTable.
+    ///
+    /// **Bounds:** `max_mmap_bytes` = 0 or ≥ 1024 per partition.
+    ///
+    /// Default: `0` (no limit).
+    pub max_mmap_bytes: usize,
+
+    /// Pass page cache hints to the OS after flushes and compactions.
+    ///
+    /// The data blocks of a newly written SSTable are advised
+    /// `MADV_WILLNEED`, so the reads that follow a heavy compaction find
+    /// them in the page cache rather than faulting them in one by one.
+    /// The memory maps of the SSTables a compaction replaced are dropped
+    /// right away instead of when the last scan over them ends, which
+    /// finishes with `pread`. Only memory-mapped SSTables on Unix are
+    /// advised.
+    ///
+    /// Default: `false`.
+    pub page_cache_hints: bool,
+
+    /// How much of each SSTable is verified when [`Db::open`] loads it.
+    ///
+    /// The header and footer checksums are always checked.
+    /// [`OpenChecks::Metadata`] also verifies and decodes every metadata
+    /// block. [`OpenChecks::FooterOnly`] skips those checksums and reads
+    /// each table's bloom filter and key sketch only on first use, so
+    /// opening a database with many large SSTables touches less of each
+    /// file. [`OpenChecks::FullBlockChecksums`] additionally verifies
+    /// every data block, reading every SSTable in full, so a damaged
+    /// table fails [`Db::open`] instead of the first read that reaches
+    /// it. The same level applies to [`Db::check_consistency`].
+    ///
+    /// Default: [`OpenChecks::Metadata`].
+    pub sstable_open_checks: OpenChecks,
+
+    /// Where new SSTables are written under `sstables/`: directly
+    /// ([`SstDirLayout::Flat`]) or in a subdirectory per compaction
+    /// strategy family ([`SstDirLayout::PerStrategy`]).
+    ///
+    /// May change between opens; existing SSTables stay where they are.
+    ///
+    /// Default: [`SstDirLayout::Flat`].
+    pub sstable_layout: SstDirLayout,
+
+    /// How new SSTable files are named: after their manifest id
+    /// ([`SstFileNaming::Id`]) or after a time-sortable ULID
+    /// ([`SstFileNaming::Ulid`]).
+    ///
+    /// May change between opens; existing SSTables keep their names.
+    ///
+    /// Default: [`SstFileNaming::Id`].
+    pub sstable_naming: SstFileNaming,
+
+    /// Store a digest of the whole file in every new SSTable, computed
+    /// with [`SstFileDigest::Xxh3`] or [`SstFileDigest::Sha256`] while the
+    /// table is written.
+    ///
+    /// A copied SSTable can then be checked without parsing its blocks,
+    /// with [`sst::verify_file_digest`]. The database checks it where
+    /// files travel or are scrubbed: [`Db::apply_delta`] rejects a shipped
+    /// SSTable that does not match, [`Db::clone_to`] verifies SSTables it
+    /// has to copy, and [`OpenChecks::FullBlockChecksums`] — for
+    /// [`Db::open`] and [`Db::check_consistency`] — and
+    /// [`sst::SstReader::verify`] verify it along with the data blocks.
+    /// SSTables written without a digest, by older versions or with this
+    /// unset, are not checked. Costs one hash pass over each SSTable
+    /// written, and up to 45 bytes per file.
+    ///
+    /// Default: `None`.
+    pub sstable_file_digest: Option<SstFileDigest>,
+
+    /// Record a CRC-32 of every value written, from the write call through
+    /// the memtable into the cells of flushed and compacted SSTables.
+    ///
+    /// The WAL, memtable, and SSTable block checksums each protect data
+    /// at rest; a bit flipped in memory between them — in a memtable, or
+    /// in a value being rewritten by compaction — goes unnoticed. With
+    /// this set, point lookups verify the value they return against its
+    /// checksum, unless [`ReadOptions::verify_value_checksum`] is off, and
+    /// a flush refuses to write a memtable holding a damaged value.
+    /// Costs a CRC per write and lookup, and 4 bytes per SSTable cell.
+    /// Existing data gains checksums as it is flushed or compacted.
+    ///
+    /// Default: `false`.
+    pub value_checksums: bool,
+
+    /// Seconds for which the idempotency token of a committed write batch
+    /// is remembered (see
+    /// [`WriteBatchWithIndex::set_idempotency_token`]). A batch retried
+    /// with the same token within this window is not applied again.
+    /// Must be in `[1, 31536000]`.
+    ///
+    /// Default: `3600` (one hour).
+    pub idempotency_retention_seconds: usize,
+
+    /// Most idempotency tokens remembered. Beyond it the oldest are
+    /// forgotten before their retention window ends, bounding the memory
+    /// and manifest space they take. Must be in `[1, 10000000]`.
+    ///
+    /// Default: `100_000`.
+    pub idempotency_max_tokens: usize,
+
+    /// Number of most recently created SSTables, per partition, whose
+    /// data blocks are read into the OS page cache by a background task
+    /// right after [`Db::open`].
+    ///
+    /// Recent SSTables hold the freshest and usually hottest data; warming
+    /// them trims the slow first reads after a restart. Reads are served
+    /// meanwhile. Use [`Db::warm_up`] to warm specific key ranges instead.
+    /// Set to `0` to disable.
+    ///
+    /// **Bounds:** 0 ≤ `warm_up_sstables` ≤ 1 024.
+    ///
+    /// Default: `0` (disabled).
+    pub warm_up_sstables: usize,
+
     /// Number of background worker threads for flushing and compaction.
     ///
     /// **Bounds:** 1 ≤ `thread_pool_size` ≤ 32.
     ///
     /// Default: `2`.
     pub thread_pool_size: usize,
+
+    /// Tasks that may wait in each background queue.
+    ///
+    /// Flushes, minor, tombstone, and major compaction each have a queue
+    /// of their own, and idle workers serve them in that order. A write
+    /// that freezes its memtable while this many flushes wait blocks until
+    /// a worker takes one, so write storms that outpace flushing slow the
+    /// writers down instead of piling up frozen memtables. Compaction
+    /// triggered by a flush or timer is dropped while its queue is full —
+    /// the compaction already queued does the same work — and counted in
+    /// [`Db::queue_stats`]. Each kind of compaction or timer task waits
+    /// at most once regardless of this depth; later requests are
+    /// coalesced into it.
+    ///
+    /// **Bounds:** 1 ≤ `background_queue_depth` ≤ 1 024.
+    ///
+    /// Default: `16`.
+    pub background_queue_depth: usize,
+
+    /// Run point-lookup bloom filter checks across SSTables in parallel.
+    ///
+    /// With many SSTables, a `get` first checks every bloom filter on up
+    /// to `thread_pool_size` threads to hide page-fault latency, then
+    /// probes the surviving SSTables newest-first as usual. Trades CPU for
+    /// read latency; only kicks in once there are at least 8 SSTables.
+    ///
+    /// Default: `false`.
+    pub parallel_sstable_probe: bool,
+
+    /// How write-ahead log replay on open handles damaged records.
+    ///
+    /// A torn final record left by a crash mid-append is always
+    /// distinguishable from corruption in the middle of a log. The default
+    /// drops the torn record and fails [`Db::open`] on mid-log corruption
+    /// instead of silently discarding everything after it. Zero-filled
+    /// tails are treated as a clean end of log in every mode.
+    ///
+    /// Default: [`WalRecoveryMode::TolerateCorruptedTail`].
+    pub wal_recovery_mode: WalRecoveryMode,
+
+    /// Number of independent LSM trees the key space is hash-partitioned
+    /// into.
+    ///
+    /// Each partition has its own write buffer, WAL, manifest, and SSTable
+    /// set, so flushes and compactions operate on smaller trees and
+    /// SSTable counts per tree stay low for very large key spaces. The
+    /// background thread pool is shared. Scans still return keys in byte
+    /// order by merging the partitions. Range deletes are applied to every
+    /// partition.
+    ///
+    /// As the WAL is not shared, each partition numbers its writes with
+    /// its own LSNs: the LSNs of [`Db::get_entry`] order the versions of
+    /// one key, but not writes to keys in different partitions, and
+    /// [`Db::latest_lsn`] is the highest of the partitions' LSNs.
+    ///
+    /// Fixed when the database is created: reopening with a different
+    /// value fails with [`DbError::InvalidConfig`].
+    ///
+    /// **Bounds:** 1 ≤ `partitions` ≤ 256.
+    ///
+    /// Default: `1` (unpartitioned).
+    pub partitions: u32,
+
+    /// Fraction of reads and writes that open a `tracing` span.
+    ///
+    /// Sampled `put`, `delete`, `delete_range`, `write`, `get`, and `scan`
+    /// calls are wrapped in a `debug`-level span carrying a hash of the
+    /// key, the bytes written or returned, and — for reads — the number of
+    /// SSTables touched and bloom filter hits and misses. Background
+    /// flushes and compactions always get an `info`-level span. Lower the
+    /// ratio to keep span overhead bounded at high request rates.
+    ///
+    /// **Bounds:** 0.0 ≤ `trace_sampling_ratio` ≤ 1.0.
+    ///
+    /// Default: `1.0` (every operation).
+    pub trace_sampling_ratio: f64,
+
+    /// Duration past which an operation is logged as slow.
+    ///
+    /// A `get`, `put`, `delete`, `delete_range`, `write`, `scan`, `count`,
+    /// flush, or compaction taking at least this long emits a `warn` event
+    /// on target `aeternusdb::slow_op`, with its elapsed time, the time
+    /// spent in WAL fsyncs, and the SSTable blocks read and SSTables
+    /// probed, whatever [`trace_sampling_ratio`](Self::trace_sampling_ratio)
+    /// is. Only work on the thread running the operation is counted.
+    ///
+    /// Default: `None` (nothing is logged).
+    pub slow_op_threshold: Option<Duration>,
+
+    /// Adaptive tuning of the compaction thresholds.
+    ///
+    /// When set, a background controller measures read amplification
+    /// (SSTables probed per `get`) and write amplification (bytes written
+    /// by flushes and compactions per byte flushed) of every partition
+    /// each [`AdaptiveCompaction::interval_seconds`], and moves
+    /// `min_compaction_threshold`, `max_compaction_threshold`, and
+    /// `tombstone_compaction_ratio` within the configured bounds: towards
+    /// eager compaction while reads probe too many SSTables, towards lazy
+    /// compaction while compaction rewrites too much. The configured
+    /// thresholds are the starting point; tuned values are not persisted.
+    /// Changes are reported to [`event_listeners`](Self::event_listeners).
+    ///
+    /// Default: `None` (thresholds stay as configured).
+    pub adaptive_compaction: Option<AdaptiveCompaction>,
+
+    /// Key prefixes whose storage is accounted per tenant, reported by
+    /// [`Db::tenant_usage`].
+    ///
+    /// Every SSTable written by a flush, compaction, or ingestion tallies
+    /// the key and value bytes of each prefix in its properties, so usage
+    /// is summed from metadata without scanning keys. A key is accounted
+    /// to the longest prefix it starts with. SSTables written before a
+    /// prefix was added carry no tally for it until they are compacted.
+    /// Costs one prefix lookup per key written to an SSTable.
+    ///
+    /// **Bounds:** every prefix and tenant non-empty, no prefix listed
+    /// twice.
+    ///
+    /// Default: empty (no accounting).
+    pub tenant_prefixes: Vec<TenantPrefix>,
+
+    /// Listeners notified of background decisions.
+    ///
+    /// Default: empty.
+    pub event_listeners: Vec<Arc<dyn EventListener>>,
+
+    /// What happens after a background task — a flush, compaction, or
+    /// timer tick — panics.
+    ///
+    /// The panic is always caught at the task boundary, so the worker
+    /// thread survives and the pool keeps its size. It is counted in
+    /// [`Db::background_stats`] and reported to
+    /// [`EventListener::on_background_panic`]. With
+    /// [`BackgroundPanicPolicy::MarkErrored`], writes are refused from
+    /// then on.
+    ///
+    /// Default: [`BackgroundPanicPolicy::RestartWorker`].
+    pub background_panic_policy: BackgroundPanicPolicy,
+
+    /// Source of the timestamps stamped on writes and SSTables, and of the
+    /// current time for memtable ages, tombstone grace, periodic
+    /// compaction, and idempotency token retention.
+    ///
+    /// A [`MockClock`] lets tests and simulations control time. Timestamps
+    /// written under a clock persist, so reopening a database with a clock
+    /// far from the one it was written with ages its data accordingly.
+    ///
+    /// Default: [`SystemClock`].
+    pub clock: Arc<dyn Clock>,
 }
 
 impl Default for DbConfig {
     fn default() -> Self {
         Self {
             write_buffer_size: 64 * 1024,
+            max_memtables_per_flush: 1,
+            max_frozen_memtables: 0,
             compaction_strategy: CompactionStrategyType::Stcs,
             min_compaction_threshold: 4,
             max_compaction_threshold: 32,
@@ -172,54 +884,53 @@ impl Default for DbConfig {
             tombstone_compaction_interval: 0,
             tombstone_bloom_fallback: true,
             tombstone_range_drop: true,
+            tombstone_gc_grace_seconds: 0,
+            periodic_compaction_seconds: 0,
+            read_depth_compaction_threshold: 0,
+            deletion_run_compaction_threshold: 0,
+            hot_sstable_compaction: false,
+            read_fanout_limit: 0,
+            read_fanout_fallback: false,
+            max_memtable_age: 0,
+            max_total_wal_size: 0,
+            wal_retention_bytes: 0,
+            wal_retention_seconds: 0,
+            obsolete_file_grace_seconds: 0,
+            row_cache_size: 0,
+            max_mmap_bytes: 0,
+            page_cache_hints: false,
+            sstable_open_checks: OpenChecks::Metadata,
+            sstable_layout: SstDirLayout::Flat,
+            sstable_naming: SstFileNaming::Id,
+            sstable_file_digest: None,
+            value_checksums: false,
+            idempotency_retention_seconds: 3600,
+            idempotency_max_tokens: 100_000,
+            warm_up_sstables: 0,
             thread_pool_size: 2,
+            background_queue_depth: 16,
+            parallel_sstable_probe: false,
+            wal_recovery_mode: WalRecoveryMode::TolerateCorruptedTail,
+            partitions: 1,
+            trace_sampling_ratio: 1.0,
+            slow_op_threshold: None,
+            adaptive_compaction: None,
+            tenant_prefixes: Vec::new(),
+            event_listeners: Vec::new(),
+            background_panic_policy: BackgroundPanicPolicy::RestartWorker,
+            clock: Arc::new(SystemClock),
         }
     }
 }
 
 impl DbConfig {
-    /// Validates all configuration parameters against their documented bounds.
-    fn validate(&self) -> Result<(), DbError> {
-        if self.write_buffer_size < 1024 || self.write_buffer_size > 256 * 1024 * 1024 {
-            return Err(DbError::InvalidConfig(
-                "write_buffer_size must be in [1024, 268435456]".into(),
-            ));
-        }
-        if self.min_compaction_threshold < 2 || self.min_compaction_threshold > 64 {
-            return Err(DbError::InvalidConfig(
-                "min_compaction_threshold must be in [2, 64]".into(),
-            ));
-        }
-        if self.max_compaction_threshold < self.min_compaction_threshold
-            || self.max_compaction_threshold > 256
-        {
-            return Err(DbError::InvalidConfig(
-                "max_compaction_threshold must be in [min_compaction_threshold, 256]".into(),
-            ));
-        }
-        if self.tombstone_compaction_ratio <= 0.0 || self.tombstone_compaction_ratio > 1.0 {
-            return Err(DbError::InvalidConfig(
-                "tombstone_compaction_ratio must be in (0.0, 1.0]".into(),
-            ));
-        }
-        if self.tombstone_compaction_interval > 604_800 {
-            return Err(DbError::InvalidConfig(
-                "tombstone_compaction_interval must be in [0, 604800]".into(),
-            ));
-        }
-        if self.thread_pool_size < 1 || self.thread_pool_size > 32 {
-            return Err(DbError::InvalidConfig(
-                "thread_pool_size must be in [1, 32]".into(),
-            ));
-        }
-        Ok(())
-    }
-
     /// Converts to the internal engine configuration.
     fn to_engine_config(&self) -> EngineConfig {
         EngineConfig {
             write_buffer_size: self.write_buffer_size,
-            compaction_strategy: self.compaction_strategy,
+            max_memtables_per_flush: self.max_memtables_per_flush,
+            max_frozen_memtables: self.max_frozen_memtables,
+            compaction_strategy: self.compaction_strategy.per_partition(self.partitions),
             bucket_low: 0.5,
             bucket_high: 1.5,
             min_sstable_size: 50,
@@ -229,7 +940,42 @@ impl DbConfig {
             tombstone_compaction_interval: self.tombstone_compaction_interval,
             tombstone_bloom_fallback: self.tombstone_bloom_fallback,
             tombstone_range_drop: self.tombstone_range_drop,
+            tombstone_gc_grace_seconds: self.tombstone_gc_grace_seconds,
+            periodic_compaction_seconds: self.periodic_compaction_seconds,
+            read_depth_compaction_threshold: self.read_depth_compaction_threshold,
+            deletion_run_compaction_threshold: self.deletion_run_compaction_threshold,
+            hot_sstable_compaction: self.hot_sstable_compaction,
+            read_fanout_limit: self.read_fanout_limit,
+            read_fanout_fallback: self.read_fanout_fallback,
             thread_pool_size: self.thread_pool_size,
+            parallel_sstable_probe: self.parallel_sstable_probe,
+            wal_recovery_mode: self.wal_recovery_mode,
+            max_memtable_age: self.max_memtable_age,
+            max_total_wal_size: self.max_total_wal_size,
+            wal_retention_bytes: self.wal_retention_bytes,
+            wal_retention_seconds: self.wal_retention_seconds,
+            obsolete_file_grace_seconds: self.obsolete_file_grace_seconds,
+            row_cache_size: self.row_cache_size / self.partitions as usize,
+            max_mmap_bytes: self.max_mmap_bytes / self.partitions as usize,
+            page_cache_hints: self.page_cache_hints,
+            slow_op_threshold: self.slow_op_threshold,
+            sstable_open_checks: self.sstable_open_checks,
+            sstable_layout: self.sstable_layout,
+            sstable_naming: self.sstable_naming,
+            sstable_file_digest: self.sstable_file_digest,
+            tenant_prefixes: {
+                let mut prefixes: Vec<Vec<u8>> = self
+                    .tenant_prefixes
+                    .iter()
+                    .map(|entry| entry.prefix.clone())
+                    .collect();
+                prefixes.sort();
+                prefixes
+            },
+            value_checksums: self.value_checksums,
+            idempotency_retention_seconds: self.idempotency_retention_seconds,
+            idempotency_max_tokens: self.idempotency_max_tokens,
+            clock: Arc::clone(&self.clock),
         }
     }
 }
@@ -245,9 +991,10 @@ pub enum DbError {
     #[error("database is closed")]
     Closed,
 
-    /// Invalid configuration parameter.
+    /// One or more configuration parameters are out of bounds, alone or
+    /// in combination. Carries every violation found.
     #[error("invalid config: {0}")]
-    InvalidConfig(String),
+    InvalidConfig(ConfigViolations),
 
     /// Key or value constraint violated.
     #[error("invalid argument: {0}")]
@@ -256,17 +1003,60 @@ pub enum DbError {
     /// An engine-internal error occurred.
     #[error("{0}")]
     Engine(#[from] EngineError),
+
+    /// The manifest does not match the files on disk. Returned by
+    /// [`Db::open`] with every issue found.
+    #[error("inconsistent database: {0}")]
+    Inconsistent(ConsistencyReport),
+
+    /// Another handle holds the write lease of the directory. Returned by
+    /// [`Db::open`] with the holder's process id, or `0` if unknown.
+    #[error("write lease held by process {0}")]
+    LeaseHeld(u32),
+
+    /// A background task panicked under
+    /// [`BackgroundPanicPolicy::MarkErrored`]; writes are refused until
+    /// the database is reopened. Carries the panic message.
+    #[error("background task panicked: {0}")]
+    BackgroundPanic(String),
+
+    /// The database is in read-only mode; writes are refused until
+    /// [`Db::set_read_only`] turns it off.
+    #[error("database is read-only")]
+    ReadOnly,
+
+    /// A read passed its [`ReadOptions::deadline`]. A scan returns the
+    /// pairs it read before the deadline in `partial`: the first pairs of
+    /// the result, in key order, so the scan can resume after the last
+    /// key. Empty for point lookups.
+    #[error("read deadline exceeded")]
+    TimedOut {
+        /// Pairs read before the deadline passed.
+        partial: Vec<KeyValue>,
+    },
 }
 
 // ------------------------------------------------------------------------------------------------
 // Background worker state
 // ------------------------------------------------------------------------------------------------
 
-/// Holds the thread pool sender and worker handles.
+/// Holds the worker and timer thread handles.
 /// Taken (`Option::take`) on shutdown to ensure single cleanup.
 struct BackgroundPool {
-    sender: crossbeam::channel::Sender<Box<dyn FnOnce() + Send>>,
     workers: Vec<thread::JoinHandle<()>>,
+    /// Periodic-compaction and memtable-age timers.
+    tickers: Vec<Ticker>,
+}
+
+/// A background timer queueing a task every period.
+enum Ticker {
+    /// A timer thread; dropping the sender stops it.
+    Thread(crossbeam::channel::Sender<()>, thread::JoinHandle<()>),
+
+    /// A timer of a simulated database, fired by
+    /// [`Db::tick_background_timers`]: the queue and the task factory.
+    #[cfg(feature = "simulation")]
+    Manual(Job, Box<dyn Fn() -> Task + Send>),
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -285,14 +1075,44 @@ struct BackgroundPool {
 ///
 /// # Background compaction
 ///
-/// When the write buffer fills, the active memtable is frozen and a
+/// When the write buffer fills — or the active memtable outlives
+/// [`DbConfig::max_memtable_age`], or its WAL outgrows
+/// [`DbConfig::max_total_wal_size`] — the active memtable is frozen and a
 /// background task is dispatched to:
 ///
 /// 1. Flush the frozen memtable to a new SSTable.
 /// 2. Run minor compaction if size-tiered thresholds are met.
 /// 3. Run tombstone compaction if the tombstone ratio is high enough.
+/// 4. Run periodic compaction on SSTables older than
+///    [`DbConfig::periodic_compaction_seconds`].
+///
+/// Flushes, minor, tombstone, and major compaction wait in separate
+/// bounded queues, and idle workers serve flushes first; see
+/// [`DbConfig::background_queue_depth`] and [`Db::queue_stats`].
+///
+/// When periodic compaction is enabled, a timer thread also schedules it
+/// independently of writes, so cold data is revisited on an idle database.
+/// Likewise, a timer enforces `max_memtable_age` when no writes arrive,
+/// and another queues compaction when lookups probe more SSTables than
+/// [`DbConfig::read_depth_compaction_threshold`] allows.
+///
+/// Major compaction must be triggered explicitly via [`Db::major_compact`],
+/// or [`Db::major_compact_async`] to run it on the background pool.
+///
+/// Between [`Db::enter_bulk_load`] and [`Db::exit_bulk_load`], flushes
+/// still run but compaction is deferred to the major compaction that
+/// ends the bulk load.
+/// [`Db::set_compaction_paused`] holds background compaction back until
+/// it is resumed; flushes still run.
+///
+/// With feature `simulation`, `Db::open_simulated` starts no background
+/// thread: queued work runs only when the caller asks for it.
+///
+/// # Write lease
 ///
-/// Major compaction must be triggered explicitly via [`Db::major_compact`].
+/// An open `Db` holds the write lease of its directory, so no other
+/// handle — in this or another process — can open it for writing.
+/// Other processes read it through [`ReadOnlyDb`].
 ///
 /// # Shutdown
 ///
@@ -300,8 +1120,27 @@ struct BackgroundPool {
 /// without calling `close`, the destructor will attempt cleanup, but
 /// errors are silently ignored.
 pub struct Db {
-    engine: Engine,
+    engine: PartitionedEngine,
+    sampler: TraceSampler,
+    /// Operations at least this slow are logged; see
+    /// [`DbConfig::slow_op_threshold`].
+    slow_op_threshold: Option<Duration>,
     bg: Mutex<Option<BackgroundPool>>,
+    /// Per-kind queues of the tasks waiting for a background worker.
+    queues: Arc<TaskQueues>,
+    changes: ChangeFeed,
+    lease: Arc<WriteLease>,
+    /// The background major compaction, if one was started.
+    major: Mutex<Option<Arc<MajorState>>>,
+    /// Serializes the commits of batches carrying an idempotency token,
+    /// so two retries of one batch cannot both miss the token.
+    idempotent_writes: Mutex<()>,
+    /// Runs every background task and handles its panics.
+    supervisor: Arc<TaskSupervisor>,
+    /// Maps the per-prefix usage of the engine to tenants.
+    tenants: TenantAccounting,
+    /// Set by [`Db::set_read_only`]; writes are refused while set.
+    read_only: AtomicBool,
     closed: AtomicBool,
 }
 
@@ -322,29 +1161,94 @@ impl Db {
     ///
     /// # Errors
     ///
-    /// - [`DbError::InvalidConfig`] — a configuration parameter is out of
-    ///   its documented bounds.
+    /// - [`DbError::InvalidConfig`] — configuration parameters are out of
+    ///   their documented bounds, alone or in combination, with every
+    ///   violation listed; or `partitions` differs from the value the
+    ///   database was created with.
+    /// - [`DbError::LeaseHeld`] — another handle, in this or another
+    ///   process, has the database open for writing; use
+    ///   [`ReadOnlyDb`] to read it meanwhile.
+    /// - [`DbError::Inconsistent`] — the manifest references SSTables or
+    ///   frozen WALs that are missing or malformed; see
+    ///   [`check_consistency`](Self::check_consistency).
     /// - [`DbError::Engine`] — the directory could not be created, the
-    ///   manifest/WAL could not be opened or replayed, or I/O failed
-    ///   during recovery.
+    ///   manifest/WAL could not be opened or replayed, I/O failed during
+    ///   recovery, or the WALs or SSTables hold LSNs the manifest never
+    ///   reserved, meaning the manifest was rolled back.
     pub fn open(path: impl AsRef<Path>, config: DbConfig) -> Result<Self, DbError> {
+        Self::open_with(path, config, false)
+    }
+
+    /// Opens a database like [`open`](Self::open), but with a
+    /// deterministic manual scheduler in place of the background thread
+    /// pool (feature `simulation`).
+    ///
+    /// No worker or timer thread is started. Flushes and compactions are
+    /// queued as usual but run only on the calling thread, in
+    /// [`run_pending_background_work`](Self::run_pending_background_work),
+    /// and timers fire only in
+    /// [`tick_background_timers`](Self::tick_background_timers). A test
+    /// can thus interleave foreground operations with background jobs in
+    /// a fixed order and reproduce a race without sleeping; background
+    /// spans nest under the caller's span. `thread_pool_size` is ignored.
+    ///
+    /// Flushes are queued past
+    /// [`DbConfig::background_queue_depth`] rather than blocking the
+    /// writer. [`close`](Self::close) runs the tasks still queued. The
+    /// write lease is renewed only when the timers are ticked.
+    ///
+    /// # Errors
+    ///
+    /// As [`open`](Self::open).
+    #[cfg(feature = "simulation")]
+    pub fn open_simulated(path: impl AsRef<Path>, config: DbConfig) -> Result<Self, DbError> {
+        Self::open_with(path, config, true)
+    }
+
+    /// Opens the database with background work run by a thread pool, or
+    /// by the caller when `manual`.
+    fn open_with(path: impl AsRef<Path>, config: DbConfig, manual: bool) -> Result<Self, DbError> {
         config.validate()?;
 
-        let pool_size = config.thread_pool_size;
+        let pool_size = if manual { 0 } else { config.thread_pool_size };
         let engine_config = config.to_engine_config();
-        let engine = Engine::open(&path, engine_config)?;
+        if let Some(existing) = PartitionedEngine::existing_partitions(&path)?
+            && existing != config.partitions
+        {
+            return Err(DbError::InvalidConfig(ConfigViolations::single(
+                &["partitions"],
+                format!(
+                    "database was created with {existing} partitions, opened with {}",
+                    config.partitions
+                ),
+            )));
+        }
+        let lease = Arc::new(WriteLease::acquire(path.as_ref())?);
+        let engine = PartitionedEngine::open(&path, engine_config, config.partitions).map_err(
+            |e| match e {
+                EngineError::Inconsistent(report) => DbError::Inconsistent(report),
+                e => DbError::Engine(e),
+            },
+        )?;
 
         // Spawn background worker thread pool.
-        let (sender, receiver) = crossbeam::channel::unbounded::<Box<dyn FnOnce() + Send>>();
+        let queues = Arc::new(TaskQueues::new(config.background_queue_depth, manual));
+        let listeners: Arc<[Arc<dyn EventListener>]> = config.event_listeners.into();
+        let tenants = TenantAccounting::new(&config.tenant_prefixes, Arc::clone(&config.clock));
+        let supervisor = Arc::new(TaskSupervisor::new(
+            config.background_panic_policy,
+            Arc::clone(&listeners),
+        ));
 
         let mut workers = Vec::with_capacity(pool_size);
         for id in 0..pool_size {
-            let rx = receiver.clone();
+            let queues = Arc::clone(&queues);
+            let supervisor = Arc::clone(&supervisor);
             let handle = thread::Builder::new()
                 .name(format!("aeternusdb-bg-{id}"))
                 .spawn(move || {
-                    while let Ok(task) = rx.recv() {
-                        task();
+                    while let Some(task) = queues.next() {
+                        supervisor.run(task);
                     }
                 })
                 .map_err(|e| {
@@ -354,18 +1258,223 @@ impl Db {
                 })?;
             workers.push(handle);
         }
-        // Workers hold their own receiver clones; drop ours.
-        drop(receiver);
 
-        info!(path = %path.as_ref().display(), pool_size, "database opened");
+        let mut tickers = Vec::new();
+        {
+            let lease = Arc::clone(&lease);
+            tickers.push(Self::spawn_ticker(
+                "aeternusdb-lease",
+                (LEASE_RENEW_SECS * 2) as usize,
+                Arc::clone(&queues),
+                Job::LeaseRenewal,
+                move || {
+                    let lease = Arc::clone(&lease);
+                    Box::new(move || {
+                        lease.renew();
+                    })
+                },
+            )?);
+        }
+        // FIFO drops tables past its TTL on the periodic timer.
+        let periodic_seconds = match config.compaction_strategy {
+            CompactionStrategyType::Fifo { ttl_seconds, .. } => ttl_seconds,
+            CompactionStrategyType::Stcs => config.periodic_compaction_seconds,
+        };
+        if periodic_seconds > 0 {
+            let engine = engine.clone();
+            tickers.push(Self::spawn_ticker(
+                "aeternusdb-periodic",
+                periodic_seconds,
+                Arc::clone(&queues),
+                Job::PeriodicCompaction,
+                move || {
+                    let engine = engine.clone();
+                    Box::new(move || Self::run_periodic_compaction(&engine))
+                },
+            )?);
+        }
+        if config.read_depth_compaction_threshold > 0 {
+            let engine = engine.clone();
+            let task_queues = Arc::clone(&queues);
+            tickers.push(Self::spawn_ticker(
+                "aeternusdb-read-depth",
+                2,
+                Arc::clone(&queues),
+                Job::ReadDepth,
+                move || {
+                    let engine = engine.clone();
+                    let queues = Arc::clone(&task_queues);
+                    Box::new(move || Self::run_read_depth_compaction(&engine, &queues))
+                },
+            )?);
+        }
+        if config.deletion_run_compaction_threshold > 0 {
+            let engine = engine.clone();
+            let task_queues = Arc::clone(&queues);
+            tickers.push(Self::spawn_ticker(
+                "aeternusdb-deletion-runs",
+                2,
+                Arc::clone(&queues),
+                Job::DeletionRuns,
+                move || {
+                    let engine = engine.clone();
+                    let queues = Arc::clone(&task_queues);
+                    Box::new(move || Self::run_deletion_compaction(&engine, &queues))
+                },
+            )?);
+        }
+        if config.obsolete_file_grace_seconds > 0 {
+            let engine = engine.clone();
+            tickers.push(Self::spawn_ticker(
+                "aeternusdb-file-gc",
+                2,
+                Arc::clone(&queues),
+                Job::FileGc,
+                move || {
+                    let engine = engine.clone();
+                    Box::new(move || {
+                        if let Err(e) = engine.purge_obsolete_files(false) {
+                            error!("background obsolete file deletion failed: {e}");
+                        }
+                    })
+                },
+            )?);
+        }
+        if config.read_fanout_limit > 0 && !listeners.is_empty() {
+            let engine = engine.clone();
+            let listeners = Arc::clone(&listeners);
+            tickers.push(Self::spawn_ticker(
+                "aeternusdb-read-fanout",
+                2,
+                Arc::clone(&queues),
+                Job::ReadFanout,
+                move || {
+                    let engine = engine.clone();
+                    let listeners = Arc::clone(&listeners);
+                    Box::new(move || Self::report_read_fanout(&engine, &listeners))
+                },
+            )?);
+        }
+        if !listeners.is_empty() {
+            let engine = engine.clone();
+            let listeners = Arc::clone(&listeners);
+            tickers.push(Self::spawn_ticker(
+                "aeternusdb-write-activity",
+                2,
+                Arc::clone(&queues),
+                Job::WriteActivity,
+                move || {
+                    let engine = engine.clone();
+                    let listeners = Arc::clone(&listeners);
+                    Box::new(move || Self::report_write_activity(&engine, &listeners))
+                },
+            )?);
+        }
+        if config.max_memtable_age > 0 {
+            let engine = engine.clone();
+            let task_queues = Arc::clone(&queues);
+            tickers.push(Self::spawn_ticker(
+                "aeternusdb-memtable-age",
+                config.max_memtable_age,
+                Arc::clone(&queues),
+                Job::MemtableAge,
+                move || {
+                    let engine = engine.clone();
+                    let queues = Arc::clone(&task_queues);
+                    Box::new(move || match engine.freeze_if_due() {
+                        Ok(true) => Self::run_flush(&engine, &queues),
+                        Ok(false) => {}
+                        Err(e) => error!("background memtable age check failed: {e}"),
+                    })
+                },
+            )?);
+        }
+        if let Some(tuning) = config.adaptive_compaction {
+            let engine = engine.clone();
+            let listeners = Arc::clone(&listeners);
+            let windows = Arc::new(Mutex::new(
+                (0..engine.partition_count())
+                    .map(|_| TuningWindow::default())
+                    .collect::<Vec<_>>(),
+            ));
+            let tuning = Arc::new(tuning);
+            let task_queues = Arc::clone(&queues);
+            tickers.push(Self::spawn_ticker(
+                "aeternusdb-tuning",
+                tuning.interval_seconds * 2,
+                Arc::clone(&queues),
+                Job::Tuning,
+                move || {
+                    let engine = engine.clone();
+                    let listeners = Arc::clone(&listeners);
+                    let windows = Arc::clone(&windows);
+                    let tuning = Arc::clone(&tuning);
+                    let queues = Arc::clone(&task_queues);
+                    Box::new(move || {
+                        Self::run_compaction_tuning(&engine, &queues, &windows, &tuning, &listeners)
+                    })
+                },
+            )?);
+        }
+
+        if config.warm_up_sstables > 0 {
+            let engine = engine.clone();
+            let count = config.warm_up_sstables;
+            queues.push(
+                TaskKind::Major,
+                Box::new(move || Self::run_warm_up(&engine, count)),
+            );
+        }
+
+        info!(
+            path = %path.as_ref().display(),
+            pool_size,
+            partitions = config.partitions,
+            "database opened"
+        );
 
         Ok(Self {
             engine,
-            bg: Mutex::new(Some(BackgroundPool { sender, workers })),
+            sampler: TraceSampler::new(config.trace_sampling_ratio),
+            slow_op_threshold: config.slow_op_threshold,
+            bg: Mutex::new(Some(BackgroundPool { workers, tickers })),
+            queues,
+            changes: ChangeFeed::default(),
+            lease,
+            major: Mutex::new(None),
+            idempotent_writes: Mutex::new(()),
+            supervisor,
+            tenants,
+            read_only: AtomicBool::new(false),
             closed: AtomicBool::new(false),
         })
     }
 
+    /// Repairs the database at `path` so that [`open`](Self::open)
+    /// succeeds again after files were damaged.
+    ///
+    /// SSTables that fail to open or fail a data block checksum are
+    /// rebuilt from their readable blocks (see
+    /// [`tools::repair_sstable`]); the damaged originals, SSTables with
+    /// nothing readable left, and the old manifest are moved to `lost/`
+    /// in the affected partition directory. A new manifest then lists the
+    /// surviving SSTables and the WALs still to be replayed. If the old
+    /// manifest is itself unreadable, every SSTable file on disk is used.
+    ///
+    /// Entries in damaged blocks are gone, so the repaired database may
+    /// miss writes, or show values that a lost delete had hidden; the
+    /// returned [`RepairReport`] says what was lost.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::LeaseHeld`] — the database is open for writing.
+    /// - [`DbError::Engine`] — reading the files or writing the repaired
+    ///   SSTables or manifest failed.
+    pub fn repair(path: impl AsRef<Path>) -> Result<RepairReport, DbError> {
+        let _lease = WriteLease::acquire(path.as_ref())?;
+        Ok(PartitionedEngine::repair(&path)?)
+    }
+
     /// Gracefully shuts down the database.
     ///
     /// Waits for all in-flight background tasks to complete, flushes
@@ -384,8 +1493,10 @@ impl Db {
             return Ok(()); // Already closed.
         }
 
+        self.changes.close();
         self.shutdown_pool();
         self.engine.close()?;
+        self.lease.release();
 
         info!("database closed");
         Ok(())
@@ -404,10 +1515,38 @@ impl Db {
     /// # Errors
     ///
     /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::BackgroundPanic`] — a background task panicked
+    ///   under [`BackgroundPanicPolicy::MarkErrored`].
+    /// - [`DbError::ReadOnly`] — the database is in read-only mode; see
+    ///   [`set_read_only`](Self::set_read_only).
     /// - [`DbError::InvalidArgument`] — `key` or `value` is empty.
     /// - [`DbError::Engine`] — WAL write or memtable operation failed.
     pub fn put(&self, key: &[u8], value: &[u8]) -> Result<(), DbError> {
-        self.check_open()?;
+        self.put_opt(key, value, &WriteOptions::default())
+    }
+
+    /// Inserts or updates a key-value pair with the given
+    /// [`WriteOptions`].
+    ///
+    /// With [`WriteOptions::disable_wal`] the write is not logged: it
+    /// survives a crash only if the memtable holding it was flushed to an
+    /// SSTable first, and after a crash reads may return an older value of
+    /// `key`. [`close`](Self::close) — or dropping the handle — flushes
+    /// unlogged writes, so a clean shutdown keeps them. With
+    /// [`WriteOptions::sync`] the WAL is fsynced before returning, even
+    /// during a bulk load.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::BackgroundPanic`] — a background task panicked
+    ///   under [`BackgroundPanicPolicy::MarkErrored`].
+    /// - [`DbError::ReadOnly`] — the database is in read-only mode; see
+    ///   [`set_read_only`](Self::set_read_only).
+    /// - [`DbError::InvalidArgument`] — `key` or `value` is empty.
+    /// - [`DbError::Engine`] — WAL write or memtable operation failed.
+    pub fn put_opt(&self, key: &[u8], value: &[u8], opts: &WriteOptions) -> Result<(), DbError> {
+        self.check_accepts_writes()?;
 
         if key.is_empty() {
             return Err(DbError::InvalidArgument("key must not be empty".into()));
@@ -416,7 +1555,26 @@ impl Db {
             return Err(DbError::InvalidArgument("value must not be empty".into()));
         }
 
-        let frozen = self.engine.put(key.to_vec(), value.to_vec())?;
+        let _slow = self.slow_op("put");
+        let _span = self
+            .sampler
+            .span(|| {
+                debug_span!(
+                    "db.put",
+                    key_hash = key_hash(key),
+                    bytes = key.len() + value.len()
+                )
+            })
+            .entered();
+        let frozen = self.changes.commit(
+            || self.engine.put(key.to_vec(), value.to_vec(), opts),
+            |_| {
+                vec![ChangeEvent::Put {
+                    key: key.to_vec(),
+                    value: value.to_vec(),
+                }]
+            },
+        )?;
         if frozen {
             self.schedule_flush();
         }
@@ -430,16 +1588,47 @@ impl Db {
     /// # Errors
     ///
     /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::BackgroundPanic`] — a background task panicked
+    ///   under [`BackgroundPanicPolicy::MarkErrored`].
+    /// - [`DbError::ReadOnly`] — the database is in read-only mode; see
+    ///   [`set_read_only`](Self::set_read_only).
     /// - [`DbError::InvalidArgument`] — `key` is empty.
     /// - [`DbError::Engine`] — WAL write or memtable operation failed.
     pub fn delete(&self, key: &[u8]) -> Result<(), DbError> {
-        self.check_open()?;
+        self.delete_opt(key, &WriteOptions::default())
+    }
 
-        if key.is_empty() {
-            return Err(DbError::InvalidArgument("key must not be empty".into()));
-        }
+    /// Deletes a key with the given [`WriteOptions`].
+    ///
+    /// An unlogged delete is lost on a crash like an unlogged
+    /// [`put_opt`](Self::put_opt): the key may reappear with its previous
+    /// value.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::BackgroundPanic`] — a background task panicked
+    ///   under [`BackgroundPanicPolicy::MarkErrored`].
+    /// - [`DbError::ReadOnly`] — the database is in read-only mode; see
+    ///   [`set_read_only`](Self::set_read_only).
+    /// - [`DbError::InvalidArgument`] — `key` is empty.
+    /// - [`DbError::Engine`] — WAL write or memtable operation failed.
+    pub fn delete_opt(&self, key: &[u8], opts: &WriteOptions) -> Result<(), DbError> {
+        self.check_accepts_writes()?;
+
+        if key.is_empty() {
+            return Err(DbError::InvalidArgument("key must not be empty".into()));
+        }
 
-        let frozen = self.engine.delete(key.to_vec())?;
+        let _slow = self.slow_op("delete");
+        let _span = self
+            .sampler
+            .span(|| debug_span!("db.delete", key_hash = key_hash(key), bytes = key.len()))
+            .entered();
+        let frozen = self.changes.commit(
+            || self.engine.delete(key.to_vec(), opts),
+            |_| vec![ChangeEvent::Delete { key: key.to_vec() }],
+        )?;
         if frozen {
             self.schedule_flush();
         }
@@ -451,11 +1640,15 @@ impl Db {
     /// # Errors
     ///
     /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::BackgroundPanic`] — a background task panicked
+    ///   under [`BackgroundPanicPolicy::MarkErrored`].
+    /// - [`DbError::ReadOnly`] — the database is in read-only mode; see
+    ///   [`set_read_only`](Self::set_read_only).
     /// - [`DbError::InvalidArgument`] — `start` or `end` is empty, or
     ///   `start >= end`.
     /// - [`DbError::Engine`] — WAL write or memtable operation failed.
     pub fn delete_range(&self, start: &[u8], end: &[u8]) -> Result<(), DbError> {
-        self.check_open()?;
+        self.check_accepts_writes()?;
 
         if start.is_empty() || end.is_empty() {
             return Err(DbError::InvalidArgument(
@@ -468,7 +1661,401 @@ impl Db {
             ));
         }
 
-        let frozen = self.engine.delete_range(start.to_vec(), end.to_vec())?;
+        let _slow = self.slow_op("delete_range");
+        let _span = self
+            .sampler
+            .span(|| {
+                debug_span!(
+                    "db.delete_range",
+                    key_hash = key_hash(start),
+                    bytes = start.len() + end.len()
+                )
+            })
+            .entered();
+        let frozen = self.changes.commit(
+            || self.engine.delete_range(start.to_vec(), end.to_vec()),
+            |_| {
+                vec![ChangeEvent::DeleteRange {
+                    start: start.to_vec(),
+                    end: end.to_vec(),
+                }]
+            },
+        )?;
+        if frozen {
+            self.schedule_flush();
+        }
+        Ok(())
+    }
+
+    /// Deletes every key at or after `start`.
+    ///
+    /// Writes one range tombstone with no upper bound, so it also covers
+    /// keys longer than any end key a caller could choose; subscribers see
+    /// a [`ChangeEvent::DeleteRange`] with an empty `end`.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::BackgroundPanic`] — a background task panicked
+    ///   under [`BackgroundPanicPolicy::MarkErrored`].
+    /// - [`DbError::ReadOnly`] — the database is in read-only mode; see
+    ///   [`set_read_only`](Self::set_read_only).
+    /// - [`DbError::InvalidArgument`] — `start` is empty.
+    /// - [`DbError::Engine`] — WAL write or memtable operation failed.
+    pub fn delete_range_from(&self, start: &[u8]) -> Result<(), DbError> {
+        self.check_accepts_writes()?;
+
+        if start.is_empty() {
+            return Err(DbError::InvalidArgument(
+                "start key must not be empty".into(),
+            ));
+        }
+
+        let _slow = self.slow_op("delete_range");
+        let _span = self
+            .sampler
+            .span(|| {
+                debug_span!(
+                    "db.delete_range",
+                    key_hash = key_hash(start),
+                    bytes = start.len()
+                )
+            })
+            .entered();
+        let frozen = self.changes.commit(
+            || self.engine.delete_range_from(start.to_vec()),
+            |_| {
+                vec![ChangeEvent::DeleteRange {
+                    start: start.to_vec(),
+                    end: Vec::new(),
+                }]
+            },
+        )?;
+        if frozen {
+            self.schedule_flush();
+        }
+        Ok(())
+    }
+
+    /// Deletes every key in the database, as
+    /// [`delete_range_from`](Self::delete_range_from) from the smallest
+    /// possible key.
+    ///
+    /// The space is reclaimed as compaction drops the covered versions.
+    ///
+    /// # Errors
+    ///
+    /// As for [`delete_range_from`](Self::delete_range_from).
+    pub fn delete_all(&self) -> Result<(), DbError> {
+        self.delete_range_from(&[0x00])
+    }
+
+    /// Drops every key at once, reclaiming the space immediately.
+    ///
+    /// Unlike [`delete_all`](Self::delete_all), nothing is written: all
+    /// SSTables and WAL segments are deleted and the memtables replaced
+    /// by empty ones. The manifest records the empty state in one entry
+    /// and is then checkpointed, so a crash leaves either the old or the
+    /// empty database. With several partitions each is cleared atomically
+    /// on its own, one after the other. Subscribers see a
+    /// [`ChangeEvent::DeleteRange`] with an empty `start` and `end`.
+    ///
+    /// Scans opened before still read the dropped data. Flushed WAL
+    /// segments kept for
+    /// [`wal_retention_bytes`](DbConfig::wal_retention_bytes) stay until
+    /// they fall outside the limits.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::BackgroundPanic`] — a background task panicked
+    ///   under [`BackgroundPanicPolicy::MarkErrored`].
+    /// - [`DbError::ReadOnly`] — the database is in read-only mode; see
+    ///   [`set_read_only`](Self::set_read_only).
+    /// - [`DbError::Engine`] — the new WAL could not be created or the
+    ///   manifest could not be written.
+    pub fn clear(&self) -> Result<(), DbError> {
+        self.check_accepts_writes()?;
+
+        let _span = info_span!("db.clear").entered();
+        self.changes.commit(
+            || self.engine.clear(),
+            |_| {
+                vec![ChangeEvent::DeleteRange {
+                    start: Vec::new(),
+                    end: Vec::new(),
+                }]
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Atomically replaces the value of `key` if it currently equals
+    /// `expected`.
+    ///
+    /// `expected` of `None` requires the key to be absent (never written,
+    /// deleted, or range-deleted); `new` of `None` deletes the key. The
+    /// check and the write run under the write lock of the key's
+    /// partition, so no other write to that partition can land between
+    /// them.
+    ///
+    /// Returns [`CasOutcome::Swapped`] if the write was applied, or
+    /// [`CasOutcome::Mismatch`] with the value actually found; a mismatch
+    /// writes nothing and publishes no [`ChangeEvent`].
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::BackgroundPanic`] — a background task panicked
+    ///   under [`BackgroundPanicPolicy::MarkErrored`].
+    /// - [`DbError::ReadOnly`] — the database is in read-only mode; see
+    ///   [`set_read_only`](Self::set_read_only).
+    /// - [`DbError::InvalidArgument`] — `key`, `expected`, or `new` is
+    ///   empty.
+    /// - [`DbError::Engine`] — SSTable read, WAL write, or memtable
+    ///   operation failed.
+    pub fn compare_and_swap(
+        &self,
+        key: &[u8],
+        expected: Option<&[u8]>,
+        new: Option<&[u8]>,
+    ) -> Result<CasOutcome, DbError> {
+        self.check_accepts_writes()?;
+
+        if key.is_empty() {
+            return Err(DbError::InvalidArgument("key must not be empty".into()));
+        }
+        if expected.is_some_and(<[u8]>::is_empty) || new.is_some_and(<[u8]>::is_empty) {
+            return Err(DbError::InvalidArgument("value must not be empty".into()));
+        }
+
+        let _span = self
+            .sampler
+            .span(|| {
+                debug_span!(
+                    "db.compare_and_swap",
+                    key_hash = key_hash(key),
+                    bytes = key.len() + new.map_or(0, <[u8]>::len)
+                )
+            })
+            .entered();
+        self.swap(key, expected, new)
+    }
+
+    /// Atomically adds `delta` to the counter stored at `key` and returns
+    /// the new value. Pass a negative `delta` to decrement.
+    ///
+    /// A counter is stored as an 8-byte little-endian two's complement
+    /// `i64` — read it back with `i64::from_le_bytes`. An absent key
+    /// counts as `0`. The update is a [`compare_and_swap`](Self::compare_and_swap)
+    /// retried until no concurrent write intervenes, so concurrent
+    /// increments never lose an update.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::BackgroundPanic`] — a background task panicked
+    ///   under [`BackgroundPanicPolicy::MarkErrored`].
+    /// - [`DbError::ReadOnly`] — the database is in read-only mode; see
+    ///   [`set_read_only`](Self::set_read_only).
+    /// - [`DbError::InvalidArgument`] — `key` is empty, its value is not
+    ///   an 8-byte counter, or the sum overflows `i64`; nothing is
+    ///   written.
+    /// - [`DbError::Engine`] — SSTable read, WAL write, or memtable
+    ///   operation failed.
+    pub fn increment(&self, key: &[u8], delta: i64) -> Result<i64, DbError> {
+        self.check_accepts_writes()?;
+
+        if key.is_empty() {
+            return Err(DbError::InvalidArgument("key must not be empty".into()));
+        }
+
+        let _span = self
+            .sampler
+            .span(|| debug_span!("db.increment", key_hash = key_hash(key), delta))
+            .entered();
+        let mut current = self.engine.get(key.to_vec())?;
+        loop {
+            let count = match current.as_deref() {
+                None => 0,
+                Some(bytes) => i64::from_le_bytes(bytes.try_into().map_err(|_| {
+                    DbError::InvalidArgument(format!(
+                        "value is not an 8-byte counter ({} bytes)",
+                        bytes.len()
+                    ))
+                })?),
+            };
+            let next = count
+                .checked_add(delta)
+                .ok_or_else(|| DbError::InvalidArgument("counter overflow".into()))?;
+            match self.swap(key, current.as_deref(), Some(next.to_le_bytes().as_slice()))? {
+                CasOutcome::Swapped => return Ok(next),
+                CasOutcome::Mismatch(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Commits every operation buffered in `batch`.
+    ///
+    /// All operations are validated before anything is written, so an
+    /// invalid operation rejects the whole batch. The batch is then applied
+    /// under a single engine write lock, so it never interleaves with other
+    /// writers; reads take no lock and may observe a commit in progress.
+    /// With [`DbConfig::partitions`] above one, each partition's share of
+    /// the batch is applied under that partition's lock in turn.
+    /// Each operation is still logged as its own WAL record, so a crash
+    /// midway through the commit may persist only a prefix of the batch.
+    ///
+    /// A batch tagged with
+    /// [`WriteBatchWithIndex::set_idempotency_token`] whose token is
+    /// already recorded is skipped, and `Ok` returned. Otherwise the token
+    /// is recorded in the manifest once the batch is durable, so a crash
+    /// in between leaves the token unrecorded and a retry applies the
+    /// batch again — safe for puts and deletes, which land on the same
+    /// values.
+    ///
+    /// The batch is left untouched; call
+    /// [`WriteBatchWithIndex::clear`] to reuse it.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::InvalidArgument`] — an operation has an empty key or
+    ///   value, a range delete has `start >= end`, or the idempotency
+    ///   token is empty or longer than 1024 bytes.
+    /// - [`DbError::BackgroundPanic`] — a background task panicked
+    ///   under [`BackgroundPanicPolicy::MarkErrored`].
+    /// - [`DbError::ReadOnly`] — the database is in read-only mode; see
+    ///   [`set_read_only`](Self::set_read_only).
+    /// - [`DbError::Engine`] — WAL write or memtable operation failed.
+    pub fn write(&self, batch: &WriteBatchWithIndex) -> Result<(), DbError> {
+        self.write_opt(batch, &WriteOptions::default())
+    }
+
+    /// Commits every operation buffered in `batch` with the given
+    /// [`WriteOptions`], applied to each operation.
+    ///
+    /// With [`WriteOptions::disable_wal`] none of the batch is logged and
+    /// a crash before the memtables holding it are flushed loses it; see
+    /// [`put_opt`](Self::put_opt). A batch with an idempotency token
+    /// needs the WAL.
+    ///
+    /// # Errors
+    ///
+    /// As [`write`](Self::write), and [`DbError::InvalidArgument`] if the
+    /// batch has an idempotency token and `disable_wal` is set.
+    pub fn write_opt(
+        &self,
+        batch: &WriteBatchWithIndex,
+        opts: &WriteOptions,
+    ) -> Result<(), DbError> {
+        self.check_accepts_writes()?;
+
+        for op in batch.ops() {
+            match op {
+                batch::WriteOp::Put { key, value } => {
+                    if key.is_empty() {
+                        return Err(DbError::InvalidArgument("key must not be empty".into()));
+                    }
+                    if value.is_empty() {
+                        return Err(DbError::InvalidArgument("value must not be empty".into()));
+                    }
+                }
+                batch::WriteOp::Delete { key } => {
+                    if key.is_empty() {
+                        return Err(DbError::InvalidArgument("key must not be empty".into()));
+                    }
+                }
+                batch::WriteOp::DeleteRange { start, end } => {
+                    if start.is_empty() || end.is_empty() {
+                        return Err(DbError::InvalidArgument(
+                            "start and end keys must not be empty".into(),
+                        ));
+                    }
+                    if start >= end {
+                        return Err(DbError::InvalidArgument(
+                            "start must be less than end".into(),
+                        ));
+                    }
+                }
+            }
+        }
+
+        let Some(token) = batch.idempotency_token() else {
+            return self.commit_batch(batch, opts);
+        };
+        if token.is_empty() || token.len() > batch::MAX_IDEMPOTENCY_TOKEN_LEN {
+            return Err(DbError::InvalidArgument(format!(
+                "idempotency token must be 1 to {} bytes long",
+                batch::MAX_IDEMPOTENCY_TOKEN_LEN
+            )));
+        }
+        if opts.disable_wal {
+            return Err(DbError::InvalidArgument(
+                "a batch with an idempotency token cannot skip the WAL".into(),
+            ));
+        }
+
+        let _guard = self.idempotent_writes.lock().unwrap();
+        if self.engine.has_applied(token)? {
+            debug!("batch skipped, idempotency token already applied");
+            return Ok(());
+        }
+        self.commit_batch(batch, opts)?;
+        self.engine.record_applied(token)?;
+        Ok(())
+    }
+
+    /// Returns whether a batch tagged with the idempotency `token` was
+    /// committed within
+    /// [`DbConfig::idempotency_retention_seconds`].
+    ///
+    /// A consumer recovering from a crash can ask before redoing side
+    /// effects tied to the batch. A token older than the retention
+    /// window, or pushed out by
+    /// [`DbConfig::idempotency_max_tokens`] newer ones, reads as not
+    /// applied.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::InvalidArgument`] — `token` is empty.
+    /// - [`DbError::Engine`] — the manifest could not be read.
+    pub fn has_applied(&self, token: &[u8]) -> Result<bool, DbError> {
+        self.check_open()?;
+
+        if token.is_empty() {
+            return Err(DbError::InvalidArgument("token must not be empty".into()));
+        }
+
+        Ok(self.engine.has_applied(token)?)
+    }
+
+    /// Applies the validated operations of `batch`.
+    fn commit_batch(
+        &self,
+        batch: &WriteBatchWithIndex,
+        opts: &WriteOptions,
+    ) -> Result<(), DbError> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let _slow = self.slow_op("write");
+        let _span = self
+            .sampler
+            .span(|| debug_span!("db.write", ops = batch.len()))
+            .entered();
+        let frozen = self.changes.commit(
+            || self.engine.write_batch(batch.ops(), opts),
+            |_| {
+                batch
+                    .ops()
+                    .iter()
+                    .map(batch::WriteOp::to_change_event)
+                    .collect()
+            },
+        )?;
         if frozen {
             self.schedule_flush();
         }
@@ -482,6 +2069,8 @@ impl Db {
     /// Retrieves the value associated with a key.
     ///
     /// Returns `Ok(None)` if the key does not exist or has been deleted.
+    /// Of several versions sharing the highest LSN, the one picked follows
+    /// the [version precedence](crate#version-precedence) rules.
     ///
     /// # Errors
     ///
@@ -495,13 +2084,141 @@ impl Db {
             return Err(DbError::InvalidArgument("key must not be empty".into()));
         }
 
-        Ok(self.engine.get(key.to_vec())?)
+        let _slow = self.slow_op("get");
+        let span = self.get_span(key);
+        let _enter = span.enter();
+        let value = self.engine.get(key.to_vec())?;
+        span.record("bytes", value.as_ref().map_or(0, Vec::len));
+        Ok(value)
+    }
+
+    /// Retrieves the value associated with a key using explicit
+    /// [`ReadOptions`].
+    ///
+    /// Behaves like [`get`](Self::get). With
+    /// `verify_checksums: false`, SSTable data blocks are not
+    /// CRC-checked, which lowers latency on hot read paths at the cost of
+    /// not detecting on-disk corruption. With a
+    /// [`deadline`](ReadOptions::deadline), a lookup that must still probe
+    /// an SSTable after it has passed gives up.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::InvalidArgument`] — `key` is empty.
+    /// - [`DbError::TimedOut`] — the deadline passed; `partial` is empty.
+    /// - [`DbError::Engine`] — SSTable read or I/O failed.
+    pub fn get_opt(&self, key: &[u8], opts: &ReadOptions) -> Result<Option<Vec<u8>>, DbError> {
+        self.check_open()?;
+
+        if key.is_empty() {
+            return Err(DbError::InvalidArgument("key must not be empty".into()));
+        }
+
+        let _slow = self.slow_op("get");
+        let span = self.get_span(key);
+        let _enter = span.enter();
+        let value = self
+            .engine
+            .get_opt(key.to_vec(), opts)
+            .map_err(Self::read_error)?;
+        span.record("bytes", value.as_ref().map_or(0, Vec::len));
+        Ok(value)
+    }
+
+    /// Retrieves the value associated with a key without copying it out of
+    /// on-disk storage.
+    ///
+    /// Values read from SSTables are returned as a [`PinnedSlice`] that
+    /// borrows the SSTable's memory map; values still in a memtable are
+    /// returned as owned copies. A pinned slice keeps its SSTable mapped
+    /// until dropped, so avoid holding many of them long-term.
+    ///
+    /// Returns `Ok(None)` if the key does not exist or has been deleted.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::InvalidArgument`] — `key` is empty.
+    /// - [`DbError::Engine`] — SSTable read or I/O failed.
+    pub fn get_pinned(&self, key: &[u8]) -> Result<Option<PinnedSlice>, DbError> {
+        self.check_open()?;
+
+        if key.is_empty() {
+            return Err(DbError::InvalidArgument("key must not be empty".into()));
+        }
+
+        let _slow = self.slow_op("get");
+        let span = self.get_span(key);
+        let _enter = span.enter();
+        let value = self.engine.get_pinned(key.to_vec())?;
+        span.record("bytes", value.as_ref().map_or(0, |v| v.len()));
+        Ok(value)
+    }
+
+    /// Retrieves the value associated with a key together with the LSN and
+    /// timestamp of the write that stored it.
+    ///
+    /// Every later write to the key — put, delete, or range delete —
+    /// carries a higher LSN, so an application can implement optimistic
+    /// concurrency by reading an [`Entry`], and later checking that
+    /// `get_entry` still reports the same `lsn` before it writes.
+    /// LSNs are only comparable for the same key: with
+    /// [`DbConfig::partitions`] above one, keys in different partitions
+    /// are numbered independently.
+    ///
+    /// Returns `Ok(None)` if the key does not exist or has been deleted.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::InvalidArgument`] — `key` is empty.
+    /// - [`DbError::Engine`] — SSTable read or I/O failed.
+    pub fn get_entry(&self, key: &[u8]) -> Result<Option<Entry>, DbError> {
+        self.check_open()?;
+
+        if key.is_empty() {
+            return Err(DbError::InvalidArgument("key must not be empty".into()));
+        }
+
+        let _slow = self.slow_op("get");
+        let span = self.get_span(key);
+        let _enter = span.enter();
+        let entry = self
+            .engine
+            .get_entry(key.to_vec(), &ReadOptions::default())?;
+        span.record("bytes", entry.as_ref().map_or(0, |e| e.value.len()));
+        Ok(entry)
+    }
+
+    /// Returns the highest LSN assigned so far.
+    ///
+    /// Every committed write has an LSN at or below it, and every later
+    /// write a higher one, so it marks a point in the write history — for
+    /// example, for a consumer to remember how far it has read. LSNs are
+    /// reserved in the manifest before they are assigned, and reopening
+    /// resumes above the last reservation, so the value never moves
+    /// backwards, even if a WAL was lost; it may jump forward across a
+    /// reopen.
+    ///
+    /// With [`DbConfig::partitions`] above one, each partition numbers its
+    /// writes on its own and this is the highest of them: the guarantees
+    /// above hold within a partition, but a later write to another
+    /// partition may get a lower LSN.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    pub fn latest_lsn(&self) -> Result<u64, DbError> {
+        self.check_open()?;
+        Ok(self.engine.latest_lsn()?)
     }
 
     /// Scans all live key-value pairs in the half-open range `[start, end)`.
     ///
     /// Returns pairs sorted by key in ascending order. Deleted keys
-    /// are excluded.
+    /// are excluded. Each key resolves to the same version as
+    /// [`get`](Self::get).
     ///
     /// Returns an empty `Vec` if the range contains no live keys.
     ///
@@ -522,95 +2239,1759 @@ impl Db {
             return Ok(Vec::new());
         }
 
+        let _slow = self.slow_op("scan");
+        let span = self.scan_span(start);
+        let _enter = span.enter();
         let results: Vec<_> = self.engine.scan(start, end)?.collect();
+        Self::record_scan(&span, &results);
         Ok(results)
     }
 
-    // --------------------------------------------------------------------------------------------
-    // Compaction
-    // --------------------------------------------------------------------------------------------
-
-    /// Runs a full **major compaction**, merging all SSTables into one.
+    /// Scans the half-open range `[start, end)` using explicit
+    /// [`ReadOptions`].
     ///
-    /// This is a **blocking** operation. All range tombstones are applied
-    /// and all spent tombstones are dropped from the output.
+    /// Behaves like [`scan`](Self::scan). With
+    /// `verify_checksums: false`, SSTable data blocks are not CRC-checked;
+    /// with `prefix_same_as_start: true`, only keys starting with `start`
+    /// are returned.
     ///
-    /// Returns `true` if compaction was performed, `false` if there
-    /// were fewer than 2 SSTables.
+    /// With a [`deadline`](ReadOptions::deadline), the partitions are
+    /// merged pair by pair and the deadline is checked before each pair.
+    /// Once it has passed, the scan stops with [`DbError::TimedOut`]
+    /// carrying the pairs read so far — a prefix of the full result — so
+    /// the caller can serve them, or resume from just past the last key.
     ///
     /// # Errors
     ///
     /// - [`DbError::Closed`] — the database has been closed.
-    /// - [`DbError::Engine`] — SSTable merge, manifest update, or I/O
-    ///   failed during compaction.
-    pub fn major_compact(&self) -> Result<bool, DbError> {
+    /// - [`DbError::InvalidArgument`] — `start` or `end` is empty.
+    /// - [`DbError::TimedOut`] — the deadline passed before the scan
+    ///   completed.
+    /// - [`DbError::Engine`] — SSTable read or I/O failed.
+    pub fn scan_opt(
+        &self,
+        start: &[u8],
+        end: &[u8],
+        opts: &ReadOptions,
+    ) -> Result<Vec<KeyValue>, DbError> {
         self.check_open()?;
-        Ok(self.engine.major_compact()?)
-    }
 
-    // --------------------------------------------------------------------------------------------
-    // Internal helpers
-    // --------------------------------------------------------------------------------------------
-
-    /// Returns `Err(DbError::Closed)` if the database has been closed.
-    fn check_open(&self) -> Result<(), DbError> {
-        if self.closed.load(Ordering::Acquire) {
-            return Err(DbError::Closed);
+        if start.is_empty() || end.is_empty() {
+            return Err(DbError::InvalidArgument(
+                "start and end keys must not be empty".into(),
+            ));
+        }
+        if start >= end {
+            return Ok(Vec::new());
         }
-        Ok(())
-    }
 
-    /// Dispatches a background task to flush the oldest frozen memtable
-    /// and run minor + tombstone compaction.
-    fn schedule_flush(&self) {
-        let guard = self.bg.lock().unwrap();
-        if let Some(bg) = guard.as_ref() {
-            let engine = self.engine.clone();
-            let _ = bg.sender.send(Box::new(move || {
-                // 1. Flush oldest frozen memtable to SSTable.
-                match engine.flush_oldest_frozen() {
-                    Ok(true) => debug!("background: flushed frozen memtable"),
-                    Ok(false) => return,
-                    Err(e) => {
-                        error!("background flush failed: {e}");
-                        return;
-                    }
-                }
+        let _slow = self.slow_op("scan");
+        let span = self.scan_span(start);
+        let _enter = span.enter();
+        let results = match opts.deadline {
+            None => self.engine.scan_opt(start, end, opts)?.collect(),
+            Some(deadline) => self.scan_until(start, end, opts, deadline)?,
+        };
+        Self::record_scan(&span, &results);
+        Ok(results)
+    }
 
-                // 2. Minor compaction — loop until no bucket meets threshold.
-                loop {
-                    match engine.minor_compact() {
-                        Ok(true) => debug!("background: minor compaction round"),
-                        Ok(false) => break,
-                        Err(e) => {
-                            error!("background minor compaction failed: {e}");
-                            break;
-                        }
-                    }
-                }
+    /// Returns the first `page_size` live pairs in `[start, end)` and the
+    /// start key of the next page, for paginating a range across requests.
+    ///
+    /// Each call reads from a fresh snapshot through the lazy scan
+    /// iterator, stopping one pair past the page, so memory stays bounded
+    /// by the page size and no iterator outlives the call. Pages are not
+    /// one consistent snapshot: a key written behind the cursor between
+    /// calls is not seen, and one written ahead of it is.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::InvalidArgument`] — `start` or `end` is empty, or
+    ///   `page_size` is `0`.
+    /// - [`DbError::Engine`] — SSTable read or I/O failed.
+    pub fn scan_paged(
+        &self,
+        start: &[u8],
+        end: &[u8],
+        page_size: usize,
+    ) -> Result<ScanPage, DbError> {
+        self.check_open()?;
 
-                // 3. Tombstone compaction — single pass.
-                match engine.tombstone_compact() {
-                    Ok(true) => debug!("background: tombstone compaction"),
-                    Ok(false) => {}
-                    Err(e) => {
-                        error!("background tombstone compaction failed: {e}");
-                    }
-                }
-            }));
+        if start.is_empty() || end.is_empty() {
+            return Err(DbError::InvalidArgument(
+                "start and end keys must not be empty".into(),
+            ));
         }
-    }
-
-    /// Drains the background task queue and joins all worker threads.
-    fn shutdown_pool(&self) {
-        if let Some(bg) = self.bg.lock().unwrap().take() {
-            // Drop sender → workers drain remaining tasks then exit.
-            drop(bg.sender);
-            for worker in bg.workers {
-                let _ = worker.join();
-            }
+        if page_size == 0 {
+            return Err(DbError::InvalidArgument(
+                "page_size must be at least 1".into(),
+            ));
+        }
+        if start >= end {
+            return Ok(ScanPage::default());
         }
+
+        let _slow = self.slow_op("scan");
+        let span = self.scan_span(start);
+        let _enter = span.enter();
+        let mut pairs = self
+            .engine
+            .scan_streaming(start, end, &ReadOptions::default())?;
+        let page: Vec<_> = pairs.by_ref().take(page_size).collect();
+        let next_start = pairs.next().map(|(key, _)| key);
+        Self::record_scan(&span, &page);
+        Ok(ScanPage {
+            pairs: page,
+            next_start,
+        })
     }
+
+    /// Returns the live key-value pairs in `[start, end)` that were written
+    /// at or after `since`, in key order.
+    ///
+    /// `since` is a UNIX timestamp in nanoseconds, compared against the
+    /// commit time of each key's current value; keys deleted since are not
+    /// reported. SSTables record the oldest and newest write of every data
+    /// block, so blocks holding only older writes are skipped unread —
+    /// incremental exports of recent changes read little more than the
+    /// changed data. Commit times come from [`DbConfig::clock`] and never
+    /// run backwards: a write made after the clock is stepped back still
+    /// gets a later commit time than every earlier write, so it is not
+    /// missed by a `since` taken before it.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::InvalidArgument`] — `start` or `end` is empty.
+    /// - [`DbError::Engine`] — SSTable read or I/O failed.
+    pub fn scan_since(
+        &self,
+        start: &[u8],
+        end: &[u8],
+        since: u64,
+    ) -> Result<Vec<KeyValue>, DbError> {
+        self.check_open()?;
+
+        if start.is_empty() || end.is_empty() {
+            return Err(DbError::InvalidArgument(
+                "start and end keys must not be empty".into(),
+            ));
+        }
+        if start >= end {
+            return Ok(Vec::new());
+        }
+
+        let _slow = self.slow_op("scan");
+        let span = self.scan_span(start);
+        let _enter = span.enter();
+        let results: Vec<_> = self
+            .engine
+            .scan_since(start, end, since, &ReadOptions::default())?
+            .collect();
+        Self::record_scan(&span, &results);
+        Ok(results)
+    }
+
+    /// Counts the live keys in the half-open range `[start, end)`.
+    ///
+    /// Counts exactly the keys [`scan`](Self::scan) would return, without
+    /// reading their values into memory. An SSTable lying inside the range
+    /// whose keys no newer write or other SSTable touches — no deletes,
+    /// no overlapping memtable entries — is counted from its properties
+    /// without reading its data blocks, so counting a range that has been
+    /// [major-compacted](Self::major_compact) costs little more than its
+    /// metadata. SSTables written by earlier releases are always read.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::InvalidArgument`] — `start` or `end` is empty.
+    /// - [`DbError::Engine`] — SSTable read or I/O failed.
+    pub fn count(&self, start: &[u8], end: &[u8]) -> Result<u64, DbError> {
+        self.check_open()?;
+
+        if start.is_empty() || end.is_empty() {
+            return Err(DbError::InvalidArgument(
+                "start and end keys must not be empty".into(),
+            ));
+        }
+        if start >= end {
+            return Ok(0);
+        }
+
+        let _slow = self.slow_op("count");
+        let span = self.scan_span(start);
+        let _enter = span.enter();
+        let count = self.engine.count(start, end)?;
+        span.record("results", count);
+        Ok(count)
+    }
+
+    /// Streams every live pair in `[start, end)` to `writer` in the
+    /// [`ExportFormat`] chosen in `options`, for bulk exports such as a
+    /// nightly load into a warehouse.
+    ///
+    /// The scan runs on the background pool and reads one snapshot per
+    /// partition, taken when the task starts: writes made during the
+    /// export are not included, and compactions may proceed. Encoded
+    /// chunks are handed to the calling thread, which writes them to
+    /// `writer` and blocks until the export is complete, so memory use is
+    /// bounded regardless of the range size. With
+    /// [`ExportOptions::max_bytes_per_sec`] set, the scan is paced to that
+    /// output rate. The export occupies one background worker while it
+    /// runs; with [`DbConfig::thread_pool_size`] at `1`, flushes wait
+    /// for it.
+    ///
+    /// The formats are documented on [`ExportFormat`]. The text formats
+    /// stop with [`DbError::InvalidArgument`] at the first key or value
+    /// that is not UTF-8, after writing every pair before it.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::InvalidArgument`] — `start` or `end` is empty, or a
+    ///   text format met non-UTF-8 data.
+    /// - [`DbError::Engine`] — SSTable read failed, or writing to
+    ///   `writer` failed.
+    pub fn export_range_to_writer(
+        &self,
+        start: &[u8],
+        end: &[u8],
+        writer: &mut impl std::io::Write,
+        options: &ExportOptions,
+    ) -> Result<ExportReport, DbError> {
+        self.check_open()?;
+
+        if start.is_empty() || end.is_empty() {
+            return Err(DbError::InvalidArgument(
+                "start and end keys must not be empty".into(),
+            ));
+        }
+
+        let (sender, receiver) = export::channel();
+        let engine = self.engine.clone();
+        let (start, end) = (start.to_vec(), end.to_vec());
+        let options = *options;
+        let task: Task = Box::new(move || {
+            let _span = info_span!("db.export", format = ?options.format).entered();
+            let encoder = export::ExportEncoder::new(&options, sender.clone());
+            if start >= end {
+                encoder.run(std::iter::empty());
+                return;
+            }
+            let opts = ReadOptions {
+                verify_checksums: options.verify_checksums,
+                ..ReadOptions::default()
+            };
+            match engine.scan_streaming(&start, &end, &opts) {
+                Ok(pairs) => encoder.run(pairs),
+                Err(e) => {
+                    let _ = sender.send(export::ExportMessage::Failed(e.into()));
+                }
+            }
+        });
+
+        self.submit(TaskKind::Major, task)?;
+        let report = export::drain(receiver, writer)?;
+        info!(
+            records = report.records,
+            bytes = report.bytes,
+            "export complete"
+        );
+        Ok(report)
+    }
+
+    /// Imports the pairs in `reader`, encoded in the [`ExportFormat`]
+    /// chosen in `options` — typically the output of
+    /// [`export_range_to_writer`](Self::export_range_to_writer) — for
+    /// migrating data between environments.
+    ///
+    /// Pairs are committed in batches of [`ImportOptions::batch_size`]. If
+    /// the import fails, every earlier batch is written and none of the
+    /// failing one. Keys that already hold a value are handled by
+    /// [`ImportOptions::conflict`], checked just before each batch is
+    /// committed; a concurrent write to the same key may land between the
+    /// check and the commit. Within one batch, a repeated key keeps its
+    /// last value.
+    ///
+    /// With [`ImportOptions::ingest_sorted`], a batch whose keys are
+    /// strictly ascending is written directly as one SSTable per
+    /// partition: pending writes are flushed first, then the SSTable is
+    /// installed as the newest layer without touching the WAL or memtable.
+    /// Use large batches, as each one adds an SSTable for compaction to
+    /// merge. Either way, subscribers receive a [`ChangeEvent::Put`] per
+    /// written pair.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::InvalidArgument`] — `batch_size` is `0`, the input is
+    ///   malformed or has an empty key or value, or a key already exists
+    ///   under [`ConflictPolicy::Fail`].
+    /// - [`DbError::BackgroundPanic`] — a background task panicked
+    ///   under [`BackgroundPanicPolicy::MarkErrored`].
+    /// - [`DbError::ReadOnly`] — the database is in read-only mode; see
+    ///   [`set_read_only`](Self::set_read_only).
+    /// - [`DbError::Engine`] — reading `reader` failed, or a write or
+    ///   SSTable build failed.
+    pub fn import_from_reader(
+        &self,
+        reader: impl std::io::Read,
+        options: &ImportOptions,
+    ) -> Result<ImportReport, DbError> {
+        self.check_accepts_writes()?;
+
+        if options.batch_size == 0 {
+            return Err(DbError::InvalidArgument(
+                "batch_size must be at least 1".into(),
+            ));
+        }
+
+        let _span = info_span!("db.import", format = ?options.format).entered();
+        let started = std::time::Instant::now();
+        let mut report = ImportReport::default();
+        let mut batch = Vec::new();
+        for pair in import::Records::new(reader, options.format) {
+            batch.push(pair?);
+            report.records += 1;
+            if batch.len() == options.batch_size {
+                self.import_batch(&mut batch, options, &mut report)?;
+            }
+        }
+        self.import_batch(&mut batch, options, &mut report)?;
+        report.duration = started.elapsed();
+
+        if report.ingested > 0 && !self.engine.defers_compaction() {
+            let guard = self.bg.lock().unwrap();
+            if guard.is_some() {
+                Self::schedule_compactions(&self.engine, &self.queues);
+            }
+        }
+        info!(
+            records = report.records,
+            written = report.written,
+            ingested = report.ingested,
+            skipped = report.skipped,
+            "import complete"
+        );
+        Ok(report)
+    }
+
+    /// Applies the conflict policy to `batch`, commits the remaining
+    /// pairs, and empties it.
+    fn import_batch(
+        &self,
+        batch: &mut Vec<(Vec<u8>, Vec<u8>)>,
+        options: &ImportOptions,
+        report: &mut ImportReport,
+    ) -> Result<(), DbError> {
+        self.check_accepts_writes()?;
+
+        if options.conflict != ConflictPolicy::Overwrite {
+            let mut kept = Vec::with_capacity(batch.len());
+            for (key, value) in batch.drain(..) {
+                if self.engine.get(key.clone())?.is_none() {
+                    kept.push((key, value));
+                } else if options.conflict == ConflictPolicy::Fail {
+                    let hex: String = key.iter().map(|b| format!("{b:02x}")).collect();
+                    return Err(DbError::InvalidArgument(format!(
+                        "key 0x{hex} already exists"
+                    )));
+                } else {
+                    report.skipped += 1;
+                }
+            }
+            *batch = kept;
+        }
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let pairs = std::mem::take(batch);
+        if options.ingest_sorted && pairs.windows(2).all(|w| w[0].0 < w[1].0) {
+            self.changes.commit(
+                || self.engine.ingest_sorted(&pairs),
+                |_| {
+                    pairs
+                        .iter()
+                        .map(|(key, value)| ChangeEvent::Put {
+                            key: key.clone(),
+                            value: value.clone(),
+                        })
+                        .collect()
+                },
+            )?;
+            report.ingested += pairs.len() as u64;
+        } else {
+            let mut writes = WriteBatchWithIndex::new();
+            for (key, value) in &pairs {
+                writes.put(key, value);
+            }
+            self.write(&writes)?;
+        }
+        report.written += pairs.len() as u64;
+        Ok(())
+    }
+
+    /// Reads the SSTable data blocks covering each `[start, end)` range in
+    /// `ranges` into the OS page cache, so that the first lookups after a
+    /// restart do not wait on disk.
+    ///
+    /// SSTables are read through `mmap`; their bloom filters and indexes
+    /// are loaded into memory when the database opens, so only data
+    /// blocks need warming. Block checksums are verified on the way.
+    /// Ranges with `start >= end` are skipped. See
+    /// [`DbConfig::warm_up_sstables`] to warm the newest SSTables
+    /// automatically on open.
+    ///
+    /// Returns the number of bytes read.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::InvalidArgument`] — a `start` or `end` is empty.
+    /// - [`DbError::Engine`] — SSTable read failed or a block checksum
+    ///   did not match.
+    pub fn warm_up(&self, ranges: &[(&[u8], &[u8])]) -> Result<u64, DbError> {
+        self.check_open()?;
+
+        if ranges
+            .iter()
+            .any(|(start, end)| start.is_empty() || end.is_empty())
+        {
+            return Err(DbError::InvalidArgument(
+                "start and end keys must not be empty".into(),
+            ));
+        }
+
+        let mut bytes = 0;
+        for (start, end) in ranges.iter().filter(|(start, end)| start < end) {
+            bytes += self.engine.warm_up(start, end)?;
+        }
+        debug!(ranges = ranges.len(), bytes, "warm-up complete");
+        Ok(bytes)
+    }
+
+    // --------------------------------------------------------------------------------------------
+    // Change notifications
+    // --------------------------------------------------------------------------------------------
+
+    /// Subscribes to committed mutations touching the half-open key range
+    /// `[start, end)`.
+    ///
+    /// Every successful [`put`](Self::put), [`delete`](Self::delete),
+    /// [`delete_range`](Self::delete_range), [`write`](Self::write),
+    /// [`compare_and_swap`](Self::compare_and_swap), and
+    /// [`increment`](Self::increment) that intersects the range is sent
+    /// to the returned receiver as a [`ChangeEvent`], in commit order,
+    /// after it has been written to the WAL. A batch produces one event
+    /// per operation. Writes that fail are not delivered, nor is anything
+    /// written before the subscription.
+    ///
+    /// The channel is unbounded, so a receiver that is never drained
+    /// buffers every matching event. Drop the receiver to unsubscribe; the
+    /// channel is disconnected when the database is closed.
+    ///
+    /// While any subscription is live, writers are serialized across
+    /// partitions to keep events in commit order.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::InvalidArgument`] — `start` or `end` is empty, or
+    ///   `start >= end`.
+    pub fn subscribe(
+        &self,
+        start: &[u8],
+        end: &[u8],
+    ) -> Result<std::sync::mpsc::Receiver<ChangeEvent>, DbError> {
+        self.check_open()?;
+
+        if start.is_empty() || end.is_empty() {
+            return Err(DbError::InvalidArgument(
+                "start and end keys must not be empty".into(),
+            ));
+        }
+        if start >= end {
+            return Err(DbError::InvalidArgument(
+                "start must be less than end".into(),
+            ));
+        }
+
+        Ok(self.changes.subscribe(start, end))
+    }
+
+    // --------------------------------------------------------------------------------------------
+    // Introspection
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns metadata for every live SSTable, newest-first.
+    ///
+    /// Each entry reports the table's id, path, file size, key and LSN
+    /// bounds, record and tombstone counts, tombstone ratio, and creation
+    /// time. Useful for capacity planning and for diagnosing key skew.
+    ///
+    /// With [`DbConfig::partitions`] above one, the tables of all
+    /// partitions are listed in partition order; ids are only unique
+    /// within a partition.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::Engine`] — the manifest could not be read.
+    pub fn sstable_metadata(&self) -> Result<Vec<SSTableMetadata>, DbError> {
+        self.check_open()?;
+        Ok(self.engine.sstable_metadata()?)
+    }
+
+    /// Lists the WAL segments on disk, oldest first, with their role:
+    /// [`Active`](WalFileStatus::Active),
+    /// [`Frozen`](WalFileStatus::Frozen) (awaiting flush), or
+    /// [`Flushed`](WalFileStatus::Flushed).
+    ///
+    /// A flushed segment is deleted as soon as the flush is recorded in
+    /// the manifest, unless [`DbConfig::wal_retention_bytes`] or
+    /// [`DbConfig::wal_retention_seconds`] keeps it. Operators can use this
+    /// to confirm that WAL segments do not leak: without retention, no
+    /// segment should stay `Flushed` across a flush or reopen.
+    ///
+    /// With [`DbConfig::partitions`] above one, the segments of all
+    /// partitions are listed in partition order; ids are only unique
+    /// within a partition.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::Engine`] — the WAL directory could not be read.
+    pub fn wal_files(&self) -> Result<Vec<WalFile>, DbError> {
+        self.check_open()?;
+        Ok(self.engine.wal_files()?)
+    }
+
+    /// Deletes the SSTable files that compactions, [`Db::clear`], and
+    /// [`Db::apply_delta`] removed from the live set, without waiting for
+    /// [`DbConfig::obsolete_file_grace_seconds`].
+    ///
+    /// Files a scan iterator or [`PinnedSlice`] still reads stay on disk
+    /// and are counted in [`PurgeReport::files_pending`]; the file GC
+    /// timer deletes them once the reader is dropped and the grace period
+    /// has passed. A backup tool can call this after copying a directory
+    /// to release the files it held back. With no grace period set,
+    /// removed files are deleted at once and there is nothing to purge.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::Engine`] — an engine lock was poisoned.
+    pub fn purge_obsolete_files(&self) -> Result<PurgeReport, DbError> {
+        self.check_open()?;
+        Ok(self.engine.purge_obsolete_files(true)?)
+    }
+
+    /// Returns bloom filter and block-read counters for point lookups
+    /// since the database was opened, summed across partitions.
+    ///
+    /// Use these to tune bloom filter sizing against a real workload: a
+    /// high [`ReadStats::bloom_false_positive_rate`] means filters are too
+    /// small. Per-SSTable counters are reported in
+    /// [`SSTableMetadata::reads`]. Only [`get`](Self::get)-style lookups
+    /// are counted — scans and compaction are not.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    pub fn read_stats(&self) -> Result<ReadStats, DbError> {
+        self.check_open()?;
+        Ok(self.engine.read_stats())
+    }
+
+    /// Returns the WAL appends, syncs, and rotations, and the SSTables
+    /// built, since the database was opened, summed across partitions.
+    ///
+    /// Together they follow a write from the WAL to its SSTable:
+    /// [`WalStats::bytes_appended`] against [`SstBuildStats::file_bytes`]
+    /// shows how much of what was logged reached disk as tables, and
+    /// [`WalStats::sync_histogram`] how long durable writes waited on
+    /// fsync. The same counters are reported per partition, as they
+    /// change, to [`EventListener::on_write_activity`].
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::Engine`] — an internal lock was poisoned.
+    pub fn write_stats(&self) -> Result<WriteStats, DbError> {
+        self.check_open()?;
+        Ok(self.engine.write_stats()?)
+    }
+
+    /// Returns the point-tombstone checks made by tombstone and periodic
+    /// compaction since the database was opened, summed across partitions.
+    ///
+    /// Each tombstone is checked against the bloom filters of the older
+    /// SSTables, and with [`DbConfig::tombstone_bloom_fallback`] every
+    /// filter that passes is resolved by reading the table. A high
+    /// [`TombstoneGcStats::bloom_false_positive_rate`] means those reads
+    /// are mostly wasted: filters are too small for the tables that
+    /// tombstones are checked against.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    pub fn tombstone_gc_stats(&self) -> Result<TombstoneGcStats, DbError> {
+        self.check_open()?;
+        Ok(self.engine.tombstone_gc_stats())
+    }
+
+    /// Returns the compactions running now, and those skipped since the
+    /// database was opened, summed across partitions.
+    ///
+    /// Every compaction claims its input SSTables before it writes its
+    /// output. One that finds an input already claimed by another running
+    /// compaction is skipped, so two jobs never replace the same SSTable
+    /// or delete its file twice; [`CompactionJobStats::skipped`] counts
+    /// them.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    pub fn compaction_job_stats(&self) -> Result<CompactionJobStats, DbError> {
+        self.check_open()?;
+        Ok(self.engine.compaction_job_stats())
+    }
+
+    /// Returns the storage used by each tenant of
+    /// [`DbConfig::tenant_prefixes`], sorted by tenant.
+    ///
+    /// Summed from the per-prefix tallies in the properties of the live
+    /// SSTables, so it reads no data blocks and suits periodic quota
+    /// checks. Live bytes count the newest put of every key per SSTable:
+    /// writes still in write buffers are not counted yet, and a key
+    /// overwritten or deleted in a newer SSTable is counted until
+    /// compaction merges the two. [`TenantUsage::exact`] is `false` while
+    /// an SSTable written before the tenant's prefixes were configured
+    /// may hold its keys.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::Engine`] — an internal lock was poisoned.
+    pub fn tenant_usage(&self) -> Result<Vec<TenantUsage>, DbError> {
+        self.check_open()?;
+        Ok(self.tenants.usage(&self.engine.prefix_usage()?))
+    }
+
+    /// Returns an approximate count of live keys, summed across
+    /// partitions.
+    ///
+    /// Computed from metadata alone — memtable key counts, the record
+    /// and tombstone counts of each SSTable, and the distinct-key sketch
+    /// each SSTable stores — so it costs no data block reads and suits
+    /// dashboards that show keyspace size. Keys present in several
+    /// SSTables with overlapping key ranges are counted once via the
+    /// merged sketches, to within about 3 % (or partly discounted for
+    /// SSTables written without a sketch), and each point tombstone is
+    /// assumed to hide one older key; range deletes are not accounted
+    /// for. The estimate is exact for a single
+    /// SSTable or memtable without deletes, e.g. after
+    /// [`major_compact`](Self::major_compact) with no writes since.
+    ///
+    /// Use [`count`](Self::count) for an exact count.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::Engine`] — an internal lock was poisoned.
+    pub fn estimate_num_keys(&self) -> Result<u64, DbError> {
+        self.check_open()?;
+        Ok(self.engine.estimate_num_keys()?)
+    }
+
+    /// Returns how many background tasks ran and panicked since the
+    /// database was opened, and whether a panic marked it errored.
+    ///
+    /// Stays available after [`close`](Self::close).
+    pub fn background_stats(&self) -> BackgroundStats {
+        self.supervisor.stats()
+    }
+
+    /// Returns how many tasks wait in each background queue, how many
+    /// compaction tasks were rejected because their queue held
+    /// [`DbConfig::background_queue_depth`] tasks, and how many compaction
+    /// and timer tasks were coalesced into one already waiting.
+    ///
+    /// Stays available after [`close`](Self::close).
+    pub fn queue_stats(&self) -> QueueStats {
+        self.queues.stats()
+    }
+
+    /// Returns what [`open`](Self::open) recovered: WAL segments and
+    /// records replayed, damaged WAL tails dropped, orphaned SSTables
+    /// removed, SSTables opened, and the time it took.
+    ///
+    /// The report is captured once at open and stays available after
+    /// [`close`](Self::close). With [`DbConfig::partitions`] above one it
+    /// covers every partition.
+    pub fn recovery_report(&self) -> RecoveryReport {
+        self.engine.recovery_report()
+    }
+
+    /// Checks the manifest against the files currently on disk.
+    ///
+    /// Verifies that every SSTable the manifest lists exists, parses, and
+    /// has sane LSN and key bounds, that SSTable ids are unique, and that
+    /// every frozen WAL exists and predates the active WAL. [`open`](Self::open)
+    /// runs the same check and fails with [`DbError::Inconsistent`] on
+    /// any issue; this method reports problems that appeared later,
+    /// e.g. files removed or damaged behind the database's back.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::Engine`] — the manifest could not be read.
+    pub fn check_consistency(&self) -> Result<ConsistencyReport, DbError> {
+        self.check_open()?;
+        Ok(self.engine.check_consistency()?)
+    }
+
+    /// Returns a structured description of the LSM tree for debugging
+    /// compaction.
+    ///
+    /// For every partition the report lists each SSTable (size, key and
+    /// LSN bounds, tombstone ratio, age) with its size-tier bucket, the
+    /// pairs of SSTables whose key ranges overlap, and the tables minor,
+    /// tombstone, periodic, and major compaction would pick right now,
+    /// along with the thresholds behind each choice.
+    /// [`DebugReport::to_dot`] renders it for Graphviz; with the `serde`
+    /// feature it can be serialized, e.g. to JSON.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    pub fn debug_report(&self) -> Result<DebugReport, DbError> {
+        self.check_open()?;
+        Ok(self.engine.debug_report()?)
+    }
+
+    /// Returns the compaction thresholds in effect for each partition, in
+    /// partition order.
+    ///
+    /// These are the configured thresholds unless
+    /// [`DbConfig::adaptive_compaction`] has tuned them since open.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    pub fn compaction_thresholds(&self) -> Result<Vec<CompactionThresholds>, DbError> {
+        self.check_open()?;
+        Ok(self.engine.compaction_thresholds()?)
+    }
+
+    /// Creates a copy of the database at `path` that can be opened
+    /// independently of this one.
+    ///
+    /// Pending write buffers are flushed, then every SSTable is hard-linked
+    /// into `path` — SSTables are immutable, so the two databases share
+    /// their files on disk until compaction rewrites them. Only the active
+    /// WAL and a new manifest are actually copied, so cloning a large
+    /// database takes milliseconds. Falls back to copying SSTables when
+    /// hard links are unavailable, e.g. when `path` is on another
+    /// filesystem; a copied SSTable with a whole-file digest (see
+    /// [`DbConfig::sstable_file_digest`]) is verified against it.
+    ///
+    /// Writes to this database block while the clone is taken.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::Engine`] — `path` exists and is not an empty directory,
+    ///   or flushing, linking, copying, verifying a copy, or writing the
+    ///   manifest failed.
+    pub fn clone_to(&self, path: impl AsRef<Path>) -> Result<(), DbError> {
+        self.check_open()?;
+        Ok(self.engine.clone_to(path)?)
+    }
+
+    // --------------------------------------------------------------------------------------------
+    // Replication
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the replication version of this database.
+    ///
+    /// A follower passes its version to the leader's
+    /// [`export_delta`](Self::export_delta) to receive exactly the
+    /// SSTables it is missing.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::InvalidArgument`] — [`DbConfig::partitions`] is above one.
+    pub fn replication_version(&self) -> Result<u64, DbError> {
+        self.check_open()?;
+        self.engine
+            .replication_version()
+            .map_err(Self::replication_error)
+    }
+
+    /// Exports the SSTables created since `since_version`, for physical
+    /// replication to a follower.
+    ///
+    /// Every write acknowledged before the call is flushed first, so the
+    /// delta, applied with [`apply_delta`](Self::apply_delta), brings a
+    /// follower at `since_version` to this database's current contents.
+    /// SSTables the follower already holds are listed but not shipped.
+    /// Writes block while the SSTables are read, and the export first
+    /// waits for compactions building an output to commit.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::InvalidArgument`] — [`DbConfig::partitions`] is above one.
+    /// - [`DbError::Engine`] — flushing or reading an SSTable failed.
+    pub fn export_delta(&self, since_version: u64) -> Result<ReplicationDelta, DbError> {
+        self.check_open()?;
+        self.engine
+            .export_delta(since_version)
+            .map_err(Self::replication_error)
+    }
+
+    /// Applies a delta exported by a leader, replacing this database's
+    /// SSTables with the leader's.
+    ///
+    /// The follower must be at `delta.since_version` (see
+    /// [`replication_version`](Self::replication_version)) and must not
+    /// be written to, flushed, or compacted between deltas — its SSTable
+    /// set mirrors the leader's. Run followers with
+    /// [`DbConfig::periodic_compaction_seconds`] at `0`. Applying a delta
+    /// does not notify [`subscribe`](Self::subscribe) receivers.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::BackgroundPanic`] — a background task panicked
+    ///   under [`BackgroundPanicPolicy::MarkErrored`].
+    /// - [`DbError::ReadOnly`] — the database is in read-only mode; see
+    ///   [`set_read_only`](Self::set_read_only).
+    /// - [`DbError::InvalidArgument`] — the delta starts at another
+    ///   version, the follower has local writes, a live SSTable is missing
+    ///   from the delta, a shipped SSTable fails its whole-file digest
+    ///   (see [`DbConfig::sstable_file_digest`]) or footer checksum, or
+    ///   [`DbConfig::partitions`] is above one.
+    /// - [`DbError::Engine`] — writing a shipped SSTable or updating the
+    ///   manifest failed.
+    pub fn apply_delta(&self, delta: &ReplicationDelta) -> Result<(), DbError> {
+        self.check_accepts_writes()?;
+        self.engine
+            .apply_delta(delta)
+            .map_err(Self::replication_error)
+    }
+
+    /// Surfaces a read that passed its deadline as
+    /// [`DbError::TimedOut`].
+    fn read_error(e: EngineError) -> DbError {
+        match e {
+            EngineError::TimedOut => DbError::TimedOut {
+                partial: Vec::new(),
+            },
+            e => DbError::Engine(e),
+        }
+    }
+
+    /// Surfaces a delta that does not fit as an invalid argument.
+    fn replication_error(e: EngineError) -> DbError {
+        match e {
+            EngineError::Replication(msg) => DbError::InvalidArgument(msg),
+            e => DbError::Engine(e),
+        }
+    }
+
+    // --------------------------------------------------------------------------------------------
+    // Compaction
+    // --------------------------------------------------------------------------------------------
+
+    /// Runs a full **major compaction**, merging all SSTables into one.
+    ///
+    /// This is a **blocking** operation. All range tombstones are applied
+    /// and all spent tombstones are dropped from the output, except those
+    /// still within [`DbConfig::tombstone_gc_grace_seconds`].
+    ///
+    /// Returns `true` if compaction was performed, `false` if there
+    /// were fewer than 2 SSTables. Under [`CompactionStrategyType::Fifo`]
+    /// nothing is merged: the oldest SSTables past the size budget or TTL
+    /// are dropped, and `true` means at least one was.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::BackgroundPanic`] — a background task panicked
+    ///   under [`BackgroundPanicPolicy::MarkErrored`].
+    /// - [`DbError::Engine`] — SSTable merge, manifest update, or I/O
+    ///   failed during compaction.
+    pub fn major_compact(&self) -> Result<bool, DbError> {
+        self.check_writable()?;
+        let _span = compaction_span("major").entered();
+        Ok(self.engine.major_compact()?)
+    }
+
+    /// Starts a **major compaction** on the background thread pool and
+    /// returns at once.
+    ///
+    /// The returned [`CompactionHandle`] reports progress and can
+    /// [`wait`](CompactionHandle::wait) for the same outcome
+    /// [`major_compact`](Self::major_compact) would return. Only one
+    /// background major compaction runs at a time: while one is in
+    /// flight, this returns another handle to it.
+    ///
+    /// Partitions are compacted one after another. The merge runs without
+    /// the engine lock, so writes go on while it runs; each partition takes
+    /// its write lock only to install the merged SSTable. SSTables flushed
+    /// meanwhile are left for a later compaction.
+    /// [`close`](Self::close) waits for a running compaction to finish.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::BackgroundPanic`] — a background task panicked
+    ///   under [`BackgroundPanicPolicy::MarkErrored`].
+    pub fn major_compact_async(&self) -> Result<CompactionHandle, DbError> {
+        self.check_writable()?;
+
+        let mut major = self.major.lock().unwrap();
+        if let Some(state) = major.as_ref()
+            && !state.is_finished()
+        {
+            return Ok(CompactionHandle::new(Arc::clone(state)));
+        }
+
+        let state = MajorState::new(self.engine.partition_count());
+        let task_state = Arc::clone(&state);
+        let engine = self.engine.clone();
+        let task: Task = Box::new(move || {
+            let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                compaction_span("major")
+                    .in_scope(|| engine.major_compact_tracked(&task_state.progress))
+            }));
+            task_state.finish(match outcome {
+                Ok(Ok(compacted)) => Ok(compacted),
+                Ok(Err(e)) => {
+                    error!("background major compaction failed: {e}");
+                    Err(e.to_string())
+                }
+                Err(payload) => {
+                    task_state.finish(Err("major compaction panicked".into()));
+                    // Let the supervisor count and report the panic.
+                    std::panic::resume_unwind(payload);
+                }
+            });
+        });
+
+        self.submit(TaskKind::Major, task)?;
+        info!("background major compaction scheduled");
+
+        *major = Some(Arc::clone(&state));
+        Ok(CompactionHandle::new(state))
+    }
+
+    /// Hints that `[start, end)` holds much garbage — e.g. after a bulk
+    /// delete — so its SSTables are compacted without waiting for the
+    /// size and tombstone ratio thresholds.
+    ///
+    /// The SSTables overlapping the range are merged into one, which drops
+    /// the versions shadowed by newer writes, and the result is rewritten,
+    /// dropping the tombstones no older SSTable needs. With
+    /// [`CompactionPriority::High`] the compaction is queued right away;
+    /// with [`CompactionPriority::Low`] it waits for the next compaction
+    /// round a flush starts. `High` hints are served first. A hint
+    /// overlapping a range held with [`lock_range`](Self::lock_range) waits
+    /// for the first round after its release.
+    ///
+    /// Only SSTables that exist now are hinted: flush first to include
+    /// the write buffers. Hints are not persisted and are dropped on
+    /// [`close`](Self::close).
+    ///
+    /// Returns the number of SSTables hinted, summed over partitions.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::BackgroundPanic`] — a background task panicked
+    ///   under [`BackgroundPanicPolicy::MarkErrored`].
+    /// - [`DbError::InvalidArgument`] — `start` or `end` is empty, or
+    ///   `start >= end`.
+    /// - [`DbError::Engine`] — an internal lock was poisoned.
+    pub fn suggest_compact_range(
+        &self,
+        start: &[u8],
+        end: &[u8],
+        priority: CompactionPriority,
+    ) -> Result<usize, DbError> {
+        self.check_writable()?;
+
+        if start.is_empty() || end.is_empty() {
+            return Err(DbError::InvalidArgument(
+                "start and end keys must not be empty".into(),
+            ));
+        }
+        if start >= end {
+            return Err(DbError::InvalidArgument(
+                "start must be less than end".into(),
+            ));
+        }
+
+        let hinted = self.engine.suggest_compact_range(start, end, priority)?;
+        info!(hinted, ?priority, "compaction range suggested");
+        if hinted > 0 && priority == CompactionPriority::High && !self.engine.defers_compaction() {
+            let guard = self.bg.lock().unwrap();
+            if guard.is_some() {
+                Self::schedule_compactions(&self.engine, &self.queues);
+            }
+        }
+        Ok(hinted)
+    }
+
+    /// Enters bulk-load mode for fast initial ingestion.
+    ///
+    /// Until [`exit_bulk_load`](Self::exit_bulk_load) is called:
+    ///
+    /// - write buffers grow to 16 × [`DbConfig::write_buffer_size`], so
+    ///   fewer, larger SSTables are flushed;
+    /// - WAL appends are not fsynced one by one — each WAL is synced once,
+    ///   when its write buffer is frozen;
+    /// - background flushes run, but minor, tombstone, and periodic
+    ///   compaction are skipped, as are the [`DbConfig::max_memtable_age`]
+    ///   and [`DbConfig::max_total_wal_size`] flush triggers.
+    ///
+    /// Writes acknowledged since the last sync can be lost on power
+    /// failure or OS crash (a process crash loses nothing). Reads behave
+    /// as usual. Calling this while already bulk loading is harmless.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::BackgroundPanic`] — a background task panicked
+    ///   under [`BackgroundPanicPolicy::MarkErrored`].
+    /// - [`DbError::Engine`] — an internal lock was poisoned.
+    pub fn enter_bulk_load(&self) -> Result<(), DbError> {
+        self.check_writable()?;
+        self.engine.set_bulk_load(true)?;
+        info!("bulk load started");
+        Ok(())
+    }
+
+    /// Leaves bulk-load mode and compacts what was loaded.
+    ///
+    /// Syncs the WAL, flushes every write buffer to SSTables, and runs a
+    /// blocking [`major_compact`](Self::major_compact). Afterwards every
+    /// setting changed by [`enter_bulk_load`](Self::enter_bulk_load) is
+    /// back to its configured value.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::BackgroundPanic`] — a background task panicked
+    ///   under [`BackgroundPanicPolicy::MarkErrored`].
+    /// - [`DbError::Engine`] — WAL sync, flush, or compaction failed.
+    pub fn exit_bulk_load(&self) -> Result<(), DbError> {
+        self.check_writable()?;
+        self.engine.set_bulk_load(false)?;
+        info_span!("db.flush", memtables = field::Empty, bytes = field::Empty)
+            .in_scope(|| self.engine.flush_all_frozen())?;
+        compaction_span("major").in_scope(|| self.engine.major_compact())?;
+        info!("bulk load finished");
+        Ok(())
+    }
+
+    /// Returns `true` between [`enter_bulk_load`](Self::enter_bulk_load)
+    /// and [`exit_bulk_load`](Self::exit_bulk_load).
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::Engine`] — an internal lock was poisoned.
+    pub fn is_bulk_loading(&self) -> Result<bool, DbError> {
+        self.check_open()?;
+        Ok(self.engine.is_bulk_loading()?)
+    }
+
+    // --------------------------------------------------------------------------------------------
+    // Maintenance modes
+    // --------------------------------------------------------------------------------------------
+
+    /// Turns read-only mode on or off, e.g. for a maintenance window or a
+    /// blue/green cutover.
+    ///
+    /// Turning it on refuses every later write — puts, deletes, range
+    /// deletes, batches, conditional writes, imports, and
+    /// [`apply_delta`](Self::apply_delta) — with [`DbError::ReadOnly`],
+    /// then flushes every frozen write buffer to SSTables before
+    /// returning. A write already in progress may still commit. The
+    /// active write buffer is kept in memory, backed by its WAL. Reads,
+    /// subscriptions, and introspection work as usual, and compaction keeps
+    /// running unless paused with
+    /// [`set_compaction_paused`](Self::set_compaction_paused).
+    ///
+    /// The mode is not persisted: a reopened database accepts writes.
+    /// Turning it on or off twice is harmless.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::BackgroundPanic`] — a background task panicked
+    ///   under [`BackgroundPanicPolicy::MarkErrored`].
+    /// - [`DbError::Engine`] — a flush failed; read-only mode is on
+    ///   regardless.
+    pub fn set_read_only(&self, read_only: bool) -> Result<(), DbError> {
+        self.check_writable()?;
+        let was = self.read_only.swap(read_only, Ordering::AcqRel);
+        if read_only {
+            info_span!("db.flush", memtables = field::Empty, bytes = field::Empty)
+                .in_scope(|| self.engine.flush_all_frozen())?;
+        }
+        if was != read_only {
+            info!(read_only, "read-only mode changed");
+        }
+        Ok(())
+    }
+
+    /// Returns `true` while read-only mode is on.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    pub fn is_read_only(&self) -> Result<bool, DbError> {
+        self.check_open()?;
+        Ok(self.read_only.load(Ordering::Acquire))
+    }
+
+    /// Pauses or resumes background compaction.
+    ///
+    /// While paused, no minor, hinted, tombstone, or periodic compaction
+    /// starts in the background, whatever triggers it; a compaction
+    /// already running finishes. Flushes go on, and explicit calls such
+    /// as [`major_compact`](Self::major_compact) still run. Resuming
+    /// queues a compaction round, so thresholds crossed meanwhile are
+    /// acted on.
+    ///
+    /// Like [`set_read_only`](Self::set_read_only), the pause is not
+    /// persisted.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    pub fn set_compaction_paused(&self, paused: bool) -> Result<(), DbError> {
+        self.check_open()?;
+        let was = self.engine.is_compaction_paused();
+        self.engine.set_compaction_paused(paused);
+        if was != paused {
+            info!(paused, "background compaction pause changed");
+        }
+        if was && !paused && !self.engine.defers_compaction() {
+            let guard = self.bg.lock().unwrap();
+            if guard.is_some() {
+                Self::schedule_compactions(&self.engine, &self.queues);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `true` while background compaction is paused.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    pub fn is_compaction_paused(&self) -> Result<bool, DbError> {
+        self.check_open()?;
+        Ok(self.engine.is_compaction_paused())
+    }
+
+    // --------------------------------------------------------------------------------------------
+    // Range locks
+    // --------------------------------------------------------------------------------------------
+
+    /// Locks the key range `[start, end)`, waiting while another holder
+    /// has an overlapping range locked; the range is released when the
+    /// returned guard is dropped.
+    ///
+    /// Range locks are advisory and in-process: they exclude only each
+    /// other, so applications can serialize multi-key maintenance on a
+    /// range without a global mutex, while holders of disjoint ranges
+    /// proceed in parallel. Reads and writes never wait for them.
+    ///
+    /// Hinted compaction honors them: a
+    /// [`suggest_compact_range`](Self::suggest_compact_range) hint
+    /// overlapping a locked range is deferred until the range is
+    /// released, then runs in the next compaction round. Minor,
+    /// tombstone, periodic, and major compaction do not change what a
+    /// read returns and are not held back.
+    ///
+    /// Locks are not reentrant: locking a range overlapping one the same
+    /// thread holds waits forever. Use
+    /// [`try_lock_range`](Self::try_lock_range) to avoid waiting.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::InvalidArgument`] — `start` or `end` is empty, or
+    ///   `start >= end`.
+    pub fn lock_range(&self, start: &[u8], end: &[u8]) -> Result<RangeGuard, DbError> {
+        self.check_open()?;
+        Self::check_lock_range(start, end)?;
+        Ok(self.engine.range_locks().lock(start, end))
+    }
+
+    /// Locks `[start, end)` like [`lock_range`](Self::lock_range) if no
+    /// overlapping range is held, or returns `Ok(None)` without waiting.
+    ///
+    /// # Errors
+    ///
+    /// As [`lock_range`](Self::lock_range).
+    pub fn try_lock_range(&self, start: &[u8], end: &[u8]) -> Result<Option<RangeGuard>, DbError> {
+        self.check_open()?;
+        Self::check_lock_range(start, end)?;
+        Ok(self.engine.range_locks().try_lock(start, end))
+    }
+
+    // --------------------------------------------------------------------------------------------
+    // Simulation
+    // --------------------------------------------------------------------------------------------
+
+    /// Runs every queued background task on the calling thread, including
+    /// the tasks queued by the ones it runs, and returns how many ran
+    /// (feature `simulation`).
+    ///
+    /// Tasks run in the order a worker would take them: flushes first,
+    /// then minor, tombstone, and major compaction, each queue oldest
+    /// first. A panicking task is handled by
+    /// [`DbConfig::background_panic_policy`], as on a worker.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::InvalidArgument`] — the database was not opened with
+    ///   [`open_simulated`](Self::open_simulated).
+    #[cfg(feature = "simulation")]
+    pub fn run_pending_background_work(&self) -> Result<usize, DbError> {
+        self.check_simulated()?;
+        let mut ran = 0;
+        while let Some(task) = self.queues.take_next() {
+            self.supervisor.run(task);
+            ran += 1;
+        }
+        Ok(ran)
+    }
+
+    /// Fires every background timer once, as if each period had elapsed
+    /// (feature `simulation`): queues the memtable age check, periodic
+    /// compaction, read depth check, compaction tuning, and lease renewal
+    /// that are enabled. Nothing runs until
+    /// [`run_pending_background_work`](Self::run_pending_background_work).
+    ///
+    /// Age-based work compares against [`DbConfig::clock`], so pair this
+    /// with a [`MockClock`] to step through time.
+    ///
+    /// Returns the number of tasks queued; as on the thread pool, a tick
+    /// whose previous task still waits is coalesced, and a compaction tick
+    /// whose queue is full is dropped.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the database has been closed.
+    /// - [`DbError::InvalidArgument`] — the database was not opened with
+    ///   [`open_simulated`](Self::open_simulated).
+    #[cfg(feature = "simulation")]
+    pub fn tick_background_timers(&self) -> Result<usize, DbError> {
+        self.check_simulated()?;
+        let guard = self.bg.lock().unwrap();
+        let Some(bg) = guard.as_ref() else {
+            return Err(DbError::Closed);
+        };
+        let mut queued = 0;
+        for ticker in &bg.tickers {
+            if let Ticker::Manual(job, make_task) = ticker
+                && self.queues.push_job(*job, make_task())
+            {
+                queued += 1;
+            }
+        }
+        Ok(queued)
+    }
+
+    /// Fails unless the database is open and was opened with
+    /// [`open_simulated`](Self::open_simulated).
+    #[cfg(feature = "simulation")]
+    fn check_simulated(&self) -> Result<(), DbError> {
+        self.check_open()?;
+        if !self.queues.is_manual() {
+            return Err(DbError::InvalidArgument(
+                "background work runs on the thread pool; open with Db::open_simulated".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    // --------------------------------------------------------------------------------------------
+    // Internal helpers
+    // --------------------------------------------------------------------------------------------
+
+    /// Starts timing `op` if [`DbConfig::slow_op_threshold`] is set.
+    fn slow_op(&self, op: &'static str) -> Option<SlowOpTimer> {
+        SlowOpTimer::start(op, self.slow_op_threshold)
+    }
+
+    /// Opens a sampled span for a point lookup; the engine fills in the
+    /// SSTable and bloom filter counters.
+    fn get_span(&self, key: &[u8]) -> tracing::Span {
+        self.sampler.span(|| {
+            debug_span!(
+                "db.get",
+                key_hash = key_hash(key),
+                bytes = field::Empty,
+                sstables_touched = field::Empty,
+                bloom_hits = field::Empty,
+                bloom_misses = field::Empty,
+            )
+        })
+    }
+
+    /// Opens a sampled span for a range scan starting at `start`.
+    fn scan_span(&self, start: &[u8]) -> tracing::Span {
+        self.sampler.span(|| {
+            debug_span!(
+                "db.scan",
+                key_hash = key_hash(start),
+                results = field::Empty,
+                bytes = field::Empty,
+                sstables_touched = field::Empty,
+            )
+        })
+    }
+
+    /// Collects a scan pair by pair until `deadline`, failing with
+    /// [`DbError::TimedOut`] and the pairs read so far once it passes.
+    fn scan_until(
+        &self,
+        start: &[u8],
+        end: &[u8],
+        opts: &ReadOptions,
+        deadline: std::time::Instant,
+    ) -> Result<Vec<KeyValue>, DbError> {
+        let mut pairs = self.engine.scan_streaming(start, end, opts)?;
+        let mut results = Vec::new();
+        loop {
+            if std::time::Instant::now() >= deadline {
+                debug!(pairs = results.len(), "scan deadline exceeded");
+                return Err(DbError::TimedOut { partial: results });
+            }
+            match pairs.next() {
+                Some(pair) => results.push(pair),
+                None => return Ok(results),
+            }
+        }
+    }
+
+    /// Records the result size of a scan on its span.
+    fn record_scan(span: &tracing::Span, results: &[KeyValue]) {
+        if span.is_disabled() {
+            return;
+        }
+        let bytes: usize = results.iter().map(|(k, v)| k.len() + v.len()).sum();
+        span.record("results", results.len());
+        span.record("bytes", bytes);
+    }
+
+    /// Shared conditional write behind [`compare_and_swap`](Self::compare_and_swap)
+    /// and [`increment`](Self::increment); arguments already validated.
+    fn swap(
+        &self,
+        key: &[u8],
+        expected: Option<&[u8]>,
+        new: Option<&[u8]>,
+    ) -> Result<CasOutcome, DbError> {
+        let (outcome, frozen) = self.changes.commit(
+            || {
+                self.engine
+                    .compare_and_swap(key.to_vec(), expected, new.map(<[u8]>::to_vec))
+            },
+            |(outcome, _)| match (outcome, new) {
+                (CasOutcome::Mismatch(_), _) => Vec::new(),
+                (CasOutcome::Swapped, Some(value)) => vec![ChangeEvent::Put {
+                    key: key.to_vec(),
+                    value: value.to_vec(),
+                }],
+                (CasOutcome::Swapped, None) => vec![ChangeEvent::Delete { key: key.to_vec() }],
+            },
+        )?;
+        if frozen {
+            self.schedule_flush();
+        }
+        Ok(outcome)
+    }
+
+    /// Returns `Err(DbError::Closed)` if the database has been closed.
+    fn check_open(&self) -> Result<(), DbError> {
+        if self.closed.load(Ordering::Acquire) {
+            return Err(DbError::Closed);
+        }
+        Ok(())
+    }
+
+    /// Like [`check_open`](Self::check_open), and also fails once a
+    /// background panic marked the database errored.
+    fn check_writable(&self) -> Result<(), DbError> {
+        self.check_open()?;
+        self.supervisor.check()
+    }
+
+    /// Like [`check_writable`](Self::check_writable), and also fails in
+    /// read-only mode. Guards the operations that write data.
+    fn check_accepts_writes(&self) -> Result<(), DbError> {
+        self.check_writable()?;
+        if self.read_only.load(Ordering::Acquire) {
+            return Err(DbError::ReadOnly);
+        }
+        Ok(())
+    }
+
+    /// Validates the range of [`lock_range`](Self::lock_range).
+    fn check_lock_range(start: &[u8], end: &[u8]) -> Result<(), DbError> {
+        if start.is_empty() || end.is_empty() {
+            return Err(DbError::InvalidArgument(
+                "start and end keys must not be empty".into(),
+            ));
+        }
+        if start >= end {
+            return Err(DbError::InvalidArgument(
+                "start must be less than end".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Queues `task` as `kind`, waiting while its queue is full.
+    ///
+    /// Fails with [`DbError::Closed`] once the pool has shut down.
+    fn submit(&self, kind: TaskKind, task: Task) -> Result<(), DbError> {
+        // Held while waiting, so the pool cannot shut down under the task.
+        let guard = self.bg.lock().unwrap();
+        if guard.is_none() {
+            return Err(DbError::Closed);
+        }
+        self.queues.push(kind, task);
+        Ok(())
+    }
+
+    /// Dispatches a background task to flush the oldest frozen memtable,
+    /// which then queues minor + tombstone compaction. Waits while the
+    /// flush queue is full.
+    fn schedule_flush(&self) {
+        let engine = self.engine.clone();
+        let queues = Arc::clone(&self.queues);
+        let _ = self.submit(
+            TaskKind::Flush,
+            Box::new(move || Self::run_flush(&engine, &queues)),
+        );
+    }
+
+    /// Queues minor compaction, which then queues tombstone and periodic
+    /// compaction, unless the minor queue is full.
+    fn schedule_compactions(engine: &PartitionedEngine, queues: &Arc<TaskQueues>) {
+        let engine = engine.clone();
+        let task_queues = Arc::clone(queues);
+        queues.push_job(
+            Job::MinorCompaction,
+            Box::new(move || Self::run_minor_compaction(&engine, &task_queues)),
+        );
+    }
+
+    /// Reads the newest `count` SSTables of each partition into the page
+    /// cache after open. Runs on a background worker.
+    fn run_warm_up(engine: &PartitionedEngine, count: usize) {
+        match engine.warm_up_recent(count) {
+            Ok(bytes) => info!(sstables = count, bytes, "background: warm-up complete"),
+            Err(e) => error!("background warm-up failed: {e}"),
+        }
+    }
+
+    /// Flushes the oldest frozen memtable, then queues minor, tombstone,
+    /// and periodic compaction. Runs on a background worker.
+    fn run_flush(engine: &PartitionedEngine, queues: &Arc<TaskQueues>) {
+        // 1. Flush oldest frozen memtable to SSTable.
+        let flushed = info_span!("db.flush", memtables = field::Empty, bytes = field::Empty)
+            .in_scope(|| engine.flush_oldest_frozen());
+        match flushed {
+            Ok(true) => debug!("background: flushed frozen memtable"),
+            Ok(false) => return,
+            Err(e) => {
+                error!("background flush failed: {e}");
+                return;
+            }
+        }
+
+        // Bulk loading defers all compaction to `exit_bulk_load`, and a
+        // pause holds it back until compaction is resumed.
+        if engine.defers_compaction() {
+            return;
+        }
+
+        Self::schedule_compactions(engine, queues);
+    }
+
+    /// Runs hinted and minor compaction after a flush, a threshold change,
+    /// or a suggested range, then queues tombstone and periodic
+    /// compaction. Runs on a background worker.
+    fn run_minor_compaction(engine: &PartitionedEngine, queues: &Arc<TaskQueues>) {
+        // Queued before compaction was paused.
+        if engine.is_compaction_paused() {
+            return;
+        }
+
+        // 2a. Hinted compaction — loop until every hint is done.
+        loop {
+            match compaction_span("hinted").in_scope(|| engine.hinted_compact()) {
+                Ok(true) => debug!("background: hinted compaction round"),
+                Ok(false) => break,
+                Err(e) => {
+                    error!("background hinted compaction failed: {e}");
+                    break;
+                }
+            }
+        }
+
+        // 2b. Minor compaction — loop until no bucket meets threshold.
+        loop {
+            match compaction_span("minor").in_scope(|| engine.minor_compact()) {
+                Ok(true) => debug!("background: minor compaction round"),
+                Ok(false) => break,
+                Err(e) => {
+                    error!("background minor compaction failed: {e}");
+                    break;
+                }
+            }
+        }
+
+        let engine = engine.clone();
+        queues.push_job(
+            Job::TombstoneCompaction,
+            Box::new(move || Self::run_tombstone_compaction(&engine)),
+        );
+    }
+
+    /// Hints the newest SSTables of every partition whose lookups probe
+    /// too many of them, then compacts the hints like a minor compaction.
+    /// Runs on a background worker.
+    fn run_read_depth_compaction(engine: &PartitionedEngine, queues: &Arc<TaskQueues>) {
+        if engine.defers_compaction() {
+            return;
+        }
+        match engine.hint_read_depth_compaction() {
+            Ok(true) => Self::run_minor_compaction(engine, queues),
+            Ok(false) => {}
+            Err(e) => error!("background read depth check failed: {e}"),
+        }
+    }
+
+    /// Reports the reads past the fan-out limit of every partition to
+    /// `listeners`. Runs on a background worker.
+    fn report_read_fanout(engine: &PartitionedEngine, listeners: &[Arc<dyn EventListener>]) {
+        for event in engine.take_read_fanout() {
+            warn!(
+                partition = event.partition,
+                reads = event.reads,
+                widest = event.widest,
+                limit = event.limit,
+                sstables = event.sstables,
+                "reads exceeded the SSTable fan-out limit"
+            );
+            for listener in listeners {
+                listener.on_read_fanout_exceeded(&event);
+            }
+        }
+    }
+
+    /// Reports what every partition wrote since the previous report to
+    /// `listeners`. Runs on a background worker.
+    fn report_write_activity(engine: &PartitionedEngine, listeners: &[Arc<dyn EventListener>]) {
+        let events = match engine.take_write_activity() {
+            Ok(events) => events,
+            Err(e) => {
+                error!("background write activity report failed: {e}");
+                return;
+            }
+        };
+        for event in events {
+            for listener in listeners {
+                listener.on_write_activity(&event);
+            }
+        }
+    }
+
+    /// Hints the SSTables of every partition whose scans stepped over
+    /// long runs of tombstones, then compacts the hints like a minor
+    /// compaction. Runs on a background worker.
+    fn run_deletion_compaction(engine: &PartitionedEngine, queues: &Arc<TaskQueues>) {
+        if engine.defers_compaction() {
+            return;
+        }
+        match engine.hint_deletion_compaction() {
+            Ok(true) => Self::run_minor_compaction(engine, queues),
+            Ok(false) => {}
+            Err(e) => error!("background tombstone run check failed: {e}"),
+        }
+    }
+
+    /// Runs tombstone compaction, then periodic compaction. Runs on a
+    /// background worker.
+    fn run_tombstone_compaction(engine: &PartitionedEngine) {
+        if engine.is_compaction_paused() {
+            return;
+        }
+
+        // 3. Tombstone compaction — single pass.
+        match compaction_span("tombstone").in_scope(|| engine.tombstone_compact()) {
+            Ok(true) => debug!("background: tombstone compaction"),
+            Ok(false) => {}
+            Err(e) => {
+                error!("background tombstone compaction failed: {e}");
+            }
+        }
+
+        // 4. Periodic compaction — rewrite every SSTable past its age.
+        Self::run_periodic_compaction(engine);
+    }
+
+    /// Runs one adaptive compaction controller step, reports the changes
+    /// to `listeners`, and compacts under the new thresholds. Runs on a
+    /// background worker.
+    fn run_compaction_tuning(
+        engine: &PartitionedEngine,
+        queues: &Arc<TaskQueues>,
+        windows: &Mutex<Vec<TuningWindow>>,
+        tuning: &AdaptiveCompaction,
+        listeners: &[Arc<dyn EventListener>],
+    ) {
+        let changes = {
+            let mut windows = windows.lock().unwrap();
+            match engine.tune_compaction(&mut windows, tuning) {
+                Ok(changes) => changes,
+                Err(e) => {
+                    error!("background compaction tuning failed: {e}");
+                    return;
+                }
+            }
+        };
+        for change in &changes {
+            for listener in listeners {
+                listener.on_compaction_tuned(change);
+            }
+        }
+        if !changes.is_empty() && !engine.defers_compaction() {
+            Self::schedule_compactions(engine, queues);
+        }
+    }
+
+    /// Rewrites SSTables that outlived `periodic_compaction_seconds` until
+    /// none are left. A no-op when periodic compaction is disabled, paused,
+    /// or deferred by a bulk load.
+    fn run_periodic_compaction(engine: &PartitionedEngine) {
+        if engine.defers_compaction() {
+            return;
+        }
+        loop {
+            match compaction_span("periodic").in_scope(|| engine.periodic_compact()) {
+                Ok(true) => debug!("background: periodic compaction round"),
+                Ok(false) => break,
+                Err(e) => {
+                    error!("background periodic compaction failed: {e}");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Spawns a timer thread that queues the task built by `make_task` as
+    /// `job`, so that time-based work (periodic compaction, memtable age
+    /// flushes) runs even when no writes arrive.
+    ///
+    /// Ticks every half of `period_secs`, clamped to `[1 s, 1 h]`. A tick
+    /// is dropped while the job's previous task still waits; while the
+    /// queue is full, a flush tick waits and a compaction tick is
+    /// dropped. The thread exits when the returned sender is dropped.
+    ///
+    /// With manual queues no thread is spawned; the timer fires in
+    /// [`tick_background_timers`](Self::tick_background_timers).
+    fn spawn_ticker(
+        name: &str,
+        period_secs: usize,
+        queues: Arc<TaskQueues>,
+        job: Job,
+        make_task: impl Fn() -> Task + Send + 'static,
+    ) -> Result<Ticker, DbError> {
+        #[cfg(feature = "simulation")]
+        if queues.is_manual() {
+            return Ok(Ticker::Manual(job, Box::new(make_task)));
+        }
+
+        let interval = std::time::Duration::from_secs((period_secs as u64 / 2).clamp(1, 3600));
+        let (stop_tx, stop_rx) = crossbeam::channel::bounded::<()>(0);
+
+        let handle = thread::Builder::new()
+            .name(name.into())
+            .spawn(move || {
+                while let Err(crossbeam::channel::RecvTimeoutError::Timeout) =
+                    stop_rx.recv_timeout(interval)
+                {
+                    queues.push_job(job, make_task());
+                }
+            })
+            .map_err(|e| {
+                DbError::Engine(EngineError::Internal(format!(
+                    "failed to spawn {name} thread: {e}"
+                )))
+            })?;
+
+        Ok(Ticker::Thread(stop_tx, handle))
+    }
+
+    /// Drains the background task queue and joins all worker threads.
+    fn shutdown_pool(&self) {
+        if let Some(bg) = self.bg.lock().unwrap().take() {
+            // Stop the timers first, so no tick is queued after shutdown.
+            for ticker in bg.tickers {
+                match ticker {
+                    Ticker::Thread(stop, handle) => {
+                        drop(stop);
+                        let _ = handle.join();
+                    }
+                    #[cfg(feature = "simulation")]
+                    Ticker::Manual(..) => {}
+                }
+            }
+
+            // Workers drain the remaining tasks, then exit. Without
+            // workers, the caller drains them.
+            self.queues.shutdown();
+            for worker in bg.workers {
+                let _ = worker.join();
+            }
+            if self.queues.is_manual() {
+                while let Some(task) = self.queues.take_next() {
+                    self.supervisor.run(task);
+                }
+            }
+        }
+    }
+}
+
+/// Span for one compaction round; the engine records the SSTables
+/// consumed and the bytes written.
+fn compaction_span(kind: &'static str) -> tracing::Span {
+    info_span!(
+        "db.compaction",
+        kind,
+        sstables_in = field::Empty,
+        bytes = field::Empty
+    )
 }
 
 impl Drop for Db {
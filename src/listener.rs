@@ -0,0 +1,36 @@
+//! Callbacks for background engine decisions.
+//!
+//! Implementations of [`EventListener`] are registered through
+//! [`DbConfig::event_listeners`](crate::DbConfig::event_listeners) and
+//! called from the background threads that made the decision. Every
+//! method has an empty default body, so a listener implements only the
+//! events it cares about.
+
+use crate::engine::{CompactionTuning, ReadFanout, WriteActivity};
+use crate::supervisor::BackgroundPanic;
+
+/// Receives notifications about background work of a [`Db`](crate::Db).
+///
+/// Callbacks run on background threads while no engine lock is held;
+/// they should return quickly and must not block on the database.
+pub trait EventListener: Send + Sync {
+    /// Called after the adaptive compaction controller changed the
+    /// thresholds of a partition.
+    fn on_compaction_tuned(&self, _event: &CompactionTuning) {}
+
+    /// Called about once a second while reads of a partition need more
+    /// SSTables than
+    /// [`DbConfig::read_fanout_limit`](crate::DbConfig::read_fanout_limit)
+    /// allows.
+    fn on_read_fanout_exceeded(&self, _event: &ReadFanout) {}
+
+    /// Called about once a second for each partition written to since
+    /// the previous call, with the WAL appends, syncs, and rotations and
+    /// the SSTables built in between.
+    fn on_write_activity(&self, _event: &WriteActivity) {}
+
+    /// Called on the worker thread after a background task panicked and
+    /// the panic was handled; see
+    /// [`DbConfig::background_panic_policy`](crate::DbConfig::background_panic_policy).
+    fn on_background_panic(&self, _event: &BackgroundPanic) {}
+}
@@ -7,7 +7,10 @@
 //! - frozen (older) WAL segments,
 //! - list of existing SSTables,
 //! - latest durable global LSN,
-//! - manifest version number.
+//! - manifest version number,
+//! - highest LSN reserved for assignment,
+//! - idempotency tokens of recently committed write batches,
+//! - compactions begun but not yet finished.
 //!
 //! The manifest acts as a *miniature WAL-driven metadata database*.
 //!
@@ -19,7 +22,10 @@
 //!    - switching active WAL,
 //!    - promoting WALs to frozen,
 //!    - adding/removing SSTables,
-//!    - updating LSN.
+//!    - updating LSN,
+//!    - recording applied idempotency tokens,
+//!    - beginning and finishing compactions,
+//!    - clearing the whole database.
 //!
 //! 2. **Manifest snapshot** (`MANIFEST-000001`) is a compact encoded
 //!    dump of the whole metadata structure. Checksum ensures corruption detection.
@@ -30,6 +36,16 @@
 //!
 //! This ensures crash recovery is always correct and consistent.
 //!
+//! ## SSTable paths
+//!
+//! A manifest opened [`with_root`](Manifest::with_root) stores SSTable
+//! paths relative to that root — the database directory — and resolves
+//! them against it when reading, so the directory can be moved as a
+//! whole. Paths recorded as absolute, or relative to the working
+//! directory, by earlier versions still resolve; see
+//! [`Manifest::get_sstables`]. The engine rewrites them on open with
+//! [`Manifest::relocate_legacy_paths`].
+//!
 //! ## Thread safety
 //!
 //! - **WAL** is internally synchronized — no external lock is required.
@@ -48,20 +64,34 @@ mod tests;
 // Includes
 // ------------------------------------------------------------------------------------------------
 
+use crate::compaction::coordinator::CompactionCoordinator;
+use crate::durability;
 use crate::encoding::{self, EncodingError};
-use crate::wal::{Wal, WalError};
+use crate::engine::SSTABLE_DIR;
+use crate::wal::{Wal, WalError, WalRecoveryMode};
 use crc32fast::Hasher as Crc32;
 use std::{
+    collections::HashMap,
     fs::{self, File, OpenOptions},
     io::{self, Read, Write},
     path::{Path, PathBuf},
-    sync::Mutex,
+    sync::{Arc, Mutex},
 };
 use thiserror::Error;
 use tracing::{error, info, warn};
 
 const SNAPSHOT_TMP_SUFFIX: &str = ".tmp";
 const SNAPSHOT_FILENAME: &str = "MANIFEST-000001";
+
+/// Snapshot flag: a `Vec<AppliedToken>` follows.
+const SNAPSHOT_FLAG_TOKENS: u8 = 0x01;
+
+/// Snapshot flag: the reserved LSN, a `u64`, follows.
+const SNAPSHOT_FLAG_RESERVED_LSN: u8 = 0x02;
+
+/// Snapshot flag: a `Vec<PendingCompaction>` follows.
+const SNAPSHOT_FLAG_PENDING_COMPACTIONS: u8 = 0x04;
+
 /// Manifest WAL filename. This is a fixed, single-segment WAL file — it does
 /// not rotate. Truncated to zero on each checkpoint.
 const WAL_FILENAME: &str = "000000.log";
@@ -89,6 +119,11 @@ pub enum ManifestError {
     #[error("Snapshot checksum mismatch")]
     SnapshotChecksumMismatch,
 
+    /// A compaction could not begin: another compaction already claimed
+    /// these of its input SSTables, or replaced them.
+    #[error("SSTables {0:?} are inputs to another compaction")]
+    CompactionConflict(Vec<u64>),
+
     /// Internal invariant violation or poisoned lock.
     #[error("Internal error: {0}")]
     Internal(String),
@@ -123,14 +158,84 @@ pub(crate) struct ManifestData {
     /// Next SSTable ID to allocate. Monotonically increasing.
     next_sst_id: u64,
 
+    /// Highest LSN the engine may assign before reserving more. `0` until
+    /// the first reservation. Monotonically increasing.
+    reserved_lsn: u64,
+
+    /// Idempotency tokens of committed write batches, oldest first.
+    applied_tokens: Vec<AppliedToken>,
+
+    /// Commit timestamp of each token in `applied_tokens`, for lookups.
+    /// A token recorded twice maps to its latest timestamp.
+    token_index: HashMap<Vec<u8>, u64>,
+
+    /// Compactions whose intent was recorded but that have not finished,
+    /// oldest first.
+    pending_compactions: Vec<PendingCompaction>,
+
     /// Runtime-only flag: true when in-memory state diverges from
     /// the last persisted snapshot. Not serialized.
     dirty: bool,
 }
 
+/// The SSTable set of a manifest, as read by [`Manifest::read_view`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ManifestView {
+    /// Next SSTable ID the writer will allocate.
+    pub next_sst_id: u64,
+
+    /// Last LSN the writer persisted.
+    pub last_lsn: u64,
+
+    /// Live SSTables.
+    pub sstables: Vec<ManifestSstEntry>,
+}
+
+/// Idempotency token of a committed write batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppliedToken {
+    /// Caller-chosen token.
+    pub token: Vec<u8>,
+
+    /// Commit time, in nanoseconds since UNIX epoch.
+    pub timestamp: u64,
+}
+
+/// A compaction whose intent the manifest recorded and that has not
+/// finished.
+///
+/// The intent is recorded before the outputs are built. The compaction
+/// commits with the `Compaction` event, which removes every input at
+/// once, so an intent whose inputs are all gone from the SSTable list was
+/// committed; one whose inputs are still listed was not. Paths are
+/// recorded and returned as for [`ManifestSstEntry`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingCompaction {
+    /// SSTables the compaction replaces.
+    pub inputs: Vec<ManifestSstEntry>,
+
+    /// SSTables the compaction writes; empty if it only drops its inputs.
+    pub outputs: Vec<ManifestSstEntry>,
+}
+
+impl PendingCompaction {
+    /// Returns `true` if the `Compaction` event of this compaction was
+    /// recorded, given the IDs of the live SSTables.
+    pub fn is_committed(&self, live: &[u64]) -> bool {
+        !self.inputs.iter().any(|entry| live.contains(&entry.id))
+    }
+
+    /// Returns the IDs of the inputs, which identify the compaction.
+    pub fn input_ids(&self) -> Vec<u64> {
+        self.inputs.iter().map(|entry| entry.id).collect()
+    }
+}
+
 /// Entry describing a single SSTable known to the manifest.
 ///
-/// Identifies table by unique ID and on-disk path.
+/// Identifies table by unique ID and on-disk path. The manifest records
+/// the path relative to its root, if it has one; entries it returns carry
+/// the resolved path.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ManifestSstEntry {
     /// Globally unique SSTable ID.
@@ -163,6 +268,44 @@ impl encoding::Decode for ManifestSstEntry {
     }
 }
 
+impl encoding::Encode for AppliedToken {
+    fn encode_to(&self, buf: &mut Vec<u8>) -> Result<(), EncodingError> {
+        encoding::Encode::encode_to(&self.token, buf)?;
+        encoding::Encode::encode_to(&self.timestamp, buf)?;
+        Ok(())
+    }
+}
+
+impl encoding::Decode for AppliedToken {
+    fn decode_from(buf: &[u8]) -> Result<(Self, usize), EncodingError> {
+        let mut offset = 0;
+        let (token, n) = Vec::<u8>::decode_from(&buf[offset..])?;
+        offset += n;
+        let (timestamp, n) = u64::decode_from(&buf[offset..])?;
+        offset += n;
+        Ok((Self { token, timestamp }, offset))
+    }
+}
+
+impl encoding::Encode for PendingCompaction {
+    fn encode_to(&self, buf: &mut Vec<u8>) -> Result<(), EncodingError> {
+        encoding::encode_vec(&self.inputs, buf)?;
+        encoding::encode_vec(&self.outputs, buf)?;
+        Ok(())
+    }
+}
+
+impl encoding::Decode for PendingCompaction {
+    fn decode_from(buf: &[u8]) -> Result<(Self, usize), EncodingError> {
+        let mut offset = 0;
+        let (inputs, n) = encoding::decode_vec::<ManifestSstEntry>(&buf[offset..])?;
+        offset += n;
+        let (outputs, n) = encoding::decode_vec::<ManifestSstEntry>(&buf[offset..])?;
+        offset += n;
+        Ok((Self { inputs, outputs }, offset))
+    }
+}
+
 impl encoding::Encode for ManifestData {
     fn encode_to(&self, buf: &mut Vec<u8>) -> Result<(), EncodingError> {
         encoding::Encode::encode_to(&self.version, buf)?;
@@ -171,9 +314,29 @@ impl encoding::Encode for ManifestData {
         encoding::encode_vec(&self.frozen_wals, buf)?;
         encoding::encode_vec(&self.sstables, buf)?;
         encoding::Encode::encode_to(&self.next_sst_id, buf)?;
-        // `dirty` is a runtime-only flag — always written as `false` for
-        // wire compatibility, but never read back.
-        encoding::Encode::encode_to(&false, buf)?;
+        // The byte that once held the runtime-only `dirty` flag, always
+        // `false`, now flags the optional fields that follow — so
+        // snapshots written before they existed decode unchanged.
+        let mut flags = 0;
+        if !self.applied_tokens.is_empty() {
+            flags |= SNAPSHOT_FLAG_TOKENS;
+        }
+        if self.reserved_lsn > 0 {
+            flags |= SNAPSHOT_FLAG_RESERVED_LSN;
+        }
+        if !self.pending_compactions.is_empty() {
+            flags |= SNAPSHOT_FLAG_PENDING_COMPACTIONS;
+        }
+        encoding::Encode::encode_to(&flags, buf)?;
+        if flags & SNAPSHOT_FLAG_TOKENS != 0 {
+            encoding::encode_vec(&self.applied_tokens, buf)?;
+        }
+        if flags & SNAPSHOT_FLAG_RESERVED_LSN != 0 {
+            encoding::Encode::encode_to(&self.reserved_lsn, buf)?;
+        }
+        if flags & SNAPSHOT_FLAG_PENDING_COMPACTIONS != 0 {
+            encoding::encode_vec(&self.pending_compactions, buf)?;
+        }
         Ok(())
     }
 }
@@ -193,22 +356,47 @@ impl encoding::Decode for ManifestData {
         offset += n;
         let (next_sst_id, n) = u64::decode_from(&buf[offset..])?;
         offset += n;
-        // `dirty` is present in the wire format for backward compatibility
-        // but its value is discarded — always initialised to `false`.
-        let (_dirty, n) = bool::decode_from(&buf[offset..])?;
+        // `dirty` itself is never persisted; see the encoder.
+        let (flags, n) = u8::decode_from(&buf[offset..])?;
         offset += n;
-        Ok((
-            Self {
-                version,
-                last_lsn,
-                active_wal,
-                frozen_wals,
-                sstables,
-                next_sst_id,
-                dirty: false,
-            },
-            offset,
-        ))
+        if flags
+            & !(SNAPSHOT_FLAG_TOKENS
+                | SNAPSHOT_FLAG_RESERVED_LSN
+                | SNAPSHOT_FLAG_PENDING_COMPACTIONS)
+            != 0
+        {
+            return Err(EncodingError::InvalidTag {
+                tag: flags as u32,
+                type_name: "ManifestData flags",
+            });
+        }
+        let mut data = Self {
+            version,
+            last_lsn,
+            active_wal,
+            frozen_wals,
+            sstables,
+            next_sst_id,
+            ..Self::default()
+        };
+        if flags & SNAPSHOT_FLAG_TOKENS != 0 {
+            let (tokens, n) = encoding::decode_vec::<AppliedToken>(&buf[offset..])?;
+            offset += n;
+            for token in tokens {
+                data.push_token(token);
+            }
+        }
+        if flags & SNAPSHOT_FLAG_RESERVED_LSN != 0 {
+            let (reserved_lsn, n) = u64::decode_from(&buf[offset..])?;
+            offset += n;
+            data.reserved_lsn = reserved_lsn;
+        }
+        if flags & SNAPSHOT_FLAG_PENDING_COMPACTIONS != 0 {
+            let (pending, n) = encoding::decode_vec::<PendingCompaction>(&buf[offset..])?;
+            offset += n;
+            data.pending_compactions = pending;
+        }
+        Ok((data, offset))
     }
 }
 
@@ -252,6 +440,30 @@ impl encoding::Encode for ManifestEvent {
                 encoding::encode_vec(added, buf)?;
                 encoding::encode_vec(removed, buf)?;
             }
+            ManifestEvent::RemoveFrozenWals { wals } => {
+                encoding::Encode::encode_to(&9u32, buf)?;
+                encoding::encode_vec(wals, buf)?;
+            }
+            ManifestEvent::ApplyToken { token } => {
+                encoding::Encode::encode_to(&10u32, buf)?;
+                encoding::Encode::encode_to(token, buf)?;
+            }
+            ManifestEvent::ReserveLsn { lsn } => {
+                encoding::Encode::encode_to(&11u32, buf)?;
+                encoding::Encode::encode_to(lsn, buf)?;
+            }
+            ManifestEvent::BeginCompaction { pending } => {
+                encoding::Encode::encode_to(&12u32, buf)?;
+                encoding::Encode::encode_to(pending, buf)?;
+            }
+            ManifestEvent::FinishCompaction { inputs } => {
+                encoding::Encode::encode_to(&13u32, buf)?;
+                encoding::encode_vec(inputs, buf)?;
+            }
+            ManifestEvent::Clear { wal } => {
+                encoding::Encode::encode_to(&14u32, buf)?;
+                encoding::Encode::encode_to(wal, buf)?;
+            }
         }
         Ok(())
     }
@@ -310,6 +522,36 @@ impl encoding::Decode for ManifestEvent {
                 offset += n;
                 Ok((ManifestEvent::Compaction { added, removed }, offset))
             }
+            9 => {
+                let (wals, n) = encoding::decode_vec::<u64>(&buf[offset..])?;
+                offset += n;
+                Ok((ManifestEvent::RemoveFrozenWals { wals }, offset))
+            }
+            10 => {
+                let (token, n) = AppliedToken::decode_from(&buf[offset..])?;
+                offset += n;
+                Ok((ManifestEvent::ApplyToken { token }, offset))
+            }
+            11 => {
+                let (lsn, n) = u64::decode_from(&buf[offset..])?;
+                offset += n;
+                Ok((ManifestEvent::ReserveLsn { lsn }, offset))
+            }
+            12 => {
+                let (pending, n) = PendingCompaction::decode_from(&buf[offset..])?;
+                offset += n;
+                Ok((ManifestEvent::BeginCompaction { pending }, offset))
+            }
+            13 => {
+                let (inputs, n) = encoding::decode_vec::<u64>(&buf[offset..])?;
+                offset += n;
+                Ok((ManifestEvent::FinishCompaction { inputs }, offset))
+            }
+            14 => {
+                let (wal, n) = u64::decode_from(&buf[offset..])?;
+                offset += n;
+                Ok((ManifestEvent::Clear { wal }, offset))
+            }
             _ => Err(EncodingError::InvalidTag {
                 tag,
                 type_name: "ManifestEvent",
@@ -360,6 +602,10 @@ impl Default for ManifestData {
             frozen_wals: Vec::new(),
             sstables: Vec::new(),
             next_sst_id: 1,
+            reserved_lsn: 0,
+            applied_tokens: Vec::new(),
+            token_index: HashMap::new(),
+            pending_compactions: Vec::new(),
             dirty: false,
         }
     }
@@ -382,9 +628,14 @@ pub enum ManifestEvent {
     /// Adds a WAL segment to the frozen list.
     AddFrozenWal { wal: u64 },
 
-    /// Removes a frozen WAL from manifest state.
+    /// Removes a frozen WAL from manifest state. Only replayed from
+    /// manifests written before `RemoveFrozenWals`.
     RemoveFrozenWal { wal: u64 },
 
+    /// Removes the frozen WALs of one flush in a single WAL entry, so a
+    /// crash never releases only part of a flushed batch.
+    RemoveFrozenWals { wals: Vec<u64> },
+
     /// Adds a new SSTable entry.
     AddSst { entry: ManifestSstEntry },
 
@@ -403,6 +654,23 @@ pub enum ManifestEvent {
         added: Vec<ManifestSstEntry>,
         removed: Vec<u64>,
     },
+
+    /// Records the idempotency token of a committed write batch.
+    ApplyToken { token: AppliedToken },
+
+    /// Reserves every LSN up to `lsn` for assignment.
+    ReserveLsn { lsn: u64 },
+
+    /// Records the intent of a compaction before its outputs are built.
+    BeginCompaction { pending: PendingCompaction },
+
+    /// Forgets the pending compaction of the given inputs once its
+    /// leftover files are deleted.
+    FinishCompaction { inputs: Vec<u64> },
+
+    /// Drops every SSTable, frozen WAL, and pending compaction, and makes
+    /// `wal` the active WAL, in a single WAL entry.
+    Clear { wal: u64 },
 }
 
 /// Serialized snapshot stored in `MANIFEST-000001`.
@@ -423,6 +691,25 @@ struct ManifestSnapshot {
     checksum: u32,
 }
 
+/// Resolves the recorded path of `entry` against the database directory
+/// `root`; see [`Manifest::get_sstables`].
+fn resolve_entry(root: &Path, mut entry: ManifestSstEntry) -> ManifestSstEntry {
+    let mut candidates = Vec::with_capacity(3);
+    if entry.path.is_relative() {
+        candidates.push(root.join(&entry.path));
+    }
+    candidates.push(entry.path.clone());
+    if let Some(name) = entry.path.file_name() {
+        candidates.push(root.join(SSTABLE_DIR).join(name));
+    }
+    let found = candidates
+        .iter()
+        .position(|path| path.is_file())
+        .unwrap_or(0);
+    entry.path = candidates.swap_remove(found);
+    entry
+}
+
 // ------------------------------------------------------------------------------------------------
 // Manifest core
 // ------------------------------------------------------------------------------------------------
@@ -442,9 +729,12 @@ struct ManifestSnapshot {
 /// Checkpoint compacts state into a snapshot and truncates WAL.
 #[derive(Debug)]
 pub struct Manifest {
-    /// Path to engine root directory.
+    /// Path to the manifest directory.
     path: PathBuf,
 
+    /// Database directory SSTable paths are recorded relative to, if any.
+    root: Option<PathBuf>,
+
     /// Manifest WAL storing metadata operations.
     ///
     /// The WAL ensures crash recovery consistency and is internally thread-safe.
@@ -454,6 +744,10 @@ pub struct Manifest {
     ///
     /// Concurrent threads update metadata safely using this lock.
     data: Mutex<ManifestData>,
+
+    /// Claims on the inputs of running compactions. Runtime-only: a
+    /// compaction a crash interrupted holds no claim.
+    coordinator: Arc<CompactionCoordinator>,
 }
 
 impl Manifest {
@@ -467,6 +761,15 @@ impl Manifest {
     /// # Returns
     /// Loaded `Manifest` with fully reconstructed state.
     pub fn open(path: impl AsRef<Path>) -> Result<Self, ManifestError> {
+        Self::open_with_recovery_mode(path, WalRecoveryMode::default())
+    }
+
+    /// Like [`open`](Self::open), but replays the manifest WAL with an
+    /// explicit [`WalRecoveryMode`].
+    pub fn open_with_recovery_mode(
+        path: impl AsRef<Path>,
+        recovery_mode: WalRecoveryMode,
+    ) -> Result<Self, ManifestError> {
         let path = path.as_ref().to_path_buf();
         fs::create_dir_all(&path)?;
 
@@ -505,15 +808,74 @@ impl Manifest {
         //    The manifest WAL records are small; we iterate all records and apply.
         let mut manifest = Manifest {
             path,
+            root: None,
             wal,
             data: Mutex::new(data),
+            coordinator: Arc::default(),
         };
 
-        manifest.replay_wal(snapshot_lsn)?;
+        manifest.replay_wal(snapshot_lsn, recovery_mode)?;
 
         Ok(manifest)
     }
 
+    /// Records SSTable paths relative to `root`, the database directory,
+    /// and resolves recorded paths against it.
+    pub fn with_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// Reads the manifest under `path` without opening it for writing.
+    ///
+    /// SSTable paths are resolved against the parent of `path`, the
+    /// database directory.
+    ///
+    /// Meant for read-only processes while a writer owns the manifest:
+    /// nothing is created or truncated, and a damaged record ends replay,
+    /// since it may be an append still in progress. A read racing with a
+    /// writer's [`checkpoint`](Self::checkpoint) can list SSTables that
+    /// were just deleted; callers retry when opening one fails.
+    pub(crate) fn read_view(path: impl AsRef<Path>) -> Result<ManifestView, ManifestError> {
+        let path = path.as_ref();
+        let snapshot_path = path.join(SNAPSHOT_FILENAME);
+        let mut data = if snapshot_path.exists() {
+            Self::read_snapshot(&snapshot_path)?.0
+        } else {
+            ManifestData::default()
+        };
+
+        let wal_path = path.join(WAL_FILENAME);
+        if wal_path.exists() {
+            for record in Wal::<ManifestEvent>::replay_read_only(&wal_path)? {
+                match record {
+                    Ok(record) => data.apply(&record),
+                    Err(_) => break,
+                }
+            }
+        }
+
+        let root = path.parent().unwrap_or(Path::new("."));
+        Ok(ManifestView {
+            next_sst_id: data.next_sst_id,
+            last_lsn: data.last_lsn,
+            sstables: data
+                .sstables
+                .into_iter()
+                .map(|entry| resolve_entry(root, entry))
+                .collect(),
+        })
+    }
+
+    /// Returns whether the snapshot under `path` is absent or readable.
+    ///
+    /// [`open`](Self::open) silently falls back to replaying the manifest
+    /// WAL alone when the snapshot is damaged; this tells the two apart.
+    pub fn snapshot_intact(path: impl AsRef<Path>) -> bool {
+        let snapshot_path = path.as_ref().join(SNAPSHOT_FILENAME);
+        !snapshot_path.exists() || Self::read_snapshot(&snapshot_path).is_ok()
+    }
+
     // --------------------------------------------------------------------
     // Internal helpers
     // --------------------------------------------------------------------
@@ -527,6 +889,17 @@ impl Manifest {
         })
     }
 
+    /// Rewrites the path of `entry` relative to the root, if it lies
+    /// below it.
+    fn relativize(&self, mut entry: ManifestSstEntry) -> ManifestSstEntry {
+        if let Some(root) = &self.root
+            && let Ok(relative) = entry.path.strip_prefix(root)
+        {
+            entry.path = relative.to_path_buf();
+        }
+        entry
+    }
+
     // --------------------------------------------------------------------
     // Read accessors
     // --------------------------------------------------------------------
@@ -542,8 +915,22 @@ impl Manifest {
     }
 
     /// Returns list of SSTable entries.
+    ///
+    /// With a root, each recorded path is resolved to the first of these
+    /// that exists, or else the first:
+    /// 1. the recorded path under the root, if it is relative;
+    /// 2. the recorded path itself, as written by earlier versions;
+    /// 3. the file of that name directly in the root's `sstables/`, for
+    ///    a database moved since an earlier version recorded the path.
     pub fn get_sstables(&self) -> Result<Vec<ManifestSstEntry>, ManifestError> {
-        Ok(self.lock_data()?.sstables.clone())
+        let sstables = self.lock_data()?.sstables.clone();
+        Ok(match &self.root {
+            Some(root) => sstables
+                .into_iter()
+                .map(|entry| resolve_entry(root, entry))
+                .collect(),
+            None => sstables,
+        })
     }
 
     /// Returns the last persistent LSN.
@@ -551,6 +938,41 @@ impl Manifest {
         Ok(self.lock_data()?.last_lsn)
     }
 
+    /// Returns the highest LSN reserved for assignment, or `0` if none
+    /// was reserved yet.
+    pub fn get_reserved_lsn(&self) -> Result<u64, ManifestError> {
+        Ok(self.lock_data()?.reserved_lsn)
+    }
+
+    /// Returns the compactions begun but not finished, oldest first, with
+    /// paths resolved as by [`get_sstables`](Self::get_sstables).
+    pub fn pending_compactions(&self) -> Result<Vec<PendingCompaction>, ManifestError> {
+        let pending = self.lock_data()?.pending_compactions.clone();
+        Ok(match &self.root {
+            Some(root) => pending
+                .into_iter()
+                .map(|p| PendingCompaction {
+                    inputs: p
+                        .inputs
+                        .into_iter()
+                        .map(|e| resolve_entry(root, e))
+                        .collect(),
+                    outputs: p
+                        .outputs
+                        .into_iter()
+                        .map(|e| resolve_entry(root, e))
+                        .collect(),
+                })
+                .collect(),
+            None => pending,
+        })
+    }
+
+    /// Returns the claims on the inputs of running compactions.
+    pub(crate) fn coordinator(&self) -> &Arc<CompactionCoordinator> {
+        &self.coordinator
+    }
+
     /// Returns `true` if in-memory state has diverged from the last snapshot.
     pub fn is_dirty(&self) -> Result<bool, ManifestError> {
         Ok(self.lock_data()?.dirty)
@@ -563,49 +985,46 @@ impl Manifest {
     // All mutation methods take `&self` rather than `&mut self`.
     // Interior mutability is provided by the `Mutex<ManifestData>` and the
     // internally-synchronised WAL.  This allows concurrent metadata updates
-    // without requiring exclusive ownership.  `checkpoint()` is the only
-    // method that requires `&mut self` because it truncates the WAL and
-    // must not race with concurrent mutations.
+    // without requiring exclusive ownership.  Each mutation is logged and
+    // applied under the data lock, which `checkpoint()` holds while it
+    // snapshots the state and truncates the WAL.
 
     /// Updates the active WAL segment.
     pub fn set_active_wal(&self, wal_id: u64) -> Result<(), ManifestError> {
         let rec = ManifestEvent::SetActiveWal { wal: wal_id };
-        self.wal.append(&rec)?;
-        self.apply_record(&rec)?;
+        self.log(&rec)?;
         Ok(())
     }
 
     /// Adds a WAL segment to frozen list.
     pub fn add_frozen_wal(&self, wal_id: u64) -> Result<(), ManifestError> {
         let rec = ManifestEvent::AddFrozenWal { wal: wal_id };
-        self.wal.append(&rec)?;
-        self.apply_record(&rec)?;
+        self.log(&rec)?;
         Ok(())
     }
 
-    /// Removes a frozen WAL.
-    pub fn remove_frozen_wal(&self, wal_id: u64) -> Result<(), ManifestError> {
-        let rec = ManifestEvent::RemoveFrozenWal { wal: wal_id };
-        self.wal.append(&rec)?;
-        self.apply_record(&rec)?;
+    /// Removes frozen WALs, all or none of them.
+    pub fn remove_frozen_wals(&self, wal_ids: &[u64]) -> Result<(), ManifestError> {
+        let rec = ManifestEvent::RemoveFrozenWals {
+            wals: wal_ids.to_vec(),
+        };
+        self.log(&rec)?;
         Ok(())
     }
 
     /// Adds an SSTable entry to manifest.
     pub fn add_sstable(&self, entry: ManifestSstEntry) -> Result<(), ManifestError> {
         let rec = ManifestEvent::AddSst {
-            entry: entry.clone(),
+            entry: self.relativize(entry),
         };
-        self.wal.append(&rec)?;
-        self.apply_record(&rec)?;
+        self.log(&rec)?;
         Ok(())
     }
 
     /// Removes SSTable entry by ID.
     pub fn remove_sstable(&self, sst_id: u64) -> Result<(), ManifestError> {
         let rec = ManifestEvent::RemoveSst { id: sst_id };
-        self.wal.append(&rec)?;
-        self.apply_record(&rec)?;
+        self.log(&rec)?;
         Ok(())
     }
 
@@ -631,6 +1050,57 @@ impl Manifest {
         Ok(self.lock_data()?.next_sst_id)
     }
 
+    /// Advances the SSTable ID counter so that the next allocation returns
+    /// at least `next_id`. A no-op if the counter is already there.
+    ///
+    /// Used when seeding a manifest with SSTables whose IDs were allocated
+    /// by another manifest.
+    pub fn advance_sst_id(&self, next_id: u64) -> Result<(), ManifestError> {
+        if next_id == 0 || self.peek_next_sst_id()? >= next_id {
+            return Ok(());
+        }
+        let rec = ManifestEvent::AllocateSstId { id: next_id - 1 };
+        self.log(&rec)?;
+        Ok(())
+    }
+
+    /// Rewrites SSTable paths recorded by earlier versions — absolute, or
+    /// relative to the working directory — relative to the root, in a
+    /// single WAL entry. Such paths break once the database directory is
+    /// copied or moved; the file is looked up by name in the root's
+    /// `sstables/`, where earlier versions wrote every SSTable, and
+    /// entries whose file is not there are left as they are.
+    ///
+    /// Returns the number of entries rewritten; without a root, none.
+    pub fn relocate_legacy_paths(&self) -> Result<usize, ManifestError> {
+        let Some(root) = &self.root else {
+            return Ok(0);
+        };
+        let added: Vec<ManifestSstEntry> = self
+            .lock_data()?
+            .sstables
+            .iter()
+            .filter(|entry| !(entry.path.is_relative() && root.join(&entry.path).is_file()))
+            .filter_map(|entry| {
+                let path = Path::new(SSTABLE_DIR).join(entry.path.file_name()?);
+                root.join(&path)
+                    .is_file()
+                    .then_some(ManifestSstEntry { id: entry.id, path })
+            })
+            .collect();
+        if added.is_empty() {
+            return Ok(0);
+        }
+
+        let rewritten = added.len();
+        let rec = ManifestEvent::Compaction {
+            removed: added.iter().map(|entry| entry.id).collect(),
+            added,
+        };
+        self.log(&rec)?;
+        Ok(rewritten)
+    }
+
     /// Atomically records a compaction: adds new SSTables and removes old ones
     /// in a single WAL entry.
     pub fn apply_compaction(
@@ -638,17 +1108,174 @@ impl Manifest {
         added: Vec<ManifestSstEntry>,
         removed: Vec<u64>,
     ) -> Result<(), ManifestError> {
+        let added = added.into_iter().map(|e| self.relativize(e)).collect();
         let rec = ManifestEvent::Compaction { added, removed };
-        self.wal.append(&rec)?;
-        self.apply_record(&rec)?;
+        self.log(&rec)?;
+        Ok(())
+    }
+
+    /// Records the intent of a compaction replacing `inputs` with
+    /// `outputs`, before any output is written.
+    ///
+    /// The compaction then commits with
+    /// [`apply_compaction`](Self::apply_compaction), and ends with
+    /// [`finish_compaction`](Self::finish_compaction) once the files it
+    /// leaves behind are deleted. Until then, recovery can tell from the
+    /// manifest alone whether to delete the outputs or the inputs.
+    ///
+    /// The inputs are claimed until the compaction finishes or is
+    /// abandoned. Fails with [`ManifestError::CompactionConflict`], and
+    /// records nothing, if another running compaction claimed any of them.
+    pub fn begin_compaction(
+        &self,
+        inputs: Vec<ManifestSstEntry>,
+        outputs: Vec<ManifestSstEntry>,
+    ) -> Result<(), ManifestError> {
+        self.begin_compaction_with(inputs, |_| Ok(outputs))?;
+        Ok(())
+    }
+
+    /// Allocates the ID of a compaction's single output and records its
+    /// intent, like [`begin_compaction`](Self::begin_compaction), both
+    /// under one lock: every ID handed out this way is listed by
+    /// [`pending_compactions`](Self::pending_compactions) until the
+    /// compaction finishes. `output_path` maps the ID to the output's
+    /// path. Returns the output's entry.
+    pub fn begin_compaction_with_output(
+        &self,
+        inputs: Vec<ManifestSstEntry>,
+        output_path: impl FnOnce(u64) -> PathBuf,
+    ) -> Result<ManifestSstEntry, ManifestError> {
+        let mut outputs = self.begin_compaction_with(inputs, |data| {
+            let id = data.next_sst_id;
+            let rec = ManifestEvent::AllocateSstId { id };
+            self.wal.append(&rec)?;
+            data.apply(&rec);
+            Ok(vec![ManifestSstEntry {
+                id,
+                path: output_path(id),
+            }])
+        })?;
+        Ok(outputs.remove(0))
+    }
+
+    /// Claims `inputs` and records the intent of a compaction replacing
+    /// them with the entries `outputs` returns, called under the data
+    /// lock. The claim is released if nothing is recorded.
+    fn begin_compaction_with(
+        &self,
+        inputs: Vec<ManifestSstEntry>,
+        outputs: impl FnOnce(&mut ManifestData) -> Result<Vec<ManifestSstEntry>, ManifestError>,
+    ) -> Result<Vec<ManifestSstEntry>, ManifestError> {
+        let input_ids: Vec<u64> = inputs.iter().map(|e| e.id).collect();
+        self.coordinator
+            .claim(&input_ids)
+            .map_err(ManifestError::CompactionConflict)?;
+        let begun = self.lock_data().and_then(|mut data| {
+            let outputs = outputs(&mut data)?;
+            let rec = ManifestEvent::BeginCompaction {
+                pending: PendingCompaction {
+                    inputs: inputs.into_iter().map(|e| self.relativize(e)).collect(),
+                    outputs: outputs.iter().map(|e| self.relativize(e.clone())).collect(),
+                },
+            };
+            self.wal.append(&rec)?;
+            data.apply(&rec);
+            Ok(outputs)
+        });
+        if begun.is_err() {
+            self.coordinator.release(&input_ids);
+        }
+        begun
+    }
+
+    /// Forgets the pending compaction of the SSTables `input_ids`, whether
+    /// it committed or was abandoned, and releases its inputs.
+    pub fn finish_compaction(&self, input_ids: &[u64]) -> Result<(), ManifestError> {
+        self.coordinator.release(input_ids);
+        let rec = ManifestEvent::FinishCompaction {
+            inputs: input_ids.to_vec(),
+        };
+        self.log(&rec)?;
+        Ok(())
+    }
+
+    /// Forgets every SSTable, frozen WAL, and pending compaction and makes
+    /// `active_wal` the active WAL, all or none of it.
+    ///
+    /// The SSTable ID counter, LSNs, and applied tokens are kept. The
+    /// caller deletes the dropped files once this returns.
+    pub fn clear(&self, active_wal: u64) -> Result<(), ManifestError> {
+        let rec = ManifestEvent::Clear { wal: active_wal };
+        self.log(&rec)?;
+        Ok(())
+    }
+
+    /// Releases the inputs of a compaction that failed after it began,
+    /// without finishing it: it stays pending for recovery to settle.
+    pub fn abandon_compaction(&self, input_ids: &[u64]) {
+        self.coordinator.release(input_ids);
+    }
+
+    /// Records the idempotency token of a committed write batch.
+    pub fn record_applied_token(&self, token: &[u8], timestamp: u64) -> Result<(), ManifestError> {
+        let rec = ManifestEvent::ApplyToken {
+            token: AppliedToken {
+                token: token.to_vec(),
+                timestamp,
+            },
+        };
+        self.log(&rec)?;
+        Ok(())
+    }
+
+    /// Returns the commit timestamp of `token`, or `None` if it was never
+    /// recorded or has been pruned.
+    pub fn applied_token_timestamp(&self, token: &[u8]) -> Result<Option<u64>, ManifestError> {
+        Ok(self.lock_data()?.token_index.get(token).copied())
+    }
+
+    /// Forgets the applied tokens committed before `min_timestamp`, then
+    /// the oldest ones beyond `max_tokens`.
+    ///
+    /// Not logged: a replay brings pruned tokens back until the caller
+    /// prunes again, and the next checkpoint leaves them out for good.
+    pub fn prune_applied_tokens(
+        &self,
+        min_timestamp: u64,
+        max_tokens: usize,
+    ) -> Result<(), ManifestError> {
+        let mut data = self.lock_data()?;
+        let expired = data
+            .applied_tokens
+            .iter()
+            .take_while(|t| t.timestamp < min_timestamp)
+            .count();
+        let excess = data.applied_tokens.len().saturating_sub(max_tokens);
+        let data = &mut *data;
+        for oldest in data.applied_tokens.drain(..expired.max(excess)) {
+            // A token recorded again later stays indexed under its newer
+            // timestamp.
+            if data.token_index.get(&oldest.token) == Some(&oldest.timestamp) {
+                data.token_index.remove(&oldest.token);
+            }
+        }
         Ok(())
     }
 
     /// Updates last durable LSN.
     pub fn update_lsn(&self, last_lsn: u64) -> Result<(), ManifestError> {
         let rec = ManifestEvent::UpdateLsn { last_lsn };
-        self.wal.append(&rec)?;
-        self.apply_record(&rec)?;
+        self.log(&rec)?;
+        Ok(())
+    }
+
+    /// Reserves every LSN up to `lsn`. Recovery resumes above the highest
+    /// reservation, so no LSN is assigned twice even if the WAL that used
+    /// it is lost.
+    pub fn reserve_lsn(&self, lsn: u64) -> Result<(), ManifestError> {
+        let rec = ManifestEvent::ReserveLsn { lsn };
+        self.log(&rec)?;
         Ok(())
     }
 
@@ -660,19 +1287,17 @@ impl Manifest {
     /// - Resets/truncates manifest WAL to reduce recovery cost.
     ///
     /// # Exclusive access
-    /// Requires `&mut self` to ensure no concurrent mutations race with the
-    /// WAL truncation step.
-    pub fn checkpoint(&mut self) -> Result<(), ManifestError> {
-        // 1. Build snapshot structure (capture current state, checksum placeholder)
-        let snapshot = {
-            let data = self.lock_data()?.clone();
+    /// Holds the data lock throughout, so no concurrent mutation can be
+    /// logged between the snapshot and the WAL truncation and lost.
+    pub fn checkpoint(&self) -> Result<(), ManifestError> {
+        let mut data = self.lock_data()?;
 
-            ManifestSnapshot {
-                version: data.version,
-                snapshot_lsn: data.last_lsn,
-                manifest_data: data,
-                checksum: 0,
-            }
+        // 1. Build snapshot structure (capture current state, checksum placeholder)
+        let snapshot = ManifestSnapshot {
+            version: data.version,
+            snapshot_lsn: data.last_lsn,
+            manifest_data: data.clone(),
+            checksum: 0,
         };
 
         // 2. Single-pass: serialize with checksum=0, compute CRC, then patch
@@ -696,30 +1321,24 @@ impl Manifest {
                 .truncate(true)
                 .open(&tmp_path)?;
             f.write_all(&snapshot_bytes)?;
-            f.sync_all()?; // ensure snapshot content durable
+            durability::sync_file(&f)?; // ensure snapshot content durable
         }
 
-        // 4. Atomic rename
+        // 4. Atomic rename, with the parent directory synced so the rename
+        //    is durable
+        fail_point!(CHECKPOINT_BEFORE_RENAME);
         let final_path = self.path.join(SNAPSHOT_FILENAME);
-        fs::rename(&tmp_path, &final_path)?;
-
-        // 5. fsync parent directory so rename is durable
-        Self::fsync_dir(&self.path)?;
+        durability::rename(&tmp_path, &final_path)?;
 
         info!("Manifest snapshot written to {:?}", final_path);
 
-        // 6. Truncate manifest WAL to header-only (safe after snapshot durability)
+        // 5. Truncate manifest WAL to header-only (safe after snapshot durability)
+        fail_point!(CHECKPOINT_BEFORE_TRUNCATE);
         self.wal.truncate()?;
 
-        // 7. Mark in-memory data as clean
-        self.lock_data()?.dirty = false;
-
-        Ok(())
-    }
+        // 6. Mark in-memory data as clean
+        data.dirty = false;
 
-    fn fsync_dir(dir: &Path) -> Result<(), ManifestError> {
-        let dir_file = File::open(dir)?;
-        dir_file.sync_all()?;
         Ok(())
     }
 
@@ -750,26 +1369,17 @@ impl Manifest {
         Ok((snap.manifest_data, snap.snapshot_lsn))
     }
 
-    fn replay_wal(&mut self, snapshot_lsn: u64) -> Result<(), ManifestError> {
-        let iter = match self.wal.replay_iter() {
-            Ok(i) => i,
-            Err(e) => {
-                return Err(ManifestError::Wal(e));
-            }
-        };
+    fn replay_wal(
+        &mut self,
+        snapshot_lsn: u64,
+        recovery_mode: WalRecoveryMode,
+    ) -> Result<(), ManifestError> {
+        let iter = self.wal.recover_iter(recovery_mode)?;
 
         let mut count: u64 = 0;
         for item in iter {
-            match item {
-                Ok(rec) => {
-                    self.apply_record(&rec)?;
-                    count += 1;
-                }
-                Err(e) => {
-                    warn!("Manifest WAL replay stopped due to WAL error: {}", e);
-                    break;
-                }
-            }
+            self.apply_record(&item?)?;
+            count += 1;
         }
 
         // Defensive check: after replay the manifest LSN must be at least
@@ -793,79 +1403,145 @@ impl Manifest {
     }
 
     fn apply_record(&self, rec: &ManifestEvent) -> Result<(), ManifestError> {
+        self.lock_data()?.apply(rec);
+        Ok(())
+    }
+
+    /// Appends `rec` to the manifest WAL and applies it, under the data
+    /// lock: records are applied in the order they are logged, and none
+    /// is logged while a [`checkpoint`](Self::checkpoint) runs.
+    fn log(&self, rec: &ManifestEvent) -> Result<(), ManifestError> {
         let mut data = self.lock_data()?;
+        self.wal.append(rec)?;
+        data.apply(rec);
+        Ok(())
+    }
+}
+
+impl ManifestData {
+    /// Appends an applied token and indexes it.
+    fn push_token(&mut self, token: AppliedToken) {
+        let latest = self.token_index.entry(token.token.clone()).or_default();
+        *latest = (*latest).max(token.timestamp);
+        self.applied_tokens.push(token);
+    }
 
+    /// Applies one replayed manifest event.
+    fn apply(&mut self, rec: &ManifestEvent) {
         match rec {
             ManifestEvent::Version { version } => {
-                data.version = *version;
-                data.dirty = true;
+                self.version = *version;
+                self.dirty = true;
             }
 
             ManifestEvent::SetActiveWal { wal } => {
-                data.active_wal = *wal;
-                data.frozen_wals.retain(|w| w != wal);
-                data.dirty = true;
+                self.active_wal = *wal;
+                self.frozen_wals.retain(|w| w != wal);
+                self.dirty = true;
             }
 
             ManifestEvent::AddFrozenWal { wal } => {
-                if !data.frozen_wals.contains(wal) {
-                    data.frozen_wals.push(*wal);
+                if !self.frozen_wals.contains(wal) {
+                    self.frozen_wals.push(*wal);
                 }
-                data.dirty = true;
+                self.dirty = true;
             }
 
             ManifestEvent::RemoveFrozenWal { wal } => {
-                data.frozen_wals.retain(|w| w != wal);
-                data.dirty = true;
+                self.frozen_wals.retain(|w| w != wal);
+                self.dirty = true;
+            }
+
+            ManifestEvent::RemoveFrozenWals { wals } => {
+                self.frozen_wals.retain(|w| !wals.contains(w));
+                self.dirty = true;
             }
 
             ManifestEvent::AddSst { entry } => {
                 // Avoid duplicate SST IDs (idempotent)
-                if !data.sstables.iter().any(|e| e.id == entry.id) {
-                    data.sstables.push(entry.clone());
+                if !self.sstables.iter().any(|e| e.id == entry.id) {
+                    self.sstables.push(entry.clone());
                 }
-                data.dirty = true;
+                self.dirty = true;
             }
 
             ManifestEvent::RemoveSst { id } => {
-                data.sstables.retain(|e| e.id != *id);
-                data.dirty = true;
+                self.sstables.retain(|e| e.id != *id);
+                self.dirty = true;
             }
 
             ManifestEvent::UpdateLsn { last_lsn } => {
-                if *last_lsn > data.last_lsn {
-                    data.last_lsn = *last_lsn;
+                if *last_lsn > self.last_lsn {
+                    self.last_lsn = *last_lsn;
                 }
-                data.dirty = true;
+                self.dirty = true;
             }
 
             ManifestEvent::AllocateSstId { id } => {
                 // Advance counter past the allocated ID (self-healing on replay).
-                if *id >= data.next_sst_id {
-                    data.next_sst_id = *id + 1;
+                if *id >= self.next_sst_id {
+                    self.next_sst_id = *id + 1;
                 }
-                data.dirty = true;
+                self.dirty = true;
             }
 
             ManifestEvent::Compaction { added, removed } => {
                 // Remove old SSTables first.
                 for id in removed {
-                    data.sstables.retain(|e| e.id != *id);
+                    self.sstables.retain(|e| e.id != *id);
                 }
                 // Add new SSTables (idempotent — skip duplicates).
                 for entry in added {
-                    if !data.sstables.iter().any(|e| e.id == entry.id) {
-                        data.sstables.push(entry.clone());
+                    if !self.sstables.iter().any(|e| e.id == entry.id) {
+                        self.sstables.push(entry.clone());
                     }
                     // Keep next_sst_id consistent.
-                    if entry.id >= data.next_sst_id {
-                        data.next_sst_id = entry.id + 1;
+                    if entry.id >= self.next_sst_id {
+                        self.next_sst_id = entry.id + 1;
                     }
                 }
-                data.dirty = true;
+                self.dirty = true;
             }
-        }
 
-        Ok(())
+            ManifestEvent::ApplyToken { token } => {
+                self.push_token(token.clone());
+                self.dirty = true;
+            }
+
+            ManifestEvent::ReserveLsn { lsn } => {
+                self.reserved_lsn = self.reserved_lsn.max(*lsn);
+                self.dirty = true;
+            }
+
+            ManifestEvent::BeginCompaction { pending } => {
+                // Skip duplicates (idempotent).
+                if !self.pending_compactions.contains(pending) {
+                    self.pending_compactions.push(pending.clone());
+                }
+                self.dirty = true;
+            }
+
+            ManifestEvent::FinishCompaction { inputs } => {
+                // Match regardless of the order the inputs are listed in.
+                let mut finished = inputs.clone();
+                finished.sort_unstable();
+                self.pending_compactions.retain(|p| {
+                    let mut ids = p.input_ids();
+                    ids.sort_unstable();
+                    ids != finished
+                });
+                self.dirty = true;
+            }
+
+            ManifestEvent::Clear { wal } => {
+                // Counters (SSTable IDs, LSNs) and applied tokens survive,
+                // so nothing is ever reused.
+                self.sstables.clear();
+                self.frozen_wals.clear();
+                self.pending_compactions.clear();
+                self.active_wal = *wal;
+                self.dirty = true;
+            }
+        }
     }
 }
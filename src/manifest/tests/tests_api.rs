@@ -7,6 +7,9 @@
 //! - `Version` event sets dirty flag
 //! - Corrupt snapshot + valid WAL data → resilient recovery
 //! - Concurrent-style allocate_sst_id monotonicity (sequential)
+//! - SSTable paths stored relative to a root, and legacy paths resolved
+//! - `clear()` drops SSTables, frozen WALs, and pending compactions but
+//!   keeps the counters, across replay
 //!
 //! ## See also
 //! - [`tests_basic`]      — lifecycle, crash-recovery
@@ -17,6 +20,7 @@
 mod tests {
     use crate::manifest::{Manifest, ManifestSstEntry};
     use std::fs;
+    use std::path::Path;
     use tempfile::TempDir;
     use tracing_subscriber::EnvFilter;
 
@@ -53,7 +57,7 @@ mod tests {
         init_tracing();
 
         let temp = TempDir::new().unwrap();
-        let mut m = open_manifest(&temp);
+        let m = open_manifest(&temp);
 
         // Fresh manifest is clean.
         assert!(!m.is_dirty().unwrap(), "fresh manifest should be clean");
@@ -153,7 +157,7 @@ mod tests {
         let temp = TempDir::new().unwrap();
 
         {
-            let mut m = open_manifest(&temp);
+            let m = open_manifest(&temp);
 
             // Pre-checkpoint state.
             m.add_sstable(sst_entry(1)).unwrap();
@@ -232,7 +236,7 @@ mod tests {
         let temp = TempDir::new().unwrap();
 
         {
-            let mut m = open_manifest(&temp);
+            let m = open_manifest(&temp);
             m.set_active_wal(5).unwrap();
             m.checkpoint().unwrap();
         }
@@ -264,7 +268,7 @@ mod tests {
 
         let id3;
         {
-            let mut m = open_manifest(&temp);
+            let m = open_manifest(&temp);
             let id1 = m.allocate_sst_id().unwrap();
             m.add_sstable(sst_entry(id1)).unwrap();
             m.set_active_wal(1).unwrap();
@@ -287,4 +291,147 @@ mod tests {
             "ID after reopen ({id4}) must exceed pre-close ID ({id3})"
         );
     }
+
+    // ================================================================
+    // 8. SSTable paths relative to a root
+    // ================================================================
+
+    /// # Scenario
+    /// A manifest opened with a root records SSTable paths below it
+    /// relative to it, and resolves recorded paths — its own and legacy
+    /// ones — after the directory moved.
+    ///
+    /// # Starting environment
+    /// A database directory `db` holding `sstables/000001.sst` and
+    /// `sstables/000002.sst`.
+    ///
+    /// # Actions
+    /// 1. Open the manifest with root `db`; add SSTable 1 by its path
+    ///    under `db`; checkpoint.
+    /// 2. Open it without a root; read the entries.
+    /// 3. Through that manifest, add SSTable 2 by its absolute path, as
+    ///    older versions recorded it; checkpoint; open with root `db` and
+    ///    read the entries.
+    /// 4. Rename `db` to `moved`; open with root `moved`; read the
+    ///    entries.
+    ///
+    /// # Expected behavior
+    /// - Step 2 lists SSTable 1 as `sstables/000001.sst`.
+    /// - Step 3 resolves SSTable 1 under `db` and keeps SSTable 2 as given.
+    /// - Step 4 resolves both under `moved`, SSTable 2 by its file name.
+    #[test]
+    fn sstable_paths_relative_to_root() {
+        init_tracing();
+
+        let temp = TempDir::new().unwrap();
+        let db = temp.path().join("db");
+        fs::create_dir_all(db.join("sstables")).unwrap();
+        fs::create_dir_all(db.join("manifest")).unwrap();
+        fs::write(db.join("sstables/000001.sst"), b"one").unwrap();
+        fs::write(db.join("sstables/000002.sst"), b"two").unwrap();
+
+        {
+            let m = Manifest::open(db.join("manifest")).unwrap().with_root(&db);
+            m.add_sstable(ManifestSstEntry {
+                id: 1,
+                path: db.join("sstables/000001.sst"),
+            })
+            .unwrap();
+            m.checkpoint().unwrap();
+        }
+
+        let m = Manifest::open(db.join("manifest")).unwrap();
+        assert_eq!(
+            m.get_sstables().unwrap()[0].path,
+            Path::new("sstables/000001.sst")
+        );
+
+        let legacy = db.join("sstables/000002.sst");
+        m.add_sstable(ManifestSstEntry {
+            id: 2,
+            path: legacy.clone(),
+        })
+        .unwrap();
+        m.checkpoint().unwrap();
+        drop(m);
+
+        let m = Manifest::open(db.join("manifest")).unwrap().with_root(&db);
+        let entries = m.get_sstables().unwrap();
+        assert_eq!(entries[0].path, db.join("sstables/000001.sst"));
+        assert_eq!(entries[1].path, legacy);
+        drop(m);
+
+        let moved = temp.path().join("moved");
+        fs::rename(&db, &moved).unwrap();
+        let m = Manifest::open(moved.join("manifest"))
+            .unwrap()
+            .with_root(&moved);
+        let paths: Vec<_> = m
+            .get_sstables()
+            .unwrap()
+            .into_iter()
+            .map(|e| e.path)
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                moved.join("sstables/000001.sst"),
+                moved.join("sstables/000002.sst")
+            ]
+        );
+    }
+
+    // ================================================================
+    // 9. clear
+    // ================================================================
+
+    /// # Scenario
+    /// `clear()` drops the whole layer set in one event.
+    ///
+    /// # Starting environment
+    /// Manifest with SSTables, a frozen WAL, a pending compaction, and
+    /// an LSN.
+    ///
+    /// # Actions
+    /// 1. `clear(5)`, without a checkpoint.
+    /// 2. Reopen, replaying the event.
+    ///
+    /// # Expected behavior
+    /// Both before and after reopening: no SSTable, frozen WAL, or pending
+    /// compaction is left, WAL 5 is active, and the SSTable ID counter and
+    /// last LSN are unchanged.
+    #[test]
+    fn clear_drops_layers_keeps_counters() {
+        init_tracing();
+
+        let temp = TempDir::new().unwrap();
+        let next_sst_id = {
+            let m = open_manifest(&temp);
+            for _ in 0..3 {
+                let id = m.allocate_sst_id().unwrap();
+                m.add_sstable(sst_entry(id)).unwrap();
+            }
+            m.add_frozen_wal(3).unwrap();
+            m.set_active_wal(4).unwrap();
+            m.begin_compaction(vec![sst_entry(1), sst_entry(2)], vec![sst_entry(9)])
+                .unwrap();
+            m.update_lsn(42).unwrap();
+            let next_sst_id = m.peek_next_sst_id().unwrap();
+
+            m.clear(5).unwrap();
+            assert!(m.get_sstables().unwrap().is_empty());
+            assert!(m.get_frozen_wals().unwrap().is_empty());
+            assert!(m.pending_compactions().unwrap().is_empty());
+            assert_eq!(m.get_active_wal().unwrap(), 5);
+            next_sst_id
+        };
+
+        let m = open_manifest(&temp);
+        assert!(m.get_sstables().unwrap().is_empty());
+        assert!(m.get_frozen_wals().unwrap().is_empty());
+        assert!(m.pending_compactions().unwrap().is_empty());
+        assert_eq!(m.get_active_wal().unwrap(), 5);
+        assert_eq!(m.peek_next_sst_id().unwrap(), next_sst_id);
+        assert_eq!(m.get_last_lsn().unwrap(), 42);
+    }
 }
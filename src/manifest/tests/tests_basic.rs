@@ -108,8 +108,8 @@ mod tests {
     /// Fresh manifest.
     ///
     /// # Actions
-    /// 1. `add_frozen_wal(1)`, `add_frozen_wal(2)`, `add_frozen_wal(3)`.
-    /// 2. `remove_frozen_wal(2)`.
+    /// 1. `add_frozen_wal(1)` through `add_frozen_wal(4)`.
+    /// 2. `remove_frozen_wals(&[2, 4])`.
     /// 3. Reopen and query `get_frozen_wals()`.
     ///
     /// # Expected behavior
@@ -119,10 +119,10 @@ mod tests {
         let temp = TempDir::new().unwrap();
         let m = open_manifest(&temp);
 
-        m.add_frozen_wal(1).unwrap();
-        m.add_frozen_wal(2).unwrap();
-        m.add_frozen_wal(3).unwrap();
-        m.remove_frozen_wal(2).unwrap();
+        for wal in 1..=4 {
+            m.add_frozen_wal(wal).unwrap();
+        }
+        m.remove_frozen_wals(&[2, 4]).unwrap();
 
         let frozen = m.get_frozen_wals().unwrap();
         assert_eq!(frozen, vec![1, 3]);
@@ -237,7 +237,7 @@ mod tests {
         let snapshot_path = temp.path().join("MANIFEST-000001");
 
         {
-            let mut m = open_manifest(&temp);
+            let m = open_manifest(&temp);
 
             m.set_active_wal(5).unwrap();
             m.add_frozen_wal(9).unwrap();
@@ -284,7 +284,7 @@ mod tests {
         let temp = TempDir::new().unwrap();
 
         {
-            let mut m = open_manifest(&temp);
+            let m = open_manifest(&temp);
             m.update_lsn(123).unwrap();
             m.checkpoint().unwrap();
         }
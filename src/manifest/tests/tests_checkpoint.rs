@@ -11,6 +11,8 @@
 //! - Large manifest state survives checkpoint round-trip
 //! - Concurrent mutations between checkpoints correctly replay
 //! - Snapshot corruption detected on reopen
+//! - Applied idempotency tokens survive checkpoint and WAL replay
+//! - Pending compactions survive checkpoint and WAL replay until finished
 //!
 //! ## See also
 //! - [`tests_basic`]      — lifecycle, crash-recovery, checksum corruption
@@ -100,7 +102,7 @@ mod tests {
         let temp = TempDir::new().unwrap();
 
         {
-            let mut m = open_manifest(&temp);
+            let m = open_manifest(&temp);
 
             for i in 1..=5u64 {
                 m.add_sstable(sst_entry(i)).unwrap();
@@ -137,7 +139,7 @@ mod tests {
         let (id_a, id_b);
 
         {
-            let mut m = open_manifest(&temp);
+            let m = open_manifest(&temp);
 
             id_a = m.allocate_sst_id().unwrap();
             id_b = m.allocate_sst_id().unwrap();
@@ -210,7 +212,7 @@ mod tests {
         let temp = TempDir::new().unwrap();
 
         {
-            let mut m = open_manifest(&temp);
+            let m = open_manifest(&temp);
 
             for i in 1..=100u64 {
                 m.add_sstable(sst_entry(i)).unwrap();
@@ -249,7 +251,7 @@ mod tests {
         let temp = TempDir::new().unwrap();
 
         {
-            let mut m = open_manifest(&temp);
+            let m = open_manifest(&temp);
 
             // Pre-checkpoint state.
             m.add_sstable(sst_entry(1)).unwrap();
@@ -289,7 +291,7 @@ mod tests {
         let temp = TempDir::new().unwrap();
 
         {
-            let mut m = open_manifest(&temp);
+            let m = open_manifest(&temp);
             m.add_sstable(sst_entry(1)).unwrap();
             m.update_lsn(100).unwrap();
             m.checkpoint().unwrap();
@@ -327,7 +329,7 @@ mod tests {
         let temp = TempDir::new().unwrap();
 
         {
-            let mut m = open_manifest(&temp);
+            let m = open_manifest(&temp);
 
             // Add initial SSTables.
             m.add_sstable(sst_entry(1)).unwrap();
@@ -349,4 +351,137 @@ mod tests {
         assert!(ids.contains(&3), "SST 3 should remain");
         assert!(ids.contains(&4), "SST 4 should have been added");
     }
+
+    // ================================================================
+    // 9. Applied tokens survive checkpoint + replay, and prune
+    // ================================================================
+
+    /// # Scenario
+    /// Record applied tokens on both sides of a checkpoint, reopen, then
+    /// prune by age and by count.
+    ///
+    /// # Expected behavior
+    /// - After reopen, tokens from the snapshot and the WAL are found
+    ///   with their timestamps.
+    /// - Pruning drops tokens older than the cutoff, then the oldest
+    ///   ones beyond the count limit; a token recorded again keeps its
+    ///   newer timestamp.
+    #[test]
+    fn applied_tokens_survive_checkpoint() {
+        init_tracing();
+
+        let temp = TempDir::new().unwrap();
+
+        {
+            let m = open_manifest(&temp);
+            m.record_applied_token(b"a", 10).unwrap();
+            m.record_applied_token(b"b", 20).unwrap();
+            m.checkpoint().unwrap();
+            m.record_applied_token(b"c", 30).unwrap();
+            m.record_applied_token(b"a", 40).unwrap();
+        }
+
+        let m2 = open_manifest(&temp);
+        assert_eq!(m2.applied_token_timestamp(b"a").unwrap(), Some(40));
+        assert_eq!(m2.applied_token_timestamp(b"b").unwrap(), Some(20));
+        assert_eq!(m2.applied_token_timestamp(b"c").unwrap(), Some(30));
+        assert_eq!(m2.applied_token_timestamp(b"d").unwrap(), None);
+
+        // Drops a@10 and b@20 by age; a stays under its newer timestamp.
+        m2.prune_applied_tokens(25, 10).unwrap();
+        assert_eq!(m2.applied_token_timestamp(b"a").unwrap(), Some(40));
+        assert_eq!(m2.applied_token_timestamp(b"b").unwrap(), None);
+
+        // Keeps only the newest token.
+        m2.prune_applied_tokens(0, 1).unwrap();
+        assert_eq!(m2.applied_token_timestamp(b"c").unwrap(), None);
+        assert_eq!(m2.applied_token_timestamp(b"a").unwrap(), Some(40));
+    }
+
+    // ================================================================
+    // 10. Reserved LSN survives checkpoint + replay, never decreases
+    // ================================================================
+
+    /// # Scenario
+    /// Reserve LSNs on both sides of a checkpoint, including a lower
+    /// reservation after a higher one, then reopen.
+    ///
+    /// # Expected behavior
+    /// The highest reservation is restored; a lower one never replaces
+    /// it.
+    #[test]
+    fn reserved_lsn_survives_checkpoint() {
+        init_tracing();
+
+        let temp = TempDir::new().unwrap();
+
+        {
+            let m = open_manifest(&temp);
+            assert_eq!(m.get_reserved_lsn().unwrap(), 0);
+            m.reserve_lsn(100).unwrap();
+            m.checkpoint().unwrap();
+            m.reserve_lsn(300).unwrap();
+            m.reserve_lsn(200).unwrap();
+            assert_eq!(m.get_reserved_lsn().unwrap(), 300);
+        }
+
+        let m2 = open_manifest(&temp);
+        assert_eq!(m2.get_reserved_lsn().unwrap(), 300);
+
+        m2.checkpoint().unwrap();
+        drop(m2);
+        let m3 = open_manifest(&temp);
+        assert_eq!(m3.get_reserved_lsn().unwrap(), 300);
+    }
+
+    // ================================================================
+    // 11. Pending compactions survive checkpoint + replay until finished
+    // ================================================================
+
+    /// # Scenario
+    /// Begin one compaction before a checkpoint and another after it,
+    /// commit the first, reopen, then finish both.
+    ///
+    /// # Expected behavior
+    /// - After reopen both are pending, in order; the first is committed
+    ///   (its inputs are gone), the second is not.
+    /// - Once finished, neither comes back after a checkpoint and reopen.
+    #[test]
+    fn pending_compactions_survive_checkpoint() {
+        init_tracing();
+
+        let temp = TempDir::new().unwrap();
+
+        {
+            let m = open_manifest(&temp);
+            for id in 1..=4 {
+                m.add_sstable(sst_entry(id)).unwrap();
+            }
+            m.begin_compaction(vec![sst_entry(1), sst_entry(2)], vec![sst_entry(5)])
+                .unwrap();
+            m.checkpoint().unwrap();
+            m.begin_compaction(vec![sst_entry(3)], Vec::new()).unwrap();
+            m.apply_compaction(vec![sst_entry(5)], vec![1, 2]).unwrap();
+        }
+
+        let m2 = open_manifest(&temp);
+        let pending = m2.pending_compactions().unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].input_ids(), vec![1, 2]);
+        assert_eq!(pending[0].outputs, vec![sst_entry(5)]);
+        assert_eq!(pending[1].input_ids(), vec![3]);
+        assert!(pending[1].outputs.is_empty());
+
+        let live: Vec<u64> = m2.get_sstables().unwrap().iter().map(|e| e.id).collect();
+        assert!(pending[0].is_committed(&live));
+        assert!(!pending[1].is_committed(&live));
+
+        m2.finish_compaction(&[1, 2]).unwrap();
+        m2.checkpoint().unwrap();
+        m2.finish_compaction(&[3]).unwrap();
+        drop(m2);
+
+        let m3 = open_manifest(&temp);
+        assert!(m3.pending_compactions().unwrap().is_empty());
+    }
 }
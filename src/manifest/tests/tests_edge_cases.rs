@@ -7,7 +7,7 @@
 //!
 //! Coverage:
 //! - `remove_sstable()` on a nonexistent ID → silent no-op
-//! - `remove_frozen_wal()` on a nonexistent ID → silent no-op
+//! - `remove_frozen_wals()` on a nonexistent ID → silent no-op
 //! - `add_sstable()` with duplicate ID → idempotent (1 entry)
 //! - `checkpoint()` immediately after open (empty state)
 //! - Post-checkpoint WAL mutations survive reopen
@@ -82,7 +82,7 @@ mod tests {
     /// Fresh manifest with no frozen WALs.
     ///
     /// # Actions
-    /// 1. `remove_frozen_wal(999)`.
+    /// 1. `remove_frozen_wals(&[999])`.
     ///
     /// # Expected behavior
     /// No error. Frozen WAL list remains empty.
@@ -93,7 +93,7 @@ mod tests {
         let temp = TempDir::new().unwrap();
         let m = open_manifest(&temp);
 
-        m.remove_frozen_wal(999).unwrap();
+        m.remove_frozen_wals(&[999]).unwrap();
 
         assert!(
             m.get_frozen_wals().unwrap().is_empty(),
@@ -162,7 +162,7 @@ mod tests {
         let temp = TempDir::new().unwrap();
 
         {
-            let mut m = open_manifest(&temp);
+            let m = open_manifest(&temp);
             m.checkpoint().unwrap();
         }
 
@@ -200,7 +200,7 @@ mod tests {
         let temp = TempDir::new().unwrap();
 
         {
-            let mut m = open_manifest(&temp);
+            let m = open_manifest(&temp);
 
             // Phase 1: Build initial state and checkpoint
             m.add_sstable(sst_entry(1)).unwrap();
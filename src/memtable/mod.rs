@@ -17,12 +17,19 @@
 //! - Returned records are sufficient to reconstruct the same memtable
 //!   state via WAL replay.
 //! - Flush iteration does **not** mutate or clear in-memory state.
+//! - `flush_view` yields the same point entries borrowed from the tree
+//!   under its read lock, so a flush of one memtable copies no key or
+//!   value before the SSTable writer encodes it.
 //!
 //! ## Frozen Memtable
 //!
 //! - A `FrozenMemtable` is read-only.
 //! - It retains ownership of the WAL to guarantee durability until
 //!   data is persisted to SSTables.
+//! - Since its keys no longer change, its first point lookup or scan
+//!   builds a `FrozenIndex` — fence keys and a bloom filter — that lets
+//!   later lookups, and scans of ranges outside its keys, skip it without
+//!   taking its lock.
 
 // ------------------------------------------------------------------------------------------------
 // Unit tests
@@ -40,14 +47,18 @@ use std::{
     collections::BTreeMap,
     path::Path,
     sync::{
-        Arc, RwLock,
-        atomic::{AtomicU64, Ordering},
+        Arc, OnceLock, RwLock, RwLockReadGuard,
+        atomic::{AtomicBool, AtomicU64, Ordering},
     },
-    time::{SystemTime, UNIX_EPOCH},
 };
 
-use crate::engine::Record;
-use crate::wal::{Wal, WalError};
+use crate::batch::WriteOp;
+use crate::clock::{Clock, SystemClock};
+
+use crate::engine::{PointEntryRef, Record, WriteOptions};
+use crate::sketch::KeySketch;
+use crate::wal::{Wal, WalCounters, WalError, WalRecoveryMode};
+use bloomfilter::Bloom;
 use thiserror::Error;
 use tracing::{error, info, trace};
 
@@ -73,6 +84,11 @@ pub enum MemtableError {
     /// Internal invariant violation or poisoned lock.
     #[error("Internal error: {0}")]
     Internal(String),
+
+    /// A value no longer matches the checksum taken when it was written,
+    /// so it was corrupted in memory.
+    #[error("Value checksum mismatch for key {0}")]
+    ValueChecksumMismatch(String),
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -105,6 +121,16 @@ pub struct Memtable {
 
     /// Monotonic log sequence number (LSN) for version ordering.
     next_lsn: AtomicU64,
+
+    /// Set once a write skips the WAL; such a memtable must be flushed
+    /// before shutdown or its unlogged writes are lost.
+    unlogged: AtomicBool,
+
+    /// Whether new puts record a checksum of their value.
+    value_checksums: AtomicBool,
+
+    /// Source of the timestamps of new records.
+    clock: Arc<dyn Clock>,
 }
 
 /// A single versioned point entry stored in the memtable.
@@ -124,6 +150,8 @@ pub enum MemtablePointEntry {
         timestamp: u64,
         /// Log sequence number for ordering updates.
         lsn: u64,
+        /// CRC-32 of `value`, taken when value checksums are enabled.
+        checksum: Option<u32>,
     },
     /// A point tombstone (deletion marker).
     Delete {
@@ -143,13 +171,22 @@ impl MemtablePointEntry {
     }
 
     /// Returns the timestamp of this entry, regardless of variant.
-    #[allow(dead_code)]
     pub fn timestamp(&self) -> u64 {
         match self {
             Self::Put { timestamp, .. } | Self::Delete { timestamp, .. } => *timestamp,
         }
     }
 
+    /// Returns where this entry stands against other versions of its
+    /// key; see [`VersionRank`].
+    pub fn rank(&self) -> VersionRank {
+        VersionRank {
+            lsn: self.lsn(),
+            point: true,
+            timestamp: self.timestamp(),
+        }
+    }
+
     /// Returns `true` if this entry is a deletion tombstone.
     #[allow(dead_code)]
     pub fn is_delete(&self) -> bool {
@@ -164,12 +201,28 @@ impl MemtablePointEntry {
             Self::Delete { .. } => None,
         }
     }
+
+    /// Returns `false` if this is a `Put` whose value no longer matches
+    /// its checksum. Entries without a checksum always pass.
+    pub fn checksum_matches(&self) -> bool {
+        match self {
+            Self::Put {
+                value,
+                checksum: Some(checksum),
+                ..
+            } => crc32fast::hash(value) == *checksum,
+            _ => true,
+        }
+    }
 }
 
 /// Discriminant tag used in the binary encoding of [`MemtablePointEntry`].
 const POINT_ENTRY_TAG_PUT: u8 = 0;
 const POINT_ENTRY_TAG_DELETE: u8 = 1;
 
+/// False positive rate of the bloom filter in a [`FrozenIndex`].
+const FROZEN_BLOOM_FALSE_POSITIVE_RATE: f64 = 0.01;
+
 impl crate::encoding::Encode for MemtablePointEntry {
     fn encode_to(&self, buf: &mut Vec<u8>) -> Result<(), crate::encoding::EncodingError> {
         match self {
@@ -177,11 +230,13 @@ impl crate::encoding::Encode for MemtablePointEntry {
                 value,
                 timestamp,
                 lsn,
+                checksum,
             } => {
                 crate::encoding::Encode::encode_to(&POINT_ENTRY_TAG_PUT, buf)?;
                 crate::encoding::Encode::encode_to(value, buf)?;
                 crate::encoding::Encode::encode_to(timestamp, buf)?;
                 crate::encoding::Encode::encode_to(lsn, buf)?;
+                crate::encoding::Encode::encode_to(checksum, buf)?;
             }
             Self::Delete { timestamp, lsn } => {
                 crate::encoding::Encode::encode_to(&POINT_ENTRY_TAG_DELETE, buf)?;
@@ -204,11 +259,15 @@ impl crate::encoding::Decode for MemtablePointEntry {
                 offset += n;
                 let (lsn, n) = <u64 as crate::encoding::Decode>::decode_from(&buf[offset..])?;
                 offset += n;
+                let (checksum, n) =
+                    <Option<u32> as crate::encoding::Decode>::decode_from(&buf[offset..])?;
+                offset += n;
                 Ok((
                     Self::Put {
                         value,
                         timestamp,
                         lsn,
+                        checksum,
                     },
                     offset,
                 ))
@@ -229,6 +288,7 @@ impl crate::encoding::Decode for MemtablePointEntry {
 }
 
 use crate::engine::RangeTombstone;
+use crate::engine::utils::{VersionRank, below_end};
 
 /// Result of a `get` operation on the memtable.
 #[derive(Debug, PartialEq)]
@@ -258,6 +318,14 @@ pub struct MemtableStats {
     /// Number of distinct keys with at least one point version.
     pub key_count: usize,
 
+    /// Number of distinct keys whose newest point version is a `Put`.
+    /// Range tombstones are not taken into account.
+    pub live_key_count: usize,
+
+    /// Smallest and largest key with a point version, or `None` if there
+    /// is none.
+    pub key_range: Option<(Vec<u8>, Vec<u8>)>,
+
     /// Total number of point entry versions across all keys.
     pub entry_count: usize,
 
@@ -268,6 +336,21 @@ pub struct MemtableStats {
     pub range_tombstone_count: usize,
 }
 
+/// What WAL replay did while constructing a memtable.
+///
+/// Returned by [`Memtable::recover`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WalReplayStats {
+    /// Records replayed into the memtable.
+    pub records: u64,
+
+    /// Bytes of damaged records cut off the WAL tail.
+    pub dropped_bytes: u64,
+
+    /// Highest record timestamp replayed, or 0 if none was.
+    pub newest_timestamp: u64,
+}
+
 /// Internal shared state of the memtable.
 ///
 /// This structure is protected by an `RwLock` and must never be
@@ -284,6 +367,9 @@ struct MemtableInner {
 
     /// Configured maximum buffer size before flush is required.
     write_buffer_size: usize,
+
+    /// Timestamp of the oldest record held, or `None` while empty.
+    oldest_timestamp: Option<u64>,
 }
 
 impl Memtable {
@@ -301,11 +387,41 @@ impl Memtable {
     ///
     /// # Crash Safety
     /// WAL replay guarantees recovery to the last durable state after a crash.
+    /// Damaged records are handled per [`WalRecoveryMode::default`].
     pub fn new<P: AsRef<Path>>(
         wal_path: P,
         max_record_size: Option<u32>,
         write_buffer_size: usize,
     ) -> Result<Self, MemtableError> {
+        Self::with_recovery_mode(
+            wal_path,
+            max_record_size,
+            write_buffer_size,
+            WalRecoveryMode::default(),
+        )
+    }
+
+    /// Like [`new`](Self::new), but replays the WAL with an explicit
+    /// [`WalRecoveryMode`] deciding how torn or corrupt records are handled.
+    pub fn with_recovery_mode<P: AsRef<Path>>(
+        wal_path: P,
+        max_record_size: Option<u32>,
+        write_buffer_size: usize,
+        recovery_mode: WalRecoveryMode,
+    ) -> Result<Self, MemtableError> {
+        Self::recover(wal_path, max_record_size, write_buffer_size, recovery_mode)
+            .map(|(memtable, _)| memtable)
+    }
+
+    /// Like [`with_recovery_mode`](Self::with_recovery_mode), but also
+    /// reports how many records were replayed and how much of a damaged
+    /// tail was dropped.
+    pub fn recover<P: AsRef<Path>>(
+        wal_path: P,
+        max_record_size: Option<u32>,
+        write_buffer_size: usize,
+        recovery_mode: WalRecoveryMode,
+    ) -> Result<(Self, WalReplayStats), MemtableError> {
         info!("Initializing Memtable with WAL replay");
 
         let wal = Wal::open(&wal_path, max_record_size)?;
@@ -315,13 +431,23 @@ impl Memtable {
             range_tombstones: BTreeMap::new(),
             approximate_size: 0,
             write_buffer_size,
+            oldest_timestamp: None,
         };
 
         let mut max_lsn_seen: u64 = 0;
 
-        let records = wal.replay_iter()?;
-        for record in records {
+        let mut replay = WalReplayStats::default();
+        let mut records = wal.recover_iter(recovery_mode)?;
+        for record in records.by_ref() {
             let record: Record = record?;
+            replay.records += 1;
+            let timestamp = record.timestamp();
+            replay.newest_timestamp = replay.newest_timestamp.max(timestamp);
+            inner.oldest_timestamp = Some(
+                inner
+                    .oldest_timestamp
+                    .map_or(timestamp, |oldest| oldest.min(timestamp)),
+            );
 
             match record {
                 Record::Put {
@@ -342,6 +468,7 @@ impl Memtable {
                         value,
                         timestamp,
                         lsn,
+                        checksum: None,
                     };
 
                     inner
@@ -402,16 +529,36 @@ impl Memtable {
             }
         }
 
+        replay.dropped_bytes = records.dropped_bytes();
+
         info!(
             "Memtable initialized successfully with LSN: {}",
             max_lsn_seen
         );
 
-        Ok(Self {
+        let memtable = Self {
             inner: Arc::new(RwLock::new(inner)),
             wal,
             next_lsn: AtomicU64::new(max_lsn_seen.saturating_add(1)),
-        })
+            unlogged: AtomicBool::new(false),
+            value_checksums: AtomicBool::new(false),
+            clock: Arc::new(SystemClock),
+        };
+        Ok((memtable, replay))
+    }
+
+    /// Stamps new records, and the memtable once frozen, with times read
+    /// from `clock` instead of the system clock.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Counts the appends and syncs of the WAL into `metrics`, shared with
+    /// the other memtables of an engine.
+    pub(crate) fn with_wal_metrics(mut self, metrics: Arc<WalCounters>) -> Self {
+        self.wal.set_metrics(metrics);
+        self
     }
 
     /// Inserts or updates a key with a new value.
@@ -422,6 +569,16 @@ impl Memtable {
     /// - The record is appended to the WAL with **no lock held**.
     /// - The in-memory tree is updated under a short write lock.
     pub fn put(&self, key: Vec<u8>, value: Vec<u8>) -> Result<(), MemtableError> {
+        self.put_opt(key, value, &WriteOptions::default())
+    }
+
+    /// Like [`put`](Self::put), with per-write [`WriteOptions`].
+    pub fn put_opt(
+        &self,
+        key: Vec<u8>,
+        value: Vec<u8>,
+        opts: &WriteOptions,
+    ) -> Result<(), MemtableError> {
         trace!("put() started, key: {}", HexKey(&key));
 
         if key.is_empty() || value.is_empty() {
@@ -431,12 +588,19 @@ impl Memtable {
         }
 
         let record_size = std::mem::size_of::<MemtablePointEntry>() + key.len() + value.len();
+        // Taken before the value is copied for the WAL, so the checksum
+        // covers the bytes the caller handed in.
+        let checksum = self
+            .value_checksums
+            .load(Ordering::Relaxed)
+            .then(|| crc32fast::hash(&value));
         let key_for_wal = key.clone();
         let value_for_wal = value.clone();
 
         let lsn = self.apply_write(
             record_size,
             "put",
+            opts,
             |lsn, timestamp| Record::Put {
                 key: key_for_wal,
                 value: value_for_wal,
@@ -448,6 +612,7 @@ impl Memtable {
                     value,
                     timestamp,
                     lsn,
+                    checksum,
                 };
                 inner
                     .tree
@@ -469,6 +634,11 @@ impl Memtable {
     /// - The record is appended to the WAL with **no lock held**.
     /// - The in-memory tree is updated under a short write lock.
     pub fn delete(&self, key: Vec<u8>) -> Result<(), MemtableError> {
+        self.delete_opt(key, &WriteOptions::default())
+    }
+
+    /// Like [`delete`](Self::delete), with per-write [`WriteOptions`].
+    pub fn delete_opt(&self, key: Vec<u8>, opts: &WriteOptions) -> Result<(), MemtableError> {
         trace!("delete() started, key: {}", HexKey(&key));
 
         if key.is_empty() {
@@ -481,6 +651,7 @@ impl Memtable {
         let lsn = self.apply_write(
             record_size,
             "delete",
+            opts,
             |lsn, timestamp| Record::Delete {
                 key: key_for_wal,
                 lsn,
@@ -512,6 +683,17 @@ impl Memtable {
     /// - The range tombstone is appended to the WAL with **no lock held**.
     /// - The in-memory tombstone map is updated under a short write lock.
     pub fn delete_range(&self, start: Vec<u8>, end: Vec<u8>) -> Result<(), MemtableError> {
+        self.delete_range_opt(start, end, &WriteOptions::default())
+    }
+
+    /// Like [`delete_range`](Self::delete_range), with per-write
+    /// [`WriteOptions`].
+    pub fn delete_range_opt(
+        &self,
+        start: Vec<u8>,
+        end: Vec<u8>,
+        opts: &WriteOptions,
+    ) -> Result<(), MemtableError> {
         trace!(
             "delete_range() started, start key: {}, end key: {}",
             HexKey(&start),
@@ -530,6 +712,33 @@ impl Memtable {
             ));
         }
 
+        self.write_range_tombstone(start, end, opts)
+    }
+
+    /// Deletes every key at or after `start`.
+    ///
+    /// Writes a range tombstone with the unbounded (empty) end, so keys of
+    /// any length past `start` are covered; see
+    /// [`RangeTombstone`](crate::engine::RangeTombstone).
+    pub fn delete_range_from(&self, start: Vec<u8>) -> Result<(), MemtableError> {
+        trace!("delete_range_from() started, start key: {}", HexKey(&start));
+
+        if start.is_empty() {
+            return Err(MemtableError::InvalidArgument(
+                "Start key is empty".to_string(),
+            ));
+        }
+
+        self.write_range_tombstone(start, Vec::new(), &WriteOptions::default())
+    }
+
+    /// Logs and applies a range tombstone whose bounds have been checked.
+    fn write_range_tombstone(
+        &self,
+        start: Vec<u8>,
+        end: Vec<u8>,
+        opts: &WriteOptions,
+    ) -> Result<(), MemtableError> {
         let record_size = std::mem::size_of::<RangeTombstone>() + start.len() + end.len();
         let start_for_wal = start.clone();
         let end_for_wal = end.clone();
@@ -537,6 +746,7 @@ impl Memtable {
         let lsn = self.apply_write(
             record_size,
             "delete_range",
+            opts,
             |lsn, timestamp| Record::RangeDelete {
                 start: start_for_wal,
                 end: end_for_wal,
@@ -563,11 +773,177 @@ impl Memtable {
         Ok(())
     }
 
+    /// Applies puts and deletes whose keys are strictly ascending as one
+    /// write.
+    ///
+    /// # Behavior
+    /// - The whole batch is checked against the write buffer up front;
+    ///   nothing is written if it does not fit.
+    /// - Consecutive LSNs are allocated and every operation is appended
+    ///   to the WAL as its own record, with **no lock held**. With
+    ///   [`WriteOptions::sync`] the WAL is fsynced once, after the last.
+    /// - Under one write lock, a batch at least as large as the memtable
+    ///   is merged with it in a single pass and the tree rebuilt from the
+    ///   sorted result, instead of inserting one key at a time.
+    ///
+    /// # Errors
+    /// - [`MemtableError::InvalidArgument`] for a range delete, an empty
+    ///   key or value, or keys out of order.
+    /// - [`MemtableError::FlushRequired`] if the batch does not fit.
+    pub fn write_sorted(&self, ops: &[WriteOp], opts: &WriteOptions) -> Result<(), MemtableError> {
+        trace!("write_sorted() started, ops: {}", ops.len());
+
+        let mut record_size = 0;
+        let mut last_key: Option<&[u8]> = None;
+        for op in ops {
+            let (key, value_len) = match op {
+                WriteOp::Put { key, value } if !value.is_empty() => (key, value.len()),
+                WriteOp::Delete { key } => (key, 0),
+                _ => {
+                    return Err(MemtableError::InvalidArgument(
+                        "Sorted writes must be non-empty puts and deletes".to_string(),
+                    ));
+                }
+            };
+            if key.is_empty() || last_key.is_some_and(|last| last >= key.as_slice()) {
+                return Err(MemtableError::InvalidArgument(
+                    "Sorted writes need non-empty, strictly ascending keys".to_string(),
+                ));
+            }
+            last_key = Some(key);
+            record_size += std::mem::size_of::<MemtablePointEntry>() + key.len() + value_len;
+        }
+        if ops.is_empty() {
+            return Ok(());
+        }
+
+        // 1. Buffer check for the whole batch.
+        {
+            let guard = self.inner.read().map_err(|_| {
+                error!("Read-write lock poisoned during write_sorted");
+                MemtableError::Internal("Read-write lock poisoned".into())
+            })?;
+            if guard.approximate_size + record_size > guard.write_buffer_size {
+                return Err(MemtableError::FlushRequired);
+            }
+        }
+
+        // 2. One LSN per operation, in order.
+        let first_lsn = self.next_lsn.fetch_add(ops.len() as u64, Ordering::SeqCst);
+        let timestamp = self.clock.now_nanos();
+        // Checksums are taken before the values are copied for the WAL,
+        // so they cover the bytes the caller handed in.
+        let checksums = self.value_checksums.load(Ordering::Relaxed);
+        let records: Vec<(Record, Option<u32>)> = ops
+            .iter()
+            .zip(first_lsn..)
+            .map(|(op, lsn)| match op {
+                WriteOp::Put { key, value } => {
+                    let checksum = checksums.then(|| crc32fast::hash(value));
+                    let record = Record::Put {
+                        key: key.clone(),
+                        value: value.clone(),
+                        lsn,
+                        timestamp,
+                    };
+                    (record, checksum)
+                }
+                WriteOp::Delete { key } => {
+                    let record = Record::Delete {
+                        key: key.clone(),
+                        lsn,
+                        timestamp,
+                    };
+                    (record, None)
+                }
+                WriteOp::DeleteRange { .. } => unreachable!("rejected above"),
+            })
+            .collect();
+
+        // 3. WAL append — durable writes with no lock held.
+        if opts.disable_wal {
+            self.unlogged.store(true, Ordering::Release);
+        } else {
+            for (record, _) in &records {
+                self.wal.append(record)?;
+            }
+            if opts.sync {
+                self.wal.sync()?;
+            }
+        }
+
+        let entries = records.into_iter().map(|(record, checksum)| match record {
+            Record::Put {
+                key,
+                value,
+                lsn,
+                timestamp,
+            } => {
+                let entry = MemtablePointEntry::Put {
+                    value,
+                    timestamp,
+                    lsn,
+                    checksum,
+                };
+                (key, entry)
+            }
+            Record::Delete {
+                key,
+                lsn,
+                timestamp,
+            } => (key, MemtablePointEntry::Delete { timestamp, lsn }),
+            Record::RangeDelete { .. } => unreachable!("rejected above"),
+        });
+
+        // 4. In-memory update under one write lock.
+        let mut guard = self.inner.write().map_err(|_| {
+            error!("Read-write lock poisoned during write_sorted");
+            MemtableError::Internal("Read-write lock poisoned".into())
+        })?;
+
+        if ops.len() < guard.tree.len() {
+            for (key, entry) in entries {
+                guard
+                    .tree
+                    .entry(key)
+                    .or_default()
+                    .insert(Reverse(entry.lsn()), entry);
+            }
+        } else {
+            // Merge join of two sorted sequences; collecting sorted input
+            // builds the tree bottom-up.
+            let mut existing = std::mem::take(&mut guard.tree).into_iter().peekable();
+            let mut merged = Vec::with_capacity(existing.len() + ops.len());
+            for (key, entry) in entries {
+                merged.extend(std::iter::from_fn(|| {
+                    existing.next_if(|(older, _)| *older < key)
+                }));
+                let mut versions = existing
+                    .next_if(|(older, _)| *older == key)
+                    .map_or_else(BTreeMap::new, |(_, versions)| versions);
+                versions.insert(Reverse(entry.lsn()), entry);
+                merged.push((key, versions));
+            }
+            merged.extend(existing);
+            guard.tree = merged.into_iter().collect();
+        }
+        guard.approximate_size += record_size;
+        guard.oldest_timestamp.get_or_insert(timestamp);
+
+        trace!(
+            "write_sorted completed with LSNs {}..={}",
+            first_lsn,
+            first_lsn + ops.len() as u64 - 1
+        );
+        Ok(())
+    }
+
     /// Shared write path: budget check → LSN allocation → WAL append → in-memory update.
     ///
     /// # Arguments
     /// - `record_size` — estimated byte cost of this write for budget tracking.
     /// - `op_name` — operation label used in error messages and tracing.
+    /// - `opts` — skips the WAL append, or fsyncs after it.
     /// - `build_record` — closure that receives `(lsn, timestamp)` and returns
     ///   the WAL [`Record`] to be durably appended.
     /// - `apply_to_inner` — closure that performs the in-memory insertion;
@@ -579,6 +955,7 @@ impl Memtable {
         &self,
         record_size: usize,
         op_name: &str,
+        opts: &WriteOptions,
         build_record: F,
         apply_to_inner: G,
     ) -> Result<u64, MemtableError>
@@ -599,11 +976,18 @@ impl Memtable {
 
         // 2. Allocate LSN only after confirming budget.
         let lsn = self.next_lsn.fetch_add(1, Ordering::SeqCst);
-        let timestamp = Self::current_timestamp();
+        let timestamp = self.clock.now_nanos();
 
         // 3. WAL append — durable write with no lock held.
-        let record = build_record(lsn, timestamp);
-        self.wal.append(&record)?;
+        if opts.disable_wal {
+            self.unlogged.store(true, Ordering::Release);
+        } else {
+            let record = build_record(lsn, timestamp);
+            self.wal.append(&record)?;
+            if opts.sync {
+                self.wal.sync()?;
+            }
+        }
 
         // 4. In-memory update — write lock held only for the insert.
         let mut guard = self.inner.write().map_err(|_| {
@@ -613,6 +997,7 @@ impl Memtable {
 
         apply_to_inner(&mut guard, lsn, timestamp);
         guard.approximate_size += record_size;
+        guard.oldest_timestamp.get_or_insert(timestamp);
 
         Ok(lsn)
     }
@@ -628,6 +1013,27 @@ impl Memtable {
     /// - `Ok(Some(value))` if visible
     /// - `Ok(None)` if deleted or not present
     pub fn get(&self, key: &[u8]) -> Result<MemtableGetResult, MemtableError> {
+        Ok(match self.get_record(key, true)? {
+            Some(Record::Put { value, .. }) => MemtableGetResult::Put(value),
+            Some(Record::Delete { .. }) => MemtableGetResult::Delete,
+            Some(Record::RangeDelete { .. }) => MemtableGetResult::RangeDelete,
+            None => MemtableGetResult::NotFound,
+        })
+    }
+
+    /// Returns the record that decides the visible state of a key, with its
+    /// LSN and timestamp: the newest point entry, or the newest range
+    /// tombstone covering the key if that is newer. `None` if the memtable
+    /// has no information about the key.
+    ///
+    /// Same resolution rules as [`get`](Self::get). With
+    /// `verify_value_checksum`, a value that no longer matches its checksum
+    /// fails with [`MemtableError::ValueChecksumMismatch`].
+    pub fn get_record(
+        &self,
+        key: &[u8],
+        verify_value_checksum: bool,
+    ) -> Result<Option<Record>, MemtableError> {
         trace!("get() started, key: {}", HexKey(key));
 
         let guard = self.inner.read().map_err(|_| {
@@ -645,15 +1051,13 @@ impl Memtable {
         // For each start key, we check ALL versions (not just the highest-LSN)
         // because a narrower tombstone with a higher LSN might not cover the
         // queried key while a wider tombstone with a lower LSN does.
-        let mut covering_tombstone_lsn: Option<u64> = None;
+        let mut covering_tombstone: Option<&RangeTombstone> = None;
         for (_start, versions) in guard.range_tombstones.range(..=key.to_vec()) {
             for tombstone in versions.values() {
-                if tombstone.start.as_slice() <= key && key < tombstone.end.as_slice() {
-                    covering_tombstone_lsn = Some(
-                        covering_tombstone_lsn
-                            .map(|lsn| lsn.max(tombstone.lsn))
-                            .unwrap_or(tombstone.lsn),
-                    );
+                if tombstone.covers(key) {
+                    if covering_tombstone.is_none_or(|t| tombstone.rank() > t.rank()) {
+                        covering_tombstone = Some(tombstone);
+                    }
                     // Found the highest-LSN covering tombstone for this start
                     // key — no need to check lower-LSN versions for the same
                     // start key (they can only have equal or lower LSN).
@@ -662,33 +1066,58 @@ impl Memtable {
             }
         }
 
-        match (point_opt, covering_tombstone_lsn) {
+        let point_record = |point: &MemtablePointEntry| match point {
+            MemtablePointEntry::Delete { lsn, timestamp } => Ok(Record::Delete {
+                key: key.to_vec(),
+                lsn: *lsn,
+                timestamp: *timestamp,
+            }),
+            MemtablePointEntry::Put { .. }
+                if verify_value_checksum && !point.checksum_matches() =>
+            {
+                error!("Value checksum mismatch, key: {}", HexKey(key));
+                Err(MemtableError::ValueChecksumMismatch(
+                    HexKey(key).to_string(),
+                ))
+            }
+            MemtablePointEntry::Put {
+                value,
+                timestamp,
+                lsn,
+                ..
+            } => Ok(Record::Put {
+                key: key.to_vec(),
+                value: value.clone(),
+                lsn: *lsn,
+                timestamp: *timestamp,
+            }),
+        };
+        let tombstone_record = |tombstone: &RangeTombstone| Record::RangeDelete {
+            start: tombstone.start.clone(),
+            end: tombstone.end.clone(),
+            lsn: tombstone.lsn,
+            timestamp: tombstone.timestamp,
+        };
+
+        Ok(match (point_opt, covering_tombstone) {
             // No point entry and no tombstone → key not found
-            (None, None) => Ok(MemtableGetResult::NotFound),
+            (None, None) => None,
 
             // No point entry but covered by range tombstone
-            (None, Some(_)) => Ok(MemtableGetResult::RangeDelete),
+            (None, Some(tombstone)) => Some(tombstone_record(tombstone)),
 
             // Point entry exists, no covering tombstone
-            (Some(point), None) => match point {
-                MemtablePointEntry::Delete { .. } => Ok(MemtableGetResult::Delete),
-                MemtablePointEntry::Put { value, .. } => Ok(MemtableGetResult::Put(value.clone())),
-            },
+            (Some(point), None) => Some(point_record(point)?),
 
-            // Both point entry and tombstone exist → compare LSNs
-            (Some(point), Some(tombstone_lsn)) => {
-                if tombstone_lsn > point.lsn() {
-                    Ok(MemtableGetResult::RangeDelete)
+            // Both point entry and tombstone exist → compare ranks
+            (Some(point), Some(tombstone)) => {
+                if tombstone.hides(key, point.rank()) {
+                    Some(tombstone_record(tombstone))
                 } else {
-                    match point {
-                        MemtablePointEntry::Delete { .. } => Ok(MemtableGetResult::Delete),
-                        MemtablePointEntry::Put { value, .. } => {
-                            Ok(MemtableGetResult::Put(value.clone()))
-                        }
-                    }
+                    Some(point_record(point)?)
                 }
             }
-        }
+        })
     }
 
     /// Performs an ordered range scan over `[start, end)`.
@@ -704,11 +1133,28 @@ impl Memtable {
         &self,
         start: &[u8],
         end: &[u8],
+    ) -> Result<impl Iterator<Item = Record>, MemtableError> {
+        self.scan_at(start, end, u64::MAX)
+    }
+
+    /// Performs an ordered range scan over `[start, end)` that sees only
+    /// the records with an LSN at or below `max_lsn`.
+    ///
+    /// Same output as [`scan`](Self::scan) for a memtable that never saw
+    /// the writes after `max_lsn`: newer versions, point tombstones, and
+    /// range tombstones are left out, so an older version they shadow is
+    /// returned instead.
+    pub fn scan_at(
+        &self,
+        start: &[u8],
+        end: &[u8],
+        max_lsn: u64,
     ) -> Result<impl Iterator<Item = Record>, MemtableError> {
         trace!(
-            "scan() started with range. Start key: {} end key: {}",
+            "scan() started with range. Start key: {} end key: {} max LSN: {}",
             HexKey(start),
-            HexKey(end)
+            HexKey(end),
+            max_lsn
         );
 
         if start >= end {
@@ -724,7 +1170,7 @@ impl Memtable {
 
         // 1) Collect point entries
         for (key, versions) in guard.tree.range(start.to_vec()..end.to_vec()) {
-            for entry in versions.values() {
+            for (_, entry) in versions.range(Reverse(max_lsn)..) {
                 let record = match entry {
                     MemtablePointEntry::Delete { lsn, timestamp } => Record::Delete {
                         key: key.clone(),
@@ -735,6 +1181,7 @@ impl Memtable {
                         value,
                         lsn,
                         timestamp,
+                        ..
                     } => Record::Put {
                         key: key.clone(),
                         value: value.clone(),
@@ -749,9 +1196,9 @@ impl Memtable {
 
         // 2) Collect range tombstones
         for (_tombstone_start, versions) in guard.range_tombstones.iter() {
-            for tombstone in versions.values() {
+            for (_, tombstone) in versions.range(Reverse(max_lsn)..) {
                 // Check if tombstone overlaps scan range
-                if tombstone.end.as_slice() <= start || tombstone.start.as_slice() >= end {
+                if !below_end(start, &tombstone.end) || tombstone.start.as_slice() >= end {
                     continue;
                 }
 
@@ -783,7 +1230,9 @@ impl Memtable {
     /// Returns a logical snapshot of the memtable suitable for flushing.
     ///
     /// The iterator emits:
-    /// - The latest version of every point key (put or delete)
+    /// - The latest version of every point key (put or delete), and the
+    ///   older versions down to the newest one at or below `horizon`,
+    ///   newest first
     /// - **All** range tombstones
     ///
     /// # Guarantees
@@ -792,8 +1241,14 @@ impl Memtable {
     /// - Does not mutate in-memory state
     ///
     /// # Intended Use
-    /// This iterator is consumed by the SSTable writer.
-    pub fn iter_for_flush(&self) -> Result<impl Iterator<Item = Record>, MemtableError> {
+    /// This iterator is consumed by the SSTable writer. Every value with a
+    /// checksum is verified first, so a value corrupted in memory fails the
+    /// flush with [`MemtableError::ValueChecksumMismatch`] instead of
+    /// reaching disk.
+    pub fn iter_for_flush(
+        &self,
+        horizon: u64,
+    ) -> Result<impl Iterator<Item = Record>, MemtableError> {
         let guard = self.inner.read().map_err(|_| {
             error!("Read-write lock poisoned during iter_for_flush");
             MemtableError::Internal("Read-write lock poisoned".into())
@@ -802,7 +1257,13 @@ impl Memtable {
         let mut records = Vec::new();
 
         for (key, versions) in guard.tree.iter() {
-            if let Some(entry) = versions.values().next() {
+            for entry in versions.values() {
+                if !entry.checksum_matches() {
+                    error!("Value checksum mismatch during flush, key: {}", HexKey(key));
+                    return Err(MemtableError::ValueChecksumMismatch(
+                        HexKey(key).to_string(),
+                    ));
+                }
                 let record = match entry {
                     MemtablePointEntry::Delete { lsn, timestamp } => Record::Delete {
                         key: key.clone(),
@@ -813,6 +1274,7 @@ impl Memtable {
                         value,
                         lsn,
                         timestamp,
+                        ..
                     } => Record::Put {
                         key: key.clone(),
                         value: value.clone(),
@@ -821,6 +1283,9 @@ impl Memtable {
                     },
                 };
                 records.push(record);
+                if entry.lsn() <= horizon {
+                    break;
+                }
             }
         }
 
@@ -839,8 +1304,37 @@ impl Memtable {
         Ok(records.into_iter())
     }
 
+    /// Returns a read-locked [`FlushView`] of the memtable, borrowing the
+    /// same entries [`iter_for_flush`](Self::iter_for_flush) copies.
+    ///
+    /// Every value with a checksum is verified first, failing with
+    /// [`MemtableError::ValueChecksumMismatch`] like `iter_for_flush`. The
+    /// read lock is held until the view is dropped.
+    pub(crate) fn flush_view(&self, horizon: u64) -> Result<FlushView<'_>, MemtableError> {
+        let guard = self.inner.read().map_err(|_| {
+            error!("Read-write lock poisoned during flush_view");
+            MemtableError::Internal("Read-write lock poisoned".into())
+        })?;
+
+        let mut point_count = 0;
+        for (key, entry) in flushed_versions(&guard.tree, horizon) {
+            if !entry.checksum_matches() {
+                error!("Value checksum mismatch during flush, key: {}", HexKey(key));
+                return Err(MemtableError::ValueChecksumMismatch(
+                    HexKey(key).to_string(),
+                ));
+            }
+            point_count += 1;
+        }
+
+        Ok(FlushView {
+            guard,
+            horizon,
+            point_count,
+        })
+    }
+
     /// Returns a snapshot of memtable statistics under a short read lock.
-    #[allow(dead_code)]
     pub fn stats(&self) -> Result<MemtableStats, MemtableError> {
         let guard = self.inner.read().map_err(|_| {
             error!("Read-write lock poisoned during stats");
@@ -849,8 +1343,16 @@ impl Memtable {
 
         let mut entry_count: usize = 0;
         let mut tombstone_count: usize = 0;
+        let mut live_key_count: usize = 0;
 
         for versions in guard.tree.values() {
+            if versions
+                .values()
+                .next()
+                .is_some_and(|entry| !entry.is_delete())
+            {
+                live_key_count += 1;
+            }
             for entry in versions.values() {
                 entry_count += 1;
                 if entry.is_delete() {
@@ -868,12 +1370,65 @@ impl Memtable {
         Ok(MemtableStats {
             size_bytes: guard.approximate_size,
             key_count: guard.tree.len(),
+            live_key_count,
+            key_range: guard
+                .tree
+                .first_key_value()
+                .zip(guard.tree.last_key_value())
+                .map(|((min, _), (max, _))| (min.clone(), max.clone())),
             entry_count,
             tombstone_count,
             range_tombstone_count,
         })
     }
 
+    /// Returns a distinct-key sketch of the point keys, tombstoned ones
+    /// included, as an SSTable flushed from this memtable would store.
+    pub(crate) fn key_sketch(&self) -> Result<KeySketch, MemtableError> {
+        let guard = self.inner.read().map_err(|_| {
+            error!("Read-write lock poisoned during key_sketch");
+            MemtableError::Internal("Read-write lock poisoned".into())
+        })?;
+
+        let mut sketch = KeySketch::new();
+        for key in guard.tree.keys() {
+            sketch.insert(key);
+        }
+        Ok(sketch)
+    }
+
+    /// Builds the point-read filter of the keys held now; see
+    /// [`FrozenIndex`].
+    fn frozen_index(&self) -> Result<FrozenIndex, MemtableError> {
+        let guard = self.inner.read().map_err(|_| {
+            error!("Read-write lock poisoned during frozen_index");
+            MemtableError::Internal("Read-write lock poisoned".into())
+        })?;
+
+        let mut bloom =
+            Bloom::new_for_fp_rate(guard.tree.len().max(1), FROZEN_BLOOM_FALSE_POSITIVE_RATE)
+                .map_err(|e| MemtableError::Internal(e.to_string()))?;
+        for key in guard.tree.keys() {
+            bloom.set(key.as_slice());
+        }
+        let fences = guard
+            .tree
+            .first_key_value()
+            .zip(guard.tree.last_key_value())
+            .map(|((min, _), (max, _))| (min.clone(), max.clone()));
+        let ranges = guard
+            .range_tombstones
+            .values()
+            .flat_map(BTreeMap::values)
+            .map(|tombstone| (tombstone.start.clone(), tombstone.end.clone()))
+            .collect();
+        Ok(FrozenIndex {
+            fences,
+            bloom,
+            ranges,
+        })
+    }
+
     /// Converts this mutable memtable into an immutable [`FrozenMemtable`].
     ///
     /// # Behavior
@@ -908,14 +1463,74 @@ impl Memtable {
         self.wal.wal_seq()
     }
 
-    /// Returns the current system timestamp in nanoseconds.
+    /// Changes the in-memory size at which writes return
+    /// [`MemtableError::FlushRequired`].
+    pub fn set_write_buffer_size(&self, write_buffer_size: usize) -> Result<(), MemtableError> {
+        let mut guard = self
+            .inner
+            .write()
+            .map_err(|_| MemtableError::Internal("Read-write lock poisoned".into()))?;
+        guard.write_buffer_size = write_buffer_size;
+        Ok(())
+    }
+
+    /// Enables or disables the fsync after every WAL append.
+    pub fn set_wal_sync(&self, sync: bool) {
+        self.wal.set_sync_appends(sync);
+    }
+
+    /// Enables or disables value checksums for subsequent puts. Enabling
+    /// them also checksums the values already held without one, such as
+    /// those just replayed from the WAL, whose records the WAL's own CRC
+    /// protected until then.
+    pub fn set_value_checksums(&self, enabled: bool) -> Result<(), MemtableError> {
+        self.value_checksums.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            return Ok(());
+        }
+        let mut guard = self
+            .inner
+            .write()
+            .map_err(|_| MemtableError::Internal("Read-write lock poisoned".into()))?;
+        for entry in guard.tree.values_mut().flat_map(BTreeMap::values_mut) {
+            if let MemtablePointEntry::Put {
+                value,
+                checksum: checksum @ None,
+                ..
+            } = entry
+            {
+                *checksum = Some(crc32fast::hash(value));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fsyncs every WAL record written so far.
+    pub fn sync_wal(&self) -> Result<(), MemtableError> {
+        Ok(self.wal.sync()?)
+    }
+
+    /// Returns `true` if any write to this memtable skipped the WAL.
+    pub fn has_unlogged_writes(&self) -> bool {
+        self.unlogged.load(Ordering::Acquire)
+    }
+
+    /// Returns the current size of the backing WAL file in bytes.
+    pub fn wal_size(&self) -> Result<u64, MemtableError> {
+        Ok(self.wal.file_size()?)
+    }
+
+    /// Returns the write timestamp (UNIX epoch nanos) of the oldest record
+    /// held, or `None` if the memtable is empty.
     ///
-    /// Used to tag entries for ordering and diagnostics.
-    fn current_timestamp() -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_nanos() as u64
+    /// Records replayed from the WAL keep their original timestamps, so
+    /// the age survives a restart.
+    pub fn oldest_timestamp(&self) -> Result<Option<u64>, MemtableError> {
+        let guard = self
+            .inner
+            .read()
+            .map_err(|_| MemtableError::Internal("Read-write lock poisoned".into()))?;
+        Ok(guard.oldest_timestamp)
     }
 }
 
@@ -933,17 +1548,70 @@ impl Memtable {
 /// This type represents a memtable that is in the process of being flushed
 /// to an on-disk SSTable.
 pub struct FrozenMemtable {
-    memtable: Memtable,
+    memtable: Arc<Memtable>,
     #[allow(dead_code)]
     creation_timestamp: u64,
+    /// Built by the first [`may_contain`](Self::may_contain) or
+    /// [`may_overlap`](Self::may_overlap).
+    index: OnceLock<FrozenIndex>,
+}
+
+/// Point-read filter of a [`FrozenMemtable`].
+///
+/// A lookup can find a record in the memtable only if the key lies
+/// between its smallest and largest point key — the fence keys — and
+/// passes the bloom filter of its point keys, or if a range tombstone of
+/// the memtable covers the key. Range tombstones are few, so they are
+/// kept as they are.
+struct FrozenIndex {
+    /// Smallest and largest point key, or `None` if there is none.
+    fences: Option<(Vec<u8>, Vec<u8>)>,
+    /// Bloom filter of the point keys.
+    bloom: Bloom<[u8]>,
+    /// Range tombstones, as `[start, end)`; an empty `end` is unbounded.
+    ranges: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl FrozenIndex {
+    fn may_contain(&self, key: &[u8]) -> bool {
+        let point = self.fences.as_ref().is_some_and(|(min, max)| {
+            min.as_slice() <= key && key <= max.as_slice() && self.bloom.check(key)
+        });
+        point
+            || self
+                .ranges
+                .iter()
+                .any(|(start, end)| start.as_slice() <= key && below_end(key, end))
+    }
+
+    fn may_overlap(&self, start: &[u8], end: &[u8]) -> bool {
+        let points = self
+            .fences
+            .as_ref()
+            .is_some_and(|(min, max)| min.as_slice() < end && max.as_slice() >= start);
+        points
+            || self.ranges.iter().any(|(range_start, range_end)| {
+                range_start.as_slice() < end && below_end(start, range_end)
+            })
+    }
 }
 
 impl FrozenMemtable {
     /// Creates a new frozen memtable by opening and replaying a WAL.
     pub fn new(memtable: Memtable) -> Self {
+        Self::from_shared(Arc::new(memtable))
+    }
+
+    /// Freezes a memtable that may still be referenced elsewhere.
+    ///
+    /// Used by the engine when retiring the active memtable: readers holding
+    /// an older view of the engine keep their `Arc`, and the caller must
+    /// not write to it afterwards.
+    pub(crate) fn from_shared(memtable: Arc<Memtable>) -> Self {
         Self {
+            creation_timestamp: memtable.clock.now_nanos(),
             memtable,
-            creation_timestamp: Memtable::current_timestamp(),
+            index: OnceLock::new(),
         }
     }
 
@@ -963,6 +1631,49 @@ impl FrozenMemtable {
         self.memtable.get(key)
     }
 
+    /// Returns the record that decides the visible state of a key.
+    pub fn get_record(
+        &self,
+        key: &[u8],
+        verify_value_checksum: bool,
+    ) -> Result<Option<Record>, MemtableError> {
+        self.memtable.get_record(key, verify_value_checksum)
+    }
+
+    /// Checks whether a point lookup for `key` could find a record in
+    /// this memtable.
+    ///
+    /// Returns `false` only when the fence keys or the bloom filter rule
+    /// the key out and no range tombstone covers it. The filter is built
+    /// on the first call; if that fails, `true` is returned.
+    pub fn may_contain(&self, key: &[u8]) -> bool {
+        self.index().is_none_or(|index| index.may_contain(key))
+    }
+
+    /// Checks whether a scan of `[start, end)` could return anything from
+    /// this memtable.
+    ///
+    /// Returns `false` only when every point key lies outside the fence
+    /// keys' range and no range tombstone overlaps it. Shares the filter
+    /// of [`may_contain`](Self::may_contain); if building it fails,
+    /// `true` is returned.
+    pub fn may_overlap(&self, start: &[u8], end: &[u8]) -> bool {
+        self.index()
+            .is_none_or(|index| index.may_overlap(start, end))
+    }
+
+    /// Returns the point-read filter, building it on first use, or `None`
+    /// if it cannot be built.
+    fn index(&self) -> Option<&FrozenIndex> {
+        match self.index.get() {
+            Some(index) => Some(index),
+            None => match self.memtable.frozen_index() {
+                Ok(index) => Some(self.index.get_or_init(|| index)),
+                Err(_) => None,
+            },
+        }
+    }
+
     /// Performs a range scan over the frozen memtable.
     pub fn scan(
         &self,
@@ -972,15 +1683,126 @@ impl FrozenMemtable {
         self.memtable.scan(start, end)
     }
 
-    /// Returns all records required to materialize this memtable into an SSTable.
-    pub fn iter_for_flush(&self) -> Result<impl Iterator<Item = Record>, MemtableError> {
-        self.memtable.iter_for_flush()
+    /// Performs a range scan over the frozen memtable that sees only the
+    /// records with an LSN at or below `max_lsn`.
+    pub fn scan_at(
+        &self,
+        start: &[u8],
+        end: &[u8],
+        max_lsn: u64,
+    ) -> Result<impl Iterator<Item = Record>, MemtableError> {
+        self.memtable.scan_at(start, end, max_lsn)
+    }
+
+    /// Returns all records required to materialize this memtable into an
+    /// SSTable, keeping the versions above `horizon`.
+    pub fn iter_for_flush(
+        &self,
+        horizon: u64,
+    ) -> Result<impl Iterator<Item = Record>, MemtableError> {
+        self.memtable.iter_for_flush(horizon)
+    }
+
+    /// Returns a [`FlushView`] of the records
+    /// [`iter_for_flush`](Self::iter_for_flush) returns, borrowed instead
+    /// of copied.
+    pub(crate) fn flush_view(&self, horizon: u64) -> Result<FlushView<'_>, MemtableError> {
+        self.memtable.flush_view(horizon)
     }
 
     /// Returns the highest assigned LSN, or `None` if empty.
     pub fn max_lsn(&self) -> Option<u64> {
         self.memtable.max_lsn()
     }
+
+    /// Returns a snapshot of memtable statistics.
+    pub fn stats(&self) -> Result<MemtableStats, MemtableError> {
+        self.memtable.stats()
+    }
+
+    /// Returns a distinct-key sketch of the point keys.
+    pub(crate) fn key_sketch(&self) -> Result<KeySketch, MemtableError> {
+        self.memtable.key_sketch()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// FlushView
+// ------------------------------------------------------------------------------------------------
+
+/// Point versions of `tree` a flush writes: the newest version of every
+/// key and the older ones down to the newest at or below `horizon`, key
+/// ascending and newest first.
+fn flushed_versions(
+    tree: &BTreeMap<Vec<u8>, BTreeMap<Reverse<u64>, MemtablePointEntry>>,
+    horizon: u64,
+) -> impl Iterator<Item = (&[u8], &MemtablePointEntry)> {
+    tree.iter().flat_map(move |(key, versions)| {
+        let mut older = versions.values();
+        let mut done = false;
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            let entry = older.next()?;
+            done = entry.lsn() <= horizon;
+            Some((key.as_slice(), entry))
+        })
+    })
+}
+
+/// A memtable read-locked for a flush, returned by
+/// [`FrozenMemtable::flush_view`].
+///
+/// Yields the records of [`Memtable::iter_for_flush`] without copying
+/// them: point entries borrow their keys and values from the tree, and
+/// only the few range tombstones are cloned. Reads of the memtable go on
+/// under the shared lock; nothing writes to a frozen memtable.
+pub(crate) struct FlushView<'a> {
+    guard: RwLockReadGuard<'a, MemtableInner>,
+    horizon: u64,
+    point_count: usize,
+}
+
+impl FlushView<'_> {
+    /// Number of point entries [`points`](Self::points) yields.
+    pub fn point_count(&self) -> usize {
+        self.point_count
+    }
+
+    /// Point entries to write, sorted by key ascending, then LSN
+    /// descending.
+    pub fn points(&self) -> impl Iterator<Item = PointEntryRef<'_>> {
+        flushed_versions(&self.guard.tree, self.horizon).map(|(key, entry)| match entry {
+            MemtablePointEntry::Put {
+                value,
+                lsn,
+                timestamp,
+                ..
+            } => PointEntryRef {
+                key,
+                value: Some(value),
+                lsn: *lsn,
+                timestamp: *timestamp,
+            },
+            MemtablePointEntry::Delete { lsn, timestamp } => PointEntryRef {
+                key,
+                value: None,
+                lsn: *lsn,
+                timestamp: *timestamp,
+            },
+        })
+    }
+
+    /// Every range tombstone, sorted by start key ascending, then LSN
+    /// descending.
+    pub fn range_tombstones(&self) -> Vec<RangeTombstone> {
+        self.guard
+            .range_tombstones
+            .values()
+            .flat_map(|versions| versions.values().cloned())
+            .collect()
+    }
 }
 
 // ------------------------------------------------------------------------------------------------
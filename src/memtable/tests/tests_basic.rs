@@ -15,6 +15,8 @@
 
 #[cfg(test)]
 mod tests {
+    use crate::batch::WriteOp;
+    use crate::engine::WriteOptions;
     use crate::memtable::{Memtable, MemtableError, MemtableGetResult, Record};
     use tempfile::TempDir;
     use tracing::Level;
@@ -154,7 +156,7 @@ mod tests {
         memtable.put(b"key4".to_vec(), b"value4".to_vec()).unwrap();
 
         // Get all records from flush iterator
-        let flushed: Vec<_> = memtable.iter_for_flush().unwrap().collect();
+        let flushed: Vec<_> = memtable.iter_for_flush(u64::MAX).unwrap().collect();
 
         // Verify we have all operations (5 puts + 3 deletes + 3 range_deletes)
         assert_eq!(flushed.len(), 10);
@@ -253,6 +255,138 @@ mod tests {
         assert_eq!(stats.range_tombstone_count, 3);
     }
 
+    /// # Scenario
+    /// `iter_for_flush()` with a version horizon keeps the older versions
+    /// a reader pinned at the horizon still sees.
+    ///
+    /// # Starting environment
+    /// Fresh memtable — empty.
+    ///
+    /// # Actions
+    /// 1. Put `key` four times, delete it, then put `other` once.
+    /// 2. Call `iter_for_flush()` with the horizon at the LSN of the
+    ///    second put, then with no horizon.
+    ///
+    /// # Expected behavior
+    /// - With the horizon: the delete and the last two puts of `key`,
+    ///   newest first, down to the second put; then `other`.
+    /// - Without: only the delete of `key`, then `other`.
+    #[test]
+    fn iter_for_flush_keeps_versions_above_horizon() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("000000.log");
+        let memtable = Memtable::new(path.to_str().unwrap(), None, 1024).unwrap();
+
+        for i in 1..=4 {
+            memtable
+                .put(b"key".to_vec(), format!("v{i}").into_bytes())
+                .unwrap();
+        }
+        memtable.delete(b"key".to_vec()).unwrap();
+        memtable.put(b"other".to_vec(), b"value".to_vec()).unwrap();
+
+        let all: Vec<_> = memtable.iter_for_flush(0).unwrap().collect();
+        assert_eq!(all.len(), 6);
+        let second_put = all[3].lsn();
+
+        let pinned: Vec<_> = memtable.iter_for_flush(second_put).unwrap().collect();
+        let versions: Vec<_> = pinned
+            .iter()
+            .map(|record| match record {
+                Record::Put { key, value, .. } => (key.clone(), Some(value.clone())),
+                Record::Delete { key, .. } => (key.clone(), None),
+                Record::RangeDelete { .. } => panic!("unexpected range delete"),
+            })
+            .collect();
+        assert_eq!(
+            versions,
+            vec![
+                (b"key".to_vec(), None),
+                (b"key".to_vec(), Some(b"v4".to_vec())),
+                (b"key".to_vec(), Some(b"v3".to_vec())),
+                (b"key".to_vec(), Some(b"v2".to_vec())),
+                (b"other".to_vec(), Some(b"value".to_vec())),
+            ]
+        );
+
+        let latest: Vec<_> = memtable.iter_for_flush(u64::MAX).unwrap().collect();
+        assert_eq!(latest.len(), 2);
+        assert!(matches!(&latest[0], Record::Delete { key, .. } if key == b"key"));
+        assert!(matches!(&latest[1], Record::Put { key, .. } if key == b"other"));
+    }
+
+    // ----------------------------------------------------------------
+    // write_sorted — one merge for an ascending batch
+    // ----------------------------------------------------------------
+
+    /// # Scenario
+    /// `write_sorted()` applies ascending puts and deletes with one LSN
+    /// each, and rejects batches it cannot merge.
+    ///
+    /// # Starting environment
+    /// Memtable holding `b = old`.
+    ///
+    /// # Actions
+    /// 1. `write_sorted()` with keys out of order, then with a range
+    ///    delete.
+    /// 2. `write_sorted()` of `put a`, `delete b`, `put c`.
+    /// 3. Read the keys; check the flush output and max LSN.
+    ///
+    /// # Expected behavior
+    /// - Step 1 fails with `InvalidArgument` and writes nothing.
+    /// - `a` and `c` read back, `b` is deleted, and `b = old` is kept
+    ///   as an older version.
+    /// - The LSN advanced by three.
+    #[test]
+    fn write_sorted_merges_batch() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("000000.log");
+        let memtable = Memtable::new(path.to_str().unwrap(), None, 1024).unwrap();
+        memtable.put(b"b".to_vec(), b"old".to_vec()).unwrap();
+        let lsn_before = memtable.max_lsn().unwrap();
+
+        let put = |key: &[u8]| WriteOp::Put {
+            key: key.to_vec(),
+            value: b"new".to_vec(),
+        };
+        let unsorted = [put(b"c"), put(b"a")];
+        let ranged = [
+            put(b"a"),
+            WriteOp::DeleteRange {
+                start: b"b".to_vec(),
+                end: b"c".to_vec(),
+            },
+        ];
+        for ops in [&unsorted[..], &ranged[..]] {
+            assert!(matches!(
+                memtable.write_sorted(ops, &WriteOptions::default()),
+                Err(MemtableError::InvalidArgument(_))
+            ));
+        }
+        assert_eq!(memtable.max_lsn(), Some(lsn_before));
+
+        let ops = [put(b"a"), WriteOp::Delete { key: b"b".to_vec() }, put(b"c")];
+        memtable
+            .write_sorted(&ops, &WriteOptions::default())
+            .unwrap();
+
+        assert_eq!(
+            memtable.get(b"a").unwrap(),
+            MemtableGetResult::Put(b"new".to_vec())
+        );
+        assert_eq!(memtable.get(b"b").unwrap(), MemtableGetResult::Delete);
+        assert_eq!(
+            memtable.get(b"c").unwrap(),
+            MemtableGetResult::Put(b"new".to_vec())
+        );
+        assert_eq!(memtable.iter_for_flush(0).unwrap().count(), 4);
+        assert_eq!(memtable.max_lsn(), Some(lsn_before + 3));
+    }
+
     // ----------------------------------------------------------------
     // Scan — basic range
     // ----------------------------------------------------------------
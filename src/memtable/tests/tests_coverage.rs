@@ -1,7 +1,8 @@
 //! Coverage tests for memtable internals.
 //!
 //! Targets code paths not exercised by the main test suites:
-//! - `MemtablePointEntry` accessor methods (`lsn`, `timestamp`, `is_delete`, `value`)
+//! - `MemtablePointEntry` accessor methods (`lsn`, `timestamp`, `is_delete`, `value`,
+//!   `checksum_matches`)
 //! - `MemtablePointEntry` `Encode` / `Decode` round-trips (both variants + invalid tag)
 //! - `ReadMemtable` trait implementations for `Memtable` and `FrozenMemtable`
 //! - `FrozenMemtable::wal_seq()` delegation
@@ -25,11 +26,29 @@ mod tests {
             value: b"hello".to_vec(),
             timestamp: 1000,
             lsn: 5,
+            checksum: None,
         };
         assert_eq!(entry.lsn(), 5);
         assert_eq!(entry.timestamp(), 1000);
         assert!(!entry.is_delete());
         assert_eq!(entry.value(), Some(b"hello".as_slice()));
+        assert!(entry.checksum_matches());
+    }
+
+    #[test]
+    fn point_entry_checksum_matches() {
+        let mut entry = MemtablePointEntry::Put {
+            value: b"hello".to_vec(),
+            timestamp: 1000,
+            lsn: 5,
+            checksum: Some(crc32fast::hash(b"hello")),
+        };
+        assert!(entry.checksum_matches());
+
+        if let MemtablePointEntry::Put { value, .. } = &mut entry {
+            value[0] ^= 0x01;
+        }
+        assert!(!entry.checksum_matches());
     }
 
     #[test]
@@ -54,6 +73,7 @@ mod tests {
             value: b"data".to_vec(),
             timestamp: 42,
             lsn: 7,
+            checksum: Some(crc32fast::hash(b"data")),
         };
         let mut buf = Vec::new();
         original.encode_to(&mut buf).unwrap();
@@ -375,7 +375,8 @@ mod tests {
     ///
     /// # Expected behavior
     /// - `key_count == 3`, `entry_count == 5` (3 puts + 2 deletes).
-    /// - `tombstone_count == 2`.
+    /// - `tombstone_count == 2`, `live_key_count == 1`.
+    /// - `key_range` spans `a..=c`, deleted keys included.
     #[test]
     fn stats_counts_point_tombstones() {
         let tmp = TempDir::new().unwrap();
@@ -393,6 +394,8 @@ mod tests {
         assert_eq!(stats.key_count, 3);
         assert_eq!(stats.entry_count, 5);
         assert_eq!(stats.tombstone_count, 2);
+        assert_eq!(stats.live_key_count, 1);
+        assert_eq!(stats.key_range, Some((b"a".to_vec(), b"c".to_vec())));
         assert_eq!(stats.range_tombstone_count, 0);
     }
 
@@ -5,8 +5,11 @@
 //! semantics as the original, but no further mutations are allowed.
 //!
 //! These tests verify that freezing a memtable preserves the data
-//! faithfully and that the underlying WAL file remains on disk as long
-//! as the frozen memtable is alive.
+//! faithfully, that its point-read filter (`may_contain()`) never rules
+//! out a key it holds and `may_overlap()` never a range it could return
+//! records for, that `flush_view()` borrows exactly the records
+//! `iter_for_flush()` copies, and that the underlying WAL file remains on
+//! disk as long as the frozen memtable is alive.
 //!
 //! ## See also
 //! - [`tests_basic`] — active `Memtable` API tests
@@ -211,7 +214,7 @@ mod tests {
 
         let frozen = memtable.frozen().unwrap();
 
-        let records: Vec<_> = frozen.iter_for_flush().unwrap().collect();
+        let records: Vec<_> = frozen.iter_for_flush(u64::MAX).unwrap().collect();
 
         assert_eq!(records.len(), 3);
 
@@ -232,6 +235,186 @@ mod tests {
         )));
     }
 
+    // ----------------------------------------------------------------
+    // flush_view — borrowed records match iter_for_flush
+    // ----------------------------------------------------------------
+
+    /// # Scenario
+    /// `flush_view()` yields the records of `iter_for_flush()`, borrowed,
+    /// for a horizon below some versions and for none.
+    ///
+    /// # Starting environment
+    /// Active memtable: `put(k, v1)` (its LSN is the horizon), `put(k, v2)`,
+    /// `put(k, v3)`, `put(j, 1)`, `delete(j)`, `delete_range(x, z)`.
+    ///
+    /// # Actions
+    /// 1. `frozen()`; for horizons `h` and `u64::MAX`, compare the
+    ///    points and range tombstones of `flush_view()` with the records
+    ///    of `iter_for_flush()`.
+    ///
+    /// # Expected behavior
+    /// - Both yield the same point entries in the same order, and
+    ///   `point_count()` matches: five with horizon `h` (every version of
+    ///   `k` down to `v1`), two with `u64::MAX`.
+    /// - Both yield the one range tombstone.
+    #[test]
+    fn flush_view_matches_iter_for_flush() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("000000.log");
+
+        let memtable = Memtable::new(&path, None, 4096).unwrap();
+        memtable.put(b"k".to_vec(), b"v1".to_vec()).unwrap();
+        let horizon = memtable.max_lsn().unwrap();
+        memtable.put(b"k".to_vec(), b"v2".to_vec()).unwrap();
+        memtable.put(b"k".to_vec(), b"v3".to_vec()).unwrap();
+        memtable.put(b"j".to_vec(), b"1".to_vec()).unwrap();
+        memtable.delete(b"j".to_vec()).unwrap();
+        memtable.delete_range(b"x".to_vec(), b"z".to_vec()).unwrap();
+
+        let frozen = memtable.frozen().unwrap();
+        for (horizon, expected) in [(horizon, 5), (u64::MAX, 2)] {
+            let mut copied_points = Vec::new();
+            let mut copied_ranges = Vec::new();
+            for record in frozen.iter_for_flush(horizon).unwrap() {
+                match record {
+                    Record::Put {
+                        key, value, lsn, ..
+                    } => copied_points.push((key, Some(value), lsn)),
+                    Record::Delete { key, lsn, .. } => copied_points.push((key, None, lsn)),
+                    Record::RangeDelete {
+                        start, end, lsn, ..
+                    } => copied_ranges.push((start, end, lsn)),
+                }
+            }
+
+            let view = frozen.flush_view(horizon).unwrap();
+            let points: Vec<_> = view
+                .points()
+                .map(|p| (p.key.to_vec(), p.value.map(<[u8]>::to_vec), p.lsn))
+                .collect();
+            let ranges: Vec<_> = view
+                .range_tombstones()
+                .into_iter()
+                .map(|rt| (rt.start, rt.end, rt.lsn))
+                .collect();
+
+            assert_eq!(points.len(), expected);
+            assert_eq!(view.point_count(), expected);
+            assert_eq!(points, copied_points);
+            assert_eq!(ranges, copied_ranges);
+            assert_eq!(ranges.len(), 1);
+        }
+    }
+
+    // ----------------------------------------------------------------
+    // may_contain / may_overlap — fence keys, bloom filter, range tombstones
+    // ----------------------------------------------------------------
+
+    /// # Scenario
+    /// `may_contain()` rules out keys the frozen memtable cannot hold,
+    /// and never a key it holds or covers with a range tombstone.
+    ///
+    /// # Starting environment
+    /// Active memtable: puts of `k_100..k_199` (even numbers only),
+    /// `delete(k_150)`, `delete_range(x_10, x_20)`.
+    ///
+    /// # Actions
+    /// 1. `frozen()` → `may_contain` for every written key.
+    /// 2. `may_contain` for keys below and above the fence keys, inside
+    ///    and outside the range tombstone, and for the odd keys in
+    ///    between.
+    ///
+    /// # Expected behavior
+    /// - Every written key, the deleted one included, may be contained.
+    /// - `a`, `k_099`, and `z` (outside the fence keys and the range
+    ///   tombstone) and `x_20` (its exclusive end) are ruled out.
+    /// - `x_10` and `x_15` (under the range tombstone) may be contained.
+    /// - Most odd keys are ruled out by the bloom filter.
+    #[test]
+    fn may_contain_rules_out_absent_keys() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("000000.log");
+
+        let memtable = Memtable::new(&path, None, 64 * 1024).unwrap();
+        for i in (100..200).step_by(2) {
+            memtable
+                .put(format!("k_{i}").into_bytes(), b"v".to_vec())
+                .unwrap();
+        }
+        memtable.delete(b"k_150".to_vec()).unwrap();
+        memtable
+            .delete_range(b"x_10".to_vec(), b"x_20".to_vec())
+            .unwrap();
+
+        let frozen = memtable.frozen().unwrap();
+
+        for i in (100..200).step_by(2) {
+            assert!(frozen.may_contain(format!("k_{i}").as_bytes()), "k_{i}");
+        }
+        for key in [&b"a"[..], b"k_099", b"z", b"x_20"] {
+            assert!(!frozen.may_contain(key), "{key:?}");
+        }
+        assert!(frozen.may_contain(b"x_10"));
+        assert!(frozen.may_contain(b"x_15"));
+
+        let false_positives = (101..200)
+            .step_by(2)
+            .filter(|i| frozen.may_contain(format!("k_{i}").as_bytes()))
+            .count();
+        assert!(false_positives < 10, "{false_positives}");
+    }
+
+    /// # Scenario
+    /// `may_overlap()` rules out scan ranges outside the fence keys and
+    /// range tombstones, and never one that could return a record.
+    ///
+    /// # Starting environment
+    /// Active memtable: puts of `k_100..k_199`,
+    /// `delete_range(x_10, x_20)`.
+    ///
+    /// # Actions
+    /// 1. `frozen()` → `may_overlap` for ranges around the fence keys and
+    ///    the range tombstone.
+    ///
+    /// # Expected behavior
+    /// - Ranges holding a point key, or overlapping the range tombstone,
+    ///   may overlap — also when they only touch the first or last key.
+    /// - `[a, k_100)`, `[k_2, x_10)`, and `[x_20, z)` are ruled out.
+    #[test]
+    fn may_overlap_rules_out_disjoint_ranges() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("000000.log");
+
+        let memtable = Memtable::new(&path, None, 64 * 1024).unwrap();
+        for i in 100..200 {
+            memtable
+                .put(format!("k_{i}").into_bytes(), b"v".to_vec())
+                .unwrap();
+        }
+        memtable
+            .delete_range(b"x_10".to_vec(), b"x_20".to_vec())
+            .unwrap();
+
+        let frozen = memtable.frozen().unwrap();
+
+        for (start, end) in [
+            (&b"a"[..], &b"k_1000"[..]),
+            (b"k_150", b"k_151"),
+            (b"k_199", b"l"),
+            (b"x_00", b"x_11"),
+            (b"x_19", b"z"),
+        ] {
+            assert!(frozen.may_overlap(start, end), "{start:?}..{end:?}");
+        }
+        for (start, end) in [
+            (&b"a"[..], &b"k_100"[..]),
+            (b"k_2", b"x_10"),
+            (b"x_20", b"z"),
+        ] {
+            assert!(!frozen.may_overlap(start, end), "{start:?}..{end:?}");
+        }
+    }
+
     // ----------------------------------------------------------------
     // WAL file lifetime guarantee
     // ----------------------------------------------------------------
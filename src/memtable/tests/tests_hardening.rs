@@ -3,7 +3,8 @@
 //! These tests exercise unusual recovery paths and memtable state
 //! combinations that are not covered by the standard basic / edge-case
 //! suites: WAL replay with only range-deletes, replay of interleaved
-//! point and range tombstones, and size accounting corner cases.
+//! point and range tombstones, size accounting corner cases, and value
+//! checksums catching in-memory corruption.
 //!
 //! ## See also
 //! - [`tests_basic`]       — standard put/get/delete/scan/recovery
@@ -11,7 +12,7 @@
 
 #[cfg(test)]
 mod tests {
-    use crate::memtable::{Memtable, MemtableGetResult};
+    use crate::memtable::{Memtable, MemtableError, MemtableGetResult, MemtablePointEntry};
     use tempfile::TempDir;
     use tracing::Level;
     use tracing_subscriber::fmt::Subscriber;
@@ -258,4 +259,79 @@ mod tests {
             }
         }
     }
+
+    // ================================================================
+    // 6. Value checksums catch a value corrupted in memory
+    // ================================================================
+
+    /// # Scenario
+    /// With value checksums enabled, a value replayed from the WAL and a
+    /// freshly written one both carry a checksum. One of them is then
+    /// corrupted in place, as a bit flip in RAM would.
+    ///
+    /// # Expected behavior
+    /// - `get()` of the corrupted key fails with `ValueChecksumMismatch`;
+    ///   the intact key still reads back.
+    /// - `get_record()` without verification returns the damaged value.
+    /// - `iter_for_flush()` and `flush_view()` refuse to hand the
+    ///   memtable to a flush.
+    #[test]
+    fn value_checksum_detects_in_memory_corruption() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let wal_path = tmp.path().join("000000.log");
+        {
+            let mt = Memtable::new(&wal_path, None, WRITE_BUFFER).unwrap();
+            mt.put(b"replayed".to_vec(), b"value_a".to_vec()).unwrap();
+        }
+
+        let mt = Memtable::new(&wal_path, None, WRITE_BUFFER).unwrap();
+        mt.set_value_checksums(true).unwrap();
+        mt.put(b"written".to_vec(), b"value_b".to_vec()).unwrap();
+
+        for key in [b"replayed".as_slice(), b"written"] {
+            let mut guard = mt.inner.write().unwrap();
+            let entry = guard
+                .tree
+                .get_mut(key)
+                .unwrap()
+                .values_mut()
+                .next()
+                .unwrap();
+            assert!(matches!(
+                entry,
+                MemtablePointEntry::Put {
+                    checksum: Some(_),
+                    ..
+                }
+            ));
+        }
+
+        {
+            let mut guard = mt.inner.write().unwrap();
+            let entry = guard.tree.get_mut(b"written".as_slice()).unwrap();
+            if let Some(MemtablePointEntry::Put { value, .. }) = entry.values_mut().next() {
+                value[0] ^= 0x01;
+            }
+        }
+
+        assert!(matches!(
+            mt.get(b"written"),
+            Err(MemtableError::ValueChecksumMismatch(_))
+        ));
+        assert_eq!(
+            mt.get(b"replayed").unwrap(),
+            MemtableGetResult::Put(b"value_a".to_vec())
+        );
+        assert!(mt.get_record(b"written", false).unwrap().is_some());
+        assert!(matches!(
+            mt.iter_for_flush(u64::MAX),
+            Err(MemtableError::ValueChecksumMismatch(_))
+        ));
+        assert!(matches!(
+            mt.flush_view(u64::MAX),
+            Err(MemtableError::ValueChecksumMismatch(_))
+        ));
+    }
 }
@@ -15,6 +15,7 @@
 //! - Scan with range tombstones (tombstone + covered puts emitted)
 //! - Mixed operations: range-deletes, point-deletes, overwrites, and
 //!   re-insertions — full raw output verified
+//! - `scan_at` leaves out the records above its LSN bound
 //!
 //! ## See also
 //! - [`tests_basic`] — active `Memtable` API tests
@@ -661,4 +662,68 @@ mod tests {
             }
         }
     }
+
+    // ----------------------------------------------------------------
+    // Scan bounded by LSN
+    // ----------------------------------------------------------------
+
+    /// # Scenario
+    /// `scan_at` sees the memtable as it was at an earlier LSN.
+    ///
+    /// # Starting environment
+    /// `a = v1` (LSN 1), `b = v1` (LSN 2), `a = v2` (LSN 3), delete of
+    /// `b` (LSN 4), `delete_range("a", "c")` (LSN 5).
+    ///
+    /// # Actions
+    /// 1. `scan_at("a", "z", max_lsn)` for `max_lsn` 0, 2, 4, and
+    ///    `u64::MAX`.
+    ///
+    /// # Expected behavior
+    /// Each scan returns exactly the records at or below its bound, in
+    /// `(key ASC, lsn DESC)` order; the unbounded one matches `scan`.
+    #[test]
+    fn scan_at_hides_later_records() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("000000.log");
+        let memtable = Memtable::new(&path, None, 1024 * 1024).unwrap();
+
+        memtable.put(b"a".to_vec(), b"v1".to_vec()).unwrap();
+        memtable.put(b"b".to_vec(), b"v1".to_vec()).unwrap();
+        memtable.put(b"a".to_vec(), b"v2".to_vec()).unwrap();
+        memtable.delete(b"b".to_vec()).unwrap();
+        memtable.delete_range(b"a".to_vec(), b"c".to_vec()).unwrap();
+
+        let scan_at = |max_lsn| -> Vec<(Vec<u8>, u64)> {
+            memtable
+                .scan_at(b"a", b"z", max_lsn)
+                .unwrap()
+                .map(|record| (record.key().to_vec(), record.lsn()))
+                .collect()
+        };
+
+        assert!(scan_at(0).is_empty());
+        assert_eq!(scan_at(2), vec![(b"a".to_vec(), 1), (b"b".to_vec(), 2)]);
+        assert_eq!(
+            scan_at(4),
+            vec![
+                (b"a".to_vec(), 3),
+                (b"a".to_vec(), 1),
+                (b"b".to_vec(), 4),
+                (b"b".to_vec(), 2),
+            ]
+        );
+        assert!(matches!(
+            memtable.scan_at(b"a", b"z", 4).unwrap().nth(2),
+            Some(Record::Delete { lsn: 4, .. })
+        ));
+
+        let unbounded: Vec<(Vec<u8>, u64)> = memtable
+            .scan(b"a", b"z")
+            .unwrap()
+            .map(|record| (record.key().to_vec(), record.lsn()))
+            .collect();
+        assert_eq!(unbounded.len(), 5);
+        assert_eq!(unbounded[0], (b"a".to_vec(), 5), "the range tombstone");
+        assert_eq!(scan_at(u64::MAX), unbounded);
+    }
 }
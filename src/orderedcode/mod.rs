@@ -0,0 +1,268 @@
+//! Order-preserving key encodings for composite keys.
+//!
+//! [`Db`](crate::Db) compares keys byte-wise, so a little-endian integer
+//! or a plain concatenation of strings does not scan in its natural order.
+//! This module encodes `u64`, `i64`, `f64`, strings, byte strings, and
+//! tuples of them such that for any two values `a < b`,
+//! `encode(&a) < encode(&b)` byte-wise.
+//!
+//! It needs no optional dependency, and the layout matches the serde codec
+//! of the `typed` feature for the same types, so keys built here can be
+//! read back through `typed::decode` and vice versa.
+//!
+//! # Layout
+//!
+//! | Type | Encoding |
+//! |------|----------|
+//! | `u64` | big-endian, 8 bytes |
+//! | `i64` | big-endian with the sign bit flipped |
+//! | `f64` | IEEE bits; sign bit flipped for positives, all bits inverted for negatives |
+//! | `str`, bytes | bytes with `0x00` escaped as `0x00 0xFF`, terminated by `0x00 0x00` |
+//! | tuples | fields concatenated in order |
+//!
+//! Tuples therefore sort field by field, and the terminator makes `"ab"`
+//! sort before `"ab\0"` and `"abc"` however the next field starts. The
+//! encoding of a leading part of a tuple, e.g. `(user_id,)`, is a byte
+//! prefix of every key starting with it, so it can bound a prefix scan.
+//!
+//! `-0.0` sorts just below `0.0`; NaNs sort above `+∞` (or below `-∞` with
+//! the sign bit set).
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use aeternusdb::{Db, DbConfig};
+//! use aeternusdb::orderedcode::{decode, encode};
+//!
+//! let db = Db::open("/tmp/orderedcode_db", DbConfig::default()).unwrap();
+//!
+//! // (user, timestamp) -> event
+//! db.put(&encode(&(7u64, -5i64)), b"old").unwrap();
+//! db.put(&encode(&(7u64, 3i64)), b"new").unwrap();
+//! db.put(&encode(&(8u64, 0i64)), b"other").unwrap();
+//!
+//! let events = db.scan(&encode(&(7u64, i64::MIN)), &encode(&(8u64, i64::MIN))).unwrap();
+//! assert_eq!(events.len(), 2);
+//! let (user, ts): (u64, i64) = decode(&events[0].0).unwrap();
+//! assert_eq!((user, ts), (7, -5));
+//! ```
+
+#[cfg(test)]
+mod tests;
+
+use thiserror::Error;
+
+/// Byte escaping a literal `0x00` inside strings and byte strings.
+const ESCAPE: u8 = 0xFF;
+
+/// Errors returned when decoding an ordered key.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum OrderedCodeError {
+    /// The input ended in the middle of a value.
+    #[error("unexpected end of input")]
+    UnexpectedEof,
+
+    /// Bytes remained after the value was decoded.
+    #[error("{0} trailing bytes after value")]
+    TrailingBytes(usize),
+
+    /// An escape byte had an unexpected value.
+    #[error("invalid byte 0x{0:02x}")]
+    InvalidByte(u8),
+
+    /// A string was not valid UTF-8.
+    #[error("invalid UTF-8 in string")]
+    InvalidUtf8,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Traits
+// ------------------------------------------------------------------------------------------------
+
+/// A value with an order-preserving encoding.
+pub trait OrderedEncode {
+    /// Appends the encoding of `self` to `out`.
+    fn encode_to(&self, out: &mut Vec<u8>);
+}
+
+/// A value that can be decoded from its order-preserving encoding.
+pub trait OrderedDecode: Sized {
+    /// Decodes a value from the front of `input` and advances it past the
+    /// consumed bytes.
+    fn decode_from(input: &mut &[u8]) -> Result<Self, OrderedCodeError>;
+}
+
+/// Encodes `value` into a new key.
+pub fn encode<T: OrderedEncode + ?Sized>(value: &T) -> Vec<u8> {
+    let mut out = Vec::new();
+    value.encode_to(&mut out);
+    out
+}
+
+/// Decodes a `T` previously produced by [`encode`].
+///
+/// # Errors
+///
+/// Fails if `bytes` is truncated, malformed for `T`, or has bytes left
+/// over after the value.
+pub fn decode<T: OrderedDecode>(mut bytes: &[u8]) -> Result<T, OrderedCodeError> {
+    let value = T::decode_from(&mut bytes)?;
+    if !bytes.is_empty() {
+        return Err(OrderedCodeError::TrailingBytes(bytes.len()));
+    }
+    Ok(value)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Primitives
+// ------------------------------------------------------------------------------------------------
+
+/// Consumes exactly `N` bytes from the front of `input`.
+fn take<const N: usize>(input: &mut &[u8]) -> Result<[u8; N], OrderedCodeError> {
+    let (head, rest) = input
+        .split_first_chunk::<N>()
+        .ok_or(OrderedCodeError::UnexpectedEof)?;
+    *input = rest;
+    Ok(*head)
+}
+
+/// Consumes one byte from the front of `input`.
+fn byte(input: &mut &[u8]) -> Result<u8, OrderedCodeError> {
+    Ok(take::<1>(input)?[0])
+}
+
+impl OrderedEncode for u64 {
+    fn encode_to(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_be_bytes());
+    }
+}
+
+impl OrderedDecode for u64 {
+    fn decode_from(input: &mut &[u8]) -> Result<Self, OrderedCodeError> {
+        Ok(u64::from_be_bytes(take(input)?))
+    }
+}
+
+impl OrderedEncode for i64 {
+    fn encode_to(&self, out: &mut Vec<u8>) {
+        ((*self as u64) ^ (1 << 63)).encode_to(out);
+    }
+}
+
+impl OrderedDecode for i64 {
+    fn decode_from(input: &mut &[u8]) -> Result<Self, OrderedCodeError> {
+        Ok((u64::decode_from(input)? ^ (1 << 63)) as i64)
+    }
+}
+
+impl OrderedEncode for f64 {
+    fn encode_to(&self, out: &mut Vec<u8>) {
+        let bits = self.to_bits();
+        let bits = if bits >> 63 == 1 {
+            !bits
+        } else {
+            bits ^ (1 << 63)
+        };
+        bits.encode_to(out);
+    }
+}
+
+impl OrderedDecode for f64 {
+    fn decode_from(input: &mut &[u8]) -> Result<Self, OrderedCodeError> {
+        let bits = u64::decode_from(input)?;
+        let bits = if bits >> 63 == 1 {
+            bits ^ (1 << 63)
+        } else {
+            !bits
+        };
+        Ok(f64::from_bits(bits))
+    }
+}
+
+impl OrderedEncode for [u8] {
+    /// Appends the bytes with every `0x00` escaped, followed by the
+    /// `0x00 0x00` terminator.
+    fn encode_to(&self, out: &mut Vec<u8>) {
+        for &b in self {
+            out.push(b);
+            if b == 0x00 {
+                out.push(ESCAPE);
+            }
+        }
+        out.extend_from_slice(&[0x00, 0x00]);
+    }
+}
+
+impl OrderedDecode for Vec<u8> {
+    fn decode_from(input: &mut &[u8]) -> Result<Self, OrderedCodeError> {
+        let mut out = Vec::new();
+        loop {
+            match byte(input)? {
+                0x00 => match byte(input)? {
+                    0x00 => return Ok(out),
+                    ESCAPE => out.push(0x00),
+                    b => return Err(OrderedCodeError::InvalidByte(b)),
+                },
+                b => out.push(b),
+            }
+        }
+    }
+}
+
+impl OrderedEncode for Vec<u8> {
+    fn encode_to(&self, out: &mut Vec<u8>) {
+        self.as_slice().encode_to(out);
+    }
+}
+
+impl OrderedEncode for str {
+    fn encode_to(&self, out: &mut Vec<u8>) {
+        self.as_bytes().encode_to(out);
+    }
+}
+
+impl OrderedEncode for String {
+    fn encode_to(&self, out: &mut Vec<u8>) {
+        self.as_bytes().encode_to(out);
+    }
+}
+
+impl OrderedDecode for String {
+    fn decode_from(input: &mut &[u8]) -> Result<Self, OrderedCodeError> {
+        String::from_utf8(Vec::decode_from(input)?).map_err(|_| OrderedCodeError::InvalidUtf8)
+    }
+}
+
+impl<T: OrderedEncode + ?Sized> OrderedEncode for &T {
+    fn encode_to(&self, out: &mut Vec<u8>) {
+        (**self).encode_to(out);
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Tuples
+// ------------------------------------------------------------------------------------------------
+
+/// Implements both traits for a tuple by concatenating its fields.
+macro_rules! tuple_impl {
+    ($($name:ident : $idx:tt),+) => {
+        impl<$($name: OrderedEncode),+> OrderedEncode for ($($name,)+) {
+            fn encode_to(&self, out: &mut Vec<u8>) {
+                $(self.$idx.encode_to(out);)+
+            }
+        }
+
+        impl<$($name: OrderedDecode),+> OrderedDecode for ($($name,)+) {
+            fn decode_from(input: &mut &[u8]) -> Result<Self, OrderedCodeError> {
+                Ok(($($name::decode_from(input)?,)+))
+            }
+        }
+    };
+}
+
+tuple_impl!(A: 0);
+tuple_impl!(A: 0, B: 1);
+tuple_impl!(A: 0, B: 1, C: 2);
+tuple_impl!(A: 0, B: 1, C: 2, D: 3);
+tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4);
+tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
@@ -0,0 +1,2 @@
+mod tests_ordering;
+mod tests_roundtrip;
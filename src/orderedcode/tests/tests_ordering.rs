@@ -0,0 +1,112 @@
+//! Tests that byte-wise order of encodings matches the natural order of
+//! the encoded values.
+
+use crate::orderedcode::*;
+
+/// Asserts that `values`, given in ascending order, encode to strictly
+/// ascending byte strings.
+fn assert_ascending<T: OrderedEncode + std::fmt::Debug>(values: &[T]) {
+    for pair in values.windows(2) {
+        assert!(
+            encode(&pair[0]) < encode(&pair[1]),
+            "{:?} should sort before {:?}",
+            pair[0],
+            pair[1]
+        );
+    }
+}
+
+#[test]
+fn u64_order() {
+    assert_ascending(&[0u64, 1, 255, 256, 65_535, 1 << 32, u64::MAX - 1, u64::MAX]);
+}
+
+#[test]
+fn i64_order() {
+    assert_ascending(&[i64::MIN, i64::MIN + 1, -256, -1, 0, 1, 256, i64::MAX]);
+}
+
+#[test]
+fn f64_order() {
+    assert_ascending(&[
+        f64::NEG_INFINITY,
+        f64::MIN,
+        -1.0,
+        -f64::MIN_POSITIVE,
+        -0.0,
+        0.0,
+        f64::MIN_POSITIVE,
+        1.0,
+        1.5,
+        f64::MAX,
+        f64::INFINITY,
+        f64::NAN,
+    ]);
+}
+
+#[test]
+fn string_order() {
+    assert_ascending(&[
+        "",
+        "\0",
+        "\0\0",
+        "\u{1}",
+        "a",
+        "a\0",
+        "a\0b",
+        "ab",
+        "b",
+        "zażółć",
+    ]);
+}
+
+#[test]
+fn bytes_order() {
+    assert_ascending(&[
+        vec![],
+        vec![0x00],
+        vec![0x00, 0xFF],
+        vec![0x01],
+        vec![0xFF],
+        vec![0xFF, 0x00],
+    ]);
+}
+
+/// A shorter string field sorts first whatever the next field holds,
+/// which plain concatenation gets wrong.
+#[test]
+fn tuple_order_field_by_field() {
+    assert_ascending(&[("a", u64::MAX), ("ab", 0), ("ab", 1), ("b", 0)]);
+    assert_ascending(&[(-1i64, "z"), (0, ""), (0, "a"), (1, "")]);
+    assert_ascending(&[(1u64, -0.5f64, "x"), (1, 0.0, "a"), (2, -1.0, "a")]);
+}
+
+#[test]
+fn tuple_prefix_is_byte_prefix() {
+    let prefix = encode(&(7u64, "user"));
+    for key in [
+        encode(&(7u64, "user", 0i64)),
+        encode(&(7u64, "user", i64::MAX)),
+        encode(&(7u64, "user", -1i64)),
+    ] {
+        assert!(key.starts_with(&prefix));
+    }
+    assert!(!encode(&(7u64, "users", 0i64)).starts_with(&prefix));
+}
+
+/// Sorting encodings byte-wise gives the same order as sorting the
+/// decoded values.
+#[test]
+fn sorted_encodings_match_sorted_values() {
+    let mut values: Vec<(i64, String)> = Vec::new();
+    for i in -20i64..20 {
+        for s in ["", "a", "a\0", "b", "ba"] {
+            values.push((i * 7919 % 101, s.to_string()));
+        }
+    }
+    let mut encoded: Vec<Vec<u8>> = values.iter().map(encode).collect();
+    encoded.sort();
+    values.sort();
+    let decoded: Vec<(i64, String)> = encoded.iter().map(|e| decode(e).unwrap()).collect();
+    assert_eq!(decoded, values);
+}
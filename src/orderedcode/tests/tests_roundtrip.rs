@@ -0,0 +1,123 @@
+//! Tests for encoding layout and decoding: byte layout of each type,
+//! round trips, and malformed input.
+
+use crate::orderedcode::*;
+
+// ------------------------------------------------------------------------------------------------
+// Layout
+// ------------------------------------------------------------------------------------------------
+
+#[test]
+fn layout_integers() {
+    assert_eq!(encode(&0x0102_0304_0506_0708u64), [1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(encode(&0i64), [0x80, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(
+        encode(&-1i64),
+        [0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]
+    );
+}
+
+#[test]
+fn layout_string_escapes_zero_and_terminates() {
+    assert_eq!(encode("a\0b"), [b'a', 0x00, 0xFF, b'b', 0x00, 0x00]);
+    assert_eq!(encode(""), [0x00, 0x00]);
+}
+
+#[test]
+fn layout_tuple_concatenates_fields() {
+    let mut expected = encode(&7u64);
+    expected.extend(encode("x"));
+    expected.extend(encode(&-2i64));
+    assert_eq!(encode(&(7u64, "x", -2i64)), expected);
+}
+
+// ------------------------------------------------------------------------------------------------
+// Round trips
+// ------------------------------------------------------------------------------------------------
+
+#[test]
+fn roundtrip_primitives() {
+    for v in [0, 1, u64::MAX / 2, u64::MAX] {
+        assert_eq!(decode::<u64>(&encode(&v)).unwrap(), v);
+    }
+    for v in [i64::MIN, -1, 0, 1, i64::MAX] {
+        assert_eq!(decode::<i64>(&encode(&v)).unwrap(), v);
+    }
+    for v in [
+        f64::NEG_INFINITY,
+        -1.5,
+        -0.0,
+        0.0,
+        f64::MIN_POSITIVE,
+        3.25,
+        f64::MAX,
+    ] {
+        assert_eq!(decode::<f64>(&encode(&v)).unwrap().to_bits(), v.to_bits());
+    }
+    assert!(decode::<f64>(&encode(&f64::NAN)).unwrap().is_nan());
+}
+
+#[test]
+fn roundtrip_strings_and_bytes() {
+    for s in ["", "plain", "nul\0inside", "\0\0", "zażółć"] {
+        assert_eq!(decode::<String>(&encode(s)).unwrap(), s);
+    }
+    let bytes = vec![0x00, 0xFF, 0x00, 0x01, 0xFF];
+    assert_eq!(decode::<Vec<u8>>(&encode(&bytes)).unwrap(), bytes);
+}
+
+#[test]
+fn roundtrip_tuples() {
+    let key = (42u64, "name\0x".to_string(), -7i64, 0.5f64, vec![0u8, 1]);
+    assert_eq!(
+        decode::<(u64, String, i64, f64, Vec<u8>)>(&encode(&key)).unwrap(),
+        key
+    );
+
+    let mut input = &encode(&(1u64, 2u64))[..];
+    assert_eq!(u64::decode_from(&mut input).unwrap(), 1);
+    assert_eq!(u64::decode_from(&mut input).unwrap(), 2);
+    assert!(input.is_empty());
+}
+
+// ------------------------------------------------------------------------------------------------
+// Malformed input
+// ------------------------------------------------------------------------------------------------
+
+#[test]
+fn decode_truncated() {
+    assert_eq!(
+        decode::<u64>(&[0, 1, 2]).unwrap_err(),
+        OrderedCodeError::UnexpectedEof
+    );
+    assert_eq!(
+        decode::<String>(b"abc").unwrap_err(),
+        OrderedCodeError::UnexpectedEof
+    );
+    assert_eq!(
+        decode::<String>(&[b'a', 0x00]).unwrap_err(),
+        OrderedCodeError::UnexpectedEof
+    );
+}
+
+#[test]
+fn decode_trailing_bytes() {
+    let mut bytes = encode(&5u64);
+    bytes.push(0);
+    assert_eq!(
+        decode::<u64>(&bytes).unwrap_err(),
+        OrderedCodeError::TrailingBytes(1)
+    );
+}
+
+#[test]
+fn decode_invalid_escape_and_utf8() {
+    assert_eq!(
+        decode::<Vec<u8>>(&[b'a', 0x00, 0x01]).unwrap_err(),
+        OrderedCodeError::InvalidByte(0x01)
+    );
+    assert_eq!(
+        decode::<String>(&[0xC3, 0x00, 0x00]).unwrap_err(),
+        OrderedCodeError::InvalidUtf8
+    );
+}
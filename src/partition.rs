@@ -0,0 +1,790 @@
+//! Hash partitioning of one database across several LSM trees.
+//!
+//! With [`DbConfig::partitions`](crate::DbConfig::partitions) above one,
+//! every key is assigned to one of N independent [`Engine`]s by a stable
+//! hash (`crc32(key) % N`). Each partition has its own memtable, WAL,
+//! manifest, and SSTable set under `<path>/partitions/NNN/`, so flushes
+//! and compactions work on smaller trees; the background thread pool is
+//! shared.
+//!
+//! [`PartitionedEngine`] exposes the same operations as [`Engine`]:
+//!
+//! - Point operations are routed to the owning partition.
+//! - Range deletes are applied to every partition, since a byte range
+//!   covers keys of all partitions.
+//! - Scans merge the per-partition results; partitions own disjoint key
+//!   sets, so no deduplication is needed.
+//! - Maintenance (flush, compaction, close, clone) runs on each partition
+//!   in turn.
+//!
+//! Partitions share no WAL: each logs its writes to its own and numbers
+//! them with its own LSNs. An LSN therefore orders writes within one
+//! partition only — which covers every version of a key, since a key
+//! never changes partition — and [`PartitionedEngine::latest_lsn`] is the
+//! highest over all partitions, not a point in one shared write history.
+//!
+//! With a single partition the engine lives directly under `<path>`,
+//! exactly as before partitioning existed.
+
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::batch::WriteOp;
+use crate::compaction::{CompactionJobStats, CompactionPriority, MajorProgress, TombstoneGcStats};
+use crate::engine::{
+    AdaptiveCompaction, CasOutcome, CompactionThresholds, CompactionTuning, ConsistencyReport,
+    DebugReport, Engine, EngineConfig, EngineError, Entry, MANIFEST_DIR, PinnedSlice, PurgeReport,
+    ReadFanout, ReadOptions, ReadStats, RecoveryReport, RepairReport, ReplicationDelta,
+    SSTableMetadata, TuningWindow, WalFile, WriteActivity, WriteOptions, WriteStats,
+};
+use crate::range_lock::RangeLocks;
+use crate::slow_op;
+use crate::tenant::PrefixTotals;
+
+/// Sub-directory holding one directory per partition.
+pub const PARTITIONS_DIR: &str = "partitions";
+
+/// Index of the partition owning `key` among `partitions`.
+pub(crate) fn partition_index(key: &[u8], partitions: usize) -> usize {
+    crc32fast::hash(key) as usize % partitions
+}
+
+/// Directory of partition `index` under the database root `base`, for a
+/// database of `partitions` partitions.
+pub(crate) fn partition_dir(base: &Path, partitions: u32, index: u32) -> PathBuf {
+    if partitions <= 1 {
+        base.to_path_buf()
+    } else {
+        base.join(PARTITIONS_DIR).join(format!("{index:03}"))
+    }
+}
+
+/// Whether `name` is a partition directory name, as written by
+/// [`partition_dir`]: three decimal digits.
+fn is_partition_name(name: &OsStr) -> bool {
+    name.to_str()
+        .is_some_and(|name| name.len() == 3 && name.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// A set of [`Engine`]s that together store one key space.
+///
+/// Cheap to clone — the engines are shared.
+#[derive(Clone)]
+pub(crate) struct PartitionedEngine {
+    engines: Arc<[Engine]>,
+    /// Set by `Db::set_compaction_paused`; background compaction is not
+    /// scheduled while set.
+    compaction_paused: Arc<AtomicBool>,
+    /// Ranges locked with `Db::lock_range`; hinted compaction skips the
+    /// hints they overlap.
+    range_locks: Arc<RangeLocks>,
+}
+
+impl PartitionedEngine {
+    /// Returns the number of partitions the database at `path` was
+    /// created with, or `None` if nothing has been written there yet.
+    ///
+    /// Only directories named like a partition's are counted, so other
+    /// files left under `partitions/` do not change the result.
+    pub fn existing_partitions(path: impl AsRef<Path>) -> Result<Option<u32>, EngineError> {
+        let base = path.as_ref();
+        let partitions_dir = base.join(PARTITIONS_DIR);
+        if partitions_dir.is_dir() {
+            let mut count = 0;
+            for entry in fs::read_dir(&partitions_dir)? {
+                let entry = entry?;
+                if entry.file_type()?.is_dir() && is_partition_name(&entry.file_name()) {
+                    count += 1;
+                }
+            }
+            if count > 0 {
+                return Ok(Some(count as u32));
+            }
+        }
+        if base.join(MANIFEST_DIR).exists() {
+            return Ok(Some(1));
+        }
+        Ok(None)
+    }
+
+    /// Repairs every partition directory of the database at `path`.
+    pub fn repair(path: impl AsRef<Path>) -> Result<RepairReport, EngineError> {
+        let base = path.as_ref();
+        let partitions = Self::existing_partitions(base)?.unwrap_or(1);
+        let mut report = RepairReport::default();
+        for p in 0..partitions {
+            report.merge(Engine::repair(partition_dir(base, partitions, p))?);
+        }
+        Ok(report)
+    }
+
+    /// Opens (or creates) `partitions` engines rooted at `path`.
+    ///
+    /// The caller must ensure `partitions` matches
+    /// [`existing_partitions`](Self::existing_partitions); keys would
+    /// otherwise be routed to the wrong trees.
+    pub fn open(
+        path: impl AsRef<Path>,
+        config: EngineConfig,
+        partitions: u32,
+    ) -> Result<Self, EngineError> {
+        let base = path.as_ref();
+        let engines = (0..partitions.max(1))
+            .map(|p| Engine::open(partition_dir(base, partitions, p), config.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            engines: Arc::from(engines),
+            compaction_paused: Arc::default(),
+            range_locks: Arc::default(),
+        })
+    }
+
+    /// Index of the partition that owns `key`.
+    fn index(&self, key: &[u8]) -> usize {
+        partition_index(key, self.engines.len())
+    }
+
+    /// Returns the engine that owns `key`.
+    fn route(&self, key: &[u8]) -> &Engine {
+        &self.engines[self.index(key)]
+    }
+
+    /// Runs `op` on every partition, returning `true` if any call did.
+    fn any(&self, op: impl Fn(&Engine) -> Result<bool, EngineError>) -> Result<bool, EngineError> {
+        let mut any = false;
+        for engine in self.engines.iter() {
+            any |= op(engine)?;
+        }
+        Ok(any)
+    }
+
+    // --------------------------------------------------------------------------------------------
+    // Writes
+    // --------------------------------------------------------------------------------------------
+
+    /// Routes to the owning partition.
+    pub fn put(
+        &self,
+        key: Vec<u8>,
+        value: Vec<u8>,
+        opts: &WriteOptions,
+    ) -> Result<bool, EngineError> {
+        self.route(&key).put_opt(key, value, opts)
+    }
+
+    /// Routes to the owning partition.
+    pub fn delete(&self, key: Vec<u8>, opts: &WriteOptions) -> Result<bool, EngineError> {
+        self.route(&key).delete_opt(key, opts)
+    }
+
+    /// Routes to the owning partition.
+    pub fn compare_and_swap(
+        &self,
+        key: Vec<u8>,
+        expected: Option<&[u8]>,
+        new: Option<Vec<u8>>,
+    ) -> Result<(CasOutcome, bool), EngineError> {
+        self.route(&key).compare_and_swap(key, expected, new)
+    }
+
+    /// Applied to every partition.
+    pub fn delete_range(&self, start_key: Vec<u8>, end_key: Vec<u8>) -> Result<bool, EngineError> {
+        self.any(|engine| engine.delete_range(start_key.clone(), end_key.clone()))
+    }
+
+    /// Applied to every partition.
+    pub fn delete_range_from(&self, start_key: Vec<u8>) -> Result<bool, EngineError> {
+        self.any(|engine| engine.delete_range_from(start_key.clone()))
+    }
+
+    /// Clears every partition in turn; each one is cleared atomically on
+    /// its own.
+    pub fn clear(&self) -> Result<(), EngineError> {
+        for engine in self.engines.iter() {
+            engine.clear()?;
+        }
+        Ok(())
+    }
+
+    /// Splits `ops` by partition, preserving their relative order, and
+    /// applies each group under that partition's write lock.
+    pub fn write_batch(&self, ops: &[WriteOp], opts: &WriteOptions) -> Result<bool, EngineError> {
+        if self.engines.len() == 1 {
+            return self.engines[0].write_batch(ops, opts);
+        }
+
+        let mut groups: Vec<Vec<WriteOp>> = vec![Vec::new(); self.engines.len()];
+        for op in ops {
+            match op {
+                WriteOp::Put { key, .. } | WriteOp::Delete { key } => {
+                    groups[self.index(key)].push(op.clone());
+                }
+                WriteOp::DeleteRange { .. } => {
+                    for group in &mut groups {
+                        group.push(op.clone());
+                    }
+                }
+            }
+        }
+
+        let mut frozen = false;
+        for (engine, group) in self.engines.iter().zip(&groups) {
+            if !group.is_empty() {
+                frozen |= engine.write_batch(group, opts)?;
+            }
+        }
+        Ok(frozen)
+    }
+
+    /// Tokens are kept by the first partition.
+    pub fn has_applied(&self, token: &[u8]) -> Result<bool, EngineError> {
+        self.engines[0].has_applied(token)
+    }
+
+    /// Syncs the WAL of every partition, so the batch the token stands
+    /// for is durable even while bulk loading, then records the token in
+    /// the first partition.
+    pub fn record_applied(&self, token: &[u8]) -> Result<(), EngineError> {
+        for engine in self.engines.iter() {
+            engine.sync_wal()?;
+        }
+        self.engines[0].record_applied(token)
+    }
+
+    /// Splits sorted `pairs` by partition and ingests each group as an
+    /// SSTable of that partition. Returns `true` if any SSTable was
+    /// written.
+    ///
+    /// The order of all of `pairs` is checked before any partition is
+    /// written, since each group may be sorted when the whole is not.
+    pub fn ingest_sorted(&self, pairs: &[(Vec<u8>, Vec<u8>)]) -> Result<bool, EngineError> {
+        if self.engines.len() == 1 {
+            return self.engines[0].ingest_sorted(pairs);
+        }
+        Engine::check_sorted(pairs)?;
+
+        let mut groups: Vec<Vec<(Vec<u8>, Vec<u8>)>> = vec![Vec::new(); self.engines.len()];
+        for pair in pairs {
+            groups[self.index(&pair.0)].push(pair.clone());
+        }
+
+        let mut ingested = false;
+        for (engine, group) in self.engines.iter().zip(&groups) {
+            ingested |= engine.ingest_sorted(group)?;
+        }
+        Ok(ingested)
+    }
+
+    // --------------------------------------------------------------------------------------------
+    // Reads
+    // --------------------------------------------------------------------------------------------
+
+    /// Routes to the owning partition.
+    pub fn get(&self, key: Vec<u8>) -> Result<Option<Vec<u8>>, EngineError> {
+        self.route(&key).get(key)
+    }
+
+    /// Routes to the owning partition.
+    pub fn get_opt(
+        &self,
+        key: Vec<u8>,
+        opts: &ReadOptions,
+    ) -> Result<Option<Vec<u8>>, EngineError> {
+        self.route(&key).get_opt(key, opts)
+    }
+
+    /// Routes to the owning partition.
+    pub fn get_entry(
+        &self,
+        key: Vec<u8>,
+        opts: &ReadOptions,
+    ) -> Result<Option<Entry>, EngineError> {
+        self.route(&key).get_entry(key, opts)
+    }
+
+    /// Highest over all partitions; each assigns LSNs on its own.
+    pub fn latest_lsn(&self) -> Result<u64, EngineError> {
+        let mut latest = 0;
+        for engine in self.engines.iter() {
+            latest = latest.max(engine.latest_lsn()?);
+        }
+        Ok(latest)
+    }
+
+    /// Routes to the owning partition.
+    pub fn get_pinned(&self, key: Vec<u8>) -> Result<Option<PinnedSlice>, EngineError> {
+        self.route(&key).get_pinned(key)
+    }
+
+    /// [`scan_opt`](Self::scan_opt) with default [`ReadOptions`].
+    pub fn scan(
+        &self,
+        start_key: &[u8],
+        end_key: &[u8],
+    ) -> Result<impl Iterator<Item = (Vec<u8>, Vec<u8>)> + Send + use<>, EngineError> {
+        self.scan_opt(start_key, end_key, &ReadOptions::default())
+    }
+
+    /// Scans every partition and merges the results into key order.
+    ///
+    /// Records the total SSTable count on the current span as
+    /// `sstables_touched`.
+    pub fn scan_opt(
+        &self,
+        start_key: &[u8],
+        end_key: &[u8],
+        opts: &ReadOptions,
+    ) -> Result<impl Iterator<Item = (Vec<u8>, Vec<u8>)> + Send + use<>, EngineError> {
+        let mut results = Vec::new();
+        let mut sstables = 0;
+        for engine in self.engines.iter() {
+            sstables += engine.sstable_count();
+            results.extend(engine.scan_opt(start_key, end_key, opts)?);
+        }
+        tracing::Span::current().record("sstables_touched", sstables);
+        slow_op::record_tables_probed(sstables);
+        if self.engines.len() > 1 {
+            results.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        }
+        Ok(results.into_iter())
+    }
+
+    /// Like [`scan_opt`](Self::scan_opt), but yields pairs lazily: each
+    /// partition is scanned from its own snapshot and the partitions are
+    /// merged pair by pair instead of being collected first.
+    pub fn scan_streaming(
+        &self,
+        start_key: &[u8],
+        end_key: &[u8],
+        opts: &ReadOptions,
+    ) -> Result<impl Iterator<Item = (Vec<u8>, Vec<u8>)> + Send + use<>, EngineError> {
+        let mut scans = Vec::with_capacity(self.engines.len());
+        for engine in self.engines.iter() {
+            slow_op::record_tables_probed(engine.sstable_count());
+            scans.push(engine.scan_opt(start_key, end_key, opts)?.peekable());
+        }
+        // Partitions share no keys, so the smallest head is always next.
+        Ok(std::iter::from_fn(move || {
+            let next = scans
+                .iter_mut()
+                .enumerate()
+                .filter_map(|(i, scan)| scan.peek().map(|(key, _)| (i, key)))
+                .min_by(|a, b| a.1.cmp(b.1))
+                .map(|(i, _)| i);
+            next.and_then(|i| scans[i].next())
+        }))
+    }
+
+    /// Scans every partition for keys written at or after `min_timestamp`
+    /// and merges the results into key order.
+    pub fn scan_since(
+        &self,
+        start_key: &[u8],
+        end_key: &[u8],
+        min_timestamp: u64,
+        opts: &ReadOptions,
+    ) -> Result<impl Iterator<Item = (Vec<u8>, Vec<u8>)> + Send + use<>, EngineError> {
+        let mut results = Vec::new();
+        let mut sstables = 0;
+        for engine in self.engines.iter() {
+            sstables += engine.sstable_count();
+            results.extend(engine.scan_since(start_key, end_key, min_timestamp, opts)?);
+        }
+        tracing::Span::current().record("sstables_touched", sstables);
+        slow_op::record_tables_probed(sstables);
+        if self.engines.len() > 1 {
+            results.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        }
+        Ok(results.into_iter())
+    }
+
+    /// Counts the live keys of every partition in `[start_key, end_key)`.
+    ///
+    /// Records the total SSTable count on the current span as
+    /// `sstables_touched`.
+    pub fn count(&self, start_key: &[u8], end_key: &[u8]) -> Result<u64, EngineError> {
+        let mut count = 0;
+        let mut sstables = 0;
+        for engine in self.engines.iter() {
+            sstables += engine.sstable_count();
+            count += engine.count(start_key, end_key)?;
+        }
+        tracing::Span::current().record("sstables_touched", sstables);
+        slow_op::record_tables_probed(sstables);
+        Ok(count)
+    }
+
+    /// Warms every partition; returns the bytes read.
+    pub fn warm_up(&self, start_key: &[u8], end_key: &[u8]) -> Result<u64, EngineError> {
+        let mut bytes = 0;
+        for engine in self.engines.iter() {
+            bytes += engine.warm_up(start_key, end_key)?;
+        }
+        Ok(bytes)
+    }
+
+    /// Warms the `count` newest SSTables of each partition; returns the
+    /// bytes read.
+    pub fn warm_up_recent(&self, count: usize) -> Result<u64, EngineError> {
+        let mut bytes = 0;
+        for engine in self.engines.iter() {
+            bytes += engine.warm_up_recent(count)?;
+        }
+        Ok(bytes)
+    }
+
+    // --------------------------------------------------------------------------------------------
+    // Introspection and maintenance
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns metadata for every live SSTable, grouped by partition in
+    /// partition order.
+    ///
+    /// SSTable ids are only unique within a partition; paths are unique.
+    pub fn sstable_metadata(&self) -> Result<Vec<SSTableMetadata>, EngineError> {
+        let mut all = Vec::new();
+        for engine in self.engines.iter() {
+            all.extend(engine.sstable_metadata()?);
+        }
+        Ok(all)
+    }
+
+    /// Concatenates the WAL segments of every partition in partition
+    /// order.
+    pub fn wal_files(&self) -> Result<Vec<WalFile>, EngineError> {
+        let mut all = Vec::new();
+        for engine in self.engines.iter() {
+            all.extend(engine.wal_files()?);
+        }
+        Ok(all)
+    }
+
+    /// Deletes the removed SSTable files no reader holds in every
+    /// partition — with `force`, without waiting for the grace period —
+    /// and sums the outcome.
+    pub fn purge_obsolete_files(&self, force: bool) -> Result<PurgeReport, EngineError> {
+        let mut total = PurgeReport::default();
+        for engine in self.engines.iter() {
+            total += if force {
+                engine.purge_obsolete_files()?
+            } else {
+                engine.collect_obsolete_files()?
+            };
+        }
+        Ok(total)
+    }
+
+    /// Sums the point-lookup counters of every partition.
+    pub fn read_stats(&self) -> ReadStats {
+        let mut total = ReadStats::default();
+        for engine in self.engines.iter() {
+            total += engine.read_stats();
+        }
+        total
+    }
+
+    /// Sums the write pipeline counters of every partition.
+    pub fn write_stats(&self) -> Result<WriteStats, EngineError> {
+        let mut total = WriteStats::default();
+        for engine in self.engines.iter() {
+            total += engine.write_stats()?;
+        }
+        Ok(total)
+    }
+
+    /// Takes what every partition wrote since the previous call, one
+    /// event per partition that wrote anything.
+    pub fn take_write_activity(&self) -> Result<Vec<WriteActivity>, EngineError> {
+        let mut events = Vec::new();
+        for (p, engine) in self.engines.iter().enumerate() {
+            events.extend(engine.take_write_activity(p)?);
+        }
+        Ok(events)
+    }
+
+    /// Takes the reads past the fan-out limit of every partition, one
+    /// event per partition that had any.
+    pub fn take_read_fanout(&self) -> Vec<ReadFanout> {
+        self.engines
+            .iter()
+            .enumerate()
+            .filter_map(|(p, engine)| engine.take_read_fanout(p))
+            .collect()
+    }
+
+    /// Sums the tombstone compaction checks of every partition.
+    pub fn tombstone_gc_stats(&self) -> TombstoneGcStats {
+        let mut total = TombstoneGcStats::default();
+        for engine in self.engines.iter() {
+            total += engine.tombstone_gc_stats();
+        }
+        total
+    }
+
+    /// Sums the compaction job counters of every partition.
+    pub fn compaction_job_stats(&self) -> CompactionJobStats {
+        let mut total = CompactionJobStats::default();
+        for engine in self.engines.iter() {
+            total += engine.compaction_job_stats();
+        }
+        total
+    }
+
+    /// Sums the per-prefix usage of every partition.
+    pub fn prefix_usage(&self) -> Result<Vec<PrefixTotals>, EngineError> {
+        let mut total: Vec<PrefixTotals> = Vec::new();
+        for engine in self.engines.iter() {
+            let usage = engine.prefix_usage()?;
+            total.resize(usage.len(), PrefixTotals::default());
+            for (total, usage) in total.iter_mut().zip(usage) {
+                *total += usage;
+            }
+        }
+        Ok(total)
+    }
+
+    /// Sums the live-key estimates of every partition. Partitions share
+    /// no keys, so no overlap discount applies between them.
+    pub fn estimate_num_keys(&self) -> Result<u64, EngineError> {
+        let mut total = 0;
+        for engine in self.engines.iter() {
+            total += engine.estimate_num_keys()?;
+        }
+        Ok(total)
+    }
+
+    /// Combines the recovery reports of every partition.
+    pub fn recovery_report(&self) -> RecoveryReport {
+        let mut report = RecoveryReport::default();
+        for engine in self.engines.iter() {
+            report.merge(engine.recovery_report().clone());
+        }
+        report
+    }
+
+    /// Checks every partition's manifest against its files and combines
+    /// the reports.
+    pub fn check_consistency(&self) -> Result<ConsistencyReport, EngineError> {
+        let mut report = ConsistencyReport::default();
+        for engine in self.engines.iter() {
+            report.merge(engine.check_consistency()?);
+        }
+        Ok(report)
+    }
+
+    /// Describes every partition's tree.
+    pub fn debug_report(&self) -> Result<DebugReport, EngineError> {
+        let partitions = self
+            .engines
+            .iter()
+            .enumerate()
+            .map(|(p, engine)| engine.debug_report(p))
+            .collect::<Result<_, _>>()?;
+        Ok(DebugReport { partitions })
+    }
+
+    /// Returns the compaction thresholds in effect, in partition order.
+    pub fn compaction_thresholds(&self) -> Result<Vec<CompactionThresholds>, EngineError> {
+        self.engines
+            .iter()
+            .map(Engine::compaction_thresholds)
+            .collect()
+    }
+
+    /// Runs one adaptive compaction controller step on every partition,
+    /// with one window per partition, and returns the changes made.
+    pub fn tune_compaction(
+        &self,
+        windows: &mut [TuningWindow],
+        tuning: &AdaptiveCompaction,
+    ) -> Result<Vec<CompactionTuning>, EngineError> {
+        let mut changes = Vec::new();
+        for (p, (engine, window)) in self.engines.iter().zip(windows).enumerate() {
+            changes.extend(engine.tune_compaction(p, window, tuning)?);
+        }
+        Ok(changes)
+    }
+
+    /// Number of partitions.
+    pub fn partition_count(&self) -> usize {
+        self.engines.len()
+    }
+
+    /// Returns the only partition, for replication: SSTable ids and
+    /// versions are per partition, so a delta describes a single tree.
+    fn replication_engine(&self) -> Result<&Engine, EngineError> {
+        match &*self.engines {
+            [engine] => Ok(engine),
+            _ => Err(EngineError::Replication(
+                "replication requires a single partition".into(),
+            )),
+        }
+    }
+
+    /// Returns the replication version of the only partition.
+    pub fn replication_version(&self) -> Result<u64, EngineError> {
+        self.replication_engine()?.replication_version()
+    }
+
+    /// Exports a delta from the only partition.
+    pub fn export_delta(&self, since_version: u64) -> Result<ReplicationDelta, EngineError> {
+        self.replication_engine()?.export_delta(since_version)
+    }
+
+    /// Applies a delta to the only partition.
+    pub fn apply_delta(&self, delta: &ReplicationDelta) -> Result<(), EngineError> {
+        self.replication_engine()?.apply_delta(delta)
+    }
+
+    /// Clones every partition into the matching layout under `target`.
+    pub fn clone_to(&self, target: impl AsRef<Path>) -> Result<(), EngineError> {
+        let target = target.as_ref();
+        if self.engines.len() == 1 {
+            return self.engines[0].clone_to(target);
+        }
+
+        if target.exists() && fs::read_dir(target)?.next().is_some() {
+            return Err(EngineError::Io(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("clone target {} is not empty", target.display()),
+            )));
+        }
+        let partitions_dir = target.join(PARTITIONS_DIR);
+        for (p, engine) in self.engines.iter().enumerate() {
+            engine.clone_to(partitions_dir.join(format!("{p:03}")))?;
+        }
+        Ok(())
+    }
+
+    /// Closes every partition.
+    pub fn close(&self) -> Result<(), EngineError> {
+        for engine in self.engines.iter() {
+            engine.close()?;
+        }
+        Ok(())
+    }
+
+    /// Freezes the active memtable of every partition whose age or WAL-size
+    /// flush trigger fires.
+    pub fn freeze_if_due(&self) -> Result<bool, EngineError> {
+        self.any(Engine::freeze_if_due)
+    }
+
+    /// Enters or leaves bulk-load mode on every partition.
+    pub fn set_bulk_load(&self, enabled: bool) -> Result<(), EngineError> {
+        for engine in self.engines.iter() {
+            engine.set_bulk_load(enabled)?;
+        }
+        Ok(())
+    }
+
+    /// Returns `true` while bulk-load mode is enabled.
+    pub fn is_bulk_loading(&self) -> Result<bool, EngineError> {
+        self.engines[0].is_bulk_loading()
+    }
+
+    /// Pauses or resumes background compaction.
+    pub fn set_compaction_paused(&self, paused: bool) {
+        self.compaction_paused.store(paused, Ordering::Release);
+    }
+
+    /// Returns `true` while background compaction is paused.
+    pub fn is_compaction_paused(&self) -> bool {
+        self.compaction_paused.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` while background compaction is paused or deferred
+    /// to the end of a bulk load.
+    pub fn defers_compaction(&self) -> bool {
+        self.is_compaction_paused() || self.is_bulk_loading().unwrap_or(false)
+    }
+
+    /// Flushes every frozen memtable of every partition.
+    pub fn flush_all_frozen(&self) -> Result<usize, EngineError> {
+        let mut count = 0;
+        for engine in self.engines.iter() {
+            count += engine.flush_all_frozen()?;
+        }
+        Ok(count)
+    }
+
+    /// Flushes the oldest frozen memtable(s) of every partition.
+    pub fn flush_oldest_frozen(&self) -> Result<bool, EngineError> {
+        self.any(Engine::flush_oldest_frozen)
+    }
+
+    /// Runs one minor compaction round on every partition.
+    pub fn minor_compact(&self) -> Result<bool, EngineError> {
+        self.any(Engine::minor_compact)
+    }
+
+    /// Runs one tombstone compaction round on every partition.
+    pub fn tombstone_compact(&self) -> Result<bool, EngineError> {
+        self.any(Engine::tombstone_compact)
+    }
+
+    /// Major-compacts every partition.
+    pub fn major_compact(&self) -> Result<bool, EngineError> {
+        self.any(Engine::major_compact)
+    }
+
+    /// Runs major compaction on every partition in turn, reporting to
+    /// `progress` as each merges and finishes.
+    pub fn major_compact_tracked(
+        &self,
+        progress: &Arc<MajorProgress>,
+    ) -> Result<bool, EngineError> {
+        self.any(|engine| {
+            let compacted = engine.major_compact_tracked(Arc::clone(progress))?;
+            progress.partitions_done.fetch_add(1, Ordering::Relaxed);
+            Ok(compacted)
+        })
+    }
+
+    /// Records a compaction hint for `[start_key, end_key)` in every
+    /// partition; returns the SSTables hinted.
+    pub fn suggest_compact_range(
+        &self,
+        start_key: &[u8],
+        end_key: &[u8],
+        priority: CompactionPriority,
+    ) -> Result<usize, EngineError> {
+        let mut count = 0;
+        for engine in self.engines.iter() {
+            count += engine.suggest_compact_range(start_key, end_key, priority)?;
+        }
+        Ok(count)
+    }
+
+    /// Hints compaction in every partition whose lookups probe too many
+    /// SSTables.
+    pub fn hint_read_depth_compaction(&self) -> Result<bool, EngineError> {
+        self.any(Engine::hint_read_depth_compaction)
+    }
+
+    /// Hints compaction in every partition whose scans stepped over long
+    /// runs of tombstones.
+    pub fn hint_deletion_compaction(&self) -> Result<bool, EngineError> {
+        self.any(Engine::hint_deletion_compaction)
+    }
+
+    /// Runs one hinted compaction round on every partition, skipping the
+    /// hints of locked ranges.
+    pub fn hinted_compact(&self) -> Result<bool, EngineError> {
+        self.any(|engine| engine.hinted_compact(&self.range_locks))
+    }
+
+    /// Returns the range locks of the database.
+    pub fn range_locks(&self) -> &Arc<RangeLocks> {
+        &self.range_locks
+    }
+
+    /// Runs one periodic compaction round on every partition.
+    pub fn periodic_compact(&self) -> Result<bool, EngineError> {
+        self.any(Engine::periodic_compact)
+    }
+}
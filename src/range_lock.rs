@@ -0,0 +1,136 @@
+//! Advisory key-range locks taken with
+//! [`Db::lock_range`](crate::Db::lock_range).
+//!
+//! A lock covers `[start, end)` and excludes only other locks that
+//! overlap it: two holders of disjoint ranges proceed in parallel, and
+//! reads and writes never wait for a lock. Applications use them to
+//! serialize multi-key maintenance — a read-modify-write over a range, a
+//! bulk delete followed by re-insertion — against each other.
+//!
+//! Hinted compaction honors the locks: a
+//! [`suggest_compact_range`](crate::Db::suggest_compact_range) hint
+//! overlapping a held range is deferred until the range is released, so
+//! maintenance can delete and hint inside its lock without the compaction
+//! starting half-way through.
+//!
+//! Locks live in memory, within one [`Db`](crate::Db) handle, and are
+//! not reentrant: a thread locking a range overlapping one it already
+//! holds waits forever.
+
+use std::sync::{Arc, Condvar, Mutex};
+
+/// One held range.
+#[derive(Debug)]
+struct HeldRange {
+    id: u64,
+    start: Vec<u8>,
+    end: Vec<u8>,
+}
+
+#[derive(Debug, Default)]
+struct LockTable {
+    held: Vec<HeldRange>,
+    next_id: u64,
+}
+
+impl LockTable {
+    fn overlaps(&self, start: &[u8], end: &[u8]) -> bool {
+        self.held
+            .iter()
+            .any(|range| range.start.as_slice() < end && start < range.end.as_slice())
+    }
+
+    fn insert(&mut self, start: &[u8], end: &[u8]) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.held.push(HeldRange {
+            id,
+            start: start.to_vec(),
+            end: end.to_vec(),
+        });
+        id
+    }
+}
+
+/// The ranges locked in one database.
+#[derive(Debug, Default)]
+pub(crate) struct RangeLocks {
+    table: Mutex<LockTable>,
+    /// Notified whenever a range is released.
+    released: Condvar,
+}
+
+impl RangeLocks {
+    /// Locks `[start, end)`, waiting while an overlapping range is held.
+    pub fn lock(self: &Arc<Self>, start: &[u8], end: &[u8]) -> RangeGuard {
+        let mut table = self.table.lock().unwrap();
+        while table.overlaps(start, end) {
+            table = self.released.wait(table).unwrap();
+        }
+        let id = table.insert(start, end);
+        self.guard(id, start, end)
+    }
+
+    /// Locks `[start, end)` if no overlapping range is held.
+    pub fn try_lock(self: &Arc<Self>, start: &[u8], end: &[u8]) -> Option<RangeGuard> {
+        let mut table = self.table.lock().unwrap();
+        if table.overlaps(start, end) {
+            return None;
+        }
+        let id = table.insert(start, end);
+        Some(self.guard(id, start, end))
+    }
+
+    /// Returns `true` if a held range overlaps `[start, end)`.
+    pub fn is_locked(&self, start: &[u8], end: &[u8]) -> bool {
+        self.table.lock().unwrap().overlaps(start, end)
+    }
+
+    fn guard(self: &Arc<Self>, id: u64, start: &[u8], end: &[u8]) -> RangeGuard {
+        RangeGuard {
+            id,
+            start: start.to_vec(),
+            end: end.to_vec(),
+            locks: Arc::clone(self),
+        }
+    }
+
+    fn release(&self, id: u64) {
+        self.table
+            .lock()
+            .unwrap()
+            .held
+            .retain(|range| range.id != id);
+        self.released.notify_all();
+    }
+}
+
+/// A held key-range lock, returned by
+/// [`Db::lock_range`](crate::Db::lock_range); the range is released when
+/// the guard is dropped.
+#[derive(Debug)]
+#[must_use = "the range is released as soon as the guard is dropped"]
+pub struct RangeGuard {
+    id: u64,
+    start: Vec<u8>,
+    end: Vec<u8>,
+    locks: Arc<RangeLocks>,
+}
+
+impl RangeGuard {
+    /// Inclusive start of the locked range.
+    pub fn start(&self) -> &[u8] {
+        &self.start
+    }
+
+    /// Exclusive end of the locked range.
+    pub fn end(&self) -> &[u8] {
+        &self.end
+    }
+}
+
+impl Drop for RangeGuard {
+    fn drop(&mut self) {
+        self.locks.release(self.id);
+    }
+}
@@ -0,0 +1,317 @@
+//! Read-only access to a database owned by another process.
+//!
+//! One process holds the write lease of a database directory through
+//! [`Db`](crate::Db); any number of other processes — sidecar analytics,
+//! backup verifiers, debugging tools — may open the same directory with
+//! [`ReadOnlyDb`]. A read-only handle never writes to the directory. It
+//! serves reads from the SSTables listed in the manifest, so a write
+//! becomes visible once the writer has flushed it.
+//!
+//! The handle discovers new SSTables by polling the manifest every
+//! [`ReadOnlyConfig::refresh_interval_ms`], or on demand through
+//! [`ReadOnlyDb::refresh`]. A refresh reopens only the tables it has not
+//! seen yet; reads in flight keep the previous set alive.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use tracing::{error, info};
+
+use crate::engine::{EngineError, ReadOnlyEngine, ReadOptions, ReadStats};
+use crate::partition::{PartitionedEngine, partition_dir, partition_index};
+use crate::{ConfigViolations, DbError, KeyValue};
+
+/// Configuration for a [`ReadOnlyDb`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadOnlyConfig {
+    /// Milliseconds between two background checks of the manifest for
+    /// new SSTables. Set to `0` to refresh only through
+    /// [`ReadOnlyDb::refresh`].
+    ///
+    /// **Bounds:** `refresh_interval_ms` = 0 or 10 ≤ `refresh_interval_ms`
+    /// ≤ 3 600 000 (1 hour).
+    ///
+    /// Default: `1000`.
+    pub refresh_interval_ms: usize,
+}
+
+impl Default for ReadOnlyConfig {
+    fn default() -> Self {
+        Self {
+            refresh_interval_ms: 1000,
+        }
+    }
+}
+
+impl ReadOnlyConfig {
+    /// Validates all configuration parameters against their documented bounds.
+    fn validate(&self) -> Result<(), DbError> {
+        if self.refresh_interval_ms != 0 && !(10..=3_600_000).contains(&self.refresh_interval_ms) {
+            return Err(DbError::InvalidConfig(ConfigViolations::single(
+                &["refresh_interval_ms"],
+                "refresh_interval_ms must be 0 or in [10, 3600000]",
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A read-only handle to a database written by another process.
+///
+/// # Thread safety
+///
+/// `ReadOnlyDb` is `Send + Sync` — it can be shared across threads via
+/// `Arc<ReadOnlyDb>`.
+///
+/// # Shutdown
+///
+/// Call [`ReadOnlyDb::close`] to stop the background refresh; dropping
+/// the handle does the same.
+pub struct ReadOnlyDb {
+    partitions: Arc<[ReadOnlyEngine]>,
+
+    /// Background refresh thread: dropping the sender stops it.
+    poller: Mutex<Option<(crossbeam::channel::Sender<()>, thread::JoinHandle<()>)>>,
+    closed: AtomicBool,
+}
+
+impl std::fmt::Debug for ReadOnlyDb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadOnlyDb")
+            .field("closed", &self.closed.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+impl ReadOnlyDb {
+    /// Opens the database at `path` for reading.
+    ///
+    /// The directory must hold a database; it is not created. Opening
+    /// does not need, and does not take, the write lease.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::InvalidConfig`] — a configuration parameter is out of
+    ///   its documented bounds.
+    /// - [`DbError::InvalidArgument`] — `path` holds no database.
+    /// - [`DbError::Engine`] — the manifest or an SSTable could not be
+    ///   read.
+    pub fn open(path: impl AsRef<Path>, config: ReadOnlyConfig) -> Result<Self, DbError> {
+        config.validate()?;
+
+        let base = path.as_ref();
+        let Some(partitions) = PartitionedEngine::existing_partitions(base)? else {
+            return Err(DbError::InvalidArgument(format!(
+                "no database at {}",
+                base.display()
+            )));
+        };
+        let engines = (0..partitions)
+            .map(|p| ReadOnlyEngine::open(partition_dir(base, partitions, p)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let engines: Arc<[ReadOnlyEngine]> = Arc::from(engines);
+
+        let poller = if config.refresh_interval_ms > 0 {
+            Some(Self::spawn_poller(
+                Arc::clone(&engines),
+                Duration::from_millis(config.refresh_interval_ms as u64),
+            )?)
+        } else {
+            None
+        };
+
+        info!(path = %base.display(), partitions, "read-only database opened");
+        Ok(Self {
+            partitions: engines,
+            poller: Mutex::new(poller),
+            closed: AtomicBool::new(false),
+        })
+    }
+
+    /// Stops the background refresh.
+    ///
+    /// Subsequent operations on this handle return [`DbError::Closed`].
+    /// Calling `close` more than once is harmless.
+    pub fn close(&self) -> Result<(), DbError> {
+        if self.closed.swap(true, Ordering::AcqRel) {
+            return Ok(());
+        }
+        self.stop_poller();
+        info!("read-only database closed");
+        Ok(())
+    }
+
+    /// Retrieves the value associated with a key, as of the last refresh.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the handle has been closed.
+    /// - [`DbError::InvalidArgument`] — `key` is empty.
+    /// - [`DbError::Engine`] — an SSTable read failed.
+    pub fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, DbError> {
+        self.get_opt(key, &ReadOptions::default())
+    }
+
+    /// Retrieves the value associated with a key using explicit
+    /// [`ReadOptions`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`get`](Self::get).
+    pub fn get_opt(&self, key: &[u8], opts: &ReadOptions) -> Result<Option<Vec<u8>>, DbError> {
+        self.check_open()?;
+
+        if key.is_empty() {
+            return Err(DbError::InvalidArgument("key must not be empty".into()));
+        }
+
+        let engine = &self.partitions[partition_index(key, self.partitions.len())];
+        Ok(engine.get_opt(key, opts)?)
+    }
+
+    /// Returns all live key-value pairs in `[start, end)`, as of the last
+    /// refresh, in key order.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the handle has been closed.
+    /// - [`DbError::InvalidArgument`] — `start` or `end` is empty.
+    /// - [`DbError::Engine`] — an SSTable read failed.
+    pub fn scan(&self, start: &[u8], end: &[u8]) -> Result<Vec<KeyValue>, DbError> {
+        self.scan_opt(start, end, &ReadOptions::default())
+    }
+
+    /// Scans the half-open range `[start, end)` using explicit
+    /// [`ReadOptions`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`scan`](Self::scan).
+    pub fn scan_opt(
+        &self,
+        start: &[u8],
+        end: &[u8],
+        opts: &ReadOptions,
+    ) -> Result<Vec<KeyValue>, DbError> {
+        self.check_open()?;
+
+        if start.is_empty() || end.is_empty() {
+            return Err(DbError::InvalidArgument(
+                "start and end keys must not be empty".into(),
+            ));
+        }
+        if start >= end {
+            return Ok(Vec::new());
+        }
+
+        let mut results = Vec::new();
+        for engine in self.partitions.iter() {
+            results.extend(engine.scan_opt(start, end, opts)?);
+        }
+        if self.partitions.len() > 1 {
+            results.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        }
+        Ok(results)
+    }
+
+    /// Re-reads the manifest now and picks up SSTables the writer added
+    /// or removed since the last refresh.
+    ///
+    /// Returns `Ok(true)` if any partition changed.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the handle has been closed.
+    /// - [`DbError::Engine`] — the manifest or a new SSTable could not be
+    ///   read; the previous SSTable set stays in use.
+    pub fn refresh(&self) -> Result<bool, DbError> {
+        self.check_open()?;
+        Ok(Self::refresh_all(&self.partitions)?)
+    }
+
+    /// Returns the writer's next SSTable id as of the last refresh, summed
+    /// over the partitions. It grows with every flush and compaction the
+    /// handle has observed.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the handle has been closed.
+    pub fn manifest_version(&self) -> Result<u64, DbError> {
+        self.check_open()?;
+        let mut version = 0;
+        for engine in self.partitions.iter() {
+            version += engine.manifest_version()?;
+        }
+        Ok(version)
+    }
+
+    /// Returns point-lookup counters accumulated since open, summed
+    /// across partitions.
+    ///
+    /// # Errors
+    ///
+    /// - [`DbError::Closed`] — the handle has been closed.
+    pub fn read_stats(&self) -> Result<ReadStats, DbError> {
+        self.check_open()?;
+        let mut total = ReadStats::default();
+        for engine in self.partitions.iter() {
+            total += engine.read_stats();
+        }
+        Ok(total)
+    }
+
+    fn check_open(&self) -> Result<(), DbError> {
+        if self.closed.load(Ordering::Acquire) {
+            return Err(DbError::Closed);
+        }
+        Ok(())
+    }
+
+    fn refresh_all(partitions: &[ReadOnlyEngine]) -> Result<bool, EngineError> {
+        let mut changed = false;
+        for engine in partitions {
+            changed |= engine.refresh()?;
+        }
+        Ok(changed)
+    }
+
+    fn spawn_poller(
+        partitions: Arc<[ReadOnlyEngine]>,
+        interval: Duration,
+    ) -> Result<(crossbeam::channel::Sender<()>, thread::JoinHandle<()>), DbError> {
+        let (stop_tx, stop_rx) = crossbeam::channel::bounded::<()>(0);
+        let handle = thread::Builder::new()
+            .name("aeternusdb-refresh".into())
+            .spawn(move || {
+                while let Err(crossbeam::channel::RecvTimeoutError::Timeout) =
+                    stop_rx.recv_timeout(interval)
+                {
+                    if let Err(e) = Self::refresh_all(&partitions) {
+                        error!("background manifest refresh failed: {e}");
+                    }
+                }
+            })
+            .map_err(|e| {
+                DbError::Engine(EngineError::Internal(format!(
+                    "failed to spawn refresh thread: {e}"
+                )))
+            })?;
+        Ok((stop_tx, handle))
+    }
+
+    fn stop_poller(&self) {
+        if let Some((stop, handle)) = self.poller.lock().unwrap().take() {
+            drop(stop);
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for ReadOnlyDb {
+    fn drop(&mut self) {
+        self.stop_poller();
+    }
+}
@@ -0,0 +1,72 @@
+//! Sampling of per-operation tracing spans.
+//!
+//! Every public read and write on [`Db`](crate::Db) can open a `tracing`
+//! span describing the operation. At high request rates recording all of
+//! them is too expensive, so [`TraceSampler`] decides per operation
+//! whether to open one, keeping roughly
+//! [`DbConfig::trace_sampling_ratio`](crate::DbConfig::trace_sampling_ratio)
+//! of them. Unsampled operations get [`Span::none`], which costs nothing.
+//!
+//! The decision is a `splitmix64` hash of a shared atomic counter, so it
+//! needs neither a lock nor a random number generator, and consecutive
+//! operations are not sampled in a fixed stride.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tracing::Span;
+
+/// Lock-free Bernoulli sampler for operation spans.
+pub(crate) struct TraceSampler {
+    /// Operations whose hash falls below this are sampled.
+    threshold: u64,
+
+    /// Sample everything (`ratio >= 1.0`), skipping the hash.
+    always: bool,
+
+    /// Monotonic operation counter fed to the hash.
+    counter: AtomicU64,
+}
+
+impl TraceSampler {
+    /// Creates a sampler keeping `ratio` of operations, clamped to `[0, 1]`.
+    pub fn new(ratio: f64) -> Self {
+        let ratio = ratio.clamp(0.0, 1.0);
+        Self {
+            threshold: (ratio * u64::MAX as f64) as u64,
+            always: ratio >= 1.0,
+            counter: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns `true` if the next operation should be traced.
+    pub fn sample(&self) -> bool {
+        if self.always {
+            return true;
+        }
+        if self.threshold == 0 {
+            return false;
+        }
+        let n = self.counter.fetch_add(1, Ordering::Relaxed);
+        splitmix64(n) < self.threshold
+    }
+
+    /// Returns the span built by `make` if this operation is sampled, and
+    /// a disabled span otherwise.
+    pub fn span(&self, make: impl FnOnce() -> Span) -> Span {
+        if self.sample() { make() } else { Span::none() }
+    }
+}
+
+/// `splitmix64` finaliser: maps a counter to a well-mixed 64-bit value.
+fn splitmix64(n: u64) -> u64 {
+    let mut z = n.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Stable hash of `key` recorded on operation spans in place of the key
+/// itself, so spans can be correlated without logging user data.
+pub(crate) fn key_hash(key: &[u8]) -> u32 {
+    crc32fast::hash(key)
+}
@@ -0,0 +1,124 @@
+//! Logging of operations slower than a configured threshold.
+//!
+//! With [`DbConfig::slow_op_threshold`](crate::DbConfig::slow_op_threshold)
+//! set, each get, write, scan, flush, and compaction starts a
+//! [`SlowOpTimer`]. If the operation takes at least the threshold, the
+//! timer emits one `warn` event on target `aeternusdb::slow_op` when it is
+//! dropped, with a breakdown of where the time went.
+//!
+//! The breakdown comes from per-thread counters that the WAL and SSTable
+//! code bump as they work: time spent in WAL fsyncs, SSTable blocks read,
+//! and SSTables probed. Counters only grow, so a timer reports the
+//! difference since it started and nested timers — a flush run inline by
+//! a write — do not disturb each other. Work handed to other threads,
+//! such as a parallel bloom probe, is not counted.
+
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+
+/// Work done by the current thread since it started.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct OpCounters {
+    /// Time spent waiting for WAL fsyncs.
+    pub wal_sync: Duration,
+
+    /// SSTable blocks read.
+    pub blocks_read: u64,
+
+    /// SSTables consulted, whether or not a block was read.
+    pub tables_probed: u64,
+}
+
+impl OpCounters {
+    /// Returns the work done between `earlier` and `self`.
+    fn since(self, earlier: Self) -> Self {
+        Self {
+            wal_sync: self.wal_sync.saturating_sub(earlier.wal_sync),
+            blocks_read: self.blocks_read - earlier.blocks_read,
+            tables_probed: self.tables_probed - earlier.tables_probed,
+        }
+    }
+}
+
+thread_local! {
+    static COUNTERS: Cell<OpCounters> = const {
+        Cell::new(OpCounters {
+            wal_sync: Duration::ZERO,
+            blocks_read: 0,
+            tables_probed: 0,
+        })
+    };
+}
+
+/// Returns the counters of the current thread.
+fn counters() -> OpCounters {
+    COUNTERS.with(Cell::get)
+}
+
+/// Applies `update` to the counters of the current thread.
+fn update(f: impl FnOnce(&mut OpCounters)) {
+    COUNTERS.with(|cell| {
+        let mut counters = cell.get();
+        f(&mut counters);
+        cell.set(counters);
+    });
+}
+
+/// Counts a WAL fsync that took `elapsed`.
+pub(crate) fn record_wal_sync(elapsed: Duration) {
+    update(|c| c.wal_sync += elapsed);
+}
+
+/// Counts one SSTable block read.
+pub(crate) fn record_block_read() {
+    update(|c| c.blocks_read += 1);
+}
+
+/// Counts `n` SSTables consulted.
+pub(crate) fn record_tables_probed(n: usize) {
+    update(|c| c.tables_probed += n as u64);
+}
+
+/// Times one operation and logs it on drop if it took at least the
+/// threshold.
+#[derive(Debug)]
+pub(crate) struct SlowOpTimer {
+    op: &'static str,
+    threshold: Duration,
+    started: Instant,
+    start_counters: OpCounters,
+}
+
+impl SlowOpTimer {
+    /// Starts timing `op`, or returns `None` if no threshold is set.
+    pub(crate) fn start(op: &'static str, threshold: Option<Duration>) -> Option<Self> {
+        threshold.map(|threshold| Self {
+            op,
+            threshold,
+            started: Instant::now(),
+            start_counters: counters(),
+        })
+    }
+}
+
+impl Drop for SlowOpTimer {
+    fn drop(&mut self) {
+        let elapsed = self.started.elapsed();
+        if elapsed < self.threshold {
+            return;
+        }
+        let work = counters().since(self.start_counters);
+        warn!(
+            target: "aeternusdb::slow_op",
+            op = self.op,
+            elapsed_us = elapsed.as_micros() as u64,
+            threshold_us = self.threshold.as_micros() as u64,
+            wal_sync_us = work.wal_sync.as_micros() as u64,
+            blocks_read = work.blocks_read,
+            tables_probed = work.tables_probed,
+            "slow operation"
+        );
+    }
+}
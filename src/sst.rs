@@ -0,0 +1,338 @@
+//! Read-only access to SSTable files for external tooling.
+//!
+//! [`SstReader`] opens a single `.sst` file produced by the engine and
+//! exposes its properties, point entries, and range tombstones without a
+//! running [`Db`](crate::Db). It is meant for ETL jobs, offline
+//! validators, and similar tools that consume SSTables directly.
+//! [`sniff_format_version`] recognises an SSTable from its last bytes
+//! without opening it, and [`verify_file_digest`] checks a copied file
+//! against its whole-file digest without parsing its blocks.
+//!
+//! The types in this module are a stable façade: they mirror the on-disk
+//! contents but do not expose the engine's internal representations, so
+//! internal refactors do not break callers. Entries are the shared
+//! [`types`](crate::types) used by every API that exposes stored versions.
+//!
+//! Entries are returned **unresolved** — every stored version of a key is
+//! yielded, deletes and range tombstones included. Applying visibility
+//! (newest LSN wins, tombstones hide older values) is up to the caller.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use aeternusdb::sst::SstReader;
+//!
+//! let reader = SstReader::open("/tmp/my_db/sstables/000001.sst").unwrap();
+//! println!("{} records", reader.properties().record_count);
+//!
+//! for point in reader.points() {
+//!     let point = point.unwrap();
+//!     println!("{:?} @ {} -> {:?}", point.key, point.lsn, point.value);
+//! }
+//! ```
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::SstFileDigest;
+use crate::encoding;
+use crate::sstable::{
+    self, BlockIterator, SST_FOOTER_TAIL_SIZE, SSTable, SSTableDataBlock, SSTableError,
+    SSTableFooter,
+};
+use crate::types::{PointEntry, RangeTombstone};
+
+// ------------------------------------------------------------------------------------------------
+// Error type
+// ------------------------------------------------------------------------------------------------
+
+/// Errors returned by [`SstReader`].
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SstError {
+    /// The file could not be read.
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    /// The file is not a valid SSTable, or a checksum did not match.
+    #[error("corrupt SSTable: {0}")]
+    Corrupt(String),
+}
+
+impl From<SSTableError> for SstError {
+    fn from(e: SSTableError) -> Self {
+        match e {
+            SSTableError::Io(e) => Self::Io(e),
+            other => Self::Corrupt(other.to_string()),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public entry types
+// ------------------------------------------------------------------------------------------------
+
+/// A single point entry stored in an SSTable.
+pub type SstPoint = PointEntry;
+
+/// A range tombstone stored in an SSTable.
+pub type SstRangeTombstone = RangeTombstone;
+
+/// Table-level properties recorded when the SSTable was built.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SstProperties {
+    /// Creation timestamp (UNIX epoch nanos).
+    pub creation_timestamp: u64,
+
+    /// Total number of point records (puts and point deletes).
+    pub record_count: u64,
+
+    /// Number of point deletes.
+    pub tombstone_count: u64,
+
+    /// Number of range tombstones.
+    pub range_tombstone_count: u64,
+
+    /// Lowest LSN present in the table.
+    pub min_lsn: u64,
+
+    /// Highest LSN present in the table.
+    pub max_lsn: u64,
+
+    /// Lowest timestamp present in the table.
+    pub min_timestamp: u64,
+
+    /// Highest timestamp present in the table.
+    pub max_timestamp: u64,
+
+    /// Smallest point key stored in the table.
+    pub min_key: Vec<u8>,
+
+    /// Largest point key stored in the table.
+    pub max_key: Vec<u8>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// SstReader
+// ------------------------------------------------------------------------------------------------
+
+/// Read-only handle to one SSTable file.
+///
+/// The file is memory-mapped and its header, footer, and metadata blocks
+/// are checksum-verified on [`open`](Self::open). Data blocks are verified
+/// as [`points`](Self::points) reads them.
+pub struct SstReader {
+    path: PathBuf,
+    table: SSTable,
+}
+
+impl SstReader {
+    /// Opens the SSTable at `path`.
+    ///
+    /// # Errors
+    ///
+    /// - [`SstError::Io`] if the file cannot be opened or mapped.
+    /// - [`SstError::Corrupt`] if the header, footer, or a metadata block
+    ///   fails validation.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SstError> {
+        let path = path.as_ref();
+        let table = SSTable::open(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            table,
+        })
+    }
+
+    /// Returns the path this reader was opened from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the file size in bytes.
+    pub fn file_size(&self) -> u64 {
+        self.table.file_size()
+    }
+
+    /// Returns the SSTable format version the file was written with.
+    pub fn format_version(&self) -> u32 {
+        self.table.format_version()
+    }
+
+    /// Returns the table's properties.
+    pub fn properties(&self) -> SstProperties {
+        let p = &self.table.properties;
+        SstProperties {
+            creation_timestamp: p.creation_timestamp,
+            record_count: p.record_count,
+            tombstone_count: p.tombstone_count,
+            range_tombstone_count: p.range_tombstones_count,
+            min_lsn: p.min_lsn,
+            max_lsn: p.max_lsn,
+            min_timestamp: p.min_timestamp,
+            max_timestamp: p.max_timestamp,
+            min_key: p.min_key.clone(),
+            max_key: p.max_key.clone(),
+        }
+    }
+
+    /// Returns `false` if the bloom filter rules `key` out as a point key.
+    ///
+    /// Range tombstones are not consulted.
+    pub fn may_contain(&self, key: &[u8]) -> bool {
+        self.table.bloom_may_contain(key)
+    }
+
+    /// Iterates every point entry in `(key ASC, LSN DESC)` order.
+    ///
+    /// Each data block is checksum-verified as it is loaded. On the first
+    /// error — a bad block, or fewer entries than
+    /// [`SstProperties::record_count`] — the iterator yields the error and
+    /// then ends.
+    pub fn points(&self) -> SstPoints<'_> {
+        SstPoints {
+            table: &self.table,
+            next_block: 0,
+            block: None,
+            yielded: 0,
+            done: false,
+        }
+    }
+
+    /// Iterates every range tombstone, sorted by start key.
+    pub fn range_tombstones(&self) -> impl Iterator<Item = SstRangeTombstone> + '_ {
+        self.table
+            .range_tombstone_iter()
+            .map(SstRangeTombstone::from)
+    }
+
+    /// Reads every data block and checks that the table is internally
+    /// consistent, then verifies the whole-file digest if the table has
+    /// one (see [`verify_file_digest`]).
+    ///
+    /// Returns the first error [`points`](Self::points) would report, or
+    /// [`SstError::Corrupt`] on a digest mismatch.
+    pub fn verify(&self) -> Result<(), SstError> {
+        for point in self.points() {
+            point?;
+        }
+        verify_file_digest(&self.path)?;
+        Ok(())
+    }
+}
+
+/// Returns the SSTable format version named by the last bytes of the
+/// file at `path`, reading nothing else, or `None` if the file does not
+/// end with the SSTable footer magic: it is not an SSTable, or one
+/// written before format version 3.
+///
+/// Nothing is verified; use [`SstReader::open`] to validate the file.
+///
+/// # Errors
+///
+/// - [`SstError::Io`] if the file cannot be read.
+pub fn sniff_format_version(path: impl AsRef<Path>) -> Result<Option<u32>, SstError> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let tail_len = len.min(SST_FOOTER_TAIL_SIZE as u64);
+    file.seek(SeekFrom::End(-(tail_len as i64)))?;
+    let mut tail = vec![0; tail_len as usize];
+    file.read_exact(&mut tail)?;
+    Ok(SSTableFooter::sniff(&tail))
+}
+
+/// Verifies the SSTable at `path` against the whole-file digest stored
+/// in it (see [`DbConfig::sstable_file_digest`](crate::DbConfig::sstable_file_digest)),
+/// parsing nothing but its footer and digest block — e.g. after copying
+/// it to a backup or an object store.
+///
+/// Returns the algorithm of the digest, or `None` for a table written
+/// without one, which is not verified.
+///
+/// # Errors
+///
+/// - [`SstError::Io`] if the file cannot be read.
+/// - [`SstError::Corrupt`] if the footer is damaged, the file size
+///   differs from the one it records, or the digest does not match.
+pub fn verify_file_digest(path: impl AsRef<Path>) -> Result<Option<SstFileDigest>, SstError> {
+    Ok(sstable::verify_file_digest_at(path)?)
+}
+
+/// Iterator over the point entries of an [`SstReader`].
+///
+/// Created by [`SstReader::points`].
+pub struct SstPoints<'a> {
+    /// Table being read.
+    table: &'a SSTable,
+
+    /// Index of the next data block to load.
+    next_block: usize,
+
+    /// Iterator over the currently loaded block.
+    block: Option<BlockIterator>,
+
+    /// Entries yielded so far, checked against the record count.
+    yielded: u64,
+
+    /// Set after the last entry or the first error.
+    done: bool,
+}
+
+impl SstPoints<'_> {
+    /// Loads the next data block, returning `false` past the last one.
+    fn load_next_block(&mut self) -> Result<bool, SstError> {
+        let Some(entry) = self.table.index.get(self.next_block) else {
+            return Ok(false);
+        };
+        self.next_block += 1;
+
+        let bytes = self.table.read_block(&entry.handle, true)?;
+        let (block, _) = encoding::decode_from_slice::<SSTableDataBlock>(&bytes)
+            .map_err(|e| SstError::Corrupt(e.to_string()))?;
+        self.block = Some(BlockIterator::new(block.data));
+        Ok(true)
+    }
+}
+
+impl Iterator for SstPoints<'_> {
+    type Item = Result<SstPoint, SstError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        loop {
+            if let Some(entry) = self.block.as_mut().and_then(BlockIterator::next_entry) {
+                self.yielded += 1;
+                return Some(Ok(SstPoint {
+                    key: entry.key,
+                    value: (!entry.is_delete).then_some(entry.value),
+                    lsn: entry.lsn,
+                    timestamp: entry.timestamp,
+                }));
+            }
+
+            match self.load_next_block() {
+                Ok(true) => {}
+                Ok(false) => {
+                    self.done = true;
+                    let expected = self.table.properties.record_count;
+                    if self.yielded != expected {
+                        return Some(Err(SstError::Corrupt(format!(
+                            "read {} point entries, properties record {expected}",
+                            self.yielded
+                        ))));
+                    }
+                    return None;
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
@@ -2,13 +2,15 @@
 //!
 //! The [`SstWriter`] struct accepts two sorted streams:
 //!
-//! - **Point entries** ([`PointEntry`]): key/value pairs or point tombstones.
+//! - **Point entries** ([`PointEntry`](crate::engine::PointEntry), or
+//!   [`PointEntryRef`](crate::engine::PointEntryRef) borrowed from a
+//!   memtable): key/value pairs or point tombstones.
 //! - **Range tombstones** ([`RangeTombstone`]):
 //!   delete intervals covering key ranges.
 //!
 //! and writes a fully-structured SSTable containing header, data blocks, bloom
-//! filter, range tombstone block, properties block, metaindex block, index
-//! block, and footer.
+//! filter, key sketch, range tombstone block, properties block, metaindex
+//! block, index block, an optional whole-file digest block, and footer.
 //!
 //! # Input Requirements
 //!
@@ -22,39 +24,129 @@
 //! # Output Guarantees
 //!
 //! - All point entries are grouped into data blocks and written with per-block CRC32.
-//! - Bloom filter is built from keys (including point tombstones).
-//! - Properties capture min/max keys, LSNs, timestamps and counts.
+//! - Bloom filter is built from keys (including point tombstones) and sized
+//!   from the number of distinct keys, not versions.
+//! - The key sketch is built from the same distinct keys. A compaction
+//!   output gets a fresh sketch of its own keys rather than a merge of its
+//!   inputs' sketches, so dropped versions and keys do not linger in it.
+//! - Properties capture min/max keys, LSNs, timestamps and counts,
+//!   including the number of distinct point keys.
+//! - With [`SstWriter::file_digest`], every byte is hashed as it is
+//!   written and the digest is stored after the index block, so the file
+//!   is not read back to compute it.
 //! - The final file is written atomically using a `.tmp` → final rename.
+//! - [`SstWriter::build`] returns [`SstBuildStats`]: the data blocks
+//!   written and their bytes before and after block encoding.
+//!
+//! # Allocations
+//!
+//! Each cell is encoded straight into its data block. Apart from the
+//! blocks themselves, the writer keeps one copy of each distinct key —
+//! for the bloom filter and key sketch, which are sized only once all keys
+//! are known — and the first key of each block; an owned entry gives up
+//! its key instead of having it copied.
 //!
 //! # Atomicity
 //!
 //! 1. Write everything to `path.tmp`.
 //! 2. Flush and sync the file.
 //! 3. Rename `path.tmp` → `path` atomically.
+//! 4. Sync the directory, so the new name survives a crash.
 //!
-//! A crash cannot produce a partially-written SSTable.
+//! A crash cannot produce a partially-written SSTable, nor lose one the
+//! manifest goes on to list.
 
 use std::{
-    fs::{File, OpenOptions, rename},
-    io::{BufWriter, Seek, Write},
+    fs::{File, OpenOptions},
+    io::{self, BufWriter, Seek, SeekFrom, Write},
     mem,
+    ops::AddAssign,
     path::Path,
-    time::{SystemTime, UNIX_EPOCH},
+    sync::Arc,
 };
 
-use crate::encoding;
+use crate::clock::{Clock, SystemClock};
+use crate::durability;
+use crate::encoding::{self, Encode};
+use crate::sketch::KeySketch;
+use crate::tenant;
 use bloomfilter::Bloom;
 
-use crate::engine::{PointEntry, RangeTombstone};
+use crate::engine::{AsPointEntry, RangeTombstone};
 
 use super::{
-    BlockHandle, MetaIndexEntry, SST_BLOOM_FILTER_FALSE_POSITIVE_RATE,
+    BlockHandle, FileDigester, MetaIndexEntry, SST_BLOOM_FILTER_FALSE_POSITIVE_RATE,
     SST_DATA_BLOCK_CHECKSUM_SIZE, SST_DATA_BLOCK_LEN_SIZE, SST_DATA_BLOCK_MAX_SIZE,
-    SST_FOOTER_SIZE, SST_HDR_MAGIC, SST_HDR_VERSION, SSTableBloomBlock, SSTableCell,
-    SSTableDataBlock, SSTableError, SSTableFooter, SSTableHeader, SSTableIndexEntry,
+    SST_FOOTER_DIGEST_SLOT, SST_FOOTER_EXTENSION_SLOTS, SST_FOOTER_MAGIC, SST_FOOTER_SIZE,
+    SST_HDR_MAGIC, SST_HDR_VERSION, SSTableBloomBlock, SSTableCell, SSTableDataBlock, SSTableError,
+    SSTableFileDigestBlock, SSTableFooter, SSTableHeader, SSTableIndexEntry, SSTablePrefixUsage,
     SSTablePropertiesBlock, SSTableRangeTombstoneCell, SSTableRangeTombstoneDataBlock,
+    SstFileDigest,
 };
 
+// ------------------------------------------------------------------------------------------------
+// SstBuildStats — what a build wrote
+// ------------------------------------------------------------------------------------------------
+
+/// What building SSTables wrote, summed per database in
+/// [`WriteStats::sstables`](crate::WriteStats::sstables).
+///
+/// Data blocks are not compressed, so `written_bytes` exceeds `raw_bytes`
+/// only by the framing of each block: its encoded length, length prefix,
+/// and checksum.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SstBuildStats {
+    /// SSTables built.
+    pub sstables: u64,
+
+    /// Data blocks written.
+    pub data_blocks: u64,
+
+    /// Bytes of the cells, keys, and values in those blocks, as handed to
+    /// the block encoder.
+    pub raw_bytes: u64,
+
+    /// Bytes the blocks take on disk.
+    pub written_bytes: u64,
+
+    /// Size of the finished files, every block and the footer included.
+    pub file_bytes: u64,
+}
+
+impl SstBuildStats {
+    /// `written_bytes / raw_bytes`: above one while blocks are stored
+    /// uncompressed.
+    ///
+    /// Returns `0.0` before any data block was written.
+    pub fn write_ratio(&self) -> f64 {
+        if self.raw_bytes == 0 {
+            return 0.0;
+        }
+        self.written_bytes as f64 / self.raw_bytes as f64
+    }
+
+    /// Builds summed since `earlier`, an earlier sum of the same builds.
+    pub(crate) fn since(&self, earlier: &Self) -> Self {
+        Self {
+            sstables: self.sstables.saturating_sub(earlier.sstables),
+            data_blocks: self.data_blocks.saturating_sub(earlier.data_blocks),
+            raw_bytes: self.raw_bytes.saturating_sub(earlier.raw_bytes),
+            written_bytes: self.written_bytes.saturating_sub(earlier.written_bytes),
+            file_bytes: self.file_bytes.saturating_sub(earlier.file_bytes),
+        }
+    }
+}
+
+impl AddAssign for SstBuildStats {
+    fn add_assign(&mut self, other: Self) {
+        self.sstables += other.sstables;
+        self.data_blocks += other.data_blocks;
+        self.raw_bytes += other.raw_bytes;
+        self.written_bytes += other.written_bytes;
+        self.file_bytes += other.file_bytes;
+    }
+}
+
 // ------------------------------------------------------------------------------------------------
 // BuildStats — accumulates metadata during SSTable construction
 // ------------------------------------------------------------------------------------------------
@@ -71,6 +163,8 @@ struct BuildStats {
     max_timestamp: u64,
     min_key: Option<Vec<u8>>,
     max_key: Option<Vec<u8>>,
+    /// Tally of each tenant prefix, in the order the prefixes were given.
+    prefix_usage: Vec<SSTablePrefixUsage>,
 }
 
 impl BuildStats {
@@ -84,6 +178,7 @@ impl BuildStats {
             max_timestamp: 0,
             min_key: None,
             max_key: None,
+            prefix_usage: Vec::new(),
         }
     }
 
@@ -96,14 +191,14 @@ impl BuildStats {
     }
 
     /// Convert collected statistics into an [`SSTablePropertiesBlock`].
-    fn into_properties(self, range_count: usize) -> SSTablePropertiesBlock {
+    fn into_properties(
+        self,
+        range_count: usize,
+        key_count: usize,
+        creation_timestamp: u64,
+    ) -> SSTablePropertiesBlock {
         SSTablePropertiesBlock {
-            creation_timestamp: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_nanos()
-                .try_into()
-                .unwrap_or(u64::MAX),
+            creation_timestamp,
             record_count: self.record_count,
             tombstone_count: self.tombstone_count,
             range_tombstones_count: range_count as u64,
@@ -113,8 +208,63 @@ impl BuildStats {
             max_timestamp: self.max_timestamp,
             min_key: self.min_key.unwrap_or_default(),
             max_key: self.max_key.unwrap_or_default(),
+            key_count: Some(key_count as u64),
+            prefix_usage: (!self.prefix_usage.is_empty()).then_some(self.prefix_usage),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// DigestWriter — hashes every byte on its way to the file
+// ------------------------------------------------------------------------------------------------
+
+/// Writer that feeds every byte written through it into a
+/// [`FileDigester`], if it has one.
+struct DigestWriter<W> {
+    inner: W,
+    digester: Option<(SstFileDigest, FileDigester)>,
+}
+
+impl<W> DigestWriter<W> {
+    fn new(inner: W, algorithm: Option<SstFileDigest>) -> Self {
+        Self {
+            inner,
+            digester: algorithm.map(|algorithm| (algorithm, FileDigester::new(algorithm))),
         }
     }
+
+    /// Returns the digest block of the bytes written so far, or `None`
+    /// without a digest. Later bytes are not hashed.
+    fn finish(&mut self) -> Option<SSTableFileDigestBlock> {
+        self.digester
+            .take()
+            .map(|(algorithm, digester)| SSTableFileDigestBlock {
+                algorithm,
+                digest: digester.finish(),
+            })
+    }
+}
+
+impl<W: Write> Write for DigestWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        if let Some((_, digester)) = &mut self.digester {
+            digester.update(&buf[..n]);
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+// The builder only asks for its position; it never seeks back over bytes
+// already hashed.
+impl<W: Seek> Seek for DigestWriter<W> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -170,27 +320,34 @@ fn write_header(writer: &mut impl Write) -> Result<(), SSTableError> {
 }
 
 /// Encodes and flushes the current data-block buffer to disk, pushing a
-/// new index entry.
+/// new index entry that carries the block's LSN and timestamp bounds.
 fn flush_data_block(
     writer: &mut (impl Write + Seek),
     current_block: &mut Vec<u8>,
-    block_first_key: &mut Option<Vec<u8>>,
+    block_stats: &mut BuildStats,
     index_entries: &mut Vec<SSTableIndexEntry>,
+    written: &mut SstBuildStats,
 ) -> Result<(), SSTableError> {
     let block = SSTableDataBlock {
         data: mem::take(current_block),
     };
     let block_bytes = encoding::encode_to_vec(&block)?;
     let (offset, data_len) = write_checksummed_block(writer, &block_bytes)?;
+    let size = (SST_DATA_BLOCK_LEN_SIZE + data_len + SST_DATA_BLOCK_CHECKSUM_SIZE) as u64;
+    written.data_blocks += 1;
+    written.raw_bytes += block.data.len() as u64;
+    written.written_bytes += size;
 
+    let bounds = mem::replace(block_stats, BuildStats::new());
     index_entries.push(SSTableIndexEntry {
-        separator_key: block_first_key.take().ok_or_else(|| {
+        separator_key: bounds.min_key.ok_or_else(|| {
             SSTableError::Internal("flush_data_block: no first key recorded for block".into())
         })?,
-        handle: BlockHandle {
-            offset,
-            size: (SST_DATA_BLOCK_LEN_SIZE + data_len + SST_DATA_BLOCK_CHECKSUM_SIZE) as u64,
-        },
+        handle: BlockHandle { offset, size },
+        min_lsn: bounds.min_lsn,
+        max_lsn: bounds.max_lsn,
+        min_timestamp: bounds.min_timestamp,
+        max_timestamp: bounds.max_timestamp,
     });
 
     Ok(())
@@ -200,64 +357,97 @@ fn flush_data_block(
 // Phase helpers — one per logical section of the SSTable
 // ------------------------------------------------------------------------------------------------
 
-/// Iterates point entries, encodes them into data blocks, populates the
-/// bloom filter, and tracks statistics.
+/// Iterates point entries, encodes them into data blocks, and tracks
+/// statistics.
 ///
-/// Returns the accumulated stats and the block-index entries.
-fn write_data_blocks(
+/// Returns the accumulated stats, the block-index entries, and the
+/// distinct keys seen, and adds the blocks written to `written`. Entries
+/// are grouped by key, so a key is distinct whenever it differs from the
+/// previous entry's.
+#[allow(clippy::type_complexity)]
+fn write_data_blocks<P: AsPointEntry>(
     writer: &mut (impl Write + Seek),
-    entries: impl Iterator<Item = PointEntry>,
-    bloom: &mut Bloom<Vec<u8>>,
-) -> Result<(BuildStats, Vec<SSTableIndexEntry>), SSTableError> {
+    entries: impl Iterator<Item = P>,
+    value_checksums: bool,
+    tenant_prefixes: &[Vec<u8>],
+    written: &mut SstBuildStats,
+) -> Result<(BuildStats, Vec<SSTableIndexEntry>, Vec<Vec<u8>>), SSTableError> {
     let mut stats = BuildStats::new();
+    stats.prefix_usage = tenant_prefixes
+        .iter()
+        .map(|prefix| SSTablePrefixUsage {
+            prefix: prefix.clone(),
+            bytes: 0,
+            entries: 0,
+        })
+        .collect();
     let mut index_entries = Vec::new();
+    let mut distinct_keys: Vec<Vec<u8>> = Vec::new();
     let mut current_block = Vec::<u8>::new();
-    let mut block_first_key: Option<Vec<u8>> = None;
+    // Bounds of the block being filled; its `min_key` is the block's
+    // first key.
+    let mut block_stats = BuildStats::new();
 
     for entry in entries {
+        let key = entry.key();
+        let value = entry.value();
+        let new_key = distinct_keys.last().map(Vec::as_slice) != Some(key);
+
+        // Close the block once it reaches its target size — but never
+        // between two versions of one key, so a lookup finds them all in
+        // the one block the index points it to.
+        if current_block.len() >= SST_DATA_BLOCK_MAX_SIZE && new_key {
+            flush_data_block(
+                writer,
+                &mut current_block,
+                &mut block_stats,
+                &mut index_entries,
+                written,
+            )?;
+        }
+
         stats.record_count += 1;
-        if entry.value.is_none() {
+        if value.is_none() {
             stats.tombstone_count += 1;
         }
-        stats.track(entry.lsn, entry.timestamp);
+        stats.track(entry.lsn(), entry.timestamp());
+        block_stats.track(entry.lsn(), entry.timestamp());
 
-        // Track min/max key (entries are sorted, so first = min, last = max).
-        if stats.min_key.is_none() {
-            stats.min_key = Some(entry.key.clone());
+        if block_stats.min_key.is_none() {
+            block_stats.min_key = Some(key.to_vec());
         }
-        stats.max_key = Some(entry.key.clone());
 
-        if block_first_key.is_none() {
-            block_first_key = Some(entry.key.clone());
+        // Versions of a key arrive newest first, so only the first one
+        // counts towards its prefix.
+        if new_key
+            && let Some(value) = value
+            && let Some(i) = tenant::match_prefix(tenant_prefixes, key)
+        {
+            let usage = &mut stats.prefix_usage[i];
+            usage.bytes += (key.len() + value.len()) as u64;
+            usage.entries += 1;
         }
-        bloom.set(&entry.key);
 
-        // Encode point cell.
+        // Encode the point cell straight into the block.
         let cell = SSTableCell {
-            key_len: u32::try_from(entry.key.len()).map_err(|_| {
-                SSTableError::Internal(format!("key too large: {} bytes", entry.key.len()))
+            key_len: u32::try_from(key.len()).map_err(|_| {
+                SSTableError::Internal(format!("key too large: {} bytes", key.len()))
             })?,
-            value_len: u32::try_from(entry.value.as_ref().map_or(0, |v| v.len()))
+            value_len: u32::try_from(value.map_or(0, <[u8]>::len))
                 .map_err(|_| SSTableError::Internal("value too large".into()))?,
-            timestamp: entry.timestamp,
-            is_delete: entry.value.is_none(),
-            lsn: entry.lsn,
+            timestamp: entry.timestamp(),
+            is_delete: value.is_none(),
+            lsn: entry.lsn(),
+            value_crc: value.filter(|_| value_checksums).map(super::crc32),
         };
-        let mut cell_bytes = encoding::encode_to_vec(&cell)?;
-        cell_bytes.extend_from_slice(&entry.key);
-        if let Some(value) = entry.value {
-            cell_bytes.extend_from_slice(&value);
+        cell.encode_to(&mut current_block)?;
+        current_block.extend_from_slice(key);
+        if let Some(value) = value {
+            current_block.extend_from_slice(value);
         }
-        current_block.extend_from_slice(&cell_bytes);
 
-        // Flush block when it reaches target size.
-        if current_block.len() >= SST_DATA_BLOCK_MAX_SIZE {
-            flush_data_block(
-                writer,
-                &mut current_block,
-                &mut block_first_key,
-                &mut index_entries,
-            )?;
+        if new_key {
+            distinct_keys.push(entry.into_key());
         }
     }
 
@@ -266,12 +456,40 @@ fn write_data_blocks(
         flush_data_block(
             writer,
             &mut current_block,
-            &mut block_first_key,
+            &mut block_stats,
             &mut index_entries,
+            written,
         )?;
     }
 
-    Ok((stats, index_entries))
+    // Entries are sorted, so the first and last distinct keys bound the
+    // table.
+    stats.min_key = distinct_keys.first().cloned();
+    stats.max_key = distinct_keys.last().cloned();
+
+    Ok((stats, index_entries, distinct_keys))
+}
+
+/// Builds a bloom filter sized for exactly `keys`.
+///
+/// Sizing from the distinct key count rather than the entry count keeps
+/// the filter from being oversized when a key has many versions.
+fn build_bloom(keys: &[Vec<u8>]) -> Result<Bloom<Vec<u8>>, SSTableError> {
+    let mut bloom = Bloom::new_for_fp_rate(keys.len().max(1), SST_BLOOM_FILTER_FALSE_POSITIVE_RATE)
+        .map_err(|e| SSTableError::Internal(e.to_string()))?;
+    for key in keys {
+        bloom.set(key);
+    }
+    Ok(bloom)
+}
+
+/// Builds the distinct-key sketch of `keys`.
+fn build_key_sketch(keys: &[Vec<u8>]) -> KeySketch {
+    let mut sketch = KeySketch::new();
+    for key in keys {
+        sketch.insert(key);
+    }
+    sketch
 }
 
 /// Iterates range tombstones, updates stats, and writes the range-delete
@@ -299,13 +517,14 @@ fn write_range_tombstones(
     write_checksummed_block(writer, &bytes)
 }
 
-/// Builds and writes the metaindex block pointing to bloom, properties,
-/// and range-delete blocks.
+/// Builds and writes the metaindex block pointing to bloom, key sketch,
+/// properties, and range-delete blocks.
 ///
 /// Returns `(block_offset, data_byte_len)`.
 fn write_metaindex(
     writer: &mut (impl Write + Seek),
     bloom: BlockHandle,
+    key_sketch: BlockHandle,
     properties: BlockHandle,
     range_deletes: BlockHandle,
 ) -> Result<(u64, usize), SSTableError> {
@@ -314,6 +533,10 @@ fn write_metaindex(
             name: "filter.bloom".to_string(),
             handle: bloom,
         },
+        MetaIndexEntry {
+            name: "meta.key_sketch".to_string(),
+            handle: key_sketch,
+        },
         MetaIndexEntry {
             name: "meta.properties".to_string(),
             handle: properties,
@@ -334,14 +557,18 @@ fn write_footer(
     file: &mut File,
     metaindex: BlockHandle,
     index: BlockHandle,
+    extensions: [BlockHandle; SST_FOOTER_EXTENSION_SLOTS],
 ) -> Result<(), SSTableError> {
     let current_pos = file.metadata()?.len();
 
     let footer = SSTableFooter {
         metaindex,
         index,
+        extensions,
         total_file_size: current_pos + SST_FOOTER_SIZE as u64,
+        format_version: SST_HDR_VERSION,
         footer_crc32: 0,
+        magic: SST_FOOTER_MAGIC,
     };
 
     let footer_bytes = encoding::encode_to_vec(&footer)?;
@@ -357,7 +584,7 @@ fn write_footer(
     writer.write_all(&footer_bytes)?;
     writer.flush()?;
     drop(writer);
-    file.sync_all()?;
+    durability::sync_file(file)?;
 
     Ok(())
 }
@@ -375,35 +602,79 @@ fn write_footer(
 /// ```
 pub struct SstWriter<P: AsRef<Path>> {
     path: P,
+    value_checksums: bool,
+    file_digest: Option<SstFileDigest>,
+    tenant_prefixes: Vec<Vec<u8>>,
+    clock: Arc<dyn Clock>,
 }
 
 impl<P: AsRef<Path>> SstWriter<P> {
     /// Create a writer targeting the given output path.
     pub fn new(path: P) -> Self {
-        Self { path }
+        Self {
+            path,
+            value_checksums: false,
+            file_digest: None,
+            tenant_prefixes: Vec::new(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Store a CRC-32 of every value in its cell, verified by point
+    /// lookups. Off by default.
+    pub fn value_checksums(mut self, enabled: bool) -> Self {
+        self.value_checksums = enabled;
+        self
+    }
+
+    /// Store a whole-file digest computed with `algorithm`, or none.
+    /// `None` by default.
+    pub fn file_digest(mut self, algorithm: Option<SstFileDigest>) -> Self {
+        self.file_digest = algorithm;
+        self
+    }
+
+    /// Tally the bytes and entries of the newest puts under each of
+    /// `prefixes` — sorted and free of duplicates — in the properties
+    /// block. None by default.
+    pub fn tenant_prefixes(mut self, prefixes: &[Vec<u8>]) -> Self {
+        self.tenant_prefixes = prefixes.to_vec();
+        self
+    }
+
+    /// Read the creation timestamp stored in the properties block from
+    /// `clock`. [`SystemClock`] by default.
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
     }
 
     /// Consume sorted iterators and write a complete SSTable.
     ///
     /// # Parameters
     ///
-    /// - `point_entries` — sorted iterator of [`PointEntry`] values.
-    /// - `point_count` — expected number of point entries (sizes bloom filter).
+    /// - `point_entries` — sorted iterator of point entries, owned
+    ///   ([`PointEntry`](crate::engine::PointEntry)) or borrowed
+    ///   ([`PointEntryRef`](crate::engine::PointEntryRef)).
+    /// - `point_count` — expected number of point entries. The bloom filter
+    ///   is sized from the distinct keys actually written, not from this.
     /// - `range_tombstones` — sorted iterator of [`RangeTombstone`] values.
     /// - `range_count` — expected number of range tombstones.
     ///
+    /// Returns what was written.
+    ///
     /// # Errors
     ///
     /// - [`SSTableError::Internal`] if both iterators are empty.
     /// - I/O errors from writing or seeking.
     /// - Encoding errors.
-    pub fn build(
+    pub fn build<E: AsPointEntry>(
         self,
-        point_entries: impl Iterator<Item = PointEntry>,
+        point_entries: impl Iterator<Item = E>,
         point_count: usize,
         range_tombstones: impl Iterator<Item = RangeTombstone>,
         range_count: usize,
-    ) -> Result<(), SSTableError> {
+    ) -> Result<SstBuildStats, SSTableError> {
         let mut point_entries = point_entries.peekable();
         let mut range_tombstones = range_tombstones.peekable();
 
@@ -426,32 +697,43 @@ impl<P: AsRef<Path>> SstWriter<P> {
             .write(true)
             .truncate(true)
             .open(&tmp_path)?;
-        let mut writer = BufWriter::new(&mut file);
+        let mut writer = BufWriter::new(DigestWriter::new(&mut file, self.file_digest));
 
         // 1. Header
         write_header(&mut writer)?;
 
-        // 2. Data blocks (point entries → blocks + bloom filter + stats)
-        let mut bloom = Bloom::new_for_fp_rate(
-            point_count + range_count,
-            SST_BLOOM_FILTER_FALSE_POSITIVE_RATE,
-        )
-        .map_err(|e| SSTableError::Internal(e.to_string()))?;
-
-        let (mut stats, index_entries) = write_data_blocks(&mut writer, point_entries, &mut bloom)?;
+        // 2. Data blocks (point entries → blocks + stats + distinct keys)
+        let mut written = SstBuildStats {
+            sstables: 1,
+            ..SstBuildStats::default()
+        };
+        let (mut stats, index_entries, distinct_keys) = write_data_blocks(
+            &mut writer,
+            point_entries,
+            self.value_checksums,
+            &self.tenant_prefixes,
+            &mut written,
+        )?;
 
-        // 3. Bloom filter block
+        // 3. Bloom filter block, sized from the distinct point keys, and
+        //    the sketch of the same keys
+        let bloom = build_bloom(&distinct_keys)?;
+        let key_sketch = build_key_sketch(&distinct_keys);
+        let key_count = distinct_keys.len();
+        drop(distinct_keys);
         let bloom_block = SSTableBloomBlock {
             data: bloom.as_slice().to_vec(),
         };
         let bloom_bytes = encoding::encode_to_vec(&bloom_block)?;
         let (bloom_off, bloom_len) = write_checksummed_block(&mut writer, &bloom_bytes)?;
+        let sketch_bytes = encoding::encode_to_vec(&key_sketch)?;
+        let (sketch_off, sketch_len) = write_checksummed_block(&mut writer, &sketch_bytes)?;
 
         // 4. Range tombstones block
         let (rt_off, rt_len) = write_range_tombstones(&mut writer, range_tombstones, &mut stats)?;
 
         // 5. Properties block
-        let properties = stats.into_properties(range_count);
+        let properties = stats.into_properties(range_count, key_count, self.clock.now_nanos());
         let props_bytes = encoding::encode_to_vec(&properties)?;
         let (props_off, props_len) = write_checksummed_block(&mut writer, &props_bytes)?;
 
@@ -462,6 +744,10 @@ impl<P: AsRef<Path>> SstWriter<P> {
                 offset: bloom_off,
                 size: bloom_len as u64,
             },
+            BlockHandle {
+                offset: sketch_off,
+                size: sketch_len as u64,
+            },
             BlockHandle {
                 offset: props_off,
                 size: props_len as u64,
@@ -477,12 +763,24 @@ impl<P: AsRef<Path>> SstWriter<P> {
         encoding::encode_vec(&index_entries, &mut index_bytes)?;
         let (idx_off, idx_len) = write_checksummed_block(&mut writer, &index_bytes)?;
 
-        // 8. Flush buffered data before footer (footer reads file length).
+        // 8. Whole-file digest block, over every byte written so far
+        let mut extensions: [BlockHandle; SST_FOOTER_EXTENSION_SLOTS] = Default::default();
+        writer.flush()?;
+        if let Some(digest) = writer.get_mut().finish() {
+            let digest_bytes = encoding::encode_to_vec(&digest)?;
+            let (digest_off, digest_len) = write_checksummed_block(&mut writer, &digest_bytes)?;
+            extensions[SST_FOOTER_DIGEST_SLOT] = BlockHandle {
+                offset: digest_off,
+                size: digest_len as u64,
+            };
+        }
+
+        // 9. Flush buffered data before footer (footer reads file length).
         writer.flush()?;
         drop(writer);
-        file.sync_all()?;
+        durability::sync_file(&file)?;
 
-        // 9. Footer + final sync
+        // 10. Footer + final sync
         write_footer(
             &mut file,
             BlockHandle {
@@ -493,9 +791,11 @@ impl<P: AsRef<Path>> SstWriter<P> {
                 offset: idx_off,
                 size: idx_len as u64,
             },
+            extensions,
         )?;
 
-        rename(&tmp_path, final_path)?;
-        Ok(())
+        written.file_bytes = file.metadata()?.len();
+        durability::rename(&tmp_path, final_path)?;
+        Ok(written)
     }
 }
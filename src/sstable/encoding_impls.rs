@@ -6,9 +6,10 @@
 use crate::encoding::{self, EncodingError};
 
 use super::{
-    BlockHandle, MetaIndexEntry, SSTableBloomBlock, SSTableCell, SSTableDataBlock, SSTableFooter,
-    SSTableHeader, SSTableIndexEntry, SSTablePropertiesBlock, SSTableRangeTombstoneCell,
-    SSTableRangeTombstoneDataBlock,
+    BlockHandle, MetaIndexEntry, SST_FOOTER_EXTENSION_SLOTS, SSTableBloomBlock, SSTableCell,
+    SSTableDataBlock, SSTableFileDigestBlock, SSTableFooter, SSTableFooterV2, SSTableHeader,
+    SSTableIndexEntry, SSTableIndexEntryV1, SSTablePrefixUsage, SSTablePropertiesBlock,
+    SSTableRangeTombstoneCell, SSTableRangeTombstoneDataBlock, SstFileDigest,
 };
 
 // ------------------------------------------------------------------------------------------------
@@ -107,13 +108,31 @@ impl encoding::Decode for SSTableBloomBlock {
 // SSTableCell
 // ------------------------------------------------------------------------------------------------
 
+/// Cell flag: the cell is a point tombstone.
+const CELL_FLAG_DELETE: u8 = 0x01;
+
+/// Cell flag: a `u32` value checksum follows the LSN.
+const CELL_FLAG_VALUE_CRC: u8 = 0x02;
+
+// The flags byte was a plain `bool` before value checksums, so cells
+// written without one decode unchanged.
 impl encoding::Encode for SSTableCell {
     fn encode_to(&self, buf: &mut Vec<u8>) -> Result<(), EncodingError> {
+        let mut flags = 0;
+        if self.is_delete {
+            flags |= CELL_FLAG_DELETE;
+        }
+        if self.value_crc.is_some() {
+            flags |= CELL_FLAG_VALUE_CRC;
+        }
         encoding::Encode::encode_to(&self.key_len, buf)?;
         encoding::Encode::encode_to(&self.value_len, buf)?;
         encoding::Encode::encode_to(&self.timestamp, buf)?;
-        encoding::Encode::encode_to(&self.is_delete, buf)?;
+        encoding::Encode::encode_to(&flags, buf)?;
         encoding::Encode::encode_to(&self.lsn, buf)?;
+        if let Some(value_crc) = self.value_crc {
+            encoding::Encode::encode_to(&value_crc, buf)?;
+        }
         Ok(())
     }
 }
@@ -127,17 +146,31 @@ impl encoding::Decode for SSTableCell {
         off += n;
         let (timestamp, n) = u64::decode_from(&buf[off..])?;
         off += n;
-        let (is_delete, n) = bool::decode_from(&buf[off..])?;
+        let (flags, n) = u8::decode_from(&buf[off..])?;
         off += n;
+        if flags & !(CELL_FLAG_DELETE | CELL_FLAG_VALUE_CRC) != 0 {
+            return Err(EncodingError::InvalidTag {
+                tag: flags as u32,
+                type_name: "SSTableCell flags",
+            });
+        }
         let (lsn, n) = u64::decode_from(&buf[off..])?;
         off += n;
+        let value_crc = if flags & CELL_FLAG_VALUE_CRC != 0 {
+            let (value_crc, n) = u32::decode_from(&buf[off..])?;
+            off += n;
+            Some(value_crc)
+        } else {
+            None
+        };
         Ok((
             Self {
                 key_len,
                 value_len,
                 timestamp,
-                is_delete,
+                is_delete: flags & CELL_FLAG_DELETE != 0,
                 lsn,
+                value_crc,
             },
             off,
         ))
@@ -215,6 +248,12 @@ impl encoding::Encode for SSTablePropertiesBlock {
         encoding::Encode::encode_to(&self.max_timestamp, buf)?;
         encoding::Encode::encode_to(&self.min_key, buf)?;
         encoding::Encode::encode_to(&self.max_key, buf)?;
+        if let Some(key_count) = self.key_count {
+            encoding::Encode::encode_to(&key_count, buf)?;
+            if let Some(prefix_usage) = &self.prefix_usage {
+                encoding::encode_vec(prefix_usage, buf)?;
+            }
+        }
         Ok(())
     }
 }
@@ -242,6 +281,22 @@ impl encoding::Decode for SSTablePropertiesBlock {
         off += n;
         let (max_key, n) = <Vec<u8>>::decode_from(&buf[off..])?;
         off += n;
+        // Tables written before the key count end here.
+        let key_count = if off < buf.len() {
+            let (key_count, n) = u64::decode_from(&buf[off..])?;
+            off += n;
+            Some(key_count)
+        } else {
+            None
+        };
+        // Tables written without tenant prefixes end here.
+        let prefix_usage = if off < buf.len() {
+            let (prefix_usage, n) = encoding::decode_vec::<SSTablePrefixUsage>(&buf[off..])?;
+            off += n;
+            Some(prefix_usage)
+        } else {
+            None
+        };
         Ok((
             Self {
                 creation_timestamp,
@@ -254,6 +309,41 @@ impl encoding::Decode for SSTablePropertiesBlock {
                 max_timestamp,
                 min_key,
                 max_key,
+                key_count,
+                prefix_usage,
+            },
+            off,
+        ))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// SSTablePrefixUsage
+// ------------------------------------------------------------------------------------------------
+
+impl encoding::Encode for SSTablePrefixUsage {
+    fn encode_to(&self, buf: &mut Vec<u8>) -> Result<(), EncodingError> {
+        encoding::Encode::encode_to(&self.prefix, buf)?;
+        encoding::Encode::encode_to(&self.bytes, buf)?;
+        encoding::Encode::encode_to(&self.entries, buf)?;
+        Ok(())
+    }
+}
+
+impl encoding::Decode for SSTablePrefixUsage {
+    fn decode_from(buf: &[u8]) -> Result<(Self, usize), EncodingError> {
+        let mut off = 0;
+        let (prefix, n) = <Vec<u8>>::decode_from(&buf[off..])?;
+        off += n;
+        let (bytes, n) = u64::decode_from(&buf[off..])?;
+        off += n;
+        let (entries, n) = u64::decode_from(&buf[off..])?;
+        off += n;
+        Ok((
+            Self {
+                prefix,
+                bytes,
+                entries,
             },
             off,
         ))
@@ -268,11 +358,56 @@ impl encoding::Encode for SSTableIndexEntry {
     fn encode_to(&self, buf: &mut Vec<u8>) -> Result<(), EncodingError> {
         encoding::Encode::encode_to(&self.separator_key, buf)?;
         encoding::Encode::encode_to(&self.handle, buf)?;
+        encoding::Encode::encode_to(&self.min_lsn, buf)?;
+        encoding::Encode::encode_to(&self.max_lsn, buf)?;
+        encoding::Encode::encode_to(&self.min_timestamp, buf)?;
+        encoding::Encode::encode_to(&self.max_timestamp, buf)?;
         Ok(())
     }
 }
 
 impl encoding::Decode for SSTableIndexEntry {
+    fn decode_from(buf: &[u8]) -> Result<(Self, usize), EncodingError> {
+        let mut off = 0;
+        let (separator_key, n) = <Vec<u8>>::decode_from(&buf[off..])?;
+        off += n;
+        let (handle, n) = BlockHandle::decode_from(&buf[off..])?;
+        off += n;
+        let (min_lsn, n) = u64::decode_from(&buf[off..])?;
+        off += n;
+        let (max_lsn, n) = u64::decode_from(&buf[off..])?;
+        off += n;
+        let (min_timestamp, n) = u64::decode_from(&buf[off..])?;
+        off += n;
+        let (max_timestamp, n) = u64::decode_from(&buf[off..])?;
+        off += n;
+        Ok((
+            Self {
+                separator_key,
+                handle,
+                min_lsn,
+                max_lsn,
+                min_timestamp,
+                max_timestamp,
+            },
+            off,
+        ))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// SSTableIndexEntryV1
+// ------------------------------------------------------------------------------------------------
+
+impl encoding::Encode for SSTableIndexEntryV1 {
+    fn encode_to(&self, buf: &mut Vec<u8>) -> Result<(), EncodingError> {
+        encoding::Encode::encode_to(&self.separator_key, buf)?;
+        encoding::Encode::encode_to(&self.handle, buf)?;
+        Ok(())
+    }
+}
+
+impl encoding::Decode for SSTableIndexEntryV1 {
     fn decode_from(buf: &[u8]) -> Result<(Self, usize), EncodingError> {
         let mut off = 0;
         let (separator_key, n) = <Vec<u8>>::decode_from(&buf[off..])?;
@@ -312,6 +447,33 @@ impl encoding::Decode for MetaIndexEntry {
     }
 }
 
+// ------------------------------------------------------------------------------------------------
+// SSTableFileDigestBlock
+// ------------------------------------------------------------------------------------------------
+
+impl encoding::Encode for SSTableFileDigestBlock {
+    fn encode_to(&self, buf: &mut Vec<u8>) -> Result<(), EncodingError> {
+        encoding::Encode::encode_to(&self.algorithm.tag(), buf)?;
+        encoding::Encode::encode_to(&self.digest, buf)?;
+        Ok(())
+    }
+}
+
+impl encoding::Decode for SSTableFileDigestBlock {
+    fn decode_from(buf: &[u8]) -> Result<(Self, usize), EncodingError> {
+        let mut off = 0;
+        let (tag, n) = u8::decode_from(&buf[off..])?;
+        off += n;
+        let algorithm = SstFileDigest::from_tag(tag).ok_or(EncodingError::InvalidTag {
+            tag: tag as u32,
+            type_name: "SstFileDigest",
+        })?;
+        let (digest, n) = Vec::<u8>::decode_from(&buf[off..])?;
+        off += n;
+        Ok((Self { algorithm, digest }, off))
+    }
+}
+
 // ------------------------------------------------------------------------------------------------
 // SSTableFooter
 // ------------------------------------------------------------------------------------------------
@@ -320,13 +482,68 @@ impl encoding::Encode for SSTableFooter {
     fn encode_to(&self, buf: &mut Vec<u8>) -> Result<(), EncodingError> {
         encoding::Encode::encode_to(&self.metaindex, buf)?;
         encoding::Encode::encode_to(&self.index, buf)?;
+        for extension in &self.extensions {
+            encoding::Encode::encode_to(extension, buf)?;
+        }
         encoding::Encode::encode_to(&self.total_file_size, buf)?;
+        encoding::Encode::encode_to(&self.format_version, buf)?;
         encoding::Encode::encode_to(&self.footer_crc32, buf)?;
+        encoding::Encode::encode_to(&self.magic, buf)?;
         Ok(())
     }
 }
 
 impl encoding::Decode for SSTableFooter {
+    fn decode_from(buf: &[u8]) -> Result<(Self, usize), EncodingError> {
+        let mut off = 0;
+        let (metaindex, n) = BlockHandle::decode_from(&buf[off..])?;
+        off += n;
+        let (index, n) = BlockHandle::decode_from(&buf[off..])?;
+        off += n;
+        let mut extensions: [BlockHandle; SST_FOOTER_EXTENSION_SLOTS] = Default::default();
+        for extension in &mut extensions {
+            let (handle, n) = BlockHandle::decode_from(&buf[off..])?;
+            off += n;
+            *extension = handle;
+        }
+        let (total_file_size, n) = u64::decode_from(&buf[off..])?;
+        off += n;
+        let (format_version, n) = u32::decode_from(&buf[off..])?;
+        off += n;
+        let (footer_crc32, n) = u32::decode_from(&buf[off..])?;
+        off += n;
+        let (magic, n) = <[u8; 8]>::decode_from(&buf[off..])?;
+        off += n;
+        Ok((
+            Self {
+                metaindex,
+                index,
+                extensions,
+                total_file_size,
+                format_version,
+                footer_crc32,
+                magic,
+            },
+            off,
+        ))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// SSTableFooterV2
+// ------------------------------------------------------------------------------------------------
+
+impl encoding::Encode for SSTableFooterV2 {
+    fn encode_to(&self, buf: &mut Vec<u8>) -> Result<(), EncodingError> {
+        encoding::Encode::encode_to(&self.metaindex, buf)?;
+        encoding::Encode::encode_to(&self.index, buf)?;
+        encoding::Encode::encode_to(&self.total_file_size, buf)?;
+        encoding::Encode::encode_to(&self.footer_crc32, buf)?;
+        Ok(())
+    }
+}
+
+impl encoding::Decode for SSTableFooterV2 {
     fn decode_from(buf: &[u8]) -> Result<(Self, usize), EncodingError> {
         let mut off = 0;
         let (metaindex, n) = BlockHandle::decode_from(&buf[off..])?;
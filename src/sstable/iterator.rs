@@ -1,11 +1,12 @@
 //! SSTable iterators — block-level and multi-block scan.
 //!
-//! This module provides two iterator types:
+//! This module provides three iterator types:
 //!
 //! - [`BlockIterator`] — decodes a single data block and yields [`BlockEntry`]
-//!   values. It supports `seek_to_first()` and `seek_to(key)` for positioning.
+//!   values. It starts at the first entry; `seek_to(key)` and `skip_to(key)` position it.
 //! - [`ScanIterator`] — walks multiple data blocks plus range tombstones,
 //!   yielding a merged stream of [`Record`] items in `(key ASC, LSN DESC)` order.
+//! - [`LazyScanIterator`] — a [`ScanIterator`] created on first use.
 //!
 //! # Block Iterator
 //!
@@ -38,14 +39,30 @@
 //! iterator advances to the next one automatically. Range tombstones are
 //! interleaved with point entries in key order.
 //!
+//! As a [`RecordSource`], the scan iterator can also seek its point
+//! entries forward, jumping straight to the block holding the target key;
+//! the engine merge uses this to skip entries hidden by a newer range
+//! tombstone.
+//!
+//! Creating a scan iterator reads the data block holding the start key.
+//! [`LazyScanIterator`] defers that to its first record and reports the
+//! smallest key it can yield, so the engine merge leaves an SSTable
+//! unread until the scan reaches the table's first key — a scan that
+//! stops early never touches the tables past that point. It also tracks
+//! the longest run of point tombstones it yields, and reports it to the
+//! SSTable when dropped, so heavily deleted tables can be compacted.
+//!
 //! The scan iterator does **not** perform visibility resolution — that is the
 //! responsibility of upper layers (engine merge iterator, visibility filter).
 
+use std::cmp::Ordering;
 use std::ops::Deref;
+use std::sync::Arc;
 
 use crate::encoding;
 
 use crate::engine::Record;
+use crate::engine::utils::{RecordSource, below_end, cmp_ends};
 
 use super::{SSTable, SSTableCell, SSTableDataBlock, SSTableError};
 
@@ -104,17 +121,18 @@ impl BlockIterator {
         Self { data, cursor: 0 }
     }
 
-    /// Reset the iterator to the first entry in the block.
-    pub fn seek_to_first(&mut self) {
-        self.cursor = 0;
-    }
-
     /// Seek to the first entry whose key is **≥ `search_key`**.
     ///
     /// This performs a **linear scan**. If corruption or truncation is detected,
     /// the iterator stops at the end of the block.
     pub fn seek_to(&mut self, search_key: &[u8]) {
         self.cursor = 0;
+        self.skip_to(search_key);
+    }
+
+    /// Advance from the current position to the first entry whose key is
+    /// **≥ `search_key`**. Never moves backwards.
+    pub fn skip_to(&mut self, search_key: &[u8]) {
         while self.cursor < self.data.len() {
             match encoding::decode_from_slice::<SSTableCell>(&self.data[self.cursor..]) {
                 Ok((cell, cell_len)) => {
@@ -147,6 +165,15 @@ impl BlockIterator {
         }
     }
 
+    /// Returns the key of the next entry without advancing the cursor, or
+    /// `None` at the end of the block or on a damaged entry.
+    pub fn peek_key(&self) -> Option<&[u8]> {
+        let (cell, cell_len) =
+            encoding::decode_from_slice::<SSTableCell>(self.data.get(self.cursor..)?).ok()?;
+        let key_start = self.cursor + cell_len;
+        self.data.get(key_start..key_start + cell.key_len as usize)
+    }
+
     /// Decode and return the next entry, advancing the cursor.
     ///
     /// Returns `None` if:
@@ -252,61 +279,161 @@ pub struct ScanIterator<S: Deref<Target = SSTable> = &'static SSTable> {
 
     /// Next point entry (Put/Delete) to yield.
     next_point: Option<Record>,
+
+    /// Whether data block checksums are verified as blocks are loaded.
+    verify_checksums: bool,
+
+    /// Data blocks whose entries are all older than this timestamp are
+    /// skipped without being read. `0` reads every block.
+    min_timestamp: u64,
 }
 
 impl<S: Deref<Target = SSTable>> ScanIterator<S> {
     /// Create a new SSTable scan iterator for the half-open range
     /// `start_key <= key < end_key`.
     pub fn new(sstable: S, start_key: Vec<u8>, end_key: Vec<u8>) -> Result<Self, SSTableError> {
+        Self::with_checksums(sstable, start_key, end_key, true)
+    }
+
+    /// Same as [`new`](Self::new), but lets the caller skip CRC32
+    /// verification of every data block loaded by the iterator.
+    pub fn with_checksums(
+        sstable: S,
+        start_key: Vec<u8>,
+        end_key: Vec<u8>,
+        verify_checksums: bool,
+    ) -> Result<Self, SSTableError> {
+        Self::since(sstable, start_key, end_key, verify_checksums, 0)
+    }
+
+    /// Same as [`with_checksums`](Self::with_checksums), but skips every
+    /// data block whose index entry shows no entry written at or after
+    /// `min_timestamp`.
+    ///
+    /// Range tombstones are yielded regardless of their timestamp.
+    pub fn since(
+        sstable: S,
+        start_key: Vec<u8>,
+        end_key: Vec<u8>,
+        verify_checksums: bool,
+        min_timestamp: u64,
+    ) -> Result<Self, SSTableError> {
         if start_key >= end_key {
             return Err(SSTableError::Internal("scan start >= end".to_string()));
         }
 
-        let current_block_index = sstable.find_block_for_key(start_key.as_slice());
-
-        let block_iter = if current_block_index < sstable.index.len() {
-            let entry = &sstable.index[current_block_index];
-            let block_bytes = SSTable::read_block_bytes(&sstable.mmap, &entry.handle)?;
-            let (block, _) = encoding::decode_from_slice::<SSTableDataBlock>(&block_bytes)?;
-            let mut it = BlockIterator::new(block.data);
-            it.seek_to(start_key.as_slice());
-            Some(it)
-        } else {
-            None
-        };
-
-        Ok(Self {
+        let mut it = Self {
             sstable,
-            current_block_index,
-            current_block_iter: block_iter,
+            current_block_index: 0,
+            current_block_iter: None,
             start_key,
             end_key,
             pending_range_idx: 0,
             next_range: None,
             next_point: None,
-        })
+            verify_checksums,
+            min_timestamp,
+        };
+
+        let first = it.sstable.find_block_for_key(it.start_key.as_slice());
+        it.current_block_index = it.next_wanted_block(first);
+        if it.current_block_index < it.sstable.index.len() {
+            let mut block_iter = it.block_iter_at(it.current_block_index)?;
+            block_iter.seek_to(it.start_key.as_slice());
+            it.current_block_iter = Some(block_iter);
+        }
+
+        Ok(it)
+    }
+
+    /// Returns the first block at or after `index` that may hold entries
+    /// written at or after `min_timestamp`, or the block count if none
+    /// does. Blocks past the end of the scan range are never skipped to.
+    fn next_wanted_block(&self, mut index: usize) -> usize {
+        let blocks = &self.sstable.index;
+        while index < blocks.len() && blocks[index].max_timestamp < self.min_timestamp {
+            // The next block starts at or past the end key: stop here.
+            if blocks
+                .get(index + 1)
+                .is_some_and(|next| next.separator_key.as_slice() >= self.end_key.as_slice())
+            {
+                return blocks.len();
+            }
+            index += 1;
+        }
+        index
+    }
+
+    /// Moves the point cursor forward to the first entry with key ≥ `key`,
+    /// or past every entry if `key` is empty (an unbounded range end).
+    ///
+    /// Loads the block holding `key` directly when it lies past the
+    /// current one, so the blocks in between are never read.
+    fn seek_points_to(&mut self, key: &[u8]) {
+        if self
+            .next_point
+            .as_ref()
+            .is_some_and(|p| !below_end(p.key(), key))
+        {
+            return;
+        }
+        self.next_point = None;
+        if self.current_block_iter.is_none() {
+            return;
+        }
+        if cmp_ends(key, &self.end_key) != Ordering::Less {
+            self.current_block_iter = None;
+            return;
+        }
+
+        let target = self.next_wanted_block(self.sstable.find_block_for_key(key));
+        if target >= self.sstable.index.len() {
+            self.current_block_iter = None;
+            return;
+        }
+        if target > self.current_block_index {
+            match self.block_iter_at(target) {
+                Ok(it) => {
+                    self.current_block_index = target;
+                    self.current_block_iter = Some(it);
+                }
+                Err(e) => {
+                    tracing::warn!(?e, "error loading block during scan seek");
+                    self.current_block_iter = None;
+                    return;
+                }
+            }
+        }
+        if let Some(it) = self.current_block_iter.as_mut() {
+            it.skip_to(key);
+        }
     }
 
     /// Load the next data block and create a fresh `BlockIterator`.
     fn load_next_block(&mut self) -> Result<bool, SSTableError> {
-        self.current_block_index += 1;
+        self.current_block_index = self.next_wanted_block(self.current_block_index + 1);
 
         if self.current_block_index >= self.sstable.index.len() {
             self.current_block_iter = None;
             return Ok(false);
         }
 
-        let entry = &self.sstable.index[self.current_block_index];
-        let block_bytes = SSTable::read_block_bytes(&self.sstable.mmap, &entry.handle)?;
-
-        let (block, _) = encoding::decode_from_slice::<SSTableDataBlock>(&block_bytes)?;
-        let mut it = BlockIterator::new(block.data);
-        it.seek_to_first();
-        self.current_block_iter = Some(it);
+        self.current_block_iter = Some(self.block_iter_at(self.current_block_index)?);
 
         Ok(true)
     }
 
+    /// Reads and decodes data block `index`, positioned at its first entry.
+    fn block_iter_at(&self, index: usize) -> Result<BlockIterator, SSTableError> {
+        let entry = &self.sstable.index[index];
+        let block_bytes = self
+            .sstable
+            .read_block(&entry.handle, self.verify_checksums)?;
+
+        let (block, _) = encoding::decode_from_slice::<SSTableDataBlock>(&block_bytes)?;
+        Ok(BlockIterator::new(block.data))
+    }
+
     /// Return the next *point entry* (Put/Delete) in the scan key range,
     /// automatically advancing to the next block as needed.
     fn next_point_or_delete(&mut self) -> Option<Record> {
@@ -353,7 +480,7 @@ impl<S: Deref<Target = SSTable>> ScanIterator<S> {
             let r = &self.sstable.range_deletes.data[self.pending_range_idx];
 
             // Skip ranges completely left of scan window
-            if r.end_key.as_slice() <= self.start_key.as_slice() {
+            if !below_end(&self.start_key, &r.end_key) {
                 self.pending_range_idx += 1;
                 continue;
             }
@@ -392,6 +519,16 @@ impl<S: Deref<Target = SSTable>> ScanIterator<S> {
     }
 }
 
+impl<S: Deref<Target = SSTable>> RecordSource for ScanIterator<S> {
+    fn max_lsn(&self) -> u64 {
+        self.sstable.max_lsn()
+    }
+
+    fn seek_points(&mut self, key: &[u8]) {
+        self.seek_points_to(key);
+    }
+}
+
 impl<S: Deref<Target = SSTable>> Iterator for ScanIterator<S> {
     type Item = Record;
 
@@ -419,3 +556,147 @@ impl<S: Deref<Target = SSTable>> Iterator for ScanIterator<S> {
         }
     }
 }
+
+// ------------------------------------------------------------------------------------------------
+// Lazy Scan Iterator
+// ------------------------------------------------------------------------------------------------
+
+/// A [`ScanIterator`] over an `Arc`-owned SSTable, created when the first
+/// record is requested.
+///
+/// As a [`RecordSource`] it reports the smallest key the scan can yield
+/// as its lower bound, and remembers a seek requested before creation.
+/// A failure to create the scan is logged and ends the iterator, like a
+/// data block that fails to load mid-scan.
+pub struct LazyScanIterator {
+    sstable: Arc<SSTable>,
+    start_key: Vec<u8>,
+    end_key: Vec<u8>,
+    verify_checksums: bool,
+    min_timestamp: u64,
+
+    /// Smallest key of any record the scan yields.
+    lower_bound: Vec<u8>,
+
+    /// Point seek requested before the scan was created.
+    seek: Option<Vec<u8>>,
+
+    /// The scan, once created.
+    scan: Option<ScanIterator<Arc<SSTable>>>,
+
+    /// Set when creating the scan failed.
+    failed: bool,
+
+    /// Point tombstones yielded since the last put.
+    tombstone_run: u64,
+
+    /// Longest such run so far, reported to the SSTable on drop.
+    longest_tombstone_run: u64,
+}
+
+impl LazyScanIterator {
+    /// Prepares a scan of `[start_key, end_key)` that reads nothing until
+    /// its first record is requested. Arguments are as for
+    /// [`ScanIterator::since`].
+    pub fn new(
+        sstable: Arc<SSTable>,
+        start_key: Vec<u8>,
+        end_key: Vec<u8>,
+        verify_checksums: bool,
+        min_timestamp: u64,
+    ) -> Result<Self, SSTableError> {
+        if start_key >= end_key {
+            return Err(SSTableError::Internal("scan start >= end".to_string()));
+        }
+        let lower_bound = sstable.scan_lower_bound(&start_key).to_vec();
+        Ok(Self {
+            sstable,
+            start_key,
+            end_key,
+            verify_checksums,
+            min_timestamp,
+            lower_bound,
+            seek: None,
+            scan: None,
+            failed: false,
+            tombstone_run: 0,
+            longest_tombstone_run: 0,
+        })
+    }
+
+    /// Returns the scan, creating it on the first call.
+    fn scan(&mut self) -> Option<&mut ScanIterator<Arc<SSTable>>> {
+        if self.scan.is_none() && !self.failed {
+            match ScanIterator::since(
+                Arc::clone(&self.sstable),
+                std::mem::take(&mut self.start_key),
+                std::mem::take(&mut self.end_key),
+                self.verify_checksums,
+                self.min_timestamp,
+            ) {
+                Ok(mut scan) => {
+                    if let Some(key) = self.seek.take() {
+                        scan.seek_points_to(&key);
+                    }
+                    self.scan = Some(scan);
+                }
+                Err(e) => {
+                    tracing::warn!(?e, id = self.sstable.id(), "error opening SSTable scan");
+                    self.failed = true;
+                }
+            }
+        }
+        self.scan.as_mut()
+    }
+}
+
+impl RecordSource for LazyScanIterator {
+    fn max_lsn(&self) -> u64 {
+        self.sstable.max_lsn()
+    }
+
+    fn seek_points(&mut self, key: &[u8]) {
+        match self.scan.as_mut() {
+            Some(scan) => scan.seek_points_to(key),
+            None => {
+                if self
+                    .seek
+                    .as_deref()
+                    .is_none_or(|seek| cmp_ends(seek, key) == Ordering::Less)
+                {
+                    self.seek = Some(key.to_vec());
+                }
+            }
+        }
+    }
+
+    fn lower_bound(&self) -> Option<&[u8]> {
+        Some(&self.lower_bound)
+    }
+}
+
+impl Iterator for LazyScanIterator {
+    type Item = Record;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.scan()?.next();
+        match &record {
+            Some(Record::Delete { .. }) => self.tombstone_run += 1,
+            // Range tombstones are interleaved without ending a run.
+            Some(Record::RangeDelete { .. }) => {}
+            Some(Record::Put { .. }) | None => {
+                self.longest_tombstone_run = self.longest_tombstone_run.max(self.tombstone_run);
+                self.tombstone_run = 0;
+            }
+        }
+        record
+    }
+}
+
+impl Drop for LazyScanIterator {
+    fn drop(&mut self) {
+        self.sstable
+            .tombstone_runs
+            .record(self.longest_tombstone_run.max(self.tombstone_run));
+    }
+}
@@ -0,0 +1,367 @@
+//! SSTable file access under a memory-map budget.
+//!
+//! An [`SstFile`] serves block reads either from a memory map of the whole
+//! file or, once demoted, with positioned reads (`pread`) into owned
+//! buffers. Mapped pages count toward the process RSS while they are
+//! resident, so with many large SSTables — e.g. the inputs of a major
+//! compaction — mapping everything can get a memory-constrained process
+//! killed.
+//!
+//! An [`MmapBudget`] caps the bytes mapped across the files attached to
+//! it. Attaching a file that does not fit unmaps the least-recently-read
+//! mapped files until it does; a file larger than the whole budget is
+//! read with `pread` from the start. A demoted file is mapped again on a
+//! later read once the budget has room, e.g. after compaction deleted
+//! other tables.
+//!
+//! Unmapping never invalidates a read in flight: every read holds its own
+//! `Arc` of the map, and the pages are released when the last one drops.
+//!
+//! With page cache hints enabled, the engine advises the OS to read ahead
+//! the data blocks of a freshly written file (`MADV_WILLNEED`) and
+//! [retires](SstFile::retire) the files a compaction replaced: rather
+//! than `MADV_DONTNEED`, which `memmap2` only offers as `unsafe`, their
+//! maps are dropped for good, and scans still reading them switch to
+//! `pread`.
+
+use std::fs::File;
+use std::io;
+use std::ops::{Deref, Range};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock, Weak};
+use std::time::Instant;
+
+#[cfg(unix)]
+use memmap2::Advice;
+use memmap2::Mmap;
+
+// ------------------------------------------------------------------------------------------------
+// FileBytes
+// ------------------------------------------------------------------------------------------------
+
+/// Bytes read from an [`SstFile`]: a range of its memory map, or an owned
+/// copy read with `pread`.
+#[derive(Clone)]
+pub(crate) enum FileBytes {
+    /// A range of the file's memory map, kept alive by the `Arc`.
+    Mapped { map: Arc<Mmap>, range: Range<usize> },
+
+    /// Read from an unmapped file.
+    Owned(Vec<u8>),
+}
+
+impl FileBytes {
+    /// Returns the sub-range `range` of these bytes, without copying if
+    /// they are mapped.
+    pub(crate) fn slice(&self, range: Range<usize>) -> FileBytes {
+        match self {
+            Self::Mapped { map, range: own } => Self::Mapped {
+                map: Arc::clone(map),
+                range: own.start + range.start..own.start + range.end,
+            },
+            Self::Owned(bytes) => Self::Owned(bytes[range].to_vec()),
+        }
+    }
+
+    /// Converts the bytes into an owned `Vec<u8>`, copying if mapped.
+    pub(crate) fn into_vec(self) -> Vec<u8> {
+        match self {
+            Self::Mapped { map, range } => map[range].to_vec(),
+            Self::Owned(bytes) => bytes,
+        }
+    }
+}
+
+impl Deref for FileBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Mapped { map, range } => &map[range.clone()],
+            Self::Owned(bytes) => bytes,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// SstFile
+// ------------------------------------------------------------------------------------------------
+
+/// An open SSTable file, read through a memory map or with `pread`.
+pub(crate) struct SstFile {
+    file: File,
+
+    /// File length in bytes.
+    len: usize,
+
+    /// The memory map, or `None` while the file is read with `pread`.
+    map: RwLock<Option<Arc<Mmap>>>,
+
+    /// Budget clock reading of the last read, for least-recently-read
+    /// demotion.
+    last_read: AtomicU64,
+
+    /// Budget the mapping is charged to, once attached.
+    budget: OnceLock<Arc<MmapBudget>>,
+
+    /// Set once the file is retired; it is never mapped again.
+    retired: AtomicBool,
+}
+
+impl SstFile {
+    /// Wraps `file` and its memory map `map`.
+    pub(crate) fn new(file: File, map: Mmap) -> Self {
+        Self {
+            file,
+            len: map.len(),
+            map: RwLock::new(Some(Arc::new(map))),
+            last_read: AtomicU64::new(0),
+            budget: OnceLock::new(),
+            retired: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns the file length in bytes.
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if reads are served from a memory map.
+    pub(crate) fn is_mapped(&self) -> bool {
+        self.map.read().map(|map| map.is_some()).unwrap_or(false)
+    }
+
+    /// Reads `range` of the file with `pread`. The caller checks it lies
+    /// within [`len`](Self::len).
+    pub(crate) fn read_at(&self, range: Range<usize>) -> io::Result<Vec<u8>> {
+        let mut bytes = vec![0; range.len()];
+        read_exact_at(&self.file, &mut bytes, range.start as u64)?;
+        Ok(bytes)
+    }
+
+    /// Returns the memory map for a read, or `None` if the file is read
+    /// with `pread`. A demoted file is mapped again if its budget now has
+    /// room.
+    pub(crate) fn map(&self) -> Option<Arc<Mmap>> {
+        let Some(budget) = self.budget.get() else {
+            return self.map.read().ok()?.clone();
+        };
+        self.last_read.store(budget.now(), Ordering::Relaxed);
+        if let Some(map) = self.map.read().ok()?.as_ref() {
+            return Some(Arc::clone(map));
+        }
+
+        if self.retired.load(Ordering::Relaxed) || !budget.reserve(self.len) {
+            return None;
+        }
+        let mut slot = self.map.write().ok()?;
+        if let Some(map) = slot.as_ref() {
+            // Remapped by a concurrent read.
+            budget.release(self.len);
+            return Some(Arc::clone(map));
+        }
+        // SAFETY: SSTable files are never written after they are renamed
+        // into place, and the map is read-only.
+        match unsafe { Mmap::map(&self.file) } {
+            Ok(map) => {
+                let map = Arc::new(map);
+                *slot = Some(Arc::clone(&map));
+                Some(map)
+            }
+            Err(e) => {
+                budget.release(self.len);
+                tracing::warn!(error = %e, "failed to remap SSTable, reading with pread");
+                None
+            }
+        }
+    }
+
+    /// Advises the OS that `range` of the file will be read soon, so that
+    /// it starts reading it into the page cache. A no-op unless the file
+    /// is mapped; a failure is only logged.
+    #[cfg(unix)]
+    pub(crate) fn will_need(&self, range: Range<usize>) {
+        let Some(map) = self.map.read().ok().and_then(|map| map.clone()) else {
+            return;
+        };
+        let end = range.end.min(self.len);
+        if range.start >= end {
+            return;
+        }
+        if let Err(e) = map.advise_range(Advice::WillNeed, range.start, end - range.start) {
+            tracing::debug!(error = %e, "madvise(WILLNEED) failed");
+        }
+    }
+
+    /// Page cache advice needs `madvise`, available on Unix only.
+    #[cfg(not(unix))]
+    pub(crate) fn will_need(&self, _range: Range<usize>) {}
+
+    /// Drops the memory map for good, once the file is deleted: reads
+    /// still in flight keep their own map, later ones use `pread`.
+    pub(crate) fn retire(&self) {
+        self.retired.store(true, Ordering::Relaxed);
+        self.unmap();
+    }
+
+    /// Drops the memory map; later reads use `pread`. Returns `true` if
+    /// the file was mapped.
+    fn unmap(&self) -> bool {
+        let Ok(mut slot) = self.map.write() else {
+            return false;
+        };
+        if slot.take().is_none() {
+            return false;
+        }
+        if let Some(budget) = self.budget.get() {
+            budget.release(self.len);
+        }
+        true
+    }
+}
+
+impl Drop for SstFile {
+    fn drop(&mut self) {
+        let mapped = self.map.get_mut().map(|map| map.is_some()).unwrap_or(false);
+        if mapped && let Some(budget) = self.budget.get() {
+            budget.release(self.len);
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// MmapBudget
+// ------------------------------------------------------------------------------------------------
+
+/// Caps the bytes memory-mapped across the [`SstFile`]s attached to it.
+pub(crate) struct MmapBudget {
+    /// Most bytes that may be mapped at once.
+    limit: usize,
+
+    /// Bytes currently mapped by attached files.
+    mapped: AtomicUsize,
+
+    /// Origin of the read clock.
+    epoch: Instant,
+
+    /// Attached files, for picking the least recently read.
+    files: Mutex<Vec<Weak<SstFile>>>,
+}
+
+impl MmapBudget {
+    /// Creates a budget of `limit` mapped bytes.
+    pub(crate) fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            mapped: AtomicUsize::new(0),
+            epoch: Instant::now(),
+            files: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns the bytes currently mapped by attached files.
+    pub(crate) fn mapped_bytes(&self) -> usize {
+        self.mapped.load(Ordering::Relaxed)
+    }
+
+    /// Charges `file` to this budget, unmapping the least-recently-read
+    /// files until it fits. A file that cannot fit is unmapped itself.
+    ///
+    /// A file is attached at most once; later calls are ignored.
+    pub(crate) fn attach(self: &Arc<Self>, file: &Arc<SstFile>) {
+        if file.budget.set(Arc::clone(self)).is_err() {
+            return;
+        }
+        file.last_read.store(self.now(), Ordering::Relaxed);
+
+        // Upgraded handles are dropped after the lock is released: dropping
+        // the last one releases its bytes, which must not wait on the lock.
+        let mut candidates: Vec<Arc<SstFile>> = Vec::new();
+        let mut files = match self.files.lock() {
+            Ok(files) => files,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        files.retain(|weak| match weak.upgrade() {
+            Some(other) => {
+                candidates.push(other);
+                true
+            }
+            None => false,
+        });
+        files.push(Arc::downgrade(file));
+
+        if !file.is_mapped() {
+            return;
+        }
+        self.mapped.fetch_add(file.len, Ordering::Relaxed);
+        if file.len > self.limit {
+            file.unmap();
+            return;
+        }
+
+        candidates.retain(|other| other.is_mapped());
+        candidates.sort_by_key(|other| other.last_read.load(Ordering::Relaxed));
+        let mut demoted = 0;
+        for other in &candidates {
+            if self.mapped_bytes() <= self.limit {
+                break;
+            }
+            if other.unmap() {
+                demoted += 1;
+            }
+        }
+        if demoted > 0 {
+            tracing::debug!(
+                demoted,
+                mapped_bytes = self.mapped_bytes(),
+                limit = self.limit,
+                "unmapped least-recently-read SSTables"
+            );
+        }
+        drop(files);
+    }
+
+    /// Milliseconds since the budget was created.
+    fn now(&self) -> u64 {
+        self.epoch.elapsed().as_millis() as u64
+    }
+
+    /// Charges `len` bytes if they fit. Returns `false` otherwise.
+    fn reserve(&self, len: usize) -> bool {
+        self.mapped
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |mapped| {
+                mapped.checked_add(len).filter(|&total| total <= self.limit)
+            })
+            .is_ok()
+    }
+
+    /// Returns `len` bytes to the budget.
+    fn release(&self, len: usize) {
+        self.mapped.fetch_sub(len, Ordering::Relaxed);
+    }
+}
+
+/// Fills `buf` from `file` at `offset`, without moving a shared cursor.
+#[cfg(unix)]
+fn read_exact_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<()> {
+    use std::os::unix::fs::FileExt;
+    file.read_exact_at(buf, offset)
+}
+
+/// Fills `buf` from `file` at `offset`, without moving a shared cursor.
+#[cfg(windows)]
+fn read_exact_at(file: &File, mut buf: &mut [u8], mut offset: u64) -> io::Result<()> {
+    use std::os::windows::fs::FileExt;
+    while !buf.is_empty() {
+        match file.seek_read(buf, offset) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => {
+                buf = &mut buf[n..];
+                offset += n as u64;
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
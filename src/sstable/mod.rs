@@ -26,26 +26,38 @@
 //! [DATA_BLOCK_LEN_LE][DATA_BLOCK_BYTES][DATA_BLOCK_CRC32_LE]
 //! ...
 //! [BLOOM_FILTER_LEN_LE][BLOOM_FILTER_BYTES][BLOOM_FILTER_CRC32_LE]
+//! [KEY_SKETCH_LEN_LE][KEY_SKETCH_BYTES][KEY_SKETCH_CRC32_LE]
 //! [RANGE_DELETES_LEN_LE][RANGE_DELETES_BYTES][RANGE_DELETES_CRC32_LE]
 //! [PROPERTIES_LEN_LE][PROPERTIES_BYTES][PROPERTIES_CRC32_LE]
 //! [METAINDEX_LEN_LE][METAINDEX_BYTES][METAINDEX_CRC32_LE]
 //! [INDEX_LEN_LE][INDEX_BYTES][INDEX_CRC32_LE]
-//! [FOOTER_BYTES]
+//! [FILE_DIGEST_LEN_LE][FILE_DIGEST_BYTES][FILE_DIGEST_CRC32_LE]   (optional)
+//! [FOOTER_BYTES][FOOTER_MAGIC]
 //! ```
 //!
 //! - **Header** — `SSTableHeader` structure with CRC32 checksum.
 //! - **Data blocks** — store serialized `SSTableCell` entries (key-value or tombstone).
 //! - **Bloom filter block** — fast existence checks for point keys.
+//! - **Key sketch block** — HyperLogLog sketch of the point keys, for
+//!   distinct-key estimates across tables.
 //! - **Range deletes block** — serialized `SSTableRangeTombstoneCell` entries.
 //! - **Properties block** — table metadata such as min/max key, LSNs, timestamps, record counts.
-//! - **Metaindex block** — directory of blocks (bloom, properties, range deletes) for easy lookup.
+//! - **Metaindex block** — directory of blocks (bloom, key sketch, properties, range deletes) for easy lookup.
 //! - **Index block** — directory of data blocks, allowing binary search for keys.
-//! - **Footer** — `SSTableFooter` structure containing offsets, sizes, and CRC32 checksum.
+//! - **File digest block** — optional XXH3 or SHA-256 digest of every
+//!   byte before it, referenced by the first footer extension slot, so a
+//!   copied file can be verified without parsing its blocks.
+//! - **Footer** — `SSTableFooter` structure containing offsets, sizes,
+//!   reserved extension block handles, the format version, a CRC32
+//!   checksum, and a closing magic (`b"SSTFOOT0"`). Files of format
+//!   version 2 and earlier have a shorter footer without the extension
+//!   handles, version, and magic.
 //!
 //! # Sub-modules
 //!
 //! - [`builder`] — [`SstWriter`] for building SSTables from sorted streams.
 //! - [`iterator`] — [`BlockIterator`], [`BlockEntry`], and [`ScanIterator`] for reading.
+//! - [`mapping`] — mapped or `pread` file access under an [`MmapBudget`](mapping::MmapBudget).
 //!
 //! # Concurrency model
 //!
@@ -70,6 +82,8 @@
 
 pub mod builder;
 pub mod iterator;
+pub(crate) mod mapping;
+pub(crate) mod salvage;
 
 #[cfg(test)]
 mod tests;
@@ -80,33 +94,57 @@ mod tests;
 
 #[allow(unused_imports)] // public API surface for downstream consumers
 pub use crate::engine::{PointEntry, RangeTombstone, Record};
-pub use builder::SstWriter;
+pub use builder::{SstBuildStats, SstWriter};
 #[allow(unused_imports)] // public API surface for downstream consumers
-pub use iterator::{BlockEntry, BlockIterator, ScanIterator};
+pub use iterator::{BlockEntry, BlockIterator, LazyScanIterator, ScanIterator};
 
 // ------------------------------------------------------------------------------------------------
 // Includes
 // ------------------------------------------------------------------------------------------------
 
-use std::sync::Arc;
+use std::ops::Range;
+use std::sync::{Arc, OnceLock};
 use std::{fs::File, io, path::Path};
 
 use crate::encoding::{self, EncodingError};
+use crate::engine::read_stats::{ReadCounters, SstAccess, SstProbe, TombstoneRuns};
+use crate::engine::utils::{VersionRank, below_end};
+use crate::sketch::KeySketch;
+use crate::slow_op;
 use bloomfilter::Bloom;
 use crc32fast::Hasher as Crc32;
+use mapping::{FileBytes, MmapBudget, SstFile};
 use memmap2::Mmap;
+use sha2::{Digest, Sha256};
 use thiserror::Error;
 use tracing::{debug, info, warn};
+use xxhash_rust::xxh3::Xxh3;
 
 // ------------------------------------------------------------------------------------------------
 // Constants
 // ------------------------------------------------------------------------------------------------
 
 const SST_HDR_MAGIC: [u8; 4] = *b"SST0";
-const SST_HDR_VERSION: u32 = 1;
+const SST_HDR_VERSION: u32 = 3;
+/// Format version before the footer carried a magic, a version, and
+/// extension slots. Still readable.
+const SST_HDR_VERSION_V2: u32 = 2;
+/// Format version before index entries carried per-block LSN and
+/// timestamp bounds. Still readable.
+const SST_HDR_VERSION_V1: u32 = 1;
 const SST_BLOOM_FILTER_FALSE_POSITIVE_RATE: f64 = 0.01;
 const SST_DATA_BLOCK_MAX_SIZE: usize = 4096;
-const SST_FOOTER_SIZE: usize = 44;
+/// Last eight bytes of every SSTable since format version 3.
+pub(crate) const SST_FOOTER_MAGIC: [u8; 8] = *b"SSTFOOT0";
+/// Trailing footer bytes naming the format: version, CRC32, and magic.
+pub(crate) const SST_FOOTER_TAIL_SIZE: usize = 16;
+/// Extension block handles reserved in the footer.
+pub(crate) const SST_FOOTER_EXTENSION_SLOTS: usize = 4;
+/// Footer extension slot of the whole-file digest block.
+pub(crate) const SST_FOOTER_DIGEST_SLOT: usize = 0;
+const SST_FOOTER_SIZE: usize = 120;
+/// Size of the footer of format versions 1 and 2.
+const SST_FOOTER_SIZE_V2: usize = 44;
 const SST_HDR_SIZE: usize = 12;
 const SST_DATA_BLOCK_LEN_SIZE: usize = 4;
 const SST_DATA_BLOCK_CHECKSUM_SIZE: usize = 4;
@@ -144,6 +182,10 @@ pub enum SSTableError {
     /// Checksum mistmatch.
     #[error("Checksum mismatch")]
     ChecksumMismatch,
+
+    /// A value does not match the checksum stored in its cell.
+    #[error("Value checksum mismatch")]
+    ValueChecksumMismatch,
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -217,6 +259,31 @@ pub struct SSTablePropertiesBlock {
 
     /// Maximum key in the SSTable.
     pub max_key: Vec<u8>,
+
+    /// Number of distinct point keys, or `None` if the table predates
+    /// this count. Encoded after every other property, so older readers
+    /// ignore it.
+    pub key_count: Option<u64>,
+
+    /// Per-prefix tally of the newest puts, one entry per prefix the
+    /// table was written with, or `None` if it was written without any.
+    /// Encoded after `key_count`.
+    pub prefix_usage: Option<Vec<SSTablePrefixUsage>>,
+}
+
+/// Bytes and entries under one key prefix, tallied while the SSTable was
+/// written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SSTablePrefixUsage {
+    /// Key prefix the tally is for.
+    pub prefix: Vec<u8>,
+
+    /// Key and value bytes of the newest put of each key whose longest
+    /// matching prefix is this one.
+    pub bytes: u64,
+
+    /// Keys counted in `bytes`.
+    pub entries: u64,
 }
 
 /// Index entry pointing to a specific data block.
@@ -227,9 +294,52 @@ pub(crate) struct SSTableIndexEntry {
 
     /// Block handle containing offset and size of the data block.
     pub(crate) handle: BlockHandle,
+
+    /// Minimum LSN of the entries in the block.
+    pub(crate) min_lsn: u64,
+
+    /// Maximum LSN of the entries in the block.
+    pub(crate) max_lsn: u64,
+
+    /// Minimum timestamp of the entries in the block.
+    pub(crate) min_timestamp: u64,
+
+    /// Maximum timestamp of the entries in the block.
+    pub(crate) max_timestamp: u64,
+}
+
+/// Index entry as written by format version 1, without block bounds.
+///
+/// Decoded into an [`SSTableIndexEntry`] whose bounds cover every LSN and
+/// timestamp, so no block of an old file is ever skipped.
+#[derive(Debug)]
+pub(crate) struct SSTableIndexEntryV1 {
+    /// Key that separates this block from the next in sorted order.
+    pub(crate) separator_key: Vec<u8>,
+
+    /// Block handle containing offset and size of the data block.
+    pub(crate) handle: BlockHandle,
+}
+
+impl From<SSTableIndexEntryV1> for SSTableIndexEntry {
+    fn from(entry: SSTableIndexEntryV1) -> Self {
+        Self {
+            separator_key: entry.separator_key,
+            handle: entry.handle,
+            min_lsn: 0,
+            max_lsn: u64::MAX,
+            min_timestamp: 0,
+            max_timestamp: u64::MAX,
+        }
+    }
 }
 
 /// SSTable footer, stored at the very end of the file.
+///
+/// Ends with [`SST_FOOTER_MAGIC`], so a file can be recognised from its
+/// last bytes, and carries the format version and a fixed area of
+/// extension block handles. A later format stores its new blocks there;
+/// readers ignore the slots they do not know.
 #[derive(Debug)]
 pub(crate) struct SSTableFooter {
     /// Handle of the metaindex block, containing references to:
@@ -241,11 +351,248 @@ pub(crate) struct SSTableFooter {
     /// Handle of the main index block, mapping separator keys to data blocks.
     pub(crate) index: BlockHandle,
 
+    /// Reserved extension block handles; an all-zero handle is an unused
+    /// slot. Slot [`SST_FOOTER_DIGEST_SLOT`] holds the file digest block
+    /// of a table written with one; the other slots are unused in format
+    /// version 3.
+    pub(crate) extensions: [BlockHandle; SST_FOOTER_EXTENSION_SLOTS],
+
     /// Total size of the SSTable file, including this footer.
     pub(crate) total_file_size: u64,
 
+    /// Format version of the file; matches the header.
+    pub(crate) format_version: u32,
+
     /// CRC32 checksum computed over the footer fields except this one.
     pub(crate) footer_crc32: u32,
+
+    /// [`SST_FOOTER_MAGIC`]; zeroed for a footer of format version 2 or
+    /// earlier, which has none.
+    pub(crate) magic: [u8; 8],
+}
+
+/// Footer as written by format versions 1 and 2: no magic, version, or
+/// extension slots.
+///
+/// Converted into an [`SSTableFooter`] with unused extension slots and
+/// `format_version` 2.
+#[derive(Debug)]
+pub(crate) struct SSTableFooterV2 {
+    /// Handle of the metaindex block.
+    pub(crate) metaindex: BlockHandle,
+
+    /// Handle of the main index block.
+    pub(crate) index: BlockHandle,
+
+    /// Total size of the SSTable file, including this footer.
+    pub(crate) total_file_size: u64,
+
+    /// CRC32 checksum computed over the footer fields except this one.
+    pub(crate) footer_crc32: u32,
+}
+
+impl From<SSTableFooterV2> for SSTableFooter {
+    fn from(footer: SSTableFooterV2) -> Self {
+        Self {
+            metaindex: footer.metaindex,
+            index: footer.index,
+            extensions: Default::default(),
+            total_file_size: footer.total_file_size,
+            format_version: SST_HDR_VERSION_V2,
+            footer_crc32: footer.footer_crc32,
+            magic: [0; 8],
+        }
+    }
+}
+
+impl SSTableFooter {
+    /// Decodes the footer at the end of `bytes` — of the current layout if
+    /// `bytes` ends with [`SST_FOOTER_MAGIC`], of format version 2
+    /// otherwise — and verifies its checksum.
+    pub(crate) fn read(bytes: &[u8]) -> Result<Self, SSTableError> {
+        if bytes.len() >= SST_FOOTER_SIZE && bytes.ends_with(&SST_FOOTER_MAGIC) {
+            let start = bytes.len() - SST_FOOTER_SIZE;
+            let (mut footer, _) = encoding::decode_from_slice::<Self>(&bytes[start..])?;
+            let stored = footer.footer_crc32;
+            footer.footer_crc32 = 0;
+            verify_footer_crc(&footer, stored)?;
+            footer.footer_crc32 = stored;
+            return Ok(footer);
+        }
+
+        let start = bytes
+            .len()
+            .checked_sub(SST_FOOTER_SIZE_V2)
+            .ok_or_else(|| SSTableError::Internal("File too small".into()))?;
+        let (mut footer, _) = encoding::decode_from_slice::<SSTableFooterV2>(&bytes[start..])?;
+        let stored = footer.footer_crc32;
+        footer.footer_crc32 = 0;
+        verify_footer_crc(&footer, stored)?;
+        footer.footer_crc32 = stored;
+        Ok(footer.into())
+    }
+
+    /// Size of this footer on disk.
+    pub(crate) fn encoded_len(&self) -> usize {
+        if self.format_version > SST_HDR_VERSION_V2 {
+            SST_FOOTER_SIZE
+        } else {
+            SST_FOOTER_SIZE_V2
+        }
+    }
+
+    /// Returns the format version named by the footer at the end of
+    /// `bytes`, without verifying anything, or `None` if `bytes` does not
+    /// end with [`SST_FOOTER_MAGIC`].
+    pub(crate) fn sniff(bytes: &[u8]) -> Option<u32> {
+        let start = bytes.len().checked_sub(SST_FOOTER_TAIL_SIZE)?;
+        let tail = &bytes[start..];
+        if !tail.ends_with(&SST_FOOTER_MAGIC) {
+            return None;
+        }
+        Some(u32::from_le_bytes(tail[..4].try_into().ok()?))
+    }
+}
+
+/// Fails with [`SSTableError::ChecksumMismatch`] unless the encoding of
+/// `footer`, its checksum zeroed, has checksum `stored`.
+fn verify_footer_crc(footer: &impl encoding::Encode, stored: u32) -> Result<(), SSTableError> {
+    if crc32(&encoding::encode_to_vec(footer)?) != stored {
+        return Err(SSTableError::ChecksumMismatch);
+    }
+    Ok(())
+}
+
+// ------------------------------------------------------------------------------------------------
+// Whole-file digest
+// ------------------------------------------------------------------------------------------------
+
+/// Algorithm of the whole-file digest stored in new SSTables, selected by
+/// [`DbConfig::sstable_file_digest`](crate::DbConfig::sstable_file_digest).
+///
+/// The digest covers every byte of the file before the digest block; the
+/// digest block and the footer pointing at it carry their own CRC32, so
+/// together they protect the whole file. Verifying it reads the file
+/// once and parses nothing but the footer and the digest block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SstFileDigest {
+    /// 128-bit XXH3: non-cryptographic and fast enough to verify at disk
+    /// speed. Catches damage in transfer and at rest.
+    Xxh3,
+
+    /// SHA-256: several times slower than XXH3, for pipelines that
+    /// require a cryptographic hash.
+    Sha256,
+}
+
+impl SstFileDigest {
+    /// Tag of the algorithm in the digest block.
+    pub(crate) fn tag(self) -> u8 {
+        match self {
+            SstFileDigest::Xxh3 => 1,
+            SstFileDigest::Sha256 => 2,
+        }
+    }
+
+    /// Algorithm of `tag`, or `None` for an unknown one.
+    pub(crate) fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            1 => Some(SstFileDigest::Xxh3),
+            2 => Some(SstFileDigest::Sha256),
+            _ => None,
+        }
+    }
+}
+
+/// Incremental hasher of an [`SstFileDigest`] algorithm.
+pub(crate) enum FileDigester {
+    Xxh3(Box<Xxh3>),
+    Sha256(Sha256),
+}
+
+impl FileDigester {
+    /// Starts a digest with `algorithm`.
+    pub(crate) fn new(algorithm: SstFileDigest) -> Self {
+        match algorithm {
+            SstFileDigest::Xxh3 => Self::Xxh3(Box::new(Xxh3::new())),
+            SstFileDigest::Sha256 => Self::Sha256(Sha256::new()),
+        }
+    }
+
+    /// Feeds `bytes` into the digest.
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Self::Xxh3(hasher) => hasher.update(bytes),
+            Self::Sha256(hasher) => hasher.update(bytes),
+        }
+    }
+
+    /// Returns the digest of every byte fed in.
+    pub(crate) fn finish(self) -> Vec<u8> {
+        match self {
+            Self::Xxh3(hasher) => hasher.digest128().to_be_bytes().to_vec(),
+            Self::Sha256(hasher) => hasher.finalize().to_vec(),
+        }
+    }
+}
+
+/// Whole-file digest block, referenced by footer extension slot
+/// [`SST_FOOTER_DIGEST_SLOT`].
+#[derive(Debug)]
+pub(crate) struct SSTableFileDigestBlock {
+    /// Algorithm the digest was computed with.
+    pub(crate) algorithm: SstFileDigest,
+
+    /// Digest of every file byte before this block.
+    pub(crate) digest: Vec<u8>,
+}
+
+/// Verifies the whole-file digest of the SSTable whose bytes are `bytes`,
+/// parsing only its footer and digest block.
+///
+/// Returns the digest algorithm, or `None` for a table written without a
+/// digest, which is not verified.
+///
+/// # Errors
+///
+/// - [`SSTableError::ChecksumMismatch`] — the footer, the digest block, or
+///   the digest does not match.
+/// - [`SSTableError::Internal`] — the file size differs from the one the
+///   footer records, or the digest block lies outside the file.
+pub(crate) fn verify_file_digest(bytes: &[u8]) -> Result<Option<SstFileDigest>, SSTableError> {
+    let footer = SSTableFooter::read(bytes)?;
+    if footer.total_file_size != bytes.len() as u64 {
+        return Err(SSTableError::Internal(format!(
+            "SSTable is {} bytes, its footer records {}",
+            bytes.len(),
+            footer.total_file_size
+        )));
+    }
+
+    let handle = &footer.extensions[SST_FOOTER_DIGEST_SLOT];
+    if handle.offset == 0 && handle.size == 0 {
+        return Ok(None);
+    }
+    let block_bytes = SSTable::read_block_bytes(bytes, handle, true)?;
+    let (block, _) = encoding::decode_from_slice::<SSTableFileDigestBlock>(&block_bytes)?;
+
+    // `read_block_bytes` checked that the block lies within `bytes`.
+    let mut digester = FileDigester::new(block.algorithm);
+    digester.update(&bytes[..handle.offset as usize]);
+    if digester.finish() != block.digest {
+        return Err(SSTableError::ChecksumMismatch);
+    }
+    Ok(Some(block.algorithm))
+}
+
+/// Maps the file at `path` and verifies its whole-file digest, like
+/// [`verify_file_digest`].
+pub(crate) fn verify_file_digest_at(
+    path: impl AsRef<Path>,
+) -> Result<Option<SstFileDigest>, SSTableError> {
+    let file = File::open(path)?;
+    let mmap = unsafe { Mmap::map(&file)? };
+    verify_file_digest(&mmap)
 }
 
 /// Represents a single key-value entry (or tombstone) in a data block.
@@ -265,6 +612,9 @@ pub(crate) struct SSTableCell {
 
     /// Log Sequence Number for versioning.
     pub(crate) lsn: u64,
+
+    /// CRC-32 of the value, written when value checksums are enabled.
+    pub(crate) value_crc: Option<u32>,
 }
 
 /// Represents a range tombstone marking deletion of keys in `[start_key, end_key)`.
@@ -273,7 +623,7 @@ pub(crate) struct SSTableRangeTombstoneCell {
     /// Start key of the deleted range (inclusive).
     pub(crate) start_key: Vec<u8>,
 
-    /// End key of the deleted range (exclusive).
+    /// End key of the deleted range (exclusive); empty if unbounded.
     pub(crate) end_key: Vec<u8>,
 
     /// Timestamp of the deletion.
@@ -284,7 +634,7 @@ pub(crate) struct SSTableRangeTombstoneCell {
 }
 
 /// Handle to a block in the SSTable file, specifying its offset and size.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub(crate) struct BlockHandle {
     /// Offset of the block in the SSTable file.
     pub(crate) offset: u64,
@@ -311,12 +661,16 @@ mod encoding_impls;
 // ------------------------------------------------------------------------------------------------
 
 /// Result of a single key lookup in an SSTable.
+///
+/// `V` is the representation of a `Put` value: an owned `Vec<u8>` for
+/// [`SSTable::get`], or a byte range into the SSTable's mmap for the
+/// zero-copy lookup used by [`Engine::get_pinned`](crate::engine::Engine::get_pinned).
 #[derive(Debug, PartialEq, Clone)]
-pub enum GetResult {
+pub enum GetResult<V = Vec<u8>> {
     /// A value stored in this SST.
     Put {
         /// Stored value.
-        value: Vec<u8>,
+        value: V,
         /// LSN of this version.
         lsn: u64,
         /// Timestamp of this version.
@@ -343,7 +697,7 @@ pub enum GetResult {
     NotFound,
 }
 
-impl GetResult {
+impl<V> GetResult<V> {
     /// Returns the **LSN** (logical sequence number) associated with this get result.
     ///
     /// Returns `0` for [`GetResult::NotFound`] — callers should match on the
@@ -370,12 +724,37 @@ impl GetResult {
         }
     }
 
-    /// Returns `true` if `self` is *newer* than `other` in MVCC ordering.
-    ///
-    /// Comparison order: higher LSN wins; on tie, higher timestamp wins.
-    fn is_newer_than(&self, other_lsn: u64, other_ts: u64) -> bool {
-        let (lsn, ts) = (self.lsn(), self.timestamp());
-        lsn > other_lsn || (lsn == other_lsn && ts > other_ts)
+    /// Returns the precedence of this version, or `None` for
+    /// [`GetResult::NotFound`].
+    pub fn rank(&self) -> Option<VersionRank> {
+        let point = match self {
+            Self::Put { .. } | Self::Delete { .. } => true,
+            Self::RangeDelete { .. } => false,
+            Self::NotFound => return None,
+        };
+        Some(VersionRank {
+            lsn: self.lsn(),
+            point,
+            timestamp: self.timestamp(),
+        })
+    }
+
+    /// Converts the `Put` value with `f`, leaving other variants unchanged.
+    fn map_value<W>(self, f: impl FnOnce(V) -> W) -> GetResult<W> {
+        match self {
+            Self::Put {
+                value,
+                lsn,
+                timestamp,
+            } => GetResult::Put {
+                value: f(value),
+                lsn,
+                timestamp,
+            },
+            Self::Delete { lsn, timestamp } => GetResult::Delete { lsn, timestamp },
+            Self::RangeDelete { lsn, timestamp } => GetResult::RangeDelete { lsn, timestamp },
+            Self::NotFound => GetResult::NotFound,
+        }
     }
 }
 
@@ -383,21 +762,54 @@ impl GetResult {
 // SSTable — immutable reader
 // ------------------------------------------------------------------------------------------------
 
-/// A fully memory-mapped, immutable **Sorted String Table (SSTable)**.
+/// How much of an SSTable is verified when it is opened, selected by
+/// [`DbConfig::sstable_open_checks`](crate::DbConfig::sstable_open_checks).
+///
+/// The header and footer checksums are always verified. Data blocks are
+/// otherwise verified as reads reach them, per
+/// [`ReadOptions::verify_checksums`](crate::ReadOptions::verify_checksums).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OpenChecks {
+    /// Verify only the header and footer. The properties, index, and
+    /// range tombstones are decoded without checking their CRC32; the
+    /// bloom filter and key sketch are not read until first used, and are
+    /// then verified. A damaged bloom filter or sketch is logged and
+    /// ignored rather than failing the read.
+    ///
+    /// Opens touch the least of each file, for deployments with many
+    /// large tables.
+    FooterOnly,
+
+    /// Verify and decode every metadata block: index, bloom filter,
+    /// properties, range tombstones, and key sketch.
+    #[default]
+    Metadata,
+
+    /// As [`Metadata`](Self::Metadata), then also verify the CRC32 of
+    /// every data block and the whole-file digest, if the table has one
+    /// (see [`SstFileDigest`]), reading the whole file.
+    FullBlockChecksums,
+}
+
+/// An immutable **Sorted String Table (SSTable)**, memory-mapped unless an
+/// [`MmapBudget`] demoted it to positioned reads.
 pub struct SSTable {
     /// Unique identifier assigned by the engine (from the manifest).
     /// Set to 0 by `SSTable::open()` — the engine sets the correct value after loading.
     id: u64,
 
-    /// Memory-mapped file containing the full SSTable bytes.
-    pub(crate) mmap: Mmap,
+    /// The SSTable file, read through a memory map or with `pread`.
+    pub(crate) file: Arc<SstFile>,
 
     /// Parsed header block containing magic/version information.
-    #[allow(dead_code)]
     pub(crate) header: SSTableHeader,
 
-    /// Bloom filter block for fast membership tests.
-    pub(crate) bloom: SSTableBloomBlock,
+    /// Bloom filter block for fast membership tests, decoded on open or,
+    /// with [`OpenChecks::FooterOnly`], on first use.
+    bloom: OnceLock<SSTableBloomBlock>,
+
+    /// Location of the bloom filter block, if the table has one.
+    bloom_handle: Option<BlockHandle>,
 
     /// Properties block with statistics and metadata.
     pub(crate) properties: SSTablePropertiesBlock,
@@ -405,11 +817,27 @@ pub struct SSTable {
     /// Range delete tombstone block.
     pub(crate) range_deletes: SSTableRangeTombstoneDataBlock,
 
+    /// Sketch of the distinct point keys, or `None` for tables written
+    /// before sketches were stored. Loaded like the bloom filter.
+    key_sketch: OnceLock<Option<KeySketch>>,
+
+    /// Location of the key sketch block, if the table has one.
+    key_sketch_handle: Option<BlockHandle>,
+
     /// Index entries mapping key ranges to data blocks.
     pub(crate) index: Vec<SSTableIndexEntry>,
 
     /// Footer containing block handles and file integrity data.
     pub(crate) footer: SSTableFooter,
+
+    /// Bloom and block-read counters for engine point lookups.
+    pub(crate) reads: ReadCounters,
+
+    /// Longest run of point tombstones stepped over by engine scans.
+    pub(crate) tombstone_runs: TombstoneRuns,
+
+    /// Lookup hits and scans of engine reads, for compaction heuristics.
+    pub(crate) access: SstAccess,
 }
 
 impl SSTable {
@@ -430,6 +858,11 @@ impl SSTable {
         self.footer.total_file_size
     }
 
+    /// Returns the format version the file was written with.
+    pub fn format_version(&self) -> u32 {
+        self.header.version
+    }
+
     /// Returns the maximum LSN stored in this SSTable.
     pub fn max_lsn(&self) -> u64 {
         self.properties.max_lsn
@@ -455,6 +888,18 @@ impl SSTable {
         self.properties.range_tombstones_count
     }
 
+    /// Returns the number of distinct point keys in this SSTable, or
+    /// `None` if the table predates this count.
+    pub(crate) fn key_count(&self) -> Option<u64> {
+        self.properties.key_count
+    }
+
+    /// Returns the per-prefix tally of this SSTable, or `None` if it was
+    /// written without tenant prefixes.
+    pub(crate) fn prefix_usage(&self) -> Option<&[SSTablePrefixUsage]> {
+        self.properties.prefix_usage.as_deref()
+    }
+
     /// Returns the minimum key stored in this SSTable.
     pub fn min_key(&self) -> &[u8] {
         &self.properties.min_key
@@ -465,6 +910,48 @@ impl SSTable {
         &self.properties.max_key
     }
 
+    /// Returns the sketch of the distinct point keys, or `None` if the
+    /// table predates key sketches.
+    pub(crate) fn key_sketch(&self) -> Option<&KeySketch> {
+        self.key_sketch
+            .get_or_init(|| {
+                let handle = self.key_sketch_handle.as_ref()?;
+                match self
+                    .read_block(handle, true)
+                    .and_then(|bytes| Self::decode_key_sketch(&bytes))
+                {
+                    Ok(sketch) => Some(sketch),
+                    Err(e) => {
+                        warn!(id = self.id, error = %e, "ignoring unreadable key sketch");
+                        None
+                    }
+                }
+            })
+            .as_ref()
+    }
+
+    /// Returns the bloom filter block, reading it on first use if it was
+    /// not decoded by [`open_with`](Self::open_with).
+    ///
+    /// A bloom filter that cannot be read is logged and replaced by an
+    /// empty one, which rules nothing out.
+    pub(crate) fn bloom(&self) -> &SSTableBloomBlock {
+        self.bloom.get_or_init(|| {
+            let bytes = self
+                .bloom_handle
+                .as_ref()
+                .map(|handle| self.read_block(handle, true))
+                .transpose();
+            match bytes.and_then(|bytes| Self::decode_bloom(bytes.as_deref())) {
+                Ok(bloom) => bloom,
+                Err(e) => {
+                    warn!(id = self.id, error = %e, "ignoring unreadable bloom filter");
+                    SSTableBloomBlock { data: Vec::new() }
+                }
+            }
+        })
+    }
+
     /// Returns the creation timestamp of this SSTable (UNIX epoch nanos).
     pub fn creation_timestamp(&self) -> u64 {
         self.properties.creation_timestamp
@@ -486,15 +973,61 @@ impl SSTable {
     /// Returns `true` if the bloom says "maybe present" or no bloom exists.
     /// Returns `false` only when the bloom definitively says "not present".
     pub fn bloom_may_contain(&self, key: &[u8]) -> bool {
-        if self.bloom.data.is_empty() {
+        let bloom = self.bloom();
+        if bloom.data.is_empty() {
             return true; // no bloom → cannot exclude
         }
-        match Bloom::from_slice(&self.bloom.data) {
+        match Bloom::from_slice(&bloom.data) {
             Ok(bloom) => bloom.check(key),
             Err(_) => true, // corrupted bloom → assume present
         }
     }
 
+    /// Checks whether a point lookup for `key` could find anything in this
+    /// SSTable.
+    ///
+    /// Returns `false` only when the bloom filter rules the key out **and**
+    /// no range tombstone covers it — i.e. [`get`](Self::get) would return
+    /// [`GetResult::NotFound`].
+    pub fn may_contain(&self, key: &[u8]) -> bool {
+        self.bloom_may_contain(key) || self.covering_range_for_key(key).is_some()
+    }
+
+    /// Checks whether `key` lies within this table's point key bounds or
+    /// under one of its range tombstones.
+    ///
+    /// Cheaper than [`may_contain`](Self::may_contain), as no bloom filter
+    /// is read, but only rules out keys outside the table's key range.
+    pub(crate) fn may_hold_key(&self, key: &[u8]) -> bool {
+        (self.record_count() > 0 && self.min_key() <= key && key <= self.max_key())
+            || self.covering_range_for_key(key).is_some()
+    }
+
+    /// Checks whether a scan of `[start, end)` could return anything from
+    /// this SSTable.
+    ///
+    /// Returns `false` only when the table's key bounds rule out every
+    /// point entry **and** no range tombstone overlaps the range — the
+    /// key bounds cover point entries only.
+    pub fn may_overlap(&self, start: &[u8], end: &[u8]) -> bool {
+        let points = self.record_count() > 0 && self.min_key() < end && self.max_key() >= start;
+        points
+            || self
+                .range_deletes
+                .data
+                .iter()
+                .any(|rd| rd.start_key.as_slice() < end && below_end(start, &rd.end_key))
+    }
+
+    /// Returns the start and exclusive end of each range tombstone stored
+    /// in this SSTable, without copying them.
+    pub(crate) fn range_tombstone_bounds(&self) -> impl Iterator<Item = (&[u8], &[u8])> + '_ {
+        self.range_deletes
+            .data
+            .iter()
+            .map(|rd| (rd.start_key.as_slice(), rd.end_key.as_slice()))
+    }
+
     /// Returns an iterator over the range tombstones stored in this SSTable.
     pub fn range_tombstone_iter(&self) -> impl Iterator<Item = crate::engine::RangeTombstone> + '_ {
         self.range_deletes
@@ -524,8 +1057,12 @@ impl SSTable {
     ///    - Magic string and version must match engine constants
     ///
     /// 3. **Decode and verify the footer**
-    ///    - Footer CRC is verified similarly
-    ///    - Contains block handles for `metaindex` and `index`
+    ///    - Layout chosen by the trailing footer magic; files without it
+    ///      have the format version 2 footer
+    ///    - Footer CRC is verified similarly, and its format version must
+    ///      match the header
+    ///    - Contains block handles for `metaindex` and `index`; extension
+    ///      slots are ignored
     ///
     /// 4. **Load the metaindex block**
     ///    This tells us where the bloom filter, properties block,
@@ -539,13 +1076,16 @@ impl SSTable {
     ///
     /// 6. **Return a fully initialized `SSTable` instance**
     ///
+    /// Every metadata block is checksum-verified; see
+    /// [`open_with`](Self::open_with) for lighter or stricter checks.
+    ///
     /// # Errors
     ///
     /// - [`SSTableError::ChecksumMismatch`]
     ///   If header or footer checksums fail.
     ///
     /// - [`SSTableError::Internal`]
-    ///   For malformed blocks, mismatched magic/version, missing properties block,
+    ///   For malformed blocks, mismatched magic/version (header or footer), missing properties block,
     ///   out-of-bounds reads, truncated block data, or unrecognized metaindex entries.
     ///
     /// # Safety
@@ -556,15 +1096,28 @@ impl SSTable {
     /// - The mmap is read-only
     /// - All block boundaries are verified before slicing
     pub fn open(path: impl AsRef<Path>) -> Result<Self, SSTableError> {
+        Self::open_with(path, OpenChecks::default())
+    }
+
+    /// Same as [`open`](Self::open), with `checks` choosing how much of the
+    /// file is verified and decoded up front.
+    ///
+    /// With [`OpenChecks::FooterOnly`], damage to the index, properties,
+    /// or range tombstones may surface as a decoding error rather than
+    /// [`SSTableError::ChecksumMismatch`], or not at all. With
+    /// [`OpenChecks::FullBlockChecksums`], a damaged data block or a
+    /// digest mismatch fails the open with
+    /// [`SSTableError::ChecksumMismatch`].
+    pub fn open_with(path: impl AsRef<Path>, checks: OpenChecks) -> Result<Self, SSTableError> {
         let path = path.as_ref();
-        debug!(?path, "opening SSTable");
+        debug!(?path, ?checks, "opening SSTable");
 
         let file = File::open(path)?;
 
         let mmap = unsafe { Mmap::map(&file)? };
 
         let file_len = mmap.len();
-        if file_len < SST_FOOTER_SIZE {
+        if file_len < SST_HDR_SIZE + SST_FOOTER_SIZE_V2 {
             return Err(SSTableError::Internal("File too small".into()));
         }
 
@@ -593,64 +1146,76 @@ impl SSTable {
             ));
         }
 
-        if header.version != SST_HDR_VERSION {
+        if !(SST_HDR_VERSION_V1..=SST_HDR_VERSION).contains(&header.version) {
             return Err(SSTableError::Internal(
                 "SSTable header version mismatch".into(),
             ));
         }
 
-        let footer_start = file_len - SST_FOOTER_SIZE;
-        let (mut footer, _) = encoding::decode_from_slice::<SSTableFooter>(&mmap[footer_start..])?;
-
-        let footer_checksum = footer.footer_crc32;
-        footer.footer_crc32 = 0;
-
-        let footer_bytes = encoding::encode_to_vec(&footer)?;
-
-        let footer_comp_checksum = crc32(&footer_bytes);
+        let footer = SSTableFooter::read(&mmap).inspect_err(|e| {
+            warn!(?path, error = %e, "unreadable SSTable footer");
+        })?;
 
-        if footer_checksum != footer_comp_checksum {
-            warn!(
-                ?path,
-                expected = footer_checksum,
-                actual = footer_comp_checksum,
-                "footer checksum mismatch"
-            );
-            return Err(SSTableError::ChecksumMismatch);
+        // Files older than version 3 have a version 2 footer.
+        if footer.format_version != header.version.max(SST_HDR_VERSION_V2) {
+            return Err(SSTableError::Internal(
+                "SSTable footer version mismatch".into(),
+            ));
         }
 
-        let metaindex_data = Self::read_block_bytes(&mmap, &footer.metaindex)?;
+        // Metadata blocks are checksum-verified unless only the footer is.
+        let verify = checks != OpenChecks::FooterOnly;
+
+        let metaindex_data = Self::read_block_bytes(&mmap, &footer.metaindex, verify)?;
         let (meta_entries, _) = encoding::decode_vec::<MetaIndexEntry>(&metaindex_data)?;
 
         let mut bloom_block: Option<BlockHandle> = None;
         let mut properties_block: Option<BlockHandle> = None;
         let mut range_deletes_block: Option<BlockHandle> = None;
+        let mut key_sketch_block: Option<BlockHandle> = None;
 
         for entry in meta_entries {
             match entry.name.as_str() {
                 "filter.bloom" => bloom_block = Some(entry.handle),
                 "meta.properties" => properties_block = Some(entry.handle),
                 "meta.range_deletes" => range_deletes_block = Some(entry.handle),
+                "meta.key_sketch" => key_sketch_block = Some(entry.handle),
                 _ => return Err(SSTableError::Internal("Unexpected match".into())),
             }
         }
 
-        let bloom = if let Some(bh) = bloom_block {
-            let bloom_bytes = Self::read_block_bytes(&mmap, &bh)?;
-            let (bloom, _) = encoding::decode_from_slice::<SSTableBloomBlock>(&bloom_bytes)
-                .map_err(|e| SSTableError::Internal(e.to_string()))?;
-            bloom
-        } else {
-            let bloom: Bloom<Vec<u8>> =
-                Bloom::new_for_fp_rate(1, SST_BLOOM_FILTER_FALSE_POSITIVE_RATE)
-                    .map_err(|e| SSTableError::Internal(e.to_string()))?;
-            SSTableBloomBlock {
-                data: bloom.as_slice().to_vec(),
-            }
-        };
+        // With `FooterOnly`, the bloom filter and key sketch are left for
+        // first use.
+        let bloom = OnceLock::new();
+        let key_sketch = OnceLock::new();
+        if verify {
+            let bloom_bytes = bloom_block
+                .as_ref()
+                .map(|bh| Self::read_block_bytes(&mmap, bh, true))
+                .transpose()?;
+            let _ = bloom.set(Self::decode_bloom(bloom_bytes.as_deref())?);
+
+            let sketch = match &key_sketch_block {
+                Some(kh) => {
+                    let kbytes = Self::read_block_bytes(&mmap, kh, true)?;
+                    match Self::decode_key_sketch(&kbytes) {
+                        Ok(sketch) => Some(sketch),
+                        Err(e) => {
+                            // Checksum-valid but of another precision:
+                            // estimate without it rather than refuse the
+                            // table.
+                            warn!(?path, error = %e, "ignoring unreadable key sketch");
+                            None
+                        }
+                    }
+                }
+                None => None,
+            };
+            let _ = key_sketch.set(sketch);
+        }
 
         let properties = if let Some(pb) = properties_block {
-            let pbytes = Self::read_block_bytes(&mmap, &pb)?;
+            let pbytes = Self::read_block_bytes(&mmap, &pb, verify)?;
             let (properties, _) = encoding::decode_from_slice::<SSTablePropertiesBlock>(&pbytes)?;
             properties
         } else {
@@ -658,33 +1223,81 @@ impl SSTable {
         };
 
         let range_deletes = if let Some(rh) = range_deletes_block {
-            let rbytes = Self::read_block_bytes(&mmap, &rh)?;
+            let rbytes = Self::read_block_bytes(&mmap, &rh, verify)?;
             let (ranges, _) = encoding::decode_vec::<SSTableRangeTombstoneCell>(&rbytes)?;
             SSTableRangeTombstoneDataBlock { data: ranges }
         } else {
             SSTableRangeTombstoneDataBlock { data: Vec::new() }
         };
 
-        let index_bytes = Self::read_block_bytes(&mmap, &footer.index)?;
-        let (index_entries, _) = encoding::decode_vec::<SSTableIndexEntry>(&index_bytes)?;
+        let index_bytes = Self::read_block_bytes(&mmap, &footer.index, verify)?;
+        let index_entries = if header.version == SST_HDR_VERSION_V1 {
+            let (entries, _) = encoding::decode_vec::<SSTableIndexEntryV1>(&index_bytes)?;
+            entries.into_iter().map(SSTableIndexEntry::from).collect()
+        } else {
+            let (entries, _) = encoding::decode_vec::<SSTableIndexEntry>(&index_bytes)?;
+            entries
+        };
 
-        info!(
-            ?path,
-            file_size = footer.total_file_size,
-            record_count = properties.record_count,
-            "SSTable opened"
-        );
+        if checks == OpenChecks::FullBlockChecksums {
+            verify_file_digest(&mmap)?;
+        }
 
-        Ok(Self {
+        let sstable = Self {
             id: 0,
-            mmap,
+            file: Arc::new(SstFile::new(file, mmap)),
             header,
             bloom,
+            bloom_handle: bloom_block,
             properties,
             range_deletes,
+            key_sketch,
+            key_sketch_handle: key_sketch_block,
             index: index_entries,
             footer,
-        })
+            reads: ReadCounters::default(),
+            tombstone_runs: TombstoneRuns::default(),
+            access: SstAccess::default(),
+        };
+
+        if checks == OpenChecks::FullBlockChecksums {
+            sstable.verify_data_blocks()?;
+        }
+
+        info!(
+            ?path,
+            file_size = sstable.footer.total_file_size,
+            record_count = sstable.properties.record_count,
+            "SSTable opened"
+        );
+
+        Ok(sstable)
+    }
+
+    /// Decodes a bloom filter block, or builds an empty filter for a table
+    /// written without one.
+    fn decode_bloom(bytes: Option<&[u8]>) -> Result<SSTableBloomBlock, SSTableError> {
+        match bytes {
+            Some(bytes) => {
+                let (bloom, _) = encoding::decode_from_slice::<SSTableBloomBlock>(bytes)
+                    .map_err(|e| SSTableError::Internal(e.to_string()))?;
+                Ok(bloom)
+            }
+            None => {
+                let bloom: Bloom<Vec<u8>> =
+                    Bloom::new_for_fp_rate(1, SST_BLOOM_FILTER_FALSE_POSITIVE_RATE)
+                        .map_err(|e| SSTableError::Internal(e.to_string()))?;
+                Ok(SSTableBloomBlock {
+                    data: bloom.as_slice().to_vec(),
+                })
+            }
+        }
+    }
+
+    /// Decodes a key sketch block.
+    fn decode_key_sketch(bytes: &[u8]) -> Result<KeySketch, SSTableError> {
+        let (sketch, _) = encoding::decode_from_slice::<KeySketch>(bytes)?;
+        Ok(sketch)
     }
 
     /// Performs a **single-SST lookup** of a key.
@@ -704,7 +1317,7 @@ impl SSTable {
     ///    Binary search on separator keys.
     ///
     /// 4. **Search inside the block**
-    ///    Using `BlockIterator`, seek to the key and collect the newest version.
+    ///    Walk the block's cells in place and collect the newest version.
     ///
     /// 5. **Merge point entries with range tombstone**
     ///    Range deletes override older point entries.
@@ -723,12 +1336,53 @@ impl SSTable {
     /// - Primary: LSN
     /// - Secondary: timestamp (tie-breaking)
     pub fn get(&self, key: &[u8]) -> Result<GetResult, SSTableError> {
+        self.get_opt(key, true)
+    }
+
+    /// Same as [`get`](Self::get), but lets the caller skip CRC32
+    /// verification of the data block that is read.
+    ///
+    /// Metadata blocks (index, bloom, properties, range tombstones) are
+    /// verified when the SSTable is opened, as chosen by [`OpenChecks`];
+    /// `verify_checksums` only affects data blocks read by this lookup.
+    ///
+    /// A value whose cell carries a checksum is verified against it along
+    /// with the block.
+    pub fn get_opt(&self, key: &[u8], verify_checksums: bool) -> Result<GetResult, SSTableError> {
+        Ok(self
+            .get_ref(
+                key,
+                verify_checksums,
+                verify_checksums,
+                &mut SstProbe::default(),
+            )?
+            .map_value(FileBytes::into_vec))
+    }
+
+    /// Same as [`get_opt`](Self::get_opt), but a `Put` value is returned as
+    /// [`FileBytes`] — a range of this SSTable's mmap rather than an owned
+    /// copy, unless the table is read with `pread`.
+    ///
+    /// A mapped data block is walked in place — no block or value bytes
+    /// are copied. What the lookup did (bloom check, block read) is written
+    /// to `probe`. With `verify_value_checksum`, a `Put` whose cell carries
+    /// a value checksum fails with [`SSTableError::ValueChecksumMismatch`]
+    /// if the value no longer matches it.
+    pub(crate) fn get_ref(
+        &self,
+        key: &[u8],
+        verify_checksums: bool,
+        verify_value_checksum: bool,
+        probe: &mut SstProbe,
+    ) -> Result<GetResult<FileBytes>, SSTableError> {
         // 1) Check range tombstones first
         let range_info = self.covering_range_for_key(key);
 
         // 2) Bloom filter check (only point keys)
-        let bloom_maybe_present = if !self.bloom.data.is_empty() {
-            match Bloom::from_slice(&self.bloom.data) {
+        let bloom = self.bloom();
+        let bloom_maybe_present = if !bloom.data.is_empty() {
+            probe.bloom_checked = true;
+            match Bloom::from_slice(&bloom.data) {
                 Ok(bloom) => bloom.check(key),
                 Err(_) => true, // corrupted bloom → fallback to full search
             }
@@ -737,6 +1391,7 @@ impl SSTable {
         };
 
         if !bloom_maybe_present {
+            probe.bloom_negative = true;
             return Ok(match range_info {
                 Some((lsn, timestamp)) => GetResult::RangeDelete { lsn, timestamp },
                 None => GetResult::NotFound,
@@ -754,42 +1409,79 @@ impl SSTable {
         let block_idx = self.find_block_for_key(key);
         let entry = &self.index[block_idx];
 
-        let raw = Self::read_block_bytes(&self.mmap, &entry.handle)?;
-        let (block, _) = encoding::decode_from_slice::<SSTableDataBlock>(&raw)?;
+        let block = self.read_block(&entry.handle, verify_checksums)?;
+        probe.block_read = true;
+
+        // The block content is an encoded `SSTableDataBlock`: a u32 length
+        // prefix followed by the cell bytes.
+        let (data_len, prefix_len) = encoding::decode_from_slice::<u32>(&block)?;
+        let data_start = prefix_len;
+        let data_end = data_start + data_len as usize;
+        if data_end > block.len() {
+            return Err(SSTableError::Internal(
+                "Data block exceeds block bounds".into(),
+            ));
+        }
+
+        // 4) Walk cells in place (point keys), keeping the newest version
+        let mut latest: Option<GetResult<FileBytes>> = None;
+        let mut cursor = data_start;
+
+        while cursor < data_end {
+            let (cell, cell_len) =
+                match encoding::decode_from_slice::<SSTableCell>(&block[cursor..data_end]) {
+                    Ok(decoded) => decoded,
+                    Err(e) => {
+                        tracing::warn!(cursor, ?e, "decode error during get");
+                        break;
+                    }
+                };
 
-        // 4) Scan block using BlockIterator (point keys)
-        let mut iter = BlockIterator::new(block.data);
-        iter.seek_to(key);
-        let mut latest: Option<GetResult> = None;
+            let key_start = cursor + cell_len;
+            let value_start = key_start + cell.key_len as usize;
+            let value_end = value_start + cell.value_len as usize;
+            if value_end > data_end {
+                // truncated -> treat as end
+                break;
+            }
+            cursor = value_end;
 
-        for item in iter {
-            if item.key != key {
+            let cell_key = &block[key_start..value_start];
+            if cell_key < key {
+                continue;
+            }
+            if cell_key > key {
                 break;
             }
+            probe.key_in_block = true;
 
-            let candidate = if item.is_delete {
+            let candidate = if cell.is_delete {
                 GetResult::Delete {
-                    lsn: item.lsn,
-                    timestamp: item.timestamp,
+                    lsn: cell.lsn,
+                    timestamp: cell.timestamp,
                 }
             } else {
+                if verify_value_checksum
+                    && let Some(value_crc) = cell.value_crc
+                    && crc32(&block[value_start..value_end]) != value_crc
+                {
+                    tracing::error!(lsn = cell.lsn, "value checksum mismatch during get");
+                    return Err(SSTableError::ValueChecksumMismatch);
+                }
                 GetResult::Put {
-                    value: item.value.to_vec(),
-                    lsn: item.lsn,
-                    timestamp: item.timestamp,
+                    value: block.slice(value_start..value_end),
+                    lsn: cell.lsn,
+                    timestamp: cell.timestamp,
                 }
             };
 
-            latest = Some(match &latest {
-                Some(existing) if candidate.is_newer_than(existing.lsn(), existing.timestamp()) => {
-                    candidate
-                }
-                Some(existing) => existing.clone(),
-                None => candidate,
+            latest = Some(match latest {
+                Some(existing) if candidate.rank() <= existing.rank() => existing,
+                _ => candidate,
             });
         }
 
-        // 5) Merge point vs range tombstone (LSN + timestamp)
+        // 5) Merge point vs range tombstone (see `VersionRank`)
         let range_delete =
             range_info.map(|(lsn, timestamp)| GetResult::RangeDelete { lsn, timestamp });
 
@@ -798,7 +1490,7 @@ impl SSTable {
             (Some(r), None) => Ok(r),
             (None, Some(rd)) => Ok(rd),
             (Some(point), Some(rd)) => {
-                if rd.is_newer_than(point.lsn(), point.timestamp()) {
+                if rd.rank() > point.rank() {
                     Ok(rd)
                 } else {
                     Ok(point)
@@ -807,6 +1499,46 @@ impl SSTable {
         }
     }
 
+    /// Checks, for each of `keys` in ascending order, whether
+    /// [`get`](Self::get) would find anything for it — a point entry or
+    /// a covering range tombstone. The bloom filter is not consulted.
+    ///
+    /// Equivalent to one `get` per key, but sweeps the index once and
+    /// reads each data block at most once however many keys fall into it.
+    /// Returns the answers in key order and the number of data blocks
+    /// read.
+    pub(crate) fn contains_sorted(&self, keys: &[&[u8]]) -> Result<(Vec<bool>, u64), SSTableError> {
+        let mut found = Vec::with_capacity(keys.len());
+        let mut block_reads = 0;
+        let mut block: Option<(usize, BlockIterator)> = None;
+        for &key in keys {
+            if self.covering_range_for_key(key).is_some() {
+                found.push(true);
+                continue;
+            }
+            if self.index.is_empty() {
+                found.push(false);
+                continue;
+            }
+
+            let block_idx = self.find_block_for_key(key);
+            let cells = match block.take() {
+                Some((idx, cells)) if idx == block_idx => cells,
+                _ => {
+                    let bytes = self.read_block(&self.index[block_idx].handle, true)?;
+                    block_reads += 1;
+                    let (data, _) = encoding::decode_from_slice::<SSTableDataBlock>(&bytes)?;
+                    BlockIterator::new(data.data)
+                }
+            };
+            let cells = &mut block.insert((block_idx, cells)).1;
+            // Keys ascend, so the cursor only ever moves forward.
+            cells.skip_to(key);
+            found.push(cells.peek_key() == Some(key));
+        }
+        Ok((found, block_reads))
+    }
+
     /// Returns a range-scan iterator over this SSTable.
     ///
     /// The iterator yields **raw MVCC entries** (Put/Delete/RangeDelete) in key order.
@@ -835,26 +1567,230 @@ impl SSTable {
         ScanIterator::new(self, start_key.to_vec(), end_key.to_vec())
     }
 
+    /// Returns a key no greater than any record a scan starting at
+    /// `start` yields: the first point key at or after `start`, or the
+    /// start of an overlapping range tombstone if that is smaller.
+    pub fn scan_lower_bound<'a>(&'a self, start: &'a [u8]) -> &'a [u8] {
+        let points = (self.record_count() > 0).then(|| self.min_key().max(start));
+        let ranges = self
+            .range_deletes
+            .data
+            .iter()
+            .filter(|rd| below_end(start, &rd.end_key))
+            .map(|rd| rd.start_key.as_slice())
+            .min();
+        points.into_iter().chain(ranges).min().unwrap_or(start)
+    }
+
     /// Creates a scan iterator that **owns** the SSTable via `Arc`.
     ///
     /// Unlike [`scan`](Self::scan), the returned iterator is `'static` — it
     /// keeps the SSTable alive through the `Arc` and does not borrow from
-    /// any lock guard.  Used by the MVCC snapshot scan path in
-    /// [`Engine::raw_scan`](crate::engine::Engine).
+    /// any lock guard. The MVCC snapshot scan path in
+    /// [`Engine::raw_scan`](crate::engine::Engine) uses the deferred
+    /// [`scan_owned_lazy`](Self::scan_owned_lazy).
     pub fn scan_owned(
         this: &Arc<Self>,
         start_key: &[u8],
         end_key: &[u8],
     ) -> Result<ScanIterator<Arc<SSTable>>, SSTableError> {
-        ScanIterator::new(Arc::clone(this), start_key.to_vec(), end_key.to_vec())
+        Self::scan_owned_opt(this, start_key, end_key, true)
+    }
+
+    /// Same as [`scan_owned`](Self::scan_owned), but lets the caller skip
+    /// CRC32 verification of every data block the iterator loads.
+    pub fn scan_owned_opt(
+        this: &Arc<Self>,
+        start_key: &[u8],
+        end_key: &[u8],
+        verify_checksums: bool,
+    ) -> Result<ScanIterator<Arc<SSTable>>, SSTableError> {
+        ScanIterator::with_checksums(
+            Arc::clone(this),
+            start_key.to_vec(),
+            end_key.to_vec(),
+            verify_checksums,
+        )
+    }
+
+    /// Same as [`scan_owned_opt`](Self::scan_owned_opt), but skips the data
+    /// blocks whose entries were all written before `min_timestamp`.
+    pub fn scan_owned_since(
+        this: &Arc<Self>,
+        start_key: &[u8],
+        end_key: &[u8],
+        verify_checksums: bool,
+        min_timestamp: u64,
+    ) -> Result<ScanIterator<Arc<SSTable>>, SSTableError> {
+        ScanIterator::since(
+            Arc::clone(this),
+            start_key.to_vec(),
+            end_key.to_vec(),
+            verify_checksums,
+            min_timestamp,
+        )
+    }
+
+    /// Same as [`scan_owned_since`](Self::scan_owned_since), but reads
+    /// nothing until the first record is requested; see
+    /// [`LazyScanIterator`].
+    pub fn scan_owned_lazy(
+        this: &Arc<Self>,
+        start_key: &[u8],
+        end_key: &[u8],
+        verify_checksums: bool,
+        min_timestamp: u64,
+    ) -> Result<LazyScanIterator, SSTableError> {
+        LazyScanIterator::new(
+            Arc::clone(this),
+            start_key.to_vec(),
+            end_key.to_vec(),
+            verify_checksums,
+            min_timestamp,
+        )
+    }
+
+    /// Reads every data block that may hold keys in `[start, end)` — to
+    /// the last block when `end` is `None` — so that the OS page cache
+    /// holds them before the first lookup does.
+    ///
+    /// Checksums are verified on the way, which touches every page of
+    /// each block. The index needs no warm-up: it is decoded into memory
+    /// by [`open`](Self::open), as is the bloom filter unless the table
+    /// was opened with [`OpenChecks::FooterOnly`].
+    ///
+    /// Returns the number of bytes read.
+    pub fn warm_up(&self, start: &[u8], end: Option<&[u8]>) -> Result<u64, SSTableError> {
+        let mut bytes = 0;
+        for entry in &self.index[self.find_block_for_key(start)..] {
+            if end.is_some_and(|end| entry.separator_key.as_slice() >= end) {
+                break;
+            }
+            self.read_block(&entry.handle, true)?;
+            bytes += entry.handle.size;
+        }
+        Ok(bytes)
+    }
+
+    /// Verifies the checksum of every data block.
+    ///
+    /// [`open`](Self::open) verifies only the metadata blocks; a damaged
+    /// data block otherwise surfaces on the first read that reaches it,
+    /// unless the table was opened with
+    /// [`OpenChecks::FullBlockChecksums`].
+    pub(crate) fn verify_data_blocks(&self) -> Result<(), SSTableError> {
+        for entry in &self.index {
+            self.read_block(&entry.handle, true)?;
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if this SSTable is read through a memory map rather
+    /// than with `pread`.
+    pub fn is_mapped(&self) -> bool {
+        self.file.is_mapped()
+    }
+
+    /// Advises the OS to read the data blocks into the page cache
+    /// (`MADV_WILLNEED`), for a table just written that reads will reach
+    /// soon. The index and bloom filter need no advice: [`open`](Self::open)
+    /// has already read them. A no-op if the table is read with `pread`,
+    /// and off Unix.
+    pub(crate) fn advise_will_need(&self) {
+        if let (Some(first), Some(last)) = (self.index.first(), self.index.last()) {
+            let start = first.handle.offset as usize;
+            let end = (last.handle.offset + last.handle.size) as usize;
+            self.file.will_need(start..end);
+        }
+    }
+
+    /// Drops this SSTable's memory map for good, once its file is deleted,
+    /// so its pages stop counting toward the process RSS while scans over
+    /// it finish; those read the rest with `pread`.
+    pub(crate) fn retire(&self) {
+        self.file.retire();
+    }
+
+    /// Charges this SSTable's memory map to `budget`, which may demote it
+    /// or other tables to `pread` access.
+    pub(crate) fn attach_budget(&self, budget: &Arc<MmapBudget>) {
+        budget.attach(&self.file);
+    }
+
+    /// Reads the content of the block referenced by a [`BlockHandle`] and,
+    /// when `verify_checksum` is set, verifies its CRC32.
+    ///
+    /// Served from the mmap without copying, or with `pread` if the table
+    /// is not mapped.
+    pub(crate) fn read_block(
+        &self,
+        handle: &BlockHandle,
+        verify_checksum: bool,
+    ) -> Result<FileBytes, SSTableError> {
+        slow_op::record_block_read();
+        if let Some(map) = self.file.map() {
+            let range = Self::block_content_range(&map, handle, verify_checksum)?;
+            return Ok(FileBytes::Mapped { map, range });
+        }
+
+        // Read the whole block; handles of metadata blocks count only the
+        // content, so the rest is read once the length prefix is known.
+        let start = usize::try_from(handle.offset)
+            .map_err(|_| SSTableError::Internal("block offset exceeds addressable range".into()))?;
+        let size = usize::try_from(handle.size)
+            .map_err(|_| SSTableError::Internal("block size exceeds addressable range".into()))?;
+        let mut block = self.read_at(start, size.max(SST_DATA_BLOCK_LEN_SIZE))?;
+        let content_len = u32::from_le_bytes(
+            block[..SST_DATA_BLOCK_LEN_SIZE]
+                .try_into()
+                .map_err(|_| SSTableError::Internal("Short block length".into()))?,
+        ) as usize;
+        let full = SST_DATA_BLOCK_LEN_SIZE + content_len + SST_DATA_BLOCK_CHECKSUM_SIZE;
+        if full > block.len() {
+            block = self.read_at(start, full)?;
+        }
+
+        let whole = BlockHandle {
+            offset: 0,
+            size: block.len() as u64,
+        };
+        let content = Self::block_content_range(&block, &whole, verify_checksum)?;
+        block.truncate(content.end);
+        block.drain(..content.start);
+        Ok(FileBytes::Owned(block))
     }
 
-    /// Reads a block referenced by a [`BlockHandle`] from the mmap and verifies
-    /// its checksum.
-    pub(crate) fn read_block_bytes(
-        mmap: &Mmap,
+    /// Reads `len` bytes at `start` with `pread`.
+    fn read_at(&self, start: usize, len: usize) -> Result<Vec<u8>, SSTableError> {
+        match start.checked_add(len) {
+            Some(end) if end <= self.file.len() => Ok(self.file.read_at(start..end)?),
+            _ => Err(SSTableError::Internal("Block out of range".into())),
+        }
+    }
+
+    /// Reads a block referenced by a [`BlockHandle`] from the mapped bytes
+    /// of an SSTable being opened and, when `verify_checksum` is set,
+    /// verifies its CRC32.
+    fn read_block_bytes(
+        mmap: &[u8],
         handle: &BlockHandle,
+        verify_checksum: bool,
     ) -> Result<Vec<u8>, SSTableError> {
+        let content = Self::block_content_range(mmap, handle, verify_checksum)?;
+        Ok(mmap[content].to_vec())
+    }
+
+    /// Locates the content of a block referenced by a [`BlockHandle`]
+    /// within `mmap` — the whole file, or the block read on its own at
+    /// offset 0 — and, when `verify_checksum` is set, verifies its CRC32.
+    ///
+    /// Returns the byte range of the block content (without the length
+    /// prefix and trailing checksum).
+    fn block_content_range(
+        mmap: &[u8],
+        handle: &BlockHandle,
+        verify_checksum: bool,
+    ) -> Result<Range<usize>, SSTableError> {
         let start = usize::try_from(handle.offset)
             .map_err(|_| SSTableError::Internal("block offset exceeds addressable range".into()))?;
         let size = usize::try_from(handle.size)
@@ -877,7 +1813,7 @@ impl SSTable {
             return Err(SSTableError::Internal("Block out of range".into()));
         }
 
-        let content = &mmap[cursor..cursor + content_len];
+        let content = cursor..cursor + content_len;
         cursor += content_len;
 
         let checksum_bytes: [u8; SST_DATA_BLOCK_CHECKSUM_SIZE] = mmap
@@ -886,15 +1822,17 @@ impl SSTable {
             .map_err(|_| SSTableError::Internal("Short checksum".into()))?;
         let stored_checksum = u32::from_le_bytes(checksum_bytes);
 
-        let mut hasher = Crc32::new();
-        hasher.update(content);
-        let computed_checksum = hasher.finalize();
+        if verify_checksum {
+            let mut hasher = Crc32::new();
+            hasher.update(&mmap[content.clone()]);
+            let computed_checksum = hasher.finalize();
 
-        if computed_checksum != stored_checksum {
-            return Err(SSTableError::ChecksumMismatch);
+            if computed_checksum != stored_checksum {
+                return Err(SSTableError::ChecksumMismatch);
+            }
         }
 
-        Ok(content.to_vec())
+        Ok(content)
     }
 
     /// Locates the index entry whose block may contain the given `key`.
@@ -916,24 +1854,19 @@ impl SSTable {
         }
     }
 
-    /// Returns the newest (highest LSN, then highest timestamp) range tombstone
-    /// that covers the given `key`, if any.
+    /// Returns the LSN and timestamp of the highest-ranked range tombstone
+    /// (see [`VersionRank`]) that covers the given `key`, if any.
     fn covering_range_for_key(&self, key: &[u8]) -> Option<(u64, u64)> {
-        let mut res: Option<(u64, u64)> = None;
-        for rd in &self.range_deletes.data {
-            if key >= rd.start_key.as_slice() && key < rd.end_key.as_slice() {
-                res = Some(match res {
-                    Some((prev_lsn, prev_ts)) => {
-                        if rd.lsn > prev_lsn || (rd.lsn == prev_lsn && rd.timestamp > prev_ts) {
-                            (rd.lsn, rd.timestamp)
-                        } else {
-                            (prev_lsn, prev_ts)
-                        }
-                    }
-                    None => (rd.lsn, rd.timestamp),
-                });
-            }
-        }
-        res
+        self.range_deletes
+            .data
+            .iter()
+            .filter(|rd| key >= rd.start_key.as_slice() && below_end(key, &rd.end_key))
+            .map(|rd| VersionRank {
+                lsn: rd.lsn,
+                point: false,
+                timestamp: rd.timestamp,
+            })
+            .max()
+            .map(|rank| (rank.lsn, rank.timestamp))
     }
 }
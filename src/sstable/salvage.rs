@@ -0,0 +1,234 @@
+//! Salvage of damaged SSTable files.
+//!
+//! [`SSTable::open`](super::SSTable::open) rejects a file whose header,
+//! footer, or any metadata block fails its checksum, and a damaged data
+//! block fails every read that reaches it. [`salvage`] instead walks the
+//! file block by block from the header on, keeping every block whose
+//! CRC32 still matches:
+//!
+//! - Blocks are laid out back to back, so after a damaged block the walk
+//!   resumes at the next offset where a checksum-valid block starts —
+//!   directly after the damaged one if its length prefix survived.
+//! - The metaindex is taken from the footer, or, if the footer is
+//!   damaged, from the last block that decodes as one. It locates the
+//!   range tombstones and marks where the data blocks end.
+//! - Without a metaindex, data blocks are the leading blocks whose cells
+//!   decode exactly and in key order.
+//!
+//! The result is fed to [`SstWriter`](super::SstWriter) to produce a fresh,
+//! valid table.
+
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::encoding;
+use crate::engine::{PointEntry, RangeTombstone};
+
+use super::{
+    MetaIndexEntry, SST_DATA_BLOCK_CHECKSUM_SIZE, SST_DATA_BLOCK_LEN_SIZE, SST_HDR_SIZE,
+    SSTableCell, SSTableDataBlock, SSTableError, SSTableFooter, SSTableRangeTombstoneCell, crc32,
+};
+
+/// Metaindex names written by the builder.
+const META_NAMES: [&str; 4] = [
+    "filter.bloom",
+    "meta.key_sketch",
+    "meta.properties",
+    "meta.range_deletes",
+];
+
+/// Entries recovered from a damaged SSTable by [`salvage`].
+#[derive(Debug, Default)]
+pub(crate) struct Salvaged {
+    /// Point entries of every readable data block, in `(key ASC, LSN DESC)`
+    /// order.
+    pub points: Vec<PointEntry>,
+
+    /// Range tombstones, sorted by start key.
+    pub ranges: Vec<RangeTombstone>,
+
+    /// Data blocks whose entries were recovered.
+    pub data_blocks: usize,
+
+    /// Contiguous stretches of the file that held no readable block.
+    pub damaged_regions: usize,
+
+    /// Whether the range-tombstone block was found and read. When `false`
+    /// the table may have held range tombstones that are now lost.
+    pub ranges_intact: bool,
+}
+
+/// Recovers every entry still readable from the SSTable at `path`.
+///
+/// # Errors
+///
+/// Only I/O errors reading the file; damage is reported in the result.
+pub(crate) fn salvage(path: &Path) -> Result<Salvaged, SSTableError> {
+    let bytes = fs::read(path)?;
+    let footer = SSTableFooter::read(&bytes).ok();
+    let end = footer
+        .as_ref()
+        .map_or(bytes.len(), |footer| bytes.len() - footer.encoded_len());
+
+    // 1. Every checksum-valid block, resyncing past damaged stretches.
+    let mut blocks: Vec<(usize, Range<usize>)> = Vec::new();
+    let mut salvaged = Salvaged::default();
+    let mut pos = SST_HDR_SIZE + SST_DATA_BLOCK_CHECKSUM_SIZE; // header + its outer CRC
+    while pos < end {
+        if let Some(content) = block_at(&bytes, pos, end) {
+            let next = content.end + SST_DATA_BLOCK_CHECKSUM_SIZE;
+            blocks.push((pos, content));
+            pos = next;
+            continue;
+        }
+        salvaged.damaged_regions += 1;
+        pos = resync(&bytes, pos, end);
+    }
+
+    // 2. The metaindex: from the footer, else the last block that decodes
+    //    as one.
+    let metaindex = footer
+        .and_then(|footer| {
+            let offset = usize::try_from(footer.metaindex.offset).ok()?;
+            decode_metaindex(&bytes[block_at(&bytes, offset, end)?])
+        })
+        .or_else(|| {
+            blocks
+                .iter()
+                .rev()
+                .find_map(|(_, content)| decode_metaindex(&bytes[content.clone()]))
+        });
+
+    // 3. Range tombstones, located through the metaindex.
+    let mut meta_start = usize::MAX;
+    if let Some(entries) = &metaindex {
+        for entry in entries {
+            let Ok(offset) = usize::try_from(entry.handle.offset) else {
+                continue;
+            };
+            meta_start = meta_start.min(offset);
+            if entry.name != "meta.range_deletes" {
+                continue;
+            }
+            if let Some(content) = block_at(&bytes, offset, end)
+                && let Ok((cells, _)) =
+                    encoding::decode_vec::<SSTableRangeTombstoneCell>(&bytes[content])
+            {
+                salvaged.ranges = cells
+                    .into_iter()
+                    .map(|rd| RangeTombstone {
+                        start: rd.start_key,
+                        end: rd.end_key,
+                        lsn: rd.lsn,
+                        timestamp: rd.timestamp,
+                    })
+                    .collect();
+                salvaged.ranges_intact = true;
+            }
+        }
+    }
+
+    // 4. Data blocks: those before the metadata blocks. Without a
+    //    metaindex, the leading blocks that decode as data.
+    for (offset, content) in blocks {
+        if offset >= meta_start {
+            break;
+        }
+        match decode_data_block(&bytes[content]) {
+            Some(points) => {
+                salvaged.points.extend(points);
+                salvaged.data_blocks += 1;
+            }
+            None if metaindex.is_none() => break,
+            None => salvaged.damaged_regions += 1,
+        }
+    }
+
+    salvaged
+        .points
+        .sort_by(|a, b| a.key.cmp(&b.key).then(b.lsn.cmp(&a.lsn)));
+    salvaged.ranges.sort_by(|a, b| a.start.cmp(&b.start));
+    Ok(salvaged)
+}
+
+/// Returns the content range of the block starting at `pos` if it ends
+/// before `end` and its CRC32 matches. Empty blocks are never written, so
+/// zero-filled stretches — whose checksum would match — are rejected.
+fn block_at(bytes: &[u8], pos: usize, end: usize) -> Option<Range<usize>> {
+    let len = block_len(bytes, pos)?;
+    let start = pos + SST_DATA_BLOCK_LEN_SIZE;
+    let content_end = start.checked_add(len)?;
+    let crc_end = content_end.checked_add(SST_DATA_BLOCK_CHECKSUM_SIZE)?;
+    if len == 0 || crc_end > end {
+        return None;
+    }
+    let stored = u32::from_le_bytes(bytes[content_end..crc_end].try_into().ok()?);
+    (crc32(&bytes[start..content_end]) == stored).then_some(start..content_end)
+}
+
+/// Reads the length prefix of a block starting at `pos`.
+fn block_len(bytes: &[u8], pos: usize) -> Option<usize> {
+    let prefix = bytes.get(pos..pos.checked_add(SST_DATA_BLOCK_LEN_SIZE)?)?;
+    Some(u32::from_le_bytes(prefix.try_into().ok()?) as usize)
+}
+
+/// Returns the next offset after the damaged block at `pos` where a
+/// checksum-valid block starts, or `end`.
+fn resync(bytes: &[u8], pos: usize, end: usize) -> usize {
+    // Only the content is damaged: the next block follows the claimed length.
+    if let Some(next) = block_len(bytes, pos).and_then(|len| {
+        pos.checked_add(SST_DATA_BLOCK_LEN_SIZE + len)?
+            .checked_add(SST_DATA_BLOCK_CHECKSUM_SIZE)
+    }) && block_at(bytes, next, end).is_some()
+    {
+        return next;
+    }
+    (pos + 1..end)
+        .find(|&p| block_at(bytes, p, end).is_some())
+        .unwrap_or(end)
+}
+
+/// Decodes `content` as a metaindex block naming only known blocks.
+fn decode_metaindex(content: &[u8]) -> Option<Vec<MetaIndexEntry>> {
+    let (entries, used) = encoding::decode_vec::<MetaIndexEntry>(content).ok()?;
+    let known = !entries.is_empty()
+        && entries
+            .iter()
+            .all(|entry| META_NAMES.contains(&entry.name.as_str()));
+    (known && used == content.len()).then_some(entries)
+}
+
+/// Decodes `content` as a data block. Returns `None` unless every cell
+/// decodes, the cells fill the block exactly, and keys are in order.
+fn decode_data_block(content: &[u8]) -> Option<Vec<PointEntry>> {
+    let (block, used) = encoding::decode_from_slice::<SSTableDataBlock>(content).ok()?;
+    if used != content.len() || block.data.is_empty() {
+        return None;
+    }
+
+    let data = &block.data;
+    let mut points: Vec<PointEntry> = Vec::new();
+    let mut cursor = 0;
+    while cursor < data.len() {
+        let (cell, cell_len) = encoding::decode_from_slice::<SSTableCell>(&data[cursor..]).ok()?;
+        let key_start = cursor + cell_len;
+        let value_start = key_start.checked_add(cell.key_len as usize)?;
+        let value_end = value_start.checked_add(cell.value_len as usize)?;
+        if value_end > data.len() || (cell.is_delete && cell.value_len != 0) {
+            return None;
+        }
+        let key = data[key_start..value_start].to_vec();
+        if points.last().is_some_and(|prev| prev.key > key) {
+            return None;
+        }
+        points.push(PointEntry {
+            key,
+            value: (!cell.is_delete).then(|| data[value_start..value_end].to_vec()),
+            lsn: cell.lsn,
+            timestamp: cell.timestamp,
+        });
+        cursor = value_end;
+    }
+    Some(points)
+}
@@ -1,14 +1,19 @@
 mod tests_basic;
 mod tests_edge_cases;
+mod tests_footer;
 mod tests_get;
+mod tests_mmap_budget;
+mod tests_open_checks;
 mod tests_scan;
 mod tests_scan_owned;
 
 // Priority 2 — robustness tests
 mod tests_corruption;
+mod tests_salvage;
 
 // Priority 3 — hardening (edge cases)
 mod tests_hardening;
 
 // Priority 4 — coverage
+mod tests_key_sketch;
 mod tests_properties;
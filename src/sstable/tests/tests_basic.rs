@@ -10,6 +10,7 @@
 //! - Rejection of empty iterators (no data at all)
 //! - Range-deletes-only SSTable (no point entries)
 //! - Points-only SSTable (no range tombstones)
+//! - Bloom filter sized from distinct keys, not versions
 //!
 //! ## See also
 //! - [`tests_get`]  — intra-SSTable `get()` with LSN resolution
@@ -74,7 +75,7 @@ mod tests {
     /// 2. `SSTable::open` the resulting file.
     ///
     /// # Expected behavior
-    /// - Header: magic = `SST0`, version = 1.
+    /// - Header: magic = `SST0`, version = 2.
     /// - Properties: 4 records, 1 tombstone, 2 range tombstones;
     ///   correct min/max key/LSN/timestamp.
     /// - Range-delete block contains both tombstones.
@@ -124,7 +125,7 @@ mod tests {
 
         // --- HEADER CHECKS ---
         assert_eq!(sstable.header.magic, *b"SST0");
-        assert_eq!(sstable.header.version, 1);
+        assert_eq!(sstable.header.version, 3);
 
         // --- PROPERTIES CHECKS ---
         let props = &sstable.properties;
@@ -157,7 +158,7 @@ mod tests {
         }
 
         // --- BLOOM FILTER CHECK ---
-        let bloom_block = &sstable.bloom;
+        let bloom_block = sstable.bloom();
         assert!(!bloom_block.data.is_empty());
 
         // This confirms that bloom filter was populated
@@ -199,7 +200,7 @@ mod tests {
         let tmp = TempDir::new().unwrap();
         let sstable_path = tmp.path().join("sstable_empty.bin");
 
-        let points = vec![];
+        let points: Vec<PointEntry> = vec![];
         let ranges = vec![];
 
         let pt_count = points.len();
@@ -241,7 +242,7 @@ mod tests {
         let tmp = TempDir::new().unwrap();
         let sstable_path = tmp.path().join("sstable_empty.bin");
 
-        let points = vec![];
+        let points: Vec<PointEntry> = vec![];
         let ranges = vec![rdel(b"a", b"f", 30, 200), rdel(b"f", b"z", 31, 201)];
 
         let pt_count = points.len();
@@ -323,4 +324,60 @@ mod tests {
         assert_eq!(sst.properties.min_key, b"a");
         assert_eq!(sst.properties.max_key, b"c");
     }
+
+    // ----------------------------------------------------------------
+    // Bloom sizing
+    // ----------------------------------------------------------------
+
+    /// # Scenario
+    /// The bloom filter is sized from distinct keys, not from versions.
+    ///
+    /// # Starting environment
+    /// No SSTable files on disk.
+    ///
+    /// # Actions
+    /// 1. Build an SSTable with 50 keys, one version each.
+    /// 2. Build an SSTable with the same 50 keys, 40 versions each, and a
+    ///    range tombstone.
+    ///
+    /// # Expected behavior
+    /// Both bloom filters have the same size, and every key is found.
+    #[test]
+    fn bloom_sized_from_distinct_keys() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let key = |k: u32| format!("key_{k:03}").into_bytes();
+
+        let single: Vec<_> = (0..50).map(|k| point(&key(k), b"v", 1, 1)).collect();
+        let single_path = tmp.path().join("single.sst");
+        sstable::SstWriter::new(&single_path)
+            .build(single.into_iter(), 50, std::iter::empty(), 0)
+            .unwrap();
+
+        let versions: Vec<_> = (0..50)
+            .flat_map(|k| (0..40u64).rev().map(move |lsn| (k, lsn + 1)))
+            .map(|(k, lsn)| point(&key(k), b"v", lsn, lsn))
+            .collect();
+        let count = versions.len();
+        let versions_path = tmp.path().join("versions.sst");
+        sstable::SstWriter::new(&versions_path)
+            .build(
+                versions.into_iter(),
+                count,
+                std::iter::once(rdel(b"zz_a", b"zz_b", 100, 100)),
+                1,
+            )
+            .unwrap();
+
+        let single = SSTable::open(&single_path).unwrap();
+        let versions = SSTable::open(&versions_path).unwrap();
+        assert_eq!(versions.record_count(), 2000);
+        assert_eq!(single.bloom().data.len(), versions.bloom().data.len());
+
+        let bloom = Bloom::from_slice(&versions.bloom().data).expect("Bloom decode");
+        for k in 0..50 {
+            assert!(bloom.check(&key(k)));
+        }
+    }
 }
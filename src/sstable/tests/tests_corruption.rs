@@ -47,7 +47,7 @@ mod tests {
 
     // SSTable format constants (mirrors src/sstable/mod.rs).
     const SST_HDR_SIZE: usize = 12;
-    const SST_FOOTER_SIZE: usize = 44;
+    const SST_FOOTER_SIZE: usize = 120;
 
     /// Build a valid SSTable and return (path, raw_bytes).
     fn build_sst(
@@ -121,8 +121,8 @@ mod tests {
     // ================================================================
 
     /// # Scenario
-    /// Corrupt the footer CRC (last 4 bytes of the file). The footer
-    /// CRC is verified during `open()`.
+    /// Corrupt the footer CRC (the 4 bytes before the 8-byte footer
+    /// magic). The footer CRC is verified during `open()`.
     ///
     /// # Expected behavior
     /// `SSTable::open()` returns `ChecksumMismatch`.
@@ -135,7 +135,7 @@ mod tests {
         let path = build_sst(tmp.path(), "sst_footer_corrupt.sst", points, vec![]);
 
         let mut bytes = fs::read(&path).unwrap();
-        let footer_crc_offset = bytes.len() - 4; // last 4 bytes = footer CRC
+        let footer_crc_offset = bytes.len() - 12; // CRC precedes the magic
         bytes[footer_crc_offset] ^= 0xFF;
         bytes[footer_crc_offset + 1] ^= 0xFF;
         fs::write(&path, &bytes).unwrap();
@@ -233,7 +233,7 @@ mod tests {
         let sst = SSTable::open(&path).unwrap();
         // The bloom data is loaded into sst.bloom.data.
         // We need to corrupt the on-disk bloom bytes.
-        let bloom_data_len = sst.bloom.data.len();
+        let bloom_data_len = sst.bloom().data.len();
         drop(sst);
 
         if bloom_data_len > 10 {
@@ -427,4 +427,132 @@ mod tests {
             );
         }
     }
+
+    // ================================================================
+    // 8. Checksum verification can be skipped per read
+    // ================================================================
+
+    /// # Scenario
+    /// Corrupt only the stored CRC of the first data block, leaving the
+    /// block content intact.
+    ///
+    /// # Expected behavior
+    /// Verified reads (`get`, `scan_owned`) fail with `ChecksumMismatch`;
+    /// reads with verification disabled (`get_opt`, `scan_owned_opt`)
+    /// return the original data.
+    #[test]
+    fn corrupt_data_block_crc_skipped_without_verification() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let points = vec![
+            point(b"apple", b"red", 1, 100),
+            point(b"banana", b"yellow", 2, 101),
+            point(b"cherry", b"dark-red", 3, 102),
+        ];
+        let path = build_sst(tmp.path(), "sst_crc_skip.sst", points, vec![]);
+
+        // First data block: [len(4) | content | crc(4)] right after the
+        // header and its trailing 4-byte CRC.
+        let mut bytes = fs::read(&path).unwrap();
+        let block_start = SST_HDR_SIZE + 4;
+        let len_bytes: [u8; 4] = bytes[block_start..block_start + 4].try_into().unwrap();
+        let content_len = u32::from_le_bytes(len_bytes) as usize;
+        let crc_offset = block_start + 4 + content_len;
+        bytes[crc_offset] ^= 0xFF;
+        fs::write(&path, &bytes).unwrap();
+
+        let sst = std::sync::Arc::new(SSTable::open(&path).unwrap());
+
+        assert!(matches!(
+            sst.get(b"apple"),
+            Err(sstable::SSTableError::ChecksumMismatch)
+        ));
+        assert!(matches!(
+            SSTable::scan_owned(&sst, b"a", b"z"),
+            Err(sstable::SSTableError::ChecksumMismatch)
+        ));
+
+        match sst.get_opt(b"apple", false).unwrap() {
+            sstable::GetResult::Put { value, .. } => assert_eq!(value, b"red"),
+            other => panic!("expected Put, got {:?}", other),
+        }
+        let records: Vec<_> = SSTable::scan_owned_opt(&sst, b"a", b"z", false)
+            .unwrap()
+            .collect();
+        assert_eq!(records.len(), 3);
+    }
+
+    // ================================================================
+    // 9. Value checksums catch a damaged value the block CRC did not
+    // ================================================================
+
+    /// # Scenario
+    /// An SSTable written with value checksums has one value altered in
+    /// place. Lookups skip the data block CRC, standing in for a value
+    /// damaged after its block was verified.
+    ///
+    /// # Expected behavior
+    /// - A lookup verifying value checksums fails with
+    ///   `ValueChecksumMismatch`; one that does not returns the damaged
+    ///   value.
+    /// - An intact value in the same block still reads back.
+    /// - Cells of an SSTable written without value checksums decode as
+    ///   before.
+    #[test]
+    fn corrupt_value_detected_by_value_checksum() {
+        use crate::engine::read_stats::SstProbe;
+
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("sst_value_crc.sst");
+        let points = vec![
+            point(b"apple", b"red", 1, 100),
+            point(b"banana", b"yellow", 2, 101),
+        ];
+        sstable::SstWriter::new(&path)
+            .value_checksums(true)
+            .build(points.into_iter(), 2, std::iter::empty(), 0)
+            .unwrap();
+
+        let mut bytes = fs::read(&path).unwrap();
+        let at = bytes.windows(6).position(|w| w == b"yellow").unwrap();
+        bytes[at] ^= 0x01;
+        fs::write(&path, &bytes).unwrap();
+        let sst = SSTable::open(&path).unwrap();
+
+        assert!(matches!(
+            sst.get_ref(b"banana", false, true, &mut SstProbe::default()),
+            Err(sstable::SSTableError::ValueChecksumMismatch)
+        ));
+        match sst.get_opt(b"banana", false).unwrap() {
+            sstable::GetResult::Put { value, .. } => assert_eq!(value, b"xellow"),
+            other => panic!("expected Put, got {:?}", other),
+        }
+        let apple = sst
+            .get_ref(b"apple", false, true, &mut SstProbe::default())
+            .unwrap()
+            .map_value(|value| value.to_vec());
+        assert_eq!(
+            apple,
+            sstable::GetResult::Put {
+                value: b"red".to_vec(),
+                lsn: 1,
+                timestamp: 100,
+            }
+        );
+
+        let plain = build_sst(
+            tmp.path(),
+            "sst_plain.sst",
+            vec![point(b"apple", b"red", 1, 100)],
+            vec![],
+        );
+        let plain = SSTable::open(&plain).unwrap();
+        assert!(matches!(
+            plain.get(b"apple").unwrap(),
+            sstable::GetResult::Put { .. }
+        ));
+    }
 }
@@ -0,0 +1,285 @@
+//! Footer layout: magic, format version, and extension slots.
+//!
+//! ## Coverage areas
+//! - New tables end with the footer magic and name their version there
+//! - Tables with the format version 2 footer still open
+//! - Unknown extension slots are ignored; a footer version that
+//!   contradicts the header is rejected
+//! - A whole-file digest in the first extension slot verifies the file
+//!   and catches a flipped byte
+//!
+//! ## See also
+//! - [`tests_corruption`] — damaged footers
+
+#[cfg(test)]
+mod tests {
+    use crate::encoding;
+    use crate::sst;
+    use crate::sstable::{
+        self, BlockHandle, GetResult, OpenChecks, PointEntry, SST_FOOTER_DIGEST_SLOT,
+        SST_FOOTER_MAGIC, SST_FOOTER_SIZE, SST_HDR_MAGIC, SST_HDR_SIZE, SSTable, SSTableError,
+        SSTableFooter, SSTableFooterV2, SSTableHeader, SstFileDigest, crc32,
+    };
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use tempfile::TempDir;
+
+    /// Builds an SSTable of `ft_000..ft_099`.
+    fn build_sst(dir: &Path) -> PathBuf {
+        build_sst_with_digest(dir, None)
+    }
+
+    /// Builds an SSTable of `ft_000..ft_099` with a whole-file digest of
+    /// `digest`, if any.
+    fn build_sst_with_digest(dir: &Path, digest: Option<SstFileDigest>) -> PathBuf {
+        let path = dir.join("000001.sst");
+        let points: Vec<PointEntry> = (0..100u64)
+            .map(|i| PointEntry {
+                key: format!("ft_{i:03}").into_bytes(),
+                value: Some(format!("value_{i}").into_bytes()),
+                lsn: i + 1,
+                timestamp: 1_000 + i,
+            })
+            .collect();
+        sstable::SstWriter::new(&path)
+            .file_digest(digest)
+            .build(points.into_iter(), 100, std::iter::empty(), 0)
+            .unwrap();
+        path
+    }
+
+    /// Decodes the current footer of `bytes`.
+    fn decode_footer(bytes: &[u8]) -> SSTableFooter {
+        let start = bytes.len() - SST_FOOTER_SIZE;
+        encoding::decode_from_slice::<SSTableFooter>(&bytes[start..])
+            .unwrap()
+            .0
+    }
+
+    /// Encodes `footer` with its checksum recomputed.
+    fn encode_footer(mut footer: SSTableFooter) -> Vec<u8> {
+        footer.footer_crc32 = 0;
+        footer.footer_crc32 = crc32(&encoding::encode_to_vec(&footer).unwrap());
+        encoding::encode_to_vec(&footer).unwrap()
+    }
+
+    /// Rewrites the header of `bytes` as format `version`, with both its
+    /// checksums.
+    fn set_header_version(bytes: &mut [u8], version: u32) {
+        let mut header = SSTableHeader {
+            magic: SST_HDR_MAGIC,
+            version,
+            header_crc: 0,
+        };
+        header.header_crc = crc32(&encoding::encode_to_vec(&header).unwrap());
+        let header_bytes = encoding::encode_to_vec(&header).unwrap();
+        bytes[..SST_HDR_SIZE].copy_from_slice(&header_bytes);
+        bytes[SST_HDR_SIZE..SST_HDR_SIZE + 4].copy_from_slice(&crc32(&header_bytes).to_le_bytes());
+    }
+
+    /// Checks that every key of `build_sst` reads back.
+    fn assert_readable(path: &Path) {
+        let sst = SSTable::open(path).unwrap();
+        for i in 0..100u64 {
+            let key = format!("ft_{i:03}");
+            assert!(
+                matches!(sst.get(key.as_bytes()).unwrap(), GetResult::Put { .. }),
+                "{key}"
+            );
+        }
+    }
+
+    // ================================================================
+    // 1. Current footer
+    // ================================================================
+
+    /// # Scenario
+    /// A new table is recognised from its last bytes.
+    ///
+    /// # Actions
+    /// 1. Build a table; sniff it and open it with `SstReader`.
+    /// 2. Sniff a file of other bytes, and one shorter than the magic.
+    ///
+    /// # Expected behavior
+    /// - The table ends with the footer magic; sniffing and the reader
+    ///   both report format version 3, and every extension slot is
+    ///   unused.
+    /// - The other files sniff as `None`.
+    #[test]
+    fn footer_names_format_version() {
+        let tmp = TempDir::new().unwrap();
+        let path = build_sst(tmp.path());
+
+        let bytes = fs::read(&path).unwrap();
+        assert!(bytes.ends_with(&SST_FOOTER_MAGIC));
+        let footer = decode_footer(&bytes);
+        assert_eq!(footer.format_version, 3);
+        assert!(
+            footer
+                .extensions
+                .iter()
+                .all(|h| h.offset == 0 && h.size == 0)
+        );
+        assert_eq!(footer.total_file_size, bytes.len() as u64);
+
+        assert_eq!(sst::sniff_format_version(&path).unwrap(), Some(3));
+        assert_eq!(sst::SstReader::open(&path).unwrap().format_version(), 3);
+
+        let other = tmp.path().join("other.bin");
+        fs::write(&other, vec![0xAB; 256]).unwrap();
+        assert_eq!(sst::sniff_format_version(&other).unwrap(), None);
+        fs::write(&other, b"SST").unwrap();
+        assert_eq!(sst::sniff_format_version(&other).unwrap(), None);
+    }
+
+    // ================================================================
+    // 2. Older and newer footers
+    // ================================================================
+
+    /// # Scenario
+    /// A table is rewritten with the footer and header of format
+    /// version 2.
+    ///
+    /// # Actions
+    /// 1. Build a table; replace its footer with the 44-byte version 2
+    ///    footer and its header version with 2.
+    /// 2. Open it; sniff it.
+    ///
+    /// # Expected behavior
+    /// It opens as format version 2 with every key readable, and does not
+    /// sniff as an SSTable.
+    #[test]
+    fn version_2_footer_still_readable() {
+        let tmp = TempDir::new().unwrap();
+        let path = build_sst(tmp.path());
+
+        let bytes = fs::read(&path).unwrap();
+        let footer = decode_footer(&bytes);
+        let mut old = bytes[..bytes.len() - SST_FOOTER_SIZE].to_vec();
+        let mut legacy = SSTableFooterV2 {
+            metaindex: footer.metaindex,
+            index: footer.index,
+            total_file_size: old.len() as u64 + 44,
+            footer_crc32: 0,
+        };
+        legacy.footer_crc32 = crc32(&encoding::encode_to_vec(&legacy).unwrap());
+        old.extend(encoding::encode_to_vec(&legacy).unwrap());
+        set_header_version(&mut old, 2);
+        fs::write(&path, &old).unwrap();
+
+        assert_readable(&path);
+        assert_eq!(SSTable::open(&path).unwrap().format_version(), 2);
+        assert_eq!(sst::sniff_format_version(&path).unwrap(), None);
+    }
+
+    /// # Scenario
+    /// A footer uses an extension slot this reader does not know, or
+    /// names a format version other than the header's.
+    ///
+    /// # Actions
+    /// 1. Point the last extension slot at the metaindex; open.
+    /// 2. Set the footer version to 2, keeping the current layout; open.
+    ///
+    /// # Expected behavior
+    /// Step 1 opens with every key readable. Step 2 fails.
+    #[test]
+    fn extension_slots_ignored_and_version_checked() {
+        let tmp = TempDir::new().unwrap();
+        let path = build_sst(tmp.path());
+        let bytes = fs::read(&path).unwrap();
+        let body = &bytes[..bytes.len() - SST_FOOTER_SIZE];
+
+        let mut footer = decode_footer(&bytes);
+        footer.extensions[3] = BlockHandle {
+            offset: footer.metaindex.offset,
+            size: footer.metaindex.size,
+        };
+        fs::write(&path, [body, &encode_footer(footer)[..]].concat()).unwrap();
+        assert_readable(&path);
+
+        let mut footer = decode_footer(&bytes);
+        footer.format_version = 2;
+        fs::write(&path, [body, &encode_footer(footer)[..]].concat()).unwrap();
+        assert!(SSTable::open(&path).is_err());
+    }
+
+    // ================================================================
+    // 3. Whole-file digest
+    // ================================================================
+
+    /// # Scenario
+    /// Tables are built with each digest algorithm, and without one.
+    ///
+    /// # Actions
+    /// 1. Build a table per algorithm; verify its digest, in memory and
+    ///    with `sst::verify_file_digest`; open it with full checks.
+    /// 2. Build a table without a digest; verify it.
+    ///
+    /// # Expected behavior
+    /// - With a digest, the first extension slot points at the digest
+    ///   block, verification names the algorithm, and the table opens
+    ///   with `OpenChecks::FullBlockChecksums` with every key readable.
+    /// - Without one, the slot is unused and verification returns `None`.
+    #[test]
+    fn file_digest_written_and_verified() {
+        for algorithm in [SstFileDigest::Xxh3, SstFileDigest::Sha256] {
+            let tmp = TempDir::new().unwrap();
+            let path = build_sst_with_digest(tmp.path(), Some(algorithm));
+
+            let bytes = fs::read(&path).unwrap();
+            let slot = &decode_footer(&bytes).extensions[SST_FOOTER_DIGEST_SLOT];
+            assert!(slot.offset > 0 && slot.size > 0);
+            assert_eq!(
+                sstable::verify_file_digest(&bytes).unwrap(),
+                Some(algorithm)
+            );
+            assert_eq!(sst::verify_file_digest(&path).unwrap(), Some(algorithm));
+            SSTable::open_with(&path, OpenChecks::FullBlockChecksums).unwrap();
+            assert_readable(&path);
+        }
+
+        let tmp = TempDir::new().unwrap();
+        let path = build_sst(tmp.path());
+        let bytes = fs::read(&path).unwrap();
+        let slot = &decode_footer(&bytes).extensions[SST_FOOTER_DIGEST_SLOT];
+        assert!(slot.offset == 0 && slot.size == 0);
+        assert_eq!(sstable::verify_file_digest(&bytes).unwrap(), None);
+        assert_eq!(sst::verify_file_digest(&path).unwrap(), None);
+    }
+
+    /// # Scenario
+    /// One byte of a data block of a table with a digest is flipped.
+    ///
+    /// # Actions
+    /// 1. Build a table with an XXH3 digest; flip a byte after the header.
+    /// 2. Verify its digest; open it with default and with full checks;
+    ///    verify it with `SstReader::verify`.
+    ///
+    /// # Expected behavior
+    /// - Digest verification fails with `ChecksumMismatch`, as does the
+    ///   open with full checks and `SstReader::verify`.
+    /// - The default open, which reads no data block, succeeds.
+    #[test]
+    fn file_digest_detects_flipped_byte() {
+        let tmp = TempDir::new().unwrap();
+        let path = build_sst_with_digest(tmp.path(), Some(SstFileDigest::Xxh3));
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[SST_HDR_SIZE + 40] ^= 0x01;
+        fs::write(&path, &bytes).unwrap();
+
+        assert!(matches!(
+            sstable::verify_file_digest(&bytes),
+            Err(SSTableError::ChecksumMismatch)
+        ));
+        assert!(matches!(
+            sst::verify_file_digest(&path),
+            Err(sst::SstError::Corrupt(_))
+        ));
+        assert!(matches!(
+            SSTable::open_with(&path, OpenChecks::FullBlockChecksums),
+            Err(SSTableError::ChecksumMismatch)
+        ));
+        SSTable::open(&path).unwrap();
+        assert!(sst::SstReader::open(&path).unwrap().verify().is_err());
+    }
+}
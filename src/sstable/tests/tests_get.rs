@@ -13,6 +13,7 @@
 //! - Point-delete vs range-delete — range wins
 //! - Point-delete vs range-delete — point wins
 //! - Multiple versions of same key — max LSN wins
+//! - Sorted batch lookup (`contains_sorted`) agrees with `get()`
 //!
 //! ## See also
 //! - [`tests_basic`] — SSTable build / open / structural validation
@@ -168,7 +169,7 @@ mod tests {
         let tmp = TempDir::new().unwrap();
         let path = tmp.path().join("sst_range_delete.bin");
 
-        let points = vec![];
+        let points: Vec<PointEntry> = vec![];
         let ranges = vec![rdel(b"a", b"z", 30, 200)];
 
         let pt_count = points.len();
@@ -424,4 +425,58 @@ mod tests {
             }
         );
     }
+
+    /// # Scenario
+    /// A sorted batch lookup answers exactly as one `get()` per key, and
+    /// reads each data block at most once.
+    ///
+    /// # Starting environment
+    /// SSTable over several data blocks holding the even keys
+    /// `g_000..g_298` — every tenth a point delete — and a range
+    /// tombstone over `[g_400, g_450)`.
+    ///
+    /// # Actions
+    /// 1. `sst.contains_sorted` over every key `g_000..g_499`.
+    ///
+    /// # Expected behavior
+    /// Each answer is `true` exactly when `get()` does not return
+    /// `GetResult::NotFound`; no more blocks are read than the table has.
+    #[test]
+    fn contains_sorted_matches_get() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("sst_contains_sorted.bin");
+
+        let points: Vec<PointEntry> = (0..300u64)
+            .step_by(2)
+            .map(|i| {
+                let key = format!("g_{i:03}");
+                if i % 10 == 0 {
+                    del(key.as_bytes(), i + 1, 1_000 + i)
+                } else {
+                    point(key.as_bytes(), &[b'v'; 100], i + 1, 1_000 + i)
+                }
+            })
+            .collect();
+        let ranges = vec![rdel(b"g_400", b"g_450", 500, 2_000)];
+        let pt_count = points.len();
+
+        sstable::SstWriter::new(&path)
+            .build(points.into_iter(), pt_count, ranges.into_iter(), 1)
+            .unwrap();
+        let sst = SSTable::open(&path).unwrap();
+        assert!(sst.index.len() >= 3);
+
+        let keys: Vec<String> = (0..500).map(|i| format!("g_{i:03}")).collect();
+        let probe: Vec<&[u8]> = keys.iter().map(|k| k.as_bytes()).collect();
+        let (found, block_reads) = sst.contains_sorted(&probe).unwrap();
+
+        assert_eq!(found.len(), keys.len());
+        for (key, found) in probe.iter().zip(found) {
+            let expected = sst.get(key).unwrap() != GetResult::NotFound;
+            assert_eq!(found, expected, "{}", String::from_utf8_lossy(key));
+        }
+        assert!(block_reads <= sst.index.len() as u64);
+    }
 }
@@ -0,0 +1,125 @@
+//! Distinct-key sketch tests: the `meta.key_sketch` block written by
+//! [`SstWriter`](crate::sstable::SstWriter) and [`KeySketch`] itself.
+//!
+//! ## Coverage areas
+//! - The sketch counts distinct keys, not versions, tombstones included
+//! - Merged sketches estimate the union of overlapping tables
+//! - Encoding round-trips and rejects foreign layouts
+
+#[cfg(test)]
+mod tests {
+    use crate::encoding::{self, EncodingError};
+    use crate::sketch::KeySketch;
+    use crate::sstable::{self, PointEntry, SSTable};
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Builds and opens an SSTable holding `key_{i}` for each `i` in
+    /// `keys`, in `versions` versions each, the oldest a tombstone.
+    fn build(dir: &Path, name: &str, keys: std::ops::Range<u32>, versions: u64) -> SSTable {
+        let path = dir.join(name);
+        let mut points = Vec::new();
+        for i in keys {
+            let key = format!("key_{i:06}").into_bytes();
+            for lsn in (1..=versions).rev() {
+                let value = (lsn > 1).then(|| b"value".to_vec());
+                points.push(PointEntry {
+                    key: key.clone(),
+                    value,
+                    lsn,
+                    timestamp: lsn,
+                });
+            }
+        }
+        let count = points.len();
+        sstable::SstWriter::new(&path)
+            .build(points.into_iter(), count, std::iter::empty(), 0)
+            .unwrap();
+        SSTable::open(&path).unwrap()
+    }
+
+    /// Asserts that `estimate` lies within 5 % of `expected`.
+    fn assert_close(estimate: u64, expected: u64) {
+        let error = estimate.abs_diff(expected) as f64 / expected as f64;
+        assert!(error < 0.05, "estimate {estimate} too far from {expected}");
+    }
+
+    /// # Scenario
+    /// A table's sketch counts each key once, however many versions it
+    /// stores.
+    ///
+    /// # Actions
+    /// 1. Build a table of 2000 keys with three versions each, the
+    ///    oldest a tombstone.
+    ///
+    /// # Expected behavior
+    /// The sketch estimates 2000 keys within 5 %; the table holds 6000
+    /// records.
+    #[test]
+    fn sketch_counts_distinct_keys() {
+        let tmp = TempDir::new().unwrap();
+        let sst = build(tmp.path(), "versions.sst", 0..2000, 3);
+
+        assert_eq!(sst.record_count(), 6000);
+        assert_close(sst.key_sketch().unwrap().estimate(), 2000);
+    }
+
+    /// # Scenario
+    /// Merging the sketches of overlapping tables estimates their union.
+    ///
+    /// # Actions
+    /// 1. Build tables over keys `0..3000` and `2000..5000`.
+    /// 2. Merge their sketches.
+    ///
+    /// # Expected behavior
+    /// The merged sketch estimates 5000 keys within 5 %, not the 6000
+    /// records of both tables.
+    #[test]
+    fn merged_sketches_estimate_union() {
+        let tmp = TempDir::new().unwrap();
+        let a = build(tmp.path(), "a.sst", 0..3000, 1);
+        let b = build(tmp.path(), "b.sst", 2000..5000, 1);
+
+        let mut union = a.key_sketch().unwrap().clone();
+        union.merge(b.key_sketch().unwrap());
+
+        assert_close(union.estimate(), 5000);
+    }
+
+    /// # Scenario
+    /// Small key sets are counted almost exactly, and an empty sketch
+    /// estimates zero.
+    #[test]
+    fn sketch_small_cardinalities() {
+        assert_eq!(KeySketch::new().estimate(), 0);
+
+        let mut sketch = KeySketch::new();
+        for i in 0..20 {
+            sketch.insert(format!("k{i}").as_bytes());
+            sketch.insert(format!("k{i}").as_bytes());
+        }
+        assert!((19..=21).contains(&sketch.estimate()));
+    }
+
+    /// # Scenario
+    /// A sketch survives encoding; a sketch with another precision is
+    /// rejected rather than misread.
+    #[test]
+    fn sketch_encoding_roundtrip() {
+        let mut sketch = KeySketch::new();
+        for i in 0..500 {
+            sketch.insert(format!("key_{i}").as_bytes());
+        }
+
+        let bytes = encoding::encode_to_vec(&sketch).unwrap();
+        let (decoded, _) = encoding::decode_from_slice::<KeySketch>(&bytes).unwrap();
+        assert_eq!(decoded, sketch);
+
+        let mut foreign = bytes.clone();
+        foreign[0] += 1;
+        assert!(matches!(
+            encoding::decode_from_slice::<KeySketch>(&foreign),
+            Err(EncodingError::InvalidTag { .. })
+        ));
+    }
+}
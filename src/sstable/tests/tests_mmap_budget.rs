@@ -0,0 +1,158 @@
+//! Memory-map budget (`mapping::MmapBudget`): demotion of the least
+//! recently read SSTables to `pread` access, and remapping once room frees;
+//! page cache advice and retired tables.
+
+#[cfg(test)]
+mod tests {
+    use crate::sstable::mapping::MmapBudget;
+    use crate::sstable::{self, GetResult, PointEntry, SSTable};
+    use std::path::Path;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    /// Builds and opens `<name>.sst` holding `<name>_000..<name>_199`.
+    fn open_sst(dir: &Path, name: &str) -> Arc<SSTable> {
+        let path = dir.join(format!("{name}.sst"));
+        let points: Vec<PointEntry> = (0..200u64)
+            .map(|i| PointEntry {
+                key: format!("{name}_{i:03}").into_bytes(),
+                value: Some(format!("value_{i:03}").into_bytes()),
+                lsn: i + 1,
+                timestamp: 1_000 + i,
+            })
+            .collect();
+        sstable::SstWriter::new(&path)
+            .build(points.into_iter(), 200, std::iter::empty(), 0)
+            .unwrap();
+        Arc::new(SSTable::open(&path).unwrap())
+    }
+
+    /// # Scenario
+    /// Attaching a table that does not fit unmaps the least recently read
+    /// one, which keeps serving reads with `pread`.
+    ///
+    /// # Starting environment
+    /// Three equally sized SSTables and a budget for two and a half.
+    ///
+    /// # Actions
+    /// 1. Attach `a`, `b`; read from `a`; attach `c`.
+    /// 2. Get, scan, and verify the demoted table.
+    ///
+    /// # Expected behavior
+    /// `b` — read less recently than `a` — is demoted; `a` and `c` stay
+    /// mapped and the budget holds their bytes. Reads from `b` return the
+    /// same data as before.
+    #[test]
+    fn attach_demotes_least_recently_read() {
+        let tmp = TempDir::new().unwrap();
+        let (a, b, c) = (
+            open_sst(tmp.path(), "a"),
+            open_sst(tmp.path(), "b"),
+            open_sst(tmp.path(), "c"),
+        );
+        let size = a.file.len();
+        assert_eq!(b.file.len(), size);
+        let budget = Arc::new(MmapBudget::new(size * 5 / 2));
+
+        a.attach_budget(&budget);
+        b.attach_budget(&budget);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        assert!(matches!(a.get(b"a_010").unwrap(), GetResult::Put { .. }));
+        c.attach_budget(&budget);
+
+        assert!(a.is_mapped());
+        assert!(!b.is_mapped());
+        assert!(c.is_mapped());
+        assert_eq!(budget.mapped_bytes(), 2 * size);
+
+        match b.get(b"b_123").unwrap() {
+            GetResult::Put { value, lsn, .. } => {
+                assert_eq!(value, b"value_123");
+                assert_eq!(lsn, 124);
+            }
+            other => panic!("expected Put, got {other:?}"),
+        }
+        assert_eq!(b.scan(b"b_", b"b_~").unwrap().count(), 200);
+        b.verify_data_blocks().unwrap();
+        assert!(!b.is_mapped());
+    }
+
+    /// # Scenario
+    /// A demoted table is mapped again on a read once the budget has room,
+    /// and tables larger than the budget are never mapped.
+    ///
+    /// # Starting environment
+    /// Two equally sized SSTables and a budget for one and a half.
+    ///
+    /// # Actions
+    /// 1. Attach both; drop the mapped one; read from the other.
+    /// 2. Attach a third table to a budget smaller than one table.
+    ///
+    /// # Expected behavior
+    /// After the drop the budget is empty and the read remaps the demoted
+    /// table. The oversized table is read with `pread` and charges nothing.
+    #[test]
+    fn read_remaps_when_room_frees() {
+        let tmp = TempDir::new().unwrap();
+        let (a, b) = (open_sst(tmp.path(), "a"), open_sst(tmp.path(), "b"));
+        let size = a.file.len();
+        let budget = Arc::new(MmapBudget::new(size * 3 / 2));
+
+        a.attach_budget(&budget);
+        b.attach_budget(&budget);
+        assert!(!a.is_mapped());
+        assert!(b.is_mapped());
+
+        drop(b);
+        assert_eq!(budget.mapped_bytes(), 0);
+        assert!(matches!(a.get(b"a_000").unwrap(), GetResult::Put { .. }));
+        assert!(a.is_mapped());
+        assert_eq!(budget.mapped_bytes(), size);
+
+        let small = Arc::new(MmapBudget::new(size / 2));
+        let c = open_sst(tmp.path(), "c");
+        c.attach_budget(&small);
+        assert!(!c.is_mapped());
+        assert_eq!(small.mapped_bytes(), 0);
+        assert!(matches!(c.get(b"c_199").unwrap(), GetResult::Put { .. }));
+        assert!(!c.is_mapped());
+    }
+
+    /// # Scenario
+    /// Page cache hints: a table advised `WILLNEED` reads as before, and
+    /// a retired one is never mapped again.
+    ///
+    /// # Starting environment
+    /// Two SSTables attached to a budget that fits both.
+    ///
+    /// # Actions
+    /// 1. Advise `a`, then read it.
+    /// 2. Retire `b`, then get, scan, and verify it.
+    ///
+    /// # Expected behavior
+    /// `a` stays mapped and returns its values. `b` is unmapped and its
+    /// bytes leave the budget; its reads return the same data with
+    /// `pread`, even though the budget has room to map it again.
+    #[test]
+    fn retired_table_stays_unmapped() {
+        let tmp = TempDir::new().unwrap();
+        let (a, b) = (open_sst(tmp.path(), "a"), open_sst(tmp.path(), "b"));
+        let size = a.file.len();
+        let budget = Arc::new(MmapBudget::new(size * 2));
+        a.attach_budget(&budget);
+        b.attach_budget(&budget);
+
+        a.advise_will_need();
+        assert!(a.is_mapped());
+        assert!(matches!(a.get(b"a_100").unwrap(), GetResult::Put { .. }));
+
+        b.retire();
+        assert!(!b.is_mapped());
+        assert_eq!(budget.mapped_bytes(), size);
+        assert!(matches!(b.get(b"b_100").unwrap(), GetResult::Put { .. }));
+        assert_eq!(b.scan(b"b_", b"b_~").unwrap().count(), 200);
+        b.verify_data_blocks().unwrap();
+        assert!(!b.is_mapped());
+        assert_eq!(budget.mapped_bytes(), size);
+    }
+}
@@ -0,0 +1,162 @@
+//! Open-time validation levels (`SSTable::open_with`, `OpenChecks`).
+//!
+//! ## Coverage areas
+//! - `FullBlockChecksums` rejects a damaged data block that the other
+//!   levels leave for the first read
+//! - `FooterOnly` defers the bloom filter and key sketch to first use
+//! - `FooterOnly` tolerates a damaged bloom filter that `Metadata` rejects
+//!
+//! ## See also
+//! - [`tests_corruption`] — how reads detect the same damage
+
+#[cfg(test)]
+mod tests {
+    use crate::sstable::{self, GetResult, OpenChecks, PointEntry, SSTable, SSTableError};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use tempfile::TempDir;
+
+    /// Builds an SSTable of `oc_000..oc_299` with 100-byte values — about
+    /// ten data blocks.
+    fn build_sst(dir: &Path) -> PathBuf {
+        let path = dir.join("000001.sst");
+        let points: Vec<PointEntry> = (0..300u64)
+            .map(|i| PointEntry {
+                key: format!("oc_{i:03}").into_bytes(),
+                value: Some(vec![b'v'; 100]),
+                lsn: i + 1,
+                timestamp: 1_000 + i,
+            })
+            .collect();
+        sstable::SstWriter::new(&path)
+            .build(points.into_iter(), 300, std::iter::empty(), 0)
+            .unwrap();
+        path
+    }
+
+    /// Flips eight bytes, `skip` bytes into the block at `offset`.
+    fn corrupt(path: &Path, offset: u64, skip: usize) {
+        let mut bytes = fs::read(path).unwrap();
+        let start = offset as usize + skip;
+        for b in &mut bytes[start..start + 8] {
+            *b ^= 0xFF;
+        }
+        fs::write(path, &bytes).unwrap();
+    }
+
+    /// # Scenario
+    /// Only `FullBlockChecksums` reads the data blocks on open.
+    ///
+    /// # Starting environment
+    /// SSTable of 300 keys across several data blocks.
+    ///
+    /// # Actions
+    /// 1. Flip bytes inside the second data block.
+    /// 2. Open the table at each level.
+    ///
+    /// # Expected behavior
+    /// `FooterOnly` and `Metadata` open it, and a read of the damaged
+    /// block fails; `FullBlockChecksums` fails with `ChecksumMismatch`.
+    #[test]
+    fn full_block_checksums_rejects_damaged_data_block() {
+        let tmp = TempDir::new().unwrap();
+        let path = build_sst(tmp.path());
+        let sst = SSTable::open(&path).unwrap();
+        let second = &sst.index[1];
+        let (offset, damaged_key) = (second.handle.offset, second.separator_key.clone());
+        drop(sst);
+        corrupt(&path, offset, 40);
+
+        for checks in [OpenChecks::FooterOnly, OpenChecks::Metadata] {
+            let sst = SSTable::open_with(&path, checks).unwrap();
+            assert!(sst.get(&damaged_key).is_err(), "{checks:?}");
+        }
+        assert!(matches!(
+            SSTable::open_with(&path, OpenChecks::FullBlockChecksums),
+            Err(SSTableError::ChecksumMismatch)
+        ));
+    }
+
+    /// # Scenario
+    /// `FooterOnly` leaves the bloom filter and key sketch unread until a
+    /// lookup or estimate needs them.
+    ///
+    /// # Actions
+    /// 1. Open the same undamaged table with `Metadata` and `FooterOnly`.
+    /// 2. Look up a present key, probe absent ones; read the key sketch.
+    ///
+    /// # Expected behavior
+    /// `Metadata` decodes both blocks on open; `FooterOnly` decodes
+    /// neither until used. Both answer alike, and once loaded the bloom
+    /// filter rules out nearly all absent keys.
+    #[test]
+    fn footer_only_loads_filter_and_sketch_lazily() {
+        let tmp = TempDir::new().unwrap();
+        let path = build_sst(tmp.path());
+
+        let eager = SSTable::open_with(&path, OpenChecks::Metadata).unwrap();
+        assert!(eager.bloom.get().is_some());
+        assert!(eager.key_sketch.get().is_some());
+
+        let lazy = SSTable::open_with(&path, OpenChecks::FooterOnly).unwrap();
+        assert!(lazy.bloom.get().is_none());
+        assert!(lazy.key_sketch.get().is_none());
+
+        assert!(matches!(
+            lazy.get(b"oc_150").unwrap(),
+            GetResult::Put { lsn: 151, .. }
+        ));
+        assert!(lazy.bloom.get().is_some());
+        let ruled_out = (0..100)
+            .filter(|i| !lazy.bloom_may_contain(format!("absent_{i}").as_bytes()))
+            .count();
+        assert!(ruled_out > 90, "{ruled_out}");
+        assert_eq!(lazy.bloom().data, eager.bloom().data);
+
+        assert_eq!(
+            lazy.key_sketch().unwrap().estimate(),
+            eager.key_sketch().unwrap().estimate()
+        );
+    }
+
+    /// # Scenario
+    /// A damaged bloom filter fails a `Metadata` open, but under
+    /// `FooterOnly` is only logged and ignored once first used.
+    ///
+    /// # Actions
+    /// 1. Flip bytes inside the bloom filter block.
+    /// 2. Open the table at each level; read every key.
+    ///
+    /// # Expected behavior
+    /// `Metadata` fails with `ChecksumMismatch`. `FooterOnly` opens, falls
+    /// back to an empty filter that rules nothing out, and returns every
+    /// key.
+    #[test]
+    fn footer_only_tolerates_damaged_bloom() {
+        let tmp = TempDir::new().unwrap();
+        let path = build_sst(tmp.path());
+        let offset = SSTable::open(&path)
+            .unwrap()
+            .bloom_handle
+            .as_ref()
+            .unwrap()
+            .offset;
+        corrupt(&path, offset, 8);
+
+        assert!(matches!(
+            SSTable::open_with(&path, OpenChecks::Metadata),
+            Err(SSTableError::ChecksumMismatch)
+        ));
+
+        let sst = SSTable::open_with(&path, OpenChecks::FooterOnly).unwrap();
+        for i in 0..300u64 {
+            let key = format!("oc_{i:03}");
+            assert!(
+                matches!(sst.get(key.as_bytes()).unwrap(), GetResult::Put { .. }),
+                "{key}"
+            );
+        }
+        assert!(sst.bloom().data.is_empty());
+        assert!(sst.bloom_may_contain(b"absent_key"));
+    }
+}
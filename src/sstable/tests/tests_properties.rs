@@ -6,7 +6,8 @@
 
 #[cfg(test)]
 mod tests {
-    use crate::sstable::{self, PointEntry, RangeTombstone, SSTable};
+    use crate::encoding;
+    use crate::sstable::{self, PointEntry, RangeTombstone, SSTable, SSTablePropertiesBlock};
     use tempfile::TempDir;
 
     fn point(key: &[u8], value: &[u8], lsn: u64, timestamp: u64) -> PointEntry {
@@ -66,6 +67,7 @@ mod tests {
         assert_eq!(sst.record_count(), 3);
         assert_eq!(sst.tombstone_count(), 1);
         assert_eq!(sst.range_tombstone_count(), 1);
+        assert_eq!(sst.key_count(), Some(3));
 
         // Key range (only point entries, not range tombstones)
         assert_eq!(sst.min_key(), b"aaa");
@@ -85,6 +87,118 @@ mod tests {
         assert_eq!(sst.id(), 0);
     }
 
+    /// Tests that the key count ignores older versions, and that a
+    /// properties block written before the count decodes without one.
+    #[test]
+    fn key_count_counts_distinct_keys() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("versions.sst");
+
+        let points = vec![
+            point(b"aaa", b"v2", 20, 2000),
+            point(b"aaa", b"v1", 10, 1000),
+            del(b"bbb", 30, 3000),
+            point(b"bbb", b"v1", 15, 1500),
+            point(b"ccc", b"v1", 5, 500),
+        ];
+        sstable::SstWriter::new(&path)
+            .build(points.into_iter(), 5, std::iter::empty(), 0)
+            .unwrap();
+
+        let sst = SSTable::open(&path).unwrap();
+        assert_eq!(sst.record_count(), 5);
+        assert_eq!(sst.key_count(), Some(3));
+
+        // The count is the last 8 bytes of the block.
+        let bytes = encoding::encode_to_vec(&sst.properties).unwrap();
+        let legacy = &bytes[..bytes.len() - 8];
+        let (decoded, _) = encoding::decode_from_slice::<SSTablePropertiesBlock>(legacy).unwrap();
+        assert_eq!(decoded.key_count, None);
+        assert_eq!(decoded.max_key, b"ccc");
+    }
+
+    /// Tests that the build stats returned by the writer match the file:
+    /// one data block per index entry, their bytes on disk above the raw
+    /// bytes by the framing alone, and the file size.
+    #[test]
+    fn build_stats_match_file() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("stats.sst");
+
+        let points: Vec<_> = (0..500u32)
+            .map(|i| point(format!("key_{i:04}").as_bytes(), &[b'v'; 64], 1, 1))
+            .collect();
+        let built = sstable::SstWriter::new(&path)
+            .build(points.into_iter(), 500, std::iter::empty(), 0)
+            .unwrap();
+
+        let sst = SSTable::open(&path).unwrap();
+        assert_eq!(built.sstables, 1);
+        assert!(built.data_blocks > 1);
+        assert_eq!(built.data_blocks, sst.index.len() as u64);
+        assert_eq!(
+            built.written_bytes,
+            sst.index.iter().map(|entry| entry.handle.size).sum::<u64>()
+        );
+        assert!(built.raw_bytes > 500 * 64);
+        assert!(built.written_bytes > built.raw_bytes);
+        assert!(built.write_ratio() > 1.0);
+        assert_eq!(built.file_bytes, sst.file_size());
+    }
+
+    /// Tests that the prefix tally counts the newest put of each key
+    /// under its longest prefix, skips deletes and keys under no prefix,
+    /// and that a table written without prefixes stores no tally.
+    #[test]
+    fn prefix_usage_tallies_newest_puts() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("prefixes.sst");
+
+        let points = vec![
+            point(b"t1/a", b"new", 20, 2000),
+            point(b"t1/a", b"older", 10, 1000),
+            del(b"t1/b", 30, 3000),
+            point(b"t1/x/c", b"v", 5, 500),
+            point(b"t2/d", b"value", 6, 600),
+            point(b"zz", b"v", 7, 700),
+        ];
+        let prefixes = vec![b"t1/".to_vec(), b"t1/x/".to_vec(), b"t2/".to_vec()];
+        sstable::SstWriter::new(&path)
+            .tenant_prefixes(&prefixes)
+            .build(points.into_iter(), 6, std::iter::empty(), 0)
+            .unwrap();
+
+        let sst = SSTable::open(&path).unwrap();
+        let usage = sst.prefix_usage().unwrap();
+        assert_eq!(usage.len(), 3);
+        assert_eq!(
+            (usage[0].prefix.as_slice(), usage[0].bytes),
+            (&b"t1/"[..], 7)
+        );
+        assert_eq!(usage[0].entries, 1);
+        assert_eq!(
+            (usage[1].prefix.as_slice(), usage[1].bytes),
+            (&b"t1/x/"[..], 7)
+        );
+        assert_eq!(usage[1].entries, 1);
+        assert_eq!(
+            (usage[2].prefix.as_slice(), usage[2].bytes),
+            (&b"t2/"[..], 9)
+        );
+        assert_eq!(usage[2].entries, 1);
+
+        let plain = tmp.path().join("plain.sst");
+        sstable::SstWriter::new(&plain)
+            .build(
+                vec![point(b"t1/a", b"v", 1, 100)].into_iter(),
+                1,
+                std::iter::empty(),
+                0,
+            )
+            .unwrap();
+        assert!(SSTable::open(&plain).unwrap().prefix_usage().is_none());
+    }
+
     /// Tests the `set_id` + `id` round-trip.
     #[test]
     fn set_and_get_id() {
@@ -156,7 +270,7 @@ mod tests {
         // SSTable with only range tombstones, no point entries
         let ranges = vec![rdel(b"a", b"z", 5, 500)];
         sstable::SstWriter::new(&path)
-            .build(std::iter::empty(), 0, ranges.into_iter(), 1)
+            .build(std::iter::empty::<PointEntry>(), 0, ranges.into_iter(), 1)
             .unwrap();
 
         let sst = SSTable::open(&path).unwrap();
@@ -14,6 +14,11 @@
 //! - Range-delete whose start is after scan-end (excluded)
 //! - Range-delete whose end is before scan-start (excluded)
 //! - Mid-range scan (partial key space)
+//! - `seek_points` skips blocks forward, keeps range tombstones, and never
+//!   moves backwards
+//! - Index entries carry per-block LSN / timestamp bounds; `scan_owned_since`
+//!   skips blocks written entirely before the cut-off
+//! - Version 1 index entries decode with unbounded block bounds
 //!
 //! ## See also
 //! - [`tests_basic`] — SSTable build / open / structural validation
@@ -268,7 +273,7 @@ mod tests {
         let tmp = TempDir::new().unwrap();
         let path = tmp.path().join("scan_ranges_only.sst");
 
-        let points = vec![];
+        let points: Vec<PointEntry> = vec![];
         let ranges = vec![rdel(b"a", b"z", 50, 999)];
 
         let pt_count = points.len();
@@ -892,4 +897,181 @@ mod tests {
             other => panic!("Expected Put(d), got {:?}", other),
         }
     }
+
+    /// # Scenario
+    /// Seek an owned scan iterator past a run of point entries.
+    ///
+    /// # Starting environment
+    /// SSTable with 1 000 puts `k_0000..k_0999` spanning many data blocks
+    /// and a range tombstone `[k_0100, k_0200)`.
+    ///
+    /// # Actions
+    /// 1. Take the first record, then `seek_points(b"k_0900")`.
+    /// 2. Take the next two records, then `seek_points(b"k_0050")`.
+    /// 3. Collect the rest.
+    ///
+    /// # Expected behavior
+    /// After the first seek the range tombstone is still yielded, followed
+    /// by `k_0900`; the backwards seek changes nothing, so the remaining
+    /// records are `k_0901..k_0999`.
+    #[test]
+    fn scan_seek_points_skips_forward() {
+        use crate::engine::utils::RecordSource;
+        use std::sync::Arc;
+
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("scan_seek_points.sst");
+
+        let points: Vec<PointEntry> = (0..1000u32)
+            .map(|i| {
+                point(
+                    format!("k_{i:04}").as_bytes(),
+                    &[b'v'; 64],
+                    u64::from(i) + 1,
+                    10,
+                )
+            })
+            .collect();
+        let ranges = vec![rdel(b"k_0100", b"k_0200", 2000, 20)];
+        let pt_count = points.len();
+        let rt_count = ranges.len();
+        sstable::SstWriter::new(&path)
+            .build(points.into_iter(), pt_count, ranges.into_iter(), rt_count)
+            .unwrap();
+
+        let sst = Arc::new(SSTable::open(&path).unwrap());
+        assert!(sst.index.len() > 4);
+        let mut scan = SSTable::scan_owned(&sst, b"k_", b"k~").unwrap();
+        assert_eq!(scan.max_lsn(), 2000);
+
+        assert_eq!(scan.next().unwrap().key(), b"k_0000");
+        scan.seek_points(b"k_0900");
+        assert!(matches!(scan.next(), Some(Record::RangeDelete { .. })));
+        assert_eq!(scan.next().unwrap().key(), b"k_0900");
+
+        scan.seek_points(b"k_0050");
+        let rest: Vec<Vec<u8>> = scan.map(|r| r.key().to_vec()).collect();
+        let expected: Vec<Vec<u8>> = (901..1000u32)
+            .map(|i| format!("k_{i:04}").into_bytes())
+            .collect();
+        assert_eq!(rest, expected);
+    }
+
+    /// # Scenario
+    /// A time-bounded scan skips data blocks older than the cut-off.
+    ///
+    /// # Starting environment
+    /// SSTable with 1 000 puts `k_0000..k_0999` written at timestamps
+    /// `1..=1000` (LSN equal to the timestamp), spanning many data blocks,
+    /// and a range tombstone `[k_0100, k_0200)` at timestamp 5.
+    ///
+    /// # Actions
+    /// 1. Inspect the index entries.
+    /// 2. `scan_owned_since` with cut-offs 900 and 2000.
+    ///
+    /// # Expected behavior
+    /// Every index entry holds the exact LSN and timestamp bounds of its
+    /// block, in increasing order. The scan since 900 yields the range
+    /// tombstone and the points of the blocks from the one holding
+    /// timestamp 900 onwards — nothing earlier. Since 2000 only the range
+    /// tombstone remains.
+    #[test]
+    fn scan_since_skips_old_blocks() {
+        use std::sync::Arc;
+
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("scan_since.sst");
+
+        let points: Vec<PointEntry> = (0..1000u64)
+            .map(|i| point(format!("k_{i:04}").as_bytes(), &[b'v'; 64], i + 1, i + 1))
+            .collect();
+        let ranges = vec![rdel(b"k_0100", b"k_0200", 2000, 5)];
+        let pt_count = points.len();
+        let rt_count = ranges.len();
+        sstable::SstWriter::new(&path)
+            .build(points.into_iter(), pt_count, ranges.into_iter(), rt_count)
+            .unwrap();
+
+        let sst = Arc::new(SSTable::open(&path).unwrap());
+        assert!(sst.index.len() > 4);
+        for (i, entry) in sst.index.iter().enumerate() {
+            assert_eq!(entry.min_lsn, entry.min_timestamp);
+            assert_eq!(entry.max_lsn, entry.max_timestamp);
+            assert!(entry.min_timestamp <= entry.max_timestamp);
+            let first_key = format!("k_{:04}", entry.min_timestamp - 1);
+            assert_eq!(entry.separator_key, first_key.into_bytes());
+            if let Some(next) = sst.index.get(i + 1) {
+                assert_eq!(entry.max_timestamp + 1, next.min_timestamp);
+            }
+        }
+        assert_eq!(sst.index.last().unwrap().max_timestamp, 1000);
+
+        let block = sst.index.iter().find(|e| e.max_timestamp >= 900).unwrap();
+        let first = block.min_timestamp - 1;
+        let mut scan = SSTable::scan_owned_since(&sst, b"k_", b"k~", true, 900).unwrap();
+        assert!(matches!(scan.next(), Some(Record::RangeDelete { .. })));
+        let keys: Vec<Vec<u8>> = scan.map(|r| r.key().to_vec()).collect();
+        let expected: Vec<Vec<u8>> = (first..1000)
+            .map(|i| format!("k_{i:04}").into_bytes())
+            .collect();
+        assert_eq!(keys, expected);
+
+        let records: Vec<Record> = SSTable::scan_owned_since(&sst, b"k_", b"k~", true, 2000)
+            .unwrap()
+            .collect();
+        assert_eq!(records.len(), 1);
+        assert!(matches!(records[0], Record::RangeDelete { .. }));
+    }
+
+    /// # Scenario
+    /// An index block written by format version 1 is decoded.
+    ///
+    /// # Starting environment
+    /// Two version 1 index entries, without block bounds.
+    ///
+    /// # Actions
+    /// 1. Encode them, decode as version 1, convert to current entries.
+    ///
+    /// # Expected behavior
+    /// Keys and handles survive; the bounds cover every LSN and timestamp,
+    /// so a time-bounded scan never skips the blocks.
+    #[test]
+    fn index_entry_v1_decodes_unbounded() {
+        use crate::encoding;
+        use crate::sstable::{BlockHandle, SSTableIndexEntry, SSTableIndexEntryV1};
+
+        let v1 = vec![
+            SSTableIndexEntryV1 {
+                separator_key: b"a".to_vec(),
+                handle: BlockHandle {
+                    offset: 12,
+                    size: 100,
+                },
+            },
+            SSTableIndexEntryV1 {
+                separator_key: b"m".to_vec(),
+                handle: BlockHandle {
+                    offset: 112,
+                    size: 80,
+                },
+            },
+        ];
+        let mut bytes = Vec::new();
+        encoding::encode_vec(&v1, &mut bytes).unwrap();
+
+        let (decoded, _) = encoding::decode_vec::<SSTableIndexEntryV1>(&bytes).unwrap();
+        let entries: Vec<SSTableIndexEntry> = decoded.into_iter().map(Into::into).collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].separator_key, b"m".to_vec());
+        assert_eq!(entries[1].handle.offset, 112);
+        assert_eq!(entries[1].handle.size, 80);
+        for entry in &entries {
+            assert_eq!((entry.min_lsn, entry.max_lsn), (0, u64::MAX));
+            assert_eq!((entry.min_timestamp, entry.max_timestamp), (0, u64::MAX));
+        }
+    }
 }
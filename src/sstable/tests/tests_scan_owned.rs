@@ -11,9 +11,11 @@
 //! - Owned scan with mixed puts, deletes, and range deletes.
 //! - Empty range owned scan yields nothing.
 //! - Owned scan sstable iterator is `'static` (compile-time proof).
+//! - Lazy owned scan: lower bound, deferred seek, same records.
 
 #[cfg(test)]
 mod tests {
+    use crate::engine::utils::RecordSource;
     use crate::sstable::{self, PointEntry, RangeTombstone, Record, SSTable};
     use std::sync::Arc;
     use tempfile::TempDir;
@@ -262,4 +264,55 @@ mod tests {
         let records: Vec<Record> = boxed.collect();
         assert_eq!(records.len(), 2);
     }
+
+    // ----------------------------------------------------------------
+    // Lazy owned scan
+    // ----------------------------------------------------------------
+
+    /// # Scenario
+    /// A lazy owned scan reports the smallest key it can yield and
+    /// produces the same records as an eager one.
+    ///
+    /// # Starting environment
+    /// SSTable with puts at `d`, `f`, `x` and a range tombstone `[b, e)`.
+    ///
+    /// # Actions
+    /// 1. Lazy scans of `[c, z)`, `[e, z)`, and `[a, c)`: read the lower
+    ///    bound, collect, compare with `scan_owned`.
+    /// 2. A lazy scan of `[c, z)` asked to seek to `g` before its first
+    ///    record.
+    ///
+    /// # Expected behavior
+    /// The bounds are `b` (the overlapping tombstone starts before `c`),
+    /// `e` (the tombstone ends at `e`), and `b`; records match. The
+    /// seeking scan yields the tombstone and `x` only.
+    #[test]
+    fn scan_owned_lazy_bounds_and_seek() {
+        let points = vec![
+            point(b"d", b"4", 10, 100),
+            point(b"f", b"6", 11, 101),
+            point(b"x", b"9", 12, 102),
+        ];
+        let ranges = vec![rdel(b"b", b"e", 13, 103)];
+        let (_tmp, arc) = build_arc_sst(points, ranges);
+
+        for (start, end, bound) in [
+            (b"c".as_slice(), b"z".as_slice(), b"b".as_slice()),
+            (b"e", b"z", b"e"),
+            (b"a", b"c", b"b"),
+        ] {
+            let lazy = SSTable::scan_owned_lazy(&arc, start, end, true, 0).unwrap();
+            assert_eq!(lazy.lower_bound(), Some(bound));
+            let lazy: Vec<Record> = lazy.collect();
+            let eager: Vec<Record> = SSTable::scan_owned(&arc, start, end).unwrap().collect();
+            assert_eq!(lazy, eager);
+        }
+
+        let mut lazy = SSTable::scan_owned_lazy(&arc, b"c", b"z", true, 0).unwrap();
+        lazy.seek_points(b"g");
+        let records: Vec<Record> = lazy.collect();
+        assert_eq!(records.len(), 2);
+        assert!(matches!(&records[0], Record::RangeDelete { start, .. } if start == b"b"));
+        assert!(matches!(&records[1], Record::Put { key, .. } if key == b"x"));
+    }
 }
@@ -0,0 +1,127 @@
+//! Change notifications for in-process subscribers.
+//!
+//! [`Db::subscribe`](crate::Db::subscribe) registers a half-open key range
+//! and returns the receiving end of a channel. Every committed mutation
+//! that intersects the range is sent to it as a [`ChangeEvent`], so caches
+//! layered on top of the database can be invalidated without polling scans.
+//!
+//! While at least one subscriber is registered, writes are serialized
+//! through the [`ChangeFeed`] lock so that events reach every subscriber
+//! in commit order. Without subscribers the feed costs one atomic load
+//! per write.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use crate::engine::utils::below_end;
+use crate::types::RecordKind;
+
+/// A committed mutation delivered to a subscriber.
+///
+/// Events carry the mutation as written, not clipped to the subscribed
+/// range: a range delete that only overlaps the range is delivered whole.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeEvent {
+    /// `key` was set to `value`.
+    Put { key: Vec<u8>, value: Vec<u8> },
+
+    /// `key` was deleted.
+    Delete { key: Vec<u8> },
+
+    /// Every key in `[start, end)` was deleted; an empty `end` means every
+    /// key from `start` on, as written by
+    /// [`Db::delete_range_from`](crate::Db::delete_range_from). An empty
+    /// `start` and `end` mean every key, dropped by
+    /// [`Db::clear`](crate::Db::clear).
+    DeleteRange { start: Vec<u8>, end: Vec<u8> },
+}
+
+impl ChangeEvent {
+    /// Returns the kind of the mutation.
+    pub fn kind(&self) -> RecordKind {
+        match self {
+            ChangeEvent::Put { .. } => RecordKind::Put,
+            ChangeEvent::Delete { .. } => RecordKind::Delete,
+            ChangeEvent::DeleteRange { .. } => RecordKind::RangeDelete,
+        }
+    }
+
+    /// Returns `true` if the mutation touches any key in `[start, end)`.
+    fn intersects(&self, start: &[u8], end: &[u8]) -> bool {
+        match self {
+            ChangeEvent::Put { key, .. } | ChangeEvent::Delete { key } => {
+                key.as_slice() >= start && key.as_slice() < end
+            }
+            ChangeEvent::DeleteRange { start: s, end: e } => {
+                s.as_slice() < end && below_end(start, e)
+            }
+        }
+    }
+}
+
+/// One registered key range and its channel.
+struct Subscriber {
+    start: Vec<u8>,
+    end: Vec<u8>,
+    sender: Sender<ChangeEvent>,
+}
+
+/// Registry of subscribers, shared by all writers of a [`Db`](crate::Db).
+#[derive(Default)]
+pub(crate) struct ChangeFeed {
+    subscribers: Mutex<Vec<Subscriber>>,
+
+    /// Mirrors `!subscribers.is_empty()` so writers can skip the lock.
+    active: AtomicBool,
+}
+
+impl ChangeFeed {
+    /// Registers `[start, end)` and returns the receiving end of its channel.
+    pub fn subscribe(&self, start: &[u8], end: &[u8]) -> Receiver<ChangeEvent> {
+        let (sender, receiver) = mpsc::channel();
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.push(Subscriber {
+            start: start.to_vec(),
+            end: end.to_vec(),
+            sender,
+        });
+        self.active.store(true, Ordering::SeqCst);
+        receiver
+    }
+
+    /// Runs `write` and, if it succeeds, publishes the events `events`
+    /// builds from its result to every subscriber whose range they
+    /// intersect.
+    ///
+    /// With subscribers registered, the feed lock is held across the write
+    /// so that concurrent writers publish in the order they committed.
+    /// Subscribers whose receiver was dropped are removed.
+    pub fn commit<T, E>(
+        &self,
+        write: impl FnOnce() -> Result<T, E>,
+        events: impl FnOnce(&T) -> Vec<ChangeEvent>,
+    ) -> Result<T, E> {
+        if !self.active.load(Ordering::SeqCst) {
+            return write();
+        }
+
+        let mut subscribers = self.subscribers.lock().unwrap();
+        let result = write()?;
+        let events = events(&result);
+        subscribers.retain(|sub| {
+            events
+                .iter()
+                .filter(|event| event.intersects(&sub.start, &sub.end))
+                .all(|event| sub.sender.send(event.clone()).is_ok())
+        });
+        self.active.store(!subscribers.is_empty(), Ordering::SeqCst);
+        Ok(result)
+    }
+
+    /// Drops every subscriber, disconnecting their receivers.
+    pub fn close(&self) {
+        self.subscribers.lock().unwrap().clear();
+        self.active.store(false, Ordering::SeqCst);
+    }
+}
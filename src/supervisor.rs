@@ -0,0 +1,159 @@
+//! Panic handling for background tasks.
+//!
+//! Every task the background pool runs — flushes, compactions, timer
+//! ticks — goes through a [`TaskSupervisor`], which catches a panic at the
+//! task boundary instead of letting it unwind the worker thread. Without
+//! it, each panic would silently kill one worker and the pool would shrink
+//! for the rest of the process's life.
+//!
+//! A caught panic is logged, counted in [`BackgroundStats`], and reported
+//! to [`EventListener::on_background_panic`]. What happens next is chosen
+//! by [`DbConfig::background_panic_policy`](crate::DbConfig::background_panic_policy).
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use tracing::error;
+
+use crate::DbError;
+use crate::listener::EventListener;
+
+/// What the database does after a background task panicked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BackgroundPanicPolicy {
+    /// The worker is restarted in place: it drops the failed task and
+    /// takes the next one, so the pool keeps its size. The work of the
+    /// failed task is lost until it is scheduled again, e.g. by the next
+    /// flush.
+    #[default]
+    RestartWorker,
+
+    /// As `RestartWorker`, and the database is also marked errored: every
+    /// later write, compaction, or bulk-load call fails with
+    /// [`DbError::BackgroundPanic`] until the database is reopened. Reads
+    /// and [`Db::close`](crate::Db::close) keep working.
+    ///
+    /// A panic may have left in-memory state half updated; this policy
+    /// stops writes from building on it.
+    MarkErrored,
+}
+
+/// A background task panic, passed to
+/// [`EventListener::on_background_panic`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackgroundPanic {
+    /// Name of the worker thread the task ran on.
+    pub thread: String,
+
+    /// The panic message, or a placeholder if the payload was not a
+    /// string.
+    pub message: String,
+
+    /// The policy applied to the panic.
+    pub policy: BackgroundPanicPolicy,
+}
+
+/// Background task counters returned by
+/// [`Db::background_stats`](crate::Db::background_stats).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BackgroundStats {
+    /// Tasks run to completion or panic since open.
+    pub tasks_run: u64,
+
+    /// Tasks that panicked since open.
+    pub tasks_panicked: u64,
+
+    /// With [`BackgroundPanicPolicy::MarkErrored`], the message of the
+    /// panic that marked the database errored.
+    pub error: Option<String>,
+}
+
+/// Runs background tasks, catching and handling their panics.
+pub(crate) struct TaskSupervisor {
+    policy: BackgroundPanicPolicy,
+    listeners: Arc<[Arc<dyn EventListener>]>,
+    tasks_run: AtomicU64,
+    tasks_panicked: AtomicU64,
+
+    /// Set by the first panic under [`BackgroundPanicPolicy::MarkErrored`].
+    error: OnceLock<String>,
+}
+
+impl TaskSupervisor {
+    pub(crate) fn new(
+        policy: BackgroundPanicPolicy,
+        listeners: Arc<[Arc<dyn EventListener>]>,
+    ) -> Self {
+        Self {
+            policy,
+            listeners,
+            tasks_run: AtomicU64::new(0),
+            tasks_panicked: AtomicU64::new(0),
+            error: OnceLock::new(),
+        }
+    }
+
+    /// Runs `task`, handling a panic according to the policy.
+    pub(crate) fn run(&self, task: Box<dyn FnOnce() + Send>) {
+        let outcome = panic::catch_unwind(AssertUnwindSafe(task));
+        self.tasks_run.fetch_add(1, Ordering::Relaxed);
+        if let Err(payload) = outcome {
+            self.handle_panic(payload.as_ref());
+        }
+    }
+
+    fn handle_panic(&self, payload: &(dyn Any + Send)) {
+        self.tasks_panicked.fetch_add(1, Ordering::Relaxed);
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "non-string panic payload".into());
+        let thread = std::thread::current()
+            .name()
+            .unwrap_or("unnamed")
+            .to_string();
+        error!(
+            thread = %thread,
+            policy = ?self.policy,
+            "background task panicked: {message}"
+        );
+
+        if self.policy == BackgroundPanicPolicy::MarkErrored {
+            let _ = self.error.set(message.clone());
+        }
+
+        let event = BackgroundPanic {
+            thread,
+            message,
+            policy: self.policy,
+        };
+        for listener in self.listeners.iter() {
+            // A panicking listener must not take the worker down either.
+            let notified =
+                panic::catch_unwind(AssertUnwindSafe(|| listener.on_background_panic(&event)));
+            if notified.is_err() {
+                error!("event listener panicked in on_background_panic");
+            }
+        }
+    }
+
+    /// Fails with [`DbError::BackgroundPanic`] once the database is
+    /// marked errored.
+    pub(crate) fn check(&self) -> Result<(), DbError> {
+        match self.error.get() {
+            Some(message) => Err(DbError::BackgroundPanic(message.clone())),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn stats(&self) -> BackgroundStats {
+        BackgroundStats {
+            tasks_run: self.tasks_run.load(Ordering::Relaxed),
+            tasks_panicked: self.tasks_panicked.load(Ordering::Relaxed),
+            error: self.error.get().cloned(),
+        }
+    }
+}
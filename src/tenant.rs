@@ -0,0 +1,170 @@
+//! Per-tenant storage accounting by key prefix.
+//!
+//! [`DbConfig::tenant_prefixes`](crate::DbConfig::tenant_prefixes) maps
+//! key prefixes to tenants. Every SSTable written while prefixes are
+//! configured tallies, per prefix, the bytes and entries of the newest
+//! put of each key it holds, and stores the tally in its properties
+//! block. [`Db::tenant_usage`](crate::Db::tenant_usage) sums the tallies
+//! of the live SSTables, so usage is read from metadata alone and stays
+//! current as flushes add tables and compactions replace them.
+//!
+//! A key belongs to the longest configured prefix it starts with. Keys
+//! under no prefix are not accounted.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::clock::Clock;
+
+/// Assigns the keys starting with [`prefix`](Self::prefix) to
+/// [`tenant`](Self::tenant).
+///
+/// Several prefixes may belong to one tenant; its usage is their sum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TenantPrefix {
+    /// Key prefix; non-empty.
+    pub prefix: Vec<u8>,
+
+    /// Tenant the prefix belongs to; non-empty.
+    pub tenant: String,
+}
+
+impl TenantPrefix {
+    /// Assigns the keys starting with `prefix` to `tenant`.
+    pub fn new(prefix: impl Into<Vec<u8>>, tenant: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            tenant: tenant.into(),
+        }
+    }
+}
+
+/// Storage used by one tenant, returned by
+/// [`Db::tenant_usage`](crate::Db::tenant_usage).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TenantUsage {
+    /// Tenant named in [`TenantPrefix::tenant`].
+    pub tenant: String,
+
+    /// Key and value bytes of the tenant's keys in SSTables.
+    ///
+    /// Each SSTable counts the newest put of every key it holds, so a key
+    /// overwritten or deleted in a newer SSTable is counted until
+    /// compaction merges the two. Writes still in write buffers are
+    /// counted once flushed.
+    pub live_bytes: u64,
+
+    /// Keys counted in [`live_bytes`](Self::live_bytes).
+    pub live_entries: u64,
+
+    /// Key and value bytes of the tenant's keys flushed or ingested into
+    /// SSTables since the database was opened.
+    pub bytes_written: u64,
+
+    /// [`bytes_written`](Self::bytes_written) per second since the
+    /// database was opened, measured with
+    /// [`DbConfig::clock`](crate::DbConfig::clock).
+    pub write_bytes_per_sec: f64,
+
+    /// `false` if an SSTable that may hold the tenant's keys carries no
+    /// tally for them — it was written before the prefix was configured —
+    /// so the live counts miss its share. Compacting the table tallies it.
+    pub exact: bool,
+}
+
+/// Usage of one configured prefix, summed across SSTables.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct PrefixTotals {
+    /// Key and value bytes of the newest put of each key, per SSTable.
+    pub live_bytes: u64,
+
+    /// Keys counted in `live_bytes`.
+    pub live_entries: u64,
+
+    /// Key and value bytes flushed or ingested since open.
+    pub bytes_written: u64,
+
+    /// SSTables that may hold keys under the prefix but carry no tally
+    /// for it.
+    pub untracked_tables: u64,
+}
+
+impl std::ops::AddAssign for PrefixTotals {
+    fn add_assign(&mut self, other: Self) {
+        self.live_bytes += other.live_bytes;
+        self.live_entries += other.live_entries;
+        self.bytes_written += other.bytes_written;
+        self.untracked_tables += other.untracked_tables;
+    }
+}
+
+/// Returns the index of the longest prefix in `prefixes` — sorted and
+/// free of duplicates — that `key` starts with.
+pub(crate) fn match_prefix(prefixes: &[Vec<u8>], key: &[u8]) -> Option<usize> {
+    // Every prefix of `key` sorts at or below it, and above every
+    // candidate with a smaller first byte.
+    let end = prefixes.partition_point(|prefix| prefix.as_slice() <= key);
+    (0..end)
+        .rev()
+        .take_while(|&i| prefixes[i].first() == key.first())
+        .find(|&i| key.starts_with(&prefixes[i]))
+}
+
+/// Returns `true` if a table with keys in `[min_key, max_key]` may hold
+/// a key starting with `prefix`.
+pub(crate) fn range_may_hold_prefix(min_key: &[u8], max_key: &[u8], prefix: &[u8]) -> bool {
+    max_key >= prefix && (min_key <= prefix || min_key.starts_with(prefix))
+}
+
+/// Maps the per-prefix totals of the engine to per-tenant usage.
+pub(crate) struct TenantAccounting {
+    /// Tenant of each configured prefix, in the sorted prefix order the
+    /// engine reports totals in.
+    tenants: Vec<String>,
+
+    /// Source of the current time for write rates.
+    clock: Arc<dyn Clock>,
+
+    /// Time the database was opened, in UNIX epoch nanos.
+    opened_at: u64,
+}
+
+impl TenantAccounting {
+    /// Creates the accounting for `prefixes`, starting the write-rate
+    /// window now.
+    pub fn new(prefixes: &[TenantPrefix], clock: Arc<dyn Clock>) -> Self {
+        let mut prefixes: Vec<&TenantPrefix> = prefixes.iter().collect();
+        prefixes.sort_by(|a, b| a.prefix.cmp(&b.prefix));
+        Self {
+            tenants: prefixes.into_iter().map(|p| p.tenant.clone()).collect(),
+            opened_at: clock.now_nanos(),
+            clock,
+        }
+    }
+
+    /// Returns the usage of every tenant, sorted by tenant, from the
+    /// totals of each prefix in sorted prefix order.
+    pub fn usage(&self, totals: &[PrefixTotals]) -> Vec<TenantUsage> {
+        let mut by_tenant: BTreeMap<&str, PrefixTotals> = BTreeMap::new();
+        for (tenant, prefix_totals) in self.tenants.iter().zip(totals) {
+            *by_tenant.entry(tenant).or_default() += *prefix_totals;
+        }
+
+        let elapsed = self.clock.now_nanos().saturating_sub(self.opened_at) as f64 / 1e9;
+        by_tenant
+            .into_iter()
+            .map(|(tenant, totals)| TenantUsage {
+                tenant: tenant.to_string(),
+                live_bytes: totals.live_bytes,
+                live_entries: totals.live_entries,
+                bytes_written: totals.bytes_written,
+                write_bytes_per_sec: if elapsed > 0.0 {
+                    totals.bytes_written as f64 / elapsed
+                } else {
+                    0.0
+                },
+                exact: totals.untracked_tables == 0,
+            })
+            .collect()
+    }
+}
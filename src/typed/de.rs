@@ -0,0 +1,367 @@
+//! Order-preserving deserializer.
+//!
+//! The layout is not self-describing, so every value is decoded by the
+//! type the caller asks for; `deserialize_any` is rejected.
+
+use serde::de::value::U32Deserializer;
+use serde::de::{self, DeserializeSeed, Visitor};
+
+use super::CodecError;
+use super::ser::{BYTES_ESCAPE, SEQ_END, SEQ_MORE};
+
+/// Deserializes a `T` from `input`, rejecting trailing bytes.
+pub(super) fn deserialize_from<'de, T: de::Deserialize<'de>>(
+    input: &'de [u8],
+) -> Result<T, CodecError> {
+    let mut de = Deserializer { input };
+    let value = T::deserialize(&mut de)?;
+    if !de.input.is_empty() {
+        return Err(CodecError::TrailingBytes(de.input.len()));
+    }
+    Ok(value)
+}
+
+/// Deserializer reading the order-preserving layout from a byte slice.
+struct Deserializer<'de> {
+    input: &'de [u8],
+}
+
+impl<'de> Deserializer<'de> {
+    /// Consumes exactly `N` bytes.
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], CodecError> {
+        let (head, rest) = self
+            .input
+            .split_first_chunk::<N>()
+            .ok_or(CodecError::UnexpectedEof)?;
+        self.input = rest;
+        Ok(*head)
+    }
+
+    fn byte(&mut self) -> Result<u8, CodecError> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    /// Consumes an escaped byte string up to and including its terminator.
+    fn escaped(&mut self) -> Result<Vec<u8>, CodecError> {
+        let mut out = Vec::new();
+        loop {
+            match self.byte()? {
+                0x00 => match self.byte()? {
+                    0x00 => return Ok(out),
+                    BYTES_ESCAPE => out.push(0x00),
+                    b => return Err(CodecError::InvalidByte(b)),
+                },
+                b => out.push(b),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, CodecError> {
+        String::from_utf8(self.escaped()?).map_err(|_| CodecError::InvalidUtf8)
+    }
+
+    /// Reads the marker in front of a sequence element: `true` if another
+    /// element follows.
+    fn seq_more(&mut self) -> Result<bool, CodecError> {
+        match self.byte()? {
+            SEQ_MORE => Ok(true),
+            SEQ_END => Ok(false),
+            b => Err(CodecError::InvalidByte(b)),
+        }
+    }
+}
+
+/// Forwards an integer method to `take` + `from_be_bytes`, flipping the
+/// sign bit for signed types.
+macro_rules! de_int {
+    ($method:ident, $visit:ident, $ty:ty) => {
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+            visitor.$visit(<$ty>::from_be_bytes(self.take()?))
+        }
+    };
+    ($method:ident, $visit:ident, $ty:ty, $uty:ty) => {
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+            let raw = <$uty>::from_be_bytes(self.take()?) ^ (1 << (<$uty>::BITS - 1));
+            visitor.$visit(raw as $ty)
+        }
+    };
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = CodecError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, CodecError> {
+        Err(CodecError::Message(
+            "typed codec is not self-describing".into(),
+        ))
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        match self.byte()? {
+            0 => visitor.visit_bool(false),
+            1 => visitor.visit_bool(true),
+            b => Err(CodecError::InvalidByte(b)),
+        }
+    }
+
+    de_int!(deserialize_u8, visit_u8, u8);
+    de_int!(deserialize_u16, visit_u16, u16);
+    de_int!(deserialize_u32, visit_u32, u32);
+    de_int!(deserialize_u64, visit_u64, u64);
+    de_int!(deserialize_u128, visit_u128, u128);
+    de_int!(deserialize_i8, visit_i8, i8, u8);
+    de_int!(deserialize_i16, visit_i16, i16, u16);
+    de_int!(deserialize_i32, visit_i32, i32, u32);
+    de_int!(deserialize_i64, visit_i64, i64, u64);
+    de_int!(deserialize_i128, visit_i128, i128, u128);
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        let bits = u32::from_be_bytes(self.take()?);
+        let bits = if bits >> 31 == 1 {
+            bits ^ (1 << 31)
+        } else {
+            !bits
+        };
+        visitor.visit_f32(f32::from_bits(bits))
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        let bits = u64::from_be_bytes(self.take()?);
+        let bits = if bits >> 63 == 1 {
+            bits ^ (1 << 63)
+        } else {
+            !bits
+        };
+        visitor.visit_f64(f64::from_bits(bits))
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        let code = u32::from_be_bytes(self.take()?);
+        let c = char::from_u32(code).ok_or(CodecError::InvalidChar(code))?;
+        visitor.visit_char(c)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_string(self.string()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_string(self.string()?)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_byte_buf(self.escaped()?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_byte_buf(self.escaped()?)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        match self.byte()? {
+            0 => visitor.visit_none(),
+            1 => visitor.visit_some(self),
+            b => Err(CodecError::InvalidByte(b)),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, CodecError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, CodecError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_seq(Terminated { de: self })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, CodecError> {
+        visitor.visit_seq(Fixed { de: self, len })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, CodecError> {
+        visitor.visit_seq(Fixed { de: self, len })
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CodecError> {
+        visitor.visit_map(Terminated { de: self })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, CodecError> {
+        visitor.visit_seq(Fixed {
+            de: self,
+            len: fields.len(),
+        })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, CodecError> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, CodecError> {
+        Err(CodecError::Message(
+            "typed codec does not store identifiers".into(),
+        ))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, CodecError> {
+        Err(CodecError::Message(
+            "typed codec cannot skip unknown values".into(),
+        ))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Compound access
+// ------------------------------------------------------------------------------------------------
+
+/// Tuples and structs: exactly `len` concatenated elements.
+struct Fixed<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    len: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for Fixed<'_, 'de> {
+    type Error = CodecError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, CodecError> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+/// Sequences and maps: elements prefixed by [`SEQ_MORE`], ended by
+/// [`SEQ_END`].
+struct Terminated<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+}
+
+impl<'de> de::SeqAccess<'de> for Terminated<'_, 'de> {
+    type Error = CodecError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, CodecError> {
+        if !self.de.seq_more()? {
+            return Ok(None);
+        }
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Terminated<'_, 'de> {
+    type Error = CodecError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, CodecError> {
+        if !self.de.seq_more()? {
+            return Ok(None);
+        }
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, CodecError> {
+        seed.deserialize(&mut *self.de)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut Deserializer<'de> {
+    type Error = CodecError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self), CodecError> {
+        let index = u32::from_be_bytes(self.take()?);
+        let value = seed.deserialize(U32Deserializer::<CodecError>::new(index))?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut Deserializer<'de> {
+    type Error = CodecError;
+
+    fn unit_variant(self) -> Result<(), CodecError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, CodecError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, CodecError> {
+        visitor.visit_seq(Fixed { de: self, len })
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, CodecError> {
+        visitor.visit_seq(Fixed {
+            de: self,
+            len: fields.len(),
+        })
+    }
+}
@@ -0,0 +1,225 @@
+//! Typed key-value layer over [`Db`] (feature `typed`).
+//!
+//! [`TypedDb<K, V>`] stores serde-serializable keys and values, so
+//! application code does not hand-roll byte encodings. Keys are encoded
+//! with an **order-preserving** codec: for any two keys `a < b` (by their
+//! `Ord`), `encode(a) < encode(b)` byte-wise, so [`TypedDb::scan`] returns
+//! keys in their natural order.
+//!
+//! The same codec is used for values; [`encode`] and [`decode`] expose it
+//! for callers that build keys for the untyped [`Db`] themselves.
+//!
+//! # Layout
+//!
+//! | Type | Encoding |
+//! |------|----------|
+//! | `bool`, `u8` | one byte |
+//! | `u16` … `u128` | big-endian, fixed width |
+//! | `i8` … `i128` | big-endian with the sign bit flipped |
+//! | `f32`, `f64` | IEEE bits; sign bit flipped for positives, all bits inverted for negatives |
+//! | `char` | `u32` code point |
+//! | `str`, bytes | bytes with `0x00` escaped as `0x00 0xFF`, terminated by `0x00 0x00` |
+//! | `Option<T>` | `0x00` for `None`, `0x01` + `T` for `Some` |
+//! | tuples, structs | fields concatenated in declaration order |
+//! | sequences, maps | each element prefixed by `0x01`, terminated by `0x00` |
+//! | enums | `u32` variant index, then the variant's fields |
+//! | unit, unit structs | nothing |
+//!
+//! Tuples and structs therefore sort field by field, like a derived `Ord`.
+//!
+//! The codec is not self-describing: values must be decoded as the type
+//! they were encoded from, and `#[serde(flatten)]`, untagged enums, and
+//! other attributes relying on `deserialize_any` are not supported.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use aeternusdb::DbConfig;
+//! use aeternusdb::typed::TypedDb;
+//!
+//! // (user id, sequence number) -> message
+//! let db: TypedDb<(u64, u32), String> =
+//!     TypedDb::open("/tmp/typed_db", DbConfig::default()).unwrap();
+//!
+//! db.put(&(7, 1), &"hello".to_string()).unwrap();
+//! db.put(&(7, 2), &"world".to_string()).unwrap();
+//! db.put(&(8, 1), &"other user".to_string()).unwrap();
+//!
+//! let messages = db.scan(&(7, 0), &(8, 0)).unwrap();
+//! assert_eq!(messages.len(), 2);
+//! ```
+
+mod de;
+mod ser;
+
+use std::marker::PhantomData;
+use std::path::Path;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+use crate::{Db, DbConfig, DbError};
+
+// ------------------------------------------------------------------------------------------------
+// Error types
+// ------------------------------------------------------------------------------------------------
+
+/// Errors produced by the order-preserving codec.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum CodecError {
+    /// Custom error raised by a `Serialize` / `Deserialize` implementation.
+    #[error("{0}")]
+    Message(String),
+
+    /// The input ended in the middle of a value.
+    #[error("unexpected end of input")]
+    UnexpectedEof,
+
+    /// Bytes remained after the value was decoded.
+    #[error("{0} trailing bytes after value")]
+    TrailingBytes(usize),
+
+    /// A tag or escape byte had an unexpected value.
+    #[error("invalid byte 0x{0:02x}")]
+    InvalidByte(u8),
+
+    /// A string was not valid UTF-8.
+    #[error("invalid UTF-8 in string")]
+    InvalidUtf8,
+
+    /// A `char` was not a valid Unicode scalar value.
+    #[error("invalid char code point {0:#x}")]
+    InvalidChar(u32),
+}
+
+impl serde::ser::Error for CodecError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Message(msg.to_string())
+    }
+}
+
+impl serde::de::Error for CodecError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Message(msg.to_string())
+    }
+}
+
+/// Errors returned by [`TypedDb`] operations.
+#[derive(Debug, Error)]
+pub enum TypedError {
+    /// The underlying database operation failed.
+    #[error("{0}")]
+    Db(#[from] DbError),
+
+    /// A key or value could not be encoded or decoded.
+    #[error("codec error: {0}")]
+    Codec(#[from] CodecError),
+}
+
+// ------------------------------------------------------------------------------------------------
+// Codec entry points
+// ------------------------------------------------------------------------------------------------
+
+/// Encodes `value` with the order-preserving codec.
+pub fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, CodecError> {
+    let mut out = Vec::new();
+    ser::serialize_into(value, &mut out)?;
+    Ok(out)
+}
+
+/// Decodes a `T` previously produced by [`encode`].
+///
+/// # Errors
+///
+/// Fails if `bytes` is truncated, malformed for `T`, or has bytes left
+/// over after the value.
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, CodecError> {
+    de::deserialize_from(bytes)
+}
+
+// ------------------------------------------------------------------------------------------------
+// TypedDb
+// ------------------------------------------------------------------------------------------------
+
+/// A [`Db`] storing keys of type `K` and values of type `V`.
+///
+/// Every method encodes its arguments with [`encode`], forwards to the
+/// matching [`Db`] method, and decodes the results. The same constraints
+/// apply: a key or value that encodes to zero bytes (such as `()`) is
+/// rejected with [`DbError::InvalidArgument`].
+///
+/// A database should be accessed with a single `K` / `V` pair; reading
+/// keys written under a different type yields [`TypedError::Codec`].
+pub struct TypedDb<K, V> {
+    db: Db,
+    _types: PhantomData<fn() -> (K, V)>,
+}
+
+impl<K, V> TypedDb<K, V>
+where
+    K: Serialize + DeserializeOwned,
+    V: Serialize + DeserializeOwned,
+{
+    /// Opens (or creates) a database at `path`; see [`Db::open`].
+    pub fn open(path: impl AsRef<Path>, config: DbConfig) -> Result<Self, TypedError> {
+        Ok(Self::new(Db::open(path, config)?))
+    }
+
+    /// Wraps an already opened [`Db`].
+    pub fn new(db: Db) -> Self {
+        Self {
+            db,
+            _types: PhantomData,
+        }
+    }
+
+    /// Returns the underlying untyped database.
+    pub fn db(&self) -> &Db {
+        &self.db
+    }
+
+    /// Unwraps the underlying untyped database.
+    pub fn into_inner(self) -> Db {
+        self.db
+    }
+
+    /// Inserts or updates `key`; see [`Db::put`].
+    pub fn put(&self, key: &K, value: &V) -> Result<(), TypedError> {
+        Ok(self.db.put(&encode(key)?, &encode(value)?)?)
+    }
+
+    /// Returns the value for `key`, or `None`; see [`Db::get`].
+    pub fn get(&self, key: &K) -> Result<Option<V>, TypedError> {
+        match self.db.get(&encode(key)?)? {
+            Some(bytes) => Ok(Some(decode(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Deletes `key`; see [`Db::delete`].
+    pub fn delete(&self, key: &K) -> Result<(), TypedError> {
+        Ok(self.db.delete(&encode(key)?)?)
+    }
+
+    /// Deletes all keys in `[start, end)`; see [`Db::delete_range`].
+    pub fn delete_range(&self, start: &K, end: &K) -> Result<(), TypedError> {
+        Ok(self.db.delete_range(&encode(start)?, &encode(end)?)?)
+    }
+
+    /// Returns all live pairs with keys in `[start, end)`, in key order;
+    /// see [`Db::scan`].
+    pub fn scan(&self, start: &K, end: &K) -> Result<Vec<(K, V)>, TypedError> {
+        self.db
+            .scan(&encode(start)?, &encode(end)?)?
+            .into_iter()
+            .map(|(k, v)| Ok((decode(&k)?, decode(&v)?)))
+            .collect()
+    }
+
+    /// Gracefully shuts down the database; see [`Db::close`].
+    pub fn close(&self) -> Result<(), TypedError> {
+        Ok(self.db.close()?)
+    }
+}
@@ -0,0 +1,287 @@
+//! Public record types shared by the APIs that expose stored versions.
+//!
+//! The engine keeps its own representations of puts, deletes, and range
+//! tombstones, shaped by the WAL, memtable, and SSTable encodings. APIs
+//! that hand versions to callers — [`SstReader`](crate::sst::SstReader)
+//! and the change feed of [`Db::subscribe`](crate::Db::subscribe) — use
+//! the types of this module instead, so every such API speaks the same
+//! vocabulary and internal refactors do not break callers:
+//!
+//! - [`Record`] — one stored version: a put, a point delete, or a range
+//!   delete, with its LSN and commit timestamp.
+//! - [`PointEntry`] — a put or point delete of one key.
+//! - [`RangeTombstone`] — a delete of every key in `[start, end)`.
+//! - [`RecordKind`] — which of the three a version is.
+//! - [`VersionRank`] — where a version stands against the others of its
+//!   key.
+//!
+//! Versions are **unresolved**: a record says what one write stored, not
+//! whether it is still visible. Of the versions of a key, the one with
+//! the greatest [`VersionRank`] is visible: the newest LSN wins, and at an
+//! equal LSN a put or point delete beats a range tombstone, then the later
+//! timestamp wins. A range tombstone hides only the versions it outranks
+//! ([`RangeTombstone::hides`]), i.e. strictly older ones. Point lookups
+//! and scans resolve versions by these rules alone.
+
+use crate::engine;
+
+pub use crate::engine::utils::VersionRank;
+
+/// Kind of a stored version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RecordKind {
+    /// A key set to a value.
+    Put,
+
+    /// A point delete of one key.
+    Delete,
+
+    /// A delete of every key in a half-open range.
+    RangeDelete,
+}
+
+/// One stored version: a put, a point delete, or a range delete.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Record {
+    /// `key` was set to `value`.
+    Put {
+        key: Vec<u8>,
+        value: Vec<u8>,
+        lsn: u64,
+        timestamp: u64,
+    },
+
+    /// `key` was deleted.
+    Delete {
+        key: Vec<u8>,
+        lsn: u64,
+        timestamp: u64,
+    },
+
+    /// Every key in `[start, end)` was deleted; an empty `end` is
+    /// unbounded.
+    RangeDelete {
+        start: Vec<u8>,
+        end: Vec<u8>,
+        lsn: u64,
+        timestamp: u64,
+    },
+}
+
+impl Record {
+    /// Returns the kind of this version.
+    pub fn kind(&self) -> RecordKind {
+        match self {
+            Record::Put { .. } => RecordKind::Put,
+            Record::Delete { .. } => RecordKind::Delete,
+            Record::RangeDelete { .. } => RecordKind::RangeDelete,
+        }
+    }
+
+    /// Returns the key written, or the start of a deleted range.
+    pub fn key(&self) -> &[u8] {
+        match self {
+            Record::Put { key, .. } | Record::Delete { key, .. } => key,
+            Record::RangeDelete { start, .. } => start,
+        }
+    }
+
+    /// Returns the value of a put, or `None` for a delete.
+    pub fn value(&self) -> Option<&[u8]> {
+        match self {
+            Record::Put { value, .. } => Some(value),
+            Record::Delete { .. } | Record::RangeDelete { .. } => None,
+        }
+    }
+
+    /// Returns the log sequence number of the write.
+    pub fn lsn(&self) -> u64 {
+        match self {
+            Record::Put { lsn, .. }
+            | Record::Delete { lsn, .. }
+            | Record::RangeDelete { lsn, .. } => *lsn,
+        }
+    }
+
+    /// Returns the commit timestamp of the write (UNIX epoch nanos).
+    pub fn timestamp(&self) -> u64 {
+        match self {
+            Record::Put { timestamp, .. }
+            | Record::Delete { timestamp, .. }
+            | Record::RangeDelete { timestamp, .. } => *timestamp,
+        }
+    }
+
+    /// Returns where this version stands against the other versions of
+    /// its key; see [`VersionRank`].
+    pub fn rank(&self) -> VersionRank {
+        VersionRank {
+            lsn: self.lsn(),
+            point: self.kind() != RecordKind::RangeDelete,
+            timestamp: self.timestamp(),
+        }
+    }
+}
+
+impl From<PointEntry> for Record {
+    fn from(entry: PointEntry) -> Self {
+        let PointEntry {
+            key,
+            value,
+            lsn,
+            timestamp,
+        } = entry;
+        match value {
+            Some(value) => Record::Put {
+                key,
+                value,
+                lsn,
+                timestamp,
+            },
+            None => Record::Delete {
+                key,
+                lsn,
+                timestamp,
+            },
+        }
+    }
+}
+
+impl From<RangeTombstone> for Record {
+    fn from(tombstone: RangeTombstone) -> Self {
+        Record::RangeDelete {
+            start: tombstone.start,
+            end: tombstone.end,
+            lsn: tombstone.lsn,
+            timestamp: tombstone.timestamp,
+        }
+    }
+}
+
+/// A put or point delete of one key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PointEntry {
+    /// User key.
+    pub key: Vec<u8>,
+
+    /// Stored value, or `None` for a point delete.
+    pub value: Option<Vec<u8>>,
+
+    /// Log sequence number of this version.
+    pub lsn: u64,
+
+    /// Commit timestamp of this version (UNIX epoch nanos).
+    pub timestamp: u64,
+}
+
+impl PointEntry {
+    /// Returns [`RecordKind::Put`] or [`RecordKind::Delete`].
+    pub fn kind(&self) -> RecordKind {
+        if self.value.is_some() {
+            RecordKind::Put
+        } else {
+            RecordKind::Delete
+        }
+    }
+}
+
+/// A range tombstone deleting keys in `[start, end)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeTombstone {
+    /// Start of the deleted range (inclusive).
+    pub start: Vec<u8>,
+
+    /// End of the deleted range (exclusive). Empty if the range is
+    /// unbounded, as written by
+    /// [`Db::delete_range_from`](crate::Db::delete_range_from).
+    pub end: Vec<u8>,
+
+    /// Log sequence number of the tombstone.
+    pub lsn: u64,
+
+    /// Commit timestamp of the tombstone (UNIX epoch nanos).
+    pub timestamp: u64,
+}
+
+impl RangeTombstone {
+    /// Returns `true` if the tombstone covers `key`.
+    pub fn covers(&self, key: &[u8]) -> bool {
+        self.start.as_slice() <= key && engine::utils::below_end(key, &self.end)
+    }
+
+    /// Returns `true` if the tombstone hides the version of `key` ranked
+    /// `version`: it covers the key and its LSN is higher. A put or point
+    /// delete of an equal LSN stays visible, whatever its timestamp.
+    pub fn hides(&self, key: &[u8], version: VersionRank) -> bool {
+        let rank = VersionRank {
+            lsn: self.lsn,
+            point: false,
+            timestamp: self.timestamp,
+        };
+        self.covers(key) && rank > version
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Conversions from the engine's representations
+// ------------------------------------------------------------------------------------------------
+
+impl From<engine::Record> for Record {
+    fn from(record: engine::Record) -> Self {
+        match record {
+            engine::Record::Put {
+                key,
+                value,
+                lsn,
+                timestamp,
+            } => Record::Put {
+                key,
+                value,
+                lsn,
+                timestamp,
+            },
+            engine::Record::Delete {
+                key,
+                lsn,
+                timestamp,
+            } => Record::Delete {
+                key,
+                lsn,
+                timestamp,
+            },
+            engine::Record::RangeDelete {
+                start,
+                end,
+                lsn,
+                timestamp,
+            } => Record::RangeDelete {
+                start,
+                end,
+                lsn,
+                timestamp,
+            },
+        }
+    }
+}
+
+impl From<engine::PointEntry> for PointEntry {
+    fn from(entry: engine::PointEntry) -> Self {
+        Self {
+            key: entry.key,
+            value: entry.value,
+            lsn: entry.lsn,
+            timestamp: entry.timestamp,
+        }
+    }
+}
+
+impl From<engine::RangeTombstone> for RangeTombstone {
+    fn from(tombstone: engine::RangeTombstone) -> Self {
+        Self {
+            start: tombstone.start,
+            end: tombstone.end,
+            lsn: tombstone.lsn,
+            timestamp: tombstone.timestamp,
+        }
+    }
+}
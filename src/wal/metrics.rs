@@ -0,0 +1,150 @@
+//! Append, sync, and rotation counters of a WAL.
+//!
+//! Every [`Wal`](super::Wal) counts into a shared [`WalCounters`]: its own
+//! by default, or one handed over with
+//! [`Wal::set_metrics`](super::Wal::set_metrics) so that every segment of
+//! a log — the memtable WALs of one engine — adds to the same totals.
+//! [`WalStats`] is a snapshot of them.
+
+use std::ops::AddAssign;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Upper bounds of the buckets of [`WalStats::sync_histogram`]; the last
+/// bucket holds every fsync that took at least the last bound.
+pub const WAL_SYNC_HISTOGRAM_BOUNDS: [Duration; 5] = [
+    Duration::from_micros(100),
+    Duration::from_millis(1),
+    Duration::from_millis(10),
+    Duration::from_millis(100),
+    Duration::from_secs(1),
+];
+
+/// Buckets of [`WalStats::sync_histogram`].
+const SYNC_BUCKETS: usize = WAL_SYNC_HISTOGRAM_BOUNDS.len() + 1;
+
+/// Snapshot of the counters of the memtable WALs.
+///
+/// Returned per database in
+/// [`WriteStats::wal`](crate::WriteStats::wal). Counters start at zero
+/// when the database is opened; records replayed by recovery are not
+/// counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WalStats {
+    /// Records appended. A batch is appended as one record.
+    pub records_appended: u64,
+
+    /// Bytes appended, including each record's length prefix and
+    /// checksum.
+    pub bytes_appended: u64,
+
+    /// Fsyncs of a WAL file, successful or not: one per appended record
+    /// unless appends are unsynced, plus explicit syncs.
+    pub syncs: u64,
+
+    /// Total time spent in those fsyncs, in nanoseconds.
+    pub sync_nanos: u64,
+
+    /// Fsyncs by duration: bucket `i` counts those shorter than
+    /// [`WAL_SYNC_HISTOGRAM_BOUNDS`]`[i]` and at least as long as the
+    /// bound before it; the last bucket counts the rest.
+    pub sync_histogram: [u64; SYNC_BUCKETS],
+
+    /// Switches to a new WAL segment: one per memtable frozen, and one
+    /// per clear.
+    pub rotations: u64,
+}
+
+impl WalStats {
+    /// Average fsync duration.
+    ///
+    /// Returns [`Duration::ZERO`] before the first fsync.
+    pub fn mean_sync_duration(&self) -> Duration {
+        if self.syncs == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_nanos(self.sync_nanos / self.syncs)
+    }
+
+    /// Counters accumulated since `earlier`, a snapshot of the same
+    /// counters.
+    pub(crate) fn since(&self, earlier: &Self) -> Self {
+        let mut sync_histogram = self.sync_histogram;
+        for (bucket, before) in sync_histogram.iter_mut().zip(earlier.sync_histogram) {
+            *bucket = bucket.saturating_sub(before);
+        }
+        Self {
+            records_appended: self
+                .records_appended
+                .saturating_sub(earlier.records_appended),
+            bytes_appended: self.bytes_appended.saturating_sub(earlier.bytes_appended),
+            syncs: self.syncs.saturating_sub(earlier.syncs),
+            sync_nanos: self.sync_nanos.saturating_sub(earlier.sync_nanos),
+            sync_histogram,
+            rotations: self.rotations.saturating_sub(earlier.rotations),
+        }
+    }
+}
+
+impl AddAssign for WalStats {
+    fn add_assign(&mut self, other: Self) {
+        self.records_appended += other.records_appended;
+        self.bytes_appended += other.bytes_appended;
+        self.syncs += other.syncs;
+        self.sync_nanos += other.sync_nanos;
+        for (bucket, other) in self.sync_histogram.iter_mut().zip(other.sync_histogram) {
+            *bucket += other;
+        }
+        self.rotations += other.rotations;
+    }
+}
+
+/// Live counters behind a [`WalStats`] snapshot.
+#[derive(Debug, Default)]
+pub(crate) struct WalCounters {
+    records_appended: AtomicU64,
+    bytes_appended: AtomicU64,
+    syncs: AtomicU64,
+    sync_nanos: AtomicU64,
+    sync_histogram: [AtomicU64; SYNC_BUCKETS],
+    rotations: AtomicU64,
+}
+
+impl WalCounters {
+    /// Counts one record of `bytes` bytes on disk.
+    pub(crate) fn record_append(&self, bytes: u64) {
+        self.records_appended.fetch_add(1, Ordering::Relaxed);
+        self.bytes_appended.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// Counts one fsync that took `elapsed`.
+    pub(crate) fn record_sync(&self, elapsed: Duration) {
+        let bucket = WAL_SYNC_HISTOGRAM_BOUNDS
+            .iter()
+            .position(|bound| elapsed < *bound)
+            .unwrap_or(SYNC_BUCKETS - 1);
+        self.syncs.fetch_add(1, Ordering::Relaxed);
+        self.sync_nanos.fetch_add(
+            u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+        self.sync_histogram[bucket].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts one switch to a new segment.
+    pub(crate) fn record_rotation(&self) {
+        self.rotations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the current values.
+    pub(crate) fn snapshot(&self) -> WalStats {
+        WalStats {
+            records_appended: self.records_appended.load(Ordering::Relaxed),
+            bytes_appended: self.bytes_appended.load(Ordering::Relaxed),
+            syncs: self.syncs.load(Ordering::Relaxed),
+            sync_nanos: self.sync_nanos.load(Ordering::Relaxed),
+            sync_histogram: std::array::from_fn(|i| self.sync_histogram[i].load(Ordering::Relaxed)),
+            rotations: self.rotations.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -39,9 +39,15 @@
 //!
 //! # Guarantees
 //!
-//! - **Durability:** Every `append()` is followed by an `fsync()` via [`File::sync_all`].  
+//! - **Durability:** Every `append()` is followed by an `fsync()` via [`File::sync_all`],
+//!   unless disabled with [`Wal::set_sync_appends`] (bulk loading). A new WAL file also has
+//!   its parent directory synced, so it cannot vanish with its records after a crash.  
+//! - **Failed syncs:** Once an `fsync()` fails, the WAL refuses every further append and
+//!   sync with [`WalError::SyncFailed`] rather than retry — see [`crate::durability`].  
 //! - **Integrity:** Both header and record checksums are verified during replay.  
 //! - **Corruption detection:** Replay stops at first failed checksum or truncated write.  
+//! - **Torn-tail recovery:** [`Wal::recover_iter`] tells a torn final record apart from
+//!   corruption in the middle of the log — see [`WalRecoveryMode`].
 //! - **Safety:** Thread-safe, generic over any [`crate::encoding`] `Encode`/`Decode` type.
 
 // ------------------------------------------------------------------------------------------------
@@ -51,6 +57,14 @@
 #[cfg(test)]
 mod tests;
 
+// ------------------------------------------------------------------------------------------------
+// Metrics
+// ------------------------------------------------------------------------------------------------
+
+mod metrics;
+pub(crate) use metrics::WalCounters;
+pub use metrics::{WAL_SYNC_HISTOGRAM_BOUNDS, WalStats};
+
 // ------------------------------------------------------------------------------------------------
 // Includes
 // ------------------------------------------------------------------------------------------------
@@ -59,10 +73,16 @@ use std::{
     fs::{File, OpenOptions},
     io::{self, Read, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
-    sync::{Arc, Mutex},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Instant,
 };
 
+use crate::durability;
 use crate::encoding::{self, EncodingError};
+use crate::slow_op;
 use crc32fast::Hasher as Crc32;
 use std::ffi::OsStr;
 use thiserror::Error;
@@ -105,6 +125,56 @@ pub enum WalError {
     /// Internal consistency or locking error.
     #[error("Internal error: {0}")]
     Internal(String),
+
+    /// A corrupt record was found with further data after it — the log is
+    /// damaged in the middle, not merely torn at the end.
+    #[error("Corrupt record at offset {0} before the end of the log")]
+    CorruptRecord(u64),
+
+    /// The final record is incomplete or corrupt, and the recovery mode
+    /// does not allow dropping it.
+    #[error("Torn record at offset {0} at the end of the log")]
+    TornTail(u64),
+
+    /// An earlier fsync of this WAL failed; records appended since the
+    /// last successful sync may be lost, so the WAL accepts no more.
+    #[error("WAL unusable after a failed sync")]
+    SyncFailed,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Recovery mode
+// ------------------------------------------------------------------------------------------------
+
+/// How `Wal::recover_iter` treats damaged records.
+///
+/// A crash during an append can leave the last record incomplete — a
+/// *torn tail*. Damage followed by further records is a different matter:
+/// it points at media corruption, and silently dropping everything after
+/// it would lose acknowledged writes. A damaged record counts as the last
+/// one only if no record with a valid checksum follows it, whatever its
+/// length prefix claims.
+///
+/// A zero-filled tail (e.g. from a preallocated segment) is always treated
+/// as a clean end of log, in every mode.
+///
+/// Whenever replay stops early without an error, the damaged tail is cut
+/// off the file so that new appends land directly after the last good
+/// record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WalRecoveryMode {
+    /// Drop a torn final record; fail with `WalError::CorruptRecord` if
+    /// corruption appears before the last record.
+    #[default]
+    TolerateCorruptedTail,
+
+    /// Fail on any damaged record, including a torn final record
+    /// (`WalError::TornTail`).
+    AbsoluteConsistency,
+
+    /// Stop at the first damaged record wherever it is and discard
+    /// everything after it.
+    PointInTime,
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -253,6 +323,16 @@ pub struct Wal<T: WalData> {
     /// Persistent header with metadata and integrity info.
     header: WalHeader,
 
+    /// Whether every append is followed by an fsync.
+    sync_appends: AtomicBool,
+
+    /// Set once an fsync has failed; appends and syncs are refused after.
+    sync_failed: AtomicBool,
+
+    /// Append, sync, and rotation counters; this WAL's own unless shared
+    /// with [`set_metrics`](Self::set_metrics).
+    metrics: Arc<WalCounters>,
+
     /// Marker field to associate this WAL with the generic record type `T`.
     _phantom: std::marker::PhantomData<T>,
 }
@@ -285,7 +365,8 @@ impl<T: WalData> Wal<T> {
             );
 
             write_header(&mut file, &header)?;
-            file.sync_all()?;
+            durability::sync_file(&file)?;
+            durability::sync_parent(path_ref)?;
 
             info!(path = %path_ref.display(), seq = wal_seq, "WAL created with new header");
 
@@ -316,10 +397,26 @@ impl<T: WalData> Wal<T> {
             inner_file: Arc::new(Mutex::new(file)),
             path: path_ref.to_path_buf(),
             header,
+            sync_appends: AtomicBool::new(true),
+            sync_failed: AtomicBool::new(false),
+            metrics: Arc::new(WalCounters::default()),
             _phantom: std::marker::PhantomData,
         })
     }
 
+    /// Counts appends, syncs, and rotations into `metrics` from now on,
+    /// so several WALs can add to the same totals.
+    pub(crate) fn set_metrics(&mut self, metrics: Arc<WalCounters>) {
+        self.metrics = metrics;
+    }
+
+    /// Returns the counters of this WAL, shared with any other WAL given
+    /// the same ones.
+    #[allow(dead_code)]
+    pub(crate) fn stats(&self) -> WalStats {
+        self.metrics.snapshot()
+    }
+
     /// Parse `wal_seq` from filename if it matches `<seq>.log`.
     fn parse_seq_from_path(path: &Path) -> Option<u64> {
         let name = path.file_name().and_then(OsStr::to_str)?;
@@ -353,11 +450,18 @@ impl<T: WalData> Wal<T> {
             .inner_file
             .lock()
             .map_err(|_| WalError::Internal("Mutex poisoned".into()))?;
+        if self.sync_failed.load(Ordering::Acquire) {
+            return Err(WalError::SyncFailed);
+        }
 
         guard.write_all(&len_bytes)?;
         guard.write_all(&record_bytes)?;
         guard.write_all(&checksum.to_le_bytes())?;
-        guard.sync_all()?;
+        self.metrics
+            .record_append((2 * U32_SIZE + record_bytes.len()) as u64);
+        if self.sync_appends.load(Ordering::Relaxed) {
+            self.sync_locked(&guard)?;
+        }
 
         trace!(
             len = record_len,
@@ -380,14 +484,90 @@ impl<T: WalData> Wal<T> {
             file: Arc::clone(&self.inner_file),
             offset: start_offset,
             max_record_size: self.header.max_record_size as usize,
+            recovery: None,
+            done: false,
+            dropped_bytes: 0,
+            _phantom: std::marker::PhantomData,
+        })
+    }
+
+    /// Opens the WAL at `path` read-only and returns a raw replay
+    /// iterator over it, like [`replay_iter`](Self::replay_iter).
+    ///
+    /// Nothing is created or truncated, so this is safe while another
+    /// process appends to the file. A record still being appended shows
+    /// up as a damaged record at the end.
+    pub fn replay_read_only(path: impl AsRef<Path>) -> Result<WalIter<T>, WalError> {
+        let mut file = File::open(path.as_ref())?;
+        let header = read_and_validate_header(&mut file)?;
+
+        Ok(WalIter {
+            file: Arc::new(Mutex::new(file)),
+            offset: WalHeader::HEADER_DISK_SIZE as u64,
+            max_record_size: header.max_record_size as usize,
+            recovery: None,
+            done: false,
+            dropped_bytes: 0,
             _phantom: std::marker::PhantomData,
         })
     }
 
+    /// Returns an iterator that replays the WAL for crash recovery.
+    ///
+    /// Unlike [`replay_iter`](Self::replay_iter), which yields the first
+    /// damaged record as an error and leaves the file untouched, this
+    /// iterator classifies damage according to `mode`: a torn or
+    /// zero-filled tail ends replay cleanly and is truncated from the file,
+    /// while corruption before the last record is reported as
+    /// [`WalError::CorruptRecord`] (unless `mode` is
+    /// [`WalRecoveryMode::PointInTime`]).
+    pub fn recover_iter(&self, mode: WalRecoveryMode) -> Result<WalIter<T>, WalError> {
+        let mut iter = self.replay_iter()?;
+        iter.recovery = Some(mode);
+        Ok(iter)
+    }
+
+    /// Enables or disables the fsync after every [`append`](Self::append).
+    ///
+    /// With syncing disabled, appended records reach the OS page cache
+    /// only and may be lost on power failure until [`sync`](Self::sync)
+    /// is called.
+    pub fn set_sync_appends(&self, sync: bool) {
+        self.sync_appends.store(sync, Ordering::Relaxed);
+    }
+
+    /// Fsyncs every record appended so far.
+    pub fn sync(&self) -> Result<(), WalError> {
+        let guard = self
+            .inner_file
+            .lock()
+            .map_err(|_| WalError::Internal("Mutex poisoned".into()))?;
+        self.sync_locked(&guard)
+    }
+
+    /// Fsyncs `file`, the locked WAL file. A failure is never retried:
+    /// the kernel may already have dropped the unsynced pages, so a second
+    /// fsync could succeed without them. The WAL is marked failed instead.
+    fn sync_locked(&self, file: &File) -> Result<(), WalError> {
+        if self.sync_failed.load(Ordering::Acquire) {
+            return Err(WalError::SyncFailed);
+        }
+        let started = Instant::now();
+        let synced = durability::sync_file(file);
+        let elapsed = started.elapsed();
+        slow_op::record_wal_sync(elapsed);
+        self.metrics.record_sync(elapsed);
+        synced.map_err(|e| {
+            self.sync_failed.store(true, Ordering::Release);
+            error!(path = %self.path.display(), error = %e, "WAL sync failed");
+            WalError::Io(e)
+        })
+    }
+
     /// Truncate (clear) the WAL and rewrite header.
     ///
     /// After truncation, WAL contains only the header and its checksum.
-    pub fn truncate(&mut self) -> Result<(), WalError> {
+    pub fn truncate(&self) -> Result<(), WalError> {
         let mut guard = self
             .inner_file
             .lock()
@@ -397,7 +577,7 @@ impl<T: WalData> Wal<T> {
         guard.seek(SeekFrom::Start(0))?;
 
         write_header(&mut *guard, &self.header)?;
-        guard.sync_all()?;
+        self.sync_locked(&guard)?;
 
         info!(path = %self.path.display(), "WAL truncated");
         Ok(())
@@ -406,7 +586,8 @@ impl<T: WalData> Wal<T> {
     /// Rotates to a new WAL segment with the next sequence number.
     ///
     /// Syncs the current WAL, opens a new WAL file with `wal_seq + 1`,
-    /// and replaces `self` with the new instance.
+    /// and replaces `self` with the new instance, which keeps counting
+    /// into the same metrics.
     ///
     /// Returns the new WAL sequence number.
     #[allow(dead_code)]
@@ -416,7 +597,7 @@ impl<T: WalData> Wal<T> {
                 .inner_file
                 .lock()
                 .map_err(|_| WalError::Internal("Mutex poisoned".into()))?;
-            guard.sync_all()?;
+            self.sync_locked(&guard)?;
         }
 
         let next_seq = self
@@ -429,7 +610,9 @@ impl<T: WalData> Wal<T> {
         let dir = cur_path.parent().unwrap_or_else(|| Path::new("."));
         let next_path = dir.join(format!("{next_seq:06}.log"));
 
-        let new_wal = Wal::<T>::open(&next_path, Some(self.header.max_record_size))?;
+        let mut new_wal = Wal::<T>::open(&next_path, Some(self.header.max_record_size))?;
+        new_wal.set_metrics(Arc::clone(&self.metrics));
+        new_wal.metrics.record_rotation();
         *self = new_wal;
 
         Ok(next_seq)
@@ -511,6 +694,15 @@ pub struct WalIter<T: WalData> {
     /// Maximum allowed record size.
     max_record_size: usize,
 
+    /// Tail classification for crash recovery; `None` for a raw replay.
+    recovery: Option<WalRecoveryMode>,
+
+    /// Set once recovery has ended replay by truncating the tail.
+    done: bool,
+
+    /// Bytes of damaged records cut off the tail by recovery.
+    dropped_bytes: u64,
+
     /// Marker field to associate this WAL iterator with the generic record type `T`.
     _phantom: std::marker::PhantomData<T>,
 }
@@ -520,78 +712,83 @@ impl<T: WalData> std::fmt::Debug for WalIter<T> {
         f.debug_struct("WalIter")
             .field("offset", &self.offset)
             .field("max_record_size", &self.max_record_size)
+            .field("recovery", &self.recovery)
             .finish_non_exhaustive()
     }
 }
 
-impl<T: WalData> Iterator for WalIter<T> {
-    type Item = Result<T, WalError>;
+/// Why reading a record failed.
+enum ReadFailure {
+    /// The file itself could not be accessed; never subject to recovery.
+    Fatal(WalError),
+
+    /// The bytes at the current offset do not form a valid record.
+    /// `record_end` is where the record would end according to its length
+    /// prefix, if that much was readable.
+    Damaged {
+        error: WalError,
+        record_end: Option<u64>,
+    },
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        // Lock only during the read of one record to reduce contention.
-        let mut guard = match self.file.lock() {
-            Ok(g) => g,
-            Err(_) => return Some(Err(WalError::Internal("Mutex poisoned".into()))),
-        };
+impl<T: WalData> WalIter<T> {
+    /// Returns the number of bytes of damaged records that recovery cut
+    /// off the end of the file so far.
+    ///
+    /// Zero-filled tails are not counted: they hold no records. Always
+    /// zero for a raw [`Wal::replay_iter`].
+    pub fn dropped_bytes(&self) -> u64 {
+        self.dropped_bytes
+    }
 
-        // Seek to our logical offset for deterministic reads.
-        if let Err(e) = guard.seek(SeekFrom::Start(self.offset)) {
-            return Some(Err(WalError::Io(e)));
+    /// Reads and decodes the record at `self.offset`, advancing past it.
+    ///
+    /// Returns `Ok(None)` at a clean end of file.
+    fn read_record(&mut self, file: &mut File) -> Result<Option<T>, ReadFailure> {
+        let file_len = file
+            .metadata()
+            .map_err(|e| ReadFailure::Fatal(WalError::Io(e)))?
+            .len();
+
+        if self.offset >= file_len {
+            trace!(offset = self.offset, "WAL replay reached end of file");
+            return Ok(None);
         }
 
+        // Seek to our logical offset for deterministic reads.
+        file.seek(SeekFrom::Start(self.offset))
+            .map_err(|e| ReadFailure::Fatal(WalError::Io(e)))?;
+
         // Read length prefix (4 bytes).
         let mut len_bytes = [0u8; U32_SIZE];
-
-        match guard.read_exact(&mut len_bytes) {
-            Ok(_) => {}
-            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
-                trace!(offset = self.offset, "WAL replay reached end of file");
-                return None;
-            }
-            Err(e) => return Some(Err(WalError::Io(e))),
-        }
+        read_exact_or_damaged(file, &mut len_bytes, None, "partial length")?;
 
         let record_len = u32::from_le_bytes(len_bytes) as usize;
+        let record_end = self.offset + (U32_SIZE + record_len + U32_SIZE) as u64;
+
         if record_len > self.max_record_size {
-            return Some(Err(WalError::RecordTooLarge(record_len)));
+            return Err(ReadFailure::Damaged {
+                error: WalError::RecordTooLarge(record_len),
+                record_end: Some(record_end),
+            });
         }
 
         trace!(offset = self.offset, len = record_len, "WAL reading record");
 
         // Read record bytes.
         let mut record_bytes = vec![0u8; record_len];
-        if let Err(e) = guard.read_exact(&mut record_bytes) {
-            if e.kind() == io::ErrorKind::UnexpectedEof {
-                warn!(
-                    offset = self.offset,
-                    len = record_len,
-                    "WAL truncated record (partial payload)"
-                );
-                return Some(Err(WalError::UnexpectedEof));
-            }
-            return Some(Err(WalError::Io(e)));
-        }
+        read_exact_or_damaged(file, &mut record_bytes, Some(record_end), "partial payload")?;
 
         // Read stored checksum.
         let mut checksum_bytes = [0u8; U32_SIZE];
-        if let Err(e) = guard.read_exact(&mut checksum_bytes) {
-            if e.kind() == io::ErrorKind::UnexpectedEof {
-                warn!(
-                    offset = self.offset,
-                    len = record_len,
-                    "WAL truncated record (partial checksum)"
-                );
-                return Some(Err(WalError::UnexpectedEof));
-            }
-            return Some(Err(WalError::Io(e)));
-        }
+        read_exact_or_damaged(
+            file,
+            &mut checksum_bytes,
+            Some(record_end),
+            "partial checksum",
+        )?;
         let stored_checksum = u32::from_le_bytes(checksum_bytes);
 
-        // Update offset for next iteration using current file cursor position.
-        if let Ok(pos) = guard.stream_position() {
-            self.offset = pos;
-        }
-
         // Verify checksum over [len || record_bytes].
         if let Err(e) = verify_crc(&[&len_bytes, &record_bytes], stored_checksum) {
             warn!(
@@ -599,17 +796,203 @@ impl<T: WalData> Iterator for WalIter<T> {
                 len = record_len,
                 "WAL record checksum mismatch"
             );
-            return Some(Err(e));
+            self.offset = record_end;
+            return Err(ReadFailure::Damaged {
+                error: e,
+                record_end: Some(record_end),
+            });
         }
 
         // Decode the record payload.
-        match encoding::decode_from_slice::<T>(&record_bytes) {
-            Ok((record, _)) => Some(Ok(record)),
-            Err(e) => Some(Err(WalError::Encoding(e))),
+        let record =
+            encoding::decode_from_slice::<T>(&record_bytes).map_err(|e| ReadFailure::Damaged {
+                error: WalError::Encoding(e),
+                record_end: Some(record_end),
+            })?;
+
+        self.offset = record_end;
+        Ok(Some(record.0))
+    }
+
+    /// Decides what a damaged record at `record_offset` means for recovery.
+    ///
+    /// Returns `None` if replay should end cleanly (the tail has been
+    /// truncated), or the error to surface otherwise.
+    fn recover_damaged(
+        &mut self,
+        file: &mut File,
+        mode: WalRecoveryMode,
+        record_offset: u64,
+        record_end: Option<u64>,
+        error: WalError,
+    ) -> Option<Result<T, WalError>> {
+        let outcome = (|| -> Result<Option<WalError>, WalError> {
+            let file_len = file.metadata()?.len();
+
+            if is_zero_filled(file, record_offset, file_len)? {
+                debug!(
+                    offset = record_offset,
+                    len = file_len - record_offset,
+                    "WAL zero-filled tail treated as end of log"
+                );
+                truncate_tail(file, record_offset)?;
+                return Ok(None);
+            }
+
+            // The length prefix of a damaged record is unverified: a
+            // corrupt one can point past the end of the file. The record
+            // is only the last one if no intact record follows it.
+            let is_last =
+                match record_end {
+                    None => true,
+                    Some(end) => end >= file_len || is_zero_filled(file, end, file_len)?,
+                } && !intact_record_after(file, record_offset + 1, file_len, self.max_record_size)?;
+
+            match (mode, is_last) {
+                (WalRecoveryMode::AbsoluteConsistency, true) => {
+                    Ok(Some(WalError::TornTail(record_offset)))
+                }
+                (WalRecoveryMode::PointInTime, _) | (_, true) => {
+                    warn!(
+                        offset = record_offset,
+                        dropped = file_len - record_offset,
+                        error = %error,
+                        "WAL tail dropped during recovery"
+                    );
+                    truncate_tail(file, record_offset)?;
+                    self.dropped_bytes = file_len - record_offset;
+                    Ok(None)
+                }
+                (_, false) => {
+                    error!(
+                        offset = record_offset,
+                        error = %error,
+                        "WAL corruption before the last record"
+                    );
+                    Ok(Some(WalError::CorruptRecord(record_offset)))
+                }
+            }
+        })();
+
+        match outcome {
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Ok(Some(e)) | Err(e) => Some(Err(e)),
         }
     }
 }
 
+impl<T: WalData> Iterator for WalIter<T> {
+    type Item = Result<T, WalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        // Lock only during the read of one record to reduce contention.
+        let file = Arc::clone(&self.file);
+        let mut guard = match file.lock() {
+            Ok(g) => g,
+            Err(_) => return Some(Err(WalError::Internal("Mutex poisoned".into()))),
+        };
+
+        let record_offset = self.offset;
+        match self.read_record(&mut guard) {
+            Ok(record) => record.map(Ok),
+            Err(ReadFailure::Fatal(e)) => Some(Err(e)),
+            Err(ReadFailure::Damaged { error, record_end }) => match self.recovery {
+                None => Some(Err(error)),
+                Some(mode) => {
+                    self.recover_damaged(&mut guard, mode, record_offset, record_end, error)
+                }
+            },
+        }
+    }
+}
+
+/// Fills `buf` from `file`, reporting a short read as a damaged record.
+fn read_exact_or_damaged(
+    file: &mut File,
+    buf: &mut [u8],
+    record_end: Option<u64>,
+    what: &str,
+) -> Result<(), ReadFailure> {
+    match file.read_exact(buf) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+            warn!(what, "WAL truncated record");
+            Err(ReadFailure::Damaged {
+                error: WalError::UnexpectedEof,
+                record_end,
+            })
+        }
+        Err(e) => Err(ReadFailure::Fatal(WalError::Io(e))),
+    }
+}
+
+/// Returns `true` if every byte in `[from, to)` is zero.
+fn is_zero_filled(file: &mut File, from: u64, to: u64) -> Result<bool, WalError> {
+    file.seek(SeekFrom::Start(from))?;
+    let mut remaining = to.saturating_sub(from);
+    let mut buf = [0u8; 4096];
+
+    while remaining > 0 {
+        let n = remaining.min(buf.len() as u64) as usize;
+        file.read_exact(&mut buf[..n])?;
+        if buf[..n].iter().any(|&b| b != 0) {
+            return Ok(false);
+        }
+        remaining -= n as u64;
+    }
+    Ok(true)
+}
+
+/// Returns `true` if a record whose checksum verifies starts anywhere in
+/// `[from, to)` and ends by `to`.
+///
+/// Only run on damaged logs, so the range is read whole.
+fn intact_record_after(
+    file: &mut File,
+    from: u64,
+    to: u64,
+    max_record_size: usize,
+) -> Result<bool, WalError> {
+    if to <= from {
+        return Ok(false);
+    }
+    file.seek(SeekFrom::Start(from))?;
+    let mut buf = vec![0u8; (to - from) as usize];
+    file.read_exact(&mut buf)?;
+
+    let read_u32 = |at: usize| u32::from_le_bytes(buf[at..at + U32_SIZE].try_into().unwrap());
+    for start in 0..buf.len().saturating_sub(2 * U32_SIZE - 1) {
+        let record_len = read_u32(start) as usize;
+        let crc_start = start + U32_SIZE + record_len;
+        if record_len > max_record_size || crc_start + U32_SIZE > buf.len() {
+            continue;
+        }
+        if compute_crc(&[&buf[start..crc_start]]) == read_u32(crc_start) {
+            trace!(
+                offset = from + start as u64,
+                "WAL intact record after damage"
+            );
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Cuts the file at `offset` so later appends follow the last good record.
+fn truncate_tail(file: &mut File, offset: u64) -> Result<(), WalError> {
+    file.set_len(offset)?;
+    durability::sync_file(file)?;
+    info!(offset, "WAL tail truncated");
+    Ok(())
+}
+
 // ------------------------------------------------------------------------------------------------
 // Header I/O helpers
 // ------------------------------------------------------------------------------------------------
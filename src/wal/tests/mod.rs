@@ -2,7 +2,9 @@ mod helpers;
 mod tests_basic;
 mod tests_corruption;
 mod tests_edge_cases;
+mod tests_metrics;
 mod tests_rotation;
+mod tests_torn_tail;
 mod tests_truncation;
 
 // Priority 2 — robustness tests
@@ -137,7 +137,7 @@ mod tests {
 
         let tmp = TempDir::new().unwrap();
         let path = tmp.path().join("000000.log");
-        let mut wal = Wal::open(path.to_str().unwrap(), None).unwrap();
+        let wal = Wal::open(path.to_str().unwrap(), None).unwrap();
 
         let insert = vec![
             MemTableRecord {
@@ -198,7 +198,7 @@ mod tests {
 
         let tmp = TempDir::new().unwrap();
         let path = tmp.path().join("000000.log");
-        let mut wal = Wal::open(path.to_str().unwrap(), None).unwrap();
+        let wal = Wal::open(path.to_str().unwrap(), None).unwrap();
 
         let batch1 = vec![
             ManifestRecord {
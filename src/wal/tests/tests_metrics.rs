@@ -0,0 +1,140 @@
+//! WAL append, sync, and rotation counters.
+//!
+//! Coverage:
+//! - Appends count records and their on-disk bytes, framing included
+//! - Every synced append and explicit `sync()` counts one fsync, bucketed
+//!   in the duration histogram
+//! - `rotate_next()` counts a rotation and keeps the counters
+//! - WALs given the same counters add to the same totals
+//!
+//! ## See also
+//! - [`tests_rotation`] — `rotate_next()` file naming
+
+#[cfg(test)]
+mod tests {
+    use crate::wal::tests::helpers::{WAL_CRC32_SIZE, WAL_HDR_SIZE};
+    use crate::wal::{Wal, WalCounters, WalStats};
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn synced_total(stats: &WalStats) -> u64 {
+        stats.sync_histogram.iter().sum()
+    }
+
+    /// # Scenario
+    /// Appends are counted with their framing.
+    ///
+    /// # Starting environment
+    /// Fresh WAL with per-append fsyncs.
+    ///
+    /// # Actions
+    /// 1. Append three records.
+    ///
+    /// # Expected behavior
+    /// Three records, bytes equal to the file past its header, and three
+    /// fsyncs, all of them in the histogram.
+    #[test]
+    fn appends_count_records_bytes_and_syncs() {
+        let tmp = TempDir::new().unwrap();
+        let wal: Wal<u64> = Wal::open(tmp.path().join("000000.log"), None).unwrap();
+
+        for i in 0..3u64 {
+            wal.append(&i).unwrap();
+        }
+
+        let stats = wal.stats();
+        assert_eq!(stats.records_appended, 3);
+        assert_eq!(
+            stats.bytes_appended,
+            wal.file_size().unwrap() - (WAL_HDR_SIZE + WAL_CRC32_SIZE) as u64
+        );
+        assert_eq!(stats.syncs, 3);
+        assert_eq!(synced_total(&stats), 3);
+        assert_eq!(stats.rotations, 0);
+    }
+
+    /// # Scenario
+    /// Unsynced appends count no fsync until an explicit sync.
+    ///
+    /// # Starting environment
+    /// Fresh WAL with per-append fsyncs disabled.
+    ///
+    /// # Actions
+    /// 1. Append two records.
+    /// 2. Call `sync()`.
+    ///
+    /// # Expected behavior
+    /// No fsync after step 1; one after step 2.
+    #[test]
+    fn unsynced_appends_count_explicit_sync_only() {
+        let tmp = TempDir::new().unwrap();
+        let wal: Wal<u64> = Wal::open(tmp.path().join("000000.log"), None).unwrap();
+        wal.set_sync_appends(false);
+
+        wal.append(&1).unwrap();
+        wal.append(&2).unwrap();
+        assert_eq!(wal.stats().records_appended, 2);
+        assert_eq!(wal.stats().syncs, 0);
+
+        wal.sync().unwrap();
+        let stats = wal.stats();
+        assert_eq!(stats.syncs, 1);
+        assert_eq!(synced_total(&stats), 1);
+        assert!(stats.mean_sync_duration() > std::time::Duration::ZERO);
+    }
+
+    /// # Scenario
+    /// Rotation keeps counting into the same counters.
+    ///
+    /// # Starting environment
+    /// Fresh WAL with per-append fsyncs.
+    ///
+    /// # Actions
+    /// 1. Append a record, rotate, append another.
+    ///
+    /// # Expected behavior
+    /// Two records and one rotation; the rotation's fsync of the old
+    /// segment is counted too.
+    #[test]
+    fn rotation_keeps_counters() {
+        let tmp = TempDir::new().unwrap();
+        let mut wal: Wal<u64> = Wal::open(tmp.path().join("000000.log"), None).unwrap();
+
+        wal.append(&1).unwrap();
+        wal.rotate_next().unwrap();
+        wal.append(&2).unwrap();
+
+        let stats = wal.stats();
+        assert_eq!(stats.records_appended, 2);
+        assert_eq!(stats.rotations, 1);
+        assert_eq!(stats.syncs, 3);
+    }
+
+    /// # Scenario
+    /// Two WALs share one set of counters.
+    ///
+    /// # Starting environment
+    /// Two fresh WALs given the same counters.
+    ///
+    /// # Actions
+    /// 1. Append one record to each.
+    ///
+    /// # Expected behavior
+    /// Both WALs, and the counters themselves, report two records.
+    #[test]
+    fn shared_counters_sum_wals() {
+        let tmp = TempDir::new().unwrap();
+        let metrics = Arc::new(WalCounters::default());
+        let mut first: Wal<u64> = Wal::open(tmp.path().join("000000.log"), None).unwrap();
+        let mut second: Wal<u64> = Wal::open(tmp.path().join("000001.log"), None).unwrap();
+        first.set_metrics(Arc::clone(&metrics));
+        second.set_metrics(Arc::clone(&metrics));
+
+        first.append(&1).unwrap();
+        second.append(&2).unwrap();
+
+        assert_eq!(first.stats().records_appended, 2);
+        assert_eq!(second.stats(), first.stats());
+        assert_eq!(metrics.snapshot().records_appended, 2);
+    }
+}
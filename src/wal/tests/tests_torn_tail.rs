@@ -0,0 +1,358 @@
+//! WAL crash-recovery tail classification tests.
+//!
+//! [`Wal::recover_iter`] distinguishes three situations that a raw
+//! [`Wal::replay_iter`] reports identically:
+//!
+//! - **Zero-filled tail** — preallocated, never-written space after the
+//!   last record. Always a clean end of log.
+//! - **Torn final record** — a crash interrupted the last append. Dropped
+//!   and truncated under `TolerateCorruptedTail`; an error under
+//!   `AbsoluteConsistency`.
+//! - **Mid-log corruption** — a damaged record followed by more data. An
+//!   error under `TolerateCorruptedTail`; dropped (with everything after
+//!   it) under `PointInTime`. A corrupt length prefix pointing past the
+//!   end of the file does not make a record the last one while intact
+//!   records follow.
+//!
+//! ## See also
+//! - [`tests_truncation`] — raw replay of truncated records
+//! - [`tests_corruption`] — raw replay of byte-flipped records
+
+#[cfg(test)]
+mod tests {
+    use crate::wal::tests::helpers::*;
+    use crate::wal::{Wal, WalError, WalRecoveryMode};
+    use std::fs::{self, OpenOptions};
+    use std::io::{Seek, SeekFrom, Write};
+    use tempfile::TempDir;
+
+    // ----------------------------------------------------------------
+    // Helpers
+    // ----------------------------------------------------------------
+
+    fn record(i: usize) -> MemTableRecord {
+        MemTableRecord {
+            key: format!("key_{i:04}").into_bytes(),
+            value: Some(format!("val_{i:04}").into_bytes()),
+            timestamp: i as u64,
+            deleted: false,
+        }
+    }
+
+    /// Write `count` records and return the file offset at which each
+    /// record starts, plus the final file size.
+    fn write_records(path: &std::path::Path, count: usize) -> (Vec<u64>, u64) {
+        let wal: Wal<MemTableRecord> = Wal::open(path, None).unwrap();
+        let mut offsets = Vec::new();
+        for i in 0..count {
+            offsets.push(wal.file_size().unwrap());
+            wal.append(&record(i)).unwrap();
+        }
+        let size = wal.file_size().unwrap();
+        (offsets, size)
+    }
+
+    fn overwrite(path: &std::path::Path, offset: u64, bytes: &[u8]) {
+        let mut f = OpenOptions::new().write(true).open(path).unwrap();
+        f.seek(SeekFrom::Start(offset)).unwrap();
+        f.write_all(bytes).unwrap();
+        f.sync_all().unwrap();
+    }
+
+    fn set_len(path: &std::path::Path, len: u64) {
+        let f = OpenOptions::new().write(true).open(path).unwrap();
+        f.set_len(len).unwrap();
+        f.sync_all().unwrap();
+    }
+
+    /// Recover with `mode`, returning the `Ok` records and the first error.
+    fn recover(
+        path: &std::path::Path,
+        mode: WalRecoveryMode,
+    ) -> (Vec<MemTableRecord>, Option<WalError>) {
+        let wal: Wal<MemTableRecord> = Wal::open(path, None).unwrap();
+        let mut records = Vec::new();
+        for item in wal.recover_iter(mode).unwrap() {
+            match item {
+                Ok(rec) => records.push(rec),
+                Err(e) => return (records, Some(e)),
+            }
+        }
+        (records, None)
+    }
+
+    // ----------------------------------------------------------------
+    // Tests
+    // ----------------------------------------------------------------
+
+    /// # Scenario
+    /// A zero-filled region follows the last record, as left by a
+    /// preallocated segment.
+    ///
+    /// # Actions
+    /// 1. Write 3 records, extend the file by 4 KiB of zeros.
+    /// 2. Recover under every mode.
+    ///
+    /// # Expected behavior
+    /// All 3 records, no error, and the zeros are truncated away.
+    #[test]
+    fn zero_filled_tail_is_clean_eof_in_every_mode() {
+        init_tracing();
+
+        for mode in [
+            WalRecoveryMode::TolerateCorruptedTail,
+            WalRecoveryMode::AbsoluteConsistency,
+            WalRecoveryMode::PointInTime,
+        ] {
+            let tmp = TempDir::new().unwrap();
+            let path = tmp.path().join("000000.log");
+            let (_, size) = write_records(&path, 3);
+            set_len(&path, size + 4096);
+
+            let (records, err) = recover(&path, mode);
+            assert_eq!(records.len(), 3, "{mode:?}");
+            assert!(err.is_none(), "{mode:?}: {err:?}");
+            assert_eq!(fs::metadata(&path).unwrap().len(), size, "{mode:?}");
+        }
+    }
+
+    /// # Scenario
+    /// The last record is torn (missing its final bytes), followed by zero
+    /// fill.
+    ///
+    /// # Expected behavior
+    /// `TolerateCorruptedTail` recovers the first 2 records and truncates
+    /// the file at the start of the torn record.
+    #[test]
+    fn torn_final_record_followed_by_zeros_is_dropped() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("000000.log");
+        let (offsets, size) = write_records(&path, 3);
+        // Zero the last record's checksum and pad with zeros.
+        overwrite(&path, size - 4, &[0; 4]);
+        set_len(&path, size + 512);
+
+        let (records, err) = recover(&path, WalRecoveryMode::TolerateCorruptedTail);
+        assert_eq!(records, vec![record(0), record(1)]);
+        assert!(err.is_none(), "{err:?}");
+        assert_eq!(fs::metadata(&path).unwrap().len(), offsets[2]);
+    }
+
+    /// # Scenario
+    /// A crash tore the final record; the WAL is recovered and then
+    /// appended to.
+    ///
+    /// # Actions
+    /// 1. Write 3 records, cut off the last record's checksum.
+    /// 2. Recover, append a new record, recover again.
+    ///
+    /// # Expected behavior
+    /// The second recovery sees the 2 intact records plus the new one —
+    /// the torn bytes no longer sit between them.
+    #[test]
+    fn torn_tail_truncated_so_later_appends_are_reachable() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("000000.log");
+        let (_, size) = write_records(&path, 3);
+        set_len(&path, size - 4);
+
+        let (records, err) = recover(&path, WalRecoveryMode::TolerateCorruptedTail);
+        assert_eq!(records.len(), 2);
+        assert!(err.is_none(), "{err:?}");
+
+        {
+            let wal: Wal<MemTableRecord> = Wal::open(&path, None).unwrap();
+            wal.append(&record(99)).unwrap();
+        }
+
+        let (records, err) = recover(&path, WalRecoveryMode::TolerateCorruptedTail);
+        assert_eq!(records, vec![record(0), record(1), record(99)]);
+        assert!(err.is_none(), "{err:?}");
+    }
+
+    /// # Scenario
+    /// A partially written length prefix is the only thing after the last
+    /// complete record.
+    ///
+    /// # Expected behavior
+    /// Treated as a torn tail: 3 records, no error, partial bytes removed.
+    #[test]
+    fn partial_length_prefix_is_torn_tail() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("000000.log");
+        let (_, size) = write_records(&path, 3);
+        let mut f = OpenOptions::new().append(true).open(&path).unwrap();
+        f.write_all(&[0x10, 0x00]).unwrap();
+        drop(f);
+
+        let (records, err) = recover(&path, WalRecoveryMode::TolerateCorruptedTail);
+        assert_eq!(records.len(), 3);
+        assert!(err.is_none(), "{err:?}");
+        assert_eq!(fs::metadata(&path).unwrap().len(), size);
+    }
+
+    /// # Scenario
+    /// Same torn final record, recovered with `AbsoluteConsistency`.
+    ///
+    /// # Expected behavior
+    /// The intact records are yielded, then `WalError::TornTail` pointing
+    /// at the torn record. The file is left untouched.
+    #[test]
+    fn torn_tail_errors_under_absolute_consistency() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("000000.log");
+        let (offsets, size) = write_records(&path, 3);
+        set_len(&path, size - 4);
+
+        let (records, err) = recover(&path, WalRecoveryMode::AbsoluteConsistency);
+        assert_eq!(records.len(), 2);
+        assert!(
+            matches!(err, Some(WalError::TornTail(off)) if off == offsets[2]),
+            "{err:?}"
+        );
+        assert_eq!(fs::metadata(&path).unwrap().len(), size - 4);
+    }
+
+    /// # Scenario
+    /// The middle record's payload is corrupted; a valid record follows.
+    ///
+    /// # Expected behavior
+    /// `TolerateCorruptedTail` yields the first record, then
+    /// `WalError::CorruptRecord` at the damaged record. Nothing is
+    /// truncated.
+    #[test]
+    fn mid_log_corruption_is_surfaced() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("000000.log");
+        let (offsets, size) = write_records(&path, 3);
+        overwrite(&path, offsets[1] + 6, &[0xFF, 0xFF, 0xFF]);
+
+        let (records, err) = recover(&path, WalRecoveryMode::TolerateCorruptedTail);
+        assert_eq!(records, vec![record(0)]);
+        assert!(
+            matches!(err, Some(WalError::CorruptRecord(off)) if off == offsets[1]),
+            "{err:?}"
+        );
+        assert_eq!(fs::metadata(&path).unwrap().len(), size);
+    }
+
+    /// # Scenario
+    /// Same mid-log corruption, recovered with `PointInTime`.
+    ///
+    /// # Expected behavior
+    /// Replay stops cleanly after the first record and the file is cut at
+    /// the damaged record, discarding the valid record after it.
+    #[test]
+    fn mid_log_corruption_truncated_under_point_in_time() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("000000.log");
+        let (offsets, _) = write_records(&path, 3);
+        overwrite(&path, offsets[1] + 6, &[0xFF, 0xFF, 0xFF]);
+
+        let (records, err) = recover(&path, WalRecoveryMode::PointInTime);
+        assert_eq!(records, vec![record(0)]);
+        assert!(err.is_none(), "{err:?}");
+        assert_eq!(fs::metadata(&path).unwrap().len(), offsets[1]);
+    }
+
+    /// # Scenario
+    /// The middle record's length prefix is corrupted so that it points
+    /// past the end of the file: once beyond the record size limit, once
+    /// within it.
+    ///
+    /// # Expected behavior
+    /// Both times `TolerateCorruptedTail` yields the first record, then
+    /// `WalError::CorruptRecord` at the damaged record, since an intact
+    /// record follows it. Nothing is truncated.
+    #[test]
+    fn corrupt_length_prefix_mid_log_is_surfaced() {
+        init_tracing();
+
+        for len in [u32::MAX, 4096] {
+            let tmp = TempDir::new().unwrap();
+            let path = tmp.path().join("000000.log");
+            let (offsets, size) = write_records(&path, 3);
+            overwrite(&path, offsets[1], &len.to_le_bytes());
+
+            let (records, err) = recover(&path, WalRecoveryMode::TolerateCorruptedTail);
+            assert_eq!(records, vec![record(0)], "len {len}");
+            assert!(
+                matches!(err, Some(WalError::CorruptRecord(off)) if off == offsets[1]),
+                "len {len}: {err:?}"
+            );
+            assert_eq!(fs::metadata(&path).unwrap().len(), size, "len {len}");
+        }
+    }
+
+    /// # Scenario
+    /// The last record's length prefix is corrupted to point past the end
+    /// of the file.
+    ///
+    /// # Expected behavior
+    /// Nothing intact follows it, so `TolerateCorruptedTail` treats it as
+    /// a torn tail: the first two records are yielded and the file is cut
+    /// at the damaged record.
+    #[test]
+    fn corrupt_length_prefix_of_last_record_is_torn_tail() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("000000.log");
+        let (offsets, _) = write_records(&path, 3);
+        overwrite(&path, offsets[2], &4096u32.to_le_bytes());
+
+        let (records, err) = recover(&path, WalRecoveryMode::TolerateCorruptedTail);
+        assert_eq!(records, vec![record(0), record(1)]);
+        assert!(err.is_none(), "{err:?}");
+        assert_eq!(fs::metadata(&path).unwrap().len(), offsets[2]);
+    }
+
+    /// # Scenario
+    /// Recovery reports how many bytes it cut off the tail.
+    ///
+    /// # Actions
+    /// 1. Write 3 records, cut off the last record's checksum, recover.
+    /// 2. Extend the file with zeros, recover again.
+    ///
+    /// # Expected behavior
+    /// `dropped_bytes()` is the size of the torn record after the first
+    /// recovery and zero after the second — zero fill holds no records.
+    #[test]
+    fn dropped_bytes_counts_torn_tail_only() {
+        init_tracing();
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("000000.log");
+        let (offsets, size) = write_records(&path, 3);
+        set_len(&path, size - 4);
+
+        let wal: Wal<MemTableRecord> = Wal::open(&path, None).unwrap();
+        let mut iter = wal
+            .recover_iter(WalRecoveryMode::TolerateCorruptedTail)
+            .unwrap();
+        assert_eq!(iter.by_ref().filter(Result::is_ok).count(), 2);
+        assert_eq!(iter.dropped_bytes(), size - 4 - offsets[2]);
+        drop(iter);
+        drop(wal);
+
+        set_len(&path, offsets[2] + 512);
+        let wal: Wal<MemTableRecord> = Wal::open(&path, None).unwrap();
+        let mut iter = wal
+            .recover_iter(WalRecoveryMode::TolerateCorruptedTail)
+            .unwrap();
+        assert_eq!(iter.by_ref().filter(Result::is_ok).count(), 2);
+        assert_eq!(iter.dropped_bytes(), 0);
+    }
+}
@@ -0,0 +1,492 @@
+//! Crash-injection tests for the `failpoints` feature.
+//!
+//! Each test arms one failpoint, lets a background commit stop there,
+//! drops the handle as a crash would, and checks that reopening the
+//! directory recovers every acknowledged write.
+//!
+//! ## Coverage areas
+//! - **WAL rotation**: crash between creating the next WAL and recording it
+//! - **Flush**: crash before the manifest lists the new SSTable, and
+//!   before it releases the flushed WALs
+//! - **Compaction**: crash before the inputs of a merge are deleted
+//! - **Manifest checkpoint**: crash before the new snapshot is renamed
+//! - **Durable renames**: every rename is synced before it is relied on,
+//!   and a power cut that loses an unsynced rename is recovered from
+//! - **Background panics**: a panicking task under each
+//!   `BackgroundPanicPolicy`
+//!
+//! Failpoints are process-wide, so the tests run one at a time.
+//! Built only with `--features failpoints`.
+
+use aeternusdb::failpoints::{self, FailAction};
+use aeternusdb::{
+    BackgroundPanic, BackgroundPanicPolicy, Db, DbConfig, DbError, EventListener, WalFileStatus,
+};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+// ------------------------------------------------------------------------------------------------
+// Helpers
+// ------------------------------------------------------------------------------------------------
+
+static SERIAL: Mutex<()> = Mutex::new(());
+
+/// Serializes the test and disarms every failpoint when dropped.
+struct Armed(#[allow(dead_code)] MutexGuard<'static, ()>);
+
+impl Armed {
+    fn new() -> Self {
+        let guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        failpoints::clear();
+        Self(guard)
+    }
+}
+
+impl Drop for Armed {
+    fn drop(&mut self) {
+        failpoints::clear();
+    }
+}
+
+/// 1 KiB write buffer, so a few hundred writes freeze and flush often;
+/// no minor compaction, so only the failpoint under test is reached.
+fn small_buffer_config() -> DbConfig {
+    DbConfig {
+        write_buffer_size: 1024,
+        min_compaction_threshold: 64,
+        max_compaction_threshold: 64,
+        ..DbConfig::default()
+    }
+}
+
+/// Writes `fp_0000..fp_{count}`.
+fn write_keys(db: &Db, count: u32) {
+    for i in 0..count {
+        let key = format!("fp_{i:04}");
+        db.put(key.as_bytes(), b"value_with_some_padding").unwrap();
+    }
+}
+
+/// Writes filler keys so that every earlier write is frozen, and hence
+/// flushed by `close`.
+fn push_out_of_memtable(db: &Db) {
+    for i in 0..100u32 {
+        let key = format!("zz_{i:04}");
+        db.put(key.as_bytes(), b"filler_value").unwrap();
+    }
+}
+
+/// Reopens `path` and asserts that `fp_0000..fp_{count}` are all present.
+fn reopen_and_verify(path: &Path, count: u32) -> Db {
+    let db = Db::open(path, small_buffer_config()).unwrap();
+    for i in 0..count {
+        let key = format!("fp_{i:04}");
+        assert_eq!(
+            db.get(key.as_bytes()).unwrap(),
+            Some(b"value_with_some_padding".to_vec()),
+            "{key} lost"
+        );
+    }
+    db
+}
+
+/// Records every background panic it is told about.
+#[derive(Default)]
+struct PanicRecorder(Mutex<Vec<BackgroundPanic>>);
+
+impl EventListener for PanicRecorder {
+    fn on_background_panic(&self, event: &BackgroundPanic) {
+        self.0.lock().unwrap().push(event.clone());
+    }
+}
+
+/// Writes 200 keys into several SSTables, then reopens with one background
+/// worker, `policy`, and a [`PanicRecorder`].
+fn open_for_panic(path: &Path, policy: BackgroundPanicPolicy) -> (Db, Arc<PanicRecorder>) {
+    let db = Db::open(path, small_buffer_config()).unwrap();
+    write_keys(&db, 200);
+    push_out_of_memtable(&db);
+    db.close().unwrap();
+
+    let recorder = Arc::new(PanicRecorder::default());
+    let config = DbConfig {
+        thread_pool_size: 1,
+        background_panic_policy: policy,
+        event_listeners: vec![recorder.clone() as Arc<dyn EventListener>],
+        ..small_buffer_config()
+    };
+    let db = Db::open(path, config).unwrap();
+    assert!(db.sstable_metadata().unwrap().len() >= 2);
+    (db, recorder)
+}
+
+/// Waits until `count` background panics have been handled.
+fn wait_for_panics(db: &Db, count: u64) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while db.background_stats().tasks_panicked < count {
+        assert!(Instant::now() < deadline, "background panic not handled");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Tests
+// ------------------------------------------------------------------------------------------------
+
+/// # Scenario
+/// A crash between creating the next WAL and recording it in the
+/// manifest.
+///
+/// # Starting environment
+/// Empty database with a 1 KiB write buffer.
+///
+/// # Actions
+/// 1. Arm `WAL_ROTATE_BEFORE_MANIFEST`; write until a put fails.
+/// 2. Drop the handle, disarm, reopen.
+/// 3. Write enough to rotate again, close, reopen.
+///
+/// # Expected behavior
+/// The first put that needs a new WAL fails; every earlier put survives.
+/// The stray WAL does not prevent later rotations.
+#[test]
+fn crash_during_wal_rotation() {
+    let _armed = Armed::new();
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+
+    failpoints::set(failpoints::WAL_ROTATE_BEFORE_MANIFEST, FailAction::Error);
+    let mut acknowledged = 0;
+    while acknowledged < 1000 {
+        let key = format!("fp_{acknowledged:04}");
+        match db.put(key.as_bytes(), b"value_with_some_padding") {
+            Ok(()) => acknowledged += 1,
+            Err(DbError::Engine(_)) => break,
+            Err(e) => panic!("unexpected error: {e}"),
+        }
+    }
+    assert!(acknowledged < 1000, "no rotation was attempted");
+    drop(db);
+    failpoints::clear();
+
+    let db = reopen_and_verify(dir.path(), acknowledged);
+    push_out_of_memtable(&db);
+    db.close().unwrap();
+    reopen_and_verify(dir.path(), acknowledged).close().unwrap();
+}
+
+/// # Scenario
+/// A crash after a flush wrote its SSTable but before the manifest lists
+/// it.
+///
+/// # Starting environment
+/// Empty database with a 1 KiB write buffer.
+///
+/// # Actions
+/// 1. Arm `FLUSH_BEFORE_MANIFEST`; write 200 keys and filler, so that
+///    background flushes run and fail.
+/// 2. Drop the handle, disarm, reopen.
+///
+/// # Expected behavior
+/// Every key is recovered from the frozen WALs, and the unlisted SSTables
+/// are removed as orphans.
+#[test]
+fn crash_before_flush_reaches_manifest() {
+    let _armed = Armed::new();
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+
+    failpoints::set(failpoints::FLUSH_BEFORE_MANIFEST, FailAction::Error);
+    write_keys(&db, 200);
+    push_out_of_memtable(&db);
+    drop(db);
+    failpoints::clear();
+
+    let db = reopen_and_verify(dir.path(), 200);
+    assert!(!db.recovery_report().orphan_files_removed.is_empty());
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// A crash after a flush registered its SSTable but before the manifest
+/// released the flushed WALs.
+///
+/// # Starting environment
+/// Empty database with a 1 KiB write buffer.
+///
+/// # Actions
+/// 1. Arm `FLUSH_BEFORE_WAL_RELEASE`; write 200 keys and filler.
+/// 2. Drop the handle, disarm, reopen, close, reopen.
+///
+/// # Expected behavior
+/// Every key is recovered. The WALs still listed as frozen are flushed
+/// again and then deleted: no segment is left `Flushed`.
+#[test]
+fn crash_before_flush_releases_wals() {
+    let _armed = Armed::new();
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+
+    failpoints::set(failpoints::FLUSH_BEFORE_WAL_RELEASE, FailAction::Error);
+    write_keys(&db, 200);
+    push_out_of_memtable(&db);
+    drop(db);
+    failpoints::clear();
+
+    reopen_and_verify(dir.path(), 200).close().unwrap();
+    let db = reopen_and_verify(dir.path(), 200);
+    let files = db.wal_files().unwrap();
+    assert!(
+        files.iter().all(|f| f.status != WalFileStatus::Flushed),
+        "leaked WAL segments: {files:?}"
+    );
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// A crash after a major compaction committed its result but before it
+/// deleted the input SSTables.
+///
+/// # Starting environment
+/// Database with 200 keys flushed to several SSTables.
+///
+/// # Actions
+/// 1. Arm `COMPACTION_BEFORE_CLEANUP`; run a major compaction.
+/// 2. Drop the handle, disarm, reopen.
+///
+/// # Expected behavior
+/// The compaction reports an error. After reopen every key is present,
+/// only the merged SSTable is live, and the compaction is rolled forward
+/// by removing its inputs.
+#[test]
+fn crash_before_compaction_cleanup() {
+    let _armed = Armed::new();
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    write_keys(&db, 200);
+    push_out_of_memtable(&db);
+    db.close().unwrap();
+
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    assert!(db.sstable_metadata().unwrap().len() >= 2);
+    failpoints::set(failpoints::COMPACTION_BEFORE_CLEANUP, FailAction::Error);
+    assert!(db.major_compact().is_err());
+    drop(db);
+    failpoints::clear();
+
+    let db = reopen_and_verify(dir.path(), 200);
+    assert_eq!(db.sstable_metadata().unwrap().len(), 1);
+    assert_eq!(db.recovery_report().compactions_rolled_forward, 1);
+    assert!(db.check_consistency().unwrap().is_consistent());
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// A crash while the shutdown checkpoint has written, but not installed,
+/// the new manifest snapshot.
+///
+/// # Starting environment
+/// Database with 200 keys, most of them frozen.
+///
+/// # Actions
+/// 1. Arm `CHECKPOINT_BEFORE_RENAME`; close.
+/// 2. Disarm, reopen, close, reopen.
+///
+/// # Expected behavior
+/// The first close fails. Every key survives both reopens; the leftover
+/// temporary snapshot does not interfere.
+#[test]
+fn crash_during_manifest_checkpoint() {
+    let _armed = Armed::new();
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    write_keys(&db, 200);
+    push_out_of_memtable(&db);
+
+    failpoints::set(failpoints::CHECKPOINT_BEFORE_RENAME, FailAction::Error);
+    assert!(db.close().is_err());
+    drop(db);
+    failpoints::clear();
+
+    reopen_and_verify(dir.path(), 200).close().unwrap();
+    reopen_and_verify(dir.path(), 200).close().unwrap();
+}
+
+/// # Scenario
+/// A power cut after flushes, a checkpoint, and a major compaction loses
+/// every directory update that was not synced.
+///
+/// # Starting environment
+/// Empty database with a 1 KiB write buffer.
+///
+/// # Actions
+/// 1. Write 200 keys and filler, close, reopen, major compaction, close.
+/// 2. Undo every rename whose directory was not synced since.
+/// 3. Reopen.
+///
+/// # Expected behavior
+/// No rename is left unsynced, so none is undone. Every key survives and
+/// the manifest matches the files on disk.
+#[test]
+fn renames_synced_before_use() {
+    let _armed = Armed::new();
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    write_keys(&db, 200);
+    push_out_of_memtable(&db);
+    db.close().unwrap();
+
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    assert!(db.sstable_metadata().unwrap().len() >= 2);
+    db.major_compact().unwrap();
+    db.close().unwrap();
+
+    let lost = failpoints::lose_unsynced_renames().unwrap();
+    assert!(lost.is_empty(), "unsynced renames: {lost:?}");
+
+    let db = reopen_and_verify(dir.path(), 200);
+    assert!(db.check_consistency().unwrap().is_consistent());
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// A power cut between renaming SSTables into place and syncing their
+/// directory loses the renames.
+///
+/// # Starting environment
+/// Empty database with a 1 KiB write buffer.
+///
+/// # Actions
+/// 1. Arm `RENAME_BEFORE_DIR_SYNC`; write 200 keys and filler, so that
+///    background flushes rename their SSTables and then fail.
+/// 2. Drop the handle; undo every unsynced rename; disarm, reopen.
+///
+/// # Expected behavior
+/// At least one rename is undone. No failed flush reached the manifest,
+/// so every key is recovered from the frozen WALs and the manifest
+/// matches the files on disk.
+#[test]
+fn crash_between_rename_and_dir_sync() {
+    let _armed = Armed::new();
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+
+    failpoints::set(failpoints::RENAME_BEFORE_DIR_SYNC, FailAction::Error);
+    write_keys(&db, 200);
+    push_out_of_memtable(&db);
+    drop(db);
+    let lost = failpoints::lose_unsynced_renames().unwrap();
+    assert!(!lost.is_empty(), "no rename was attempted");
+    failpoints::clear();
+
+    let db = reopen_and_verify(dir.path(), 200);
+    assert!(db.check_consistency().unwrap().is_consistent());
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// A background major compaction panics under the default
+/// `RestartWorker` policy.
+///
+/// # Starting environment
+/// Database with 200 keys in several SSTables, one background worker, and
+/// a recording event listener.
+///
+/// # Actions
+/// 1. Arm `COMPACTION_BEFORE_CLEANUP` to panic; start an async major
+///    compaction and wait for it.
+/// 2. Disarm; start another async major compaction and wait for it.
+/// 3. Drop the handle and reopen.
+///
+/// # Expected behavior
+/// The first compaction reports an error. The panic is counted once and
+/// reported to the listener with the worker's name and the panic message,
+/// and the database is not marked errored. The single worker survives to
+/// run the second compaction. Every key survives the reopen.
+#[test]
+fn background_panic_restarts_worker() {
+    let _armed = Armed::new();
+    let dir = TempDir::new().unwrap();
+    let (db, recorder) = open_for_panic(dir.path(), BackgroundPanicPolicy::RestartWorker);
+
+    failpoints::set(failpoints::COMPACTION_BEFORE_CLEANUP, FailAction::Panic);
+    assert!(db.major_compact_async().unwrap().wait().is_err());
+    wait_for_panics(&db, 1);
+    failpoints::clear();
+
+    let events = recorder.0.lock().unwrap().clone();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].thread, "aeternusdb-bg-0");
+    assert!(
+        events[0]
+            .message
+            .contains(failpoints::COMPACTION_BEFORE_CLEANUP)
+    );
+    assert_eq!(events[0].policy, BackgroundPanicPolicy::RestartWorker);
+
+    // With a single worker, this returns only if the worker survived.
+    let _ = db.major_compact_async().unwrap().wait();
+    let stats = db.background_stats();
+    assert!(stats.tasks_run >= 1);
+    assert_eq!(stats.tasks_panicked, 1);
+    assert_eq!(stats.error, None);
+    drop(db);
+
+    reopen_and_verify(dir.path(), 200).close().unwrap();
+}
+
+/// # Scenario
+/// A background major compaction panics under the `MarkErrored` policy.
+///
+/// # Starting environment
+/// Database with 200 keys in several SSTables, one background worker, and
+/// a recording event listener.
+///
+/// # Actions
+/// 1. Arm `COMPACTION_BEFORE_CLEANUP` to panic; start an async major
+///    compaction and wait for it.
+/// 2. Disarm; put a key, start another compaction, and read a key.
+/// 3. Drop the handle, reopen, and put a key.
+///
+/// # Expected behavior
+/// The panic is reported with the `MarkErrored` policy and recorded in
+/// the stats. The put and the compaction fail with
+/// `DbError::BackgroundPanic`, while the read still succeeds. After
+/// reopen every key is present and writes are accepted again.
+#[test]
+fn background_panic_marks_db_errored() {
+    let _armed = Armed::new();
+    let dir = TempDir::new().unwrap();
+    let (db, recorder) = open_for_panic(dir.path(), BackgroundPanicPolicy::MarkErrored);
+
+    failpoints::set(failpoints::COMPACTION_BEFORE_CLEANUP, FailAction::Panic);
+    assert!(db.major_compact_async().unwrap().wait().is_err());
+    wait_for_panics(&db, 1);
+    failpoints::clear();
+
+    let events = recorder.0.lock().unwrap().clone();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].policy, BackgroundPanicPolicy::MarkErrored);
+    let stats = db.background_stats();
+    assert_eq!(stats.error.as_deref(), Some(events[0].message.as_str()));
+
+    assert!(matches!(
+        db.put(b"after", b"panic"),
+        Err(DbError::BackgroundPanic(msg)) if msg == events[0].message
+    ));
+    assert!(matches!(
+        db.major_compact_async(),
+        Err(DbError::BackgroundPanic(_))
+    ));
+    assert_eq!(
+        db.get(b"fp_0000").unwrap(),
+        Some(b"value_with_some_padding".to_vec())
+    );
+    drop(db);
+
+    let db = reopen_and_verify(dir.path(), 200);
+    assert_eq!(db.background_stats().error, None);
+    db.put(b"after", b"reopen").unwrap();
+    db.close().unwrap();
+}
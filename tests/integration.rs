@@ -6,11 +6,60 @@
 //!
 //! ## Coverage areas
 //! - **Lifecycle**: open, close, idempotent close, Drop-based cleanup
-//! - **CRUD**: put, get, delete, delete_range, overwrite, nonexistent keys
-//! - **Scan**: range queries, empty ranges, tombstone filtering
-//! - **Persistence**: data survives close → reopen, deletes survive reopen
-//! - **Compaction**: major compaction preserves data, removes deleted keys
+//! - **CRUD**: put, get, get_pinned, get_entry, delete, delete_range, overwrite, nonexistent keys,
+//!   unbounded `delete_range_from` / `delete_all` across partitions and reopen, `clear`
+//! - **Write batches**: `WriteBatchWithIndex` overlay reads, commit, validation
+//! - **Conditional writes**: `compare_and_swap` matches, mismatches, change events;
+//!   `increment` counters under concurrent updates
+//! - **Scan**: range queries, empty ranges, tombstone filtering, paged scans,
+//!   key counts
+//! - **Read options**: `get_opt` / `scan_opt` with checksum verification toggled
+//!   and read deadlines
+//! - **Write options**: `put_opt` / `delete_opt` / `write_opt` skipping the
+//!   WAL or forcing an fsync
+//! - **Time-bounded scans**: `scan_since` returns keys written after a cut-off
+//! - **Export and import**: `export_range_to_writer` formats, rate
+//!   limiting, and UTF-8 rejection; `import_from_reader` round trips,
+//!   SSTable ingestion, conflict policies, and malformed input
+//! - **Persistence**: data survives close → reopen, deletes survive reopen,
+//!   SSTable layout and naming survive moving the database directory
+//! - **Compaction**: major compaction preserves data, removes deleted keys,
+//!   periodic compaction rewrites idle SSTables, memtable age flushes an
+//!   idle write buffer, bulk load defers compaction to its exit, adaptive
+//!   compaction tunes thresholds and notifies event listeners, background
+//!   major compaction reports progress through `CompactionHandle`,
+//!   background queues stay within `background_queue_depth`,
+//!   `suggest_compact_range` hints compact a bulk-deleted range,
+//!   scans over long runs of tombstones compact a queue's deleted head,
+//!   FIFO compaction drops the oldest SSTables past its size budget,
+//!   compactions release their claims on input SSTables,
+//!   paused background compaction resumes on request
+//! - **Read-only mode**: `set_read_only` flushes, refuses writes, and
+//!   keeps serving reads
+//! - **Range locks**: `lock_range` / `try_lock_range` exclude overlapping
+//!   ranges only, and never block reads or writes
+//! - **Change notifications**: range subscriptions, commit ordering,
+//!   dropped receivers
+//! - **Introspection**: per-SSTable metadata listing, compaction debug
+//!   report, read counters, read fan-out reports, recovery report,
+//!   consistency check, raw `sst::SstReader` access, per-tenant usage by
+//!   key prefix
+//! - **Repair**: `Db::repair` rebuilds a damaged SSTable offline
+//! - **Open checks**: `sstable_open_checks` levels against a damaged
+//!   data block
+//! - **Cloning**: `clone_to` hard-link copies, SSTable deltas shipped with
+//!   `export_delta` / `apply_delta`, shipped SSTables checked against
+//!   their whole-file digest
+//! - **Partitioning**: hash-partitioned key space with ordered scans
+//! - **Tracing**: sampled per-operation spans, slow-operation events
+//! - **Row cache**: repeated point lookups served from the row cache
+//! - **Mmap limit**: SSTables past `max_mmap_bytes` read with `pread`
+//! - **Warm-up**: `warm_up` ranges and `warm_up_sstables` on open
+//! - **Cross-process access**: exclusive write lease, stale lease
+//!   takeover, `ReadOnlyDb` refresh and background polling
 //! - **Config validation**: all `DbConfig` constraint violations rejected
+//!   and reported together, including cross-field conflicts; workload
+//!   presets and `DbConfig::builder` profiles produce valid configs
 //! - **Error handling**: closed-db operations, empty-key rejection, invalid ranges
 //! - **Concurrency**: multi-thread writes, concurrent readers during writes
 //! - **Full-stack**: end-to-end lifecycle with writes, deletes, range-deletes,
@@ -21,9 +70,20 @@
 //! - [`sstable::tests`] — SSTable read/write unit tests
 //! - [`memtable::tests`] — memtable unit tests
 
-use aeternusdb::{Db, DbConfig, DbError};
+use aeternusdb::sst::{self, SstError, SstReader};
+use aeternusdb::types::{Record, RecordKind};
+use aeternusdb::{
+    AdaptiveCompaction, CasOutcome, ChangeEvent, CompactionPriority, CompactionStrategyType,
+    CompactionTuning, ConfigProfile, ConflictPolicy, ConsistencyIssue, Db, DbConfig, DbError,
+    EventListener, ExportFormat, ExportOptions, ImportOptions, MockClock, OpenChecks, ReadFanout,
+    ReadOnlyConfig, ReadOnlyDb, ReadOptions, ReadStats, ScanPage, SstDirLayout, SstFileDigest,
+    SstFileNaming, TenantPrefix, TuningReason, WalFileStatus, WriteActivity, WriteBatchWithIndex,
+    WriteOptions, WriteStats,
+};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
+use std::time::{Duration, Instant};
 use tempfile::TempDir;
 
 // ------------------------------------------------------------------------------------------------
@@ -42,6 +102,15 @@ fn small_buffer_config() -> DbConfig {
     }
 }
 
+/// Writes 100 filler keys (`zz_*`) so that, with a small write buffer,
+/// every earlier write is frozen and flushed to an SSTable by `close`.
+fn push_out_of_memtable(db: &Db) {
+    for i in 0..100u32 {
+        let key = format!("zz_{:04}", i);
+        db.put(key.as_bytes(), b"filler_value").unwrap();
+    }
+}
+
 /// Reopen a database at the same path with default config.
 fn reopen(path: &std::path::Path) -> Db {
     Db::open(path, DbConfig::default()).expect("reopen")
@@ -231,6 +300,117 @@ fn delete_range_basic() {
     db.close().unwrap();
 }
 
+/// # Scenario
+/// Unbounded range deletes reach keys of any length, in every partition.
+///
+/// # Starting environment
+/// Database with `partitions: 3`; a subscriber on the whole key space.
+///
+/// # Actions
+/// 1. Put `a`, `m`, and `m` followed by 300 `\xff` bytes.
+/// 2. `delete_range_from("m")`; reopen.
+/// 3. Put `z`; `delete_all()`.
+///
+/// # Expected behavior
+/// After step 2 only `a` remains, also after reopen; the subscriber saw a
+/// `DeleteRange` with an empty end. After step 3 nothing remains, and an
+/// empty start is rejected.
+#[test]
+fn delete_range_from_and_delete_all() {
+    let dir = TempDir::new().unwrap();
+    let config = || DbConfig {
+        partitions: 3,
+        ..DbConfig::default()
+    };
+    let db = Db::open(dir.path(), config()).unwrap();
+    let changes = db.subscribe(b"\x00", b"\xff").unwrap();
+    let long_key = [b"m".as_slice(), &[0xFF; 300]].concat();
+    db.put(b"a", b"1").unwrap();
+    db.put(b"m", b"2").unwrap();
+    db.put(&long_key, b"3").unwrap();
+
+    db.delete_range_from(b"m").unwrap();
+    assert_eq!(db.get(&long_key).unwrap(), None);
+    db.close().unwrap();
+    assert_eq!(
+        changes.iter().last(),
+        Some(ChangeEvent::DeleteRange {
+            start: b"m".to_vec(),
+            end: Vec::new()
+        })
+    );
+
+    let db = Db::open(dir.path(), config()).unwrap();
+    assert_eq!(db.get(b"a").unwrap(), Some(b"1".to_vec()));
+    assert_eq!(db.get(b"m").unwrap(), None);
+    assert_eq!(db.get(&long_key).unwrap(), None);
+
+    db.put(b"z", b"4").unwrap();
+    db.delete_all().unwrap();
+    assert!(db.scan(b"\x00", &[0xFF; 400]).unwrap().is_empty());
+    assert!(matches!(
+        db.delete_range_from(b""),
+        Err(DbError::InvalidArgument(_))
+    ));
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// `clear` drops every key and its files at once, in every partition.
+///
+/// # Starting environment
+/// Database with `partitions: 2` and a small write buffer; a subscriber
+/// on the whole key space.
+///
+/// # Actions
+/// 1. Put 300 keys; `set_read_only(true)` to flush them to SSTables.
+/// 2. `clear()` while read-only, then again after `set_read_only(false)`.
+/// 3. Put one key; reopen.
+///
+/// # Expected behavior
+/// The read-only clear is refused. The other leaves no key and no
+/// SSTable, and the subscriber sees a `DeleteRange` with an empty start
+/// and end. After reopening only the key written after the clear exists.
+#[test]
+fn clear_drops_every_key() {
+    let dir = TempDir::new().unwrap();
+    let config = || DbConfig {
+        partitions: 2,
+        ..small_buffer_config()
+    };
+    let db = Db::open(dir.path(), config()).unwrap();
+    let changes = db.subscribe(b"\x00", b"\xff").unwrap();
+    for i in 0..300 {
+        db.put(format!("key_{i:04}").as_bytes(), &[b'v'; 32])
+            .unwrap();
+    }
+    db.set_read_only(true).unwrap();
+    assert!(!db.sstable_metadata().unwrap().is_empty());
+    assert!(matches!(db.clear(), Err(DbError::ReadOnly)));
+    db.set_read_only(false).unwrap();
+
+    db.clear().unwrap();
+    assert!(db.scan(b"\x00", b"\xff").unwrap().is_empty());
+    assert!(db.sstable_metadata().unwrap().is_empty());
+    db.put(b"after", b"1").unwrap();
+    db.close().unwrap();
+    let events: Vec<ChangeEvent> = changes.iter().skip(300).collect();
+    assert_eq!(
+        events[0],
+        ChangeEvent::DeleteRange {
+            start: Vec::new(),
+            end: Vec::new()
+        }
+    );
+
+    let db = Db::open(dir.path(), config()).unwrap();
+    assert_eq!(
+        db.scan(b"\x00", b"\xff").unwrap(),
+        vec![(b"after".to_vec(), b"1".to_vec())]
+    );
+    db.close().unwrap();
+}
+
 /// # Scenario
 /// Getting a key that was never inserted returns `None`.
 ///
@@ -252,6 +432,406 @@ fn get_nonexistent_key() {
     db.close().unwrap();
 }
 
+/// # Scenario
+/// `compare_and_swap` applies a write only when the current value matches.
+///
+/// # Starting environment
+/// Database with two partitions and a change subscription on every key.
+///
+/// # Actions
+/// 1. Claim `"lock"` with `expected = None`; claim it again.
+/// 2. Release it with the wrong owner, then the right one.
+/// 3. Pass empty `expected` and `new` values.
+///
+/// # Expected behavior
+/// The second claim and the wrong release return the current value and
+/// publish nothing; the others succeed and publish a put and a delete.
+/// Empty values are rejected.
+#[test]
+fn compare_and_swap_conditional_writes() {
+    let dir = TempDir::new().unwrap();
+    let config = DbConfig {
+        partitions: 2,
+        ..small_buffer_config()
+    };
+    let db = Db::open(dir.path(), config).unwrap();
+    let changes = db.subscribe(b"\x00", b"\xff").unwrap();
+
+    assert_eq!(
+        db.compare_and_swap(b"lock", None, Some(b"owner-a".as_slice()))
+            .unwrap(),
+        CasOutcome::Swapped
+    );
+    assert_eq!(
+        db.compare_and_swap(b"lock", None, Some(b"owner-b".as_slice()))
+            .unwrap(),
+        CasOutcome::Mismatch(Some(b"owner-a".to_vec()))
+    );
+    assert_eq!(
+        db.compare_and_swap(b"lock", Some(b"owner-b".as_slice()), None)
+            .unwrap(),
+        CasOutcome::Mismatch(Some(b"owner-a".to_vec()))
+    );
+    assert_eq!(
+        db.compare_and_swap(b"lock", Some(b"owner-a".as_slice()), None)
+            .unwrap(),
+        CasOutcome::Swapped
+    );
+    assert_eq!(db.get(b"lock").unwrap(), None);
+
+    let events: Vec<_> = changes.try_iter().collect();
+    assert_eq!(
+        events,
+        vec![
+            ChangeEvent::Put {
+                key: b"lock".to_vec(),
+                value: b"owner-a".to_vec(),
+            },
+            ChangeEvent::Delete {
+                key: b"lock".to_vec(),
+            },
+        ]
+    );
+
+    assert!(matches!(
+        db.compare_and_swap(b"lock", Some(b"".as_slice()), Some(b"x".as_slice())),
+        Err(DbError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        db.compare_and_swap(b"lock", None, Some(b"".as_slice())),
+        Err(DbError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        db.compare_and_swap(b"", None, Some(b"x".as_slice())),
+        Err(DbError::InvalidArgument(_))
+    ));
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// `increment` maintains counters without lost updates.
+///
+/// # Starting environment
+/// Database with two partitions and a small write buffer.
+///
+/// # Actions
+/// 1. Four threads each increment `"hits"` by 1, 250 times.
+/// 2. Decrement it by 400; read it with `get`.
+/// 3. Increment a non-counter value; overflow a counter.
+///
+/// # Expected behavior
+/// The counter reaches 1000, then 600, stored as 8 little-endian bytes.
+/// Non-counter values and overflows are rejected and leave the stored
+/// value unchanged.
+#[test]
+fn increment_counters() {
+    let dir = TempDir::new().unwrap();
+    let config = DbConfig {
+        partitions: 2,
+        ..small_buffer_config()
+    };
+    let db = Arc::new(Db::open(dir.path(), config).unwrap());
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let db = Arc::clone(&db);
+            thread::spawn(move || {
+                for _ in 0..250 {
+                    db.increment(b"hits", 1).unwrap();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(db.increment(b"hits", 0).unwrap(), 1000);
+    assert_eq!(db.increment(b"hits", -400).unwrap(), 600);
+    assert_eq!(
+        db.get(b"hits").unwrap(),
+        Some(600i64.to_le_bytes().to_vec())
+    );
+
+    db.put(b"name", b"not a number").unwrap();
+    assert!(matches!(
+        db.increment(b"name", 1),
+        Err(DbError::InvalidArgument(_))
+    ));
+    assert_eq!(db.get(b"name").unwrap(), Some(b"not a number".to_vec()));
+
+    assert_eq!(db.increment(b"big", i64::MAX).unwrap(), i64::MAX);
+    assert!(matches!(
+        db.increment(b"big", 1),
+        Err(DbError::InvalidArgument(_))
+    ));
+    assert_eq!(db.increment(b"big", 0).unwrap(), i64::MAX);
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// `get_entry` exposes the version of a value for optimistic concurrency.
+///
+/// # Starting environment
+/// Database with two partitions and a small write buffer.
+///
+/// # Actions
+/// 1. Put `"acct"`, read its entry, put it again, read again.
+/// 2. Write 200 more keys, close, reopen, read the entry again.
+/// 3. Delete `"acct"`.
+///
+/// # Expected behavior
+/// The second entry has the new value and a higher LSN; the entry
+/// survives reopen unchanged; a deleted key has no entry; an empty key
+/// is rejected.
+#[test]
+fn get_entry_reports_versions() {
+    let dir = TempDir::new().unwrap();
+    let config = || DbConfig {
+        partitions: 2,
+        ..small_buffer_config()
+    };
+    let db = Db::open(dir.path(), config()).unwrap();
+
+    db.put(b"acct", b"100").unwrap();
+    let read = db.get_entry(b"acct").unwrap().unwrap();
+    assert_eq!(read.value, b"100".to_vec());
+
+    db.put(b"acct", b"90").unwrap();
+    let current = db.get_entry(b"acct").unwrap().unwrap();
+    assert_eq!(current.value, b"90".to_vec());
+    assert!(current.lsn > read.lsn, "stale read must be detectable");
+    assert!(current.timestamp >= read.timestamp);
+
+    for i in 0..200u32 {
+        db.put(format!("key_{i:04}").as_bytes(), b"filler").unwrap();
+    }
+    db.close().unwrap();
+
+    let db = Db::open(dir.path(), config()).unwrap();
+    assert_eq!(db.get_entry(b"acct").unwrap(), Some(current));
+    db.delete(b"acct").unwrap();
+    assert_eq!(db.get_entry(b"acct").unwrap(), None);
+    assert!(matches!(
+        db.get_entry(b""),
+        Err(DbError::InvalidArgument(_))
+    ));
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// `get_pinned` returns the same values as `get`, from memtables and
+/// SSTables alike.
+///
+/// # Starting environment
+/// Database with a small write buffer so that most data reaches SSTables.
+///
+/// # Actions
+/// 1. Put 200 keys and delete one.
+/// 2. `get_pinned` every key, a deleted key, and a missing key.
+/// 3. Close and call `get_pinned`.
+///
+/// # Expected behavior
+/// Values match `get`; deleted and missing keys return `None`; the closed
+/// handle returns `DbError::Closed`.
+#[test]
+fn get_pinned_matches_get() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+
+    for i in 0..200u32 {
+        db.put(
+            format!("pin_{i:04}").as_bytes(),
+            format!("value_{i}").as_bytes(),
+        )
+        .unwrap();
+    }
+    db.delete(b"pin_0007").unwrap();
+
+    for i in 0..200u32 {
+        let key = format!("pin_{i:04}");
+        let expected = db.get(key.as_bytes()).unwrap();
+        let pinned = db.get_pinned(key.as_bytes()).unwrap();
+        assert_eq!(pinned.as_deref(), expected.as_deref());
+    }
+    assert!(db.get_pinned(b"pin_0007").unwrap().is_none());
+    assert!(db.get_pinned(b"missing").unwrap().is_none());
+
+    db.close().unwrap();
+    assert!(matches!(db.get_pinned(b"pin_0001"), Err(DbError::Closed)));
+}
+
+/// # Scenario
+/// Point lookups with `parallel_sstable_probe` return the same results as
+/// the default sequential probe.
+///
+/// # Starting environment
+/// Database written with a small write buffer so data spans many SSTables.
+///
+/// # Actions
+/// 1. Put 400 keys, delete every ninth, range-delete a slice.
+/// 2. Close and reopen with `parallel_sstable_probe: true`.
+/// 3. `get` every key.
+///
+/// # Expected behavior
+/// Every lookup matches the result recorded before reopening.
+#[test]
+fn parallel_sstable_probe_matches_get() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+
+    for i in 0..400u32 {
+        db.put(
+            format!("par_{i:04}").as_bytes(),
+            format!("value_{i}").as_bytes(),
+        )
+        .unwrap();
+    }
+    for i in (0..400u32).step_by(9) {
+        db.delete(format!("par_{i:04}").as_bytes()).unwrap();
+    }
+    db.delete_range(b"par_0200", b"par_0250").unwrap();
+
+    let expected: Vec<_> = (0..410u32)
+        .map(|i| db.get(format!("par_{i:04}").as_bytes()).unwrap())
+        .collect();
+    db.close().unwrap();
+
+    let config = DbConfig {
+        parallel_sstable_probe: true,
+        ..small_buffer_config()
+    };
+    let db = Db::open(dir.path(), config).unwrap();
+    for (i, want) in expected.iter().enumerate() {
+        let got = db.get(format!("par_{i:04}").as_bytes()).unwrap();
+        assert_eq!(&got, want, "mismatch for par_{i:04}");
+    }
+    db.close().unwrap();
+}
+
+// ================================================================================================
+// Write batches
+// ================================================================================================
+
+/// # Scenario
+/// A `WriteBatchWithIndex` exposes its pending writes overlaid on the
+/// database before it is committed.
+///
+/// # Starting environment
+/// Database with keys `a`, `b`, `c`, `d`.
+///
+/// # Actions
+/// 1. Batch: put `a`, delete `b`, range-delete `[c, e)`, then put `d`.
+/// 2. Read every key through `get_from_batch_and_db` and through `get`.
+///
+/// # Expected behavior
+/// The overlay shows the batch's view (`a` new, `b`/`c` gone, `d` rewritten
+/// after the range delete, `z` from the db), while `get` still sees the
+/// original data.
+#[test]
+fn batch_reads_own_writes_before_commit() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), DbConfig::default()).unwrap();
+    for k in [b"a", b"b", b"c", b"d", b"z"] {
+        db.put(k, b"old").unwrap();
+    }
+
+    let mut batch = WriteBatchWithIndex::new();
+    batch.put(b"a", b"new");
+    batch.delete(b"b");
+    batch.delete_range(b"c", b"e");
+    batch.put(b"d", b"after_range");
+    assert_eq!(batch.len(), 4);
+
+    let overlay = |k: &[u8]| batch.get_from_batch_and_db(&db, k).unwrap();
+    assert_eq!(overlay(b"a"), Some(b"new".to_vec()));
+    assert_eq!(overlay(b"b"), None);
+    assert_eq!(overlay(b"c"), None);
+    assert_eq!(overlay(b"d"), Some(b"after_range".to_vec()));
+    assert_eq!(overlay(b"z"), Some(b"old".to_vec()));
+    assert_eq!(overlay(b"missing"), None);
+
+    for k in [b"a", b"b", b"c", b"d"] {
+        assert_eq!(db.get(k).unwrap(), Some(b"old".to_vec()));
+    }
+
+    db.close().unwrap();
+    assert!(matches!(
+        batch.get_from_batch_and_db(&db, b"a"),
+        Err(DbError::Closed)
+    ));
+}
+
+/// # Scenario
+/// `Db::write` commits a batch, and the result survives reopen.
+///
+/// # Actions
+/// 1. Commit a batch of 500 puts, a delete, and a range delete with a
+///    small write buffer (forcing freezes mid-batch).
+/// 2. Close and reopen.
+///
+/// # Expected behavior
+/// Reads after commit and after reopen match the batch's own view.
+#[test]
+fn batch_write_commits_and_persists() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+
+    let mut batch = WriteBatchWithIndex::new();
+    for i in 0..500u32 {
+        batch.put(format!("wb_{i:04}").as_bytes(), format!("v{i}").as_bytes());
+    }
+    batch.delete(b"wb_0007");
+    batch.delete_range(b"wb_0100", b"wb_0200");
+    db.write(&batch).unwrap();
+
+    let check = |db: &Db| {
+        for i in 0..500u32 {
+            let key = format!("wb_{i:04}");
+            let expected = batch.get_from_batch(key.as_bytes()).unwrap();
+            assert_eq!(
+                db.get(key.as_bytes()).unwrap().as_deref(),
+                expected,
+                "{key}"
+            );
+        }
+    };
+    check(&db);
+    assert!(db.get(b"wb_0150").unwrap().is_none());
+    db.close().unwrap();
+
+    let db = reopen(dir.path());
+    check(&db);
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// An invalid operation anywhere in a batch rejects the whole batch.
+///
+/// # Actions
+/// 1. Batch: valid put, then a put with an empty value.
+/// 2. `Db::write`.
+///
+/// # Expected behavior
+/// `Err(DbError::InvalidArgument)` and the valid put is not applied.
+#[test]
+fn batch_write_rejects_invalid_batch_entirely() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), DbConfig::default()).unwrap();
+
+    let mut batch = WriteBatchWithIndex::new();
+    batch.put(b"ok", b"value");
+    batch.put(b"bad", b"");
+    assert!(matches!(db.write(&batch), Err(DbError::InvalidArgument(_))));
+    assert!(db.get(b"ok").unwrap().is_none());
+
+    batch.clear();
+    assert!(batch.is_empty());
+    batch.delete_range(b"z", b"a");
+    assert!(matches!(db.write(&batch), Err(DbError::InvalidArgument(_))));
+    db.close().unwrap();
+}
+
 // ================================================================================================
 // Scan
 // ================================================================================================
@@ -348,261 +928,3919 @@ fn scan_excludes_deleted_keys() {
     db.close().unwrap();
 }
 
-// ================================================================================================
-// Persistence
-// ================================================================================================
-
 /// # Scenario
-/// Data written before `close()` is readable after reopening.
+/// `scan_since` reports only keys written after a point in time.
 ///
 /// # Starting environment
-/// Empty temporary directory.
+/// Database with a small write buffer so that data reaches SSTables.
 ///
 /// # Actions
-/// 1. Open database, put `"persist_key"` → `"persist_value"`, close.
-/// 2. Reopen database from the same directory.
-/// 3. `get("persist_key")`.
+/// 1. Put `ts_0000..ts_0299`, close and reopen, and note the time.
+/// 2. Overwrite `ts_0010`, put `ts_0300`, delete `ts_0299`, and put then
+///    delete `ts_0302`.
+/// 3. `scan_since` the noted time, before and after closing and reopening.
 ///
 /// # Expected behavior
-/// The reopened database returns `Some("persist_value")`.
+/// Both scans return exactly `ts_0010` (new value) and `ts_0300`; a
+/// cut-off of zero returns the same as `scan`; a cut-off in the future
+/// returns nothing.
 #[test]
-fn persistence_across_reopen() {
+fn scan_since_returns_recent_writes() {
     let dir = TempDir::new().unwrap();
-
     {
-        let db = Db::open(dir.path(), DbConfig::default()).unwrap();
-        db.put(b"persist_key", b"persist_value").unwrap();
+        let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+        for i in 0..300u32 {
+            db.put(format!("ts_{i:04}").as_bytes(), b"old").unwrap();
+        }
         db.close().unwrap();
     }
 
-    {
-        let db = reopen(dir.path());
-        assert_eq!(
-            db.get(b"persist_key").unwrap(),
-            Some(b"persist_value".to_vec())
-        );
-        db.close().unwrap();
-    }
+    let now = || {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64
+    };
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    let since = now();
+    db.put(b"ts_0010", b"new").unwrap();
+    db.put(b"ts_0300", b"new").unwrap();
+    db.delete(b"ts_0299").unwrap();
+    db.put(b"ts_0302", b"gone").unwrap();
+    db.delete(b"ts_0302").unwrap();
+
+    let expected = vec![
+        (b"ts_0010".to_vec(), b"new".to_vec()),
+        (b"ts_0300".to_vec(), b"new".to_vec()),
+    ];
+    assert_eq!(db.scan_since(b"ts_", b"ts~", since).unwrap(), expected);
+    db.close().unwrap();
+
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    assert_eq!(db.scan_since(b"ts_", b"ts~", since).unwrap(), expected);
+    assert_eq!(
+        db.scan_since(b"ts_", b"ts~", 0).unwrap(),
+        db.scan(b"ts_", b"ts~").unwrap()
+    );
+    assert!(db.scan_since(b"ts_", b"ts~", u64::MAX).unwrap().is_empty());
+    assert!(matches!(
+        db.scan_since(b"", b"ts~", since),
+        Err(DbError::InvalidArgument(_))
+    ));
+    db.close().unwrap();
 }
 
 /// # Scenario
-/// Hundreds of writes survive close → reopen with a small write buffer
-/// that triggers multiple flushes.
+/// `count` agrees with `scan` over overwrites and deletes, and counts a
+/// compacted range from SSTable metadata alone.
 ///
 /// # Starting environment
-/// Empty temporary directory, 1 KiB write buffer (forces frequent flushes).
+/// Database with a small write buffer so that data reaches SSTables.
 ///
 /// # Actions
-/// 1. Write 500 sequentially-named keys, close.
-/// 2. Reopen and verify all 500 keys.
+/// 1. Put `cn_0000..cn_0299`, overwrite every third key, delete every
+///    tenth, range-delete `[cn_0100, cn_0150)`, and push it all out of
+///    the memtable with `zz_*` fillers.
+/// 2. Count several ranges, before and after closing and reopening.
+/// 3. Major-compact, close, damage the first data block of the single
+///    SSTable, and reopen.
+/// 4. Count the SSTable's whole key span and scan the `cn_` range.
 ///
 /// # Expected behavior
-/// Every key is present with its original value after reopen.
+/// - Every count equals the length of the matching scan; an inverted
+///   range counts zero and an empty key is rejected.
+/// - After step 3 the count is still 225 plus the 100 fillers — no data
+///   block was read, the fillers left in the memtable sorting above the
+///   SSTable — while the scan stops short at the damaged block.
 #[test]
-fn persistence_many_writes() {
+fn count_matches_scan() {
     let dir = TempDir::new().unwrap();
-
-    {
-        let db = Db::open(dir.path(), small_buffer_config()).unwrap();
-        for i in 0..500u32 {
-            let key = format!("key_{:04}", i);
-            let val = format!("val_{:04}", i);
-            db.put(key.as_bytes(), val.as_bytes()).unwrap();
-        }
-        db.close().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    for i in 0..300u32 {
+        db.put(format!("cn_{i:04}").as_bytes(), b"value").unwrap();
+    }
+    for i in (0..300u32).step_by(3) {
+        db.put(format!("cn_{i:04}").as_bytes(), b"newer").unwrap();
+    }
+    for i in (0..300u32).step_by(10) {
+        db.delete(format!("cn_{i:04}").as_bytes()).unwrap();
     }
+    db.delete_range(b"cn_0100", b"cn_0150").unwrap();
+    push_out_of_memtable(&db);
 
-    {
-        let db = Db::open(dir.path(), small_buffer_config()).unwrap();
-        for i in 0..500u32 {
-            let key = format!("key_{:04}", i);
-            let val = format!("val_{:04}", i);
+    let ranges: [(&[u8], &[u8]); 4] = [
+        (b"cn_", b"cn~"),
+        (b"cn_0050", b"cn_0120"),
+        (b"cn_0100", b"cn_0150"),
+        (b"a", b"b"),
+    ];
+    let check = |db: &Db| {
+        for (start, end) in ranges {
             assert_eq!(
-                db.get(key.as_bytes()).unwrap(),
-                Some(val.into_bytes()),
-                "key_{:04} should be present after reopen",
-                i
+                db.count(start, end).unwrap(),
+                db.scan(start, end).unwrap().len() as u64
             );
         }
-        db.close().unwrap();
+        assert_eq!(db.count(b"cn_", b"cn~").unwrap(), 225);
+    };
+    check(&db);
+    assert_eq!(db.count(b"cn~", b"cn_").unwrap(), 0);
+    assert!(matches!(
+        db.count(b"", b"cn~"),
+        Err(DbError::InvalidArgument(_))
+    ));
+    db.close().unwrap();
+
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    check(&db);
+    db.major_compact().unwrap();
+    let sstables = db.sstable_metadata().unwrap();
+    assert_eq!(sstables.len(), 1);
+    db.close().unwrap();
+    drop(db);
+
+    // The first data block follows the 12-byte header and its length.
+    let mut bytes = std::fs::read(&sstables[0].path).unwrap();
+    for b in &mut bytes[20..28] {
+        *b ^= 0xFF;
     }
+    std::fs::write(&sstables[0].path, &bytes).unwrap();
+
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    assert_eq!(db.count(b"cn_", b"zz~").unwrap(), 325);
+    assert!(db.scan(b"cn_", b"cn~").unwrap().len() < 225);
+    db.close().unwrap();
 }
 
 /// # Scenario
-/// Point-delete tombstones survive close → reopen.
+/// `export_range_to_writer` writes a range in each format, from a
+/// partitioned database, matching `scan`.
 ///
 /// # Starting environment
-/// Empty temporary directory.
+/// Database with two partitions and a small write buffer.
 ///
 /// # Actions
-/// 1. Put `"alive"` → `"yes"` and `"dead"` → `"soon"`, then `delete("dead")`, close.
-/// 2. Reopen and get both keys.
+/// 1. Put `ex_0000..ex_0199` (one value containing a comma, a quote, and
+///    a newline), delete `ex_0005`, and put a key outside the range.
+/// 2. Export `[ex_, ex~)` in each format; export an empty range.
 ///
 /// # Expected behavior
-/// `"alive"` returns `Some("yes")`; `"dead"` returns `None`.
+/// - Length-prefixed output decodes to exactly the `scan` result.
+/// - CSV starts with the header, quotes the special value, and has one
+///   line per pair; JSON Lines escapes it.
+/// - Every report counts 199 records and the bytes written.
+/// - The empty range writes nothing but the CSV header.
 #[test]
-fn persistence_deletes_survive_reopen() {
+fn export_range_formats() {
     let dir = TempDir::new().unwrap();
+    let config = DbConfig {
+        partitions: 2,
+        ..small_buffer_config()
+    };
+    let db = Db::open(dir.path(), config).unwrap();
+    for i in 0..200u32 {
+        db.put(format!("ex_{i:04}").as_bytes(), format!("v{i}").as_bytes())
+            .unwrap();
+    }
+    db.put(b"ex_0007", b"a,\"b\"\nc").unwrap();
+    db.delete(b"ex_0005").unwrap();
+    db.put(b"other", b"outside").unwrap();
+    let expected = db.scan(b"ex_", b"ex~").unwrap();
+    assert_eq!(expected.len(), 199);
 
-    {
-        let db = Db::open(dir.path(), DbConfig::default()).unwrap();
-        db.put(b"alive", b"yes").unwrap();
-        db.put(b"dead", b"soon").unwrap();
-        db.delete(b"dead").unwrap();
-        db.close().unwrap();
+    let export = |format| {
+        let mut out = Vec::new();
+        let options = ExportOptions {
+            format,
+            ..ExportOptions::default()
+        };
+        let report = db
+            .export_range_to_writer(b"ex_", b"ex~", &mut out, &options)
+            .unwrap();
+        assert_eq!(report.records, 199);
+        assert_eq!(report.bytes, out.len() as u64);
+        out
+    };
+
+    let binary = export(ExportFormat::LengthPrefixed);
+    let mut decoded = Vec::new();
+    let mut rest = binary.as_slice();
+    while !rest.is_empty() {
+        let mut field = || {
+            let len = u32::from_le_bytes(rest[..4].try_into().unwrap()) as usize;
+            let bytes = rest[4..4 + len].to_vec();
+            rest = &rest[4 + len..];
+            bytes
+        };
+        let key = field();
+        let value = field();
+        decoded.push((key, value));
     }
+    assert_eq!(decoded, expected);
 
-    {
-        let db = reopen(dir.path());
-        assert_eq!(db.get(b"alive").unwrap(), Some(b"yes".to_vec()));
-        assert_eq!(db.get(b"dead").unwrap(), None);
+    let csv = String::from_utf8(export(ExportFormat::Csv)).unwrap();
+    assert!(csv.starts_with("key,value\nex_0000,v0\n"));
+    assert!(csv.contains("ex_0007,\"a,\"\"b\"\"\nc\"\n"));
+    assert!(!csv.contains("ex_0005"));
+    // Header, one line per pair, and the quoted newline.
+    assert_eq!(csv.lines().count(), 1 + 199 + 1);
+
+    let jsonl = String::from_utf8(export(ExportFormat::JsonLines)).unwrap();
+    assert_eq!(jsonl.lines().count(), 199);
+    assert!(jsonl.starts_with("{\"key\":\"ex_0000\",\"value\":\"v0\"}\n"));
+    assert!(jsonl.contains("{\"key\":\"ex_0007\",\"value\":\"a,\\\"b\\\"\\nc\"}\n"));
+
+    let mut out = Vec::new();
+    let options = ExportOptions {
+        format: ExportFormat::Csv,
+        ..ExportOptions::default()
+    };
+    let report = db
+        .export_range_to_writer(b"zz", b"aa", &mut out, &options)
+        .unwrap();
+    assert_eq!(report.records, 0);
+    assert_eq!(out, b"key,value\n");
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// Exports are paced by `max_bytes_per_sec`, reject non-UTF-8 data in
+/// text formats, and validate their arguments.
+///
+/// # Starting environment
+/// Freshly opened database with default config.
+///
+/// # Actions
+/// 1. Put 100 keys with 1 KiB values and export them at 200 KiB/s.
+/// 2. Put a key whose value is not UTF-8 and export it as JSON Lines.
+/// 3. Export with an empty start key, then after `close`.
+///
+/// # Expected behavior
+/// - The paced export takes at least a quarter of a second.
+/// - The JSON Lines export writes the pairs before the bad one, then
+///   fails with `DbError::InvalidArgument`.
+/// - Invalid arguments and the closed handle are rejected.
+#[test]
+fn export_range_rate_limit_and_errors() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), DbConfig::default()).unwrap();
+    for i in 0..100u32 {
+        db.put(format!("rl_{i:04}").as_bytes(), &[b'x'; 1024])
+            .unwrap();
+    }
+
+    let mut out = Vec::new();
+    let paced = ExportOptions {
+        max_bytes_per_sec: 200 * 1024,
+        ..ExportOptions::default()
+    };
+    let report = db
+        .export_range_to_writer(b"rl_", b"rl~", &mut out, &paced)
+        .unwrap();
+    assert_eq!(report.records, 100);
+    assert!(report.bytes > 100 * 1024);
+    assert!(
+        report.duration >= std::time::Duration::from_millis(250),
+        "export took {:?}",
+        report.duration
+    );
+
+    db.put(b"bin_1", b"text").unwrap();
+    db.put(b"bin_2", &[0xff, 0xfe]).unwrap();
+    let mut out = Vec::new();
+    let jsonl = ExportOptions {
+        format: ExportFormat::JsonLines,
+        ..ExportOptions::default()
+    };
+    assert!(matches!(
+        db.export_range_to_writer(b"bin_", b"bin~", &mut out, &jsonl),
+        Err(DbError::InvalidArgument(_))
+    ));
+    assert_eq!(out, b"{\"key\":\"bin_1\",\"value\":\"text\"}\n");
+
+    assert!(matches!(
+        db.export_range_to_writer(b"", b"z", &mut Vec::new(), &jsonl),
+        Err(DbError::InvalidArgument(_))
+    ));
+    db.close().unwrap();
+    assert!(matches!(
+        db.export_range_to_writer(b"a", b"z", &mut Vec::new(), &jsonl),
+        Err(DbError::Closed)
+    ));
+}
+
+/// # Scenario
+/// Data exported from one database imports into another in every
+/// format, through write batches or direct SSTable ingestion.
+///
+/// # Starting environment
+/// A source database holding `im_0000..im_0299`, one value with a comma,
+/// a quote, and a newline; a second database with two partitions.
+///
+/// # Actions
+/// 1. Export the source in each format and import it into a fresh
+///    target with batches of 64.
+/// 2. Import the length-prefixed export with `ingest_sorted`, then
+///    close and reopen the target.
+///
+/// # Expected behavior
+/// - Every target scans exactly like the source; reports count 300
+///   records read and written.
+/// - The ingested import reports every pair as ingested, adds SSTables
+///   without growing the WAL, and survives the reopen.
+#[test]
+fn import_round_trips_export() {
+    let src_dir = TempDir::new().unwrap();
+    let src = Db::open(src_dir.path(), small_buffer_config()).unwrap();
+    for i in 0..300u32 {
+        src.put(format!("im_{i:04}").as_bytes(), format!("v{i}").as_bytes())
+            .unwrap();
+    }
+    src.put(b"im_0007", b"a,\"b\"\nc").unwrap();
+    let expected = src.scan(b"im_", b"im~").unwrap();
+
+    let target_config = || DbConfig {
+        partitions: 2,
+        ..small_buffer_config()
+    };
+    for format in [
+        ExportFormat::LengthPrefixed,
+        ExportFormat::Csv,
+        ExportFormat::JsonLines,
+    ] {
+        let mut data = Vec::new();
+        let export = ExportOptions {
+            format,
+            ..ExportOptions::default()
+        };
+        src.export_range_to_writer(b"im_", b"im~", &mut data, &export)
+            .unwrap();
+
+        let dir = TempDir::new().unwrap();
+        let db = Db::open(dir.path(), target_config()).unwrap();
+        let options = ImportOptions {
+            format,
+            batch_size: 64,
+            ..ImportOptions::default()
+        };
+        let report = db.import_from_reader(data.as_slice(), &options).unwrap();
+        assert_eq!((report.records, report.written), (300, 300), "{format:?}");
+        assert_eq!(report.ingested, 0);
+        assert_eq!(db.scan(b"im_", b"im~").unwrap(), expected, "{format:?}");
         db.close().unwrap();
     }
+
+    let mut data = Vec::new();
+    src.export_range_to_writer(b"im_", b"im~", &mut data, &ExportOptions::default())
+        .unwrap();
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), target_config()).unwrap();
+    let wal_bytes = |db: &Db| db.wal_files().unwrap().iter().map(|f| f.size).sum::<u64>();
+    let wal_before = wal_bytes(&db);
+    let options = ImportOptions {
+        batch_size: 100,
+        ingest_sorted: true,
+        ..ImportOptions::default()
+    };
+    let report = db.import_from_reader(data.as_slice(), &options).unwrap();
+    assert_eq!((report.written, report.ingested), (300, 300));
+    assert!(!db.sstable_metadata().unwrap().is_empty());
+    assert_eq!(wal_bytes(&db), wal_before);
+    assert_eq!(db.scan(b"im_", b"im~").unwrap(), expected);
+    db.close().unwrap();
+
+    let db = Db::open(dir.path(), target_config()).unwrap();
+    assert_eq!(db.scan(b"im_", b"im~").unwrap(), expected);
+    db.close().unwrap();
+    src.close().unwrap();
 }
 
-// ================================================================================================
-// Compaction
-// ================================================================================================
+/// # Scenario
+/// Import conflict policies and input validation.
+///
+/// # Starting environment
+/// Database holding `cp_0001 = old`.
+///
+/// # Actions
+/// 1. Import `cp_0000..cp_0002 = new` as CSV with `SkipExisting`, then
+///    with `Fail`, then with `Overwrite`.
+/// 2. Import malformed inputs: a truncated length-prefixed record, CSV
+///    without its header, a JSON line with an unknown member, an empty
+///    value; and a batch size of zero.
+///
+/// # Expected behavior
+/// - `SkipExisting` writes two pairs and skips `cp_0001`.
+/// - `Fail` rejects the import with `DbError::InvalidArgument` and
+///   writes nothing; `Overwrite` replaces every value.
+/// - Every malformed input fails with `DbError::InvalidArgument`.
+#[test]
+fn import_conflicts_and_errors() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), DbConfig::default()).unwrap();
+    db.put(b"cp_0001", b"old").unwrap();
+    let csv = b"key,value\r\ncp_0000,new\r\ncp_0001,new\r\ncp_0002,new\r\n";
+    let import = |conflict| {
+        let options = ImportOptions {
+            format: ExportFormat::Csv,
+            conflict,
+            ..ImportOptions::default()
+        };
+        db.import_from_reader(csv.as_slice(), &options)
+    };
+
+    let report = import(ConflictPolicy::SkipExisting).unwrap();
+    assert_eq!((report.written, report.skipped), (2, 1));
+    assert_eq!(db.get(b"cp_0001").unwrap(), Some(b"old".to_vec()));
+    assert_eq!(db.get(b"cp_0002").unwrap(), Some(b"new".to_vec()));
+
+    db.delete(b"cp_0002").unwrap();
+    assert!(matches!(
+        import(ConflictPolicy::Fail),
+        Err(DbError::InvalidArgument(_))
+    ));
+    assert_eq!(db.get(b"cp_0002").unwrap(), None);
+
+    let report = import(ConflictPolicy::Overwrite).unwrap();
+    assert_eq!((report.written, report.skipped), (3, 0));
+    assert_eq!(db.get(b"cp_0001").unwrap(), Some(b"new".to_vec()));
+
+    let invalid: [(ExportFormat, &[u8]); 4] = [
+        (ExportFormat::LengthPrefixed, b"\x02\0\0\0k"),
+        (ExportFormat::Csv, b"a,b\n"),
+        (
+            ExportFormat::JsonLines,
+            b"{\"key\":\"a\",\"value\":\"b\",\"ttl\":\"1\"}\n",
+        ),
+        (ExportFormat::JsonLines, b"{\"key\":\"a\",\"value\":\"\"}\n"),
+    ];
+    for (format, input) in invalid {
+        let options = ImportOptions {
+            format,
+            ..ImportOptions::default()
+        };
+        assert!(
+            matches!(
+                db.import_from_reader(input, &options),
+                Err(DbError::InvalidArgument(_))
+            ),
+            "{format:?} {input:?}"
+        );
+    }
+    let options = ImportOptions {
+        batch_size: 0,
+        ..ImportOptions::default()
+    };
+    assert!(matches!(
+        db.import_from_reader(&[][..], &options),
+        Err(DbError::InvalidArgument(_))
+    ));
+    db.close().unwrap();
+}
 
 /// # Scenario
-/// Major compaction merges multiple SSTables into one while preserving
-/// all live data.
+/// `scan_paged` walks a range page by page, resuming from `next_start`.
 ///
 /// # Starting environment
-/// 1 KiB write buffer — 200 writes produce multiple SSTables.
+/// Database with two partitions and a small write buffer holding
+/// `pg_0000..pg_0255` and a key outside the range.
 ///
 /// # Actions
-/// 1. Write 200 keys, close (flushes all frozen memtables).
-/// 2. Reopen, run `major_compact()`.
-/// 3. Verify all 200 keys are still readable.
+/// 1. Page through `[pg_, pg~)` with a page size of 64, deleting the
+///    first key of the next page after the second page.
+/// 2. Page with a size larger than the range; call with a page size of
+///    zero and with an empty range.
 ///
 /// # Expected behavior
-/// `major_compact` returns `true` (compaction happened). All keys survive.
+/// - Four pages of at most 64 pairs, the last with `next_start: None`;
+///   together they equal a full scan without the deleted key.
+/// - A large page holds the whole range and ends the walk.
+/// - A page size of zero is rejected; an empty range yields an empty
+///   page.
 #[test]
-fn major_compaction() {
+fn scan_paged_resumes_from_cursor() {
     let dir = TempDir::new().unwrap();
-    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    let config = DbConfig {
+        partitions: 2,
+        ..small_buffer_config()
+    };
+    let db = Db::open(dir.path(), config).unwrap();
+    for i in 0..256u32 {
+        db.put(format!("pg_{i:04}").as_bytes(), b"value").unwrap();
+    }
+    db.put(b"zz", b"outside").unwrap();
 
-    // Write enough data to create multiple SSTables.
-    for i in 0..200u32 {
-        let key = format!("mc_{:04}", i);
-        let val = format!("val_{:04}", i);
-        db.put(key.as_bytes(), val.as_bytes()).unwrap();
+    let mut pages = Vec::new();
+    let mut start = b"pg_".to_vec();
+    loop {
+        let page = db.scan_paged(&start, b"pg~", 64).unwrap();
+        assert!(page.pairs.len() <= 64);
+        pages.push(page.pairs);
+        if pages.len() == 2 {
+            db.delete(page.next_start.as_deref().unwrap()).unwrap();
+        }
+        match page.next_start {
+            Some(next) => start = next,
+            None => break,
+        }
     }
-    // Close flushes everything.
+    assert_eq!(pages.len(), 4);
+    assert_eq!(pages.concat(), db.scan(b"pg_", b"pg~").unwrap());
+    assert_eq!(pages.concat().len(), 255);
+
+    let page = db.scan_paged(b"pg_", b"pg~", 1000).unwrap();
+    assert_eq!(page.pairs.len(), 255);
+    assert_eq!(page.next_start, None);
+    assert!(matches!(
+        db.scan_paged(b"pg_", b"pg~", 0),
+        Err(DbError::InvalidArgument(_))
+    ));
+    assert_eq!(
+        db.scan_paged(b"pg~", b"pg_", 10).unwrap(),
+        ScanPage::default()
+    );
     db.close().unwrap();
+}
 
-    // Reopen and run major compaction.
+/// # Scenario
+/// `get_opt` / `scan_opt` return the same data as `get` / `scan`, with
+/// checksum verification on or off.
+///
+/// # Starting environment
+/// Database with a small write buffer so that data reaches SSTables.
+///
+/// # Actions
+/// 1. Put 200 keys, delete every tenth, and major-compact.
+/// 2. Read every key and scan the full range with default options and
+///    with `verify_checksums: false`.
+/// 3. Close and call `get_opt` / `scan_opt`.
+///
+/// # Expected behavior
+/// All reads agree; operations on the closed handle return `DbError::Closed`.
+#[test]
+fn read_options_match_default_reads() {
+    let dir = TempDir::new().unwrap();
     let db = Db::open(dir.path(), small_buffer_config()).unwrap();
-    let compacted = db.major_compact().unwrap();
-    assert!(compacted, "should have compacted multiple SSTables");
 
-    // All data should still be present.
     for i in 0..200u32 {
-        let key = format!("mc_{:04}", i);
-        let val = format!("val_{:04}", i);
+        db.put(format!("ro_{i:04}").as_bytes(), b"value").unwrap();
+    }
+    for i in (0..200u32).step_by(10) {
+        db.delete(format!("ro_{i:04}").as_bytes()).unwrap();
+    }
+    db.major_compact().unwrap();
+
+    let fast = ReadOptions {
+        verify_checksums: false,
+        ..ReadOptions::default()
+    };
+    for i in 0..200u32 {
+        let key = format!("ro_{i:04}");
+        let expected = db.get(key.as_bytes()).unwrap();
         assert_eq!(
-            db.get(key.as_bytes()).unwrap(),
-            Some(val.into_bytes()),
-            "mc_{:04} should survive major compaction",
-            i
+            db.get_opt(key.as_bytes(), &ReadOptions::default()).unwrap(),
+            expected
         );
+        assert_eq!(db.get_opt(key.as_bytes(), &fast).unwrap(), expected);
     }
 
+    let expected = db.scan(b"ro_", b"ro_~").unwrap();
+    assert_eq!(expected.len(), 180);
+    assert_eq!(db.scan_opt(b"ro_", b"ro_~", &fast).unwrap(), expected);
+
     db.close().unwrap();
+    assert!(matches!(
+        db.get_opt(b"ro_0001", &fast),
+        Err(DbError::Closed)
+    ));
+    assert!(matches!(
+        db.scan_opt(b"a", b"z", &fast),
+        Err(DbError::Closed)
+    ));
 }
 
 /// # Scenario
-/// Major compaction physically removes point-deleted keys from SSTables.
+/// Reads with a `deadline` give up once it has passed; scans return the
+/// pairs read so far.
 ///
 /// # Starting environment
-/// 1 KiB write buffer — writes produce multiple SSTables.
+/// Database with two partitions and a small write buffer holding
+/// `dl_0000..dl_0299` in SSTables.
 ///
 /// # Actions
-/// 1. Write 100 keys, point-delete even-indexed keys, close.
-/// 2. Reopen, run `major_compact()`.
-/// 3. Verify even keys return `None`, odd keys return their values.
+/// 1. `get_opt` and `scan_opt` with a deadline already passed.
+/// 2. Scan with a deadline 1 ms away, repeatedly.
+/// 3. `get_opt` and `scan_opt` with a deadline a minute away.
 ///
 /// # Expected behavior
-/// Tombstones are applied during compaction; deleted keys are gone.
+/// - The passed deadline fails both with `DbError::TimedOut`; the scan's
+///   `partial` is empty.
+/// - A near deadline either completes or returns a key-ordered prefix
+///   of the full result.
+/// - The distant deadline returns the same as `get` / `scan`.
 #[test]
-fn major_compaction_removes_deleted_keys() {
+fn read_deadline_times_out() {
     let dir = TempDir::new().unwrap();
-
-    {
-        let db = Db::open(dir.path(), small_buffer_config()).unwrap();
-        for i in 0..100u32 {
-            let key = format!("del_{:04}", i);
-            let val = format!("val_{:04}", i);
-            db.put(key.as_bytes(), val.as_bytes()).unwrap();
-        }
-        // Delete half the keys.
-        for i in (0..100u32).step_by(2) {
-            let key = format!("del_{:04}", i);
-            db.delete(key.as_bytes()).unwrap();
-        }
-        db.close().unwrap();
+    let config = DbConfig {
+        partitions: 2,
+        ..small_buffer_config()
+    };
+    let db = Db::open(dir.path(), config).unwrap();
+    for i in 0..300u32 {
+        db.put(format!("dl_{i:04}").as_bytes(), b"value").unwrap();
     }
+    push_out_of_memtable(&db);
+    let full = db.scan(b"dl_", b"dl~").unwrap();
 
-    {
-        let db = Db::open(dir.path(), small_buffer_config()).unwrap();
-        db.major_compact().unwrap();
+    let deadline = |after: Duration| ReadOptions {
+        deadline: Some(Instant::now() + after),
+        ..ReadOptions::default()
+    };
+    let passed = ReadOptions {
+        deadline: Some(Instant::now() - Duration::from_millis(1)),
+        ..ReadOptions::default()
+    };
+    assert!(matches!(
+        db.get_opt(b"dl_0000", &passed),
+        Err(DbError::TimedOut { partial }) if partial.is_empty()
+    ));
+    assert!(matches!(
+        db.scan_opt(b"dl_", b"dl~", &passed),
+        Err(DbError::TimedOut { partial }) if partial.is_empty()
+    ));
 
-        for i in 0..100u32 {
-            let key = format!("del_{:04}", i);
-            if i % 2 == 0 {
-                assert_eq!(db.get(key.as_bytes()).unwrap(), None);
-            } else {
-                let val = format!("val_{:04}", i);
-                assert_eq!(db.get(key.as_bytes()).unwrap(), Some(val.into_bytes()));
+    for _ in 0..20 {
+        match db.scan_opt(b"dl_", b"dl~", &deadline(Duration::from_millis(1))) {
+            Ok(results) => assert_eq!(results, full),
+            Err(DbError::TimedOut { partial }) => {
+                assert_eq!(partial, full[..partial.len()]);
             }
+            Err(e) => panic!("unexpected error: {e}"),
         }
-        db.close().unwrap();
+    }
+
+    let distant = deadline(Duration::from_secs(60));
+    assert_eq!(
+        db.get_opt(b"dl_0000", &distant).unwrap(),
+        Some(b"value".to_vec())
+    );
+    assert_eq!(db.scan_opt(b"dl_", b"dl~", &distant).unwrap(), full);
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// Writes that skip the WAL are visible at once and kept by a clean close.
+///
+/// # Starting environment
+/// Freshly opened database with default config.
+///
+/// # Actions
+/// 1. Put 50 keys with `disable_wal`, one key with `sync`, and commit a
+///    batch with `disable_wal` that deletes one key and adds another.
+/// 2. Read them back; close; reopen and read again.
+/// 3. Call `put_opt` on the closed handle.
+///
+/// # Expected behavior
+/// All writes read back before and after the reopen, and the WAL-less
+/// writes reached an SSTable. The closed handle returns `DbError::Closed`.
+#[test]
+fn write_options_disable_wal_kept_on_close() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), DbConfig::default()).unwrap();
+    let unlogged = WriteOptions {
+        disable_wal: true,
+        ..WriteOptions::default()
+    };
+    let synced = WriteOptions {
+        sync: true,
+        ..WriteOptions::default()
+    };
+
+    for i in 0..50u32 {
+        db.put_opt(format!("wo_{i:04}").as_bytes(), b"cached", &unlogged)
+            .unwrap();
+    }
+    db.put_opt(b"wo_synced", b"durable", &synced).unwrap();
+    let mut batch = WriteBatchWithIndex::new();
+    batch.delete(b"wo_0007");
+    batch.put(b"wo_batched", b"cached");
+    db.write_opt(&batch, &unlogged).unwrap();
+
+    let check = |db: &Db| {
+        assert_eq!(db.scan(b"wo_", b"wo_~").unwrap().len(), 51);
+        assert_eq!(db.get(b"wo_0007").unwrap(), None);
+        assert_eq!(db.get(b"wo_batched").unwrap(), Some(b"cached".to_vec()));
+        assert_eq!(db.get(b"wo_synced").unwrap(), Some(b"durable".to_vec()));
+    };
+    check(&db);
+    db.close().unwrap();
+
+    let db = reopen(dir.path());
+    check(&db);
+    assert!(!db.sstable_metadata().unwrap().is_empty());
+    db.close().unwrap();
+    assert!(matches!(
+        db.put_opt(b"wo_0001", b"x", &unlogged),
+        Err(DbError::Closed)
+    ));
+}
+
+// ================================================================================================
+// Persistence
+// ================================================================================================
+
+/// # Scenario
+/// Data written before `close()` is readable after reopening.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. Open database, put `"persist_key"` → `"persist_value"`, close.
+/// 2. Reopen database from the same directory.
+/// 3. `get("persist_key")`.
+///
+/// # Expected behavior
+/// The reopened database returns `Some("persist_value")`.
+#[test]
+fn persistence_across_reopen() {
+    let dir = TempDir::new().unwrap();
+
+    {
+        let db = Db::open(dir.path(), DbConfig::default()).unwrap();
+        db.put(b"persist_key", b"persist_value").unwrap();
+        db.close().unwrap();
+    }
+
+    {
+        let db = reopen(dir.path());
+        assert_eq!(
+            db.get(b"persist_key").unwrap(),
+            Some(b"persist_value".to_vec())
+        );
+        db.close().unwrap();
+    }
+}
+
+/// # Scenario
+/// Hundreds of writes survive close → reopen with a small write buffer
+/// that triggers multiple flushes.
+///
+/// # Starting environment
+/// Empty temporary directory, 1 KiB write buffer (forces frequent flushes).
+///
+/// # Actions
+/// 1. Write 500 sequentially-named keys, close.
+/// 2. Reopen and verify all 500 keys.
+///
+/// # Expected behavior
+/// Every key is present with its original value after reopen.
+#[test]
+fn persistence_many_writes() {
+    let dir = TempDir::new().unwrap();
+
+    {
+        let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+        for i in 0..500u32 {
+            let key = format!("key_{:04}", i);
+            let val = format!("val_{:04}", i);
+            db.put(key.as_bytes(), val.as_bytes()).unwrap();
+        }
+        db.close().unwrap();
+    }
+
+    {
+        let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+        for i in 0..500u32 {
+            let key = format!("key_{:04}", i);
+            let val = format!("val_{:04}", i);
+            assert_eq!(
+                db.get(key.as_bytes()).unwrap(),
+                Some(val.into_bytes()),
+                "key_{:04} should be present after reopen",
+                i
+            );
+        }
+        db.close().unwrap();
+    }
+}
+
+/// # Scenario
+/// Point-delete tombstones survive close → reopen.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. Put `"alive"` → `"yes"` and `"dead"` → `"soon"`, then `delete("dead")`, close.
+/// 2. Reopen and get both keys.
+///
+/// # Expected behavior
+/// `"alive"` returns `Some("yes")`; `"dead"` returns `None`.
+#[test]
+fn persistence_deletes_survive_reopen() {
+    let dir = TempDir::new().unwrap();
+
+    {
+        let db = Db::open(dir.path(), DbConfig::default()).unwrap();
+        db.put(b"alive", b"yes").unwrap();
+        db.put(b"dead", b"soon").unwrap();
+        db.delete(b"dead").unwrap();
+        db.close().unwrap();
+    }
+
+    {
+        let db = reopen(dir.path());
+        assert_eq!(db.get(b"alive").unwrap(), Some(b"yes".to_vec()));
+        assert_eq!(db.get(b"dead").unwrap(), None);
+        db.close().unwrap();
+    }
+}
+
+/// # Scenario
+/// SSTables written in per-strategy directories under ULID names are
+/// found again after the database directory is moved.
+///
+/// # Starting environment
+/// Empty database directory `db`, opened with
+/// `SstDirLayout::PerStrategy` and `SstFileNaming::Ulid`.
+///
+/// # Actions
+/// 1. Write `mv_0000..mv_0299`; flush them; major-compact; close.
+/// 2. Rename `db` to `moved`; reopen with the default layout and naming.
+/// 3. Write and flush more keys; close.
+///
+/// # Expected behavior
+/// - After step 1 every SSTable lives in `db/sstables/stcs/` under a
+///   26-character name.
+/// - After step 2 every key reads back from `moved`.
+/// - The SSTables flushed in step 3 live directly in
+///   `moved/sstables/` under id names.
+#[test]
+fn sstable_layout_survives_moved_directory() {
+    let dir = TempDir::new().unwrap();
+    let db_dir = dir.path().join("db");
+    let config = DbConfig {
+        sstable_layout: SstDirLayout::PerStrategy,
+        sstable_naming: SstFileNaming::Ulid,
+        ..small_buffer_config()
+    };
+
+    let db = Db::open(&db_dir, config).unwrap();
+    for i in 0..300u32 {
+        db.put(format!("mv_{i:04}").as_bytes(), b"value_with_some_padding")
+            .unwrap();
+    }
+    push_out_of_memtable(&db);
+    db.major_compact().unwrap();
+    let metadata = db.sstable_metadata().unwrap();
+    assert!(!metadata.is_empty());
+    for sst in &metadata {
+        assert_eq!(sst.path.parent().unwrap(), db_dir.join("sstables/stcs"));
+        let stem = sst.path.file_stem().unwrap().to_str().unwrap();
+        assert_eq!(stem.len(), 26, "{stem}");
+    }
+    db.close().unwrap();
+
+    let moved = dir.path().join("moved");
+    std::fs::rename(&db_dir, &moved).unwrap();
+    let db = Db::open(&moved, small_buffer_config()).unwrap();
+    for i in 0..300u32 {
+        assert_eq!(
+            db.get(format!("mv_{i:04}").as_bytes()).unwrap(),
+            Some(b"value_with_some_padding".to_vec()),
+            "key {i}"
+        );
+    }
+
+    let before: Vec<_> = db
+        .sstable_metadata()
+        .unwrap()
+        .into_iter()
+        .map(|m| m.id)
+        .collect();
+    for i in 0..100u32 {
+        db.put(format!("new_{i:04}").as_bytes(), b"value_with_some_padding")
+            .unwrap();
+    }
+    push_out_of_memtable(&db);
+    db.close().unwrap();
+
+    let db = reopen(&moved);
+    let added: Vec<_> = db
+        .sstable_metadata()
+        .unwrap()
+        .into_iter()
+        .filter(|m| !before.contains(&m.id))
+        .collect();
+    assert!(!added.is_empty());
+    for sst in &added {
+        assert_eq!(sst.path, moved.join(format!("sstables/{:06}.sst", sst.id)));
+    }
+    db.close().unwrap();
+}
+
+// ================================================================================================
+// Compaction
+// ================================================================================================
+
+/// # Scenario
+/// Major compaction merges multiple SSTables into one while preserving
+/// all live data.
+///
+/// # Starting environment
+/// 1 KiB write buffer — 200 writes produce multiple SSTables.
+///
+/// # Actions
+/// 1. Write 200 keys, close (flushes all frozen memtables).
+/// 2. Reopen, run `major_compact()`.
+/// 3. Verify all 200 keys are still readable.
+///
+/// # Expected behavior
+/// `major_compact` returns `true` (compaction happened). All keys survive.
+#[test]
+fn major_compaction() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+
+    // Write enough data to create multiple SSTables.
+    for i in 0..200u32 {
+        let key = format!("mc_{:04}", i);
+        let val = format!("val_{:04}", i);
+        db.put(key.as_bytes(), val.as_bytes()).unwrap();
+    }
+    // Close flushes everything.
+    db.close().unwrap();
+
+    // Reopen and run major compaction.
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    let compacted = db.major_compact().unwrap();
+    assert!(compacted, "should have compacted multiple SSTables");
+
+    // All data should still be present.
+    for i in 0..200u32 {
+        let key = format!("mc_{:04}", i);
+        let val = format!("val_{:04}", i);
+        assert_eq!(
+            db.get(key.as_bytes()).unwrap(),
+            Some(val.into_bytes()),
+            "mc_{:04} should survive major compaction",
+            i
+        );
+    }
+
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// Major compaction physically removes point-deleted keys from SSTables.
+///
+/// # Starting environment
+/// 1 KiB write buffer — writes produce multiple SSTables.
+///
+/// # Actions
+/// 1. Write 100 keys, point-delete even-indexed keys, close.
+/// 2. Reopen, run `major_compact()`.
+/// 3. Verify even keys return `None`, odd keys return their values.
+///
+/// # Expected behavior
+/// Tombstones are applied during compaction; deleted keys are gone.
+#[test]
+fn major_compaction_removes_deleted_keys() {
+    let dir = TempDir::new().unwrap();
+
+    {
+        let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+        for i in 0..100u32 {
+            let key = format!("del_{:04}", i);
+            let val = format!("val_{:04}", i);
+            db.put(key.as_bytes(), val.as_bytes()).unwrap();
+        }
+        // Delete half the keys.
+        for i in (0..100u32).step_by(2) {
+            let key = format!("del_{:04}", i);
+            db.delete(key.as_bytes()).unwrap();
+        }
+        db.close().unwrap();
+    }
+
+    {
+        let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+        db.major_compact().unwrap();
+
+        for i in 0..100u32 {
+            let key = format!("del_{:04}", i);
+            if i % 2 == 0 {
+                assert_eq!(db.get(key.as_bytes()).unwrap(), None);
+            } else {
+                let val = format!("val_{:04}", i);
+                assert_eq!(db.get(key.as_bytes()).unwrap(), Some(val.into_bytes()));
+            }
+        }
+        db.close().unwrap();
+    }
+}
+
+/// # Scenario
+/// A background major compaction reports progress and can be awaited;
+/// only one runs at a time.
+///
+/// # Starting environment
+/// 1 KiB write buffer — 200 writes produce multiple SSTables — and a
+/// minor compaction threshold of 64, so they are not merged in the
+/// background.
+///
+/// # Actions
+/// 1. Write 200 keys, push them out of the memtable, close, reopen.
+/// 2. `major_compact_async()` twice; wait on both handles.
+/// 3. Start another one on the single SSTable left and wait.
+/// 4. Close and call `major_compact_async()`.
+///
+/// # Expected behavior
+/// The first run returns `true` and its final progress counts every
+/// input record as merged; the second handle completes without error. One SSTable
+/// remains and every key reads back. The second run returns `false`;
+/// the closed handle returns `DbError::Closed`.
+#[test]
+fn major_compaction_async() {
+    let dir = TempDir::new().unwrap();
+    let config = || DbConfig {
+        min_compaction_threshold: 64,
+        max_compaction_threshold: 64,
+        ..small_buffer_config()
+    };
+    let db = Db::open(dir.path(), config()).unwrap();
+    for i in 0..200u32 {
+        db.put(format!("ma_{i:04}").as_bytes(), b"value").unwrap();
+    }
+    push_out_of_memtable(&db);
+    db.close().unwrap();
+
+    let db = Db::open(dir.path(), config()).unwrap();
+    assert!(db.sstable_metadata().unwrap().len() >= 2);
+    let first = db.major_compact_async().unwrap();
+    let second = db.major_compact_async().unwrap();
+    assert!(first.wait().unwrap());
+    // Joined the first run, or — had it already finished — ran on the
+    // single SSTable left.
+    second.wait().unwrap();
+
+    let progress = first.progress();
+    assert!(progress.finished);
+    assert_eq!(progress.partitions, 1);
+    assert_eq!(progress.partitions_done, 1);
+    assert!(progress.records_total >= 200);
+    assert_eq!(progress.records_merged, progress.records_total);
+    assert_eq!(db.sstable_metadata().unwrap().len(), 1);
+    assert_eq!(db.scan(b"ma_", b"ma_~").unwrap().len(), 200);
+
+    let again = db.major_compact_async().unwrap();
+    assert!(!again.wait().unwrap());
+    assert!(again.is_finished());
+
+    db.close().unwrap();
+    assert!(matches!(db.major_compact_async(), Err(DbError::Closed)));
+}
+
+/// # Scenario
+/// A write storm against a single worker with one-task queues is slowed
+/// down by the flush queue instead of queueing without bound.
+///
+/// # Starting environment
+/// 1 KiB write buffer, `thread_pool_size: 1`, `background_queue_depth: 1`.
+///
+/// # Actions
+/// 1. Put 2 000 keys while another thread samples `queue_stats()`.
+/// 2. Close, then read `queue_stats()` again and reopen.
+///
+/// # Expected behavior
+/// No sampled queue ever holds more than one task. After close every
+/// queue is empty, and every key reads back after reopen.
+#[test]
+fn background_queues_bounded() {
+    let dir = TempDir::new().unwrap();
+    let config = DbConfig {
+        thread_pool_size: 1,
+        background_queue_depth: 1,
+        ..small_buffer_config()
+    };
+    let db = Arc::new(Db::open(dir.path(), config).unwrap());
+    let done = Arc::new(AtomicBool::new(false));
+
+    let sampler = {
+        let (db, done) = (Arc::clone(&db), Arc::clone(&done));
+        thread::spawn(move || {
+            let mut samples = Vec::new();
+            while !done.load(Ordering::Relaxed) {
+                samples.push(db.queue_stats());
+                thread::sleep(Duration::from_millis(1));
+            }
+            samples
+        })
+    };
+    for i in 0..2000u32 {
+        db.put(format!("bq_{i:05}").as_bytes(), b"value_with_padding")
+            .unwrap();
+    }
+    done.store(true, Ordering::Relaxed);
+    let samples = sampler.join().unwrap();
+    assert!(!samples.is_empty());
+    for stats in &samples {
+        assert!(stats.flush <= 1, "{stats:?}");
+        assert!(stats.minor <= 1, "{stats:?}");
+        assert!(stats.tombstone <= 1, "{stats:?}");
+        assert!(stats.major <= 1, "{stats:?}");
+    }
+
+    db.close().unwrap();
+    let stats = db.queue_stats();
+    assert_eq!(
+        (stats.flush, stats.minor, stats.tombstone, stats.major),
+        (0, 0, 0, 0)
+    );
+    drop(db);
+
+    let db = reopen(dir.path());
+    assert_eq!(db.scan(b"bq_", b"bq_~").unwrap().len(), 2000);
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// A range hinted after a bulk delete is compacted without waiting for
+/// the tombstone ratio trigger, and its tombstones are dropped.
+///
+/// # Starting environment
+/// 2 KiB write buffer, `min_compaction_threshold: 64`, and
+/// `tombstone_compaction_ratio: 1.0`, so neither trigger fires on its
+/// own.
+///
+/// # Actions
+/// 1. Put `hc_0000..hc_0299`, then delete each key while putting a
+///    matching `hd_` key, and reopen.
+/// 2. `suggest_compact_range(b"hc_", b"hc_~", High)`.
+/// 3. Poll `sstable_metadata()` for up to 10 seconds.
+///
+/// # Expected behavior
+/// The hint covers at least two SSTables. Every tombstone is dropped,
+/// the `hc_` range scans empty, and the other keys are intact. Invalid
+/// ranges and a closed database are rejected.
+#[test]
+fn suggest_compact_range_drops_deleted_keys() {
+    let dir = TempDir::new().unwrap();
+    let config = || DbConfig {
+        write_buffer_size: 2048,
+        min_compaction_threshold: 64,
+        max_compaction_threshold: 64,
+        tombstone_compaction_ratio: 1.0,
+        ..small_buffer_config()
+    };
+    let db = Db::open(dir.path(), config()).unwrap();
+    for i in 0..300u32 {
+        db.put(format!("hc_{i:04}").as_bytes(), b"value_with_padding")
+            .unwrap();
+    }
+    for i in 0..300u32 {
+        db.delete(format!("hc_{i:04}").as_bytes()).unwrap();
+        db.put(format!("hd_{i:04}").as_bytes(), b"value_with_padding")
+            .unwrap();
+    }
+    push_out_of_memtable(&db);
+    db.close().unwrap();
+
+    let db = Db::open(dir.path(), config()).unwrap();
+    let tombstones = |db: &Db| -> u64 {
+        db.sstable_metadata()
+            .unwrap()
+            .iter()
+            .map(|m| m.tombstone_count)
+            .sum()
+    };
+    assert!(tombstones(&db) > 0);
+
+    let hinted = db
+        .suggest_compact_range(b"hc_", b"hc_~", CompactionPriority::High)
+        .unwrap();
+    assert!(hinted >= 2, "hinted {hinted} SSTables");
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while tombstones(&db) > 0 {
+        assert!(Instant::now() < deadline, "hinted range never compacted");
+        thread::sleep(Duration::from_millis(50));
+    }
+    assert!(db.scan(b"hc_", b"hc_~").unwrap().is_empty());
+    assert_eq!(db.scan(b"hd_", b"hd_~").unwrap().len(), 300);
+    assert_eq!(db.scan(b"zz_", b"zz_~").unwrap().len(), 100);
+
+    assert!(matches!(
+        db.suggest_compact_range(b"b", b"a", CompactionPriority::Low),
+        Err(DbError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        db.suggest_compact_range(b"", b"a", CompactionPriority::Low),
+        Err(DbError::InvalidArgument(_))
+    ));
+    db.close().unwrap();
+    assert!(matches!(
+        db.suggest_compact_range(b"a", b"b", CompactionPriority::High),
+        Err(DbError::Closed)
+    ));
+}
+
+/// # Scenario
+/// Periodic compaction rewrites aged SSTables on an idle database.
+///
+/// # Starting environment
+/// Database populated with a small write buffer, then closed.
+///
+/// # Actions
+/// 1. Reopen with `periodic_compaction_seconds: 1` and record SSTable ids.
+/// 2. Issue no writes; poll `sstable_metadata()` for up to 10 seconds.
+///
+/// # Expected behavior
+/// Every original SSTable is replaced by the background timer, and all
+/// data is still readable.
+#[test]
+fn periodic_compaction_rewrites_idle_sstables() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    for i in 0..100u32 {
+        db.put(format!("per_{i:04}").as_bytes(), b"value").unwrap();
+    }
+    db.close().unwrap();
+
+    let config = DbConfig {
+        periodic_compaction_seconds: 1,
+        ..small_buffer_config()
+    };
+    let db = Db::open(dir.path(), config).unwrap();
+    let original: Vec<u64> = db
+        .sstable_metadata()
+        .unwrap()
+        .iter()
+        .map(|m| m.id)
+        .collect();
+    assert!(!original.is_empty());
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    loop {
+        let current: Vec<u64> = db
+            .sstable_metadata()
+            .unwrap()
+            .iter()
+            .map(|m| m.id)
+            .collect();
+        if current.iter().all(|id| !original.contains(id)) {
+            break;
+        }
+        assert!(
+            std::time::Instant::now() < deadline,
+            "SSTables {current:?} not rewritten in time"
+        );
+        thread::sleep(std::time::Duration::from_millis(100));
+    }
+
+    for i in 0..100u32 {
+        assert_eq!(
+            db.get(format!("per_{i:04}").as_bytes()).unwrap(),
+            Some(b"value".to_vec())
+        );
+    }
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// A bulk load writes large SSTables without compacting them, then ends
+/// in a single major compaction.
+///
+/// # Starting environment
+/// Database with a 1 KiB write buffer.
+///
+/// # Actions
+/// 1. `enter_bulk_load`, then put 4 000 keys.
+/// 2. Wait for background flushes to produce at least 4 SSTables.
+/// 3. `exit_bulk_load`.
+///
+/// # Expected behavior
+/// During the load every SSTable is larger than the configured write
+/// buffer. After the exit the database is no longer bulk loading, holds
+/// a single SSTable, and returns every key.
+#[test]
+fn bulk_load_compacts_on_exit() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    db.enter_bulk_load().unwrap();
+    assert!(db.is_bulk_loading().unwrap());
+
+    for i in 0..4000u32 {
+        db.put(format!("bulk_{i:05}").as_bytes(), &[b'v'; 32])
+            .unwrap();
+    }
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while db.sstable_metadata().unwrap().len() < 4 {
+        assert!(
+            std::time::Instant::now() < deadline,
+            "bulk-loaded write buffers not flushed in time"
+        );
+        thread::sleep(std::time::Duration::from_millis(50));
+    }
+    for meta in db.sstable_metadata().unwrap() {
+        assert!(meta.file_size > 4096, "small SSTable {meta:?}");
+    }
+
+    db.exit_bulk_load().unwrap();
+    assert!(!db.is_bulk_loading().unwrap());
+    assert_eq!(db.sstable_metadata().unwrap().len(), 1);
+    for i in (0..4000u32).step_by(97) {
+        assert_eq!(
+            db.get(format!("bulk_{i:05}").as_bytes()).unwrap(),
+            Some(vec![b'v'; 32])
+        );
+    }
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// A maintenance window: compaction is paused and the database switched
+/// to read-only mode, then both are turned off again.
+///
+/// # Starting environment
+/// Database with a 1 KiB write buffer and a minor compaction threshold
+/// of 4.
+///
+/// # Actions
+/// 1. Pause compaction, put 400 keys, enter read-only mode.
+/// 2. Try a put, a delete, a range delete, and a batch; read the keys.
+/// 3. Resume compaction and wait for it.
+/// 4. Leave read-only mode, put a key, close, reopen.
+///
+/// # Expected behavior
+/// - After step 1 no frozen WAL is left and at least 4 SSTables wait
+///   uncompacted.
+/// - Every write in step 2 fails with `DbError::ReadOnly`; every key
+///   still reads back.
+/// - Resuming compacts the SSTables.
+/// - Writes succeed again; the reopened database is not read-only.
+#[test]
+fn read_only_mode_refuses_writes() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    db.set_compaction_paused(true).unwrap();
+    assert!(db.is_compaction_paused().unwrap());
+    for i in 0..400u32 {
+        db.put(format!("ro_{i:04}").as_bytes(), &[b'v'; 32])
+            .unwrap();
+    }
+
+    db.set_read_only(true).unwrap();
+    assert!(db.is_read_only().unwrap());
+    let files = db.wal_files().unwrap();
+    assert!(
+        files.iter().all(|f| f.status != WalFileStatus::Frozen),
+        "unflushed WAL segments: {files:?}"
+    );
+    let paused_count = db.sstable_metadata().unwrap().len();
+    assert!(paused_count >= 4, "{paused_count} SSTables");
+
+    assert!(matches!(db.put(b"ro_new", b"v"), Err(DbError::ReadOnly)));
+    assert!(matches!(db.delete(b"ro_0000"), Err(DbError::ReadOnly)));
+    assert!(matches!(
+        db.delete_range(b"ro_0000", b"ro_0100"),
+        Err(DbError::ReadOnly)
+    ));
+    let mut batch = WriteBatchWithIndex::new();
+    batch.put(b"ro_new", b"v");
+    assert!(matches!(db.write(&batch), Err(DbError::ReadOnly)));
+    for i in 0..400u32 {
+        assert_eq!(
+            db.get(format!("ro_{i:04}").as_bytes()).unwrap(),
+            Some(vec![b'v'; 32])
+        );
+    }
+    assert_eq!(db.scan(b"ro_", b"ro_~").unwrap().len(), 400);
+
+    db.set_compaction_paused(false).unwrap();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while db.sstable_metadata().unwrap().len() >= paused_count {
+        assert!(Instant::now() < deadline, "compaction not resumed in time");
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    db.set_read_only(false).unwrap();
+    db.put(b"ro_new", b"v").unwrap();
+    db.close().unwrap();
+
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    assert!(!db.is_read_only().unwrap());
+    assert_eq!(db.get(b"ro_new").unwrap(), Some(b"v".to_vec()));
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// Threads lock overlapping and disjoint key ranges while others read
+/// and write inside them.
+///
+/// # Actions
+/// 1. Lock `[b, m)`; try to lock `[f, z)` and `[m, z)`.
+/// 2. Put and read a key inside `[b, m)` while it is locked.
+/// 3. From another thread, lock `[c, d)`; drop the `[b, m)` guard after
+///    checking it waits.
+/// 4. Lock invalid ranges, and any range after close.
+///
+/// # Expected behavior
+/// - `[f, z)` is refused; `[m, z)` touches `[b, m)` only at its
+///   exclusive end and is granted.
+/// - Reads and writes inside a locked range go through.
+/// - The other thread gets `[c, d)` only once `[b, m)` is released.
+/// - Empty or inverted ranges fail with `InvalidArgument`, a closed
+///   database with `Closed`.
+#[test]
+fn range_locks_exclude_overlapping_ranges() {
+    let dir = TempDir::new().unwrap();
+    let db = Arc::new(Db::open(dir.path(), DbConfig::default()).unwrap());
+
+    let guard = db.lock_range(b"b", b"m").unwrap();
+    assert_eq!((guard.start(), guard.end()), (&b"b"[..], &b"m"[..]));
+    assert!(db.try_lock_range(b"f", b"z").unwrap().is_none());
+    let tail = db.try_lock_range(b"m", b"z").unwrap();
+    assert!(tail.is_some());
+
+    db.put(b"key", b"value").unwrap();
+    assert_eq!(db.get(b"key").unwrap(), Some(b"value".to_vec()));
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let waiter = {
+        let db = Arc::clone(&db);
+        thread::spawn(move || {
+            let inner = db.lock_range(b"c", b"d").unwrap();
+            tx.send(()).unwrap();
+            drop(inner);
+        })
+    };
+    assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+    drop(guard);
+    rx.recv_timeout(Duration::from_secs(10)).unwrap();
+    waiter.join().unwrap();
+    drop(tail);
+
+    assert!(matches!(
+        db.lock_range(b"m", b"b"),
+        Err(DbError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        db.try_lock_range(b"", b"b"),
+        Err(DbError::InvalidArgument(_))
+    ));
+    db.close().unwrap();
+    assert!(matches!(db.lock_range(b"a", b"b"), Err(DbError::Closed)));
+}
+
+/// # Scenario
+/// An idle database flushes its write buffer once it reaches
+/// `max_memtable_age`.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. Open with `max_memtable_age: 2` and a large write buffer.
+/// 2. Put a few keys, then issue no writes; poll `sstable_metadata()` for
+///    up to 10 seconds.
+///
+/// # Expected behavior
+/// The background timer flushes the keys to an SSTable, and they stay
+/// readable.
+#[test]
+fn memtable_age_flushes_idle_database() {
+    let dir = TempDir::new().unwrap();
+    let config = DbConfig {
+        max_memtable_age: 2,
+        ..DbConfig::default()
+    };
+    let db = Db::open(dir.path(), config).unwrap();
+    for i in 0..10u32 {
+        db.put(format!("age_{i:04}").as_bytes(), b"value").unwrap();
+    }
+    assert!(db.sstable_metadata().unwrap().is_empty());
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while db.sstable_metadata().unwrap().is_empty() {
+        assert!(
+            std::time::Instant::now() < deadline,
+            "write buffer not flushed in time"
+        );
+        thread::sleep(std::time::Duration::from_millis(100));
+    }
+
+    for i in 0..10u32 {
+        assert_eq!(
+            db.get(format!("age_{i:04}").as_bytes()).unwrap(),
+            Some(b"value".to_vec())
+        );
+    }
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// `clone_to` produces an independent copy of a live database.
+///
+/// # Starting environment
+/// Database with data in SSTables, frozen write buffers, and the active
+/// write buffer, including a deleted key.
+///
+/// # Actions
+/// 1. `clone_to` a fresh directory and open the clone.
+/// 2. Write different values to the source and the clone.
+///
+/// # Expected behavior
+/// The clone starts with exactly the source's data, and later writes to
+/// either database are invisible to the other, including after reopen.
+#[test]
+fn clone_to_creates_independent_copy() {
+    let dir = TempDir::new().unwrap();
+    let clone_dir = TempDir::new().unwrap();
+    let clone_path = clone_dir.path().join("clone");
+
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    for i in 0..200u32 {
+        db.put(
+            format!("cl_{i:04}").as_bytes(),
+            format!("v_{i:04}").as_bytes(),
+        )
+        .unwrap();
+    }
+    db.delete(b"cl_0007").unwrap();
+
+    db.clone_to(&clone_path).unwrap();
+    let clone = Db::open(&clone_path, small_buffer_config()).unwrap();
+    assert_eq!(
+        clone.scan(b"cl_", b"cl_\xff").unwrap(),
+        db.scan(b"cl_", b"cl_\xff").unwrap()
+    );
+    assert_eq!(clone.get(b"cl_0007").unwrap(), None);
+
+    db.put(b"cl_0000", b"source").unwrap();
+    clone.put(b"cl_0001", b"clone").unwrap();
+    db.major_compact().unwrap();
+    clone.major_compact().unwrap();
+
+    assert_eq!(clone.get(b"cl_0000").unwrap(), Some(b"v_0000".to_vec()));
+    assert_eq!(db.get(b"cl_0001").unwrap(), Some(b"v_0001".to_vec()));
+    db.close().unwrap();
+    clone.close().unwrap();
+
+    let clone = reopen(&clone_path);
+    assert_eq!(clone.get(b"cl_0000").unwrap(), Some(b"v_0000".to_vec()));
+    assert_eq!(clone.get(b"cl_0001").unwrap(), Some(b"clone".to_vec()));
+    assert_eq!(clone.get(b"cl_0199").unwrap(), Some(b"v_0199".to_vec()));
+    clone.close().unwrap();
+}
+
+/// # Scenario
+/// A hash-partitioned database behaves like an unpartitioned one.
+///
+/// # Starting environment
+/// Database opened with `partitions: 4` and a small write buffer.
+///
+/// # Actions
+/// 1. Put 400 keys, delete some, range-delete a span, and commit a batch.
+/// 2. Scan, major-compact, close, reopen, scan again.
+///
+/// # Expected behavior
+/// Scans return keys in byte order with deletes applied, every partition
+/// holds data, and the contents survive compaction and reopen.
+#[test]
+fn partitioned_db_keeps_ordered_scans() {
+    let dir = TempDir::new().unwrap();
+    let config = || DbConfig {
+        partitions: 4,
+        ..small_buffer_config()
+    };
+
+    let db = Db::open(dir.path(), config()).unwrap();
+    for i in 0..400u32 {
+        db.put(
+            format!("pt_{i:04}").as_bytes(),
+            format!("v_{i:04}").as_bytes(),
+        )
+        .unwrap();
+    }
+    for i in (0..400u32).step_by(10) {
+        db.delete(format!("pt_{i:04}").as_bytes()).unwrap();
+    }
+    db.delete_range(b"pt_0100", b"pt_0200").unwrap();
+    let mut batch = WriteBatchWithIndex::new();
+    batch.put(b"pt_0150", b"batched");
+    batch.delete(b"pt_0301");
+    db.write(&batch).unwrap();
+
+    let expected: Vec<(Vec<u8>, Vec<u8>)> = (0..400u32)
+        .filter(|i| i % 10 != 0 && !(100..200).contains(i) && *i != 301)
+        .map(|i| {
+            (
+                format!("pt_{i:04}").into_bytes(),
+                format!("v_{i:04}").into_bytes(),
+            )
+        })
+        .chain(std::iter::once((b"pt_0150".to_vec(), b"batched".to_vec())))
+        .collect::<std::collections::BTreeMap<_, _>>()
+        .into_iter()
+        .collect();
+
+    assert_eq!(db.scan(b"pt_", b"pt_\xff").unwrap(), expected);
+    db.major_compact().unwrap();
+    assert_eq!(db.scan(b"pt_", b"pt_\xff").unwrap(), expected);
+    db.close().unwrap();
+
+    for p in 0..4 {
+        assert!(
+            dir.path()
+                .join(format!("partitions/{p:03}/manifest"))
+                .is_dir()
+        );
+    }
+
+    let db = Db::open(dir.path(), config()).unwrap();
+    assert_eq!(db.scan(b"pt_", b"pt_\xff").unwrap(), expected);
+    assert_eq!(db.get(b"pt_0150").unwrap(), Some(b"batched".to_vec()));
+    assert_eq!(db.get(b"pt_0110").unwrap(), None);
+    db.close().unwrap();
+}
+
+/// Counts newly created spans by name.
+#[derive(Clone, Default)]
+struct SpanCounter(Arc<std::sync::Mutex<std::collections::HashMap<&'static str, usize>>>);
+
+impl SpanCounter {
+    fn count(&self, name: &str) -> usize {
+        self.0.lock().unwrap().get(name).copied().unwrap_or(0)
+    }
+}
+
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for SpanCounter {
+    fn on_new_span(
+        &self,
+        attrs: &tracing::span::Attributes<'_>,
+        _id: &tracing::span::Id,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        *self
+            .0
+            .lock()
+            .unwrap()
+            .entry(attrs.metadata().name())
+            .or_default() += 1;
+    }
+}
+
+/// # Scenario
+/// `trace_sampling_ratio` controls how many operations open a span.
+///
+/// # Starting environment
+/// A subscriber that counts spans, installed for the current thread.
+///
+/// # Actions
+/// For ratios `0.0`, `0.5`, and `1.0`: open a database, issue 200 puts
+/// and 200 gets.
+///
+/// # Expected behavior
+/// No `db.get` spans at `0.0`, all 200 at `1.0`, and a proportionate
+/// share in between.
+#[test]
+fn trace_sampling_ratio_limits_operation_spans() {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    for (ratio, min, max) in [(0.0, 0, 0), (0.5, 60, 140), (1.0, 200, 200)] {
+        let counter = SpanCounter::default();
+        let subscriber = tracing_subscriber::registry().with(counter.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            let dir = TempDir::new().unwrap();
+            let config = DbConfig {
+                trace_sampling_ratio: ratio,
+                ..DbConfig::default()
+            };
+            let db = Db::open(dir.path(), config).unwrap();
+            for i in 0..200u32 {
+                db.put(format!("ts_{i:04}").as_bytes(), b"v").unwrap();
+            }
+            for i in 0..200u32 {
+                db.get(format!("ts_{i:04}").as_bytes()).unwrap();
+            }
+            db.close().unwrap();
+        });
+
+        let gets = counter.count("db.get");
+        assert!(
+            (min..=max).contains(&gets),
+            "ratio {ratio}: {gets} db.get spans, expected {min}..={max}"
+        );
+        assert_eq!(counter.count("db.put") == 0, ratio == 0.0);
+    }
+
+    let config = DbConfig {
+        trace_sampling_ratio: 1.5,
+        ..DbConfig::default()
+    };
+    let dir = TempDir::new().unwrap();
+    assert!(matches!(
+        Db::open(dir.path(), config).unwrap_err(),
+        DbError::InvalidConfig(_)
+    ));
+}
+
+/// Records the `op` and counter fields of every slow-operation event.
+#[derive(Clone, Default)]
+struct SlowOpRecorder(Arc<std::sync::Mutex<Vec<SlowOpEvent>>>);
+
+/// Fields of one `aeternusdb::slow_op` event.
+#[derive(Debug, Default)]
+struct SlowOpEvent {
+    op: String,
+    fields: std::collections::HashMap<&'static str, u64>,
+}
+
+impl tracing::field::Visit for SlowOpEvent {
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        if field.name() == "op" {
+            self.op = value.to_string();
+        }
+    }
+
+    fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+        self.fields.insert(field.name(), value);
+    }
+
+    fn record_debug(&mut self, _field: &tracing::field::Field, _value: &dyn std::fmt::Debug) {}
+}
+
+impl SlowOpRecorder {
+    /// Returns the recorded events for `op`.
+    fn events(&self, op: &str) -> Vec<std::collections::HashMap<&'static str, u64>> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| event.op == op)
+            .map(|event| event.fields.clone())
+            .collect()
+    }
+}
+
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for SlowOpRecorder {
+    fn on_event(
+        &self,
+        event: &tracing::Event<'_>,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        if event.metadata().target() != "aeternusdb::slow_op" {
+            return;
+        }
+        let mut recorded = SlowOpEvent::default();
+        event.record(&mut recorded);
+        self.0.lock().unwrap().push(recorded);
+    }
+}
+
+/// # Scenario
+/// `slow_op_threshold` logs operations at least that slow.
+///
+/// # Starting environment
+/// A subscriber that records slow-operation events, installed for the
+/// current thread.
+///
+/// # Actions
+/// 1. With a zero threshold: bulk-load 100 keys so the flush and
+///    compaction run on this thread, then get a key, scan the range, and
+///    delete a range and a suffix of it.
+/// 2. With a one-hour threshold: repeat the puts, gets, and scan.
+///
+/// # Expected behavior
+/// With the zero threshold every put, flush, compaction, get, scan, and
+/// range delete is logged; the get reports the SSTable it probed and the block it
+/// read, and each put its WAL sync time. With the one-hour threshold
+/// nothing is logged.
+#[test]
+fn slow_op_threshold_logs_slow_operations() {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let recorder = SlowOpRecorder::default();
+    let subscriber = tracing_subscriber::registry().with(recorder.clone());
+    tracing::subscriber::with_default(subscriber, || {
+        let dir = TempDir::new().unwrap();
+        let config = DbConfig {
+            slow_op_threshold: Some(Duration::ZERO),
+            ..DbConfig::default()
+        };
+        let db = Db::open(dir.path(), config).unwrap();
+        db.enter_bulk_load().unwrap();
+        for i in 0..100u32 {
+            db.put(format!("so_{i:04}").as_bytes(), b"v").unwrap();
+        }
+        db.exit_bulk_load().unwrap();
+        assert_eq!(db.get(b"so_0042").unwrap(), Some(b"v".to_vec()));
+        assert_eq!(db.scan(b"so_", b"so_\xff").unwrap().len(), 100);
+        db.delete_range(b"so_0090", b"so_0095").unwrap();
+        db.delete_range_from(b"so_0098").unwrap();
+        db.close().unwrap();
+    });
+
+    let puts = recorder.events("put");
+    assert_eq!(puts.len(), 100);
+    assert!(puts.iter().all(|put| put.contains_key("wal_sync_us")));
+    assert!(!recorder.events("flush").is_empty());
+    assert!(!recorder.events("compaction").is_empty());
+    let gets = recorder.events("get");
+    assert_eq!(gets.len(), 1);
+    assert_eq!(gets[0]["tables_probed"], 1);
+    assert!(gets[0]["blocks_read"] >= 1);
+    assert_eq!(recorder.events("scan").len(), 1);
+    assert_eq!(recorder.events("delete_range").len(), 2);
+
+    let recorder = SlowOpRecorder::default();
+    let subscriber = tracing_subscriber::registry().with(recorder.clone());
+    tracing::subscriber::with_default(subscriber, || {
+        let dir = TempDir::new().unwrap();
+        let config = DbConfig {
+            slow_op_threshold: Some(Duration::from_secs(3600)),
+            ..DbConfig::default()
+        };
+        let db = Db::open(dir.path(), config).unwrap();
+        for i in 0..100u32 {
+            db.put(format!("so_{i:04}").as_bytes(), b"v").unwrap();
+        }
+        db.get(b"so_0042").unwrap();
+        db.scan(b"so_", b"so_\xff").unwrap();
+        db.close().unwrap();
+    });
+    assert!(recorder.0.lock().unwrap().is_empty());
+}
+
+/// Records every compaction tuning decision.
+#[derive(Default)]
+struct TuningRecorder {
+    events: std::sync::Mutex<Vec<CompactionTuning>>,
+}
+
+impl EventListener for TuningRecorder {
+    fn on_compaction_tuned(&self, event: &CompactionTuning) {
+        self.events.lock().unwrap().push(event.clone());
+    }
+}
+
+/// # Scenario
+/// Adaptive compaction reacts to read amplification and tells listeners.
+///
+/// # Starting environment
+/// 1 KiB write buffer, `min_compaction_threshold: 32` so SSTables pile
+/// up; adaptive compaction sampling every second with a read target of
+/// one probe per get.
+///
+/// # Actions
+/// 1. Write 600 keys, producing many SSTables.
+/// 2. Look up absent keys until the listener reports a change.
+///
+/// # Expected behavior
+/// The listener receives a `ReadAmplification` change lowering
+/// `min_compaction_threshold`, and `compaction_thresholds` reports the
+/// lowered value.
+#[test]
+fn adaptive_compaction_notifies_listener() {
+    let dir = TempDir::new().unwrap();
+    let recorder = Arc::new(TuningRecorder::default());
+    let config = DbConfig {
+        min_compaction_threshold: 32,
+        max_compaction_threshold: 64,
+        adaptive_compaction: Some(AdaptiveCompaction {
+            interval_seconds: 1,
+            target_read_amplification: 1.0,
+            ..AdaptiveCompaction::default()
+        }),
+        event_listeners: vec![recorder.clone() as Arc<dyn EventListener>],
+        ..small_buffer_config()
+    };
+    let db = Db::open(dir.path(), config).unwrap();
+    for i in 0..600u32 {
+        db.put(format!("ac_{i:04}").as_bytes(), b"value").unwrap();
+    }
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while recorder.events.lock().unwrap().is_empty() {
+        assert!(std::time::Instant::now() < deadline, "no tuning event");
+        for i in 0..100u32 {
+            db.get(format!("ac_{i:04}x").as_bytes()).unwrap();
+        }
+        thread::sleep(std::time::Duration::from_millis(100));
+    }
+
+    let event = recorder.events.lock().unwrap()[0].clone();
+    assert_eq!(event.reason, TuningReason::ReadAmplification);
+    assert_eq!(event.previous.min_threshold, 32);
+    assert!(event.current.min_threshold < 32);
+    assert!(db.compaction_thresholds().unwrap()[0].min_threshold < 32);
+    db.close().unwrap();
+}
+
+/// Records every read fan-out report.
+#[derive(Default)]
+struct FanoutRecorder {
+    events: std::sync::Mutex<Vec<ReadFanout>>,
+}
+
+impl EventListener for FanoutRecorder {
+    fn on_read_fanout_exceeded(&self, event: &ReadFanout) {
+        self.events.lock().unwrap().push(event.clone());
+    }
+}
+
+/// # Scenario
+/// Reads over a deep SSTable stack are counted and reported to
+/// listeners.
+///
+/// # Starting environment
+/// 1 KiB write buffer, `min_compaction_threshold: 32` so SSTables pile
+/// up; `read_fanout_limit: 2`.
+///
+/// # Actions
+/// 1. Write 600 keys, producing many SSTables.
+/// 2. Look up absent keys until the listener reports.
+///
+/// # Expected behavior
+/// The listener receives a report for partition 0 whose widest read
+/// exceeds the limit, and `read_stats` counts the reads.
+#[test]
+fn read_fanout_notifies_listener() {
+    let dir = TempDir::new().unwrap();
+    let recorder = Arc::new(FanoutRecorder::default());
+    let config = DbConfig {
+        min_compaction_threshold: 32,
+        max_compaction_threshold: 64,
+        read_fanout_limit: 2,
+        event_listeners: vec![recorder.clone() as Arc<dyn EventListener>],
+        ..small_buffer_config()
+    };
+    let db = Db::open(dir.path(), config).unwrap();
+    for i in 0..600u32 {
+        db.put(format!("fo_{i:04}").as_bytes(), b"value").unwrap();
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while recorder.events.lock().unwrap().is_empty() {
+        assert!(Instant::now() < deadline, "no fan-out event");
+        for i in 0..100u32 {
+            db.get(format!("fo_{i:04}x").as_bytes()).unwrap();
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    let event = recorder.events.lock().unwrap()[0].clone();
+    assert_eq!(event.partition, 0);
+    assert_eq!(event.limit, 2);
+    assert!(event.reads > 0 && event.widest > 2);
+    assert!(db.read_stats().unwrap().fanout_exceeded >= event.reads);
+    db.close().unwrap();
+}
+
+/// Sums every write activity report.
+#[derive(Default)]
+struct WriteRecorder {
+    total: std::sync::Mutex<WriteStats>,
+}
+
+impl EventListener for WriteRecorder {
+    fn on_write_activity(&self, event: &WriteActivity) {
+        *self.total.lock().unwrap() += event.stats;
+    }
+}
+
+/// # Scenario
+/// WAL appends and SSTable builds are counted and reported to
+/// listeners.
+///
+/// # Starting environment
+/// 1 KiB write buffer, so writes rotate the WAL and flush SSTables.
+///
+/// # Actions
+/// 1. Write 300 keys.
+/// 2. Wait until the listener has been told of every write and of a
+///    flushed SSTable.
+///
+/// # Expected behavior
+/// `write_stats` counts 300 WAL records, rotations, and the flushed
+/// SSTables; the WAL reports received add up to the same counters.
+#[test]
+fn write_activity_notifies_listener() {
+    let dir = TempDir::new().unwrap();
+    let recorder = Arc::new(WriteRecorder::default());
+    let config = DbConfig {
+        event_listeners: vec![recorder.clone() as Arc<dyn EventListener>],
+        ..small_buffer_config()
+    };
+    let db = Db::open(dir.path(), config).unwrap();
+    for i in 0..300u32 {
+        db.put(format!("wa_{i:04}").as_bytes(), b"value").unwrap();
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while recorder.total.lock().unwrap().sstables.sstables == 0
+        || recorder.total.lock().unwrap().wal.records_appended < 300
+    {
+        assert!(Instant::now() < deadline, "no write activity event");
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    let stats = db.write_stats().unwrap();
+    assert_eq!(stats.wal.records_appended, 300);
+    assert!(stats.wal.rotations > 0);
+    assert!(stats.sstables.file_bytes > 0);
+    let reported = *recorder.total.lock().unwrap();
+    assert_eq!(reported.wal, stats.wal);
+    assert!(reported.sstables.sstables <= stats.sstables.sstables);
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// `clone_to` refuses a target directory that already holds files.
+///
+/// # Actions
+/// 1. Create a file inside the target directory.
+/// 2. `clone_to` that directory.
+///
+/// # Expected behavior
+/// Returns `DbError::Engine` and leaves the existing file untouched.
+#[test]
+fn clone_to_rejects_non_empty_target() {
+    let dir = TempDir::new().unwrap();
+    let target = TempDir::new().unwrap();
+    std::fs::write(target.path().join("keep.txt"), b"keep").unwrap();
+
+    let db = Db::open(dir.path(), DbConfig::default()).unwrap();
+    db.put(b"k", b"v").unwrap();
+
+    assert!(matches!(
+        db.clone_to(target.path()),
+        Err(DbError::Engine(_))
+    ));
+    assert_eq!(
+        std::fs::read(target.path().join("keep.txt")).unwrap(),
+        b"keep"
+    );
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// A follower kept in step with SSTable deltas serves the leader's data.
+///
+/// # Starting environment
+/// Leader with a 1 KiB write buffer; empty follower with default config.
+///
+/// # Actions
+/// 1. Write 300 keys to the leader; export from the follower's version
+///    and apply.
+/// 2. Overwrite and delete keys on the leader; ship the next delta.
+///
+/// # Expected behavior
+/// After each apply the follower's full scan equals the leader's; a delta
+/// applied twice is rejected with `DbError::InvalidArgument`.
+#[test]
+fn replication_deltas_keep_follower_in_step() {
+    let leader_dir = TempDir::new().unwrap();
+    let follower_dir = TempDir::new().unwrap();
+    let leader = Db::open(leader_dir.path(), small_buffer_config()).unwrap();
+    let follower = Db::open(follower_dir.path(), DbConfig::default()).unwrap();
+
+    for i in 0..300u32 {
+        let key = format!("key_{i:04}");
+        leader.put(key.as_bytes(), b"v1").unwrap();
+    }
+    let delta = leader
+        .export_delta(follower.replication_version().unwrap())
+        .unwrap();
+    follower.apply_delta(&delta).unwrap();
+    assert_eq!(
+        follower.scan(b"key_", b"key~").unwrap(),
+        leader.scan(b"key_", b"key~").unwrap()
+    );
+
+    for i in 0..50u32 {
+        let key = format!("key_{i:04}");
+        leader.put(key.as_bytes(), b"v2").unwrap();
+    }
+    leader.delete_range(b"key_0100", b"key_0200").unwrap();
+    let delta = leader
+        .export_delta(follower.replication_version().unwrap())
+        .unwrap();
+    follower.apply_delta(&delta).unwrap();
+    assert_eq!(
+        follower.scan(b"key_", b"key~").unwrap(),
+        leader.scan(b"key_", b"key~").unwrap()
+    );
+    assert_eq!(follower.get(b"key_0000").unwrap(), Some(b"v2".to_vec()));
+    assert_eq!(follower.get(b"key_0150").unwrap(), None);
+
+    assert!(matches!(
+        follower.apply_delta(&delta),
+        Err(DbError::InvalidArgument(_))
+    ));
+    follower.close().unwrap();
+    leader.close().unwrap();
+}
+
+/// # Scenario
+/// SSTables written with a whole-file digest carry it to a follower, and
+/// a shipped SSTable damaged in transit is refused.
+///
+/// # Starting environment
+/// Leader with a 1 KiB write buffer and `sstable_file_digest` set to
+/// XXH3; empty follower with default config.
+///
+/// # Actions
+/// 1. Write 300 keys to the leader and export a delta.
+/// 2. Write every shipped SSTable to a file and verify its digest.
+/// 3. Flip one byte of the first shipped SSTable; apply the damaged delta.
+/// 4. Apply the intact delta.
+///
+/// # Expected behavior
+/// - Every shipped SSTable verifies with an XXH3 digest.
+/// - The damaged delta is rejected with `DbError::InvalidArgument` and
+///   leaves the follower empty.
+/// - The intact delta applies, and the follower serves the leader's data.
+#[test]
+fn replication_rejects_sstable_failing_file_digest() {
+    let leader_dir = TempDir::new().unwrap();
+    let follower_dir = TempDir::new().unwrap();
+    let leader = Db::open(
+        leader_dir.path(),
+        DbConfig {
+            sstable_file_digest: Some(SstFileDigest::Xxh3),
+            ..small_buffer_config()
+        },
+    )
+    .unwrap();
+    let follower = Db::open(follower_dir.path(), DbConfig::default()).unwrap();
+
+    for i in 0..300u32 {
+        let key = format!("key_{i:04}");
+        leader.put(key.as_bytes(), b"v1").unwrap();
+    }
+    let delta = leader
+        .export_delta(follower.replication_version().unwrap())
+        .unwrap();
+    assert!(!delta.sstables.is_empty());
+
+    let scratch = TempDir::new().unwrap();
+    let copy = scratch.path().join("copy.sst");
+    for shipped in &delta.sstables {
+        std::fs::write(&copy, &shipped.data).unwrap();
+        assert_eq!(
+            sst::verify_file_digest(&copy).unwrap(),
+            Some(SstFileDigest::Xxh3)
+        );
+    }
+
+    let mut damaged = delta.clone();
+    damaged.sstables[0].data[100] ^= 0xFF;
+    assert!(matches!(
+        follower.apply_delta(&damaged),
+        Err(DbError::InvalidArgument(_))
+    ));
+    assert!(follower.scan(b"key_", b"key~").unwrap().is_empty());
+
+    follower.apply_delta(&delta).unwrap();
+    assert_eq!(
+        follower.scan(b"key_", b"key~").unwrap(),
+        leader.scan(b"key_", b"key~").unwrap()
+    );
+    follower.close().unwrap();
+    leader.close().unwrap();
+}
+
+/// # Scenario
+/// With a grace period, compacted-away SSTable files stay on disk until
+/// `purge_obsolete_files` deletes them.
+///
+/// # Starting environment
+/// Small write buffer, background compaction paused,
+/// `obsolete_file_grace_seconds` = 3600.
+///
+/// # Actions
+/// 1. Put 400 keys; major compact.
+/// 2. Purge obsolete files; purge again.
+///
+/// # Expected behavior
+/// - After step 1 the directory holds more SSTable files than the
+///   database lists.
+/// - The first purge deletes the extra files, leaving exactly the listed
+///   ones; the second finds nothing left. Every key still reads back.
+#[test]
+fn purge_obsolete_files_after_compaction() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(
+        dir.path(),
+        DbConfig {
+            obsolete_file_grace_seconds: 3600,
+            ..small_buffer_config()
+        },
+    )
+    .unwrap();
+    db.set_compaction_paused(true).unwrap();
+    for i in 0..400u32 {
+        db.put(format!("gc_{i:04}").as_bytes(), &[b'v'; 32])
+            .unwrap();
+    }
+    db.major_compact().unwrap();
+
+    let on_disk = || {
+        let mut files: Vec<_> = std::fs::read_dir(dir.path().join("sstables"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "sst"))
+            .collect();
+        files.sort();
+        files
+    };
+    let mut live: Vec<_> = db
+        .sstable_metadata()
+        .unwrap()
+        .into_iter()
+        .map(|m| m.path)
+        .collect();
+    live.sort();
+    assert!(on_disk().len() > live.len());
+
+    let report = db.purge_obsolete_files().unwrap();
+    assert!(report.files_deleted > 0);
+    assert_eq!(report.files_pending, 0);
+    assert_eq!(on_disk(), live);
+    assert_eq!(db.purge_obsolete_files().unwrap().files_deleted, 0);
+    assert_eq!(db.scan(b"gc_", b"gc_~").unwrap().len(), 400);
+    db.close().unwrap();
+}
+
+// ================================================================================================
+// Change notifications
+// ================================================================================================
+
+/// # Scenario
+/// A subscriber receives exactly the committed mutations touching its
+/// range, in commit order.
+///
+/// # Starting environment
+/// Database with `a` written before the subscription.
+///
+/// # Actions
+/// 1. Subscribe to `[b, d)`.
+/// 2. Put `b1`, `x`, `c1`; delete `b1`; range-delete `[a, b5)` and
+///    `[e, f)`; commit a batch putting `c2` and `z`.
+/// 3. Close the database.
+///
+/// # Expected behavior
+/// The receiver yields the puts of `b1` and `c1`, the delete of `b1`, the
+/// unclipped range delete `[a, b5)`, and the batch put of `c2`, in that
+/// order, then reports disconnection.
+#[test]
+fn subscribe_delivers_range_changes() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), DbConfig::default()).unwrap();
+    db.put(b"a", b"before").unwrap();
+
+    let changes = db.subscribe(b"b", b"d").unwrap();
+    db.put(b"b1", b"1").unwrap();
+    db.put(b"x", b"outside").unwrap();
+    db.put(b"c1", b"2").unwrap();
+    db.delete(b"b1").unwrap();
+    db.delete_range(b"a", b"b5").unwrap();
+    db.delete_range(b"e", b"f").unwrap();
+    let mut batch = WriteBatchWithIndex::new();
+    batch.put(b"c2", b"3");
+    batch.put(b"z", b"outside");
+    db.write(&batch).unwrap();
+    db.close().unwrap();
+
+    let events: Vec<ChangeEvent> = changes.iter().collect();
+    assert_eq!(
+        events,
+        vec![
+            ChangeEvent::Put {
+                key: b"b1".to_vec(),
+                value: b"1".to_vec()
+            },
+            ChangeEvent::Put {
+                key: b"c1".to_vec(),
+                value: b"2".to_vec()
+            },
+            ChangeEvent::Delete {
+                key: b"b1".to_vec()
+            },
+            ChangeEvent::DeleteRange {
+                start: b"a".to_vec(),
+                end: b"b5".to_vec()
+            },
+            ChangeEvent::Put {
+                key: b"c2".to_vec(),
+                value: b"3".to_vec()
+            },
+        ]
+    );
+}
+
+/// # Scenario
+/// Concurrent writers, across partitions, are delivered in commit order
+/// and dropped receivers stop receiving.
+///
+/// # Starting environment
+/// Database with `partitions: 4`.
+///
+/// # Actions
+/// 1. Subscribe twice to the whole key space; drop the second receiver.
+/// 2. Four threads each put 100 keys, overwriting a shared `counter` key
+///    after every write.
+/// 3. Read `counter` from the database.
+///
+/// # Expected behavior
+/// The receiver yields 800 puts; the last `counter` event matches the
+/// value stored in the database. Writes succeed after the second
+/// receiver is gone.
+#[test]
+fn subscribe_orders_concurrent_writes() {
+    let dir = TempDir::new().unwrap();
+    let config = DbConfig {
+        partitions: 4,
+        ..DbConfig::default()
+    };
+    let db = Arc::new(Db::open(dir.path(), config).unwrap());
+    let changes = db.subscribe(b"\x00", b"\xff").unwrap();
+    drop(db.subscribe(b"\x00", b"\xff").unwrap());
+
+    let handles: Vec<_> = (0..4u32)
+        .map(|t| {
+            let db = Arc::clone(&db);
+            thread::spawn(move || {
+                for i in 0..100u32 {
+                    let key = format!("t{t}_{i:04}");
+                    db.put(key.as_bytes(), b"v").unwrap();
+                    db.put(b"counter", key.as_bytes()).unwrap();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let events: Vec<ChangeEvent> = changes.try_iter().collect();
+    assert_eq!(events.len(), 800);
+    let last_counter = events
+        .iter()
+        .rev()
+        .find_map(|event| match event {
+            ChangeEvent::Put { key, value } if key == b"counter" => Some(value.clone()),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(db.get(b"counter").unwrap(), Some(last_counter));
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// Invalid subscription ranges are rejected.
+///
+/// # Starting environment
+/// Freshly opened database.
+///
+/// # Actions
+/// 1. Subscribe with an empty bound, with `start == end`, and after close.
+///
+/// # Expected behavior
+/// The first two return `DbError::InvalidArgument`; the last returns
+/// `DbError::Closed`.
+#[test]
+fn subscribe_rejects_invalid_range() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), DbConfig::default()).unwrap();
+
+    assert!(matches!(
+        db.subscribe(b"", b"z"),
+        Err(DbError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        db.subscribe(b"k", b"k"),
+        Err(DbError::InvalidArgument(_))
+    ));
+    db.close().unwrap();
+    assert!(matches!(db.subscribe(b"a", b"z"), Err(DbError::Closed)));
+}
+
+// ================================================================================================
+// Introspection
+// ================================================================================================
+
+/// # Scenario
+/// `sstable_metadata()` lists every SSTable with consistent bounds.
+///
+/// # Starting environment
+/// Database with a 1 KiB write buffer.
+///
+/// # Actions
+/// 1. Write 200 keys, close, reopen (all frozen data is flushed).
+/// 2. Call `sstable_metadata()`.
+/// 3. Close the database and call it again.
+///
+/// # Expected behavior
+/// At least one entry is returned; each entry's path exists and its key
+/// bounds fall inside the written key space. After close the call fails
+/// with `DbError::Closed`.
+#[test]
+fn sstable_metadata_lists_tables() {
+    let dir = TempDir::new().unwrap();
+    {
+        let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+        for i in 0..200u32 {
+            let key = format!("meta_{:04}", i);
+            db.put(key.as_bytes(), b"some_value_padding").unwrap();
+        }
+        db.close().unwrap();
+    }
+
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    let meta = db.sstable_metadata().unwrap();
+    assert!(!meta.is_empty());
+    for m in &meta {
+        assert!(m.path.exists());
+        assert!(m.min_key.as_slice() >= b"meta_0000".as_slice());
+        assert!(m.max_key.as_slice() <= b"meta_0199".as_slice());
+        assert!(m.file_size > 0);
+    }
+
+    db.close().unwrap();
+    assert!(matches!(db.sstable_metadata(), Err(DbError::Closed)));
+}
+
+/// # Scenario
+/// `debug_report()` describes every partition's SSTables.
+///
+/// # Starting environment
+/// Database with a 1 KiB write buffer and two partitions.
+///
+/// # Actions
+/// 1. Write 400 keys, close, reopen.
+/// 2. Call `debug_report()` and render it with `to_dot()`.
+///
+/// # Expected behavior
+/// One partition report per partition; together they list exactly the
+/// SSTables of `sstable_metadata()`, and the DOT output names each
+/// partition cluster.
+#[test]
+fn debug_report_describes_partitions() {
+    let dir = TempDir::new().unwrap();
+    let config = || DbConfig {
+        partitions: 2,
+        ..small_buffer_config()
+    };
+    {
+        let db = Db::open(dir.path(), config()).unwrap();
+        for i in 0..400u32 {
+            let key = format!("dbg_{:04}", i);
+            db.put(key.as_bytes(), b"some_value_padding").unwrap();
+        }
+        db.close().unwrap();
+    }
+
+    let db = Db::open(dir.path(), config()).unwrap();
+    let report = db.debug_report().unwrap();
+    assert_eq!(report.partitions.len(), 2);
+    let listed: usize = report.partitions.iter().map(|p| p.sstables.len()).sum();
+    assert!(listed > 0);
+    assert_eq!(listed, db.sstable_metadata().unwrap().len());
+
+    let dot = report.to_dot();
+    assert!(dot.contains("subgraph cluster_p0 {"));
+    assert!(dot.contains("subgraph cluster_p1 {"));
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// `recovery_report()` describes what `open` recovered.
+///
+/// # Starting environment
+/// Database with a 1 KiB write buffer.
+///
+/// # Actions
+/// 1. Open an empty directory and read the report.
+/// 2. Write 200 keys, close, reopen, and read the report again.
+///
+/// # Expected behavior
+/// The fresh database replays one empty WAL and opens no SSTables. The
+/// reopened one opens SSTables and drops nothing; the report stays
+/// readable after close.
+#[test]
+fn recovery_report_describes_open() {
+    let dir = TempDir::new().unwrap();
+    {
+        let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+        let report = db.recovery_report();
+        assert_eq!(report.wal_segments_replayed, 1);
+        assert_eq!(report.records_recovered, 0);
+        assert_eq!(report.sstables_opened, 0);
+        for i in 0..200u32 {
+            let key = format!("rr_{:04}", i);
+            db.put(key.as_bytes(), b"some_value_padding").unwrap();
+        }
+        db.close().unwrap();
+    }
+
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    db.close().unwrap();
+    let report = db.recovery_report();
+    assert!(report.sstables_opened > 0);
+    assert!(report.wal_segments_replayed >= 1);
+    assert!(report.recovered_lsn >= 200);
+    assert_eq!(report.wal_tails_dropped, 0);
+    assert_eq!(report.wal_bytes_dropped, 0);
+    assert!(report.orphan_files_removed.is_empty());
+}
+
+/// # Scenario
+/// `check_consistency` passes on a healthy database, and an SSTable
+/// removed behind its back makes the next open fail with a report.
+///
+/// # Starting environment
+/// Database with several SSTables.
+///
+/// # Actions
+/// 1. Call `check_consistency`.
+/// 2. Close, delete one SSTable file, and reopen.
+///
+/// # Expected behavior
+/// The check finds no issue; the reopen returns
+/// `DbError::Inconsistent` naming the missing SSTable.
+#[test]
+fn check_consistency_and_inconsistent_open() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    for i in 0..200u32 {
+        db.put(format!("cc_{i:04}").as_bytes(), b"value").unwrap();
+    }
+    db.close().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+
+    let report = db.check_consistency().unwrap();
+    assert!(report.is_consistent(), "{report}");
+    let sstables = db.sstable_metadata().unwrap();
+    assert_eq!(report.sstables_checked, sstables.len());
+    db.close().unwrap();
+
+    std::fs::remove_file(&sstables[0].path).unwrap();
+    match Db::open(dir.path(), small_buffer_config()) {
+        Err(DbError::Inconsistent(report)) => {
+            assert_eq!(
+                report.issues,
+                vec![ConsistencyIssue::MissingSSTable {
+                    id: sstables[0].id,
+                    path: sstables[0].path.clone(),
+                }]
+            );
+        }
+        other => panic!("expected DbError::Inconsistent, got {other:?}"),
+    }
+}
+
+/// # Scenario
+/// `Db::repair` makes a database with a damaged SSTable openable again.
+///
+/// # Starting environment
+/// Database with several SSTables.
+///
+/// # Actions
+/// 1. Call `Db::repair` while the database is open.
+/// 2. Close, damage the footer of one SSTable, and reopen.
+/// 3. Repair, reopen, and read every key.
+///
+/// # Expected behavior
+/// The repair of an open database fails with `DbError::LeaseHeld`. The
+/// damaged SSTable fails the reopen; repair rebuilds it from its intact
+/// data blocks, and afterwards every key reads back.
+#[test]
+fn repair_damaged_database() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    for i in 0..200u32 {
+        db.put(format!("rp_{i:04}").as_bytes(), b"value").unwrap();
+    }
+    db.close().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    assert!(matches!(Db::repair(dir.path()), Err(DbError::LeaseHeld(_))));
+    let sstables = db.sstable_metadata().unwrap();
+    db.close().unwrap();
+    drop(db);
+
+    let mut bytes = std::fs::read(&sstables[0].path).unwrap();
+    let len = bytes.len();
+    for b in &mut bytes[len - 12..len - 4] {
+        *b ^= 0xFF;
+    }
+    std::fs::write(&sstables[0].path, &bytes).unwrap();
+    assert!(Db::open(dir.path(), small_buffer_config()).is_err());
+
+    let report = Db::repair(dir.path()).unwrap();
+    assert_eq!(report.sstables_repaired, 1);
+    assert_eq!(report.sstables_kept, sstables.len() - 1);
+    assert!(report.sstables_lost.is_empty());
+
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    assert!(db.check_consistency().unwrap().is_consistent());
+    for i in 0..200u32 {
+        let key = format!("rp_{i:04}");
+        assert_eq!(db.get(key.as_bytes()).unwrap(), Some(b"value".to_vec()));
+    }
+}
+
+/// # Scenario
+/// `sstable_open_checks` decides whether a damaged data block fails the
+/// open or only the reads that reach it.
+///
+/// # Starting environment
+/// Database with several SSTables.
+///
+/// # Actions
+/// 1. Close and damage the first data block of one SSTable.
+/// 2. Reopen with `OpenChecks::FooterOnly` and `OpenChecks::Metadata`,
+///    checking consistency each time.
+/// 3. Reopen with `OpenChecks::FullBlockChecksums`.
+///
+/// # Expected behavior
+/// The first two opens succeed and find no issue. The last returns
+/// `DbError::Inconsistent` naming the damaged SSTable as unreadable.
+#[test]
+fn sstable_open_checks_levels() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    for i in 0..200u32 {
+        db.put(format!("oc_{i:04}").as_bytes(), b"value").unwrap();
+    }
+    db.close().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    let sstables = db.sstable_metadata().unwrap();
+    db.close().unwrap();
+    drop(db);
+
+    // The first data block follows the 12-byte header and its length.
+    let mut bytes = std::fs::read(&sstables[0].path).unwrap();
+    for b in &mut bytes[20..28] {
+        *b ^= 0xFF;
+    }
+    std::fs::write(&sstables[0].path, &bytes).unwrap();
+
+    let config = |checks| DbConfig {
+        sstable_open_checks: checks,
+        ..small_buffer_config()
+    };
+    for checks in [OpenChecks::FooterOnly, OpenChecks::Metadata] {
+        let db = Db::open(dir.path(), config(checks)).unwrap();
+        assert!(
+            db.check_consistency().unwrap().is_consistent(),
+            "{checks:?}"
+        );
+        db.close().unwrap();
+    }
+
+    match Db::open(dir.path(), config(OpenChecks::FullBlockChecksums)) {
+        Err(DbError::Inconsistent(report)) => {
+            assert!(matches!(
+                report.issues.as_slice(),
+                [ConsistencyIssue::UnreadableSSTable { id, .. }] if *id == sstables[0].id
+            ));
+        }
+        other => panic!("expected DbError::Inconsistent, got {other:?}"),
+    }
+}
+
+/// # Scenario
+/// With `max_mmap_bytes` set, SSTables past the limit are read with
+/// `pread` and still serve every read.
+///
+/// # Starting environment
+/// Database with a 1 KiB write buffer and a 4 KiB mmap limit.
+///
+/// # Actions
+/// 1. Write 300 keys, close, and reopen.
+/// 2. List the SSTables; get and scan every key.
+///
+/// # Expected behavior
+/// The mapped tables total at most 4 KiB and some tables are not mapped;
+/// every key reads back.
+#[test]
+fn max_mmap_bytes_limits_mapped_sstables() {
+    let dir = TempDir::new().unwrap();
+    let config = || DbConfig {
+        max_mmap_bytes: 4096,
+        ..small_buffer_config()
+    };
+    let db = Db::open(dir.path(), config()).unwrap();
+    for i in 0..300u32 {
+        db.put(format!("mm_{i:04}").as_bytes(), b"some_value_padding")
+            .unwrap();
+    }
+    db.close().unwrap();
+
+    let db = Db::open(dir.path(), config()).unwrap();
+    let sstables = db.sstable_metadata().unwrap();
+    let mapped: u64 = sstables
+        .iter()
+        .filter(|m| m.mapped)
+        .map(|m| m.file_size)
+        .sum();
+    assert!(mapped <= 4096);
+    assert!(sstables.iter().any(|m| !m.mapped));
+
+    for i in 0..300u32 {
+        let key = format!("mm_{i:04}");
+        assert_eq!(
+            db.get(key.as_bytes()).unwrap(),
+            Some(b"some_value_padding".to_vec())
+        );
+    }
+    assert_eq!(db.scan(b"mm_", b"mm_~").unwrap().len(), 300);
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// `read_stats()` accounts for every bloom filter check of a lookup.
+///
+/// # Starting environment
+/// Database with a 1 KiB write buffer.
+///
+/// # Actions
+/// 1. Write 200 keys, close, reopen (all frozen data is flushed).
+/// 2. Get 100 keys that were never written; call `read_stats()`.
+/// 3. Close the database and call it again.
+///
+/// # Expected behavior
+/// 100 gets are counted, each bloom check is either a negative or a
+/// false positive, and only false positives read a block. After close
+/// the call fails with `DbError::Closed`.
+#[test]
+fn read_stats_track_bloom_checks() {
+    let dir = TempDir::new().unwrap();
+    {
+        let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+        for i in 0..200u32 {
+            let key = format!("rs_{:04}", i);
+            db.put(key.as_bytes(), b"some_value_padding").unwrap();
+        }
+        db.close().unwrap();
+    }
+
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    assert_eq!(db.read_stats().unwrap(), ReadStats::default());
+    for i in 0..100u32 {
+        let key = format!("absent_{:04}", i);
+        assert!(db.get(key.as_bytes()).unwrap().is_none());
+    }
+
+    let stats = db.read_stats().unwrap();
+    assert_eq!(stats.gets, 100);
+    assert!(stats.bloom_checks > 0);
+    assert_eq!(
+        stats.bloom_negatives + stats.bloom_false_positives,
+        stats.bloom_checks
+    );
+    assert_eq!(stats.block_reads, stats.bloom_false_positives);
+
+    db.close().unwrap();
+    assert!(matches!(db.read_stats(), Err(DbError::Closed)));
+}
+
+/// # Scenario
+/// `estimate_num_keys()` approximates the live keys of a partitioned
+/// database from metadata.
+///
+/// # Starting environment
+/// Database with a 1 KiB write buffer and 4 partitions.
+///
+/// # Actions
+/// 1. Call `estimate_num_keys()` on the empty database.
+/// 2. Write 400 distinct keys; call it again.
+/// 3. Close the database and call it again.
+///
+/// # Expected behavior
+/// The empty database estimates 0. With no overwrites or deletes the
+/// overlap discount can only undercount, so the estimate is at most 400
+/// and at least half of it. After close the call fails with
+/// `DbError::Closed`.
+#[test]
+fn estimate_num_keys_tracks_writes() {
+    let dir = TempDir::new().unwrap();
+    let config = DbConfig {
+        partitions: 4,
+        ..small_buffer_config()
+    };
+    let db = Db::open(dir.path(), config).unwrap();
+    assert_eq!(db.estimate_num_keys().unwrap(), 0);
+
+    for i in 0..400u32 {
+        let key = format!("ek_{:04}", i);
+        db.put(key.as_bytes(), b"some_value_padding").unwrap();
+    }
+    let estimate = db.estimate_num_keys().unwrap();
+    assert!((200..=400).contains(&estimate), "estimate {estimate}");
+
+    db.close().unwrap();
+    assert!(matches!(db.estimate_num_keys(), Err(DbError::Closed)));
+}
+
+/// # Scenario
+/// `tenant_usage()` accounts the keys under configured prefixes to their
+/// tenants from SSTable properties.
+///
+/// # Starting environment
+/// Database with a 1 KiB write buffer, a mock clock, and prefixes
+/// `acme/` and `acme-eu/` of tenant `acme` and `beta/` of tenant `beta`.
+///
+/// # Actions
+/// 1. Write 100 keys under each of `acme/`, `acme-eu/`, `beta/`, and the
+///    unassigned `other/`, plus filler keys; turn on read-only mode.
+/// 2. Advance the clock 10 s; call `tenant_usage()`.
+/// 3. Close, reopen with prefix `gamma/` of tenant `gamma` added; call it
+///    again.
+/// 4. Close the database and call it again.
+///
+/// # Expected behavior
+/// Both tenants report 25 bytes per flushed key, equal to the bytes
+/// written, at a tenth of that per second. After reopen every key is
+/// counted and nothing is written yet; `gamma` holds nothing but is not
+/// exact, since the SSTables span `gamma/` without a tally for it. After
+/// close the call fails with `DbError::Closed`.
+#[test]
+fn tenant_usage_accounts_prefixes() {
+    let dir = TempDir::new().unwrap();
+    let clock = Arc::new(MockClock::new(1_700_000_000_000_000_000));
+    let prefixes = vec![
+        TenantPrefix::new("acme/", "acme"),
+        TenantPrefix::new("acme-eu/", "acme"),
+        TenantPrefix::new("beta/", "beta"),
+    ];
+    let config = DbConfig {
+        tenant_prefixes: prefixes.clone(),
+        clock: clock.clone(),
+        ..small_buffer_config()
+    };
+    let db = Db::open(dir.path(), config).unwrap();
+
+    // Every key and its value add up to 25 bytes.
+    for i in 0..100u32 {
+        db.put(format!("acme/{i:04}").as_bytes(), &[b'a'; 16])
+            .unwrap();
+        db.put(format!("acme-eu/{i:04}").as_bytes(), &[b'a'; 13])
+            .unwrap();
+        db.put(format!("beta/{i:04}").as_bytes(), &[b'b'; 16])
+            .unwrap();
+        db.put(format!("other/{i:04}").as_bytes(), &[b'o'; 16])
+            .unwrap();
+    }
+    push_out_of_memtable(&db);
+    db.set_read_only(true).unwrap();
+    clock.advance(Duration::from_secs(10));
+
+    let usage = db.tenant_usage().unwrap();
+    assert_eq!(usage.len(), 2);
+    assert_eq!(
+        (usage[0].tenant.as_str(), usage[1].tenant.as_str()),
+        ("acme", "beta")
+    );
+    for tenant in &usage {
+        assert!(tenant.exact);
+        assert!(tenant.live_entries > 0);
+        assert_eq!(tenant.live_bytes, tenant.live_entries * 25);
+        assert_eq!(tenant.bytes_written, tenant.live_bytes);
+        assert_eq!(
+            tenant.write_bytes_per_sec,
+            tenant.bytes_written as f64 / 10.0
+        );
+    }
+    db.close().unwrap();
+
+    let mut prefixes = prefixes;
+    prefixes.push(TenantPrefix::new("gamma/", "gamma"));
+    let config = DbConfig {
+        tenant_prefixes: prefixes,
+        clock: clock.clone(),
+        ..small_buffer_config()
+    };
+    let db = Db::open(dir.path(), config).unwrap();
+    let usage = db.tenant_usage().unwrap();
+    assert_eq!(usage.len(), 3);
+    assert_eq!((usage[0].live_entries, usage[0].live_bytes), (200, 5000));
+    assert_eq!((usage[1].live_entries, usage[1].live_bytes), (100, 2500));
+    assert!(usage[0].exact && usage[1].exact);
+    assert!(usage.iter().all(|tenant| tenant.bytes_written == 0));
+    assert_eq!(usage[2].tenant, "gamma");
+    assert_eq!(usage[2].live_bytes, 0);
+    assert!(!usage[2].exact);
+
+    db.close().unwrap();
+    assert!(matches!(db.tenant_usage(), Err(DbError::Closed)));
+}
+
+/// # Scenario
+/// The row cache answers repeated lookups and sees writes.
+///
+/// # Starting environment
+/// Two-partition database with a 1 KiB write buffer and a 64 KiB row
+/// cache.
+///
+/// # Actions
+/// 1. Write 200 keys, close, reopen.
+/// 2. Get `rc_0000` twice.
+/// 3. Overwrite it and get it again.
+///
+/// # Expected behavior
+/// The second get is a row cache hit; the get after the overwrite
+/// returns the new value.
+#[test]
+fn row_cache_hits_and_invalidation() {
+    let dir = TempDir::new().unwrap();
+    let config = || DbConfig {
+        row_cache_size: 64 * 1024,
+        partitions: 2,
+        ..small_buffer_config()
+    };
+    {
+        let db = Db::open(dir.path(), config()).unwrap();
+        for i in 0..200u32 {
+            let key = format!("rc_{:04}", i);
+            db.put(key.as_bytes(), b"some_value_padding").unwrap();
+        }
+        db.close().unwrap();
+    }
+
+    let db = Db::open(dir.path(), config()).unwrap();
+    for _ in 0..2 {
+        assert_eq!(
+            db.get(b"rc_0000").unwrap(),
+            Some(b"some_value_padding".to_vec())
+        );
+    }
+    assert_eq!(db.read_stats().unwrap().row_cache_hits, 1);
+
+    db.put(b"rc_0000", b"updated").unwrap();
+    assert_eq!(db.get(b"rc_0000").unwrap(), Some(b"updated".to_vec()));
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// Warm-up reads SSTable data for the requested ranges.
+///
+/// # Starting environment
+/// Two-partition database with a 1 KiB write buffer and `wu_0000..wu_0199`
+/// flushed to SSTables.
+///
+/// # Actions
+/// 1. Reopen with `warm_up_sstables: 2`.
+/// 2. Warm the whole `wu_` range, a range past every key, and an empty
+///    range; then pass an empty key.
+///
+/// # Expected behavior
+/// The whole range reads some bytes, the other ranges read none, the
+/// empty key is rejected, and reads return the stored values.
+#[test]
+fn warm_up_ranges() {
+    let dir = TempDir::new().unwrap();
+    let config = || DbConfig {
+        partitions: 2,
+        warm_up_sstables: 2,
+        ..small_buffer_config()
+    };
+    {
+        let db = Db::open(dir.path(), config()).unwrap();
+        for i in 0..200u32 {
+            let key = format!("wu_{:04}", i);
+            db.put(key.as_bytes(), b"some_value_padding").unwrap();
+        }
+        push_out_of_memtable(&db);
+        db.close().unwrap();
+    }
+
+    let db = Db::open(dir.path(), config()).unwrap();
+    let all = (b"wu_".as_slice(), b"wu_~".as_slice());
+    let past_end = (b"\xf0".as_slice(), b"\xff".as_slice());
+    let empty = (b"wu_0050".as_slice(), b"wu_0050".as_slice());
+    assert!(db.warm_up(&[all]).unwrap() > 0);
+    assert_eq!(db.warm_up(&[past_end, empty]).unwrap(), 0);
+    assert!(matches!(
+        db.warm_up(&[(b"".as_slice(), b"wu_".as_slice())]),
+        Err(DbError::InvalidArgument(_))
+    ));
+    assert_eq!(
+        db.get(b"wu_0123").unwrap(),
+        Some(b"some_value_padding".to_vec())
+    );
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// Only one handle may have a database open for writing.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. Open a database, then open the same directory again.
+/// 2. Close the first handle and open the directory again.
+/// 3. Close, plant a `LEASE` file last renewed in 1970, and open again.
+///
+/// # Expected behavior
+/// The second open fails with `DbError::LeaseHeld` naming this process;
+/// after `close` the directory opens normally, and an abandoned lease is
+/// taken over.
+#[test]
+fn write_lease_is_exclusive() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), DbConfig::default()).unwrap();
+    db.put(b"k", b"v").unwrap();
+
+    match Db::open(dir.path(), DbConfig::default()) {
+        Err(DbError::LeaseHeld(pid)) => assert_eq!(pid, std::process::id()),
+        other => panic!("expected LeaseHeld, got {other:?}"),
+    }
+    assert_eq!(db.get(b"k").unwrap(), Some(b"v".to_vec()));
+    db.close().unwrap();
+
+    let db = Db::open(dir.path(), DbConfig::default()).unwrap();
+    db.close().unwrap();
+
+    std::fs::write(dir.path().join("LEASE"), "1 1 0\n").unwrap();
+    let db = Db::open(dir.path(), DbConfig::default()).unwrap();
+    assert_eq!(db.get(b"k").unwrap(), Some(b"v".to_vec()));
+    db.close().unwrap();
+    assert!(!dir.path().join("LEASE").exists());
+}
+
+/// # Scenario
+/// A read-only handle follows the SSTables of a database it does not own.
+///
+/// # Starting environment
+/// Partitioned database with 200 keys flushed to SSTables, closed.
+///
+/// # Actions
+/// 1. Open a `ReadOnlyDb` with background refresh disabled.
+/// 2. Reopen the writer, overwrite and delete keys, flush them, reopen,
+///    major-compact, and close.
+/// 3. Read before and after `refresh()`.
+///
+/// # Expected behavior
+/// The reader opens while the writer holds the lease. Before the refresh
+/// it serves the old state — even though compaction deleted its
+/// SSTables; after it, the new one.
+#[test]
+fn read_only_db_refresh_sees_flushed_writes() {
+    let dir = TempDir::new().unwrap();
+    let config = || DbConfig {
+        partitions: 2,
+        ..small_buffer_config()
+    };
+    {
+        let db = Db::open(dir.path(), config()).unwrap();
+        for i in 0..200u32 {
+            let key = format!("ro_{:04}", i);
+            db.put(key.as_bytes(), b"v1").unwrap();
+        }
+        push_out_of_memtable(&db);
+        db.close().unwrap();
+    }
+
+    let reader = ReadOnlyDb::open(
+        dir.path(),
+        ReadOnlyConfig {
+            refresh_interval_ms: 0,
+        },
+    )
+    .unwrap();
+    assert_eq!(reader.get(b"ro_0007").unwrap(), Some(b"v1".to_vec()));
+    assert_eq!(reader.scan(b"ro_", b"ro_~").unwrap().len(), 200);
+    let version = reader.manifest_version().unwrap();
+    assert!(!reader.refresh().unwrap());
+
+    let db = Db::open(dir.path(), config()).unwrap();
+    db.put(b"ro_0007", b"v2").unwrap();
+    db.delete(b"ro_0008").unwrap();
+    push_out_of_memtable(&db);
+    db.close().unwrap();
+    let db = Db::open(dir.path(), config()).unwrap();
+    db.major_compact().unwrap();
+    db.close().unwrap();
+
+    assert_eq!(reader.get(b"ro_0008").unwrap(), Some(b"v1".to_vec()));
+    assert!(reader.refresh().unwrap());
+    assert!(reader.manifest_version().unwrap() > version);
+    assert_eq!(reader.get(b"ro_0007").unwrap(), Some(b"v2".to_vec()));
+    assert_eq!(reader.get(b"ro_0008").unwrap(), None);
+    let scan = reader.scan(b"ro_", b"ro_~").unwrap();
+    assert_eq!(scan.len(), 199);
+    assert!(scan.windows(2).all(|w| w[0].0 < w[1].0));
+
+    reader.close().unwrap();
+    assert!(matches!(reader.get(b"ro_0007"), Err(DbError::Closed)));
+}
+
+/// # Scenario
+/// A read-only handle discovers new SSTables by polling the manifest.
+///
+/// # Starting environment
+/// Database with one key flushed to an SSTable, closed.
+///
+/// # Actions
+/// 1. Open a `ReadOnlyDb` refreshing every 10 ms.
+/// 2. Reopen the writer, put a new key, flush it, close.
+/// 3. Wait for the reader to see the key.
+///
+/// # Expected behavior
+/// The key becomes visible without an explicit `refresh()`. Opening a
+/// directory without a database, or with an out-of-range interval, fails.
+#[test]
+fn read_only_db_polls_manifest() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    db.put(b"first", b"1").unwrap();
+    push_out_of_memtable(&db);
+    db.close().unwrap();
+
+    let reader = ReadOnlyDb::open(
+        dir.path(),
+        ReadOnlyConfig {
+            refresh_interval_ms: 10,
+        },
+    )
+    .unwrap();
+    assert_eq!(reader.get(b"second").unwrap(), None);
+
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    db.put(b"second", b"2").unwrap();
+    push_out_of_memtable(&db);
+    db.close().unwrap();
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while reader.get(b"second").unwrap().is_none() {
+        assert!(std::time::Instant::now() < deadline, "refresh never ran");
+        thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert_eq!(reader.get(b"first").unwrap(), Some(b"1".to_vec()));
+    drop(reader);
+
+    let empty = TempDir::new().unwrap();
+    assert!(matches!(
+        ReadOnlyDb::open(empty.path(), ReadOnlyConfig::default()),
+        Err(DbError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        ReadOnlyDb::open(
+            dir.path(),
+            ReadOnlyConfig {
+                refresh_interval_ms: 5,
+            }
+        ),
+        Err(DbError::InvalidConfig(_))
+    ));
+}
+
+/// # Scenario
+/// SSTables written by the engine can be read back through `sst::SstReader`.
+///
+/// # Starting environment
+/// Database with a 1 KiB write buffer.
+///
+/// # Actions
+/// 1. Write 200 keys, close, reopen (all frozen data is flushed).
+/// 2. Open every SSTable listed by `sstable_metadata()` with `SstReader`.
+/// 3. Read properties, points, and range tombstones; call `verify()`.
+///
+/// # Expected behavior
+/// Every reader agrees with the metadata, yields exactly `record_count`
+/// points in key order, and verifies cleanly. Every point is one of the
+/// written keys.
+/// A file that is not an SSTable is rejected with `SstError::Corrupt`.
+#[test]
+fn sst_reader_reads_engine_sstables() {
+    let dir = TempDir::new().unwrap();
+    {
+        let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+        for i in 0..200u32 {
+            let key = format!("sst_{:04}", i);
+            db.put(key.as_bytes(), b"some_value_padding").unwrap();
+        }
+        db.close().unwrap();
+    }
+
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    let meta = db.sstable_metadata().unwrap();
+    db.close().unwrap();
+    assert!(!meta.is_empty());
+
+    for m in &meta {
+        let reader = SstReader::open(&m.path).unwrap();
+        let props = reader.properties();
+        assert_eq!(reader.file_size(), m.file_size);
+        assert_eq!(props.record_count, m.record_count);
+        assert_eq!(props.min_key, m.min_key);
+        assert_eq!(props.max_key, m.max_key);
+
+        let points: Vec<_> = reader.points().map(Result::unwrap).collect();
+        assert_eq!(points.len() as u64, props.record_count);
+        assert!(points.windows(2).all(|w| w[0].key <= w[1].key));
+        for p in &points {
+            assert!(reader.may_contain(&p.key));
+            assert!(p.key.starts_with(b"sst_"));
+            assert_eq!(p.value.as_deref(), Some(b"some_value_padding".as_slice()));
+        }
+        assert_eq!(
+            reader.range_tombstones().count() as u64,
+            props.range_tombstone_count
+        );
+        reader.verify().unwrap();
+    }
+
+    let bogus = dir.path().join("bogus.sst");
+    std::fs::write(&bogus, vec![0u8; 128]).unwrap();
+    assert!(matches!(SstReader::open(&bogus), Err(SstError::Corrupt(_))));
+}
+
+/// # Scenario
+/// The change feed and `SstReader` describe the same writes with the
+/// shared `types` vocabulary.
+///
+/// # Starting environment
+/// Database with a 1 KiB write buffer and a subscriber over every key.
+///
+/// # Actions
+/// 1. Put `t_a`, delete `t_b`, range-delete `[t_c, t_d)`.
+/// 2. Push the writes out of the memtable; close and reopen.
+/// 3. Read every SSTable with `SstReader`, converting its entries to
+///    `types::Record`.
+///
+/// # Expected behavior
+/// - The events report kinds `Put`, `Delete`, `RangeDelete` in order.
+/// - The SSTables hold a `Put` of `t_a`, a `Delete` of `t_b`, and a
+///   `RangeDelete` of `[t_c, t_d)`, whose LSNs increase in write order.
+#[test]
+fn types_shared_by_change_feed_and_sst_reader() {
+    let dir = TempDir::new().unwrap();
+    {
+        let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+        let changes = db.subscribe(b"\x00", b"\xff").unwrap();
+        db.put(b"t_a", b"1").unwrap();
+        db.delete(b"t_b").unwrap();
+        db.delete_range(b"t_c", b"t_d").unwrap();
+
+        let kinds: Vec<_> = changes.try_iter().map(|e| e.kind()).collect();
+        assert_eq!(
+            kinds,
+            [RecordKind::Put, RecordKind::Delete, RecordKind::RangeDelete]
+        );
+        push_out_of_memtable(&db);
+        db.close().unwrap();
+    }
+
+    let db = Db::open(dir.path(), small_buffer_config()).unwrap();
+    let meta = db.sstable_metadata().unwrap();
+    db.close().unwrap();
+
+    let mut records: Vec<Record> = Vec::new();
+    for m in &meta {
+        let reader = SstReader::open(&m.path).unwrap();
+        records.extend(reader.points().map(|p| Record::from(p.unwrap())));
+        records.extend(reader.range_tombstones().map(Record::from));
+    }
+    records.retain(|r| r.key().starts_with(b"t_"));
+    records.sort_by_key(Record::lsn);
+
+    let written: Vec<_> = records
+        .iter()
+        .map(|r| (r.kind(), r.key(), r.value()))
+        .collect();
+    assert_eq!(
+        written,
+        [
+            (RecordKind::Put, b"t_a".as_slice(), Some(b"1".as_slice())),
+            (RecordKind::Delete, b"t_b".as_slice(), None),
+            (RecordKind::RangeDelete, b"t_c".as_slice(), None),
+        ]
+    );
+    assert!(
+        matches!(&records[2], Record::RangeDelete { end, .. } if end == b"t_d"),
+        "{records:?}"
+    );
+}
+
+// ================================================================================================
+// Config validation
+// ================================================================================================
+
+/// # Scenario
+/// `write_buffer_size` below the 1024-byte minimum is rejected.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. `Db::open` with `write_buffer_size: 100`.
+///
+/// # Expected behavior
+/// Returns `Err(DbError::InvalidConfig(_))`.
+#[test]
+fn config_write_buffer_too_small() {
+    let dir = TempDir::new().unwrap();
+    let config = DbConfig {
+        write_buffer_size: 100,
+        ..DbConfig::default()
+    };
+    let err = Db::open(dir.path(), config).unwrap_err();
+    assert!(matches!(err, DbError::InvalidConfig(_)));
+}
+
+/// # Scenario
+/// `min_compaction_threshold` below 2 is rejected.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. `Db::open` with `min_compaction_threshold: 1`.
+///
+/// # Expected behavior
+/// Returns `Err(DbError::InvalidConfig(_))`.
+#[test]
+fn config_min_threshold_too_small() {
+    let dir = TempDir::new().unwrap();
+    let config = DbConfig {
+        min_compaction_threshold: 1,
+        ..DbConfig::default()
+    };
+    let err = Db::open(dir.path(), config).unwrap_err();
+    assert!(matches!(err, DbError::InvalidConfig(_)));
+}
+
+/// # Scenario
+/// `max_compaction_threshold` below `min_compaction_threshold` is rejected.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. `Db::open` with `min: 8, max: 4`.
+///
+/// # Expected behavior
+/// Returns `Err(DbError::InvalidConfig(_))`.
+#[test]
+fn config_max_below_min() {
+    let dir = TempDir::new().unwrap();
+    let config = DbConfig {
+        min_compaction_threshold: 8,
+        max_compaction_threshold: 4,
+        ..DbConfig::default()
+    };
+    let err = Db::open(dir.path(), config).unwrap_err();
+    assert!(matches!(err, DbError::InvalidConfig(_)));
+}
+
+/// # Scenario
+/// `tombstone_compaction_ratio` outside `(0.0, 1.0]` is rejected.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. `Db::open` with ratio `0.0` (boundary — exclusive).
+/// 2. `Db::open` with ratio `1.5` (above upper bound).
+///
+/// # Expected behavior
+/// Both return `Err(DbError::InvalidConfig(_))`.
+#[test]
+fn config_tombstone_ratio_out_of_range() {
+    let dir = TempDir::new().unwrap();
+
+    let config = DbConfig {
+        tombstone_compaction_ratio: 0.0,
+        ..DbConfig::default()
+    };
+    assert!(matches!(
+        Db::open(dir.path(), config).unwrap_err(),
+        DbError::InvalidConfig(_)
+    ));
+
+    let config = DbConfig {
+        tombstone_compaction_ratio: 1.5,
+        ..DbConfig::default()
+    };
+    assert!(matches!(
+        Db::open(dir.path(), config).unwrap_err(),
+        DbError::InvalidConfig(_)
+    ));
+}
+
+/// # Scenario
+/// `tombstone_gc_grace_seconds` above one year is rejected.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. `Db::open` with `tombstone_gc_grace_seconds: 31_536_001`.
+/// 2. `Db::open` with `tombstone_gc_grace_seconds: 31_536_000` (boundary).
+///
+/// # Expected behavior
+/// The first returns `Err(DbError::InvalidConfig(_))`; the second opens.
+#[test]
+fn config_tombstone_gc_grace_out_of_range() {
+    let dir = TempDir::new().unwrap();
+
+    let config = DbConfig {
+        tombstone_gc_grace_seconds: 31_536_001,
+        ..DbConfig::default()
+    };
+    assert!(matches!(
+        Db::open(dir.path(), config).unwrap_err(),
+        DbError::InvalidConfig(_)
+    ));
+
+    let config = DbConfig {
+        tombstone_gc_grace_seconds: 31_536_000,
+        ..DbConfig::default()
+    };
+    Db::open(dir.path(), config).unwrap().close().unwrap();
+}
+
+/// # Scenario
+/// `periodic_compaction_seconds` above one year is rejected.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. `Db::open` with `periodic_compaction_seconds: 31_536_001`.
+/// 2. `Db::open` with `periodic_compaction_seconds: 31_536_000` (boundary).
+///
+/// # Expected behavior
+/// The first returns `Err(DbError::InvalidConfig(_))`; the second opens.
+#[test]
+fn config_periodic_compaction_out_of_range() {
+    let dir = TempDir::new().unwrap();
+
+    let config = DbConfig {
+        periodic_compaction_seconds: 31_536_001,
+        ..DbConfig::default()
+    };
+    assert!(matches!(
+        Db::open(dir.path(), config).unwrap_err(),
+        DbError::InvalidConfig(_)
+    ));
+
+    let config = DbConfig {
+        periodic_compaction_seconds: 31_536_000,
+        ..DbConfig::default()
+    };
+    Db::open(dir.path(), config).unwrap().close().unwrap();
+}
+
+/// # Scenario
+/// Out-of-range flush trigger options are rejected.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. `Db::open` with `max_memtable_age: 31_536_001`.
+/// 2. `Db::open` with `max_total_wal_size: 1023`.
+/// 3. `Db::open` with both at their boundaries (`31_536_000`, `1024`).
+///
+/// # Expected behavior
+/// The first two return `Err(DbError::InvalidConfig(_))`; the third opens.
+#[test]
+fn config_flush_triggers_out_of_range() {
+    let dir = TempDir::new().unwrap();
+
+    for config in [
+        DbConfig {
+            max_memtable_age: 31_536_001,
+            ..DbConfig::default()
+        },
+        DbConfig {
+            max_total_wal_size: 1023,
+            ..DbConfig::default()
+        },
+    ] {
+        assert!(matches!(
+            Db::open(dir.path(), config).unwrap_err(),
+            DbError::InvalidConfig(_)
+        ));
+    }
+
+    let config = DbConfig {
+        max_memtable_age: 31_536_000,
+        max_total_wal_size: 1024,
+        ..DbConfig::default()
+    };
+    Db::open(dir.path(), config).unwrap().close().unwrap();
+}
+
+/// # Scenario
+/// Out-of-range WAL retention options are rejected.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. `Db::open` with `wal_retention_bytes: 1023`.
+/// 2. `Db::open` with `wal_retention_seconds: 31_536_001`.
+/// 3. `Db::open` with both at their boundaries (`1024`, `31_536_000`).
+///
+/// # Expected behavior
+/// The first two return `Err(DbError::InvalidConfig(_))`; the third opens.
+#[test]
+fn config_wal_retention_out_of_range() {
+    let dir = TempDir::new().unwrap();
+
+    for config in [
+        DbConfig {
+            wal_retention_bytes: 1023,
+            ..DbConfig::default()
+        },
+        DbConfig {
+            wal_retention_seconds: 31_536_001,
+            ..DbConfig::default()
+        },
+    ] {
+        assert!(matches!(
+            Db::open(dir.path(), config).unwrap_err(),
+            DbError::InvalidConfig(_)
+        ));
+    }
+
+    let config = DbConfig {
+        wal_retention_bytes: 1024,
+        wal_retention_seconds: 31_536_000,
+        ..DbConfig::default()
+    };
+    Db::open(dir.path(), config).unwrap().close().unwrap();
+}
+
+/// # Scenario
+/// A row cache too small to hold a row is rejected.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. `Db::open` with `row_cache_size: 1023`.
+/// 2. `Db::open` with `row_cache_size: 1024`.
+///
+/// # Expected behavior
+/// The first returns `Err(DbError::InvalidConfig(_))`; the second opens.
+#[test]
+fn config_row_cache_size_too_small() {
+    let dir = TempDir::new().unwrap();
+    let config = DbConfig {
+        row_cache_size: 1023,
+        ..DbConfig::default()
+    };
+    assert!(matches!(
+        Db::open(dir.path(), config).unwrap_err(),
+        DbError::InvalidConfig(_)
+    ));
+
+    let config = DbConfig {
+        row_cache_size: 1024,
+        ..DbConfig::default()
+    };
+    Db::open(dir.path(), config).unwrap().close().unwrap();
+}
+
+/// # Scenario
+/// An mmap limit too small to be meaningful is rejected.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. `Db::open` with `max_mmap_bytes: 1023`.
+/// 2. `Db::open` with `max_mmap_bytes: 1024`.
+///
+/// # Expected behavior
+/// The first returns `Err(DbError::InvalidConfig(_))`; the second opens.
+#[test]
+fn config_max_mmap_bytes_too_small() {
+    let dir = TempDir::new().unwrap();
+    let config = DbConfig {
+        max_mmap_bytes: 1023,
+        ..DbConfig::default()
+    };
+    assert!(matches!(
+        Db::open(dir.path(), config).unwrap_err(),
+        DbError::InvalidConfig(_)
+    ));
+
+    let config = DbConfig {
+        max_mmap_bytes: 1024,
+        ..DbConfig::default()
+    };
+    Db::open(dir.path(), config).unwrap().close().unwrap();
+}
+
+/// # Scenario
+/// Warming more SSTables than the documented bound is rejected.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. `Db::open` with `warm_up_sstables: 1025`.
+/// 2. `Db::open` with `warm_up_sstables: 1024`.
+///
+/// # Expected behavior
+/// The first returns `Err(DbError::InvalidConfig(_))`; the second opens.
+#[test]
+fn config_warm_up_sstables_too_large() {
+    let dir = TempDir::new().unwrap();
+    let config = DbConfig {
+        warm_up_sstables: 1025,
+        ..DbConfig::default()
+    };
+    assert!(matches!(
+        Db::open(dir.path(), config).unwrap_err(),
+        DbError::InvalidConfig(_)
+    ));
+
+    let config = DbConfig {
+        warm_up_sstables: 1024,
+        ..DbConfig::default()
+    };
+    Db::open(dir.path(), config).unwrap().close().unwrap();
+}
+
+/// # Scenario
+/// Adaptive compaction bounds that contradict each other are rejected.
+///
+/// # Actions
+/// 1. `Db::open` with a zero sampling interval, a read target below 1,
+///    a `min_threshold` floor above its ceiling, a `max_threshold` floor
+///    below the `min_threshold` floor, and a zero tombstone ratio floor.
+/// 2. `Db::open` with the default bounds.
+///
+/// # Expected behavior
+/// Every case in step 1 returns `Err(DbError::InvalidConfig(_))`; the
+/// defaults open.
+#[test]
+fn config_adaptive_compaction_invalid_bounds() {
+    let dir = TempDir::new().unwrap();
+
+    for tuning in [
+        AdaptiveCompaction {
+            interval_seconds: 0,
+            ..AdaptiveCompaction::default()
+        },
+        AdaptiveCompaction {
+            target_read_amplification: 0.5,
+            ..AdaptiveCompaction::default()
+        },
+        AdaptiveCompaction {
+            min_threshold_floor: 20,
+            min_threshold_ceiling: 10,
+            ..AdaptiveCompaction::default()
+        },
+        AdaptiveCompaction {
+            min_threshold_floor: 8,
+            max_threshold_floor: 4,
+            ..AdaptiveCompaction::default()
+        },
+        AdaptiveCompaction {
+            tombstone_ratio_floor: 0.0,
+            ..AdaptiveCompaction::default()
+        },
+    ] {
+        let config = DbConfig {
+            adaptive_compaction: Some(tuning),
+            ..DbConfig::default()
+        };
+        assert!(matches!(
+            Db::open(dir.path(), config).unwrap_err(),
+            DbError::InvalidConfig(_)
+        ));
+    }
+
+    let config = DbConfig {
+        adaptive_compaction: Some(AdaptiveCompaction::default()),
+        ..DbConfig::default()
+    };
+    Db::open(dir.path(), config).unwrap().close().unwrap();
+}
+
+/// # Scenario
+/// `partitions` outside `[1, 256]`, or differing from the count the
+/// database was created with, is rejected.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. `Db::open` with `partitions: 0` and `partitions: 257`.
+/// 2. Create the database with `partitions: 4`, then reopen it with
+///    `1` and with `8`.
+/// 3. Leave a stray file and directory under `partitions/`; reopen
+///    with `4`.
+///
+/// # Expected behavior
+/// Every rejected open returns `Err(DbError::InvalidConfig(_))`;
+/// reopening with `4` succeeds, stray entries or not.
+#[test]
+fn config_partitions_out_of_range_or_changed() {
+    let dir = TempDir::new().unwrap();
+
+    for partitions in [0, 257] {
+        let config = DbConfig {
+            partitions,
+            ..DbConfig::default()
+        };
+        assert!(matches!(
+            Db::open(dir.path(), config).unwrap_err(),
+            DbError::InvalidConfig(_)
+        ));
+    }
+
+    let with = |partitions| DbConfig {
+        partitions,
+        ..DbConfig::default()
+    };
+    Db::open(dir.path(), with(4)).unwrap().close().unwrap();
+    for partitions in [1, 8] {
+        assert!(matches!(
+            Db::open(dir.path(), with(partitions)).unwrap_err(),
+            DbError::InvalidConfig(_)
+        ));
+    }
+    Db::open(dir.path(), with(4)).unwrap().close().unwrap();
+
+    let partitions_dir = dir.path().join("partitions");
+    std::fs::write(partitions_dir.join(".DS_Store"), b"").unwrap();
+    std::fs::create_dir(partitions_dir.join("backup")).unwrap();
+    Db::open(dir.path(), with(4)).unwrap().close().unwrap();
+}
+
+/// # Scenario
+/// `max_memtables_per_flush` outside `[1, 64]` is rejected.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. `Db::open` with `max_memtables_per_flush: 0`.
+/// 2. `Db::open` with `max_memtables_per_flush: 65`.
+///
+/// # Expected behavior
+/// Both return `Err(DbError::InvalidConfig(_))`.
+#[test]
+fn config_max_memtables_per_flush_out_of_range() {
+    let dir = TempDir::new().unwrap();
+
+    for value in [0, 65] {
+        let config = DbConfig {
+            max_memtables_per_flush: value,
+            ..DbConfig::default()
+        };
+        assert!(matches!(
+            Db::open(dir.path(), config).unwrap_err(),
+            DbError::InvalidConfig(_)
+        ));
+    }
+}
+
+/// # Scenario
+/// Batched background flushes preserve all data.
+///
+/// # Starting environment
+/// Small write buffer with `max_memtables_per_flush: 8`.
+///
+/// # Actions
+/// 1. Burst 2 000 puts, then close and reopen.
+///
+/// # Expected behavior
+/// Every key reads back its value after reopen.
+#[test]
+fn batched_flush_preserves_data() {
+    let dir = TempDir::new().unwrap();
+    let config = DbConfig {
+        max_memtables_per_flush: 8,
+        ..small_buffer_config()
+    };
+    let db = Db::open(dir.path(), config).unwrap();
+
+    for i in 0..2000u32 {
+        db.put(format!("b_{i:05}").as_bytes(), format!("v_{i}").as_bytes())
+            .unwrap();
+    }
+    db.close().unwrap();
+
+    let db = reopen(dir.path());
+    for i in 0..2000u32 {
+        assert_eq!(
+            db.get(format!("b_{i:05}").as_bytes()).unwrap(),
+            Some(format!("v_{i}").into_bytes())
+        );
+    }
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// `thread_pool_size` of 0 is rejected (at least 1 thread required).
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. `Db::open` with `thread_pool_size: 0`.
+///
+/// # Expected behavior
+/// Returns `Err(DbError::InvalidConfig(_))`.
+#[test]
+fn config_zero_threads() {
+    let dir = TempDir::new().unwrap();
+    let config = DbConfig {
+        thread_pool_size: 0,
+        ..DbConfig::default()
+    };
+    let err = Db::open(dir.path(), config).unwrap_err();
+    assert!(matches!(err, DbError::InvalidConfig(_)));
+}
+
+/// # Scenario
+/// `background_queue_depth` outside [1, 1024] is rejected.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. `Db::open` with `background_queue_depth: 0`, then `1025`.
+///
+/// # Expected behavior
+/// Both return `Err(DbError::InvalidConfig(_))`.
+#[test]
+fn config_background_queue_depth_bounds() {
+    let dir = TempDir::new().unwrap();
+    for depth in [0, 1025] {
+        let config = DbConfig {
+            background_queue_depth: depth,
+            ..DbConfig::default()
+        };
+        let err = Db::open(dir.path(), config).unwrap_err();
+        assert!(matches!(err, DbError::InvalidConfig(_)));
     }
 }
 
-// ================================================================================================
-// Config validation
-// ================================================================================================
-
 /// # Scenario
-/// `write_buffer_size` below the 1024-byte minimum is rejected.
+/// `tenant_prefixes` with an empty prefix, an empty tenant, or a prefix
+/// listed twice is rejected.
 ///
 /// # Starting environment
 /// Empty temporary directory.
 ///
 /// # Actions
-/// 1. `Db::open` with `write_buffer_size: 100`.
+/// 1. `Db::open` with each of the three invalid mappings.
 ///
 /// # Expected behavior
-/// Returns `Err(DbError::InvalidConfig(_))`.
+/// All return `Err(DbError::InvalidConfig(_))`.
 #[test]
-fn config_write_buffer_too_small() {
+fn config_tenant_prefixes_invalid() {
     let dir = TempDir::new().unwrap();
-    let config = DbConfig {
-        write_buffer_size: 100,
-        ..DbConfig::default()
-    };
-    let err = Db::open(dir.path(), config).unwrap_err();
-    assert!(matches!(err, DbError::InvalidConfig(_)));
+    for prefixes in [
+        vec![TenantPrefix::new("", "acme")],
+        vec![TenantPrefix::new("acme/", "")],
+        vec![
+            TenantPrefix::new("acme/", "acme"),
+            TenantPrefix::new("acme/", "beta"),
+        ],
+    ] {
+        let config = DbConfig {
+            tenant_prefixes: prefixes,
+            ..DbConfig::default()
+        };
+        let err = Db::open(dir.path(), config).unwrap_err();
+        assert!(matches!(err, DbError::InvalidConfig(_)));
+    }
 }
 
 /// # Scenario
-/// `min_compaction_threshold` below 2 is rejected.
+/// `deletion_run_compaction_threshold` above 1 000 000 is rejected.
 ///
 /// # Starting environment
 /// Empty temporary directory.
 ///
 /// # Actions
-/// 1. `Db::open` with `min_compaction_threshold: 1`.
+/// 1. `Db::open` with `deletion_run_compaction_threshold: 1_000_001`.
 ///
 /// # Expected behavior
 /// Returns `Err(DbError::InvalidConfig(_))`.
 #[test]
-fn config_min_threshold_too_small() {
+fn config_deletion_run_compaction_threshold_too_large() {
     let dir = TempDir::new().unwrap();
     let config = DbConfig {
-        min_compaction_threshold: 1,
+        deletion_run_compaction_threshold: 1_000_001,
         ..DbConfig::default()
     };
     let err = Db::open(dir.path(), config).unwrap_err();
@@ -610,22 +4848,34 @@ fn config_min_threshold_too_small() {
 }
 
 /// # Scenario
-/// `max_compaction_threshold` below `min_compaction_threshold` is rejected.
-///
-/// # Starting environment
-/// Empty temporary directory.
+/// A FIFO strategy with neither a size budget nor a TTL is rejected.
 ///
-/// # Actions
-/// 1. `Db::open` with `min: 8, max: 4`.
+/// # Expected behavior
+/// `Db::open` returns `DbError::InvalidConfig`.
+#[test]
+fn config_fifo_without_limits() {
+    let dir = TempDir::new().unwrap();
+    let config = DbConfig {
+        compaction_strategy: CompactionStrategyType::Fifo {
+            max_total_bytes: 0,
+            ttl_seconds: 0,
+        },
+        ..DbConfig::default()
+    };
+    let err = Db::open(dir.path(), config).unwrap_err();
+    assert!(matches!(err, DbError::InvalidConfig(_)));
+}
+
+/// # Scenario
+/// `read_fanout_limit` above its bound is rejected.
 ///
 /// # Expected behavior
-/// Returns `Err(DbError::InvalidConfig(_))`.
+/// `Db::open` returns `DbError::InvalidConfig`.
 #[test]
-fn config_max_below_min() {
+fn config_read_fanout_limit_too_large() {
     let dir = TempDir::new().unwrap();
     let config = DbConfig {
-        min_compaction_threshold: 8,
-        max_compaction_threshold: 4,
+        read_fanout_limit: 65_537,
         ..DbConfig::default()
     };
     let err = Db::open(dir.path(), config).unwrap_err();
@@ -633,60 +4883,163 @@ fn config_max_below_min() {
 }
 
 /// # Scenario
-/// `tombstone_compaction_ratio` outside `(0.0, 1.0]` is rejected.
+/// Every workload preset is a valid configuration.
 ///
 /// # Starting environment
-/// Empty temporary directory.
+/// Empty temporary directories.
 ///
 /// # Actions
-/// 1. `Db::open` with ratio `0.0` (boundary — exclusive).
-/// 2. `Db::open` with ratio `1.5` (above upper bound).
+/// 1. For each preset: open a database, write and read back 200 keys,
+///    close it.
 ///
 /// # Expected behavior
-/// Both return `Err(DbError::InvalidConfig(_))`.
+/// Every preset opens and serves reads and writes. The point-lookup
+/// preset sizes the row cache from its argument, and the write-heavy one
+/// uses a larger write buffer than the default.
 #[test]
-fn config_tombstone_ratio_out_of_range() {
-    let dir = TempDir::new().unwrap();
+fn config_presets_open() {
+    let presets = [
+        DbConfig::for_write_heavy(),
+        DbConfig::for_read_heavy(),
+        DbConfig::for_point_lookup(8),
+        DbConfig::for_point_lookup(0),
+        DbConfig::for_bulk_load(),
+    ];
+    for config in presets {
+        let dir = TempDir::new().unwrap();
+        let db = Db::open(dir.path(), config).unwrap();
+        for i in 0..200u32 {
+            db.put(format!("cp_{i:04}").as_bytes(), b"v").unwrap();
+        }
+        assert_eq!(db.get(b"cp_0100").unwrap(), Some(b"v".to_vec()));
+        assert_eq!(db.scan(b"cp_", b"cp_\xff").unwrap().len(), 200);
+        db.close().unwrap();
+    }
 
-    let config = DbConfig {
-        tombstone_compaction_ratio: 0.0,
-        ..DbConfig::default()
-    };
-    assert!(matches!(
-        Db::open(dir.path(), config).unwrap_err(),
-        DbError::InvalidConfig(_)
-    ));
+    assert_eq!(
+        DbConfig::for_point_lookup(8).row_cache_size,
+        8 * 1024 * 1024
+    );
+    assert_eq!(DbConfig::for_point_lookup(0).row_cache_size, 0);
+    assert!(DbConfig::for_write_heavy().write_buffer_size > DbConfig::default().write_buffer_size);
+}
 
-    let config = DbConfig {
-        tombstone_compaction_ratio: 1.5,
-        ..DbConfig::default()
-    };
-    assert!(matches!(
-        Db::open(dir.path(), config).unwrap_err(),
-        DbError::InvalidConfig(_)
-    ));
+/// # Scenario
+/// `DbConfig::builder` applies a profile, then individual settings, and
+/// validates the result.
+///
+/// # Starting environment
+/// None.
+///
+/// # Actions
+/// 1. Set `partitions`, apply the write-heavy profile, override
+///    `thread_pool_size`, and build.
+/// 2. Apply the bulk-load profile and then `Balanced`, and build.
+/// 3. Build with `write_buffer_size: 100`.
+///
+/// # Expected behavior
+/// 1. The profile's knobs are set, the override wins, and `partitions`,
+///    which no profile tunes, is kept.
+/// 2. `Balanced` restores every knob the bulk-load profile changed.
+/// 3. Fails with `DbError::InvalidConfig`.
+#[test]
+fn config_builder_applies_profile_and_overrides() {
+    let config = DbConfig::builder()
+        .partitions(4)
+        .profile(ConfigProfile::WriteHeavy)
+        .thread_pool_size(8)
+        .build()
+        .unwrap();
+    let preset = DbConfig::for_write_heavy();
+    assert_eq!(config.partitions, 4);
+    assert_eq!(config.write_buffer_size, preset.write_buffer_size);
+    assert_eq!(
+        config.min_compaction_threshold,
+        preset.min_compaction_threshold
+    );
+    assert_eq!(config.thread_pool_size, 8);
+
+    let config = DbConfig::builder()
+        .profile(ConfigProfile::BulkLoad)
+        .profile(ConfigProfile::Balanced)
+        .build()
+        .unwrap();
+    let defaults = DbConfig::default();
+    assert_eq!(config.write_buffer_size, defaults.write_buffer_size);
+    assert_eq!(
+        config.max_memtables_per_flush,
+        defaults.max_memtables_per_flush
+    );
+    assert_eq!(config.max_frozen_memtables, defaults.max_frozen_memtables);
+    assert_eq!(
+        config.min_compaction_threshold,
+        defaults.min_compaction_threshold
+    );
+    assert_eq!(config.thread_pool_size, defaults.thread_pool_size);
+
+    let built = DbConfig::builder().write_buffer_size(100).build();
+    assert!(matches!(built, Err(DbError::InvalidConfig(_))));
 }
 
 /// # Scenario
-/// `thread_pool_size` of 0 is rejected (at least 1 thread required).
+/// A configuration with several problems reports all of them at once,
+/// including settings that are only invalid in combination.
 ///
 /// # Starting environment
 /// Empty temporary directory.
 ///
 /// # Actions
-/// 1. `Db::open` with `thread_pool_size: 0`.
+/// 1. `Db::open` with `write_buffer_size: 100`, `thread_pool_size: 0`,
+///    `read_fanout_fallback` without a `read_fanout_limit`, and a
+///    `row_cache_size` of 4 KiB split over 8 partitions.
+/// 2. `Db::open` with the same row cache over 4 partitions and
+///    `read_fanout_limit: 8`, other settings valid.
 ///
 /// # Expected behavior
-/// Returns `Err(DbError::InvalidConfig(_))`.
+/// 1. Fails with `DbError::InvalidConfig` listing four violations, in
+///    field order; the cross-field ones name both fields involved, and
+///    the error message lists every violation.
+/// 2. Opens: each partition gets 1 KiB of row cache.
 #[test]
-fn config_zero_threads() {
+fn config_violations_reported_together() {
     let dir = TempDir::new().unwrap();
     let config = DbConfig {
+        write_buffer_size: 100,
         thread_pool_size: 0,
+        read_fanout_fallback: true,
+        row_cache_size: 4096,
+        partitions: 8,
         ..DbConfig::default()
     };
-    let err = Db::open(dir.path(), config).unwrap_err();
-    assert!(matches!(err, DbError::InvalidConfig(_)));
+
+    let Err(DbError::InvalidConfig(report)) = Db::open(dir.path(), config) else {
+        panic!("expected DbError::InvalidConfig");
+    };
+    let fields: Vec<Vec<&str>> = report.violations.iter().map(|v| v.fields.clone()).collect();
+    assert_eq!(
+        fields,
+        vec![
+            vec!["write_buffer_size"],
+            vec!["read_fanout_fallback", "read_fanout_limit"],
+            vec!["row_cache_size", "partitions"],
+            vec!["thread_pool_size"],
+        ]
+    );
+    assert!(report.involves("partitions"));
+    assert!(!report.involves("max_mmap_bytes"));
+    let message = DbError::InvalidConfig(report.clone()).to_string();
+    for violation in &report.violations {
+        assert!(message.contains(&violation.message));
+    }
+
+    let config = DbConfig {
+        read_fanout_limit: 8,
+        read_fanout_fallback: true,
+        row_cache_size: 4096,
+        partitions: 4,
+        ..DbConfig::default()
+    };
+    Db::open(dir.path(), config).unwrap().close().unwrap();
 }
 
 // ================================================================================================
@@ -1000,3 +5353,434 @@ fn full_lifecycle_with_compaction() {
         db.close().unwrap();
     }
 }
+
+/// # Scenario
+/// Value checksums can be turned on for an existing database and off
+/// again without losing data.
+///
+/// # Starting environment
+/// Database with a 1 KiB write buffer, written without value checksums.
+///
+/// # Actions
+/// 1. Reopen with `value_checksums`, write more keys, some left in the
+///    memtable, and run a major compaction.
+/// 2. Get every key, with and without `verify_value_checksum`.
+/// 3. Reopen without `value_checksums` and get every key.
+///
+/// # Expected behavior
+/// Every key reads back the value last written in every step.
+#[test]
+fn value_checksums_round_trip() {
+    let dir = TempDir::new().unwrap();
+    let config = |value_checksums| DbConfig {
+        value_checksums,
+        ..small_buffer_config()
+    };
+    let db = Db::open(dir.path(), config(false)).unwrap();
+    for i in 0..100u32 {
+        db.put(format!("vc_{i:04}").as_bytes(), b"before_checksums")
+            .unwrap();
+    }
+    db.close().unwrap();
+
+    let db = Db::open(dir.path(), config(true)).unwrap();
+    for i in 50..200u32 {
+        db.put(format!("vc_{i:04}").as_bytes(), b"with_checksums")
+            .unwrap();
+    }
+    db.major_compact().unwrap();
+    for i in 200..205u32 {
+        db.put(format!("vc_{i:04}").as_bytes(), b"in_memtable")
+            .unwrap();
+    }
+
+    let expected = |i: u32| match i {
+        0..50 => b"before_checksums".as_slice(),
+        50..200 => b"with_checksums",
+        _ => b"in_memtable",
+    };
+    let unverified = ReadOptions {
+        verify_value_checksum: false,
+        ..ReadOptions::default()
+    };
+    for i in 0..205u32 {
+        let key = format!("vc_{i:04}");
+        assert_eq!(
+            db.get(key.as_bytes()).unwrap().as_deref(),
+            Some(expected(i))
+        );
+        assert_eq!(
+            db.get_opt(key.as_bytes(), &unverified).unwrap().as_deref(),
+            Some(expected(i))
+        );
+    }
+    db.close().unwrap();
+
+    let db = Db::open(dir.path(), config(false)).unwrap();
+    for i in 0..205u32 {
+        let key = format!("vc_{i:04}");
+        assert_eq!(
+            db.get(key.as_bytes()).unwrap().as_deref(),
+            Some(expected(i))
+        );
+    }
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// A batch retried with the same idempotency token, before and after a
+/// reopen, is applied once.
+///
+/// # Starting environment
+/// Database with two partitions.
+///
+/// # Actions
+/// 1. Write a tokenized batch, then overwrite one of its keys directly.
+/// 2. Retry the batch, reopen, and retry it again.
+/// 3. Write a batch with a new token, and one with an empty token.
+///
+/// # Expected behavior
+/// - `has_applied` reports the first token from step 1 on, across the
+///   reopen; the retries leave the direct overwrite in place.
+/// - The new token's batch is applied; the empty token is rejected.
+#[test]
+fn idempotency_token_applies_once() {
+    let dir = TempDir::new().unwrap();
+    let config = || DbConfig {
+        partitions: 2,
+        ..DbConfig::default()
+    };
+    let mut batch = WriteBatchWithIndex::new();
+    batch.put(b"order_1", b"placed");
+    batch.put(b"order_2", b"placed");
+    batch.set_idempotency_token(b"request-1");
+
+    let db = Db::open(dir.path(), config()).unwrap();
+    assert!(!db.has_applied(b"request-1").unwrap());
+    db.write(&batch).unwrap();
+    assert!(db.has_applied(b"request-1").unwrap());
+    db.put(b"order_1", b"shipped").unwrap();
+    db.write(&batch).unwrap();
+    db.close().unwrap();
+
+    let db = Db::open(dir.path(), config()).unwrap();
+    assert!(db.has_applied(b"request-1").unwrap());
+    db.write(&batch).unwrap();
+    assert_eq!(db.get(b"order_1").unwrap(), Some(b"shipped".to_vec()));
+    assert_eq!(db.get(b"order_2").unwrap(), Some(b"placed".to_vec()));
+
+    let mut next = WriteBatchWithIndex::new();
+    next.put(b"order_1", b"cancelled");
+    next.set_idempotency_token(b"request-2");
+    db.write(&next).unwrap();
+    assert!(db.has_applied(b"request-2").unwrap());
+    assert_eq!(db.get(b"order_1").unwrap(), Some(b"cancelled".to_vec()));
+
+    next.set_idempotency_token(b"");
+    assert!(matches!(db.write(&next), Err(DbError::InvalidArgument(_))));
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// A mock clock set in `DbConfig::clock` drives write timestamps and
+/// idempotency token retention.
+///
+/// # Actions
+/// 1. Open with a `MockClock` at a fixed time and write a key and a
+///    tokenized batch.
+/// 2. Advance the clock past `idempotency_retention_seconds`.
+///
+/// # Expected behavior
+/// - The key's entry carries the mock time as its timestamp.
+/// - The token is applied until the clock passes the retention window,
+///   then forgotten.
+#[test]
+fn mock_clock_controls_time() {
+    let dir = TempDir::new().unwrap();
+    let start = 1_700_000_000_000_000_000;
+    let clock = Arc::new(MockClock::new(start));
+    let db = Db::open(
+        dir.path(),
+        DbConfig {
+            idempotency_retention_seconds: 60,
+            clock: clock.clone(),
+            ..DbConfig::default()
+        },
+    )
+    .unwrap();
+
+    db.put(b"key", b"value").unwrap();
+    assert_eq!(db.get_entry(b"key").unwrap().unwrap().timestamp, start);
+
+    let mut batch = WriteBatchWithIndex::new();
+    batch.put(b"other", b"value");
+    batch.set_idempotency_token(b"token");
+    db.write(&batch).unwrap();
+
+    clock.advance(Duration::from_secs(59));
+    assert!(db.has_applied(b"token").unwrap());
+    clock.advance(Duration::from_secs(2));
+    assert!(!db.has_applied(b"token").unwrap());
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// `latest_lsn` follows writes and never moves backwards across a reopen.
+///
+/// # Actions
+/// 1. Open an empty database, write two keys.
+/// 2. Close, reopen, write one more key.
+///
+/// # Expected behavior
+/// - Each write raises `latest_lsn`.
+/// - After the reopen it is at least the value before the close.
+#[test]
+fn latest_lsn_is_monotonic_across_reopen() {
+    let dir = TempDir::new().unwrap();
+
+    let db = Db::open(dir.path(), DbConfig::default()).unwrap();
+    let empty = db.latest_lsn().unwrap();
+    db.put(b"a", b"1").unwrap();
+    let first = db.latest_lsn().unwrap();
+    assert!(first > empty);
+    db.put(b"b", b"2").unwrap();
+    let second = db.latest_lsn().unwrap();
+    assert!(second > first);
+    db.close().unwrap();
+
+    let db = Db::open(dir.path(), DbConfig::default()).unwrap();
+    assert!(db.latest_lsn().unwrap() >= second);
+    db.put(b"c", b"3").unwrap();
+    assert!(db.latest_lsn().unwrap() > second);
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// Lookups that probe more SSTables than
+/// `read_depth_compaction_threshold` allows queue compaction, though no
+/// size bucket reaches `min_compaction_threshold`.
+///
+/// # Starting environment
+/// Database with a 1 KiB write buffer and a compaction threshold of 64,
+/// reopened with several SSTables and a read depth threshold of 2.
+///
+/// # Actions
+/// 1. Look up 200 absent keys, each probing every SSTable.
+/// 2. Wait for the background timer to compact.
+///
+/// # Expected behavior
+/// Two SSTables remain and every key still reads back.
+#[test]
+fn read_depth_guardrail_compacts() {
+    let dir = TempDir::new().unwrap();
+    let config = |threshold| DbConfig {
+        min_compaction_threshold: 64,
+        max_compaction_threshold: 64,
+        read_depth_compaction_threshold: threshold,
+        ..small_buffer_config()
+    };
+
+    let db = Db::open(dir.path(), config(0)).unwrap();
+    for i in 0..400u32 {
+        db.put(format!("depth_{i:04}").as_bytes(), &[b'v'; 32])
+            .unwrap();
+    }
+    db.close().unwrap();
+
+    let db = Db::open(dir.path(), config(2)).unwrap();
+    assert!(db.sstable_metadata().unwrap().len() >= 4);
+    for i in 0..200u32 {
+        assert_eq!(db.get(format!("absent_{i:04}").as_bytes()).unwrap(), None);
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while db.sstable_metadata().unwrap().len() > 2 {
+        assert!(
+            Instant::now() < deadline,
+            "read depth not compacted in time"
+        );
+        thread::sleep(Duration::from_millis(50));
+    }
+    for i in (0..400u32).step_by(7) {
+        assert_eq!(
+            db.get(format!("depth_{i:04}").as_bytes()).unwrap(),
+            Some(vec![b'v'; 32])
+        );
+    }
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// Scans that step over more point tombstones in a row than
+/// `deletion_run_compaction_threshold` allows queue compaction of the
+/// deleted head, though no size bucket reaches `min_compaction_threshold`.
+///
+/// # Starting environment
+/// Database with a 1 KiB write buffer and a compaction threshold of 64,
+/// holding 400 queue entries of which the first 300 were deleted,
+/// reopened with a deletion run threshold of 10.
+///
+/// # Actions
+/// 1. Scan the queue from its head.
+/// 2. Wait for the background timer to compact.
+///
+/// # Expected behavior
+/// The SSTables hold fewer point tombstones than before, and the scan
+/// still returns the 100 remaining entries.
+#[test]
+fn deletion_runs_compact_deleted_head() {
+    let dir = TempDir::new().unwrap();
+    let config = |threshold| DbConfig {
+        min_compaction_threshold: 64,
+        max_compaction_threshold: 64,
+        deletion_run_compaction_threshold: threshold,
+        ..small_buffer_config()
+    };
+    let tombstones = |db: &Db| -> u64 {
+        db.sstable_metadata()
+            .unwrap()
+            .iter()
+            .map(|meta| meta.tombstone_count)
+            .sum()
+    };
+
+    let db = Db::open(dir.path(), config(0)).unwrap();
+    for i in 0..400u32 {
+        db.put(format!("queue_{i:04}").as_bytes(), &[b'v'; 32])
+            .unwrap();
+    }
+    for i in 0..300u32 {
+        db.delete(format!("queue_{i:04}").as_bytes()).unwrap();
+    }
+    db.close().unwrap();
+
+    let db = Db::open(dir.path(), config(10)).unwrap();
+    let before = tombstones(&db);
+    assert!(before > 0);
+    assert_eq!(db.scan(b"queue_", b"queue_~").unwrap().len(), 100);
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while tombstones(&db) >= before {
+        assert!(
+            Instant::now() < deadline,
+            "deleted head not compacted in time"
+        );
+        thread::sleep(Duration::from_millis(50));
+    }
+    let entries = db.scan(b"queue_", b"queue_~").unwrap();
+    assert_eq!(entries.len(), 100);
+    assert_eq!(entries[0].0, b"queue_0300");
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// FIFO compaction keeps the SSTables within their size budget by
+/// dropping the oldest ones, never merging.
+///
+/// # Starting environment
+/// `CompactionStrategyType::Fifo` with a 16 KiB budget and a 1 KiB write
+/// buffer.
+///
+/// # Actions
+/// 1. Write 1000 keys of 64-byte values, then wait for background
+///    compaction to settle.
+///
+/// # Expected behavior
+/// - The SSTables come to total at most the budget, with the oldest
+///   keys gone.
+/// - The newest key still reads back.
+#[test]
+fn fifo_compaction_drops_oldest() {
+    const BUDGET: usize = 16 * 1024;
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(
+        dir.path(),
+        DbConfig {
+            compaction_strategy: CompactionStrategyType::Fifo {
+                max_total_bytes: BUDGET,
+                ttl_seconds: 0,
+            },
+            ..small_buffer_config()
+        },
+    )
+    .unwrap();
+    let total = |db: &Db| -> u64 {
+        db.sstable_metadata()
+            .unwrap()
+            .iter()
+            .map(|meta| meta.file_size)
+            .sum()
+    };
+
+    for i in 0..1000u32 {
+        db.put(format!("event_{i:04}").as_bytes(), &[b'e'; 64])
+            .unwrap();
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while total(&db) > BUDGET as u64 || db.get(b"event_0000").unwrap().is_some() {
+        assert!(Instant::now() < deadline, "SSTables not dropped in time");
+        thread::sleep(Duration::from_millis(50));
+    }
+    assert_eq!(db.get(b"event_0999").unwrap(), Some(vec![b'e'; 64]));
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// Background and manual compactions of several partitions run side by
+/// side and each claims its inputs from the coordinator.
+///
+/// # Starting environment
+/// Two partitions with a 1 KiB write buffer.
+///
+/// # Actions
+/// 1. Write 2000 keys from two threads, letting background compaction
+///    run.
+/// 2. Run major compaction, then read the compaction job counters.
+///
+/// # Expected behavior
+/// - Once background work settles, no compaction holds a claim.
+/// - Every key reads back.
+#[test]
+fn compaction_job_stats_release_every_claim() {
+    let dir = TempDir::new().unwrap();
+    let db = Arc::new(
+        Db::open(
+            dir.path(),
+            DbConfig {
+                partitions: 2,
+                ..small_buffer_config()
+            },
+        )
+        .unwrap(),
+    );
+
+    let writers: Vec<_> = (0..2u32)
+        .map(|t| {
+            let db = Arc::clone(&db);
+            thread::spawn(move || {
+                for i in 0..1000u32 {
+                    db.put(format!("job_{t}_{i:04}").as_bytes(), b"value")
+                        .unwrap();
+                }
+            })
+        })
+        .collect();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+    db.major_compact().unwrap();
+
+    // A background compaction may still be finishing.
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while db.compaction_job_stats().unwrap().running > 0 {
+        assert!(Instant::now() < deadline, "compaction claims not released");
+        thread::sleep(Duration::from_millis(10));
+    }
+    for t in 0..2u32 {
+        for i in 0..1000u32 {
+            let key = format!("job_{t}_{i:04}");
+            assert_eq!(db.get(key.as_bytes()).unwrap(), Some(b"value".to_vec()));
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! Integration tests for the `typed` feature.
+//!
+//! These tests exercise `aeternusdb::typed` — the order-preserving codec
+//! and the `TypedDb` wrapper — through the public API only.
+//!
+//! ## Coverage areas
+//! - **Codec ordering**: unsigned, signed, float, string, option, and tuple
+//!   keys encode to bytes that sort like the values themselves
+//! - **Codec round-trip**: every encoded value decodes back unchanged;
+//!   malformed and trailing input is rejected
+//! - **TypedDb**: put, get, delete, delete_range, ordered scan over tuple
+//!   keys, persistence across reopen
+//!
+//! Built only with `--features typed`.
+
+use aeternusdb::DbConfig;
+use aeternusdb::typed::{CodecError, TypedDb, TypedError, decode, encode};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+use tempfile::TempDir;
+
+// ------------------------------------------------------------------------------------------------
+// Helpers
+// ------------------------------------------------------------------------------------------------
+
+/// Asserts that `values` (given in ascending order) encode to strictly
+/// ascending byte strings and decode back unchanged.
+fn assert_order_preserved<T>(values: &[T])
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let encoded: Vec<Vec<u8>> = values.iter().map(|v| encode(v).unwrap()).collect();
+    for (i, pair) in encoded.windows(2).enumerate() {
+        assert!(
+            pair[0] < pair[1],
+            "{:?} must encode below {:?}",
+            values[i],
+            values[i + 1]
+        );
+    }
+    for (value, bytes) in values.iter().zip(&encoded) {
+        assert_eq!(&decode::<T>(bytes).unwrap(), value);
+    }
+}
+
+// ================================================================================================
+// Codec
+// ================================================================================================
+
+/// # Scenario
+/// Scalar keys encode in their natural order.
+///
+/// # Starting environment
+/// None — pure codec test.
+///
+/// # Actions
+/// 1. Encode ascending `u64`, `i64`, `f64`, `String`, and `Option<u32>`
+///    values.
+///
+/// # Expected behavior
+/// Encodings are strictly ascending and decode back to the inputs;
+/// `u64` encodes as 8 big-endian bytes.
+#[test]
+fn codec_preserves_scalar_order() {
+    assert_order_preserved(&[0u64, 1, 255, 256, 65_536, u64::MAX - 1, u64::MAX]);
+    assert_order_preserved(&[i64::MIN, -256, -1, 0, 1, 256, i64::MAX]);
+    assert_order_preserved(&[f64::NEG_INFINITY, -1.5, -0.0, 0.0, 1e-9, 2.5, f64::INFINITY]);
+    assert_order_preserved(&[
+        String::new(),
+        "\0".to_string(),
+        "\0\0".to_string(),
+        "a".to_string(),
+        "a\0".to_string(),
+        "aa".to_string(),
+        "b".to_string(),
+    ]);
+    assert_order_preserved(&[None, Some(0u32), Some(7)]);
+
+    assert_eq!(
+        encode(&0x0102_0304_0506_0708u64).unwrap(),
+        [1, 2, 3, 4, 5, 6, 7, 8]
+    );
+}
+
+/// # Scenario
+/// Tuple keys sort field by field, including variable-length fields.
+///
+/// # Starting environment
+/// None — pure codec test.
+///
+/// # Actions
+/// 1. Encode ascending `(String, u64)` and `(u32, Vec<u8>, i16)` tuples.
+///
+/// # Expected behavior
+/// Encodings are strictly ascending and decode back to the inputs; a
+/// shorter string sorts before any extension of it regardless of the
+/// following field.
+#[test]
+fn codec_preserves_tuple_order() {
+    assert_order_preserved(&[
+        ("a".to_string(), u64::MAX),
+        ("ab".to_string(), 0),
+        ("ab".to_string(), 1),
+        ("b".to_string(), 0),
+    ]);
+    assert_order_preserved(&[
+        (1u32, vec![], 5i16),
+        (1, vec![0u8], -5),
+        (1, vec![0, 0], -5),
+        (1, vec![1], i16::MIN),
+        (2, vec![], i16::MIN),
+    ]);
+}
+
+/// # Scenario
+/// Malformed input is rejected instead of decoding garbage.
+///
+/// # Starting environment
+/// None — pure codec test.
+///
+/// # Actions
+/// 1. Decode a truncated `u64`.
+/// 2. Decode a `u32` with an extra trailing byte.
+/// 3. Decode a string with an invalid escape.
+///
+/// # Expected behavior
+/// Each call fails with the matching `CodecError` variant.
+#[test]
+fn codec_rejects_malformed_input() {
+    assert!(matches!(
+        decode::<u64>(&[0, 1, 2]),
+        Err(CodecError::UnexpectedEof)
+    ));
+    assert!(matches!(
+        decode::<u32>(&[0, 0, 0, 1, 9]),
+        Err(CodecError::TrailingBytes(1))
+    ));
+    assert!(matches!(
+        decode::<String>(&[b'a', 0x00, 0x07]),
+        Err(CodecError::InvalidByte(0x07))
+    ));
+}
+
+// ================================================================================================
+// TypedDb
+// ================================================================================================
+
+/// # Scenario
+/// `TypedDb` round-trips typed keys and values and scans in key order.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. Put `(user, seq) -> String` pairs for three users, out of order.
+/// 2. Get, scan one user's range, delete one key, range-delete a user.
+/// 3. Close and reopen.
+///
+/// # Expected behavior
+/// Scans return keys in tuple order with sequence numbers above 255
+/// sorted after smaller ones; deletes are visible and survive reopen.
+#[test]
+fn typed_db_round_trip_and_ordered_scan() {
+    let dir = TempDir::new().unwrap();
+    {
+        let db: TypedDb<(u64, u32), String> =
+            TypedDb::open(dir.path(), DbConfig::default()).unwrap();
+        for seq in [300u32, 2, 1, 256] {
+            for user in [9u64, 7, 8] {
+                db.put(&(user, seq), &format!("u{user}-m{seq}")).unwrap();
+            }
+        }
+
+        assert_eq!(db.get(&(7, 256)).unwrap().as_deref(), Some("u7-m256"));
+        assert_eq!(db.get(&(7, 3)).unwrap(), None);
+
+        let user7: Vec<_> = db
+            .scan(&(7, 0), &(8, 0))
+            .unwrap()
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(user7, vec![(7, 1), (7, 2), (7, 256), (7, 300)]);
+
+        db.delete(&(7, 2)).unwrap();
+        db.delete_range(&(8, 0), &(9, 0)).unwrap();
+        db.close().unwrap();
+    }
+
+    let db: TypedDb<(u64, u32), String> = TypedDb::open(dir.path(), DbConfig::default()).unwrap();
+    let all: Vec<_> = db
+        .scan(&(0, 0), &(u64::MAX, u32::MAX))
+        .unwrap()
+        .into_iter()
+        .map(|(k, _)| k)
+        .collect();
+    assert_eq!(
+        all,
+        vec![
+            (7, 1),
+            (7, 256),
+            (7, 300),
+            (9, 1),
+            (9, 2),
+            (9, 256),
+            (9, 300)
+        ]
+    );
+    assert_eq!(db.get(&(9, 300)).unwrap().as_deref(), Some("u9-m300"));
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// Reading data written under a different key type surfaces a codec error.
+///
+/// # Starting environment
+/// Empty temporary directory.
+///
+/// # Actions
+/// 1. Write a `String` key through the untyped `Db`.
+/// 2. Scan it through a `TypedDb<u64, u64>`.
+///
+/// # Expected behavior
+/// The scan fails with `TypedError::Codec`.
+#[test]
+fn typed_db_reports_mismatched_types() {
+    let dir = TempDir::new().unwrap();
+    let db: TypedDb<u64, u64> = TypedDb::open(dir.path(), DbConfig::default()).unwrap();
+    db.db()
+        .put(&encode("not a u64").unwrap(), &encode(&1u64).unwrap())
+        .unwrap();
+
+    assert!(matches!(db.scan(&0, &u64::MAX), Err(TypedError::Codec(_))));
+    db.close().unwrap();
+}
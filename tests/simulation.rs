@@ -0,0 +1,352 @@
+//! Deterministic scheduling tests for the `simulation` feature.
+//!
+//! Each test opens the database with `Db::open_simulated`, so no
+//! background thread runs: flushes, compactions, and timers happen only
+//! where the test calls `run_pending_background_work` or
+//! `tick_background_timers`, and every interleaving is fixed.
+//!
+//! ## Coverage areas
+//! - **Manual scheduling**: queued flushes run only on request, in order
+//! - **Coalescing**: one compaction per burst of flushes, one task per timer
+//! - **Interleaving**: writes between queueing and running a compaction
+//! - **Timers**: the memtable age timer under a `MockClock`
+//! - **Range locks**: hinted compaction deferred while its range is locked
+//! - **Shutdown**: `close` runs the tasks still queued
+//! - **Thread pool databases**: the manual calls are refused
+//!
+//! Built only with `--features simulation`.
+
+use aeternusdb::{CompactionPriority, Db, DbConfig, DbError, MockClock};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+// ------------------------------------------------------------------------------------------------
+// Helpers
+// ------------------------------------------------------------------------------------------------
+
+/// 1 KiB write buffer, so a few hundred writes freeze often; no minor
+/// compaction, so flushes leave one SSTable each.
+fn small_buffer_config() -> DbConfig {
+    DbConfig {
+        write_buffer_size: 1024,
+        min_compaction_threshold: 64,
+        max_compaction_threshold: 64,
+        ..DbConfig::default()
+    }
+}
+
+/// Writes `sim_0000..sim_{count}` with `value`.
+fn write_keys(db: &Db, count: u32, value: &[u8]) {
+    for i in 0..count {
+        db.put(format!("sim_{i:04}").as_bytes(), value).unwrap();
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Tests
+// ------------------------------------------------------------------------------------------------
+
+/// # Scenario
+/// Writes freeze many write buffers while no background work runs.
+///
+/// # Starting environment
+/// Empty simulated database with a 1 KiB write buffer.
+///
+/// # Actions
+/// 1. Write 400 keys; check the SSTables and queues.
+/// 2. Run the pending background work, twice.
+///
+/// # Expected behavior
+/// - After step 1 no SSTable exists and the flushes wait in their queue,
+///   past `background_queue_depth`.
+/// - The first run flushes every frozen write buffer, leaving the queues
+///   empty and the task count matching; the second runs nothing.
+/// - Every key reads back throughout.
+#[test]
+fn background_work_runs_on_request() {
+    let dir = TempDir::new().unwrap();
+    let config = DbConfig {
+        background_queue_depth: 2,
+        ..small_buffer_config()
+    };
+    let db = Db::open_simulated(dir.path(), config).unwrap();
+    write_keys(&db, 400, b"value_with_some_padding");
+
+    assert!(db.sstable_metadata().unwrap().is_empty());
+    let queued = db.queue_stats().flush;
+    assert!(queued > 2, "{queued} flushes queued");
+
+    let ran = db.run_pending_background_work().unwrap();
+    assert!(ran >= queued, "{ran} tasks ran");
+    assert_eq!(db.background_stats().tasks_run, ran as u64);
+    assert!(db.sstable_metadata().unwrap().len() >= 2);
+    let stats = db.queue_stats();
+    assert_eq!(
+        (stats.flush, stats.minor, stats.tombstone, stats.major),
+        (0, 0, 0, 0)
+    );
+    assert_eq!(db.run_pending_background_work().unwrap(), 0);
+
+    for i in 0..400u32 {
+        assert_eq!(
+            db.get(format!("sim_{i:04}").as_bytes()).unwrap(),
+            Some(b"value_with_some_padding".to_vec())
+        );
+    }
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// A burst of flushes each asks for a minor compaction, and the timers
+/// are ticked twice without running anything.
+///
+/// # Starting environment
+/// Empty simulated database with a 1 KiB write buffer.
+///
+/// # Actions
+/// 1. Write 400 keys; run the pending background work.
+/// 2. Tick the timers twice; run the pending background work.
+///
+/// # Expected behavior
+/// - Step 1 runs every flush, one minor compaction, and one tombstone
+///   compaction; every flush after the first is coalesced into the
+///   waiting minor compaction.
+/// - The second tick queues nothing and coalesces every timer task.
+#[test]
+fn burst_of_flushes_queues_one_compaction() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open_simulated(dir.path(), small_buffer_config()).unwrap();
+    write_keys(&db, 400, b"value_with_some_padding");
+    let flushes = db.queue_stats().flush;
+    assert!(flushes > 2, "{flushes} flushes queued");
+
+    assert_eq!(db.run_pending_background_work().unwrap(), flushes + 2);
+    let stats = db.queue_stats();
+    assert_eq!(stats.coalesced, flushes as u64 - 1);
+    assert_eq!(stats.rejected, 0);
+
+    let ticked = db.tick_background_timers().unwrap();
+    assert!(ticked >= 1);
+    assert_eq!(db.tick_background_timers().unwrap(), 0);
+    assert_eq!(
+        db.queue_stats().coalesced,
+        flushes as u64 - 1 + ticked as u64
+    );
+    assert!(db.run_pending_background_work().unwrap() >= ticked);
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// Keys are overwritten and deleted after a major compaction is queued
+/// but before it runs.
+///
+/// # Starting environment
+/// Simulated database with 400 keys flushed to several SSTables.
+///
+/// # Actions
+/// 1. Start an async major compaction.
+/// 2. Overwrite every even key, delete every tenth, flush them by
+///    writing more keys.
+/// 3. Run the pending background work; wait for the handle.
+///
+/// # Expected behavior
+/// The compaction has not started after step 2. It then reports success
+/// and every key reads its newest value: overwritten, deleted, or
+/// untouched.
+#[test]
+fn writes_between_queued_and_running_compaction() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open_simulated(dir.path(), small_buffer_config()).unwrap();
+    write_keys(&db, 400, b"v1");
+    db.run_pending_background_work().unwrap();
+    assert!(db.sstable_metadata().unwrap().len() >= 2);
+
+    let handle = db.major_compact_async().unwrap();
+    for i in (0..400u32).step_by(2) {
+        db.put(format!("sim_{i:04}").as_bytes(), b"v2").unwrap();
+    }
+    for i in (0..400u32).step_by(10) {
+        db.delete(format!("sim_{i:04}").as_bytes()).unwrap();
+    }
+    for i in 0..100u32 {
+        db.put(format!("zz_{i:04}").as_bytes(), b"filler").unwrap();
+    }
+    assert!(!handle.is_finished());
+
+    db.run_pending_background_work().unwrap();
+    assert!(handle.is_finished());
+    assert!(handle.wait().unwrap());
+    for i in 0..400u32 {
+        let expected = match i {
+            i if i % 10 == 0 => None,
+            i if i % 2 == 0 => Some(b"v2".to_vec()),
+            _ => Some(b"v1".to_vec()),
+        };
+        assert_eq!(
+            db.get(format!("sim_{i:04}").as_bytes()).unwrap(),
+            expected,
+            "key {i}"
+        );
+    }
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// The memtable age timer fires only when ticked, and flushes only once
+/// the mock clock says the write buffer is old enough.
+///
+/// # Starting environment
+/// Empty simulated database with `max_memtable_age: 60`, a large write
+/// buffer, and a `MockClock`.
+///
+/// # Actions
+/// 1. Put a key; tick the timers and run the pending work.
+/// 2. Advance the clock by 61 seconds; tick and run again.
+///
+/// # Expected behavior
+/// Each tick queues at least the age check. The first run flushes
+/// nothing; the second flushes the key to an SSTable.
+#[test]
+fn timers_fire_on_tick() {
+    let dir = TempDir::new().unwrap();
+    let clock = Arc::new(MockClock::new(1_700_000_000_000_000_000));
+    let config = DbConfig {
+        max_memtable_age: 60,
+        clock: clock.clone(),
+        ..DbConfig::default()
+    };
+    let db = Db::open_simulated(dir.path(), config).unwrap();
+    db.put(b"aged", b"value").unwrap();
+
+    assert!(db.tick_background_timers().unwrap() >= 1);
+    db.run_pending_background_work().unwrap();
+    assert!(db.sstable_metadata().unwrap().is_empty());
+
+    clock.advance(Duration::from_secs(61));
+    assert!(db.tick_background_timers().unwrap() >= 1);
+    db.run_pending_background_work().unwrap();
+    assert_eq!(db.sstable_metadata().unwrap().len(), 1);
+    assert_eq!(db.get(b"aged").unwrap(), Some(b"value".to_vec()));
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// A range is hinted for compaction while an application holds a range
+/// lock over it.
+///
+/// # Starting environment
+/// Simulated database with `tombstone_compaction_ratio: 1.0`, holding
+/// SSTables in which `hc_0000..hc_0299` were put, then deleted.
+///
+/// # Actions
+/// 1. Lock `[hc_, hc_~)`; suggest the range with `High` priority; run
+///    the pending background work.
+/// 2. Drop the guard; write filler keys to start a flush; run the
+///    pending background work.
+///
+/// # Expected behavior
+/// Step 1 queues a compaction round that leaves the hinted range alone:
+/// every tombstone stays. Step 2 compacts the range and drops them.
+#[test]
+fn hinted_compaction_waits_for_range_lock() {
+    let dir = TempDir::new().unwrap();
+    let config = DbConfig {
+        tombstone_compaction_ratio: 1.0,
+        ..small_buffer_config()
+    };
+    let db = Db::open_simulated(dir.path(), config).unwrap();
+    for i in 0..300u32 {
+        db.put(format!("hc_{i:04}").as_bytes(), b"value_with_padding")
+            .unwrap();
+    }
+    for i in 0..300u32 {
+        db.delete(format!("hc_{i:04}").as_bytes()).unwrap();
+    }
+    write_keys(&db, 200, b"filler_value_with_padding");
+    db.run_pending_background_work().unwrap();
+    let tombstones = |db: &Db| -> u64 {
+        db.sstable_metadata()
+            .unwrap()
+            .iter()
+            .map(|m| m.tombstone_count)
+            .sum()
+    };
+    let before = tombstones(&db);
+    assert!(before > 0);
+
+    let guard = db.lock_range(b"hc_", b"hc_~").unwrap();
+    let hinted = db
+        .suggest_compact_range(b"hc_", b"hc_~", CompactionPriority::High)
+        .unwrap();
+    assert!(hinted >= 2, "hinted {hinted} SSTables");
+    assert!(db.run_pending_background_work().unwrap() >= 1);
+    assert_eq!(tombstones(&db), before);
+
+    drop(guard);
+    write_keys(&db, 200, b"second_filler_with_padding");
+    db.run_pending_background_work().unwrap();
+    assert_eq!(tombstones(&db), 0);
+    assert!(db.scan(b"hc_", b"hc_~").unwrap().is_empty());
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// A simulated database is closed with background work still queued.
+///
+/// # Starting environment
+/// Simulated database with 400 keys, its flushes not yet run.
+///
+/// # Actions
+/// 1. Start an async major compaction; close without running anything.
+/// 2. Reopen.
+///
+/// # Expected behavior
+/// `close` runs the queued flushes and the compaction: the handle
+/// reports success, and every key reads back after reopen.
+#[test]
+fn close_runs_queued_work() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open_simulated(dir.path(), small_buffer_config()).unwrap();
+    write_keys(&db, 400, b"value_with_some_padding");
+    assert!(db.queue_stats().flush > 0);
+
+    let handle = db.major_compact_async().unwrap();
+    db.close().unwrap();
+    assert!(handle.is_finished());
+    assert!(handle.wait().unwrap());
+
+    let db = Db::open_simulated(dir.path(), small_buffer_config()).unwrap();
+    assert!(!db.sstable_metadata().unwrap().is_empty());
+    for i in 0..400u32 {
+        assert_eq!(
+            db.get(format!("sim_{i:04}").as_bytes()).unwrap(),
+            Some(b"value_with_some_padding".to_vec())
+        );
+    }
+    db.close().unwrap();
+}
+
+/// # Scenario
+/// The manual scheduling calls are used on a database opened with
+/// `Db::open`.
+///
+/// # Actions
+/// 1. Open with `Db::open`; run pending work and tick the timers.
+///
+/// # Expected behavior
+/// Both fail with `DbError::InvalidArgument`.
+#[test]
+fn thread_pool_database_refuses_manual_calls() {
+    let dir = TempDir::new().unwrap();
+    let db = Db::open(dir.path(), DbConfig::default()).unwrap();
+    assert!(matches!(
+        db.run_pending_background_work(),
+        Err(DbError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        db.tick_background_timers(),
+        Err(DbError::InvalidArgument(_))
+    ));
+    db.close().unwrap();
+}